@@ -0,0 +1,137 @@
+//! Fuzzes the stamp/query path of a small universe.
+//!
+//! The input is decoded as a byte stream of operations: stamps of every
+//! shape and blend op, point and volume queries, and propagation steps.
+//! Coordinates range past the universe bounds so out-of-bounds handling is
+//! exercised too. The target only checks that nothing panics and that a
+//! point query is stable when repeated.
+
+#![no_main]
+
+use glam::Vec3;
+use libfuzzer_sys::fuzz_target;
+use murk::{BlendOp, Field, FieldMod, QueryResolution, Stamp, StampShape, Universe, UniverseConfig};
+
+/// Upper bound on operations decoded from one input.
+const MAX_OPS: usize = 64;
+
+/// Reads fuzz bytes as bounded values, yielding zeros once exhausted.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl Decoder<'_> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn byte(&mut self) -> u8 {
+        let Some((&first, rest)) = self.bytes.split_first() else {
+            return 0;
+        };
+        self.bytes = rest;
+        first
+    }
+
+    /// A value in `[0, 1]`.
+    fn unit(&mut self) -> f32 {
+        f32::from(u16::from_le_bytes([self.byte(), self.byte()])) / f32::from(u16::MAX)
+    }
+
+    /// A value in `[-extent, extent]`.
+    fn signed(&mut self, extent: f32) -> f32 {
+        (self.unit() * 2.0 - 1.0) * extent
+    }
+
+    /// A point reaching a quarter past each face of `half`-sized bounds.
+    fn point(&mut self, half: Vec3) -> Vec3 {
+        let reach = half * 1.25;
+        Vec3::new(self.signed(reach.x), self.signed(reach.y), self.signed(reach.z))
+    }
+
+    fn field(&mut self) -> Field {
+        let all = Field::all();
+        all[usize::from(self.byte()) % all.len()]
+    }
+
+    fn blend_op(&mut self) -> BlendOp {
+        match self.byte() % 7 {
+            0 => BlendOp::Set,
+            1 => BlendOp::Add,
+            2 => BlendOp::Subtract,
+            3 => BlendOp::Multiply,
+            4 => BlendOp::Max,
+            5 => BlendOp::Min,
+            _ => BlendOp::Lerp { factor: self.unit() },
+        }
+    }
+
+    fn shape(&mut self, half: Vec3) -> StampShape {
+        match self.byte() % 3 {
+            0 => StampShape::sphere(self.point(half), self.unit() * half.x),
+            1 => {
+                let (a, b) = (self.point(half), self.point(half));
+                StampShape::box_min_max(a.min(b), a.max(b))
+            }
+            _ => StampShape::capsule(self.point(half), self.point(half), self.unit() * half.x),
+        }
+    }
+
+    fn stamp(&mut self, half: Vec3) -> Stamp {
+        let shape = self.shape(half);
+        let mods = (0..=self.byte() % 3)
+            .map(|_| FieldMod::new(self.field(), self.blend_op(), self.signed(8.0)))
+            .collect();
+        let stamp = Stamp::new(shape, mods);
+        if self.byte() & 1 == 1 {
+            stamp.with_falloff()
+        } else {
+            stamp
+        }
+    }
+
+    fn resolution(&mut self) -> QueryResolution {
+        match self.byte() % 6 {
+            0 => QueryResolution::Depth(self.byte() % 12),
+            1 => QueryResolution::Variance(self.unit()),
+            2 => QueryResolution::Coarse,
+            3 => QueryResolution::Medium,
+            4 => QueryResolution::Fine,
+            _ => QueryResolution::Full,
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Decoder { bytes: data };
+    let mut config = UniverseConfig::with_bounds(32.0, 32.0, 8.0);
+    config.base_resolution = 4.0;
+    config.planar = input.byte() & 1 == 1;
+    let half = config.bounds.size() / 2.0;
+    let mut universe = Universe::new(config);
+
+    for _ in 0..MAX_OPS {
+        if input.is_empty() {
+            break;
+        }
+        match input.byte() % 4 {
+            0 => {
+                let stamp = input.stamp(half);
+                universe.stamp(&stamp);
+            }
+            1 => {
+                let point = input.point(half);
+                let first = universe.query_point(point);
+                let second = universe.query_point(point);
+                assert_eq!(first.values.as_slice(), second.values.as_slice());
+                assert_eq!(first.depth, second.depth);
+            }
+            2 => {
+                let center = input.point(half);
+                let radius = input.unit() * half.x;
+                let _ = universe.query_volume(center, radius, input.resolution());
+            }
+            _ => universe.step(f64::from(input.unit())),
+        }
+    }
+});
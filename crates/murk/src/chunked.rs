@@ -0,0 +1,659 @@
+//! Chunked universe for campaign-scale worlds.
+//!
+//! A single octree covering a whole ocean does not fit in memory. A
+//! [`ChunkedUniverse`] tiles the world horizontally into chunks, each a
+//! [`Universe`] over one tile, and keeps only the chunks around active
+//! arenas loaded:
+//!
+//! - [`ChunkedUniverse::focus`] unloads the chunks away from every focus
+//!   point and starts loading the ones near any of them. Chunks are saved
+//!   to and loaded from a [`ChunkStore`] on a background thread. An
+//!   unloaded chunk stays in memory until its save succeeds: a chunk wanted
+//!   again before then is taken back as is, and one whose save fails is
+//!   loaded again, with the error reported by [`ChunkedUniverse::flush`].
+//! - Stamps and queries span chunk borders: a stamp is applied to every
+//!   chunk its shape overlaps, and a volume query merges the results of
+//!   every chunk the sphere overlaps. Chunks they touch are loaded on
+//!   demand, waiting for a pending background load if there is one.
+//! - [`ChunkedUniverse::step`] advances the loaded chunks only; unloaded
+//!   chunks stay as they were saved. Fields do not propagate across chunk
+//!   borders.
+//!
+//! Chunks that were never saved start as universes holding default values
+//! everywhere.
+//!
+//! ```
+//! use glam::Vec3;
+//! use murk::chunked::{ChunkCoord, ChunkedUniverse, MemoryChunkStore};
+//! use murk::{Field, Stamp, UniverseConfig};
+//!
+//! // 1 km chunks; chunk (0, 0) spans -500 to 500 m
+//! let config = UniverseConfig {
+//!     base_resolution: 8.0,
+//!     ..UniverseConfig::with_bounds(1000.0, 1000.0, 64.0)
+//! };
+//! let store = MemoryChunkStore::default();
+//! let mut world = ChunkedUniverse::new(config, store.clone());
+//!
+//! // A fire on the border between two chunks burns in both
+//! world.stamp(&Stamp::fire(Vec3::new(500.0, 0.0, 0.0), 40.0, 1.0)).unwrap();
+//! assert_eq!(world.loaded_chunks().count(), 2);
+//! let west = world.query_point(Vec3::new(490.0, 0.0, 0.0)).unwrap();
+//! let east = world.query_point(Vec3::new(510.0, 0.0, 0.0)).unwrap();
+//! assert!(west.get(Field::Temperature) > 0.0 && east.get(Field::Temperature) > 0.0);
+//!
+//! // The fleets move on: the fire's chunks are saved and unloaded
+//! world.focus(&[Vec3::new(20_000.0, 0.0, 0.0)], 1000.0);
+//! world.flush().unwrap();
+//! assert!(!world.is_loaded(ChunkCoord::new(0, 0)));
+//! assert!(store.contains(ChunkCoord::new(0, 0)));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "directory-store")]
+use std::fs::{self, File};
+use std::io;
+#[cfg(feature = "directory-store")]
+use std::io::{BufReader, BufWriter, Write};
+#[cfg(feature = "directory-store")]
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::field::FieldValues;
+use crate::query::{PointResult, QueryResolution, QueryResult};
+use crate::stamp::Stamp;
+use crate::stats::FieldStats;
+use crate::universe::{Universe, UniverseConfig};
+use crate::Bounds;
+
+/// Horizontal index of a chunk; chunk `(0, 0)` covers the bounds of the
+/// chunk config.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub struct ChunkCoord {
+    /// Index along X
+    pub x: i32,
+    /// Index along Y
+    pub y: i32,
+}
+
+impl ChunkCoord {
+    /// Create a chunk coordinate.
+    #[must_use]
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Persistent storage for unloaded chunks.
+///
+/// Called from the chunk worker thread, one call at a time.
+pub trait ChunkStore: Send + 'static {
+    /// Load the chunk saved at `coord`, or `None` if none was saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the saved chunk cannot be read.
+    fn load(&mut self, coord: ChunkCoord) -> io::Result<Option<Universe>>;
+
+    /// Save `chunk` at `coord`, replacing any chunk saved there before.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk cannot be written.
+    fn save(&mut self, coord: ChunkCoord, chunk: &Universe) -> io::Result<()>;
+}
+
+/// Chunk store keeping chunks in memory, for tests and small worlds.
+///
+/// Clones share the same chunks.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryChunkStore {
+    chunks: Arc<Mutex<BTreeMap<ChunkCoord, Universe>>>,
+}
+
+impl MemoryChunkStore {
+    /// Check whether a chunk is saved at `coord`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn contains(&self, coord: ChunkCoord) -> bool {
+        self.chunks.lock().unwrap().contains_key(&coord)
+    }
+
+    /// Get the number of saved chunks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.chunks.lock().unwrap().len()
+    }
+
+    /// Check whether no chunk is saved.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ChunkStore for MemoryChunkStore {
+    fn load(&mut self, coord: ChunkCoord) -> io::Result<Option<Universe>> {
+        Ok(self.chunks.lock().unwrap().get(&coord).cloned())
+    }
+
+    fn save(&mut self, coord: ChunkCoord, chunk: &Universe) -> io::Result<()> {
+        self.chunks.lock().unwrap().insert(coord, chunk.clone());
+        Ok(())
+    }
+}
+
+/// Chunk store keeping one JSON file per chunk in a directory (feature
+/// `directory-store`).
+#[cfg(feature = "directory-store")]
+#[derive(Debug, Clone)]
+pub struct DirectoryChunkStore {
+    dir: PathBuf,
+}
+
+#[cfg(feature = "directory-store")]
+impl DirectoryChunkStore {
+    /// Create a store in `dir`, created on the first save.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Get the path of the file holding the chunk at `coord`.
+    #[must_use]
+    pub fn path(&self, coord: ChunkCoord) -> PathBuf {
+        self.dir.join(format!("chunk_{}_{}.json", coord.x, coord.y))
+    }
+}
+
+#[cfg(feature = "directory-store")]
+impl ChunkStore for DirectoryChunkStore {
+    fn load(&mut self, coord: ChunkCoord) -> io::Result<Option<Universe>> {
+        let file = match File::open(self.path(coord)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Ok(Some(serde_json::from_reader(BufReader::new(file))?))
+    }
+
+    fn save(&mut self, coord: ChunkCoord, chunk: &Universe) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        // Written aside and renamed, so a failed save keeps the old chunk
+        let path = self.path(coord);
+        let partial = path.with_extension("json.partial");
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        serde_json::to_writer(&mut writer, chunk)?;
+        writer.flush()?;
+        fs::rename(partial, path)
+    }
+}
+
+/// Outcome of loading a chunk on the worker.
+type Loaded = io::Result<Option<Universe>>;
+
+/// Outcome of saving a chunk on the worker.
+type Saved = io::Result<()>;
+
+/// Work queued for the chunk worker.
+enum Job {
+    Load(ChunkCoord, Sender<Loaded>),
+    Save(ChunkCoord, Arc<Universe>, Sender<Saved>),
+}
+
+/// Background thread running store calls in queue order.
+#[derive(Debug)]
+struct Worker {
+    jobs: Option<Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    fn spawn(mut store: impl ChunkStore) -> Self {
+        let (jobs, queue) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            for job in queue {
+                match job {
+                    Job::Load(coord, reply) => {
+                        let _ = reply.send(store.load(coord));
+                    }
+                    Job::Save(coord, chunk, reply) => {
+                        let _ = reply.send(store.save(coord, &chunk));
+                    }
+                }
+            }
+        });
+        Self {
+            jobs: Some(jobs),
+            thread: Some(thread),
+        }
+    }
+
+    /// Queue a job; if the worker has died, its reply channel is dropped.
+    fn send(&self, job: Job) {
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(job);
+        }
+    }
+
+    fn load(&self, coord: ChunkCoord) -> Receiver<Loaded> {
+        let (reply, loaded) = mpsc::channel();
+        self.send(Job::Load(coord, reply));
+        loaded
+    }
+
+    fn save(&self, coord: ChunkCoord, chunk: Arc<Universe>) -> Receiver<Saved> {
+        let (reply, saved) = mpsc::channel();
+        self.send(Job::Save(coord, chunk, reply));
+        saved
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // Closing the queue lets the worker finish pending saves and exit
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn worker_stopped() -> io::Error {
+    io::Error::other("chunk worker stopped")
+}
+
+/// A chunk unloaded by [`ChunkedUniverse::focus`] whose save is in flight.
+#[derive(Debug)]
+struct Unloading {
+    chunk: Arc<Universe>,
+    saved: Receiver<Saved>,
+}
+
+/// A world tiled into chunk universes loaded around active arenas.
+#[derive(Debug)]
+pub struct ChunkedUniverse {
+    /// Config of chunk `(0, 0)`; other chunks are offset copies
+    config: UniverseConfig,
+    /// Loaded chunks
+    chunks: BTreeMap<ChunkCoord, Universe>,
+    /// Background loads not yet collected
+    pending: BTreeMap<ChunkCoord, Receiver<Loaded>>,
+    /// Unloaded chunks kept until their save succeeds
+    unloading: BTreeMap<ChunkCoord, Unloading>,
+    /// First save error not yet reported by `flush`
+    failure: Option<io::Error>,
+    worker: Worker,
+}
+
+impl ChunkedUniverse {
+    /// Create a chunked universe whose chunks are copies of `config`, tiled
+    /// from its bounds, and persisted in `store`.
+    #[must_use]
+    pub fn new(config: UniverseConfig, store: impl ChunkStore) -> Self {
+        Self {
+            config,
+            chunks: BTreeMap::new(),
+            pending: BTreeMap::new(),
+            unloading: BTreeMap::new(),
+            failure: None,
+            worker: Worker::spawn(store),
+        }
+    }
+
+    /// Get the config of chunk `(0, 0)`.
+    #[must_use]
+    pub fn config(&self) -> &UniverseConfig {
+        &self.config
+    }
+
+    /// Get the horizontal size of a chunk.
+    #[must_use]
+    pub fn chunk_size(&self) -> Vec2 {
+        self.config.bounds.size().truncate()
+    }
+
+    /// Get the chunk containing `position`.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn chunk_at(&self, position: Vec3) -> ChunkCoord {
+        let cell = ((position - self.config.bounds.min).truncate() / self.chunk_size()).floor();
+        ChunkCoord::new(cell.x as i32, cell.y as i32)
+    }
+
+    /// Get the bounds of the chunk at `coord`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn chunk_bounds(&self, coord: ChunkCoord) -> Bounds {
+        let size = self.chunk_size();
+        let offset = Vec3::new(coord.x as f32 * size.x, coord.y as f32 * size.y, 0.0);
+        Bounds::from_min_max(self.config.bounds.min + offset, self.config.bounds.max + offset)
+    }
+
+    /// Check whether the chunk at `coord` is loaded.
+    #[must_use]
+    pub fn is_loaded(&self, coord: ChunkCoord) -> bool {
+        self.chunks.contains_key(&coord)
+    }
+
+    /// Iterate over the loaded chunks, in coordinate order.
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = ChunkCoord> + '_ {
+        self.chunks.keys().copied()
+    }
+
+    /// Get a loaded chunk.
+    #[must_use]
+    pub fn chunk(&self, coord: ChunkCoord) -> Option<&Universe> {
+        self.chunks.get(&coord)
+    }
+
+    /// Get the chunk at `coord`, loading it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store fails to load the chunk.
+    pub fn chunk_mut(&mut self, coord: ChunkCoord) -> io::Result<&mut Universe> {
+        let chunk = match self.chunks.remove(&coord) {
+            Some(chunk) => chunk,
+            None => self.fetch(coord)?,
+        };
+        Ok(self.chunks.entry(coord).or_insert(chunk))
+    }
+
+    /// Take back a chunk still being saved, collect it from its pending
+    /// load, or load it now.
+    fn fetch(&mut self, coord: ChunkCoord) -> io::Result<Universe> {
+        if let Some(unloading) = self.unloading.remove(&coord) {
+            return Ok(Arc::unwrap_or_clone(unloading.chunk));
+        }
+        let loaded = match self.pending.remove(&coord) {
+            Some(loaded) => loaded,
+            None => self.worker.load(coord),
+        };
+        let Some(mut chunk) = loaded.recv().map_err(|_| worker_stopped())?? else {
+            let bounds = self.chunk_bounds(coord);
+            let mut chunk = Universe::new(UniverseConfig {
+                bounds,
+                ..self.config.clone()
+            });
+            // An empty octree ignores stamps until it holds a value
+            chunk.set_point(bounds.center(), FieldValues::new());
+            return Ok(chunk);
+        };
+        // Processes are not serialized
+        for process in &self.config.processes {
+            chunk.add_process(Arc::clone(process));
+        }
+        Ok(chunk)
+    }
+
+    /// Get the chunks overlapping the horizontal extent of `bounds`.
+    fn chunks_in(&self, bounds: &Bounds) -> impl Iterator<Item = ChunkCoord> {
+        let (min, max) = (self.chunk_at(bounds.min), self.chunk_at(bounds.max));
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| ChunkCoord::new(x, y)))
+    }
+
+    /// Keep loaded only the chunks within `radius` (horizontally) of one of
+    /// `centers`, such as the positions of active arenas.
+    ///
+    /// Chunks further away are saved and unloaded; chunks within reach that
+    /// are not loaded start loading in the background. Chunks whose earlier
+    /// save failed are loaded again.
+    pub fn focus(&mut self, centers: &[Vec3], radius: f32) {
+        self.collect_saves(false);
+        let reach = Vec3::new(radius, radius, 0.0);
+        let wanted: BTreeSet<ChunkCoord> = centers
+            .iter()
+            .flat_map(|&c| self.chunks_in(&Bounds::from_min_max(c - reach, c + reach)))
+            .collect();
+        let (keep, unload) = std::mem::take(&mut self.chunks)
+            .into_iter()
+            .partition(|(coord, _)| wanted.contains(coord));
+        self.chunks = keep;
+        for (coord, chunk) in unload {
+            let chunk = Arc::new(chunk);
+            let saved = self.worker.save(coord, Arc::clone(&chunk));
+            self.unloading.insert(coord, Unloading { chunk, saved });
+        }
+        self.pending.retain(|coord, _| wanted.contains(coord));
+        for coord in wanted {
+            if let Some(unloading) = self.unloading.remove(&coord) {
+                self.chunks.insert(coord, Arc::unwrap_or_clone(unloading.chunk));
+            } else if !self.chunks.contains_key(&coord) && !self.pending.contains_key(&coord) {
+                let loaded = self.worker.load(coord);
+                self.pending.insert(coord, loaded);
+            }
+        }
+    }
+
+    /// Save every loaded chunk, keeping it loaded, and wait for all queued
+    /// saves to finish.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first save error since the last flush. Unloaded chunks
+    /// that failed to save are loaded again, so nothing is lost.
+    pub fn flush(&mut self) -> io::Result<()> {
+        let saves: Vec<Receiver<Saved>> = self
+            .chunks
+            .iter()
+            .map(|(&coord, chunk)| self.worker.save(coord, Arc::new(chunk.clone())))
+            .collect();
+        self.collect_saves(true);
+        for saved in saves {
+            if let Err(e) = saved.recv().unwrap_or_else(|_| Err(worker_stopped())) {
+                self.failure.get_or_insert(e);
+            }
+        }
+        self.failure.take().map_or(Ok(()), Err)
+    }
+
+    /// Release unloaded chunks whose save succeeded and load again those
+    /// whose save failed, waiting for saves in flight if `wait` is set.
+    fn collect_saves(&mut self, wait: bool) {
+        for (coord, unloading) in std::mem::take(&mut self.unloading) {
+            let result = if wait {
+                unloading.saved.recv().unwrap_or_else(|_| Err(worker_stopped()))
+            } else {
+                match unloading.saved.try_recv() {
+                    Ok(result) => result,
+                    Err(TryRecvError::Empty) => {
+                        self.unloading.insert(coord, unloading);
+                        continue;
+                    }
+                    Err(TryRecvError::Disconnected) => Err(worker_stopped()),
+                }
+            };
+            if let Err(e) = result {
+                self.failure.get_or_insert(e);
+                self.chunks.insert(coord, Arc::unwrap_or_clone(unloading.chunk));
+            }
+        }
+    }
+
+    /// Apply a stamp to every chunk it overlaps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an overlapped chunk fails to load; chunks before
+    /// it are already stamped.
+    pub fn stamp(&mut self, stamp: &Stamp) -> io::Result<()> {
+        let coords: Vec<ChunkCoord> = self.chunks_in(&stamp.shape.bounds()).collect();
+        for coord in coords {
+            self.chunk_mut(coord)?.stamp(stamp);
+        }
+        Ok(())
+    }
+
+    /// Query a single point.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chunk holding the point fails to load.
+    pub fn query_point(&mut self, position: Vec3) -> io::Result<PointResult> {
+        let coord = self.chunk_at(position);
+        Ok(self.chunk_mut(coord)?.query_point(position))
+    }
+
+    /// Query a volume, merging the results of every chunk it overlaps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an overlapped chunk fails to load.
+    pub fn query_volume(
+        &mut self,
+        center: Vec3,
+        radius: f32,
+        resolution: QueryResolution,
+    ) -> io::Result<QueryResult> {
+        let reach = Vec3::splat(radius);
+        let reach = Bounds::from_min_max(center - reach, center + reach);
+        let coords: Vec<ChunkCoord> = self
+            .chunks_in(&reach)
+            .filter(|&coord| self.chunk_bounds(coord).intersects_sphere(center, radius))
+            .collect();
+        let mut merged = QueryResult {
+            stats: FieldStats::empty(),
+            ..QueryResult::default()
+        };
+        for coord in coords {
+            let result = self.chunk_mut(coord)?.query_volume(center, radius, resolution);
+            merged.stats = FieldStats::merge(&merged.stats, &result.stats);
+            merged.nodes_visited += result.nodes_visited;
+            merged.max_depth_reached = merged.max_depth_reached.max(result.max_depth_reached);
+            merged.regions.extend(result.regions);
+        }
+        Ok(merged)
+    }
+
+    /// Advance every loaded chunk by one tick.
+    pub fn step(&mut self, dt: f64) {
+        for chunk in self.chunks.values_mut() {
+            chunk.step(dt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::Field;
+
+    fn config() -> UniverseConfig {
+        UniverseConfig {
+            base_resolution: 8.0,
+            ..UniverseConfig::with_bounds(1000.0, 1000.0, 64.0)
+        }
+    }
+
+    #[test]
+    fn test_chunk_tiling() {
+        let world = ChunkedUniverse::new(config(), MemoryChunkStore::default());
+        assert_eq!(world.chunk_at(Vec3::ZERO), ChunkCoord::new(0, 0));
+        assert_eq!(world.chunk_at(Vec3::new(-501.0, 1700.0, 0.0)), ChunkCoord::new(-1, 2));
+
+        let bounds = world.chunk_bounds(ChunkCoord::new(-1, 2));
+        assert_eq!(bounds.min, Vec3::new(-1500.0, 1500.0, -32.0));
+        assert_eq!(bounds.max, Vec3::new(-500.0, 2500.0, 32.0));
+    }
+
+    #[test]
+    fn test_unloaded_chunks_are_reloaded_from_the_store() {
+        let store = MemoryChunkStore::default();
+        let mut world = ChunkedUniverse::new(config(), store.clone());
+        let fire = Vec3::new(3000.0, -200.0, 0.0);
+        world.stamp(&Stamp::fire(fire, 40.0, 1.0)).unwrap();
+        let hot = world.query_point(fire).unwrap().values;
+        assert!(hot.get(Field::Temperature) > 0.0);
+
+        world.focus(&[Vec3::ZERO], 600.0);
+        assert_eq!(world.loaded_chunks().count(), 0);
+        // Reloaded right away: the load is queued after the save
+        let reloaded = world.query_point(fire).unwrap().values;
+        assert_eq!(reloaded.as_slice(), hot.as_slice());
+
+        world.focus(&[Vec3::ZERO], 600.0);
+        world.flush().unwrap();
+        assert_eq!(store.len(), 1);
+        // Collected from the background load started by the focus
+        assert!(world.query_volume(Vec3::ZERO, 400.0, QueryResolution::Coarse).is_ok());
+        assert_eq!(world.loaded_chunks().collect::<Vec<_>>(), [ChunkCoord::new(0, 0)]);
+    }
+
+    /// Memory store whose next `failures` saves fail.
+    #[derive(Clone, Default)]
+    struct FlakyStore {
+        inner: MemoryChunkStore,
+        failures: Arc<Mutex<u32>>,
+    }
+
+    impl ChunkStore for FlakyStore {
+        fn load(&mut self, coord: ChunkCoord) -> io::Result<Option<Universe>> {
+            self.inner.load(coord)
+        }
+
+        fn save(&mut self, coord: ChunkCoord, chunk: &Universe) -> io::Result<()> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(io::Error::other("disk full"));
+            }
+            self.inner.save(coord, chunk)
+        }
+    }
+
+    #[test]
+    fn test_chunk_stays_resident_until_saved() {
+        let store = FlakyStore::default();
+        let mut world = ChunkedUniverse::new(config(), store.clone());
+        let fire = Vec3::new(100.0, 100.0, 0.0);
+        world.stamp(&Stamp::fire(fire, 40.0, 1.0)).unwrap();
+
+        *store.failures.lock().unwrap() = 1;
+        world.focus(&[Vec3::new(20_000.0, 0.0, 0.0)], 1000.0);
+        assert!(world.flush().is_err());
+        assert!(world.is_loaded(ChunkCoord::new(0, 0)));
+        assert!(!store.inner.contains(ChunkCoord::new(0, 0)));
+
+        // The next unload saves it
+        world.focus(&[Vec3::new(20_000.0, 0.0, 0.0)], 1000.0);
+        world.flush().unwrap();
+        assert!(!world.is_loaded(ChunkCoord::new(0, 0)));
+        let reloaded = world.query_point(fire).unwrap().values;
+        assert!(reloaded.get(Field::Temperature) > 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "directory-store")]
+    fn test_directory_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("murk-chunks-{}", std::process::id()));
+        let fire = Vec3::new(100.0, 100.0, 0.0);
+        let hot = {
+            let mut world = ChunkedUniverse::new(config(), DirectoryChunkStore::new(&dir));
+            world.stamp(&Stamp::fire(fire, 40.0, 1.0)).unwrap();
+            world.flush().unwrap();
+            world.query_point(fire).unwrap().values
+        };
+        assert!(DirectoryChunkStore::new(&dir).path(ChunkCoord::new(0, 0)).exists());
+
+        let mut world = ChunkedUniverse::new(config(), DirectoryChunkStore::new(&dir));
+        let reloaded = world.query_point(fire).unwrap().values;
+        assert!(reloaded.get(Field::Temperature) > 0.0);
+        assert_eq!(reloaded.as_slice(), hot.as_slice());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -63,6 +63,117 @@ impl Field {
     pub const fn index(self) -> usize {
         self as usize
     }
+
+    /// Get the unit values of this field are stored in.
+    #[must_use]
+    pub const fn unit(self) -> Unit {
+        match self {
+            Field::Occupancy
+            | Field::Integrity
+            | Field::Smoke
+            | Field::Signal
+            | Field::SonarReturn => Unit::Fraction,
+            Field::Material => Unit::MaterialId,
+            Field::Temperature => Unit::Kelvin,
+            Field::Noise => Unit::Decibel,
+            Field::CurrentX | Field::CurrentY => Unit::MetersPerSecond,
+            Field::Depth => Unit::Meters,
+            Field::Salinity => Unit::PartsPerThousand,
+        }
+    }
+
+    /// Get the canonical transform bringing this field to a common scale.
+    ///
+    /// Unsigned fields map onto [0, 1] and signed ones onto [-1, 1], so
+    /// observations can mix fields without one dominating by its units.
+    #[must_use]
+    pub const fn normalization(self) -> Normalization {
+        match self {
+            Field::Occupancy
+            | Field::Integrity
+            | Field::Smoke
+            | Field::Signal
+            | Field::SonarReturn => Normalization::Identity,
+            Field::Material => Normalization::Linear { min: 0.0, max: 255.0 },
+            // Ambient water near 0, burning wrecks and explosions past 0.5
+            Field::Temperature => Normalization::Linear { min: 250.0, max: 1500.0 },
+            Field::Noise => Normalization::Linear { min: 0.0, max: 200.0 },
+            Field::CurrentX | Field::CurrentY => Normalization::Symmetric { limit: 10.0 },
+            Field::Depth => Normalization::Linear { min: 0.0, max: 10000.0 },
+            Field::Salinity => Normalization::Linear { min: 0.0, max: 50.0 },
+        }
+    }
+
+    /// Normalize a raw value of this field.
+    #[must_use]
+    pub fn normalize(self, value: f32) -> f32 {
+        self.normalization().apply(value)
+    }
+}
+
+/// Physical unit of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Unit {
+    /// Dimensionless fraction in [0, 1]
+    Fraction,
+    /// Material identifier (a category, not a quantity)
+    MaterialId,
+    /// Kelvin
+    Kelvin,
+    /// Decibels
+    Decibel,
+    /// Meters per second
+    MetersPerSecond,
+    /// Meters
+    Meters,
+    /// Parts per thousand
+    PartsPerThousand,
+}
+
+impl Unit {
+    /// Get the unit symbol (empty for dimensionless units).
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Unit::Fraction | Unit::MaterialId => "",
+            Unit::Kelvin => "K",
+            Unit::Decibel => "dB",
+            Unit::MetersPerSecond => "m/s",
+            Unit::Meters => "m",
+            Unit::PartsPerThousand => "ppt",
+        }
+    }
+}
+
+/// Transform from a field's raw values to a common scale.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Normalization {
+    /// Already in [0, 1]; values are only clamped
+    Identity,
+    /// Maps [min, max] onto [0, 1], clamping outside
+    Linear {
+        /// Raw value mapped to 0
+        min: f32,
+        /// Raw value mapped to 1
+        max: f32,
+    },
+    /// Maps [-limit, limit] onto [-1, 1], clamping outside, so zero stays zero
+    Symmetric {
+        /// Raw magnitude mapped to 1
+        limit: f32,
+    },
+}
+
+impl Normalization {
+    /// Apply the transform to a raw value.
+    #[must_use]
+    pub fn apply(self, value: f32) -> f32 {
+        match self {
+            Normalization::Identity => value.clamp(0.0, 1.0),
+            Normalization::Linear { min, max } => ((value - min) / (max - min)).clamp(0.0, 1.0),
+            Normalization::Symmetric { limit } => (value / limit).clamp(-1.0, 1.0),
+        }
+    }
 }
 
 /// How values are aggregated when combining cells.
@@ -302,4 +413,18 @@ mod tests {
         assert_eq!(config.clamp(0.5), 0.5);
         assert_eq!(config.clamp(1.5), 1.0);
     }
+
+    #[test]
+    fn test_field_normalization() {
+        assert_eq!(Field::Temperature.unit().symbol(), "K");
+        assert!(Field::Temperature.normalize(293.0) < 0.1);
+        assert_eq!(Field::Temperature.normalize(5000.0), 1.0);
+        assert_eq!(Field::Noise.normalize(100.0), 0.5);
+        assert_eq!(Field::CurrentX.normalize(-5.0), -0.5);
+        assert_eq!(Field::Occupancy.normalize(1.5), 1.0);
+        for field in Field::all() {
+            let config = FieldConfig::default_for(*field);
+            assert!(field.normalize(config.default_value).abs() <= 1.0);
+        }
+    }
 }
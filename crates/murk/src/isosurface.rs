@@ -0,0 +1,505 @@
+//! Isosurface and isoline extraction.
+//!
+//! Extracts the surface where a field crosses a threshold, so smoke clouds
+//! and thermal plumes can be visualized without exporting dense grids.
+//!
+//! The field is sampled at the vertices of a regular grid over the requested
+//! region (clipped to the world bounds), with cells no smaller than the
+//! octree's base resolution and at most [`MAX_CELLS_PER_AXIS`] cells along
+//! each axis.
+//!
+//! - **Surfaces** use marching cubes with every cube split into six
+//!   tetrahedra sharing its main diagonal. The split is consistent between
+//!   neighboring cubes, so the mesh has no cracks and none of the ambiguous
+//!   cases of the classic 256-case table. Triangles are wound
+//!   counter-clockwise when seen from the low side, so normals point from
+//!   values at or above the threshold toward values below it.
+//! - **Isolines** use marching squares on a horizontal slice, with every
+//!   square split into two triangles. Segments are joined into polylines;
+//!   closed loops end with their first point.
+//!
+//! Samples equal to the threshold count as inside.
+
+use std::collections::HashMap;
+
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::field::Field;
+use crate::octree::Octree;
+use crate::query::PointQuery;
+use crate::Bounds;
+
+/// Maximum number of grid cells along each axis.
+pub const MAX_CELLS_PER_AXIS: usize = 64;
+
+/// Cube corners as (x, y, z) offsets; corner `i` has bit 0 = x, 1 = y, 2 = z.
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0],
+    [1, 0, 0],
+    [0, 1, 0],
+    [1, 1, 0],
+    [0, 0, 1],
+    [1, 0, 1],
+    [0, 1, 1],
+    [1, 1, 1],
+];
+
+/// Six tetrahedra around the 0-7 diagonal (one per axis ordering).
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 2, 3, 7],
+    [0, 2, 6, 7],
+    [0, 4, 6, 7],
+    [0, 4, 5, 7],
+    [0, 1, 5, 7],
+];
+
+/// Triangle mesh of an isosurface.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IsoMesh {
+    /// Vertex positions (shared between adjacent triangles)
+    pub vertices: Vec<Vec3>,
+    /// Triangles as indices into `vertices`
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl IsoMesh {
+    /// Check if the mesh has no triangles.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Total surface area.
+    #[must_use]
+    pub fn area(&self) -> f32 {
+        self.triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.map(|i| self.vertices[i as usize]);
+                0.5 * (b - a).cross(c - a).length()
+            })
+            .sum()
+    }
+}
+
+/// Field samples on a regular grid.
+struct SampleGrid {
+    origin: Vec3,
+    step: Vec3,
+    /// Number of samples along each axis
+    dims: [usize; 3],
+    values: Vec<f32>,
+}
+
+impl SampleGrid {
+    /// Samples `field` over `region` (clipped to the octree bounds), with
+    /// `flat` collapsing the z axis to a single layer at `region.min.z`.
+    ///
+    /// Returns `None` if the clipped region is empty.
+    fn sample(octree: &Octree, field: Field, region: Bounds, flat: bool) -> Option<Self> {
+        let world = octree.config().bounds;
+        let min = region.min.max(world.min);
+        let max = region.max.min(world.max);
+        let extent = max - min;
+        if extent.x <= 0.0 || extent.y <= 0.0 || (!flat && extent.z <= 0.0) {
+            return None;
+        }
+
+        let base = octree.config().base_resolution;
+        // The ratio is positive and the cast saturates, so clamping afterwards is exact
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let cells = |length: f32| ((length / base).ceil() as usize).clamp(1, MAX_CELLS_PER_AXIS);
+        let dims = [
+            cells(extent.x) + 1,
+            cells(extent.y) + 1,
+            if flat { 1 } else { cells(extent.z) + 1 },
+        ];
+        // Dimensions are at most MAX_CELLS_PER_AXIS + 1, exactly representable in f32
+        #[allow(clippy::cast_precision_loss)]
+        let step = Vec3::new(
+            extent.x / (dims[0] - 1) as f32,
+            extent.y / (dims[1] - 1) as f32,
+            if flat { 0.0 } else { extent.z / (dims[2] - 1) as f32 },
+        );
+
+        let mut grid = Self {
+            origin: min,
+            step,
+            dims,
+            values: Vec::with_capacity(dims[0] * dims[1] * dims[2]),
+        };
+        for z in 0..dims[2] {
+            for y in 0..dims[1] {
+                for x in 0..dims[0] {
+                    let position = grid.position([x, y, z]);
+                    let value = octree.query_point(&PointQuery::new(position)).values.get(field);
+                    grid.values.push(value);
+                }
+            }
+        }
+        Some(grid)
+    }
+
+    /// Linear index of a grid vertex.
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        (z * self.dims[1] + y) * self.dims[0] + x
+    }
+
+    /// World position of a grid vertex.
+    #[allow(clippy::cast_precision_loss)] // Indices are at most MAX_CELLS_PER_AXIS
+    fn position(&self, [x, y, z]: [usize; 3]) -> Vec3 {
+        self.origin + self.step * Vec3::new(x as f32, y as f32, z as f32)
+    }
+
+    /// Point where the field crosses `threshold` between two grid vertices.
+    fn crossing(&self, a: usize, b: usize, threshold: f32) -> Vec3 {
+        let (va, vb) = (self.values[a], self.values[b]);
+        let t = if (vb - va).abs() > f32::EPSILON {
+            ((threshold - va) / (vb - va)).clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+        let (pa, pb) = (self.vertex_position(a), self.vertex_position(b));
+        pa + (pb - pa) * t
+    }
+
+    /// World position of a grid vertex given its linear index.
+    fn vertex_position(&self, index: usize) -> Vec3 {
+        let x = index % self.dims[0];
+        let y = (index / self.dims[0]) % self.dims[1];
+        let z = index / (self.dims[0] * self.dims[1]);
+        self.position([x, y, z])
+    }
+}
+
+/// Interpolated points shared between cells, keyed by grid edge.
+#[derive(Default)]
+struct EdgePoints {
+    ids: HashMap<(usize, usize), u32>,
+    points: Vec<Vec3>,
+}
+
+impl EdgePoints {
+    /// Returns the ID of the crossing on edge `a`-`b`, creating it if needed.
+    fn get(&mut self, grid: &SampleGrid, a: usize, b: usize, threshold: f32) -> u32 {
+        let key = (a.min(b), a.max(b));
+        if let Some(&id) = self.ids.get(&key) {
+            return id;
+        }
+        let id = u32::try_from(self.points.len()).expect("crossings are bounded by the grid size");
+        self.points.push(grid.crossing(key.0, key.1, threshold));
+        self.ids.insert(key, id);
+        id
+    }
+}
+
+/// Extracts the surface where `field` crosses `threshold` within `region`.
+#[must_use]
+pub fn extract_isosurface(octree: &Octree, field: Field, threshold: f32, region: Bounds) -> IsoMesh {
+    let Some(grid) = SampleGrid::sample(octree, field, region, false) else {
+        return IsoMesh::default();
+    };
+    let mut edges = EdgePoints::default();
+    let mut triangles = Vec::new();
+
+    for z in 0..grid.dims[2] - 1 {
+        for y in 0..grid.dims[1] - 1 {
+            for x in 0..grid.dims[0] - 1 {
+                let corners = CORNERS.map(|[dx, dy, dz]| grid.index([x + dx, y + dy, z + dz]));
+                for tet in TETRAHEDRA {
+                    let vertices = tet.map(|c| corners[c]);
+                    polygonize(&grid, &mut edges, &mut triangles, vertices, threshold);
+                }
+            }
+        }
+    }
+
+    IsoMesh {
+        vertices: edges.points,
+        triangles,
+    }
+}
+
+/// Adds the triangles of one tetrahedron to `triangles`.
+fn polygonize(
+    grid: &SampleGrid,
+    edges: &mut EdgePoints,
+    triangles: &mut Vec<[u32; 3]>,
+    vertices: [usize; 4],
+    threshold: f32,
+) {
+    let (inside, outside): (Vec<usize>, Vec<usize>) =
+        vertices.iter().partition(|&&v| grid.values[v] >= threshold);
+    if inside.is_empty() || outside.is_empty() {
+        return;
+    }
+
+    // Crossings in cyclic order around the tetrahedron
+    let ring: Vec<u32> = match (inside.as_slice(), outside.as_slice()) {
+        ([a], [b, c, d]) | ([b, c, d], [a]) => vec![
+            edges.get(grid, *a, *b, threshold),
+            edges.get(grid, *a, *c, threshold),
+            edges.get(grid, *a, *d, threshold),
+        ],
+        ([a, b], [c, d]) => vec![
+            edges.get(grid, *a, *c, threshold),
+            edges.get(grid, *a, *d, threshold),
+            edges.get(grid, *b, *d, threshold),
+            edges.get(grid, *b, *c, threshold),
+        ],
+        _ => return,
+    };
+
+    // Wind so the normal points from the inside toward the outside
+    #[allow(clippy::cast_precision_loss)] // A tetrahedron has four corners
+    let centroid = |set: &[usize]| {
+        set.iter().map(|&v| grid.vertex_position(v)).sum::<Vec3>() / set.len() as f32
+    };
+    let outward = centroid(&outside) - centroid(&inside);
+    let p = |i: u32| edges.points[i as usize];
+    let normal = (p(ring[1]) - p(ring[0])).cross(p(ring[2]) - p(ring[0]));
+    let flip = normal.dot(outward) < 0.0;
+
+    for i in 1..ring.len() - 1 {
+        let triangle = [ring[0], ring[i], ring[i + 1]];
+        triangles.push(if flip {
+            [triangle[0], triangle[2], triangle[1]]
+        } else {
+            triangle
+        });
+    }
+}
+
+/// Extracts the lines where `field` crosses `threshold` on the horizontal
+/// slice at height `z`, within the x/y extent of `region`.
+///
+/// Returns polylines of (x, y) points; closed loops end with their first
+/// point.
+#[must_use]
+pub fn extract_isolines(
+    octree: &Octree,
+    field: Field,
+    threshold: f32,
+    region: Bounds,
+    z: f32,
+) -> Vec<Vec<Vec2>> {
+    let world = octree.config().bounds;
+    if z < world.min.z || z > world.max.z {
+        return Vec::new();
+    }
+    let slice = Bounds::from_min_max(
+        region.min.truncate().extend(z),
+        region.max.truncate().extend(z),
+    );
+    let Some(grid) = SampleGrid::sample(octree, field, slice, true) else {
+        return Vec::new();
+    };
+    let mut edges = EdgePoints::default();
+    let mut segments: Vec<[u32; 2]> = Vec::new();
+
+    for y in 0..grid.dims[1] - 1 {
+        for x in 0..grid.dims[0] - 1 {
+            let square = [[x, y], [x + 1, y], [x + 1, y + 1], [x, y + 1]]
+                .map(|[sx, sy]| grid.index([sx, sy, 0]));
+            for triangle in [[square[0], square[1], square[2]], [square[0], square[2], square[3]]] {
+                let (inside, outside): (Vec<usize>, Vec<usize>) =
+                    triangle.iter().partition(|&&v| grid.values[v] >= threshold);
+                let segment = match (inside.as_slice(), outside.as_slice()) {
+                    ([a], [b, c]) | ([b, c], [a]) => [
+                        edges.get(&grid, *a, *b, threshold),
+                        edges.get(&grid, *a, *c, threshold),
+                    ],
+                    _ => continue,
+                };
+                if segment[0] != segment[1] {
+                    segments.push(segment);
+                }
+            }
+        }
+    }
+
+    join_segments(&segments)
+        .into_iter()
+        .map(|chain| chain.iter().map(|&i| edges.points[i as usize].truncate()).collect())
+        .collect()
+}
+
+/// Joins segments sharing endpoints into chains of point IDs.
+///
+/// Every point is shared by at most two segments, so chains are either open
+/// paths or closed loops (ending with their first point).
+fn join_segments(segments: &[[u32; 2]]) -> Vec<Vec<u32>> {
+    let mut neighbors: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (i, [a, b]) in segments.iter().enumerate() {
+        neighbors.entry(*a).or_default().push(i);
+        neighbors.entry(*b).or_default().push(i);
+    }
+    let mut used = vec![false; segments.len()];
+
+    let walk = |start: usize, from: u32, used: &mut Vec<bool>| {
+        let mut chain = vec![from];
+        let mut segment = start;
+        let mut point = from;
+        loop {
+            used[segment] = true;
+            let [a, b] = segments[segment];
+            point = if a == point { b } else { a };
+            chain.push(point);
+            let next = neighbors[&point].iter().copied().find(|&s| !used[s]);
+            match next {
+                Some(s) => segment = s,
+                None => return chain,
+            }
+        }
+    };
+
+    let mut chains = Vec::new();
+    // Open paths first, starting from their ends, so they are not split
+    for (i, [a, b]) in segments.iter().enumerate() {
+        if used[i] {
+            continue;
+        }
+        if neighbors[a].len() == 1 {
+            chains.push(walk(i, *a, &mut used));
+        } else if neighbors[b].len() == 1 {
+            chains.push(walk(i, *b, &mut used));
+        }
+    }
+    // Everything left is a closed loop
+    for i in 0..segments.len() {
+        if !used[i] {
+            chains.push(walk(i, segments[i][0], &mut used));
+        }
+    }
+    chains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::FieldValues;
+    use crate::octree::OctreeConfig;
+
+    /// An octree with 1 m cells where `value(x, y, z)` is set at every cell.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)] // Small whole sizes
+    fn octree_with(size: f32, value: impl Fn(Vec3) -> f32) -> Octree {
+        let bounds = Bounds::new(size, size, size);
+        let mut octree = Octree::new(OctreeConfig {
+            bounds,
+            base_resolution: 1.0,
+            max_depth: OctreeConfig::calculate_max_depth(&bounds, 1.0),
+            ..Default::default()
+        });
+        let half = size / 2.0;
+        let cells = size as i32;
+        for z in 0..cells {
+            for y in 0..cells {
+                for x in 0..cells {
+                    let center = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5) - half;
+                    let mut values = FieldValues::new();
+                    values.set(Field::Smoke, value(center));
+                    octree.set_point(center, values);
+                }
+            }
+        }
+        octree
+    }
+
+    #[test]
+    fn test_empty_field_has_no_surface() {
+        let octree = Octree::with_bounds(Bounds::new(16.0, 16.0, 16.0), 1.0);
+        let mesh = extract_isosurface(&octree, Field::Smoke, 0.5, octree.config().bounds);
+        assert!(mesh.is_empty());
+        assert!(extract_isolines(&octree, Field::Smoke, 0.5, octree.config().bounds, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_sphere_surface() {
+        let octree = octree_with(16.0, |p| 5.0 - p.length());
+        let mesh = extract_isosurface(&octree, Field::Smoke, 0.0, octree.config().bounds);
+
+        assert!(!mesh.is_empty());
+        // Vertices lie near the radius-5 sphere
+        for v in &mesh.vertices {
+            assert!((v.length() - 5.0).abs() < 1.0, "{v}");
+        }
+        // Area is close to 4 pi r^2
+        let expected = 4.0 * std::f32::consts::PI * 25.0;
+        assert!((mesh.area() - expected).abs() / expected < 0.15);
+    }
+
+    #[test]
+    fn test_surface_normals_point_outward() {
+        let octree = octree_with(16.0, |p| 5.0 - p.length());
+        let mesh = extract_isosurface(&octree, Field::Smoke, 0.0, octree.config().bounds);
+
+        for t in &mesh.triangles {
+            let [a, b, c] = t.map(|i| mesh.vertices[i as usize]);
+            let normal = (b - a).cross(c - a);
+            assert!(normal.dot((a + b + c) / 3.0) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_surface_is_closed() {
+        // Every edge of a closed surface is shared by exactly two triangles
+        let octree = octree_with(16.0, |p| 4.0 - p.length());
+        let mesh = extract_isosurface(&octree, Field::Smoke, 0.0, octree.config().bounds);
+
+        let mut edges: HashMap<(u32, u32), u32> = HashMap::new();
+        for t in &mesh.triangles {
+            for (a, b) in [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])] {
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        assert!(edges.values().all(|&count| count == 2));
+    }
+
+    #[test]
+    fn test_isolines_circle() {
+        let octree = octree_with(16.0, |p| 5.0 - p.truncate().length());
+        let lines = extract_isolines(&octree, Field::Smoke, 0.0, octree.config().bounds, 0.5);
+
+        assert_eq!(lines.len(), 1);
+        let loop_points = &lines[0];
+        assert_eq!(loop_points.first(), loop_points.last());
+        for p in loop_points {
+            assert!((p.length() - 5.0).abs() < 1.0, "{p}");
+        }
+    }
+
+    #[test]
+    fn test_isolines_open_at_region_edge() {
+        // A plane crossing the slice gives one open line
+        let octree = octree_with(16.0, |p| p.x);
+        let lines = extract_isolines(&octree, Field::Smoke, 0.0, octree.config().bounds, 0.5);
+
+        assert_eq!(lines.len(), 1);
+        assert_ne!(lines[0].first(), lines[0].last());
+        assert!(lines[0].iter().all(|p| p.x.abs() < 1.0));
+    }
+
+    #[test]
+    fn test_region_is_clipped() {
+        let octree = octree_with(16.0, |p| 5.0 - p.length());
+        let outside = Bounds::from_min_max(Vec3::splat(100.0), Vec3::splat(200.0));
+        assert!(extract_isosurface(&octree, Field::Smoke, 0.0, outside).is_empty());
+
+        // Half the world: only the positive-x half of the sphere
+        let half = Bounds::from_min_max(Vec3::new(0.0, -8.0, -8.0), Vec3::splat(8.0));
+        let mesh = extract_isosurface(&octree, Field::Smoke, 0.0, half);
+        assert!(!mesh.is_empty());
+        assert!(mesh.vertices.iter().all(|v| v.x >= -1e-4));
+    }
+
+    #[test]
+    fn test_join_segments() {
+        let chains = join_segments(&[[1, 2], [0, 1], [5, 6], [6, 7], [7, 5]]);
+        assert_eq!(chains.len(), 2);
+        assert_eq!(chains[0], vec![2, 1, 0]);
+        assert_eq!(chains[1].len(), 4);
+        assert_eq!(chains[1].first(), chains[1].last());
+    }
+}
@@ -46,26 +46,38 @@
 #![warn(clippy::pedantic)]
 #![allow(clippy::module_name_repetitions)]
 
+pub mod chunked;
 pub mod field;
 pub mod hash;
+pub mod isosurface;
 pub mod node;
 pub mod octree;
+pub mod pathfinding;
 pub mod propagation;
 pub mod query;
+pub mod schedule;
 pub mod stamp;
 pub mod stats;
+pub mod template;
 pub mod universe;
 
 // Re-exports for convenience
-pub use field::{Field, FieldConfig, FieldValues};
+#[cfg(feature = "directory-store")]
+pub use chunked::DirectoryChunkStore;
+pub use chunked::{ChunkCoord, ChunkStore, ChunkedUniverse, MemoryChunkStore};
+pub use field::{Field, FieldConfig, FieldValues, Normalization, Unit};
 pub use hash::hash_universe;
+pub use isosurface::IsoMesh;
 pub use node::{NodeState, OctreeNode};
-pub use octree::{Direction, Octree};
-pub use propagation::{apply_decay, apply_diffusion};
-pub use query::{QueryResolution, VolumeQuery};
+pub use octree::{Direction, FrozenRegion, NodeKind, NodeSummary, Octree};
+pub use pathfinding::{CostGrid, FieldCost, PathCosts};
+pub use propagation::{apply_decay, apply_diffusion, ConfiguredPropagation, FieldProcess};
+pub use query::{Histogram, QueryRegion, QueryResolution, VolumeQuery};
+pub use schedule::{ScheduledStamp, StampScheduler};
 pub use stamp::{BlendOp, FieldMod, Stamp, StampShape};
 pub use stats::{FieldStats, ScalarStats};
-pub use universe::{Universe, UniverseConfig};
+pub use template::{ModTemplate, Scalar, ShapeTemplate, StampLibrary, StampTemplate, TemplateError};
+pub use universe::{Universe, UniverseConfig, UniverseStats};
 
 /// Axis-aligned bounding box.
 #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -146,6 +158,13 @@ impl Bounds {
             .all(|&c| center.distance_squared(c) <= r2)
     }
 
+    /// Get the volume shared with another bounds (0 if disjoint).
+    #[must_use]
+    pub fn overlap_volume(&self, other: &Self) -> f32 {
+        let extent = (self.max.min(other.max) - self.min.max(other.min)).max(glam::Vec3::ZERO);
+        extent.x * extent.y * extent.z
+    }
+
     /// Get the octant index for a point (0-7).
     #[must_use]
     pub fn octant_index(&self, point: glam::Vec3) -> usize {
@@ -179,6 +198,24 @@ impl Bounds {
         );
         Self { min, max }
     }
+
+    /// Get the quadrant index for a point (0-3), ignoring its Z coordinate.
+    ///
+    /// Quadrants are numbered like the octants with a clear Z bit.
+    #[must_use]
+    pub fn quadrant_index(&self, point: glam::Vec3) -> usize {
+        self.octant_index(point) & 3
+    }
+
+    /// Get the bounds of a child quadrant, which spans the full Z range.
+    #[must_use]
+    pub fn quadrant_bounds(&self, quadrant: usize) -> Self {
+        let octant = self.child_bounds(quadrant & 3);
+        Self {
+            min: octant.min.with_z(self.min.z),
+            max: octant.max.with_z(self.max.z),
+        }
+    }
 }
 
 impl Default for Bounds {
@@ -215,4 +252,23 @@ mod tests {
         assert_eq!(child.min, glam::Vec3::new(-5.0, -5.0, -5.0));
         assert_eq!(child.max, glam::Vec3::new(0.0, 0.0, 0.0));
     }
+
+    #[test]
+    fn test_quadrant_bounds_span_depth() {
+        let bounds = Bounds::new(10.0, 10.0, 4.0);
+        assert_eq!(bounds.quadrant_index(glam::Vec3::new(1.0, 1.0, 1.0)), 3);
+        assert_eq!(bounds.quadrant_index(glam::Vec3::new(-1.0, 1.0, -1.0)), 2);
+        let child = bounds.quadrant_bounds(1);
+        assert_eq!(child.min, glam::Vec3::new(0.0, -5.0, -2.0));
+        assert_eq!(child.max, glam::Vec3::new(5.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn test_bounds_overlap_volume() {
+        let bounds = Bounds::new(10.0, 10.0, 10.0);
+        let shifted = Bounds::from_min_max(glam::Vec3::ZERO, glam::Vec3::splat(10.0));
+        assert_eq!(bounds.overlap_volume(&bounds), 1000.0);
+        assert_eq!(bounds.overlap_volume(&shifted), 125.0);
+        assert_eq!(bounds.overlap_volume(&shifted.child_bounds(7)), 0.0);
+    }
 }
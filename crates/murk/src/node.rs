@@ -2,7 +2,9 @@
 //!
 //! Nodes can be empty, leaf (with field values), or internal (with children and stats).
 
-use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::field::FieldValues;
 use crate::stats::FieldStats;
@@ -28,6 +30,50 @@ impl Default for NodeState {
     }
 }
 
+/// Logical time of the last stamp or query that reached a node.
+///
+/// Queries only borrow the octree, so the time is kept in an atomic and can
+/// be updated through a shared reference. It only orders evictions under a
+/// memory budget and is not part of the state hash.
+#[derive(Debug, Default)]
+pub struct Recency(AtomicU64);
+
+impl Recency {
+    /// Get the recorded time.
+    #[must_use]
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Record `time` if it is later than the recorded one.
+    pub fn touch(&self, time: u64) {
+        self.0.fetch_max(time, Ordering::Relaxed);
+    }
+
+    /// Advance the time by one and return the new value (used as a clock).
+    pub fn advance(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+impl Clone for Recency {
+    fn clone(&self) -> Self {
+        Self(AtomicU64::new(self.get()))
+    }
+}
+
+impl Serialize for Recency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Recency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(|t| Self(AtomicU64::new(t)))
+    }
+}
+
 /// A node in the octree.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OctreeNode {
@@ -37,6 +83,9 @@ pub struct OctreeNode {
     pub depth: u8,
     /// Node state (empty, leaf, or internal)
     pub state: NodeState,
+    /// When a stamp or query last reached this node
+    #[serde(default)]
+    pub last_touched: Recency,
 }
 
 impl OctreeNode {
@@ -47,6 +96,7 @@ impl OctreeNode {
             bounds,
             depth,
             state: NodeState::Empty,
+            last_touched: Recency::default(),
         }
     }
 
@@ -57,6 +107,7 @@ impl OctreeNode {
             bounds,
             depth,
             state: NodeState::Leaf { values },
+            last_touched: Recency::default(),
         }
     }
 
@@ -129,6 +180,17 @@ impl OctreeNode {
 
     /// Convert this node to an internal node, distributing current value to children.
     pub fn split(&mut self) {
+        self.split_into(8, Bounds::child_bounds);
+    }
+
+    /// Like [`OctreeNode::split`], but divides the node in X and Y only,
+    /// into four children spanning its full Z range (quadrants 0-3).
+    pub fn split_planar(&mut self) {
+        self.split_into(4, Bounds::quadrant_bounds);
+    }
+
+    /// Split into the first `count` children, bounded by `child_bounds`.
+    fn split_into(&mut self, count: usize, child_bounds: fn(&Bounds, usize) -> Bounds) {
         let values = match &self.state {
             NodeState::Empty => FieldValues::new(),
             NodeState::Leaf { values } => *values,
@@ -136,8 +198,10 @@ impl OctreeNode {
         };
 
         let children: [Option<Box<OctreeNode>>; 8] = std::array::from_fn(|i| {
-            let child_bounds = self.bounds.child_bounds(i);
-            Some(Box::new(OctreeNode::leaf(child_bounds, self.depth + 1, values)))
+            (i < count).then(|| {
+                let bounds = child_bounds(&self.bounds, i);
+                Box::new(OctreeNode::leaf(bounds, self.depth + 1, values))
+            })
         });
 
         self.state = NodeState::Internal {
@@ -186,6 +250,34 @@ impl OctreeNode {
         }
     }
 
+    /// Replace the children with a single leaf of their mean values,
+    /// regardless of their variance (or an empty node if all are empty).
+    ///
+    /// Returns true if the node was internal.
+    pub fn coarsen(&mut self) -> bool {
+        if !self.is_internal() {
+            return false;
+        }
+        self.update_stats();
+        let merged = match &self.state {
+            NodeState::Internal { children, stats } => {
+                children.iter().flatten().any(|c| !c.is_empty()).then(|| stats.clone())
+            }
+            _ => None,
+        };
+        self.state = match merged {
+            Some(stats) => {
+                let mut values = FieldValues::new();
+                for (i, scalar_stats) in stats.scalars.iter().enumerate() {
+                    values.as_slice_mut()[i] = scalar_stats.mean;
+                }
+                NodeState::Leaf { values }
+            }
+            None => NodeState::Empty,
+        };
+        true
+    }
+
     /// Update cached statistics from children.
     pub fn update_stats(&mut self) {
         if let NodeState::Internal { children, stats } = &mut self.state {
@@ -229,6 +321,37 @@ mod tests {
         assert!(children.iter().all(|c| c.is_some()));
     }
 
+    #[test]
+    fn test_node_split_planar() {
+        let bounds = Bounds::new(100.0, 100.0, 10.0);
+        let mut node = OctreeNode::leaf(bounds, 0, FieldValues::new());
+
+        node.split_planar();
+        let children = node.children().unwrap();
+        assert!(children[..4].iter().all(Option::is_some));
+        assert!(children[4..].iter().all(Option::is_none));
+        let child = children[3].as_ref().unwrap();
+        assert_eq!(child.bounds.min, Vec3::new(0.0, 0.0, -5.0));
+        assert_eq!(child.bounds.max, Vec3::new(50.0, 50.0, 5.0));
+    }
+
+    #[test]
+    fn test_node_coarsen() {
+        let bounds = Bounds::new(100.0, 100.0, 100.0);
+        let mut node = OctreeNode::leaf(bounds, 0, FieldValues::new());
+        node.split();
+        let ambient = FieldValues::new().get(crate::field::Field::Temperature);
+        let mut hot = FieldValues::new();
+        hot.set(crate::field::Field::Temperature, ambient + 800.0);
+        node.children_mut().unwrap()[0].as_mut().unwrap().make_leaf(hot);
+
+        assert!(node.coarsen());
+        let mean = ambient + 100.0;
+        let values = node.values().unwrap();
+        assert!((values.get(crate::field::Field::Temperature) - mean).abs() < 1e-3);
+        assert!(!node.coarsen());
+    }
+
     #[test]
     fn test_child_bounds() {
         let bounds = Bounds::new(100.0, 100.0, 100.0);
@@ -2,13 +2,22 @@
 //!
 //! The octree provides hierarchical spatial storage with lazy allocation
 //! and statistical aggregation at each level.
+//!
+//! Localized stamps refine the tree around them, so a long-running world
+//! keeps growing. With [`OctreeConfig::memory_budget`] set,
+//! [`Octree::enforce_memory_budget`] coarsens the finest subtrees that
+//! stamps and queries reached least recently until the nodes fit again.
+//!
+//! Surface-only scenarios can set [`OctreeConfig::planar`]: cells then split
+//! in X and Y only, so every cell spans the full depth range and the tree
+//! behaves as a quadtree, at half the branching of the 3D layout.
 
 use glam::Vec3;
 use serde::{Deserialize, Serialize};
 
-use crate::field::FieldValues;
-use crate::node::{NodeState, OctreeNode};
-use crate::query::{PointQuery, PointResult, QueryResult, VolumeQuery};
+use crate::field::{Field, FieldValues};
+use crate::node::{NodeState, OctreeNode, Recency};
+use crate::query::{PointQuery, PointResult, QueryRegion, QueryResult, VolumeQuery};
 use crate::stamp::Stamp;
 use crate::stats::FieldStats;
 use crate::Bounds;
@@ -26,6 +35,13 @@ pub struct OctreeConfig {
     pub merge_threshold: f32,
     /// Variance threshold for splitting cells
     pub split_threshold: f32,
+    /// Upper bound on the memory held by nodes, in bytes (`None` for no
+    /// limit)
+    #[serde(default)]
+    pub memory_budget: Option<usize>,
+    /// Split cells in X and Y only, keeping the full Z range in one cell
+    #[serde(default)]
+    pub planar: bool,
 }
 
 impl Default for OctreeConfig {
@@ -36,6 +52,8 @@ impl Default for OctreeConfig {
             max_depth: 10,
             merge_threshold: 0.02,
             split_threshold: 0.1,
+            memory_budget: None,
+            planar: false,
         }
     }
 }
@@ -48,6 +66,86 @@ impl OctreeConfig {
         let levels = (max_dim / base_resolution).log2().ceil() as u8;
         levels.min(16) // Cap at 16 to avoid excessive depth
     }
+
+    /// Calculate maximum depth for a planar tree, from the X and Y extent only.
+    #[must_use]
+    pub fn calculate_planar_max_depth(bounds: &Bounds, base_resolution: f32) -> u8 {
+        let size = bounds.size();
+        let flat = Bounds::from_min_max(Vec3::ZERO, Vec3::new(size.x, size.y, 0.0));
+        Self::calculate_max_depth(&flat, base_resolution)
+    }
+
+    /// Number of children created when a cell splits (4 when planar, else 8).
+    #[must_use]
+    pub const fn arity(&self) -> usize {
+        if self.planar {
+            4
+        } else {
+            8
+        }
+    }
+
+    /// Index of the child of a cell with `bounds` that contains `point`.
+    #[must_use]
+    pub fn child_index(&self, bounds: &Bounds, point: Vec3) -> usize {
+        if self.planar {
+            bounds.quadrant_index(point)
+        } else {
+            bounds.octant_index(point)
+        }
+    }
+
+    /// Bounds of child `index` of a cell with `bounds`.
+    #[must_use]
+    pub fn child_bounds(&self, bounds: &Bounds, index: usize) -> Bounds {
+        if self.planar {
+            bounds.quadrant_bounds(index)
+        } else {
+            bounds.child_bounds(index)
+        }
+    }
+
+    /// Split `node` into its children, keeping the counters up to date.
+    fn split(&self, node: &mut OctreeNode, node_count: &mut usize, leaf_count: &mut usize) {
+        if !node.is_leaf() {
+            *leaf_count += 1;
+        }
+        if self.planar {
+            node.split_planar();
+        } else {
+            node.split();
+        }
+        *node_count += self.arity();
+        *leaf_count += self.arity() - 1;
+    }
+}
+
+/// Region where some fields cannot be changed by stamps or propagation.
+///
+/// Used for static features such as land terrain, so explosions do not carve
+/// holes in coastlines. Direct writes with [`Octree::set_point`] are still
+/// allowed, so frozen values can be authored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrozenRegion {
+    /// Region bounds
+    pub bounds: Bounds,
+    /// Fields held fixed inside the region
+    pub fields: Vec<Field>,
+}
+
+impl FrozenRegion {
+    /// Check if `field` is frozen at `point`.
+    #[must_use]
+    pub fn freezes(&self, point: Vec3, field: Field) -> bool {
+        self.fields.contains(&field) && self.bounds.contains(point)
+    }
+
+    /// Check if the region covers part, but not all, of `bounds`.
+    fn straddles(&self, bounds: &Bounds) -> bool {
+        let size = bounds.size();
+        let overlap = self.bounds.overlap_volume(bounds);
+        overlap > 0.0 && overlap < size.x * size.y * size.z
+    }
 }
 
 /// Sparse octree for field storage.
@@ -61,6 +159,15 @@ pub struct Octree {
     node_count: usize,
     /// Number of leaf nodes
     leaf_count: usize,
+    /// Regions where stamps and propagation cannot change some fields
+    #[serde(default)]
+    frozen: Vec<FrozenRegion>,
+    /// Logical clock advanced by every stamp and query
+    #[serde(default)]
+    clock: Recency,
+    /// Subtrees coarsened to stay within the memory budget
+    #[serde(default)]
+    evictions: u64,
 }
 
 impl Octree {
@@ -73,6 +180,9 @@ impl Octree {
             config,
             node_count: 1,
             leaf_count: 0,
+            frozen: Vec::new(),
+            clock: Recency::default(),
+            evictions: 0,
         }
     }
 
@@ -94,6 +204,31 @@ impl Octree {
         &self.config
     }
 
+    /// Freeze `fields` inside `bounds` against stamps and propagation.
+    pub fn freeze_region(&mut self, bounds: Bounds, fields: &[Field]) {
+        self.frozen.push(FrozenRegion {
+            bounds,
+            fields: fields.to_vec(),
+        });
+    }
+
+    /// Remove all frozen regions.
+    pub fn clear_frozen(&mut self) {
+        self.frozen.clear();
+    }
+
+    /// Get the frozen regions.
+    #[must_use]
+    pub fn frozen_regions(&self) -> &[FrozenRegion] {
+        &self.frozen
+    }
+
+    /// Check if `field` is frozen at `point`.
+    #[must_use]
+    pub fn is_frozen(&self, point: Vec3, field: Field) -> bool {
+        self.frozen.iter().any(|r| r.freezes(point, field))
+    }
+
     /// Get the root node.
     #[must_use]
     pub fn root(&self) -> &OctreeNode {
@@ -107,6 +242,141 @@ impl Octree {
             node_count: self.node_count,
             leaf_count: self.leaf_count,
             max_depth: self.config.max_depth,
+            memory_bytes: self.memory_bytes(),
+            memory_budget: self.config.memory_budget,
+            evictions: self.evictions,
+        }
+    }
+
+    /// Count the nodes at each depth, from the root (index 0) down to the
+    /// deepest allocated level.
+    #[must_use]
+    pub fn nodes_by_depth(&self) -> Vec<usize> {
+        let mut counts = Vec::new();
+        let mut level = vec![&self.root];
+        while !level.is_empty() {
+            counts.push(level.len());
+            level = level
+                .into_iter()
+                .filter_map(|node| node.children())
+                .flat_map(|children| children.iter().flatten().map(|child| &**child))
+                .collect();
+        }
+        counts
+    }
+
+    /// Approximate memory held by the nodes, in bytes.
+    fn memory_bytes(&self) -> usize {
+        self.node_count * std::mem::size_of::<OctreeNode>()
+    }
+
+    /// Coarsen subtrees until the nodes fit the memory budget, if one is set.
+    ///
+    /// Candidates are internal nodes whose children are all leaves (or
+    /// empty); each is replaced by a leaf holding its mean values. The
+    /// candidates least recently reached by a stamp or query go first,
+    /// deepest first among equals. Nodes straddling a frozen region are
+    /// never coarsened, so the budget may stay exceeded. Returns the number
+    /// of subtrees coarsened, which is also added to
+    /// [`OctreeStats::evictions`].
+    pub fn enforce_memory_budget(&mut self) -> usize {
+        let Some(budget) = self.config.memory_budget else {
+            return 0;
+        };
+        let mut evicted = 0;
+        while self.memory_bytes() > budget {
+            let mut candidates = Vec::new();
+            Self::collect_evictable(&self.root, &self.frozen, &mut Vec::new(), &mut candidates);
+            if candidates.is_empty() {
+                break;
+            }
+            candidates.sort_by_key(|(touched, path)| (*touched, std::cmp::Reverse(path.len())));
+            for (_, path) in candidates {
+                if self.memory_bytes() <= budget {
+                    break;
+                }
+                self.node_at_mut(&path).coarsen();
+                for depth in (0..path.len()).rev() {
+                    self.node_at_mut(&path[..depth]).update_stats();
+                }
+                self.node_count -= self.config.arity();
+                self.leaf_count -= self.config.arity() - 1;
+                evicted += 1;
+            }
+        }
+        self.evictions += evicted as u64;
+        evicted
+    }
+
+    /// Collect (last touched, octant path) of every internal node with no
+    /// internal children that does not straddle a frozen region.
+    fn collect_evictable(
+        node: &OctreeNode,
+        frozen: &[FrozenRegion],
+        path: &mut Vec<u8>,
+        out: &mut Vec<(u64, Vec<u8>)>,
+    ) {
+        let NodeState::Internal { children, .. } = &node.state else {
+            return;
+        };
+        if children.iter().flatten().any(|c| c.is_internal()) {
+            for (octant, child) in (0u8..).zip(children.iter()) {
+                if let Some(child) = child {
+                    path.push(octant);
+                    Self::collect_evictable(child, frozen, path, out);
+                    path.pop();
+                }
+            }
+        } else if !frozen.iter().any(|r| r.straddles(&node.bounds)) {
+            out.push((node.last_touched.get(), path.clone()));
+        }
+    }
+
+    /// Node reached from the root by following octant indices.
+    fn node_at_mut(&mut self, path: &[u8]) -> &mut OctreeNode {
+        let mut node = &mut self.root;
+        for &octant in path {
+            node = node
+                .children_mut()
+                .and_then(|c| c[octant as usize].as_deref_mut())
+                .expect("eviction path leads to an existing node");
+        }
+        node
+    }
+
+    /// Summarize the tree for debugging, down to `max_depth` levels below the root.
+    ///
+    /// Nodes deeper than `max_depth` are not listed, but are still counted in
+    /// their ancestors' `subtree_nodes`.
+    #[must_use]
+    pub fn debug_tree(&self, max_depth: u8) -> NodeSummary {
+        Self::summarize(&self.root, max_depth, self.config.merge_threshold)
+    }
+
+    /// Recursive helper for [`Octree::debug_tree`].
+    fn summarize(node: &OctreeNode, max_depth: u8, merge_threshold: f32) -> NodeSummary {
+        let stats = node.stats();
+        let mut children = Vec::new();
+        let mut subtree_nodes = 1;
+        for child in node.children().into_iter().flatten().flatten() {
+            let summary = Self::summarize(child, max_depth, merge_threshold);
+            subtree_nodes += summary.subtree_nodes;
+            if node.depth < max_depth {
+                children.push(summary);
+            }
+        }
+        NodeSummary {
+            bounds: node.bounds,
+            depth: node.depth,
+            kind: match node.state {
+                NodeState::Empty => NodeKind::Empty,
+                NodeState::Leaf { .. } => NodeKind::Leaf,
+                NodeState::Internal { .. } => NodeKind::Internal,
+            },
+            uniform: stats.as_ref().is_none_or(|s| s.is_uniform(merge_threshold)),
+            stats,
+            subtree_nodes,
+            children,
         }
     }
 
@@ -117,10 +387,17 @@ impl Octree {
             return PointResult::default();
         }
 
-        self.query_point_recursive(&self.root, query)
+        let now = self.clock.advance();
+        self.query_point_recursive(&self.root, query, now)
     }
 
-    fn query_point_recursive(&self, node: &OctreeNode, query: &PointQuery) -> PointResult {
+    fn query_point_recursive(
+        &self,
+        node: &OctreeNode,
+        query: &PointQuery,
+        now: u64,
+    ) -> PointResult {
+        node.last_touched.touch(now);
         match &node.state {
             NodeState::Empty => PointResult {
                 values: FieldValues::new(),
@@ -133,9 +410,9 @@ impl Octree {
                 interpolated: false,
             },
             NodeState::Internal { children, stats } => {
-                let octant = node.bounds.octant_index(query.position);
+                let octant = self.config.child_index(&node.bounds, query.position);
                 if let Some(child) = &children[octant] {
-                    self.query_point_recursive(child, query)
+                    self.query_point_recursive(child, query, now)
                 } else {
                     // No child at this octant, use stats
                     let mut values = FieldValues::new();
@@ -156,7 +433,8 @@ impl Octree {
     #[must_use]
     pub fn query_volume(&self, query: &VolumeQuery) -> QueryResult {
         let mut result = QueryResult::default();
-        self.query_volume_recursive(&self.root, query, &mut result);
+        let now = self.clock.advance();
+        self.query_volume_recursive(&self.root, query, &mut result, now);
         result
     }
 
@@ -165,14 +443,74 @@ impl Octree {
         node: &OctreeNode,
         query: &VolumeQuery,
         result: &mut QueryResult,
+        now: u64,
+    ) {
+        if self.visit_volume(node, query, result, now) {
+            if let NodeState::Internal { children, .. } = &node.state {
+                for child in children.iter().flatten() {
+                    self.query_volume_recursive(child, query, result, now);
+                }
+            }
+        }
+    }
+
+    /// Query many volumes in a single traversal.
+    ///
+    /// Each node is visited once for all the queries that reach it, and its
+    /// statistics are merged into every query it satisfies. The results are
+    /// the same as calling [`Self::query_volume`] for each query in turn,
+    /// including `nodes_visited`.
+    #[must_use]
+    pub fn query_batch(&self, queries: &[VolumeQuery]) -> Vec<QueryResult> {
+        let mut results = vec![QueryResult::default(); queries.len()];
+        let active: Vec<usize> = (0..queries.len()).collect();
+        let now = self.clock.advance();
+        self.query_batch_recursive(&self.root, queries, &active, &mut results, now);
+        results
+    }
+
+    fn query_batch_recursive(
+        &self,
+        node: &OctreeNode,
+        queries: &[VolumeQuery],
+        active: &[usize],
+        results: &mut [QueryResult],
+        now: u64,
     ) {
+        let descend: Vec<usize> = active
+            .iter()
+            .copied()
+            .filter(|&i| self.visit_volume(node, &queries[i], &mut results[i], now))
+            .collect();
+        if descend.is_empty() {
+            return;
+        }
+        if let NodeState::Internal { children, .. } = &node.state {
+            for child in children.iter().flatten() {
+                self.query_batch_recursive(child, queries, &descend, results, now);
+            }
+        }
+    }
+
+    /// Visit one node for a volume query, merging it into the result if it
+    /// answers the query here. Returns true if the query must descend into
+    /// the node's children instead. Nodes the query reaches are marked as
+    /// touched at `now`.
+    fn visit_volume(
+        &self,
+        node: &OctreeNode,
+        query: &VolumeQuery,
+        result: &mut QueryResult,
+        now: u64,
+    ) -> bool {
         result.nodes_visited += 1;
         result.max_depth_reached = result.max_depth_reached.max(node.depth);
 
         // Check if this node intersects the query sphere
         if !node.bounds.intersects_sphere(query.center, query.radius) {
-            return;
+            return false;
         }
+        node.last_touched.touch(now);
 
         let max_depth = query.resolution.max_depth(self.config.max_depth);
         let variance_threshold = query.resolution.variance_threshold();
@@ -181,40 +519,73 @@ impl Octree {
             NodeState::Empty => {
                 // Use default values
                 let empty_stats = FieldStats::from_values(&FieldValues::new());
-                result.stats = FieldStats::merge(&result.stats, &empty_stats);
+                Self::merge_region(node, query, empty_stats, false, result);
+                false
             }
             NodeState::Leaf { values } => {
-                let leaf_stats = FieldStats::from_values(values);
-                result.stats = FieldStats::merge(&result.stats, &leaf_stats);
+                let has_data = values.as_slice() != FieldValues::new().as_slice();
+                Self::merge_region(node, query, FieldStats::from_values(values), has_data, result);
+                false
             }
-            NodeState::Internal { children, stats } => {
+            NodeState::Internal { stats, .. } => {
                 // Check early-out conditions
                 let use_cached_stats = node.depth >= max_depth
                     || node.bounds.is_fully_inside_sphere(query.center, query.radius)
                     || variance_threshold.map_or(false, |t| stats.is_uniform(t));
 
                 if use_cached_stats {
-                    result.stats = FieldStats::merge(&result.stats, stats);
-                } else {
-                    // Recurse into children
-                    for child in children.iter().flatten() {
-                        self.query_volume_recursive(child, query, result);
-                    }
+                    // Summarized nodes count as data unless every field sits at its default.
+                    // Compared bit for bit: untouched cells hold the default exactly, and
+                    // an epsilon would hide small real values.
+                    let defaults = FieldValues::new();
+                    let has_data = Field::all().iter().any(|f| {
+                        let (s, default) = (stats.get(*f), defaults.get(*f).to_bits());
+                        s.min.to_bits() != default || s.max.to_bits() != default
+                    });
+                    Self::merge_region(node, query, stats.clone(), has_data, result);
                 }
+                !use_cached_stats
             }
         }
     }
 
+    /// Merge a node's statistics into a query result and record it as a region.
+    fn merge_region(
+        node: &OctreeNode,
+        query: &VolumeQuery,
+        stats: FieldStats,
+        has_data: bool,
+        result: &mut QueryResult,
+    ) {
+        result.stats = FieldStats::merge(&result.stats, &stats);
+        result.regions.push(QueryRegion {
+            stats,
+            volume: node.bounds.overlap_volume(&query.bounds()),
+            has_data,
+        });
+    }
+
     /// Apply a stamp to the octree.
     pub fn apply_stamp(&mut self, stamp: &Stamp) {
         let config = self.config.clone();
-        Self::apply_stamp_recursive(&mut self.root, stamp, &config, &mut self.node_count, &mut self.leaf_count);
+        let now = self.clock.advance();
+        Self::apply_stamp_recursive(
+            &mut self.root,
+            stamp,
+            now,
+            &config,
+            &self.frozen,
+            &mut self.node_count,
+            &mut self.leaf_count,
+        );
     }
 
     fn apply_stamp_recursive(
         node: &mut OctreeNode,
         stamp: &Stamp,
+        now: u64,
         config: &OctreeConfig,
+        frozen: &[FrozenRegion],
         node_count: &mut usize,
         leaf_count: &mut usize,
     ) {
@@ -222,57 +593,86 @@ impl Octree {
         if !stamp.shape.intersects(&node.bounds) {
             return;
         }
+        node.last_touched.touch(now);
+
+        // Cells straddling a frozen boundary are split so the mask is exact
+        // to the base resolution
+        let straddles_frozen = node.depth < config.max_depth
+            && frozen.iter().any(|r| r.straddles(&node.bounds));
 
         match &mut node.state {
+            NodeState::Empty if straddles_frozen => {
+                config.split(node, node_count, leaf_count);
+                Self::apply_stamp_recursive(
+                    node, stamp, now, config, frozen, node_count, leaf_count,
+                );
+            }
             NodeState::Empty => {
                 // Materialize as leaf and apply
                 node.state = NodeState::Leaf {
                     values: FieldValues::new(),
                 };
                 *leaf_count += 1;
-                Self::apply_stamp_to_leaf(node, stamp);
+                Self::apply_stamp_to_leaf(node, stamp, config, frozen);
             }
             NodeState::Leaf { .. } => {
                 // Check if we need to split
-                if node.depth < config.max_depth && Self::should_split_for_stamp(node, stamp, config) {
-                    node.split();
-                    *node_count += 8;
-                    *leaf_count += 7; // Was 1 leaf, now 8 leaves
-                    Self::apply_stamp_recursive(node, stamp, config, node_count, leaf_count);
+                if node.depth < config.max_depth
+                    && (straddles_frozen || Self::should_split_for_stamp(node, stamp, config))
+                {
+                    config.split(node, node_count, leaf_count);
+                    Self::apply_stamp_recursive(
+                    node, stamp, now, config, frozen, node_count, leaf_count,
+                );
                 } else {
-                    Self::apply_stamp_to_leaf(node, stamp);
+                    Self::apply_stamp_to_leaf(node, stamp, config, frozen);
                 }
             }
             NodeState::Internal { children, .. } => {
                 // Recurse into children
                 for child in children.iter_mut().flatten() {
-                    Self::apply_stamp_recursive(child, stamp, config, node_count, leaf_count);
+                    Self::apply_stamp_recursive(
+                        child, stamp, now, config, frozen, node_count, leaf_count,
+                    );
                 }
                 // Update cached stats
                 node.update_stats();
-                // Try to merge if variance is low
-                if node.try_merge(config.merge_threshold) {
-                    *node_count -= 8;
-                    *leaf_count -= 7;
+                // Try to merge if variance is low (never across a frozen boundary)
+                if !straddles_frozen && node.try_merge(config.merge_threshold) {
+                    *node_count -= config.arity();
+                    *leaf_count -= config.arity() - 1;
                 }
             }
         }
     }
 
+    /// Part of a cell a stamp is evaluated over.
+    ///
+    /// In a planar tree each cell is a full-depth column, so the stamp is
+    /// evaluated on the column's slice nearest the stamp's center depth.
+    fn stamp_footprint(node: &OctreeNode, stamp: &Stamp, config: &OctreeConfig) -> Bounds {
+        if !config.planar {
+            return node.bounds;
+        }
+        let z = stamp.shape.bounds().center().z.clamp(node.bounds.min.z, node.bounds.max.z);
+        Bounds::from_min_max(node.bounds.min.with_z(z), node.bounds.max.with_z(z))
+    }
+
     fn should_split_for_stamp(node: &OctreeNode, stamp: &Stamp, config: &OctreeConfig) -> bool {
         // Split if the stamp would create a significant gradient across the cell
         // For now, use a simple heuristic: split if stamp doesn't cover entire cell
+        let cell = Self::stamp_footprint(node, stamp, config);
         let cell_fully_covered = match &stamp.shape {
             crate::stamp::StampShape::Sphere { center, radius } => {
-                node.bounds.is_fully_inside_sphere(*center, *radius)
+                cell.is_fully_inside_sphere(*center, *radius)
             }
             crate::stamp::StampShape::Box { bounds } => {
-                bounds.min.x <= node.bounds.min.x
-                    && bounds.max.x >= node.bounds.max.x
-                    && bounds.min.y <= node.bounds.min.y
-                    && bounds.max.y >= node.bounds.max.y
-                    && bounds.min.z <= node.bounds.min.z
-                    && bounds.max.z >= node.bounds.max.z
+                bounds.min.x <= cell.min.x
+                    && bounds.max.x >= cell.max.x
+                    && bounds.min.y <= cell.min.y
+                    && bounds.max.y >= cell.max.y
+                    && bounds.min.z <= cell.min.z
+                    && bounds.max.z >= cell.max.z
             }
             crate::stamp::StampShape::Capsule { .. } => false, // Conservative
         };
@@ -280,14 +680,22 @@ impl Octree {
         !cell_fully_covered && node.cell_size() > config.base_resolution * 2.0
     }
 
-    fn apply_stamp_to_leaf(node: &mut OctreeNode, stamp: &Stamp) {
+    fn apply_stamp_to_leaf(
+        node: &mut OctreeNode,
+        stamp: &Stamp,
+        config: &OctreeConfig,
+        frozen: &[FrozenRegion],
+    ) {
+        let center = Self::stamp_footprint(node, stamp, config).center();
         if let NodeState::Leaf { values } = &mut node.state {
             // Sample at cell center
-            let center = node.bounds.center();
             let intensity = stamp.shape.intensity_at(center, stamp.falloff);
 
             if intensity > 0.0 {
                 for modification in &stamp.modifications {
+                    if frozen.iter().any(|r| r.freezes(center, modification.field)) {
+                        continue;
+                    }
                     let current = values.get(modification.field);
                     let new_value = if stamp.falloff {
                         // Interpolate based on intensity
@@ -382,49 +790,57 @@ impl Octree {
         if !self.config.bounds.contains(position) {
             return;
         }
-        let max_depth = self.config.max_depth;
-        Self::set_point_recursive(&mut self.root, position, values, max_depth, &mut self.node_count, &mut self.leaf_count);
+        Self::set_point_recursive(
+            &mut self.root,
+            position,
+            values,
+            &self.config,
+            &mut self.node_count,
+            &mut self.leaf_count,
+        );
     }
 
     fn set_point_recursive(
         node: &mut OctreeNode,
         position: Vec3,
         values: FieldValues,
-        max_depth: u8,
+        config: &OctreeConfig,
         node_count: &mut usize,
         leaf_count: &mut usize,
     ) {
         match &mut node.state {
             NodeState::Empty => {
-                if node.depth >= max_depth {
+                if node.depth >= config.max_depth {
                     node.state = NodeState::Leaf { values };
                     *leaf_count += 1;
                 } else {
-                    node.split();
-                    *node_count += 8;
-                    *leaf_count += 8;
-                    Self::set_point_recursive(node, position, values, max_depth, node_count, leaf_count);
+                    config.split(node, node_count, leaf_count);
+                    Self::set_point_recursive(
+                        node, position, values, config, node_count, leaf_count,
+                    );
                 }
             }
             NodeState::Leaf { values: v } => {
-                if node.depth >= max_depth {
+                if node.depth >= config.max_depth {
                     *v = values;
                 } else {
-                    node.split();
-                    *node_count += 8;
-                    *leaf_count += 7;
-                    Self::set_point_recursive(node, position, values, max_depth, node_count, leaf_count);
+                    config.split(node, node_count, leaf_count);
+                    Self::set_point_recursive(
+                        node, position, values, config, node_count, leaf_count,
+                    );
                 }
             }
             NodeState::Internal { children, .. } => {
-                let octant = node.bounds.octant_index(position);
+                let octant = config.child_index(&node.bounds, position);
                 if children[octant].is_none() {
-                    let child_bounds = node.bounds.child_bounds(octant);
+                    let child_bounds = config.child_bounds(&node.bounds, octant);
                     children[octant] = Some(Box::new(OctreeNode::new(child_bounds, node.depth + 1)));
                     *node_count += 1;
                 }
                 if let Some(child) = &mut children[octant] {
-                    Self::set_point_recursive(child, position, values, max_depth, node_count, leaf_count);
+                    Self::set_point_recursive(
+                        child, position, values, config, node_count, leaf_count,
+                    );
                 }
                 node.update_stats();
             }
@@ -441,6 +857,43 @@ pub struct OctreeStats {
     pub leaf_count: usize,
     /// Maximum depth
     pub max_depth: u8,
+    /// Approximate memory held by the nodes, in bytes
+    pub memory_bytes: usize,
+    /// Configured memory budget, in bytes
+    pub memory_budget: Option<usize>,
+    /// Subtrees coarsened so far to stay within the memory budget
+    pub evictions: u64,
+}
+
+/// Kind of an octree node, as reported by [`Octree::debug_tree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// Not yet written (uses defaults)
+    Empty,
+    /// Holds raw field values
+    Leaf,
+    /// Has children
+    Internal,
+}
+
+/// Debug snapshot of a node and the part of its subtree within the depth limit.
+#[derive(Debug, Clone)]
+pub struct NodeSummary {
+    /// Spatial bounds of the node
+    pub bounds: Bounds,
+    /// Depth in the tree (0 = root)
+    pub depth: u8,
+    /// Node kind
+    pub kind: NodeKind,
+    /// Whether the node's variance is within the merge threshold. An
+    /// internal node that is not uniform is why a region stays subdivided.
+    pub uniform: bool,
+    /// Field statistics (`None` for empty nodes)
+    pub stats: Option<FieldStats>,
+    /// Number of nodes in the subtree, including this one
+    pub subtree_nodes: usize,
+    /// Summaries of the children, in octant order (empty past the depth limit)
+    pub children: Vec<NodeSummary>,
 }
 
 /// Direction for neighbor finding.
@@ -500,6 +953,7 @@ impl Direction {
 mod tests {
     use super::*;
     use crate::field::Field;
+    use crate::query::QueryResolution;
     use crate::stamp::{BlendOp, FieldMod, StampShape};
 
     #[test]
@@ -539,6 +993,55 @@ mod tests {
         assert!(result.mean(Field::Temperature) > 0.0);
     }
 
+    #[test]
+    fn test_volume_query_distribution() {
+        let mut octree = Octree::with_bounds(Bounds::new(100.0, 100.0, 100.0), 10.0);
+        let mut hot = FieldValues::new();
+        hot.set(Field::Temperature, 500.0);
+        octree.set_point(Vec3::new(5.0, 5.0, 5.0), hot);
+
+        let query = VolumeQuery::new(Vec3::ZERO, 50.0).with_resolution(QueryResolution::Full);
+        let result = octree.query_volume(&query);
+
+        let coverage = result.coverage();
+        assert!(coverage > 0.0 && coverage < 0.05);
+        assert_eq!(result.p50(Field::Temperature), 0.0);
+        assert!((result.percentile(Field::Temperature, 1.0) - 500.0).abs() < 1e-3);
+        let histogram = result.histogram(Field::Temperature, 5);
+        assert_eq!(histogram.counts[4], 1.0);
+        assert_eq!(histogram.total(), result.field_stats(Field::Temperature).sample_count as f32);
+    }
+
+    #[test]
+    fn test_query_batch_matches_single_queries() {
+        let mut octree = Octree::with_bounds(Bounds::new(100.0, 100.0, 100.0), 1.0);
+        octree.apply_stamp(&Stamp::new(
+            StampShape::sphere(Vec3::new(10.0, 0.0, 0.0), 15.0),
+            vec![FieldMod::new(Field::Temperature, BlendOp::Set, 500.0)],
+        ));
+
+        let queries = [
+            VolumeQuery::new(Vec3::ZERO, 30.0),
+            VolumeQuery::new(Vec3::new(10.0, 0.0, 0.0), 5.0)
+                .with_resolution(QueryResolution::Full),
+            VolumeQuery::new(Vec3::new(-40.0, -40.0, 0.0), 8.0)
+                .with_resolution(QueryResolution::Coarse),
+            VolumeQuery::new(Vec3::new(500.0, 0.0, 0.0), 1.0),
+        ];
+        let batch = octree.query_batch(&queries);
+
+        assert_eq!(batch.len(), queries.len());
+        for (query, result) in queries.iter().zip(&batch) {
+            let single = octree.query_volume(query);
+            assert_eq!(result.nodes_visited, single.nodes_visited);
+            assert_eq!(result.max_depth_reached, single.max_depth_reached);
+            assert_eq!(result.regions.len(), single.regions.len());
+            assert_eq!(result.mean(Field::Temperature), single.mean(Field::Temperature));
+        }
+        assert!(batch[1].mean(Field::Temperature) > 0.0);
+        assert!(batch[3].regions.is_empty());
+    }
+
     // ===== Neighbor Finding Tests =====
 
     #[test]
@@ -677,6 +1180,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_debug_tree_counts_and_depth_limit() {
+        let mut octree = Octree::with_bounds(Bounds::new(100.0, 100.0, 100.0), 10.0);
+        let mut values = FieldValues::new();
+        values.set(Field::Temperature, 100.0);
+        octree.set_point(Vec3::new(-25.0, -25.0, 0.0), values);
+
+        let full = octree.debug_tree(u8::MAX);
+        assert_eq!(full.kind, NodeKind::Internal);
+        assert_eq!(full.subtree_nodes, octree.stats().node_count);
+        assert!(!full.uniform);
+
+        let shallow = octree.debug_tree(0);
+        assert!(shallow.children.is_empty());
+        assert_eq!(shallow.subtree_nodes, full.subtree_nodes);
+        assert_eq!(octree.debug_tree(1).children.len(), 8);
+        assert!(octree.stats().memory_bytes > 0);
+        let by_depth = octree.nodes_by_depth();
+        assert_eq!(by_depth[..2], [1, 8]);
+        assert_eq!(by_depth.iter().sum::<usize>(), octree.stats().node_count);
+    }
+
     #[test]
     fn test_collect_leaves_empty_octree() {
         // Create an empty octree
@@ -740,4 +1265,43 @@ mod tests {
             );
         }
     }
+    #[test]
+    fn test_frozen_region_blocks_stamps() {
+        let mut octree = Octree::with_bounds(Bounds::new(32.0, 32.0, 32.0), 1.0);
+        let land = Bounds::from_min_max(Vec3::splat(-16.0), Vec3::new(0.0, 16.0, 16.0));
+        let shore = Vec3::new(-0.5, 0.5, 0.5);
+        let inland = Vec3::new(-0.5, 3.5, 0.5);
+        let sea = Vec3::new(1.5, 0.5, 0.5);
+        let mut rock = FieldValues::new();
+        rock.set(Field::Occupancy, 1.0);
+        octree.set_point(shore, rock);
+        octree.freeze_region(land, &[Field::Occupancy]);
+
+        let get = |octree: &Octree, p: Vec3, field: Field| octree.query_point(&PointQuery::new(p)).get(field);
+        let inland_before = get(&octree, inland, Field::Occupancy);
+        let sea_before = get(&octree, sea, Field::Occupancy);
+        let heat_before = get(&octree, shore, Field::Temperature);
+
+        octree.apply_stamp(&Stamp::explosion(Vec3::new(0.5, 0.5, 0.5), 6.0, 1.0));
+
+        // Frozen field is untouched on land, even in cells never stored before
+        assert_eq!(get(&octree, shore, Field::Occupancy), 1.0);
+        assert_eq!(get(&octree, inland, Field::Occupancy), inland_before);
+        // Other fields still change on land, and the sea is carved
+        assert!(get(&octree, shore, Field::Temperature) > heat_before);
+        assert!(get(&octree, sea, Field::Occupancy) < sea_before);
+    }
+
+    #[test]
+    fn test_clear_frozen() {
+        let mut octree = Octree::with_bounds(Bounds::new(32.0, 32.0, 32.0), 1.0);
+        octree.freeze_region(Bounds::new(8.0, 8.0, 8.0), &[Field::Depth]);
+
+        assert!(octree.is_frozen(Vec3::ZERO, Field::Depth));
+        assert!(!octree.is_frozen(Vec3::ZERO, Field::Occupancy));
+        assert!(!octree.is_frozen(Vec3::splat(10.0), Field::Depth));
+
+        octree.clear_frozen();
+        assert!(octree.frozen_regions().is_empty());
+    }
 }
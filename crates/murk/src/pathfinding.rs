@@ -0,0 +1,569 @@
+//! Coarse surface pathfinding over the `Occupancy` and `Depth` fields.
+//!
+//! [`find_path`] lays a grid of square cells over the x/y extent of the
+//! world, on the horizontal slice at the height of the start, and runs A*
+//! across it:
+//!
+//! - **Cells** are `resolution` wide, but no smaller than the octree's base
+//!   resolution and at most [`MAX_CELLS_PER_AXIS`] along each axis. Each is
+//!   sampled once, at its center, the first time the search reaches it, so
+//!   obstacles narrower than a cell may be missed.
+//! - **Navigable** cells have an occupancy below [`BLOCKING_OCCUPANCY`] and
+//!   at least `draft` meters of water. A `Depth` of zero, which is what
+//!   unwritten space reads, counts as unsurveyed open water, so worlds
+//!   without bathymetry are planned on occupancy alone.
+//! - **Moves** go to the eight neighboring cells; diagonal moves may not
+//!   cut between two blocked cells. A move costs its length times one plus
+//!   the penalty of the cell it enters, which is zero unless
+//!   [`find_path_with_costs`] is given [`PathCosts`]: ramps over field
+//!   values, such as loud water or shallows, and caller-supplied grids,
+//!   such as an enemy-threat heatmap.
+//!
+//! The cell path is then shortened by skipping every waypoint that the
+//! previous one can reach in a straight line over navigable cells at no
+//! greater cost, so open water costs a single leg. The returned waypoints
+//! start at the start, end at the goal and follow cell centers in between.
+
+// Grid coordinates are bounded by MAX_CELLS_PER_AXIS
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use glam::{Vec2, Vec3};
+
+use crate::field::{Field, FieldValues};
+use crate::octree::Octree;
+use crate::query::PointQuery;
+
+/// Maximum number of grid cells along each axis.
+pub const MAX_CELLS_PER_AXIS: usize = 256;
+
+/// Occupancy at which a cell is impassable.
+pub const BLOCKING_OCCUPANCY: f32 = 0.5;
+
+/// Penalty ramping linearly with a field's value.
+///
+/// The penalty is zero at `zero` and `weight` at `full`, clamped beyond
+/// them. `full` may lie below `zero` to penalize low values: ramping
+/// `Depth` from 200 down to 20 prefers deep water.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldCost {
+    /// Field sampled at each cell center
+    pub field: Field,
+    /// Field value with no penalty
+    pub zero: f32,
+    /// Field value with the full penalty
+    pub full: f32,
+    /// Penalty at `full`
+    pub weight: f32,
+}
+
+impl FieldCost {
+    /// Create a field cost.
+    #[must_use]
+    pub const fn new(field: Field, zero: f32, full: f32, weight: f32) -> Self {
+        Self {
+            field,
+            zero,
+            full,
+            weight,
+        }
+    }
+
+    fn penalty(&self, value: f32) -> f32 {
+        let ramp = (value - self.zero) / (self.full - self.zero);
+        // NaN only when `zero == full == value`
+        self.weight * if ramp.is_nan() { 1.0 } else { ramp.clamp(0.0, 1.0) }
+    }
+}
+
+/// Grid of penalties supplied by the caller, such as a threat heatmap.
+///
+/// Values are row-major: `dims[0]` cells along x per row, `dims[1]` rows
+/// along y, starting from `min`. Positions outside the grid carry no
+/// penalty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostGrid {
+    min: Vec2,
+    cell_size: f32,
+    dims: [usize; 2],
+    values: Vec<f32>,
+    weight: f32,
+}
+
+impl CostGrid {
+    /// Create a grid of `cell_size` square cells whose values are scaled by
+    /// `weight`.
+    ///
+    /// Returns `None` unless `values` holds `dims[0] * dims[1]` entries and
+    /// `cell_size` is positive.
+    #[must_use]
+    pub fn new(
+        min: Vec2,
+        cell_size: f32,
+        dims: [usize; 2],
+        values: Vec<f32>,
+        weight: f32,
+    ) -> Option<Self> {
+        (values.len() == dims[0] * dims[1] && cell_size > 0.0 && cell_size.is_finite()).then_some(
+            Self {
+                min,
+                cell_size,
+                dims,
+                values,
+                weight,
+            },
+        )
+    }
+
+    fn penalty(&self, position: Vec2) -> f32 {
+        let grid = (position - self.min) / self.cell_size;
+        // Also rejects NaN
+        if !(grid.x >= 0.0 && grid.y >= 0.0) {
+            return 0.0;
+        }
+        let (x, y) = (grid.x as usize, grid.y as usize);
+        if x < self.dims[0] && y < self.dims[1] {
+            self.weight * self.values[y * self.dims[0] + x]
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Extra costs steering routes away from fields and hazards.
+///
+/// The penalties of a cell add up; a negative total counts as zero.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathCosts {
+    /// Penalties ramping with field values
+    pub fields: Vec<FieldCost>,
+    /// Penalties looked up in caller-supplied grids
+    pub grids: Vec<CostGrid>,
+}
+
+impl PathCosts {
+    /// Create an empty set of costs, under which routes are shortest paths.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a penalty ramping with a field's value.
+    #[must_use]
+    pub fn with_field(mut self, cost: FieldCost) -> Self {
+        self.fields.push(cost);
+        self
+    }
+
+    /// Add a grid of penalties.
+    #[must_use]
+    pub fn with_grid(mut self, grid: CostGrid) -> Self {
+        self.grids.push(grid);
+        self
+    }
+
+    /// Returns the factor applied to the length of moves into a cell.
+    fn multiplier(&self, position: Vec2, values: &FieldValues) -> f32 {
+        let fields: f32 = self.fields.iter().map(|c| c.penalty(values.get(c.field))).sum();
+        let grids: f32 = self.grids.iter().map(|g| g.penalty(position)).sum();
+        1.0 + (fields + grids).max(0.0)
+    }
+}
+
+/// Cost of the cells of the search grid, sampled on demand.
+struct NavGrid<'a> {
+    octree: &'a Octree,
+    costs: &'a PathCosts,
+    origin: Vec2,
+    cell: f32,
+    dims: [usize; 2],
+    z: f32,
+    draft: f32,
+    /// Per cell: `None` until sampled, then the cost of moving into it per
+    /// cell length, infinite if impassable
+    cost: Vec<Option<f32>>,
+}
+
+impl<'a> NavGrid<'a> {
+    fn new(
+        octree: &'a Octree,
+        costs: &'a PathCosts,
+        z: f32,
+        draft: f32,
+        resolution: f32,
+    ) -> Option<Self> {
+        let world = octree.config().bounds;
+        if z < world.min.z || z > world.max.z {
+            return None;
+        }
+        let extent = (world.max - world.min).truncate();
+        let mut cell = resolution.max(octree.config().base_resolution);
+        cell = cell.max(extent.max_element() / MAX_CELLS_PER_AXIS as f32);
+        if !(cell.is_finite() && cell > 0.0) {
+            return None;
+        }
+        let count = |length: f32| ((length / cell).ceil() as usize).clamp(1, MAX_CELLS_PER_AXIS);
+        let dims = [count(extent.x), count(extent.y)];
+        Some(Self {
+            octree,
+            costs,
+            origin: world.min.truncate(),
+            cell,
+            dims,
+            z,
+            draft,
+            cost: vec![None; dims[0] * dims[1]],
+        })
+    }
+
+    /// Returns the index of the cell containing `position`, if any.
+    fn cell_at(&self, position: Vec2) -> Option<usize> {
+        let grid = (position - self.origin) / self.cell;
+        // Also rejects NaN
+        if !(grid.x >= 0.0 && grid.y >= 0.0) {
+            return None;
+        }
+        let (x, y) = (grid.x as usize, grid.y as usize);
+        (x < self.dims[0] && y < self.dims[1]).then(|| y * self.dims[0] + x)
+    }
+
+    /// Returns the center of a cell.
+    fn center(&self, index: usize) -> Vec2 {
+        let (x, y) = (index % self.dims[0], index / self.dims[0]);
+        self.origin + (Vec2::new(x as f32, y as f32) + 0.5) * self.cell
+    }
+
+    /// Returns the cost of moving into a cell per cell length, sampling it
+    /// if needed.
+    fn cost(&mut self, index: usize) -> f32 {
+        if let Some(cost) = self.cost[index] {
+            return cost;
+        }
+        let center = self.center(index);
+        let values = self.octree.query_point(&PointQuery::new(center.extend(self.z))).values;
+        let depth = values.get(Field::Depth);
+        let navigable = values.get(Field::Occupancy) < BLOCKING_OCCUPANCY
+            && (depth <= 0.0 || depth >= self.draft);
+        let cost = if navigable {
+            self.costs.multiplier(center, &values)
+        } else {
+            f32::INFINITY
+        };
+        self.cost[index] = Some(cost);
+        cost
+    }
+
+    /// Returns whether a cell can be sailed.
+    fn is_navigable(&mut self, index: usize) -> bool {
+        self.cost(index).is_finite()
+    }
+
+    /// Returns the navigable neighbors of a cell with the cost of the move,
+    /// in cells.
+    fn neighbors(&mut self, index: usize) -> Vec<(usize, f32)> {
+        let [width, height] = self.dims;
+        let (x, y) = ((index % width) as isize, (index / width) as isize);
+        let at = |dx: isize, dy: isize| {
+            let (nx, ny) = (x + dx, y + dy);
+            (nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height)
+                .then(|| ny as usize * width + nx as usize)
+        };
+        let mut moves = Vec::with_capacity(8);
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)] {
+            let Some(next) = at(dx, dy) else {
+                continue;
+            };
+            let diagonal = dx != 0 && dy != 0;
+            if diagonal {
+                // Both orthogonal cells must exist since the diagonal does
+                let (side_x, side_y) = (at(dx, 0).unwrap_or(next), at(0, dy).unwrap_or(next));
+                if !self.is_navigable(side_x) && !self.is_navigable(side_y) {
+                    continue;
+                }
+            }
+            let cost = self.cost(next);
+            if cost.is_finite() {
+                moves.push((next, cost * if diagonal { std::f32::consts::SQRT_2 } else { 1.0 }));
+            }
+        }
+        moves
+    }
+
+    /// Returns the cost of sailing straight from `from` to `to`, in cell
+    /// lengths, or `None` if a cell along the way is impassable.
+    fn leg_cost(&mut self, from: Vec2, to: Vec2) -> Option<f32> {
+        let steps = ((from.distance(to) / (self.cell * 0.5)).ceil() as usize).max(1);
+        let mut total = 0.0;
+        for i in 0..=steps {
+            let point = from.lerp(to, i as f32 / steps as f32);
+            let cost = self.cost(self.cell_at(point)?);
+            if !cost.is_finite() {
+                return None;
+            }
+            total += cost;
+        }
+        Some(total / (steps + 1) as f32 * from.distance(to) / self.cell)
+    }
+}
+
+/// Open-set entry of the A* search, ordered for a min-heap by estimated
+/// total cost, then by cell index for determinism.
+#[derive(Clone, Copy, PartialEq)]
+struct Open {
+    estimate: f32,
+    index: usize,
+}
+
+impl Eq for Open {}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Finds a route for a vessel of `draft` meters from `start` to `goal` on
+/// the horizontal slice at the height of `start`, searching cells about
+/// `resolution` wide.
+///
+/// Returns the waypoints from `start` to `goal` (at the start's height), or
+/// `None` if either end lies outside the world or in an impassable cell,
+/// or no route connects them.
+#[must_use]
+pub fn find_path(
+    octree: &Octree,
+    start: Vec3,
+    goal: Vec3,
+    draft: f32,
+    resolution: f32,
+) -> Option<Vec<Vec3>> {
+    find_path_with_costs(octree, start, goal, draft, resolution, &PathCosts::new())
+}
+
+/// Like [`find_path`], but finds the cheapest route under `costs` rather
+/// than the shortest.
+#[must_use]
+pub fn find_path_with_costs(
+    octree: &Octree,
+    start: Vec3,
+    goal: Vec3,
+    draft: f32,
+    resolution: f32,
+    costs: &PathCosts,
+) -> Option<Vec<Vec3>> {
+    let mut grid = NavGrid::new(octree, costs, start.z, draft, resolution)?;
+    let (from, to) = (start.truncate(), goal.truncate());
+    let first = grid.cell_at(from)?;
+    let last = grid.cell_at(to)?;
+    if !grid.is_navigable(first) || !grid.is_navigable(last) {
+        return None;
+    }
+
+    let cells = search(&mut grid, first, last)?;
+    let mut points = vec![from];
+    if let [_, inner @ .., _] = cells.as_slice() {
+        points.extend(inner.iter().map(|&c| grid.center(c)));
+    }
+    points.push(to);
+
+    // Skip waypoints while a straight leg from the last one kept costs no
+    // more than following them
+    let mut route = vec![from];
+    let mut anchor = 0;
+    while anchor < points.len() - 1 {
+        let mut next = anchor + 1;
+        let mut followed = grid.leg_cost(points[anchor], points[next]).unwrap_or(f32::INFINITY);
+        while next + 1 < points.len() {
+            followed += grid.leg_cost(points[next], points[next + 1]).unwrap_or(f32::INFINITY);
+            match grid.leg_cost(points[anchor], points[next + 1]) {
+                // Allow for rounding along collinear waypoints
+                Some(direct) if direct <= followed * 1.0001 => next += 1,
+                _ => break,
+            }
+        }
+        route.push(points[next]);
+        anchor = next;
+    }
+    Some(route.into_iter().map(|p| p.extend(start.z)).collect())
+}
+
+/// Runs A* from cell `first` to cell `last`, returning the cells of the
+/// cheapest route, both ends included.
+fn search(grid: &mut NavGrid<'_>, first: usize, last: usize) -> Option<Vec<usize>> {
+    let width = grid.dims[0];
+    let (gx, gy) = ((last % width) as f32, (last / width) as f32);
+    // Octile distance to the goal, in cells
+    let heuristic = |index: usize| {
+        let dx = ((index % width) as f32 - gx).abs();
+        let dy = ((index / width) as f32 - gy).abs();
+        dx.max(dy) + (std::f32::consts::SQRT_2 - 1.0) * dx.min(dy)
+    };
+
+    let count = grid.cost.len();
+    let mut cost = vec![f32::INFINITY; count];
+    let mut came_from = vec![usize::MAX; count];
+    let mut open = BinaryHeap::new();
+    cost[first] = 0.0;
+    open.push(Open {
+        estimate: heuristic(first),
+        index: first,
+    });
+
+    while let Some(Open { estimate, index }) = open.pop() {
+        if index == last {
+            let mut cells = vec![last];
+            while let Some(&cell) = cells.last().filter(|&&c| c != first) {
+                cells.push(came_from[cell]);
+            }
+            cells.reverse();
+            return Some(cells);
+        }
+        // Skip entries superseded by a cheaper route
+        if estimate > cost[index] + heuristic(index) {
+            continue;
+        }
+        for (next, step) in grid.neighbors(index) {
+            let reached = cost[index] + step;
+            if reached < cost[next] {
+                cost[next] = reached;
+                came_from[next] = index;
+                open.push(Open {
+                    estimate: reached + heuristic(next),
+                    index: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::octree::OctreeConfig;
+    use crate::Bounds;
+
+    /// A 256 m square world with 4 m cells at the leaves.
+    fn sea() -> Octree {
+        let bounds = Bounds::new(256.0, 256.0, 16.0);
+        Octree::new(OctreeConfig {
+            bounds,
+            base_resolution: 4.0,
+            max_depth: OctreeConfig::calculate_max_depth(&bounds, 4.0),
+            ..OctreeConfig::default()
+        })
+    }
+
+    /// Sets every leaf in the rectangle `[min, max]` at z = 0.
+    fn paint(octree: &mut Octree, min: Vec2, max: Vec2, field: Field, value: f32) {
+        let mut y = min.y + 2.0;
+        while y < max.y {
+            let mut x = min.x + 2.0;
+            while x < max.x {
+                let position = Vec3::new(x, y, 0.0);
+                let mut values = octree.query_point(&PointQuery::new(position)).values;
+                values.set(field, value);
+                octree.set_point(position, values);
+                x += 4.0;
+            }
+            y += 4.0;
+        }
+    }
+
+    #[test]
+    fn open_water_is_one_leg() {
+        let octree = sea();
+        let (start, goal) = (Vec3::new(-100.0, -100.0, 0.0), Vec3::new(100.0, 90.0, 0.0));
+        assert_eq!(find_path(&octree, start, goal, 5.0, 8.0), Some(vec![start, goal]));
+    }
+
+    #[test]
+    fn routes_around_an_island() {
+        let mut octree = sea();
+        // A wall from the south edge to y = 64, between start and goal
+        paint(&mut octree, Vec2::new(-16.0, -128.0), Vec2::new(16.0, 64.0), Field::Occupancy, 1.0);
+        let (start, goal) = (Vec3::new(-80.0, 0.0, 0.0), Vec3::new(80.0, 0.0, 0.0));
+
+        let route = find_path(&octree, start, goal, 5.0, 8.0).unwrap();
+        assert_eq!(route.first(), Some(&start));
+        assert_eq!(route.last(), Some(&goal));
+        assert!(route.len() > 2);
+        assert!(route.iter().any(|p| p.y > 64.0));
+        // No leg crosses the wall
+        for leg in route.windows(2) {
+            for i in 0..=100 {
+                let p = leg[0].lerp(leg[1], i as f32 / 100.0);
+                assert!(p.x.abs() > 16.0 || p.y > 64.0, "{p} is inside the wall");
+            }
+        }
+    }
+
+    #[test]
+    fn shallows_stop_deep_drafts_only() {
+        let mut octree = sea();
+        // Shallows across the whole world
+        paint(&mut octree, Vec2::new(-16.0, -128.0), Vec2::new(16.0, 128.0), Field::Depth, 3.0);
+        let (start, goal) = (Vec3::new(-80.0, 0.0, 0.0), Vec3::new(80.0, 0.0, 0.0));
+
+        assert!(find_path(&octree, start, goal, 2.0, 8.0).is_some());
+        assert_eq!(find_path(&octree, start, goal, 5.0, 8.0), None);
+        // Starting aground or off the map fails outright
+        let aground = Vec3::new(0.0, 0.0, 0.0);
+        assert_eq!(find_path(&octree, aground, goal, 5.0, 8.0), None);
+        let outside = Vec3::new(500.0, 0.0, 0.0);
+        assert_eq!(find_path(&octree, start, outside, 2.0, 8.0), None);
+    }
+
+    #[test]
+    fn field_costs_steer_around_noise() {
+        let mut octree = sea();
+        // A loud band from the south edge to y = 64, between start and goal
+        paint(&mut octree, Vec2::new(-16.0, -128.0), Vec2::new(16.0, 64.0), Field::Noise, 120.0);
+        let (start, goal) = (Vec3::new(-80.0, 0.0, 0.0), Vec3::new(80.0, 0.0, 0.0));
+
+        // Noise alone blocks nothing
+        assert_eq!(find_path(&octree, start, goal, 5.0, 8.0), Some(vec![start, goal]));
+        let costs = PathCosts::new().with_field(FieldCost::new(Field::Noise, 60.0, 120.0, 10.0));
+        let route = find_path_with_costs(&octree, start, goal, 5.0, 8.0, &costs).unwrap();
+        assert!(route.iter().any(|p| p.y > 64.0), "{route:?} crosses the noise");
+        // A penalty too small to outweigh the detour changes nothing
+        let mild = PathCosts::new().with_field(FieldCost::new(Field::Noise, 60.0, 120.0, 0.1));
+        assert_eq!(find_path_with_costs(&octree, start, goal, 5.0, 8.0, &mild).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn threat_grid_steers_around_hot_cells() {
+        let octree = sea();
+        // 32 m cells over the world, hot in the two columns around x = 0
+        // south of y = 64
+        let values = (0..64)
+            .map(|i| {
+                let (x, y) = (i % 8, i / 8);
+                if (3..5).contains(&x) && y < 6 { 1.0 } else { 0.0 }
+            })
+            .collect();
+        let grid = CostGrid::new(Vec2::splat(-128.0), 32.0, [8, 8], values, 20.0).unwrap();
+        let costs = PathCosts::new().with_grid(grid);
+        let (start, goal) = (Vec3::new(-80.0, 0.0, 0.0), Vec3::new(80.0, 0.0, 0.0));
+
+        let route = find_path_with_costs(&octree, start, goal, 5.0, 8.0, &costs).unwrap();
+        assert!(route.iter().any(|p| p.y > 64.0), "{route:?} crosses the threat");
+        assert_eq!(CostGrid::new(Vec2::ZERO, 32.0, [8, 8], vec![0.0; 3], 1.0), None);
+    }
+}
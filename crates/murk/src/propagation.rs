@@ -1,93 +1,244 @@
-//! Field propagation: diffusion and decay.
+//! Field propagation: diffusion, decay and custom field processes.
 //!
 //! This module provides functions for evolving field values over time through
 //! physical processes like heat diffusion and signal decay.
+//!
+//! Each step runs a list of [`FieldProcess`]es over the octree. The built-in
+//! [`ConfiguredPropagation`] applies the diffusion and decay set in each
+//! field's [`FieldConfig`] and always runs first; processes registered with
+//! [`UniverseConfig::processes`](crate::UniverseConfig::processes) or
+//! [`Universe::add_process`] follow in registration order, so models such as
+//! algal bloom growth or oil weathering can be added without modifying the
+//! crate.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use glam::Vec3;
+//! use murk::propagation::FieldProcess;
+//! use murk::{Field, Octree, Universe, UniverseConfig};
+//!
+//! /// Grows the signal by 10% per second wherever there is some.
+//! #[derive(Debug)]
+//! struct Bloom;
+//!
+//! impl FieldProcess for Bloom {
+//!     fn name(&self) -> &'static str {
+//!         "bloom"
+//!     }
+//!
+//!     fn fields(&self) -> &[Field] {
+//!         &[Field::Signal]
+//!     }
+//!
+//!     fn step(&self, octree: &mut Octree, dt: f64) {
+//!         for (position, mut values) in octree.collect_leaves() {
+//!             let signal = values.get(Field::Signal);
+//!             if signal > 0.0 {
+//!                 values.set(Field::Signal, signal * (1.0 + 0.1 * dt as f32));
+//!                 octree.set_point(position, values);
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let mut universe = Universe::new(UniverseConfig::with_bounds(64.0, 64.0, 16.0));
+//! universe.add_process(Arc::new(Bloom));
+//! assert_eq!(universe.processes()[0].name(), "bloom");
+//! ```
+
+use std::fmt;
 
 use glam::Vec3;
 
-use crate::field::{Field, FieldValues, Propagation};
-use crate::octree::Direction;
+use crate::field::{Field, FieldConfig, FieldValues, Propagation};
+use crate::octree::{Direction, Octree};
+use crate::query::PointQuery;
 use crate::universe::Universe;
 
-/// Propagate all fields for one timestep.
+/// A process that evolves field values over one timestep.
 ///
-/// This is the core propagation step that applies diffusion and decay to all
-/// fields based on their configuration. It operates in three phases:
+/// Processes write through [`Octree::set_point`], which is not masked by
+/// frozen regions. [`propagate_all`] re-applies the mask after each process,
+/// putting back any frozen value the process overwrote.
+pub trait FieldProcess: fmt::Debug + Send + Sync {
+    /// Name of the process, for introspection and logs.
+    fn name(&self) -> &'static str;
+
+    /// Fields the process may change.
+    fn fields(&self) -> &[Field];
+
+    /// Advance the process by `dt` seconds.
+    fn step(&self, octree: &mut Octree, dt: f64);
+}
+
+/// Built-in process applying each field's configured [`Propagation`].
+///
+/// It operates in three phases:
 ///
 /// 1. **Collect**: Gather all leaf nodes from the octree (deterministic order)
 /// 2. **Compute**: Calculate new values for each leaf based on propagation rules
 /// 3. **Apply**: Write updated values back to the octree
 ///
 /// This separation ensures determinism by reading from a frozen snapshot before
-/// any writes occur.
-pub fn propagate_all(universe: &mut Universe, dt: f64) {
-    let dt_f32 = dt as f32;
-
-    // Phase 1: Collect all leaves
-    let leaves = universe.octree().collect_leaves();
+/// any writes occur. Fields frozen with [`Universe::freeze_region`] keep their
+/// values but still act as neighbors for diffusion.
+#[derive(Debug, Clone)]
+pub struct ConfiguredPropagation {
+    configs: [FieldConfig; Field::COUNT],
+    fields: Vec<Field>,
+}
 
-    if leaves.is_empty() {
-        return;
+impl ConfiguredPropagation {
+    /// Create the process for the given per-field configurations.
+    #[must_use]
+    pub fn new(configs: &[FieldConfig; Field::COUNT]) -> Self {
+        let fields = configs
+            .iter()
+            .filter(|c| c.propagation != Propagation::None)
+            .map(|c| c.field)
+            .collect();
+        Self {
+            configs: configs.clone(),
+            fields,
+        }
     }
 
-    // Phase 2: Compute updates for each leaf
-    let updates: Vec<(Vec3, FieldValues)> = leaves
-        .iter()
-        .map(|(pos, old_values)| {
-            let mut new_values = *old_values;
-
-            for field in Field::all() {
-                let config = universe.field_config(*field);
-                let old_val = old_values.get(*field);
-
-                let new_val = match config.propagation {
-                    Propagation::None => old_val,
-                    Propagation::Diffusion { rate } => {
-                        let neighbors = get_xy_neighbor_values(universe, *pos, *field);
-                        apply_diffusion(old_val, &neighbors, rate, dt_f32)
-                    }
-                    Propagation::Decay { rate } => {
-                        apply_decay(old_val, config.default_value, rate, dt_f32)
-                    }
-                    Propagation::DiffusionDecay {
-                        diffusion_rate,
-                        decay_rate,
-                    } => {
-                        let neighbors = get_xy_neighbor_values(universe, *pos, *field);
-                        let diffused = apply_diffusion(old_val, &neighbors, diffusion_rate, dt_f32);
-                        apply_decay(diffused, config.default_value, decay_rate, dt_f32)
-                    }
-                };
-
-                new_values.set(*field, config.clamp(new_val));
+    /// New values of one leaf.
+    fn propagate(
+        &self,
+        octree: &Octree,
+        pos: Vec3,
+        old_values: &FieldValues,
+        dt: f32,
+    ) -> FieldValues {
+        let mut new_values = *old_values;
+
+        for field in &self.fields {
+            if octree.is_frozen(pos, *field) {
+                continue;
             }
+            let config = &self.configs[field.index()];
+            let old_val = old_values.get(*field);
 
-            (*pos, new_values)
-        })
-        .collect();
+            let new_val = match config.propagation {
+                Propagation::None => old_val,
+                Propagation::Diffusion { rate } => {
+                    let neighbors = get_xy_neighbor_values(octree, config, pos);
+                    apply_diffusion(old_val, &neighbors, rate, dt)
+                }
+                Propagation::Decay { rate } => {
+                    apply_decay(old_val, config.default_value, rate, dt)
+                }
+                Propagation::DiffusionDecay {
+                    diffusion_rate,
+                    decay_rate,
+                } => {
+                    let neighbors = get_xy_neighbor_values(octree, config, pos);
+                    let diffused = apply_diffusion(old_val, &neighbors, diffusion_rate, dt);
+                    apply_decay(diffused, config.default_value, decay_rate, dt)
+                }
+            };
 
-    // Phase 3: Apply updates
-    for (pos, values) in updates {
-        universe.set_point(pos, values);
+            new_values.set(*field, config.clamp(new_val));
+        }
+
+        new_values
+    }
+}
+
+impl FieldProcess for ConfiguredPropagation {
+    fn name(&self) -> &'static str {
+        "propagation"
+    }
+
+    fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    fn step(&self, octree: &mut Octree, dt: f64) {
+        if self.fields.is_empty() {
+            return;
+        }
+        let dt_f32 = dt as f32;
+
+        // Phase 1: Collect all leaves
+        let leaves = octree.collect_leaves();
+
+        // Phase 2: Compute updates for each leaf
+        let updates: Vec<(Vec3, FieldValues)> = leaves
+            .iter()
+            .map(|(pos, old_values)| (*pos, self.propagate(octree, *pos, old_values, dt_f32)))
+            .collect();
+
+        // Phase 3: Apply updates
+        for (pos, values) in updates {
+            octree.set_point(pos, values);
+        }
     }
 }
 
-/// Get neighbor field values in the XY plane (4 neighbors).
+/// Propagate all fields for one timestep.
+///
+/// Runs [`ConfiguredPropagation`] for the universe's field configurations,
+/// then the universe's registered processes in order. Frozen fields a
+/// registered process overwrites are restored after it runs.
+pub fn propagate_all(universe: &mut Universe, dt: f64) {
+    let builtin = ConfiguredPropagation::new(universe.field_configs());
+    let processes = universe.processes().to_vec();
+    let octree = universe.octree_mut();
+    builtin.step(octree, dt);
+    for process in processes {
+        step_masked(process.as_ref(), octree, dt);
+    }
+}
+
+/// Run `process`, then restore every frozen field it changed.
+///
+/// The pre-step octree is only cloned when frozen regions exist. Changed
+/// leaves were written by [`Octree::set_point`] and so sit at full depth,
+/// which lets the restore write back exactly those cells.
+fn step_masked(process: &dyn FieldProcess, octree: &mut Octree, dt: f64) {
+    if octree.frozen_regions().is_empty() {
+        process.step(octree, dt);
+        return;
+    }
+    let before = octree.clone();
+    process.step(octree, dt);
+
+    for (pos, mut values) in octree.collect_leaves() {
+        let old_values = before.query_point(&PointQuery::new(pos)).values;
+        let mut changed = false;
+        for &field in Field::all() {
+            let old_val = old_values.get(field);
+            // Compare bits so any rewrite of a frozen value, even to -0.0, is undone.
+            let rewritten = values.get(field).to_bits() != old_val.to_bits();
+            if rewritten && octree.is_frozen(pos, field) {
+                values.set(field, old_val);
+                changed = true;
+            }
+        }
+        if changed {
+            octree.set_point(pos, values);
+        }
+    }
+}
+
+/// Get neighbor values for a field in the XY plane.
 ///
 /// Returns the field values from up to 4 neighbors (PosX, NegX, PosY, NegY).
 /// For neighbors outside world bounds, the field's configured default value is used.
 /// For empty cells within bounds, the queried value (which may be interpolated) is used.
-fn get_xy_neighbor_values(universe: &Universe, pos: Vec3, field: Field) -> Vec<f32> {
-    let default_value = universe.field_config(field).default_value;
-
+fn get_xy_neighbor_values(octree: &Octree, config: &FieldConfig, pos: Vec3) -> Vec<f32> {
     Direction::xy_directions()
         .iter()
         .map(|dir| {
-            universe
-                .octree()
+            octree
                 .find_neighbor(pos, *dir)
-                .map(|values| values.get(field))
-                .unwrap_or(default_value) // Out-of-bounds uses default
+                // Out-of-bounds uses default
+                .map_or(config.default_value, |values| values.get(config.field))
         })
         .collect()
 }
@@ -221,4 +372,83 @@ mod tests {
             "Uniform field should remain stable, got {new_value}"
         );
     }
+
+    /// Sets the X current to the time elapsed since the first step.
+    #[derive(Debug)]
+    struct Clock;
+
+    impl FieldProcess for Clock {
+        fn name(&self) -> &'static str {
+            "clock"
+        }
+
+        fn fields(&self) -> &[Field] {
+            &[Field::CurrentX]
+        }
+
+        fn step(&self, octree: &mut Octree, dt: f64) {
+            for (pos, mut values) in octree.collect_leaves() {
+                values.set(Field::CurrentX, values.get(Field::CurrentX) + dt as f32);
+                octree.set_point(pos, values);
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_process_runs_after_configured_propagation() {
+        use std::sync::Arc;
+
+        use crate::UniverseConfig;
+
+        let mut universe = Universe::new(UniverseConfig {
+            processes: vec![Arc::new(Clock)],
+            ..UniverseConfig::with_bounds(64.0, 64.0, 16.0)
+        });
+        let pos = Vec3::new(10.0, 10.0, 4.0);
+        let mut values = FieldValues::new();
+        values.set(Field::Noise, 100.0);
+        universe.set_point(pos, values);
+
+        universe.step(1.0);
+        universe.step(1.0);
+
+        let values = universe.query_point(pos).values;
+        assert!((values.get(Field::CurrentX) - 2.0).abs() < EPSILON);
+        assert!(values.get(Field::Noise) < 100.0, "built-in decay still applies");
+        assert_eq!(universe.processes()[0].name(), "clock");
+
+        let builtin = ConfiguredPropagation::new(universe.field_configs());
+        assert!(builtin.fields().contains(&Field::Noise));
+        assert!(!builtin.fields().contains(&Field::CurrentX));
+    }
+
+    #[test]
+    fn test_custom_process_cannot_overwrite_frozen_region() {
+        use std::sync::Arc;
+
+        use crate::{Bounds, UniverseConfig};
+
+        let mut universe = Universe::new(UniverseConfig {
+            processes: vec![Arc::new(Clock)],
+            ..UniverseConfig::with_bounds(64.0, 64.0, 16.0)
+        });
+        let frozen = Vec3::new(10.0, 10.0, 4.0);
+        let free = Vec3::new(-20.0, -20.0, 4.0);
+        let mut values = FieldValues::new();
+        values.set(Field::CurrentX, 5.0);
+        universe.set_point(frozen, values);
+        universe.set_point(free, values);
+        universe.freeze_region(
+            Bounds::from_min_max(Vec3::new(0.0, 0.0, -8.0), Vec3::new(32.0, 32.0, 8.0)),
+            &[Field::CurrentX],
+        );
+
+        universe.step(1.0);
+        universe.step(1.0);
+
+        let frozen_x = universe.query_point(frozen).values.get(Field::CurrentX);
+        let free_x = universe.query_point(free).values.get(Field::CurrentX);
+        assert!((frozen_x - 5.0).abs() < EPSILON, "frozen value changed to {frozen_x}");
+        assert!((free_x - 7.0).abs() < EPSILON, "free value is {free_x}");
+    }
 }
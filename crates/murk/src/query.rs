@@ -106,7 +106,96 @@ impl VolumeQuery {
     }
 }
 
+/// Statistics of one octree node that contributed to a volume query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRegion {
+    /// Node statistics (a single sample for leaves and empty nodes)
+    pub stats: FieldStats,
+    /// Volume of the node inside the query's bounding box
+    pub volume: f32,
+    /// Whether the node holds non-default data
+    pub has_data: bool,
+}
+
+impl QueryRegion {
+    /// Samples of a field as `(value, weight)` atoms.
+    ///
+    /// The region's samples are placed at its min, mean and max in
+    /// proportions that reproduce its mean and variance. This is exact for
+    /// leaves and for nodes holding at most two distinct values.
+    #[allow(clippy::cast_precision_loss)] // Counts are weights; rounding past 2^24 is fine
+    fn atoms(&self, field: Field) -> [(f32, f32); 3] {
+        let s = self.stats.get(field);
+        let n = s.sample_count as f32;
+        if s.max <= s.min {
+            return [(s.min, n), (s.min, 0.0), (s.min, 0.0)];
+        }
+        // Bhatia-Davis bounds the variance by a * b, so the weights are valid
+        let (a, b) = ((s.mean - s.min).max(0.0), (s.max - s.mean).max(0.0));
+        let variance = s.variance.min(a * b);
+        let p_min = if a > 0.0 { variance / (a * (a + b)) } else { 0.0 };
+        let p_max = if b > 0.0 { variance / (b * (a + b)) } else { 0.0 };
+        let p_mean = (1.0 - p_min - p_max).max(0.0);
+        [(s.min, n * p_min), (s.mean, n * p_mean), (s.max, n * p_max)]
+    }
+
+    /// Weight of this region strictly below `value` for a field.
+    fn weight_below(&self, field: Field, value: f32) -> f32 {
+        self.atoms(field)
+            .iter()
+            .filter(|(v, _)| *v < value)
+            .map(|(_, w)| w)
+            .sum()
+    }
+}
+
+/// Histogram of one field over a queried volume.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// Lower edge of the first bucket
+    pub min: f32,
+    /// Upper edge of the last bucket
+    pub max: f32,
+    /// Sample weight per bucket (fractional for summarized nodes)
+    pub counts: Vec<f32>,
+}
+
+impl Histogram {
+    /// Width of each bucket.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Bucket counts are small
+    pub fn bucket_width(&self) -> f32 {
+        if self.counts.is_empty() {
+            0.0
+        } else {
+            (self.max - self.min) / self.counts.len() as f32
+        }
+    }
+
+    /// Total sample weight.
+    #[must_use]
+    pub fn total(&self) -> f32 {
+        self.counts.iter().sum()
+    }
+
+    /// Bucket weights normalized to sum to 1 (all zero if empty).
+    #[must_use]
+    pub fn fractions(&self) -> Vec<f32> {
+        let total = self.total();
+        self.counts
+            .iter()
+            .map(|c| if total > 0.0 { c / total } else { 0.0 })
+            .collect()
+    }
+}
+
 /// Result of a volume query.
+///
+/// Beyond the merged moments in `stats`, the result keeps the statistics of
+/// every node it merged (`regions`), from which histograms, percentiles and
+/// coverage are estimated. Leaves contribute exact values; a node summarized
+/// by its cached statistics contributes samples at its min, mean and max,
+/// weighted to match its mean and variance.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct QueryResult {
     /// Field statistics for the queried region
@@ -115,6 +204,9 @@ pub struct QueryResult {
     pub nodes_visited: u32,
     /// Maximum depth reached
     pub max_depth_reached: u8,
+    /// Nodes merged into `stats`, in traversal order
+    #[serde(default)]
+    pub regions: Vec<QueryRegion>,
 }
 
 impl QueryResult {
@@ -142,11 +234,123 @@ impl QueryResult {
         self.stats.get(field).max
     }
 
+    /// Get the mean value for a field on its canonical normalized scale.
+    ///
+    /// See [`Field::normalization`].
+    #[must_use]
+    pub fn normalized(&self, field: Field) -> f32 {
+        field.normalize(self.mean(field))
+    }
+
     /// Get the full scalar stats for a field.
     #[must_use]
     pub fn field_stats(&self, field: Field) -> &ScalarStats {
         self.stats.get(field)
     }
+
+    /// Histogram of a field with `buckets` equal-width buckets spanning
+    /// the field's min and max.
+    ///
+    /// Values equal to the max fall in the last bucket. If every value is
+    /// the same, all weight falls in the first bucket.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Bucket counts are small; sample counts are weights
+    pub fn histogram(&self, field: Field, buckets: usize) -> Histogram {
+        let s = self.stats.get(field);
+        if s.sample_count == 0 || buckets == 0 {
+            return Histogram {
+                min: 0.0,
+                max: 0.0,
+                counts: vec![0.0; buckets],
+            };
+        }
+        let mut counts = vec![0.0; buckets];
+        let width = (s.max - s.min) / buckets as f32;
+        if width <= 0.0 {
+            counts[0] = s.sample_count as f32;
+        } else {
+            // Each bucket gets the weight between its edges; the last bucket
+            // is closed so the max is included
+            let mut below = 0.0;
+            for (i, count) in counts.iter_mut().enumerate() {
+                let weight = if i + 1 == buckets {
+                    s.sample_count as f32
+                } else {
+                    let edge = s.min + width * (i + 1) as f32;
+                    self.regions.iter().map(|r| r.weight_below(field, edge)).sum()
+                };
+                *count = (weight - below).max(0.0);
+                below = weight;
+            }
+        }
+        Histogram {
+            min: s.min,
+            max: s.max,
+            counts,
+        }
+    }
+
+    /// Estimate the value below which fraction `p` (0-1) of a field's
+    /// samples fall.
+    ///
+    /// Returns 0 if the query found no samples.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Sample counts are weights; rounding past 2^24 is fine
+    pub fn percentile(&self, field: Field, p: f32) -> f32 {
+        let s = self.stats.get(field);
+        if s.sample_count == 0 {
+            return 0.0;
+        }
+        let target = p.clamp(0.0, 1.0) * s.sample_count as f32;
+        let mut atoms: Vec<(f32, f32)> = self
+            .regions
+            .iter()
+            .flat_map(|r| r.atoms(field))
+            .filter(|(_, w)| *w > 0.0)
+            .collect();
+        atoms.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Smallest value whose cumulative weight reaches the target
+        let mut cumulative = 0.0;
+        for (value, weight) in &atoms {
+            cumulative += weight;
+            if cumulative >= target {
+                return *value;
+            }
+        }
+        s.max
+    }
+
+    /// Median of a field.
+    #[must_use]
+    pub fn p50(&self, field: Field) -> f32 {
+        self.percentile(field, 0.5)
+    }
+
+    /// 90th percentile of a field.
+    #[must_use]
+    pub fn p90(&self, field: Field) -> f32 {
+        self.percentile(field, 0.9)
+    }
+
+    /// Fraction (0-1) of the queried volume holding non-default data.
+    ///
+    /// Volumes are measured against the query's bounding box, so this is an
+    /// approximation for nodes straddling the sphere's surface.
+    #[must_use]
+    pub fn coverage(&self) -> f32 {
+        let total: f32 = self.regions.iter().map(|r| r.volume).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let covered: f32 = self
+            .regions
+            .iter()
+            .filter(|r| r.has_data)
+            .map(|r| r.volume)
+            .sum();
+        covered / total
+    }
 }
 
 /// Point query (single location).
@@ -304,6 +508,23 @@ impl FoveatedResult {
         result
     }
 
+    /// Get the observation as a flat vector of normalized means.
+    ///
+    /// Same layout as [`FoveatedResult::to_flat_vec`], with each value passed
+    /// through [`Field::normalize`].
+    #[must_use]
+    pub fn to_normalized_flat_vec(&self, fields: &[Field]) -> Vec<f32> {
+        let mut result = Vec::new();
+        for shell in &self.shell_stats {
+            for sector_stats in shell {
+                for field in fields {
+                    result.push(field.normalize(sector_stats.get(*field).mean));
+                }
+            }
+        }
+        result
+    }
+
     /// Get shape of the observation tensor.
     #[must_use]
     pub fn shape(&self, num_fields: usize) -> (usize, usize, usize) {
@@ -331,4 +552,94 @@ mod tests {
         assert_eq!(QueryResolution::Depth(5).max_depth(10), 5);
         assert_eq!(QueryResolution::Full.max_depth(10), 10);
     }
+
+    fn region(values: &[f32], volume: f32) -> QueryRegion {
+        let stats = values
+            .iter()
+            .map(|&v| {
+                let mut field_values = crate::field::FieldValues::new();
+                field_values.set(Field::Smoke, v);
+                FieldStats::from_values(&field_values)
+            })
+            .collect::<Vec<_>>();
+        QueryRegion {
+            stats: FieldStats::merge_many(&stats),
+            volume,
+            has_data: values.iter().any(|&v| v != 0.0),
+        }
+    }
+
+    fn result(regions: Vec<QueryRegion>) -> QueryResult {
+        let stats = regions.iter().map(|r| r.stats.clone()).collect::<Vec<_>>();
+        QueryResult {
+            stats: FieldStats::merge_many(&stats),
+            regions,
+            ..QueryResult::default()
+        }
+    }
+
+    #[test]
+    fn test_percentiles_of_leaves_are_exact() {
+        let leaves = (1..=10_u8).map(|v| region(&[f32::from(v)], 1.0)).collect();
+        let result = result(leaves);
+
+        assert!((result.p50(Field::Smoke) - 5.0).abs() < 1e-3);
+        assert!((result.p90(Field::Smoke) - 9.0).abs() < 1e-3);
+        assert_eq!(result.percentile(Field::Smoke, 0.0), 1.0);
+        assert!((result.percentile(Field::Smoke, 1.0) - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_percentile_matches_cached_moments() {
+        // One summarized node: moments of {0, 4, 8} and of {0 x7, 16}
+        let spread = result(vec![region(&[0.0, 4.0, 8.0], 8.0)]);
+        let skewed = result(vec![region(&[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 16.0], 8.0)]);
+
+        assert!((spread.p50(Field::Smoke) - 4.0).abs() < 1e-3);
+        assert!((spread.p90(Field::Smoke) - 8.0).abs() < 1e-3);
+        assert!(skewed.p50(Field::Smoke).abs() < 1e-3);
+        assert!((skewed.histogram(Field::Smoke, 2).counts[1] - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let result = result(vec![
+            region(&[0.0], 1.0),
+            region(&[1.0], 1.0),
+            region(&[1.0], 1.0),
+            region(&[4.0], 1.0),
+        ]);
+
+        let histogram = result.histogram(Field::Smoke, 4);
+
+        assert_eq!(histogram.counts, vec![1.0, 2.0, 0.0, 1.0]);
+        assert_eq!(histogram.bucket_width(), 1.0);
+        assert_eq!(histogram.fractions()[1], 0.5);
+    }
+
+    #[test]
+    fn test_histogram_of_uniform_field() {
+        let result = result(vec![region(&[3.0], 1.0), region(&[3.0], 1.0)]);
+
+        let histogram = result.histogram(Field::Smoke, 3);
+
+        assert_eq!(histogram.counts, vec![2.0, 0.0, 0.0]);
+        assert_eq!(histogram.min, 3.0);
+    }
+
+    #[test]
+    fn test_empty_result() {
+        let result = QueryResult::default();
+
+        assert_eq!(result.p50(Field::Smoke), 0.0);
+        assert_eq!(result.coverage(), 0.0);
+        assert_eq!(result.histogram(Field::Smoke, 2).total(), 0.0);
+    }
+
+    #[test]
+    fn test_coverage_is_volume_weighted() {
+        let result = result(vec![region(&[2.0], 3.0), region(&[0.0], 1.0)]);
+
+        assert_eq!(result.coverage(), 0.75);
+    }
 }
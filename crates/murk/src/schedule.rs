@@ -0,0 +1,204 @@
+//! Delayed and periodic stamps.
+//!
+//! The scheduler holds stamps to be applied at a future simulation time, so
+//! scenarios can script timed events (volcanic vent pulses, scheduled sonar
+//! pings) without driving them every tick.
+//!
+//! Stamps are released in order of fire time, with ties broken by the order
+//! they were scheduled, so replay is deterministic.
+
+use serde::{Deserialize, Serialize};
+
+use crate::stamp::Stamp;
+
+/// A stamp waiting to be applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledStamp {
+    /// Schedule ID (for cancellation)
+    pub id: u64,
+    /// Stamp to apply
+    pub stamp: Stamp,
+    /// Simulation time of the next application
+    pub next_time: f64,
+    /// Seconds between applications (0 for one-shot stamps)
+    pub interval: f64,
+    /// Applications left (`None` repeats forever)
+    pub remaining: Option<u32>,
+}
+
+/// Queue of delayed and periodic stamps.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StampScheduler {
+    entries: Vec<ScheduledStamp>,
+    next_id: u64,
+}
+
+impl StampScheduler {
+    /// Create an empty scheduler.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule `stamp` to be applied once at `at_time`.
+    pub fn schedule(&mut self, stamp: Stamp, at_time: f64) -> u64 {
+        self.push(stamp, at_time, 0.0, Some(1))
+    }
+
+    /// Schedule `stamp` every `interval` seconds, first at `start_time`.
+    ///
+    /// `count` limits the number of applications (`None` repeats forever).
+    /// Returns `None` without scheduling if `interval` is not positive or
+    /// `count` is zero.
+    pub fn schedule_periodic(
+        &mut self,
+        stamp: Stamp,
+        start_time: f64,
+        interval: f64,
+        count: Option<u32>,
+    ) -> Option<u64> {
+        if interval.is_nan() || interval <= 0.0 || count == Some(0) {
+            return None;
+        }
+        Some(self.push(stamp, start_time, interval, count))
+    }
+
+    fn push(&mut self, stamp: Stamp, at_time: f64, interval: f64, remaining: Option<u32>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(ScheduledStamp {
+            id,
+            stamp,
+            next_time: at_time,
+            interval,
+            remaining,
+        });
+        id
+    }
+
+    /// Cancel a scheduled stamp. Returns whether it was pending.
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        self.entries.len() != before
+    }
+
+    /// Remove all scheduled stamps.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Number of pending schedules.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if nothing is scheduled.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pending schedules, in scheduling order.
+    #[must_use]
+    pub fn pending(&self) -> &[ScheduledStamp] {
+        &self.entries
+    }
+
+    /// Take every application due at or before `until`, in firing order.
+    ///
+    /// Periodic stamps due several times in the window are returned once per
+    /// application. Exhausted schedules are removed.
+    pub fn drain_due(&mut self, until: f64) -> Vec<Stamp> {
+        let mut due: Vec<(f64, u64, Stamp)> = Vec::new();
+        for entry in &mut self.entries {
+            while entry.next_time <= until && entry.remaining != Some(0) {
+                due.push((entry.next_time, entry.id, entry.stamp.clone()));
+                entry.remaining = entry.remaining.map(|n| n - 1);
+                if entry.interval <= 0.0 {
+                    entry.remaining = Some(0);
+                }
+                entry.next_time += entry.interval;
+            }
+        }
+        self.entries.retain(|e| e.remaining != Some(0));
+
+        due.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        due.into_iter().map(|(_, _, stamp)| stamp).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    fn ping(x: f32) -> Stamp {
+        Stamp::sonar_ping(Vec3::new(x, 0.0, 0.0), 1.0, 1.0)
+    }
+
+    fn xs(stamps: &[Stamp]) -> Vec<f32> {
+        stamps.iter().map(|s| s.shape.bounds().center().x).collect()
+    }
+
+    #[test]
+    fn test_one_shot() {
+        let mut scheduler = StampScheduler::new();
+        scheduler.schedule(ping(1.0), 2.0);
+
+        assert!(scheduler.drain_due(1.5).is_empty());
+        assert_eq!(scheduler.drain_due(2.0).len(), 1);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_firing_order() {
+        let mut scheduler = StampScheduler::new();
+        scheduler.schedule(ping(3.0), 3.0);
+        scheduler.schedule(ping(1.0), 1.0);
+        scheduler.schedule(ping(2.0), 1.0);
+
+        // By time, then by scheduling order
+        assert_eq!(xs(&scheduler.drain_due(5.0)), vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_periodic_count() {
+        let mut scheduler = StampScheduler::new();
+        scheduler.schedule_periodic(ping(0.0), 1.0, 1.0, Some(3)).unwrap();
+
+        assert_eq!(scheduler.drain_due(1.0).len(), 1);
+        // Two applications fall in one window
+        assert_eq!(scheduler.drain_due(3.0).len(), 2);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_periodic_forever() {
+        let mut scheduler = StampScheduler::new();
+        scheduler.schedule_periodic(ping(0.0), 0.0, 0.5, None).unwrap();
+
+        assert_eq!(scheduler.drain_due(10.0).len(), 21);
+        assert_eq!(scheduler.len(), 1);
+        assert_eq!(scheduler.pending()[0].next_time, 10.5);
+    }
+
+    #[test]
+    fn test_invalid_periodic() {
+        let mut scheduler = StampScheduler::new();
+        assert!(scheduler.schedule_periodic(ping(0.0), 0.0, 0.0, None).is_none());
+        assert!(scheduler.schedule_periodic(ping(0.0), 0.0, 1.0, Some(0)).is_none());
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut scheduler = StampScheduler::new();
+        let id = scheduler.schedule(ping(0.0), 1.0);
+
+        assert!(scheduler.cancel(id));
+        assert!(!scheduler.cancel(id));
+        assert!(scheduler.drain_due(2.0).is_empty());
+    }
+}
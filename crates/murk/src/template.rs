@@ -0,0 +1,451 @@
+//! Reusable, parameterized stamp templates.
+//!
+//! A [`StampTemplate`] describes a multi-field stamp whose sizes and values
+//! may refer to named parameters with defaults (e.g. `radius`, `intensity`).
+//! A [`StampLibrary`] maps names to templates, so content such as a
+//! `"depth_charge"` or `"volcanic_vent"` is authored as data and placed by
+//! name with parameter overrides.
+//!
+//! Templates are plain serde types; hosts load them from any format they
+//! already use (JSON, TOML, RON). In JSON a library looks like:
+//!
+//! ```json
+//! {
+//!   "flare": {
+//!     "params": { "radius": 5.0, "intensity": 1.0 },
+//!     "shape": { "Sphere": { "radius": "radius" } },
+//!     "modifications": [
+//!       { "field": "Temperature", "op": "Add",
+//!         "value": { "param": "intensity", "scale": 300.0 } },
+//!       { "field": "Signal", "op": "Max", "value": "intensity" }
+//!     ],
+//!     "falloff": true
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::field::Field;
+use crate::stamp::{BlendOp, FieldMod, Stamp, StampShape};
+use crate::Bounds;
+
+/// Errors from instantiating a stamp template.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TemplateError {
+    /// No template with this name is registered
+    #[error("unknown stamp template: {0}")]
+    UnknownTemplate(String),
+    /// An override or reference names a parameter the template does not declare
+    #[error("unknown stamp template parameter: {0}")]
+    UnknownParameter(String),
+}
+
+/// A number in a template: a constant or derived from a parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Scalar {
+    /// Fixed value
+    Const(f32),
+    /// Value of the named parameter
+    Param(String),
+    /// `offset + scale * parameter`
+    Scaled {
+        /// Parameter name
+        param: String,
+        /// Multiplier applied to the parameter
+        scale: f32,
+        /// Constant added after scaling
+        #[serde(default)]
+        offset: f32,
+    },
+}
+
+impl Scalar {
+    /// Resolve against parameter values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TemplateError::UnknownParameter`] if the parameter is missing.
+    pub fn resolve(&self, params: &BTreeMap<String, f32>) -> Result<f32, TemplateError> {
+        let lookup = |name: &String| {
+            params
+                .get(name)
+                .copied()
+                .ok_or_else(|| TemplateError::UnknownParameter(name.clone()))
+        };
+        match self {
+            Scalar::Const(value) => Ok(*value),
+            Scalar::Param(name) => lookup(name),
+            Scalar::Scaled {
+                param,
+                scale,
+                offset,
+            } => Ok(offset + scale * lookup(param)?),
+        }
+    }
+}
+
+impl From<f32> for Scalar {
+    fn from(value: f32) -> Self {
+        Scalar::Const(value)
+    }
+}
+
+/// Shape of a template, placed relative to the stamp center.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ShapeTemplate {
+    /// Sphere around the center
+    Sphere {
+        /// Sphere radius
+        radius: Scalar,
+    },
+    /// Axis-aligned box around the center
+    Box {
+        /// Half sizes along x, y and z
+        half_extents: [Scalar; 3],
+    },
+    /// Vertical capsule rising from the center (vents, plumes)
+    Column {
+        /// Capsule radius
+        radius: Scalar,
+        /// Height of the column above the center
+        height: Scalar,
+    },
+}
+
+/// A field modification whose value may depend on parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModTemplate {
+    /// Which field to modify
+    pub field: Field,
+    /// How to apply the modification
+    pub op: BlendOp,
+    /// The value to apply
+    pub value: Scalar,
+}
+
+impl ModTemplate {
+    /// Create a modification template.
+    #[must_use]
+    pub fn new(field: Field, op: BlendOp, value: impl Into<Scalar>) -> Self {
+        Self {
+            field,
+            op,
+            value: value.into(),
+        }
+    }
+}
+
+/// Named-parameter recipe for a multi-field stamp.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StampTemplate {
+    /// Declared parameters and their defaults
+    #[serde(default)]
+    pub params: BTreeMap<String, f32>,
+    /// Shape, relative to the stamp center
+    pub shape: ShapeTemplate,
+    /// Field modifications
+    pub modifications: Vec<ModTemplate>,
+    /// Whether modifications fade towards the shape's edge
+    #[serde(default)]
+    pub falloff: bool,
+}
+
+impl StampTemplate {
+    /// Create a template without parameters or falloff.
+    #[must_use]
+    pub fn new(shape: ShapeTemplate, modifications: Vec<ModTemplate>) -> Self {
+        Self {
+            params: BTreeMap::new(),
+            shape,
+            modifications,
+            falloff: false,
+        }
+    }
+
+    /// Declare a parameter with its default value.
+    #[must_use]
+    pub fn with_param(mut self, name: &str, default: f32) -> Self {
+        self.params.insert(name.to_string(), default);
+        self
+    }
+
+    /// Enable falloff.
+    #[must_use]
+    pub fn with_falloff(mut self) -> Self {
+        self.falloff = true;
+        self
+    }
+
+    /// Build a stamp at `center`, with `overrides` replacing parameter defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TemplateError::UnknownParameter`] if an override is not a
+    /// declared parameter, or the template refers to an undeclared one.
+    pub fn instantiate(
+        &self,
+        center: Vec3,
+        overrides: &BTreeMap<String, f32>,
+    ) -> Result<Stamp, TemplateError> {
+        let mut params = self.params.clone();
+        for (name, value) in overrides {
+            let slot = params
+                .get_mut(name)
+                .ok_or_else(|| TemplateError::UnknownParameter(name.clone()))?;
+            *slot = *value;
+        }
+
+        let shape = match &self.shape {
+            ShapeTemplate::Sphere { radius } => {
+                StampShape::sphere(center, radius.resolve(&params)?)
+            }
+            ShapeTemplate::Box { half_extents } => {
+                let [x, y, z] = half_extents;
+                let half = Vec3::new(x.resolve(&params)?, y.resolve(&params)?, z.resolve(&params)?);
+                StampShape::aabb(Bounds::from_min_max(center - half, center + half))
+            }
+            ShapeTemplate::Column { radius, height } => StampShape::capsule(
+                center,
+                center + Vec3::Z * height.resolve(&params)?,
+                radius.resolve(&params)?,
+            ),
+        };
+        let modifications = self
+            .modifications
+            .iter()
+            .map(|m| Ok(FieldMod::new(m.field, m.op, m.value.resolve(&params)?)))
+            .collect::<Result<Vec<_>, TemplateError>>()?;
+
+        let stamp = Stamp::new(shape, modifications);
+        Ok(if self.falloff {
+            stamp.with_falloff()
+        } else {
+            stamp
+        })
+    }
+}
+
+/// Registry of stamp templates by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StampLibrary {
+    templates: BTreeMap<String, StampTemplate>,
+}
+
+impl StampLibrary {
+    /// Create an empty library.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a library with the built-in templates: `explosion`, `fire`
+    /// and `sonar_ping` (matching the [`Stamp`] constructors), plus
+    /// `depth_charge` and `volcanic_vent`.
+    #[must_use]
+    pub fn with_builtins() -> Self {
+        let intensity = |scale: f32, offset: f32| Scalar::Scaled {
+            param: "intensity".to_string(),
+            scale,
+            offset,
+        };
+        let sphere = || ShapeTemplate::Sphere {
+            radius: Scalar::Param("radius".to_string()),
+        };
+
+        let mut library = Self::new();
+        library.insert(
+            "explosion",
+            StampTemplate::new(sphere(), vec![
+                ModTemplate::new(Field::Occupancy, BlendOp::Subtract, intensity(0.8, 0.0)),
+                ModTemplate::new(Field::Temperature, BlendOp::Add, intensity(500.0, 0.0)),
+                ModTemplate::new(Field::Noise, BlendOp::Add, intensity(120.0, 0.0)),
+                ModTemplate::new(Field::Integrity, BlendOp::Multiply, intensity(-0.8, 1.0)),
+            ])
+            .with_param("radius", 10.0)
+            .with_param("intensity", 1.0)
+            .with_falloff(),
+        );
+        library.insert(
+            "fire",
+            StampTemplate::new(sphere(), vec![
+                ModTemplate::new(Field::Temperature, BlendOp::Lerp { factor: 0.1 }, 800.0),
+                ModTemplate::new(Field::Smoke, BlendOp::Add, intensity(0.3, 0.0)),
+            ])
+            .with_param("radius", 10.0)
+            .with_param("intensity", 1.0)
+            .with_falloff(),
+        );
+        library.insert(
+            "sonar_ping",
+            StampTemplate::new(sphere(), vec![
+                ModTemplate::new(
+                    Field::SonarReturn,
+                    BlendOp::Max,
+                    Scalar::Param("strength".to_string()),
+                ),
+                ModTemplate::new(Field::Noise, BlendOp::Add, 80.0),
+            ])
+            .with_param("radius", 50.0)
+            .with_param("strength", 1.0)
+            .with_falloff(),
+        );
+        library.insert(
+            "depth_charge",
+            StampTemplate::new(sphere(), vec![
+                ModTemplate::new(Field::Noise, BlendOp::Max, intensity(180.0, 0.0)),
+                ModTemplate::new(Field::SonarReturn, BlendOp::Max, intensity(0.8, 0.0)),
+                ModTemplate::new(Field::Integrity, BlendOp::Multiply, intensity(-0.5, 1.0)),
+            ])
+            .with_param("radius", 30.0)
+            .with_param("intensity", 1.0)
+            .with_falloff(),
+        );
+        library.insert(
+            "volcanic_vent",
+            StampTemplate::new(
+                ShapeTemplate::Column {
+                    radius: Scalar::Param("radius".to_string()),
+                    height: Scalar::Param("height".to_string()),
+                },
+                vec![
+                    ModTemplate::new(Field::Temperature, BlendOp::Lerp { factor: 0.2 }, 1200.0),
+                    ModTemplate::new(Field::Smoke, BlendOp::Add, intensity(0.4, 0.0)),
+                    ModTemplate::new(Field::Noise, BlendOp::Max, intensity(90.0, 0.0)),
+                ],
+            )
+            .with_param("radius", 20.0)
+            .with_param("height", 50.0)
+            .with_param("intensity", 1.0)
+            .with_falloff(),
+        );
+        library
+    }
+
+    /// Register a template, replacing any with the same name.
+    pub fn insert(&mut self, name: &str, template: StampTemplate) {
+        self.templates.insert(name.to_string(), template);
+    }
+
+    /// Register every template of `other`, replacing same-named ones.
+    pub fn extend(&mut self, other: StampLibrary) {
+        self.templates.extend(other.templates);
+    }
+
+    /// Get a template by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&StampTemplate> {
+        self.templates.get(name)
+    }
+
+    /// Remove a template, returning it if it was registered.
+    pub fn remove(&mut self, name: &str) -> Option<StampTemplate> {
+        self.templates.remove(name)
+    }
+
+    /// Iterate over template names in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.templates.keys().map(String::as_str)
+    }
+
+    /// Number of registered templates.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    /// Whether no templates are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// Build a stamp from the named template.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TemplateError::UnknownTemplate`] if `name` is not registered,
+    /// or the errors of [`StampTemplate::instantiate`].
+    pub fn instantiate(
+        &self,
+        name: &str,
+        center: Vec3,
+        overrides: &BTreeMap<String, f32>,
+    ) -> Result<Stamp, TemplateError> {
+        self.get(name)
+            .ok_or_else(|| TemplateError::UnknownTemplate(name.to_string()))?
+            .instantiate(center, overrides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(pairs: &[(&str, f32)]) -> BTreeMap<String, f32> {
+        pairs.iter().map(|(k, v)| ((*k).to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_builtin_explosion_matches_constructor() {
+        let library = StampLibrary::with_builtins();
+        let center = Vec3::new(1.0, 2.0, 3.0);
+        let from_template = library
+            .instantiate("explosion", center, &overrides(&[("radius", 15.0), ("intensity", 0.5)]))
+            .unwrap();
+        let direct = Stamp::explosion(center, 15.0, 0.5);
+
+        assert_eq!(from_template.shape.bounds(), direct.shape.bounds());
+        assert_eq!(from_template.falloff, direct.falloff);
+        for (a, b) in from_template.modifications.iter().zip(&direct.modifications) {
+            assert_eq!(a.field, b.field);
+            assert!((a.value - b.value).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_unknown_names_are_rejected() {
+        let library = StampLibrary::with_builtins();
+        let none = BTreeMap::new();
+        assert_eq!(
+            library.instantiate("kraken", Vec3::ZERO, &none).unwrap_err(),
+            TemplateError::UnknownTemplate("kraken".to_string())
+        );
+        assert_eq!(
+            library
+                .instantiate("fire", Vec3::ZERO, &overrides(&[("heat", 1.0)]))
+                .unwrap_err(),
+            TemplateError::UnknownParameter("heat".to_string())
+        );
+    }
+
+    #[test]
+    fn test_template_roundtrips_through_json() {
+        let json = r#"{
+            "flare": {
+                "params": { "radius": 5.0, "intensity": 1.0 },
+                "shape": { "Column": { "radius": "radius", "height": 20.0 } },
+                "modifications": [
+                    { "field": "Temperature", "op": "Add",
+                      "value": { "param": "intensity", "scale": 300.0 } }
+                ]
+            }
+        }"#;
+        let library: StampLibrary = serde_json::from_str(json).unwrap();
+        let stamp = library
+            .instantiate("flare", Vec3::ZERO, &overrides(&[("intensity", 2.0)]))
+            .unwrap();
+
+        assert!((stamp.modifications[0].value - 600.0).abs() < 1e-5);
+        assert!(stamp.shape.contains(Vec3::new(0.0, 0.0, 15.0)));
+        assert!(!stamp.falloff);
+        let back: StampLibrary =
+            serde_json::from_str(&serde_json::to_string(&library).unwrap()).unwrap();
+        assert_eq!(back, library);
+    }
+}
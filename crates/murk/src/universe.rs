@@ -3,17 +3,25 @@
 //! The Universe wraps the octree and provides a convenient high-level interface
 //! for common operations.
 
-use glam::Vec3;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use glam::{Vec2, Vec3};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 
 use crate::field::{Field, FieldConfig, FieldValues};
-use crate::octree::{Octree, OctreeConfig, OctreeStats};
+use crate::isosurface::{self, IsoMesh};
+use crate::octree::{FrozenRegion, Octree, OctreeConfig};
+use crate::pathfinding::{self, PathCosts};
+use crate::propagation::FieldProcess;
 use crate::query::{
     FoveatedQuery, FoveatedResult, PointQuery, PointResult, QueryResolution, QueryResult,
     VolumeQuery,
 };
+use crate::schedule::StampScheduler;
 use crate::stamp::Stamp;
 // FieldStats imported via query module
 use crate::Bounds;
@@ -31,6 +39,21 @@ pub struct UniverseConfig {
     pub split_threshold: f32,
     /// Field configurations (optional overrides)
     pub field_configs: Vec<FieldConfig>,
+    /// Upper bound on octree memory, in bytes (`None` for no limit).
+    ///
+    /// When stamps or propagation push the octree past it, the regions
+    /// least recently stamped or queried are coarsened (see
+    /// [`Octree::enforce_memory_budget`]).
+    #[serde(default)]
+    pub memory_budget: Option<usize>,
+    /// Custom field processes, run each step after the configured
+    /// propagation, in order (not serialized).
+    #[serde(skip)]
+    pub processes: Vec<Arc<dyn FieldProcess>>,
+    /// Surface-only mode: cells split in X and Y only, each spanning the
+    /// full depth range (see [`OctreeConfig::planar`]).
+    #[serde(default)]
+    pub planar: bool,
 }
 
 impl Default for UniverseConfig {
@@ -41,6 +64,9 @@ impl Default for UniverseConfig {
             merge_threshold: 0.02,
             split_threshold: 0.1,
             field_configs: Vec::new(),
+            memory_budget: None,
+            processes: Vec::new(),
+            planar: false,
         }
     }
 }
@@ -56,6 +82,81 @@ impl UniverseConfig {
     }
 }
 
+/// Health and usage counters of a universe, from [`Universe::stats`].
+///
+/// Usage counters run from the universe's creation (or deserialization)
+/// and are not reset by [`Universe::reset`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UniverseStats {
+    /// Total number of octree nodes
+    pub node_count: usize,
+    /// Number of leaf nodes
+    pub leaf_count: usize,
+    /// Maximum octree depth
+    pub max_depth: u8,
+    /// Allocated nodes per depth, root first
+    pub nodes_by_depth: Vec<usize>,
+    /// Approximate memory held by the nodes, in bytes
+    pub memory_bytes: usize,
+    /// Configured memory budget, in bytes
+    pub memory_budget: Option<usize>,
+    /// Subtrees coarsened to stay within the memory budget
+    pub evictions: u64,
+    /// Stamps applied, directly or by the scheduler
+    pub stamps_applied: u64,
+    /// Point and volume queries answered (each sector of a foveated
+    /// observation counts as one)
+    pub queries_served: u64,
+    /// Octree nodes visited by those queries
+    pub nodes_visited: u64,
+    /// Wall-clock duration of the last [`Universe::step`]
+    pub last_step_duration: Option<Duration>,
+}
+
+impl UniverseStats {
+    /// Average number of nodes visited per query (0 before any query).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn avg_nodes_visited(&self) -> f64 {
+        if self.queries_served == 0 {
+            0.0
+        } else {
+            self.nodes_visited as f64 / self.queries_served as f64
+        }
+    }
+}
+
+/// Usage counters; queries only borrow the universe, so they are atomic.
+#[derive(Debug, Default)]
+struct Usage {
+    stamps_applied: u64,
+    queries_served: AtomicU64,
+    nodes_visited: AtomicU64,
+    last_step_duration: Option<Duration>,
+}
+
+impl Usage {
+    /// Count queries that visited the given numbers of nodes.
+    fn record_queries(&self, nodes_visited: impl IntoIterator<Item = u32>) {
+        let (queries, nodes) = nodes_visited
+            .into_iter()
+            .fold((0, 0), |(q, n), visited| (q + 1, n + u64::from(visited)));
+        self.queries_served.fetch_add(queries, Ordering::Relaxed);
+        self.nodes_visited.fetch_add(nodes, Ordering::Relaxed);
+    }
+}
+
+impl Clone for Usage {
+    fn clone(&self) -> Self {
+        Self {
+            stamps_applied: self.stamps_applied,
+            queries_served: AtomicU64::new(self.queries_served.load(Ordering::Relaxed)),
+            nodes_visited: AtomicU64::new(self.nodes_visited.load(Ordering::Relaxed)),
+            last_step_duration: self.last_step_duration,
+        }
+    }
+}
+
 /// The Universe: top-level container for spatial fields.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Universe {
@@ -72,13 +173,26 @@ pub struct Universe {
     rng: Option<ChaCha8Rng>,
     /// Original seed for replay
     seed: Option<u64>,
+    /// Delayed and periodic stamps
+    #[serde(default)]
+    scheduler: StampScheduler,
+    /// Usage counters (runtime only)
+    #[serde(skip)]
+    usage: Usage,
+    /// Custom field processes (runtime only)
+    #[serde(skip)]
+    processes: Vec<Arc<dyn FieldProcess>>,
 }
 
 impl Universe {
     /// Create a new Universe.
     #[must_use]
     pub fn new(config: UniverseConfig) -> Self {
-        let max_depth = OctreeConfig::calculate_max_depth(&config.bounds, config.base_resolution);
+        let max_depth = if config.planar {
+            OctreeConfig::calculate_planar_max_depth(&config.bounds, config.base_resolution)
+        } else {
+            OctreeConfig::calculate_max_depth(&config.bounds, config.base_resolution)
+        };
 
         let octree = Octree::new(OctreeConfig {
             bounds: config.bounds,
@@ -86,6 +200,8 @@ impl Universe {
             max_depth,
             merge_threshold: config.merge_threshold,
             split_threshold: config.split_threshold,
+            memory_budget: config.memory_budget,
+            planar: config.planar,
         });
 
         // Initialize field configs with defaults, then apply overrides
@@ -103,6 +219,9 @@ impl Universe {
             time: 0.0,
             rng: None,
             seed: None,
+            scheduler: StampScheduler::new(),
+            usage: Usage::default(),
+            processes: config.processes,
         }
     }
 
@@ -141,10 +260,23 @@ impl Universe {
         self.time
     }
 
-    /// Get octree statistics.
+    /// Get octree statistics and usage counters.
     #[must_use]
-    pub fn stats(&self) -> OctreeStats {
-        self.octree.stats()
+    pub fn stats(&self) -> UniverseStats {
+        let octree = self.octree.stats();
+        UniverseStats {
+            node_count: octree.node_count,
+            leaf_count: octree.leaf_count,
+            max_depth: octree.max_depth,
+            nodes_by_depth: self.octree.nodes_by_depth(),
+            memory_bytes: octree.memory_bytes,
+            memory_budget: octree.memory_budget,
+            evictions: octree.evictions,
+            stamps_applied: self.usage.stamps_applied,
+            queries_served: self.usage.queries_served.load(Ordering::Relaxed),
+            nodes_visited: self.usage.nodes_visited.load(Ordering::Relaxed),
+            last_step_duration: self.usage.last_step_duration,
+        }
     }
 
     /// Get the world bounds.
@@ -174,6 +306,29 @@ impl Universe {
         &self.field_configs[field.index()]
     }
 
+    /// Get all field configurations, indexed by [`Field::index`].
+    #[must_use]
+    pub fn field_configs(&self) -> &[FieldConfig; Field::COUNT] {
+        &self.field_configs
+    }
+
+    /// Get the custom field processes, in the order they run.
+    #[must_use]
+    pub fn processes(&self) -> &[Arc<dyn FieldProcess>] {
+        &self.processes
+    }
+
+    /// Register a custom field process, run each step after the
+    /// configured propagation and any processes registered before it.
+    pub fn add_process(&mut self, process: Arc<dyn FieldProcess>) {
+        self.processes.push(process);
+    }
+
+    /// Get write access to the octree (for field processes).
+    pub(crate) fn octree_mut(&mut self) -> &mut Octree {
+        &mut self.octree
+    }
+
     // ========================================================================
     // Mutation
     // ========================================================================
@@ -181,13 +336,66 @@ impl Universe {
     /// Apply a stamp to the universe.
     pub fn stamp(&mut self, stamp: &Stamp) {
         self.octree.apply_stamp(stamp);
+        self.usage.stamps_applied += 1;
+        self.octree.enforce_memory_budget();
     }
 
     /// Apply multiple stamps.
     pub fn stamp_many(&mut self, stamps: &[Stamp]) {
         for stamp in stamps {
             self.octree.apply_stamp(stamp);
+            self.usage.stamps_applied += 1;
         }
+        self.octree.enforce_memory_budget();
+    }
+
+    /// Schedule a stamp to be applied once at simulation time `at_time`.
+    ///
+    /// Returns a schedule ID for [`Self::cancel_scheduled`]. Times already
+    /// passed are applied on the next step.
+    pub fn schedule_stamp(&mut self, stamp: Stamp, at_time: f64) -> u64 {
+        self.scheduler.schedule(stamp, at_time)
+    }
+
+    /// Schedule a stamp every `interval` seconds, first one interval from now.
+    ///
+    /// `count` limits the number of applications (`None` repeats forever).
+    /// Returns `None` if `interval` is not positive or `count` is zero.
+    pub fn schedule_periodic(&mut self, stamp: Stamp, interval: f64, count: Option<u32>) -> Option<u64> {
+        self.scheduler
+            .schedule_periodic(stamp, self.time + interval, interval, count)
+    }
+
+    /// Cancel a scheduled stamp. Returns whether it was pending.
+    pub fn cancel_scheduled(&mut self, id: u64) -> bool {
+        self.scheduler.cancel(id)
+    }
+
+    /// Get the stamp scheduler.
+    #[must_use]
+    pub fn scheduler(&self) -> &StampScheduler {
+        &self.scheduler
+    }
+
+    /// Make `fields` inside `bounds` immutable to stamps and propagation.
+    ///
+    /// Use for static features such as land: set the terrain with
+    /// [`Self::set_point`] (which is not masked), then freeze `Occupancy` and
+    /// `Depth` so explosions cannot carve holes in it. Cleared by
+    /// [`Self::reset`].
+    pub fn freeze_region(&mut self, bounds: Bounds, fields: &[Field]) {
+        self.octree.freeze_region(bounds, fields);
+    }
+
+    /// Remove all frozen regions.
+    pub fn clear_frozen(&mut self) {
+        self.octree.clear_frozen();
+    }
+
+    /// Get the frozen regions.
+    #[must_use]
+    pub fn frozen_regions(&self) -> &[FrozenRegion] {
+        self.octree.frozen_regions()
     }
 
     /// Set field values at a point.
@@ -202,26 +410,86 @@ impl Universe {
     /// Query a single point.
     #[must_use]
     pub fn query_point(&self, position: Vec3) -> PointResult {
-        self.octree.query_point(&PointQuery::new(position))
+        let result = self.octree.query_point(&PointQuery::new(position));
+        self.usage.record_queries([u32::from(result.depth) + 1]);
+        result
     }
 
     /// Query a volume.
     #[must_use]
     pub fn query_volume(&self, center: Vec3, radius: f32, resolution: QueryResolution) -> QueryResult {
-        self.octree.query_volume(
+        let result = self.octree.query_volume(
             &VolumeQuery::new(center, radius).with_resolution(resolution),
-        )
+        );
+        self.usage.record_queries([result.nodes_visited]);
+        result
+    }
+
+    /// Extract the surface where `field` crosses `threshold` within `region`.
+    ///
+    /// See [`crate::isosurface`] for the sampling and winding conventions.
+    #[must_use]
+    pub fn extract_isosurface(&self, field: Field, threshold: f32, region: Bounds) -> IsoMesh {
+        isosurface::extract_isosurface(&self.octree, field, threshold, region)
+    }
+
+    /// Extract contour lines where `field` crosses `threshold` on the
+    /// horizontal slice at height `z`, within the x/y extent of `region`.
+    #[must_use]
+    pub fn extract_isolines(&self, field: Field, threshold: f32, region: Bounds, z: f32) -> Vec<Vec<Vec2>> {
+        isosurface::extract_isolines(&self.octree, field, threshold, region, z)
+    }
+
+    /// Find a surface route for a vessel of `draft` meters from `start` to
+    /// `goal`, searching cells about `resolution` wide.
+    ///
+    /// See [`crate::pathfinding`] for what counts as navigable.
+    #[must_use]
+    pub fn find_path(
+        &self,
+        start: Vec3,
+        goal: Vec3,
+        draft: f32,
+        resolution: f32,
+    ) -> Option<Vec<Vec3>> {
+        pathfinding::find_path(&self.octree, start, goal, draft, resolution)
+    }
+
+    /// Like [`Self::find_path`], but find the cheapest route under `costs`,
+    /// e.g. one avoiding loud water or an enemy-threat heatmap.
+    #[must_use]
+    pub fn find_path_with_costs(
+        &self,
+        start: Vec3,
+        goal: Vec3,
+        draft: f32,
+        resolution: f32,
+        costs: &PathCosts,
+    ) -> Option<Vec<Vec3>> {
+        pathfinding::find_path_with_costs(&self.octree, start, goal, draft, resolution, costs)
+    }
+
+    /// Query many volumes in a single octree traversal.
+    ///
+    /// Returns one result per query, in order, equal to what
+    /// [`Self::query_volume`] would return for each; nodes shared by several
+    /// queries are visited once.
+    #[must_use]
+    pub fn query_batch(&self, queries: &[VolumeQuery]) -> Vec<QueryResult> {
+        let results = self.octree.query_batch(queries);
+        self.usage.record_queries(results.iter().map(|r| r.nodes_visited));
+        results
     }
 
     /// Get a foveated observation for an agent.
+    ///
+    /// All sectors of all shells are answered by one batched traversal.
     #[must_use]
     pub fn observe_foveated(&self, query: &FoveatedQuery) -> FoveatedResult {
-        let mut shell_stats = Vec::with_capacity(query.shells.len());
-        let mut total_nodes_visited = 0;
+        let heading_angle = query.heading.y.atan2(query.heading.x);
+        let mut sectors = Vec::new();
 
         for shell in &query.shells {
-            let mut sector_stats = Vec::with_capacity(shell.sectors as usize);
-
             // For each sector in this shell
             for sector_idx in 0..shell.sectors {
                 // Calculate sector center
@@ -229,7 +497,6 @@ impl Universe {
                 let mid_radius = (shell.radius_inner + shell.radius_outer) / 2.0;
 
                 // Rotate by heading
-                let heading_angle = query.heading.y.atan2(query.heading.x);
                 let sector_angle = heading_angle + angle;
 
                 let sector_center = query.position
@@ -237,19 +504,30 @@ impl Universe {
 
                 let sector_radius = (shell.radius_outer - shell.radius_inner) / 2.0;
 
-                // Query this sector
-                let result = self.octree.query_volume(
-                    &VolumeQuery::new(sector_center, sector_radius)
+                sectors.push(
+                    VolumeQuery::new(sector_center, sector_radius)
                         .with_resolution(shell.resolution),
                 );
-
-                total_nodes_visited += result.nodes_visited;
-                sector_stats.push(result.stats);
             }
-
-            shell_stats.push(sector_stats);
         }
 
+        let mut results = self.query_batch(&sectors).into_iter();
+        let mut total_nodes_visited = 0;
+        let shell_stats = query
+            .shells
+            .iter()
+            .map(|shell| {
+                results
+                    .by_ref()
+                    .take(shell.sectors as usize)
+                    .map(|result| {
+                        total_nodes_visited += result.nodes_visited;
+                        result.stats
+                    })
+                    .collect()
+            })
+            .collect();
+
         FoveatedResult {
             shell_stats,
             nodes_visited: total_nodes_visited,
@@ -262,13 +540,23 @@ impl Universe {
 
     /// Advance simulation by one tick.
     ///
-    /// This propagates fields (diffusion, decay) according to their configurations.
+    /// Scheduled stamps due by the end of the step are applied first, in
+    /// firing order, then fields propagate (diffusion, decay) according to
+    /// their configurations.
     pub fn step(&mut self, dt: f64) {
+        let started = Instant::now();
+        for stamp in self.scheduler.drain_due(self.time + dt) {
+            self.octree.apply_stamp(&stamp);
+            self.usage.stamps_applied += 1;
+        }
+
         // Propagate fields (diffusion, decay)
         crate::propagation::propagate_all(self, dt);
+        self.octree.enforce_memory_budget();
 
         self.tick += 1;
         self.time += dt;
+        self.usage.last_step_duration = Some(started.elapsed());
     }
 
     /// Reset the universe to initial state.
@@ -278,6 +566,7 @@ impl Universe {
     pub fn reset(&mut self) {
         let config = self.octree.config().clone();
         self.octree = Octree::new(config);
+        self.scheduler.clear();
         self.tick = 0;
         self.time = 0.0;
         // Re-seed RNG if a seed exists (for deterministic replay)
@@ -317,6 +606,74 @@ mod tests {
         assert!(result.mean(Field::Noise) > 0.0);
     }
 
+    #[test]
+    fn test_memory_budget_evicts_least_recent_regions() {
+        let node_size = std::mem::size_of::<crate::node::OctreeNode>();
+        let mut universe = Universe::new(UniverseConfig {
+            memory_budget: Some(400 * node_size),
+            ..UniverseConfig::with_bounds(256.0, 256.0, 64.0)
+        });
+        let old = Vec3::new(-100.0, -100.0, 0.0);
+        let recent = Vec3::new(100.0, 100.0, 0.0);
+        // Materialize the tree so stamps refine it around their centers
+        universe.set_point(Vec3::ZERO, FieldValues::new());
+        universe.stamp(&Stamp::explosion(old, 3.0, 1.0));
+        universe.stamp(&Stamp::explosion(recent, 3.0, 1.0));
+
+        let stats = universe.stats();
+        assert!(stats.evictions > 0);
+        assert!(stats.memory_bytes <= 400 * node_size);
+        // The recent stamp keeps its detail; the old one was coarsened
+        assert!(universe.query_point(recent).depth > universe.query_point(old).depth);
+        let ambient = FieldValues::new().get(Field::Temperature);
+        assert!(universe.query_point(old).values.get(Field::Temperature) > ambient);
+    }
+
+    #[test]
+    fn test_planar_universe_uses_columns() {
+        let stamp = Stamp::explosion(Vec3::new(20.0, -10.0, 0.0), 8.0, 1.0);
+        let mut flat = Universe::new(UniverseConfig {
+            planar: true,
+            ..UniverseConfig::with_bounds(128.0, 128.0, 32.0)
+        });
+        let mut full = Universe::new(UniverseConfig::with_bounds(128.0, 128.0, 32.0));
+        for universe in [&mut flat, &mut full] {
+            universe.set_point(Vec3::ZERO, FieldValues::new());
+            universe.stamp(&stamp);
+        }
+
+        let surface = flat.query_point(Vec3::new(20.0, -10.0, 0.0));
+        let deep = flat.query_point(Vec3::new(20.0, -10.0, -15.0));
+        assert_eq!(surface.values.as_slice(), deep.values.as_slice());
+        assert!(surface.values.get(Field::Temperature) > 0.0);
+        assert!(flat.stats().node_count < full.stats().node_count);
+
+        let nodes = flat.stats().nodes_by_depth;
+        assert!(nodes.windows(2).all(|w| w[1] <= 4 * w[0]));
+        assert_eq!(nodes.iter().sum::<usize>(), flat.stats().node_count);
+        let result = flat.query_volume(Vec3::new(20.0, -10.0, 0.0), 4.0, QueryResolution::Fine);
+        assert!(result.mean(Field::Temperature) > 0.0);
+    }
+
+    #[test]
+    fn test_stats_count_usage() {
+        let mut universe = Universe::new(UniverseConfig::with_bounds(100.0, 100.0, 50.0));
+        assert_eq!(universe.stats().avg_nodes_visited(), 0.0);
+
+        universe.stamp(&Stamp::explosion(Vec3::ZERO, 10.0, 1.0));
+        universe.schedule_stamp(Stamp::explosion(Vec3::ZERO, 5.0, 1.0), 0.5);
+        universe.step(1.0);
+        let _ = universe.query_point(Vec3::ZERO);
+        let _ = universe.query_volume(Vec3::ZERO, 15.0, QueryResolution::Fine);
+
+        let stats = universe.stats();
+        assert_eq!(stats.stamps_applied, 2);
+        assert_eq!(stats.queries_served, 2);
+        assert!(stats.avg_nodes_visited() >= 1.0);
+        assert!(stats.last_step_duration.is_some());
+        assert_eq!(stats.nodes_by_depth.iter().sum::<usize>(), stats.node_count);
+    }
+
     #[test]
     fn test_universe_foveated_observation() {
         let mut universe = Universe::new(UniverseConfig::with_bounds(200.0, 200.0, 50.0));
@@ -567,4 +924,63 @@ mod tests {
             noise_after
         );
     }
+    #[test]
+    fn test_scheduled_stamp_applied_on_time() {
+        let config = UniverseConfig::with_bounds(64.0, 64.0, 32.0);
+        let mut universe = Universe::new(config);
+        universe.schedule_stamp(Stamp::sonar_ping(Vec3::ZERO, 4.0, 1.0), 1.0);
+
+        universe.step(0.5);
+        assert_eq!(universe.query_point(Vec3::ZERO).get(Field::SonarReturn), 0.0);
+
+        universe.step(0.5);
+        assert!(universe.query_point(Vec3::ZERO).get(Field::SonarReturn) > 0.0);
+        assert!(universe.scheduler().is_empty());
+    }
+
+    #[test]
+    fn test_periodic_stamp_count() {
+        let config = UniverseConfig::with_bounds(64.0, 64.0, 32.0);
+        let mut universe = Universe::new(config);
+        let id = universe
+            .schedule_periodic(Stamp::sonar_ping(Vec3::ZERO, 4.0, 1.0), 1.0, Some(2))
+            .unwrap();
+        assert_eq!(universe.scheduler().pending()[0].id, id);
+
+        universe.step(1.0);
+        assert_eq!(universe.scheduler().pending()[0].remaining, Some(1));
+        universe.step(1.0);
+        assert!(universe.scheduler().is_empty());
+    }
+
+    #[test]
+    fn test_reset_clears_schedule() {
+        let mut universe = Universe::default();
+        let id = universe.schedule_stamp(Stamp::sonar_ping(Vec3::ZERO, 4.0, 1.0), 5.0);
+        universe.reset();
+
+        assert!(universe.scheduler().is_empty());
+        assert!(!universe.cancel_scheduled(id));
+    }
+    #[test]
+    fn test_frozen_region_blocks_propagation() {
+        let config = UniverseConfig::with_bounds(32.0, 32.0, 32.0);
+        let mut universe = Universe::new(config);
+        let land = Bounds::from_min_max(Vec3::splat(-16.0), Vec3::new(0.0, 16.0, 16.0));
+        let mut hot = FieldValues::new();
+        hot.set(Field::Temperature, 1000.0);
+        universe.set_point(Vec3::new(-0.5, 0.5, 0.5), hot);
+        universe.set_point(Vec3::new(0.5, 0.5, 0.5), hot);
+        universe.freeze_region(land, &[Field::Temperature]);
+
+        for _ in 0..5 {
+            universe.step(1.0);
+        }
+
+        let frozen = universe.query_point(Vec3::new(-0.5, 0.5, 0.5)).get(Field::Temperature);
+        let free = universe.query_point(Vec3::new(0.5, 0.5, 0.5)).get(Field::Temperature);
+        assert_eq!(frozen, 1000.0);
+        assert!(free < 1000.0);
+        assert_eq!(universe.frozen_regions().len(), 1);
+    }
 }
@@ -0,0 +1,74 @@
+//! Contact selection throughput, against the target of 10k agent
+//! observations per second.
+//!
+//! Criterion reports throughput in observations per second: one element is
+//! the contact list of one agent.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use glam::Vec2;
+use tidebreak_core::arena::Arena;
+use tidebreak_core::entity::{
+    EntityId, EntityInner, EntityTag, ShipComponents, Track, TrackQuality,
+};
+use tidebreak_core::interest::{ContactSortKey, InterestManager};
+
+const AGENTS: u64 = 100;
+const TRACKS: u64 = 200;
+const K: usize = 16;
+
+/// Agents on a line, each tracking every target of a shared field.
+fn arena() -> (Arena, Vec<EntityId>) {
+    let mut arena = Arena::new();
+    let agents = (0..AGENTS)
+        .map(|i| {
+            #[allow(clippy::cast_precision_loss)]
+            let mut ship = ShipComponents::at_position(Vec2::new(i as f32 * 250.0, 0.0), 0.0);
+            ship.sensor.track_table = (0..TRACKS)
+                .map(|t| {
+                    #[allow(clippy::cast_precision_loss)]
+                    let position = Vec2::new((t * 97 % 25_000) as f32, (t * 53 % 8000) as f32);
+                    let target = EntityId::new(10_000 + t);
+                    let mut track = Track::new(target, position, TrackQuality::Coarse);
+                    track.velocity = Some(Vec2::new(5.0, -3.0));
+                    track
+                })
+                .collect();
+            arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+        })
+        .collect();
+    (arena, agents)
+}
+
+fn bench_observations(c: &mut Criterion) {
+    let (mut arena, agents) = arena();
+    let mut group = c.benchmark_group("interest");
+    group.throughput(Throughput::Elements(AGENTS));
+
+    for key in [ContactSortKey::Distance, ContactSortKey::Threat] {
+        let mut interest = InterestManager::new(key);
+        group.bench_function(format!("{key:?}/cached"), |b| {
+            b.iter(|| {
+                for &agent in &agents {
+                    black_box(interest.contacts_for(&arena, agent, K));
+                }
+            });
+        });
+
+        // Every track table changes between observations, as after a tick
+        group.bench_function(format!("{key:?}/updated"), |b| {
+            b.iter(|| {
+                for &agent in &agents {
+                    let ship = arena.get_mut(agent).and_then(|e| e.as_ship_mut()).unwrap();
+                    ship.sensor.touch_tracks();
+                }
+                for &agent in &agents {
+                    black_box(interest.contacts_for(&arena, agent, K));
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_observations);
+criterion_main!(benches);
@@ -5,6 +5,7 @@
 //! - Spatial indexing for proximity queries
 //! - Entity lifecycle management (spawn/despawn)
 //! - Trace ID generation for causal chain tracking
+//! - Delta sync between arenas ([`Arena::diff`] / [`Arena::apply_delta`])
 //!
 //! # Architecture
 //!
@@ -58,12 +59,31 @@
 //! ```
 
 use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::hash::{Hash, Hasher};
 
 use glam::Vec2;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-
-use crate::entity::{Entity, EntityId, EntityInner, EntityTag};
+use thiserror::Error;
+
+use crate::attachment::{Attachment, AttachmentBook};
+use crate::comms::IntentChannel;
+use crate::currents::CurrentField;
+use crate::environment::{Environment, OccupancyField};
+use crate::entity::{
+    AirWing, AttributeValue, CombatState, ControllerId, Entity, EntityId, EntityInner,
+    EntityMetadata, EntityTag, InventoryState, MineState, PhysicsState, SensorState,
+    SignatureState, SubmarineState, TeamId, TransformState,
+};
+use crate::logistics::SupplyLedger;
+use crate::geofence::GeofenceBook;
+use crate::orders::{OrderBook, RulesOfEngagement};
 use crate::output::TraceId;
+use crate::steering::SteeringAssistBook;
+use crate::proximity::ProximityBook;
+use crate::trigger::TriggerBook;
+use crate::wind::Wind;
 
 // =============================================================================
 // Spatial Index
@@ -92,6 +112,12 @@ use crate::output::TraceId;
 pub struct SpatialIndex {
     /// Entity positions indexed by ID.
     positions: HashMap<EntityId, Vec2>,
+    /// Entity tags indexed by ID, for tag-filtered queries.
+    #[serde(default)]
+    tags: HashMap<EntityId, EntityTag>,
+    /// Hull radii of entities with a non-zero radius.
+    #[serde(default)]
+    radii: HashMap<EntityId, f32>,
 }
 
 impl SpatialIndex {
@@ -100,6 +126,8 @@ impl SpatialIndex {
     pub fn new() -> Self {
         Self {
             positions: HashMap::new(),
+            tags: HashMap::new(),
+            radii: HashMap::new(),
         }
     }
 
@@ -113,6 +141,19 @@ impl SpatialIndex {
         self.positions.insert(id, pos);
     }
 
+    /// Inserts or updates an entity's position and records its tag, so it
+    /// can be found by tag-filtered queries.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The entity ID
+    /// * `pos` - The entity's position
+    /// * `tag` - The entity's tag
+    pub fn insert_tagged(&mut self, id: EntityId, pos: Vec2, tag: EntityTag) {
+        self.positions.insert(id, pos);
+        self.tags.insert(id, tag);
+    }
+
     /// Removes an entity from the spatial index.
     ///
     /// # Arguments
@@ -120,6 +161,24 @@ impl SpatialIndex {
     /// * `id` - The entity ID to remove
     pub fn remove(&mut self, id: EntityId) {
         self.positions.remove(&id);
+        self.tags.remove(&id);
+        self.radii.remove(&id);
+    }
+
+    /// Sets the hull radius of an entity used by segment queries.
+    ///
+    /// Entities without a radius are treated as points.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The entity ID
+    /// * `radius` - The hull radius (0 or less removes it)
+    pub fn set_radius(&mut self, id: EntityId, radius: f32) {
+        if radius > 0.0 {
+            self.radii.insert(id, radius);
+        } else {
+            self.radii.remove(&id);
+        }
     }
 
     /// Returns the position of an entity, if known.
@@ -160,6 +219,88 @@ impl SpatialIndex {
         results
     }
 
+    /// Queries for the `k` entities closest to a point.
+    ///
+    /// With `filter_tag`, only entities inserted with that tag (see
+    /// [`insert_tagged`](Self::insert_tagged)) are considered.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - The query point
+    /// * `k` - Maximum number of entities to return
+    /// * `filter_tag` - Only return entities with this tag, if given
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` entity IDs sorted by distance, nearest first. Ties are
+    /// broken by entity ID so the order is deterministic.
+    #[must_use]
+    pub fn query_knearest(
+        &self,
+        center: Vec2,
+        k: usize,
+        filter_tag: Option<EntityTag>,
+    ) -> Vec<EntityId> {
+        let mut candidates: Vec<(f32, EntityId)> = self
+            .positions
+            .iter()
+            .filter(|(id, _)| filter_tag.is_none_or(|tag| self.tags.get(id) == Some(&tag)))
+            .map(|(id, pos)| (center.distance_squared(*pos), *id))
+            .collect();
+
+        // Partial selection first, then sort only the k survivors
+        let by_distance =
+            |a: &(f32, EntityId), b: &(f32, EntityId)| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1));
+        if k < candidates.len() {
+            candidates.select_nth_unstable_by(k, by_distance);
+            candidates.truncate(k);
+        }
+        candidates.sort_unstable_by(by_distance);
+        candidates.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Queries for entities intersecting a swept segment.
+    ///
+    /// An entity is hit when its hull (a circle of its radius, see
+    /// [`set_radius`](Self::set_radius)) overlaps the segment from `start` to
+    /// `end` swept with the given `width`. Used for hit-scan weapons and
+    /// line-of-sight checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Segment start point
+    /// * `end` - Segment end point
+    /// * `width` - Full width of the swept segment (0 for a ray)
+    ///
+    /// # Returns
+    ///
+    /// Entity IDs ordered by how far along the segment they are hit
+    /// (closest to `start` first), ties broken by ID.
+    #[must_use]
+    pub fn query_segment(&self, start: Vec2, end: Vec2, width: f32) -> Vec<EntityId> {
+        let dir = end - start;
+        let len_sq = dir.length_squared();
+        let half_width = width * 0.5;
+
+        let mut hits: Vec<(f32, EntityId)> = self
+            .positions
+            .iter()
+            .filter_map(|(id, pos)| {
+                let t = if len_sq > 0.0 {
+                    ((*pos - start).dot(dir) / len_sq).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let reach = half_width + self.radii.get(id).copied().unwrap_or(0.0);
+                let closest = start + dir * t;
+                (pos.distance_squared(closest) <= reach * reach).then_some((t, *id))
+            })
+            .collect();
+
+        hits.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        hits.into_iter().map(|(_, id)| id).collect()
+    }
+
     /// Returns the number of entities in the spatial index.
     #[must_use]
     pub fn len(&self) -> usize {
@@ -187,10 +328,100 @@ impl SpatialIndex {
     }
 }
 
+// =============================================================================
+// World Bounds
+// =============================================================================
+
+/// What happens to an entity that crosses the world bounds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BoundaryPolicy {
+    /// Hold the entity at the edge and zero the crossing velocity component.
+    #[default]
+    Clamp,
+    /// Reflect the entity off the edge and reverse the crossing velocity
+    /// component.
+    Bounce,
+    /// Re-enter from the opposite edge (toroidal world).
+    Wrap,
+    /// Remove the entity and record an `EntityOutOfBounds` event.
+    Despawn,
+}
+
+/// Axis-aligned play area, enforced on moving entities by the
+/// `PhysicsResolver`.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::{BoundaryPolicy, WorldBounds};
+/// use glam::Vec2;
+///
+/// let bounds = WorldBounds::centered(200.0, 100.0, BoundaryPolicy::Wrap);
+/// assert!(bounds.contains(Vec2::new(100.0, -50.0)));
+/// assert_eq!(bounds.edge_distances(Vec2::ZERO), [100.0, 100.0, 50.0, 50.0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorldBounds {
+    /// Minimum corner.
+    pub min: Vec2,
+    /// Maximum corner.
+    pub max: Vec2,
+    /// Behavior at the edges.
+    pub policy: BoundaryPolicy,
+}
+
+impl WorldBounds {
+    /// Creates bounds from min/max corners.
+    #[must_use]
+    pub const fn new(min: Vec2, max: Vec2, policy: BoundaryPolicy) -> Self {
+        Self { min, max, policy }
+    }
+
+    /// Creates bounds of the given size centered at the origin.
+    #[must_use]
+    pub fn centered(width: f32, height: f32, policy: BoundaryPolicy) -> Self {
+        let half = Vec2::new(width, height) / 2.0;
+        Self::new(-half, half, policy)
+    }
+
+    /// Width and height of the play area.
+    #[must_use]
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// Returns true if `position` is inside the bounds (edges included).
+    #[must_use]
+    pub fn contains(&self, position: Vec2) -> bool {
+        position.cmpge(self.min).all() && position.cmple(self.max).all()
+    }
+
+    /// Distances from `position` to the `[min_x, max_x, min_y, max_y]` edges.
+    ///
+    /// Negative when `position` is beyond that edge.
+    #[must_use]
+    pub fn edge_distances(&self, position: Vec2) -> [f32; 4] {
+        [
+            position.x - self.min.x,
+            self.max.x - position.x,
+            position.y - self.min.y,
+            self.max.y - position.y,
+        ]
+    }
+}
+
 // =============================================================================
 // Arena
 // =============================================================================
 
+/// Errors produced when spawning with an explicit ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum SpawnError {
+    /// An entity with this ID already exists.
+    #[error("entity ID {0} is already in use")]
+    IdInUse(EntityId),
+}
+
 /// Combat arena containing all simulation entities.
 ///
 /// The Arena is the central container for a combat simulation. It manages:
@@ -253,9 +484,87 @@ pub struct Arena {
     tick: u64,
     /// Monotonically increasing trace ID counter.
     next_trace_id: u64,
+    /// Intent broadcast channel between friendly entities.
+    ///
+    /// Use `comms()` or `comms_mut()` to access the channel.
+    #[serde(default)]
+    comms: IntentChannel,
+    /// Play area enforced by the physics resolver, if any.
+    #[serde(default)]
+    bounds: Option<WorldBounds>,
+    /// Pending cargo transfers between entities.
+    ///
+    /// Use `logistics()` or `logistics_mut()` to access the ledger.
+    #[serde(default)]
+    logistics: SupplyLedger,
+    /// Namespace stamped on automatically assigned IDs.
+    ///
+    /// Use `id_namespace()` and `set_id_namespace()` to access it.
+    #[serde(default)]
+    id_namespace: u16,
+    /// Sea currents drifting entities in the physics resolver, if any.
+    #[serde(default)]
+    currents: Option<CurrentField>,
+    /// Surface wind drifting smoke and entities with windage, if any.
+    #[serde(default)]
+    wind: Option<Wind>,
+    /// Fraction of the wind speed each wind-blown entity drifts at.
+    ///
+    /// Use `windage()` and `set_windage()` to access it.
+    #[serde(default)]
+    windage: BTreeMap<EntityId, f32>,
+    /// Time of day, sea state and smoke limiting visual detection.
+    ///
+    /// Use `environment()` or `environment_mut()` to access it.
+    #[serde(default)]
+    environment: Environment,
+    /// Standing orders from commanders to subordinates.
+    ///
+    /// Use `orders()` or `orders_mut()` to access the book.
+    #[serde(default)]
+    orders: OrderBook,
+    /// Keep-out areas enforced by the safety resolver.
+    ///
+    /// Use `geofences()` or `geofences_mut()` to access the book.
+    #[serde(default)]
+    geofences: GeofenceBook,
+    /// Collision-avoidance assists applied by the physics resolver.
+    ///
+    /// Use `steering_assist()` or `steering_assist_mut()` to access the book.
+    #[serde(default)]
+    steering: SteeringAssistBook,
+    /// Parent-child links of composite platforms.
+    ///
+    /// Use `attachments()`, `attach()` and `detach()` to access the book.
+    #[serde(default)]
+    attachments: AttachmentBook,
+    /// Tick at which each entity spawned with a time-to-live is despawned.
+    ///
+    /// Use `spawn_with_ttl()`, `set_ttl()` and `expiry()` to access it.
+    #[serde(default)]
+    lifetimes: BTreeMap<EntityId, u64>,
+    /// Scenario triggers and when they fired.
+    ///
+    /// Use `triggers()` or `triggers_mut()` to access the book.
+    #[serde(default)]
+    triggers: TriggerBook,
+    /// Rules of engagement of teams not weapons free.
+    ///
+    /// Use `rules_of_engagement()` and `set_rules_of_engagement()` to access them.
+    #[serde(default)]
+    roe: BTreeMap<TeamId, RulesOfEngagement>,
+    /// Targets within range of each proximity watch.
+    ///
+    /// Use `proximity()` or `proximity_mut()` to access the book.
+    #[serde(default)]
+    proximity: ProximityBook,
 }
 
 impl Arena {
+    /// Candidates drawn by [`find_spawn_position`](Self::find_spawn_position)
+    /// before giving up.
+    pub const SPAWN_ATTEMPTS: u32 = 64;
+
     /// Creates a new empty arena.
     ///
     /// The arena starts at tick 0 with no entities.
@@ -267,6 +576,22 @@ impl Arena {
             spatial: SpatialIndex::new(),
             tick: 0,
             next_trace_id: 0,
+            comms: IntentChannel::default(),
+            bounds: None,
+            logistics: SupplyLedger::default(),
+            id_namespace: 0,
+            currents: None,
+            wind: None,
+            windage: BTreeMap::new(),
+            environment: Environment::default(),
+            orders: OrderBook::default(),
+            geofences: GeofenceBook::default(),
+            steering: SteeringAssistBook::default(),
+            attachments: AttachmentBook::default(),
+            lifetimes: BTreeMap::new(),
+            triggers: TriggerBook::default(),
+            roe: BTreeMap::new(),
+            proximity: ProximityBook::default(),
         }
     }
 
@@ -300,23 +625,205 @@ impl Arena {
     /// assert!(arena.get(id).is_some());
     /// ```
     pub fn spawn(&mut self, tag: EntityTag, inner: EntityInner) -> EntityId {
-        let id = EntityId::new(self.next_id);
+        // Skip IDs already taken by `spawn_with_id`
+        let mut id = EntityId::namespaced(self.id_namespace, self.next_id);
+        while self.entities.contains_key(&id) {
+            self.next_id += 1;
+            id = EntityId::namespaced(self.id_namespace, self.next_id);
+        }
         self.next_id += 1;
+        self.insert(Entity::new(id, tag, inner));
+        id
+    }
+
+    /// Spawns a new entity with an explicit ID.
+    ///
+    /// Used to merge scenario content generated elsewhere (typically in
+    /// another ID namespace, see [`set_id_namespace`](Self::set_id_namespace))
+    /// and for client-side prediction, where the ID is chosen before the
+    /// authoritative arena sees the entity.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpawnError::IdInUse`] if an entity with `id` already exists.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::arena::{Arena, SpawnError};
+    /// use tidebreak_core::entity::{EntityId, EntityTag, EntityInner, ShipComponents};
+    ///
+    /// let mut arena = Arena::new();
+    /// let id = EntityId::namespaced(2, 0);
+    /// let ship = || EntityInner::Ship(ShipComponents::default());
+    ///
+    /// assert_eq!(arena.spawn_with_id(id, EntityTag::Ship, ship()), Ok(id));
+    /// assert_eq!(
+    ///     arena.spawn_with_id(id, EntityTag::Ship, ship()),
+    ///     Err(SpawnError::IdInUse(id))
+    /// );
+    /// ```
+    pub fn spawn_with_id(
+        &mut self,
+        id: EntityId,
+        tag: EntityTag,
+        inner: EntityInner,
+    ) -> Result<EntityId, SpawnError> {
+        if self.entities.contains_key(&id) {
+            return Err(SpawnError::IdInUse(id));
+        }
+        self.insert(Entity::new(id, tag, inner));
+        Ok(id)
+    }
 
-        let entity = Entity::new(id, tag, inner);
+    /// Spawns a new entity that is despawned `ticks` ticks from now.
+    ///
+    /// Meant for short-lived entities such as flares, smoke markers and
+    /// debris. The [`LifetimeResolver`](crate::resolver::LifetimeResolver)
+    /// removes the entity while resolving the tick that reaches its
+    /// [`expiry`](Self::expiry), so it is gone from the state at that tick.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::arena::Arena;
+    /// use tidebreak_core::entity::{EntityTag, EntityInner, ProjectileComponents};
+    ///
+    /// let mut arena = Arena::new();
+    /// let flare = arena.spawn_with_ttl(
+    ///     EntityTag::Projectile,
+    ///     EntityInner::Projectile(ProjectileComponents::default()),
+    ///     30,
+    /// );
+    /// assert_eq!(arena.expiry(flare), Some(30));
+    /// ```
+    pub fn spawn_with_ttl(&mut self, tag: EntityTag, inner: EntityInner, ticks: u64) -> EntityId {
+        let id = self.spawn(tag, inner);
+        self.set_ttl(id, ticks);
+        id
+    }
+
+    /// Finds a clear position to spawn a hull within `radius` of `center`.
+    ///
+    /// Candidates are drawn uniformly over the disc from `rng`. A candidate
+    /// is clear if it lies inside the world bounds, off solid ground (see
+    /// [`OccupancyField::BLOCKING`]), and more than `min_separation` from
+    /// every ship and platform. Returns `None` if none of
+    /// [`SPAWN_ATTEMPTS`](Self::SPAWN_ATTEMPTS) candidates is clear.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use glam::Vec2;
+    /// use rand::SeedableRng;
+    /// use rand_chacha::ChaCha8Rng;
+    /// use tidebreak_core::arena::Arena;
+    /// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+    ///
+    /// let mut arena = Arena::new();
+    /// arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+    /// let mut rng = ChaCha8Rng::seed_from_u64(7);
+    ///
+    /// let position = arena.find_spawn_position(Vec2::ZERO, 500.0, 100.0, &mut rng).unwrap();
+    /// assert!(position.length() >= 100.0 && position.length() <= 500.0);
+    /// // No room next to the ship
+    /// assert_eq!(arena.find_spawn_position(Vec2::ZERO, 50.0, 100.0, &mut rng), None);
+    /// ```
+    pub fn find_spawn_position(
+        &self,
+        center: Vec2,
+        radius: f32,
+        min_separation: f32,
+        rng: &mut impl Rng,
+    ) -> Option<Vec2> {
+        let clear = |position: Vec2| {
+            let inside = self.bounds.is_none_or(|b| b.contains(position));
+            let solid = self
+                .environment
+                .occupancy
+                .as_ref()
+                .is_some_and(|o| o.occupancy_at(position) >= OccupancyField::BLOCKING);
+            let crowded = self
+                .spatial
+                .query_radius(position, min_separation)
+                .into_iter()
+                .any(|id| self.get(id).is_some_and(|e| e.is_ship() || e.is_platform()));
+            inside && !solid && !crowded
+        };
+        (0..Self::SPAWN_ATTEMPTS).find_map(|_| {
+            // The square root spreads candidates evenly over the area
+            let distance = radius * rng.gen::<f32>().sqrt();
+            let direction = Vec2::from_angle(std::f32::consts::TAU * rng.gen::<f32>());
+            let candidate = center + direction * distance;
+            clear(candidate).then_some(candidate)
+        })
+    }
+
+    /// Schedules an existing entity to be despawned `ticks` ticks from now,
+    /// replacing any earlier schedule.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the entity does not exist.
+    pub fn set_ttl(&mut self, id: EntityId, ticks: u64) -> bool {
+        if !self.entities.contains_key(&id) {
+            return false;
+        }
+        self.lifetimes.insert(id, self.tick.saturating_add(ticks));
+        true
+    }
+
+    /// Cancels an entity's scheduled despawn, returning its expiry tick.
+    pub fn clear_ttl(&mut self, id: EntityId) -> Option<u64> {
+        self.lifetimes.remove(&id)
+    }
+
+    /// Returns the tick at which an entity is scheduled to be despawned.
+    #[must_use]
+    pub fn expiry(&self, id: EntityId) -> Option<u64> {
+        self.lifetimes.get(&id).copied()
+    }
+
+    /// Returns the scheduled despawns as (entity, expiry tick), in ID order.
+    pub fn lifetimes(&self) -> impl Iterator<Item = (EntityId, u64)> + '_ {
+        self.lifetimes.iter().map(|(id, tick)| (*id, *tick))
+    }
+
+    /// Returns the namespace stamped on automatically assigned IDs.
+    #[must_use]
+    pub const fn id_namespace(&self) -> u16 {
+        self.id_namespace
+    }
+
+    /// Sets the namespace stamped on automatically assigned IDs.
+    ///
+    /// Give each producer of scenario content its own namespace so their
+    /// arenas can later be merged without ID collisions. Namespace 0 (the
+    /// default) yields plain sequential IDs.
+    pub fn set_id_namespace(&mut self, namespace: u16) {
+        self.id_namespace = namespace;
+    }
+
+    /// Adds a spawned entity to the entity map and spatial index.
+    fn insert(&mut self, entity: Entity) {
+        let id = entity.id();
+        let tag = entity.tag();
 
         // Update spatial index with entity position
         if let Some(pos) = Self::get_entity_position(&entity) {
-            self.spatial.insert(id, pos);
+            self.spatial.insert_tagged(id, pos, tag);
+            self.spatial
+                .set_radius(id, Self::get_entity_radius(&entity));
         }
 
         self.entities.insert(id, entity);
-        id
     }
 
     /// Despawns an entity from the arena.
     ///
     /// The entity is removed from both the entity map and the spatial index.
+    /// Entities attached to it with `cascade` are despawned too; others
+    /// are released where they are.
     ///
     /// # Arguments
     ///
@@ -327,6 +834,19 @@ impl Arena {
     /// The removed entity, if it existed.
     pub fn despawn(&mut self, id: EntityId) -> Option<Entity> {
         self.spatial.remove(id);
+        self.comms.remove_sender(id);
+        self.logistics.remove_entity(id);
+        self.orders.remove_entity(id);
+        self.geofences.remove_entity(id);
+        self.attachments.remove(id);
+        self.lifetimes.remove(&id);
+        self.windage.remove(&id);
+        let children: Vec<EntityId> = self.attachments.children(id).collect();
+        for child in children {
+            if self.attachments.remove(child).is_some_and(|a| a.cascade) {
+                self.despawn(child);
+            }
+        }
         self.entities.remove(&id)
     }
 
@@ -390,6 +910,388 @@ impl Arena {
         self.entities.is_empty()
     }
 
+    /// Assigns (or clears) the team of an entity.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity exists.
+    pub fn set_team(&mut self, id: EntityId, team: Option<TeamId>) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(entity) => {
+                entity.set_team(team);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Hands control of an entity to `controller` (or, with `None`, to no
+    /// one).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity exists.
+    pub fn set_controller(&mut self, id: EntityId, controller: Option<ControllerId>) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(entity) => {
+                entity.set_controller(controller);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces an entity's cosmetic metadata.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity exists.
+    pub fn set_metadata(&mut self, id: EntityId, metadata: EntityMetadata) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(entity) => {
+                entity.set_metadata(metadata);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if `controller` may command the entity.
+    ///
+    /// A caller acting as a controller (`Some`) may only command entities
+    /// it controls; a caller acting as no one (`None`), such as a script
+    /// driving the whole scenario, may command any entity. Missing entities
+    /// cannot be commanded.
+    #[must_use]
+    pub fn can_command(&self, controller: Option<ControllerId>, id: EntityId) -> bool {
+        self.entities
+            .get(&id)
+            .is_some_and(|entity| controller.is_none() || entity.controller() == controller)
+    }
+
+    /// Returns the IDs of all entities under `controller`, sorted by ID.
+    pub fn controlled_by(&self, controller: ControllerId) -> impl Iterator<Item = EntityId> + '_ {
+        self.entities
+            .values()
+            .filter(move |e| e.controller() == Some(controller))
+            .map(Entity::id)
+    }
+
+    /// Sets (or with `None`, removes) an attribute of an entity.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity exists.
+    pub fn set_attribute(
+        &mut self,
+        id: EntityId,
+        key: impl Into<String>,
+        value: Option<AttributeValue>,
+    ) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(entity) => {
+                let key = key.into();
+                match value {
+                    Some(value) => entity.set_attribute(key, value),
+                    None => entity.remove_attribute(&key),
+                };
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds a label to an entity.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity exists.
+    pub fn add_label(&mut self, id: EntityId, label: impl Into<String>) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(entity) => {
+                entity.add_label(label);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a label from an entity.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entity exists and carried the label.
+    pub fn remove_label(&mut self, id: EntityId, label: &str) -> bool {
+        self.entities
+            .get_mut(&id)
+            .is_some_and(|entity| entity.remove_label(label))
+    }
+
+    /// Returns the IDs of all entities carrying a label, sorted by ID.
+    pub fn query_by_label<'a>(&'a self, label: &'a str) -> impl Iterator<Item = EntityId> + 'a {
+        self.entities
+            .values()
+            .filter(move |e| e.has_label(label))
+            .map(Entity::id)
+    }
+
+    /// Returns a reference to the intent channel.
+    #[must_use]
+    pub const fn comms(&self) -> &IntentChannel {
+        &self.comms
+    }
+
+    /// Returns a mutable reference to the intent channel.
+    #[must_use]
+    pub fn comms_mut(&mut self) -> &mut IntentChannel {
+        &mut self.comms
+    }
+
+    /// Returns a reference to the supply ledger.
+    #[must_use]
+    pub const fn logistics(&self) -> &SupplyLedger {
+        &self.logistics
+    }
+
+    /// Returns a mutable reference to the supply ledger.
+    #[must_use]
+    pub fn logistics_mut(&mut self) -> &mut SupplyLedger {
+        &mut self.logistics
+    }
+
+    /// Returns the world bounds, if any.
+    #[must_use]
+    pub const fn bounds(&self) -> Option<&WorldBounds> {
+        self.bounds.as_ref()
+    }
+
+    /// Sets (or clears) the world bounds.
+    ///
+    /// Entities already outside are handled the next time they move.
+    pub fn set_bounds(&mut self, bounds: Option<WorldBounds>) {
+        self.bounds = bounds;
+    }
+
+    /// Returns the sea currents, if any.
+    #[must_use]
+    pub const fn currents(&self) -> Option<&CurrentField> {
+        self.currents.as_ref()
+    }
+
+    /// Sets (or clears) the sea currents that drift entities each tick.
+    pub fn set_currents(&mut self, currents: Option<CurrentField>) {
+        self.currents = currents;
+    }
+
+    /// Returns the surface wind, if any.
+    #[must_use]
+    pub const fn wind(&self) -> Option<&Wind> {
+        self.wind.as_ref()
+    }
+
+    /// Sets (or clears) the surface wind that drifts smoke and entities
+    /// with windage each tick.
+    pub fn set_wind(&mut self, wind: Option<Wind>) {
+        self.wind = wind;
+    }
+
+    /// Returns the fraction of the wind speed an entity drifts at; 0.0 for
+    /// entities the wind does not move.
+    #[must_use]
+    pub fn windage(&self, id: EntityId) -> f32 {
+        self.windage.get(&id).copied().unwrap_or(0.0)
+    }
+
+    /// Sets the fraction of the wind speed an entity drifts at (see
+    /// [`Wind`] for typical values). Zero stops the wind moving it.
+    ///
+    /// # Returns
+    ///
+    /// `false`, changing nothing, if the entity does not exist or the
+    /// coefficient is negative or not finite.
+    pub fn set_windage(&mut self, id: EntityId, windage: f32) -> bool {
+        if !(self.entities.contains_key(&id) && windage.is_finite() && windage >= 0.0) {
+            return false;
+        }
+        if windage == 0.0 {
+            self.windage.remove(&id);
+        } else {
+            self.windage.insert(id, windage);
+        }
+        true
+    }
+
+    /// Returns the visibility conditions.
+    #[must_use]
+    pub const fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// Returns mutable visibility conditions.
+    #[must_use]
+    pub fn environment_mut(&mut self) -> &mut Environment {
+        &mut self.environment
+    }
+
+    /// Returns the standing orders.
+    #[must_use]
+    pub const fn orders(&self) -> &OrderBook {
+        &self.orders
+    }
+
+    /// Returns mutable standing orders.
+    #[must_use]
+    pub fn orders_mut(&mut self) -> &mut OrderBook {
+        &mut self.orders
+    }
+
+    /// Returns the geofences.
+    #[must_use]
+    pub const fn geofences(&self) -> &GeofenceBook {
+        &self.geofences
+    }
+
+    /// Returns mutable geofences.
+    #[must_use]
+    pub fn geofences_mut(&mut self) -> &mut GeofenceBook {
+        &mut self.geofences
+    }
+
+    /// Returns the scenario triggers.
+    #[must_use]
+    pub const fn triggers(&self) -> &TriggerBook {
+        &self.triggers
+    }
+
+    /// Returns mutable scenario triggers.
+    #[must_use]
+    pub fn triggers_mut(&mut self) -> &mut TriggerBook {
+        &mut self.triggers
+    }
+
+    /// Returns the targets within range of each proximity watch.
+    #[must_use]
+    pub const fn proximity(&self) -> &ProximityBook {
+        &self.proximity
+    }
+
+    /// Returns the mutable targets within range of each proximity watch.
+    #[must_use]
+    pub fn proximity_mut(&mut self) -> &mut ProximityBook {
+        &mut self.proximity
+    }
+
+    /// Returns a team's rules of engagement (weapons free unless set).
+    #[must_use]
+    pub fn rules_of_engagement(&self, team: TeamId) -> RulesOfEngagement {
+        self.roe.get(&team).copied().unwrap_or_default()
+    }
+
+    /// Sets a team's rules of engagement.
+    pub fn set_rules_of_engagement(&mut self, team: TeamId, roe: RulesOfEngagement) {
+        if roe == RulesOfEngagement::default() {
+            self.roe.remove(&team);
+        } else {
+            self.roe.insert(team, roe);
+        }
+    }
+
+    /// Returns `true` if the entity's team allows it to fire.
+    #[must_use]
+    pub fn may_fire(&self, id: EntityId) -> bool {
+        self.get(id)
+            .and_then(Entity::team)
+            .is_none_or(|team| self.rules_of_engagement(team) != RulesOfEngagement::HoldFire)
+    }
+
+    /// Returns the attachments.
+    #[must_use]
+    pub const fn attachments(&self) -> &AttachmentBook {
+        &self.attachments
+    }
+
+    /// Attaches an entity to a parent (see [`crate::attachment`]) and moves
+    /// it onto the parent at once, replacing any earlier attachment.
+    ///
+    /// # Returns
+    ///
+    /// `true` if both entities exist and the link forms no cycle.
+    pub fn attach(&mut self, child: EntityId, attachment: Attachment) -> bool {
+        if !self.entities.contains_key(&child)
+            || !self.entities.contains_key(&attachment.parent)
+            || !self.attachments.insert(child, attachment)
+        {
+            return false;
+        }
+        self.sync_attachments();
+        true
+    }
+
+    /// Releases an attached entity where it is, returning its attachment.
+    pub fn detach(&mut self, child: EntityId) -> Option<Attachment> {
+        self.attachments.remove(child)
+    }
+
+    /// Moves every attached entity onto its parent: to the parent's pose
+    /// plus its offset, at the velocity of the root of its chain.
+    ///
+    /// The physics resolver calls this every tick after moving the other
+    /// entities.
+    pub fn sync_attachments(&mut self) {
+        let children: Vec<EntityId> = self.attachments.iter().map(|(id, _)| id).collect();
+        for child in children {
+            let Some((pose, velocity)) = self.world_pose(child) else {
+                continue;
+            };
+            if let Some(entity) = self.entities.get_mut(&child) {
+                let (transform, physics) = motion_mut(entity);
+                transform.position = pose.position;
+                transform.heading = pose.heading;
+                if let Some(physics) = physics {
+                    physics.velocity = velocity;
+                }
+            }
+            self.update_spatial(child);
+        }
+    }
+
+    /// Returns the transform an entity would have at the end of its chain of
+    /// attachments, and the velocity of the chain's root.
+    fn world_pose(&self, id: EntityId) -> Option<(TransformState, Vec2)> {
+        let entity = self.entities.get(&id)?;
+        let (transform, velocity) = match entity.inner() {
+            EntityInner::Ship(c) => (c.transform, c.physics.velocity),
+            EntityInner::Platform(c) => (c.transform, Vec2::ZERO),
+            EntityInner::Projectile(c) => (c.transform, c.physics.velocity),
+            EntityInner::Squadron(c) => (c.transform, c.physics.velocity),
+        };
+        let Some(attachment) = self.attachments.get(id) else {
+            return Some((transform, velocity));
+        };
+        let (parent, velocity) = self.world_pose(attachment.parent)?;
+        let (position, heading) = attachment.pose(&parent);
+        Some((
+            TransformState {
+                position,
+                heading,
+                ..transform
+            },
+            velocity,
+        ))
+    }
+
+    /// Returns the steering assists.
+    #[must_use]
+    pub const fn steering_assist(&self) -> &SteeringAssistBook {
+        &self.steering
+    }
+
+    /// Returns mutable steering assists.
+    #[must_use]
+    pub fn steering_assist_mut(&mut self) -> &mut SteeringAssistBook {
+        &mut self.steering
+    }
+
     /// Returns a reference to the spatial index.
     #[must_use]
     pub fn spatial(&self) -> &SpatialIndex {
@@ -420,38 +1322,723 @@ impl Arena {
     pub fn update_spatial(&mut self, id: EntityId) {
         if let Some(entity) = self.entities.get(&id) {
             if let Some(pos) = Self::get_entity_position(entity) {
-                self.spatial.insert(id, pos);
+                self.spatial.insert_tagged(id, pos, entity.tag());
+                self.spatial.set_radius(id, Self::get_entity_radius(entity));
+            }
+        }
+    }
+
+    /// Returns a deterministic hash of the full simulation state.
+    ///
+    /// Covers the tick, ID counters and namespace, every entity (in ID order), the intent
+    /// channel, the supply ledger, the world bounds, the currents, the wind and windage, the
+    /// environment, the standing orders, the geofences, the steering assists, the attachments,
+    /// the scheduled despawns, the triggers, the rules of engagement and the targets in
+    /// range of proximity watches. The spatial index is derived from entity positions and
+    /// is not hashed separately. Two arenas with equal hashes are considered identical for
+    /// replay verification.
+    ///
+    /// The hash is 64-bit FNV-1a over little-endian integers and the JSON encoding of each
+    /// component, so it is the same on every platform and toolchain; lockstep peers and
+    /// replay files compare it across machines.
+    #[must_use]
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::default();
+        self.tick.hash(&mut hasher);
+        self.next_id.hash(&mut hasher);
+        self.next_trace_id.hash(&mut hasher);
+        self.id_namespace.hash(&mut hasher);
+
+        // Components are hashed through their JSON encoding: serde_json
+        // writes floats in their shortest exact form and BTreeMaps in key
+        // order, and its output does not depend on the toolchain, unlike
+        // derived `Debug` output.
+        let mut writer = HashWriter(&mut hasher);
+        for entity in self.entities.values() {
+            let _ = serde_json::to_writer(&mut writer, entity);
+        }
+        let _ = serde_json::to_writer(&mut writer, &self.comms);
+        let _ = serde_json::to_writer(&mut writer, &self.logistics);
+        if let Some(bounds) = &self.bounds {
+            let _ = serde_json::to_writer(&mut writer, bounds);
+        }
+        if let Some(currents) = &self.currents {
+            let _ = serde_json::to_writer(&mut writer, currents);
+        }
+        if let Some(wind) = &self.wind {
+            let _ = serde_json::to_writer(&mut writer, wind);
+        }
+        if !self.windage.is_empty() {
+            let _ = serde_json::to_writer(&mut writer, &self.windage);
+        }
+        if self.environment != Environment::default() {
+            let _ = serde_json::to_writer(&mut writer, &self.environment);
+        }
+        if !self.orders.is_empty() {
+            let _ = serde_json::to_writer(&mut writer, &self.orders);
+        }
+        if !self.geofences.is_empty() {
+            let _ = serde_json::to_writer(&mut writer, &self.geofences);
+        }
+        if !self.steering.is_empty() {
+            let _ = serde_json::to_writer(&mut writer, &self.steering);
+        }
+        if !self.attachments.is_empty() {
+            let _ = serde_json::to_writer(&mut writer, &self.attachments);
+        }
+        if !self.lifetimes.is_empty() {
+            let _ = serde_json::to_writer(&mut writer, &self.lifetimes);
+        }
+        if self.triggers != TriggerBook::default() {
+            let _ = serde_json::to_writer(&mut writer, &self.triggers);
+        }
+        if !self.roe.is_empty() {
+            let _ = serde_json::to_writer(&mut writer, &self.roe);
+        }
+        if !self.proximity.is_empty() {
+            let _ = serde_json::to_writer(&mut writer, &self.proximity);
+        }
+        hasher.finish()
+    }
+
+    /// Helper to extract position from an entity's inner components.
+    ///
+    /// # Returns
+    ///
+    /// Currently all entity types have a position, so this always returns `Some`.
+    /// However, we return `Option<Vec2>` for future extensibility:
+    ///
+    /// - Abstract entities (e.g., fleet command, faction state) may lack spatial presence
+    /// - Entities being transferred between layers may temporarily have no position
+    /// - This allows callers to handle the None case gracefully without breaking changes
+    ///
+    /// The `#[allow(clippy::unnecessary_wraps)]` acknowledges that today this always
+    /// returns `Some`, but the API contract explicitly supports `None` for future use.
+    #[allow(clippy::unnecessary_wraps)]
+    fn get_entity_position(entity: &Entity) -> Option<Vec2> {
+        match entity.inner() {
+            EntityInner::Ship(c) => Some(c.transform.position),
+            EntityInner::Platform(c) => Some(c.transform.position),
+            EntityInner::Projectile(c) => Some(c.transform.position),
+            EntityInner::Squadron(c) => Some(c.transform.position),
+        }
+    }
+
+    /// Helper to extract the hull radius from an entity's transform.
+    fn get_entity_radius(entity: &Entity) -> f32 {
+        match entity.inner() {
+            EntityInner::Ship(c) => c.transform.radius,
+            EntityInner::Platform(c) => c.transform.radius,
+            EntityInner::Projectile(c) => c.transform.radius,
+            EntityInner::Squadron(c) => c.transform.radius,
+        }
+    }
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the transform of an entity, and its physics if it can move.
+fn motion_mut(entity: &mut Entity) -> (&mut TransformState, Option<&mut PhysicsState>) {
+    match entity.inner_mut() {
+        EntityInner::Ship(c) => (&mut c.transform, Some(&mut c.physics)),
+        EntityInner::Platform(c) => (&mut c.transform, None),
+        EntityInner::Projectile(c) => (&mut c.transform, Some(&mut c.physics)),
+        EntityInner::Squadron(c) => (&mut c.transform, Some(&mut c.physics)),
+    }
+}
+
+/// 64-bit FNV-1a hasher with a fixed integer encoding.
+///
+/// Unlike `DefaultHasher`, whose algorithm may change between Rust releases,
+/// and the default `Hasher` integer methods, which use native byte order,
+/// this produces the same value everywhere. Used for state hashes and for
+/// every seed derived from the master seed.
+pub(crate) struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    /// Writes `text` prefixed by its length, independently of how the
+    /// standard `Hash` impl of `str` feeds a hasher.
+    pub(crate) fn write_text(&mut self, text: &str) {
+        self.write_usize(text.len());
+        self.write(text.as_bytes());
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+/// Adapter feeding serialized output straight into a hasher.
+struct HashWriter<'a>(&'a mut Fnv1a);
+
+impl io::Write for HashWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Delta Sync
+// =============================================================================
+
+/// A single component update carried by an [`EntityChange`].
+///
+/// Components are sent whole; attributes and labels are sent per key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ComponentChange {
+    /// New transform state
+    Transform(TransformState),
+    /// New physics state
+    Physics(PhysicsState),
+    /// New combat state
+    Combat(CombatState),
+    /// New sensor state
+    Sensor(SensorState),
+    /// New ship inventory
+    Inventory(InventoryState),
+    /// New ship signature
+    Signature(SignatureState),
+    /// New ship diving state
+    Submarine(Option<SubmarineState>),
+    /// New squadron air-to-air state
+    AirWing(Option<AirWing>),
+    /// New platform mine state
+    Mine(Option<MineState>),
+    /// New platform stockpile
+    Stockpile(Option<InventoryState>),
+    /// New team assignment
+    Team(Option<TeamId>),
+    /// New controller
+    Controller(Option<ControllerId>),
+    /// New cosmetic metadata
+    Metadata(EntityMetadata),
+    /// Attribute set (`Some`) or removed (`None`)
+    Attribute(String, Option<AttributeValue>),
+    /// Label added (`true`) or removed (`false`)
+    Label(String, bool),
+}
+
+impl ComponentChange {
+    /// Returns the name of the component this change targets.
+    #[must_use]
+    pub const fn component_name(&self) -> &'static str {
+        match self {
+            Self::Transform(_) => "transform",
+            Self::Physics(_) => "physics",
+            Self::Combat(_) => "combat",
+            Self::Sensor(_) => "sensor",
+            Self::Inventory(_) => "inventory",
+            Self::Signature(_) => "signature",
+            Self::Submarine(_) => "submarine",
+            Self::AirWing(_) => "air_wing",
+            Self::Mine(_) => "mine",
+            Self::Stockpile(_) => "stockpile",
+            Self::Team(_) => "team",
+            Self::Controller(_) => "controller",
+            Self::Metadata(_) => "metadata",
+            Self::Attribute(..) => "attribute",
+            Self::Label(..) => "label",
+        }
+    }
+
+    /// Returns `true` if the entity type has the component this change targets.
+    #[must_use]
+    pub const fn applies_to(&self, inner: &EntityInner) -> bool {
+        match self {
+            Self::Transform(_)
+            | Self::Team(_)
+            | Self::Controller(_)
+            | Self::Metadata(_)
+            | Self::Attribute(..)
+            | Self::Label(..) => true,
+            Self::Physics(_) => !matches!(inner, EntityInner::Platform(_)),
+            Self::Combat(_) => matches!(inner, EntityInner::Ship(_) | EntityInner::Squadron(_)),
+            Self::Sensor(_) => matches!(inner, EntityInner::Ship(_) | EntityInner::Platform(_)),
+            Self::Inventory(_) | Self::Signature(_) | Self::Submarine(_) => {
+                matches!(inner, EntityInner::Ship(_))
+            }
+            Self::Mine(_) | Self::Stockpile(_) => matches!(inner, EntityInner::Platform(_)),
+            Self::AirWing(_) => matches!(inner, EntityInner::Squadron(_)),
+        }
+    }
+
+    /// Writes the change into an entity.
+    ///
+    /// Changes that do not [apply to](Self::applies_to) the entity type are
+    /// ignored.
+    fn apply(&self, entity: &mut Entity) {
+        match self {
+            Self::Team(team) => entity.set_team(*team),
+            Self::Controller(controller) => entity.set_controller(*controller),
+            Self::Metadata(metadata) => entity.set_metadata(metadata.clone()),
+            Self::Attribute(key, Some(value)) => {
+                entity.set_attribute(key.clone(), value.clone());
             }
+            Self::Attribute(key, None) => {
+                entity.remove_attribute(key);
+            }
+            Self::Label(label, true) => {
+                entity.add_label(label.clone());
+            }
+            Self::Label(label, false) => {
+                entity.remove_label(label);
+            }
+            _ => self.apply_inner(entity.inner_mut()),
+        }
+    }
+
+    fn apply_inner(&self, inner: &mut EntityInner) {
+        match (self, inner) {
+            (Self::Transform(t), EntityInner::Ship(c)) => c.transform = *t,
+            (Self::Transform(t), EntityInner::Platform(c)) => c.transform = *t,
+            (Self::Transform(t), EntityInner::Projectile(c)) => c.transform = *t,
+            (Self::Transform(t), EntityInner::Squadron(c)) => c.transform = *t,
+            (Self::Physics(p), EntityInner::Ship(c)) => c.physics = *p,
+            (Self::Physics(p), EntityInner::Projectile(c)) => c.physics = *p,
+            (Self::Physics(p), EntityInner::Squadron(c)) => c.physics = *p,
+            (Self::Combat(s), EntityInner::Ship(c)) => c.combat.clone_from(s),
+            (Self::Combat(s), EntityInner::Squadron(c)) => c.combat.clone_from(s),
+            (Self::Sensor(s), EntityInner::Ship(c)) => c.sensor.clone_from(s),
+            (Self::Sensor(s), EntityInner::Platform(c)) => c.sensor.clone_from(s),
+            (Self::Inventory(s), EntityInner::Ship(c)) => c.inventory.clone_from(s),
+            (Self::Signature(s), EntityInner::Ship(c)) => c.signature = *s,
+            (Self::Submarine(s), EntityInner::Ship(c)) => c.submarine = *s,
+            (Self::AirWing(w), EntityInner::Squadron(c)) => c.air_wing = *w,
+            (Self::Mine(m), EntityInner::Platform(c)) => c.mine = *m,
+            (Self::Stockpile(s), EntityInner::Platform(c)) => c.stockpile.clone_from(s),
+            _ => {}
+        }
+    }
+}
+
+/// Component updates for one entity present in both arenas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityChange {
+    /// Entity being updated
+    pub id: EntityId,
+    /// Updated components, in a fixed order
+    pub changes: Vec<ComponentChange>,
+}
+
+/// Difference between two arenas, produced by [`Arena::diff`].
+///
+/// Applying the delta to the arena it was computed from (with
+/// [`Arena::apply_delta`]) reproduces the target arena, including its
+/// [`state_hash`](Arena::state_hash). Networked observers and distributed
+/// rollout workers can ship deltas instead of full snapshots each tick.
+///
+/// An entity whose type changed between the two arenas appears in both
+/// `despawned` and `spawned`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArenaDelta {
+    /// Target tick
+    pub tick: u64,
+    /// Target entity ID counter
+    pub next_id: u64,
+    /// Target trace ID counter
+    pub next_trace_id: u64,
+    /// Target ID namespace
+    pub id_namespace: u16,
+    /// Entities to add, sorted by ID
+    pub spawned: Vec<Entity>,
+    /// Entities to remove, sorted by ID
+    pub despawned: Vec<EntityId>,
+    /// Entities with updated components, sorted by ID
+    pub changed: Vec<EntityChange>,
+    /// Replacement intent channel, if it changed
+    pub comms: Option<IntentChannel>,
+    /// Replacement supply ledger, if it changed
+    pub logistics: Option<SupplyLedger>,
+    /// Replacement world bounds, if they changed
+    pub bounds: Option<Option<WorldBounds>>,
+    /// Replacement sea currents, if they changed
+    pub currents: Option<Option<CurrentField>>,
+    /// Replacement wind, if it changed
+    #[serde(default)]
+    pub wind: Option<Option<Wind>>,
+    /// Replacement windage, if it changed
+    #[serde(default)]
+    pub windage: Option<BTreeMap<EntityId, f32>>,
+    /// Replacement environment, if it changed
+    #[serde(default)]
+    pub environment: Option<Environment>,
+    /// Replacement standing orders, if they changed
+    #[serde(default)]
+    pub orders: Option<OrderBook>,
+    /// Replacement geofences, if they changed
+    #[serde(default)]
+    pub geofences: Option<GeofenceBook>,
+    /// Replacement steering assists, if they changed
+    #[serde(default)]
+    pub steering: Option<SteeringAssistBook>,
+    /// Replacement attachments, if they changed
+    #[serde(default)]
+    pub attachments: Option<AttachmentBook>,
+    /// Replacement scheduled despawns, if they changed
+    #[serde(default)]
+    pub lifetimes: Option<BTreeMap<EntityId, u64>>,
+    /// Replacement triggers, if they changed
+    #[serde(default)]
+    pub triggers: Option<TriggerBook>,
+    /// Replacement rules of engagement, if they changed
+    #[serde(default)]
+    pub roe: Option<BTreeMap<TeamId, RulesOfEngagement>>,
+    /// Replacement targets in range of proximity watches, if they changed
+    #[serde(default)]
+    pub proximity: Option<ProximityBook>,
+}
+
+impl ArenaDelta {
+    /// Returns `true` if the delta changes nothing but the counters.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spawned.is_empty()
+            && self.despawned.is_empty()
+            && self.changed.is_empty()
+            && self.comms.is_none()
+            && self.logistics.is_none()
+            && self.bounds.is_none()
+            && self.currents.is_none()
+            && self.wind.is_none()
+            && self.windage.is_none()
+            && self.environment.is_none()
+            && self.orders.is_none()
+            && self.geofences.is_none()
+            && self.steering.is_none()
+            && self.attachments.is_none()
+            && self.lifetimes.is_none()
+            && self.triggers.is_none()
+            && self.roe.is_none()
+            && self.proximity.is_none()
+    }
+}
+
+/// Errors produced when applying an [`ArenaDelta`].
+///
+/// A delta that fails validation leaves the arena untouched.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DeltaError {
+    /// The delta removes or updates an entity the arena does not have.
+    #[error("entity {0} does not exist")]
+    MissingEntity(EntityId),
+    /// The delta spawns an entity whose ID is already in use.
+    #[error("entity ID {0} is already in use")]
+    IdInUse(EntityId),
+    /// The delta updates a component the entity type does not have.
+    #[error("entity {id} has no {component} component")]
+    ComponentMismatch {
+        /// Entity being updated.
+        id: EntityId,
+        /// Name of the missing component.
+        component: &'static str,
+    },
+}
+
+impl Arena {
+    /// Computes the delta that turns this arena into `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::arena::Arena;
+    /// use tidebreak_core::entity::{EntityTag, EntityInner, ShipComponents};
+    /// use glam::Vec2;
+    ///
+    /// let mut server = Arena::new();
+    /// let id = server.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+    /// let mut client = server.clone();
+    ///
+    /// server.get_mut(id).unwrap().as_ship_mut().unwrap().transform.position = Vec2::new(5.0, 0.0);
+    /// server.update_spatial(id);
+    ///
+    /// let delta = client.diff(&server);
+    /// assert_eq!(delta.changed.len(), 1);
+    ///
+    /// client.apply_delta(&delta).unwrap();
+    /// assert_eq!(client.state_hash(), server.state_hash());
+    /// ```
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> ArenaDelta {
+        let mut spawned = Vec::new();
+        let mut despawned = Vec::new();
+        let mut changed = Vec::new();
+
+        for (id, entity) in &self.entities {
+            match other.entities.get(id) {
+                None => despawned.push(*id),
+                Some(target) if target.tag() != entity.tag() => despawned.push(*id),
+                Some(target) => {
+                    let updates = diff_entity(entity, target);
+                    if !updates.is_empty() {
+                        changed.push(EntityChange {
+                            id: *id,
+                            changes: updates,
+                        });
+                    }
+                }
+            }
+        }
+        for (id, target) in &other.entities {
+            let replaced = self
+                .entities
+                .get(id)
+                .is_none_or(|entity| entity.tag() != target.tag());
+            if replaced {
+                spawned.push(target.clone());
+            }
+        }
+
+        ArenaDelta {
+            tick: other.tick,
+            next_id: other.next_id,
+            next_trace_id: other.next_trace_id,
+            id_namespace: other.id_namespace,
+            spawned,
+            despawned,
+            changed,
+            comms: (self.comms != other.comms).then(|| other.comms.clone()),
+            logistics: (self.logistics != other.logistics).then(|| other.logistics.clone()),
+            bounds: (self.bounds != other.bounds).then_some(other.bounds),
+            currents: (self.currents != other.currents).then(|| other.currents.clone()),
+            wind: (self.wind != other.wind).then_some(other.wind),
+            windage: (self.windage != other.windage).then(|| other.windage.clone()),
+            environment: (self.environment != other.environment)
+                .then(|| other.environment.clone()),
+            orders: (self.orders != other.orders).then(|| other.orders.clone()),
+            geofences: (self.geofences != other.geofences).then(|| other.geofences.clone()),
+            steering: (self.steering != other.steering).then(|| other.steering.clone()),
+            attachments: (self.attachments != other.attachments)
+                .then(|| other.attachments.clone()),
+            lifetimes: (self.lifetimes != other.lifetimes).then(|| other.lifetimes.clone()),
+            triggers: (self.triggers != other.triggers).then(|| other.triggers.clone()),
+            roe: (self.roe != other.roe).then(|| other.roe.clone()),
+            proximity: (self.proximity != other.proximity).then(|| other.proximity.clone()),
+        }
+    }
+
+    /// Applies a delta computed by [`diff`](Self::diff).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DeltaError`] if the delta does not fit this arena (for
+    /// example, it was computed against a different base). The arena is
+    /// unchanged in that case.
+    pub fn apply_delta(&mut self, delta: &ArenaDelta) -> Result<(), DeltaError> {
+        self.validate_delta(delta)?;
+
+        for id in &delta.despawned {
+            self.despawn(*id);
+        }
+        for entity in &delta.spawned {
+            self.insert(entity.clone());
+        }
+        for change in &delta.changed {
+            if let Some(entity) = self.entities.get_mut(&change.id) {
+                for component in &change.changes {
+                    component.apply(entity);
+                }
+            }
+            self.update_spatial(change.id);
+        }
+
+        self.tick = delta.tick;
+        self.next_id = delta.next_id;
+        self.next_trace_id = delta.next_trace_id;
+        self.id_namespace = delta.id_namespace;
+        if let Some(comms) = &delta.comms {
+            self.comms = comms.clone();
+        }
+        if let Some(logistics) = &delta.logistics {
+            self.logistics = logistics.clone();
+        }
+        if let Some(bounds) = delta.bounds {
+            self.bounds = bounds;
+        }
+        if let Some(currents) = &delta.currents {
+            self.currents.clone_from(currents);
+        }
+        if let Some(wind) = delta.wind {
+            self.wind = wind;
+        }
+        if let Some(windage) = &delta.windage {
+            self.windage.clone_from(windage);
+        }
+        if let Some(environment) = &delta.environment {
+            self.environment.clone_from(environment);
+        }
+        if let Some(orders) = &delta.orders {
+            self.orders.clone_from(orders);
+        }
+        if let Some(geofences) = &delta.geofences {
+            self.geofences.clone_from(geofences);
+        }
+        if let Some(steering) = &delta.steering {
+            self.steering.clone_from(steering);
+        }
+        if let Some(attachments) = &delta.attachments {
+            self.attachments.clone_from(attachments);
+        }
+        if let Some(lifetimes) = &delta.lifetimes {
+            self.lifetimes.clone_from(lifetimes);
+        }
+        if let Some(triggers) = &delta.triggers {
+            self.triggers.clone_from(triggers);
+        }
+        if let Some(roe) = &delta.roe {
+            self.roe.clone_from(roe);
+        }
+        if let Some(proximity) = &delta.proximity {
+            self.proximity.clone_from(proximity);
+        }
+        Ok(())
+    }
+
+    /// Checks a delta against this arena without modifying it.
+    fn validate_delta(&self, delta: &ArenaDelta) -> Result<(), DeltaError> {
+        for id in &delta.despawned {
+            if !self.entities.contains_key(id) {
+                return Err(DeltaError::MissingEntity(*id));
+            }
+        }
+        for entity in &delta.spawned {
+            let id = entity.id();
+            if self.entities.contains_key(&id) && !delta.despawned.contains(&id) {
+                return Err(DeltaError::IdInUse(id));
+            }
+        }
+        for change in &delta.changed {
+            let entity = self
+                .entities
+                .get(&change.id)
+                .filter(|_| !delta.despawned.contains(&change.id))
+                .ok_or(DeltaError::MissingEntity(change.id))?;
+            if let Some(component) = change
+                .changes
+                .iter()
+                .find(|c| !c.applies_to(entity.inner()))
+            {
+                return Err(DeltaError::ComponentMismatch {
+                    id: change.id,
+                    component: component.component_name(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lists the component updates that turn `from` into `to` (same entity type).
+fn diff_entity(from: &Entity, to: &Entity) -> Vec<ComponentChange> {
+    let mut changes = Vec::new();
+
+    match (from.inner(), to.inner()) {
+        (EntityInner::Ship(a), EntityInner::Ship(b)) => {
+            push_if_changed(&mut changes, &a.transform, &b.transform, ComponentChange::Transform);
+            push_if_changed(&mut changes, &a.physics, &b.physics, ComponentChange::Physics);
+            push_if_changed(&mut changes, &a.combat, &b.combat, ComponentChange::Combat);
+            push_if_changed(&mut changes, &a.sensor, &b.sensor, ComponentChange::Sensor);
+            push_if_changed(&mut changes, &a.inventory, &b.inventory, ComponentChange::Inventory);
+            push_if_changed(&mut changes, &a.signature, &b.signature, ComponentChange::Signature);
+            push_if_changed(&mut changes, &a.submarine, &b.submarine, ComponentChange::Submarine);
+        }
+        (EntityInner::Platform(a), EntityInner::Platform(b)) => {
+            push_if_changed(&mut changes, &a.transform, &b.transform, ComponentChange::Transform);
+            push_if_changed(&mut changes, &a.sensor, &b.sensor, ComponentChange::Sensor);
+            push_if_changed(&mut changes, &a.mine, &b.mine, ComponentChange::Mine);
+            push_if_changed(&mut changes, &a.stockpile, &b.stockpile, ComponentChange::Stockpile);
+        }
+        (EntityInner::Projectile(a), EntityInner::Projectile(b)) => {
+            push_if_changed(&mut changes, &a.transform, &b.transform, ComponentChange::Transform);
+            push_if_changed(&mut changes, &a.physics, &b.physics, ComponentChange::Physics);
+        }
+        (EntityInner::Squadron(a), EntityInner::Squadron(b)) => {
+            push_if_changed(&mut changes, &a.transform, &b.transform, ComponentChange::Transform);
+            push_if_changed(&mut changes, &a.physics, &b.physics, ComponentChange::Physics);
+            push_if_changed(&mut changes, &a.combat, &b.combat, ComponentChange::Combat);
+            push_if_changed(&mut changes, &a.air_wing, &b.air_wing, ComponentChange::AirWing);
+        }
+        _ => {}
+    }
+
+    if from.team() != to.team() {
+        changes.push(ComponentChange::Team(to.team()));
+    }
+    if from.controller() != to.controller() {
+        changes.push(ComponentChange::Controller(to.controller()));
+    }
+    push_if_changed(&mut changes, from.metadata(), to.metadata(), ComponentChange::Metadata);
+    for (key, value) in from.attributes() {
+        if !to.attributes().contains_key(key) {
+            changes.push(ComponentChange::Attribute(key.clone(), None));
+        } else if to.attribute(key) != Some(value) {
+            changes.push(ComponentChange::Attribute(key.clone(), to.attribute(key).cloned()));
         }
     }
-
-    /// Helper to extract position from an entity's inner components.
-    ///
-    /// # Returns
-    ///
-    /// Currently all entity types have a position, so this always returns `Some`.
-    /// However, we return `Option<Vec2>` for future extensibility:
-    ///
-    /// - Abstract entities (e.g., fleet command, faction state) may lack spatial presence
-    /// - Entities being transferred between layers may temporarily have no position
-    /// - This allows callers to handle the None case gracefully without breaking changes
-    ///
-    /// The `#[allow(clippy::unnecessary_wraps)]` acknowledges that today this always
-    /// returns `Some`, but the API contract explicitly supports `None` for future use.
-    #[allow(clippy::unnecessary_wraps)]
-    fn get_entity_position(entity: &Entity) -> Option<Vec2> {
-        match entity.inner() {
-            EntityInner::Ship(c) => Some(c.transform.position),
-            EntityInner::Platform(c) => Some(c.transform.position),
-            EntityInner::Projectile(c) => Some(c.transform.position),
-            EntityInner::Squadron(c) => Some(c.transform.position),
+    for (key, value) in to.attributes() {
+        if !from.attributes().contains_key(key) {
+            changes.push(ComponentChange::Attribute(key.clone(), Some(value.clone())));
         }
     }
+    for label in from.labels().difference(to.labels()) {
+        changes.push(ComponentChange::Label(label.clone(), false));
+    }
+    for label in to.labels().difference(from.labels()) {
+        changes.push(ComponentChange::Label(label.clone(), true));
+    }
+    changes
 }
 
-impl Default for Arena {
-    fn default() -> Self {
-        Self::new()
+fn push_if_changed<T: Clone + PartialEq>(
+    changes: &mut Vec<ComponentChange>,
+    from: &T,
+    to: &T,
+    wrap: fn(T) -> ComponentChange,
+) {
+    if from != to {
+        changes.push(wrap(to.clone()));
     }
 }
 
@@ -599,6 +2186,127 @@ mod tests {
             assert!(!index.update(EntityId::new(999), Vec2::new(0.0, 0.0)));
         }
 
+        #[test]
+        fn query_knearest_sorted_by_distance() {
+            let mut index = SpatialIndex::new();
+            index.insert(EntityId::new(1), Vec2::new(300.0, 0.0));
+            index.insert(EntityId::new(2), Vec2::new(100.0, 0.0));
+            index.insert(EntityId::new(3), Vec2::new(0.0, -200.0));
+            index.insert(EntityId::new(4), Vec2::new(1000.0, 0.0));
+
+            let results = index.query_knearest(Vec2::ZERO, 3, None);
+
+            assert_eq!(
+                results,
+                vec![EntityId::new(2), EntityId::new(3), EntityId::new(1)]
+            );
+        }
+
+        #[test]
+        fn query_knearest_breaks_ties_by_id() {
+            let mut index = SpatialIndex::new();
+            index.insert(EntityId::new(7), Vec2::new(0.0, 50.0));
+            index.insert(EntityId::new(3), Vec2::new(50.0, 0.0));
+            index.insert(EntityId::new(5), Vec2::new(-50.0, 0.0));
+
+            let results = index.query_knearest(Vec2::ZERO, 2, None);
+
+            assert_eq!(results, vec![EntityId::new(3), EntityId::new(5)]);
+        }
+
+        #[test]
+        fn query_knearest_k_larger_than_index() {
+            let mut index = SpatialIndex::new();
+            index.insert(EntityId::new(1), Vec2::new(10.0, 0.0));
+
+            assert_eq!(index.query_knearest(Vec2::ZERO, 5, None).len(), 1);
+            assert!(index.query_knearest(Vec2::ZERO, 0, None).is_empty());
+        }
+
+        #[test]
+        fn query_knearest_filters_by_tag() {
+            let mut index = SpatialIndex::new();
+            index.insert_tagged(
+                EntityId::new(1),
+                Vec2::new(10.0, 0.0),
+                EntityTag::Projectile,
+            );
+            index.insert_tagged(EntityId::new(2), Vec2::new(20.0, 0.0), EntityTag::Ship);
+            index.insert_tagged(EntityId::new(3), Vec2::new(30.0, 0.0), EntityTag::Ship);
+            index.insert(EntityId::new(4), Vec2::new(5.0, 0.0));
+
+            let ships = index.query_knearest(Vec2::ZERO, 5, Some(EntityTag::Ship));
+
+            assert_eq!(ships, vec![EntityId::new(2), EntityId::new(3)]);
+        }
+
+        #[test]
+        fn remove_clears_tag() {
+            let mut index = SpatialIndex::new();
+            index.insert_tagged(EntityId::new(1), Vec2::ZERO, EntityTag::Ship);
+            index.remove(EntityId::new(1));
+            index.insert(EntityId::new(1), Vec2::ZERO);
+
+            assert!(index
+                .query_knearest(Vec2::ZERO, 1, Some(EntityTag::Ship))
+                .is_empty());
+        }
+
+        #[test]
+        fn query_segment_hits_points_within_half_width() {
+            let mut index = SpatialIndex::new();
+            index.insert(EntityId::new(1), Vec2::new(50.0, 4.0));
+            index.insert(EntityId::new(2), Vec2::new(50.0, 6.0));
+            index.insert(EntityId::new(3), Vec2::new(150.0, 0.0));
+
+            let hits = index.query_segment(Vec2::ZERO, Vec2::new(100.0, 0.0), 10.0);
+
+            assert_eq!(hits, vec![EntityId::new(1)]);
+        }
+
+        #[test]
+        fn query_segment_uses_hull_radius() {
+            let mut index = SpatialIndex::new();
+            index.insert(EntityId::new(1), Vec2::new(50.0, 30.0));
+            index.insert(EntityId::new(2), Vec2::new(104.0, 0.0));
+
+            assert!(index
+                .query_segment(Vec2::ZERO, Vec2::new(100.0, 0.0), 0.0)
+                .is_empty());
+
+            index.set_radius(EntityId::new(1), 30.0);
+            index.set_radius(EntityId::new(2), 5.0);
+            let hits = index.query_segment(Vec2::ZERO, Vec2::new(100.0, 0.0), 0.0);
+
+            assert_eq!(hits, vec![EntityId::new(1), EntityId::new(2)]);
+        }
+
+        #[test]
+        fn query_segment_orders_by_distance_along_segment() {
+            let mut index = SpatialIndex::new();
+            index.insert(EntityId::new(1), Vec2::new(80.0, 0.0));
+            index.insert(EntityId::new(2), Vec2::new(20.0, 0.0));
+            index.insert(EntityId::new(3), Vec2::new(50.0, 0.0));
+
+            let hits = index.query_segment(Vec2::ZERO, Vec2::new(100.0, 0.0), 1.0);
+
+            assert_eq!(
+                hits,
+                vec![EntityId::new(2), EntityId::new(3), EntityId::new(1)]
+            );
+        }
+
+        #[test]
+        fn query_segment_degenerate_is_radius_query() {
+            let mut index = SpatialIndex::new();
+            index.insert(EntityId::new(1), Vec2::new(3.0, 0.0));
+            index.insert(EntityId::new(2), Vec2::new(30.0, 0.0));
+
+            let hits = index.query_segment(Vec2::ZERO, Vec2::ZERO, 10.0);
+
+            assert_eq!(hits, vec![EntityId::new(1)]);
+        }
+
         #[test]
         fn serialization_roundtrip() {
             let mut index = SpatialIndex::new();
@@ -928,6 +2636,83 @@ mod tests {
             assert_eq!(arena.spatial().get(id), Some(Vec2::new(500.0, 500.0)));
         }
 
+        #[test]
+        fn query_by_label_returns_sorted_members() {
+            let mut arena = Arena::new();
+            let ids: Vec<_> = (0..4)
+                .map(|_| {
+                    arena.spawn(
+                        EntityTag::Ship,
+                        EntityInner::Ship(ShipComponents::default()),
+                    )
+                })
+                .collect();
+            assert!(arena.add_label(ids[3], "convoy_1"));
+            arena.add_label(ids[1], "convoy_1");
+            arena.add_label(ids[2], "escort");
+
+            let convoy: Vec<_> = arena.query_by_label("convoy_1").collect();
+            assert_eq!(convoy, vec![ids[1], ids[3]]);
+
+            assert!(arena.remove_label(ids[1], "convoy_1"));
+            assert!(!arena.remove_label(ids[1], "convoy_1"));
+            assert!(!arena.add_label(EntityId::new(99), "convoy_1"));
+            assert_eq!(arena.query_by_label("convoy_1").count(), 1);
+        }
+
+        #[test]
+        fn controllers_only_command_their_own_entities() {
+            let mut arena = Arena::new();
+            let ids: Vec<_> = (0..3)
+                .map(|_| {
+                    arena.spawn(
+                        EntityTag::Ship,
+                        EntityInner::Ship(ShipComponents::default()),
+                    )
+                })
+                .collect();
+            let (human, agent) = (ControllerId::Human(0), ControllerId::Agent(0));
+            assert!(arena.set_controller(ids[0], Some(human)));
+            arena.set_controller(ids[2], Some(human));
+            arena.set_controller(ids[1], Some(agent));
+            assert!(!arena.set_controller(EntityId::new(99), Some(human)));
+
+            assert_eq!(arena.controlled_by(human).collect::<Vec<_>>(), vec![ids[0], ids[2]]);
+            assert!(arena.can_command(Some(human), ids[0]));
+            assert!(!arena.can_command(Some(human), ids[1]));
+            assert!(arena.can_command(None, ids[1]));
+            assert!(!arena.can_command(None, EntityId::new(99)));
+
+            // Handing over control mid-episode
+            arena.set_controller(ids[0], Some(agent));
+            assert!(!arena.can_command(Some(human), ids[0]));
+            assert!(arena.can_command(Some(agent), ids[0]));
+            arena.set_controller(ids[2], None);
+            assert!(!arena.can_command(Some(human), ids[2]));
+        }
+
+        #[test]
+        fn update_spatial_syncs_hull_radius() {
+            let mut arena = Arena::new();
+            let id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(0.0, 40.0), 0.0)),
+            );
+            let ray = |arena: &Arena| {
+                arena
+                    .spatial()
+                    .query_segment(Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0), 0.0)
+            };
+            assert!(ray(&arena).is_empty());
+
+            if let Some(ship) = arena.get_mut(id).and_then(Entity::as_ship_mut) {
+                ship.transform.radius = 50.0;
+            }
+            arena.update_spatial(id);
+
+            assert_eq!(ray(&arena), vec![id]);
+        }
+
         #[test]
         fn spatial_queries_work_through_arena() {
             let mut arena = Arena::new();
@@ -1057,4 +2842,394 @@ mod tests {
             assert_eq!(nearby1, nearby2);
         }
     }
+
+    mod state_hash_tests {
+        use super::*;
+
+        fn create_arena() -> Arena {
+            let mut arena = Arena::new();
+            arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(10.0, 0.0), 0.0)),
+            );
+            arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(PlatformComponents::at_position(Vec2::new(0.0, 5.0))),
+            );
+            arena
+        }
+
+        #[test]
+        fn hasher_is_fnv1a_with_little_endian_integers() {
+            let hash = |f: &dyn Fn(&mut Fnv1a)| {
+                let mut hasher = Fnv1a::default();
+                f(&mut hasher);
+                hasher.finish()
+            };
+
+            // Reference vectors
+            assert_eq!(hash(&|_| {}), 0xcbf2_9ce4_8422_2325);
+            assert_eq!(hash(&|h| h.write(b"a")), 0xaf63_dc4c_8601_ec8c);
+            assert_eq!(hash(&|h| h.write(b"foobar")), 0x8594_4171_f739_67e8);
+
+            let bytes = 0x0102_0304_u32.to_le_bytes();
+            assert_eq!(hash(&|h| h.write_u32(0x0102_0304)), hash(&|h| h.write(&bytes)));
+            assert_eq!(hash(&|h| h.write_usize(7)), hash(&|h| h.write_u64(7)));
+        }
+
+        #[test]
+        fn identical_arenas_hash_equal() {
+            assert_eq!(create_arena().state_hash(), create_arena().state_hash());
+            let arena = create_arena();
+            assert_eq!(arena.clone().state_hash(), arena.state_hash());
+        }
+
+        #[test]
+        fn component_change_changes_hash() {
+            let mut arena = create_arena();
+            let before = arena.state_hash();
+
+            let ship = arena
+                .get_mut(EntityId::new(0))
+                .unwrap()
+                .as_ship_mut()
+                .unwrap();
+            ship.combat.hp -= 1.0;
+
+            assert_ne!(arena.state_hash(), before);
+        }
+
+        #[test]
+        fn tick_changes_hash() {
+            let mut arena = create_arena();
+            let before = arena.state_hash();
+            arena.advance_tick();
+            assert_ne!(arena.state_hash(), before);
+        }
+
+        #[test]
+        fn pending_transfer_changes_hash() {
+            let mut arena = create_arena();
+            let before = arena.state_hash();
+            arena.logistics_mut().request(
+                EntityId::new(1),
+                EntityId::new(0),
+                crate::entity::Cargo::Fuel(10.0),
+            );
+            assert_ne!(arena.state_hash(), before);
+        }
+
+        #[test]
+        fn bounds_change_hash() {
+            let mut arena = create_arena();
+            let before = arena.state_hash();
+            arena.set_bounds(Some(WorldBounds::centered(
+                100.0,
+                100.0,
+                BoundaryPolicy::Clamp,
+            )));
+            assert_ne!(arena.state_hash(), before);
+        }
+
+        #[test]
+        fn currents_change_hash() {
+            let mut arena = create_arena();
+            let before = arena.state_hash();
+            arena.set_currents(Some(CurrentField::uniform(Vec2::new(1.0, 0.0))));
+            assert_ne!(arena.state_hash(), before);
+        }
+
+        #[test]
+        fn wind_changes_hash() {
+            let mut arena = create_arena();
+            let before = arena.state_hash();
+            arena.set_wind(Some(Wind::new(10.0, 0.0)));
+            let windy = arena.state_hash();
+            assert_ne!(windy, before);
+            let id = arena.entities_sorted().next().unwrap().id();
+            assert!(arena.set_windage(id, Wind::LIFERAFT_WINDAGE));
+            assert_ne!(arena.state_hash(), windy);
+        }
+
+        #[test]
+        fn environment_changes_hash() {
+            let mut arena = create_arena();
+            let before = arena.state_hash();
+            arena.environment_mut().sea_state = 3;
+            assert_ne!(arena.state_hash(), before);
+        }
+    }
+
+    mod id_namespace_tests {
+        use super::*;
+
+        fn ship() -> EntityInner {
+            EntityInner::Ship(ShipComponents::default())
+        }
+
+        #[test]
+        fn default_namespace_is_sequential() {
+            let mut arena = Arena::new();
+            assert_eq!(arena.spawn(EntityTag::Ship, ship()), EntityId::new(0));
+            assert_eq!(arena.spawn(EntityTag::Ship, ship()), EntityId::new(1));
+        }
+
+        #[test]
+        fn namespaced_arenas_merge_without_collisions() {
+            let mut a = Arena::new();
+            let mut b = Arena::new();
+            a.set_id_namespace(1);
+            b.set_id_namespace(2);
+            let from_a: Vec<_> = (0..3).map(|_| a.spawn(EntityTag::Ship, ship())).collect();
+            let from_b: Vec<_> = (0..3).map(|_| b.spawn(EntityTag::Ship, ship())).collect();
+
+            let mut merged = Arena::new();
+            for id in from_a.iter().chain(&from_b) {
+                merged.spawn_with_id(*id, EntityTag::Ship, ship()).unwrap();
+            }
+
+            assert_eq!(merged.entity_count(), 6);
+            assert!(from_a.iter().all(|id| id.namespace() == 1));
+            assert!(from_b.iter().all(|id| id.namespace() == 2));
+        }
+
+        #[test]
+        fn spawn_with_id_rejects_duplicates() {
+            let mut arena = Arena::new();
+            let id = arena.spawn(EntityTag::Ship, ship());
+
+            assert_eq!(
+                arena.spawn_with_id(id, EntityTag::Ship, ship()),
+                Err(SpawnError::IdInUse(id))
+            );
+            assert_eq!(arena.entity_count(), 1);
+        }
+
+        #[test]
+        fn spawn_with_id_updates_spatial_index() {
+            let mut arena = Arena::new();
+            let id = EntityId::namespaced(4, 9);
+            let inner = EntityInner::Ship(ShipComponents::at_position(Vec2::new(10.0, 0.0), 0.0));
+
+            arena.spawn_with_id(id, EntityTag::Ship, inner).unwrap();
+
+            assert_eq!(arena.spatial().get(id), Some(Vec2::new(10.0, 0.0)));
+        }
+
+        #[test]
+        fn spawn_skips_explicitly_taken_ids() {
+            let mut arena = Arena::new();
+            arena.spawn_with_id(EntityId::new(0), EntityTag::Ship, ship()).unwrap();
+            arena.spawn_with_id(EntityId::new(1), EntityTag::Ship, ship()).unwrap();
+
+            assert_eq!(arena.spawn(EntityTag::Ship, ship()), EntityId::new(2));
+        }
+
+        #[test]
+        fn namespace_changes_state_hash() {
+            let mut arena = Arena::new();
+            let before = arena.state_hash();
+            arena.set_id_namespace(5);
+            assert_ne!(arena.state_hash(), before);
+        }
+    }
+
+    mod delta_tests {
+        use super::*;
+        use crate::environment::SmokeField;
+
+        fn ship_at(x: f32) -> EntityInner {
+            EntityInner::Ship(ShipComponents::at_position(Vec2::new(x, 0.0), 0.0))
+        }
+
+        fn move_ship(arena: &mut Arena, id: EntityId, x: f32) {
+            let ship = arena.get_mut(id).unwrap().as_ship_mut().unwrap();
+            ship.transform.position = Vec2::new(x, 0.0);
+            arena.update_spatial(id);
+        }
+
+        #[test]
+        fn identical_arenas_produce_empty_delta() {
+            let mut arena = Arena::new();
+            arena.spawn(EntityTag::Ship, ship_at(0.0));
+
+            assert!(arena.diff(&arena.clone()).is_empty());
+        }
+
+        #[test]
+        fn delta_reproduces_target() {
+            let mut base = Arena::new();
+            let moved = base.spawn(EntityTag::Ship, ship_at(0.0));
+            let removed = base.spawn(EntityTag::Ship, ship_at(10.0));
+            let mut target = base.clone();
+
+            move_ship(&mut target, moved, 25.0);
+            target.set_attribute(moved, "fuel_state", Some(AttributeValue::from("low")));
+            target.add_label(moved, "flagship");
+            target.set_team(moved, Some(TeamId::new(1)));
+            target.set_controller(moved, Some(ControllerId::Agent(1)));
+            let metadata = EntityMetadata {
+                name: Some("Resolute".into()),
+                ..EntityMetadata::default()
+            };
+            assert!(target.set_metadata(moved, metadata));
+            target.despawn(removed);
+            let added = target.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(PlatformComponents::at_position(Vec2::new(5.0, 5.0))),
+            );
+            target.set_bounds(Some(WorldBounds::centered(100.0, 100.0, BoundaryPolicy::Clamp)));
+            target.set_currents(Some(CurrentField::uniform(Vec2::new(0.5, 0.0))));
+            target.environment_mut().smoke = Some(SmokeField::uniform(0.5));
+            target.advance_tick();
+
+            let delta = base.diff(&target);
+            assert_eq!(delta.despawned, vec![removed]);
+            assert_eq!(delta.spawned.len(), 1);
+            assert_eq!(delta.spawned[0].id(), added);
+
+            base.apply_delta(&delta).unwrap();
+            assert_eq!(base.state_hash(), target.state_hash());
+            assert_eq!(base.spatial().get(moved), Some(Vec2::new(25.0, 0.0)));
+            assert_eq!(base.spatial().get(removed), None);
+        }
+
+        #[test]
+        fn only_changed_components_are_sent() {
+            let mut base = Arena::new();
+            let id = base.spawn(EntityTag::Ship, ship_at(0.0));
+            let mut target = base.clone();
+            move_ship(&mut target, id, 3.0);
+
+            let delta = base.diff(&target);
+
+            assert_eq!(delta.changed.len(), 1);
+            assert!(matches!(
+                delta.changed[0].changes.as_slice(),
+                [ComponentChange::Transform(_)]
+            ));
+        }
+
+        #[test]
+        fn attribute_and_label_removal() {
+            let mut base = Arena::new();
+            let id = base.spawn(EntityTag::Ship, ship_at(0.0));
+            base.set_attribute(id, "role", Some(AttributeValue::from("escort")));
+            base.add_label(id, "picket");
+            let mut target = base.clone();
+            target.set_attribute(id, "role", None);
+            target.remove_label(id, "picket");
+
+            base.apply_delta(&base.diff(&target)).unwrap();
+
+            let entity = base.get(id).unwrap();
+            assert!(entity.attributes().is_empty());
+            assert!(entity.labels().is_empty());
+        }
+
+        #[test]
+        fn type_change_respawns_entity() {
+            let mut base = Arena::new();
+            let id = EntityId::new(7);
+            base.spawn_with_id(id, EntityTag::Ship, ship_at(0.0)).unwrap();
+            let mut target = Arena::new();
+            let projectile = EntityInner::Projectile(ProjectileComponents::default());
+            target
+                .spawn_with_id(id, EntityTag::Projectile, projectile)
+                .unwrap();
+
+            let delta = base.diff(&target);
+            assert_eq!(delta.despawned, vec![id]);
+            assert_eq!(delta.spawned.len(), 1);
+
+            base.apply_delta(&delta).unwrap();
+            assert!(base.get(id).unwrap().is_projectile());
+        }
+
+        #[test]
+        fn mismatched_base_is_rejected_untouched() {
+            let mut base = Arena::new();
+            let id = base.spawn(EntityTag::Ship, ship_at(0.0));
+            let mut target = base.clone();
+            move_ship(&mut target, id, 1.0);
+            let delta = base.diff(&target);
+
+            let mut other = Arena::new();
+            let before = other.state_hash();
+            assert_eq!(other.apply_delta(&delta), Err(DeltaError::MissingEntity(id)));
+            assert_eq!(other.state_hash(), before);
+        }
+
+        #[test]
+        fn component_mismatch_is_rejected() {
+            let mut arena = Arena::new();
+            let id = arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(PlatformComponents::default()),
+            );
+            let mut delta = arena.diff(&arena);
+            delta.changed.push(EntityChange {
+                id,
+                changes: vec![ComponentChange::Physics(PhysicsState::default())],
+            });
+
+            assert_eq!(
+                arena.apply_delta(&delta),
+                Err(DeltaError::ComponentMismatch {
+                    id,
+                    component: "physics"
+                })
+            );
+        }
+
+        #[test]
+        fn delta_survives_serialization() {
+            let mut base = Arena::new();
+            let id = base.spawn(EntityTag::Ship, ship_at(0.0));
+            let mut target = base.clone();
+            move_ship(&mut target, id, 8.0);
+            target.spawn(EntityTag::Ship, ship_at(20.0));
+
+            let delta = base.diff(&target);
+            let json = serde_json::to_string(&delta).unwrap();
+            let restored: ArenaDelta = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(restored, delta);
+        }
+    }
+
+    mod world_bounds_tests {
+        use super::*;
+
+        #[test]
+        fn contains_includes_edges() {
+            let bounds = WorldBounds::new(Vec2::ZERO, Vec2::new(10.0, 5.0), BoundaryPolicy::Clamp);
+
+            assert!(bounds.contains(Vec2::new(10.0, 0.0)));
+            assert!(!bounds.contains(Vec2::new(10.1, 0.0)));
+            assert!(!bounds.contains(Vec2::new(5.0, -0.1)));
+        }
+
+        #[test]
+        fn edge_distances_negative_outside() {
+            let bounds = WorldBounds::centered(20.0, 10.0, BoundaryPolicy::Clamp);
+
+            let d = bounds.edge_distances(Vec2::new(12.0, 1.0));
+
+            let expected = [22.0_f32, -2.0, 6.0, 4.0];
+            assert_eq!(d.map(f32::to_bits), expected.map(f32::to_bits));
+        }
+
+        #[test]
+        fn bounds_survive_serialization() {
+            let mut arena = Arena::new();
+            let bounds = WorldBounds::centered(50.0, 50.0, BoundaryPolicy::Despawn);
+            arena.set_bounds(Some(bounds));
+
+            let json = serde_json::to_string(&arena).unwrap();
+            let restored: Arena = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(restored.bounds(), Some(&bounds));
+        }
+    }
 }
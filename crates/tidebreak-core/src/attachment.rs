@@ -0,0 +1,222 @@
+//! Parent-child attachments for composite platforms.
+//!
+//! Some entities ride on others: a towed sonar array trails its ship, a
+//! boat sits in its davits until launched, a turret turns with its hull.
+//! The arena's [`AttachmentBook`] records, per child entity, an
+//! [`Attachment`] to a parent with an offset in the parent's frame.
+//!
+//! - [`Arena::attach`] links a child and moves it onto its parent at once;
+//!   links that would form a cycle are refused.
+//! - The [`PhysicsResolver`](crate::resolver::PhysicsResolver) does not
+//!   integrate attached entities. After moving everything else it places
+//!   each child at its parent's pose plus the offset, with the parent's
+//!   velocity, so chains (a boat on a davit on a ship) follow their root.
+//! - [`Arena::detach`] releases a child where it is, keeping its velocity.
+//! - Despawning a parent despawns the children attached with `cascade`
+//!   and releases the others.
+//!
+//! # Example
+//!
+//! ```
+//! use glam::Vec2;
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::attachment::Attachment;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, PlatformComponents, ShipComponents};
+//!
+//! let mut arena = Arena::new();
+//! let ship = arena.spawn(
+//!     EntityTag::Ship,
+//!     EntityInner::Ship(ShipComponents::at_position(Vec2::new(100.0, 0.0), 0.0)),
+//! );
+//! let array = arena.spawn(
+//!     EntityTag::Platform,
+//!     EntityInner::Platform(PlatformComponents::default()),
+//! );
+//!
+//! assert!(arena.attach(array, Attachment::new(ship).with_offset(Vec2::new(-300.0, 0.0))));
+//! assert_eq!(arena.spatial().get(array), Some(Vec2::new(-200.0, 0.0)));
+//!
+//! arena.despawn(ship);
+//! assert!(arena.get(array).is_none());
+//! ```
+//!
+//! [`Arena::attach`]: crate::arena::Arena::attach
+//! [`Arena::detach`]: crate::arena::Arena::detach
+
+use std::collections::BTreeMap;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{EntityId, TransformState};
+
+/// Link from a child entity to the parent it rides on.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// Entity carrying the child
+    pub parent: EntityId,
+    /// Position in the parent's frame (+X ahead, +Y to port), in meters
+    pub offset: Vec2,
+    /// Heading relative to the parent's, in radians
+    pub heading: f32,
+    /// Whether the child is despawned with its parent (otherwise released)
+    pub cascade: bool,
+}
+
+impl Attachment {
+    /// Creates a cascading attachment at the parent's position and heading.
+    #[must_use]
+    pub const fn new(parent: EntityId) -> Self {
+        Self {
+            parent,
+            offset: Vec2::ZERO,
+            heading: 0.0,
+            cascade: true,
+        }
+    }
+
+    /// Builder method to set the offset in the parent's frame.
+    #[must_use]
+    pub const fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Builder method to set the heading relative to the parent's.
+    #[must_use]
+    pub const fn with_heading(mut self, heading: f32) -> Self {
+        self.heading = heading;
+        self
+    }
+
+    /// Builder method to choose whether the child is despawned with its
+    /// parent.
+    #[must_use]
+    pub const fn with_cascade(mut self, cascade: bool) -> Self {
+        self.cascade = cascade;
+        self
+    }
+
+    /// Returns the child's world position and heading on a parent with the
+    /// given transform.
+    #[must_use]
+    pub fn pose(&self, parent: &TransformState) -> (Vec2, f32) {
+        let position = parent.position + Vec2::from_angle(parent.heading).rotate(self.offset);
+        (position, parent.heading + self.heading)
+    }
+}
+
+/// Per-arena record of attachments, keyed by child entity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentBook {
+    links: BTreeMap<EntityId, Attachment>,
+}
+
+impl AttachmentBook {
+    /// Creates an empty book.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Links `child` to its parent, replacing any earlier attachment.
+    ///
+    /// Returns `false`, and changes nothing, if the link would attach an
+    /// entity to itself or to one of its own descendants.
+    pub fn insert(&mut self, child: EntityId, attachment: Attachment) -> bool {
+        if self.lineage(attachment.parent).any(|id| id == child) {
+            return false;
+        }
+        self.links.insert(child, attachment);
+        true
+    }
+
+    /// Removes the attachment of `child`, returning it.
+    pub fn remove(&mut self, child: EntityId) -> Option<Attachment> {
+        self.links.remove(&child)
+    }
+
+    /// Returns the attachment of `child`, if it is attached.
+    #[must_use]
+    pub fn get(&self, child: EntityId) -> Option<&Attachment> {
+        self.links.get(&child)
+    }
+
+    /// Returns the entity `child` rides on, if any.
+    #[must_use]
+    pub fn parent_of(&self, child: EntityId) -> Option<EntityId> {
+        self.links.get(&child).map(|a| a.parent)
+    }
+
+    /// Iterates over the entities attached directly to `parent`, in ID
+    /// order.
+    pub fn children(&self, parent: EntityId) -> impl Iterator<Item = EntityId> + '_ {
+        self.links
+            .iter()
+            .filter(move |(_, a)| a.parent == parent)
+            .map(|(child, _)| *child)
+    }
+
+    /// Iterates over `id` and its ancestors, nearest first.
+    pub fn lineage(&self, id: EntityId) -> impl Iterator<Item = EntityId> + '_ {
+        std::iter::successors(Some(id), |id| self.parent_of(*id))
+    }
+
+    /// Iterates over (child, attachment) pairs in child ID order.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &Attachment)> {
+        self.links.iter().map(|(id, a)| (*id, a))
+    }
+
+    /// Returns the number of attached entities.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Returns true if no entity is attached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    /// Drops all attachments.
+    pub fn clear(&mut self) {
+        self.links.clear();
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pose_rotates_offset_with_parent() {
+        let parent = TransformState::new(Vec2::new(10.0, 0.0), std::f32::consts::FRAC_PI_2);
+        let attachment = Attachment::new(EntityId::new(1))
+            .with_offset(Vec2::new(-5.0, 0.0))
+            .with_heading(0.5);
+        let (position, heading) = attachment.pose(&parent);
+        assert!(position.distance(Vec2::new(10.0, -5.0)) < 1e-5);
+        assert!((heading - (std::f32::consts::FRAC_PI_2 + 0.5)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn book_refuses_cycles() {
+        let (a, b, c) = (EntityId::new(1), EntityId::new(2), EntityId::new(3));
+        let mut book = AttachmentBook::new();
+        assert!(book.insert(b, Attachment::new(a)));
+        assert!(book.insert(c, Attachment::new(b)));
+        assert!(!book.insert(a, Attachment::new(c)));
+        assert!(!book.insert(a, Attachment::new(a)));
+        assert_eq!(book.lineage(c).collect::<Vec<_>>(), vec![c, b, a]);
+        assert_eq!(book.children(a).collect::<Vec<_>>(), vec![b]);
+
+        book.remove(b);
+        assert_eq!(book.parent_of(c), Some(b));
+        assert_eq!(book.len(), 1);
+    }
+}
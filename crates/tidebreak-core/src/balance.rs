@@ -0,0 +1,388 @@
+//! Heuristic force balance for curriculum scheduling.
+//!
+//! The [`BalanceEvaluator`] scores every team's fighting strength from the
+//! current world state, so a curriculum scheduler can spot one-sided
+//! scenarios early and truncate them instead of playing them out.
+//!
+//! # Model
+//!
+//! - A unit is a teamed ship or squadron that is neither destroyed nor
+//!   surrendered. Unteamed entities are ignored.
+//! - A unit's firepower is `base_firepower` plus one per weapon that can
+//!   fire: operational, not disabled by `WEAPONS_DISABLED`, and (for ships)
+//!   with ammunition of its type in the inventory. It is weighted by the
+//!   unit's current HP.
+//! - Positioning is the fraction of live enemy units held in at least one
+//!   friendly track table.
+//! - The team score is `firepower * (1 + positioning_weight * positioning)`.
+//! - Win probabilities follow Lanchester's square law: each team's squared
+//!   score as a share of the sum of squared scores.
+//!
+//! Teams whose units are all out of the fight are still reported, with a
+//! zero score, as long as one of their entities remains in the arena.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::simulation::Simulation;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+//! use glam::Vec2;
+//!
+//! let mut sim = Simulation::new(42);
+//! for (x, team) in [(0.0, 0), (500.0, 0), (1000.0, 1)] {
+//!     let ship = ShipComponents::at_position(Vec2::new(x, 0.0), 0.0);
+//!     let id = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(ship));
+//!     sim.arena_mut().set_team(id, Some(TeamId::new(team)));
+//! }
+//!
+//! let report = sim.evaluate_balance();
+//! let blue = report.get(TeamId::new(0)).unwrap();
+//! assert!((blue.win_probability - 0.8).abs() < 1e-6);
+//! assert!(report.is_one_sided(0.75));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::arena::Arena;
+use crate::entity::components::{CombatState, InventoryState, SensorState, StatusFlags};
+use crate::entity::{Entity, EntityId, EntityInner, TeamId};
+
+/// Configuration for balance evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceConfig {
+    /// Firepower of a unit with no usable weapons, so unarmed units such as
+    /// aggregate squadrons still count.
+    pub base_firepower: f32,
+    /// Score bonus at full positioning (every enemy tracked).
+    pub positioning_weight: f32,
+}
+
+impl Default for BalanceConfig {
+    fn default() -> Self {
+        Self {
+            base_firepower: 1.0,
+            positioning_weight: 0.5,
+        }
+    }
+}
+
+/// Strength estimate for one team.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamBalance {
+    /// Team being scored.
+    pub team: TeamId,
+    /// Units still in the fight.
+    pub units: usize,
+    /// Total HP of those units.
+    pub hp: f32,
+    /// HP-weighted firepower of those units.
+    pub firepower: f32,
+    /// Fraction of live enemy units tracked by the team [0, 1].
+    pub positioning: f32,
+    /// Combined strength score.
+    pub score: f32,
+    /// Estimated probability of winning [0, 1].
+    pub win_probability: f32,
+}
+
+/// Balance of every team at one tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceReport {
+    /// Tick the report was evaluated at.
+    pub tick: u64,
+    /// Per-team estimates, sorted by team.
+    pub teams: Vec<TeamBalance>,
+}
+
+impl BalanceReport {
+    /// Returns the estimate for `team`, if it has any entities.
+    #[must_use]
+    pub fn get(&self, team: TeamId) -> Option<&TeamBalance> {
+        self.teams.iter().find(|t| t.team == team)
+    }
+
+    /// Returns the team with the highest score, or `None` if no team has a
+    /// positive score or the top score is shared.
+    #[must_use]
+    pub fn leader(&self) -> Option<&TeamBalance> {
+        let best = self
+            .teams
+            .iter()
+            .max_by(|a, b| a.score.total_cmp(&b.score))?;
+        let tied = self.teams.iter().filter(|t| t.score >= best.score).count() > 1;
+        (best.score > 0.0 && !tied).then_some(best)
+    }
+
+    /// Returns true if some team's win probability is at least `threshold`.
+    #[must_use]
+    pub fn is_one_sided(&self, threshold: f32) -> bool {
+        self.teams.iter().any(|t| t.win_probability >= threshold)
+    }
+}
+
+/// A unit taking part in the evaluation.
+struct Unit {
+    id: EntityId,
+    hp: f32,
+    firepower: f32,
+}
+
+/// Evaluator producing [`BalanceReport`]s from an arena.
+#[derive(Debug, Clone, Default)]
+pub struct BalanceEvaluator {
+    config: BalanceConfig,
+}
+
+impl BalanceEvaluator {
+    /// Creates an evaluator with the default configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_config(BalanceConfig::default())
+    }
+
+    /// Creates an evaluator with a custom configuration.
+    #[must_use]
+    pub const fn with_config(config: BalanceConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the evaluator configuration.
+    #[must_use]
+    pub const fn config(&self) -> &BalanceConfig {
+        &self.config
+    }
+
+    /// Scores every team in `arena`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn evaluate(&self, arena: &Arena) -> BalanceReport {
+        let mut teams: BTreeMap<TeamId, Vec<Unit>> = BTreeMap::new();
+        let mut tracked: BTreeMap<TeamId, BTreeSet<EntityId>> = BTreeMap::new();
+        for entity in arena.entities_sorted() {
+            let Some(team) = entity.team() else {
+                continue;
+            };
+            let units = teams.entry(team).or_default();
+            if let Some(unit) = self.unit(entity) {
+                units.push(unit);
+                if let Some(sensor) = Self::sensor(entity) {
+                    tracked
+                        .entry(team)
+                        .or_default()
+                        .extend(sensor.track_table.iter().map(|t| t.target_id));
+                }
+            }
+        }
+
+        let mut balances: Vec<TeamBalance> = teams
+            .iter()
+            .map(|(&team, units)| {
+                let enemies: Vec<EntityId> = teams
+                    .iter()
+                    .filter(|(&other, _)| other != team)
+                    .flat_map(|(_, units)| units.iter().map(|u| u.id))
+                    .collect();
+                let positioning = match tracked.get(&team) {
+                    Some(seen) if !enemies.is_empty() => {
+                        enemies.iter().filter(|id| seen.contains(id)).count() as f32
+                            / enemies.len() as f32
+                    }
+                    _ => 0.0,
+                };
+                let firepower: f32 = units.iter().map(|u| u.hp * u.firepower).sum();
+                TeamBalance {
+                    team,
+                    units: units.len(),
+                    hp: units.iter().map(|u| u.hp).sum(),
+                    firepower,
+                    positioning,
+                    score: firepower * self.config.positioning_weight.mul_add(positioning, 1.0),
+                    win_probability: 0.0,
+                }
+            })
+            .collect();
+
+        let total: f32 = balances.iter().map(|t| t.score * t.score).sum();
+        let count = balances.len() as f32;
+        for balance in &mut balances {
+            balance.win_probability = if total > 0.0 {
+                balance.score * balance.score / total
+            } else {
+                1.0 / count
+            };
+        }
+
+        BalanceReport {
+            tick: arena.current_tick(),
+            teams: balances,
+        }
+    }
+
+    /// Returns the unit view of an entity, if it is still in the fight.
+    #[allow(clippy::cast_precision_loss)]
+    fn unit(&self, entity: &Entity) -> Option<Unit> {
+        let (combat, inventory): (&CombatState, Option<&InventoryState>) = match entity.inner() {
+            EntityInner::Ship(c) => (&c.combat, Some(&c.inventory)),
+            EntityInner::Squadron(c) => (&c.combat, None),
+            EntityInner::Platform(_) | EntityInner::Projectile(_) => return None,
+        };
+        if combat.is_destroyed()
+            || combat.hp <= 0.0
+            || combat.status_flags.contains(StatusFlags::SURRENDERED)
+        {
+            return None;
+        }
+        let ready = if combat.status_flags.contains(StatusFlags::WEAPONS_DISABLED) {
+            0
+        } else {
+            combat
+                .weapons
+                .iter()
+                .filter(|w| w.operational)
+                .filter(|w| inventory.is_none_or(|i| i.get_ammo(w.ammo_type) > 0))
+                .count()
+        };
+        Some(Unit {
+            id: entity.id(),
+            hp: combat.hp,
+            firepower: self.config.base_firepower + ready as f32,
+        })
+    }
+
+    /// Returns the sensors of a unit, unless they are disabled.
+    fn sensor(entity: &Entity) -> Option<&SensorState> {
+        let EntityInner::Ship(c) = entity.inner() else {
+            return None;
+        };
+        (!c.combat.status_flags.contains(StatusFlags::SENSORS_DISABLED)).then_some(&c.sensor)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::{AmmoType, Track, TrackQuality, WeaponState};
+    use crate::entity::{EntityTag, ShipComponents, SquadronComponents};
+    use crate::tests::spawn_team_ship;
+    use glam::Vec2;
+
+    fn combat(arena: &mut Arena, id: EntityId) -> &mut CombatState {
+        &mut arena.get_mut(id).unwrap().as_ship_mut().unwrap().combat
+    }
+
+    mod scoring_tests {
+        use super::*;
+
+        #[test]
+        fn empty_arena_has_no_teams() {
+            let report = BalanceEvaluator::new().evaluate(&Arena::new());
+
+            assert!(report.teams.is_empty());
+            assert!(report.leader().is_none());
+            assert!(!report.is_one_sided(0.5));
+        }
+
+        #[test]
+        fn mirror_teams_are_even() {
+            let mut arena = Arena::new();
+            spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(1));
+
+            let report = BalanceEvaluator::new().evaluate(&arena);
+
+            assert_eq!(report.teams.len(), 2);
+            assert!((report.teams[0].win_probability - 0.5).abs() < 1e-6);
+            assert!(report.leader().is_none());
+        }
+
+        #[test]
+        fn damaged_team_is_weaker() {
+            let mut arena = Arena::new();
+            spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            let hurt = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(1));
+            combat(&mut arena, hurt).hp = 50.0;
+
+            let report = BalanceEvaluator::new().evaluate(&arena);
+
+            assert_eq!(report.leader().map(|t| t.team), Some(TeamId::new(0)));
+            // Square law: half the strength, a fifth of the odds
+            let red = report.get(TeamId::new(1)).unwrap();
+            assert!((red.win_probability - 0.2).abs() < 1e-6);
+        }
+
+        #[test]
+        fn weapons_without_ammo_do_not_count() {
+            let mut arena = Arena::new();
+            let armed = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            let dry = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(1));
+            for id in [armed, dry] {
+                combat(&mut arena, id).weapons.push(WeaponState::new(0, 1.0, AmmoType::Shell));
+            }
+            let ship = arena.get_mut(armed).unwrap().as_ship_mut().unwrap();
+            ship.inventory.ammo.insert(AmmoType::Shell, 10);
+
+            let report = BalanceEvaluator::new().evaluate(&arena);
+
+            let blue = report.get(TeamId::new(0)).unwrap();
+            let red = report.get(TeamId::new(1)).unwrap();
+            assert!((blue.firepower - 2.0 * red.firepower).abs() < 1e-3);
+        }
+
+        #[test]
+        fn destroyed_team_is_reported_with_zero_score() {
+            let mut arena = Arena::new();
+            spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            let sunk = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(1));
+            combat(&mut arena, sunk).status_flags.insert(StatusFlags::DESTROYED);
+
+            let report = BalanceEvaluator::new().evaluate(&arena);
+
+            let red = report.get(TeamId::new(1)).unwrap();
+            assert_eq!(red.units, 0);
+            assert!(red.score.abs() < f32::EPSILON);
+            assert!(report.is_one_sided(0.99));
+        }
+
+        #[test]
+        fn tracking_enemies_improves_positioning() {
+            let mut arena = Arena::new();
+            let scout = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            let enemy = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(1));
+            let ship = arena.get_mut(scout).unwrap().as_ship_mut().unwrap();
+            ship.sensor.track_table.push(Track::new(
+                enemy,
+                Vec2::new(100.0, 0.0),
+                TrackQuality::FireControl,
+            ));
+
+            let report = BalanceEvaluator::new().evaluate(&arena);
+
+            let blue = report.get(TeamId::new(0)).unwrap();
+            assert!((blue.positioning - 1.0).abs() < f32::EPSILON);
+            assert!((blue.score - 1.5 * blue.firepower).abs() < 1e-3);
+            assert!(report.get(TeamId::new(1)).unwrap().positioning.abs() < f32::EPSILON);
+        }
+
+        #[test]
+        fn squadrons_count_as_units() {
+            let mut arena = Arena::new();
+            let squadron = SquadronComponents::at_position(Vec2::ZERO, 0.0).with_craft_count(4, 25.0);
+            let id = arena.spawn(EntityTag::Squadron, EntityInner::Squadron(squadron));
+            arena.set_team(id, Some(TeamId::new(3)));
+            arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+            );
+
+            let report = BalanceEvaluator::new().evaluate(&arena);
+
+            assert_eq!(report.teams.len(), 1);
+            assert!((report.teams[0].hp - 100.0).abs() < 1e-3);
+            assert!((report.teams[0].win_probability - 1.0).abs() < f32::EPSILON);
+        }
+    }
+}
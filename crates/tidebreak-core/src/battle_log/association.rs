@@ -0,0 +1,429 @@
+//! Track-to-truth association metrics: OSPA, track purity and fragmentation.
+//!
+//! A track's target ID is the sensor model's own claim about what it is
+//! tracking, so scoring against it flatters a noisy picture. The
+//! [`AssociationEvaluator`] instead associates each team's tracks with the
+//! true opposing entities by position, using the assignment that minimizes
+//! the OSPA distance, and scores the result:
+//!
+//! - **OSPA**: the optimal sub-pattern assignment distance between the
+//!   tracks and the truths, with distances capped at
+//!   [`AssociationConfig::cutoff`] and each missed or false track costing
+//!   the cutoff. It splits into a localization and a cardinality component.
+//! - **Purity**: over the run so far, the share of each track's
+//!   associations that went to the truth it was most often associated with.
+//! - **Fragmentation**: over the run so far, how often a truth that was
+//!   associated with a track on the previous evaluated tick lost that
+//!   association, to another track or to none, while still present.
+//!
+//! Only pairs closer than the cutoff count as associated. Tracks are told
+//! apart by their target ID, which is what the team would use to label them.
+//!
+//! With [`BattleLogConfig::fog_of_war`](super::BattleLogConfig) set, the
+//! battle log runs an evaluator with the default configuration every tick
+//! and writes its output to `association.arrows`.
+//!
+//! # Example
+//!
+//! ```
+//! use glam::Vec2;
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::battle_log::association::{AssociationConfig, AssociationEvaluator};
+//! use tidebreak_core::battle_log::fog::FogEvaluator;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+//! use tidebreak_core::entity::{Track, TrackQuality};
+//!
+//! let mut arena = Arena::new();
+//! let blue = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+//! let red = arena.spawn(
+//!     EntityTag::Ship,
+//!     EntityInner::Ship(ShipComponents::at_position(Vec2::new(1000.0, 0.0), 0.0)),
+//! );
+//! arena.set_team(blue, Some(TeamId::new(0)));
+//! arena.set_team(red, Some(TeamId::new(1)));
+//! let sensor = &mut arena.get_mut(blue).unwrap().as_ship_mut().unwrap().sensor;
+//! sensor.track_table.push(Track::new(red, Vec2::new(1003.0, 4.0), TrackQuality::Coarse));
+//!
+//! let report = FogEvaluator::new().evaluate(0, &arena);
+//! let mut evaluator = AssociationEvaluator::new(AssociationConfig::default());
+//! let blue_metrics = &evaluator.evaluate(&arena, &report.tracks)[0];
+//! assert_eq!((blue_metrics.tracks, blue_metrics.associated), (1, 1));
+//! assert_eq!(blue_metrics.ospa, 5.0);
+//! assert_eq!(blue_metrics.purity, Some(1.0));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use glam::Vec2;
+
+use crate::arena::Arena;
+use crate::entity::{Entity, EntityId, TeamId};
+
+use super::fog::{position, BelievedTrack};
+
+/// Default OSPA cutoff distance, in meters.
+pub const DEFAULT_CUTOFF: f32 = 500.0;
+
+/// Default OSPA order.
+pub const DEFAULT_ORDER: f32 = 2.0;
+
+/// OSPA parameters, which also gate association.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AssociationConfig {
+    /// Largest distance at which a track and a truth are associated, and
+    /// the cost of a missed truth or a false track.
+    pub cutoff: f32,
+    /// OSPA order `p`; higher orders weigh outlying errors more.
+    pub order: f32,
+}
+
+impl Default for AssociationConfig {
+    fn default() -> Self {
+        Self {
+            cutoff: DEFAULT_CUTOFF,
+            order: DEFAULT_ORDER,
+        }
+    }
+}
+
+/// One team's association metrics for one tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssociationMetrics {
+    /// Team the metrics describe.
+    pub team: TeamId,
+    /// Opposing entities present.
+    pub truths: usize,
+    /// Tracks the team holds, including those of despawned targets.
+    pub tracks: usize,
+    /// Track-truth pairs closer than the cutoff.
+    pub associated: usize,
+    /// OSPA distance; 0.0 with neither tracks nor truths.
+    pub ospa: f32,
+    /// Localization component of the OSPA distance.
+    pub localization: f32,
+    /// Cardinality component of the OSPA distance.
+    pub cardinality: f32,
+    /// Association-weighted track purity over the run so far, if any track
+    /// has been associated.
+    pub purity: Option<f32>,
+    /// Fragmentations over the run so far.
+    pub fragmentations: usize,
+}
+
+/// Scores each team's tracks against ground truth by optimal association.
+///
+/// Keeps per-team association history between calls; evaluate ticks in
+/// order.
+#[derive(Debug, Clone, Default)]
+pub struct AssociationEvaluator {
+    config: AssociationConfig,
+    /// Associations of each track so far, by truth.
+    history: BTreeMap<(TeamId, EntityId), BTreeMap<EntityId, u64>>,
+    /// The track each truth was associated with on the previous tick.
+    previous: BTreeMap<(TeamId, EntityId), Option<EntityId>>,
+    fragmentations: BTreeMap<TeamId, usize>,
+}
+
+impl AssociationEvaluator {
+    /// Creates an evaluator with no history.
+    #[must_use]
+    pub fn new(config: AssociationConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &AssociationConfig {
+        &self.config
+    }
+
+    /// Evaluates every team's `tracks` (e.g. a [`FogReport`]'s) against
+    /// `arena`, the true state at the same tick.
+    ///
+    /// [`FogReport`]: super::fog::FogReport
+    pub fn evaluate(
+        &mut self,
+        arena: &Arena,
+        tracks: &[BelievedTrack],
+    ) -> Vec<AssociationMetrics> {
+        let teams: BTreeSet<TeamId> = arena.entities_sorted().filter_map(Entity::team).collect();
+        let mut seen = BTreeSet::new();
+        let mut metrics = Vec::with_capacity(teams.len());
+
+        for &team in &teams {
+            let held: Vec<(EntityId, Vec2)> = tracks
+                .iter()
+                .filter(|t| t.team == team)
+                .map(|t| (t.track.target_id, t.track.position))
+                .collect();
+            let truths: Vec<(EntityId, Vec2)> = arena
+                .entities_sorted()
+                .filter(|e| e.team().is_some_and(|t| t != team))
+                .map(|e| (e.id(), position(e)))
+                .collect();
+            let AssociationConfig { cutoff, order } = self.config;
+            let pairs = assign(&held, &truths, |a, b| {
+                f64::from(a.distance(b).min(cutoff)).powf(f64::from(order))
+            });
+            let mut team_metrics = self.ospa(team, &held, &truths, &pairs);
+
+            let mut associated: BTreeMap<EntityId, EntityId> = BTreeMap::new();
+            for &(track, truth) in &pairs {
+                let (label, at) = held[track];
+                let (id, true_at) = truths[truth];
+                if at.distance(true_at) < self.config.cutoff {
+                    associated.insert(id, label);
+                    *self.history.entry((team, label)).or_default().entry(id).or_insert(0) += 1;
+                }
+            }
+            team_metrics.associated = associated.len();
+
+            let fragmentations = self.fragmentations.entry(team).or_insert(0);
+            for &(id, _) in &truths {
+                let now = associated.get(&id).copied();
+                let before = self.previous.insert((team, id), now).flatten();
+                if before.is_some_and(|label| now != Some(label)) {
+                    *fragmentations += 1;
+                }
+                seen.insert((team, id));
+            }
+            team_metrics.fragmentations = *fragmentations;
+            team_metrics.purity = self.purity(team);
+            metrics.push(team_metrics);
+        }
+
+        // Forget despawned truths; a truth that returns starts afresh
+        self.previous.retain(|key, _| seen.contains(key));
+        metrics
+    }
+
+    /// Computes the OSPA distance and its components for one team.
+    #[allow(clippy::cast_precision_loss)]
+    fn ospa(
+        &self,
+        team: TeamId,
+        held: &[(EntityId, Vec2)],
+        truths: &[(EntityId, Vec2)],
+        pairs: &[(usize, usize)],
+    ) -> AssociationMetrics {
+        let AssociationConfig { cutoff, order } = self.config;
+        let n = held.len().max(truths.len());
+        let (localization, cardinality) = if n == 0 {
+            (0.0, 0.0)
+        } else {
+            let located: f32 = pairs
+                .iter()
+                .map(|&(track, truth)| {
+                    let distance = held[track].1.distance(truths[truth].1);
+                    distance.min(cutoff).powf(order)
+                })
+                .sum();
+            let missing = (n - pairs.len()) as f32 * cutoff.powf(order);
+            (located / n as f32, missing / n as f32)
+        };
+        let root = |x: f32| x.powf(order.recip());
+        AssociationMetrics {
+            team,
+            truths: truths.len(),
+            tracks: held.len(),
+            associated: 0,
+            ospa: root(localization + cardinality),
+            localization: root(localization),
+            cardinality: root(cardinality),
+            purity: None,
+            fragmentations: 0,
+        }
+    }
+
+    /// Returns a team's association-weighted track purity so far.
+    #[allow(clippy::cast_precision_loss)]
+    fn purity(&self, team: TeamId) -> Option<f32> {
+        let (pure, total) = self
+            .history
+            .range((team, EntityId::new(0))..=(team, EntityId::new(u64::MAX)))
+            .fold((0, 0), |(pure, total), (_, counts)| {
+                let best = counts.values().copied().max().unwrap_or(0);
+                (pure + best, total + counts.values().sum::<u64>())
+            });
+        (total > 0).then(|| pure as f32 / total as f32)
+    }
+}
+
+/// Pairs `tracks` with `truths`, as `(track, truth)` indices, minimizing the
+/// total `cost` of the pairs' positions; every member of the smaller set is
+/// paired.
+///
+/// Uses the Hungarian algorithm with potentials, O(n²m).
+fn assign(
+    tracks: &[(EntityId, Vec2)],
+    truths: &[(EntityId, Vec2)],
+    cost: impl Fn(Vec2, Vec2) -> f64,
+) -> Vec<(usize, usize)> {
+    let transposed = tracks.len() > truths.len();
+    let (rows, cols) = if transposed {
+        (truths, tracks)
+    } else {
+        (tracks, truths)
+    };
+    let (n, m) = (rows.len(), cols.len());
+    if n == 0 {
+        return Vec::new();
+    }
+    let cost = |i: usize, j: usize| cost(rows[i].1, cols[j].1);
+
+    // 1-based, with column 0 as the free row being inserted
+    let mut row_potential = vec![0.0; n + 1];
+    let mut col_potential = vec![0.0; m + 1];
+    let mut owner = vec![0_usize; m + 1];
+    let mut way = vec![0_usize; m + 1];
+    for row in 1..=n {
+        owner[0] = row;
+        let mut col = 0;
+        let mut min_to = vec![f64::INFINITY; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[col] = true;
+            let i = owner[col];
+            let (mut delta, mut next) = (f64::INFINITY, 0);
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let reduced = cost(i - 1, j - 1) - row_potential[i] - col_potential[j];
+                if reduced < min_to[j] {
+                    min_to[j] = reduced;
+                    way[j] = col;
+                }
+                if min_to[j] < delta {
+                    delta = min_to[j];
+                    next = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    row_potential[owner[j]] += delta;
+                    col_potential[j] -= delta;
+                } else {
+                    min_to[j] -= delta;
+                }
+            }
+            col = next;
+            if owner[col] == 0 {
+                break;
+            }
+        }
+        while col != 0 {
+            let previous = way[col];
+            owner[col] = owner[previous];
+            col = previous;
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = (1..=m)
+        .filter(|&j| owner[j] != 0)
+        .map(|j| {
+            let (row, col) = (owner[j] - 1, j - 1);
+            if transposed {
+                (col, row)
+            } else {
+                (row, col)
+            }
+        })
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle_log::fog::FogEvaluator;
+    use crate::entity::{Track, TrackQuality};
+    use crate::tests::spawn_team_ship;
+
+    fn set_tracks(arena: &mut Arena, observer: EntityId, targets: &[(EntityId, f32)]) {
+        let sensor = &mut arena.get_mut(observer).unwrap().as_ship_mut().unwrap().sensor;
+        sensor.track_table = targets
+            .iter()
+            .map(|&(target, x)| Track::new(target, Vec2::new(x, 0.0), TrackQuality::Coarse))
+            .collect();
+    }
+
+    fn blue(evaluator: &mut AssociationEvaluator, arena: &Arena) -> AssociationMetrics {
+        let report = FogEvaluator::new().evaluate(0, arena);
+        evaluator.evaluate(arena, &report.tracks)[0].clone()
+    }
+
+    #[test]
+    fn assignment_is_optimal_not_greedy() {
+        let at = |x: f32| (EntityId::new(0), Vec2::new(x, 0.0));
+        let distance = |a: Vec2, b: Vec2| f64::from(a.distance(b));
+        // Greedy would pair 0 with 1 (distance 1) and leave 0 with 9
+        let pairs = assign(&[at(0.0), at(2.0)], &[at(1.0), at(-8.0)], distance);
+        assert_eq!(pairs, vec![(0, 1), (1, 0)]);
+        let pairs = assign(&[at(0.0), at(5.0), at(9.0)], &[at(8.0)], distance);
+        assert_eq!(pairs, vec![(2, 0)]);
+        assert_eq!(assign(&[], &[at(1.0)], distance), vec![]);
+    }
+
+    #[test]
+    fn ospa_charges_misses_and_false_tracks_the_cutoff() {
+        let mut arena = Arena::new();
+        let observer = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let red_a = spawn_team_ship(&mut arena, Vec2::new(1000.0, 0.0), Some(1));
+        spawn_team_ship(&mut arena, Vec2::new(5000.0, 0.0), Some(1));
+        let config = AssociationConfig {
+            cutoff: 100.0,
+            order: 1.0,
+        };
+        let mut evaluator = AssociationEvaluator::new(config);
+
+        let none = blue(&mut evaluator, &arena);
+        assert_eq!((none.ospa, none.cardinality, none.localization), (100.0, 100.0, 0.0));
+        assert_eq!(none.purity, None);
+
+        // One track 10 m off, one missed truth
+        set_tracks(&mut arena, observer, &[(red_a, 1010.0)]);
+        let one = blue(&mut evaluator, &arena);
+        assert_eq!((one.associated, one.localization, one.cardinality), (1, 5.0, 50.0));
+        assert_eq!(one.ospa, 55.0);
+
+        // A track far from every truth is paired but not associated
+        set_tracks(&mut arena, observer, &[(red_a, 3000.0)]);
+        let far = blue(&mut evaluator, &arena);
+        assert_eq!((far.associated, far.ospa), (0, 100.0));
+    }
+
+    #[test]
+    fn swapped_labels_lower_purity_and_fragment() {
+        let mut arena = Arena::new();
+        let observer = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let red_a = spawn_team_ship(&mut arena, Vec2::new(1000.0, 0.0), Some(1));
+        let red_b = spawn_team_ship(&mut arena, Vec2::new(2000.0, 0.0), Some(1));
+        let mut evaluator = AssociationEvaluator::new(AssociationConfig::default());
+
+        set_tracks(&mut arena, observer, &[(red_a, 1000.0), (red_b, 2000.0)]);
+        blue(&mut evaluator, &arena);
+        blue(&mut evaluator, &arena);
+        // The tracks swap targets: both truths fragment and each track has
+        // one of three associations elsewhere
+        set_tracks(&mut arena, observer, &[(red_a, 2000.0), (red_b, 1000.0)]);
+        let swapped = blue(&mut evaluator, &arena);
+        assert_eq!(swapped.fragmentations, 2);
+        assert_eq!(swapped.ospa, 0.0);
+        assert_eq!(swapped.purity, Some(4.0 / 6.0));
+
+        // Losing a truth's track fragments it; its despawn does not
+        set_tracks(&mut arena, observer, &[(red_a, 2000.0)]);
+        assert_eq!(blue(&mut evaluator, &arena).fragmentations, 3);
+        arena.despawn(red_b);
+        assert_eq!(blue(&mut evaluator, &arena).fragmentations, 3);
+    }
+}
@@ -0,0 +1,314 @@
+//! Minimal flatbuffers encoder for Arrow IPC metadata.
+//!
+//! Arrow IPC messages carry their metadata as flatbuffers. Only a handful of
+//! tables are needed to write a stream (`Message`, `Schema`, `Field`, the type
+//! tables and `RecordBatch`), so instead of a code generator this module
+//! encodes an object tree directly.
+//!
+//! The buffer is written front to back: each table is preceded by its vtable,
+//! and children are written after their parent with the parent's offset slot
+//! patched once the child's position is known. All offsets therefore point
+//! forward, as flatbuffers requires.
+
+/// Value stored in a table field.
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    /// `ubyte` (also used for union type tags).
+    U8(u8),
+    /// `bool`.
+    Bool(bool),
+    /// `short` (also used for `short`-backed enums).
+    I16(i16),
+    /// `int`.
+    I32(i32),
+    /// `long`.
+    I64(i64),
+    /// Offset to a child object.
+    Offset(Object),
+}
+
+impl Value {
+    /// Inline size (and alignment) of the field.
+    const fn size(&self) -> usize {
+        match self {
+            Self::U8(_) | Self::Bool(_) => 1,
+            Self::I16(_) => 2,
+            Self::I32(_) | Self::Offset(_) => 4,
+            Self::I64(_) => 8,
+        }
+    }
+}
+
+/// Object referenced by an offset.
+#[derive(Debug, Clone)]
+pub(crate) enum Object {
+    /// Table with `(field id, value)` pairs.
+    Table(Vec<(u16, Value)>),
+    /// UTF-8 string.
+    String(String),
+    /// Vector of tables.
+    Tables(Vec<Object>),
+    /// Vector of structs, already encoded; `align` is the struct alignment.
+    Structs {
+        /// Number of structs.
+        len: u32,
+        /// Struct alignment in bytes.
+        align: usize,
+        /// Little-endian struct bytes.
+        bytes: Vec<u8>,
+    },
+}
+
+/// Encodes `root` (which must be a table) into a finished flatbuffer.
+///
+/// The result is padded to a multiple of 8 bytes.
+pub(crate) fn finish(root: Object) -> Vec<u8> {
+    let mut builder = Builder { buf: vec![0; 4] };
+    let root_pos = builder.write_object(root);
+    builder.patch_offset(0, root_pos);
+    builder.pad_to(8);
+    builder.buf
+}
+
+struct Builder {
+    buf: Vec<u8>,
+}
+
+impl Builder {
+    fn pad_to(&mut self, align: usize) {
+        while !self.buf.len().is_multiple_of(align) {
+            self.buf.push(0);
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn patch_offset(&mut self, slot: usize, target: usize) {
+        let offset = (target - slot) as u32;
+        self.buf[slot..slot + 4].copy_from_slice(&offset.to_le_bytes());
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn write_object(&mut self, object: Object) -> usize {
+        match object {
+            Object::String(s) => {
+                self.pad_to(4);
+                let pos = self.buf.len();
+                self.buf.extend((s.len() as u32).to_le_bytes());
+                self.buf.extend(s.as_bytes());
+                self.buf.push(0);
+                pos
+            }
+            Object::Structs { len, align, bytes } => {
+                // The length prefix sits immediately before the first element.
+                self.pad_to(4);
+                while !(self.buf.len() + 4).is_multiple_of(align) {
+                    self.buf.extend([0; 4]);
+                }
+                let pos = self.buf.len();
+                self.buf.extend(len.to_le_bytes());
+                self.buf.extend(bytes);
+                pos
+            }
+            Object::Tables(children) => {
+                self.pad_to(4);
+                let pos = self.buf.len();
+                self.buf.extend((children.len() as u32).to_le_bytes());
+                let slots = self.buf.len();
+                self.buf.resize(slots + children.len() * 4, 0);
+                for (i, child) in children.into_iter().enumerate() {
+                    let child_pos = self.write_object(child);
+                    self.patch_offset(slots + i * 4, child_pos);
+                }
+                pos
+            }
+            Object::Table(fields) => self.write_table(fields),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn write_table(&mut self, fields: Vec<(u16, Value)>) -> usize {
+        // Lay out fields after the 4-byte vtable offset, each aligned to its
+        // size relative to the (8-aligned) table start.
+        let mut layout = Vec::with_capacity(fields.len());
+        let mut end = 4_usize;
+        for (_, value) in &fields {
+            let size = value.size();
+            end = end.next_multiple_of(size);
+            layout.push(end);
+            end += size;
+        }
+
+        // vtable: [vtable size, table size, field offsets by id...]
+        let slots = fields
+            .iter()
+            .map(|(id, _)| usize::from(*id) + 1)
+            .max()
+            .unwrap_or(0);
+        let mut vtable = vec![0_u16; 2 + slots];
+        vtable[0] = (4 + 2 * slots) as u16;
+        vtable[1] = end as u16;
+        for ((id, _), offset) in fields.iter().zip(&layout) {
+            vtable[2 + usize::from(*id)] = *offset as u16;
+        }
+
+        self.pad_to(2);
+        let vtable_pos = self.buf.len();
+        for entry in vtable {
+            self.buf.extend(entry.to_le_bytes());
+        }
+
+        self.pad_to(8);
+        let table_pos = self.buf.len();
+        self.buf.resize(table_pos + end, 0);
+        let soffset = (table_pos - vtable_pos) as i32;
+        self.buf[table_pos..table_pos + 4].copy_from_slice(&soffset.to_le_bytes());
+
+        let mut children = Vec::new();
+        for ((_, value), offset) in fields.into_iter().zip(layout) {
+            let at = table_pos + offset;
+            match value {
+                Value::U8(v) => self.buf[at] = v,
+                Value::Bool(v) => self.buf[at] = u8::from(v),
+                Value::I16(v) => self.buf[at..at + 2].copy_from_slice(&v.to_le_bytes()),
+                Value::I32(v) => self.buf[at..at + 4].copy_from_slice(&v.to_le_bytes()),
+                Value::I64(v) => self.buf[at..at + 8].copy_from_slice(&v.to_le_bytes()),
+                Value::Offset(child) => children.push((at, child)),
+            }
+        }
+        for (slot, child) in children {
+            let child_pos = self.write_object(child);
+            self.patch_offset(slot, child_pos);
+        }
+        table_pos
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+pub(crate) mod reader {
+    //! Just enough of a flatbuffers reader to check encoded metadata.
+
+    pub fn u16_at(buf: &[u8], pos: usize) -> u16 {
+        u16::from_le_bytes([buf[pos], buf[pos + 1]])
+    }
+
+    pub fn u32_at(buf: &[u8], pos: usize) -> u32 {
+        u32::from_le_bytes(buf[pos..pos + 4].try_into().unwrap())
+    }
+
+    pub fn i64_at(buf: &[u8], pos: usize) -> i64 {
+        i64::from_le_bytes(buf[pos..pos + 8].try_into().unwrap())
+    }
+
+    /// Follows the uoffset stored at `pos`.
+    pub fn deref(buf: &[u8], pos: usize) -> usize {
+        pos + u32_at(buf, pos) as usize
+    }
+
+    /// Root table position.
+    pub fn root(buf: &[u8]) -> usize {
+        deref(buf, 0)
+    }
+
+    /// Absolute position of field `id` of the table at `table`, if present.
+    pub fn field(buf: &[u8], table: usize, id: usize) -> Option<usize> {
+        let soffset = i32::from_le_bytes(buf[table..table + 4].try_into().unwrap());
+        let vtable = usize::try_from(i64::try_from(table).unwrap() - i64::from(soffset)).unwrap();
+        let vtable_size = usize::from(u16_at(buf, vtable));
+        let slot = 4 + 2 * id;
+        if slot >= vtable_size {
+            return None;
+        }
+        match u16_at(buf, vtable + slot) {
+            0 => None,
+            offset => Some(table + usize::from(offset)),
+        }
+    }
+
+    /// Reads the string referenced by the offset at `pos`.
+    pub fn string(buf: &[u8], pos: usize) -> String {
+        let at = deref(buf, pos);
+        let len = u32_at(buf, at) as usize;
+        assert_eq!(buf[at + 4 + len], 0, "string must be NUL-terminated");
+        String::from_utf8(buf[at + 4..at + 4 + len].to_vec()).unwrap()
+    }
+
+    /// Positions of the tables in the vector referenced by the offset at `pos`.
+    pub fn tables(buf: &[u8], pos: usize) -> Vec<usize> {
+        let at = deref(buf, pos);
+        let len = u32_at(buf, at) as usize;
+        (0..len).map(|i| deref(buf, at + 4 + 4 * i)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::reader::*;
+    use super::*;
+
+    #[test]
+    fn encodes_scalars_and_strings() {
+        let buf = finish(Object::Table(vec![
+            (0, Value::I16(4)),
+            (1, Value::U8(3)),
+            (2, Value::Offset(Object::String("tick".to_string()))),
+            (3, Value::I64(-7)),
+        ]));
+        let root = root(&buf);
+
+        assert_eq!(buf.len() % 8, 0);
+        assert_eq!(root % 8, 0);
+        assert_eq!(u16_at(&buf, field(&buf, root, 0).unwrap()), 4);
+        assert_eq!(buf[field(&buf, root, 1).unwrap()], 3);
+        assert_eq!(string(&buf, field(&buf, root, 2).unwrap()), "tick");
+        let long = field(&buf, root, 3).unwrap();
+        assert_eq!(long % 8, 0);
+        assert_eq!(i64_at(&buf, long), -7);
+        assert_eq!(field(&buf, root, 4), None);
+    }
+
+    #[test]
+    fn encodes_nested_tables() {
+        let child = |n: i32| Object::Table(vec![(0, Value::I32(n))]);
+        let buf = finish(Object::Table(vec![(
+            1,
+            Value::Offset(Object::Tables(vec![
+                child(1),
+                child(2),
+                Object::Table(vec![]),
+            ])),
+        )]));
+        let root = root(&buf);
+
+        assert_eq!(field(&buf, root, 0), None);
+        let children = tables(&buf, field(&buf, root, 1).unwrap());
+        assert_eq!(children.len(), 3);
+        assert_eq!(u32_at(&buf, field(&buf, children[1], 0).unwrap()), 2);
+        assert_eq!(field(&buf, children[2], 0), None);
+    }
+
+    #[test]
+    fn struct_vectors_are_aligned() {
+        let bytes: Vec<u8> = [5_i64, 6].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let buf = finish(Object::Table(vec![
+            (0, Value::U8(1)),
+            (
+                1,
+                Value::Offset(Object::Structs {
+                    len: 1,
+                    align: 8,
+                    bytes,
+                }),
+            ),
+        ]));
+        let at = deref(&buf, field(&buf, root(&buf), 1).unwrap());
+
+        assert_eq!(u32_at(&buf, at), 1);
+        assert_eq!((at + 4) % 8, 0);
+        assert_eq!(i64_at(&buf, at + 4), 5);
+        assert_eq!(i64_at(&buf, at + 12), 6);
+    }
+}
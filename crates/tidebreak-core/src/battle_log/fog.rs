@@ -0,0 +1,284 @@
+//! Fog-of-war evaluation: each team's believed picture against ground truth.
+//!
+//! The [`FogEvaluator`] builds every team's fused track picture (see
+//! [`TeamObservationBuilder`]) once per tick and scores it against the true
+//! state of the opposing entities, i.e. those on another team:
+//!
+//! - **Position error**: distance from each track to its target's true
+//!   position.
+//! - **Track continuity**: the fraction of opposing entities tracked on the
+//!   previous evaluated tick that are still tracked.
+//! - **Time to detect**: ticks from an opposing entity first being present to
+//!   the team first tracking it. Entities present when evaluation starts
+//!   count from that tick.
+//!
+//! With [`BattleLogConfig::fog_of_war`](super::BattleLogConfig) set, the
+//! battle log runs an evaluator every tick and writes its output to
+//! `tracks.arrows` and `fog.arrows`; the ground truth is `entities.arrows`.
+//!
+//! # Example
+//!
+//! ```
+//! use glam::Vec2;
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::battle_log::fog::FogEvaluator;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+//! use tidebreak_core::entity::{Track, TrackQuality};
+//!
+//! let mut arena = Arena::new();
+//! let blue = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+//! let red = arena.spawn(
+//!     EntityTag::Ship,
+//!     EntityInner::Ship(ShipComponents::at_position(Vec2::new(1000.0, 0.0), 0.0)),
+//! );
+//! arena.set_team(blue, Some(TeamId::new(0)));
+//! arena.set_team(red, Some(TeamId::new(1)));
+//! let sensor = &mut arena.get_mut(blue).unwrap().as_ship_mut().unwrap().sensor;
+//! sensor.track_table.push(Track::new(red, Vec2::new(1003.0, 4.0), TrackQuality::Coarse));
+//!
+//! let mut evaluator = FogEvaluator::new();
+//! let report = evaluator.evaluate(0, &arena);
+//! let blue_metrics = &report.metrics[0];
+//! assert_eq!((blue_metrics.opposing, blue_metrics.detected), (1, 1));
+//! assert_eq!(blue_metrics.mean_error, Some(5.0));
+//! assert_eq!(report.tracks[0].error, Some(5.0));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use glam::Vec2;
+
+use crate::arena::Arena;
+use crate::entity::{Entity, EntityId, EntityInner, TeamId};
+use crate::team_observation::{FusedTrack, TeamObservationBuilder};
+
+/// One track of a team's believed picture, scored against ground truth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BelievedTrack {
+    /// Team holding the track.
+    pub team: TeamId,
+    /// The team's fused track.
+    pub track: FusedTrack,
+    /// Distance to the target's true position, or `None` if the target no
+    /// longer exists.
+    pub error: Option<f32>,
+}
+
+/// One team's fog-of-war metrics for one tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FogMetrics {
+    /// Team the metrics describe.
+    pub team: TeamId,
+    /// Opposing entities present.
+    pub opposing: usize,
+    /// Opposing entities the team tracks.
+    pub detected: usize,
+    /// Opposing entities tracked for the first time this tick.
+    pub new_detections: usize,
+    /// Mean position error over the tracked opposing entities.
+    pub mean_error: Option<f32>,
+    /// Fraction of the opposing entities tracked on the previous evaluated
+    /// tick that are still tracked, if there were any.
+    pub continuity: Option<f32>,
+    /// Mean time to detect, in ticks, of this tick's new detections.
+    pub mean_time_to_detect: Option<f32>,
+}
+
+/// Everything evaluated for one tick, ordered by team.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FogReport {
+    /// Every team's tracks, by team and then target ID.
+    pub tracks: Vec<BelievedTrack>,
+    /// Every team's metrics.
+    pub metrics: Vec<FogMetrics>,
+}
+
+/// What one team has seen of one opposing entity so far.
+#[derive(Debug, Clone, Copy)]
+struct Sighting {
+    /// First tick the entity was present.
+    present_since: u64,
+    /// Whether the team has ever tracked it.
+    detected: bool,
+    /// Whether the team tracked it on the previous evaluated tick.
+    tracked: bool,
+}
+
+/// Scores each team's believed picture against ground truth over a run.
+///
+/// Keeps per-team detection history between calls; evaluate ticks in
+/// order.
+#[derive(Debug, Clone, Default)]
+pub struct FogEvaluator {
+    sightings: BTreeMap<(TeamId, EntityId), Sighting>,
+}
+
+impl FogEvaluator {
+    /// Creates an evaluator with no history.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluates every team's picture of `arena`, the state after `tick`.
+    pub fn evaluate(&mut self, tick: u64, arena: &Arena) -> FogReport {
+        let teams: BTreeSet<TeamId> = arena.entities_sorted().filter_map(Entity::team).collect();
+        let mut report = FogReport::default();
+        let mut seen = BTreeSet::new();
+
+        for &team in &teams {
+            let observation = TeamObservationBuilder::new(team).build(arena);
+            let tracked: BTreeMap<EntityId, &FusedTrack> = observation
+                .tracks
+                .iter()
+                .map(|track| (track.target_id, track))
+                .collect();
+
+            let mut metrics = FogMetrics {
+                team,
+                opposing: 0,
+                detected: 0,
+                new_detections: 0,
+                mean_error: None,
+                continuity: None,
+                mean_time_to_detect: None,
+            };
+            let (mut error_sum, mut was_tracked, mut kept) = (0.0, 0_usize, 0_usize);
+            let mut detect_ticks = 0;
+
+            for entity in arena.entities_sorted() {
+                if entity.team().is_none_or(|t| t == team) {
+                    continue;
+                }
+                let id = entity.id();
+                seen.insert((team, id));
+                metrics.opposing += 1;
+                let sighting = self.sightings.entry((team, id)).or_insert(Sighting {
+                    present_since: tick,
+                    detected: false,
+                    tracked: false,
+                });
+                let track = tracked.get(&id);
+                if sighting.tracked {
+                    was_tracked += 1;
+                    kept += usize::from(track.is_some());
+                }
+                sighting.tracked = track.is_some();
+                let Some(track) = track else {
+                    continue;
+                };
+                metrics.detected += 1;
+                error_sum += track.position.distance(position(entity));
+                if !sighting.detected {
+                    sighting.detected = true;
+                    metrics.new_detections += 1;
+                    detect_ticks += tick - sighting.present_since;
+                }
+            }
+
+            #[allow(clippy::cast_precision_loss)]
+            {
+                if metrics.detected > 0 {
+                    metrics.mean_error = Some(error_sum / metrics.detected as f32);
+                }
+                if was_tracked > 0 {
+                    metrics.continuity = Some(kept as f32 / was_tracked as f32);
+                }
+                if metrics.new_detections > 0 {
+                    metrics.mean_time_to_detect =
+                        Some(detect_ticks as f32 / metrics.new_detections as f32);
+                }
+            }
+            report.metrics.push(metrics);
+
+            report
+                .tracks
+                .extend(observation.tracks.iter().map(|track| BelievedTrack {
+                    team,
+                    track: *track,
+                    error: arena
+                        .get(track.target_id)
+                        .map(|target| track.position.distance(position(target))),
+                }));
+        }
+
+        // Forget despawned entities and teams that have left
+        self.sightings.retain(|key, _| seen.contains(key));
+        report
+    }
+}
+
+/// Returns the true position of an entity.
+pub(super) fn position(entity: &Entity) -> Vec2 {
+    match entity.inner() {
+        EntityInner::Ship(c) => c.transform.position,
+        EntityInner::Platform(c) => c.transform.position,
+        EntityInner::Projectile(c) => c.transform.position,
+        EntityInner::Squadron(c) => c.transform.position,
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{Track, TrackQuality};
+    use crate::tests::spawn_team_ship;
+
+    fn set_tracks(arena: &mut Arena, observer: EntityId, targets: &[(EntityId, f32)]) {
+        let sensor = &mut arena.get_mut(observer).unwrap().as_ship_mut().unwrap().sensor;
+        sensor.track_table = targets
+            .iter()
+            .map(|&(target, x)| Track::new(target, Vec2::new(x, 0.0), TrackQuality::Coarse))
+            .collect();
+    }
+
+    #[test]
+    fn time_to_detect_and_continuity() {
+        let mut arena = Arena::new();
+        let blue = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let red_a = spawn_team_ship(&mut arena, Vec2::new(1000.0, 0.0), Some(1));
+        let red_b = spawn_team_ship(&mut arena, Vec2::new(2000.0, 0.0), Some(1));
+        let mut evaluator = FogEvaluator::new();
+
+        let metrics = |report: FogReport| report.metrics[0].clone();
+        let first = metrics(evaluator.evaluate(10, &arena));
+        assert_eq!((first.opposing, first.detected), (2, 0));
+        assert_eq!(first.continuity, None);
+
+        set_tracks(&mut arena, blue, &[(red_a, 1010.0), (red_b, 2000.0)]);
+        let second = metrics(evaluator.evaluate(14, &arena));
+        assert_eq!(second.new_detections, 2);
+        assert_eq!(second.mean_time_to_detect, Some(4.0));
+        assert_eq!(second.mean_error, Some(5.0));
+
+        // Losing one of two tracks halves continuity; regaining it is not a
+        // new detection
+        set_tracks(&mut arena, blue, &[(red_a, 1000.0)]);
+        let third = metrics(evaluator.evaluate(15, &arena));
+        assert_eq!(third.continuity, Some(0.5));
+        set_tracks(&mut arena, blue, &[(red_a, 1000.0), (red_b, 2000.0)]);
+        let fourth = metrics(evaluator.evaluate(16, &arena));
+        assert_eq!((fourth.detected, fourth.new_detections), (2, 0));
+        assert_eq!(fourth.continuity, Some(1.0));
+    }
+
+    #[test]
+    fn stale_tracks_of_despawned_targets_have_no_error() {
+        let mut arena = Arena::new();
+        let blue = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let red = spawn_team_ship(&mut arena, Vec2::new(1000.0, 0.0), Some(1));
+        set_tracks(&mut arena, blue, &[(red, 1000.0)]);
+        arena.despawn(red);
+
+        let report = FogEvaluator::new().evaluate(0, &arena);
+        assert_eq!(report.tracks.len(), 1);
+        assert_eq!(report.tracks[0].error, None);
+        // Red has no entities left, so only blue is scored
+        assert_eq!(report.metrics.len(), 1);
+        assert_eq!(report.metrics[0].opposing, 0);
+    }
+}
@@ -0,0 +1,623 @@
+//! Arrow IPC streaming format writer.
+//!
+//! Writes the [Arrow IPC streaming format] (`.arrows`): a schema message
+//! followed by record batch messages and an end-of-stream marker. Only
+//! non-nested columns of the types the battle log needs are supported.
+//!
+//! Each message is framed as `0xFFFFFFFF`, the metadata length, the
+//! flatbuffer-encoded `Message`, then the body buffers, all 8-byte aligned.
+//!
+//! [Arrow IPC streaming format]: https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format
+
+use std::io::{self, Write};
+
+use super::flatbuf::{self, Object, Value};
+
+/// `MetadataVersion::V5`.
+const METADATA_V5: i16 = 4;
+/// `MessageHeader` union tags.
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_RECORD_BATCH: u8 = 3;
+/// `Type` union tags.
+const TYPE_INT: u8 = 2;
+const TYPE_FLOATING_POINT: u8 = 3;
+const TYPE_UTF8: u8 = 5;
+/// `Precision::SINGLE`.
+const PRECISION_SINGLE: i16 = 1;
+/// Continuation marker preceding every message.
+const CONTINUATION: [u8; 4] = [0xFF; 4];
+
+/// Logical column type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    /// Unsigned 32-bit integer.
+    UInt32,
+    /// Unsigned 64-bit integer.
+    UInt64,
+    /// 32-bit float.
+    Float32,
+    /// UTF-8 string.
+    Utf8,
+}
+
+impl DataType {
+    fn type_union(self) -> (u8, Object) {
+        match self {
+            Self::UInt32 => (TYPE_INT, int_type(32)),
+            Self::UInt64 => (TYPE_INT, int_type(64)),
+            Self::Float32 => (
+                TYPE_FLOATING_POINT,
+                Object::Table(vec![(0, Value::I16(PRECISION_SINGLE))]),
+            ),
+            Self::Utf8 => (TYPE_UTF8, Object::Table(vec![])),
+        }
+    }
+}
+
+fn int_type(bit_width: i32) -> Object {
+    Object::Table(vec![(0, Value::I32(bit_width)), (1, Value::Bool(false))])
+}
+
+/// Schema field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    /// Column name.
+    pub name: &'static str,
+    /// Column type.
+    pub data_type: DataType,
+    /// Whether the column may contain nulls.
+    pub nullable: bool,
+}
+
+impl Field {
+    /// Creates a non-nullable field.
+    #[must_use]
+    pub const fn new(name: &'static str, data_type: DataType) -> Self {
+        Self {
+            name,
+            data_type,
+            nullable: false,
+        }
+    }
+
+    /// Creates a nullable field.
+    #[must_use]
+    pub const fn nullable(name: &'static str, data_type: DataType) -> Self {
+        Self {
+            name,
+            data_type,
+            nullable: true,
+        }
+    }
+}
+
+/// Column values for one record batch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    /// `UInt32` values.
+    UInt32(Vec<Option<u32>>),
+    /// `UInt64` values.
+    UInt64(Vec<Option<u64>>),
+    /// `Float32` values.
+    Float32(Vec<Option<f32>>),
+    /// `Utf8` values.
+    Utf8(Vec<Option<String>>),
+}
+
+impl Column {
+    /// Number of values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::UInt32(v) => v.len(),
+            Self::UInt64(v) => v.len(),
+            Self::Float32(v) => v.len(),
+            Self::Utf8(v) => v.len(),
+        }
+    }
+
+    /// Returns true if the column has no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Type of the values.
+    #[must_use]
+    pub const fn data_type(&self) -> DataType {
+        match self {
+            Self::UInt32(_) => DataType::UInt32,
+            Self::UInt64(_) => DataType::UInt64,
+            Self::Float32(_) => DataType::Float32,
+            Self::Utf8(_) => DataType::Utf8,
+        }
+    }
+
+    /// Validity flags, one per value.
+    fn validity(&self) -> Vec<bool> {
+        match self {
+            Self::UInt32(v) => v.iter().map(Option::is_some).collect(),
+            Self::UInt64(v) => v.iter().map(Option::is_some).collect(),
+            Self::Float32(v) => v.iter().map(Option::is_some).collect(),
+            Self::Utf8(v) => v.iter().map(Option::is_some).collect(),
+        }
+    }
+
+    /// Appends the value buffers (after validity) to `body`.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn write_values(&self, body: &mut Body) {
+        match self {
+            Self::UInt32(v) => {
+                body.push(
+                    &v.iter()
+                        .flat_map(|x| x.unwrap_or(0).to_le_bytes())
+                        .collect::<Vec<_>>(),
+                );
+            }
+            Self::UInt64(v) => {
+                body.push(
+                    &v.iter()
+                        .flat_map(|x| x.unwrap_or(0).to_le_bytes())
+                        .collect::<Vec<_>>(),
+                );
+            }
+            Self::Float32(v) => {
+                body.push(
+                    &v.iter()
+                        .flat_map(|x| x.unwrap_or(0.0).to_le_bytes())
+                        .collect::<Vec<_>>(),
+                );
+            }
+            Self::Utf8(v) => {
+                let mut offsets = Vec::with_capacity((v.len() + 1) * 4);
+                let mut data = Vec::new();
+                offsets.extend(0_i32.to_le_bytes());
+                for s in v {
+                    data.extend(s.as_deref().unwrap_or("").as_bytes());
+                    offsets.extend((data.len() as i32).to_le_bytes());
+                }
+                body.push(&offsets);
+                body.push(&data);
+            }
+        }
+    }
+}
+
+/// Record batch body under construction.
+#[derive(Default)]
+struct Body {
+    bytes: Vec<u8>,
+    /// `(offset, length)` of each buffer.
+    buffers: Vec<(i64, i64)>,
+}
+
+impl Body {
+    #[allow(clippy::cast_possible_wrap)]
+    fn push(&mut self, data: &[u8]) {
+        self.buffers
+            .push((self.bytes.len() as i64, data.len() as i64));
+        self.bytes.extend_from_slice(data);
+        let padded = self.bytes.len().next_multiple_of(8);
+        self.bytes.resize(padded, 0);
+    }
+}
+
+/// Streaming writer for one Arrow IPC stream.
+#[derive(Debug)]
+pub struct StreamWriter<W: Write> {
+    inner: W,
+    fields: Vec<Field>,
+}
+
+impl<W: Write> StreamWriter<W> {
+    /// Creates a writer and writes the schema message.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from the underlying writer.
+    pub fn new(mut inner: W, fields: &[Field]) -> io::Result<Self> {
+        let schema = schema_message(fields);
+        write_message(&mut inner, &schema, &[])?;
+        Ok(Self {
+            inner,
+            fields: fields.to_vec(),
+        })
+    }
+
+    /// Returns the schema fields.
+    #[must_use]
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Writes one record batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if the columns do not match the schema (count,
+    /// types, equal lengths, or nulls in a non-nullable column), or any error
+    /// from the underlying writer.
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn write_batch(&mut self, columns: &[Column]) -> io::Result<()> {
+        let rows = self.validate(columns)?;
+
+        let mut body = Body::default();
+        let mut nodes = Vec::with_capacity(columns.len() * 16);
+        for column in columns {
+            let validity = column.validity();
+            let null_count = validity.iter().filter(|valid| !**valid).count();
+            nodes.extend((rows as i64).to_le_bytes());
+            nodes.extend((null_count as i64).to_le_bytes());
+
+            if null_count == 0 {
+                body.push(&[]);
+            } else {
+                let mut bitmap = vec![0_u8; rows.div_ceil(8)];
+                for (i, _) in validity.iter().enumerate().filter(|(_, valid)| **valid) {
+                    bitmap[i / 8] |= 1 << (i % 8);
+                }
+                body.push(&bitmap);
+            }
+            column.write_values(&mut body);
+        }
+
+        let buffers: Vec<u8> = body
+            .buffers
+            .iter()
+            .flat_map(|(offset, length)| {
+                offset.to_le_bytes().into_iter().chain(length.to_le_bytes())
+            })
+            .collect();
+        let batch = Object::Table(vec![
+            (0, Value::I64(rows as i64)),
+            (1, Value::Offset(structs(columns.len(), nodes))),
+            (2, Value::Offset(structs(body.buffers.len(), buffers))),
+        ]);
+        let message = message(HEADER_RECORD_BATCH, batch, body.bytes.len());
+        write_message(&mut self.inner, &message, &body.bytes)
+    }
+
+    /// Writes the end-of-stream marker and returns the inner writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.inner.write_all(&CONTINUATION)?;
+        self.inner.write_all(&0_i32.to_le_bytes())?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Checks `columns` against the schema and returns the row count.
+    fn validate(&self, columns: &[Column]) -> io::Result<usize> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+        if columns.len() != self.fields.len() {
+            return Err(invalid(format!(
+                "expected {} columns, got {}",
+                self.fields.len(),
+                columns.len()
+            )));
+        }
+        let rows = columns.first().map_or(0, Column::len);
+        for (field, column) in self.fields.iter().zip(columns) {
+            if column.data_type() != field.data_type {
+                return Err(invalid(format!(
+                    "column '{}' has the wrong type",
+                    field.name
+                )));
+            }
+            if column.len() != rows {
+                return Err(invalid(format!(
+                    "column '{}' has the wrong length",
+                    field.name
+                )));
+            }
+            if !field.nullable && column.validity().contains(&false) {
+                return Err(invalid(format!("column '{}' is not nullable", field.name)));
+            }
+        }
+        Ok(rows)
+    }
+}
+
+fn structs(len: usize, bytes: Vec<u8>) -> Object {
+    #[allow(clippy::cast_possible_truncation)]
+    Object::Structs {
+        len: len as u32,
+        align: 8,
+        bytes,
+    }
+}
+
+fn schema_message(fields: &[Field]) -> Vec<u8> {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let (type_tag, type_table) = field.data_type.type_union();
+            Object::Table(vec![
+                (0, Value::Offset(Object::String(field.name.to_string()))),
+                (1, Value::Bool(field.nullable)),
+                (2, Value::U8(type_tag)),
+                (3, Value::Offset(type_table)),
+                // Readers expect a (possibly empty) children vector.
+                (5, Value::Offset(Object::Tables(vec![]))),
+            ])
+        })
+        .collect();
+    let schema = Object::Table(vec![
+        // Endianness::Little
+        (0, Value::I16(0)),
+        (1, Value::Offset(Object::Tables(fields))),
+    ]);
+    message(HEADER_SCHEMA, schema, 0)
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn message(header_type: u8, header: Object, body_length: usize) -> Vec<u8> {
+    flatbuf::finish(Object::Table(vec![
+        (0, Value::I16(METADATA_V5)),
+        (1, Value::U8(header_type)),
+        (2, Value::Offset(header)),
+        (3, Value::I64(body_length as i64)),
+    ]))
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+fn write_message<W: Write>(out: &mut W, metadata: &[u8], body: &[u8]) -> io::Result<()> {
+    // `flatbuf::finish` pads to 8, so prefix + metadata stays 8-aligned.
+    out.write_all(&CONTINUATION)?;
+    out.write_all(&(metadata.len() as i32).to_le_bytes())?;
+    out.write_all(metadata)?;
+    out.write_all(body)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::super::flatbuf::reader::*;
+    use super::*;
+
+    /// Splits a stream into `(metadata, body)` messages, checking framing.
+    fn messages(stream: &[u8]) -> Vec<(&[u8], &[u8])> {
+        let mut out = Vec::new();
+        let mut pos = 0;
+        loop {
+            assert_eq!(stream[pos..pos + 4], CONTINUATION);
+            let len = u32_at(stream, pos + 4) as usize;
+            pos += 8;
+            if len == 0 {
+                assert_eq!(pos, stream.len());
+                return out;
+            }
+            assert_eq!((pos + len) % 8, 0);
+            let metadata = &stream[pos..pos + len];
+            let message = root(metadata);
+            let body_len =
+                usize::try_from(i64_at(metadata, field(metadata, message, 3).unwrap())).unwrap();
+            out.push((metadata, &stream[pos + len..pos + len + body_len]));
+            pos += len + body_len;
+        }
+    }
+
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("tick", DataType::UInt64),
+            Field::nullable("value", DataType::Float32),
+            Field::nullable("kind", DataType::Utf8),
+        ]
+    }
+
+    fn write(columns: &[Column]) -> Vec<u8> {
+        let mut writer = StreamWriter::new(Vec::new(), &fields()).unwrap();
+        writer.write_batch(columns).unwrap();
+        writer.finish().unwrap()
+    }
+
+    fn columns() -> Vec<Column> {
+        vec![
+            Column::UInt64(vec![Some(1), Some(2), Some(3)]),
+            Column::Float32(vec![Some(0.5), None, Some(2.0)]),
+            Column::Utf8(vec![Some("ab".into()), Some(String::new()), None]),
+        ]
+    }
+
+    mod framing_tests {
+        use super::*;
+
+        #[test]
+        fn stream_has_schema_batch_and_eos() {
+            let stream = write(&columns());
+            let messages = messages(&stream);
+
+            assert_eq!(messages.len(), 2);
+            let header_type = |m: &[u8]| m[field(m, root(m), 1).unwrap()];
+            assert_eq!(header_type(messages[0].0), HEADER_SCHEMA);
+            assert_eq!(header_type(messages[1].0), HEADER_RECORD_BATCH);
+            assert!(messages[0].1.is_empty());
+        }
+
+        #[test]
+        fn schema_lists_fields() {
+            let stream = write(&columns());
+            let (meta, _) = messages(&stream)[0];
+            let schema = deref(meta, field(meta, root(meta), 2).unwrap());
+            let fields = tables(meta, field(meta, schema, 1).unwrap());
+
+            let names: Vec<_> = fields
+                .iter()
+                .map(|f| string(meta, field(meta, *f, 0).unwrap()))
+                .collect();
+            assert_eq!(names, vec!["tick", "value", "kind"]);
+            assert_eq!(meta[field(meta, fields[0], 2).unwrap()], TYPE_INT);
+            assert_eq!(
+                meta[field(meta, fields[1], 2).unwrap()],
+                TYPE_FLOATING_POINT
+            );
+            assert_eq!(meta[field(meta, fields[2], 2).unwrap()], TYPE_UTF8);
+            assert!(field(meta, fields[0], 5).is_some());
+        }
+    }
+
+    mod batch_tests {
+        use super::*;
+
+        /// `(length, null_count)` nodes or `(offset, length)` buffers.
+        type Pairs = Vec<(i64, i64)>;
+
+        /// Returns `(length, nodes, buffers)` of the batch message.
+        fn batch(meta: &[u8]) -> (i64, Pairs, Pairs) {
+            let batch = deref(meta, field(meta, root(meta), 2).unwrap());
+            let pairs = |id| {
+                let at = deref(meta, field(meta, batch, id).unwrap());
+                (0..u32_at(meta, at) as usize)
+                    .map(|i| {
+                        (
+                            i64_at(meta, at + 4 + 16 * i),
+                            i64_at(meta, at + 12 + 16 * i),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            (
+                i64_at(meta, field(meta, batch, 0).unwrap()),
+                pairs(1),
+                pairs(2),
+            )
+        }
+
+        #[test]
+        fn nodes_record_length_and_nulls() {
+            let stream = write(&columns());
+            let (meta, _) = messages(&stream)[1];
+            let (length, nodes, buffers) = batch(meta);
+
+            assert_eq!(length, 3);
+            assert_eq!(nodes, vec![(3, 0), (3, 1), (3, 1)]);
+            // 2 buffers per primitive column, 3 for utf8
+            assert_eq!(buffers.len(), 7);
+        }
+
+        #[test]
+        fn body_holds_values() {
+            let stream = write(&columns());
+            let (meta, body) = messages(&stream)[1];
+            let (_, _, buffers) = batch(meta);
+            let slice = |i: usize| {
+                let (offset, length) = buffers[i];
+                &body[usize::try_from(offset).unwrap()..usize::try_from(offset + length).unwrap()]
+            };
+
+            assert!(buffers.iter().all(|(offset, _)| offset % 8 == 0));
+            // tick: no validity, values 1..3
+            assert!(slice(0).is_empty());
+            assert_eq!(u32_at(slice(1), 8), 2);
+            // value: bitmap 0b101
+            assert_eq!(slice(2), &[0b101]);
+            assert_eq!(slice(3)[8..12], 2.0_f32.to_le_bytes());
+            // kind: bitmap 0b011, offsets [0, 2, 2, 2], data "ab"
+            assert_eq!(slice(4), &[0b011]);
+            assert_eq!(slice(5).len(), 16);
+            assert_eq!(u32_at(slice(5), 4), 2);
+            assert_eq!(slice(6), b"ab");
+        }
+
+        #[test]
+        fn rejects_mismatched_columns() {
+            let mut writer = StreamWriter::new(Vec::new(), &fields()).unwrap();
+
+            assert!(writer.write_batch(&columns()[..2]).is_err());
+
+            let mut wrong_type = columns();
+            wrong_type[0] = Column::UInt32(vec![Some(1), Some(2), Some(3)]);
+            assert!(writer.write_batch(&wrong_type).is_err());
+
+            let mut null_in_required = columns();
+            null_in_required[0] = Column::UInt64(vec![Some(1), None, Some(3)]);
+            assert!(writer.write_batch(&null_in_required).is_err());
+        }
+    }
+
+    /// Reads the stream back with arrow-rs, independent of our own reader.
+    mod arrow_rs_tests {
+        use super::*;
+        use arrow_array::cast::AsArray;
+        use arrow_array::types::{Float32Type, UInt32Type, UInt64Type};
+        use arrow_array::{Array, RecordBatch};
+        use arrow_ipc::reader::StreamReader;
+        use arrow_schema::DataType as ArrowType;
+
+        fn read(stream: &[u8]) -> Vec<RecordBatch> {
+            StreamReader::try_new(stream, None)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        }
+
+        #[test]
+        fn schema_matches_fields() {
+            let stream = write(&columns());
+            let schema = StreamReader::try_new(&stream[..], None).unwrap().schema();
+
+            let described: Vec<_> = schema
+                .fields()
+                .iter()
+                .map(|f| (f.name().as_str(), f.data_type().clone(), f.is_nullable()))
+                .collect();
+            assert_eq!(
+                described,
+                vec![
+                    ("tick", ArrowType::UInt64, false),
+                    ("value", ArrowType::Float32, true),
+                    ("kind", ArrowType::Utf8, true),
+                ]
+            );
+        }
+
+        #[test]
+        fn values_and_nulls_round_trip() {
+            let batches = read(&write(&columns()));
+            assert_eq!(batches.len(), 1);
+            let batch = &batches[0];
+            assert_eq!(batch.num_rows(), 3);
+
+            let tick = batch.column(0).as_primitive::<UInt64Type>();
+            assert_eq!(tick.values().to_vec(), vec![1, 2, 3]);
+            assert_eq!(tick.null_count(), 0);
+
+            let value = batch.column(1).as_primitive::<Float32Type>();
+            let value: Vec<_> = value.iter().map(|v| v.map(f32::to_bits)).collect();
+            assert_eq!(
+                value,
+                vec![Some(0.5_f32.to_bits()), None, Some(2.0_f32.to_bits())]
+            );
+
+            let kind: Vec<_> = batch.column(2).as_string::<i32>().iter().collect();
+            assert_eq!(kind, vec![Some("ab"), Some(""), None]);
+        }
+
+        #[test]
+        fn multiple_batches_and_uint32() {
+            let fields = [Field::nullable("slot", DataType::UInt32)];
+            let mut writer = StreamWriter::new(Vec::new(), &fields).unwrap();
+            writer
+                .write_batch(&[Column::UInt32(vec![Some(u32::MAX), None])])
+                .unwrap();
+            writer.write_batch(&[Column::UInt32(vec![])]).unwrap();
+            writer
+                .write_batch(&[Column::UInt32((0..100).map(Some).collect())])
+                .unwrap();
+            let batches = read(&writer.finish().unwrap());
+
+            let rows: Vec<_> = batches.iter().map(RecordBatch::num_rows).collect();
+            assert_eq!(rows, vec![2, 0, 100]);
+            let first: Vec<_> = batches[0].column(0).as_primitive::<UInt32Type>().iter().collect();
+            assert_eq!(first, vec![Some(u32::MAX), None]);
+            let last = batches[2].column(0).as_primitive::<UInt32Type>();
+            assert_eq!(last.value(99), 99);
+        }
+    }
+}
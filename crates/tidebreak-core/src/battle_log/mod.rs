@@ -0,0 +1,1050 @@
+//! Event-sourced battle log.
+//!
+//! The `BattleLog` streams every tick's events, applied damage and entity
+//! summaries to [Arrow IPC stream] files, so a run can be analysed after the
+//! fact without logging from Python. It is enabled through
+//! [`SimulationConfig::battle_log`](crate::simulation::SimulationConfig) or
+//! [`Simulation::open_battle_log`](crate::simulation::Simulation::open_battle_log).
+//!
+//! Rows are buffered and written as one record batch per stream every
+//! [`BattleLogConfig::batch_ticks`] ticks. The files are valid streams once
+//! the log is closed (or dropped), and can be loaded with e.g.
+//! `pyarrow.ipc.open_stream("events.arrows").read_pandas()`.
+//!
+//! # Schema
+//!
+//! `tick` is the tick during which the row was produced; entity summaries
+//! describe the state after that tick resolved.
+//!
+//! **`events.arrows`**: one row per `Event` output.
+//!
+//! | column        | type    | nullable | contents                                        |
+//! |---------------|---------|----------|-------------------------------------------------|
+//! | `tick`        | uint64  | no       | tick                                            |
+//! | `trace_id`    | uint64  | no       | causal trace ID                                 |
+//! | `kind`        | utf8    | no       | `weapon_fired`, `damage_dealt`, ...             |
+//! | `entity`      | uint64  | no       | primary entity (see `Event::primary_entity`)    |
+//! | `other`       | uint64  | yes      | target, observed contact, source or destroyer   |
+//! | `value`       | float32 | yes      | damage amount or threat score                   |
+//! | `weapon_slot` | uint32  | yes      | weapon slot                                     |
+//! | `quality`     | utf8    | yes      | track quality (`cue`, `coarse`, ...)            |
+//!
+//! **`damage.arrows`**: one row per `ApplyDamage` modifier.
+//!
+//! | column     | type    | nullable | contents                                  |
+//! |------------|---------|----------|-------------------------------------------|
+//! | `tick`     | uint64  | no       | tick                                      |
+//! | `source`   | uint64  | no       | entity whose plugin emitted the modifier  |
+//! | `target`   | uint64  | no       | damaged entity                            |
+//! | `amount`   | float32 | no       | damage amount                             |
+//! | `hp_after` | float32 | yes      | target HP after resolution, if it has HP  |
+//!
+//! **`entities.arrows`**: one row per entity every
+//! [`BattleLogConfig::entity_summary_every`] ticks.
+//!
+//! | column    | type    | nullable | contents                           |
+//! |-----------|---------|----------|------------------------------------|
+//! | `tick`    | uint64  | no       | tick                               |
+//! | `entity`  | uint64  | no       | entity ID                          |
+//! | `tag`     | utf8    | no       | `Ship`, `Platform`, ...            |
+//! | `team`    | uint32  | yes      | team                               |
+//! | `x`, `y`  | float32 | no       | position                           |
+//! | `heading` | float32 | no       | heading (radians)                  |
+//! | `vx`, `vy`| float32 | yes      | velocity (platforms have none)     |
+//! | `hp`      | float32 | yes      | HP (ships and squadrons)           |
+//! | `max_hp`  | float32 | yes      | maximum HP (ships and squadrons)   |
+//!
+//! With [`BattleLogConfig::fog_of_war`] set, three more streams compare each
+//! team's believed picture against that ground truth (see [`fog`] and
+//! [`association`]):
+//!
+//! **`tracks.arrows`**: one row per team per fused track, every tick.
+//!
+//! | column      | type    | nullable | contents                                  |
+//! |-------------|---------|----------|-------------------------------------------|
+//! | `tick`      | uint64  | no       | tick                                      |
+//! | `team`      | uint32  | no       | team holding the track                    |
+//! | `target`    | uint64  | no       | tracked entity                            |
+//! | `x`, `y`    | float32 | no       | believed position                         |
+//! | `vx`, `vy`  | float32 | no       | believed velocity                         |
+//! | `quality`   | utf8    | no       | track quality (`cue`, `coarse`, ...)      |
+//! | `age`       | float32 | no       | seconds since the freshest update         |
+//! | `reporters` | uint32  | no       | members holding the track                 |
+//! | `error`     | float32 | yes      | distance to the true position, if present |
+//!
+//! **`fog.arrows`**: one row per team every tick.
+//!
+//! | column                | type    | nullable | contents                             |
+//! |-----------------------|---------|----------|--------------------------------------|
+//! | `tick`                | uint64  | no       | tick                                 |
+//! | `team`                | uint32  | no       | team                                 |
+//! | `opposing`            | uint32  | no       | entities on other teams              |
+//! | `detected`            | uint32  | no       | opposing entities tracked            |
+//! | `new_detections`      | uint32  | no       | opposing entities first tracked      |
+//! | `mean_error`          | float32 | yes      | mean position error of those tracked |
+//! | `continuity`          | float32 | yes      | share of last tick's tracks kept     |
+//! | `mean_time_to_detect` | float32 | yes      | mean ticks to detect, new detections |
+//!
+//! **`association.arrows`**: one row per team every tick, from associating
+//! the team's tracks with the truth by position.
+//!
+//! | column           | type    | nullable | contents                                |
+//! |------------------|---------|----------|-----------------------------------------|
+//! | `tick`           | uint64  | no       | tick                                    |
+//! | `team`           | uint32  | no       | team                                    |
+//! | `truths`         | uint32  | no       | entities on other teams                 |
+//! | `tracks`         | uint32  | no       | tracks held                             |
+//! | `associated`     | uint32  | no       | track-truth pairs within the cutoff     |
+//! | `ospa`           | float32 | no       | OSPA distance                           |
+//! | `localization`   | float32 | no       | OSPA localization component             |
+//! | `cardinality`    | float32 | no       | OSPA cardinality component              |
+//! | `purity`         | float32 | yes      | track purity over the run so far        |
+//! | `fragmentations` | uint32  | no       | fragmentations over the run so far      |
+//!
+//! [Arrow IPC stream]: https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format
+
+pub mod association;
+mod flatbuf;
+pub mod fog;
+pub mod ipc;
+pub mod query;
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+
+use crate::arena::Arena;
+use crate::entity::components::{Subsystem, TrackQuality};
+use crate::entity::{Entity, EntityId, EntityInner, TeamId};
+use crate::output::{Event, Modifier, Output, OutputEnvelope};
+
+use association::{AssociationConfig, AssociationEvaluator, AssociationMetrics};
+use fog::{BelievedTrack, FogEvaluator, FogMetrics};
+use ipc::{Column, DataType, Field, StreamWriter};
+
+/// File name of the event stream.
+pub const EVENTS_FILE: &str = "events.arrows";
+/// File name of the damage stream.
+pub const DAMAGE_FILE: &str = "damage.arrows";
+/// File name of the entity summary stream.
+pub const ENTITIES_FILE: &str = "entities.arrows";
+/// File name of the believed track stream (fog-of-war evaluation only).
+pub const TRACKS_FILE: &str = "tracks.arrows";
+/// File name of the fog-of-war metrics stream (fog-of-war evaluation only).
+pub const FOG_FILE: &str = "fog.arrows";
+/// File name of the track association stream (fog-of-war evaluation only).
+pub const ASSOCIATION_FILE: &str = "association.arrows";
+
+/// Default number of ticks per record batch.
+pub const DEFAULT_BATCH_TICKS: u64 = 64;
+
+/// Battle log settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BattleLogConfig {
+    /// Directory the stream files are created in (created if missing).
+    pub dir: PathBuf,
+    /// Ticks buffered per record batch.
+    pub batch_ticks: u64,
+    /// Interval between entity summaries (1 = every tick, 0 = never).
+    pub entity_summary_every: u64,
+    /// Evaluation mode: also write each team's believed tracks, fog-of-war
+    /// metrics and track association metrics every tick.
+    pub fog_of_war: bool,
+}
+
+impl BattleLogConfig {
+    /// Creates a config writing to `dir` with default batching.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            batch_ticks: DEFAULT_BATCH_TICKS,
+            entity_summary_every: 1,
+            fog_of_war: false,
+        }
+    }
+}
+
+// =============================================================================
+// Rows
+// =============================================================================
+
+/// A row type with a fixed Arrow schema.
+trait Row: Sized {
+    const FIELDS: &'static [Field];
+
+    fn columns(rows: &[Self]) -> Vec<Column>;
+}
+
+/// One event flattened to the columns of `events.arrows`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRow {
+    /// Tick the event was produced on
+    pub tick: u64,
+    /// Causal trace ID
+    pub trace_id: u64,
+    /// Event kind in snake case (`weapon_fired`, `damage_dealt`, ...)
+    pub kind: &'static str,
+    /// Primary entity (see `Event::primary_entity`)
+    pub entity: u64,
+    /// Target, observed contact, source or destroyer
+    pub other: Option<u64>,
+    /// Damage amount, threat score, radius, cargo amount or overruns
+    pub value: Option<f32>,
+    /// Weapon slot
+    pub weapon_slot: Option<u32>,
+    /// Track quality (`cue`, `coarse`, ...)
+    pub quality: Option<&'static str>,
+}
+
+impl EventRow {
+    /// Flattens an event envelope, or returns `None` for other outputs.
+    #[must_use]
+    pub fn from_envelope(envelope: &OutputEnvelope) -> Option<Self> {
+        let event = envelope.output().as_event()?;
+        Some(Self::new(envelope.tick(), envelope, event))
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn new(tick: u64, envelope: &OutputEnvelope, event: &Event) -> Self {
+        let mut row = Self {
+            tick,
+            trace_id: envelope.trace_id().as_u64(),
+            kind: "",
+            entity: event.primary_entity().as_u64(),
+            other: None,
+            value: None,
+            weapon_slot: None,
+            quality: None,
+        };
+        match event {
+            Event::WeaponFired { weapon_slot, .. } => {
+                row.kind = "weapon_fired";
+                row.weapon_slot = Some(*weapon_slot as u32);
+            }
+            Event::DamageDealt { source, amount, .. } => {
+                row.kind = "damage_dealt";
+                row.other = Some(source.as_u64());
+                row.value = Some(*amount);
+            }
+            Event::EntityDestroyed { destroyer, .. } => {
+                row.kind = "entity_destroyed";
+                row.other = destroyer.map(EntityId::as_u64);
+            }
+            Event::ContactDetected {
+                target, quality, ..
+            } => {
+                row.kind = "contact_detected";
+                row.other = Some(target.as_u64());
+                row.quality = Some(quality_name(*quality));
+            }
+            Event::ThreatAssessed { target, score, .. } => {
+                row.kind = "threat_assessed";
+                row.other = Some(target.as_u64());
+                row.value = Some(*score);
+            }
+            Event::WeaponAssigned {
+                target,
+                weapon_slot,
+                ..
+            } => {
+                row.kind = "weapon_assigned";
+                row.other = Some(target.as_u64());
+                row.weapon_slot = Some(*weapon_slot as u32);
+            }
+            Event::EntityOutOfBounds { .. } | Event::EntityExpired { .. } => {
+                let expired = matches!(event, Event::EntityExpired { .. });
+                row.kind = if expired { "entity_expired" } else { "entity_out_of_bounds" };
+            }
+            Event::PluginBudgetExceeded { overruns, .. } => {
+                row.kind = "plugin_budget_exceeded";
+                #[allow(clippy::cast_precision_loss)]
+                let overruns = *overruns as f32;
+                row.value = Some(overruns);
+            }
+            Event::MineDetonated { trigger, .. } => {
+                row.kind = "mine_detonated";
+                row.other = Some(trigger.as_u64());
+            }
+            Event::CargoTransferred { from, cargo, .. } => {
+                row.kind = "cargo_transferred";
+                row.other = Some(from.as_u64());
+                row.value = Some(cargo.amount());
+            }
+            Event::ReloadStarted { weapon_slot, .. }
+            | Event::ReloadCompleted { weapon_slot, .. } => {
+                let started = matches!(event, Event::ReloadStarted { .. });
+                row.kind = if started { "reload_started" } else { "reload_completed" };
+                row.weapon_slot = Some(*weapon_slot as u32);
+            }
+            Event::EnteredRange { target, radius, .. }
+            | Event::LeftRange { target, radius, .. } => {
+                let entered = matches!(event, Event::EnteredRange { .. });
+                row.kind = if entered { "entered_range" } else { "left_range" };
+                row.other = Some(target.as_u64());
+                row.value = Some(*radius);
+            }
+            Event::ShipDelivered { .. } | Event::ShellSplash { .. } => {
+                let delivered = matches!(event, Event::ShipDelivered { .. });
+                row.kind = if delivered { "ship_delivered" } else { "shell_splash" };
+            }
+            Event::LockAcquired { target, .. } | Event::LockLost { target, .. } => {
+                let acquired = matches!(event, Event::LockAcquired { .. });
+                row.kind = if acquired { "lock_acquired" } else { "lock_lost" };
+                row.other = Some(target.as_u64());
+            }
+            Event::GeofenceViolated { depth, .. } => {
+                row.kind = "geofence_violated";
+                row.value = Some(*depth);
+            }
+            Event::SubsystemDestroyed { source, subsystem, .. } => {
+                row.kind = "subsystem_destroyed";
+                row.other = Some(source.as_u64());
+                if let Subsystem::Weapon(slot) = subsystem {
+                    row.weapon_slot = Some(*slot as u32);
+                }
+            }
+        }
+        row
+    }
+}
+
+const fn quality_name(quality: TrackQuality) -> &'static str {
+    match quality {
+        TrackQuality::Cue => "cue",
+        TrackQuality::Coarse => "coarse",
+        TrackQuality::FireControl => "fire_control",
+        TrackQuality::Shared => "shared",
+    }
+}
+
+impl Row for EventRow {
+    const FIELDS: &'static [Field] = &[
+        Field::new("tick", DataType::UInt64),
+        Field::new("trace_id", DataType::UInt64),
+        Field::new("kind", DataType::Utf8),
+        Field::new("entity", DataType::UInt64),
+        Field::nullable("other", DataType::UInt64),
+        Field::nullable("value", DataType::Float32),
+        Field::nullable("weapon_slot", DataType::UInt32),
+        Field::nullable("quality", DataType::Utf8),
+    ];
+
+    fn columns(rows: &[Self]) -> Vec<Column> {
+        vec![
+            Column::UInt64(rows.iter().map(|r| Some(r.tick)).collect()),
+            Column::UInt64(rows.iter().map(|r| Some(r.trace_id)).collect()),
+            Column::Utf8(rows.iter().map(|r| Some(r.kind.to_string())).collect()),
+            Column::UInt64(rows.iter().map(|r| Some(r.entity)).collect()),
+            Column::UInt64(rows.iter().map(|r| r.other).collect()),
+            Column::Float32(rows.iter().map(|r| r.value).collect()),
+            Column::UInt32(rows.iter().map(|r| r.weapon_slot).collect()),
+            Column::Utf8(rows.iter().map(|r| r.quality.map(str::to_string)).collect()),
+        ]
+    }
+}
+
+struct DamageRow {
+    tick: u64,
+    source: u64,
+    target: u64,
+    amount: f32,
+    hp_after: Option<f32>,
+}
+
+impl Row for DamageRow {
+    const FIELDS: &'static [Field] = &[
+        Field::new("tick", DataType::UInt64),
+        Field::new("source", DataType::UInt64),
+        Field::new("target", DataType::UInt64),
+        Field::new("amount", DataType::Float32),
+        Field::nullable("hp_after", DataType::Float32),
+    ];
+
+    fn columns(rows: &[Self]) -> Vec<Column> {
+        vec![
+            Column::UInt64(rows.iter().map(|r| Some(r.tick)).collect()),
+            Column::UInt64(rows.iter().map(|r| Some(r.source)).collect()),
+            Column::UInt64(rows.iter().map(|r| Some(r.target)).collect()),
+            Column::Float32(rows.iter().map(|r| Some(r.amount)).collect()),
+            Column::Float32(rows.iter().map(|r| r.hp_after).collect()),
+        ]
+    }
+}
+
+struct EntityRow {
+    tick: u64,
+    entity: u64,
+    tag: String,
+    team: Option<u32>,
+    x: f32,
+    y: f32,
+    heading: f32,
+    velocity: Option<(f32, f32)>,
+    hp: Option<(f32, f32)>,
+}
+
+impl EntityRow {
+    fn new(tick: u64, entity: &Entity) -> Self {
+        let (transform, physics, combat) = match entity.inner() {
+            EntityInner::Ship(c) => (&c.transform, Some(&c.physics), Some(&c.combat)),
+            EntityInner::Platform(c) => (&c.transform, None, None),
+            EntityInner::Projectile(c) => (&c.transform, Some(&c.physics), None),
+            EntityInner::Squadron(c) => (&c.transform, Some(&c.physics), Some(&c.combat)),
+        };
+        Self {
+            tick,
+            entity: entity.id().as_u64(),
+            tag: entity.tag().to_string(),
+            team: entity.team().map(TeamId::as_u32),
+            x: transform.position.x,
+            y: transform.position.y,
+            heading: transform.heading,
+            velocity: physics.map(|p| (p.velocity.x, p.velocity.y)),
+            hp: combat.map(|c| (c.hp, c.max_hp)),
+        }
+    }
+}
+
+impl Row for EntityRow {
+    const FIELDS: &'static [Field] = &[
+        Field::new("tick", DataType::UInt64),
+        Field::new("entity", DataType::UInt64),
+        Field::new("tag", DataType::Utf8),
+        Field::nullable("team", DataType::UInt32),
+        Field::new("x", DataType::Float32),
+        Field::new("y", DataType::Float32),
+        Field::new("heading", DataType::Float32),
+        Field::nullable("vx", DataType::Float32),
+        Field::nullable("vy", DataType::Float32),
+        Field::nullable("hp", DataType::Float32),
+        Field::nullable("max_hp", DataType::Float32),
+    ];
+
+    fn columns(rows: &[Self]) -> Vec<Column> {
+        vec![
+            Column::UInt64(rows.iter().map(|r| Some(r.tick)).collect()),
+            Column::UInt64(rows.iter().map(|r| Some(r.entity)).collect()),
+            Column::Utf8(rows.iter().map(|r| Some(r.tag.clone())).collect()),
+            Column::UInt32(rows.iter().map(|r| r.team).collect()),
+            Column::Float32(rows.iter().map(|r| Some(r.x)).collect()),
+            Column::Float32(rows.iter().map(|r| Some(r.y)).collect()),
+            Column::Float32(rows.iter().map(|r| Some(r.heading)).collect()),
+            Column::Float32(rows.iter().map(|r| r.velocity.map(|v| v.0)).collect()),
+            Column::Float32(rows.iter().map(|r| r.velocity.map(|v| v.1)).collect()),
+            Column::Float32(rows.iter().map(|r| r.hp.map(|h| h.0)).collect()),
+            Column::Float32(rows.iter().map(|r| r.hp.map(|h| h.1)).collect()),
+        ]
+    }
+}
+
+struct TrackRow {
+    tick: u64,
+    track: BelievedTrack,
+}
+
+impl Row for TrackRow {
+    const FIELDS: &'static [Field] = &[
+        Field::new("tick", DataType::UInt64),
+        Field::new("team", DataType::UInt32),
+        Field::new("target", DataType::UInt64),
+        Field::new("x", DataType::Float32),
+        Field::new("y", DataType::Float32),
+        Field::new("vx", DataType::Float32),
+        Field::new("vy", DataType::Float32),
+        Field::new("quality", DataType::Utf8),
+        Field::new("age", DataType::Float32),
+        Field::new("reporters", DataType::UInt32),
+        Field::nullable("error", DataType::Float32),
+    ];
+
+    fn columns(rows: &[Self]) -> Vec<Column> {
+        let track = |f: fn(&Self) -> f32| rows.iter().map(|r| Some(f(r))).collect();
+        vec![
+            Column::UInt64(rows.iter().map(|r| Some(r.tick)).collect()),
+            Column::UInt32(rows.iter().map(|r| Some(r.track.team.as_u32())).collect()),
+            Column::UInt64(
+                rows.iter()
+                    .map(|r| Some(r.track.track.target_id.as_u64()))
+                    .collect(),
+            ),
+            Column::Float32(track(|r| r.track.track.position.x)),
+            Column::Float32(track(|r| r.track.track.position.y)),
+            Column::Float32(track(|r| r.track.track.velocity.x)),
+            Column::Float32(track(|r| r.track.track.velocity.y)),
+            Column::Utf8(
+                rows.iter()
+                    .map(|r| Some(quality_name(r.track.track.quality).to_string()))
+                    .collect(),
+            ),
+            Column::Float32(track(|r| r.track.track.age)),
+            Column::UInt32(rows.iter().map(|r| Some(count(r.track.track.reporters))).collect()),
+            Column::Float32(rows.iter().map(|r| r.track.error).collect()),
+        ]
+    }
+}
+
+struct FogRow {
+    tick: u64,
+    metrics: FogMetrics,
+}
+
+impl Row for FogRow {
+    const FIELDS: &'static [Field] = &[
+        Field::new("tick", DataType::UInt64),
+        Field::new("team", DataType::UInt32),
+        Field::new("opposing", DataType::UInt32),
+        Field::new("detected", DataType::UInt32),
+        Field::new("new_detections", DataType::UInt32),
+        Field::nullable("mean_error", DataType::Float32),
+        Field::nullable("continuity", DataType::Float32),
+        Field::nullable("mean_time_to_detect", DataType::Float32),
+    ];
+
+    fn columns(rows: &[Self]) -> Vec<Column> {
+        let counts = |f: fn(&FogMetrics) -> usize| {
+            rows.iter().map(|r| Some(count(f(&r.metrics)))).collect()
+        };
+        vec![
+            Column::UInt64(rows.iter().map(|r| Some(r.tick)).collect()),
+            Column::UInt32(rows.iter().map(|r| Some(r.metrics.team.as_u32())).collect()),
+            Column::UInt32(counts(|m| m.opposing)),
+            Column::UInt32(counts(|m| m.detected)),
+            Column::UInt32(counts(|m| m.new_detections)),
+            Column::Float32(rows.iter().map(|r| r.metrics.mean_error).collect()),
+            Column::Float32(rows.iter().map(|r| r.metrics.continuity).collect()),
+            Column::Float32(rows.iter().map(|r| r.metrics.mean_time_to_detect).collect()),
+        ]
+    }
+}
+
+struct AssociationRow {
+    tick: u64,
+    metrics: AssociationMetrics,
+}
+
+impl Row for AssociationRow {
+    const FIELDS: &'static [Field] = &[
+        Field::new("tick", DataType::UInt64),
+        Field::new("team", DataType::UInt32),
+        Field::new("truths", DataType::UInt32),
+        Field::new("tracks", DataType::UInt32),
+        Field::new("associated", DataType::UInt32),
+        Field::new("ospa", DataType::Float32),
+        Field::new("localization", DataType::Float32),
+        Field::new("cardinality", DataType::Float32),
+        Field::nullable("purity", DataType::Float32),
+        Field::new("fragmentations", DataType::UInt32),
+    ];
+
+    fn columns(rows: &[Self]) -> Vec<Column> {
+        let counts = |f: fn(&AssociationMetrics) -> usize| {
+            rows.iter().map(|r| Some(count(f(&r.metrics)))).collect()
+        };
+        let distances = |f: fn(&AssociationMetrics) -> f32| {
+            rows.iter().map(|r| Some(f(&r.metrics))).collect()
+        };
+        vec![
+            Column::UInt64(rows.iter().map(|r| Some(r.tick)).collect()),
+            Column::UInt32(rows.iter().map(|r| Some(r.metrics.team.as_u32())).collect()),
+            Column::UInt32(counts(|m| m.truths)),
+            Column::UInt32(counts(|m| m.tracks)),
+            Column::UInt32(counts(|m| m.associated)),
+            Column::Float32(distances(|m| m.ospa)),
+            Column::Float32(distances(|m| m.localization)),
+            Column::Float32(distances(|m| m.cardinality)),
+            Column::Float32(rows.iter().map(|r| r.metrics.purity).collect()),
+            Column::UInt32(counts(|m| m.fragmentations)),
+        ]
+    }
+}
+
+/// Narrows a count to a `uint32` column value, saturating.
+fn count(n: usize) -> u32 {
+    u32::try_from(n).unwrap_or(u32::MAX)
+}
+
+/// Fog-of-war evaluation state and its streams.
+struct FogStreams {
+    evaluator: FogEvaluator,
+    association: AssociationEvaluator,
+    tracks: Stream<TrackRow>,
+    metrics: Stream<FogRow>,
+    associations: Stream<AssociationRow>,
+}
+
+// =============================================================================
+// Streams
+// =============================================================================
+
+/// One buffered Arrow stream.
+struct Stream<R: Row> {
+    writer: Option<StreamWriter<BufWriter<File>>>,
+    rows: Vec<R>,
+}
+
+impl<R: Row> Stream<R> {
+    fn create(config: &BattleLogConfig, name: &str) -> io::Result<Self> {
+        let file = File::create(config.dir.join(name))?;
+        Ok(Self {
+            writer: Some(StreamWriter::new(BufWriter::new(file), R::FIELDS)?),
+            rows: Vec::new(),
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        let columns = R::columns(&self.rows);
+        self.rows.clear();
+        match &mut self.writer {
+            Some(writer) => writer.write_batch(&columns),
+            None => Ok(()),
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()?;
+        match self.writer.take() {
+            Some(writer) => writer.finish().map(drop),
+            None => Ok(()),
+        }
+    }
+}
+
+// =============================================================================
+// BattleLog
+// =============================================================================
+
+/// Writer for the event, damage and entity streams of one run.
+///
+/// # Example
+///
+/// ```no_run
+/// use tidebreak_core::battle_log::BattleLogConfig;
+/// use tidebreak_core::simulation::{Simulation, SimulationConfig};
+///
+/// let config = SimulationConfig {
+///     battle_log: Some(BattleLogConfig::new("runs/episode-0")),
+///     ..SimulationConfig::default()
+/// };
+/// let mut sim = Simulation::with_config(42, config)?;
+/// for _ in 0..100 {
+///     sim.step();
+/// }
+/// sim.close_battle_log()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct BattleLog {
+    config: BattleLogConfig,
+    events: Stream<EventRow>,
+    damage: Stream<DamageRow>,
+    entities: Stream<EntityRow>,
+    fog: Option<FogStreams>,
+    buffered_ticks: u64,
+}
+
+impl std::fmt::Debug for BattleLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BattleLog")
+            .field("config", &self.config)
+            .field("buffered_ticks", &self.buffered_ticks)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BattleLog {
+    /// Creates the log directory and stream files, writing their schemas.
+    ///
+    /// Existing files of the same name are truncated.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from creating the directory or files.
+    pub fn create(config: BattleLogConfig) -> io::Result<Self> {
+        std::fs::create_dir_all(&config.dir)?;
+        Ok(Self {
+            events: Stream::create(&config, EVENTS_FILE)?,
+            damage: Stream::create(&config, DAMAGE_FILE)?,
+            entities: Stream::create(&config, ENTITIES_FILE)?,
+            fog: if config.fog_of_war {
+                Some(FogStreams {
+                    evaluator: FogEvaluator::new(),
+                    association: AssociationEvaluator::new(AssociationConfig::default()),
+                    tracks: Stream::create(&config, TRACKS_FILE)?,
+                    metrics: Stream::create(&config, FOG_FILE)?,
+                    associations: Stream::create(&config, ASSOCIATION_FILE)?,
+                })
+            } else {
+                None
+            },
+            config,
+            buffered_ticks: 0,
+        })
+    }
+
+    /// Returns the log configuration.
+    #[must_use]
+    pub const fn config(&self) -> &BattleLogConfig {
+        &self.config
+    }
+
+    /// Records one tick: its outputs and the state they resolved into.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from writing a completed batch.
+    pub fn record_tick(
+        &mut self,
+        tick: u64,
+        outputs: &[OutputEnvelope],
+        state: &Arena,
+    ) -> io::Result<()> {
+        for envelope in outputs {
+            match envelope.output() {
+                Output::Event(event) => self.events.rows.push(EventRow::new(tick, envelope, event)),
+                Output::Modifier(Modifier::ApplyDamage { target, amount, .. }) => {
+                    let hp_after = state.get(*target).and_then(|e| match e.inner() {
+                        EntityInner::Ship(c) => Some(c.combat.hp),
+                        EntityInner::Squadron(c) => Some(c.combat.hp),
+                        _ => None,
+                    });
+                    self.damage.rows.push(DamageRow {
+                        tick,
+                        source: envelope.source().entity_id().as_u64(),
+                        target: target.as_u64(),
+                        amount: *amount,
+                        hp_after,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let every = self.config.entity_summary_every;
+        if every > 0 && tick.is_multiple_of(every) {
+            self.entities
+                .rows
+                .extend(state.entities_sorted().map(|e| EntityRow::new(tick, e)));
+        }
+
+        if let Some(fog) = &mut self.fog {
+            let report = fog.evaluator.evaluate(tick, state);
+            let associations = fog.association.evaluate(state, &report.tracks).into_iter();
+            fog.associations
+                .rows
+                .extend(associations.map(|metrics| AssociationRow { tick, metrics }));
+            let tracks = report.tracks.into_iter();
+            fog.tracks
+                .rows
+                .extend(tracks.map(|track| TrackRow { tick, track }));
+            let metrics = report.metrics.into_iter();
+            fog.metrics
+                .rows
+                .extend(metrics.map(|metrics| FogRow { tick, metrics }));
+        }
+
+        self.buffered_ticks += 1;
+        if self.buffered_ticks >= self.config.batch_ticks {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Writes all buffered rows as record batches.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from the underlying files.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.buffered_ticks = 0;
+        self.events.flush()?;
+        self.damage.flush()?;
+        self.entities.flush()?;
+        if let Some(fog) = &mut self.fog {
+            fog.tracks.flush()?;
+            fog.metrics.flush()?;
+            fog.associations.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Flushes buffered rows and terminates all streams.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from the underlying files.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.finish_streams()
+    }
+
+    fn finish_streams(&mut self) -> io::Result<()> {
+        let events = self.events.finish();
+        let damage = self.damage.finish();
+        let entities = self.entities.finish();
+        let fog = self.fog.as_mut().map_or(Ok(()), |fog| {
+            let tracks = fog.tracks.finish();
+            let metrics = fog.metrics.finish();
+            tracks.and(metrics).and(fog.associations.finish())
+        });
+        events.and(damage).and(entities).and(fog)
+    }
+}
+
+impl Drop for BattleLog {
+    fn drop(&mut self) {
+        // Best effort; call `finish` to observe errors.
+        let _ = self.finish_streams();
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{DamageType, EntityId, EntityTag, ShipComponents};
+    use crate::output::{PluginId, PluginInstanceId, TraceId};
+    use glam::Vec2;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "tidebreak-battle-log-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn envelope(output: Output, entity: EntityId) -> OutputEnvelope {
+        OutputEnvelope::new(
+            output,
+            PluginInstanceId::new(entity, PluginId::new("test")),
+            TraceId::new(7),
+            0,
+            0,
+        )
+    }
+
+    /// Number of record batch messages and total rows in a stream file.
+    fn batches(path: &std::path::Path) -> (usize, i64) {
+        use flatbuf::reader::{deref, field, i64_at, root, u32_at};
+
+        let stream = std::fs::read(path).unwrap();
+        let (mut pos, mut count, mut rows) = (0, 0, 0);
+        loop {
+            let len = u32_at(&stream, pos + 4) as usize;
+            pos += 8;
+            if len == 0 {
+                return (count - 1, rows);
+            }
+            let meta = &stream[pos..pos + len];
+            let message = root(meta);
+            if meta[field(meta, message, 1).unwrap()] == 3 {
+                let batch = deref(meta, field(meta, message, 2).unwrap());
+                rows += i64_at(meta, field(meta, batch, 0).unwrap());
+            }
+            let body = i64_at(meta, field(meta, message, 3).unwrap());
+            pos += len + usize::try_from(body).unwrap();
+            count += 1;
+        }
+    }
+
+    #[test]
+    fn quality_names_are_snake_case() {
+        assert_eq!(quality_name(TrackQuality::FireControl), "fire_control");
+    }
+
+    #[test]
+    fn event_rows_capture_variant_fields() {
+        let event = Event::DamageDealt {
+            source: EntityId::new(1),
+            target: EntityId::new(2),
+            amount: 12.5,
+        };
+        let row = EventRow::new(
+            3,
+            &envelope(Output::Event(event.clone()), EntityId::new(1)),
+            &event,
+        );
+
+        assert_eq!(row.kind, "damage_dealt");
+        assert_eq!(row.entity, 2);
+        assert_eq!(row.other, Some(1));
+        assert_eq!(row.value, Some(12.5));
+        assert_eq!(row.trace_id, 7);
+    }
+
+    #[test]
+    fn records_streams_in_batches() {
+        let dir = temp_dir("batches");
+        let mut arena = Arena::new();
+        let ship = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+        );
+        let outputs = vec![
+            envelope(
+                Output::Event(Event::WeaponFired {
+                    source: ship,
+                    weapon_slot: 0,
+                }),
+                ship,
+            ),
+            envelope(
+                Output::Modifier(Modifier::ApplyDamage {
+                    target: ship,
+                    amount: 5.0,
+                    damage_type: DamageType::Kinetic,
+                }),
+                ship,
+            ),
+        ];
+
+        let mut log = BattleLog::create(BattleLogConfig {
+            batch_ticks: 2,
+            ..BattleLogConfig::new(&dir)
+        })
+        .unwrap();
+        for tick in 0..5 {
+            log.record_tick(tick, &outputs, &arena).unwrap();
+        }
+        log.finish().unwrap();
+
+        // 5 ticks at 2 ticks per batch: two full batches plus the remainder
+        assert_eq!(batches(&dir.join(EVENTS_FILE)), (3, 5));
+        assert_eq!(batches(&dir.join(DAMAGE_FILE)), (3, 5));
+        assert_eq!(batches(&dir.join(ENTITIES_FILE)), (3, 5));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn log_files_are_readable_by_arrow_rs() {
+        use arrow_array::cast::AsArray;
+        use arrow_array::types::{Float32Type, UInt32Type, UInt64Type};
+        use arrow_array::Array;
+        use arrow_ipc::reader::StreamReader;
+
+        let dir = temp_dir("arrow-rs");
+        let mut arena = Arena::new();
+        let ship = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(Vec2::new(10.0, -4.0), 0.0)),
+        );
+        let fired = envelope(
+            Output::Event(Event::WeaponFired {
+                source: ship,
+                weapon_slot: 2,
+            }),
+            ship,
+        );
+
+        let mut log = BattleLog::create(BattleLogConfig::new(&dir)).unwrap();
+        for tick in 0..3 {
+            log.record_tick(tick, std::slice::from_ref(&fired), &arena)
+                .unwrap();
+        }
+        log.finish().unwrap();
+
+        let read = |name: &str, fields: &[Field]| {
+            let file = File::open(dir.join(name)).unwrap();
+            let reader = StreamReader::try_new(file, None).unwrap();
+            let described: Vec<_> = reader
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| (f.name().clone(), f.is_nullable()))
+                .collect();
+            let expected: Vec<_> = fields
+                .iter()
+                .map(|f| (f.name.to_string(), f.nullable))
+                .collect();
+            assert_eq!(described, expected);
+            let mut batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+            assert_eq!(batches.len(), 1);
+            batches.remove(0)
+        };
+
+        let events = read(EVENTS_FILE, EventRow::FIELDS);
+        let ticks: Vec<_> = events.column(0).as_primitive::<UInt64Type>().iter().collect();
+        assert_eq!(ticks, vec![Some(0), Some(1), Some(2)]);
+        let kinds: Vec<_> = events.column(2).as_string::<i32>().iter().collect();
+        assert_eq!(kinds, vec![Some("weapon_fired"); 3]);
+        let slots = events.column(6).as_primitive::<UInt32Type>();
+        assert_eq!(slots.value(0), 2);
+        assert!(events.column(5).is_null(0));
+
+        let entities = read(ENTITIES_FILE, EntityRow::FIELDS);
+        assert_eq!(entities.num_rows(), 3);
+        let ids = entities.column(1).as_primitive::<UInt64Type>();
+        assert_eq!(ids.value(0), ship.as_u64());
+        let x = entities.column(4).as_primitive::<Float32Type>();
+        assert_eq!(x.value(2).to_bits(), 10.0_f32.to_bits());
+        assert!(entities.column(3).is_null(0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn entity_summaries_respect_interval() {
+        let dir = temp_dir("interval");
+        let mut arena = Arena::new();
+        arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::default()),
+        );
+
+        let mut log = BattleLog::create(BattleLogConfig {
+            entity_summary_every: 3,
+            ..BattleLogConfig::new(&dir)
+        })
+        .unwrap();
+        for tick in 0..7 {
+            log.record_tick(tick, &[], &arena).unwrap();
+        }
+        drop(log);
+
+        // Ticks 0, 3 and 6; the dropped log still terminates its streams
+        assert_eq!(batches(&dir.join(ENTITIES_FILE)), (1, 3));
+        assert_eq!(batches(&dir.join(EVENTS_FILE)), (0, 0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fog_of_war_streams_only_in_evaluation_mode() {
+        use crate::entity::TeamId;
+
+        let dir = temp_dir("fog");
+        let mut arena = Arena::new();
+        for team in 0..2 {
+            let id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            arena.set_team(id, Some(TeamId::new(team)));
+        }
+
+        let log = BattleLog::create(BattleLogConfig::new(&dir)).unwrap();
+        drop(log);
+        assert!(!dir.join(FOG_FILE).exists());
+        assert!(!dir.join(ASSOCIATION_FILE).exists());
+
+        let mut log = BattleLog::create(BattleLogConfig {
+            fog_of_war: true,
+            ..BattleLogConfig::new(&dir)
+        })
+        .unwrap();
+        for tick in 0..3 {
+            log.record_tick(tick, &[], &arena).unwrap();
+        }
+        log.finish().unwrap();
+
+        // One metrics row per team per tick; nobody has a track
+        assert_eq!(batches(&dir.join(FOG_FILE)), (1, 6));
+        assert_eq!(batches(&dir.join(ASSOCIATION_FILE)), (1, 6));
+        assert_eq!(batches(&dir.join(TRACKS_FILE)), (0, 0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,364 @@
+//! In-memory event history with a small query language.
+//!
+//! The event resolver only keeps the most recent step's events. With an
+//! [`EventHistory`] enabled (see
+//! [`Simulation::enable_event_history`](crate::simulation::Simulation::enable_event_history)),
+//! every event envelope is kept in a bounded ring so it can be searched
+//! with an [`EventQuery`] after the fact. Matches project to [`EventRow`]s,
+//! the row type of `events.arrows`, and from there to an Arrow IPC stream
+//! with [`to_arrow`].
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::battle_log::query::{EventHistory, EventQuery};
+//! use tidebreak_core::entity::EntityId;
+//! use tidebreak_core::output::{Event, Output, OutputEnvelope, PluginId, PluginInstanceId, TraceId};
+//!
+//! let (shooter, ship) = (EntityId::new(1), EntityId::new(2));
+//! let hit = |tick| OutputEnvelope::new(
+//!     Output::Event(Event::DamageDealt { source: shooter, target: ship, amount: 10.0 }),
+//!     PluginInstanceId::new(shooter, PluginId::new("weapon")),
+//!     TraceId::new(tick),
+//!     tick,
+//!     0,
+//! );
+//!
+//! let mut history = EventHistory::new();
+//! history.enable(1024);
+//! history.record([hit(50), hit(150)].iter());
+//!
+//! let query = EventQuery::new().kind("DamageDealt").target(ship).ticks(100, 200);
+//! let rows = history.rows(&query);
+//! assert_eq!(rows.len(), 1);
+//! assert_eq!(rows[0].tick, 150);
+//! ```
+
+use std::collections::VecDeque;
+use std::io;
+
+use crate::entity::EntityId;
+use crate::output::{OutputEnvelope, TraceId};
+
+use super::ipc::StreamWriter;
+use super::{EventRow, Row};
+
+// =============================================================================
+// EventQuery
+// =============================================================================
+
+/// Filter over event envelopes; every set criterion must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventQuery {
+    kind: Option<String>,
+    entity: Option<EntityId>,
+    source: Option<EntityId>,
+    target: Option<EntityId>,
+    ticks: Option<(u64, u64)>,
+    trace_id: Option<TraceId>,
+}
+
+impl EventQuery {
+    /// Creates a query matching every event.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only events of `kind`, given as the variant name (`DamageDealt`) or
+    /// in snake case (`damage_dealt`).
+    #[must_use]
+    pub fn kind(mut self, kind: &str) -> Self {
+        self.kind = Some(snake_case(kind));
+        self
+    }
+
+    /// Only events naming `entity` in any role.
+    #[must_use]
+    pub const fn entity(mut self, entity: EntityId) -> Self {
+        self.entity = Some(entity);
+        self
+    }
+
+    /// Only events emitted by a plugin (or resolver) running on `source`.
+    #[must_use]
+    pub const fn source(mut self, source: EntityId) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Only events acting upon `target` (see `Event::target`).
+    #[must_use]
+    pub const fn target(mut self, target: EntityId) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Only events produced on ticks `start..end` (end exclusive).
+    #[must_use]
+    pub const fn ticks(mut self, start: u64, end: u64) -> Self {
+        self.ticks = Some((start, end));
+        self
+    }
+
+    /// Only events in the causal trace `trace_id`.
+    #[must_use]
+    pub const fn trace_id(mut self, trace_id: TraceId) -> Self {
+        self.trace_id = Some(trace_id);
+        self
+    }
+
+    /// Returns true if `envelope` is an event matching every criterion.
+    #[must_use]
+    pub fn matches(&self, envelope: &OutputEnvelope) -> bool {
+        let Some(event) = envelope.output().as_event() else {
+            return false;
+        };
+        if self
+            .ticks
+            .is_some_and(|(start, end)| !(start..end).contains(&envelope.tick()))
+            || self.trace_id.is_some_and(|t| t != envelope.trace_id())
+            || self
+                .source
+                .is_some_and(|s| s != envelope.source().entity_id())
+            || self.target.is_some_and(|t| event.target() != Some(t))
+        {
+            return false;
+        }
+        if self.kind.is_none() && self.entity.is_none() {
+            return true;
+        }
+        let row = EventRow::new(envelope.tick(), envelope, event);
+        self.kind.as_deref().is_none_or(|kind| kind == row.kind)
+            && self.entity.is_none_or(|e| {
+                row.entity == e.as_u64() || row.other == Some(e.as_u64())
+            })
+    }
+}
+
+/// Converts a variant name such as `DamageDealt` to `damage_dealt`.
+fn snake_case(kind: &str) -> String {
+    let mut out = String::with_capacity(kind.len() + 4);
+    for (i, c) in kind.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// =============================================================================
+// EventHistory
+// =============================================================================
+
+/// Bounded record of event envelopes across ticks, oldest first.
+///
+/// Disabled (capacity zero) until [`enable`](Self::enable) is called.
+#[derive(Debug, Clone, Default)]
+pub struct EventHistory {
+    capacity: usize,
+    envelopes: VecDeque<OutputEnvelope>,
+}
+
+impl EventHistory {
+    /// Creates a disabled history.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps at most the last `capacity` events, dropping any recorded so
+    /// far. A capacity of zero disables the history.
+    pub fn enable(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.envelopes.clear();
+    }
+
+    /// Returns the number of events kept, or zero while disabled.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns true if events are being recorded.
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Appends the event envelopes among `envelopes`, dropping the oldest
+    /// once full. Does nothing while disabled.
+    pub fn record<'a>(&mut self, envelopes: impl IntoIterator<Item = &'a OutputEnvelope>) {
+        if self.capacity == 0 {
+            return;
+        }
+        for envelope in envelopes {
+            if !envelope.output().is_event() {
+                continue;
+            }
+            if self.envelopes.len() == self.capacity {
+                self.envelopes.pop_front();
+            }
+            self.envelopes.push_back(envelope.clone());
+        }
+    }
+
+    /// Returns the number of recorded events.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.envelopes.len()
+    }
+
+    /// Returns true if no events are recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.envelopes.is_empty()
+    }
+
+    /// Drops all recorded events, staying enabled.
+    pub fn clear(&mut self) {
+        self.envelopes.clear();
+    }
+
+    /// Iterates over the recorded events, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &OutputEnvelope> {
+        self.envelopes.iter()
+    }
+
+    /// Returns the recorded events matching `query`, oldest first.
+    #[must_use]
+    pub fn query(&self, query: &EventQuery) -> Vec<&OutputEnvelope> {
+        self.envelopes.iter().filter(|e| query.matches(e)).collect()
+    }
+
+    /// Returns the recorded events matching `query` as flat rows.
+    #[must_use]
+    pub fn rows(&self, query: &EventQuery) -> Vec<EventRow> {
+        self.envelopes
+            .iter()
+            .filter(|e| query.matches(e))
+            .filter_map(EventRow::from_envelope)
+            .collect()
+    }
+}
+
+/// Encodes `rows` as an Arrow IPC stream with the `events.arrows` schema.
+///
+/// # Errors
+///
+/// Returns an error only if encoding fails, which indicates a bug.
+pub fn to_arrow(rows: &[EventRow]) -> io::Result<Vec<u8>> {
+    let mut writer = StreamWriter::new(Vec::new(), EventRow::FIELDS)?;
+    if !rows.is_empty() {
+        writer.write_batch(&EventRow::columns(rows))?;
+    }
+    writer.finish()
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{Command, Event, Output, PluginId, PluginInstanceId};
+
+    fn envelope(output: Output, source: u64, tick: u64, trace: u64) -> OutputEnvelope {
+        OutputEnvelope::new(
+            output,
+            PluginInstanceId::new(EntityId::new(source), PluginId::new("test")),
+            TraceId::new(trace),
+            tick,
+            0,
+        )
+    }
+
+    fn damage(source: u64, target: u64, tick: u64) -> OutputEnvelope {
+        let event = Event::DamageDealt {
+            source: EntityId::new(source),
+            target: EntityId::new(target),
+            amount: 5.0,
+        };
+        envelope(Output::Event(event), source, tick, tick)
+    }
+
+    fn fired(source: u64, tick: u64) -> OutputEnvelope {
+        let event = Event::WeaponFired {
+            source: EntityId::new(source),
+            weapon_slot: 0,
+        };
+        envelope(Output::Event(event), source, tick, 99)
+    }
+
+    fn history() -> EventHistory {
+        let mut history = EventHistory::new();
+        history.enable(16);
+        history.record(&[damage(1, 2, 10), fired(1, 10), damage(3, 1, 20), fired(3, 30)]);
+        history
+    }
+
+    #[test]
+    fn snake_case_accepts_both_spellings() {
+        assert_eq!(snake_case("DamageDealt"), "damage_dealt");
+        assert_eq!(snake_case("damage_dealt"), "damage_dealt");
+    }
+
+    #[test]
+    fn filters_combine() {
+        let history = history();
+        let count = |query: EventQuery| history.query(&query).len();
+
+        assert_eq!(count(EventQuery::new()), 4);
+        assert_eq!(count(EventQuery::new().kind("WeaponFired")), 2);
+        // Entity 1 shoots at tick 10 and is hit at tick 20
+        assert_eq!(count(EventQuery::new().entity(EntityId::new(1))), 3);
+        assert_eq!(count(EventQuery::new().target(EntityId::new(1))), 1);
+        assert_eq!(count(EventQuery::new().source(EntityId::new(3))), 2);
+        assert_eq!(count(EventQuery::new().ticks(10, 20)), 2);
+        assert_eq!(count(EventQuery::new().trace_id(TraceId::new(99))), 2);
+        assert_eq!(
+            count(EventQuery::new().kind("damage_dealt").ticks(15, 25)),
+            1
+        );
+    }
+
+    #[test]
+    fn history_is_bounded_and_skips_non_events() {
+        let mut history = EventHistory::new();
+        history.record(&[fired(1, 0)]);
+        assert!(history.is_empty());
+
+        history.enable(2);
+        let command = Command::SetHeading {
+            target: EntityId::new(1),
+            heading: 0.0,
+        };
+        history.record(&[
+            fired(1, 0),
+            envelope(Output::Command(command), 1, 0, 0),
+            fired(1, 1),
+            fired(1, 2),
+        ]);
+        let ticks: Vec<u64> = history.iter().map(OutputEnvelope::tick).collect();
+        assert_eq!(ticks, vec![1, 2]);
+    }
+
+    #[test]
+    fn arrow_stream_has_one_batch() {
+        let history = history();
+        let rows = history.rows(&EventQuery::new().kind("DamageDealt"));
+        assert_eq!(rows[0].other, Some(1));
+
+        let bytes = to_arrow(&rows).unwrap();
+        let empty = to_arrow(&[]).unwrap();
+        assert!(bytes.len() > empty.len());
+        // End-of-stream marker
+        assert_eq!(bytes[bytes.len() - 8..], [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]);
+    }
+}
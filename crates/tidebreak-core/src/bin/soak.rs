@@ -0,0 +1,482 @@
+//! Long-run soak test for the combat simulation.
+//!
+//! Runs a large scenario for millions of ticks and fails if the process
+//! breaches a resource ceiling or stops being deterministic. Regressions such
+//! as unbounded logs, leaked entities or slowly degrading tick times only show
+//! up hours into a training run; this binary finds them before that.
+//!
+//! # Scenario
+//!
+//! Two teams of ships orbit the origin on a ring, running the default plugin
+//! bundles plus classification. Every ship broadcasts an intent each tick, and
+//! one ship is despawned and replaced every `--churn-every` ticks to exercise
+//! entity lifecycle paths.
+//!
+//! # Checks
+//!
+//! Every `--checkpoint-every` ticks the harness:
+//!
+//! - compares resident memory against `--max-rss-mb` (Linux only)
+//! - compares the mean tick time of the window against `--max-tick-ms`
+//! - compares the entity count against `--max-entities`
+//! - replays the window from the previous checkpoint in a fresh simulation and
+//!   verifies the state hash matches
+//!
+//! On failure the last few checkpoint reports are printed so the trend leading
+//! up to the breach is visible, and the process exits with status 1.
+//!
+//! # Usage
+//!
+//! ```text
+//! cargo run --release -p tidebreak-core --bin soak -- --ticks 5000000 --ships 200
+//! ```
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use glam::Vec2;
+
+use tidebreak_core::entity::{EntityId, EntityInner, EntityTag, ShipComponents, TeamId};
+use tidebreak_core::output::{Command, Output, OutputKind, PluginId};
+use tidebreak_core::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+use tidebreak_core::resolver::ClassificationModel;
+use tidebreak_core::{Arena, PluginRegistry, Simulation, WorldView};
+
+/// Radius of the ring the ships orbit on.
+const RING_RADIUS: f32 = 20_000.0;
+
+/// Orbital speed of the ships.
+const ORBIT_SPEED: f32 = 30.0;
+
+/// Number of checkpoint reports kept for failure diagnostics.
+const REPORT_HISTORY: usize = 8;
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+const USAGE: &str = "\
+Usage: soak [OPTIONS]
+
+Options:
+  --ticks <N>             Total ticks to run [default: 2000000]
+  --ships <N>             Ships in the scenario [default: 200]
+  --seed <N>              Master seed [default: 42]
+  --checkpoint-every <N>  Ticks between checkpoints [default: 10000]
+  --churn-every <N>       Ticks between ship replacements, 0 disables [default: 1000]
+  --max-rss-mb <N>        Resident memory ceiling in MiB [default: 1024]
+  --max-tick-ms <F>       Mean tick time ceiling per window [default: 20]
+  --max-entities <N>      Entity count ceiling [default: 2 * ships]
+  -h, --help              Print this help";
+
+/// Soak run parameters.
+#[derive(Debug, Clone)]
+struct SoakConfig {
+    ticks: u64,
+    ships: usize,
+    seed: u64,
+    checkpoint_every: u64,
+    churn_every: u64,
+    max_rss_mb: f64,
+    max_tick_ms: f64,
+    max_entities: usize,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            ticks: 2_000_000,
+            ships: 200,
+            seed: 42,
+            checkpoint_every: 10_000,
+            churn_every: 1_000,
+            max_rss_mb: 1024.0,
+            max_tick_ms: 20.0,
+            max_entities: 400,
+        }
+    }
+}
+
+impl SoakConfig {
+    /// Parses command-line arguments (without the program name).
+    ///
+    /// Returns `Ok(None)` if help was requested.
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut config = Self::default();
+        let mut max_entities = None;
+
+        while let Some(flag) = args.next() {
+            if flag == "-h" || flag == "--help" {
+                return Ok(None);
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for '{flag}'"))?;
+            match flag.as_str() {
+                "--ticks" => config.ticks = parse(&flag, &value)?,
+                "--ships" => config.ships = parse(&flag, &value)?,
+                "--seed" => config.seed = parse(&flag, &value)?,
+                "--checkpoint-every" => config.checkpoint_every = parse(&flag, &value)?,
+                "--churn-every" => config.churn_every = parse(&flag, &value)?,
+                "--max-rss-mb" => config.max_rss_mb = parse(&flag, &value)?,
+                "--max-tick-ms" => config.max_tick_ms = parse(&flag, &value)?,
+                "--max-entities" => max_entities = Some(parse(&flag, &value)?),
+                _ => return Err(format!("unknown option '{flag}'")),
+            }
+        }
+
+        if config.checkpoint_every == 0 {
+            return Err("--checkpoint-every must be positive".to_string());
+        }
+        config.max_entities = max_entities.unwrap_or(config.ships * 2);
+        Ok(Some(config))
+    }
+}
+
+fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for '{flag}'"))
+}
+
+// =============================================================================
+// Scenario
+// =============================================================================
+
+/// Steers ships onto a circular orbit around the origin.
+///
+/// Keeps the scenario spatially bounded no matter how long it runs.
+struct OrbitPlugin {
+    declaration: PluginDeclaration,
+}
+
+impl OrbitPlugin {
+    fn new() -> Self {
+        Self {
+            declaration: PluginDeclaration {
+                id: PluginId::from_static("soak_orbit"),
+                required_tags: vec![EntityTag::Ship],
+                reads: vec![ComponentKind::Transform],
+                emits: vec![OutputKind::Command],
+                runs_after: vec![],
+                every_tick: false,
+            },
+        }
+    }
+}
+
+impl Plugin for OrbitPlugin {
+    fn declaration(&self) -> &PluginDeclaration {
+        &self.declaration
+    }
+
+    fn run(&self, ctx: &PluginContext, view: &WorldView) -> Vec<Output> {
+        let Some(transform) = view.get_transform(ctx.entity_id) else {
+            return vec![];
+        };
+        let radial = transform.position.normalize_or(Vec2::X);
+        let tangent = radial.perp();
+        let correction = (RING_RADIUS - transform.position.length()) * 0.01;
+        vec![Output::Command(Command::SetVelocity {
+            target: ctx.entity_id,
+            velocity: tangent * ORBIT_SPEED + radial * correction,
+        })]
+    }
+}
+
+/// Builds a simulation with the soak plugin and resolver set.
+///
+/// Used for both the main run and checkpoint replays, so the two are
+/// guaranteed to be configured identically.
+fn build_simulation(seed: u64) -> Simulation {
+    let mut sim = Simulation::new(seed);
+    *sim.plugins_mut() = PluginRegistry::default_bundles();
+    sim.plugins_mut()
+        .register(EntityTag::Ship, Arc::new(OrbitPlugin::new()));
+    sim.enable_classification(ClassificationModel::default());
+    sim
+}
+
+/// Spawns a ship on the ring at `angle`, alternating teams by `slot`.
+fn spawn_ring_ship(arena: &mut Arena, angle: f32, slot: u64) -> EntityId {
+    let position = Vec2::from_angle(angle) * RING_RADIUS;
+    let components = ShipComponents::at_position(position, angle);
+    let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(components));
+    arena.set_team(id, Some(TeamId::new(u32::from(slot % 2 == 1))));
+    id
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn populate(arena: &mut Arena, ships: usize) {
+    for slot in 0..ships as u64 {
+        let angle = std::f32::consts::TAU * slot as f32 / ships as f32;
+        spawn_ring_ship(arena, angle, slot);
+    }
+}
+
+/// Advances the scenario by one tick.
+///
+/// Harness-side mutations (intents, churn) happen here rather than in
+/// plugins, and replays go through the same function.
+#[allow(clippy::cast_precision_loss)]
+fn advance(sim: &mut Simulation, churn_every: u64) {
+    let tick = sim.tick();
+    let arena = sim.arena_mut();
+
+    let ids: Vec<_> = arena.entity_ids_sorted().collect();
+    for id in &ids {
+        let position = arena.spatial().get(*id).unwrap_or(Vec2::ZERO);
+        // Payload is within the default max_intent_len
+        let _ = arena
+            .comms_mut()
+            .broadcast(*id, vec![position.x, position.y], tick);
+    }
+
+    if churn_every > 0 && tick > 0 && tick.is_multiple_of(churn_every) {
+        if let Some(oldest) = ids.first() {
+            arena.despawn(*oldest);
+        }
+        let angle = (tick / churn_every) as f32 * 0.618_034 * std::f32::consts::TAU;
+        spawn_ring_ship(arena, angle, tick / churn_every);
+    }
+
+    sim.step();
+    let _ = sim.take_events();
+}
+
+// =============================================================================
+// Measurement
+// =============================================================================
+
+/// Resident set size of this process in MiB, if available.
+///
+/// Reads `/proc/self/statm` and assumes 4 KiB pages.
+#[allow(clippy::cast_precision_loss)]
+fn resident_mb() -> Option<f64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some((pages * 4096) as f64 / (1024.0 * 1024.0))
+}
+
+/// Metrics gathered at one checkpoint.
+#[derive(Debug, Clone, Copy)]
+struct Report {
+    tick: u64,
+    entities: usize,
+    rss_mb: Option<f64>,
+    mean_tick_ms: f64,
+    max_tick_ms: f64,
+    hash: u64,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tick {:>10}  entities {:>5}  rss ",
+            self.tick, self.entities
+        )?;
+        match self.rss_mb {
+            Some(mb) => write!(f, "{mb:>8.1} MiB")?,
+            None => write!(f, "{:>12}", "n/a")?,
+        }
+        write!(
+            f,
+            "  tick mean {:>7.3} ms  max {:>8.3} ms  hash {:016x}",
+            self.mean_tick_ms, self.max_tick_ms, self.hash
+        )
+    }
+}
+
+/// Why a soak run failed.
+#[derive(Debug, Clone, Copy)]
+enum Failure {
+    Memory {
+        rss_mb: f64,
+        ceiling: f64,
+    },
+    TickTime {
+        mean_ms: f64,
+        ceiling: f64,
+    },
+    Entities {
+        count: usize,
+        ceiling: usize,
+    },
+    Divergence {
+        from_tick: u64,
+        expected: u64,
+        replayed: u64,
+    },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Memory { rss_mb, ceiling } => {
+                write!(
+                    f,
+                    "resident memory {rss_mb:.1} MiB exceeds ceiling {ceiling:.1} MiB"
+                )
+            }
+            Self::TickTime { mean_ms, ceiling } => {
+                write!(
+                    f,
+                    "mean tick time {mean_ms:.3} ms exceeds ceiling {ceiling:.3} ms"
+                )
+            }
+            Self::Entities { count, ceiling } => {
+                write!(f, "entity count {count} exceeds ceiling {ceiling}")
+            }
+            Self::Divergence {
+                from_tick,
+                expected,
+                replayed,
+            } => write!(
+                f,
+                "replay from tick {from_tick} diverged: expected hash {expected:016x}, \
+                 replayed {replayed:016x}"
+            ),
+        }
+    }
+}
+
+// =============================================================================
+// Run
+// =============================================================================
+
+/// Replays `ticks` ticks from `checkpoint` and returns the final state hash.
+fn replay(config: &SoakConfig, checkpoint: &Arena, ticks: u64) -> u64 {
+    let mut sim = build_simulation(config.seed);
+    sim.restore(checkpoint.clone());
+    for _ in 0..ticks {
+        advance(&mut sim, config.churn_every);
+    }
+    sim.arena().state_hash()
+}
+
+/// Checks one finished window against the ceilings.
+fn check(config: &SoakConfig, report: &Report) -> Result<(), Failure> {
+    if let Some(rss_mb) = report.rss_mb {
+        if rss_mb > config.max_rss_mb {
+            return Err(Failure::Memory {
+                rss_mb,
+                ceiling: config.max_rss_mb,
+            });
+        }
+    }
+    if report.mean_tick_ms > config.max_tick_ms {
+        return Err(Failure::TickTime {
+            mean_ms: report.mean_tick_ms,
+            ceiling: config.max_tick_ms,
+        });
+    }
+    if report.entities > config.max_entities {
+        return Err(Failure::Entities {
+            count: report.entities,
+            ceiling: config.max_entities,
+        });
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn run(config: &SoakConfig) -> Result<(), (Failure, VecDeque<Report>)> {
+    let mut sim = build_simulation(config.seed);
+    populate(sim.arena_mut(), config.ships);
+
+    let mut checkpoint = sim.arena().clone();
+    let mut history: VecDeque<Report> = VecDeque::with_capacity(REPORT_HISTORY);
+    let mut window_total = Duration::ZERO;
+    let mut window_max = Duration::ZERO;
+    let mut window_ticks = 0_u64;
+
+    while sim.tick() < config.ticks {
+        let started = Instant::now();
+        advance(&mut sim, config.churn_every);
+        let elapsed = started.elapsed();
+        window_total += elapsed;
+        window_max = window_max.max(elapsed);
+        window_ticks += 1;
+
+        if window_ticks < config.checkpoint_every && sim.tick() < config.ticks {
+            continue;
+        }
+
+        let report = Report {
+            tick: sim.tick(),
+            entities: sim.arena().entity_count(),
+            rss_mb: resident_mb(),
+            mean_tick_ms: window_total.as_secs_f64() * 1000.0 / window_ticks as f64,
+            max_tick_ms: window_max.as_secs_f64() * 1000.0,
+            hash: sim.arena().state_hash(),
+        };
+        println!("{report}");
+        if history.len() == REPORT_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(report);
+
+        if let Err(failure) = check(config, &report) {
+            return Err((failure, history));
+        }
+
+        let replayed = replay(config, &checkpoint, window_ticks);
+        if replayed != report.hash {
+            let failure = Failure::Divergence {
+                from_tick: checkpoint.current_tick(),
+                expected: report.hash,
+                replayed,
+            };
+            return Err((failure, history));
+        }
+
+        checkpoint = sim.arena().clone();
+        window_total = Duration::ZERO;
+        window_max = Duration::ZERO;
+        window_ticks = 0;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let config = match SoakConfig::from_args(std::env::args().skip(1)) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    println!("soak: {config:?}");
+    let started = Instant::now();
+    match run(&config) {
+        Ok(()) => {
+            println!(
+                "soak passed: {} ticks in {:.1} s",
+                config.ticks,
+                started.elapsed().as_secs_f64()
+            );
+            ExitCode::SUCCESS
+        }
+        Err((failure, history)) => {
+            eprintln!("soak FAILED: {failure}");
+            eprintln!("last {} checkpoints:", history.len());
+            for report in &history {
+                eprintln!("  {report}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
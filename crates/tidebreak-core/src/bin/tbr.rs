@@ -0,0 +1,186 @@
+//! Inspector for `.tbr` replay files.
+//!
+//! Prints what a replay holds without a simulation: the header and
+//! keyframes, the recorded events, or the entities of a keyframe. To watch a
+//! replay at an arbitrary tick, load it with
+//! [`Replay::import`](tidebreak_core::replay::Replay::import) and
+//! [`seek`](tidebreak_core::replay::Replay::seek) a simulation set up like
+//! the recorded one.
+//!
+//! # Usage
+//!
+//! ```text
+//! cargo run -p tidebreak-core --features replay --bin tbr -- info run.tbr
+//! cargo run -p tidebreak-core --features replay --bin tbr -- events run.tbr --kind damage_dealt
+//! cargo run -p tidebreak-core --features replay --bin tbr -- frame run.tbr 600
+//! ```
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::process::ExitCode;
+
+use tidebreak_core::battle_log::EventRow;
+use tidebreak_core::entity::EntityInner;
+use tidebreak_core::replay::Replay;
+
+const USAGE: &str = "\
+Usage: tbr <COMMAND> <FILE> [OPTIONS]
+
+Commands:
+  info <FILE>             Print the header, keyframes and event counts
+  events <FILE>           Print one line per recorded event
+      --from <TICK>       First tick to print [default: start]
+      --to <TICK>         Last tick to print [default: end]
+      --kind <KIND>       Only events of this kind (e.g. damage_dealt)
+  frame <FILE> <TICK>     Print the entities of the keyframe at or before TICK
+  -h, --help              Print this help";
+
+fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for '{flag}'"))
+}
+
+fn load(path: &str) -> Result<Replay, String> {
+    let file = File::open(path).map_err(|e| format!("cannot open '{path}': {e}"))?;
+    Replay::import(BufReader::new(file)).map_err(|e| format!("cannot read '{path}': {e}"))
+}
+
+fn info(replay: &Replay) {
+    let header = replay.header();
+    println!("seed:      {}", header.seed);
+    println!("scenario:  {}", header.scenario.as_deref().unwrap_or("-"));
+    println!(
+        "ticks:     {}..{} ({} steps)",
+        header.start_tick,
+        replay.end_tick(),
+        header.tick_count
+    );
+    let keyframes: Vec<String> = replay.keyframes().iter().map(|k| k.tick.to_string()).collect();
+    println!(
+        "keyframes: every {} steps at {}",
+        header.keyframe_interval,
+        keyframes.join(", ")
+    );
+
+    let mut counts = BTreeMap::new();
+    for row in replay
+        .ticks()
+        .iter()
+        .flat_map(|t| &t.events)
+        .filter_map(EventRow::from_envelope)
+    {
+        *counts.entry(row.kind).or_insert(0_usize) += 1;
+    }
+    println!("events:");
+    for (kind, count) in counts {
+        println!("  {kind:<24} {count}");
+    }
+}
+
+fn events(replay: &Replay, mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let (mut from, mut to, mut kind) = (0, u64::MAX, None);
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for '{flag}'"))?;
+        match flag.as_str() {
+            "--from" => from = parse(&flag, &value)?,
+            "--to" => to = parse(&flag, &value)?,
+            "--kind" => kind = Some(value),
+            _ => return Err(format!("unknown option '{flag}'")),
+        }
+    }
+
+    println!("{:>8} {:<24} {:>10} {:>10} {:>10}", "tick", "kind", "entity", "other", "value");
+    let rows = replay
+        .ticks()
+        .iter()
+        .filter(|t| (from..=to).contains(&t.tick))
+        .flat_map(|t| &t.events)
+        .filter_map(EventRow::from_envelope)
+        .filter(|row| kind.as_deref().is_none_or(|kind| row.kind == kind));
+    for row in rows {
+        let other = row.other.map_or_else(|| "-".to_string(), |id| id.to_string());
+        let value = row.value.map_or_else(|| "-".to_string(), |v| format!("{v:.2}"));
+        println!(
+            "{:>8} {:<24} {:>10} {other:>10} {value:>10}",
+            row.tick, row.kind, row.entity
+        );
+    }
+    Ok(())
+}
+
+fn frame(replay: &Replay, tick: u64) -> Result<(), String> {
+    let keyframe = replay
+        .keyframes()
+        .iter()
+        .rev()
+        .find(|k| k.tick <= tick)
+        .ok_or_else(|| format!("no keyframe at or before tick {tick}"))?;
+    println!("keyframe at tick {}", keyframe.tick);
+    println!(
+        "{:>10} {:<10} {:>5} {:>10} {:>10} {:>8} {:>8}",
+        "entity", "tag", "team", "x", "y", "heading", "hp"
+    );
+    for entity in keyframe.arena.entities_sorted() {
+        let (transform, hp) = match entity.inner() {
+            EntityInner::Ship(c) => (&c.transform, format!("{:.1}", c.combat.hp)),
+            EntityInner::Squadron(c) => (&c.transform, format!("{:.1}", c.combat.hp)),
+            EntityInner::Platform(c) => (&c.transform, "-".to_string()),
+            EntityInner::Projectile(c) => (&c.transform, "-".to_string()),
+        };
+        let team = entity
+            .team()
+            .map_or_else(|| "-".to_string(), |t| t.as_u32().to_string());
+        println!(
+            "{:>10} {:<10} {team:>5} {:>10.1} {:>10.1} {:>8.3} {hp:>8}",
+            entity.id().as_u64(),
+            format!("{:?}", entity.tag()),
+            transform.position.x,
+            transform.position.y,
+            transform.heading
+        );
+    }
+    Ok(())
+}
+
+fn run(mut args: impl Iterator<Item = String>) -> Result<bool, String> {
+    let Some(command) = args.next() else {
+        return Ok(false);
+    };
+    if command == "-h" || command == "--help" {
+        return Ok(false);
+    }
+    let path = args.next().ok_or("missing replay file")?;
+    let replay = load(&path)?;
+    match command.as_str() {
+        "info" => info(&replay),
+        "events" => events(&replay, args)?,
+        "frame" => {
+            let tick = args.next().ok_or("missing tick")?;
+            frame(&replay, parse("TICK", &tick)?)?;
+        }
+        _ => return Err(format!("unknown command '{command}'")),
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,714 @@
+//! Bandwidth-limited observation codec.
+//!
+//! Research into policies operating over degraded datalinks needs
+//! observations that fit a fixed bit budget. [`ObservationCodec`] quantizes
+//! columns of `f32` values to a uniform number of bits per value and
+//! bit-packs the result, so the encoding cost stays in Rust rather than
+//! Python.
+//!
+//! Each column is quantized over its own range, so a feature spanning
+//! kilometers does not wipe out one spanning radians. Put values of one
+//! feature (e.g. the x of every contact) in one column.
+//!
+//! # Wire Format
+//!
+//! | Field      | Bits               | Description                          |
+//! |------------|--------------------|--------------------------------------|
+//! | columns    | 16                 | Number of columns                    |
+//! | bits       | 8                  | Bits per value (1..=16)              |
+//! | per column | 80 each            | Value count (16), then the minimum   |
+//! |            |                    | and maximum of its range (`f32`)     |
+//! | values     | `count * bits`     | Quantized values, column by column,  |
+//! |            |                    | MSB first                            |
+//!
+//! The headers count against the bit budget, which is met exactly: bits per
+//! value are the largest (capped at 16) that keep the whole message, padded
+//! to whole bytes, within the budget.
+//!
+//! # Compact Dtypes
+//!
+//! Replay buffers are memory-bound rather than bandwidth-bound, so
+//! [`ObservationDtype`] offers fixed-width alternatives to `f32`: IEEE
+//! half precision ([`f32_to_f16_bits`]) or `i8` with a fixed scale
+//! ([`quantize_i8`]). Both are elementwise and need no header.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::codec::ObservationCodec;
+//!
+//! let codec = ObservationCodec::new(512);
+//! let positions = [1200.0, -3400.0, 560.0];
+//! let headings = [0.1, 3.0, -1.5];
+//!
+//! let packed = codec.encode_columns(&[&positions, &headings]).unwrap();
+//! assert!(packed.len() * 8 <= 512);
+//!
+//! let decoded = ObservationCodec::decode_columns(&packed).unwrap();
+//! for (a, b) in headings.iter().zip(&decoded[1]) {
+//!     assert!((a - b).abs() < 0.01);
+//! }
+//! ```
+
+use thiserror::Error;
+
+/// Size of the message header in bits.
+pub const HEADER_BITS: usize = 16 + 8;
+
+/// Size of each column's header in bits.
+pub const COLUMN_HEADER_BITS: usize = 16 + 32 + 32;
+
+/// Maximum number of bits used per value.
+pub const MAX_BITS_PER_VALUE: u8 = 16;
+
+/// Errors produced while encoding or decoding observations.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CodecError {
+    /// The budget cannot fit the headers plus one bit per value.
+    #[error("bit budget {budget} is too small for {count} values")]
+    BudgetTooSmall {
+        /// Configured bit budget.
+        budget: usize,
+        /// Number of values to encode.
+        count: usize,
+    },
+    /// More values in a column than its header can describe.
+    #[error("cannot encode {0} values in a column (maximum is 65535)")]
+    TooManyValues(usize),
+    /// More columns than the header can describe.
+    #[error("cannot encode {0} columns (maximum is 65535)")]
+    TooManyColumns(usize),
+    /// The input contains NaN or infinite values.
+    #[error("cannot encode non-finite values")]
+    NonFinite,
+    /// The message is shorter than its header claims.
+    #[error("packed message is truncated")]
+    Truncated,
+    /// The header declares an invalid bits-per-value.
+    #[error("invalid bits per value: {0}")]
+    InvalidBits(u8),
+    /// A column header declares a range that is not finite and ordered.
+    #[error("invalid column range")]
+    InvalidRange,
+}
+
+// =============================================================================
+// Bit Packing
+// =============================================================================
+
+/// MSB-first bit writer.
+#[derive(Debug, Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_len: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u8) {
+        for i in (0..bits).rev() {
+            if self.bit_len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if (value >> i) & 1 == 1 {
+                let last = self.bytes.len() - 1;
+                self.bytes[last] |= 0x80 >> (self.bit_len % 8);
+            }
+            self.bit_len += 1;
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// MSB-first bit reader.
+#[derive(Debug)]
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn read(&mut self, bits: u8) -> Result<u32, CodecError> {
+        let mut value = 0u32;
+        for _ in 0..bits {
+            let byte = self.bytes.get(self.pos / 8).ok_or(CodecError::Truncated)?;
+            let bit = (byte >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | u32::from(bit);
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+}
+
+// =============================================================================
+// ObservationCodec
+// =============================================================================
+
+/// Range a column is quantized over.
+///
+/// The span is taken in `f64`: the distance between two finite `f32`s can
+/// overflow `f32` (e.g. `f32::MIN` to `f32::MAX`) but not `f64`.
+#[derive(Debug, Clone, Copy)]
+struct ColumnRange {
+    min: f32,
+    max: f32,
+}
+
+impl ColumnRange {
+    fn of(values: &[f32]) -> Self {
+        let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        if values.is_empty() {
+            Self { min: 0.0, max: 0.0 }
+        } else {
+            Self { min, max }
+        }
+    }
+
+    fn span(self) -> f64 {
+        f64::from(self.max) - f64::from(self.min)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn quantize(self, value: f32, levels: f64) -> u32 {
+        let span = self.span();
+        if span > 0.0 {
+            // In [0, levels], so the conversion is exact
+            ((f64::from(value) - f64::from(self.min)) / span * levels).round() as u32
+        } else {
+            0
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn dequantize(self, q: u32, levels: f64) -> f32 {
+        // Between min and max, so back in f32 range
+        (f64::from(self.min) + self.span() * (f64::from(q) / levels)) as f32
+    }
+}
+
+/// Quantizing bit-packer with a fixed bit budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObservationCodec {
+    bit_budget: usize,
+}
+
+impl ObservationCodec {
+    /// Creates a codec that produces messages of at most `bit_budget` bits.
+    #[must_use]
+    pub const fn new(bit_budget: usize) -> Self {
+        Self { bit_budget }
+    }
+
+    /// Returns the configured bit budget.
+    #[must_use]
+    pub const fn bit_budget(&self) -> usize {
+        self.bit_budget
+    }
+
+    /// Returns the bits per value used for a message of `count` values in
+    /// `columns` columns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CodecError::BudgetTooSmall`] if not even one bit per value
+    /// fits after the headers.
+    pub fn bits_per_value(&self, columns: usize, count: usize) -> Result<u8, CodecError> {
+        let too_small = CodecError::BudgetTooSmall {
+            budget: self.bit_budget,
+            count,
+        };
+        // Messages are padded to whole bytes, and the padding counts too
+        let usable = self.bit_budget / 8 * 8;
+        let headers = HEADER_BITS + columns * COLUMN_HEADER_BITS;
+        let Some(available) = usable.checked_sub(headers) else {
+            return Err(too_small);
+        };
+        if count == 0 {
+            return Ok(MAX_BITS_PER_VALUE);
+        }
+        let available = available / count;
+        if available == 0 {
+            return Err(too_small);
+        }
+        // Capped at 16, so the narrowing is lossless.
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(available.min(usize::from(MAX_BITS_PER_VALUE)) as u8)
+    }
+
+    /// Quantizes and packs `values` as a single column.
+    ///
+    /// # Errors
+    ///
+    /// See [`encode_columns`](Self::encode_columns).
+    pub fn encode(&self, values: &[f32]) -> Result<Vec<u8>, CodecError> {
+        self.encode_columns(&[values])
+    }
+
+    /// Quantizes each column over its own range and packs them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are too many columns or values in one,
+    /// the input contains non-finite values, or it does not fit the bit
+    /// budget.
+    pub fn encode_columns(&self, columns: &[&[f32]]) -> Result<Vec<u8>, CodecError> {
+        let column_count =
+            u16::try_from(columns.len()).map_err(|_| CodecError::TooManyColumns(columns.len()))?;
+        let mut lengths = Vec::with_capacity(columns.len());
+        for column in columns {
+            let len =
+                u16::try_from(column.len()).map_err(|_| CodecError::TooManyValues(column.len()))?;
+            if column.iter().any(|v| !v.is_finite()) {
+                return Err(CodecError::NonFinite);
+            }
+            lengths.push(len);
+        }
+        let count = columns.iter().map(|column| column.len()).sum();
+        let bits = self.bits_per_value(columns.len(), count)?;
+        let levels = f64::from((1u32 << bits) - 1);
+        let ranges: Vec<ColumnRange> = columns.iter().copied().map(ColumnRange::of).collect();
+
+        let mut writer = BitWriter::default();
+        writer.write(u32::from(column_count), 16);
+        writer.write(u32::from(bits), 8);
+        for (len, range) in lengths.iter().zip(&ranges) {
+            writer.write(u32::from(*len), 16);
+            writer.write(range.min.to_bits(), 32);
+            writer.write(range.max.to_bits(), 32);
+        }
+        for (column, range) in columns.iter().zip(&ranges) {
+            for &v in *column {
+                writer.write(range.quantize(v, levels), bits);
+            }
+        }
+        Ok(writer.finish())
+    }
+
+    /// Unpacks and dequantizes a message produced by [`encode`](Self::encode)
+    /// or [`encode_columns`](Self::encode_columns), with the columns one
+    /// after another.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is truncated or malformed.
+    pub fn decode(packed: &[u8]) -> Result<Vec<f32>, CodecError> {
+        Ok(Self::decode_columns(packed)?.concat())
+    }
+
+    /// Unpacks and dequantizes a message into its columns.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is truncated or malformed.
+    pub fn decode_columns(packed: &[u8]) -> Result<Vec<Vec<f32>>, CodecError> {
+        let mut reader = BitReader::new(packed);
+        let column_count = reader.read(16)?;
+        // Read as 8 bits, so always fits.
+        #[allow(clippy::cast_possible_truncation)]
+        let bits = reader.read(8)? as u8;
+        if bits == 0 || bits > MAX_BITS_PER_VALUE {
+            return Err(CodecError::InvalidBits(bits));
+        }
+        let levels = f64::from((1u32 << bits) - 1);
+
+        let mut headers = Vec::new();
+        for _ in 0..column_count {
+            let len = reader.read(16)?;
+            let range = ColumnRange {
+                min: f32::from_bits(reader.read(32)?),
+                max: f32::from_bits(reader.read(32)?),
+            };
+            // Also rejects NaN
+            if !(range.min.is_finite() && range.max.is_finite() && range.min <= range.max) {
+                return Err(CodecError::InvalidRange);
+            }
+            headers.push((len, range));
+        }
+        headers
+            .into_iter()
+            .map(|(len, range)| {
+                (0..len)
+                    .map(|_| reader.read(bits).map(|q| range.dequantize(q, levels)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Worst-case absolute reconstruction error for `columns`.
+    ///
+    /// This is half a quantization step over the widest column range.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CodecError::BudgetTooSmall`] if the values do not fit.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn max_error(&self, columns: &[&[f32]]) -> Result<f32, CodecError> {
+        let count = columns.iter().map(|column| column.len()).sum();
+        let bits = self.bits_per_value(columns.len(), count)?;
+        let span = columns
+            .iter()
+            .map(|column| ColumnRange::of(column).span())
+            .fold(0.0, f64::max);
+        Ok((span / f64::from((1u32 << bits) - 1) / 2.0) as f32)
+    }
+}
+
+// =============================================================================
+// Compact Dtypes
+// =============================================================================
+
+/// Element type of a flat observation buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ObservationDtype {
+    /// Full precision
+    #[default]
+    F32,
+    /// IEEE 754 half precision, stored as raw `u16` bits
+    F16,
+    /// Signed bytes; each value is stored as `round(value / scale)`,
+    /// saturating at ±127
+    Int8 {
+        /// Value of one quantization step
+        scale: f32,
+    },
+}
+
+impl ObservationDtype {
+    /// Returns the size of one value in bytes.
+    #[must_use]
+    pub const fn bytes_per_value(&self) -> usize {
+        match self {
+            Self::F32 => 4,
+            Self::F16 => 2,
+            Self::Int8 { .. } => 1,
+        }
+    }
+}
+
+/// Converts `value` to IEEE 754 half-precision bits, rounding to nearest
+/// even.
+///
+/// Values beyond the half-precision range become infinite; NaN stays NaN.
+#[must_use]
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+pub const fn f32_to_f16_bits(value: f32) -> u16 {
+    const fn round(value: u32, dropped: u32, halfway: u32) -> u32 {
+        if dropped > halfway || (dropped == halfway && value & 1 == 1) {
+            value + 1
+        } else {
+            value
+        }
+    }
+
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x007f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa == 0 { 0 } else { 0x0200 };
+        return sign | 0x7c00 | nan;
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        // Subnormal half (or zero)
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x0080_0000;
+        let shift = (14 - exponent) as u32;
+        let half = round(
+            mantissa >> shift,
+            mantissa & ((1 << shift) - 1),
+            1 << (shift - 1),
+        );
+        return sign | half as u16;
+    }
+    // A carry out of the mantissa correctly bumps the exponent
+    let half = round(
+        ((exponent as u32) << 10) | (mantissa >> 13),
+        mantissa & 0x1fff,
+        0x1000,
+    );
+    sign | half as u16
+}
+
+/// Converts IEEE 754 half-precision bits back to `f32` (exactly).
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn f16_bits_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = u32::from(bits >> 10) & 0x1f;
+    let mantissa = u32::from(bits & 0x03ff);
+    let magnitude = match exponent {
+        0 => mantissa as f32 * 2f32.powi(-24),
+        0x1f => f32::from_bits(0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(((exponent + 112) << 23) | (mantissa << 13)),
+    };
+    sign * magnitude
+}
+
+/// Quantizes `value` to a signed byte in steps of `scale`, saturating at
+/// ±127. NaN (or a non-positive scale) gives 0.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn quantize_i8(value: f32, scale: f32) -> i8 {
+    let steps = value / scale;
+    if steps.is_nan() || scale <= 0.0 {
+        return 0;
+    }
+    steps.round().clamp(-127.0, 127.0) as i8
+}
+
+/// Writes `values` into `out` as half-precision bits.
+///
+/// Only the overlapping prefix of the two slices is written.
+pub fn encode_f16(values: &[f32], out: &mut [u16]) {
+    for (slot, value) in out.iter_mut().zip(values) {
+        *slot = f32_to_f16_bits(*value);
+    }
+}
+
+/// Writes `values` into `out` quantized in steps of `scale`.
+///
+/// Only the overlapping prefix of the two slices is written.
+pub fn encode_i8(values: &[f32], scale: f32, out: &mut [i8]) {
+    for (slot, value) in out.iter_mut().zip(values) {
+        *slot = quantize_i8(*value, scale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod bit_packing_tests {
+        use super::*;
+
+        #[test]
+        fn roundtrip_odd_widths() {
+            let mut writer = BitWriter::default();
+            writer.write(0b101, 3);
+            writer.write(0x1234, 13);
+            writer.write(1, 1);
+            let bytes = writer.finish();
+            assert_eq!(bytes.len(), 3);
+
+            let mut reader = BitReader::new(&bytes);
+            assert_eq!(reader.read(3).unwrap(), 0b101);
+            assert_eq!(reader.read(13).unwrap(), 0x1234);
+            assert_eq!(reader.read(1).unwrap(), 1);
+        }
+
+        #[test]
+        fn reading_past_end_is_truncated() {
+            let mut reader = BitReader::new(&[0xFF]);
+            assert_eq!(reader.read(9), Err(CodecError::Truncated));
+        }
+    }
+
+    mod codec_tests {
+        use super::*;
+
+        /// Headers of a message with `columns` columns.
+        const fn headers(columns: usize) -> usize {
+            HEADER_BITS + columns * COLUMN_HEADER_BITS
+        }
+
+        #[test]
+        fn respects_bit_budget() {
+            let values: Vec<f32> = (0..50u8).map(|i| f32::from(i) * 0.3).collect();
+            for budget in [200, 203, 401, 1000, 5007] {
+                let codec = ObservationCodec::new(budget);
+                let packed = codec.encode(&values).unwrap();
+                assert!(packed.len() * 8 <= budget, "{} bytes over {budget}", packed.len());
+            }
+        }
+
+        #[test]
+        fn error_within_bound() {
+            let values: Vec<f32> = (0..20u8).map(|i| f32::from(i).sin() * 100.0).collect();
+            let codec = ObservationCodec::new(headers(1) + 20 * 6);
+
+            let decoded = ObservationCodec::decode(&codec.encode(&values).unwrap()).unwrap();
+            let bound = codec.max_error(&[&values]).unwrap() + 1e-3;
+
+            assert_eq!(decoded.len(), values.len());
+            for (a, b) in values.iter().zip(&decoded) {
+                assert!((a - b).abs() <= bound, "{a} vs {b} exceeds {bound}");
+            }
+        }
+
+        #[test]
+        fn extremes_are_exact() {
+            let values = [-3.5, 7.25, 0.0];
+            let codec = ObservationCodec::new(headers(1) + 3 * 4);
+
+            let decoded = ObservationCodec::decode(&codec.encode(&values).unwrap()).unwrap();
+
+            assert!((decoded[0] - -3.5).abs() < f32::EPSILON);
+            assert!((decoded[1] - 7.25).abs() < f32::EPSILON);
+        }
+
+        #[test]
+        fn columns_keep_their_own_range() {
+            // Kilometer positions next to headings in radians
+            let positions = [-5000.0, 12000.0, 250.0, 8000.0];
+            let headings = [0.1, -0.2, 0.15, 0.05];
+            let codec = ObservationCodec::new(headers(2) + 8 * 8);
+
+            let packed = codec.encode_columns(&[&positions, &headings]).unwrap();
+            let decoded = ObservationCodec::decode_columns(&packed).unwrap();
+
+            assert_eq!(decoded.len(), 2);
+            let step = (0.15 + 0.2) / 255.0;
+            for (a, b) in headings.iter().zip(&decoded[1]) {
+                assert!((a - b).abs() <= step, "{a} vs {b}");
+            }
+            let flat = ObservationCodec::decode(&packed).unwrap();
+            assert_eq!(flat, decoded.concat());
+        }
+
+        #[test]
+        fn full_f32_range_decodes_finite() {
+            let values = [f32::MIN, 0.0, f32::MAX];
+            let codec = ObservationCodec::new(1024);
+
+            let decoded = ObservationCodec::decode(&codec.encode(&values).unwrap()).unwrap();
+
+            assert!(decoded.iter().all(|v| v.is_finite()), "{decoded:?}");
+            // The extremes are exact
+            assert_eq!(decoded[0].to_bits(), f32::MIN.to_bits());
+            assert_eq!(decoded[2].to_bits(), f32::MAX.to_bits());
+            assert!(codec.max_error(&[&values]).unwrap().is_finite());
+        }
+
+        #[test]
+        fn decode_rejects_invalid_range() {
+            let mut packed = ObservationCodec::new(1024).encode(&[1.0, 2.0]).unwrap();
+            // Minimum of the first column: 0xFF... is NaN
+            packed[5..9].fill(0xFF);
+            assert_eq!(ObservationCodec::decode(&packed), Err(CodecError::InvalidRange));
+        }
+
+        #[test]
+        fn constant_input_roundtrips() {
+            let values = [2.0; 8];
+            let codec = ObservationCodec::new(1024);
+
+            let decoded = ObservationCodec::decode(&codec.encode(&values).unwrap()).unwrap();
+
+            assert!(decoded.iter().all(|v| (v - 2.0).abs() < f32::EPSILON));
+        }
+
+        #[test]
+        fn empty_input_roundtrips() {
+            let codec = ObservationCodec::new(headers(1));
+            let decoded = ObservationCodec::decode(&codec.encode(&[]).unwrap()).unwrap();
+            assert!(decoded.is_empty());
+        }
+
+        #[test]
+        fn budget_too_small() {
+            let codec = ObservationCodec::new(headers(1) + 3);
+            assert_eq!(
+                codec.encode(&[1.0; 4]),
+                Err(CodecError::BudgetTooSmall {
+                    budget: headers(1) + 3,
+                    count: 4
+                })
+            );
+        }
+
+        #[test]
+        fn rejects_non_finite() {
+            let codec = ObservationCodec::new(1024);
+            assert_eq!(codec.encode(&[1.0, f32::NAN]), Err(CodecError::NonFinite));
+        }
+
+        #[test]
+        fn bits_capped_at_sixteen() {
+            let codec = ObservationCodec::new(1_000_000);
+            assert_eq!(codec.bits_per_value(1, 4).unwrap(), MAX_BITS_PER_VALUE);
+        }
+
+        #[test]
+        fn decode_rejects_truncated() {
+            let codec = ObservationCodec::new(1024);
+            let packed = codec.encode(&[1.0, 2.0, 3.0]).unwrap();
+            assert_eq!(
+                ObservationCodec::decode(&packed[..packed.len() - 2]),
+                Err(CodecError::Truncated)
+            );
+        }
+    }
+
+    mod dtype_tests {
+        use super::*;
+
+        #[test]
+        fn f16_roundtrips_representable_values() {
+            for value in [0.0, -0.0, 1.0, -2.5, 0.333_251_95, 65504.0, 6.1e-5, 5.96e-8] {
+                let bits = f32_to_f16_bits(value);
+                let back = f16_bits_to_f32(bits);
+                assert!((back - value).abs() <= value.abs() * 1e-3 + 1e-8, "{value} -> {back}");
+            }
+            assert_eq!(f32_to_f16_bits(1.0), 0x3c00);
+            assert_eq!(f32_to_f16_bits(-2.0), 0xc000);
+            assert_eq!(f32_to_f16_bits(-0.0), 0x8000);
+        }
+
+        #[test]
+        fn f16_rounds_to_nearest_even() {
+            // 2049 is halfway between 2048 and 2050; ties go to the even 2048
+            assert_eq!(f16_bits_to_f32(f32_to_f16_bits(2049.0)), 2048.0);
+            assert_eq!(f16_bits_to_f32(f32_to_f16_bits(2051.0)), 2052.0);
+            // Rounding up out of the largest finite value overflows
+            assert_eq!(f32_to_f16_bits(65520.0), 0x7c00);
+        }
+
+        #[test]
+        fn f16_handles_specials() {
+            assert_eq!(f32_to_f16_bits(f32::INFINITY), 0x7c00);
+            assert_eq!(f32_to_f16_bits(f32::NEG_INFINITY), 0xfc00);
+            assert_eq!(f32_to_f16_bits(1e6), 0x7c00);
+            assert_eq!(f32_to_f16_bits(1e-10), 0);
+            assert!(f16_bits_to_f32(f32_to_f16_bits(f32::NAN)).is_nan());
+        }
+
+        #[test]
+        fn int8_quantizes_and_saturates() {
+            assert_eq!(quantize_i8(1.24, 0.1), 12);
+            assert_eq!(quantize_i8(-1.26, 0.1), -13);
+            assert_eq!(quantize_i8(1e6, 0.1), 127);
+            assert_eq!(quantize_i8(-1e6, 0.1), -127);
+            assert_eq!(quantize_i8(f32::NAN, 0.1), 0);
+            assert_eq!(quantize_i8(1.0, 0.0), 0);
+        }
+
+        #[test]
+        fn slices_write_overlap_only() {
+            let mut halves = [0u16; 2];
+            encode_f16(&[1.0, 2.0, 3.0], &mut halves);
+            assert_eq!(halves, [0x3c00, 0x4000]);
+
+            let mut bytes = [9i8; 3];
+            encode_i8(&[1.0, -1.0], 0.5, &mut bytes);
+            assert_eq!(bytes, [2, -2, 9]);
+        }
+    }
+}
@@ -0,0 +1,438 @@
+//! Intent broadcasting between friendly entities.
+//!
+//! The intent channel lets agents attach a small message (an intent vector)
+//! to their entity each tick. Friendly entities within comms range receive
+//! the message after a configurable latency, unless either end of the link
+//! sits inside a jamming zone. This supports learned-communication
+//! experiments without leaving the simulator.
+//!
+//! # Delivery Rules
+//!
+//! An intent sent by `sender` at tick `t` is visible to `receiver` at tick
+//! `now` when all of the following hold:
+//!
+//! - `sender != receiver` and both belong to the same team
+//! - `now >= t + latency_ticks`
+//! - the two entities are within `range` of each other (current positions)
+//! - neither entity is inside a [`JammingZone`]
+//!
+//! Only the most recent deliverable intent per sender is visible.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+//! use glam::Vec2;
+//!
+//! let mut arena = Arena::new();
+//! let a = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)));
+//! let b = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::at_position(Vec2::new(10.0, 0.0), 0.0)));
+//! arena.set_team(a, Some(TeamId::new(0)));
+//! arena.set_team(b, Some(TeamId::new(0)));
+//!
+//! arena.comms_mut().broadcast(a, vec![1.0, 0.5], 0).unwrap();
+//!
+//! let received = arena.comms().received_by(&arena, b);
+//! assert_eq!(received.len(), 1);
+//! assert_eq!(received[0].payload, vec![1.0, 0.5]);
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::arena::Arena;
+use crate::entity::EntityId;
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Default comms range in world units.
+pub const DEFAULT_COMMS_RANGE: f32 = 5000.0;
+
+/// Default maximum number of values in an intent payload.
+pub const DEFAULT_MAX_INTENT_LEN: usize = 8;
+
+/// Configuration for the intent channel.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CommsConfig {
+    /// Maximum sender-receiver distance for delivery.
+    pub range: f32,
+    /// Ticks between sending and delivery.
+    pub latency_ticks: u64,
+    /// Maximum payload length accepted by [`IntentChannel::broadcast`].
+    pub max_intent_len: usize,
+}
+
+impl Default for CommsConfig {
+    fn default() -> Self {
+        Self {
+            range: DEFAULT_COMMS_RANGE,
+            latency_ticks: 0,
+            max_intent_len: DEFAULT_MAX_INTENT_LEN,
+        }
+    }
+}
+
+/// A circular region in which communication is denied.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JammingZone {
+    /// Center of the zone.
+    pub center: Vec2,
+    /// Radius of the zone.
+    pub radius: f32,
+}
+
+impl JammingZone {
+    /// Creates a new jamming zone.
+    #[must_use]
+    pub const fn new(center: Vec2, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Returns `true` if the position lies inside the zone.
+    #[must_use]
+    pub fn contains(&self, position: Vec2) -> bool {
+        position.distance_squared(self.center) <= self.radius * self.radius
+    }
+}
+
+// =============================================================================
+// Intent
+// =============================================================================
+
+/// A message broadcast by an entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Intent {
+    /// Entity that sent the intent.
+    pub sender: EntityId,
+    /// Tick on which the intent was sent.
+    pub sent_tick: u64,
+    /// Intent payload.
+    pub payload: Vec<f32>,
+}
+
+/// Errors produced by the intent channel.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CommsError {
+    /// The payload exceeds the configured maximum length.
+    #[error("intent payload has {len} values, maximum is {max}")]
+    PayloadTooLong {
+        /// Length of the rejected payload.
+        len: usize,
+        /// Configured maximum.
+        max: usize,
+    },
+}
+
+// =============================================================================
+// IntentChannel
+// =============================================================================
+
+/// Per-arena storage of broadcast intents.
+///
+/// Each sender keeps a short history so that latency can be modeled: intents
+/// newer than the latency window are held back, and anything older than the
+/// newest deliverable intent is pruned.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntentChannel {
+    config: CommsConfig,
+    jammers: Vec<JammingZone>,
+    history: BTreeMap<EntityId, VecDeque<Intent>>,
+}
+
+impl IntentChannel {
+    /// Creates an empty channel with the given configuration.
+    #[must_use]
+    pub fn new(config: CommsConfig) -> Self {
+        Self {
+            config,
+            jammers: Vec::new(),
+            history: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the channel configuration.
+    #[must_use]
+    pub const fn config(&self) -> &CommsConfig {
+        &self.config
+    }
+
+    /// Replaces the channel configuration.
+    pub fn set_config(&mut self, config: CommsConfig) {
+        self.config = config;
+    }
+
+    /// Adds a jamming zone.
+    pub fn add_jammer(&mut self, zone: JammingZone) {
+        self.jammers.push(zone);
+    }
+
+    /// Removes all jamming zones.
+    pub fn clear_jammers(&mut self) {
+        self.jammers.clear();
+    }
+
+    /// Returns the active jamming zones.
+    #[must_use]
+    pub fn jammers(&self) -> &[JammingZone] {
+        &self.jammers
+    }
+
+    /// Returns `true` if the position is inside any jamming zone.
+    #[must_use]
+    pub fn is_jammed(&self, position: Vec2) -> bool {
+        self.jammers.iter().any(|z| z.contains(position))
+    }
+
+    /// Broadcasts an intent from `sender` on the given tick.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CommsError::PayloadTooLong`] if the payload exceeds
+    /// `max_intent_len`.
+    pub fn broadcast(
+        &mut self,
+        sender: EntityId,
+        payload: Vec<f32>,
+        tick: u64,
+    ) -> Result<(), CommsError> {
+        if payload.len() > self.config.max_intent_len {
+            return Err(CommsError::PayloadTooLong {
+                len: payload.len(),
+                max: self.config.max_intent_len,
+            });
+        }
+
+        let latency = self.config.latency_ticks;
+        let queue = self.history.entry(sender).or_default();
+        queue.push_back(Intent {
+            sender,
+            sent_tick: tick,
+            payload,
+        });
+
+        // Keep only the newest intent that is already deliverable plus
+        // everything still in flight.
+        let cutoff = tick.saturating_sub(latency);
+        while queue.len() > 1 && queue[1].sent_tick <= cutoff {
+            queue.pop_front();
+        }
+        Ok(())
+    }
+
+    /// Returns the newest intent from `sender` deliverable at `tick`.
+    #[must_use]
+    pub fn latest_from(&self, sender: EntityId, tick: u64) -> Option<&Intent> {
+        self.history
+            .get(&sender)?
+            .iter()
+            .rev()
+            .find(|i| i.sent_tick + self.config.latency_ticks <= tick)
+    }
+
+    /// Returns the intents visible to `receiver` at the arena's current tick.
+    ///
+    /// Results are ordered by sender ID.
+    #[must_use]
+    pub fn received_by<'a>(&'a self, arena: &Arena, receiver: EntityId) -> Vec<&'a Intent> {
+        let Some(receiver_entity) = arena.get(receiver) else {
+            return Vec::new();
+        };
+        let Some(receiver_pos) = arena.spatial().get(receiver) else {
+            return Vec::new();
+        };
+        if self.is_jammed(receiver_pos) {
+            return Vec::new();
+        }
+
+        let tick = arena.current_tick();
+        let range_sq = self.config.range * self.config.range;
+
+        self.history
+            .keys()
+            .filter(|&&sender| sender != receiver)
+            .filter(|&&sender| {
+                arena
+                    .get(sender)
+                    .is_some_and(|e| e.is_friendly_to(receiver_entity))
+            })
+            .filter(|&&sender| {
+                arena.spatial().get(sender).is_some_and(|pos| {
+                    pos.distance_squared(receiver_pos) <= range_sq && !self.is_jammed(pos)
+                })
+            })
+            .filter_map(|&sender| self.latest_from(sender, tick))
+            .collect()
+    }
+
+    /// Removes all intents sent by `sender`.
+    pub fn remove_sender(&mut self, sender: EntityId) {
+        self.history.remove(&sender);
+    }
+
+    /// Removes all stored intents, keeping configuration and jammers.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Returns the number of senders with stored intents.
+    #[must_use]
+    pub fn sender_count(&self) -> usize {
+        self.history.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::spawn_team_ship;
+
+    mod broadcast_tests {
+        use super::*;
+
+        #[test]
+        fn rejects_oversized_payload() {
+            let mut channel = IntentChannel::new(CommsConfig {
+                max_intent_len: 2,
+                ..CommsConfig::default()
+            });
+
+            let result = channel.broadcast(EntityId::new(0), vec![0.0; 3], 0);
+            assert_eq!(result, Err(CommsError::PayloadTooLong { len: 3, max: 2 }));
+            assert_eq!(channel.sender_count(), 0);
+        }
+
+        #[test]
+        fn latency_delays_delivery() {
+            let mut channel = IntentChannel::new(CommsConfig {
+                latency_ticks: 2,
+                ..CommsConfig::default()
+            });
+            let sender = EntityId::new(0);
+            channel.broadcast(sender, vec![1.0], 5).unwrap();
+
+            assert!(channel.latest_from(sender, 6).is_none());
+            assert_eq!(channel.latest_from(sender, 7).unwrap().sent_tick, 5);
+        }
+
+        #[test]
+        fn newer_intent_replaces_older() {
+            let mut channel = IntentChannel::default();
+            let sender = EntityId::new(0);
+            channel.broadcast(sender, vec![1.0], 0).unwrap();
+            channel.broadcast(sender, vec![2.0], 1).unwrap();
+
+            assert_eq!(channel.latest_from(sender, 1).unwrap().payload, vec![2.0]);
+            assert_eq!(channel.history[&sender].len(), 1);
+        }
+
+        #[test]
+        fn in_flight_intents_are_retained() {
+            let mut channel = IntentChannel::new(CommsConfig {
+                latency_ticks: 3,
+                ..CommsConfig::default()
+            });
+            let sender = EntityId::new(0);
+            for tick in 0..5 {
+                channel.broadcast(sender, vec![0.5], tick).unwrap();
+            }
+
+            // Tick 1 is the newest deliverable at tick 4; 2..=4 are in flight.
+            assert_eq!(channel.history[&sender].len(), 4);
+            assert_eq!(channel.latest_from(sender, 4).unwrap().sent_tick, 1);
+        }
+    }
+
+    mod delivery_tests {
+        use super::*;
+
+        #[test]
+        fn friendly_in_range_receives() {
+            let mut arena = Arena::new();
+            let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            let b = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+
+            arena.comms_mut().broadcast(a, vec![0.25], 0).unwrap();
+
+            let received = arena.comms().received_by(&arena, b);
+            assert_eq!(received.len(), 1);
+            assert_eq!(received[0].sender, a);
+        }
+
+        #[test]
+        fn sender_does_not_receive_own_intent() {
+            let mut arena = Arena::new();
+            let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+
+            arena.comms_mut().broadcast(a, vec![0.25], 0).unwrap();
+
+            assert!(arena.comms().received_by(&arena, a).is_empty());
+        }
+
+        #[test]
+        fn enemy_and_neutral_do_not_receive() {
+            let mut arena = Arena::new();
+            let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            let enemy = spawn_team_ship(&mut arena, Vec2::new(10.0, 0.0), Some(1));
+            let neutral = spawn_team_ship(&mut arena, Vec2::new(20.0, 0.0), None);
+
+            arena.comms_mut().broadcast(a, vec![0.25], 0).unwrap();
+
+            assert!(arena.comms().received_by(&arena, enemy).is_empty());
+            assert!(arena.comms().received_by(&arena, neutral).is_empty());
+        }
+
+        #[test]
+        fn out_of_range_does_not_receive() {
+            let mut arena = Arena::new();
+            arena.comms_mut().set_config(CommsConfig {
+                range: 50.0,
+                ..CommsConfig::default()
+            });
+            let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            let b = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+
+            arena.comms_mut().broadcast(a, vec![0.25], 0).unwrap();
+
+            assert!(arena.comms().received_by(&arena, b).is_empty());
+        }
+
+        #[test]
+        fn jamming_blocks_either_end() {
+            let mut arena = Arena::new();
+            let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            let b = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+            arena.comms_mut().broadcast(a, vec![0.25], 0).unwrap();
+
+            arena
+                .comms_mut()
+                .add_jammer(JammingZone::new(Vec2::new(100.0, 0.0), 10.0));
+            assert!(arena.comms().received_by(&arena, b).is_empty());
+
+            arena.comms_mut().clear_jammers();
+            arena
+                .comms_mut()
+                .add_jammer(JammingZone::new(Vec2::ZERO, 10.0));
+            assert!(arena.comms().received_by(&arena, b).is_empty());
+
+            arena.comms_mut().clear_jammers();
+            assert_eq!(arena.comms().received_by(&arena, b).len(), 1);
+        }
+
+        #[test]
+        fn despawn_clears_sender_history() {
+            let mut arena = Arena::new();
+            let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+            arena.comms_mut().broadcast(a, vec![0.25], 0).unwrap();
+
+            arena.despawn(a);
+
+            assert_eq!(arena.comms().sender_count(), 0);
+        }
+    }
+}
@@ -0,0 +1,466 @@
+//! Layered tuning configuration.
+//!
+//! A [`TidebreakConfig`] gathers the tunable constants of a run (simulation
+//! settings, ship physics and sensor defaults, and the murk substrate) in one
+//! place, so parameter sweeps can be driven from files and the command line
+//! instead of editing `Default` impls.
+//!
+//! # Layers
+//!
+//! Configs are built up in layers, each overriding the keys it sets:
+//!
+//! 1. Built-in defaults ([`TidebreakConfig::default`]), matching the
+//!    component defaults
+//! 2. Scenario TOML files ([`merge_toml`](TidebreakConfig::merge_toml),
+//!    [`merge_file`](TidebreakConfig::merge_file))
+//! 3. Command-line `key=value` assignments
+//!    ([`apply_override`](TidebreakConfig::apply_override))
+//! 4. Python keyword overrides, via `Simulation(config=...)`
+//!
+//! Every layer is partial; unknown sections or keys are rejected so typos
+//! surface as errors rather than silently keeping a default.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::config::TidebreakConfig;
+//!
+//! let mut config = TidebreakConfig::from_toml(
+//!     r#"
+//!     [physics]
+//!     max_speed = 14.0
+//!
+//!     [sensor]
+//!     radar_range = 8000.0
+//!     "#,
+//! )?;
+//! config.apply_override("physics.max_speed=18")?;
+//! config.apply_override("simulation.seed=7")?;
+//! assert_eq!(config.physics.max_speed, 18.0);
+//! assert_eq!(config.sensor.radar_range, 8000.0);
+//!
+//! let sim = config.build();
+//! assert_eq!(sim.seed(), 7);
+//! # Ok::<(), tidebreak_core::config::ConfigError>(())
+//! ```
+
+use std::path::Path;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use toml::{Table, Value};
+
+use crate::entity::{EngineProfile, PhysicsState, SensorState, ShipComponents};
+use crate::resolver::FIXED_DT;
+use crate::simulation::{Simulation, SimulationConfig};
+
+/// Errors from loading, merging or validating a config.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// A config file could not be read.
+    #[error("cannot read config: {0}")]
+    Io(#[from] std::io::Error),
+    /// A layer is not valid TOML, or names an unknown key or a value of
+    /// the wrong type.
+    #[error("invalid config: {0}")]
+    Parse(String),
+    /// A command-line override is not of the form `section.key=value`.
+    #[error("invalid override '{0}', expected 'section.key=value'")]
+    InvalidOverride(String),
+    /// A value is out of range or not finite.
+    #[error("invalid value for {0}")]
+    InvalidValue(&'static str),
+}
+
+/// Simulation settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationParams {
+    /// Master seed.
+    pub seed: u64,
+    /// Physics ticks per agent decision.
+    pub action_interval: u32,
+}
+
+impl Default for SimulationParams {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            action_interval: 1,
+        }
+    }
+}
+
+/// Physics timestep and the movement limits of newly spawned ships.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PhysicsParams {
+    /// Seconds per physics tick.
+    pub dt: f32,
+    /// Ship maximum speed in m/s.
+    pub max_speed: f32,
+    /// Ship maximum turn rate in rad/s.
+    pub max_turn_rate: f32,
+    /// Ship engine response under throttle.
+    pub engine: EngineProfile,
+}
+
+impl Default for PhysicsParams {
+    fn default() -> Self {
+        let physics = PhysicsState::default();
+        Self {
+            dt: FIXED_DT,
+            max_speed: physics.max_speed,
+            max_turn_rate: physics.max_turn_rate,
+            engine: physics.engine,
+        }
+    }
+}
+
+/// Sensor ranges of newly spawned ships.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SensorParams {
+    /// Radar range in meters.
+    pub radar_range: f32,
+    /// Sonar range in meters.
+    pub sonar_range: f32,
+    /// Visual range in clear daylight, in meters.
+    pub visual_range: f32,
+}
+
+impl Default for SensorParams {
+    fn default() -> Self {
+        let sensor = SensorState::default();
+        Self {
+            radar_range: sensor.radar_range,
+            sonar_range: sensor.sonar_range,
+            visual_range: sensor.visual_range,
+        }
+    }
+}
+
+/// Murk substrate settings (see [`murk::UniverseConfig`]).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MurkParams {
+    /// World width in meters.
+    pub width: f32,
+    /// World height in meters.
+    pub height: f32,
+    /// World depth in meters.
+    pub depth: f32,
+    /// Cell size at maximum octree depth.
+    pub base_resolution: f32,
+    /// Variance threshold for merging cells.
+    pub merge_threshold: f32,
+    /// Variance threshold for splitting cells.
+    pub split_threshold: f32,
+    /// Octree memory budget in bytes, unlimited if unset.
+    pub memory_budget: Option<usize>,
+    /// Split cells in X and Y only, for surface-only scenarios.
+    pub planar: bool,
+}
+
+impl Default for MurkParams {
+    fn default() -> Self {
+        let universe = murk::UniverseConfig::default();
+        Self {
+            width: universe.bounds.max.x - universe.bounds.min.x,
+            height: universe.bounds.max.y - universe.bounds.min.y,
+            depth: universe.bounds.max.z - universe.bounds.min.z,
+            base_resolution: universe.base_resolution,
+            merge_threshold: universe.merge_threshold,
+            split_threshold: universe.split_threshold,
+            memory_budget: universe.memory_budget,
+            planar: universe.planar,
+        }
+    }
+}
+
+/// Tunable parameters of a run, built up from layered overrides.
+///
+/// See the [module docs](self) for the layering.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TidebreakConfig {
+    /// Simulation settings.
+    pub simulation: SimulationParams,
+    /// Physics timestep and ship movement limits.
+    pub physics: PhysicsParams,
+    /// Ship sensor ranges.
+    pub sensor: SensorParams,
+    /// Murk substrate settings.
+    pub murk: MurkParams,
+}
+
+impl TidebreakConfig {
+    /// Parses a TOML document over the defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is invalid (see
+    /// [`merge_toml`](Self::merge_toml)).
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        config.merge_toml(text)?;
+        Ok(config)
+    }
+
+    /// Loads a TOML file over the defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is invalid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        config.merge_file(path)?;
+        Ok(config)
+    }
+
+    /// Overrides the keys set in the TOML document `text`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the config unchanged, if `text` is not
+    /// TOML, names an unknown key, or sets an invalid value.
+    pub fn merge_toml(&mut self, text: &str) -> Result<(), ConfigError> {
+        let layer: Table = text.parse().map_err(|e: toml::de::Error| {
+            ConfigError::Parse(e.message().to_string())
+        })?;
+        self.merge(layer)
+    }
+
+    /// Overrides the keys set in the TOML file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the config unchanged, if the file cannot
+    /// be read or is invalid.
+    pub fn merge_file(&mut self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let text = std::fs::read_to_string(path)?;
+        self.merge_toml(&text)
+    }
+
+    /// Sets the dotted `key` (e.g. `physics.engine.acceleration`) to
+    /// `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the config unchanged, if the key is
+    /// unknown or the value invalid.
+    pub fn set(&mut self, key: &str, value: Value) -> Result<(), ConfigError> {
+        let mut layer = Table::new();
+        let mut parts = key.rsplit('.');
+        let leaf = parts.next().unwrap_or_default();
+        layer.insert(leaf.to_string(), value);
+        for part in parts {
+            let mut parent = Table::new();
+            parent.insert(part.to_string(), Value::Table(layer));
+            layer = parent;
+        }
+        self.merge(layer)
+    }
+
+    /// Applies a command-line assignment such as `physics.max_speed=12.5`.
+    ///
+    /// The value is read as a TOML value; anything that does not parse as
+    /// one is taken as a string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the config unchanged, if the assignment
+    /// is malformed, the key unknown or the value invalid.
+    pub fn apply_override(&mut self, assignment: &str) -> Result<(), ConfigError> {
+        let Some((key, value)) = assignment.split_once('=') else {
+            return Err(ConfigError::InvalidOverride(assignment.to_string()));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if key.is_empty() || key.split('.').any(str::is_empty) {
+            return Err(ConfigError::InvalidOverride(assignment.to_string()));
+        }
+        let value = format!("v = {value}")
+            .parse::<Table>()
+            .ok()
+            .and_then(|mut t| t.remove("v"))
+            .unwrap_or_else(|| Value::String(value.to_string()));
+        self.set(key, value)
+    }
+
+    /// Serializes the full config, e.g. to record the settings of a run.
+    #[must_use]
+    pub fn to_toml(&self) -> String {
+        // Every field is a plain number, so serialization cannot fail
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    /// Checks that every value is finite and in range.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid value found.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let positive = [
+            (self.physics.dt, "physics.dt"),
+            (self.murk.width, "murk.width"),
+            (self.murk.height, "murk.height"),
+            (self.murk.depth, "murk.depth"),
+            (self.murk.base_resolution, "murk.base_resolution"),
+        ];
+        let non_negative = [
+            (self.physics.max_speed, "physics.max_speed"),
+            (self.physics.max_turn_rate, "physics.max_turn_rate"),
+            (self.physics.engine.acceleration, "physics.engine.acceleration"),
+            (self.physics.engine.deceleration, "physics.engine.deceleration"),
+            (self.physics.engine.min_turn_radius, "physics.engine.min_turn_radius"),
+            (self.sensor.radar_range, "sensor.radar_range"),
+            (self.sensor.sonar_range, "sensor.sonar_range"),
+            (self.sensor.visual_range, "sensor.visual_range"),
+            (self.murk.merge_threshold, "murk.merge_threshold"),
+            (self.murk.split_threshold, "murk.split_threshold"),
+        ];
+        for (value, name) in positive {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(ConfigError::InvalidValue(name));
+            }
+        }
+        for (value, name) in non_negative {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(ConfigError::InvalidValue(name));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.physics.engine.reverse_limit) {
+            return Err(ConfigError::InvalidValue("physics.engine.reverse_limit"));
+        }
+        if !(0.0..=1.0).contains(&self.physics.engine.full_speed_turn) {
+            return Err(ConfigError::InvalidValue("physics.engine.full_speed_turn"));
+        }
+        Ok(())
+    }
+
+    /// Returns the simulation features this config sets.
+    #[must_use]
+    pub fn simulation_config(&self) -> SimulationConfig {
+        SimulationConfig {
+            action_interval: self.simulation.action_interval,
+            physics_dt: Some(self.physics.dt),
+            ..SimulationConfig::default()
+        }
+    }
+
+    /// Creates a simulation with this config's seed and settings.
+    #[must_use]
+    pub fn build(&self) -> Simulation {
+        let mut sim = Simulation::new(self.simulation.seed);
+        sim.set_action_interval(self.simulation.action_interval);
+        sim.set_physics_dt(self.physics.dt);
+        sim
+    }
+
+    /// Returns the murk universe settings.
+    #[must_use]
+    pub fn universe_config(&self) -> murk::UniverseConfig {
+        murk::UniverseConfig {
+            bounds: murk::Bounds::new(self.murk.width, self.murk.height, self.murk.depth),
+            base_resolution: self.murk.base_resolution,
+            merge_threshold: self.murk.merge_threshold,
+            split_threshold: self.murk.split_threshold,
+            memory_budget: self.murk.memory_budget,
+            planar: self.murk.planar,
+            ..murk::UniverseConfig::default()
+        }
+    }
+
+    /// Creates ship components at `position` with this config's physics
+    /// and sensor defaults.
+    #[must_use]
+    pub fn ship(&self, position: Vec2, heading: f32) -> ShipComponents {
+        let mut ship = ShipComponents::at_position(position, heading)
+            .with_physics(self.physics.max_speed, self.physics.max_turn_rate)
+            .with_engine(self.physics.engine)
+            .with_sensors(self.sensor.radar_range, self.sensor.sonar_range);
+        ship.sensor.visual_range = self.sensor.visual_range;
+        ship
+    }
+
+    /// Deep-merges `layer` over the current values, validating the result.
+    fn merge(&mut self, layer: Table) -> Result<(), ConfigError> {
+        let mut merged = Table::try_from(&*self).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        merge_tables(&mut merged, layer);
+        let config: Self = merged
+            .try_into()
+            .map_err(|e: toml::de::Error| ConfigError::Parse(e.message().to_string()))?;
+        config.validate()?;
+        *self = config;
+        Ok(())
+    }
+}
+
+/// Recursively overrides the keys of `base` with those set in `layer`.
+fn merge_tables(base: &mut Table, layer: Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(layer)) => merge_tables(base, layer),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_component_defaults() {
+        let config = TidebreakConfig::default();
+        assert_eq!(config.ship(Vec2::ZERO, 0.0), ShipComponents::default());
+        assert_eq!(config.universe_config().bounds, murk::UniverseConfig::default().bounds);
+        assert_eq!(TidebreakConfig::from_toml(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn later_layers_override_earlier_ones() {
+        let mut config = TidebreakConfig::from_toml(
+            "[physics]\nmax_speed = 12.0\n[physics.engine]\nacceleration = 4.0\n",
+        )
+        .unwrap();
+        config.merge_toml("[physics]\nmax_turn_rate = 0.5").unwrap();
+        config.apply_override("physics.max_speed = 20").unwrap();
+
+        assert_eq!(config.physics.max_speed, 20.0);
+        assert_eq!(config.physics.max_turn_rate, 0.5);
+        assert_eq!(config.physics.engine.acceleration, 4.0);
+        // Keys not set by any layer keep their defaults
+        let defaults = EngineProfile::default();
+        assert_eq!(config.physics.engine.deceleration, defaults.deceleration);
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_values() {
+        let mut config = TidebreakConfig::default();
+        assert!(matches!(
+            config.merge_toml("[physics]\nmax_sped = 3.0"),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            config.apply_override("physics.max_speed"),
+            Err(ConfigError::InvalidOverride(_))
+        ));
+        assert!(matches!(
+            config.apply_override("physics.dt=0"),
+            Err(ConfigError::InvalidValue("physics.dt"))
+        ));
+        assert!(matches!(
+            config.apply_override("sensor.radar_range=far"),
+            Err(ConfigError::Parse(_))
+        ));
+        assert_eq!(config, TidebreakConfig::default());
+    }
+}
@@ -0,0 +1,228 @@
+//! Sea-current drift sampled from the murk substrate.
+//!
+//! A [`CurrentField`] is a 2D grid snapshot of the `CurrentX`/`CurrentY`
+//! fields at the sea surface. Once installed with [`Arena::set_currents`],
+//! the `PhysicsResolver` carries every moving entity along with the water
+//! each tick, scaled per entity type by the field's [`DriftCoupling`].
+//! Drift moves the entity over the ground without changing its velocity
+//! through the water.
+//!
+//! Coupling is opt-in: arenas without currents behave as before. The
+//! simulation does not own a universe, so the host re-samples the field
+//! whenever the currents change:
+//!
+//! ```
+//! use glam::Vec2;
+//! use tidebreak_core::currents::CurrentField;
+//! use tidebreak_core::murk::Universe;
+//! use tidebreak_core::simulation::Simulation;
+//!
+//! let mut sim = Simulation::new(42);
+//! let universe = Universe::default();
+//!
+//! let currents = CurrentField::sample(
+//!     &universe,
+//!     Vec2::new(-500.0, -500.0),
+//!     Vec2::new(500.0, 500.0),
+//!     50.0,
+//!     0.0,
+//! );
+//! sim.arena_mut().set_currents(Some(currents));
+//! sim.step();
+//! ```
+//!
+//! [`Arena::set_currents`]: crate::arena::Arena::set_currents
+
+use glam::Vec2;
+use murk::{Field, Universe};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::EntityTag;
+use crate::grid::SampleGrid;
+
+pub use crate::grid::MAX_SAMPLES_PER_AXIS;
+
+/// Fraction of the current that carries each entity type.
+///
+/// Deep-draft hulls are carried by the water; fast, light or airborne
+/// entities barely notice it. Platforms never drift.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DriftCoupling {
+    /// Scale applied to ships
+    pub ship: f32,
+    /// Scale applied to projectiles (torpedoes, shells, missiles)
+    pub projectile: f32,
+    /// Scale applied to aircraft squadrons
+    pub squadron: f32,
+}
+
+impl DriftCoupling {
+    /// Returns the drift scale for an entity type.
+    #[must_use]
+    pub const fn factor(&self, tag: EntityTag) -> f32 {
+        match tag {
+            EntityTag::Ship => self.ship,
+            EntityTag::Projectile => self.projectile,
+            EntityTag::Squadron => self.squadron,
+            EntityTag::Platform => 0.0,
+        }
+    }
+}
+
+impl Default for DriftCoupling {
+    fn default() -> Self {
+        Self {
+            ship: 1.0,
+            projectile: 0.2,
+            squadron: 0.0,
+        }
+    }
+}
+
+/// Grid snapshot of surface currents, in m/s.
+///
+/// Lookups between samples are bilinear; positions outside the sampled
+/// area use the nearest edge sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrentField {
+    /// Sampled current vectors
+    grid: SampleGrid<Vec2>,
+    /// Per-type drift scaling
+    coupling: DriftCoupling,
+}
+
+impl CurrentField {
+    /// Creates a field with the same current everywhere.
+    #[must_use]
+    pub fn uniform(current: Vec2) -> Self {
+        Self {
+            grid: SampleGrid::uniform(current),
+            coupling: DriftCoupling::default(),
+        }
+    }
+
+    /// Samples `CurrentX`/`CurrentY` from `universe` over the rectangle
+    /// `[min, max]` at altitude `z`.
+    ///
+    /// Samples are at most `spacing` apart (and at most
+    /// [`MAX_SAMPLES_PER_AXIS`] per axis), including both edges.
+    #[must_use]
+    pub fn sample(universe: &Universe, min: Vec2, max: Vec2, spacing: f32, z: f32) -> Self {
+        Self {
+            grid: SampleGrid::sample(universe, min, max, spacing, z, |point| {
+                Vec2::new(point.get(Field::CurrentX), point.get(Field::CurrentY))
+            }),
+            coupling: DriftCoupling::default(),
+        }
+    }
+
+    /// Returns this field with the given drift coupling.
+    #[must_use]
+    pub const fn with_coupling(mut self, coupling: DriftCoupling) -> Self {
+        self.coupling = coupling;
+        self
+    }
+
+    /// Returns the drift coupling.
+    #[must_use]
+    pub const fn coupling(&self) -> &DriftCoupling {
+        &self.coupling
+    }
+
+    /// Returns the current at `position`.
+    ///
+    /// A malformed field (for example, a truncated snapshot) has no current.
+    #[must_use]
+    pub fn current_at(&self, position: Vec2) -> Vec2 {
+        self.grid.at(position).unwrap_or(Vec2::ZERO)
+    }
+
+    /// Returns the drift velocity of an entity of type `tag` at `position`.
+    #[must_use]
+    pub fn drift(&self, tag: EntityTag, position: Vec2) -> Vec2 {
+        let factor = self.coupling.factor(tag);
+        if factor == 0.0 {
+            return Vec2::ZERO;
+        }
+        self.current_at(position) * factor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use murk::{BlendOp, FieldMod, Stamp, StampShape, UniverseConfig};
+
+    fn field_2x2() -> CurrentField {
+        CurrentField {
+            grid: SampleGrid::from_parts(
+                Vec2::ZERO,
+                Vec2::splat(10.0),
+                [2, 2],
+                vec![
+                    Vec2::new(0.0, 0.0),
+                    Vec2::new(2.0, 0.0),
+                    Vec2::new(0.0, 2.0),
+                    Vec2::new(2.0, 2.0),
+                ],
+            ),
+            coupling: DriftCoupling::default(),
+        }
+    }
+
+    #[test]
+    fn uniform_is_constant() {
+        let field = CurrentField::uniform(Vec2::new(1.0, -0.5));
+        assert_eq!(field.current_at(Vec2::ZERO), Vec2::new(1.0, -0.5));
+        assert_eq!(field.current_at(Vec2::splat(1e6)), Vec2::new(1.0, -0.5));
+    }
+
+    #[test]
+    fn current_at_interpolates_and_clamps() {
+        let field = field_2x2();
+        assert_eq!(field.current_at(Vec2::new(5.0, 5.0)), Vec2::new(1.0, 1.0));
+        assert_eq!(field.current_at(Vec2::new(10.0, 0.0)), Vec2::new(2.0, 0.0));
+        // Outside the grid uses the nearest edge
+        assert_eq!(
+            field.current_at(Vec2::new(-50.0, 50.0)),
+            Vec2::new(0.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn drift_scales_by_entity_type() {
+        let field = CurrentField::uniform(Vec2::new(2.0, 0.0)).with_coupling(DriftCoupling {
+            ship: 0.5,
+            projectile: 0.0,
+            squadron: 0.0,
+        });
+        assert_eq!(
+            field.drift(EntityTag::Ship, Vec2::ZERO),
+            Vec2::new(1.0, 0.0)
+        );
+        assert_eq!(field.drift(EntityTag::Projectile, Vec2::ZERO), Vec2::ZERO);
+        assert_eq!(field.drift(EntityTag::Platform, Vec2::ZERO), Vec2::ZERO);
+    }
+
+    #[test]
+    fn sample_reads_universe_currents() {
+        let mut universe = Universe::new(UniverseConfig::with_bounds(200.0, 200.0, 50.0));
+        let stamp = Stamp::new(
+            StampShape::Sphere {
+                center: glam::Vec3::new(50.0, 50.0, 0.0),
+                radius: 500.0,
+            },
+            vec![
+                FieldMod::new(Field::CurrentX, BlendOp::Set, 1.5),
+                FieldMod::new(Field::CurrentY, BlendOp::Set, -0.5),
+            ],
+        );
+        universe.stamp(&stamp);
+
+        let field = CurrentField::sample(&universe, Vec2::ZERO, Vec2::splat(100.0), 25.0, 0.0);
+        assert_eq!(field.grid.dims(), [5, 5]);
+        let current = field.current_at(Vec2::new(40.0, 60.0));
+        assert!((current.x - 1.5).abs() < 1e-3);
+        assert!((current.y + 0.5).abs() < 1e-3);
+    }
+}
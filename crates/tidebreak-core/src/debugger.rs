@@ -0,0 +1,444 @@
+//! Tick-level debugger: breakpoints and run-until.
+//!
+//! Breakpoints are evaluated by [`Simulation::run_until`] after the plugin
+//! phase of each tick, against the tick's pending outputs and the snapshot
+//! the plugins read. When one hits, the simulation pauses *before*
+//! resolution: the pending outputs stay inspectable through
+//! [`Simulation::pending_outputs`], and the next [`Simulation::step`]
+//! resolves them and completes the tick.
+//!
+//! ```
+//! use tidebreak_core::debugger::{Breakpoint, StopReason};
+//! use tidebreak_core::simulation::Simulation;
+//!
+//! let mut sim = Simulation::new(42);
+//! sim.add_breakpoint(Breakpoint::at_tick(5));
+//!
+//! let reason = sim.run_until(|_| false, 100);
+//! assert!(matches!(reason, StopReason::Breakpoint(_)));
+//! assert!(sim.is_paused());
+//! assert_eq!(sim.tick(), 5);
+//!
+//! sim.step(); // resolve tick 5
+//! assert_eq!(sim.tick(), 6);
+//! ```
+//!
+//! [`Simulation::run_until`]: crate::simulation::Simulation::run_until
+//! [`Simulation::pending_outputs`]: crate::simulation::Simulation::pending_outputs
+//! [`Simulation::step`]: crate::simulation::Simulation::step
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::arena::Arena;
+use crate::entity::components::{CombatState, StatusFlags};
+use crate::entity::{Entity, EntityId, EntityInner};
+use crate::output::{Event, Modifier, OutputEnvelope};
+use crate::resolver::area_damage;
+
+/// Predicate over one pending output.
+type OutputPredicate = dyn Fn(&OutputEnvelope) -> bool + Send + Sync;
+/// Predicate over the pre-resolution state.
+type StatePredicate = dyn Fn(&Arena) -> bool + Send + Sync;
+
+/// Handle returned when a breakpoint is added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BreakpointId(pub(crate) u32);
+
+/// A breakpoint that fired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreakpointHit {
+    /// Breakpoint that fired.
+    pub id: BreakpointId,
+    /// Breakpoint name.
+    pub name: String,
+    /// Tick paused before resolution.
+    pub tick: u64,
+    /// Entity that triggered the breakpoint, if it is entity-specific.
+    pub entity: Option<EntityId>,
+}
+
+/// Why [`Simulation::run_until`](crate::simulation::Simulation::run_until)
+/// returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// The predicate returned true.
+    Predicate,
+    /// One or more breakpoints fired; the tick is paused before resolution.
+    Breakpoint(Vec<BreakpointHit>),
+    /// The tick budget was exhausted.
+    MaxTicks,
+}
+
+/// Condition that pauses the simulation before a tick is resolved.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::debugger::Breakpoint;
+/// use tidebreak_core::output::Event;
+///
+/// let fired = Breakpoint::on_event("weapon fired", |e| {
+///     matches!(e, Event::WeaponFired { .. })
+/// });
+/// assert_eq!(fired.name(), "weapon fired");
+/// ```
+pub struct Breakpoint {
+    name: String,
+    condition: Condition,
+}
+
+enum Condition {
+    Output(Box<OutputPredicate>),
+    State(Box<StatePredicate>),
+    Tick(u64),
+    HpBelow {
+        entity: Option<EntityId>,
+        threshold: f32,
+    },
+    Destroyed,
+}
+
+impl fmt::Debug for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Breakpoint")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Breakpoint {
+    /// Fires when any pending output matches `predicate`.
+    pub fn on_output(
+        name: impl Into<String>,
+        predicate: impl Fn(&OutputEnvelope) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            condition: Condition::Output(Box::new(predicate)),
+        }
+    }
+
+    /// Fires when any pending event matches `predicate`.
+    pub fn on_event(
+        name: impl Into<String>,
+        predicate: impl Fn(&Event) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self::on_output(name, move |envelope| {
+            envelope.output().as_event().is_some_and(&predicate)
+        })
+    }
+
+    /// Fires when `predicate` holds for the state the plugins read.
+    pub fn on_state(
+        name: impl Into<String>,
+        predicate: impl Fn(&Arena) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            condition: Condition::State(Box::new(predicate)),
+        }
+    }
+
+    /// Fires before tick `tick` is resolved.
+    #[must_use]
+    pub fn at_tick(tick: u64) -> Self {
+        Self {
+            name: format!("tick {tick}"),
+            condition: Condition::Tick(tick),
+        }
+    }
+
+    /// Fires when pending modifiers would take any entity's HP from at least
+    /// `threshold` to below it.
+    #[must_use]
+    pub fn hp_below(threshold: f32) -> Self {
+        Self {
+            name: format!("hp < {threshold}"),
+            condition: Condition::HpBelow {
+                entity: None,
+                threshold,
+            },
+        }
+    }
+
+    /// Like [`hp_below`](Self::hp_below), for a single entity.
+    #[must_use]
+    pub fn entity_hp_below(entity: EntityId, threshold: f32) -> Self {
+        Self {
+            name: format!("{entity:?} hp < {threshold}"),
+            condition: Condition::HpBelow {
+                entity: Some(entity),
+                threshold,
+            },
+        }
+    }
+
+    /// Fires on a pending `EntityDestroyed` event, or when pending damage
+    /// would destroy an entity.
+    #[must_use]
+    pub fn entity_destroyed() -> Self {
+        Self {
+            name: "entity destroyed".to_string(),
+            condition: Condition::Destroyed,
+        }
+    }
+
+    /// Returns the breakpoint name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Evaluates the breakpoint for a tick.
+    ///
+    /// Returns `None` if it does not fire, otherwise the triggering entity
+    /// (if any).
+    #[allow(clippy::option_option)]
+    pub(crate) fn check(
+        &self,
+        tick: u64,
+        current: &Arena,
+        pending: &[OutputEnvelope],
+    ) -> Option<Option<EntityId>> {
+        match &self.condition {
+            Condition::Output(predicate) => pending
+                .iter()
+                .find(|envelope| predicate(envelope))
+                .map(|envelope| Some(output_entity(envelope))),
+            Condition::State(predicate) => predicate(current).then_some(None),
+            Condition::Tick(at) => (*at == tick).then_some(None),
+            Condition::HpBelow { entity, threshold } => project_hp(current, pending)
+                .into_iter()
+                .filter(|(id, _)| entity.is_none_or(|e| e == *id))
+                .find(|(_, p)| p.before >= *threshold && p.after < *threshold)
+                .map(|(id, _)| Some(id)),
+            Condition::Destroyed => pending
+                .iter()
+                .find_map(|envelope| match envelope.output().as_event() {
+                    Some(Event::EntityDestroyed { entity, .. }) => Some(*entity),
+                    _ => None,
+                })
+                .or_else(|| {
+                    project_hp(current, pending)
+                        .into_iter()
+                        .find(|(_, p)| p.destroys)
+                        .map(|(id, _)| id)
+                })
+                .map(Some),
+        }
+    }
+}
+
+/// Entity a pending output is about.
+fn output_entity(envelope: &OutputEnvelope) -> EntityId {
+    envelope
+        .output()
+        .as_event()
+        .map_or(envelope.source().entity_id(), Event::primary_entity)
+}
+
+/// HP of one entity before and after the pending modifiers.
+struct HpProjection {
+    before: f32,
+    after: f32,
+    destroys: bool,
+}
+
+/// Applies pending damage and healing in output order, as the
+/// `CombatResolver` will, to the entities they target.
+fn project_hp(current: &Arena, pending: &[OutputEnvelope]) -> BTreeMap<EntityId, HpProjection> {
+    let mut projections = BTreeMap::new();
+    for envelope in pending {
+        let changes = match envelope.output().as_modifier() {
+            Some(Modifier::ApplyDamage { target, amount, .. }) => vec![(*target, -amount)],
+            Some(Modifier::ApplyHealing { target, amount }) => vec![(*target, *amount)],
+            Some(Modifier::ApplyAreaDamage {
+                center,
+                radius,
+                amount,
+                falloff,
+            }) => area_damage(current, *center, *radius, *amount, *falloff)
+                .into_iter()
+                .map(|(target, damage)| (target, -damage))
+                .collect(),
+            _ => continue,
+        };
+        for (target, delta) in changes {
+            let Some(combat) = current.get(target).and_then(combat_state) else {
+                continue;
+            };
+            if combat.status_flags.contains(StatusFlags::DESTROYED) {
+                continue;
+            }
+            let projection = projections.entry(target).or_insert(HpProjection {
+                before: combat.hp,
+                after: combat.hp,
+                destroys: false,
+            });
+            projection.after = (projection.after + delta).min(combat.max_hp);
+            if projection.after <= 0.0 {
+                projection.after = 0.0;
+                projection.destroys = true;
+            }
+        }
+    }
+    projections
+}
+
+const fn combat_state(entity: &Entity) -> Option<&CombatState> {
+    match entity.inner() {
+        EntityInner::Ship(c) => Some(&c.combat),
+        EntityInner::Squadron(c) => Some(&c.combat),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{DamageType, EntityTag, ShipComponents};
+    use crate::output::{Output, PluginId, PluginInstanceId, TraceId};
+
+    fn make_envelope(output: Output, entity: EntityId) -> OutputEnvelope {
+        OutputEnvelope::new(
+            output,
+            PluginInstanceId::new(entity, PluginId::new("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn damage(target: EntityId, amount: f32) -> OutputEnvelope {
+        make_envelope(
+            Output::Modifier(Modifier::ApplyDamage {
+                target,
+                amount,
+                damage_type: DamageType::Kinetic,
+            }),
+            target,
+        )
+    }
+
+    fn setup() -> (Arena, EntityId) {
+        let mut arena = Arena::new();
+        let ship = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::default()),
+        );
+        (arena, ship)
+    }
+
+    mod breakpoint_tests {
+        use super::*;
+
+        #[test]
+        fn at_tick_fires_only_at_tick() {
+            let (arena, _) = setup();
+            let bp = Breakpoint::at_tick(3);
+
+            assert_eq!(bp.check(2, &arena, &[]), None);
+            assert_eq!(bp.check(3, &arena, &[]), Some(None));
+        }
+
+        #[test]
+        fn on_event_reports_primary_entity() {
+            let (arena, ship) = setup();
+            let bp = Breakpoint::on_event("fired", |e| matches!(e, Event::WeaponFired { .. }));
+            let fired = make_envelope(
+                Output::Event(Event::WeaponFired {
+                    source: ship,
+                    weapon_slot: 0,
+                }),
+                ship,
+            );
+
+            assert_eq!(bp.check(0, &arena, &[damage(ship, 1.0)]), None);
+            assert_eq!(bp.check(0, &arena, &[fired]), Some(Some(ship)));
+        }
+
+        #[test]
+        fn hp_below_fires_on_crossing() {
+            let (arena, ship) = setup();
+            let bp = Breakpoint::hp_below(50.0);
+
+            assert_eq!(bp.check(0, &arena, &[damage(ship, 40.0)]), None);
+            assert_eq!(
+                bp.check(0, &arena, &[damage(ship, 30.0), damage(ship, 30.0)]),
+                Some(Some(ship))
+            );
+        }
+
+        #[test]
+        fn hp_below_ignores_entities_already_below() {
+            let (mut arena, ship) = setup();
+            arena
+                .get_mut(ship)
+                .unwrap()
+                .as_ship_mut()
+                .unwrap()
+                .combat
+                .hp = 10.0;
+            let bp = Breakpoint::hp_below(50.0);
+
+            assert_eq!(bp.check(0, &arena, &[damage(ship, 5.0)]), None);
+        }
+
+        #[test]
+        fn entity_hp_below_is_scoped() {
+            let (mut arena, ship) = setup();
+            let other = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let bp = Breakpoint::entity_hp_below(ship, 50.0);
+
+            assert_eq!(bp.check(0, &arena, &[damage(other, 90.0)]), None);
+            assert_eq!(bp.check(0, &arena, &[damage(ship, 90.0)]), Some(Some(ship)));
+        }
+
+        #[test]
+        fn entity_destroyed_fires_on_lethal_damage() {
+            let (arena, ship) = setup();
+            let bp = Breakpoint::entity_destroyed();
+
+            assert_eq!(bp.check(0, &arena, &[damage(ship, 99.0)]), None);
+            assert_eq!(
+                bp.check(0, &arena, &[damage(ship, 100.0)]),
+                Some(Some(ship))
+            );
+        }
+
+        #[test]
+        fn entity_destroyed_fires_on_event() {
+            let (arena, ship) = setup();
+            let event = make_envelope(
+                Output::Event(Event::EntityDestroyed {
+                    entity: ship,
+                    destroyer: None,
+                    assists: vec![],
+                }),
+                ship,
+            );
+
+            assert_eq!(
+                Breakpoint::entity_destroyed().check(0, &arena, &[event]),
+                Some(Some(ship))
+            );
+        }
+
+        #[test]
+        fn on_state_sees_pre_resolution_arena() {
+            let (arena, _) = setup();
+            let bp = Breakpoint::on_state("any ship", |a| a.entity_count() > 0);
+
+            assert_eq!(bp.check(0, &arena, &[]), Some(None));
+            assert_eq!(bp.check(0, &Arena::new(), &[]), None);
+        }
+    }
+}
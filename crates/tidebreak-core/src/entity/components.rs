@@ -9,22 +9,25 @@
 //! - [`SensorState`]: Detection capabilities and track table
 //! - [`InventoryState`]: Fuel and ammunition
 //!
+//! Plugin-specific state that doesn't warrant a component is kept in each
+//! entity's [`Attributes`] (a typed key-value store of [`AttributeValue`]s).
+//!
 //! Composite structs group these components by entity type:
 //! - [`ShipComponents`]: All components (transform, physics, combat, sensor, inventory)
-//! - [`PlatformComponents`]: Transform and sensor (stationary installations)
+//! - [`PlatformComponents`]: Transform, sensor and optional mine (stationary installations)
 //! - [`ProjectileComponents`]: Transform and physics (in-flight weapons)
 //! - [`SquadronComponents`]: Transform, physics, and combat (grouped aircraft)
 //!
 //! Access traits provide uniform access to components across different entity types:
 //! - [`HasTransform`], [`HasPhysics`], [`HasCombat`], [`HasSensor`], [`HasInventory`]
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 
 use bitflags::bitflags;
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
-use crate::entity::EntityId;
+use crate::entity::{EntityId, EntityTag};
 
 // =============================================================================
 // Supporting Types
@@ -45,6 +48,59 @@ pub enum AmmoType {
     DepthCharge,
     /// Countermeasure flares and chaff
     Countermeasure,
+    /// Smoke generator charges, one per smoke screen
+    Smoke,
+}
+
+impl AmmoType {
+    /// Every ammunition type, in declaration order.
+    pub const ALL: [Self; 7] = [
+        Self::Bullet,
+        Self::Missile,
+        Self::Torpedo,
+        Self::Shell,
+        Self::DepthCharge,
+        Self::Countermeasure,
+        Self::Smoke,
+    ];
+}
+
+/// A quantity of transferable supplies.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Cargo {
+    /// Fuel, in the units of [`InventoryState::fuel`]
+    Fuel(f32),
+    /// Rounds of one ammunition type
+    Ammo(AmmoType, u32),
+}
+
+impl Cargo {
+    /// Returns the amount as a float (fuel units or rounds).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn amount(&self) -> f32 {
+        match self {
+            Self::Fuel(amount) => *amount,
+            Self::Ammo(_, count) => *count as f32,
+        }
+    }
+
+    /// Returns true if both are the same kind of cargo (fuel, or the same
+    /// ammunition type), regardless of amount.
+    #[must_use]
+    pub fn same_kind(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Fuel(_), Self::Fuel(_)) => true,
+            (Self::Ammo(a, _), Self::Ammo(b, _)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Returns true if there is nothing to transfer.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.amount() <= 0.0
+    }
 }
 
 /// Emissions mode for sensor systems.
@@ -104,9 +160,48 @@ pub struct Track {
     pub age: f32,
     /// Classification confidence (0.0-1.0)
     pub classification_confidence: f32,
+    /// Believed entity type, once classified (may be wrong if misclassified)
+    #[serde(default)]
+    pub classified_as: Option<EntityTag>,
+    /// Last position fixes, oldest first (at most [`Track::HISTORY_LEN`])
+    #[serde(default)]
+    pub history: VecDeque<TrackFix>,
+}
+
+/// A position of a contact observed at a given time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackFix {
+    /// Simulation time of the observation (seconds)
+    pub time: f32,
+    /// Observed position
+    pub position: Vec2,
+}
+
+/// Course and speed of a contact estimated from its track history.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackEstimate {
+    /// Estimated velocity (m/s)
+    pub velocity: Vec2,
+    /// Estimated course (radians, world frame)
+    pub course: f32,
+    /// Estimated speed (m/s)
+    pub speed: f32,
+    /// Standard error of the course (radians)
+    pub course_sigma: f32,
+    /// Standard error of the speed (m/s)
+    pub speed_sigma: f32,
+    /// Number of fixes the estimate is fitted to
+    pub fixes: usize,
 }
 
 impl Track {
+    /// Number of position fixes kept per track.
+    pub const HISTORY_LEN: usize = 8;
+
+    /// Fewest fixes a course and speed estimate is fitted to; with fewer
+    /// the fit would be exact and its uncertainty unknown.
+    pub const MIN_ESTIMATE_FIXES: usize = 3;
+
     /// Creates a new track with the given parameters.
     #[must_use]
     pub fn new(target_id: EntityId, position: Vec2, quality: TrackQuality) -> Self {
@@ -117,8 +212,87 @@ impl Track {
             quality,
             age: 0.0,
             classification_confidence: 0.0,
+            classified_as: None,
+            history: VecDeque::new(),
         }
     }
+
+    /// Returns `true` if the track has been classified.
+    ///
+    /// Unclassified tracks should be treated as "unknown" by observers.
+    #[must_use]
+    pub const fn is_identified(&self) -> bool {
+        self.classified_as.is_some()
+    }
+
+    /// Updates the track with a position observed at `time` (seconds).
+    ///
+    /// The fix becomes the track's position, resets its age and is added to
+    /// the history, dropping the oldest fix once [`Self::HISTORY_LEN`] are
+    /// kept. The velocity is replaced by the new estimate, if there is one.
+    pub fn record_fix(&mut self, time: f32, position: Vec2) {
+        if self.history.len() >= Self::HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(TrackFix { time, position });
+        self.position = position;
+        self.age = 0.0;
+        if let Some(estimate) = self.estimate() {
+            self.velocity = Some(estimate.velocity);
+        }
+    }
+
+    /// Estimates the contact's course and speed from the history.
+    ///
+    /// Fits a constant velocity to the fixes by least squares. The scatter
+    /// of the fixes about the fitted line gives the standard error of the
+    /// velocity, reported as speed and course uncertainty. Returns `None`
+    /// with fewer than [`Self::MIN_ESTIMATE_FIXES`] fixes or when they were
+    /// all taken at the same time.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn estimate(&self) -> Option<TrackEstimate> {
+        let n = self.history.len();
+        if n < Self::MIN_ESTIMATE_FIXES {
+            return None;
+        }
+        // Times relative to the first fix keep long runs precise
+        let start = self.history.front()?.time;
+        let count = n as f32;
+        let mean_t = self.history.iter().map(|f| f.time - start).sum::<f32>() / count;
+        let mean_p = self.history.iter().map(|f| f.position).sum::<Vec2>() / count;
+        let mut stt = 0.0;
+        let mut stp = Vec2::ZERO;
+        for fix in &self.history {
+            let dt = fix.time - start - mean_t;
+            stt += dt * dt;
+            stp += (fix.position - mean_p) * dt;
+        }
+        if stt <= f32::EPSILON {
+            return None;
+        }
+        let velocity = stp / stt;
+        let residual: f32 = self
+            .history
+            .iter()
+            .map(|f| {
+                let fitted = mean_p + velocity * (f.time - start - mean_t);
+                f.position.distance_squared(fitted)
+            })
+            .sum();
+        // Per-axis variance of the fixes (two fitted parameters per axis)
+        let variance = residual / (2.0 * (count - 2.0));
+        let speed_sigma = (variance / stt).sqrt();
+        let speed = velocity.length();
+        Some(TrackEstimate {
+            velocity,
+            course: velocity.y.atan2(velocity.x),
+            speed,
+            course_sigma: speed_sigma.atan2(speed),
+            speed_sigma,
+            fixes: n,
+        })
+    }
 }
 
 impl Default for Track {
@@ -130,6 +304,8 @@ impl Default for Track {
             quality: TrackQuality::default(),
             age: 0.0,
             classification_confidence: 0.0,
+            classified_as: None,
+            history: VecDeque::new(),
         }
     }
 }
@@ -147,6 +323,30 @@ pub struct WeaponState {
     pub ammo_type: AmmoType,
     /// Whether this weapon is operational
     pub operational: bool,
+    /// Rounds per magazine (0 for a cooldown-only weapon with no magazine)
+    #[serde(default)]
+    pub magazine_size: u32,
+    /// Rounds left in the magazine
+    #[serde(default)]
+    pub rounds: u32,
+    /// Rounds expended per shot (0 is treated as 1)
+    #[serde(default)]
+    pub burst_size: u32,
+    /// Ticks needed to reload a magazine
+    #[serde(default)]
+    pub reload_ticks: u32,
+    /// Ticks left in the reload in progress (0 when not reloading)
+    #[serde(default)]
+    pub reload_remaining: u32,
+    /// Ballistics of a gun resolved on firing, without projectile entities
+    #[serde(default)]
+    pub gun: Option<GunBallistics>,
+    /// Hit points of the mount (0 for a weapon that is never hit on its own)
+    #[serde(default)]
+    pub max_hp: f32,
+    /// Hit points left on the mount
+    #[serde(default)]
+    pub hp: f32,
 }
 
 impl WeaponState {
@@ -159,13 +359,108 @@ impl WeaponState {
             max_cooldown,
             ammo_type,
             operational: true,
+            magazine_size: 0,
+            rounds: 0,
+            burst_size: 1,
+            reload_ticks: 0,
+            reload_remaining: 0,
+            gun: None,
+            max_hp: 0.0,
+            hp: 0.0,
         }
     }
 
+    /// Gives the weapon a full magazine of `size` rounds that takes
+    /// `reload_ticks` ticks to reload.
+    #[must_use]
+    pub const fn with_magazine(mut self, size: u32, reload_ticks: u32) -> Self {
+        self.magazine_size = size;
+        self.rounds = size;
+        self.reload_ticks = reload_ticks;
+        self
+    }
+
+    /// Sets the rounds expended per shot.
+    #[must_use]
+    pub const fn with_burst(mut self, burst_size: u32) -> Self {
+        self.burst_size = burst_size;
+        self
+    }
+
+    /// Makes the weapon a gun whose shells are resolved on firing (see
+    /// [`GunBallistics`]).
+    #[must_use]
+    pub const fn with_gun(mut self, gun: GunBallistics) -> Self {
+        self.gun = Some(gun);
+        self
+    }
+
+    /// Gives the mount `max_hp` hit points of its own, so hits on the hull
+    /// can knock it out (see [`CombatState::subsystem_exposure`]).
+    #[must_use]
+    pub const fn with_hp(mut self, max_hp: f32) -> Self {
+        self.max_hp = max_hp;
+        self.hp = max_hp;
+        self
+    }
+
     /// Returns true if the weapon is ready to fire.
+    ///
+    /// A magazine-fed weapon must also have rounds left and not be reloading.
     #[must_use]
     pub fn is_ready(&self) -> bool {
-        self.operational && self.cooldown <= 0.0
+        self.operational
+            && self.cooldown <= 0.0
+            && !self.is_reloading()
+            && (!self.has_magazine() || self.rounds > 0)
+    }
+
+    /// Returns true if the weapon is magazine-fed.
+    #[must_use]
+    pub const fn has_magazine(&self) -> bool {
+        self.magazine_size > 0
+    }
+
+    /// Returns true while a reload is in progress.
+    #[must_use]
+    pub const fn is_reloading(&self) -> bool {
+        self.reload_remaining > 0
+    }
+
+    /// Returns true if a reload would add rounds: the weapon is magazine-fed,
+    /// not already reloading, and its magazine is not full.
+    #[must_use]
+    pub const fn can_reload(&self) -> bool {
+        self.has_magazine() && !self.is_reloading() && self.rounds < self.magazine_size
+    }
+
+    /// Returns the rounds expended per shot.
+    #[must_use]
+    pub fn rounds_per_shot(&self) -> u32 {
+        self.burst_size.max(1)
+    }
+
+    /// Returns the fraction of the cooldown still to run, from 1.0 just
+    /// after firing to 0.0 when the weapon can fire again.
+    #[must_use]
+    pub fn cooldown_fraction(&self) -> f32 {
+        if self.max_cooldown > 0.0 {
+            (self.cooldown / self.max_cooldown).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the fraction of the reload completed (0.0 when not reloading).
+    #[must_use]
+    pub fn reload_progress(&self) -> f32 {
+        if self.is_reloading() && self.reload_ticks > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let progress = 1.0 - self.reload_remaining as f32 / self.reload_ticks as f32;
+            progress
+        } else {
+            0.0
+        }
     }
 }
 
@@ -177,6 +472,74 @@ impl Default for WeaponState {
             max_cooldown: 1.0,
             ammo_type: AmmoType::Bullet,
             operational: true,
+            magazine_size: 0,
+            rounds: 0,
+            burst_size: 1,
+            reload_ticks: 0,
+            reload_remaining: 0,
+            gun: None,
+            max_hp: 0.0,
+            hp: 0.0,
+        }
+    }
+}
+
+/// Ballistics of a naval gun resolved as hitscan.
+///
+/// Each round is resolved the tick it is fired: it lands at the target's
+/// position after the time of flight (the gun leads perfectly), displaced
+/// by a normally distributed error whose standard deviation grows with
+/// range (`dispersion`) and with the target's travel during the flight
+/// (`lead_error`). A round landing within `hit_radius` of the target's
+/// hull hits; any other round splashes into the sea. Rounds fired beyond
+/// `max_range` fall short at `max_range`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GunBallistics {
+    /// Damage per round that hits
+    pub damage: f32,
+    /// Maximum range (m)
+    pub max_range: f32,
+    /// Muzzle velocity (m/s), for the time of flight
+    pub muzzle_velocity: f32,
+    /// Aiming error per meter of range (radians, one standard deviation)
+    pub dispersion: f32,
+    /// Fraction of the target's travel during the flight added to the
+    /// aiming error
+    pub lead_error: f32,
+    /// Distance from the hull within which a round hits (m)
+    pub hit_radius: f32,
+}
+
+impl GunBallistics {
+    /// Returns the seconds a round takes to travel `range` meters.
+    #[must_use]
+    pub fn time_of_flight(&self, range: f32) -> f32 {
+        if self.muzzle_velocity > 0.0 {
+            range / self.muzzle_velocity
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the standard deviation (m) of the impact point around a
+    /// target at `range` moving at `target_speed`.
+    #[must_use]
+    pub fn miss_sigma(&self, range: f32, target_speed: f32) -> f32 {
+        self.dispersion * range + self.lead_error * target_speed * self.time_of_flight(range)
+    }
+}
+
+impl Default for GunBallistics {
+    /// A 5-inch dual-purpose gun.
+    fn default() -> Self {
+        Self {
+            damage: 10.0,
+            max_range: 15_000.0,
+            muzzle_velocity: 800.0,
+            dispersion: 0.002,
+            lead_error: 0.1,
+            hit_radius: 15.0,
         }
     }
 }
@@ -251,6 +614,198 @@ bitflags! {
     }
 }
 
+/// A part of a hull that damage can knock out on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Subsystem {
+    /// The weapon mount in the given slot
+    Weapon(usize),
+    /// The sensor suite
+    Sensors,
+}
+
+// =============================================================================
+// Attributes
+// =============================================================================
+
+/// A typed value in an entity's attribute store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AttributeValue {
+    /// Floating-point value
+    Float(f32),
+    /// Integer value
+    Int(i64),
+    /// Boolean value
+    Bool(bool),
+    /// String value
+    Text(String),
+}
+
+impl AttributeValue {
+    /// Returns the value as `f32` if it is a `Float`.
+    #[must_use]
+    pub const fn as_f32(&self) -> Option<f32> {
+        match self {
+            Self::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `i64` if it is an `Int`.
+    #[must_use]
+    pub const fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `bool` if it is a `Bool`.
+    #[must_use]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `&str` if it is `Text`.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Text(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl From<f32> for AttributeValue {
+    fn from(v: f32) -> Self {
+        Self::Float(v)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(v: i64) -> Self {
+        Self::Int(v)
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(v: bool) -> Self {
+        Self::Bool(v)
+    }
+}
+
+impl From<&str> for AttributeValue {
+    fn from(v: &str) -> Self {
+        Self::Text(v.to_owned())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(v: String) -> Self {
+        Self::Text(v)
+    }
+}
+
+/// Per-entity key-value store for plugin-specific state.
+///
+/// Keys are ordered, so iteration and hashing are deterministic. By
+/// convention keys are namespaced by plugin, e.g. `"patrol.waypoint"`.
+pub type Attributes = BTreeMap<String, AttributeValue>;
+
+// =============================================================================
+// Mines
+// =============================================================================
+
+/// Fuze deciding whether a ship inside a mine's trigger radius sets it off.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum MineFuze {
+    /// Detonates on any ship inside the trigger radius.
+    #[default]
+    Proximity,
+    /// Influence fuze on radiated noise: detonates when the ship's speed
+    /// (m/s) reaches the threshold. Slow transits pass safely.
+    Acoustic {
+        /// Minimum speed that triggers the mine (m/s)
+        threshold: f32,
+    },
+    /// Influence fuze on magnetic signature: detonates when the ship's hull
+    /// radius (m) reaches the threshold. Small craft pass safely.
+    Magnetic {
+        /// Minimum hull radius that triggers the mine (m)
+        threshold: f32,
+    },
+}
+
+impl MineFuze {
+    /// Returns true if a ship with this transform and physics sets the fuze off.
+    #[must_use]
+    pub fn triggered_by(&self, transform: &TransformState, physics: &PhysicsState) -> bool {
+        match self {
+            Self::Proximity => true,
+            Self::Acoustic { threshold } => physics.speed() >= *threshold,
+            Self::Magnetic { threshold } => transform.radius >= *threshold,
+        }
+    }
+}
+
+/// State of a laid mine.
+///
+/// Mines are platforms carrying a `MineState`. They stay hidden from
+/// sensors until a minesweeping ship detects them with sonar.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MineState {
+    /// Fuze type
+    pub fuze: MineFuze,
+    /// Distance (m) within which a passing ship is tested against the fuze
+    pub trigger_radius: f32,
+    /// Damage dealt to every ship within the blast radius
+    pub damage: f32,
+    /// Radius (m) of the detonation
+    pub blast_radius: f32,
+    /// True once the mine has been found by minesweeping
+    pub detected: bool,
+}
+
+impl MineState {
+    /// Creates an undetected mine with the given fuze and default warhead.
+    #[must_use]
+    pub fn new(fuze: MineFuze) -> Self {
+        Self {
+            fuze,
+            ..Self::default()
+        }
+    }
+
+    /// Builder method to set the trigger radius.
+    #[must_use]
+    pub const fn with_trigger_radius(mut self, trigger_radius: f32) -> Self {
+        self.trigger_radius = trigger_radius;
+        self
+    }
+
+    /// Builder method to set the warhead damage and blast radius.
+    #[must_use]
+    pub const fn with_warhead(mut self, damage: f32, blast_radius: f32) -> Self {
+        self.damage = damage;
+        self.blast_radius = blast_radius;
+        self
+    }
+}
+
+impl Default for MineState {
+    fn default() -> Self {
+        Self {
+            fuze: MineFuze::Proximity,
+            trigger_radius: 50.0,
+            damage: 80.0,
+            blast_radius: 75.0,
+            detected: false,
+        }
+    }
+}
+
 // =============================================================================
 // Core State Components
 // =============================================================================
@@ -264,13 +819,27 @@ pub struct TransformState {
     pub position: Vec2,
     /// Heading in radians (counter-clockwise from +X axis)
     pub heading: f32,
+    /// Hull radius in meters, used by segment queries (0 = point)
+    #[serde(default)]
+    pub radius: f32,
 }
 
 impl TransformState {
     /// Creates a new transform state at the given position and heading.
     #[must_use]
     pub fn new(position: Vec2, heading: f32) -> Self {
-        Self { position, heading }
+        Self {
+            position,
+            heading,
+            radius: 0.0,
+        }
+    }
+
+    /// Returns this transform with the given hull radius.
+    #[must_use]
+    pub const fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
     }
 
     /// Returns the forward direction vector based on the current heading.
@@ -291,10 +860,125 @@ impl Default for TransformState {
         Self {
             position: Vec2::ZERO,
             heading: 0.0,
+            radius: 0.0,
         }
     }
 }
 
+/// Engine response - how quickly a hull reaches an ordered throttle and
+/// heading.
+///
+/// Used by the physics resolver once a throttle has been set; entities
+/// driven by raw `SetVelocity` commands ignore it. The presets cover the
+/// common hull classes, from nimble corvettes to sluggish capital ships.
+///
+/// Under throttle a hull turns toward its ordered heading no faster than
+/// its [`turn_rate`](Self::turn_rate): its `max_turn_rate` at rest, falling
+/// to `full_speed_turn` of that at max speed, and never tighter than
+/// `min_turn_radius` allows at its current speed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EngineProfile {
+    /// Rate of gaining speed toward the ordered throttle, in m/s²
+    pub acceleration: f32,
+    /// Rate of shedding speed (including when reversing), in m/s²
+    pub deceleration: f32,
+    /// Largest astern throttle, as a fraction of max speed (0 = no reverse)
+    pub reverse_limit: f32,
+    /// Fraction of the max turn rate left at max speed (1 = no loss)
+    #[serde(default = "EngineProfile::default_full_speed_turn")]
+    pub full_speed_turn: f32,
+    /// Tightest turning circle radius in meters (0 = pivots in place)
+    #[serde(default = "EngineProfile::default_min_turn_radius")]
+    pub min_turn_radius: f32,
+}
+
+impl EngineProfile {
+    /// Small, quick-responding hull.
+    pub const CORVETTE: Self = Self::new(4.0, 6.0, 0.5).with_turning(0.6, 100.0);
+    /// Medium hull; the default profile.
+    pub const DESTROYER: Self = Self::new(2.0, 3.0, 0.4).with_turning(0.5, 200.0);
+    /// Large warship.
+    pub const CRUISER: Self = Self::new(1.0, 1.5, 0.3).with_turning(0.4, 400.0);
+    /// Carrier or battleship.
+    pub const CAPITAL: Self = Self::new(0.5, 0.75, 0.25).with_turning(0.35, 700.0);
+
+    /// Creates an engine profile with no turning limits beyond the hull's
+    /// max turn rate.
+    #[must_use]
+    pub const fn new(acceleration: f32, deceleration: f32, reverse_limit: f32) -> Self {
+        Self {
+            acceleration,
+            deceleration,
+            reverse_limit,
+            full_speed_turn: 1.0,
+            min_turn_radius: 0.0,
+        }
+    }
+
+    /// Builder method to set the turning limits.
+    #[must_use]
+    pub const fn with_turning(mut self, full_speed_turn: f32, min_turn_radius: f32) -> Self {
+        self.full_speed_turn = full_speed_turn;
+        self.min_turn_radius = min_turn_radius;
+        self
+    }
+
+    const fn default_full_speed_turn() -> f32 {
+        Self::DESTROYER.full_speed_turn
+    }
+
+    const fn default_min_turn_radius() -> f32 {
+        Self::DESTROYER.min_turn_radius
+    }
+
+    /// Returns the turn rate in rad/s available at `speed` to a hull of
+    /// `max_speed` and `max_turn_rate`.
+    #[must_use]
+    pub fn turn_rate(&self, speed: f32, max_speed: f32, max_turn_rate: f32) -> f32 {
+        let speed = speed.abs();
+        let fraction = if max_speed > 0.0 {
+            (speed / max_speed).min(1.0)
+        } else {
+            0.0
+        };
+        let loss = 1.0 - self.full_speed_turn.clamp(0.0, 1.0);
+        let rate = max_turn_rate.max(0.0) * (1.0 - loss * fraction);
+        if self.min_turn_radius > 0.0 {
+            rate.min(speed / self.min_turn_radius)
+        } else {
+            rate
+        }
+    }
+
+    /// Returns `fraction` limited to the throttle range `[-reverse_limit, 1]`.
+    #[must_use]
+    pub fn clamp_throttle(&self, fraction: f32) -> f32 {
+        fraction.clamp(-self.reverse_limit.max(0.0), 1.0)
+    }
+
+    /// Advances a signed speed along the heading toward `target` over `dt`.
+    ///
+    /// Gaining speed in the current direction uses `acceleration`; slowing
+    /// down or reversing uses `deceleration`.
+    #[must_use]
+    pub fn approach(&self, speed: f32, target: f32, dt: f32) -> f32 {
+        let gaining = speed * target >= 0.0 && target.abs() > speed.abs();
+        let rate = if gaining {
+            self.acceleration
+        } else {
+            self.deceleration
+        };
+        let step = rate.max(0.0) * dt;
+        speed + (target - speed).clamp(-step, step)
+    }
+}
+
+impl Default for EngineProfile {
+    fn default() -> Self {
+        Self::DESTROYER
+    }
+}
+
 /// Physics state - velocity and movement constraints.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct PhysicsState {
@@ -306,6 +990,22 @@ pub struct PhysicsState {
     pub max_speed: f32,
     /// Maximum turn rate in rad/s
     pub max_turn_rate: f32,
+    /// Ordered throttle as a fraction of max speed (negative = astern).
+    ///
+    /// `None` until a `SetThrottle` command arrives; a `SetVelocity`
+    /// command clears it again.
+    #[serde(default)]
+    pub throttle: Option<f32>,
+    /// Acceleration, reverse and turning limits applied while under
+    /// throttle
+    #[serde(default)]
+    pub engine: EngineProfile,
+    /// Heading a throttled entity is turning toward, in radians.
+    ///
+    /// Set by `SetHeading` commands and cleared once reached; entities not
+    /// under throttle take the ordered heading at once.
+    #[serde(default)]
+    pub ordered_heading: Option<f32>,
 }
 
 impl PhysicsState {
@@ -317,9 +1017,19 @@ impl PhysicsState {
             angular_velocity: 0.0,
             max_speed,
             max_turn_rate,
+            throttle: None,
+            engine: EngineProfile::default(),
+            ordered_heading: None,
         }
     }
 
+    /// Returns this state with the given engine profile.
+    #[must_use]
+    pub const fn with_engine(mut self, engine: EngineProfile) -> Self {
+        self.engine = engine;
+        self
+    }
+
     /// Returns the current speed (magnitude of velocity).
     #[must_use]
     pub fn speed(&self) -> f32 {
@@ -340,6 +1050,9 @@ impl Default for PhysicsState {
             angular_velocity: 0.0,
             max_speed: 10.0,
             max_turn_rate: 1.0,
+            throttle: None,
+            engine: EngineProfile::default(),
+            ordered_heading: None,
         }
     }
 }
@@ -355,9 +1068,25 @@ pub struct CombatState {
     pub weapons: Vec<WeaponState>,
     /// Status flags (disabled systems, destroyed, etc.)
     pub status_flags: StatusFlags,
+    /// Shield or other soft mitigation taking damage before HP (none by default)
+    #[serde(default)]
+    pub mitigation: Option<MitigationState>,
+    /// Chance that a hit reaching the hull also strikes one of the weapon
+    /// or sensor mounts with hit points of their own
+    #[serde(default = "CombatState::default_subsystem_exposure")]
+    pub subsystem_exposure: f32,
+    /// Hostile entity whose damage last reached the hull
+    #[serde(default)]
+    pub last_damager: Option<EntityId>,
+    /// Hull damage taken so far from each hostile entity
+    #[serde(default)]
+    pub damage_by_source: BTreeMap<EntityId, f32>,
 }
 
 impl CombatState {
+    /// Chance that a hit on the hull also strikes a weapon or sensor mount.
+    pub const DEFAULT_SUBSYSTEM_EXPOSURE: f32 = 0.25;
+
     /// Creates a new combat state with the given max HP.
     #[must_use]
     pub fn new(max_hp: f32) -> Self {
@@ -366,6 +1095,10 @@ impl CombatState {
             max_hp,
             weapons: Vec::new(),
             status_flags: StatusFlags::empty(),
+            mitigation: None,
+            subsystem_exposure: Self::DEFAULT_SUBSYSTEM_EXPOSURE,
+            last_damager: None,
+            damage_by_source: BTreeMap::new(),
         }
     }
 
@@ -377,9 +1110,31 @@ impl CombatState {
             max_hp,
             weapons,
             status_flags: StatusFlags::empty(),
+            mitigation: None,
+            subsystem_exposure: Self::DEFAULT_SUBSYSTEM_EXPOSURE,
+            last_damager: None,
+            damage_by_source: BTreeMap::new(),
         }
     }
 
+    /// Adds damage mitigation taken before HP.
+    #[must_use]
+    pub const fn with_mitigation(mut self, mitigation: MitigationState) -> Self {
+        self.mitigation = Some(mitigation);
+        self
+    }
+
+    /// Builder method to set the chance that a hull hit strikes a mount.
+    #[must_use]
+    pub const fn with_subsystem_exposure(mut self, exposure: f32) -> Self {
+        self.subsystem_exposure = exposure;
+        self
+    }
+
+    const fn default_subsystem_exposure() -> f32 {
+        Self::DEFAULT_SUBSYSTEM_EXPOSURE
+    }
+
     /// Returns the health percentage (0.0-1.0).
     #[must_use]
     pub fn health_percent(&self) -> f32 {
@@ -414,6 +1169,27 @@ impl CombatState {
         self.status_flags.contains(StatusFlags::SENSORS_DISABLED)
     }
 
+    /// Credits `amount` of hull damage to `source`, making it the last
+    /// damager.
+    pub fn record_damage(&mut self, source: EntityId, amount: f32) {
+        *self.damage_by_source.entry(source).or_default() += amount;
+        self.last_damager = Some(source);
+    }
+
+    /// Returns the entities other than the last damager that damaged this
+    /// one, most damage first (ties by ID).
+    #[must_use]
+    pub fn assists(&self) -> Vec<EntityId> {
+        let mut assists: Vec<(EntityId, f32)> = self
+            .damage_by_source
+            .iter()
+            .filter(|(&source, _)| Some(source) != self.last_damager)
+            .map(|(&source, &amount)| (source, amount))
+            .collect();
+        assists.sort_by(|a, b| b.1.total_cmp(&a.1));
+        assists.into_iter().map(|(source, _)| source).collect()
+    }
+
     /// Returns a weapon by slot index.
     #[must_use]
     pub fn get_weapon(&self, slot: usize) -> Option<&WeaponState> {
@@ -434,7 +1210,133 @@ impl Default for CombatState {
             max_hp: 100.0,
             weapons: Vec::new(),
             status_flags: StatusFlags::empty(),
+            mitigation: None,
+            subsystem_exposure: Self::DEFAULT_SUBSYSTEM_EXPOSURE,
+            last_damager: None,
+            damage_by_source: BTreeMap::new(),
+        }
+    }
+}
+
+/// Kind of damage, for mitigation resistances.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DamageType {
+    /// Shells, bullets, ramming
+    #[default]
+    Kinetic,
+    /// Blasts (area damage is always explosive)
+    Explosive,
+    /// Beams and other directed energy
+    Energy,
+}
+
+/// Fraction [0, 1] of each damage type negated before it reaches the pool
+/// or hull.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Resistances {
+    /// Resistance to kinetic damage
+    pub kinetic: f32,
+    /// Resistance to explosive damage
+    pub explosive: f32,
+    /// Resistance to energy damage
+    pub energy: f32,
+}
+
+impl Resistances {
+    /// Returns the resistance to `damage_type`, clamped to [0, 1].
+    #[must_use]
+    pub fn get(&self, damage_type: DamageType) -> f32 {
+        let resistance = match damage_type {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Explosive => self.explosive,
+            DamageType::Energy => self.energy,
+        };
+        resistance.clamp(0.0, 1.0)
+    }
+}
+
+/// Regenerating damage pool (an energy shield) consulted before HP.
+///
+/// A hit is first reduced by the resistance to its type, then absorbed by
+/// the pool; whatever the pool cannot absorb reaches the hull. The pool
+/// regenerates at `regen_rate` once `regen_delay` seconds have passed
+/// without a hit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MitigationState {
+    /// Pool size when fully charged
+    pub capacity: f32,
+    /// Pool remaining
+    pub pool: f32,
+    /// Pool regenerated per second
+    pub regen_rate: f32,
+    /// Seconds after a hit before the pool regenerates
+    pub regen_delay: f32,
+    /// Seconds left before regeneration resumes
+    pub recharge_in: f32,
+    /// Per-type damage resistances
+    pub resistances: Resistances,
+}
+
+impl MitigationState {
+    /// Creates a fully charged pool with no regeneration delay or
+    /// resistances.
+    #[must_use]
+    pub const fn new(capacity: f32, regen_rate: f32) -> Self {
+        Self {
+            capacity,
+            pool: capacity,
+            regen_rate,
+            regen_delay: 0.0,
+            recharge_in: 0.0,
+            resistances: Resistances {
+                kinetic: 0.0,
+                explosive: 0.0,
+                energy: 0.0,
+            },
+        }
+    }
+
+    /// Sets the seconds after a hit before the pool regenerates.
+    #[must_use]
+    pub const fn with_regen_delay(mut self, seconds: f32) -> Self {
+        self.regen_delay = seconds;
+        self
+    }
+
+    /// Sets the per-type resistances.
+    #[must_use]
+    pub const fn with_resistances(mut self, resistances: Resistances) -> Self {
+        self.resistances = resistances;
+        self
+    }
+
+    /// Takes a hit, returning the damage left for the hull.
+    pub fn absorb(&mut self, amount: f32, damage_type: DamageType) -> f32 {
+        let amount = amount * (1.0 - self.resistances.get(damage_type));
+        if amount <= 0.0 {
+            return amount;
+        }
+        let absorbed = amount.min(self.pool.max(0.0));
+        self.pool -= absorbed;
+        self.recharge_in = self.regen_delay;
+        amount - absorbed
+    }
+
+    /// Advances regeneration by `dt` seconds.
+    pub fn regenerate(&mut self, dt: f32) {
+        if self.recharge_in > 0.0 {
+            self.recharge_in = (self.recharge_in - dt).max(0.0);
+            return;
         }
+        self.pool = (self.pool + self.regen_rate * dt).min(self.capacity);
+    }
+}
+
+impl Default for MitigationState {
+    fn default() -> Self {
+        Self::new(100.0, 10.0).with_regen_delay(3.0)
     }
 }
 
@@ -449,9 +1351,25 @@ pub struct SensorState {
     pub emissions_mode: EmissionsMode,
     /// Track table - known contacts
     pub track_table: Vec<Track>,
+    /// Lookout and periscope range in clear daylight (meters)
+    #[serde(default = "SensorState::default_visual_range")]
+    pub visual_range: f32,
+    /// Hit points of the sensor suite (0 for sensors never hit on their own)
+    #[serde(default)]
+    pub max_hp: f32,
+    /// Hit points left on the sensor suite
+    #[serde(default)]
+    pub hp: f32,
+    /// Number of changes to the track table so far; see
+    /// [`touch_tracks`](Self::touch_tracks)
+    #[serde(default)]
+    pub track_generation: u64,
 }
 
 impl SensorState {
+    /// Visual range of lookouts on a clear day, in meters.
+    pub const DEFAULT_VISUAL_RANGE: f32 = 3000.0;
+
     /// Creates a new sensor state with the given ranges.
     #[must_use]
     pub fn new(radar_range: f32, sonar_range: f32) -> Self {
@@ -460,59 +1378,484 @@ impl SensorState {
             sonar_range,
             emissions_mode: EmissionsMode::default(),
             track_table: Vec::new(),
+            visual_range: Self::DEFAULT_VISUAL_RANGE,
+            max_hp: 0.0,
+            hp: 0.0,
+            track_generation: 0,
         }
     }
 
-    /// Returns the effective radar range based on emissions mode.
+    /// Builder method to set the visual range.
+    #[must_use]
+    pub const fn with_visual_range(mut self, visual_range: f32) -> Self {
+        self.visual_range = visual_range;
+        self
+    }
+
+    /// Gives the sensor suite `max_hp` hit points of its own, so hits on
+    /// the hull degrade it (see [`CombatState::subsystem_exposure`]).
+    #[must_use]
+    pub const fn with_hp(mut self, max_hp: f32) -> Self {
+        self.max_hp = max_hp;
+        self.hp = max_hp;
+        self
+    }
+
+    const fn default_visual_range() -> f32 {
+        Self::DEFAULT_VISUAL_RANGE
+    }
+
+    /// Returns the fraction of their range the sensors keep after damage:
+    /// hp / `max_hp`, or 1 for sensors without hit points of their own.
+    #[must_use]
+    pub fn integrity(&self) -> f32 {
+        if self.max_hp > 0.0 {
+            (self.hp / self.max_hp).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns the effective radar range based on emissions mode and
+    /// damage.
     #[must_use]
     pub fn effective_radar_range(&self) -> f32 {
         match self.emissions_mode {
             EmissionsMode::Silent | EmissionsMode::Passive => 0.0,
-            EmissionsMode::Active => self.radar_range,
+            EmissionsMode::Active => self.radar_range * self.integrity(),
         }
     }
 
-    /// Returns the effective sonar range based on emissions mode.
+    /// Returns the effective sonar range based on emissions mode and
+    /// damage.
     #[must_use]
     pub fn effective_sonar_range(&self) -> f32 {
-        match self.emissions_mode {
+        let range = match self.emissions_mode {
             EmissionsMode::Silent => self.sonar_range * 0.5, // Passive only, reduced range
             EmissionsMode::Passive => self.sonar_range * 0.75,
             EmissionsMode::Active => self.sonar_range,
+        };
+        range * self.integrity()
+    }
+
+    /// Records a change to the track table.
+    ///
+    /// Views derived from the table, such as the contacts of
+    /// [`InterestManager`](crate::interest::InterestManager), are rebuilt
+    /// only when the generation changes. The resolvers call this whenever
+    /// they update the table; code editing `track_table` directly must
+    /// call it too.
+    pub fn touch_tracks(&mut self) {
+        self.track_generation = self.track_generation.wrapping_add(1);
+    }
+
+    /// Finds a track by target ID.
+    #[must_use]
+    pub fn find_track(&self, target_id: EntityId) -> Option<&Track> {
+        self.track_table.iter().find(|t| t.target_id == target_id)
+    }
+
+    /// Finds a mutable track by target ID.
+    #[must_use]
+    pub fn find_track_mut(&mut self, target_id: EntityId) -> Option<&mut Track> {
+        self.track_table
+            .iter_mut()
+            .find(|t| t.target_id == target_id)
+    }
+
+    /// Returns tracks at or above the given quality level.
+    #[must_use]
+    pub fn tracks_at_quality(&self, min_quality: TrackQuality) -> Vec<&Track> {
+        self.track_table
+            .iter()
+            .filter(|t| t.quality >= min_quality)
+            .collect()
+    }
+}
+
+impl Default for SensorState {
+    fn default() -> Self {
+        Self {
+            radar_range: 10000.0,
+            sonar_range: 5000.0,
+            emissions_mode: EmissionsMode::default(),
+            track_table: Vec::new(),
+            visual_range: Self::DEFAULT_VISUAL_RANGE,
+            max_hp: 0.0,
+            hp: 0.0,
+            track_generation: 0,
+        }
+    }
+}
+
+/// Sensory footprint - how visible an entity is to each sensor channel.
+///
+/// The fields hold the signature of an undamaged hull at rest.
+/// [`current`](Self::current) adjusts them for speed, damage and emissions
+/// mode, and the range factors turn a signature into a multiplier on the
+/// observer's detection range, relative to the reference hull ([`Default`]).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SignatureState {
+    /// Radar cross section in m²
+    pub radar_cross_section: f32,
+    /// Radiated noise in dB re 1 µPa at 1 m
+    pub acoustic_level: f32,
+    /// Infrared signature relative to the reference hull
+    pub thermal: f32,
+}
+
+impl SignatureState {
+    /// Radar cross section of the reference hull, in m².
+    pub const REFERENCE_RCS: f32 = 1000.0;
+    /// Radiated noise of the reference hull, in dB.
+    pub const REFERENCE_ACOUSTIC: f32 = 120.0;
+    /// Infrared signature of the reference hull.
+    pub const REFERENCE_THERMAL: f32 = 1.0;
+    /// Largest detection range multiplier any signature can produce.
+    pub const MAX_RANGE_FACTOR: f32 = 4.0;
+
+    /// Extra radiated noise at full speed, in dB.
+    const SPEED_NOISE_DB: f32 = 20.0;
+    /// Extra radiated noise when fully damaged, in dB.
+    const DAMAGE_NOISE_DB: f32 = 6.0;
+
+    /// Creates a signature from its at-rest values.
+    #[must_use]
+    pub const fn new(radar_cross_section: f32, acoustic_level: f32, thermal: f32) -> Self {
+        Self {
+            radar_cross_section,
+            acoustic_level,
+            thermal,
         }
     }
 
-    /// Finds a track by target ID.
+    /// Returns the signature at `speed_fraction` of max speed (0-1), with
+    /// `damage` (0 = intact, 1 = destroyed) and the given emissions mode.
+    ///
+    /// - Speed adds machinery and flow noise and engine heat.
+    /// - Damage adds radar returns from wreckage, noise and fires.
+    /// - Active emissions add the noise of pinging sonar; silent running
+    ///   quiets the hull.
+    #[must_use]
+    pub fn current(&self, speed_fraction: f32, damage: f32, emissions: EmissionsMode) -> Self {
+        let speed = speed_fraction.clamp(0.0, 1.0);
+        let damage = damage.clamp(0.0, 1.0);
+        let emissions_db = match emissions {
+            EmissionsMode::Silent => -6.0,
+            EmissionsMode::Passive => 0.0,
+            EmissionsMode::Active => 10.0,
+        };
+        Self {
+            radar_cross_section: self.radar_cross_section * (1.0 + damage),
+            acoustic_level: self.acoustic_level
+                + Self::SPEED_NOISE_DB * speed
+                + Self::DAMAGE_NOISE_DB * damage
+                + emissions_db,
+            thermal: self.thermal * (1.0 + speed) * (1.0 + 2.0 * damage),
+        }
+    }
+
+    /// Radar detection range multiplier (fourth root of the RCS ratio, per
+    /// the radar equation).
+    #[must_use]
+    pub fn radar_range_factor(&self) -> f32 {
+        Self::range_factor((self.radar_cross_section / Self::REFERENCE_RCS).powf(0.25))
+    }
+
+    /// Sonar detection range multiplier (spherical spreading: every 20 dB
+    /// above the reference multiplies range by ten).
+    #[must_use]
+    pub fn acoustic_range_factor(&self) -> f32 {
+        Self::range_factor(10f32.powf((self.acoustic_level - Self::REFERENCE_ACOUSTIC) / 20.0))
+    }
+
+    /// Infrared detection range multiplier (inverse-square falloff).
+    #[must_use]
+    pub fn thermal_range_factor(&self) -> f32 {
+        Self::range_factor((self.thermal / Self::REFERENCE_THERMAL).sqrt())
+    }
+
+    /// Limits a range multiplier to `[0, MAX_RANGE_FACTOR]`.
+    fn range_factor(factor: f32) -> f32 {
+        if factor.is_nan() {
+            0.0
+        } else {
+            factor.clamp(0.0, Self::MAX_RANGE_FACTOR)
+        }
+    }
+}
+
+impl Default for SignatureState {
+    fn default() -> Self {
+        Self::new(
+            Self::REFERENCE_RCS,
+            Self::REFERENCE_ACOUSTIC,
+            Self::REFERENCE_THERMAL,
+        )
+    }
+}
+
+/// Diving state of a submarine.
+///
+/// Submarines are ships carrying a `SubmarineState`. Depth splits their
+/// behavior into three bands:
+///
+/// - **Surfaced** (depth 0): sensors, signature and charging as a surface
+///   ship.
+/// - **Snorkeling** (up to `snorkel_depth`): the diesels run through the
+///   snorkel mast, recharging the battery at the cost of a small radar
+///   return and extra noise.
+/// - **Submerged** (below `snorkel_depth`): invisible to radar and infrared
+///   and limited to sonar, running on a battery that drains with speed.
+///
+/// Below `crush_depth` the hull takes damage every second.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SubmarineState {
+    /// Current depth in meters below the surface (0 = surfaced)
+    pub depth: f32,
+    /// Depth the submarine is diving or rising toward
+    pub ordered_depth: f32,
+    /// Vertical speed in m/s
+    pub dive_rate: f32,
+    /// Deepest depth at which the snorkel mast reaches the surface
+    pub snorkel_depth: f32,
+    /// Depth below which the hull takes damage
+    pub crush_depth: f32,
+    /// HP lost per second below crush depth
+    pub crush_damage_rate: f32,
+    /// Battery charge remaining
+    pub battery: f32,
+    /// Battery capacity
+    pub max_battery: f32,
+    /// Charge drained per second at full speed while submerged
+    pub battery_drain: f32,
+    /// Charge restored per second while snorkeling or surfaced
+    pub recharge_rate: f32,
+}
+
+impl SubmarineState {
+    /// Fraction of the full-speed drain used by a submerged boat at rest.
+    pub const HOTEL_LOAD: f32 = 0.1;
+    /// Fraction of the hull's radar cross section shown by the snorkel mast.
+    pub const SNORKEL_RCS_FRACTION: f32 = 0.05;
+    /// Extra radiated noise of the diesels while snorkeling, in dB.
+    pub const SNORKEL_NOISE_DB: f32 = 10.0;
+    /// Fraction of the hull's infrared signature shown by diesel exhaust.
+    pub const SNORKEL_THERMAL_FRACTION: f32 = 0.5;
+
+    /// Creates a surfaced submarine with a full battery.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder method to set the crush depth.
+    #[must_use]
+    pub const fn with_crush_depth(mut self, crush_depth: f32) -> Self {
+        self.crush_depth = crush_depth;
+        self
+    }
+
+    /// Builder method to set a full battery of the given capacity.
+    #[must_use]
+    pub const fn with_battery(mut self, max_battery: f32) -> Self {
+        self.battery = max_battery;
+        self.max_battery = max_battery;
+        self
+    }
+
+    /// Returns `true` at the surface.
+    #[must_use]
+    pub fn is_surfaced(&self) -> bool {
+        self.depth <= 0.0
+    }
+
+    /// Returns `true` below the surface but shallow enough to snorkel.
+    #[must_use]
+    pub fn is_snorkeling(&self) -> bool {
+        self.depth > 0.0 && self.depth <= self.snorkel_depth
+    }
+
+    /// Returns `true` below snorkel depth: on battery and sonar only.
+    #[must_use]
+    pub fn is_submerged(&self) -> bool {
+        self.depth > self.snorkel_depth
+    }
+
+    /// Returns the battery charge as a fraction of capacity (0.0-1.0).
+    #[must_use]
+    pub fn battery_fraction(&self) -> f32 {
+        if self.max_battery > 0.0 {
+            (self.battery / self.max_battery).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the charge drained per second while submerged at
+    /// `speed_fraction` of max speed.
+    #[must_use]
+    pub fn drain(&self, speed_fraction: f32) -> f32 {
+        let speed = speed_fraction.clamp(0.0, 1.0);
+        self.battery_drain * (Self::HOTEL_LOAD + (1.0 - Self::HOTEL_LOAD) * speed)
+    }
+
+    /// Adjusts a surface-ship signature for the current depth.
+    ///
+    /// Submerged boats show no radar or infrared signature; snorkeling
+    /// boats show the mast and the diesel exhaust and noise.
+    #[must_use]
+    pub fn mask(&self, signature: SignatureState) -> SignatureState {
+        if self.is_submerged() {
+            SignatureState {
+                radar_cross_section: 0.0,
+                thermal: 0.0,
+                ..signature
+            }
+        } else if self.is_snorkeling() {
+            SignatureState {
+                radar_cross_section: signature.radar_cross_section * Self::SNORKEL_RCS_FRACTION,
+                acoustic_level: signature.acoustic_level + Self::SNORKEL_NOISE_DB,
+                thermal: signature.thermal * Self::SNORKEL_THERMAL_FRACTION,
+            }
+        } else {
+            signature
+        }
+    }
+}
+
+impl Default for SubmarineState {
+    fn default() -> Self {
+        Self {
+            depth: 0.0,
+            ordered_depth: 0.0,
+            dive_rate: 2.0,
+            snorkel_depth: 15.0,
+            crush_depth: 300.0,
+            crush_damage_rate: 5.0,
+            battery: 3600.0,
+            max_battery: 3600.0,
+            battery_drain: 1.0,
+            recharge_rate: 2.0,
+        }
+    }
+}
+
+/// Role of the craft in a squadron, for air-to-air exchange rates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CraftType {
+    /// Air superiority craft
+    #[default]
+    Fighter,
+    /// Strike craft with defensive guns only
+    Bomber,
+    /// Long-range scouts, lightly armed
+    Patrol,
+}
+
+impl CraftType {
+    /// Returns the multiplier on the air-to-air fire of this craft type
+    /// against `target`.
+    ///
+    /// | firing \ target | Fighter | Bomber | Patrol |
+    /// |-----------------|---------|--------|--------|
+    /// | Fighter         | 1.0     | 1.5    | 1.25   |
+    /// | Bomber          | 0.25    | 0.25   | 0.25   |
+    /// | Patrol          | 0.5     | 1.0    | 1.0    |
+    #[must_use]
+    pub const fn exchange_factor(self, target: Self) -> f32 {
+        match (self, target) {
+            (Self::Fighter, Self::Fighter) | (Self::Patrol, Self::Bomber | Self::Patrol) => 1.0,
+            (Self::Fighter, Self::Bomber) => 1.5,
+            (Self::Fighter, Self::Patrol) => 1.25,
+            (Self::Bomber, _) => 0.25,
+            (Self::Patrol, Self::Fighter) => 0.5,
+        }
+    }
+}
+
+/// Air-to-air state of a squadron.
+///
+/// While a squadron with ordnance left is within `engagement_range` of the
+/// enemy squadron it is intercepting, the
+/// [`CombatResolver`](crate::resolver::CombatResolver) resolves a
+/// simultaneous exchange of fire between the two each tick. A squadron
+/// under attack returns fire if it has ordnance, whether or not it has an
+/// intercept order of its own.
+///
+/// Each side deals, per second, `lethality` times its remaining HP, scaled
+/// by [`CraftType::exchange_factor`] against the other side and by its
+/// remaining fraction of ordnance, so depleted squadrons fight at reduced
+/// effect. Ordnance is counted in seconds of fire and drains by one per
+/// second engaged; a squadron engaged by several enemies splits its fire
+/// between them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AirWing {
+    /// Role of the squadron's craft
+    pub craft: CraftType,
+    /// Seconds of air-to-air fire remaining
+    pub ordnance: f32,
+    /// Seconds of air-to-air fire when fully armed
+    pub max_ordnance: f32,
+    /// Distance within which the squadron engages, in meters
+    pub engagement_range: f32,
+    /// Fraction of the squadron's HP dealt as damage per second of fire
+    pub lethality: f32,
+    /// Enemy squadron being intercepted, if any
+    pub intercept: Option<EntityId>,
+}
+
+impl AirWing {
+    /// Creates a fully armed wing of the given craft type.
+    #[must_use]
+    pub fn new(craft: CraftType) -> Self {
+        Self {
+            craft,
+            ..Self::default()
+        }
+    }
+
+    /// Builder method to set a full load of `ordnance` seconds of fire.
+    #[must_use]
+    pub const fn with_ordnance(mut self, ordnance: f32) -> Self {
+        self.ordnance = ordnance;
+        self.max_ordnance = ordnance;
+        self
+    }
+
+    /// Builder method to set the engagement range.
     #[must_use]
-    pub fn find_track(&self, target_id: EntityId) -> Option<&Track> {
-        self.track_table.iter().find(|t| t.target_id == target_id)
+    pub const fn with_engagement_range(mut self, range: f32) -> Self {
+        self.engagement_range = range;
+        self
     }
 
-    /// Finds a mutable track by target ID.
+    /// Returns the remaining ordnance as a fraction of a full load (0.0-1.0).
     #[must_use]
-    pub fn find_track_mut(&mut self, target_id: EntityId) -> Option<&mut Track> {
-        self.track_table
-            .iter_mut()
-            .find(|t| t.target_id == target_id)
+    pub fn ordnance_fraction(&self) -> f32 {
+        if self.max_ordnance > 0.0 {
+            (self.ordnance / self.max_ordnance).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
     }
 
-    /// Returns tracks at or above the given quality level.
+    /// Returns `true` if the wing has ordnance left to fire.
     #[must_use]
-    pub fn tracks_at_quality(&self, min_quality: TrackQuality) -> Vec<&Track> {
-        self.track_table
-            .iter()
-            .filter(|t| t.quality >= min_quality)
-            .collect()
+    pub fn is_armed(&self) -> bool {
+        self.ordnance > 0.0
     }
 }
 
-impl Default for SensorState {
+impl Default for AirWing {
     fn default() -> Self {
         Self {
-            radar_range: 10000.0,
-            sonar_range: 5000.0,
-            emissions_mode: EmissionsMode::default(),
-            track_table: Vec::new(),
+            craft: CraftType::Fighter,
+            ordnance: 60.0,
+            max_ordnance: 60.0,
+            engagement_range: 2000.0,
+            lethality: 0.02,
+            intercept: None,
         }
     }
 }
@@ -581,6 +1924,51 @@ impl InventoryState {
         }
         false
     }
+
+    /// Returns the part of `cargo` this inventory can supply.
+    #[must_use]
+    pub fn supply(&self, cargo: Cargo) -> Cargo {
+        match cargo {
+            Cargo::Fuel(amount) => Cargo::Fuel(amount.min(self.fuel).max(0.0)),
+            Cargo::Ammo(ammo_type, count) => {
+                Cargo::Ammo(ammo_type, count.min(self.get_ammo(ammo_type)))
+            }
+        }
+    }
+
+    /// Returns the part of `cargo` this inventory has room for.
+    ///
+    /// Fuel is limited by `max_fuel`; ammunition storage is unbounded.
+    #[must_use]
+    pub fn room(&self, cargo: Cargo) -> Cargo {
+        match cargo {
+            Cargo::Fuel(amount) => Cargo::Fuel(amount.min(self.max_fuel - self.fuel).max(0.0)),
+            ammo @ Cargo::Ammo(..) => ammo,
+        }
+    }
+
+    /// Removes cargo, saturating at zero.
+    pub fn withdraw(&mut self, cargo: Cargo) {
+        match cargo {
+            Cargo::Fuel(amount) => self.fuel = (self.fuel - amount).max(0.0),
+            Cargo::Ammo(ammo_type, count) => {
+                if let Some(held) = self.ammo.get_mut(&ammo_type) {
+                    *held = held.saturating_sub(count);
+                }
+            }
+        }
+    }
+
+    /// Adds cargo; fuel is capped at `max_fuel`.
+    pub fn deposit(&mut self, cargo: Cargo) {
+        match cargo {
+            Cargo::Fuel(amount) => self.fuel = (self.fuel + amount).min(self.max_fuel),
+            Cargo::Ammo(ammo_type, count) => {
+                let held = self.ammo.entry(ammo_type).or_insert(0);
+                *held = held.saturating_add(count);
+            }
+        }
+    }
 }
 
 impl Default for InventoryState {
@@ -613,6 +2001,12 @@ pub struct ShipComponents {
     pub sensor: SensorState,
     /// Fuel and ammunition
     pub inventory: InventoryState,
+    /// Radar, acoustic and infrared footprint
+    #[serde(default)]
+    pub signature: SignatureState,
+    /// Depth, battery and crush depth, if this ship is a submarine
+    #[serde(default)]
+    pub submarine: Option<SubmarineState>,
 }
 
 impl ShipComponents {
@@ -631,6 +2025,17 @@ impl ShipComponents {
         }
     }
 
+    /// Creates an unarmed merchant ship at the given position.
+    ///
+    /// Merchants are slow and clumsy but their large hulls soak more damage
+    /// than a warship's.
+    #[must_use]
+    pub fn merchant(position: Vec2, heading: f32) -> Self {
+        Self::at_position(position, heading)
+            .with_max_hp(250.0)
+            .with_physics(7.0, 0.3)
+    }
+
     /// Builder method to set max HP.
     #[must_use]
     pub fn with_max_hp(mut self, max_hp: f32) -> Self {
@@ -641,7 +2046,15 @@ impl ShipComponents {
     /// Builder method to set physics limits.
     #[must_use]
     pub fn with_physics(mut self, max_speed: f32, max_turn_rate: f32) -> Self {
-        self.physics = PhysicsState::new(max_speed, max_turn_rate);
+        self.physics =
+            PhysicsState::new(max_speed, max_turn_rate).with_engine(self.physics.engine);
+        self
+    }
+
+    /// Builder method to set the engine profile.
+    #[must_use]
+    pub fn with_engine(mut self, engine: EngineProfile) -> Self {
+        self.physics.engine = engine;
         self
     }
 
@@ -651,6 +2064,45 @@ impl ShipComponents {
         self.sensor = SensorState::new(radar_range, sonar_range);
         self
     }
+
+    /// Builder method to set the at-rest signature.
+    #[must_use]
+    pub fn with_signature(mut self, signature: SignatureState) -> Self {
+        self.signature = signature;
+        self
+    }
+
+    /// Builder method to make this ship a submarine.
+    #[must_use]
+    pub fn with_submarine(mut self, submarine: SubmarineState) -> Self {
+        self.submarine = Some(submarine);
+        self
+    }
+
+    /// Returns the fraction of max speed the ship is moving at (0.0-1.0).
+    #[must_use]
+    pub fn speed_fraction(&self) -> f32 {
+        if self.physics.max_speed > 0.0 {
+            (self.physics.speed() / self.physics.max_speed).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the signature for the ship's current speed, damage,
+    /// emissions mode and, for submarines, depth.
+    #[must_use]
+    pub fn current_signature(&self) -> SignatureState {
+        let signature = self.signature.current(
+            self.speed_fraction(),
+            1.0 - self.combat.health_percent(),
+            self.sensor.emissions_mode,
+        );
+        match &self.submarine {
+            Some(submarine) => submarine.mask(signature),
+            None => signature,
+        }
+    }
 }
 
 
@@ -664,6 +2116,12 @@ pub struct PlatformComponents {
     pub transform: TransformState,
     /// Detection capabilities and track table
     pub sensor: SensorState,
+    /// Mine warhead and fuze, if this platform is a mine
+    #[serde(default)]
+    pub mine: Option<MineState>,
+    /// Supplies held for resupplying other entities, if this is a depot
+    #[serde(default)]
+    pub stockpile: Option<InventoryState>,
 }
 
 impl PlatformComponents {
@@ -679,6 +2137,21 @@ impl PlatformComponents {
         Self {
             transform: TransformState::new(position, 0.0),
             sensor: SensorState::default(),
+            mine: None,
+            stockpile: None,
+        }
+    }
+
+    /// Creates a mine at the given position.
+    ///
+    /// Mines carry no sensors of their own.
+    #[must_use]
+    pub fn mine(position: Vec2, mine: MineState) -> Self {
+        Self {
+            transform: TransformState::new(position, 0.0),
+            sensor: SensorState::new(0.0, 0.0).with_visual_range(0.0),
+            mine: Some(mine),
+            stockpile: None,
         }
     }
 
@@ -688,19 +2161,115 @@ impl PlatformComponents {
         self.sensor = SensorState::new(radar_range, sonar_range);
         self
     }
+
+    /// Builder method to make this platform a supply depot.
+    #[must_use]
+    pub fn with_stockpile(mut self, stockpile: InventoryState) -> Self {
+        self.stockpile = Some(stockpile);
+        self
+    }
+}
+
+
+/// Pattern a seeker flies while it has no lock.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SearchPattern {
+    /// Hold course with the seeker fixed on the boresight.
+    #[default]
+    Straight,
+    /// Hold course while the seeker head sweeps from side to side.
+    Sweep {
+        /// Largest offset of the seeker from the boresight (radians)
+        half_angle: f32,
+        /// Seconds for one full sweep
+        period: f32,
+    },
+    /// Turn at the full turn rate so the cone sweeps the horizon.
+    Orbit,
+}
+
+/// Homing seeker of a guided projectile.
+///
+/// A target can be locked while it lies within `range` and half of `fov`
+/// of the projectile's heading, and is neither screened by smoke nor
+/// masked by solid terrain. A target that turns out of the cone breaks the
+/// lock, so a hard enough turn defeats a seeker with a narrow cone.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeekerState {
+    /// Full width of the seeker cone (radians)
+    pub fov: f32,
+    /// Greatest distance at which a target can be locked (m)
+    pub range: f32,
+    /// Target designated at launch, preferred while searching
+    pub cue: Option<EntityId>,
+    /// Pattern flown while no target is locked
+    pub search: SearchPattern,
+    /// Target currently locked, kept up to date from the seeker's
+    /// `LockAcquired` and `LockLost` events
+    pub lock: Option<EntityId>,
+}
+
+impl SeekerState {
+    /// Returns the offset (radians) of the seeker from the boresight
+    /// while searching, `time` seconds into the simulation.
+    #[must_use]
+    pub fn search_offset(&self, time: f32) -> f32 {
+        match self.search {
+            SearchPattern::Sweep { half_angle, period } if period > 0.0 => {
+                half_angle * (std::f32::consts::TAU * time / period).sin()
+            }
+            SearchPattern::Straight | SearchPattern::Sweep { .. } | SearchPattern::Orbit => 0.0,
+        }
+    }
+
+    /// Returns the signed angle (radians) from `boresight` to the bearing
+    /// of `to` from `from`, or 0 if the points coincide.
+    #[must_use]
+    pub fn off_axis(from: Vec2, boresight: f32, to: Vec2) -> f32 {
+        let offset = to - from;
+        if offset == Vec2::ZERO {
+            0.0
+        } else {
+            Vec2::from_angle(boresight).angle_to(offset)
+        }
+    }
+
+    /// Returns `true` if `to` lies within the cone of a seeker at `from`
+    /// looking along `boresight`.
+    #[must_use]
+    pub fn in_cone(&self, from: Vec2, boresight: f32, to: Vec2) -> bool {
+        from.distance(to) <= self.range
+            && Self::off_axis(from, boresight, to).abs() <= self.fov / 2.0
+    }
 }
 
+impl Default for SeekerState {
+    /// An anti-ship missile seeker with a 60 degree cone.
+    fn default() -> Self {
+        Self {
+            fov: std::f32::consts::FRAC_PI_3,
+            range: 10_000.0,
+            cue: None,
+            search: SearchPattern::Straight,
+            lock: None,
+        }
+    }
+}
 
 /// Components for Projectile entities.
 ///
 /// Projectiles are in-flight weapons (missiles, torpedoes, shells). They have
-/// physics for movement but no complex systems.
+/// physics for movement and, if guided, a seeker.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectileComponents {
     /// Position and heading
     pub transform: TransformState,
     /// Velocity and movement limits
     pub physics: PhysicsState,
+    /// Homing seeker (`None` for unguided rounds)
+    #[serde(default)]
+    pub seeker: Option<SeekerState>,
 }
 
 impl ProjectileComponents {
@@ -720,9 +2289,20 @@ impl ProjectileComponents {
                 angular_velocity: 0.0,
                 max_speed: velocity.length() * 1.5, // Some margin for guidance
                 max_turn_rate: 0.5,                 // Limited maneuverability
+                throttle: None,
+                engine: EngineProfile::default(),
+                ordered_heading: None,
             },
+            seeker: None,
         }
     }
+
+    /// Fits a homing seeker.
+    #[must_use]
+    pub const fn with_seeker(mut self, seeker: SeekerState) -> Self {
+        self.seeker = Some(seeker);
+        self
+    }
 }
 
 impl Default for ProjectileComponents {
@@ -734,7 +2314,11 @@ impl Default for ProjectileComponents {
                 angular_velocity: 0.0,
                 max_speed: 500.0, // Fast by default
                 max_turn_rate: 0.5,
+                throttle: None,
+                engine: EngineProfile::default(),
+                ordered_heading: None,
             },
+            seeker: None,
         }
     }
 }
@@ -751,6 +2335,10 @@ pub struct SquadronComponents {
     pub physics: PhysicsState,
     /// Aggregate health and weapons
     pub combat: CombatState,
+    /// Air-to-air role and ordnance, if the squadron can fight other
+    /// squadrons
+    #[serde(default)]
+    pub air_wing: Option<AirWing>,
 }
 
 impl SquadronComponents {
@@ -767,6 +2355,7 @@ impl SquadronComponents {
             transform: TransformState::new(position, heading),
             physics: PhysicsState::default(),
             combat: CombatState::default(),
+            air_wing: None,
         }
     }
 
@@ -783,6 +2372,13 @@ impl SquadronComponents {
         self.combat = CombatState::new(total_hp);
         self
     }
+
+    /// Builder method to give the squadron an air-to-air wing.
+    #[must_use]
+    pub const fn with_air_wing(mut self, air_wing: AirWing) -> Self {
+        self.air_wing = Some(air_wing);
+        self
+    }
 }
 
 impl Default for SquadronComponents {
@@ -794,8 +2390,12 @@ impl Default for SquadronComponents {
                 angular_velocity: 0.0,
                 max_speed: 150.0,   // Aircraft are fast
                 max_turn_rate: 2.0, // And maneuverable
+                throttle: None,
+                engine: EngineProfile::default(),
+                ordered_heading: None,
             },
             combat: CombatState::default(),
+            air_wing: None,
         }
     }
 }
@@ -1055,6 +2655,46 @@ mod tests {
             let deserialized: PhysicsState = serde_json::from_str(&json).unwrap();
             assert_eq!(physics, deserialized);
         }
+
+        #[test]
+        fn legacy_json_defaults_engine() {
+            let json = r#"{"velocity":[1.0,0.0],"angular_velocity":0.0,"max_speed":5.0,"max_turn_rate":1.0}"#;
+            let physics: PhysicsState = serde_json::from_str(json).unwrap();
+            assert_eq!(physics.throttle, None);
+            assert_eq!(physics.engine, EngineProfile::DESTROYER);
+        }
+    }
+
+    mod engine_profile_tests {
+        use super::*;
+
+        #[test]
+        fn clamp_throttle_limits_reverse() {
+            let engine = EngineProfile::new(1.0, 1.0, 0.25);
+            assert_eq!(engine.clamp_throttle(2.0), 1.0);
+            assert_eq!(engine.clamp_throttle(-1.0), -0.25);
+            assert_eq!(engine.clamp_throttle(0.5), 0.5);
+        }
+
+        #[test]
+        fn approach_accelerates_and_decelerates_at_own_rates() {
+            let engine = EngineProfile::new(1.0, 3.0, 0.5);
+            assert_eq!(engine.approach(0.0, 10.0, 1.0), 1.0);
+            assert_eq!(engine.approach(10.0, 0.0, 1.0), 7.0);
+            // Reversing sheds forward speed at the deceleration rate
+            assert_eq!(engine.approach(1.0, -5.0, 1.0), -2.0);
+            // Never overshoots the target
+            assert_eq!(engine.approach(9.5, 10.0, 1.0), 10.0);
+        }
+
+        #[test]
+        fn ship_builders_keep_engine() {
+            let ship = ShipComponents::new()
+                .with_engine(EngineProfile::CAPITAL)
+                .with_physics(8.0, 0.5);
+            assert_eq!(ship.physics.engine, EngineProfile::CAPITAL);
+            assert_eq!(ship.physics.max_speed, 8.0);
+        }
     }
 
     mod combat_state_tests {
@@ -1137,6 +2777,36 @@ mod tests {
         }
     }
 
+    mod mitigation_state_tests {
+        use super::*;
+
+        #[test]
+        fn resistance_then_pool_then_hull() {
+            let mut shield = MitigationState::new(30.0, 5.0).with_resistances(Resistances {
+                energy: 0.5,
+                ..Resistances::default()
+            });
+            // 40 energy -> 20 after resistance, all absorbed
+            assert_eq!(shield.absorb(40.0, DamageType::Energy), 0.0);
+            assert!((shield.pool - 10.0).abs() < 1e-5);
+            // 25 kinetic: 10 absorbed, 15 through
+            assert!((shield.absorb(25.0, DamageType::Kinetic) - 15.0).abs() < 1e-5);
+            assert_eq!(shield.pool, 0.0);
+        }
+
+        #[test]
+        fn regenerates_after_delay_up_to_capacity() {
+            let mut shield = MitigationState::new(10.0, 4.0).with_regen_delay(1.0);
+            shield.absorb(10.0, DamageType::Kinetic);
+            shield.regenerate(1.0);
+            assert_eq!(shield.pool, 0.0);
+            shield.regenerate(2.0);
+            assert!((shield.pool - 8.0).abs() < 1e-5);
+            shield.regenerate(2.0);
+            assert_eq!(shield.pool, 10.0);
+        }
+    }
+
     mod sensor_state_tests {
         use super::*;
 
@@ -1235,6 +2905,136 @@ mod tests {
         }
     }
 
+    mod signature_state_tests {
+        use super::*;
+
+        #[test]
+        fn reference_signature_has_unit_factors() {
+            let signature = SignatureState::default();
+            assert!((signature.radar_range_factor() - 1.0).abs() < 1e-6);
+            assert!((signature.acoustic_range_factor() - 1.0).abs() < 1e-6);
+            assert!((signature.thermal_range_factor() - 1.0).abs() < 1e-6);
+        }
+
+        #[test]
+        fn at_rest_passive_intact_is_unchanged() {
+            let signature = SignatureState::new(500.0, 110.0, 0.5);
+            assert_eq!(
+                signature.current(0.0, 0.0, EmissionsMode::Passive),
+                signature
+            );
+        }
+
+        #[test]
+        fn speed_and_emissions_raise_noise() {
+            let signature = SignatureState::default();
+            let full_speed = signature.current(1.0, 0.0, EmissionsMode::Passive);
+            assert!((full_speed.acoustic_range_factor() - 4.0).abs() < 1e-4);
+            assert!(full_speed.thermal > signature.thermal);
+
+            let silent = signature.current(0.0, 0.0, EmissionsMode::Silent);
+            let active = signature.current(0.0, 0.0, EmissionsMode::Active);
+            assert!(silent.acoustic_range_factor() < 1.0);
+            assert!(active.acoustic_range_factor() > 1.0);
+        }
+
+        #[test]
+        fn damage_raises_radar_and_thermal() {
+            let signature = SignatureState::default();
+            let wrecked = signature.current(0.0, 1.0, EmissionsMode::Passive);
+            assert_eq!(wrecked.radar_cross_section, 2.0 * SignatureState::REFERENCE_RCS);
+            assert_eq!(wrecked.thermal, 3.0);
+        }
+
+        #[test]
+        fn small_rcs_shrinks_radar_range() {
+            let stealthy = SignatureState::new(SignatureState::REFERENCE_RCS / 16.0, 120.0, 1.0);
+            assert!((stealthy.radar_range_factor() - 0.5).abs() < 1e-6);
+        }
+
+        #[test]
+        fn ship_current_signature_uses_components() {
+            let mut ship = ShipComponents::new().with_physics(10.0, 1.0);
+            ship.physics.velocity = Vec2::new(10.0, 0.0);
+            ship.combat.hp = 50.0;
+            ship.sensor.emissions_mode = EmissionsMode::Active;
+
+            let expected = ship.signature.current(1.0, 0.5, EmissionsMode::Active);
+            assert_eq!(ship.current_signature(), expected);
+        }
+
+        #[test]
+        fn legacy_ship_json_defaults_signature() {
+            let ship = ShipComponents::default();
+            let mut json = serde_json::to_value(&ship).unwrap();
+            json.as_object_mut().unwrap().remove("signature");
+            let restored: ShipComponents = serde_json::from_value(json).unwrap();
+            assert_eq!(restored.signature, SignatureState::default());
+        }
+    }
+
+    mod submarine_state_tests {
+        use super::*;
+
+        fn at_depth(depth: f32) -> SubmarineState {
+            SubmarineState {
+                depth,
+                ..SubmarineState::default()
+            }
+        }
+
+        #[test]
+        fn depth_bands() {
+            assert!(at_depth(0.0).is_surfaced());
+            assert!(at_depth(10.0).is_snorkeling());
+            assert!(at_depth(15.0).is_snorkeling());
+            assert!(at_depth(15.5).is_submerged());
+            assert!(!at_depth(15.5).is_snorkeling());
+        }
+
+        #[test]
+        fn drain_grows_with_speed() {
+            let sub = SubmarineState::default();
+            assert!((sub.drain(0.0) - SubmarineState::HOTEL_LOAD).abs() < 1e-6);
+            assert!((sub.drain(1.0) - 1.0).abs() < 1e-6);
+            assert!((sub.drain(5.0) - 1.0).abs() < 1e-6);
+        }
+
+        #[test]
+        fn submerged_hides_from_radar_and_infrared() {
+            let signature = SignatureState::default();
+            let masked = at_depth(100.0).mask(signature);
+            assert_eq!(masked.radar_cross_section, 0.0);
+            assert_eq!(masked.thermal, 0.0);
+            assert_eq!(masked.acoustic_level, signature.acoustic_level);
+            assert_eq!(masked.radar_range_factor(), 0.0);
+        }
+
+        #[test]
+        fn snorkeling_is_louder_but_small_on_radar() {
+            let signature = SignatureState::default();
+            let masked = at_depth(10.0).mask(signature);
+            assert!(masked.radar_range_factor() < signature.radar_range_factor());
+            assert!(masked.acoustic_range_factor() > signature.acoustic_range_factor());
+            assert_eq!(at_depth(0.0).mask(signature), signature);
+        }
+
+        #[test]
+        fn ship_signature_applies_depth() {
+            let ship = ShipComponents::new().with_submarine(at_depth(100.0));
+            assert_eq!(ship.current_signature().radar_cross_section, 0.0);
+        }
+
+        #[test]
+        fn legacy_ship_json_is_not_a_submarine() {
+            let ship = ShipComponents::default();
+            let mut json = serde_json::to_value(&ship).unwrap();
+            json.as_object_mut().unwrap().remove("submarine");
+            let restored: ShipComponents = serde_json::from_value(json).unwrap();
+            assert_eq!(restored.submarine, None);
+        }
+    }
+
     mod inventory_state_tests {
         use super::*;
 
@@ -1287,6 +3087,56 @@ mod tests {
             let deserialized: InventoryState = serde_json::from_str(&json).unwrap();
             assert_eq!(inventory, deserialized);
         }
+
+        #[test]
+        fn supply_is_limited_by_stock() {
+            let mut ammo = BTreeMap::new();
+            ammo.insert(AmmoType::Shell, 5);
+            let mut inventory = InventoryState::with_ammo(100.0, ammo);
+            inventory.fuel = 40.0;
+
+            assert_eq!(inventory.supply(Cargo::Fuel(60.0)), Cargo::Fuel(40.0));
+            assert_eq!(
+                inventory.supply(Cargo::Ammo(AmmoType::Shell, 8)),
+                Cargo::Ammo(AmmoType::Shell, 5)
+            );
+            assert!(inventory.supply(Cargo::Ammo(AmmoType::Missile, 8)).is_empty());
+        }
+
+        #[test]
+        fn room_is_limited_by_fuel_capacity() {
+            let mut inventory = InventoryState::new(100.0);
+            inventory.fuel = 70.0;
+
+            assert_eq!(inventory.room(Cargo::Fuel(50.0)), Cargo::Fuel(30.0));
+            assert_eq!(
+                inventory.room(Cargo::Ammo(AmmoType::Torpedo, 50)),
+                Cargo::Ammo(AmmoType::Torpedo, 50)
+            );
+        }
+
+        #[test]
+        fn withdraw_and_deposit() {
+            let mut inventory = InventoryState::new(100.0);
+            inventory.withdraw(Cargo::Fuel(30.0));
+            inventory.deposit(Cargo::Ammo(AmmoType::Missile, 4));
+            inventory.withdraw(Cargo::Ammo(AmmoType::Missile, 1));
+
+            assert!((inventory.fuel - 70.0).abs() < 1e-6);
+            assert_eq!(inventory.get_ammo(AmmoType::Missile), 3);
+
+            inventory.deposit(Cargo::Fuel(50.0));
+            assert!((inventory.fuel - 100.0).abs() < 1e-6);
+        }
+
+        #[test]
+        fn cargo_kinds() {
+            assert!(Cargo::Fuel(1.0).same_kind(&Cargo::Fuel(5.0)));
+            assert!(Cargo::Ammo(AmmoType::Shell, 1).same_kind(&Cargo::Ammo(AmmoType::Shell, 9)));
+            assert!(!Cargo::Ammo(AmmoType::Shell, 1).same_kind(&Cargo::Ammo(AmmoType::Bullet, 1)));
+            assert!(!Cargo::Fuel(1.0).same_kind(&Cargo::Ammo(AmmoType::Shell, 1)));
+            assert!((Cargo::Ammo(AmmoType::Shell, 3).amount() - 3.0).abs() < 1e-6);
+        }
     }
 
     mod status_flags_tests {
@@ -1424,6 +3274,82 @@ mod tests {
             let deserialized: PlatformComponents = serde_json::from_str(&json).unwrap();
             assert_eq!(platform, deserialized);
         }
+
+        #[test]
+        fn with_stockpile_makes_depot() {
+            let platform = PlatformComponents::at_position(Vec2::ZERO)
+                .with_stockpile(InventoryState::new(5000.0));
+            assert!((platform.stockpile.unwrap().fuel - 5000.0).abs() < 1e-6);
+            assert!(PlatformComponents::default().stockpile.is_none());
+        }
+
+        #[test]
+        fn mine_has_no_sensors() {
+            let platform = PlatformComponents::mine(Vec2::new(3.0, 4.0), MineState::default());
+            assert_eq!(platform.transform.position, Vec2::new(3.0, 4.0));
+            assert!(platform.sensor.radar_range.abs() < 1e-6);
+            assert!(platform.mine.is_some_and(|m| !m.detected));
+        }
+
+        #[test]
+        fn mine_field_defaults_when_missing() {
+            let json = r#"{"transform":{"position":[0.0,0.0],"heading":0.0},
+                "sensor":{"radar_range":1.0,"sonar_range":1.0,"emissions_mode":"Passive",
+                "track_table":[]}}"#;
+            let platform: PlatformComponents = serde_json::from_str(json).unwrap();
+            assert!(platform.mine.is_none());
+        }
+    }
+
+    mod mine_tests {
+        use super::*;
+
+        #[test]
+        fn proximity_fuze_always_triggers() {
+            let fuze = MineFuze::Proximity;
+            assert!(fuze.triggered_by(&TransformState::default(), &PhysicsState::default()));
+        }
+
+        #[test]
+        fn acoustic_fuze_triggers_on_speed() {
+            let fuze = MineFuze::Acoustic { threshold: 5.0 };
+            let transform = TransformState::default();
+            let mut physics = PhysicsState {
+                velocity: Vec2::new(3.0, 0.0),
+                ..PhysicsState::default()
+            };
+            assert!(!fuze.triggered_by(&transform, &physics));
+            physics.velocity = Vec2::new(3.0, 4.0);
+            assert!(fuze.triggered_by(&transform, &physics));
+        }
+
+        #[test]
+        fn magnetic_fuze_triggers_on_hull_radius() {
+            let fuze = MineFuze::Magnetic { threshold: 20.0 };
+            let physics = PhysicsState::default();
+            assert!(!fuze.triggered_by(&TransformState::default().with_radius(10.0), &physics));
+            assert!(fuze.triggered_by(&TransformState::default().with_radius(25.0), &physics));
+        }
+
+        #[test]
+        fn builders_set_fields() {
+            let mine = MineState::new(MineFuze::Acoustic { threshold: 2.0 })
+                .with_trigger_radius(30.0)
+                .with_warhead(120.0, 40.0);
+            assert_eq!(mine.fuze, MineFuze::Acoustic { threshold: 2.0 });
+            assert!((mine.trigger_radius - 30.0).abs() < 1e-6);
+            assert!((mine.damage - 120.0).abs() < 1e-6);
+            assert!((mine.blast_radius - 40.0).abs() < 1e-6);
+            assert!(!mine.detected);
+        }
+
+        #[test]
+        fn serialization_roundtrip() {
+            let mine = MineState::new(MineFuze::Magnetic { threshold: 15.0 });
+            let json = serde_json::to_string(&mine).unwrap();
+            let deserialized: MineState = serde_json::from_str(&json).unwrap();
+            assert_eq!(mine, deserialized);
+        }
     }
 
     mod projectile_components_tests {
@@ -1634,6 +3560,16 @@ mod tests {
             assert!(!weapon.is_ready());
         }
 
+        #[test]
+        fn cooldown_fraction() {
+            let mut weapon = WeaponState::new(0, 4.0, AmmoType::Shell);
+            assert_eq!(weapon.cooldown_fraction(), 0.0);
+            weapon.cooldown = 1.0;
+            assert!((weapon.cooldown_fraction() - 0.25).abs() < 1e-6);
+            weapon.max_cooldown = 0.0;
+            assert_eq!(weapon.cooldown_fraction(), 0.0);
+        }
+
         #[test]
         fn serialization_roundtrip() {
             let weapon = WeaponState::new(1, 3.0, AmmoType::Torpedo);
@@ -1677,10 +3613,45 @@ mod tests {
             track.velocity = Some(Vec2::new(10.0, 5.0));
             track.age = 2.5;
             track.classification_confidence = 0.8;
+            track.classified_as = Some(EntityTag::Ship);
 
             let json = serde_json::to_string(&track).unwrap();
             let deserialized: Track = serde_json::from_str(&json).unwrap();
             assert_eq!(track, deserialized);
         }
+
+        #[test]
+        fn history_keeps_the_last_fixes() {
+            let mut track = Track::new(EntityId::new(1), Vec2::ZERO, TrackQuality::Coarse);
+            for i in 0..12u8 {
+                let t = f32::from(i);
+                track.record_fix(t, Vec2::new(10.0 * t, 0.0));
+            }
+            assert_eq!(track.history.len(), Track::HISTORY_LEN);
+            assert_eq!(track.history[0].time, 4.0);
+            assert_eq!(track.position, Vec2::new(110.0, 0.0));
+            assert!(track.velocity.unwrap().distance(Vec2::new(10.0, 0.0)) < 1e-3);
+        }
+
+        #[test]
+        fn estimate_reports_course_speed_and_uncertainty() {
+            let mut track = Track::new(EntityId::new(1), Vec2::ZERO, TrackQuality::Coarse);
+            track.record_fix(0.0, Vec2::ZERO);
+            track.record_fix(1.0, Vec2::new(0.0, 5.0));
+            assert!(track.estimate().is_none());
+
+            track.record_fix(2.0, Vec2::new(0.0, 10.0));
+            let exact = track.estimate().unwrap();
+            assert!((exact.course - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+            assert!((exact.speed - 5.0).abs() < 1e-4);
+            assert!(exact.speed_sigma < 1e-4);
+
+            // A noisy fix leaves the estimate less certain
+            track.record_fix(3.0, Vec2::new(3.0, 15.0));
+            let noisy = track.estimate().unwrap();
+            assert_eq!(noisy.fixes, 4);
+            assert!(noisy.speed_sigma > 0.1);
+            assert!(noisy.course_sigma > 0.01);
+        }
     }
 }
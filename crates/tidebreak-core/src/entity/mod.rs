@@ -2,6 +2,8 @@
 //!
 //! This module provides the core entity types for Tidebreak's combat simulation:
 //! - [`EntityId`]: Unique identifier for entities
+//! - [`TeamId`]: Side/faction membership used for friend-or-foe checks
+//! - [`ControllerId`]: Player or agent allowed to command an entity
 //! - [`EntityTag`]: Type classification for plugin bundle selection
 //! - [`EntityInner`]: Type-safe storage for entity-specific components
 //! - [`Entity`]: The complete entity container
@@ -32,13 +34,22 @@
 pub mod components;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::fmt;
 
 pub use components::{
     // Supporting types
+    AirWing,
     AmmoType,
+    AttributeValue,
+    Attributes,
+    Cargo,
     CombatState,
+    CraftType,
+    DamageType,
     EmissionsMode,
+    EngineProfile,
+    GunBallistics,
     HasCombat,
     HasInventory,
     HasPhysics,
@@ -46,16 +57,27 @@ pub use components::{
     // Access traits
     HasTransform,
     InventoryState,
+    MineFuze,
+    MineState,
+    MitigationState,
     PhysicsState,
     // Composite component structs
     PlatformComponents,
     ProjectileComponents,
+    Resistances,
+    SearchPattern,
+    SeekerState,
     SensorState,
     ShipComponents,
+    SignatureState,
     SquadronComponents,
     StatId,
     StatusFlags,
+    SubmarineState,
+    Subsystem,
     Track,
+    TrackEstimate,
+    TrackFix,
     TrackQuality,
     // Core state components
     TransformState,
@@ -103,8 +125,50 @@ impl EntityId {
     pub const fn as_u64(self) -> u64 {
         self.0
     }
+
+    /// Creates an ID in a producer namespace.
+    ///
+    /// The namespace occupies the high [`NAMESPACE_BITS`] bits and `local`
+    /// the rest (truncated to fit), so IDs generated by different producers
+    /// never collide when their content is merged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::entity::EntityId;
+    ///
+    /// let id = EntityId::namespaced(3, 7);
+    /// assert_eq!(id.namespace(), 3);
+    /// assert_eq!(id.local(), 7);
+    /// assert_eq!(EntityId::namespaced(0, 7), EntityId::new(7));
+    /// ```
+    #[must_use]
+    pub const fn namespaced(namespace: u16, local: u64) -> Self {
+        Self(((namespace as u64) << LOCAL_ID_BITS) | (local & LOCAL_ID_MASK))
+    }
+
+    /// Returns the producer namespace (high bits) of this identifier.
+    #[must_use]
+    pub const fn namespace(self) -> u16 {
+        (self.0 >> LOCAL_ID_BITS) as u16
+    }
+
+    /// Returns the ID within its namespace (low bits).
+    #[must_use]
+    pub const fn local(self) -> u64 {
+        self.0 & LOCAL_ID_MASK
+    }
 }
 
+/// Number of high `EntityId` bits reserved for the producer namespace.
+pub const NAMESPACE_BITS: u32 = 16;
+
+/// Number of low `EntityId` bits available within a namespace.
+const LOCAL_ID_BITS: u32 = u64::BITS - NAMESPACE_BITS;
+
+/// Mask selecting the local part of an `EntityId`.
+const LOCAL_ID_MASK: u64 = (1 << LOCAL_ID_BITS) - 1;
+
 impl fmt::Debug for EntityId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "EntityId({})", self.0)
@@ -129,6 +193,87 @@ impl From<EntityId> for u64 {
     }
 }
 
+/// Team (side) identifier.
+///
+/// Entities sharing a `TeamId` are considered friendly to each other. Entities
+/// without a team are neutral and are never treated as friendly.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::entity::TeamId;
+///
+/// let blue = TeamId::new(0);
+/// let red = TeamId::new(1);
+///
+/// assert_ne!(blue, red);
+/// assert_eq!(red.as_u32(), 1);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct TeamId(u32);
+
+impl TeamId {
+    /// Creates a new `TeamId` from a raw `u32` value.
+    #[must_use]
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw `u32` value of this identifier.
+    #[must_use]
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for TeamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u32> for TeamId {
+    fn from(id: u32) -> Self {
+        Self::new(id)
+    }
+}
+
+/// Who controls an entity: a human player, a scripted AI or a learning agent.
+///
+/// Control is independent of [`TeamId`]: one team may be split between
+/// several controllers (e.g. a human flagship escorted by agents), and
+/// control can be handed over mid-episode. An entity without a controller
+/// accepts commands only from callers that do not act as a controller.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::entity::ControllerId;
+///
+/// let player = ControllerId::Human(0);
+/// assert_ne!(player, ControllerId::Agent(0));
+/// assert_eq!(ControllerId::Agent(3).to_string(), "agent:3");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ControllerId {
+    /// Human player, by seat
+    Human(u32),
+    /// Scripted (rule-based) controller
+    Scripted(u32),
+    /// Learning agent, by agent index
+    Agent(u32),
+}
+
+impl fmt::Display for ControllerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Human(id) => write!(f, "human:{id}"),
+            Self::Scripted(id) => write!(f, "scripted:{id}"),
+            Self::Agent(id) => write!(f, "agent:{id}"),
+        }
+    }
+}
+
 /// Entity type tag for plugin bundle selection.
 ///
 /// `EntityTag` determines which plugins are eligible to run on an entity.
@@ -282,6 +427,48 @@ impl EntityInner {
     }
 }
 
+/// Cosmetic description of an entity for viewers and logs.
+///
+/// Metadata never affects the simulation; every field is optional.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::entity::{Entity, EntityId, EntityMetadata};
+///
+/// let ship = Entity::new_ship(EntityId::new(7)).with_metadata(EntityMetadata {
+///     name: Some("Resolute".into()),
+///     hull_number: Some("D32".into()),
+///     ..EntityMetadata::default()
+/// });
+///
+/// assert_eq!(ship.display_name(), "Resolute (D32)");
+/// assert_eq!(Entity::new_ship(EntityId::new(8)).display_name(), "Ship 8");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EntityMetadata {
+    /// Display name, e.g. "Resolute"
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Class label, e.g. "Daring-class destroyer"
+    #[serde(default)]
+    pub class_name: Option<String>,
+    /// Hull number or pennant, e.g. "D32"
+    #[serde(default)]
+    pub hull_number: Option<String>,
+    /// Display color as RGB, typically the faction's
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+}
+
+impl EntityMetadata {
+    /// Returns `true` if no field is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// A complete entity in the combat simulation.
 ///
 /// An `Entity` combines:
@@ -314,6 +501,16 @@ pub struct Entity {
     id: EntityId,
     tag: EntityTag,
     inner: EntityInner,
+    #[serde(default)]
+    team: Option<TeamId>,
+    #[serde(default)]
+    controller: Option<ControllerId>,
+    #[serde(default)]
+    attributes: Attributes,
+    #[serde(default)]
+    labels: BTreeSet<String>,
+    #[serde(default)]
+    metadata: EntityMetadata,
 }
 
 impl Entity {
@@ -331,7 +528,42 @@ impl Entity {
     /// (e.g., `EntityTag::Ship` with `EntityInner::Ship(_)`).
     #[must_use]
     pub const fn new(id: EntityId, tag: EntityTag, inner: EntityInner) -> Self {
-        Self { id, tag, inner }
+        Self {
+            id,
+            tag,
+            inner,
+            team: None,
+            controller: None,
+            attributes: Attributes::new(),
+            labels: BTreeSet::new(),
+            metadata: EntityMetadata {
+                name: None,
+                class_name: None,
+                hull_number: None,
+                color: None,
+            },
+        }
+    }
+
+    /// Returns this entity with the given team assigned.
+    #[must_use]
+    pub const fn with_team(mut self, team: TeamId) -> Self {
+        self.team = Some(team);
+        self
+    }
+
+    /// Returns this entity under the given controller.
+    #[must_use]
+    pub const fn with_controller(mut self, controller: ControllerId) -> Self {
+        self.controller = Some(controller);
+        self
+    }
+
+    /// Returns this entity with the given metadata.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: EntityMetadata) -> Self {
+        self.metadata = metadata;
+        self
     }
 
     /// Creates a new ship entity with default components.
@@ -414,6 +646,109 @@ impl Entity {
         &mut self.inner
     }
 
+    /// Returns the entity's team, if it belongs to one.
+    #[must_use]
+    pub const fn team(&self) -> Option<TeamId> {
+        self.team
+    }
+
+    /// Assigns (or clears) the entity's team.
+    pub fn set_team(&mut self, team: Option<TeamId>) {
+        self.team = team;
+    }
+
+    /// Returns the entity's controller, if it has one.
+    #[must_use]
+    pub const fn controller(&self) -> Option<ControllerId> {
+        self.controller
+    }
+
+    /// Assigns (or clears) the entity's controller.
+    pub fn set_controller(&mut self, controller: Option<ControllerId>) {
+        self.controller = controller;
+    }
+
+    /// Returns the entity's attribute store.
+    #[must_use]
+    pub const fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+
+    /// Returns an attribute value by key.
+    #[must_use]
+    pub fn attribute(&self, key: &str) -> Option<&AttributeValue> {
+        self.attributes.get(key)
+    }
+
+    /// Sets an attribute, returning the previous value.
+    pub fn set_attribute(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<AttributeValue>,
+    ) -> Option<AttributeValue> {
+        self.attributes.insert(key.into(), value.into())
+    }
+
+    /// Removes an attribute, returning its value.
+    pub fn remove_attribute(&mut self, key: &str) -> Option<AttributeValue> {
+        self.attributes.remove(key)
+    }
+
+    /// Returns the entity's labels, in sorted order.
+    #[must_use]
+    pub const fn labels(&self) -> &BTreeSet<String> {
+        &self.labels
+    }
+
+    /// Returns `true` if the entity carries the label.
+    #[must_use]
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels.contains(label)
+    }
+
+    /// Adds a label. Returns `true` if it was not already present.
+    pub fn add_label(&mut self, label: impl Into<String>) -> bool {
+        self.labels.insert(label.into())
+    }
+
+    /// Removes a label. Returns `true` if it was present.
+    pub fn remove_label(&mut self, label: &str) -> bool {
+        self.labels.remove(label)
+    }
+
+    /// Returns the entity's cosmetic metadata.
+    #[must_use]
+    pub const fn metadata(&self) -> &EntityMetadata {
+        &self.metadata
+    }
+
+    /// Replaces the entity's cosmetic metadata.
+    pub fn set_metadata(&mut self, metadata: EntityMetadata) {
+        self.metadata = metadata;
+    }
+
+    /// Returns a human-readable name for viewers and logs.
+    ///
+    /// This is the metadata name followed by the hull number in
+    /// parentheses, whichever are set, or else the tag and ID.
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        match (&self.metadata.name, &self.metadata.hull_number) {
+            (Some(name), Some(hull)) => format!("{name} ({hull})"),
+            (Some(name), None) => name.clone(),
+            (None, Some(hull)) => hull.clone(),
+            (None, None) => format!("{} {}", self.tag, self.id),
+        }
+    }
+
+    /// Returns `true` if both entities belong to the same team.
+    ///
+    /// Entities without a team are never friendly, not even to each other.
+    #[must_use]
+    pub fn is_friendly_to(&self, other: &Self) -> bool {
+        self.team.is_some() && self.team == other.team
+    }
+
     /// Returns `true` if this entity is a ship.
     #[must_use]
     pub const fn is_ship(&self) -> bool {
@@ -438,6 +773,18 @@ impl Entity {
         matches!(self.tag, EntityTag::Squadron)
     }
 
+    /// Returns `true` if this entity has combat state and it has been destroyed.
+    ///
+    /// Platforms and projectiles carry no combat state and are never destroyed.
+    #[must_use]
+    pub fn is_destroyed(&self) -> bool {
+        match &self.inner {
+            EntityInner::Ship(c) => c.combat.is_destroyed(),
+            EntityInner::Squadron(c) => c.combat.is_destroyed(),
+            EntityInner::Platform(_) | EntityInner::Projectile(_) => false,
+        }
+    }
+
     /// Returns the ship components if this is a ship, `None` otherwise.
     #[must_use]
     pub const fn as_ship(&self) -> Option<&ShipComponents> {
@@ -462,6 +809,39 @@ impl Entity {
         self.inner.as_platform_mut()
     }
 
+    /// Returns the mine state if this is a mine, `None` otherwise.
+    #[must_use]
+    pub fn mine(&self) -> Option<&MineState> {
+        self.as_platform()?.mine.as_ref()
+    }
+
+    /// Returns the diving state if this is a submarine, `None` otherwise.
+    #[must_use]
+    pub fn submarine(&self) -> Option<&SubmarineState> {
+        self.as_ship()?.submarine.as_ref()
+    }
+
+    /// Returns the supplies held by the entity: a ship's inventory or a
+    /// depot platform's stockpile.
+    #[must_use]
+    pub fn inventory(&self) -> Option<&InventoryState> {
+        match &self.inner {
+            EntityInner::Ship(c) => Some(&c.inventory),
+            EntityInner::Platform(c) => c.stockpile.as_ref(),
+            EntityInner::Projectile(_) | EntityInner::Squadron(_) => None,
+        }
+    }
+
+    /// Returns mutable supplies held by the entity (see [`inventory`](Self::inventory)).
+    #[must_use]
+    pub fn inventory_mut(&mut self) -> Option<&mut InventoryState> {
+        match &mut self.inner {
+            EntityInner::Ship(c) => Some(&mut c.inventory),
+            EntityInner::Platform(c) => c.stockpile.as_mut(),
+            EntityInner::Projectile(_) | EntityInner::Squadron(_) => None,
+        }
+    }
+
     /// Returns the projectile components if this is a projectile, `None` otherwise.
     #[must_use]
     pub const fn as_projectile(&self) -> Option<&ProjectileComponents> {
@@ -500,6 +880,21 @@ mod tests {
             assert_eq!(id.as_u64(), 42);
         }
 
+        #[test]
+        fn namespaced_splits_high_and_low_bits() {
+            let id = EntityId::namespaced(0xBEEF, 12345);
+            assert_eq!(id.namespace(), 0xBEEF);
+            assert_eq!(id.local(), 12345);
+            assert!(EntityId::namespaced(1, 0) > EntityId::namespaced(0, u64::MAX));
+        }
+
+        #[test]
+        fn namespaced_truncates_local_overflow() {
+            let id = EntityId::namespaced(1, u64::MAX);
+            assert_eq!(id.namespace(), 1);
+            assert_eq!(id.local(), (1 << (64 - NAMESPACE_BITS)) - 1);
+        }
+
         #[test]
         fn copy_semantics() {
             let id1 = EntityId::new(1);
@@ -781,5 +1176,152 @@ mod tests {
             assert_eq!(entity1.id(), entity2.id());
             assert_eq!(entity1.tag(), entity2.tag());
         }
+
+        #[test]
+        fn team_defaults_to_none() {
+            let entity = Entity::new_ship(EntityId::new(1));
+            assert_eq!(entity.team(), None);
+        }
+
+        #[test]
+        fn friendliness_requires_shared_team() {
+            let a = Entity::new_ship(EntityId::new(1)).with_team(TeamId::new(0));
+            let b = Entity::new_ship(EntityId::new(2)).with_team(TeamId::new(0));
+            let c = Entity::new_ship(EntityId::new(3)).with_team(TeamId::new(1));
+            let neutral1 = Entity::new_ship(EntityId::new(4));
+            let neutral2 = Entity::new_ship(EntityId::new(5));
+
+            assert!(a.is_friendly_to(&b));
+            assert!(!a.is_friendly_to(&c));
+            assert!(!neutral1.is_friendly_to(&neutral2));
+        }
+
+        #[test]
+        fn controller_is_independent_of_team() {
+            let mut entity = Entity::new_ship(EntityId::new(1))
+                .with_team(TeamId::new(0))
+                .with_controller(ControllerId::Human(0));
+            assert_eq!(entity.controller(), Some(ControllerId::Human(0)));
+
+            entity.set_controller(Some(ControllerId::Agent(2)));
+            assert_eq!(entity.controller(), Some(ControllerId::Agent(2)));
+            assert_eq!(entity.team(), Some(TeamId::new(0)));
+
+            let json = serde_json::to_string(&entity).unwrap();
+            let deserialized: Entity = serde_json::from_str(&json).unwrap();
+            assert_eq!(deserialized.controller(), Some(ControllerId::Agent(2)));
+        }
+
+        #[test]
+        fn team_survives_serialization() {
+            let entity = Entity::new_ship(EntityId::new(7)).with_team(TeamId::new(3));
+            let json = serde_json::to_string(&entity).unwrap();
+            let deserialized: Entity = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(deserialized.team(), Some(TeamId::new(3)));
+        }
+
+        #[test]
+        fn labels_add_and_remove() {
+            let mut entity = Entity::new_ship(EntityId::new(1));
+
+            assert!(entity.add_label("convoy_1"));
+            assert!(!entity.add_label("convoy_1"));
+            entity.add_label("escort");
+
+            assert!(entity.has_label("convoy_1"));
+            assert_eq!(
+                entity.labels().iter().collect::<Vec<_>>(),
+                vec!["convoy_1", "escort"]
+            );
+            assert!(entity.remove_label("convoy_1"));
+            assert!(!entity.has_label("convoy_1"));
+        }
+
+        #[test]
+        fn labels_survive_serialization() {
+            let mut entity = Entity::new_ship(EntityId::new(7));
+            entity.add_label("convoy_1");
+            let json = serde_json::to_string(&entity).unwrap();
+            let deserialized: Entity = serde_json::from_str(&json).unwrap();
+
+            assert!(deserialized.has_label("convoy_1"));
+        }
+
+        #[test]
+        fn metadata_names_the_entity() {
+            let mut entity = Entity::new_ship(EntityId::new(3));
+            assert!(entity.metadata().is_empty());
+            assert_eq!(entity.display_name(), "Ship 3");
+
+            entity.set_metadata(EntityMetadata {
+                hull_number: Some("F81".into()),
+                color: Some([200, 30, 30]),
+                ..EntityMetadata::default()
+            });
+            assert_eq!(entity.display_name(), "F81");
+
+            let json = serde_json::to_string(&entity).unwrap();
+            let deserialized: Entity = serde_json::from_str(&json).unwrap();
+            assert_eq!(deserialized.metadata(), entity.metadata());
+        }
+
+        #[test]
+        fn attributes_default_to_empty() {
+            let entity = Entity::new_ship(EntityId::new(1));
+            assert!(entity.attributes().is_empty());
+            assert_eq!(entity.attribute("missing"), None);
+        }
+
+        #[test]
+        fn set_and_remove_attributes() {
+            let mut entity = Entity::new_ship(EntityId::new(1));
+
+            assert_eq!(entity.set_attribute("patrol.leg", 2_i64), None);
+            assert_eq!(
+                entity.set_attribute("patrol.leg", 3_i64),
+                Some(AttributeValue::Int(2))
+            );
+            entity.set_attribute("patrol.name", "north");
+
+            assert_eq!(
+                entity
+                    .attribute("patrol.leg")
+                    .and_then(AttributeValue::as_i64),
+                Some(3)
+            );
+            assert_eq!(
+                entity
+                    .attribute("patrol.name")
+                    .and_then(AttributeValue::as_str),
+                Some("north")
+            );
+            assert_eq!(
+                entity.remove_attribute("patrol.leg"),
+                Some(AttributeValue::Int(3))
+            );
+            assert_eq!(entity.attributes().len(), 1);
+        }
+
+        #[test]
+        fn attributes_survive_serialization() {
+            let mut entity = Entity::new_ship(EntityId::new(7));
+            entity.set_attribute("a", 1.5_f32);
+            entity.set_attribute("b", true);
+            let json = serde_json::to_string(&entity).unwrap();
+            let deserialized: Entity = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(deserialized.attributes(), entity.attributes());
+        }
+
+        #[test]
+        fn attributes_missing_from_json_default_to_empty() {
+            let entity = Entity::new_ship(EntityId::new(7));
+            let mut json: serde_json::Value = serde_json::to_value(&entity).unwrap();
+            json.as_object_mut().unwrap().remove("attributes");
+            let deserialized: Entity = serde_json::from_value(json).unwrap();
+
+            assert!(deserialized.attributes().is_empty());
+        }
     }
 }
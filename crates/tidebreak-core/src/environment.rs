@@ -0,0 +1,775 @@
+//! Visibility conditions: time of day, sea state and smoke; and solid
+//! obstacles.
+//!
+//! The [`Environment`] stored in the arena limits the visual detection
+//! channel of the `SensorPlugin`:
+//!
+//! - The [`WorldClock`] turns ticks into a date, a time of day and a phase
+//!   of the moon. Lookouts see furthest at noon and a fraction of that at
+//!   night, with a short twilight between; a full moon lights the night
+//!   enough to see further than on a moonless one.
+//! - Rough seas hide hulls in the swell, shortening visual range by a
+//!   fixed fraction per step of sea state.
+//! - A [`SmokeField`] snapshot of the murk `Smoke` field blocks lines of
+//!   sight through dense smoke.
+//! - Smoke screens laid by ships (see `Command::DeploySmoke`) add
+//!   [`SmokePuff`] clouds that block sight, and weapons will not engage
+//!   through them, until they disperse. The host copies them into the
+//!   universe with [`Environment::smoke_stamps`].
+//!
+//! An [`OccupancyField`] snapshot of the murk `Occupancy` field shelters
+//! entities behind solid cells from blasts (see
+//! `Modifier::ApplyAreaDamage`).
+//!
+//! The defaults (noon, calm, no smoke) leave visual range unchanged. Like
+//! currents, smoke is sampled by the host whenever the universe changes:
+//!
+//! ```
+//! use glam::Vec2;
+//! use tidebreak_core::environment::{SmokeField, WorldClock};
+//! use tidebreak_core::murk::Universe;
+//! use tidebreak_core::simulation::Simulation;
+//!
+//! let mut sim = Simulation::new(42);
+//! let universe = Universe::default();
+//!
+//! let environment = sim.arena_mut().environment_mut();
+//! environment.clock = WorldClock::starting_at(22.0);
+//! environment.sea_state = 4;
+//! environment.smoke = Some(SmokeField::sample(
+//!     &universe,
+//!     Vec2::new(-500.0, -500.0),
+//!     Vec2::new(500.0, 500.0),
+//!     50.0,
+//!     0.0,
+//! ));
+//! sim.step();
+//! ```
+
+use std::f32::consts::TAU;
+
+use glam::Vec2;
+use murk::{BlendOp, Field, FieldMod, Stamp, StampShape, Universe};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::EntityId;
+use crate::grid::SampleGrid;
+use crate::resolver::FIXED_DT;
+
+/// Seconds in a day.
+pub const SECONDS_PER_DAY: f32 = 86_400.0;
+
+/// Highest sea state (Douglas scale).
+pub const MAX_SEA_STATE: u8 = 9;
+
+/// Days from one new moon to the next.
+pub const SYNODIC_MONTH: f32 = 29.53;
+
+/// Part of the day, by how light it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DayPhase {
+    /// The sun is well below the horizon.
+    Night,
+    /// Morning twilight.
+    Dawn,
+    /// Full daylight.
+    Day,
+    /// Evening twilight.
+    Dusk,
+}
+
+/// Date, time and light at one tick, as reported in observations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockReading {
+    /// Days since the calendar's epoch
+    pub day: u64,
+    /// Hour of the day (0-24)
+    pub hour: f32,
+    /// Part of the day
+    pub phase: DayPhase,
+    /// Sunlight: 0 at night, 1 in daylight
+    pub daylight: f32,
+    /// Phase of the moon: 0 new, 0.5 full
+    pub moon_phase: f32,
+    /// Lit fraction of the moon's disc (0-1)
+    pub moon_illumination: f32,
+    /// Sunlight and moonlight together (0-1)
+    pub light: f32,
+}
+
+/// Date, time of day and phase of the moon, advancing with the simulation
+/// tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WorldClock {
+    /// Seconds after midnight at tick 0
+    pub start: f32,
+    /// Length of a day in seconds; shorten it to cycle faster
+    pub day_length: f32,
+    /// Day number at tick 0
+    #[serde(default)]
+    pub start_day: u64,
+    /// Phase of the moon at tick 0: 0 new, 0.5 full
+    #[serde(default)]
+    pub moon_phase: f32,
+}
+
+impl WorldClock {
+    /// Half-width of the twilight band, in units of solar elevation (-1 at
+    /// midnight to 1 at noon).
+    const TWILIGHT: f32 = 0.1;
+    /// Fraction of daylight given by a full moon.
+    pub const MOONLIGHT: f32 = 0.25;
+
+    /// Creates a clock starting at `hour` (0-24) on a normal day.
+    #[must_use]
+    pub fn starting_at(hour: f32) -> Self {
+        Self {
+            start: hour * 3600.0,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the days elapsed since the start of day 0 at `tick`, or
+    /// `None` if the clock is stopped.
+    fn days(&self, tick: u64) -> Option<f64> {
+        if self.day_length.is_nan() || self.day_length <= 0.0 {
+            return None;
+        }
+        // f64 keeps long runs from losing sub-second precision
+        #[allow(clippy::cast_precision_loss)]
+        let seconds = f64::from(self.start) + tick as f64 * f64::from(FIXED_DT);
+        Some(seconds / f64::from(self.day_length))
+    }
+
+    /// Returns the seconds after midnight at `tick`.
+    #[must_use]
+    pub fn time_of_day(&self, tick: u64) -> f32 {
+        #[allow(clippy::cast_possible_truncation)]
+        self.days(tick).map_or(self.start, |days| {
+            (days.rem_euclid(1.0) * f64::from(self.day_length)) as f32
+        })
+    }
+
+    /// Returns the day number at `tick`.
+    #[must_use]
+    pub fn day(&self, tick: u64) -> u64 {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let elapsed = self.days(tick).map_or(0, |days| days.max(0.0) as u64);
+        self.start_day + elapsed
+    }
+
+    /// Returns the hour (0-24) at `tick`, scaled to a 24-hour day.
+    #[must_use]
+    pub fn hour(&self, tick: u64) -> f32 {
+        if self.day_length.is_nan() || self.day_length <= 0.0 {
+            return self.start / 3600.0;
+        }
+        self.time_of_day(tick) / self.day_length * 24.0
+    }
+
+    /// Returns the height of the sun at `tick`: -1 at midnight, 0 at
+    /// sunrise and sunset, 1 at noon.
+    ///
+    /// Hosts can drive a diurnal surface temperature from it.
+    #[must_use]
+    pub fn solar_elevation(&self, tick: u64) -> f32 {
+        -(self.hour(tick) / 24.0 * TAU).cos()
+    }
+
+    /// Returns how light it is at `tick`: 0 at night, 1 in daylight.
+    #[must_use]
+    pub fn daylight(&self, tick: u64) -> f32 {
+        let elevation = self.solar_elevation(tick);
+        ((elevation + Self::TWILIGHT) / (2.0 * Self::TWILIGHT)).clamp(0.0, 1.0)
+    }
+
+    /// Returns the part of the day at `tick`.
+    #[must_use]
+    pub fn phase(&self, tick: u64) -> DayPhase {
+        let daylight = self.daylight(tick);
+        if daylight <= 0.0 {
+            DayPhase::Night
+        } else if daylight >= 1.0 {
+            DayPhase::Day
+        } else if self.hour(tick) < 12.0 {
+            DayPhase::Dawn
+        } else {
+            DayPhase::Dusk
+        }
+    }
+
+    /// Returns the phase of the moon at `tick`: 0 new, 0.5 full.
+    #[must_use]
+    pub fn moon_phase_at(&self, tick: u64) -> f32 {
+        let months = self.days(tick).unwrap_or(0.0) / f64::from(SYNODIC_MONTH);
+        #[allow(clippy::cast_possible_truncation)]
+        let phase = (f64::from(self.moon_phase) + months).rem_euclid(1.0) as f32;
+        phase
+    }
+
+    /// Returns the lit fraction of the moon's disc at `tick`.
+    #[must_use]
+    pub fn moon_illumination(&self, tick: u64) -> f32 {
+        (1.0 - (self.moon_phase_at(tick) * TAU).cos()) / 2.0
+    }
+
+    /// Returns how light it is at `tick` from sun and moon together: 0 on
+    /// a moonless night, [`Self::MOONLIGHT`] under a full moon, 1 in
+    /// daylight.
+    #[must_use]
+    pub fn light(&self, tick: u64) -> f32 {
+        let daylight = self.daylight(tick);
+        daylight + (1.0 - daylight) * Self::MOONLIGHT * self.moon_illumination(tick)
+    }
+
+    /// Returns the date, time and light at `tick`.
+    #[must_use]
+    pub fn reading(&self, tick: u64) -> ClockReading {
+        ClockReading {
+            day: self.day(tick),
+            hour: self.hour(tick),
+            phase: self.phase(tick),
+            daylight: self.daylight(tick),
+            moon_phase: self.moon_phase_at(tick),
+            moon_illumination: self.moon_illumination(tick),
+            light: self.light(tick),
+        }
+    }
+}
+
+impl Default for WorldClock {
+    /// Noon on day 0 under a new moon.
+    fn default() -> Self {
+        Self {
+            start: 12.0 * 3600.0,
+            day_length: SECONDS_PER_DAY,
+            start_day: 0,
+            moon_phase: 0.0,
+        }
+    }
+}
+
+/// Grid snapshot of smoke density (0-1).
+///
+/// Lookups between samples are bilinear; positions outside the sampled
+/// area use the nearest edge sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmokeField {
+    /// Sampled densities
+    grid: SampleGrid<f32>,
+}
+
+impl SmokeField {
+    /// Fraction of light absorbed per meter of full-density smoke.
+    pub const EXTINCTION: f32 = 0.02;
+    /// Spacing of the samples taken along a line of sight, in meters.
+    const LINE_STEP: f32 = 25.0;
+    /// Largest number of samples taken along a line of sight.
+    const MAX_LINE_SAMPLES: usize = 64;
+
+    /// Creates a field with the same density everywhere.
+    #[must_use]
+    pub fn uniform(density: f32) -> Self {
+        Self {
+            grid: SampleGrid::uniform(density),
+        }
+    }
+
+    /// Samples `Smoke` from `universe` over the rectangle `[min, max]` at
+    /// altitude `z`.
+    ///
+    /// Samples are at most `spacing` apart (and at most
+    /// [`MAX_SAMPLES_PER_AXIS`](crate::currents::MAX_SAMPLES_PER_AXIS) per
+    /// axis), including both edges.
+    #[must_use]
+    pub fn sample(universe: &Universe, min: Vec2, max: Vec2, spacing: f32, z: f32) -> Self {
+        Self {
+            grid: SampleGrid::sample(universe, min, max, spacing, z, |point| {
+                point.get(Field::Smoke)
+            }),
+        }
+    }
+
+    /// Returns the smoke density at `position`, from 0 (clear) to 1.
+    ///
+    /// A malformed field (for example, a truncated snapshot) is clear.
+    #[must_use]
+    pub fn density_at(&self, position: Vec2) -> f32 {
+        let density = self.grid.at(position).unwrap_or(0.0);
+        if density.is_nan() {
+            0.0
+        } else {
+            density.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Returns the fraction of light passing from `from` to `to`, from 0
+    /// (opaque) to 1 (clear).
+    #[must_use]
+    pub fn transmission(&self, from: Vec2, to: Vec2) -> f32 {
+        let distance = from.distance(to);
+        if !distance.is_finite() || distance <= 0.0 {
+            return 1.0;
+        }
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let samples =
+            ((distance / Self::LINE_STEP).ceil() as usize).clamp(1, Self::MAX_LINE_SAMPLES);
+        #[allow(clippy::cast_precision_loss)]
+        let segment = distance / samples as f32;
+        #[allow(clippy::cast_precision_loss)]
+        let optical_depth: f32 = (0..samples)
+            .map(|i| {
+                let t = (i as f32 + 0.5) / samples as f32;
+                self.density_at(from.lerp(to, t)) * segment
+            })
+            .sum();
+        (-Self::EXTINCTION * optical_depth).exp()
+    }
+}
+
+/// Grid snapshot of occupancy (0 = open water, 1 = solid).
+///
+/// Lookups between samples are bilinear; positions outside the sampled
+/// area use the nearest edge sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OccupancyField {
+    /// Sampled occupancy
+    grid: SampleGrid<f32>,
+}
+
+impl OccupancyField {
+    /// Occupancy at which a cell blocks a blast.
+    pub const BLOCKING: f32 = 0.8;
+    /// Spacing of the samples taken along a line, in meters.
+    const LINE_STEP: f32 = 10.0;
+    /// Largest number of samples taken along a line.
+    const MAX_LINE_SAMPLES: usize = 64;
+
+    /// Creates a field with the same occupancy everywhere.
+    #[must_use]
+    pub fn uniform(occupancy: f32) -> Self {
+        Self {
+            grid: SampleGrid::uniform(occupancy),
+        }
+    }
+
+    /// Samples `Occupancy` from `universe` over the rectangle `[min, max]`
+    /// at altitude `z`.
+    ///
+    /// Samples are at most `spacing` apart (and at most
+    /// [`MAX_SAMPLES_PER_AXIS`](crate::currents::MAX_SAMPLES_PER_AXIS) per
+    /// axis), including both edges.
+    #[must_use]
+    pub fn sample(universe: &Universe, min: Vec2, max: Vec2, spacing: f32, z: f32) -> Self {
+        Self {
+            grid: SampleGrid::sample(universe, min, max, spacing, z, |point| {
+                point.get(Field::Occupancy)
+            }),
+        }
+    }
+
+    /// Returns the occupancy at `position`, from 0 (open) to 1 (solid).
+    ///
+    /// A malformed field (for example, a truncated snapshot) is open.
+    #[must_use]
+    pub fn occupancy_at(&self, position: Vec2) -> f32 {
+        let occupancy = self.grid.at(position).unwrap_or(0.0);
+        if occupancy.is_nan() {
+            0.0
+        } else {
+            occupancy.clamp(0.0, 1.0)
+        }
+    }
+
+    /// Returns `true` if a blocking cell lies strictly between `from` and
+    /// `to`.
+    #[must_use]
+    pub fn is_blocked(&self, from: Vec2, to: Vec2) -> bool {
+        let distance = from.distance(to);
+        if !distance.is_finite() || distance <= 0.0 {
+            return false;
+        }
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let samples =
+            ((distance / Self::LINE_STEP).ceil() as usize).clamp(1, Self::MAX_LINE_SAMPLES);
+        #[allow(clippy::cast_precision_loss)]
+        (0..samples).any(|i| {
+            let t = (i as f32 + 0.5) / samples as f32;
+            self.occupancy_at(from.lerp(to, t)) >= Self::BLOCKING
+        })
+    }
+}
+
+/// A cloud of smoke laid by a ship, uniformly dense until it disperses.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SmokePuff {
+    /// Center of the cloud
+    pub position: Vec2,
+    /// Radius of the cloud in meters
+    pub radius: f32,
+    /// Smoke density inside the cloud (0-1)
+    pub density: f32,
+    /// Tick at which the cloud has dispersed
+    pub expires: u64,
+}
+
+impl SmokePuff {
+    /// Radius of a freshly laid cloud, in meters.
+    pub const RADIUS: f32 = 60.0;
+    /// Density of a freshly laid cloud.
+    pub const DENSITY: f32 = 1.0;
+    /// Ticks a cloud lasts (two minutes).
+    pub const LIFETIME: u64 = 7200;
+
+    /// Creates a standard cloud at `position` laid at `tick`.
+    #[must_use]
+    pub const fn new(position: Vec2, tick: u64) -> Self {
+        Self {
+            position,
+            radius: Self::RADIUS,
+            density: Self::DENSITY,
+            expires: tick.saturating_add(Self::LIFETIME),
+        }
+    }
+
+    /// Returns the density times the length of the segment `from`-`to`
+    /// inside the cloud.
+    #[must_use]
+    pub fn optical_depth(&self, from: Vec2, to: Vec2) -> f32 {
+        let span = to - from;
+        let length = span.length();
+        if !length.is_finite() || length <= 0.0 {
+            return 0.0;
+        }
+        // Chord of the line through the cloud, clipped to the segment
+        let along = (self.position - from).dot(span / length);
+        let miss = (from + span / length * along).distance(self.position);
+        if miss >= self.radius {
+            return 0.0;
+        }
+        let half_chord = (self.radius * self.radius - miss * miss).sqrt();
+        let inside = ((along + half_chord).min(length) - (along - half_chord).max(0.0)).max(0.0);
+        self.density.clamp(0.0, 1.0) * inside
+    }
+
+    /// Returns the stamp writing this cloud into the `Smoke` field at
+    /// altitude `z`.
+    #[must_use]
+    pub fn stamp(&self, z: f32) -> Stamp {
+        Stamp::new(
+            StampShape::sphere(self.position.extend(z), self.radius),
+            vec![FieldMod::new(Field::Smoke, BlendOp::Max, self.density)],
+        )
+    }
+}
+
+/// A ship laying a smoke screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmokeEmitter {
+    /// Ship laying the smoke
+    pub source: EntityId,
+    /// Ticks of smoke left to lay
+    pub remaining: u32,
+    /// Ticks of smoke laid so far
+    pub elapsed: u32,
+}
+
+impl SmokeEmitter {
+    /// Ticks between the clouds an emitter lays.
+    pub const PUFF_INTERVAL: u32 = 30;
+
+    /// Creates an emitter laying smoke behind `source` for `duration` ticks.
+    #[must_use]
+    pub const fn new(source: EntityId, duration: u32) -> Self {
+        Self {
+            source,
+            remaining: duration,
+            elapsed: 0,
+        }
+    }
+}
+
+/// Weather, light and obscurants affecting visual detection, and obstacles
+/// sheltering from blasts.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Environment {
+    /// Time of day
+    pub clock: WorldClock,
+    /// Sea state on the Douglas scale (0 = calm, 9 = phenomenal)
+    pub sea_state: u8,
+    /// Smoke snapshot, if any
+    pub smoke: Option<SmokeField>,
+    /// Smoke clouds laid by ships, oldest first
+    #[serde(default)]
+    pub screens: Vec<SmokePuff>,
+    /// Ships currently laying smoke, in deployment order
+    #[serde(default)]
+    pub emitters: Vec<SmokeEmitter>,
+    /// Occupancy snapshot, if any
+    #[serde(default)]
+    pub occupancy: Option<OccupancyField>,
+}
+
+impl Environment {
+    /// Fraction of daylight visual range left on a dark night.
+    pub const NIGHT_VISIBILITY: f32 = 0.2;
+    /// Fraction of visual range lost per step of sea state.
+    pub const SEA_STATE_PENALTY: f32 = 0.06;
+    /// Least transmission through smoke at which a target can be seen.
+    pub const MIN_TRANSMISSION: f32 = 0.1;
+
+    /// Returns the multiplier on visual range from light and sea state at
+    /// `tick`.
+    #[must_use]
+    pub fn visual_range_factor(&self, tick: u64) -> f32 {
+        let light = self.clock.light(tick);
+        let sea = f32::from(self.sea_state.min(MAX_SEA_STATE));
+        (Self::NIGHT_VISIBILITY + (1.0 - Self::NIGHT_VISIBILITY) * light)
+            * (1.0 - Self::SEA_STATE_PENALTY * sea)
+    }
+
+    /// Returns the fraction of light passing from `from` to `to` through
+    /// smoke.
+    #[must_use]
+    pub fn transmission(&self, from: Vec2, to: Vec2) -> f32 {
+        let field = self
+            .smoke
+            .as_ref()
+            .map_or(1.0, |smoke| smoke.transmission(from, to));
+        let screened: f32 = self
+            .screens
+            .iter()
+            .map(|puff| puff.optical_depth(from, to))
+            .sum();
+        field * (-SmokeField::EXTINCTION * screened).exp()
+    }
+
+    /// Returns `true` if smoke hides `to` from `from`.
+    #[must_use]
+    pub fn is_screened(&self, from: Vec2, to: Vec2) -> bool {
+        self.transmission(from, to) < Self::MIN_TRANSMISSION
+    }
+
+    /// Returns `true` if solid obstacles shelter `to` from a blast at
+    /// `from`.
+    #[must_use]
+    pub fn is_occluded(&self, from: Vec2, to: Vec2) -> bool {
+        self.occupancy
+            .as_ref()
+            .is_some_and(|occupancy| occupancy.is_blocked(from, to))
+    }
+
+    /// Returns the stamps writing every smoke cloud into the universe at
+    /// altitude `z`.
+    ///
+    /// Stamps only raise the `Smoke` field, so applying them again after
+    /// each step is harmless.
+    #[must_use]
+    pub fn smoke_stamps(&self, z: f32) -> Vec<Stamp> {
+        self.screens.iter().map(|puff| puff.stamp(z)).collect()
+    }
+
+    /// Returns `true` if a lookout at `from` with daylight range
+    /// `visual_range` can see a target at `to` at `tick`.
+    #[must_use]
+    pub fn can_see(&self, tick: u64, from: Vec2, to: Vec2, visual_range: f32) -> bool {
+        from.distance(to) <= visual_range * self.visual_range_factor(tick)
+            && !self.is_screened(from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ticks in one hour of simulated time.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    const HOUR: u64 = (3600.0 / FIXED_DT) as u64;
+
+    mod clock_tests {
+        use super::*;
+
+        #[test]
+        fn default_clock_starts_at_noon() {
+            let clock = WorldClock::default();
+            assert_eq!(clock.hour(0), 12.0);
+            assert_eq!(clock.daylight(0), 1.0);
+        }
+
+        #[test]
+        fn clock_wraps_at_midnight() {
+            let clock = WorldClock::starting_at(23.0);
+            assert!((clock.hour(2 * HOUR) - 1.0).abs() < 1e-3);
+        }
+
+        #[test]
+        fn night_is_dark_and_dawn_is_twilight() {
+            assert_eq!(WorldClock::starting_at(0.0).daylight(0), 0.0);
+            let dawn = WorldClock::starting_at(6.0).daylight(0);
+            assert!(dawn > 0.0 && dawn < 1.0);
+        }
+
+        #[test]
+        fn short_days_cycle_faster() {
+            let clock = WorldClock {
+                start: 0.0,
+                day_length: 60.0,
+                ..WorldClock::default()
+            };
+            // 30 seconds into a one-minute day is noon
+            assert!((clock.hour(1800) - 12.0).abs() < 1e-3);
+        }
+
+        #[test]
+        fn days_and_phases_advance() {
+            let clock = WorldClock {
+                start_day: 100,
+                ..WorldClock::starting_at(22.0)
+            };
+            assert_eq!(clock.day(0), 100);
+            assert_eq!(clock.phase(0), DayPhase::Night);
+            assert_eq!(clock.day(3 * HOUR), 101);
+            assert_eq!(clock.phase(8 * HOUR), DayPhase::Dawn);
+            assert_eq!(clock.phase(14 * HOUR), DayPhase::Day);
+            assert_eq!(clock.phase(20 * HOUR), DayPhase::Dusk);
+        }
+
+        #[test]
+        fn full_moon_lights_the_night() {
+            let new_moon = WorldClock::starting_at(0.0);
+            let full_moon = WorldClock {
+                moon_phase: 0.5,
+                ..new_moon
+            };
+            assert_eq!(new_moon.light(0), 0.0);
+            assert!((full_moon.light(0) - WorldClock::MOONLIGHT).abs() < 1e-6);
+            // A quarter of a month later the moon is half lit
+            let week = 24 * HOUR * 7 + 9 * HOUR;
+            let quarter = full_moon.moon_illumination(week);
+            assert!((quarter - 0.5).abs() < 0.05, "illumination {quarter}");
+
+            let environment = Environment {
+                clock: full_moon,
+                ..Environment::default()
+            };
+            let dark = Environment {
+                clock: new_moon,
+                ..Environment::default()
+            };
+            assert!(environment.visual_range_factor(0) > dark.visual_range_factor(0));
+        }
+    }
+
+    mod smoke_tests {
+        use super::*;
+
+        #[test]
+        fn clear_air_transmits_everything() {
+            let smoke = SmokeField::uniform(0.0);
+            assert_eq!(smoke.transmission(Vec2::ZERO, Vec2::new(1000.0, 0.0)), 1.0);
+        }
+
+        #[test]
+        fn dense_smoke_blocks_sight() {
+            let smoke = SmokeField::uniform(1.0);
+            let through = smoke.transmission(Vec2::ZERO, Vec2::new(200.0, 0.0));
+            assert!((through - (-4.0f32).exp()).abs() < 1e-4);
+            assert_eq!(smoke.transmission(Vec2::ZERO, Vec2::ZERO), 1.0);
+        }
+
+        #[test]
+        fn puff_blocks_lines_through_it() {
+            let puff = SmokePuff::new(Vec2::new(100.0, 0.0), 0);
+            let through = puff.optical_depth(Vec2::ZERO, Vec2::new(200.0, 0.0));
+            assert!((through - 2.0 * SmokePuff::RADIUS).abs() < 1e-3);
+            // Ending inside the cloud only counts the part travelled
+            let into = puff.optical_depth(Vec2::ZERO, Vec2::new(100.0, 0.0));
+            assert!((into - SmokePuff::RADIUS).abs() < 1e-3);
+            assert_eq!(puff.optical_depth(Vec2::ZERO, Vec2::new(0.0, 200.0)), 0.0);
+            assert_eq!(puff.optical_depth(Vec2::ZERO, Vec2::new(20.0, 0.0)), 0.0);
+        }
+
+        #[test]
+        fn density_is_clamped() {
+            assert_eq!(SmokeField::uniform(3.0).density_at(Vec2::ZERO), 1.0);
+            assert_eq!(SmokeField::uniform(f32::NAN).density_at(Vec2::ZERO), 0.0);
+        }
+    }
+
+    mod occupancy_tests {
+        use super::*;
+
+        #[test]
+        fn solid_cells_block_lines_through_them() {
+            // A wall from x = 40 to x = 60, uniform along y
+            let mut values = vec![0.0; 11];
+            values[4..=6].fill(1.0);
+            let wall = OccupancyField {
+                grid: SampleGrid::from_parts(Vec2::ZERO, Vec2::splat(10.0), [11, 1], values),
+            };
+
+            assert!(wall.is_blocked(Vec2::ZERO, Vec2::new(100.0, 0.0)));
+            assert!(!wall.is_blocked(Vec2::ZERO, Vec2::new(0.0, 100.0)));
+            assert!(!wall.is_blocked(Vec2::ZERO, Vec2::ZERO));
+
+            let environment = Environment {
+                occupancy: Some(wall),
+                ..Environment::default()
+            };
+            assert!(environment.is_occluded(Vec2::new(100.0, 0.0), Vec2::ZERO));
+            assert!(!Environment::default().is_occluded(Vec2::ZERO, Vec2::new(100.0, 0.0)));
+        }
+    }
+
+    mod visibility_tests {
+        use super::*;
+
+        #[test]
+        fn default_environment_keeps_full_range() {
+            let environment = Environment::default();
+            assert_eq!(environment.visual_range_factor(0), 1.0);
+            assert!(environment.can_see(0, Vec2::ZERO, Vec2::new(1000.0, 0.0), 1000.0));
+        }
+
+        #[test]
+        fn night_and_rough_seas_shorten_range() {
+            let environment = Environment {
+                clock: WorldClock::starting_at(0.0),
+                sea_state: 5,
+                ..Environment::default()
+            };
+            let factor = Environment::NIGHT_VISIBILITY * (1.0 - 5.0 * Environment::SEA_STATE_PENALTY);
+            assert!((environment.visual_range_factor(0) - factor).abs() < 1e-6);
+            assert!(!environment.can_see(0, Vec2::ZERO, Vec2::new(500.0, 0.0), 1000.0));
+        }
+
+        #[test]
+        fn smoke_screen_hides_target() {
+            let environment = Environment {
+                smoke: Some(SmokeField::uniform(1.0)),
+                ..Environment::default()
+            };
+            assert!(!environment.can_see(0, Vec2::ZERO, Vec2::new(500.0, 0.0), 1000.0));
+            assert!(environment.can_see(0, Vec2::ZERO, Vec2::new(50.0, 0.0), 1000.0));
+        }
+
+        #[test]
+        fn laid_smoke_screens_target() {
+            let environment = Environment {
+                screens: vec![SmokePuff::new(Vec2::new(500.0, 0.0), 0)],
+                ..Environment::default()
+            };
+            assert!(environment.is_screened(Vec2::ZERO, Vec2::new(1000.0, 0.0)));
+            assert!(!environment.is_screened(Vec2::ZERO, Vec2::new(0.0, 1000.0)));
+            assert_eq!(environment.smoke_stamps(0.0).len(), 1);
+        }
+    }
+}
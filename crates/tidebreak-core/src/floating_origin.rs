@@ -0,0 +1,123 @@
+//! Large-world coordinates for the strategic layer.
+//!
+//! The arena works in `f32` meters, which is precise to under a centimeter
+//! within [`FloatingOrigin::PRECISE_EXTENT`] of the origin but degrades to
+//! decimeters and worse past a few hundred kilometers. Campaign maps
+//! spanning thousands of kilometers keep positions as `f64` world
+//! coordinates ([`DVec2`], meters) instead, and a [`FloatingOrigin`] places
+//! each battle's arena around a point in that world:
+//!
+//! - [`FloatingOrigin::to_local`] turns world positions into arena
+//!   positions when building a battle, e.g. the `initial_state` of ships in
+//!   a `BattlePackage`.
+//! - [`FloatingOrigin::to_world`] turns arena positions back into world
+//!   positions when reporting results to the campaign.
+//! - [`FloatingOrigin::rebase`] moves the origin as the action drifts away
+//!   from it; the caller shifts the arena positions it holds by the offset
+//!   returned.
+//!
+//! ```
+//! use glam::{DVec2, Vec2};
+//! use tidebreak_core::floating_origin::FloatingOrigin;
+//!
+//! // Two fleets meeting 3,000 km east of the campaign origin
+//! let blue = DVec2::new(3_000_000.25, 12_000.5);
+//! let red = DVec2::new(3_008_000.75, 12_000.5);
+//! let origin = FloatingOrigin::centered_on([blue, red]).unwrap();
+//!
+//! assert_eq!(origin.to_local(blue), Vec2::new(-4000.25, 0.0));
+//! assert_eq!(origin.to_world(Vec2::new(4000.25, 0.0)), red);
+//! assert!(origin.is_precise(red));
+//! ```
+
+use glam::{DVec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// World point the arena's origin sits on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FloatingOrigin {
+    /// World position of the arena's `(0, 0)`, in meters
+    pub origin: DVec2,
+}
+
+impl FloatingOrigin {
+    /// Distance from the origin, in meters, within which arena positions
+    /// keep sub-centimeter precision (`f32` steps are 7.8 mm at 100 km).
+    pub const PRECISE_EXTENT: f64 = 100_000.0;
+
+    /// Creates an origin at `origin` world meters.
+    #[must_use]
+    pub const fn new(origin: DVec2) -> Self {
+        Self { origin }
+    }
+
+    /// Creates an origin at the center of the bounding box of `points`,
+    /// or `None` if there are none.
+    #[must_use]
+    pub fn centered_on(points: impl IntoIterator<Item = DVec2>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+        Some(Self::new((min + max) * 0.5))
+    }
+
+    /// Converts a world position to an arena position.
+    #[must_use]
+    pub fn to_local(&self, world: DVec2) -> Vec2 {
+        (world - self.origin).as_vec2()
+    }
+
+    /// Converts an arena position to a world position.
+    #[must_use]
+    pub fn to_world(&self, local: Vec2) -> DVec2 {
+        self.origin + local.as_dvec2()
+    }
+
+    /// Returns whether `world` is within [`Self::PRECISE_EXTENT`] of the
+    /// origin, so its arena position loses no meaningful precision.
+    #[must_use]
+    pub fn is_precise(&self, world: DVec2) -> bool {
+        (world - self.origin).abs().max_element() <= Self::PRECISE_EXTENT
+    }
+
+    /// Moves the origin to `origin` and returns the offset, in arena
+    /// meters, to subtract from arena positions taken against the old one.
+    pub fn rebase(&mut self, origin: DVec2) -> Vec2 {
+        let offset = self.to_local(origin);
+        self.origin = origin;
+        offset
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_keep_precision_far_from_the_world_origin() {
+        let origin = FloatingOrigin::new(DVec2::new(-7_500_000.0, 4_200_000.0));
+        let world = DVec2::new(-7_512_345.25, 4_187_654.25);
+
+        // Plain f32 positions only resolve half meters out here
+        assert_ne!(world.as_vec2().as_dvec2(), world);
+        assert_eq!(origin.to_world(origin.to_local(world)), world);
+        assert!(origin.is_precise(world));
+        assert!(!origin.is_precise(DVec2::ZERO));
+    }
+
+    #[test]
+    fn rebase_returns_the_shift_of_arena_positions() {
+        let mut origin = FloatingOrigin::new(DVec2::new(1_000_000.0, 0.0));
+        let ship = Vec2::new(60_000.0, -500.0);
+        let world = origin.to_world(ship);
+
+        let offset = origin.rebase(world);
+        assert_eq!(offset, ship);
+        assert_eq!(origin.to_local(world), ship - offset);
+        assert!(FloatingOrigin::centered_on([]).is_none());
+    }
+}
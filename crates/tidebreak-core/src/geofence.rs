@@ -0,0 +1,271 @@
+//! Keep-out geofences for constrained control.
+//!
+//! Safe-RL experiments need hard constraints enforced by the engine rather
+//! than by the policy. The arena's [`GeofenceBook`] holds, per entity, a
+//! list of [`Geofence`]s: circles or polygons the entity must not enter.
+//! After movement is integrated, the
+//! [`SafetyResolver`](crate::resolver::SafetyResolver) checks every fenced
+//! entity and, for each fence it has entered:
+//!
+//! - records an `Event::GeofenceViolated` with the penetration depth, the
+//!   constraint signal for the learner
+//! - applies the fence's [`FencePolicy`]: `Clamp` pushes the entity back
+//!   onto the fence and removes the velocity carrying it inward, `Reject`
+//!   undoes the tick's movement and stops the entity
+//!
+//! Fences of despawned entities are dropped.
+//!
+//! # Example
+//!
+//! ```
+//! use glam::Vec2;
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+//! use tidebreak_core::geofence::{FencePolicy, Geofence};
+//!
+//! let mut arena = Arena::new();
+//! let ship = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+//!
+//! let harbor = Geofence::circle(Vec2::new(1000.0, 0.0), 200.0);
+//! let strait = Geofence::polygon(vec![
+//!     Vec2::new(0.0, 500.0),
+//!     Vec2::new(500.0, 500.0),
+//!     Vec2::new(500.0, 1000.0),
+//! ])
+//! .with_policy(FencePolicy::Reject);
+//! arena.geofences_mut().add(ship, harbor.clone());
+//! assert_eq!(arena.geofences_mut().add(ship, strait), 1);
+//!
+//! let (depth, _) = harbor.penetration(Vec2::new(900.0, 0.0)).unwrap();
+//! assert!((depth - 100.0).abs() < 1e-3);
+//! ```
+
+use std::collections::BTreeMap;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::EntityId;
+
+/// What the safety resolver does to an entity that enters a fence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FencePolicy {
+    /// Push the entity back onto the fence and cancel its inward velocity.
+    #[default]
+    Clamp,
+    /// Put the entity back where it was before the tick and stop it.
+    Reject,
+}
+
+/// Area covered by a fence.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FenceShape {
+    /// Disc around `center`.
+    Circle {
+        /// Center in world coordinates
+        center: Vec2,
+        /// Radius in meters
+        radius: f32,
+    },
+    /// Simple polygon; fewer than three vertices cover nothing.
+    Polygon {
+        /// Corners in order (either winding)
+        vertices: Vec<Vec2>,
+    },
+}
+
+/// Keep-out area for one entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Geofence {
+    /// Area the entity must stay out of
+    pub shape: FenceShape,
+    /// Response to an entry
+    pub policy: FencePolicy,
+}
+
+impl Geofence {
+    /// Creates a clamping fence around a disc.
+    #[must_use]
+    pub const fn circle(center: Vec2, radius: f32) -> Self {
+        Self {
+            shape: FenceShape::Circle { center, radius },
+            policy: FencePolicy::Clamp,
+        }
+    }
+
+    /// Creates a clamping fence around a polygon.
+    #[must_use]
+    pub const fn polygon(vertices: Vec<Vec2>) -> Self {
+        Self {
+            shape: FenceShape::Polygon { vertices },
+            policy: FencePolicy::Clamp,
+        }
+    }
+
+    /// Sets the response to an entry.
+    #[must_use]
+    pub const fn with_policy(mut self, policy: FencePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns `true` if `position` is strictly inside the fence.
+    #[must_use]
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.penetration(position).is_some()
+    }
+
+    /// Returns how far `position` is inside the fence, and the nearest
+    /// point on the fence's edge, or `None` if it is outside or on the edge.
+    #[must_use]
+    pub fn penetration(&self, position: Vec2) -> Option<(f32, Vec2)> {
+        match &self.shape {
+            FenceShape::Circle { center, radius } => {
+                let offset = position - *center;
+                let depth = radius - offset.length();
+                let outward = offset.try_normalize().unwrap_or(Vec2::X);
+                (depth > 0.0).then(|| (depth, *center + outward * *radius))
+            }
+            FenceShape::Polygon { vertices } => {
+                if vertices.len() < 3 || !polygon_contains(vertices, position) {
+                    return None;
+                }
+                let edges = vertices.iter().zip(vertices.iter().cycle().skip(1));
+                edges
+                    .map(|(&a, &b)| {
+                        let edge = closest_on_segment(a, b, position);
+                        (position.distance(edge), edge)
+                    })
+                    .min_by(|x, y| x.0.total_cmp(&y.0))
+                    .filter(|(depth, _)| *depth > 0.0)
+            }
+        }
+    }
+}
+
+/// Even-odd test of `point` against a polygon.
+fn polygon_contains(vertices: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    let edges = vertices.iter().zip(vertices.iter().cycle().skip(1));
+    for (&a, &b) in edges {
+        if (a.y > point.y) != (b.y > point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Returns the point of segment `a`-`b` nearest `point`.
+fn closest_on_segment(a: Vec2, b: Vec2, point: Vec2) -> Vec2 {
+    let along = b - a;
+    let length_squared = along.length_squared();
+    if length_squared == 0.0 {
+        return a;
+    }
+    let t = ((point - a).dot(along) / length_squared).clamp(0.0, 1.0);
+    a + along * t
+}
+
+/// Per-arena record of geofences, keyed by the entity they constrain.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeofenceBook {
+    fences: BTreeMap<EntityId, Vec<Geofence>>,
+}
+
+impl GeofenceBook {
+    /// Creates an empty book.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a fence for `entity`, returning its index among the entity's
+    /// fences (reported in violation events).
+    pub fn add(&mut self, entity: EntityId, fence: Geofence) -> usize {
+        let fences = self.fences.entry(entity).or_default();
+        fences.push(fence);
+        fences.len() - 1
+    }
+
+    /// Returns the fences of `entity`, in the order they were added.
+    #[must_use]
+    pub fn get(&self, entity: EntityId) -> &[Geofence] {
+        self.fences.get(&entity).map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterates over (entity, fences) pairs in ID order.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &[Geofence])> {
+        self.fences.iter().map(|(id, fences)| (*id, fences.as_slice()))
+    }
+
+    /// Returns the number of fenced entities.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.fences.len()
+    }
+
+    /// Returns true if no entity is fenced.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fences.is_empty()
+    }
+
+    /// Drops the fences of an entity (e.g. when it is despawned).
+    pub fn remove_entity(&mut self, entity: EntityId) {
+        self.fences.remove(&entity);
+    }
+
+    /// Drops all fences.
+    pub fn clear(&mut self) {
+        self.fences.clear();
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circle_penetration_points_to_nearest_edge() {
+        let fence = Geofence::circle(Vec2::ZERO, 100.0);
+        let (depth, edge) = fence.penetration(Vec2::new(0.0, 60.0)).unwrap();
+        assert!((depth - 40.0).abs() < 1e-4);
+        assert!(edge.distance(Vec2::new(0.0, 100.0)) < 1e-4);
+        assert!(fence.penetration(Vec2::new(100.0, 0.0)).is_none());
+        assert!(fence.contains(Vec2::ZERO));
+    }
+
+    #[test]
+    fn polygon_penetration_and_containment() {
+        let square = Geofence::polygon(vec![
+            Vec2::ZERO,
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(0.0, 100.0),
+        ]);
+        let (depth, edge) = square.penetration(Vec2::new(90.0, 50.0)).unwrap();
+        assert!((depth - 10.0).abs() < 1e-4);
+        assert!(edge.distance(Vec2::new(100.0, 50.0)) < 1e-4);
+        assert!(square.penetration(Vec2::new(150.0, 50.0)).is_none());
+        assert!(!Geofence::polygon(vec![Vec2::ZERO, Vec2::X]).contains(Vec2::ZERO));
+    }
+
+    #[test]
+    fn book_indexes_fences_per_entity() {
+        let mut book = GeofenceBook::new();
+        let id = EntityId::new(3);
+        assert_eq!(book.add(id, Geofence::circle(Vec2::ZERO, 1.0)), 0);
+        assert_eq!(book.add(id, Geofence::circle(Vec2::X, 1.0)), 1);
+        assert_eq!(book.get(id).len(), 2);
+        assert!(book.get(EntityId::new(4)).is_empty());
+        book.remove_entity(id);
+        assert!(book.is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+//! Regular 2D grids of values sampled from the murk substrate.
+//!
+//! The simulation does not own a universe, so environmental fields that
+//! entities react to (currents, smoke) are captured by the host as grid
+//! snapshots and looked up with bilinear interpolation.
+
+use std::ops::{Add, Mul};
+
+use glam::Vec2;
+use murk::query::PointResult;
+use murk::Universe;
+use serde::{Deserialize, Serialize};
+
+/// Largest number of samples taken along each axis by [`SampleGrid::sample`].
+pub const MAX_SAMPLES_PER_AXIS: usize = 256;
+
+/// Grid snapshot of a value over a rectangle.
+///
+/// Lookups between samples are bilinear; positions outside the sampled
+/// area use the nearest edge sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SampleGrid<T> {
+    /// Position of the first sample
+    origin: Vec2,
+    /// Distance between samples along each axis
+    step: Vec2,
+    /// Number of samples along x and y
+    dims: [usize; 2],
+    /// Sampled values, row-major (x fastest)
+    values: Vec<T>,
+}
+
+impl<T> SampleGrid<T>
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    /// Creates a grid with the same value everywhere.
+    pub(crate) fn uniform(value: T) -> Self {
+        Self {
+            origin: Vec2::ZERO,
+            step: Vec2::ONE,
+            dims: [1, 1],
+            values: vec![value],
+        }
+    }
+
+    /// Samples `universe` over the rectangle `[min, max]` at altitude `z`,
+    /// reading each sample with `read`.
+    ///
+    /// Samples are at most `spacing` apart (and at most
+    /// [`MAX_SAMPLES_PER_AXIS`] per axis), including both edges.
+    pub(crate) fn sample(
+        universe: &Universe,
+        min: Vec2,
+        max: Vec2,
+        spacing: f32,
+        z: f32,
+        read: impl Fn(&PointResult) -> T,
+    ) -> Self {
+        let extent = (max - min).max(Vec2::ZERO);
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let (dims, step) = {
+            let count = |length: f32| {
+                let cells = (length / spacing).ceil();
+                if cells.is_finite() && cells > 0.0 {
+                    (cells as usize + 1).min(MAX_SAMPLES_PER_AXIS)
+                } else {
+                    1
+                }
+            };
+            let dims = [count(extent.x), count(extent.y)];
+            let step = |length: f32, n: usize| if n > 1 { length / (n - 1) as f32 } else { 1.0 };
+            (
+                dims,
+                Vec2::new(step(extent.x, dims[0]), step(extent.y, dims[1])),
+            )
+        };
+
+        let mut values = Vec::with_capacity(dims[0] * dims[1]);
+        for y in 0..dims[1] {
+            for x in 0..dims[0] {
+                #[allow(clippy::cast_precision_loss)]
+                let position = min + Vec2::new(x as f32, y as f32) * step;
+                values.push(read(&universe.query_point(position.extend(z))));
+            }
+        }
+
+        Self {
+            origin: min,
+            step,
+            dims,
+            values,
+        }
+    }
+
+    /// Returns the interpolated value at `position`, or `None` if the grid
+    /// is malformed (for example, a truncated snapshot).
+    pub(crate) fn at(&self, position: Vec2) -> Option<T> {
+        if self.values.is_empty() || self.values.len() != self.dims[0] * self.dims[1] {
+            return None;
+        }
+        let grid = (position - self.origin) / self.step;
+        let (x0, x1, tx) = Self::axis(grid.x, self.dims[0]);
+        let (y0, y1, ty) = Self::axis(grid.y, self.dims[1]);
+        let at = |x: usize, y: usize| self.values[y * self.dims[0] + x];
+        let lerp = |a: T, b: T, t: f32| a * (1.0 - t) + b * t;
+        let bottom = lerp(at(x0, y0), at(x1, y0), tx);
+        let top = lerp(at(x0, y1), at(x1, y1), tx);
+        Some(lerp(bottom, top, ty))
+    }
+
+    /// Splits a grid coordinate into neighbouring sample indices and the
+    /// interpolation weight between them, clamped to the grid.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn axis(coordinate: f32, count: usize) -> (usize, usize, f32) {
+        let last = count - 1;
+        let clamped = if coordinate.is_nan() {
+            0.0
+        } else {
+            coordinate.clamp(0.0, last as f32)
+        };
+        let lower = (clamped.floor() as usize).min(last);
+        let upper = (lower + 1).min(last);
+        (lower, upper, clamped - lower as f32)
+    }
+}
+
+#[cfg(test)]
+impl<T> SampleGrid<T> {
+    /// Builds a grid directly from its parts.
+    pub(crate) fn from_parts(origin: Vec2, step: Vec2, dims: [usize; 2], values: Vec<T>) -> Self {
+        Self {
+            origin,
+            step,
+            dims,
+            values,
+        }
+    }
+
+    /// Returns the number of samples along x and y.
+    pub(crate) const fn dims(&self) -> [usize; 2] {
+        self.dims
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use murk::Field;
+
+    #[test]
+    fn at_interpolates_and_clamps() {
+        let grid = SampleGrid::from_parts(
+            Vec2::ZERO,
+            Vec2::splat(10.0),
+            [2, 2],
+            vec![0.0, 2.0, 4.0, 6.0],
+        );
+        assert_eq!(grid.at(Vec2::new(5.0, 5.0)), Some(3.0));
+        assert_eq!(grid.at(Vec2::new(10.0, 0.0)), Some(2.0));
+        // Outside the grid uses the nearest edge
+        assert_eq!(grid.at(Vec2::new(-50.0, 50.0)), Some(4.0));
+        assert_eq!(grid.at(Vec2::splat(f32::NAN)), Some(0.0));
+    }
+
+    #[test]
+    fn malformed_grid_has_no_value() {
+        let grid = SampleGrid::from_parts(Vec2::ZERO, Vec2::ONE, [2, 2], vec![1.0]);
+        assert_eq!(grid.at(Vec2::ZERO), None);
+    }
+
+    #[test]
+    fn sample_caps_resolution() {
+        let universe = Universe::default();
+        let read = |p: &PointResult| p.get(Field::Smoke);
+        let grid = SampleGrid::sample(&universe, Vec2::ZERO, Vec2::splat(1e6), 0.0, 0.0, read);
+        assert_eq!(grid.dims(), [1, 1]);
+
+        let grid = SampleGrid::sample(&universe, Vec2::ZERO, Vec2::splat(1e6), 1.0, 0.0, read);
+        assert_eq!(grid.dims(), [MAX_SAMPLES_PER_AXIS, MAX_SAMPLES_PER_AXIS]);
+    }
+}
@@ -0,0 +1,693 @@
+//! Interest management for observation building.
+//!
+//! Observation builders only care about the K most relevant contacts of each
+//! agent. Rebuilding that list from the full track table every time an
+//! observation is requested is wasteful when the table has not changed, and
+//! fully sorting the table is wasteful when only K entries are kept.
+//!
+//! [`InterestManager`] keeps a per-agent cache of which K tracks are the
+//! nearest (or most threatening) contacts. A selection is reused while the
+//! agent's track table keeps its generation (see
+//! [`SensorState::touch_tracks`](crate::entity::SensorState::touch_tracks))
+//! and the agent stays within one cell of the manager's grid, so repeated
+//! observations between track updates cost O(K) instead of a selection over
+//! the whole table. The contacts' relative bearing, distance and velocity
+//! are always computed from the agent's current kinematics; only the choice
+//! and order of the K tracks may lag by up to a cell.
+//!
+//! # Contact order
+//!
+//! Contacts are ordered nearest first by default. Observations were built
+//! from the first K tracks in track table order before this module existed;
+//! [`ContactSortKey::TrackTable`] keeps that order.
+//!
+//! [`InterestManager::contacts_matching`] picks the contacts with an explicit
+//! sort key and a [`ContactFilter`] (minimum quality, observed tags, teams)
+//! instead of the manager's default sort key and no filter.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::{EntityId, EntityInner, EntityTag, ShipComponents, Track, TrackQuality};
+//! use tidebreak_core::interest::{ContactSortKey, InterestManager};
+//! use glam::Vec2;
+//!
+//! let mut arena = Arena::new();
+//! let mut ship = ShipComponents::default();
+//! ship.sensor.track_table.push(Track::new(EntityId::new(10), Vec2::new(500.0, 0.0), TrackQuality::Coarse));
+//! ship.sensor.track_table.push(Track::new(EntityId::new(11), Vec2::new(50.0, 0.0), TrackQuality::Coarse));
+//! let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+//!
+//! let mut interest = InterestManager::new(ContactSortKey::Distance);
+//! let contacts = interest.contacts_for(&arena, id, 1);
+//! assert_eq!(contacts[0].target_id, EntityId::new(11));
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::entity::{
+    Entity, EntityId, EntityInner, EntityTag, TeamId, Track, TrackEstimate, TrackQuality,
+};
+use crate::plugins::ThreatWeights;
+
+// =============================================================================
+// Sort Key
+// =============================================================================
+
+/// Ordering used to pick the K contacts that are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ContactSortKey {
+    /// Nearest contacts first.
+    #[default]
+    Distance,
+    /// Highest threat score first, scored by [`ThreatWeights::default`] as
+    /// in the `ThreatEvaluationPlugin`.
+    Threat,
+    /// Best track quality first, nearest first among equals.
+    Quality,
+    /// The first K tracks in track table order, oldest track first.
+    TrackTable,
+}
+
+// =============================================================================
+// Contact Filter
+// =============================================================================
+
+/// Which tracks are eligible to become contacts.
+///
+/// The default filter keeps every track.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContactFilter {
+    /// Lowest track quality kept
+    pub min_quality: TrackQuality,
+    /// Observed tags kept (unidentified contacts are dropped); empty keeps
+    /// all
+    pub tags: Vec<EntityTag>,
+    /// Teams of the tracked entities kept; empty keeps all
+    pub teams: Vec<TeamId>,
+}
+
+impl ContactFilter {
+    /// Builder method to set the lowest track quality kept.
+    #[must_use]
+    pub const fn with_min_quality(mut self, min_quality: TrackQuality) -> Self {
+        self.min_quality = min_quality;
+        self
+    }
+
+    /// Builder method to keep only contacts classified as one of `tags`.
+    #[must_use]
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = EntityTag>) -> Self {
+        self.tags = tags.into_iter().collect();
+        self
+    }
+
+    /// Builder method to keep only contacts on one of `teams`.
+    ///
+    /// Teams are those of the tracked entities in the arena, as if every
+    /// track carried perfect identification friend-or-foe.
+    #[must_use]
+    pub fn with_teams(mut self, teams: impl IntoIterator<Item = TeamId>) -> Self {
+        self.teams = teams.into_iter().collect();
+        self
+    }
+
+    /// Returns true if `track`, whose target is on `team`, is kept.
+    #[must_use]
+    pub fn accepts(&self, track: &Track, team: Option<TeamId>) -> bool {
+        track.quality >= self.min_quality
+            && (self.tags.is_empty() || track.classified_as.is_some_and(|t| self.tags.contains(&t)))
+            && (self.teams.is_empty() || team.is_some_and(|t| self.teams.contains(&t)))
+    }
+}
+
+// =============================================================================
+// Cached Contact
+// =============================================================================
+
+/// A contact selected for an agent's observation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CachedContact {
+    /// Tracked entity.
+    pub target_id: EntityId,
+    /// Estimated position of the contact.
+    pub position: Vec2,
+    /// Bearing from the agent to the contact (radians, world frame).
+    pub rel_heading: f32,
+    /// Distance from the agent to the contact.
+    pub distance: f32,
+    /// Track quality.
+    pub quality: TrackQuality,
+    /// Threat score under [`ThreatWeights::default`].
+    pub threat: f32,
+    /// Classified tag, or `None` while the contact is unidentified.
+    pub observed_tag: Option<EntityTag>,
+    /// Velocity of the contact relative to the agent (the contact is taken
+    /// as stationary without a velocity estimate).
+    #[serde(default)]
+    pub rel_velocity: Vec2,
+    /// Course and speed estimated from the track history, if it has enough
+    /// fixes (see [`Track::estimate`]).
+    #[serde(default)]
+    pub estimate: Option<TrackEstimate>,
+}
+
+/// Inputs a selection of contacts depends on, besides the filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SelectionKey {
+    /// Generation of the agent's track table
+    generation: u64,
+    /// Length of the agent's track table, so a table edited without a
+    /// generation bump never leaves stale indices in the selection
+    tracks: usize,
+    /// Grid cell of the agent's position
+    cell: (i64, i64),
+    /// Cell of the agent's velocity, for threat ordering only
+    velocity_cell: Option<(i64, i64)>,
+    k: usize,
+    sort_key: ContactSortKey,
+    /// Hash of the tracked entities' teams, when filtering by team
+    teams: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ContactCache {
+    key: Option<SelectionKey>,
+    filter: ContactFilter,
+    /// Indices of the selected tracks, in contact order
+    selected: Vec<usize>,
+    contacts: Vec<CachedContact>,
+}
+
+// =============================================================================
+// InterestManager
+// =============================================================================
+
+/// Per-agent nearest-K contact caches.
+///
+/// Selections are keyed on track table generations, which restored or
+/// forked arenas can repeat with different tracks: call
+/// [`clear`](Self::clear) after replacing the arena an agent lives in.
+#[derive(Debug, Clone)]
+pub struct InterestManager {
+    sort_key: ContactSortKey,
+    cell_size: f32,
+    caches: BTreeMap<EntityId, ContactCache>,
+    rebuilds: u64,
+}
+
+impl Default for InterestManager {
+    fn default() -> Self {
+        Self::new(ContactSortKey::default())
+    }
+}
+
+impl InterestManager {
+    /// Side of the grid cells agents may move within without their contacts
+    /// being selected again, in meters.
+    pub const DEFAULT_CELL_SIZE: f32 = 100.0;
+
+    /// Speed step, in m/s, of the agent's velocity within which threat
+    /// ordered contacts are not selected again.
+    const VELOCITY_STEP: f32 = 1.0;
+
+    /// Creates an empty manager using the given sort key.
+    #[must_use]
+    pub fn new(sort_key: ContactSortKey) -> Self {
+        Self {
+            sort_key,
+            cell_size: Self::DEFAULT_CELL_SIZE,
+            caches: BTreeMap::new(),
+            rebuilds: 0,
+        }
+    }
+
+    /// Builder method to set the side of the grid cells, in meters. Smaller
+    /// cells follow the agent's movement more closely at the cost of more
+    /// selections.
+    #[must_use]
+    pub fn with_cell_size(mut self, cell_size: f32) -> Self {
+        self.cell_size = cell_size;
+        self.caches.clear();
+        self
+    }
+
+    /// Returns the side of the grid cells, in meters.
+    #[must_use]
+    pub const fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Returns the active sort key.
+    #[must_use]
+    pub const fn sort_key(&self) -> ContactSortKey {
+        self.sort_key
+    }
+
+    /// Changes the sort key, invalidating all caches.
+    pub fn set_sort_key(&mut self, sort_key: ContactSortKey) {
+        if self.sort_key != sort_key {
+            self.sort_key = sort_key;
+            self.caches.clear();
+        }
+    }
+
+    /// Returns the up-to-`k` most relevant contacts of `agent`.
+    ///
+    /// The cached selection is reused while the agent's track table keeps
+    /// its generation and the agent stays in the same grid cell. Agents
+    /// without a sensor (or that no longer exist) have no contacts.
+    pub fn contacts_for(&mut self, arena: &Arena, agent: EntityId, k: usize) -> &[CachedContact] {
+        self.contacts_matching(arena, agent, k, self.sort_key, &ContactFilter::default())
+    }
+
+    /// Returns the up-to-`k` contacts of `agent` that pass `filter`, ordered
+    /// by `sort_key`.
+    ///
+    /// Cached like [`Self::contacts_for`]; a different key or filter than
+    /// the cached list was built with rebuilds it.
+    pub fn contacts_matching(
+        &mut self,
+        arena: &Arena,
+        agent: EntityId,
+        k: usize,
+        sort_key: ContactSortKey,
+        filter: &ContactFilter,
+    ) -> &[CachedContact] {
+        let Some((own_pos, own_vel, sensor)) = arena.get(agent).and_then(|e| match e.inner() {
+            EntityInner::Ship(c) => Some((c.transform.position, c.physics.velocity, &c.sensor)),
+            _ => None,
+        }) else {
+            self.caches.remove(&agent);
+            return &[];
+        };
+        let tracks = sensor.track_table.as_slice();
+
+        let team_of = |track: &Track| arena.get(track.target_id).and_then(Entity::team);
+        let key = SelectionKey {
+            generation: sensor.track_generation,
+            tracks: tracks.len(),
+            cell: cell_of(own_pos, self.cell_size),
+            velocity_cell: (sort_key == ContactSortKey::Threat)
+                .then(|| cell_of(own_vel, Self::VELOCITY_STEP)),
+            k,
+            sort_key,
+            teams: if filter.teams.is_empty() {
+                0
+            } else {
+                let mut hasher = DefaultHasher::new();
+                for track in tracks {
+                    team_of(track).hash(&mut hasher);
+                }
+                hasher.finish()
+            },
+        };
+        let cache = self.caches.entry(agent).or_default();
+        let weights = ThreatWeights::default();
+        if cache.key == Some(key) && cache.filter == *filter {
+            cache.contacts.clear();
+            cache.contacts.extend(
+                cache
+                    .selected
+                    .iter()
+                    .map(|&index| contact(&tracks[index], own_pos, own_vel, &weights)),
+            );
+        } else {
+            let eligible = tracks
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| filter.accepts(t, team_of(t)));
+            let selected = select_contacts(eligible, own_pos, own_vel, k, sort_key);
+            (cache.selected, cache.contacts) = selected.into_iter().unzip();
+            cache.key = Some(key);
+            cache.filter.clone_from(filter);
+            self.rebuilds += 1;
+        }
+        &cache.contacts
+    }
+
+    /// Drops caches for agents that no longer exist in the arena.
+    pub fn retain_existing(&mut self, arena: &Arena) {
+        self.caches.retain(|id, _| arena.get(*id).is_some());
+    }
+
+    /// Drops all caches.
+    pub fn clear(&mut self) {
+        self.caches.clear();
+    }
+
+    /// Number of agents with a cached contact list.
+    #[must_use]
+    pub fn cached_agents(&self) -> usize {
+        self.caches.len()
+    }
+
+    /// Total number of cache rebuilds since creation.
+    #[must_use]
+    pub const fn rebuild_count(&self) -> u64 {
+        self.rebuilds
+    }
+}
+
+/// Grid cell of `position` for cells of side `size`.
+fn cell_of(position: Vec2, size: f32) -> (i64, i64) {
+    let cell = (position / size).floor();
+    // Saturating casts; cells past i64 only occur for non-finite positions
+    #[allow(clippy::cast_possible_truncation)]
+    (cell.x as i64, cell.y as i64)
+}
+
+/// The contact of `track` as seen by an agent at `own_pos` moving at
+/// `own_vel`.
+fn contact(track: &Track, own_pos: Vec2, own_vel: Vec2, weights: &ThreatWeights) -> CachedContact {
+    let rel = track.position - own_pos;
+    CachedContact {
+        target_id: track.target_id,
+        position: track.position,
+        rel_heading: rel.y.atan2(rel.x),
+        distance: rel.length(),
+        quality: track.quality,
+        threat: weights.score(track, own_pos, own_vel),
+        observed_tag: track.classified_as,
+        rel_velocity: track.velocity.unwrap_or(Vec2::ZERO) - own_vel,
+        estimate: track.estimate(),
+    }
+}
+
+/// Selects the top `k` contacts without fully sorting the track table,
+/// returning them with their track table indices.
+///
+/// Ties are broken by target ID so the result is deterministic.
+fn select_contacts<'a>(
+    tracks: impl Iterator<Item = (usize, &'a Track)>,
+    own_pos: Vec2,
+    own_vel: Vec2,
+    k: usize,
+    sort_key: ContactSortKey,
+) -> Vec<(usize, CachedContact)> {
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let weights = ThreatWeights::default();
+    let mut contacts: Vec<(usize, CachedContact)> = tracks
+        .map(|(index, track)| (index, contact(track, own_pos, own_vel, &weights)))
+        .collect();
+
+    let compare = |(i, a): &(usize, CachedContact), (j, b): &(usize, CachedContact)| {
+        let primary = match sort_key {
+            ContactSortKey::Distance => a.distance.total_cmp(&b.distance),
+            ContactSortKey::Threat => b.threat.total_cmp(&a.threat),
+            ContactSortKey::Quality => {
+                (b.quality.cmp(&a.quality)).then(a.distance.total_cmp(&b.distance))
+            }
+            ContactSortKey::TrackTable => i.cmp(j),
+        };
+        primary.then(a.target_id.cmp(&b.target_id))
+    };
+
+    if contacts.len() > k {
+        contacts.select_nth_unstable_by(k - 1, compare);
+        contacts.truncate(k);
+    }
+    contacts.sort_unstable_by(compare);
+    contacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityTag, ShipComponents};
+
+    fn spawn_with_tracks(arena: &mut Arena, tracks: Vec<Track>) -> EntityId {
+        let mut ship = ShipComponents::default();
+        ship.sensor.track_table = tracks;
+        arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+    }
+
+    fn track_at(id: u64, x: f32) -> Track {
+        Track::new(EntityId::new(id), Vec2::new(x, 0.0), TrackQuality::Coarse)
+    }
+
+    mod selection_tests {
+        use super::*;
+
+        #[test]
+        fn nearest_k_in_distance_order() {
+            let mut arena = Arena::new();
+            let tracks = vec![
+                track_at(1, 400.0),
+                track_at(2, 100.0),
+                track_at(3, 300.0),
+                track_at(4, 200.0),
+            ];
+            let agent = spawn_with_tracks(&mut arena, tracks);
+
+            let mut interest = InterestManager::new(ContactSortKey::Distance);
+            let ids: Vec<_> = interest
+                .contacts_for(&arena, agent, 3)
+                .iter()
+                .map(|c| c.target_id.as_u64())
+                .collect();
+
+            assert_eq!(ids, vec![2, 4, 3]);
+        }
+
+        #[test]
+        fn threat_prefers_closing_contacts() {
+            let mut arena = Arena::new();
+            let mut closing = track_at(1, 300.0);
+            closing.velocity = Some(Vec2::new(-50.0, 0.0));
+            let mut opening = track_at(2, 250.0);
+            opening.velocity = Some(Vec2::new(50.0, 0.0));
+            let agent = spawn_with_tracks(&mut arena, vec![opening, closing.clone()]);
+
+            let mut interest = InterestManager::new(ContactSortKey::Threat);
+            let first = interest.contacts_for(&arena, agent, 1)[0];
+
+            assert_eq!(first.target_id, EntityId::new(1));
+            // Same score the threat evaluation plugin reports
+            let score = ThreatWeights::default().score(&closing, Vec2::ZERO, Vec2::ZERO);
+            assert_eq!(first.threat.to_bits(), score.to_bits());
+        }
+
+        #[test]
+        fn ties_break_by_target_id() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(&mut arena, vec![track_at(9, 100.0), track_at(3, 100.0)]);
+
+            let mut interest = InterestManager::default();
+            let ids: Vec<_> = interest
+                .contacts_for(&arena, agent, 2)
+                .iter()
+                .map(|c| c.target_id.as_u64())
+                .collect();
+
+            assert_eq!(ids, vec![3, 9]);
+        }
+
+        #[test]
+        fn filter_and_quality_sort_pick_matching_contacts() {
+            let mut arena = Arena::new();
+            let red = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+            arena.set_team(red, Some(TeamId::new(1)));
+            let mut cue = track_at(1, 100.0);
+            cue.quality = TrackQuality::Cue;
+            let mut ship = track_at(2, 300.0);
+            ship.classified_as = Some(EntityTag::Ship);
+            ship.velocity = Some(Vec2::new(-10.0, 0.0));
+            let mut enemy = track_at(red.as_u64(), 200.0);
+            enemy.quality = TrackQuality::FireControl;
+            let agent = spawn_with_tracks(&mut arena, vec![cue, ship, enemy]);
+
+            let mut interest = InterestManager::default();
+            let mut ids = |key, filter: &ContactFilter| -> Vec<u64> {
+                let contacts = interest.contacts_matching(&arena, agent, 3, key, filter);
+                contacts.iter().map(|c| c.target_id.as_u64()).collect()
+            };
+            let coarse = ContactFilter::default().with_min_quality(TrackQuality::Coarse);
+            let all = ContactFilter::default();
+            assert_eq!(ids(ContactSortKey::Quality, &all), [red.as_u64(), 2, 1]);
+            assert_eq!(ids(ContactSortKey::Distance, &coarse), [red.as_u64(), 2]);
+            let ships = ContactFilter::default().with_tags([EntityTag::Ship]);
+            assert_eq!(ids(ContactSortKey::Distance, &ships), [2]);
+            let reds = ContactFilter::default().with_teams([TeamId::new(1)]);
+            assert_eq!(ids(ContactSortKey::Distance, &reds), [red.as_u64()]);
+
+            let key = ContactSortKey::Distance;
+            let contact = interest.contacts_matching(&arena, agent, 1, key, &ships)[0];
+            assert_eq!(contact.rel_velocity, Vec2::new(-10.0, 0.0));
+        }
+
+        #[test]
+        fn track_table_order_keeps_the_first_tracks() {
+            let mut arena = Arena::new();
+            let tracks = vec![track_at(4, 400.0), track_at(2, 100.0), track_at(3, 300.0)];
+            let agent = spawn_with_tracks(&mut arena, tracks);
+
+            let mut interest = InterestManager::new(ContactSortKey::TrackTable);
+            let ids: Vec<_> = interest
+                .contacts_for(&arena, agent, 2)
+                .iter()
+                .map(|c| c.target_id.as_u64())
+                .collect();
+
+            assert_eq!(ids, vec![4, 2]);
+        }
+
+        #[test]
+        fn entities_without_sensors_have_no_contacts() {
+            let mut arena = Arena::new();
+            let platform = arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(crate::entity::PlatformComponents::default()),
+            );
+
+            let mut interest = InterestManager::default();
+            assert!(interest.contacts_for(&arena, platform, 4).is_empty());
+            assert!(interest
+                .contacts_for(&arena, EntityId::new(99), 4)
+                .is_empty());
+        }
+    }
+
+    mod cache_tests {
+        use super::*;
+
+        #[test]
+        fn unchanged_agent_reuses_cache() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(&mut arena, vec![track_at(1, 100.0)]);
+
+            let mut interest = InterestManager::default();
+            let _ = interest.contacts_for(&arena, agent, 4);
+            let _ = interest.contacts_for(&arena, agent, 4);
+
+            assert_eq!(interest.rebuild_count(), 1);
+        }
+
+        #[test]
+        fn track_change_invalidates_cache() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(&mut arena, vec![track_at(1, 100.0)]);
+
+            let mut interest = InterestManager::default();
+            let _ = interest.contacts_for(&arena, agent, 4);
+
+            let sensor = &mut arena.get_mut(agent).unwrap().as_ship_mut().unwrap().sensor;
+            sensor.track_table.push(track_at(2, 50.0));
+            sensor.touch_tracks();
+
+            let contacts = interest.contacts_for(&arena, agent, 4);
+            assert_eq!(contacts.len(), 2);
+            assert_eq!(contacts[0].target_id, EntityId::new(2));
+            assert_eq!(interest.rebuild_count(), 2);
+        }
+
+        #[test]
+        fn truncated_track_table_without_touch_selects_again() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(
+                &mut arena,
+                vec![track_at(1, 100.0), track_at(2, 200.0), track_at(3, 300.0)],
+            );
+
+            let mut interest = InterestManager::default();
+            assert_eq!(interest.contacts_for(&arena, agent, 4).len(), 3);
+
+            let sensor = &mut arena.get_mut(agent).unwrap().as_ship_mut().unwrap().sensor;
+            sensor.track_table.truncate(1);
+
+            let contacts = interest.contacts_for(&arena, agent, 4);
+            assert_eq!(contacts.len(), 1);
+            assert_eq!(contacts[0].target_id, EntityId::new(1));
+            assert_eq!(interest.rebuild_count(), 2);
+        }
+
+        #[test]
+        fn classification_change_invalidates_cache() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(&mut arena, vec![track_at(1, 100.0)]);
+
+            let mut interest = InterestManager::default();
+            assert_eq!(
+                interest.contacts_for(&arena, agent, 4)[0].observed_tag,
+                None
+            );
+
+            let sensor = &mut arena.get_mut(agent).unwrap().as_ship_mut().unwrap().sensor;
+            sensor.track_table[0].classified_as = Some(EntityTag::Ship);
+            sensor.touch_tracks();
+
+            let contacts = interest.contacts_for(&arena, agent, 4);
+            assert_eq!(contacts[0].observed_tag, Some(EntityTag::Ship));
+            assert_eq!(interest.rebuild_count(), 2);
+        }
+
+        fn move_agent(arena: &mut Arena, agent: EntityId, x: f32) {
+            arena.get_mut(agent).unwrap().as_ship_mut().unwrap().transform.position =
+                Vec2::new(x, 0.0);
+        }
+
+        #[test]
+        fn moving_within_cell_reuses_selection() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(&mut arena, vec![track_at(1, 500.0), track_at(2, 50.0)]);
+
+            let mut interest = InterestManager::default();
+            let _ = interest.contacts_for(&arena, agent, 1);
+            move_agent(&mut arena, agent, 40.0);
+            let contacts = interest.contacts_for(&arena, agent, 1);
+
+            assert_eq!(contacts[0].target_id, EntityId::new(2));
+            // Kinematics follow the agent even when the selection is reused
+            assert!((contacts[0].distance - 10.0).abs() < 1e-4);
+            assert_eq!(interest.rebuild_count(), 1);
+        }
+
+        #[test]
+        fn moving_to_another_cell_selects_again() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(&mut arena, vec![track_at(1, 500.0), track_at(2, 50.0)]);
+
+            let mut interest = InterestManager::default();
+            let _ = interest.contacts_for(&arena, agent, 1);
+            move_agent(&mut arena, agent, 450.0);
+
+            assert_eq!(interest.contacts_for(&arena, agent, 1)[0].target_id, EntityId::new(1));
+            assert_eq!(interest.rebuild_count(), 2);
+        }
+
+        #[test]
+        fn changing_sort_key_clears_caches() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(&mut arena, vec![track_at(1, 100.0)]);
+
+            let mut interest = InterestManager::default();
+            let _ = interest.contacts_for(&arena, agent, 4);
+            interest.set_sort_key(ContactSortKey::Threat);
+
+            assert_eq!(interest.cached_agents(), 0);
+        }
+
+        #[test]
+        fn retain_existing_drops_despawned_agents() {
+            let mut arena = Arena::new();
+            let agent = spawn_with_tracks(&mut arena, vec![track_at(1, 100.0)]);
+
+            let mut interest = InterestManager::default();
+            let _ = interest.contacts_for(&arena, agent, 4);
+            arena.despawn(agent);
+            interest.retain_existing(&arena);
+
+            assert_eq!(interest.cached_agents(), 0);
+        }
+    }
+}
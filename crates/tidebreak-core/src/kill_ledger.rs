@@ -0,0 +1,157 @@
+//! Kill, assist and damage tallies per controller.
+//!
+//! The `CombatResolver` credits every point of hull damage dealt to a
+//! hostile entity (one on another team, or on none) to the damaging
+//! entity, both on the target (see [`CombatState::damage_by_source`]) and
+//! in the [`KillLedger`] under the damaging entity's controller. When an
+//! entity is destroyed, the entity that last damaged it is credited with
+//! the kill and every other entity that damaged it with an assist; the
+//! ledger counts one kill for the destroyer's controller and one assist for
+//! each other controller among the assisting entities.
+//!
+//! Damage and kills by entities without a controller are reported in
+//! `EntityDestroyed` events but not tallied.
+//!
+//! ```
+//! use tidebreak_core::entity::ControllerId;
+//! use tidebreak_core::kill_ledger::KillLedger;
+//!
+//! let ledger = KillLedger::new();
+//! ledger.record_damage(ControllerId::Agent(0), 40.0);
+//! ledger.record_damage(ControllerId::Agent(1), 60.0);
+//! ledger.record_kill(Some(ControllerId::Agent(1)), [ControllerId::Agent(0)]);
+//!
+//! let tally = ledger.tally(ControllerId::Agent(0));
+//! assert_eq!((tally.kills, tally.assists, tally.damage), (0, 1, 40.0));
+//! assert_eq!(ledger.tally(ControllerId::Agent(1)).kills, 1);
+//! ```
+//!
+//! [`CombatState::damage_by_source`]:
+//!     crate::entity::components::CombatState::damage_by_source
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::ControllerId;
+
+/// Kills, assists and damage credited to one controller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ControllerTally {
+    /// Hostile entities destroyed
+    pub kills: u32,
+    /// Hostile entities destroyed by another controller after taking
+    /// damage from this one
+    pub assists: u32,
+    /// Hull damage dealt to hostile entities
+    pub damage: f32,
+}
+
+/// Running tallies per controller, shared with the `CombatResolver`.
+#[derive(Debug, Default)]
+pub struct KillLedger {
+    tallies: Mutex<BTreeMap<ControllerId, ControllerTally>>,
+}
+
+impl KillLedger {
+    /// Creates an empty ledger.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Credits `amount` of hull damage to `controller`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn record_damage(&self, controller: ControllerId, amount: f32) {
+        let mut tallies = self.tallies.lock().unwrap();
+        tallies.entry(controller).or_default().damage += amount;
+    }
+
+    /// Credits a kill to the destroyer's controller and an assist to each
+    /// distinct other controller in `assists`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn record_kill(
+        &self,
+        destroyer: Option<ControllerId>,
+        assists: impl IntoIterator<Item = ControllerId>,
+    ) {
+        let assists: BTreeSet<ControllerId> =
+            assists.into_iter().filter(|&c| Some(c) != destroyer).collect();
+        let mut tallies = self.tallies.lock().unwrap();
+        if let Some(destroyer) = destroyer {
+            tallies.entry(destroyer).or_default().kills += 1;
+        }
+        for controller in assists {
+            tallies.entry(controller).or_default().assists += 1;
+        }
+    }
+
+    /// Returns the tally of `controller` (all zeros if it has none).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn tally(&self, controller: ControllerId) -> ControllerTally {
+        self.tallies.lock().unwrap().get(&controller).copied().unwrap_or_default()
+    }
+
+    /// Returns the tallies of every controller credited with anything.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn tallies(&self) -> BTreeMap<ControllerId, ControllerTally> {
+        self.tallies.lock().unwrap().clone()
+    }
+
+    /// Replaces every tally, e.g. to resume from a checkpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn restore(&self, tallies: BTreeMap<ControllerId, ControllerTally>) {
+        *self.tallies.lock().unwrap() = tallies;
+    }
+
+    /// Drops every tally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn clear(&self) {
+        self.tallies.lock().unwrap().clear();
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assists_count_once_per_controller() {
+        let ledger = KillLedger::new();
+        let (a, b) = (ControllerId::Agent(0), ControllerId::Scripted(0));
+        ledger.record_kill(Some(a), [a, b, b]);
+        ledger.record_kill(None, [a]);
+
+        assert_eq!(ledger.tally(a).kills, 1);
+        assert_eq!(ledger.tally(a).assists, 1);
+        assert_eq!(ledger.tally(b).assists, 1);
+        assert_eq!(ledger.tallies().len(), 2);
+        ledger.clear();
+        assert_eq!(ledger.tally(a), ControllerTally::default());
+    }
+}
@@ -0,0 +1,412 @@
+//! Self-play league: frozen opponents chosen per episode.
+//!
+//! A [`League`] holds named opponent snapshots ([`ScriptParams`] for a
+//! [`ScriptedPolicy`]), the learner's record against each, and a
+//! [`Selection`] rule. [`League::select`] picks the next episode's
+//! opponent from the episode seed and the league state alone, so a run is
+//! reproducible from its seeds and a checkpointed league:
+//!
+//! - `round_robin`: opponents in file order, one per episode.
+//! - `uniform`: uniformly at random.
+//! - `pfsp`: prioritized fictitious self-play. Each opponent is weighted
+//!   by the learner's win rate `p` against it (0.5 before any games):
+//!   `(1 - p)^exponent` for `hard` weighting, which favors opponents the
+//!   learner loses to, or `p * (1 - p)` for `variance`, which favors even
+//!   matches. If every weight is zero the draw is uniform.
+//!
+//! Leagues are stored as TOML; the records are part of the file, so saving
+//! with [`League::to_toml`] checkpoints the league state.
+//!
+//! ```toml
+//! selection = { mode = "pfsp", weighting = "hard", exponent = 2.0 }
+//!
+//! [[opponents]]
+//! name = "brawler-v1"
+//! params = { preferred_range = 1000.0 }
+//!
+//! [[opponents]]
+//! name = "kiter-v2"
+//! params = { preferred_range = 6000.0, attack_angle = 1.57 }
+//! games = 12
+//! wins = 9.0
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::entity::TeamId;
+//! use tidebreak_core::league::League;
+//!
+//! let mut league = League::from_toml(r#"
+//!     selection = { mode = "round_robin" }
+//!     [[opponents]]
+//!     name = "a"
+//!     [[opponents]]
+//!     name = "b"
+//! "#).unwrap();
+//!
+//! assert_eq!(league.select(7).name, "a");
+//! assert_eq!(league.select(7).name, "b");
+//! league.record("a", 1.0).unwrap();
+//! assert_eq!(league.opponent("a").unwrap().win_rate(), 1.0);
+//!
+//! let policy = league.opponent("b").unwrap().policy(TeamId::new(1));
+//! assert_eq!(policy.name(), "b");
+//! ```
+
+use std::collections::BTreeSet;
+use std::io;
+use std::path::Path;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::entity::TeamId;
+use crate::plugins::{ScriptParams, ScriptedPolicy};
+
+/// Errors from loading or updating a league.
+#[derive(Debug, Error)]
+pub enum LeagueError {
+    /// The league file could not be read.
+    #[error("failed to read league: {0}")]
+    Io(#[from] io::Error),
+    /// The league is not valid TOML or has unknown keys.
+    #[error("invalid league: {0}")]
+    Parse(String),
+    /// The league has no opponents.
+    #[error("league has no opponents")]
+    Empty,
+    /// Two opponents share a name.
+    #[error("duplicate opponent '{0}'")]
+    DuplicateOpponent(String),
+    /// No opponent has the given name.
+    #[error("unknown opponent '{0}'")]
+    UnknownOpponent(String),
+    /// A result was outside 0-1.
+    #[error("result must be between 0 (loss) and 1 (win), got {0}")]
+    InvalidResult(f32),
+}
+
+/// How [`Selection::Pfsp`] weights an opponent from the learner's win rate
+/// against it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PfspWeighting {
+    /// `(1 - p)^exponent`: favor opponents the learner loses to.
+    #[default]
+    Hard,
+    /// `p * (1 - p)`: favor evenly matched opponents.
+    Variance,
+}
+
+/// Rule choosing each episode's opponent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Selection {
+    /// Every opponent in turn.
+    #[default]
+    RoundRobin,
+    /// Uniformly at random.
+    Uniform,
+    /// Prioritized fictitious self-play.
+    Pfsp {
+        /// Weighting function
+        #[serde(default)]
+        weighting: PfspWeighting,
+        /// Exponent of `hard` weighting
+        #[serde(default = "default_exponent")]
+        exponent: f32,
+    },
+}
+
+const fn default_exponent() -> f32 {
+    2.0
+}
+
+/// A frozen opponent and the learner's record against it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LeagueOpponent {
+    /// Unique snapshot name
+    pub name: String,
+    /// Frozen behavior
+    #[serde(default)]
+    pub params: ScriptParams,
+    /// Episodes the learner has played against it
+    #[serde(default)]
+    pub games: u32,
+    /// Learner's summed results (1 per win, 0.5 per draw)
+    #[serde(default)]
+    pub wins: f32,
+}
+
+impl LeagueOpponent {
+    /// Creates an opponent with no games played.
+    #[must_use]
+    pub fn new(name: impl Into<String>, params: ScriptParams) -> Self {
+        Self {
+            name: name.into(),
+            params,
+            games: 0,
+            wins: 0.0,
+        }
+    }
+
+    /// Returns the learner's win rate against this opponent, or 0.5 before
+    /// any games.
+    #[must_use]
+    pub fn win_rate(&self) -> f32 {
+        if self.games == 0 {
+            0.5
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            let games = self.games as f32;
+            self.wins / games
+        }
+    }
+
+    /// Returns a plugin playing this opponent with the ships of `team`.
+    #[must_use]
+    pub fn policy(&self, team: TeamId) -> ScriptedPolicy {
+        ScriptedPolicy::new(self.name.clone(), self.params, team)
+    }
+}
+
+/// Opponent pool with per-episode selection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct League {
+    /// Selection rule
+    #[serde(default)]
+    pub selection: Selection,
+    /// Episodes selected so far
+    #[serde(default)]
+    pub episodes: u64,
+    /// Opponent pool, in file order
+    pub opponents: Vec<LeagueOpponent>,
+}
+
+impl League {
+    /// Creates a league over `opponents`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no opponents or two share a name.
+    pub fn new(selection: Selection, opponents: Vec<LeagueOpponent>) -> Result<Self, LeagueError> {
+        let league = Self {
+            selection,
+            episodes: 0,
+            opponents,
+        };
+        league.validate()?;
+        Ok(league)
+    }
+
+    /// Parses a league from TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML is malformed, has unknown keys, or
+    /// fails validation.
+    pub fn from_toml(text: &str) -> Result<Self, LeagueError> {
+        let league: Self =
+            toml::from_str(text).map_err(|e| LeagueError::Parse(e.message().to_string()))?;
+        league.validate()?;
+        Ok(league)
+    }
+
+    /// Loads a league from a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LeagueError> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Serializes the league, records included, to TOML.
+    ///
+    /// # Panics
+    ///
+    /// Panics only if TOML serialization fails, which the league's plain-data
+    /// fields rule out.
+    #[must_use]
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("league serializes to TOML")
+    }
+
+    /// Checks that there is an opponent and that names are unique.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem found.
+    pub fn validate(&self) -> Result<(), LeagueError> {
+        if self.opponents.is_empty() {
+            return Err(LeagueError::Empty);
+        }
+        let mut names = BTreeSet::new();
+        for opponent in &self.opponents {
+            if !names.insert(opponent.name.as_str()) {
+                return Err(LeagueError::DuplicateOpponent(opponent.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the opponent named `name`.
+    #[must_use]
+    pub fn opponent(&self, name: &str) -> Option<&LeagueOpponent> {
+        self.opponents.iter().find(|o| o.name == name)
+    }
+
+    /// Picks the opponent for the next episode and counts the episode.
+    ///
+    /// Depends only on `seed` and the league state, so replaying the same
+    /// seeds against the same checkpoint picks the same opponents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the league has no opponents, which [`validate`](Self::validate)
+    /// rules out.
+    pub fn select(&mut self, seed: u64) -> &LeagueOpponent {
+        let index = self.select_index(seed);
+        self.episodes += 1;
+        &self.opponents[index]
+    }
+
+    fn select_index(&self, seed: u64) -> usize {
+        let count = self.opponents.len();
+        let weights: Vec<f32> = match self.selection {
+            Selection::RoundRobin => {
+                #[allow(clippy::cast_possible_truncation)]
+                return (self.episodes % count as u64) as usize;
+            }
+            Selection::Uniform => vec![1.0; count],
+            Selection::Pfsp {
+                weighting,
+                exponent,
+            } => self
+                .opponents
+                .iter()
+                .map(|o| {
+                    let p = o.win_rate();
+                    match weighting {
+                        PfspWeighting::Hard => (1.0 - p).powf(exponent),
+                        PfspWeighting::Variance => p * (1.0 - p),
+                    }
+                })
+                .collect(),
+        };
+
+        // Key the draw on the episode too, so reused seeds still rotate
+        let mut rng = ChaCha8Rng::seed_from_u64(seed ^ self.episodes.rotate_left(32));
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 || !total.is_finite() {
+            return rng.gen_range(0..count);
+        }
+        let mut draw = rng.gen::<f32>() * total;
+        for (index, weight) in weights.iter().enumerate() {
+            if draw < *weight {
+                return index;
+            }
+            draw -= weight;
+        }
+        // Rounding left the draw past the end; take the last weighted one
+        weights.iter().rposition(|w| *w > 0.0).unwrap_or(count - 1)
+    }
+
+    /// Records the learner's `result` against `name`: 1 for a win, 0.5 for
+    /// a draw, 0 for a loss.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such opponent or `result` is outside
+    /// 0-1.
+    pub fn record(&mut self, name: &str, result: f32) -> Result<(), LeagueError> {
+        if !(0.0..=1.0).contains(&result) {
+            return Err(LeagueError::InvalidResult(result));
+        }
+        let opponent = self
+            .opponents
+            .iter_mut()
+            .find(|o| o.name == name)
+            .ok_or_else(|| LeagueError::UnknownOpponent(name.to_string()))?;
+        opponent.games += 1;
+        opponent.wins += result;
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn league(selection: Selection) -> League {
+        let opponents = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| LeagueOpponent::new(name, ScriptParams::default()))
+            .collect();
+        League::new(selection, opponents).unwrap()
+    }
+
+    fn picks(league: &mut League, seeds: std::ops::Range<u64>) -> Vec<String> {
+        seeds.map(|seed| league.select(seed).name.clone()).collect()
+    }
+
+    #[test]
+    fn selection_is_deterministic_from_seed_and_state() {
+        let pfsp = Selection::Pfsp {
+            weighting: PfspWeighting::Hard,
+            exponent: 2.0,
+        };
+        let (mut first, mut second) = (league(pfsp), league(pfsp));
+        assert_eq!(picks(&mut first, 0..50), picks(&mut second, 0..50));
+
+        let mut round_robin = league(Selection::RoundRobin);
+        assert_eq!(picks(&mut round_robin, 0..4), ["a", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn hard_pfsp_avoids_beaten_opponents() {
+        let mut league = league(Selection::Pfsp {
+            weighting: PfspWeighting::Hard,
+            exponent: 2.0,
+        });
+        for _ in 0..10 {
+            league.record("a", 1.0).unwrap();
+            league.record("b", 1.0).unwrap();
+        }
+        assert!(picks(&mut league, 0..20).iter().all(|name| name == "c"));
+        assert!(matches!(
+            league.record("z", 1.0),
+            Err(LeagueError::UnknownOpponent(_))
+        ));
+    }
+
+    #[test]
+    fn toml_round_trip_keeps_records() {
+        let mut league = league(Selection::Pfsp {
+            weighting: PfspWeighting::Variance,
+            exponent: 2.0,
+        });
+        league.select(3);
+        league.record("b", 0.5).unwrap();
+        let restored = League::from_toml(&league.to_toml()).unwrap();
+        assert_eq!(restored, league);
+
+        assert!(matches!(
+            League::from_toml("opponents = []"),
+            Err(LeagueError::Empty)
+        ));
+        assert!(matches!(
+            League::from_toml("[[opponents]]\nname = \"a\"\n[[opponents]]\nname = \"a\""),
+            Err(LeagueError::DuplicateOpponent(_))
+        ));
+    }
+}
@@ -32,25 +32,81 @@ pub use murk;
 
 // Core modules
 pub mod arena;
+pub mod attachment;
+pub mod balance;
+pub mod battle_log;
+pub mod codec;
+pub mod comms;
+pub mod config;
+pub mod currents;
+pub mod debugger;
 pub mod entity;
+pub mod environment;
+pub mod floating_origin;
+pub mod geofence;
+mod grid;
+pub mod interest;
+pub mod kill_ledger;
+pub mod league;
+pub mod logistics;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod orders;
 pub mod output;
 pub mod plugin;
 pub mod plugins;
+pub mod proximity;
+#[cfg(feature = "replay")]
+pub mod replay;
 pub mod resolver;
+pub mod rng_audit;
+pub mod scenario;
+pub mod schema;
 pub mod simulation;
+pub mod steering;
+pub mod team_fields;
+pub mod team_observation;
+pub mod trigger;
+#[cfg(feature = "viz")]
+pub mod viz;
+pub mod watchdog;
+pub mod wind;
 pub mod world_view;
+pub mod wreckage;
 
 // Placeholder modules - to be implemented
 // pub mod contracts;
 
 // Re-exports for convenience
-pub use arena::{Arena, SpatialIndex};
+pub use arena::{
+    Arena, ArenaDelta, BoundaryPolicy, DeltaError, SpatialIndex, SpawnError, WorldBounds,
+};
+pub use balance::{BalanceConfig, BalanceEvaluator, BalanceReport, TeamBalance};
+pub use comms::{CommsConfig, CommsError, Intent, IntentChannel, JammingZone};
+pub use currents::{CurrentField, DriftCoupling};
+pub use environment::{ClockReading, DayPhase, Environment, OccupancyField, SmokeField, WorldClock};
+pub use floating_origin::FloatingOrigin;
+pub use kill_ledger::{ControllerTally, KillLedger};
+pub use logistics::{PendingTransfer, SupplyLedger, TransferConfig};
 pub use output::PluginId;
 pub use plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration, PluginRegistry};
-pub use plugins::{MovementPlugin, ProjectilePlugin, SensorPlugin, WeaponPlugin};
-pub use resolver::{CombatResolver, EventResolver, PhysicsResolver, Resolver};
-pub use simulation::Simulation;
+pub use plugins::{
+    MovementPlugin, OrderFollowerPlugin, ProjectilePlugin, ProximityPlugin, SensorPlugin,
+    ThreatEvaluationPlugin, WeaponPlugin,
+};
+pub use resolver::{
+    AggregateCombatResolver, ClassificationResolver, CombatResolver, EventResolver,
+    LogisticsResolver, MinefieldResolver, OrderResolver, PhysicsResolver, Resolver,
+    SafetyResolver, SmokeResolver, SubmarineResolver, WeaponAssignmentResolver, WeaponResolver,
+};
+pub use rng_audit::{RngAuditLog, RngDraw};
+pub use scenario::{BattlePackage, ScenarioError, ScenarioRandomizer};
+pub use simulation::{CombatModel, Simulation, SimulationConfig};
+pub use team_fields::{SensorCoverage, TeamFieldView};
+pub use team_observation::{TeamObservation, TeamObservationBuilder, Zone};
+pub use watchdog::{PluginBudget, PluginTiming, PluginWatchdog};
 pub use world_view::WorldView;
+pub use wreckage::{WreckageConfig, WreckageSystem};
 
 // Test modules
 #[cfg(test)]
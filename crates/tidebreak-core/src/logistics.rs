@@ -0,0 +1,335 @@
+//! Cargo transfers between ships and supply depots.
+//!
+//! A transfer moves fuel or ammunition from one entity's supplies (a ship's
+//! inventory or a depot platform's stockpile) to another's. Requesting a
+//! transfer records it in the arena's [`SupplyLedger`]; it completes only
+//! after the two entities have stayed alongside each other for
+//! `required_ticks` consecutive ticks.
+//!
+//! # Alongside Rules
+//!
+//! Two entities are alongside on a tick when:
+//!
+//! - both exist, are not destroyed and hold supplies
+//! - they are within `range` of each other
+//! - their velocities differ by at most `max_relative_speed` (platforms
+//!   count as stationary)
+//!
+//! Drifting apart resets the count. Pending transfers whose entities are
+//! despawned are dropped. On completion the amount moved is limited by
+//! what the source holds and what the receiver has room for.
+//!
+//! Transfers are resolved by the
+//! [`LogisticsResolver`](crate::resolver::LogisticsResolver), which reports
+//! each completed transfer as a `CargoTransferred` event.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::{Cargo, EntityInner, EntityTag, ShipComponents};
+//! use glam::Vec2;
+//!
+//! let mut arena = Arena::new();
+//! let a = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)));
+//! let b = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::at_position(Vec2::new(50.0, 0.0), 0.0)));
+//!
+//! assert!(arena.logistics_mut().request(a, b, Cargo::Fuel(100.0)));
+//! assert!(arena.logistics().is_alongside(&arena, a, b));
+//! ```
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::entity::{Cargo, Entity, EntityId, EntityInner};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Configuration for cargo transfers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TransferConfig {
+    /// Maximum distance between the two entities.
+    pub range: f32,
+    /// Maximum difference between their velocities (m/s).
+    pub max_relative_speed: f32,
+    /// Consecutive alongside ticks needed to complete a transfer.
+    pub required_ticks: u64,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            range: 200.0,
+            max_relative_speed: 1.0,
+            required_ticks: 60,
+        }
+    }
+}
+
+// =============================================================================
+// PendingTransfer
+// =============================================================================
+
+/// A requested transfer waiting for its entities to stay alongside.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PendingTransfer {
+    /// Entity giving the cargo.
+    pub from: EntityId,
+    /// Entity receiving the cargo.
+    pub to: EntityId,
+    /// Requested cargo.
+    pub cargo: Cargo,
+    /// Consecutive ticks the two entities have been alongside.
+    pub alongside_ticks: u64,
+}
+
+// =============================================================================
+// SupplyLedger
+// =============================================================================
+
+/// Per-arena record of pending cargo transfers, in request order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SupplyLedger {
+    config: TransferConfig,
+    pending: Vec<PendingTransfer>,
+}
+
+impl SupplyLedger {
+    /// Creates an empty ledger with the given configuration.
+    #[must_use]
+    pub const fn new(config: TransferConfig) -> Self {
+        Self {
+            config,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the transfer configuration.
+    #[must_use]
+    pub const fn config(&self) -> &TransferConfig {
+        &self.config
+    }
+
+    /// Replaces the transfer configuration.
+    pub fn set_config(&mut self, config: TransferConfig) {
+        self.config = config;
+    }
+
+    /// Returns the pending transfers in request order.
+    #[must_use]
+    pub fn pending(&self) -> &[PendingTransfer] {
+        &self.pending
+    }
+
+    /// Requests a transfer of `cargo` from `from` to `to`.
+    ///
+    /// Returns false, leaving the ledger unchanged, if the request is empty,
+    /// targets the source itself, or the same kind of cargo is already
+    /// pending between the two entities (re-requesting keeps the progress
+    /// of the existing transfer).
+    pub fn request(&mut self, from: EntityId, to: EntityId, cargo: Cargo) -> bool {
+        if from == to || cargo.is_empty() {
+            return false;
+        }
+        if self
+            .pending
+            .iter()
+            .any(|p| p.from == from && p.to == to && p.cargo.same_kind(&cargo))
+        {
+            return false;
+        }
+        self.pending.push(PendingTransfer {
+            from,
+            to,
+            cargo,
+            alongside_ticks: 0,
+        });
+        true
+    }
+
+    /// Cancels every pending transfer from `from` to `to`.
+    ///
+    /// Returns the number of transfers cancelled.
+    pub fn cancel(&mut self, from: EntityId, to: EntityId) -> usize {
+        let before = self.pending.len();
+        self.pending.retain(|p| p.from != from || p.to != to);
+        before - self.pending.len()
+    }
+
+    /// Drops every pending transfer involving `id`.
+    pub fn remove_entity(&mut self, id: EntityId) {
+        self.pending.retain(|p| p.from != id && p.to != id);
+    }
+
+    /// Replaces the pending transfers.
+    pub(crate) fn set_pending(&mut self, pending: Vec<PendingTransfer>) {
+        self.pending = pending;
+    }
+
+    /// Returns true if `a` and `b` are alongside each other in `arena`.
+    #[must_use]
+    pub fn is_alongside(&self, arena: &Arena, a: EntityId, b: EntityId) -> bool {
+        let (Some(a), Some(b)) = (
+            arena.get(a).and_then(Self::supplier),
+            arena.get(b).and_then(Self::supplier),
+        ) else {
+            return false;
+        };
+        a.0.distance(b.0) <= self.config.range
+            && a.1.distance(b.1) <= self.config.max_relative_speed
+    }
+
+    /// Returns the position and velocity of a live entity holding supplies.
+    fn supplier(entity: &Entity) -> Option<(Vec2, Vec2)> {
+        entity.inventory()?;
+        match entity.inner() {
+            EntityInner::Ship(c) if !c.combat.is_destroyed() => {
+                Some((c.transform.position, c.physics.velocity))
+            }
+            EntityInner::Platform(c) => Some((c.transform.position, Vec2::ZERO)),
+            _ => None,
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::{AmmoType, InventoryState, StatusFlags};
+    use crate::entity::{EntityTag, PlatformComponents, ShipComponents};
+
+    fn ship(arena: &mut Arena, x: f32) -> EntityId {
+        arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(Vec2::new(x, 0.0), 0.0)),
+        )
+    }
+
+    mod request_tests {
+        use super::*;
+
+        #[test]
+        fn request_records_transfer() {
+            let mut ledger = SupplyLedger::default();
+            let (a, b) = (EntityId::new(0), EntityId::new(1));
+
+            assert!(ledger.request(a, b, Cargo::Fuel(10.0)));
+            assert_eq!(ledger.pending().len(), 1);
+            assert_eq!(ledger.pending()[0].alongside_ticks, 0);
+        }
+
+        #[test]
+        fn duplicate_kind_is_ignored() {
+            let mut ledger = SupplyLedger::default();
+            let (a, b) = (EntityId::new(0), EntityId::new(1));
+
+            assert!(ledger.request(a, b, Cargo::Ammo(AmmoType::Shell, 5)));
+            assert!(!ledger.request(a, b, Cargo::Ammo(AmmoType::Shell, 9)));
+            assert!(ledger.request(a, b, Cargo::Ammo(AmmoType::Missile, 2)));
+            assert!(ledger.request(b, a, Cargo::Ammo(AmmoType::Shell, 5)));
+            assert_eq!(ledger.pending().len(), 3);
+        }
+
+        #[test]
+        fn empty_or_self_requests_are_rejected() {
+            let mut ledger = SupplyLedger::default();
+            let a = EntityId::new(0);
+
+            assert!(!ledger.request(a, EntityId::new(1), Cargo::Fuel(0.0)));
+            assert!(!ledger.request(a, a, Cargo::Fuel(5.0)));
+            assert!(ledger.pending().is_empty());
+        }
+
+        #[test]
+        fn cancel_and_remove_entity() {
+            let mut ledger = SupplyLedger::default();
+            let (a, b, c) = (EntityId::new(0), EntityId::new(1), EntityId::new(2));
+            ledger.request(a, b, Cargo::Fuel(1.0));
+            ledger.request(a, b, Cargo::Ammo(AmmoType::Shell, 1));
+            ledger.request(c, a, Cargo::Fuel(1.0));
+            ledger.request(b, c, Cargo::Fuel(1.0));
+
+            assert_eq!(ledger.cancel(a, b), 2);
+            ledger.remove_entity(a);
+            assert_eq!(ledger.pending().len(), 1);
+            assert_eq!(ledger.pending()[0].from, b);
+        }
+    }
+
+    mod alongside_tests {
+        use super::*;
+
+        #[test]
+        fn close_and_speed_matched_is_alongside() {
+            let mut arena = Arena::new();
+            let a = ship(&mut arena, 0.0);
+            let b = ship(&mut arena, 150.0);
+            let far = ship(&mut arena, 1000.0);
+            let ledger = SupplyLedger::default();
+
+            assert!(ledger.is_alongside(&arena, a, b));
+            assert!(!ledger.is_alongside(&arena, a, far));
+        }
+
+        #[test]
+        fn speed_mismatch_is_not_alongside() {
+            let mut arena = Arena::new();
+            let a = ship(&mut arena, 0.0);
+            let b = ship(&mut arena, 50.0);
+            arena
+                .get_mut(b)
+                .unwrap()
+                .as_ship_mut()
+                .unwrap()
+                .physics
+                .velocity = Vec2::new(5.0, 0.0);
+
+            assert!(!SupplyLedger::default().is_alongside(&arena, a, b));
+        }
+
+        #[test]
+        fn depot_counts_as_stationary() {
+            let mut arena = Arena::new();
+            let depot = arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(
+                    PlatformComponents::at_position(Vec2::ZERO)
+                        .with_stockpile(InventoryState::new(5000.0)),
+                ),
+            );
+            let plain = arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(PlatformComponents::at_position(Vec2::ZERO)),
+            );
+            let a = ship(&mut arena, 10.0);
+            let ledger = SupplyLedger::default();
+
+            assert!(ledger.is_alongside(&arena, depot, a));
+            assert!(!ledger.is_alongside(&arena, plain, a));
+        }
+
+        #[test]
+        fn destroyed_ship_is_not_alongside() {
+            let mut arena = Arena::new();
+            let a = ship(&mut arena, 0.0);
+            let b = ship(&mut arena, 10.0);
+            arena
+                .get_mut(b)
+                .unwrap()
+                .as_ship_mut()
+                .unwrap()
+                .combat
+                .status_flags
+                .insert(StatusFlags::DESTROYED);
+
+            assert!(!SupplyLedger::default().is_alongside(&arena, a, b));
+        }
+    }
+}
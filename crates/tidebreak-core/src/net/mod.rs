@@ -0,0 +1,309 @@
+//! Server-authoritative lockstep networking (feature `net`).
+//!
+//! Every peer runs its own [`Simulation`](crate::simulation::Simulation)
+//! from the same seed and scenario. Only commands cross the network:
+//!
+//! 1. At tick `T` each client submits its commands for tick `T + k`, where
+//!    `k` is the server's input delay.
+//! 2. The server orders the submissions for a tick (by peer ID, then
+//!    submission order) and broadcasts the ordered list.
+//! 3. Every peer, the server included, steps with exactly that list via
+//!    [`Simulation::step_with_commands`](crate::simulation::Simulation::step_with_commands)
+//!    and reports its [`state_hash`](crate::arena::Arena::state_hash). The
+//!    server compares each client's hash against its own and reports a
+//!    [`NetError::Desync`] on mismatch. The hash, like the trace IDs and
+//!    RNG seeds derived from the master seed, is FNV-1a with a fixed byte
+//!    order, so peers on different platforms or toolchains agree.
+//!
+//! The ordering logic ([`TickBuffer`]) is independent of the transport. The
+//! provided transport ([`LockstepServer`], [`LockstepClient`]) uses blocking
+//! TCP with length-prefixed JSON [`Message`]s.
+//!
+//! ```no_run
+//! use tidebreak_core::net::{LockstepClient, LockstepServer};
+//! use tidebreak_core::simulation::Simulation;
+//!
+//! let mut server = LockstepServer::bind("127.0.0.1:9100", Simulation::new(42), 2)?;
+//! std::thread::spawn(|| {
+//!     let mut client = LockstepClient::connect("127.0.0.1:9100", Simulation::new(42))?;
+//!     for _ in 0..100 {
+//!         client.step(Vec::new())?;
+//!     }
+//!     Ok::<_, tidebreak_core::net::NetError>(())
+//! });
+//! server.accept_peers(1)?;
+//! for _ in 0..100 {
+//!     server.step()?;
+//! }
+//! # Ok::<(), tidebreak_core::net::NetError>(())
+//! ```
+
+mod tcp;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::output::Command;
+
+pub use tcp::{Connection, LockstepClient, LockstepServer};
+
+// =============================================================================
+// Protocol
+// =============================================================================
+
+/// Identifier the server assigns to a connected client.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct PeerId(u32);
+
+impl PeerId {
+    /// Creates a peer ID from a raw value.
+    #[must_use]
+    pub const fn new(id: u32) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw value.
+    #[must_use]
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for PeerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A message exchanged between server and clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Message {
+    /// Server to client on connect.
+    Welcome {
+        /// ID assigned to the client
+        peer: PeerId,
+        /// Ticks between submitting and executing a command
+        input_delay: u64,
+        /// Server tick the client must start from
+        tick: u64,
+        /// Server state hash at that tick
+        hash: u64,
+    },
+    /// Client to server: commands to execute on `tick`.
+    Submit {
+        /// Tick the commands are for
+        tick: u64,
+        /// Commands, in the client's order
+        commands: Vec<Command>,
+    },
+    /// Server to clients: the ordered commands for `tick`.
+    Commands {
+        /// Tick the commands are for
+        tick: u64,
+        /// Commands, in execution order
+        commands: Vec<Command>,
+    },
+    /// Client to server: state hash after stepping `tick`.
+    Hash {
+        /// Tick that was stepped
+        tick: u64,
+        /// `state_hash` after the step
+        hash: u64,
+    },
+}
+
+/// Errors produced by the lockstep layer.
+#[derive(Debug, Error)]
+pub enum NetError {
+    /// The transport failed.
+    #[error("network I/O failed: {0}")]
+    Io(#[from] io::Error),
+    /// A message could not be encoded or decoded.
+    #[error("malformed message: {0}")]
+    Codec(#[from] serde_json::Error),
+    /// A peer sent a message that does not fit the protocol state.
+    #[error("unexpected message from peer {peer}: {message}")]
+    Protocol {
+        /// Offending peer (the server is reported as peer 0 to clients).
+        peer: PeerId,
+        /// Description of the violation.
+        message: String,
+    },
+    /// A submission arrived for a tick whose commands are already fixed.
+    #[error("peer {peer} submitted commands for tick {tick}, which is already closed")]
+    LateSubmission {
+        /// Submitting peer.
+        peer: PeerId,
+        /// Tick of the submission.
+        tick: u64,
+    },
+    /// The simulation has an active plugin watchdog, whose wall-clock
+    /// budget would desync the peers.
+    #[error("cannot run lockstep with a plugin budget or disabled plugins")]
+    WatchdogActive,
+    /// A peer's state diverged from the server's.
+    #[error("peer {peer} desynced at tick {tick}: expected hash {expected:#x}, got {actual:#x}")]
+    Desync {
+        /// Diverged peer.
+        peer: PeerId,
+        /// Tick after which the hashes differ.
+        tick: u64,
+        /// Server hash.
+        expected: u64,
+        /// Peer hash.
+        actual: u64,
+    },
+}
+
+// =============================================================================
+// TickBuffer
+// =============================================================================
+
+/// Server-side collection and ordering of submitted commands.
+///
+/// Submissions for a tick can arrive in any order; [`close`](Self::close)
+/// returns them ordered by peer ID, then by submission order, so the result
+/// is independent of network timing.
+#[derive(Debug, Clone, Default)]
+pub struct TickBuffer {
+    /// Next tick to be closed; earlier ticks reject submissions.
+    next_tick: u64,
+    pending: BTreeMap<u64, BTreeMap<PeerId, Vec<Command>>>,
+}
+
+impl TickBuffer {
+    /// Creates a buffer whose first open tick is `tick`.
+    #[must_use]
+    pub fn starting_at(tick: u64) -> Self {
+        Self {
+            next_tick: tick,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the next tick to be closed.
+    #[must_use]
+    pub const fn next_tick(&self) -> u64 {
+        self.next_tick
+    }
+
+    /// Queues commands from `peer` for `tick`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NetError::LateSubmission`] if `tick` is already closed.
+    pub fn submit(
+        &mut self,
+        peer: PeerId,
+        tick: u64,
+        commands: Vec<Command>,
+    ) -> Result<(), NetError> {
+        if tick < self.next_tick {
+            return Err(NetError::LateSubmission { peer, tick });
+        }
+        self.pending
+            .entry(tick)
+            .or_default()
+            .entry(peer)
+            .or_default()
+            .extend(commands);
+        Ok(())
+    }
+
+    /// Closes the next tick and returns its commands in execution order.
+    ///
+    /// Returns the closed tick with the commands.
+    pub fn close(&mut self) -> (u64, Vec<Command>) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        let commands = self
+            .pending
+            .remove(&tick)
+            .map(|peers| peers.into_values().flatten().collect())
+            .unwrap_or_default();
+        (tick, commands)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::EntityId;
+    use glam::Vec2;
+
+    fn heading(target: u64, heading: f32) -> Command {
+        Command::SetHeading {
+            target: EntityId::new(target),
+            heading,
+        }
+    }
+
+    mod tick_buffer_tests {
+        use super::*;
+
+        #[test]
+        fn orders_by_peer_then_submission() {
+            let mut buffer = TickBuffer::default();
+            buffer.submit(PeerId::new(2), 0, vec![heading(2, 0.0)]).unwrap();
+            buffer.submit(PeerId::new(1), 0, vec![heading(1, 0.0)]).unwrap();
+            buffer.submit(PeerId::new(1), 0, vec![heading(1, 1.0)]).unwrap();
+
+            let (tick, commands) = buffer.close();
+
+            assert_eq!(tick, 0);
+            assert_eq!(
+                commands,
+                vec![heading(1, 0.0), heading(1, 1.0), heading(2, 0.0)]
+            );
+        }
+
+        #[test]
+        fn ticks_are_kept_apart() {
+            let mut buffer = TickBuffer::default();
+            buffer.submit(PeerId::new(1), 1, vec![heading(1, 1.0)]).unwrap();
+
+            assert_eq!(buffer.close(), (0, Vec::new()));
+            assert_eq!(buffer.close(), (1, vec![heading(1, 1.0)]));
+        }
+
+        #[test]
+        fn late_submission_is_rejected() {
+            let mut buffer = TickBuffer::starting_at(5);
+
+            let result = buffer.submit(PeerId::new(1), 4, Vec::new());
+
+            assert!(matches!(
+                result,
+                Err(NetError::LateSubmission { tick: 4, .. })
+            ));
+        }
+    }
+
+    mod message_tests {
+        use super::*;
+
+        #[test]
+        fn messages_survive_serialization() {
+            let message = Message::Commands {
+                tick: 3,
+                commands: vec![Command::SetVelocity {
+                    target: EntityId::new(1),
+                    velocity: Vec2::new(1.0, 2.0),
+                }],
+            };
+
+            let json = serde_json::to_string(&message).unwrap();
+            let restored: Message = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(restored, message);
+        }
+    }
+}
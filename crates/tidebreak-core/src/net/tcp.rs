@@ -0,0 +1,523 @@
+//! Blocking TCP transport for lockstep.
+//!
+//! Each [`Message`] is sent as a 4-byte big-endian length followed by that
+//! many bytes of JSON. Both sides block until the other has caught up, so
+//! the slowest peer sets the pace; a timeout (see
+//! [`LockstepServer::with_timeout`]) turns a stalled peer into an error
+//! instead of a hang.
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use super::{Message, NetError, PeerId, TickBuffer};
+use crate::output::Command;
+use crate::simulation::Simulation;
+
+/// Largest accepted message, in bytes.
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+/// Peer ID used for the server's own submissions and errors.
+const SERVER_PEER: PeerId = PeerId::new(0);
+
+// =============================================================================
+// Connection
+// =============================================================================
+
+/// A framed message stream over TCP.
+#[derive(Debug)]
+pub struct Connection {
+    stream: TcpStream,
+}
+
+impl Connection {
+    /// Wraps a connected stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket options cannot be set.
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self { stream })
+    }
+
+    /// Sets the read and write timeout (`None` blocks indefinitely).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket options cannot be set.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.stream.set_read_timeout(timeout)?;
+        self.stream.set_write_timeout(timeout)
+    }
+
+    /// Sends one message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be encoded or written.
+    pub fn send(&mut self, message: &Message) -> Result<(), NetError> {
+        let body = serde_json::to_vec(message)?;
+        let len = u32::try_from(body.len())
+            .ok()
+            .filter(|_| body.len() <= MAX_MESSAGE_BYTES)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        self.stream.write_all(&len.to_be_bytes())?;
+        self.stream.write_all(&body)?;
+        Ok(())
+    }
+
+    /// Receives one message, blocking until it arrives.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the read fails or times out, or if the message
+    /// is oversized or malformed.
+    pub fn recv(&mut self) -> Result<Message, NetError> {
+        let mut len = [0; 4];
+        self.stream.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_MESSAGE_BYTES {
+            return Err(io::Error::from(io::ErrorKind::InvalidData).into());
+        }
+        let mut body = vec![0; len];
+        self.stream.read_exact(&mut body)?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+/// Builds a protocol error for an unexpected message.
+fn unexpected(peer: PeerId, expected: &str, got: &Message) -> NetError {
+    NetError::Protocol {
+        peer,
+        message: format!("expected {expected}, got {got:?}"),
+    }
+}
+
+// =============================================================================
+// Server
+// =============================================================================
+
+/// Authoritative lockstep host.
+///
+/// Owns the reference simulation, orders client submissions and checks
+/// every client's state hash against its own after each tick.
+#[derive(Debug)]
+pub struct LockstepServer {
+    listener: TcpListener,
+    sim: Simulation,
+    input_delay: u64,
+    timeout: Option<Duration>,
+    peers: Vec<(PeerId, Connection)>,
+    buffer: TickBuffer,
+    next_peer: u32,
+}
+
+impl LockstepServer {
+    /// Listens on `addr` (use port 0 for an ephemeral port).
+    ///
+    /// Commands submitted at tick `T` execute on tick `T + input_delay`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NetError::WatchdogActive`] if `sim`'s plugin watchdog
+    /// [`is_active`](crate::watchdog::PluginWatchdog::is_active), or an error
+    /// if the address cannot be bound.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        sim: Simulation,
+        input_delay: u64,
+    ) -> Result<Self, NetError> {
+        if sim.plugin_watchdog().is_active() {
+            return Err(NetError::WatchdogActive);
+        }
+        let listener = TcpListener::bind(addr)?;
+        let buffer = TickBuffer::starting_at(sim.tick());
+        Ok(Self {
+            listener,
+            sim,
+            input_delay,
+            timeout: None,
+            peers: Vec::new(),
+            buffer,
+            next_peer: SERVER_PEER.as_u32() + 1,
+        })
+    }
+
+    /// Sets the read and write timeout for client connections.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the bound address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket address cannot be queried.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Returns the authoritative simulation.
+    #[must_use]
+    pub const fn simulation(&self) -> &Simulation {
+        &self.sim
+    }
+
+    /// Returns the number of connected clients.
+    #[must_use]
+    pub fn peer_count(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Returns the input delay in ticks.
+    #[must_use]
+    pub const fn input_delay(&self) -> u64 {
+        self.input_delay
+    }
+
+    /// Blocks until `count` more clients have connected.
+    ///
+    /// Each client is told the current tick and state hash, and must start
+    /// from an identical simulation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if accepting or greeting a client fails.
+    pub fn accept_peers(&mut self, count: usize) -> Result<(), NetError> {
+        for _ in 0..count {
+            let (stream, _) = self.listener.accept()?;
+            let mut connection = Connection::new(stream)?;
+            connection.set_timeout(self.timeout)?;
+            let peer = PeerId::new(self.next_peer);
+            self.next_peer += 1;
+            connection.send(&Message::Welcome {
+                peer,
+                input_delay: self.input_delay,
+                tick: self.sim.tick(),
+                hash: self.sim.arena().state_hash(),
+            })?;
+            self.peers.push((peer, connection));
+        }
+        Ok(())
+    }
+
+    /// Queues the server's own commands for the tick `input_delay` ahead.
+    ///
+    /// Server commands are ordered before every client's.
+    ///
+    /// # Errors
+    ///
+    /// Never fails for the current tick; the `Result` mirrors
+    /// [`TickBuffer::submit`].
+    pub fn submit(&mut self, commands: Vec<Command>) -> Result<(), NetError> {
+        let tick = self.sim.tick() + self.input_delay;
+        self.buffer.submit(SERVER_PEER, tick, commands)
+    }
+
+    /// Runs one lockstep tick.
+    ///
+    /// Collects every client's submission, broadcasts the ordered commands
+    /// for the current tick, steps the authoritative simulation and verifies
+    /// every client's hash. Returns the state hash after the step.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NetError::Desync`] if a client's hash differs, or an error
+    /// if a client misbehaves or its connection fails.
+    pub fn step(&mut self) -> Result<u64, NetError> {
+        let tick = self.sim.tick();
+        let submit_tick = tick + self.input_delay;
+
+        for (peer, connection) in &mut self.peers {
+            match connection.recv()? {
+                Message::Submit {
+                    tick: submitted,
+                    commands,
+                } if submitted == submit_tick => {
+                    self.buffer.submit(*peer, submitted, commands)?;
+                }
+                other => {
+                    let expected = format!("submit for tick {submit_tick}");
+                    return Err(unexpected(*peer, &expected, &other));
+                }
+            }
+        }
+
+        let (closed, commands) = self.buffer.close();
+        debug_assert_eq!(closed, tick, "tick buffer out of step with simulation");
+        let broadcast = Message::Commands {
+            tick,
+            commands: commands.clone(),
+        };
+        for (_, connection) in &mut self.peers {
+            connection.send(&broadcast)?;
+        }
+
+        self.sim.step_with_commands(&commands);
+        let expected = self.sim.arena().state_hash();
+
+        for (peer, connection) in &mut self.peers {
+            match connection.recv()? {
+                Message::Hash {
+                    tick: stepped,
+                    hash,
+                } if stepped == tick => {
+                    if hash != expected {
+                        return Err(NetError::Desync {
+                            peer: *peer,
+                            tick,
+                            expected,
+                            actual: hash,
+                        });
+                    }
+                }
+                other => {
+                    return Err(unexpected(*peer, &format!("hash for tick {tick}"), &other));
+                }
+            }
+        }
+        Ok(expected)
+    }
+}
+
+// =============================================================================
+// Client
+// =============================================================================
+
+/// Lockstep client running a local replica of the simulation.
+#[derive(Debug)]
+pub struct LockstepClient {
+    connection: Connection,
+    sim: Simulation,
+    peer: PeerId,
+    input_delay: u64,
+}
+
+impl LockstepClient {
+    /// Connects to a server and checks that `sim` matches its state.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NetError::WatchdogActive`] if `sim`'s plugin watchdog is
+    /// active, [`NetError::Desync`] if `sim` differs from the server's
+    /// simulation (wrong tick or hash), or an error if the connection fails.
+    pub fn connect(addr: impl ToSocketAddrs, sim: Simulation) -> Result<Self, NetError> {
+        if sim.plugin_watchdog().is_active() {
+            return Err(NetError::WatchdogActive);
+        }
+        let mut connection = Connection::new(TcpStream::connect(addr)?)?;
+        let (peer, input_delay, tick, hash) = match connection.recv()? {
+            Message::Welcome {
+                peer,
+                input_delay,
+                tick,
+                hash,
+            } => (peer, input_delay, tick, hash),
+            other => return Err(unexpected(SERVER_PEER, "welcome", &other)),
+        };
+
+        let actual = sim.arena().state_hash();
+        if sim.tick() != tick || actual != hash {
+            return Err(NetError::Desync {
+                peer,
+                tick,
+                expected: hash,
+                actual,
+            });
+        }
+        Ok(Self {
+            connection,
+            sim,
+            peer,
+            input_delay,
+        })
+    }
+
+    /// Sets the read and write timeout (`None` blocks indefinitely).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket options cannot be set.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.connection.set_timeout(timeout)
+    }
+
+    /// Returns the ID the server assigned to this client.
+    #[must_use]
+    pub const fn peer(&self) -> PeerId {
+        self.peer
+    }
+
+    /// Returns the input delay in ticks.
+    #[must_use]
+    pub const fn input_delay(&self) -> u64 {
+        self.input_delay
+    }
+
+    /// Returns the local replica.
+    #[must_use]
+    pub const fn simulation(&self) -> &Simulation {
+        &self.sim
+    }
+
+    /// Consumes the client, returning the local replica.
+    #[must_use]
+    pub fn into_simulation(self) -> Simulation {
+        self.sim
+    }
+
+    /// Runs one lockstep tick.
+    ///
+    /// Submits `commands` for the tick `input_delay` ahead, then steps with
+    /// the server's ordered commands for the current tick and reports the
+    /// resulting hash. Returns that hash.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server misbehaves or the connection fails.
+    /// The server drops the connection after detecting a desync, which
+    /// surfaces here as an I/O error on the next step.
+    pub fn step(&mut self, commands: Vec<Command>) -> Result<u64, NetError> {
+        let tick = self.sim.tick();
+        self.connection.send(&Message::Submit {
+            tick: tick + self.input_delay,
+            commands,
+        })?;
+
+        let commands = match self.connection.recv()? {
+            Message::Commands {
+                tick: closed,
+                commands,
+            } if closed == tick => commands,
+            other => {
+                let expected = format!("commands for tick {tick}");
+                return Err(unexpected(SERVER_PEER, &expected, &other));
+            }
+        };
+
+        self.sim.step_with_commands(&commands);
+        let hash = self.sim.arena().state_hash();
+        self.connection.send(&Message::Hash { tick, hash })?;
+        Ok(hash)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityId, EntityInner, EntityTag, ShipComponents};
+    use glam::Vec2;
+    use std::thread;
+
+    fn scenario() -> (Simulation, EntityId) {
+        let mut sim = Simulation::new(7);
+        let ship = sim.arena_mut().spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::default().with_physics(20.0, 1.0)),
+        );
+        (sim, ship)
+    }
+
+    fn local_server() -> LockstepServer {
+        let (sim, _) = scenario();
+        LockstepServer::bind("127.0.0.1:0", sim, 2)
+            .unwrap()
+            .with_timeout(Duration::from_secs(5))
+    }
+
+    #[test]
+    fn peers_stay_in_lockstep() {
+        let mut server = local_server();
+        let addr = server.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let (sim, ship) = scenario();
+            let mut client = LockstepClient::connect(addr, sim).unwrap();
+            let mut hashes = Vec::new();
+            for tick in 0..6 {
+                let commands = if tick == 1 {
+                    vec![Command::SetVelocity {
+                        target: ship,
+                        velocity: Vec2::new(10.0, 0.0),
+                    }]
+                } else {
+                    Vec::new()
+                };
+                hashes.push(client.step(commands).unwrap());
+            }
+            (client.peer(), hashes, client.into_simulation())
+        });
+
+        server.accept_peers(1).unwrap();
+        let hashes: Vec<_> = (0..6).map(|_| server.step().unwrap()).collect();
+        let (peer, client_hashes, client_sim) = client.join().unwrap();
+
+        assert_eq!(peer, PeerId::new(1));
+        assert_eq!(client_hashes, hashes);
+        let ship = client_sim.arena().get(EntityId::new(0)).unwrap().as_ship().unwrap();
+        assert_eq!(ship.physics.velocity, Vec2::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn mismatched_start_is_rejected() {
+        let mut server = local_server();
+        let addr = server.local_addr().unwrap();
+
+        let client = thread::spawn(move || LockstepClient::connect(addr, Simulation::new(7)));
+        server.accept_peers(1).unwrap();
+
+        assert!(matches!(client.join().unwrap(), Err(NetError::Desync { .. })));
+    }
+
+    #[test]
+    fn budgeted_simulation_is_refused() {
+        use crate::watchdog::PluginBudget;
+
+        let budgeted = || {
+            let (mut sim, _) = scenario();
+            sim.plugin_watchdog_mut()
+                .set_budget(Some(PluginBudget::new(Duration::from_millis(1), 1)));
+            sim
+        };
+
+        assert!(matches!(
+            LockstepServer::bind("127.0.0.1:0", budgeted(), 2),
+            Err(NetError::WatchdogActive)
+        ));
+        // Refused before any connection is attempted
+        assert!(matches!(
+            LockstepClient::connect("127.0.0.1:9", budgeted()),
+            Err(NetError::WatchdogActive)
+        ));
+    }
+
+    #[test]
+    fn divergent_client_is_detected() {
+        let mut server = local_server();
+        let addr = server.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let (sim, ship) = scenario();
+            let mut client = LockstepClient::connect(addr, sim).unwrap();
+            // Corrupt the replica behind the server's back
+            let entity = client.sim.arena_mut().get_mut(ship).unwrap();
+            entity.as_ship_mut().unwrap().transform.heading = 1.0;
+            let _ = client.step(Vec::new());
+        });
+
+        server.accept_peers(1).unwrap();
+        let result = server.step();
+        client.join().unwrap();
+
+        assert!(matches!(
+            result,
+            Err(NetError::Desync { tick: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//! Standing orders from commanders to subordinates.
+//!
+//! Hierarchical control splits a team into commanders, which emit
+//! [`Order`] outputs, and subordinates, whose behavior plugins carry those
+//! orders out. The arena's [`OrderBook`] holds each subordinate's standing
+//! order between ticks, so plugins can keep acting on it long after it was
+//! issued.
+//!
+//! # Issuing Rules
+//!
+//! An order is accepted (see [`can_issue`]) when:
+//!
+//! - the commander and the subordinate both exist and are different
+//!   entities
+//! - they are on the same team
+//!
+//! An accepted order replaces the subordinate's standing order; `Cancel`
+//! removes it. Standing orders of despawned subordinates are dropped.
+//!
+//! Orders emitted by plugins are filed by the
+//! [`OrderResolver`](crate::resolver::OrderResolver).
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+//! use tidebreak_core::orders::can_issue;
+//! use tidebreak_core::output::Order;
+//!
+//! let mut arena = Arena::new();
+//! let flagship = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+//! let escort = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+//! arena.set_team(flagship, Some(TeamId::new(0)));
+//! arena.set_team(escort, Some(TeamId::new(0)));
+//!
+//! let order = Order::Screen {
+//!     commander: flagship,
+//!     subordinate: escort,
+//!     protect: flagship,
+//!     distance: 800.0,
+//!     bearing: 0.0,
+//! };
+//! assert!(can_issue(&arena, &order));
+//! arena.orders_mut().issue(order, 0);
+//! assert!(arena.orders().get(escort).is_some());
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::output::Order;
+
+/// Returns true if `order` may be filed in `arena` (see the module docs).
+#[must_use]
+pub fn can_issue(arena: &Arena, order: &Order) -> bool {
+    let (commander, subordinate) = (order.commander(), order.subordinate());
+    if commander == subordinate {
+        return false;
+    }
+    match (arena.get(commander), arena.get(subordinate)) {
+        (Some(commander), Some(subordinate)) => commander.is_friendly_to(subordinate),
+        _ => false,
+    }
+}
+
+/// An order in force for a subordinate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandingOrder {
+    /// The order being carried out.
+    pub order: Order,
+    /// Tick the order was issued on.
+    pub issued_tick: u64,
+}
+
+/// Per-arena record of standing orders, keyed by subordinate.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrderBook {
+    orders: BTreeMap<EntityId, StandingOrder>,
+}
+
+impl OrderBook {
+    /// Creates an empty order book.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Files `order` as its subordinate's standing order.
+    ///
+    /// Replaces any previous order; `Order::Cancel` removes it instead. No
+    /// checks are made; see [`can_issue`].
+    pub fn issue(&mut self, order: Order, tick: u64) {
+        let subordinate = order.subordinate();
+        if matches!(order, Order::Cancel { .. }) {
+            self.orders.remove(&subordinate);
+        } else {
+            self.orders.insert(
+                subordinate,
+                StandingOrder {
+                    order,
+                    issued_tick: tick,
+                },
+            );
+        }
+    }
+
+    /// Returns the standing order of `subordinate`, if any.
+    #[must_use]
+    pub fn get(&self, subordinate: EntityId) -> Option<&StandingOrder> {
+        self.orders.get(&subordinate)
+    }
+
+    /// Iterates over (subordinate, standing order) pairs in ID order.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &StandingOrder)> {
+        self.orders.iter().map(|(id, order)| (*id, order))
+    }
+
+    /// Returns the number of standing orders.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Returns true if no orders are standing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Drops the standing order of a despawned entity.
+    pub fn remove_entity(&mut self, id: EntityId) {
+        self.orders.remove(&id);
+    }
+
+    /// Drops all standing orders.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+    }
+}
+
+/// Team-wide rules of engagement.
+///
+/// Set per team with
+/// [`Arena::set_rules_of_engagement`](crate::arena::Arena::set_rules_of_engagement);
+/// the weapon and combat resolvers drop `FireWeapon` commands from ships of
+/// a team that holds fire. Reloads are unaffected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RulesOfEngagement {
+    /// Weapons may be fired at will.
+    #[default]
+    WeaponsFree,
+    /// No weapon may be fired.
+    HoldFire,
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::spawn_team_ship;
+    use glam::Vec2;
+
+    fn move_to(commander: EntityId, subordinate: EntityId, x: f32) -> Order {
+        Order::MoveTo {
+            commander,
+            subordinate,
+            destination: Vec2::new(x, 0.0),
+            speed: 0.0,
+        }
+    }
+
+    #[test]
+    fn only_teammates_can_be_ordered() {
+        let mut arena = Arena::new();
+        let commander = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let friend = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let enemy = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+        let loner = spawn_team_ship(&mut arena, Vec2::ZERO, None);
+
+        assert!(can_issue(&arena, &move_to(commander, friend, 0.0)));
+        assert!(!can_issue(&arena, &move_to(commander, enemy, 0.0)));
+        assert!(!can_issue(&arena, &move_to(loner, loner, 0.0)));
+        assert!(!can_issue(&arena, &move_to(commander, commander, 0.0)));
+        assert!(!can_issue(&arena, &move_to(commander, EntityId::new(99), 0.0)));
+    }
+
+    #[test]
+    fn newer_order_replaces_and_cancel_removes() {
+        let (commander, subordinate) = (EntityId::new(1), EntityId::new(2));
+        let mut book = OrderBook::new();
+        book.issue(move_to(commander, subordinate, 10.0), 3);
+        book.issue(move_to(commander, subordinate, 20.0), 5);
+
+        let standing = book.get(subordinate).unwrap();
+        assert_eq!(standing.order, move_to(commander, subordinate, 20.0));
+        assert_eq!(standing.issued_tick, 5);
+        assert_eq!(book.len(), 1);
+
+        book.issue(
+            Order::Cancel {
+                commander,
+                subordinate,
+            },
+            6,
+        );
+        assert!(book.is_empty());
+    }
+}
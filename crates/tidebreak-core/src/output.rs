@@ -10,6 +10,8 @@
 //! - [`Command`]: Direct state change requests (`SetVelocity`, `FireWeapon`, etc.)
 //! - [`Modifier`]: Value modifications (`ApplyDamage`, `ModifyStat`, etc.)
 //! - [`Event`]: Notifications of things that happened (`WeaponFired`, `DamageDealt`, etc.)
+//! - [`Order`]: Directions from a commander to a subordinate (`MoveTo`, `Engage`, etc.)
+//! - [`CustomOutput`]: Domain-specific proposals defined outside this crate
 //!
 //! All outputs are wrapped in [`OutputEnvelope`] which provides causal chain metadata
 //! for debugging, replay, and traceability.
@@ -51,10 +53,14 @@
 use std::borrow::Cow;
 
 use glam::Vec2;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use thiserror::Error;
 
-use crate::entity::components::{StatId, StatusFlags, TrackQuality};
+use crate::entity::components::{
+    AttributeValue, Cargo, DamageType, MineState, StatId, StatusFlags, Subsystem, TrackQuality,
+};
 use crate::entity::EntityId;
 
 // =============================================================================
@@ -78,7 +84,7 @@ use crate::entity::EntityId;
 /// // Runtime creation
 /// let weapon_plugin = PluginId::new("weapon_control");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct PluginId(Cow<'static, str>);
 
@@ -153,7 +159,7 @@ impl From<String> for PluginId {
 ///
 /// assert_eq!(instance.entity_id(), EntityId::new(42));
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct PluginInstanceId {
     entity_id: EntityId,
     plugin_id: PluginId,
@@ -286,8 +292,15 @@ impl From<EventId> for u64 {
 ///
 /// - `SetVelocity`: Change an entity's velocity vector
 /// - `SetHeading`: Change an entity's heading angle
+/// - `SetThrottle`: Order an engine speed, reached at the hull's acceleration
+/// - `SetDepth`: Order a submarine to a depth, reached at its dive rate
 /// - `FireWeapon`: Fire a weapon at a target entity
 /// - `SpawnProjectile`: Create a new projectile entity
+/// - `LayMine`: Drop a mine at the source's position
+/// - `TransferCargo`: Move fuel or ammunition between two entities
+/// - `ReloadWeapon`: Refill a magazine-fed weapon
+/// - `DeploySmoke`: Lay a smoke screen behind a ship
+/// - `InterceptSquadron`: Send a squadron to engage an enemy squadron
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Command {
     /// Set the velocity of an entity.
@@ -304,6 +317,29 @@ pub enum Command {
         /// New heading in radians (counter-clockwise from +X)
         heading: f32,
     },
+    /// Order an engine throttle for an entity.
+    ///
+    /// The entity then gains or sheds speed along its heading at the rates
+    /// of its [`EngineProfile`](crate::entity::EngineProfile) each tick,
+    /// until a `SetVelocity` command takes direct control again.
+    SetThrottle {
+        /// Entity to modify
+        target: EntityId,
+        /// Fraction of max speed, from -1 (full astern) to 1 (full ahead);
+        /// astern is further limited by the engine's reverse limit
+        fraction: f32,
+    },
+    /// Order a submarine to a depth.
+    ///
+    /// The submarine dives or rises toward the ordered depth at its
+    /// [`SubmarineState::dive_rate`](crate::entity::SubmarineState::dive_rate).
+    /// Ignored by entities that are not submarines.
+    SetDepth {
+        /// Submarine to modify
+        target: EntityId,
+        /// Ordered depth in meters below the surface (0 = surfaced)
+        depth: f32,
+    },
     /// Fire a weapon at a target.
     FireWeapon {
         /// Entity firing the weapon
@@ -322,6 +358,62 @@ pub enum Command {
         /// Target position for the projectile
         target_pos: Vec2,
     },
+    /// Lay a mine at the source's current position.
+    ///
+    /// The mine joins the source's team and ignores that team's ships.
+    LayMine {
+        /// Entity laying the mine
+        source: EntityId,
+        /// Fuze and warhead of the new mine
+        mine: MineState,
+    },
+    /// Transfer fuel or ammunition once both entities are alongside.
+    ///
+    /// See [`crate::logistics`] for the alongside rules.
+    TransferCargo {
+        /// Entity giving the cargo
+        from: EntityId,
+        /// Entity receiving the cargo
+        to: EntityId,
+        /// Cargo to transfer
+        cargo: Cargo,
+    },
+    /// Start reloading a magazine-fed weapon.
+    ///
+    /// Ignored if the weapon has no magazine, is already reloading, or its
+    /// magazine is full. Weapons also start reloading on their own when
+    /// their magazine runs dry.
+    ReloadWeapon {
+        /// Entity owning the weapon
+        source: EntityId,
+        /// Weapon slot to reload
+        slot: usize,
+    },
+    /// Lay a smoke screen behind a ship for `duration` ticks.
+    ///
+    /// Uses one [`AmmoType::Smoke`](crate::entity::AmmoType::Smoke) charge.
+    /// Ignored if the ship has none left, is already laying smoke, or is
+    /// a submerged submarine.
+    DeploySmoke {
+        /// Ship laying the smoke
+        source: EntityId,
+        /// Ticks to keep laying smoke
+        duration: u32,
+    },
+    /// Order a squadron to engage an enemy squadron in air-to-air combat.
+    ///
+    /// The order stands until the target is destroyed, the source runs out
+    /// of ordnance or a new order replaces it; the two exchange fire while
+    /// within the source's engagement range (see
+    /// [`AirWing`](crate::entity::AirWing)). The order does not steer the
+    /// source. Ignored unless both entities are live squadrons on
+    /// different teams and the source has an air wing.
+    InterceptSquadron {
+        /// Squadron intercepting
+        source: EntityId,
+        /// Enemy squadron to engage
+        target: EntityId,
+    },
 }
 
 impl Command {
@@ -331,17 +423,52 @@ impl Command {
         match self {
             Self::SetVelocity { target, .. }
             | Self::SetHeading { target, .. }
-            | Self::FireWeapon { target, .. } => Some(*target),
-            Self::SpawnProjectile { .. } => None,
+            | Self::SetThrottle { target, .. }
+            | Self::SetDepth { target, .. }
+            | Self::FireWeapon { target, .. }
+            | Self::InterceptSquadron { target, .. } => Some(*target),
+            Self::TransferCargo { to, .. } => Some(*to),
+            Self::SpawnProjectile { .. }
+            | Self::LayMine { .. }
+            | Self::ReloadWeapon { .. }
+            | Self::DeploySmoke { .. } => None,
         }
     }
 
+    /// Returns `true` for commands that hold a setpoint rather than trigger
+    /// a one-off action.
+    ///
+    /// Sustained commands are repeated on the ticks between decisions (see
+    /// [`SimulationConfig::action_interval`]); one-shot commands such as
+    /// firing or laying a mine are applied only on the decision tick.
+    ///
+    /// [`SimulationConfig::action_interval`]: crate::simulation::SimulationConfig::action_interval
+    #[must_use]
+    pub const fn is_sustained(&self) -> bool {
+        matches!(
+            self,
+            Self::SetVelocity { .. }
+                | Self::SetHeading { .. }
+                | Self::SetThrottle { .. }
+                | Self::SetDepth { .. }
+        )
+    }
+
     /// Returns the source entity for this command, if applicable.
     #[must_use]
     pub const fn source(&self) -> Option<EntityId> {
         match self {
-            Self::FireWeapon { source, .. } | Self::SpawnProjectile { source, .. } => Some(*source),
-            Self::SetVelocity { target, .. } | Self::SetHeading { target, .. } => Some(*target),
+            Self::FireWeapon { source, .. }
+            | Self::SpawnProjectile { source, .. }
+            | Self::LayMine { source, .. }
+            | Self::ReloadWeapon { source, .. }
+            | Self::DeploySmoke { source, .. }
+            | Self::InterceptSquadron { source, .. } => Some(*source),
+            Self::TransferCargo { from, .. } => Some(*from),
+            Self::SetVelocity { target, .. }
+            | Self::SetHeading { target, .. }
+            | Self::SetThrottle { target, .. }
+            | Self::SetDepth { target, .. } => Some(*target),
         }
     }
 }
@@ -355,9 +482,11 @@ impl Command {
 /// # Variants
 ///
 /// - `ApplyDamage`: Reduce an entity's HP
+/// - `ApplyAreaDamage`: Reduce the HP of everything caught in a blast
 /// - `ApplyHealing`: Increase an entity's HP
 /// - `SetStatusFlag`: Enable or disable a status flag
 /// - `ModifyStat`: Add a delta to a stat value
+/// - `SetAttribute`: Set or remove an entry in the attribute store
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Modifier {
     /// Apply damage to an entity.
@@ -366,6 +495,27 @@ pub enum Modifier {
         target: EntityId,
         /// Damage amount (positive value)
         amount: f32,
+        /// Kind of damage, for the target's mitigation resistances
+        #[serde(default)]
+        damage_type: DamageType,
+    },
+    /// Damage every ship and squadron within `radius` of `center`.
+    ///
+    /// Blast damage is always [`DamageType::Explosive`].
+    ///
+    /// Damage is `amount` at the center, attenuated with distance by
+    /// `(1 - distance / radius)^falloff`, so a `falloff` of 0 is uniform, 1
+    /// linear and 2 quadratic. Targets behind solid obstacles (see
+    /// `Environment::is_occluded`) are sheltered.
+    ApplyAreaDamage {
+        /// Center of the blast
+        center: Vec2,
+        /// Radius of the blast in meters
+        radius: f32,
+        /// Damage at the center (positive value)
+        amount: f32,
+        /// Exponent of the attenuation with distance
+        falloff: f32,
     },
     /// Apply healing to an entity.
     ApplyHealing {
@@ -392,17 +542,31 @@ pub enum Modifier {
         /// Delta to add (can be negative)
         delta: f32,
     },
+    /// Set or remove an attribute of an entity.
+    ///
+    /// When several plugins set the same key in one tick, the last output in
+    /// resolution order wins.
+    SetAttribute {
+        /// Entity to modify
+        target: EntityId,
+        /// Attribute key
+        key: String,
+        /// New value, or `None` to remove the attribute
+        value: Option<AttributeValue>,
+    },
 }
 
 impl Modifier {
-    /// Returns the target entity for this modifier.
+    /// Returns the target entity for this modifier, if it has a single one.
     #[must_use]
-    pub const fn target(&self) -> EntityId {
+    pub const fn target(&self) -> Option<EntityId> {
         match self {
             Self::ApplyDamage { target, .. }
             | Self::ApplyHealing { target, .. }
             | Self::SetStatusFlag { target, .. }
-            | Self::ModifyStat { target, .. } => *target,
+            | Self::ModifyStat { target, .. }
+            | Self::SetAttribute { target, .. } => Some(*target),
+            Self::ApplyAreaDamage { .. } => None,
         }
     }
 }
@@ -419,6 +583,23 @@ impl Modifier {
 /// - `DamageDealt`: Damage was applied to an entity
 /// - `EntityDestroyed`: An entity was destroyed
 /// - `ContactDetected`: A sensor detected a contact
+/// - `ThreatAssessed`: A tracked contact was scored for threat
+/// - `WeaponAssigned`: A shooter was assigned to engage a target
+/// - `EntityOutOfBounds`: An entity left the world bounds and was removed
+/// - `PluginBudgetExceeded`: A plugin instance was disabled by the watchdog
+/// - `MineDetonated`: A mine was set off by a passing ship
+/// - `CargoTransferred`: Fuel or ammunition changed hands
+/// - `ReloadStarted`: A weapon began reloading its magazine
+/// - `ReloadCompleted`: A weapon finished reloading its magazine
+/// - `EnteredRange`: An entity came within a proximity threshold
+/// - `LeftRange`: An entity moved beyond a proximity threshold
+/// - `ShipDelivered`: A convoy ship reached its destination
+/// - `ShellSplash`: A gun round missed and fell into the sea
+/// - `LockAcquired`: A projectile seeker locked onto a target
+/// - `LockLost`: A projectile seeker lost its target
+/// - `GeofenceViolated`: An entity entered one of its keep-out areas
+/// - `SubsystemDestroyed`: A weapon mount or sensor suite was knocked out
+/// - `EntityExpired`: An entity reached the end of its lifetime and was removed
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Event {
     /// A weapon was fired.
@@ -443,6 +624,9 @@ pub enum Event {
         entity: EntityId,
         /// Entity that destroyed it (if known)
         destroyer: Option<EntityId>,
+        /// Other entities that damaged it, most damage first
+        #[serde(default)]
+        assists: Vec<EntityId>,
     },
     /// A contact was detected by sensors.
     ContactDetected {
@@ -453,6 +637,141 @@ pub enum Event {
         /// Quality of the detection
         quality: TrackQuality,
     },
+    /// A tracked contact was assessed for threat.
+    ThreatAssessed {
+        /// Entity that performed the assessment
+        observer: EntityId,
+        /// Entity that was assessed
+        target: EntityId,
+        /// Threat score (higher is more threatening)
+        score: f32,
+    },
+    /// A shooter was assigned to engage a target.
+    WeaponAssigned {
+        /// Entity assigned to fire
+        shooter: EntityId,
+        /// Entity to be engaged
+        target: EntityId,
+        /// Weapon slot to fire
+        weapon_slot: usize,
+    },
+    /// An entity crossed the world bounds and was despawned.
+    EntityOutOfBounds {
+        /// Entity that was removed
+        entity: EntityId,
+        /// Position at which it crossed the bounds
+        position: Vec2,
+    },
+    /// A plugin instance overran its execution budget too often and was
+    /// disabled.
+    PluginBudgetExceeded {
+        /// Entity the plugin runs for
+        entity: EntityId,
+        /// Disabled plugin
+        plugin: PluginId,
+        /// Number of overruns
+        overruns: u32,
+    },
+    /// A mine was set off and removed.
+    MineDetonated {
+        /// Mine that detonated
+        mine: EntityId,
+        /// Ship that set off the fuze
+        trigger: EntityId,
+    },
+    /// A cargo transfer completed.
+    CargoTransferred {
+        /// Entity that gave the cargo
+        from: EntityId,
+        /// Entity that received the cargo
+        to: EntityId,
+        /// Cargo actually moved
+        cargo: Cargo,
+    },
+    /// A weapon began reloading its magazine.
+    ReloadStarted {
+        /// Entity owning the weapon
+        source: EntityId,
+        /// Weapon slot reloading
+        weapon_slot: usize,
+    },
+    /// A weapon finished reloading and its magazine is full.
+    ReloadCompleted {
+        /// Entity owning the weapon
+        source: EntityId,
+        /// Weapon slot reloaded
+        weapon_slot: usize,
+    },
+    /// An entity came within a proximity threshold of an observer.
+    EnteredRange {
+        /// Entity watching the threshold
+        observer: EntityId,
+        /// Entity that crossed it
+        target: EntityId,
+        /// Threshold distance
+        radius: f32,
+    },
+    /// An entity moved beyond a proximity threshold of an observer, or was
+    /// removed while inside it.
+    LeftRange {
+        /// Entity watching the threshold
+        observer: EntityId,
+        /// Entity that crossed it
+        target: EntityId,
+        /// Threshold distance
+        radius: f32,
+    },
+    /// A convoy ship reached its destination.
+    ShipDelivered {
+        /// Ship that arrived
+        ship: EntityId,
+    },
+    /// A gun round missed and fell into the sea.
+    ShellSplash {
+        /// Entity that fired the round
+        source: EntityId,
+        /// Point of impact
+        position: Vec2,
+    },
+    /// A projectile seeker locked onto a target.
+    LockAcquired {
+        /// Projectile whose seeker locked on
+        seeker: EntityId,
+        /// Entity locked
+        target: EntityId,
+    },
+    /// A projectile seeker lost its target out of the cone, behind smoke
+    /// or terrain, or to its destruction.
+    LockLost {
+        /// Projectile whose seeker lost the lock
+        seeker: EntityId,
+        /// Entity that was locked
+        target: EntityId,
+    },
+    /// An entity entered one of its keep-out geofences and was pushed back
+    /// or stopped.
+    GeofenceViolated {
+        /// Entity that entered the fence
+        entity: EntityId,
+        /// Index of the fence among the entity's fences
+        fence: usize,
+        /// Distance the entity would have been inside the fence (m)
+        depth: f32,
+    },
+    /// A weapon mount or sensor suite lost its last hit point.
+    SubsystemDestroyed {
+        /// Entity that lost the subsystem
+        entity: EntityId,
+        /// Entity whose hit knocked it out
+        source: EntityId,
+        /// Subsystem knocked out
+        subsystem: Subsystem,
+    },
+    /// An entity spawned with a time-to-live expired and was despawned.
+    EntityExpired {
+        /// Entity that was removed
+        entity: EntityId,
+    },
 }
 
 impl Event {
@@ -460,14 +779,279 @@ impl Event {
     #[must_use]
     pub const fn primary_entity(&self) -> EntityId {
         match self {
-            Self::WeaponFired { source, .. } => *source,
+            Self::WeaponFired { source, .. }
+            | Self::ReloadStarted { source, .. }
+            | Self::ReloadCompleted { source, .. }
+            | Self::ShellSplash { source, .. } => *source,
             Self::DamageDealt { target, .. } => *target,
-            Self::EntityDestroyed { entity, .. } => *entity,
-            Self::ContactDetected { observer, .. } => *observer,
+            Self::EntityDestroyed { entity, .. }
+            | Self::EntityOutOfBounds { entity, .. }
+            | Self::PluginBudgetExceeded { entity, .. }
+            | Self::GeofenceViolated { entity, .. }
+            | Self::SubsystemDestroyed { entity, .. }
+            | Self::EntityExpired { entity } => *entity,
+            Self::MineDetonated { mine, .. } => *mine,
+            Self::CargoTransferred { to, .. } => *to,
+            Self::ShipDelivered { ship } => *ship,
+            Self::ContactDetected { observer, .. }
+            | Self::ThreatAssessed { observer, .. }
+            | Self::EnteredRange { observer, .. }
+            | Self::LeftRange { observer, .. } => *observer,
+            Self::WeaponAssigned { shooter, .. } => *shooter,
+            Self::LockAcquired { seeker, .. } | Self::LockLost { seeker, .. } => *seeker,
+        }
+    }
+
+    /// Returns the entity acted upon, for events that have one: the
+    /// damaged, detected, assessed, assigned or ranged target, the entity
+    /// that triggered a mine, the receiver of cargo, or the target of a
+    /// seeker.
+    #[must_use]
+    pub const fn target(&self) -> Option<EntityId> {
+        match self {
+            Self::DamageDealt { target, .. }
+            | Self::ContactDetected { target, .. }
+            | Self::ThreatAssessed { target, .. }
+            | Self::WeaponAssigned { target, .. }
+            | Self::EnteredRange { target, .. }
+            | Self::LeftRange { target, .. }
+            | Self::LockAcquired { target, .. }
+            | Self::LockLost { target, .. } => Some(*target),
+            Self::MineDetonated { trigger, .. } => Some(*trigger),
+            Self::CargoTransferred { to, .. } => Some(*to),
+            Self::WeaponFired { .. }
+            | Self::EntityDestroyed { .. }
+            | Self::EntityOutOfBounds { .. }
+            | Self::PluginBudgetExceeded { .. }
+            | Self::ReloadStarted { .. }
+            | Self::ReloadCompleted { .. }
+            | Self::ShipDelivered { .. }
+            | Self::ShellSplash { .. }
+            | Self::GeofenceViolated { .. }
+            | Self::SubsystemDestroyed { .. }
+            | Self::EntityExpired { .. } => None,
         }
     }
 }
 
+// =============================================================================
+// Order
+// =============================================================================
+
+/// Order outputs direct a subordinate entity.
+///
+/// Commanders emit orders rather than commands for the units under them.
+/// The [`OrderResolver`](crate::resolver::OrderResolver) files each valid
+/// order as the subordinate's standing order in the arena's
+/// [`OrderBook`](crate::orders::OrderBook), and a behavior plugin such as
+/// [`OrderFollowerPlugin`](crate::plugins::OrderFollowerPlugin) translates
+/// it into commands on the following ticks. A new order replaces the
+/// previous one.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::output::{Order, Output, OutputKind};
+/// use tidebreak_core::entity::EntityId;
+/// use glam::Vec2;
+///
+/// let order = Order::MoveTo {
+///     commander: EntityId::new(1),
+///     subordinate: EntityId::new(2),
+///     destination: Vec2::new(500.0, 0.0),
+///     speed: 8.0,
+/// };
+/// assert_eq!(order.subordinate(), EntityId::new(2));
+/// assert_eq!(Output::from(order).kind(), OutputKind::Order);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Order {
+    /// Proceed to a position and stop there.
+    MoveTo {
+        /// Entity issuing the order
+        commander: EntityId,
+        /// Entity receiving the order
+        subordinate: EntityId,
+        /// Position to reach
+        destination: Vec2,
+        /// Transit speed in m/s (0 = maximum speed)
+        speed: f32,
+    },
+    /// Close with a target, using the subordinate's own track of it.
+    Engage {
+        /// Entity issuing the order
+        commander: EntityId,
+        /// Entity receiving the order
+        subordinate: EntityId,
+        /// Entity to engage
+        target: EntityId,
+    },
+    /// Keep station on a protected entity.
+    Screen {
+        /// Entity issuing the order
+        commander: EntityId,
+        /// Entity receiving the order
+        subordinate: EntityId,
+        /// Entity to screen
+        protect: EntityId,
+        /// Station distance from the protected entity in meters
+        distance: f32,
+        /// Station bearing relative to the protected entity's heading
+        /// (radians, 0 = ahead)
+        bearing: f32,
+    },
+    /// Withdraw the subordinate's standing order.
+    Cancel {
+        /// Entity issuing the order
+        commander: EntityId,
+        /// Entity receiving the order
+        subordinate: EntityId,
+    },
+}
+
+impl Order {
+    /// Returns the entity issuing the order.
+    #[must_use]
+    pub const fn commander(&self) -> EntityId {
+        match self {
+            Self::MoveTo { commander, .. }
+            | Self::Engage { commander, .. }
+            | Self::Screen { commander, .. }
+            | Self::Cancel { commander, .. } => *commander,
+        }
+    }
+
+    /// Returns the entity receiving the order.
+    #[must_use]
+    pub const fn subordinate(&self) -> EntityId {
+        match self {
+            Self::MoveTo { subordinate, .. }
+            | Self::Engage { subordinate, .. }
+            | Self::Screen { subordinate, .. }
+            | Self::Cancel { subordinate, .. } => *subordinate,
+        }
+    }
+}
+
+// =============================================================================
+// Custom Outputs
+// =============================================================================
+
+/// A payload type carried by [`CustomOutput`].
+///
+/// Downstream crates implement this for their own proposal types so their
+/// plugins and resolvers can exchange them without extending [`Output`].
+/// `KIND` names the payload on the wire and should be unique, e.g. prefixed
+/// with the crate name.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use tidebreak_core::entity::EntityId;
+/// use tidebreak_core::output::{CustomOutput, CustomPayload, Output, OutputKind};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct LaunchDrone {
+///     carrier: EntityId,
+///     altitude: f32,
+/// }
+///
+/// impl CustomPayload for LaunchDrone {
+///     const KIND: &'static str = "aviation.launch_drone";
+/// }
+///
+/// let launch = LaunchDrone { carrier: EntityId::new(3), altitude: 120.0 };
+/// let output = Output::from(CustomOutput::new(&launch).unwrap());
+/// assert_eq!(output.kind(), OutputKind::Custom);
+///
+/// let custom = output.as_custom().unwrap();
+/// assert_eq!(custom.decode::<LaunchDrone>(), Some(launch));
+/// ```
+pub trait CustomPayload: Serialize + DeserializeOwned {
+    /// Name identifying this payload type.
+    const KIND: &'static str;
+}
+
+/// Error encoding a [`CustomPayload`] into a [`CustomOutput`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("cannot encode custom output `{kind}`: {message}")]
+pub struct CustomOutputError {
+    /// Kind of the payload that failed to encode
+    pub kind: Cow<'static, str>,
+    /// Why encoding failed
+    pub message: String,
+}
+
+/// A domain-specific output defined outside this crate.
+///
+/// The payload is stored as a self-describing value tagged with its kind,
+/// so custom outputs route, record and replay like the built-in ones.
+/// Resolvers registered for [`OutputKind::Custom`] receive every custom
+/// output and pick out the kinds they understand with [`Self::decode`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomOutput {
+    /// Name of the payload type
+    kind: Cow<'static, str>,
+    /// Encoded payload
+    payload: serde_json::Value,
+}
+
+impl CustomOutput {
+    /// Encodes a typed payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CustomOutputError`] if the payload cannot be represented,
+    /// e.g. a map keyed by structs. Non-finite floats encode as `null`.
+    pub fn new<T: CustomPayload>(payload: &T) -> Result<Self, CustomOutputError> {
+        let value = serde_json::to_value(payload).map_err(|e| CustomOutputError {
+            kind: Cow::Borrowed(T::KIND),
+            message: e.to_string(),
+        })?;
+        Ok(Self::from_value(T::KIND, value))
+    }
+
+    /// Creates a custom output from an already encoded payload.
+    #[must_use]
+    pub fn from_value(kind: impl Into<Cow<'static, str>>, payload: serde_json::Value) -> Self {
+        Self {
+            kind: kind.into(),
+            payload,
+        }
+    }
+
+    /// Returns the name of the payload type.
+    #[must_use]
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the encoded payload.
+    #[must_use]
+    pub const fn payload(&self) -> &serde_json::Value {
+        &self.payload
+    }
+
+    /// Returns `true` if the payload is of type `T`.
+    #[must_use]
+    pub fn is<T: CustomPayload>(&self) -> bool {
+        self.kind == T::KIND
+    }
+
+    /// Decodes the payload as `T`.
+    ///
+    /// Returns `None` if the payload is of another kind or does not match
+    /// the shape of `T`.
+    #[must_use]
+    pub fn decode<T: CustomPayload>(&self) -> Option<T> {
+        if !self.is::<T>() {
+            return None;
+        }
+        T::deserialize(&self.payload).ok()
+    }
+}
+
 // =============================================================================
 // Top-Level Output Enum
 // =============================================================================
@@ -483,6 +1067,10 @@ pub enum OutputKind {
     Modifier,
     /// Event outputs (notifications)
     Event,
+    /// Order outputs (directions to subordinates)
+    Order,
+    /// Custom outputs (domain-specific proposals)
+    Custom,
 }
 
 impl fmt::Display for OutputKind {
@@ -491,6 +1079,8 @@ impl fmt::Display for OutputKind {
             Self::Command => write!(f, "Command"),
             Self::Modifier => write!(f, "Modifier"),
             Self::Event => write!(f, "Event"),
+            Self::Order => write!(f, "Order"),
+            Self::Custom => write!(f, "Custom"),
         }
     }
 }
@@ -522,6 +1112,10 @@ pub enum Output {
     Modifier(Modifier),
     /// An event output (notification)
     Event(Event),
+    /// An order output (direction to a subordinate)
+    Order(Order),
+    /// A custom output (domain-specific proposal)
+    Custom(CustomOutput),
 }
 
 impl Output {
@@ -532,6 +1126,8 @@ impl Output {
             Self::Command(_) => OutputKind::Command,
             Self::Modifier(_) => OutputKind::Modifier,
             Self::Event(_) => OutputKind::Event,
+            Self::Order(_) => OutputKind::Order,
+            Self::Custom(_) => OutputKind::Custom,
         }
     }
 
@@ -553,6 +1149,18 @@ impl Output {
         matches!(self, Self::Event(_))
     }
 
+    /// Returns `true` if this is an order output.
+    #[must_use]
+    pub const fn is_order(&self) -> bool {
+        matches!(self, Self::Order(_))
+    }
+
+    /// Returns `true` if this is a custom output.
+    #[must_use]
+    pub const fn is_custom(&self) -> bool {
+        matches!(self, Self::Custom(_))
+    }
+
     /// Returns the command if this is a command output.
     #[must_use]
     pub const fn as_command(&self) -> Option<&Command> {
@@ -579,6 +1187,24 @@ impl Output {
             _ => None,
         }
     }
+
+    /// Returns the order if this is an order output.
+    #[must_use]
+    pub const fn as_order(&self) -> Option<&Order> {
+        match self {
+            Self::Order(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Returns the custom output if this is one.
+    #[must_use]
+    pub const fn as_custom(&self) -> Option<&CustomOutput> {
+        match self {
+            Self::Custom(c) => Some(c),
+            _ => None,
+        }
+    }
 }
 
 impl From<Command> for Output {
@@ -599,6 +1225,18 @@ impl From<Event> for Output {
     }
 }
 
+impl From<Order> for Output {
+    fn from(o: Order) -> Self {
+        Self::Order(o)
+    }
+}
+
+impl From<CustomOutput> for Output {
+    fn from(c: CustomOutput) -> Self {
+        Self::Custom(c)
+    }
+}
+
 // =============================================================================
 // Output Envelope
 // =============================================================================
@@ -626,12 +1264,13 @@ impl From<Event> for Output {
 /// use tidebreak_core::output::{
 ///     Output, Modifier, OutputEnvelope, PluginInstanceId, PluginId, TraceId, EventId,
 /// };
-/// use tidebreak_core::entity::EntityId;
+/// use tidebreak_core::entity::{DamageType, EntityId};
 ///
 /// let envelope = OutputEnvelope::new(
 ///     Output::Modifier(Modifier::ApplyDamage {
 ///         target: EntityId::new(2),
 ///         amount: 50.0,
+///         damage_type: DamageType::Kinetic,
 ///     }),
 ///     PluginInstanceId::new(EntityId::new(1), PluginId::new("weapon")),
 ///     TraceId::new(100),
@@ -923,6 +1562,54 @@ mod tests {
             assert_eq!(cmd.source(), Some(EntityId::new(1)));
         }
 
+        #[test]
+        fn set_throttle_is_sustained() {
+            let cmd = Command::SetThrottle {
+                target: EntityId::new(3),
+                fraction: 0.5,
+            };
+
+            assert_eq!(cmd.target(), Some(EntityId::new(3)));
+            assert_eq!(cmd.source(), Some(EntityId::new(3)));
+            assert!(cmd.is_sustained());
+        }
+
+        #[test]
+        fn set_depth_is_sustained() {
+            let cmd = Command::SetDepth {
+                target: EntityId::new(4),
+                depth: 80.0,
+            };
+
+            assert_eq!(cmd.target(), Some(EntityId::new(4)));
+            assert_eq!(cmd.source(), Some(EntityId::new(4)));
+            assert!(cmd.is_sustained());
+        }
+
+        #[test]
+        fn deploy_smoke_is_one_shot() {
+            let cmd = Command::DeploySmoke {
+                source: EntityId::new(5),
+                duration: 600,
+            };
+
+            assert_eq!(cmd.target(), None);
+            assert_eq!(cmd.source(), Some(EntityId::new(5)));
+            assert!(!cmd.is_sustained());
+        }
+
+        #[test]
+        fn intercept_squadron_is_one_shot() {
+            let cmd = Command::InterceptSquadron {
+                source: EntityId::new(6),
+                target: EntityId::new(7),
+            };
+
+            assert_eq!(cmd.target(), Some(EntityId::new(7)));
+            assert_eq!(cmd.source(), Some(EntityId::new(6)));
+            assert!(!cmd.is_sustained());
+        }
+
         #[test]
         fn set_heading() {
             let cmd = Command::SetHeading {
@@ -957,6 +1644,29 @@ mod tests {
             assert_eq!(cmd.source(), Some(EntityId::new(1)));
         }
 
+        #[test]
+        fn lay_mine() {
+            let cmd = Command::LayMine {
+                source: EntityId::new(4),
+                mine: MineState::default(),
+            };
+
+            assert_eq!(cmd.target(), None);
+            assert_eq!(cmd.source(), Some(EntityId::new(4)));
+        }
+
+        #[test]
+        fn transfer_cargo() {
+            let cmd = Command::TransferCargo {
+                from: EntityId::new(1),
+                to: EntityId::new(2),
+                cargo: Cargo::Fuel(50.0),
+            };
+
+            assert_eq!(cmd.target(), Some(EntityId::new(2)));
+            assert_eq!(cmd.source(), Some(EntityId::new(1)));
+        }
+
         #[test]
         fn serialization_roundtrip() {
             let cmd = Command::FireWeapon {
@@ -978,9 +1688,10 @@ mod tests {
             let m = Modifier::ApplyDamage {
                 target: EntityId::new(1),
                 amount: 50.0,
+                damage_type: DamageType::Kinetic,
             };
 
-            assert_eq!(m.target(), EntityId::new(1));
+            assert_eq!(m.target(), Some(EntityId::new(1)));
         }
 
         #[test]
@@ -990,7 +1701,7 @@ mod tests {
                 amount: 25.0,
             };
 
-            assert_eq!(m.target(), EntityId::new(2));
+            assert_eq!(m.target(), Some(EntityId::new(2)));
         }
 
         #[test]
@@ -1001,7 +1712,7 @@ mod tests {
                 value: true,
             };
 
-            assert_eq!(m.target(), EntityId::new(3));
+            assert_eq!(m.target(), Some(EntityId::new(3)));
         }
 
         #[test]
@@ -1012,7 +1723,30 @@ mod tests {
                 delta: -10.0,
             };
 
-            assert_eq!(m.target(), EntityId::new(4));
+            assert_eq!(m.target(), Some(EntityId::new(4)));
+        }
+
+        #[test]
+        fn set_attribute() {
+            let m = Modifier::SetAttribute {
+                target: EntityId::new(5),
+                key: "patrol.leg".to_string(),
+                value: Some(AttributeValue::Int(1)),
+            };
+
+            assert_eq!(m.target(), Some(EntityId::new(5)));
+        }
+
+        #[test]
+        fn apply_area_damage_has_no_single_target() {
+            let m = Modifier::ApplyAreaDamage {
+                center: Vec2::ZERO,
+                radius: 100.0,
+                amount: 50.0,
+                falloff: 1.0,
+            };
+
+            assert_eq!(m.target(), None);
         }
 
         #[test]
@@ -1057,6 +1791,7 @@ mod tests {
             let e = Event::EntityDestroyed {
                 entity: EntityId::new(3),
                 destroyer: Some(EntityId::new(1)),
+                assists: vec![],
             };
 
             assert_eq!(e.primary_entity(), EntityId::new(3));
@@ -1067,6 +1802,7 @@ mod tests {
             let e = Event::EntityDestroyed {
                 entity: EntityId::new(3),
                 destroyer: None,
+                assists: vec![],
             };
 
             assert_eq!(e.primary_entity(), EntityId::new(3));
@@ -1083,6 +1819,69 @@ mod tests {
             assert_eq!(e.primary_entity(), EntityId::new(1));
         }
 
+        #[test]
+        fn threat_assessed() {
+            let e = Event::ThreatAssessed {
+                observer: EntityId::new(1),
+                target: EntityId::new(2),
+                score: 0.8,
+            };
+
+            assert_eq!(e.primary_entity(), EntityId::new(1));
+        }
+
+        #[test]
+        fn weapon_assigned() {
+            let e = Event::WeaponAssigned {
+                shooter: EntityId::new(4),
+                target: EntityId::new(2),
+                weapon_slot: 1,
+            };
+
+            assert_eq!(e.primary_entity(), EntityId::new(4));
+        }
+
+        #[test]
+        fn entity_out_of_bounds_primary_entity() {
+            let e = Event::EntityOutOfBounds {
+                entity: EntityId::new(6),
+                position: Vec2::new(101.0, 0.0),
+            };
+
+            assert_eq!(e.primary_entity(), EntityId::new(6));
+        }
+
+        #[test]
+        fn entity_expired_primary_entity() {
+            let e = Event::EntityExpired {
+                entity: EntityId::new(8),
+            };
+
+            assert_eq!(e.primary_entity(), EntityId::new(8));
+            assert_eq!(e.target(), None);
+        }
+
+        #[test]
+        fn mine_detonated_primary_entity() {
+            let e = Event::MineDetonated {
+                mine: EntityId::new(7),
+                trigger: EntityId::new(2),
+            };
+
+            assert_eq!(e.primary_entity(), EntityId::new(7));
+        }
+
+        #[test]
+        fn cargo_transferred_primary_entity() {
+            let e = Event::CargoTransferred {
+                from: EntityId::new(3),
+                to: EntityId::new(8),
+                cargo: Cargo::Fuel(10.0),
+            };
+
+            assert_eq!(e.primary_entity(), EntityId::new(8));
+        }
+
         #[test]
         fn serialization_roundtrip() {
             let e = Event::ContactDetected {
@@ -1096,6 +1895,40 @@ mod tests {
         }
     }
 
+    mod order_tests {
+        use super::*;
+
+        #[test]
+        fn accessors_return_both_ends() {
+            let order = Order::Screen {
+                commander: EntityId::new(1),
+                subordinate: EntityId::new(2),
+                protect: EntityId::new(3),
+                distance: 500.0,
+                bearing: 0.0,
+            };
+            assert_eq!(order.commander(), EntityId::new(1));
+            assert_eq!(order.subordinate(), EntityId::new(2));
+
+            let output = Output::from(order.clone());
+            assert!(output.is_order());
+            assert_eq!(output.as_order(), Some(&order));
+            assert!(output.as_command().is_none());
+        }
+
+        #[test]
+        fn serialization_roundtrip() {
+            let order = Order::Engage {
+                commander: EntityId::new(1),
+                subordinate: EntityId::new(2),
+                target: EntityId::new(9),
+            };
+            let json = serde_json::to_string(&order).unwrap();
+            let deserialized: Order = serde_json::from_str(&json).unwrap();
+            assert_eq!(order, deserialized);
+        }
+    }
+
     mod output_tests {
         use super::*;
 
@@ -1110,6 +1943,7 @@ mod tests {
             let mod_output = Output::Modifier(Modifier::ApplyDamage {
                 target: EntityId::new(1),
                 amount: 10.0,
+                damage_type: DamageType::Kinetic,
             });
             assert_eq!(mod_output.kind(), OutputKind::Modifier);
 
@@ -1141,6 +1975,7 @@ mod tests {
             let e = Output::Event(Event::EntityDestroyed {
                 entity: EntityId::new(1),
                 destroyer: None,
+                assists: vec![],
             });
             assert!(!e.is_command());
             assert!(!e.is_modifier());
@@ -1173,6 +2008,7 @@ mod tests {
             let m = Modifier::ApplyDamage {
                 target: EntityId::new(1),
                 amount: 10.0,
+                damage_type: DamageType::Kinetic,
             };
             let output: Output = m.into();
             assert!(output.is_modifier());
@@ -1199,6 +2035,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: EntityId::new(2),
                     amount: 50.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 Output::Event(Event::DamageDealt {
                     source: EntityId::new(1),
@@ -1215,6 +2052,87 @@ mod tests {
         }
     }
 
+    mod custom_output_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Flare {
+            source: EntityId,
+            position: Vec2,
+            burn: Option<f32>,
+        }
+
+        impl CustomPayload for Flare {
+            const KIND: &'static str = "test.flare";
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Beacon {
+            source: EntityId,
+        }
+
+        impl CustomPayload for Beacon {
+            const KIND: &'static str = "test.beacon";
+        }
+
+        fn flare() -> Flare {
+            Flare {
+                source: EntityId::new(4),
+                position: Vec2::new(10.0, -2.5),
+                burn: None,
+            }
+        }
+
+        #[test]
+        fn decodes_its_own_kind_only() {
+            let custom = CustomOutput::new(&flare()).unwrap();
+            assert_eq!(custom.kind(), "test.flare");
+            assert!(custom.is::<Flare>());
+            assert_eq!(custom.decode::<Flare>(), Some(flare()));
+
+            assert!(!custom.is::<Beacon>());
+            assert_eq!(custom.decode::<Beacon>(), None);
+
+            // Right kind, wrong shape
+            let forged = CustomOutput::from_value(Flare::KIND, serde_json::json!(3));
+            assert_eq!(forged.decode::<Flare>(), None);
+        }
+
+        #[test]
+        fn routes_as_custom() {
+            let output = Output::from(CustomOutput::new(&flare()).unwrap());
+            assert_eq!(output.kind(), OutputKind::Custom);
+            assert!(output.is_custom());
+            assert!(!output.is_event());
+            assert!(output.as_custom().is_some());
+            assert_eq!(format!("{}", OutputKind::Custom), "Custom");
+        }
+
+        #[test]
+        fn serialization_roundtrip() {
+            let output = Output::Custom(CustomOutput::new(&flare()).unwrap());
+            let json = serde_json::to_string(&output).unwrap();
+            let deserialized: Output = serde_json::from_str(&json).unwrap();
+            assert_eq!(output, deserialized);
+            assert_eq!(deserialized.as_custom().unwrap().decode::<Flare>(), Some(flare()));
+        }
+
+        #[test]
+        fn keeps_nulls_and_large_integers() {
+            #[derive(Debug, PartialEq, Serialize, Deserialize)]
+            struct Seed(Option<u64>);
+
+            impl CustomPayload for Seed {
+                const KIND: &'static str = "test.seed";
+            }
+
+            for seed in [Seed(None), Seed(Some(u64::MAX))] {
+                let custom = CustomOutput::new(&seed).unwrap();
+                assert_eq!(custom.decode::<Seed>(), Some(seed));
+            }
+        }
+    }
+
     mod output_kind_tests {
         use super::*;
 
@@ -1223,6 +2141,7 @@ mod tests {
             let _cmd = OutputKind::Command;
             let _m = OutputKind::Modifier;
             let _e = OutputKind::Event;
+            let _o = OutputKind::Order;
         }
 
         #[test]
@@ -1230,6 +2149,7 @@ mod tests {
             assert_eq!(format!("{}", OutputKind::Command), "Command");
             assert_eq!(format!("{}", OutputKind::Modifier), "Modifier");
             assert_eq!(format!("{}", OutputKind::Event), "Event");
+            assert_eq!(format!("{}", OutputKind::Order), "Order");
         }
 
         #[test]
@@ -1311,6 +2231,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: EntityId::new(2),
                     amount: 50.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 PluginInstanceId::new(EntityId::new(1), PluginId::new("weapon")),
                 TraceId::new(200),
@@ -1363,6 +2284,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: EntityId::new(2),
                     amount: 50.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 PluginInstanceId::new(EntityId::new(1), PluginId::new("damage_calc")),
                 TraceId::new(1),
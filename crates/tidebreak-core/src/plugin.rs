@@ -20,11 +20,16 @@
 //! - Required entity tags (which entity types it operates on)
 //! - Components it reads (for `WorldView` scoping)
 //! - Output kinds it emits (for resolver routing)
+//! - Plugins it runs after (for output ordering)
 //!
 //! # Plugin Registry
 //!
 //! The [`PluginRegistry`] bundles plugins by entity tag, allowing efficient
-//! lookup of which plugins should run on each entity type.
+//! lookup of which plugins should run on each entity type. Each bundle is
+//! kept in a deterministic topological order: every plugin comes after the
+//! plugins named in its `runs_after`, and otherwise plugins are ordered by
+//! ID, whatever order they were registered in. Registering a plugin that
+//! would close a `runs_after` cycle is rejected.
 //!
 //! # Example
 //!
@@ -51,6 +56,8 @@
 //!                 required_tags: vec![EntityTag::Ship, EntityTag::Squadron],
 //!                 reads: vec![ComponentKind::Transform, ComponentKind::Physics],
 //!                 emits: vec![OutputKind::Command],
+//!                 runs_after: vec![],
+//!                 every_tick: false,
 //!             },
 //!         }
 //!     }
@@ -83,6 +90,7 @@ use std::fmt;
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::entity::{EntityId, EntityTag};
 use crate::output::{Output, OutputKind, TraceId};
@@ -108,6 +116,7 @@ pub use crate::output::PluginId;
 /// - `Combat`: Health, weapons, status ([`CombatState`](crate::entity::CombatState))
 /// - `Sensor`: Detection capabilities ([`SensorState`](crate::entity::SensorState))
 /// - `Inventory`: Fuel and ammunition ([`InventoryState`](crate::entity::InventoryState))
+/// - `Attributes`: Plugin-specific key-value state ([`Attributes`](crate::entity::Attributes))
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ComponentKind {
     /// Transform component (position, heading)
@@ -120,6 +129,12 @@ pub enum ComponentKind {
     Sensor,
     /// Inventory component (fuel, ammunition)
     Inventory,
+    /// Attribute store (plugin-specific key-value state)
+    Attributes,
+    /// Signature (radar, acoustic and infrared footprint)
+    Signature,
+    /// Submarine diving state (depth, battery)
+    Submarine,
 }
 
 impl fmt::Display for ComponentKind {
@@ -130,6 +145,9 @@ impl fmt::Display for ComponentKind {
             Self::Combat => write!(f, "Combat"),
             Self::Sensor => write!(f, "Sensor"),
             Self::Inventory => write!(f, "Inventory"),
+            Self::Attributes => write!(f, "Attributes"),
+            Self::Signature => write!(f, "Signature"),
+            Self::Submarine => write!(f, "Submarine"),
         }
     }
 }
@@ -145,11 +163,14 @@ impl fmt::Display for ComponentKind {
 /// - `required_tags`: Which entity types this plugin operates on
 /// - `reads`: Which component types the plugin needs to read
 /// - `emits`: Which output kinds the plugin may emit
+/// - `runs_after`: Which plugins must precede this one
+/// - `every_tick`: Whether the plugin also runs between decision ticks
 ///
 /// This information is used to:
 /// - Route plugins to appropriate entities
 /// - Scope the [`WorldView`] to only allowed components
 /// - Validate output types at debug time
+/// - Order plugins within a [`PluginRegistry`] bundle
 ///
 /// # Example
 ///
@@ -163,6 +184,8 @@ impl fmt::Display for ComponentKind {
 ///     required_tags: vec![EntityTag::Ship, EntityTag::Platform],
 ///     reads: vec![ComponentKind::Transform, ComponentKind::Sensor],
 ///     emits: vec![OutputKind::Event],
+///     runs_after: vec![],
+///     every_tick: false,
 /// };
 ///
 /// assert!(decl.reads.contains(&ComponentKind::Sensor));
@@ -180,6 +203,14 @@ pub struct PluginDeclaration {
     /// Output kinds this plugin may emit.
     /// Used for validation and resolver routing.
     pub emits: Vec<OutputKind>,
+    /// Plugins this plugin runs after when registered for the same tag.
+    /// Their outputs for an entity are resolved before this plugin's; IDs
+    /// not registered for the tag are ignored.
+    pub runs_after: Vec<PluginId>,
+    /// Run on every physics tick rather than only on decision ticks (see
+    /// `Simulation::set_action_interval`). For plugins that model the
+    /// platform rather than decide for it, such as missile guidance.
+    pub every_tick: bool,
 }
 
 impl PluginDeclaration {
@@ -200,6 +231,12 @@ impl PluginDeclaration {
     pub fn emits_output(&self, kind: OutputKind) -> bool {
         self.emits.contains(&kind)
     }
+
+    /// Checks if this plugin runs after the given plugin.
+    #[must_use]
+    pub fn follows(&self, id: &PluginId) -> bool {
+        self.runs_after.contains(id)
+    }
 }
 
 // =============================================================================
@@ -294,6 +331,20 @@ pub trait Plugin: Send + Sync {
 // Plugin Registry
 // =============================================================================
 
+/// Errors produced when registering a plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PluginOrderError {
+    /// The `runs_after` declarations of the plugins for a tag form a cycle.
+    #[error("runs_after cycle among {tag} plugins {plugins:?}")]
+    Cycle {
+        /// Tag the plugin was registered for
+        tag: EntityTag,
+        /// Plugins that could not be ordered: those on a cycle and those
+        /// running after them
+        plugins: Vec<PluginId>,
+    },
+}
+
 /// Registry of plugins organized by entity tag.
 ///
 /// The registry allows efficient lookup of which plugins should run on
@@ -334,6 +385,8 @@ pub trait Plugin: Send + Sync {
 ///         required_tags: vec![EntityTag::Ship],
 ///         reads: vec![ComponentKind::Transform],
 ///         emits: vec![OutputKind::Command],
+///         runs_after: vec![],
+///         every_tick: false,
 ///     },
 /// });
 ///
@@ -366,11 +419,90 @@ impl PluginRegistry {
     ///
     /// * `tag` - The entity tag to register the plugin for
     /// * `plugin` - The plugin to register (wrapped in Arc for shared ownership)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugin would close a `runs_after` cycle; use
+    /// [`try_register`](Self::try_register) to handle that case.
     pub fn register(&mut self, tag: EntityTag, plugin: Arc<dyn Plugin>) {
-        self.bundles.entry(tag).or_default().push(plugin);
+        if let Err(error) = self.try_register(tag, plugin) {
+            panic!("{error}");
+        }
+    }
+
+    /// Registers a plugin for the given entity tag, keeping the tag's
+    /// plugins in topological order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PluginOrderError::Cycle`], leaving the registry unchanged,
+    /// if the plugin's `runs_after` declarations, or those of the plugins
+    /// already registered for `tag`, would form a cycle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tidebreak_core::entity::EntityTag;
+    /// use tidebreak_core::output::{Output, OutputKind};
+    /// use tidebreak_core::plugin::{
+    ///     Plugin, PluginContext, PluginDeclaration, PluginId, PluginOrderError, PluginRegistry,
+    /// };
+    /// use tidebreak_core::world_view::WorldView;
+    ///
+    /// struct Stage(PluginDeclaration);
+    ///
+    /// impl Plugin for Stage {
+    ///     fn declaration(&self) -> &PluginDeclaration {
+    ///         &self.0
+    ///     }
+    ///     fn run(&self, _ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+    ///         vec![]
+    ///     }
+    /// }
+    ///
+    /// let stage = |id: &'static str, after: &[&'static str]| {
+    ///     Arc::new(Stage(PluginDeclaration {
+    ///         id: PluginId::new(id),
+    ///         required_tags: vec![EntityTag::Ship],
+    ///         reads: vec![],
+    ///         emits: vec![OutputKind::Command],
+    ///         runs_after: after.iter().copied().map(PluginId::new).collect(),
+    ///         every_tick: false,
+    ///     }))
+    /// };
+    ///
+    /// let mut registry = PluginRegistry::new();
+    /// registry.try_register(EntityTag::Ship, stage("a_steering", &["z_targeting"]))?;
+    /// registry.try_register(EntityTag::Ship, stage("z_targeting", &[]))?;
+    /// let order: Vec<_> = registry
+    ///     .plugins_for(EntityTag::Ship)
+    ///     .iter()
+    ///     .map(|p| p.declaration().id.as_str())
+    ///     .collect();
+    /// assert_eq!(order, ["z_targeting", "a_steering"]);
+    ///
+    /// let cycle = registry.try_register(EntityTag::Ship, stage("z_targeting", &["a_steering"]));
+    /// assert!(matches!(cycle, Err(PluginOrderError::Cycle { .. })));
+    /// # Ok::<(), PluginOrderError>(())
+    /// ```
+    pub fn try_register(
+        &mut self,
+        tag: EntityTag,
+        plugin: Arc<dyn Plugin>,
+    ) -> Result<(), PluginOrderError> {
+        let mut plugins = self.bundles.get(&tag).cloned().unwrap_or_default();
+        plugins.push(plugin);
+        let ordered = topological_order(&plugins).map_err(|plugins| PluginOrderError::Cycle {
+            tag,
+            plugins,
+        })?;
+        self.bundles.insert(tag, ordered);
+        Ok(())
     }
 
-    /// Returns the plugins registered for the given entity tag.
+    /// Returns the plugins registered for the given entity tag, in
+    /// topological order.
     ///
     /// # Arguments
     ///
@@ -461,6 +593,28 @@ impl PluginRegistry {
     }
 }
 
+/// Orders plugins so each follows those named in its `runs_after`, breaking
+/// ties by ID and then by position in `plugins`.
+///
+/// Returns the IDs of the plugins that cannot be ordered on a cycle.
+fn topological_order(plugins: &[Arc<dyn Plugin>]) -> Result<Vec<Arc<dyn Plugin>>, Vec<PluginId>> {
+    let mut pending: Vec<usize> = (0..plugins.len()).collect();
+    pending.sort_by(|&a, &b| plugins[a].declaration().id.cmp(&plugins[b].declaration().id));
+    let mut ordered: Vec<Arc<dyn Plugin>> = Vec::with_capacity(plugins.len());
+    while !pending.is_empty() {
+        // The first pending plugin none of whose predecessors are pending
+        let ready = pending.iter().position(|&i| {
+            let declaration = plugins[i].declaration();
+            !pending.iter().any(|&j| declaration.follows(&plugins[j].declaration().id))
+        });
+        let Some(ready) = ready else {
+            return Err(pending.iter().map(|&i| plugins[i].declaration().id.clone()).collect());
+        };
+        ordered.push(Arc::clone(&plugins[pending.remove(ready)]));
+    }
+    Ok(ordered)
+}
+
 impl fmt::Debug for PluginRegistry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PluginRegistry")
@@ -581,6 +735,8 @@ mod tests {
             assert_eq!(format!("{}", ComponentKind::Combat), "Combat");
             assert_eq!(format!("{}", ComponentKind::Sensor), "Sensor");
             assert_eq!(format!("{}", ComponentKind::Inventory), "Inventory");
+            assert_eq!(format!("{}", ComponentKind::Attributes), "Attributes");
+            assert_eq!(format!("{}", ComponentKind::Submarine), "Submarine");
         }
 
         #[test]
@@ -619,6 +775,8 @@ mod tests {
                 required_tags: vec![EntityTag::Ship, EntityTag::Squadron],
                 reads: vec![ComponentKind::Transform, ComponentKind::Physics],
                 emits: vec![OutputKind::Command, OutputKind::Event],
+                runs_after: vec![],
+                every_tick: false,
             }
         }
 
@@ -659,6 +817,8 @@ mod tests {
                 required_tags: vec![],
                 reads: vec![],
                 emits: vec![],
+                runs_after: vec![],
+                every_tick: false,
             };
 
             assert!(!decl.supports_tag(EntityTag::Ship));
@@ -727,9 +887,25 @@ mod tests {
                         required_tags: tags,
                         reads: vec![ComponentKind::Transform],
                         emits: vec![OutputKind::Command],
+                        runs_after: vec![],
+                        every_tick: false,
                     },
                 }
             }
+
+            fn after(id: &'static str, after: &[&'static str]) -> Arc<Self> {
+                let mut plugin = Self::new(id, vec![EntityTag::Ship]);
+                plugin.declaration.runs_after = after.iter().copied().map(PluginId::new).collect();
+                Arc::new(plugin)
+            }
+        }
+
+        fn ship_order(registry: &PluginRegistry) -> Vec<&str> {
+            registry
+                .plugins_for(EntityTag::Ship)
+                .iter()
+                .map(|p| p.declaration().id.as_str())
+                .collect()
         }
 
         impl Plugin for TestPlugin {
@@ -862,6 +1038,54 @@ mod tests {
             assert!(debug.contains("registration_count"));
         }
 
+        #[test]
+        fn order_ignores_registration_order() {
+            let mut forward = PluginRegistry::new();
+            let mut backward = PluginRegistry::new();
+            for id in ["sensor", "movement", "weapon"] {
+                forward.register(EntityTag::Ship, TestPlugin::after(id, &[]));
+            }
+            for id in ["weapon", "movement", "sensor"] {
+                backward.register(EntityTag::Ship, TestPlugin::after(id, &[]));
+            }
+
+            assert_eq!(ship_order(&forward), ["movement", "sensor", "weapon"]);
+            assert_eq!(ship_order(&forward), ship_order(&backward));
+        }
+
+        #[test]
+        fn runs_after_precedes_id_order() {
+            let mut registry = PluginRegistry::new();
+            registry.register(EntityTag::Ship, TestPlugin::after("a", &["c"]));
+            registry.register(EntityTag::Ship, TestPlugin::after("b", &[]));
+            // Unregistered predecessors are ignored
+            registry.register(EntityTag::Ship, TestPlugin::after("c", &["missing"]));
+
+            assert_eq!(ship_order(&registry), ["b", "c", "a"]);
+        }
+
+        #[test]
+        fn cycles_are_rejected() {
+            let mut registry = PluginRegistry::new();
+            registry.register(EntityTag::Ship, TestPlugin::after("a", &["b"]));
+            registry.register(EntityTag::Ship, TestPlugin::after("b", &[]));
+
+            let result = registry.try_register(EntityTag::Ship, TestPlugin::after("c", &["a"]));
+            assert!(result.is_ok());
+            let result = registry.try_register(EntityTag::Ship, TestPlugin::after("b", &["c"]));
+            assert_eq!(
+                result,
+                Err(PluginOrderError::Cycle {
+                    tag: EntityTag::Ship,
+                    plugins: vec![PluginId::new("a"), PluginId::new("b"), PluginId::new("c")],
+                })
+            );
+            assert_eq!(ship_order(&registry), ["b", "a", "c"]);
+            // Self-dependency is a cycle too
+            let result = registry.try_register(EntityTag::Ship, TestPlugin::after("d", &["d"]));
+            assert!(result.is_err());
+        }
+
         #[test]
         fn plugin_declaration_accessible() {
             let mut registry = PluginRegistry::new();
@@ -909,6 +1133,8 @@ mod tests {
                     required_tags: vec![EntityTag::Ship],
                     reads: vec![ComponentKind::Transform],
                     emits: vec![OutputKind::Command],
+                    runs_after: vec![],
+                    every_tick: false,
                 },
             };
 
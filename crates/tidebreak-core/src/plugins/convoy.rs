@@ -0,0 +1,452 @@
+//! Convoy plugin for AI-controlled merchant ships.
+//!
+//! The `ConvoyPlugin` sails every ship carrying its label (default
+//! [`MERCHANT_LABEL`]) along a route of waypoints, taking them in order:
+//!
+//! - **Route**: head for the next waypoint at the cruise throttle; within
+//!   the arrival radius it counts as reached and the next one is taken.
+//! - **Scatter**: when any merchant of the same team and label loses HP,
+//!   the whole convoy scatters for `scatter_ticks`: each ship runs at full
+//!   throttle directly away from the convoy's center, then resumes the
+//!   route from where it left off. A lone merchant scatters on a bearing
+//!   derived from its ID.
+//! - **Arrival**: on reaching the final waypoint a merchant stops and emits
+//!   `Event::ShipDelivered` once, which a
+//!   [`ScoreKeeper`](crate::resolver::ScoreKeeper) scores as a safe arrival.
+//!
+//! Progress is kept in the merchant's attributes (`convoy.leg`,
+//! `convoy.hp`, `convoy.scatter_until` and [`DELIVERED_ATTRIBUTE`]), so it
+//! is part of the world state and survives snapshots.
+//!
+//! # Supported Entity Types
+//!
+//! - Ships
+//!
+//! # Outputs
+//!
+//! - `Command::SetHeading`: Course to the next waypoint or away from the
+//!   convoy
+//! - `Command::SetThrottle`: Cruise, scatter or stop
+//! - `Modifier::SetAttribute`: Route progress
+//! - `Event::ShipDelivered`: The merchant reached the end of the route
+
+use std::f32::consts::TAU;
+
+use glam::Vec2;
+
+use crate::arena::Arena;
+use crate::entity::components::AttributeValue;
+use crate::entity::{Entity, EntityId, EntityInner, EntityTag, ShipComponents, TeamId};
+use crate::output::{Command, Event, Modifier, Output, OutputKind, PluginId};
+use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+use crate::world_view::WorldView;
+
+/// Label carried by merchant ships.
+pub const MERCHANT_LABEL: &str = "merchant";
+
+/// Attribute set to `true` once a merchant has been delivered.
+pub const DELIVERED_ATTRIBUTE: &str = "convoy.delivered";
+
+const LEG_ATTRIBUTE: &str = "convoy.leg";
+const HP_ATTRIBUTE: &str = "convoy.hp";
+const SCATTER_ATTRIBUTE: &str = "convoy.scatter_until";
+
+/// Angle between successive scatter bearings of lone merchants.
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+/// Spawns a merchant at `position`, labelled [`MERCHANT_LABEL`].
+///
+/// See [`ShipComponents::merchant`] for the ship class.
+pub fn spawn_merchant(
+    arena: &mut Arena,
+    position: Vec2,
+    heading: f32,
+    team: Option<TeamId>,
+) -> EntityId {
+    let id = arena.spawn(
+        EntityTag::Ship,
+        EntityInner::Ship(ShipComponents::merchant(position, heading)),
+    );
+    arena.add_label(id, MERCHANT_LABEL);
+    arena.set_team(id, team);
+    id
+}
+
+/// Plugin sailing merchant ships along a convoy route.
+///
+/// # Example
+///
+/// ```
+/// use glam::Vec2;
+/// use tidebreak_core::entity::{EntityTag, TeamId};
+/// use tidebreak_core::plugins::{spawn_merchant, ConvoyPlugin};
+/// use tidebreak_core::resolver::ScoringRules;
+/// use tidebreak_core::simulation::Simulation;
+/// use std::sync::Arc;
+///
+/// let mut sim = Simulation::new(42);
+/// let route = vec![Vec2::new(300.0, 0.0)];
+/// sim.plugins_mut().register(EntityTag::Ship, Arc::new(ConvoyPlugin::new(route)));
+/// sim.enable_scoring(ScoringRules::new().with_delivery(10.0));
+/// spawn_merchant(sim.arena_mut(), Vec2::ZERO, 0.0, Some(TeamId::new(0)));
+///
+/// for _ in 0..90 * 60 {
+///     sim.step();
+/// }
+/// assert_eq!(sim.scores()[0].delivered, 1);
+/// ```
+pub struct ConvoyPlugin {
+    declaration: PluginDeclaration,
+    /// Waypoints, in order
+    route: Vec<Vec2>,
+    /// Label of the ships controlled
+    label: String,
+    /// Distance at which a waypoint counts as reached
+    arrival_radius: f32,
+    /// Throttle fraction while following the route
+    cruise_throttle: f32,
+    /// Ticks a convoy stays scattered after a hit
+    scatter_ticks: u64,
+}
+
+impl ConvoyPlugin {
+    /// Default distance at which a waypoint counts as reached.
+    pub const DEFAULT_ARRIVAL_RADIUS: f32 = 100.0;
+    /// Default throttle fraction while following the route.
+    pub const DEFAULT_CRUISE_THROTTLE: f32 = 0.7;
+    /// Default ticks a convoy stays scattered after a hit (30 s).
+    pub const DEFAULT_SCATTER_TICKS: u64 = 1800;
+
+    /// Creates a plugin sailing merchants along `route`.
+    #[must_use]
+    pub fn new(route: Vec<Vec2>) -> Self {
+        Self {
+            declaration: PluginDeclaration {
+                id: PluginId::from_static("convoy"),
+                required_tags: vec![EntityTag::Ship],
+                reads: vec![
+                    ComponentKind::Transform,
+                    ComponentKind::Combat,
+                    ComponentKind::Attributes,
+                ],
+                emits: vec![OutputKind::Command, OutputKind::Modifier, OutputKind::Event],
+                runs_after: vec![],
+                every_tick: false,
+            },
+            route,
+            label: MERCHANT_LABEL.to_string(),
+            arrival_radius: Self::DEFAULT_ARRIVAL_RADIUS,
+            cruise_throttle: Self::DEFAULT_CRUISE_THROTTLE,
+            scatter_ticks: Self::DEFAULT_SCATTER_TICKS,
+        }
+    }
+
+    /// Controls ships carrying `label` instead of [`MERCHANT_LABEL`], e.g.
+    /// to run several convoys on different routes.
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Sets the distance at which a waypoint counts as reached.
+    #[must_use]
+    pub const fn with_arrival_radius(mut self, radius: f32) -> Self {
+        self.arrival_radius = radius;
+        self
+    }
+
+    /// Sets the throttle fraction while following the route.
+    #[must_use]
+    pub const fn with_cruise_throttle(mut self, fraction: f32) -> Self {
+        self.cruise_throttle = fraction;
+        self
+    }
+
+    /// Sets the ticks a convoy stays scattered after a hit.
+    #[must_use]
+    pub const fn with_scatter_ticks(mut self, ticks: u64) -> Self {
+        self.scatter_ticks = ticks;
+        self
+    }
+
+    /// Returns the route.
+    #[must_use]
+    pub fn route(&self) -> &[Vec2] {
+        &self.route
+    }
+
+    /// Returns the label of the ships controlled.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the distance at which a waypoint counts as reached.
+    #[must_use]
+    pub const fn arrival_radius(&self) -> f32 {
+        self.arrival_radius
+    }
+
+    /// Returns the throttle fraction while following the route.
+    #[must_use]
+    pub const fn cruise_throttle(&self) -> f32 {
+        self.cruise_throttle
+    }
+
+    /// Returns the ticks a convoy stays scattered after a hit.
+    #[must_use]
+    pub const fn scatter_ticks(&self) -> u64 {
+        self.scatter_ticks
+    }
+
+    /// Returns true if `entity` has been delivered.
+    #[must_use]
+    pub fn is_delivered(entity: &Entity) -> bool {
+        entity
+            .attribute(DELIVERED_ATTRIBUTE)
+            .and_then(AttributeValue::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// Returns the convoy `entity` sails in: the undelivered ships with the
+    /// same label and team, itself included.
+    fn convoy<'a>(&self, view: &WorldView<'a>, entity: &Entity) -> Vec<&'a Entity> {
+        view.query_by_tag(EntityTag::Ship)
+            .filter_map(|id| view.get_entity(id))
+            .filter(|e| e.has_label(&self.label) && e.team() == entity.team())
+            .filter(|e| !Self::is_delivered(e))
+            .collect()
+    }
+
+    /// Returns true if `entity` has lost HP since its last run.
+    fn was_hit(view: &WorldView, entity: &Entity) -> bool {
+        let Some(combat) = view.get_combat(entity.id()) else {
+            return false;
+        };
+        view.get_attribute(entity.id(), HP_ATTRIBUTE)
+            .and_then(AttributeValue::as_f32)
+            .is_some_and(|last| combat.hp < last)
+    }
+
+    /// Returns the bearing a merchant at `position` scatters on.
+    fn scatter_bearing(id: EntityId, position: Vec2, convoy: &[&Entity]) -> f32 {
+        let positions: Vec<Vec2> = convoy
+            .iter()
+            .filter_map(|e| e.as_ship())
+            .map(|ship| ship.transform.position)
+            .collect();
+        #[allow(clippy::cast_precision_loss)]
+        let center = positions.iter().sum::<Vec2>() / positions.len().max(1) as f32;
+        let away = position - center;
+        if positions.len() > 1 && away.length_squared() > f32::EPSILON {
+            away.y.atan2(away.x)
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            let spread = id.local() as f32 * GOLDEN_ANGLE;
+            spread.rem_euclid(TAU)
+        }
+    }
+
+    fn set_attribute(id: EntityId, key: &str, value: AttributeValue) -> Output {
+        Output::Modifier(Modifier::SetAttribute {
+            target: id,
+            key: key.to_string(),
+            value: Some(value),
+        })
+    }
+
+    fn steer(id: EntityId, heading: Option<f32>, fraction: f32, outputs: &mut Vec<Output>) {
+        if let Some(heading) = heading {
+            outputs.push(Output::Command(Command::SetHeading {
+                target: id,
+                heading,
+            }));
+        }
+        outputs.push(Output::Command(Command::SetThrottle {
+            target: id,
+            fraction: fraction.clamp(0.0, 1.0),
+        }));
+    }
+}
+
+impl Plugin for ConvoyPlugin {
+    fn declaration(&self) -> &PluginDeclaration {
+        &self.declaration
+    }
+
+    fn run(&self, ctx: &PluginContext, view: &WorldView) -> Vec<Output> {
+        let id = ctx.entity_id;
+        let (Some(entity), Some(transform), Some(combat)) = (
+            view.get_entity(id),
+            view.get_transform(id),
+            view.get_combat(id),
+        ) else {
+            return vec![];
+        };
+        if !entity.has_label(&self.label) || Self::is_delivered(entity) || combat.is_destroyed()
+        {
+            return vec![];
+        }
+        let position = transform.position;
+        let mut outputs = Vec::new();
+
+        let last_hp = view
+            .get_attribute(id, HP_ATTRIBUTE)
+            .and_then(AttributeValue::as_f32);
+        if last_hp != Some(combat.hp) {
+            outputs.push(Self::set_attribute(id, HP_ATTRIBUTE, AttributeValue::Float(combat.hp)));
+        }
+
+        let convoy = self.convoy(view, entity);
+        #[allow(clippy::cast_possible_wrap)]
+        let tick = ctx.tick as i64;
+        let mut scatter_until = view
+            .get_attribute(id, SCATTER_ATTRIBUTE)
+            .and_then(AttributeValue::as_i64)
+            .unwrap_or(0);
+        if convoy.iter().any(|e| Self::was_hit(view, e)) {
+            #[allow(clippy::cast_possible_wrap)]
+            let ticks = self.scatter_ticks as i64;
+            scatter_until = tick + ticks;
+            outputs.push(Self::set_attribute(
+                id,
+                SCATTER_ATTRIBUTE,
+                AttributeValue::Int(scatter_until),
+            ));
+        }
+        if tick < scatter_until {
+            let bearing = Self::scatter_bearing(id, position, &convoy);
+            Self::steer(id, Some(bearing), 1.0, &mut outputs);
+            return outputs;
+        }
+
+        let stored_leg = view
+            .get_attribute(id, LEG_ATTRIBUTE)
+            .and_then(AttributeValue::as_i64)
+            .unwrap_or(0);
+        let mut leg = usize::try_from(stored_leg).unwrap_or(0);
+        while self
+            .route
+            .get(leg)
+            .is_some_and(|waypoint| position.distance(*waypoint) <= self.arrival_radius)
+        {
+            leg += 1;
+        }
+        if i64::try_from(leg).ok() != Some(stored_leg) {
+            #[allow(clippy::cast_possible_wrap)]
+            let value = AttributeValue::Int(leg as i64);
+            outputs.push(Self::set_attribute(id, LEG_ATTRIBUTE, value));
+        }
+
+        let Some(waypoint) = self.route.get(leg) else {
+            outputs.push(Self::set_attribute(id, DELIVERED_ATTRIBUTE, AttributeValue::Bool(true)));
+            outputs.push(Output::Event(Event::ShipDelivered { ship: id }));
+            Self::steer(id, None, 0.0, &mut outputs);
+            return outputs;
+        };
+        let offset = *waypoint - position;
+        Self::steer(
+            id,
+            Some(offset.y.atan2(offset.x)),
+            self.cruise_throttle,
+            &mut outputs,
+        );
+        outputs
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::TraceId;
+
+    fn run_for(plugin: &ConvoyPlugin, arena: &Arena, id: EntityId) -> Vec<Output> {
+        let view = WorldView::for_plugin(arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+        plugin.run(&ctx, &view)
+    }
+
+    /// Applies the attribute modifiers among `outputs` to `arena`.
+    fn apply(arena: &mut Arena, outputs: &[Output]) {
+        for output in outputs {
+            if let Output::Modifier(Modifier::SetAttribute { target, key, value }) = output {
+                arena.set_attribute(*target, key.clone(), value.clone());
+            }
+        }
+    }
+
+    fn heading_and_throttle(outputs: &[Output]) -> (Option<f32>, f32) {
+        let heading = outputs.iter().find_map(|o| match o {
+            Output::Command(Command::SetHeading { heading, .. }) => Some(*heading),
+            _ => None,
+        });
+        let throttle = outputs.iter().find_map(|o| match o {
+            Output::Command(Command::SetThrottle { fraction, .. }) => Some(*fraction),
+            _ => None,
+        });
+        (heading, throttle.unwrap())
+    }
+
+    #[test]
+    fn follows_route_and_delivers_once() {
+        let mut arena = Arena::new();
+        let id = spawn_merchant(&mut arena, Vec2::ZERO, 0.0, Some(TeamId::new(0)));
+        let route = vec![Vec2::new(50.0, 0.0), Vec2::new(0.0, 1000.0)];
+        let plugin = ConvoyPlugin::new(route);
+
+        // The first waypoint is already within the arrival radius
+        let outputs = run_for(&plugin, &arena, id);
+        let (heading, throttle) = heading_and_throttle(&outputs);
+        assert!((heading.unwrap() - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(throttle, ConvoyPlugin::DEFAULT_CRUISE_THROTTLE);
+        apply(&mut arena, &outputs);
+        assert_eq!(
+            arena.get(id).unwrap().attribute(LEG_ATTRIBUTE),
+            Some(&AttributeValue::Int(1))
+        );
+
+        arena.get_mut(id).unwrap().as_ship_mut().unwrap().transform.position =
+            Vec2::new(0.0, 950.0);
+        let outputs = run_for(&plugin, &arena, id);
+        assert!(outputs
+            .iter()
+            .any(|o| matches!(o, Output::Event(Event::ShipDelivered { ship }) if *ship == id)));
+        apply(&mut arena, &outputs);
+        assert!(ConvoyPlugin::is_delivered(arena.get(id).unwrap()));
+        assert!(run_for(&plugin, &arena, id).is_empty());
+    }
+
+    #[test]
+    fn convoy_scatters_when_one_merchant_is_hit() {
+        let mut arena = Arena::new();
+        let team = Some(TeamId::new(0));
+        let west = spawn_merchant(&mut arena, Vec2::new(-100.0, 0.0), 0.0, team);
+        let east = spawn_merchant(&mut arena, Vec2::new(100.0, 0.0), 0.0, team);
+        let plugin = ConvoyPlugin::new(vec![Vec2::new(0.0, 5000.0)]).with_scatter_ticks(10);
+        for id in [west, east] {
+            let outputs = run_for(&plugin, &arena, id);
+            apply(&mut arena, &outputs);
+        }
+
+        arena.get_mut(west).unwrap().as_ship_mut().unwrap().combat.hp -= 10.0;
+        let outputs = run_for(&plugin, &arena, east);
+        let (heading, throttle) = heading_and_throttle(&outputs);
+        // Away from the convoy's center, at full speed
+        assert!(heading.unwrap().abs() < 1e-6);
+        assert_eq!(throttle, 1.0);
+        apply(&mut arena, &outputs);
+        assert_eq!(
+            arena.get(east).unwrap().attribute(SCATTER_ATTRIBUTE),
+            Some(&AttributeValue::Int(10))
+        );
+    }
+}
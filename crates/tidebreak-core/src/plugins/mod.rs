@@ -6,6 +6,11 @@
 //! - [`SensorPlugin`]: Detects nearby entities and emits contact events
 //! - [`WeaponPlugin`]: Fires weapons at tracked targets
 //! - [`ProjectilePlugin`]: Handles projectile behavior
+//! - [`ThreatEvaluationPlugin`]: Scores tracked contacts for threat (opt-in)
+//! - [`ProximityPlugin`]: Reports entities crossing distance thresholds (opt-in)
+//! - [`OrderFollowerPlugin`]: Carries out commanders' standing orders (opt-in)
+//! - [`ConvoyPlugin`]: Sails merchant ships along a convoy route (opt-in)
+//! - [`ScriptedPolicy`]: Drives a team's ships from frozen parameters (opt-in)
 //!
 //! # Architecture
 //!
@@ -20,12 +25,22 @@
 //! to create a registry with all MVP plugins pre-registered for their appropriate
 //! entity types.
 
+mod convoy;
 mod movement;
+mod order_follower;
 mod projectile;
+mod proximity;
+mod scripted;
 mod sensor;
+mod threat;
 mod weapon;
 
+pub use convoy::{spawn_merchant, ConvoyPlugin, DELIVERED_ATTRIBUTE, MERCHANT_LABEL};
 pub use movement::MovementPlugin;
+pub use order_follower::OrderFollowerPlugin;
 pub use projectile::ProjectilePlugin;
+pub use proximity::{ProximityPlugin, TeamFilter};
+pub use scripted::{ScriptParams, ScriptedPolicy};
 pub use sensor::SensorPlugin;
+pub use threat::{ThreatEvaluationPlugin, ThreatWeights};
 pub use weapon::WeaponPlugin;
@@ -47,6 +47,8 @@ impl MovementPlugin {
                 required_tags: vec![EntityTag::Ship, EntityTag::Squadron],
                 reads: vec![ComponentKind::Transform, ComponentKind::Physics],
                 emits: vec![OutputKind::Command],
+                runs_after: vec![],
+                every_tick: false,
             },
         }
     }
@@ -0,0 +1,341 @@
+//! Order follower plugin for subordinates under hierarchical command.
+//!
+//! The `OrderFollowerPlugin` reads its entity's standing order from the
+//! arena's [`OrderBook`](crate::orders::OrderBook) and steers toward it with
+//! `SetHeading` and `SetThrottle` commands every run, until the order is
+//! replaced or cancelled:
+//!
+//! - `MoveTo`: head for the destination at the ordered speed, stopping
+//!   within the arrival radius.
+//! - `Engage`: close on the subordinate's own track of the target, holding
+//!   with the bow on it once within the engagement range. Without a track
+//!   (or a sensor) the subordinate holds position. Firing is left to the
+//!   weapon plugin.
+//! - `Screen`: keep station at the ordered distance and bearing from the
+//!   protected entity, matching its heading and speed once on station.
+//!
+//! # Supported Entity Types
+//!
+//! - Ships
+//! - Squadrons
+//!
+//! # Outputs
+//!
+//! - `Command::SetHeading`: Course toward the order's goal
+//! - `Command::SetThrottle`: Speed toward the order's goal
+
+use glam::Vec2;
+
+use crate::entity::{EntityId, EntityTag};
+use crate::output::{Command, Order, Output, OutputKind, PluginId};
+use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+use crate::world_view::WorldView;
+
+/// Plugin translating standing orders into movement commands.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::plugin::Plugin;
+/// use tidebreak_core::plugins::OrderFollowerPlugin;
+///
+/// let plugin = OrderFollowerPlugin::new().with_engage_range(3000.0);
+/// assert_eq!(plugin.declaration().id.as_str(), "order_follower");
+/// assert_eq!(plugin.engage_range(), 3000.0);
+/// ```
+pub struct OrderFollowerPlugin {
+    declaration: PluginDeclaration,
+    /// Distance at which a goal position counts as reached
+    arrival_radius: f32,
+    /// Distance an engaging subordinate closes to
+    engage_range: f32,
+}
+
+impl OrderFollowerPlugin {
+    /// Default distance at which a goal position counts as reached.
+    pub const DEFAULT_ARRIVAL_RADIUS: f32 = 50.0;
+    /// Default distance an engaging subordinate closes to.
+    pub const DEFAULT_ENGAGE_RANGE: f32 = 2000.0;
+
+    /// Creates a plugin with the default radii.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            declaration: PluginDeclaration {
+                id: PluginId::from_static("order_follower"),
+                required_tags: vec![EntityTag::Ship, EntityTag::Squadron],
+                reads: vec![
+                    ComponentKind::Transform,
+                    ComponentKind::Physics,
+                    ComponentKind::Sensor,
+                ],
+                emits: vec![OutputKind::Command],
+                runs_after: vec![],
+                every_tick: false,
+            },
+            arrival_radius: Self::DEFAULT_ARRIVAL_RADIUS,
+            engage_range: Self::DEFAULT_ENGAGE_RANGE,
+        }
+    }
+
+    /// Sets the distance at which a goal position counts as reached.
+    #[must_use]
+    pub const fn with_arrival_radius(mut self, radius: f32) -> Self {
+        self.arrival_radius = radius;
+        self
+    }
+
+    /// Sets the distance an engaging subordinate closes to.
+    #[must_use]
+    pub const fn with_engage_range(mut self, range: f32) -> Self {
+        self.engage_range = range;
+        self
+    }
+
+    /// Returns the arrival radius.
+    #[must_use]
+    pub const fn arrival_radius(&self) -> f32 {
+        self.arrival_radius
+    }
+
+    /// Returns the engagement range.
+    #[must_use]
+    pub const fn engage_range(&self) -> f32 {
+        self.engage_range
+    }
+
+    /// Commands steering `entity` at `position` toward `goal`.
+    ///
+    /// Within `stop_radius` of the goal the entity turns to the heading in
+    /// `hold` (if any) and throttles to its fraction; otherwise it heads for
+    /// the goal at `fraction` of max speed.
+    fn steer(
+        entity: EntityId,
+        position: Vec2,
+        goal: Vec2,
+        stop_radius: f32,
+        fraction: f32,
+        hold: (Option<f32>, f32),
+    ) -> Vec<Output> {
+        let offset = goal - position;
+        let (heading, fraction) = if offset.length() <= stop_radius {
+            (hold.0, hold.1)
+        } else {
+            (Some(offset.y.atan2(offset.x)), fraction)
+        };
+        let mut outputs = Vec::with_capacity(2);
+        if let Some(heading) = heading {
+            outputs.push(Output::Command(Command::SetHeading {
+                target: entity,
+                heading,
+            }));
+        }
+        outputs.push(Output::Command(Command::SetThrottle {
+            target: entity,
+            fraction: fraction.clamp(0.0, 1.0),
+        }));
+        outputs
+    }
+}
+
+impl Default for OrderFollowerPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Plugin for OrderFollowerPlugin {
+    fn declaration(&self) -> &PluginDeclaration {
+        &self.declaration
+    }
+
+    fn run(&self, ctx: &PluginContext, view: &WorldView) -> Vec<Output> {
+        let id = ctx.entity_id;
+        let (Some(standing), Some(transform), Some(physics)) = (
+            view.standing_order(id),
+            view.get_transform(id),
+            view.get_physics(id),
+        ) else {
+            return vec![];
+        };
+        let position = transform.position;
+        let max_speed = physics.max_speed.max(f32::EPSILON);
+
+        match standing.order {
+            Order::MoveTo {
+                destination, speed, ..
+            } => {
+                let fraction = if speed > 0.0 { speed / max_speed } else { 1.0 };
+                Self::steer(
+                    id,
+                    position,
+                    destination,
+                    self.arrival_radius,
+                    fraction,
+                    (None, 0.0),
+                )
+            }
+            Order::Engage { target, .. } => {
+                let track = view
+                    .get_sensor(id)
+                    .and_then(|sensor| sensor.find_track(target));
+                let Some(track) = track else {
+                    return Self::steer(id, position, position, 0.0, 0.0, (None, 0.0));
+                };
+                let offset = track.position - position;
+                let bearing = offset.y.atan2(offset.x);
+                Self::steer(
+                    id,
+                    position,
+                    track.position,
+                    self.engage_range,
+                    1.0,
+                    (Some(bearing), 0.0),
+                )
+            }
+            Order::Screen {
+                protect,
+                distance,
+                bearing,
+                ..
+            } => {
+                let (Some(escorted), Some(escorted_physics)) =
+                    (view.get_transform(protect), view.get_physics(protect))
+                else {
+                    return Self::steer(id, position, position, 0.0, 0.0, (None, 0.0));
+                };
+                let direction = Vec2::from_angle(escorted.heading + bearing);
+                let station = escorted.position + direction * distance;
+                let matched = escorted_physics.velocity.length() / max_speed;
+                Self::steer(
+                    id,
+                    position,
+                    station,
+                    self.arrival_radius,
+                    1.0,
+                    (Some(escorted.heading), matched),
+                )
+            }
+            Order::Cancel { .. } => vec![],
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+    use crate::entity::{Track, TrackQuality};
+    use crate::output::TraceId;
+    use crate::tests::spawn_team_ship;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn run_for(arena: &Arena, id: EntityId) -> Vec<Command> {
+        let plugin = OrderFollowerPlugin::new();
+        let view = WorldView::for_plugin(arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+        plugin
+            .run(&ctx, &view)
+            .into_iter()
+            .filter_map(|output| output.as_command().cloned())
+            .collect()
+    }
+
+    fn heading_and_throttle(commands: &[Command]) -> (Option<f32>, f32) {
+        let heading = commands.iter().find_map(|c| match c {
+            Command::SetHeading { heading, .. } => Some(*heading),
+            _ => None,
+        });
+        let throttle = commands.iter().find_map(|c| match c {
+            Command::SetThrottle { fraction, .. } => Some(*fraction),
+            _ => None,
+        });
+        (heading, throttle.unwrap())
+    }
+
+    #[test]
+    fn idle_without_standing_order() {
+        let mut arena = Arena::new();
+        let id = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        assert!(run_for(&arena, id).is_empty());
+    }
+
+    #[test]
+    fn move_to_heads_for_destination_and_stops() {
+        let mut arena = Arena::new();
+        let commander = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let id = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let max_speed = arena.get(id).unwrap().as_ship().unwrap().physics.max_speed;
+        let order = Order::MoveTo {
+            commander,
+            subordinate: id,
+            destination: Vec2::new(0.0, 1000.0),
+            speed: max_speed / 2.0,
+        };
+        arena.orders_mut().issue(order, 0);
+
+        let (heading, throttle) = heading_and_throttle(&run_for(&arena, id));
+        assert!((heading.unwrap() - FRAC_PI_2).abs() < 1e-6);
+        assert!((throttle - 0.5).abs() < 1e-6);
+
+        arena.get_mut(id).unwrap().as_ship_mut().unwrap().transform.position =
+            Vec2::new(0.0, 990.0);
+        assert_eq!(heading_and_throttle(&run_for(&arena, id)), (None, 0.0));
+    }
+
+    #[test]
+    fn engage_needs_a_track() {
+        let mut arena = Arena::new();
+        let commander = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let id = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let target = EntityId::new(99);
+        let order = Order::Engage {
+            commander,
+            subordinate: id,
+            target,
+        };
+        arena.orders_mut().issue(order, 0);
+        assert_eq!(heading_and_throttle(&run_for(&arena, id)), (None, 0.0));
+
+        let sensor = &mut arena.get_mut(id).unwrap().as_ship_mut().unwrap().sensor;
+        sensor.track_table.push(Track::new(
+            target,
+            Vec2::new(5000.0, 0.0),
+            TrackQuality::Coarse,
+        ));
+        assert_eq!(heading_and_throttle(&run_for(&arena, id)), (Some(0.0), 1.0));
+    }
+
+    #[test]
+    fn screen_keeps_station_on_protected_entity() {
+        let mut arena = Arena::new();
+        let flagship = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let id = spawn_team_ship(&mut arena, Vec2::new(0.0, -2000.0), Some(0));
+        let order = Order::Screen {
+            commander: flagship,
+            subordinate: id,
+            protect: flagship,
+            distance: 1000.0,
+            bearing: 0.0,
+        };
+        arena.orders_mut().issue(order, 0);
+
+        // Station is 1000 m ahead of the flagship, i.e. at (1000, 0)
+        let (heading, throttle) = heading_and_throttle(&run_for(&arena, id));
+        let expected = Vec2::new(1000.0, 2000.0);
+        assert!((heading.unwrap() - expected.y.atan2(expected.x)).abs() < 1e-6);
+        assert_eq!(throttle, 1.0);
+
+        arena.get_mut(id).unwrap().as_ship_mut().unwrap().transform.position =
+            Vec2::new(1000.0, 0.0);
+        assert_eq!(heading_and_throttle(&run_for(&arena, id)), (Some(0.0), 0.0));
+    }
+}
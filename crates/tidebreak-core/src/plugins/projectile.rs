@@ -1,8 +1,17 @@
 //! Projectile plugin for in-flight weapon behavior.
 //!
-//! The `ProjectilePlugin` handles projectile movement and guidance.
-//! In MVP, projectiles maintain their current velocity. Future versions
-//! will implement homing and target tracking.
+//! The `ProjectilePlugin` steers guided projectiles. Unguided rounds (no
+//! [`SeekerState`]) keep their current velocity.
+//!
+//! A guided projectile homes on the target its seeker has locked, turning
+//! no faster than its `max_turn_rate`. Each run the lock is checked again:
+//! a target that has left the cone or range, is screened by smoke, is
+//! masked by solid terrain, or has been destroyed is lost. Without a lock
+//! the projectile flies its [`SearchPattern`] and locks the candidate
+//! nearest the seeker's axis, preferring the cued target.
+//!
+//! Guidance runs on every physics tick, also between the decision ticks of
+//! a simulation with an action interval.
 //!
 //! # Supported Entity Types
 //!
@@ -10,19 +19,28 @@
 //!
 //! # Outputs
 //!
-//! Currently emits no outputs (projectiles maintain their velocity).
-//! Future versions will emit `SetVelocity` commands for homing.
+//! - `Command::SetHeading`, `Command::SetVelocity`: Turn toward the target
+//!   or along the search pattern
+//! - `Event::LockAcquired`: The seeker locked onto a target
+//! - `Event::LockLost`: The seeker lost its target
+//!
+//! # State
+//!
+//! The plugin keeps no state of its own. The lock lives in the projectile's
+//! [`SeekerState::lock`], which the [`crate::resolver::TrackResolver`]
+//! updates from the lock events, so it is hashed, diffed and restored with
+//! the rest of the arena.
 
-use crate::entity::EntityTag;
-use crate::output::{Output, OutputKind, PluginId};
+use glam::Vec2;
+
+use crate::entity::{CombatState, Entity, EntityId, EntityTag, SearchPattern, SeekerState};
+use crate::output::{Command, Event, Output, OutputKind, PluginId};
 use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+use crate::resolver::FIXED_DT;
 use crate::world_view::WorldView;
 
 /// Plugin that handles projectile behavior.
 ///
-/// For MVP, projectiles maintain their current velocity.
-/// Later versions will implement homing and target tracking.
-///
 /// # Example
 ///
 /// ```
@@ -44,11 +62,68 @@ impl ProjectilePlugin {
             declaration: PluginDeclaration {
                 id: PluginId::from_static("projectile"),
                 required_tags: vec![EntityTag::Projectile],
-                reads: vec![ComponentKind::Transform, ComponentKind::Physics],
-                emits: vec![OutputKind::Command],
+                reads: vec![
+                    ComponentKind::Transform,
+                    ComponentKind::Physics,
+                    ComponentKind::Combat,
+                ],
+                emits: vec![OutputKind::Command, OutputKind::Event],
+                runs_after: vec![],
+                // Guidance keeps steering between agent decisions
+                every_tick: true,
             },
         }
     }
+
+    /// Returns the position of `target` if a seeker at `from` looking along
+    /// `boresight` can hold it.
+    fn visible(
+        view: &WorldView,
+        projectile: &Entity,
+        seeker: &SeekerState,
+        from: Vec2,
+        boresight: f32,
+        target: EntityId,
+    ) -> Option<Vec2> {
+        let entity = view.get_entity(target)?;
+        if !matches!(entity.tag(), EntityTag::Ship | EntityTag::Squadron)
+            || projectile.is_friendly_to(entity)
+            || view.get_combat(target).is_some_and(CombatState::is_destroyed)
+        {
+            return None;
+        }
+        let to = view.get_transform(target)?.position;
+        let environment = view.environment();
+        (seeker.in_cone(from, boresight, to)
+            && !environment.is_screened(from, to)
+            && !environment.is_occluded(from, to))
+        .then_some(to)
+    }
+
+    /// Returns the target to lock, if any: the cue when visible, otherwise
+    /// the candidate nearest the seeker's axis.
+    fn acquire(
+        view: &WorldView,
+        projectile: &Entity,
+        seeker: &SeekerState,
+        from: Vec2,
+        boresight: f32,
+    ) -> Option<EntityId> {
+        if let Some(cue) = seeker.cue {
+            if Self::visible(view, projectile, seeker, from, boresight, cue).is_some() {
+                return Some(cue);
+            }
+        }
+        view.query_in_radius(from, seeker.range)
+            .into_iter()
+            .filter(|&id| id != projectile.id())
+            .filter_map(|id| {
+                let to = Self::visible(view, projectile, seeker, from, boresight, id)?;
+                Some((SeekerState::off_axis(from, boresight, to).abs(), id))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+            .map(|(_, id)| id)
+    }
 }
 
 impl Default for ProjectilePlugin {
@@ -62,10 +137,54 @@ impl Plugin for ProjectilePlugin {
         &self.declaration
     }
 
-    fn run(&self, _ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
-        // For MVP: projectiles just maintain current velocity
-        // Later: implement homing toward target
-        vec![]
+    #[allow(clippy::cast_precision_loss)]
+    fn run(&self, ctx: &PluginContext, view: &WorldView) -> Vec<Output> {
+        let id = ctx.entity_id;
+        let Some(entity) = view.get_entity(id) else {
+            return vec![];
+        };
+        let Some(projectile) = entity.as_projectile() else {
+            return vec![];
+        };
+        let Some(seeker) = projectile.seeker else {
+            return vec![];
+        };
+        let position = projectile.transform.position;
+        let heading = projectile.transform.heading;
+        let mut outputs = Vec::new();
+
+        let held = seeker.lock.and_then(|target| {
+            let to = Self::visible(view, entity, &seeker, position, heading, target);
+            if to.is_none() {
+                outputs.push(Output::Event(Event::LockLost { seeker: id, target }));
+            }
+            to.map(|to| (target, to))
+        });
+        let locked = held.or_else(|| {
+            let boresight = heading + seeker.search_offset(ctx.tick as f32 * FIXED_DT);
+            let target = Self::acquire(view, entity, &seeker, position, boresight)?;
+            outputs.push(Output::Event(Event::LockAcquired { seeker: id, target }));
+            let to = view.get_transform(target)?.position;
+            Some((target, to))
+        });
+
+        let max_turn = projectile.physics.max_turn_rate * FIXED_DT;
+        let turn = match (locked, seeker.search) {
+            (Some((_, to)), _) => SeekerState::off_axis(position, heading, to),
+            (None, SearchPattern::Orbit) => max_turn,
+            (None, SearchPattern::Straight | SearchPattern::Sweep { .. }) => 0.0,
+        }
+        .clamp(-max_turn, max_turn);
+        if turn != 0.0 {
+            let heading = heading + turn;
+            let speed = projectile.physics.velocity.length();
+            outputs.push(Output::Command(Command::SetHeading { target: id, heading }));
+            outputs.push(Output::Command(Command::SetVelocity {
+                target: id,
+                velocity: Vec2::from_angle(heading) * speed,
+            }));
+        }
+        outputs
     }
 }
 
@@ -77,7 +196,7 @@ impl Plugin for ProjectilePlugin {
 mod tests {
     use super::*;
     use crate::arena::Arena;
-    use crate::entity::{EntityId, EntityInner, ProjectileComponents};
+    use crate::entity::{EntityInner, ProjectileComponents};
     use crate::output::TraceId;
     use glam::Vec2;
 
@@ -113,6 +232,11 @@ mod tests {
         assert!(decl.reads.contains(&ComponentKind::Physics));
     }
 
+    #[test]
+    fn declaration_runs_every_tick() {
+        assert!(ProjectilePlugin::new().declaration().every_tick);
+    }
+
     #[test]
     fn declaration_emits_commands() {
         let plugin = ProjectilePlugin::new();
@@ -163,6 +287,177 @@ mod tests {
         assert!(outputs.is_empty());
     }
 
+    mod seeker_tests {
+        use super::*;
+        use crate::entity::TeamId;
+        use crate::environment::{OccupancyField, SmokeField};
+        use crate::output::{OutputEnvelope, PluginInstanceId};
+        use crate::resolver::{Resolver, TrackResolver};
+        use crate::tests::spawn_team_ship;
+        use std::f32::consts::FRAC_PI_2;
+
+        fn missile(arena: &mut Arena, seeker: SeekerState) -> EntityId {
+            let components = ProjectileComponents::at_position_with_velocity(
+                Vec2::ZERO,
+                0.0,
+                Vec2::new(300.0, 0.0),
+            )
+            .with_seeker(seeker);
+            let id = arena.spawn(EntityTag::Projectile, EntityInner::Projectile(components));
+            arena.set_team(id, Some(TeamId::new(0)));
+            id
+        }
+
+        /// Runs the plugin for `id` and records its lock events in `arena`.
+        fn run_for(plugin: &ProjectilePlugin, arena: &mut Arena, id: EntityId) -> Vec<Output> {
+            let view = WorldView::for_plugin(arena, plugin.declaration(), arena.current_tick());
+            let ctx = PluginContext {
+                entity_id: id,
+                tick: arena.current_tick(),
+                trace_id: TraceId::new(0),
+            };
+            let outputs = plugin.run(&ctx, &view);
+            let source = PluginInstanceId::new(id, plugin.declaration().id.clone());
+            let envelopes: Vec<OutputEnvelope> = (0..)
+                .zip(&outputs)
+                .map(|(seq, output)| {
+                    OutputEnvelope::new(output.clone(), source.clone(), ctx.trace_id, 0, seq)
+                })
+                .collect();
+            let current = arena.clone();
+            TrackResolver::new().resolve(&envelopes.iter().collect::<Vec<_>>(), &current, arena);
+            outputs
+        }
+
+        fn lock_of(arena: &Arena, id: EntityId) -> Option<EntityId> {
+            arena.get(id)?.as_projectile()?.seeker?.lock
+        }
+
+        fn events(outputs: &[Output]) -> Vec<Event> {
+            outputs.iter().filter_map(|o| o.as_event().cloned()).collect()
+        }
+
+        fn heading(outputs: &[Output]) -> Option<f32> {
+            outputs.iter().find_map(|o| match o {
+                Output::Command(Command::SetHeading { heading, .. }) => Some(*heading),
+                _ => None,
+            })
+        }
+
+        fn move_to(arena: &mut Arena, id: EntityId, position: Vec2) {
+            arena.get_mut(id).unwrap().as_ship_mut().unwrap().transform.position = position;
+        }
+
+        #[test]
+        fn locks_cue_and_turns_toward_it_at_turn_rate() {
+            let mut arena = Arena::new();
+            let _decoy = spawn_team_ship(&mut arena, Vec2::new(3000.0, 0.0), Some(1));
+            let target = spawn_team_ship(&mut arena, Vec2::new(3000.0, 1000.0), Some(1));
+            let id = missile(&mut arena, SeekerState {
+                cue: Some(target),
+                ..SeekerState::default()
+            });
+            let plugin = ProjectilePlugin::new();
+
+            let outputs = run_for(&plugin, &mut arena, id);
+            assert_eq!(events(&outputs), vec![Event::LockAcquired { seeker: id, target }]);
+            assert_eq!(lock_of(&arena, id), Some(target));
+            let turn = heading(&outputs).unwrap();
+            assert!((turn - 0.5 * FIXED_DT).abs() < 1e-6);
+            let velocity = outputs.iter().find_map(|o| match o {
+                Output::Command(Command::SetVelocity { velocity, .. }) => Some(*velocity),
+                _ => None,
+            });
+            assert!((velocity.unwrap().length() - 300.0).abs() < 1e-3);
+
+            // A held lock raises no further events
+            assert!(events(&run_for(&plugin, &mut arena, id)).is_empty());
+            assert_eq!(lock_of(&arena, id), Some(target));
+        }
+
+        #[test]
+        fn uncued_seeker_takes_target_nearest_axis() {
+            let mut arena = Arena::new();
+            let _friend = spawn_team_ship(&mut arena, Vec2::new(1000.0, 0.0), Some(0));
+            let _wide = spawn_team_ship(&mut arena, Vec2::new(2000.0, 800.0), Some(1));
+            let near_axis = spawn_team_ship(&mut arena, Vec2::new(4000.0, -100.0), Some(1));
+            let id = missile(&mut arena, SeekerState::default());
+            let plugin = ProjectilePlugin::new();
+
+            run_for(&plugin, &mut arena, id);
+            assert_eq!(lock_of(&arena, id), Some(near_axis));
+        }
+
+        #[test]
+        fn target_turning_out_of_cone_breaks_lock() {
+            let mut arena = Arena::new();
+            let target = spawn_team_ship(&mut arena, Vec2::new(2000.0, 0.0), Some(1));
+            let id = missile(&mut arena, SeekerState::default());
+            let plugin = ProjectilePlugin::new();
+            run_for(&plugin, &mut arena, id);
+
+            move_to(&mut arena, target, Vec2::new(0.0, 2000.0));
+            let outputs = run_for(&plugin, &mut arena, id);
+            assert_eq!(events(&outputs), vec![Event::LockLost { seeker: id, target }]);
+            assert_eq!(lock_of(&arena, id), None);
+            // Straight search holds course
+            assert!(heading(&outputs).is_none());
+
+            move_to(&mut arena, target, Vec2::new(2000.0, 100.0));
+            let outputs = run_for(&plugin, &mut arena, id);
+            assert_eq!(events(&outputs), vec![Event::LockAcquired { seeker: id, target }]);
+        }
+
+        #[test]
+        fn smoke_and_terrain_mask_target() {
+            let mut arena = Arena::new();
+            let target = spawn_team_ship(&mut arena, Vec2::new(2000.0, 0.0), Some(1));
+            let id = missile(&mut arena, SeekerState::default());
+            let plugin = ProjectilePlugin::new();
+            run_for(&plugin, &mut arena, id);
+
+            arena.environment_mut().smoke = Some(SmokeField::uniform(1.0));
+            let outputs = run_for(&plugin, &mut arena, id);
+            assert_eq!(events(&outputs), vec![Event::LockLost { seeker: id, target }]);
+
+            arena.environment_mut().smoke = None;
+            arena.environment_mut().occupancy = Some(OccupancyField::uniform(1.0));
+            assert!(events(&run_for(&plugin, &mut arena, id)).is_empty());
+            assert_eq!(lock_of(&arena, id), None);
+        }
+
+        #[test]
+        fn sweep_widens_search_and_orbit_turns() {
+            let mut arena = Arena::new();
+            let _target = spawn_team_ship(&mut arena, Vec2::new(0.0, 2000.0), Some(1));
+            let sweep = SearchPattern::Sweep {
+                half_angle: FRAC_PI_2,
+                period: 4.0,
+            };
+            let id = missile(&mut arena, SeekerState {
+                search: sweep,
+                ..SeekerState::default()
+            });
+            let plugin = ProjectilePlugin::new();
+
+            // At tick 0 the head looks ahead; a quarter period later it
+            // looks abeam
+            assert!(events(&run_for(&plugin, &mut arena, id)).is_empty());
+            for _ in 0..60 {
+                arena.advance_tick();
+            }
+            assert_eq!(events(&run_for(&plugin, &mut arena, id)).len(), 1);
+
+            let orbiter = missile(&mut arena, SeekerState {
+                range: 100.0,
+                search: SearchPattern::Orbit,
+                ..SeekerState::default()
+            });
+            let turn = heading(&run_for(&plugin, &mut arena, orbiter)).unwrap();
+            assert!((turn - 0.5 * FIXED_DT).abs() < 1e-6);
+        }
+    }
+
     #[test]
     fn plugin_is_send_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
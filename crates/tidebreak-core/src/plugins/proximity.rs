@@ -0,0 +1,377 @@
+//! Proximity plugin for range-crossing triggers.
+//!
+//! The `ProximityPlugin` watches the distance from its entity (the observer)
+//! to every other entity and reports when one crosses any of the configured
+//! radii. Candidates come from the spatial index, so each run costs one
+//! radius query rather than a scan over all pairs. Targets can be filtered
+//! by tag and by team relation to the observer.
+//!
+//! # Supported Entity Types
+//!
+//! - All (register for the observer tags of interest)
+//!
+//! # Outputs
+//!
+//! - `Event::EnteredRange`: A target came within a radius (distance at most
+//!   the radius)
+//! - `Event::LeftRange`: A target moved beyond a radius, or was removed
+//!
+//! # State
+//!
+//! Which targets were inside each radius on the previous run is kept in the
+//! arena's [`ProximityBook`](crate::proximity::ProximityBook) under the
+//! plugin's ID, where the
+//! [`ProximityResolver`](crate::resolver::ProximityResolver) records the
+//! crossings, so restoring or forking the arena carries it along. Plugins
+//! watching the same observers need distinct IDs (see
+//! [`ProximityPlugin::with_id`]).
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{Entity, EntityTag};
+use crate::output::{Event, Output, OutputKind, PluginId};
+use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+use crate::world_view::WorldView;
+
+/// Team relation a target must have to the observer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TeamFilter {
+    /// Every target.
+    #[default]
+    Any,
+    /// Targets on the observer's team.
+    Friendly,
+    /// Targets not on the observer's team, including unteamed ones.
+    Hostile,
+}
+
+impl TeamFilter {
+    /// Returns true if `target` passes the filter as seen from `observer`.
+    #[must_use]
+    pub fn accepts(self, observer: &Entity, target: &Entity) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Friendly => observer.is_friendly_to(target),
+            Self::Hostile => !observer.is_friendly_to(target),
+        }
+    }
+}
+
+/// Plugin that reports targets crossing distance thresholds.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::entity::EntityTag;
+/// use tidebreak_core::plugin::Plugin;
+/// use tidebreak_core::plugins::{ProximityPlugin, TeamFilter};
+///
+/// let plugin = ProximityPlugin::new(500.0)
+///     .with_radii([2000.0, 500.0])
+///     .with_target_tags([EntityTag::Ship])
+///     .with_team_filter(TeamFilter::Hostile);
+/// assert_eq!(plugin.declaration().id.as_str(), "proximity");
+/// assert_eq!(plugin.radii(), &[500.0, 2000.0]);
+/// ```
+pub struct ProximityPlugin {
+    declaration: PluginDeclaration,
+    /// Thresholds, ascending
+    radii: Vec<f32>,
+    /// Target tags considered; empty means all
+    target_tags: Vec<EntityTag>,
+    team: TeamFilter,
+}
+
+impl ProximityPlugin {
+    /// Creates a plugin reporting crossings of a single `radius`.
+    #[must_use]
+    pub fn new(radius: f32) -> Self {
+        Self {
+            declaration: PluginDeclaration {
+                id: PluginId::from_static("proximity"),
+                required_tags: vec![
+                    EntityTag::Ship,
+                    EntityTag::Platform,
+                    EntityTag::Projectile,
+                    EntityTag::Squadron,
+                ],
+                reads: vec![ComponentKind::Transform],
+                emits: vec![OutputKind::Event],
+                runs_after: vec![],
+                every_tick: false,
+            },
+            radii: Vec::new(),
+            target_tags: Vec::new(),
+            team: TeamFilter::Any,
+        }
+        .with_radii([radius])
+    }
+
+    /// Replaces the plugin ID (default "proximity"), under which the
+    /// targets in range are kept in the arena.
+    #[must_use]
+    pub fn with_id(mut self, id: PluginId) -> Self {
+        self.declaration.id = id;
+        self
+    }
+
+    /// Replaces the thresholds with `radii`.
+    ///
+    /// Radii that are not finite and positive are ignored; duplicates are
+    /// merged.
+    #[must_use]
+    pub fn with_radii(mut self, radii: impl IntoIterator<Item = f32>) -> Self {
+        self.radii = radii
+            .into_iter()
+            .filter(|r| r.is_finite() && *r > 0.0)
+            .collect();
+        self.radii.sort_by(f32::total_cmp);
+        self.radii.dedup();
+        self
+    }
+
+    /// Only reports targets with one of `tags`.
+    #[must_use]
+    pub fn with_target_tags(mut self, tags: impl IntoIterator<Item = EntityTag>) -> Self {
+        self.target_tags = tags.into_iter().collect();
+        self
+    }
+
+    /// Only reports targets with the given team relation to the observer.
+    #[must_use]
+    pub const fn with_team_filter(mut self, team: TeamFilter) -> Self {
+        self.team = team;
+        self
+    }
+
+    /// Returns the thresholds, ascending.
+    #[must_use]
+    pub fn radii(&self) -> &[f32] {
+        &self.radii
+    }
+
+    /// Returns the target tags considered; empty means all.
+    #[must_use]
+    pub fn target_tags(&self) -> &[EntityTag] {
+        &self.target_tags
+    }
+
+    /// Returns the team filter.
+    #[must_use]
+    pub const fn team_filter(&self) -> TeamFilter {
+        self.team
+    }
+
+    /// Returns true if `target` is a candidate for `observer`.
+    fn accepts(&self, observer: &Entity, target: &Entity) -> bool {
+        (self.target_tags.is_empty() || self.target_tags.contains(&target.tag()))
+            && self.team.accepts(observer, target)
+    }
+}
+
+impl Plugin for ProximityPlugin {
+    fn declaration(&self) -> &PluginDeclaration {
+        &self.declaration
+    }
+
+    fn run(&self, ctx: &PluginContext, view: &WorldView) -> Vec<Output> {
+        let observer = ctx.entity_id;
+        let (Some(entity), Some(transform), Some(&outer)) = (
+            view.get_entity(observer),
+            view.get_transform(observer),
+            self.radii.last(),
+        ) else {
+            return vec![];
+        };
+
+        let mut now = BTreeSet::new();
+        for target in view.query_in_radius(transform.position, outer) {
+            if target == observer {
+                continue;
+            }
+            let (Some(other), Some(other_transform)) =
+                (view.get_entity(target), view.get_transform(target))
+            else {
+                continue;
+            };
+            if !self.accepts(entity, other) {
+                continue;
+            }
+            let distance = transform.position.distance(other_transform.position);
+            let first = self.radii.partition_point(|r| *r < distance);
+            now.extend((first..self.radii.len()).map(|index| (target, index)));
+        }
+
+        let before: BTreeSet<_> = view
+            .in_range(&self.declaration.id, observer)
+            .filter_map(|(target, radius)| {
+                let index = self.radii.iter().position(|r| r.to_bits() == radius.to_bits())?;
+                Some((target, index))
+            })
+            .collect();
+        let left = before.difference(&now).map(|&(target, index)| {
+            Output::Event(Event::LeftRange {
+                observer,
+                target,
+                radius: self.radii[index],
+            })
+        });
+        let entered = now.difference(&before).map(|&(target, index)| {
+            Output::Event(Event::EnteredRange {
+                observer,
+                target,
+                radius: self.radii[index],
+            })
+        });
+        left.chain(entered).collect()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+    use crate::entity::{EntityId, TeamId};
+    use crate::output::{OutputEnvelope, PluginInstanceId, TraceId};
+    use crate::resolver::{ProximityResolver, Resolver};
+    use crate::tests::spawn_team_ship;
+    use glam::Vec2;
+
+    /// Runs the plugin for `id` and records its crossings in `arena`.
+    fn run_for(arena: &mut Arena, plugin: &ProximityPlugin, id: EntityId) -> Vec<Event> {
+        let view = WorldView::for_plugin(arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+        let source = PluginInstanceId::new(id, plugin.declaration().id.clone());
+        let envelopes: Vec<OutputEnvelope> = (0..)
+            .zip(plugin.run(&ctx, &view))
+            .map(|(seq, output)| OutputEnvelope::new(output, source.clone(), ctx.trace_id, 0, seq))
+            .collect();
+        let current = arena.clone();
+        ProximityResolver::new().resolve(&envelopes.iter().collect::<Vec<_>>(), &current, arena);
+        envelopes
+            .iter()
+            .filter_map(|envelope| envelope.output().as_event().cloned())
+            .collect()
+    }
+
+    fn move_to(arena: &mut Arena, id: EntityId, position: Vec2) {
+        arena.get_mut(id).unwrap().as_ship_mut().unwrap().transform.position = position;
+        arena.update_spatial(id);
+    }
+
+    #[test]
+    fn reports_entering_and_leaving() {
+        let mut arena = Arena::new();
+        let observer = spawn_team_ship(&mut arena, Vec2::ZERO, None);
+        let target = spawn_team_ship(&mut arena, Vec2::new(900.0, 0.0), None);
+        let plugin = ProximityPlugin::new(500.0);
+
+        assert!(run_for(&mut arena, &plugin, observer).is_empty());
+
+        move_to(&mut arena, target, Vec2::new(400.0, 0.0));
+        let events = run_for(&mut arena, &plugin, observer);
+        assert_eq!(
+            events,
+            vec![Event::EnteredRange {
+                observer,
+                target,
+                radius: 500.0
+            }]
+        );
+        // Staying inside reports nothing
+        assert!(run_for(&mut arena, &plugin, observer).is_empty());
+
+        move_to(&mut arena, target, Vec2::new(600.0, 0.0));
+        let events = run_for(&mut arena, &plugin, observer);
+        assert!(matches!(events[..], [Event::LeftRange { radius, .. }] if radius == 500.0));
+    }
+
+    #[test]
+    fn each_radius_is_crossed_separately() {
+        let mut arena = Arena::new();
+        let observer = spawn_team_ship(&mut arena, Vec2::ZERO, None);
+        spawn_team_ship(&mut arena, Vec2::new(300.0, 0.0), None);
+        let plugin = ProximityPlugin::new(500.0).with_radii([1000.0, 500.0, 200.0]);
+
+        let radii: Vec<_> = run_for(&mut arena, &plugin, observer)
+            .into_iter()
+            .map(|event| match event {
+                Event::EnteredRange { radius, .. } => radius,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(radii, vec![500.0, 1000.0]);
+    }
+
+    #[test]
+    fn removed_target_leaves_range() {
+        let mut arena = Arena::new();
+        let observer = spawn_team_ship(&mut arena, Vec2::ZERO, None);
+        let target = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), None);
+        let plugin = ProximityPlugin::new(500.0);
+        run_for(&mut arena, &plugin, observer);
+
+        arena.despawn(target);
+        let events = run_for(&mut arena, &plugin, observer);
+        assert!(matches!(events[..], [Event::LeftRange { target: t, .. }] if t == target));
+    }
+
+    #[test]
+    fn filters_by_team_and_tag() {
+        let mut arena = Arena::new();
+        let observer = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+        spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(1));
+        let enemy = spawn_team_ship(&mut arena, Vec2::new(200.0, 0.0), Some(2));
+
+        let hostile = ProximityPlugin::new(500.0).with_team_filter(TeamFilter::Hostile);
+        let events = run_for(&mut arena, &hostile, observer);
+        assert!(matches!(events[..], [Event::EnteredRange { target, .. }] if target == enemy));
+
+        let platforms = ProximityPlugin::new(500.0)
+            .with_id(PluginId::from_static("platform_proximity"))
+            .with_target_tags([EntityTag::Platform]);
+        assert!(run_for(&mut arena, &platforms, observer).is_empty());
+    }
+
+    #[test]
+    fn targets_in_range_travel_with_the_arena() {
+        let mut arena = Arena::new();
+        let observer = spawn_team_ship(&mut arena, Vec2::ZERO, None);
+        spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), None);
+        let plugin = ProximityPlugin::new(500.0);
+        let mut snapshot = arena.clone();
+        assert_eq!(run_for(&mut arena, &plugin, observer).len(), 1);
+
+        let mut fork = arena.clone();
+        assert!(run_for(&mut fork, &plugin, observer).is_empty());
+        // The snapshot predates the crossing
+        assert_eq!(run_for(&mut snapshot, &plugin, observer).len(), 1);
+    }
+
+    #[test]
+    fn watches_with_distinct_ids_keep_separate_records() {
+        let mut arena = Arena::new();
+        let observer = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+        spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(1));
+        let any = ProximityPlugin::new(500.0);
+        let hostile = ProximityPlugin::new(500.0)
+            .with_id(PluginId::from_static("hostile_proximity"))
+            .with_team_filter(TeamFilter::Hostile);
+        assert_eq!(run_for(&mut arena, &any, observer).len(), 1);
+
+        // The friend turns hostile: news to the hostile watch only
+        arena.set_team(EntityId::new(1), Some(TeamId::new(2)));
+        assert!(run_for(&mut arena, &any, observer).is_empty());
+        assert_eq!(run_for(&mut arena, &hostile, observer).len(), 1);
+    }
+}
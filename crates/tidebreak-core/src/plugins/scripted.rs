@@ -0,0 +1,292 @@
+//! Scripted policy plugin for frozen opponents.
+//!
+//! A `ScriptedPolicy` drives every ship of one team from a fixed set of
+//! [`ScriptParams`], so an opponent snapshot is plain data that can be
+//! stored in a league file (see [`crate::league`]) and replayed exactly:
+//!
+//! - **Retreat**: below `retreat_below` of max HP, run from the nearest
+//!   hostile track at full throttle.
+//! - **Close / open**: outside `preferred_range` (plus or minus
+//!   `range_tolerance`), head toward or away from the nearest hostile track
+//!   at `throttle`.
+//! - **Hold**: within the band, steer `attack_angle` off the target's
+//!   bearing at half `throttle`.
+//! - **Patrol**: with no hostile track, keep the current heading at
+//!   `patrol_throttle`.
+//!
+//! Firing is left to the weapon plugin.
+//!
+//! # Supported Entity Types
+//!
+//! - Ships
+//!
+//! # Outputs
+//!
+//! - `Command::SetHeading`: Course for the current behavior
+//! - `Command::SetThrottle`: Speed for the current behavior
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::components::Track;
+use crate::entity::{EntityId, EntityTag, TeamId};
+use crate::output::{Command, Output, OutputKind, PluginId};
+use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+use crate::world_view::WorldView;
+
+/// Tunable behavior of a scripted policy.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptParams {
+    /// Distance kept from the target, in meters
+    pub preferred_range: f32,
+    /// Fraction of `preferred_range` the distance may stray before closing
+    /// or opening
+    pub range_tolerance: f32,
+    /// Throttle fraction while closing or opening range
+    pub throttle: f32,
+    /// Throttle fraction with no hostile track
+    pub patrol_throttle: f32,
+    /// HP fraction below which the ship runs
+    pub retreat_below: f32,
+    /// Heading offset from the target's bearing while holding range, in
+    /// radians (0 is bow-on, pi/2 broadside)
+    pub attack_angle: f32,
+}
+
+impl Default for ScriptParams {
+    fn default() -> Self {
+        Self {
+            preferred_range: 3000.0,
+            range_tolerance: 0.2,
+            throttle: 1.0,
+            patrol_throttle: 0.3,
+            retreat_below: 0.25,
+            attack_angle: 0.0,
+        }
+    }
+}
+
+/// Plugin driving one team's ships with frozen [`ScriptParams`].
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::entity::TeamId;
+/// use tidebreak_core::plugin::Plugin;
+/// use tidebreak_core::plugins::{ScriptParams, ScriptedPolicy};
+///
+/// let params = ScriptParams { preferred_range: 5000.0, ..ScriptParams::default() };
+/// let policy = ScriptedPolicy::new("kiter-v3", params, TeamId::new(1));
+/// assert_eq!(policy.declaration().id.as_str(), "scripted_policy");
+/// assert_eq!(policy.name(), "kiter-v3");
+/// ```
+pub struct ScriptedPolicy {
+    declaration: PluginDeclaration,
+    /// Name of the snapshot, for logs and league results
+    name: String,
+    params: ScriptParams,
+    /// Team whose ships are driven
+    team: TeamId,
+}
+
+impl ScriptedPolicy {
+    /// Creates a policy driving the ships of `team`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, params: ScriptParams, team: TeamId) -> Self {
+        Self {
+            declaration: PluginDeclaration {
+                id: PluginId::from_static("scripted_policy"),
+                required_tags: vec![EntityTag::Ship],
+                reads: vec![
+                    ComponentKind::Transform,
+                    ComponentKind::Combat,
+                    ComponentKind::Sensor,
+                ],
+                emits: vec![OutputKind::Command],
+                runs_after: vec![],
+                every_tick: false,
+            },
+            name: name.into(),
+            params,
+            team,
+        }
+    }
+
+    /// Returns the snapshot name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the behavior parameters.
+    #[must_use]
+    pub const fn params(&self) -> &ScriptParams {
+        &self.params
+    }
+
+    /// Returns the team driven.
+    #[must_use]
+    pub const fn team(&self) -> TeamId {
+        self.team
+    }
+
+    /// Returns the nearest track of an entity not on the policy's team.
+    fn nearest_hostile<'a>(
+        &self,
+        view: &WorldView<'a>,
+        id: EntityId,
+        position: Vec2,
+    ) -> Option<&'a Track> {
+        view.get_sensor(id)?
+            .track_table
+            .iter()
+            .filter(|track| {
+                view.get_entity(track.target_id)
+                    .is_none_or(|target| target.team() != Some(self.team))
+            })
+            .min_by(|a, b| {
+                position
+                    .distance_squared(a.position)
+                    .total_cmp(&position.distance_squared(b.position))
+                    .then(a.target_id.cmp(&b.target_id))
+            })
+    }
+
+    fn steer(id: EntityId, heading: Option<f32>, fraction: f32) -> Vec<Output> {
+        let mut outputs = Vec::with_capacity(2);
+        if let Some(heading) = heading {
+            outputs.push(Output::Command(Command::SetHeading {
+                target: id,
+                heading,
+            }));
+        }
+        outputs.push(Output::Command(Command::SetThrottle {
+            target: id,
+            fraction: fraction.clamp(0.0, 1.0),
+        }));
+        outputs
+    }
+}
+
+impl Plugin for ScriptedPolicy {
+    fn declaration(&self) -> &PluginDeclaration {
+        &self.declaration
+    }
+
+    fn run(&self, ctx: &PluginContext, view: &WorldView) -> Vec<Output> {
+        let id = ctx.entity_id;
+        let (Some(entity), Some(transform), Some(combat)) = (
+            view.get_entity(id),
+            view.get_transform(id),
+            view.get_combat(id),
+        ) else {
+            return vec![];
+        };
+        if entity.team() != Some(self.team) || combat.is_destroyed() {
+            return vec![];
+        }
+        let params = &self.params;
+        let position = transform.position;
+
+        let Some(target) = self.nearest_hostile(view, id, position) else {
+            return Self::steer(id, None, params.patrol_throttle);
+        };
+        let offset = target.position - position;
+        let bearing = offset.y.atan2(offset.x);
+        let back = position - target.position;
+        let away = back.y.atan2(back.x);
+        let distance = offset.length();
+
+        if combat.hp < params.retreat_below * combat.max_hp {
+            return Self::steer(id, Some(away), 1.0);
+        }
+        let band = params.preferred_range * params.range_tolerance;
+        if distance > params.preferred_range + band {
+            Self::steer(id, Some(bearing), params.throttle)
+        } else if distance < params.preferred_range - band {
+            Self::steer(id, Some(away), params.throttle)
+        } else {
+            Self::steer(id, Some(bearing + params.attack_angle), params.throttle / 2.0)
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+    use crate::entity::TrackQuality;
+    use crate::output::TraceId;
+    use crate::tests::spawn_team_ship;
+    use std::f32::consts::PI;
+
+    fn outputs(policy: &ScriptedPolicy, arena: &Arena, id: EntityId) -> Vec<Output> {
+        let view = WorldView::for_plugin(arena, policy.declaration(), 0);
+        let ctx = PluginContext {
+            entity_id: id,
+            tick: 0,
+            trace_id: TraceId::new(0),
+        };
+        policy.run(&ctx, &view)
+    }
+
+    fn run_for(policy: &ScriptedPolicy, arena: &Arena, id: EntityId) -> (Option<f32>, f32) {
+        let outputs = outputs(policy, arena, id);
+        let heading = outputs.iter().find_map(|o| match o {
+            Output::Command(Command::SetHeading { heading, .. }) => Some(*heading),
+            _ => None,
+        });
+        let throttle = outputs.iter().find_map(|o| match o {
+            Output::Command(Command::SetThrottle { fraction, .. }) => Some(*fraction),
+            _ => None,
+        });
+        (heading, throttle.unwrap())
+    }
+
+    fn track(arena: &mut Arena, observer: EntityId, target: EntityId, x: f32) {
+        let sensor = &mut arena.get_mut(observer).unwrap().as_ship_mut().unwrap().sensor;
+        sensor
+            .track_table
+            .push(Track::new(target, Vec2::new(x, 0.0), TrackQuality::Coarse));
+    }
+
+    #[test]
+    fn closes_opens_and_retreats() {
+        let mut arena = Arena::new();
+        let red = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+        let blue = spawn_team_ship(&mut arena, Vec2::new(10_000.0, 0.0), Some(0));
+        let policy = ScriptedPolicy::new("test", ScriptParams::default(), TeamId::new(1));
+
+        assert_eq!(run_for(&policy, &arena, red), (None, 0.3));
+        // Blue ships are not driven
+        track(&mut arena, blue, red, 0.0);
+        assert!(outputs(&policy, &arena, blue).is_empty());
+
+        track(&mut arena, red, blue, 10_000.0);
+        assert_eq!(run_for(&policy, &arena, red), (Some(0.0), 1.0));
+
+        arena.get_mut(red).unwrap().as_ship_mut().unwrap().sensor.track_table[0].position =
+            Vec2::new(1000.0, 0.0);
+        assert_eq!(run_for(&policy, &arena, red), (Some(PI), 1.0));
+
+        arena.get_mut(red).unwrap().as_ship_mut().unwrap().combat.hp = 10.0;
+        arena.get_mut(red).unwrap().as_ship_mut().unwrap().sensor.track_table[0].position =
+            Vec2::new(3000.0, 0.0);
+        assert_eq!(run_for(&policy, &arena, red), (Some(PI), 1.0));
+    }
+
+    #[test]
+    fn ignores_tracks_of_teammates() {
+        let mut arena = Arena::new();
+        let red = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+        let wingman = spawn_team_ship(&mut arena, Vec2::new(500.0, 0.0), Some(1));
+        track(&mut arena, red, wingman, 500.0);
+        let policy = ScriptedPolicy::new("test", ScriptParams::default(), TeamId::new(1));
+        assert_eq!(run_for(&policy, &arena, red), (None, 0.3));
+    }
+}
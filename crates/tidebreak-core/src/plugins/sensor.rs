@@ -1,7 +1,31 @@
 //! Sensor plugin for entity detection.
 //!
-//! The `SensorPlugin` detects nearby entities using radar and emits
-//! `ContactDetected` events for each detection.
+//! The `SensorPlugin` detects nearby entities using radar, passive sonar and
+//! lookouts, and emits `ContactDetected` events for each detection.
+//!
+//! # Detection Model
+//!
+//! Ranges depend on the target's current signature (see `SignatureState`):
+//!
+//! - Radar detects out to `radar_range` scaled by the target's radar cross
+//!   section factor.
+//! - Sonar detects ships out to the effective sonar range scaled by the
+//!   target's acoustic factor, so fast, damaged or pinging ships are heard
+//!   from further away.
+//!
+//! Entities without a signature model (platforms, projectiles, squadrons)
+//! are seen by radar at the nominal range.
+//!
+//! Lookouts (and periscopes) see out to `visual_range`, shortened at night
+//! and in rough seas and blocked by smoke (see `Environment`). A visual
+//! sighting identifies the target, so it is reported at `FireControl`
+//! quality; other detections are `Coarse`. Submerged submarines can neither
+//! see nor be seen.
+//!
+//! A submerged submarine has no radar: it detects by sonar alone.
+//!
+//! All three ranges shrink in proportion to the hit points left on a
+//! damaged sensor suite (see `SensorState::integrity`).
 //!
 //! # Supported Entity Types
 //!
@@ -10,18 +34,21 @@
 //!
 //! # Outputs
 //!
-//! - `Event::ContactDetected`: Emitted for each entity within radar range
+//! - `Event::ContactDetected`: Emitted for each entity detected by any
+//!   channel
+//!
+//! Mines stay hidden until they have been found by minesweeping.
 
-use crate::entity::components::TrackQuality;
-use crate::entity::EntityTag;
+use crate::entity::components::{SignatureState, SubmarineState, TrackQuality};
+use crate::entity::{Entity, EntityTag};
 use crate::output::{Event, Output, OutputKind, PluginId};
 use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
 use crate::world_view::WorldView;
 
 /// Plugin that detects nearby entities using sensors.
 ///
-/// The sensor plugin queries for entities within radar range and emits
-/// `ContactDetected` events for each detection.
+/// The sensor plugin queries for entities within detection range of their
+/// signature and emits `ContactDetected` events for each detection.
 ///
 /// # Example
 ///
@@ -44,8 +71,15 @@ impl SensorPlugin {
             declaration: PluginDeclaration {
                 id: PluginId::from_static("sensor"),
                 required_tags: vec![EntityTag::Ship, EntityTag::Platform],
-                reads: vec![ComponentKind::Transform, ComponentKind::Sensor],
+                reads: vec![
+                    ComponentKind::Transform,
+                    ComponentKind::Sensor,
+                    ComponentKind::Signature,
+                    ComponentKind::Submarine,
+                ],
                 emits: vec![OutputKind::Event],
+                runs_after: vec![],
+                every_tick: false,
             },
         }
     }
@@ -73,8 +107,18 @@ impl Plugin for SensorPlugin {
             return outputs;
         };
 
-        // Query nearby entities using radar range
-        let nearby = view.query_in_radius(transform.position, sensor.radar_range);
+        // Query out to the furthest range any signature could be detected at
+        let submerged = view
+            .get_submarine(ctx.entity_id)
+            .is_some_and(SubmarineState::is_submerged);
+        let integrity = sensor.integrity();
+        let radar_range = if submerged { 0.0 } else { sensor.radar_range * integrity };
+        let sonar_range = sensor.effective_sonar_range();
+        let visual_range = if submerged { 0.0 } else { sensor.visual_range * integrity };
+        let reach = (radar_range.max(sonar_range) * SignatureState::MAX_RANGE_FACTOR)
+            .max(visual_range);
+        let nearby = view.query_in_radius(transform.position, reach);
+        let environment = view.environment();
 
         for target_id in nearby {
             // Skip self
@@ -82,12 +126,47 @@ impl Plugin for SensorPlugin {
                 continue;
             }
 
-            // Emit ContactDetected event
-            // Use Coarse quality for initial radar detection
+            // Skip mines that have not been swept
+            if view
+                .get_entity(target_id)
+                .and_then(Entity::mine)
+                .is_some_and(|mine| !mine.detected)
+            {
+                continue;
+            }
+
+            let Some(target) = view.get_transform(target_id) else {
+                continue;
+            };
+            let hidden = view
+                .get_submarine(target_id)
+                .is_some_and(SubmarineState::is_submerged);
+            let sighted = visual_range > 0.0
+                && !hidden
+                && environment.can_see(ctx.tick, transform.position, target.position, visual_range);
+            let distance = transform.position.distance(target.position);
+            let quality = if sighted {
+                // A visual sighting identifies the target
+                TrackQuality::FireControl
+            } else {
+                let detected = match view.current_signature(target_id) {
+                    Some(signature) => {
+                        distance <= radar_range * signature.radar_range_factor()
+                            || distance <= sonar_range * signature.acoustic_range_factor()
+                    }
+                    None => distance <= radar_range,
+                };
+                if !detected {
+                    continue;
+                }
+                // Use Coarse quality for radar and sonar detections
+                TrackQuality::Coarse
+            };
+
             outputs.push(Output::Event(Event::ContactDetected {
                 observer: ctx.entity_id,
                 target: target_id,
-                quality: TrackQuality::Coarse,
+                quality,
             }));
         }
 
@@ -104,7 +183,8 @@ mod tests {
     use super::*;
     use crate::arena::Arena;
     use crate::entity::{
-        EntityId, EntityInner, PlatformComponents, ProjectileComponents, ShipComponents,
+        EntityId, EntityInner, MineState, PlatformComponents, ProjectileComponents,
+        ShipComponents,
     };
     use crate::output::TraceId;
     use glam::Vec2;
@@ -139,6 +219,7 @@ mod tests {
 
         assert!(decl.reads.contains(&ComponentKind::Transform));
         assert!(decl.reads.contains(&ComponentKind::Sensor));
+        assert!(decl.reads.contains(&ComponentKind::Signature));
     }
 
     #[test]
@@ -357,6 +438,37 @@ mod tests {
         assert_eq!(outputs.len(), 1);
     }
 
+    #[test]
+    fn run_hides_undetected_mines() {
+        let plugin = SensorPlugin::new();
+        let mut arena = Arena::new();
+
+        let ship_id = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(Vec2::new(0.0, 0.0), 0.0)),
+        );
+        let mine_id = arena.spawn(
+            EntityTag::Platform,
+            EntityInner::Platform(PlatformComponents::mine(
+                Vec2::new(100.0, 0.0),
+                MineState::default(),
+            )),
+        );
+        let ctx = PluginContext {
+            entity_id: ship_id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+
+        let view = WorldView::for_plugin(&arena, plugin.declaration(), arena.current_tick());
+        assert!(plugin.run(&ctx, &view).is_empty());
+
+        let platform = arena.get_mut(mine_id).unwrap().as_platform_mut().unwrap();
+        platform.mine.as_mut().unwrap().detected = true;
+        let view = WorldView::for_plugin(&arena, plugin.declaration(), arena.current_tick());
+        assert_eq!(plugin.run(&ctx, &view).len(), 1);
+    }
+
     #[test]
     fn run_with_nonexistent_entity() {
         let plugin = SensorPlugin::new();
@@ -374,6 +486,159 @@ mod tests {
         assert!(outputs.is_empty());
     }
 
+    fn contacts(arena: &Arena, observer: EntityId) -> Vec<Output> {
+        let plugin = SensorPlugin::new();
+        let view = WorldView::for_plugin(arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: observer,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+        plugin.run(&ctx, &view)
+    }
+
+    #[test]
+    fn small_radar_cross_section_shrinks_detection_range() {
+        let mut arena = Arena::new();
+        let ship_id = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(Vec2::new(0.0, 0.0), 0.0)),
+        );
+        // 1/16 of the reference RCS halves radar range to 5000m
+        let stealthy = SignatureState::new(SignatureState::REFERENCE_RCS / 16.0, 120.0, 1.0);
+        let target_id = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(
+                ShipComponents::at_position(Vec2::new(6000.0, 0.0), 0.0).with_signature(stealthy),
+            ),
+        );
+        assert!(contacts(&arena, ship_id).is_empty());
+
+        let target = arena.get_mut(target_id).unwrap().as_ship_mut().unwrap();
+        target.transform.position = Vec2::new(4000.0, 0.0);
+        arena.update_spatial(target_id);
+        assert_eq!(contacts(&arena, ship_id).len(), 1);
+    }
+
+    #[test]
+    fn loud_ship_heard_beyond_radar_range() {
+        let mut arena = Arena::new();
+        let ship_id = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(
+                ShipComponents::at_position(Vec2::new(0.0, 0.0), 0.0).with_sensors(1000.0, 4000.0),
+            ),
+        );
+        let target_id = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(Vec2::new(6000.0, 0.0), 0.0)),
+        );
+        // At rest: passive sonar reaches 3000m
+        assert!(contacts(&arena, ship_id).is_empty());
+
+        // At full speed the target is 20 dB louder: range grows 10x, capped at 4x
+        let target = arena.get_mut(target_id).unwrap().as_ship_mut().unwrap();
+        target.physics.velocity = Vec2::new(target.physics.max_speed, 0.0);
+        assert_eq!(contacts(&arena, ship_id).len(), 1);
+    }
+
+    #[test]
+    fn submerged_submarine_is_sonar_only() {
+        let mut arena = Arena::new();
+        let sub = SubmarineState {
+            depth: 100.0,
+            ..SubmarineState::default()
+        };
+        let sub_id = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(
+                ShipComponents::at_position(Vec2::new(0.0, 0.0), 0.0)
+                    .with_sensors(10000.0, 1000.0)
+                    .with_submarine(sub),
+            ),
+        );
+        arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(Vec2::new(5000.0, 0.0), 0.0)),
+        );
+        assert!(contacts(&arena, sub_id).is_empty());
+
+        // Back at the surface the radar works again
+        let ship = arena.get_mut(sub_id).unwrap().as_ship_mut().unwrap();
+        ship.submarine.as_mut().unwrap().depth = 0.0;
+        assert_eq!(contacts(&arena, sub_id).len(), 1);
+    }
+
+    mod visual_tests {
+        use super::*;
+        use crate::environment::{SmokeField, WorldClock};
+
+        /// Radar-silent observer at the origin and a target ship at `x`.
+        fn setup(x: f32) -> (Arena, EntityId) {
+            let mut arena = Arena::new();
+            let observer = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(
+                    ShipComponents::at_position(Vec2::ZERO, 0.0).with_sensors(0.0, 0.0),
+                ),
+            );
+            arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(x, 0.0), 0.0)),
+            );
+            (arena, observer)
+        }
+
+        fn qualities(arena: &Arena, observer: EntityId) -> Vec<TrackQuality> {
+            contacts(arena, observer)
+                .into_iter()
+                .filter_map(|output| match output {
+                    Output::Event(Event::ContactDetected { quality, .. }) => Some(quality),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        #[test]
+        fn sighting_is_fire_control_quality() {
+            let (arena, observer) = setup(2000.0);
+            assert_eq!(qualities(&arena, observer), vec![TrackQuality::FireControl]);
+        }
+
+        #[test]
+        fn night_shortens_visual_range() {
+            let (mut arena, observer) = setup(2000.0);
+            arena.environment_mut().clock = WorldClock::starting_at(0.0);
+            assert!(contacts(&arena, observer).is_empty());
+        }
+
+        #[test]
+        fn smoke_hides_target_from_lookouts_but_not_radar() {
+            let (mut arena, observer) = setup(2000.0);
+            arena.environment_mut().smoke = Some(SmokeField::uniform(0.5));
+            assert!(contacts(&arena, observer).is_empty());
+
+            let ship = arena.get_mut(observer).unwrap().as_ship_mut().unwrap();
+            ship.sensor.radar_range = 10000.0;
+            assert_eq!(qualities(&arena, observer), vec![TrackQuality::Coarse]);
+        }
+
+        #[test]
+        fn periscope_depth_sees_but_deep_does_not() {
+            let (mut arena, observer) = setup(2000.0);
+            let ship = arena.get_mut(observer).unwrap().as_ship_mut().unwrap();
+            ship.submarine = Some(SubmarineState {
+                depth: 10.0,
+                ..SubmarineState::default()
+            });
+            assert_eq!(qualities(&arena, observer), vec![TrackQuality::FireControl]);
+
+            let ship = arena.get_mut(observer).unwrap().as_ship_mut().unwrap();
+            ship.submarine.as_mut().unwrap().depth = 100.0;
+            assert!(contacts(&arena, observer).is_empty());
+        }
+    }
+
     #[test]
     fn plugin_is_send_sync() {
         fn assert_send_sync<T: Send + Sync>() {}
@@ -0,0 +1,294 @@
+//! Threat evaluation plugin.
+//!
+//! The `ThreatEvaluationPlugin` scores every track in an entity's track table
+//! and emits a `ThreatAssessed` event per track. Scores combine closing
+//! velocity, proximity relative to an engagement range, and classification
+//! confidence, and are used both by scripted opponents and as auxiliary
+//! observation features.
+//!
+//! # Supported Entity Types
+//!
+//! - Ships
+//!
+//! # Outputs
+//!
+//! - `Event::ThreatAssessed`: Emitted for each track in the track table
+
+use glam::Vec2;
+
+use crate::entity::components::Track;
+use crate::entity::EntityTag;
+use crate::output::{Event, Output, OutputKind, PluginId};
+use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+use crate::world_view::WorldView;
+
+/// Weights and scales used to compute threat scores.
+///
+/// The score is a weighted sum of three terms, each in `[0, 1]`:
+///
+/// - **closing**: closing speed divided by `reference_speed`, clamped
+/// - **range**: 1 inside `engagement_range`, falling off as
+///   `engagement_range / distance` beyond it
+/// - **classification**: the track's classification confidence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThreatWeights {
+    /// Weight of the closing-speed term.
+    pub closing: f32,
+    /// Weight of the range term.
+    pub range: f32,
+    /// Weight of the classification term.
+    pub classification: f32,
+    /// Range within which a contact is considered able to engage.
+    pub engagement_range: f32,
+    /// Closing speed that saturates the closing term.
+    pub reference_speed: f32,
+}
+
+impl Default for ThreatWeights {
+    fn default() -> Self {
+        Self {
+            closing: 0.4,
+            range: 0.4,
+            classification: 0.2,
+            engagement_range: 2000.0,
+            reference_speed: 20.0,
+        }
+    }
+}
+
+impl ThreatWeights {
+    /// Scores a track as seen from `own_pos` moving at `own_vel`.
+    ///
+    /// Tracks without a velocity estimate are treated as stationary.
+    #[must_use]
+    pub fn score(&self, track: &Track, own_pos: Vec2, own_vel: Vec2) -> f32 {
+        let rel = track.position - own_pos;
+        let distance = rel.length();
+
+        let closing = if distance > f32::EPSILON && self.reference_speed > 0.0 {
+            let rel_vel = track.velocity.unwrap_or(Vec2::ZERO) - own_vel;
+            (-rel.dot(rel_vel) / distance / self.reference_speed).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let range = if distance <= self.engagement_range {
+            1.0
+        } else {
+            self.engagement_range / distance
+        };
+
+        let classification = track.classification_confidence.clamp(0.0, 1.0);
+
+        self.closing * closing + self.range * range + self.classification * classification
+    }
+}
+
+/// Plugin that scores tracked contacts for threat.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::plugins::ThreatEvaluationPlugin;
+/// use tidebreak_core::plugin::Plugin;
+///
+/// let plugin = ThreatEvaluationPlugin::new();
+/// assert_eq!(plugin.declaration().id.as_str(), "threat_evaluation");
+/// ```
+pub struct ThreatEvaluationPlugin {
+    declaration: PluginDeclaration,
+    weights: ThreatWeights,
+}
+
+impl ThreatEvaluationPlugin {
+    /// Creates a new `ThreatEvaluationPlugin` with default weights.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_weights(ThreatWeights::default())
+    }
+
+    /// Creates a new `ThreatEvaluationPlugin` with custom weights.
+    #[must_use]
+    pub fn with_weights(weights: ThreatWeights) -> Self {
+        Self {
+            declaration: PluginDeclaration {
+                id: PluginId::from_static("threat_evaluation"),
+                required_tags: vec![EntityTag::Ship],
+                reads: vec![
+                    ComponentKind::Transform,
+                    ComponentKind::Physics,
+                    ComponentKind::Sensor,
+                ],
+                emits: vec![OutputKind::Event],
+                runs_after: vec![],
+                every_tick: false,
+            },
+            weights,
+        }
+    }
+
+    /// Returns the weights used for scoring.
+    #[must_use]
+    pub const fn weights(&self) -> &ThreatWeights {
+        &self.weights
+    }
+}
+
+impl Default for ThreatEvaluationPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Plugin for ThreatEvaluationPlugin {
+    fn declaration(&self) -> &PluginDeclaration {
+        &self.declaration
+    }
+
+    fn run(&self, ctx: &PluginContext, view: &WorldView) -> Vec<Output> {
+        let Some(transform) = view.get_transform(ctx.entity_id) else {
+            return vec![];
+        };
+        let Some(sensor) = view.get_sensor(ctx.entity_id) else {
+            return vec![];
+        };
+        let own_vel = view
+            .get_physics(ctx.entity_id)
+            .map_or(Vec2::ZERO, |p| p.velocity);
+
+        sensor
+            .track_table
+            .iter()
+            .map(|track| {
+                Output::Event(Event::ThreatAssessed {
+                    observer: ctx.entity_id,
+                    target: track.target_id,
+                    score: self.weights.score(track, transform.position, own_vel),
+                })
+            })
+            .collect()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+    use crate::entity::components::TrackQuality;
+    use crate::entity::{EntityId, EntityInner, ShipComponents};
+    use crate::output::TraceId;
+
+    fn run_for(arena: &Arena, plugin: &ThreatEvaluationPlugin, id: EntityId) -> Vec<Output> {
+        let view = WorldView::for_plugin(arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+        plugin.run(&ctx, &view)
+    }
+
+    fn score_of(output: &Output) -> f32 {
+        match output {
+            Output::Event(Event::ThreatAssessed { score, .. }) => *score,
+            _ => panic!("Expected ThreatAssessed event"),
+        }
+    }
+
+    #[test]
+    fn new_creates_plugin() {
+        let plugin = ThreatEvaluationPlugin::new();
+        assert_eq!(plugin.declaration().id.as_str(), "threat_evaluation");
+        assert!(plugin.declaration().emits.contains(&OutputKind::Event));
+    }
+
+    #[test]
+    fn emits_one_assessment_per_track() {
+        let plugin = ThreatEvaluationPlugin::new();
+        let mut arena = Arena::new();
+        let mut ship = ShipComponents::default();
+        ship.sensor.track_table = vec![
+            Track::new(
+                EntityId::new(10),
+                Vec2::new(100.0, 0.0),
+                TrackQuality::Coarse,
+            ),
+            Track::new(
+                EntityId::new(11),
+                Vec2::new(9000.0, 0.0),
+                TrackQuality::Coarse,
+            ),
+        ];
+        let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+
+        let outputs = run_for(&arena, &plugin, id);
+
+        assert_eq!(outputs.len(), 2);
+        assert!(score_of(&outputs[0]) > score_of(&outputs[1]));
+    }
+
+    #[test]
+    fn closing_contact_scores_higher() {
+        let weights = ThreatWeights::default();
+        let mut closing = Track::new(
+            EntityId::new(1),
+            Vec2::new(500.0, 0.0),
+            TrackQuality::Coarse,
+        );
+        closing.velocity = Some(Vec2::new(-10.0, 0.0));
+        let mut opening = closing.clone();
+        opening.velocity = Some(Vec2::new(10.0, 0.0));
+
+        let a = weights.score(&closing, Vec2::ZERO, Vec2::ZERO);
+        let b = weights.score(&opening, Vec2::ZERO, Vec2::ZERO);
+
+        assert!(a > b);
+    }
+
+    #[test]
+    fn classification_confidence_raises_score() {
+        let weights = ThreatWeights::default();
+        let unknown = Track::new(
+            EntityId::new(1),
+            Vec2::new(500.0, 0.0),
+            TrackQuality::Coarse,
+        );
+        let mut classified = unknown.clone();
+        classified.classification_confidence = 1.0;
+
+        assert!(
+            weights.score(&classified, Vec2::ZERO, Vec2::ZERO)
+                > weights.score(&unknown, Vec2::ZERO, Vec2::ZERO)
+        );
+    }
+
+    #[test]
+    fn score_is_bounded_by_weight_sum() {
+        let weights = ThreatWeights::default();
+        let mut track = Track::new(EntityId::new(1), Vec2::new(10.0, 0.0), TrackQuality::Shared);
+        track.velocity = Some(Vec2::new(-1000.0, 0.0));
+        track.classification_confidence = 1.0;
+
+        let score = weights.score(&track, Vec2::ZERO, Vec2::ZERO);
+        let max = weights.closing + weights.range + weights.classification;
+
+        assert!(score <= max + f32::EPSILON);
+    }
+
+    #[test]
+    fn run_with_nonexistent_entity() {
+        let plugin = ThreatEvaluationPlugin::new();
+        let arena = Arena::new();
+        assert!(run_for(&arena, &plugin, EntityId::new(999)).is_empty());
+    }
+
+    #[test]
+    fn plugin_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ThreatEvaluationPlugin>();
+    }
+}
@@ -11,6 +11,22 @@
 //! # Outputs
 //!
 //! - `Command::FireWeapon`: Emitted when firing at a tracked target
+//! - `Command::ReloadWeapon`: Emitted to top up partly spent magazines while
+//!   no target is tracked
+//!
+//! Weapons that are cooling down, reloading or out of rounds hold fire
+//! (see `WeaponState::is_ready`).
+//!
+//! # Rules of Engagement
+//!
+//! With [`WeaponPlugin::with_identification_required`], unidentified tracks
+//! (see `Track::is_identified`) are never engaged.
+//!
+//! Tracks hidden behind a smoke screen (see
+//! [`Environment::is_screened`](crate::environment::Environment::is_screened))
+//! are never engaged.
+
+use glam::Vec2;
 
 use crate::entity::EntityTag;
 use crate::output::{Command, Output, OutputKind, PluginId};
@@ -33,6 +49,7 @@ use crate::world_view::WorldView;
 /// ```
 pub struct WeaponPlugin {
     declaration: PluginDeclaration,
+    require_identification: bool,
 }
 
 impl WeaponPlugin {
@@ -49,9 +66,27 @@ impl WeaponPlugin {
                     ComponentKind::Sensor,
                 ],
                 emits: vec![OutputKind::Command, OutputKind::Event],
+                runs_after: vec![],
+                every_tick: false,
             },
+            require_identification: false,
+        }
+    }
+
+    /// Creates a `WeaponPlugin` that holds fire on unidentified tracks.
+    #[must_use]
+    pub fn with_identification_required() -> Self {
+        Self {
+            require_identification: true,
+            ..Self::new()
         }
     }
+
+    /// Returns true if only identified tracks are engaged.
+    #[must_use]
+    pub const fn requires_identification(&self) -> bool {
+        self.require_identification
+    }
 }
 
 impl Default for WeaponPlugin {
@@ -78,10 +113,25 @@ impl Plugin for WeaponPlugin {
             return outputs;
         };
 
-        // Check if we have any tracks to fire at
-        if sensor.track_table.is_empty() {
+        let position = view
+            .get_transform(ctx.entity_id)
+            .map_or(Vec2::ZERO, |transform| transform.position);
+        let environment = view.environment();
+
+        // Pick the first track we are allowed to engage and can see through smoke
+        let Some(track) = sensor.track_table.iter().find(|t| {
+            (!self.require_identification || t.is_identified())
+                && !environment.is_screened(position, t.position)
+        }) else {
+            // Nothing to shoot at: use the lull to top up magazines
+            for weapon in combat.weapons.iter().filter(|w| w.can_reload()) {
+                outputs.push(Output::Command(Command::ReloadWeapon {
+                    source: ctx.entity_id,
+                    slot: weapon.slot,
+                }));
+            }
             return outputs;
-        }
+        };
 
         // Check each weapon
         for weapon in &combat.weapons {
@@ -89,14 +139,11 @@ impl Plugin for WeaponPlugin {
                 continue;
             }
 
-            // Fire at first available target from tracks
-            if let Some(track) = sensor.track_table.first() {
-                outputs.push(Output::Command(Command::FireWeapon {
-                    source: ctx.entity_id,
-                    target: track.target_id,
-                    slot: weapon.slot,
-                }));
-            }
+            outputs.push(Output::Command(Command::FireWeapon {
+                source: ctx.entity_id,
+                target: track.target_id,
+                slot: weapon.slot,
+            }));
         }
 
         outputs
@@ -113,6 +160,7 @@ mod tests {
     use crate::arena::Arena;
     use crate::entity::components::{AmmoType, Track, TrackQuality, WeaponState};
     use crate::entity::{EntityId, EntityInner, ShipComponents, SquadronComponents};
+    use crate::environment::SmokePuff;
     use crate::output::TraceId;
     use glam::Vec2;
 
@@ -317,6 +365,63 @@ mod tests {
         assert!(outputs.is_empty());
     }
 
+    #[test]
+    fn run_skips_reloading_weapons() {
+        let plugin = WeaponPlugin::new();
+        let mut arena = Arena::new();
+        let (ship_id, _) = create_ship_with_weapon_and_track(&mut arena);
+        let weapon = &mut arena.get_mut(ship_id).unwrap().as_ship_mut().unwrap().combat.weapons[0];
+        *weapon = weapon.clone().with_magazine(4, 10);
+        weapon.reload_remaining = 5;
+
+        let view = WorldView::for_plugin(&arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: ship_id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+
+        assert!(plugin.run(&ctx, &view).is_empty());
+    }
+
+    #[test]
+    fn run_reloads_partial_magazines_without_targets() {
+        let plugin = WeaponPlugin::new();
+        let mut arena = Arena::new();
+
+        let mut ship_components = ShipComponents::at_position(Vec2::new(0.0, 0.0), 0.0);
+        let mut partial = WeaponState::new(0, 1.0, AmmoType::Shell).with_magazine(6, 30);
+        partial.rounds = 2;
+        ship_components.combat.weapons.push(partial);
+        // Full magazine and cooldown-only weapons need no reload
+        ship_components
+            .combat
+            .weapons
+            .push(WeaponState::new(1, 1.0, AmmoType::Shell).with_magazine(6, 30));
+        ship_components
+            .combat
+            .weapons
+            .push(WeaponState::new(2, 1.0, AmmoType::Missile));
+        let ship_id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship_components));
+
+        let view = WorldView::for_plugin(&arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: ship_id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+
+        let outputs = plugin.run(&ctx, &view);
+
+        assert_eq!(
+            outputs,
+            vec![Output::Command(Command::ReloadWeapon {
+                source: ship_id,
+                slot: 0,
+            })]
+        );
+    }
+
     #[test]
     fn run_fires_multiple_weapons() {
         let plugin = WeaponPlugin::new();
@@ -404,6 +509,78 @@ mod tests {
         let _ = target_id;
     }
 
+    #[test]
+    fn identification_required_holds_fire_on_unknowns() {
+        let plugin = WeaponPlugin::with_identification_required();
+        assert!(plugin.requires_identification());
+        let mut arena = Arena::new();
+
+        let (ship_id, _) = create_ship_with_weapon_and_track(&mut arena);
+
+        let view = WorldView::for_plugin(&arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: ship_id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+
+        assert!(plugin.run(&ctx, &view).is_empty());
+    }
+
+    #[test]
+    fn identification_required_fires_at_identified_track() {
+        let plugin = WeaponPlugin::with_identification_required();
+        let mut arena = Arena::new();
+
+        let (ship_id, target_id) = create_ship_with_weapon_and_track(&mut arena);
+        arena
+            .get_mut(ship_id)
+            .unwrap()
+            .as_ship_mut()
+            .unwrap()
+            .sensor
+            .track_table[0]
+            .classified_as = Some(EntityTag::Ship);
+
+        let view = WorldView::for_plugin(&arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: ship_id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+
+        let outputs = plugin.run(&ctx, &view);
+        assert_eq!(outputs.len(), 1);
+        assert!(matches!(
+            &outputs[0],
+            Output::Command(Command::FireWeapon { target, .. }) if *target == target_id
+        ));
+    }
+
+    #[test]
+    fn holds_fire_through_smoke_screen() {
+        let plugin = WeaponPlugin::new();
+        let mut arena = Arena::new();
+
+        let (ship_id, _) = create_ship_with_weapon_and_track(&mut arena);
+        arena
+            .environment_mut()
+            .screens
+            .push(SmokePuff::new(Vec2::new(2500.0, 0.0), 0));
+
+        let view = WorldView::for_plugin(&arena, plugin.declaration(), arena.current_tick());
+        let ctx = PluginContext {
+            entity_id: ship_id,
+            tick: arena.current_tick(),
+            trace_id: TraceId::new(0),
+        };
+
+        assert!(!plugin
+            .run(&ctx, &view)
+            .iter()
+            .any(|output| matches!(output, Output::Command(Command::FireWeapon { .. }))));
+    }
+
     #[test]
     fn run_with_nonexistent_entity() {
         let plugin = WeaponPlugin::new();
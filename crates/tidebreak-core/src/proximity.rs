@@ -0,0 +1,154 @@
+//! Targets within range of proximity watches.
+//!
+//! A [`ProximityPlugin`](crate::plugins::ProximityPlugin) reports targets
+//! crossing its radii by comparing the targets in range now with those in
+//! range on its previous run. The latter are kept in the arena's
+//! [`ProximityBook`], by plugin ID and observer, so they are snapshotted,
+//! diffed and hashed with the rest of the state: a restored or forked
+//! simulation reports exactly the crossings the original would. The
+//! [`ProximityResolver`](crate::resolver::ProximityResolver) keeps the book
+//! up to date from the `EnteredRange` and `LeftRange` events.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::EntityId;
+//! use tidebreak_core::output::PluginId;
+//!
+//! let mut arena = Arena::new();
+//! let watch = PluginId::from_static("proximity");
+//! let (observer, target) = (EntityId::new(0), EntityId::new(1));
+//! arena.proximity_mut().enter(&watch, observer, target, 500.0);
+//!
+//! let inside: Vec<_> = arena.proximity().inside(&watch, observer).collect();
+//! assert_eq!(inside, vec![(target, 500.0)]);
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entity::EntityId;
+use crate::output::PluginId;
+
+/// Per-arena record of the targets within each radius of each observer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProximityBook {
+    /// Per plugin and observer, the (target, radius bits) pairs in range
+    #[serde(default)]
+    inside: BTreeMap<PluginId, BTreeMap<EntityId, BTreeSet<(EntityId, u32)>>>,
+}
+
+impl ProximityBook {
+    /// Creates an empty book.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the (target, radius) pairs `watch` has within range of
+    /// `observer`, by target and then radius bits.
+    pub fn inside(
+        &self,
+        watch: &PluginId,
+        observer: EntityId,
+    ) -> impl Iterator<Item = (EntityId, f32)> + '_ {
+        self.inside
+            .get(watch)
+            .and_then(|observers| observers.get(&observer))
+            .into_iter()
+            .flatten()
+            .map(|&(target, radius)| (target, f32::from_bits(radius)))
+    }
+
+    /// Records that `target` came within `radius` of `observer`.
+    pub fn enter(&mut self, watch: &PluginId, observer: EntityId, target: EntityId, radius: f32) {
+        self.inside
+            .entry(watch.clone())
+            .or_default()
+            .entry(observer)
+            .or_default()
+            .insert((target, radius.to_bits()));
+    }
+
+    /// Records that `target` moved beyond `radius` of `observer`.
+    pub fn leave(&mut self, watch: &PluginId, observer: EntityId, target: EntityId, radius: f32) {
+        let Some(observers) = self.inside.get_mut(watch) else {
+            return;
+        };
+        if let Some(targets) = observers.get_mut(&observer) {
+            targets.remove(&(target, radius.to_bits()));
+            if targets.is_empty() {
+                observers.remove(&observer);
+            }
+        }
+        if observers.is_empty() {
+            self.inside.remove(watch);
+        }
+    }
+
+    /// Forgets the targets of observers for which `keep` returns false.
+    pub fn retain_observers(&mut self, mut keep: impl FnMut(EntityId) -> bool) {
+        for observers in self.inside.values_mut() {
+            observers.retain(|&observer, _| keep(observer));
+        }
+        self.inside.retain(|_, observers| !observers.is_empty());
+    }
+
+    /// Returns true if no target is in range of any observer.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inside.is_empty()
+    }
+
+    /// Forgets every target.
+    pub fn clear(&mut self) {
+        self.inside.clear();
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+
+    #[test]
+    fn book_is_part_of_arena_state() {
+        let watch = PluginId::from_static("proximity");
+        let (observer, target) = (EntityId::new(0), EntityId::new(1));
+        let mut arena = Arena::new();
+        let before = arena.clone();
+        arena.proximity_mut().enter(&watch, observer, target, 500.0);
+        assert_ne!(arena.state_hash(), before.state_hash());
+
+        let mut restored = before.clone();
+        restored.apply_delta(&before.diff(&arena)).unwrap();
+        assert_eq!(restored.proximity(), arena.proximity());
+        let json = serde_json::to_string(&arena).unwrap();
+        let decoded: Arena = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.proximity(), arena.proximity());
+
+        arena.proximity_mut().leave(&watch, observer, target, 500.0);
+        assert!(arena.proximity().is_empty());
+        assert_eq!(arena.state_hash(), before.state_hash());
+    }
+
+    #[test]
+    fn retain_observers_drops_empty_watches() {
+        let watch = PluginId::from_static("proximity");
+        let mut book = ProximityBook::new();
+        book.enter(&watch, EntityId::new(0), EntityId::new(2), 100.0);
+        book.enter(&watch, EntityId::new(1), EntityId::new(2), 100.0);
+
+        book.retain_observers(|id| id == EntityId::new(1));
+        assert_eq!(book.inside(&watch, EntityId::new(0)).count(), 0);
+        assert_eq!(book.inside(&watch, EntityId::new(1)).count(), 1);
+        book.retain_observers(|_| false);
+        assert!(book.is_empty());
+    }
+}
@@ -0,0 +1,766 @@
+//! Portable `.tbr` replay files (feature `replay`).
+//!
+//! A [`Replay`] records a run tick by tick: the commands stepped with, the
+//! events the tick produced, and any edits made to the arena from outside
+//! between steps (e.g. actions applied directly from Python, captured as an
+//! [`ArenaDelta`]). Every [`keyframe_interval`](ReplayHeader::keyframe_interval)
+//! ticks it also keeps a full snapshot of the arena, so a viewer can
+//! [`seek`](Replay::seek) to any tick by restoring the nearest keyframe and
+//! re-stepping at most one interval, instead of replaying the whole event
+//! log from the start.
+//!
+//! Re-stepping reproduces the run only in a simulation with the recorded
+//! seed and the same plugins and resolvers; the events stored with each
+//! tick can be read without one. Each step also stores the resulting
+//! [`state_hash`](Arena::state_hash), so a seek that does not reproduce the
+//! recording fails with [`ReplayError::Diverged`] instead of silently
+//! returning a different state.
+//!
+//! # Branching
+//!
+//! For counterfactual ("what if") analysis,
+//! [`Simulation::fork_from`] starts an independent simulation at any
+//! recorded tick, to be stepped with different actions, and
+//! [`branch`](Replay::branch) cuts the recording at that tick so the fork
+//! can be recorded as a replay of its own.
+//!
+//! # File Format
+//!
+//! | Field         | Size           | Description                                  |
+//! |---------------|----------------|----------------------------------------------|
+//! | magic         | 4              | `TBR\0`                                      |
+//! | version       | 2              | Format version, little endian                |
+//! | header length | 4              | Length of the header, little endian          |
+//! | header        | header length  | [`ReplayHeader`] as JSON                     |
+//! | body          | rest of file   | Ticks and keyframes as zlib-compressed JSON  |
+//!
+//! The header is left uncompressed so tools can list a file without
+//! inflating it. Readers reject versions newer than [`FORMAT_VERSION`].
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+//! use tidebreak_core::replay::Replay;
+//! use tidebreak_core::simulation::Simulation;
+//!
+//! let mut sim = Simulation::new(7);
+//! sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+//!
+//! let mut replay = Replay::new(10).with_scenario("scenarios/duel.toml");
+//! for _ in 0..25 {
+//!     replay.record_step(&mut sim, &[]).unwrap();
+//! }
+//! let hash = sim.arena().state_hash();
+//!
+//! let mut file = Vec::new();
+//! replay.export(&mut file).unwrap();
+//! let loaded = Replay::import(file.as_slice()).unwrap();
+//! assert_eq!(loaded.header().scenario.as_deref(), Some("scenarios/duel.toml"));
+//!
+//! let mut viewer = Simulation::new(loaded.header().seed);
+//! loaded.seek(&mut viewer, 25).unwrap();
+//! assert_eq!(viewer.arena().state_hash(), hash);
+//! ```
+
+use std::io::{self, Read, Write};
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::arena::{Arena, ArenaDelta, DeltaError};
+use crate::entity::ControllerId;
+use crate::kill_ledger::ControllerTally;
+use crate::output::{Command, OutputEnvelope};
+use crate::resolver::FIXED_DT;
+use crate::simulation::Simulation;
+
+/// Leading bytes of every `.tbr` file.
+pub const MAGIC: [u8; 4] = *b"TBR\0";
+
+/// Format version written by [`Replay::export`].
+pub const FORMAT_VERSION: u16 = 1;
+
+/// Errors produced while recording, reading or seeking a replay.
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    /// Reading or writing the file failed.
+    #[error("replay I/O failed: {0}")]
+    Io(#[from] io::Error),
+    /// The header or body could not be encoded or decoded.
+    #[error("malformed replay: {0}")]
+    Codec(#[from] serde_json::Error),
+    /// The file does not start with [`MAGIC`].
+    #[error("not a .tbr replay")]
+    BadMagic,
+    /// The file was written by a newer format version.
+    #[error("unsupported replay version {0} (newest supported is {FORMAT_VERSION})")]
+    UnsupportedVersion(u16),
+    /// The simulation is not at the tick the replay continues from.
+    #[error("simulation is at tick {actual}, but the replay continues at tick {expected}")]
+    Discontinuous {
+        /// Next tick the replay expects
+        expected: u64,
+        /// Tick the simulation is at
+        actual: u64,
+    },
+    /// The requested tick is outside the recording.
+    #[error("tick {tick} is outside the recorded range {start}..={end}")]
+    TickOutOfRange {
+        /// Requested tick
+        tick: u64,
+        /// First recorded tick
+        start: u64,
+        /// Tick after the last recorded one
+        end: u64,
+    },
+    /// Recorded arena edits could not be re-applied.
+    #[error("recorded edit does not apply: {0}")]
+    Delta(#[from] DeltaError),
+    /// The simulation has an active plugin watchdog, whose wall-clock
+    /// budget makes steps irreproducible.
+    #[error("cannot replay a simulation with a plugin budget or disabled plugins")]
+    WatchdogActive,
+    /// Re-stepping did not reproduce the recorded state.
+    #[error("replay diverged at tick {tick}: expected hash {expected:#x}, got {actual:#x}")]
+    Diverged {
+        /// Tick whose result differs
+        tick: u64,
+        /// Recorded state hash
+        expected: u64,
+        /// Re-stepped state hash
+        actual: u64,
+    },
+}
+
+/// Metadata stored uncompressed at the start of a `.tbr` file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    /// Seed of the recorded simulation
+    pub seed: u64,
+    /// Scenario the run was set up from (a path or name), if known
+    pub scenario: Option<String>,
+    /// Tick of the first recorded step
+    pub start_tick: u64,
+    /// Number of recorded steps
+    pub tick_count: u64,
+    /// Steps between keyframes
+    pub keyframe_interval: u64,
+    /// Physics ticks per decision of the recorded simulation
+    #[serde(default = "default_action_interval")]
+    pub action_interval: u32,
+    /// Seconds per physics tick of the recorded simulation
+    #[serde(default = "default_physics_dt")]
+    pub physics_dt: f32,
+}
+
+const fn default_action_interval() -> u32 {
+    1
+}
+
+const fn default_physics_dt() -> f32 {
+    FIXED_DT
+}
+
+/// One recorded step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickRecord {
+    /// Tick the step executed
+    pub tick: u64,
+    /// Changes made to the arena since the previous step, if any
+    pub edits: Option<ArenaDelta>,
+    /// Commands the step was taken with
+    pub commands: Vec<Command>,
+    /// Events the step produced
+    pub events: Vec<OutputEnvelope>,
+    /// [`Arena::state_hash`] after the step
+    #[serde(default)]
+    pub state_hash: Option<u64>,
+}
+
+/// Full arena snapshot taken before a step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// Tick about to execute
+    pub tick: u64,
+    /// Arena state, edits included
+    pub arena: Arena,
+    /// Sustained commands being repeated between decision ticks
+    #[serde(default)]
+    pub held_commands: Vec<OutputEnvelope>,
+    /// [`Simulation::kill_ledger`] tallies accumulated before the step
+    #[serde(default)]
+    pub kill_tallies: Vec<(ControllerId, ControllerTally)>,
+}
+
+/// Compressed part of the file.
+#[derive(Serialize, Deserialize)]
+struct ReplayBody {
+    ticks: Vec<TickRecord>,
+    keyframes: Vec<Keyframe>,
+}
+
+/// Recording of a run, with keyframes for seeking.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    header: ReplayHeader,
+    ticks: Vec<TickRecord>,
+    keyframes: Vec<Keyframe>,
+    /// Arena after the last recorded step, to detect outside edits
+    last: Option<Arena>,
+}
+
+impl Replay {
+    /// Creates an empty replay keeping a keyframe every `keyframe_interval`
+    /// steps (at least 1).
+    #[must_use]
+    pub fn new(keyframe_interval: u64) -> Self {
+        Self {
+            header: ReplayHeader {
+                seed: 0,
+                scenario: None,
+                start_tick: 0,
+                tick_count: 0,
+                keyframe_interval: keyframe_interval.max(1),
+                action_interval: default_action_interval(),
+                physics_dt: default_physics_dt(),
+            },
+            ticks: Vec::new(),
+            keyframes: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// Records the scenario the run was set up from.
+    #[must_use]
+    pub fn with_scenario(mut self, scenario: impl Into<String>) -> Self {
+        self.header.scenario = Some(scenario.into());
+        self
+    }
+
+    /// Returns the file header.
+    #[must_use]
+    pub const fn header(&self) -> &ReplayHeader {
+        &self.header
+    }
+
+    /// Returns the recorded steps in tick order.
+    #[must_use]
+    pub fn ticks(&self) -> &[TickRecord] {
+        &self.ticks
+    }
+
+    /// Returns the keyframes in tick order.
+    #[must_use]
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Returns the tick after the last recorded step.
+    #[must_use]
+    pub const fn end_tick(&self) -> u64 {
+        self.header.start_tick + self.header.tick_count
+    }
+
+    /// Returns the recorded step that executed `tick`.
+    #[must_use]
+    pub fn tick(&self, tick: u64) -> Option<&TickRecord> {
+        let index = tick.checked_sub(self.header.start_tick)?;
+        self.ticks.get(usize::try_from(index).ok()?)
+    }
+
+    /// Steps `sim` with `commands` and records the step.
+    ///
+    /// The first step fixes the seed, start tick, action interval and
+    /// physics step. Edits made to the
+    /// arena since the previous recorded step are stored with this one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReplayError::Discontinuous`] if `sim` is not at the tick
+    /// after the last recorded step, or [`ReplayError::WatchdogActive`] if
+    /// its plugin watchdog [`is_active`](crate::watchdog::PluginWatchdog::is_active);
+    /// nothing is stepped then.
+    pub fn record_step(
+        &mut self,
+        sim: &mut Simulation,
+        commands: &[Command],
+    ) -> Result<(), ReplayError> {
+        if sim.plugin_watchdog().is_active() {
+            return Err(ReplayError::WatchdogActive);
+        }
+        let tick = sim.tick();
+        if self.ticks.is_empty() {
+            self.header.seed = sim.seed();
+            self.header.start_tick = tick;
+            self.header.action_interval = sim.action_interval();
+            self.header.physics_dt = sim.physics_dt();
+        } else if tick != self.end_tick() {
+            return Err(ReplayError::Discontinuous {
+                expected: self.end_tick(),
+                actual: tick,
+            });
+        }
+
+        let due = self.header.tick_count.is_multiple_of(self.header.keyframe_interval);
+        let edits = match &self.last {
+            Some(last) if !due => Some(last.diff(sim.arena())).filter(|d| !d.is_empty()),
+            _ => {
+                self.keyframes.push(Keyframe {
+                    tick,
+                    arena: sim.arena().clone(),
+                    held_commands: sim.held_commands().to_vec(),
+                    kill_tallies: sim.kill_ledger().tallies().into_iter().collect(),
+                });
+                None
+            }
+        };
+
+        sim.step_with_commands(commands);
+        self.ticks.push(TickRecord {
+            tick,
+            edits,
+            commands: commands.to_vec(),
+            events: sim.events(),
+            state_hash: Some(sim.arena().state_hash()),
+        });
+        self.header.tick_count += 1;
+        self.last = Some(sim.arena().clone());
+        Ok(())
+    }
+
+    /// Puts `sim` in the state it had just before `tick` executed (or, for
+    /// the end tick, after the last recorded step).
+    ///
+    /// Restores the nearest keyframe at or before `tick`, kill tallies
+    /// included, then re-steps the recorded edits and commands up to it. `sim` must use the recorded
+    /// seed, plugins and resolvers for the result to match the run.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReplayError::TickOutOfRange`] for a tick outside the
+    /// recording, [`ReplayError::WatchdogActive`] if `sim` has an active
+    /// plugin watchdog, [`ReplayError::Delta`] if a recorded edit does not
+    /// apply, or [`ReplayError::Diverged`] if the re-stepped state differs
+    /// from the recorded one.
+    pub fn seek(&self, sim: &mut Simulation, tick: u64) -> Result<(), ReplayError> {
+        if sim.plugin_watchdog().is_active() {
+            return Err(ReplayError::WatchdogActive);
+        }
+        let out_of_range = ReplayError::TickOutOfRange {
+            tick,
+            start: self.header.start_tick,
+            end: self.end_tick(),
+        };
+        if tick < self.header.start_tick || tick > self.end_tick() {
+            return Err(out_of_range);
+        }
+        let keyframe = self
+            .keyframes
+            .iter()
+            .rev()
+            .find(|k| k.tick <= tick)
+            .ok_or(out_of_range)?;
+
+        sim.restore(keyframe.arena.clone());
+        sim.set_held_commands(keyframe.held_commands.clone());
+        sim.kill_ledger().restore(keyframe.kill_tallies.iter().copied().collect());
+        let mut last = None;
+        for record in self.ticks.iter().filter(|r| (keyframe.tick..tick).contains(&r.tick)) {
+            if let Some(edits) = &record.edits {
+                sim.arena_mut().apply_delta(edits)?;
+            }
+            sim.step_with_commands(&record.commands);
+            last = Some(record);
+        }
+        if let Some(record) = last {
+            let actual = sim.arena().state_hash();
+            match record.state_hash {
+                Some(expected) if expected != actual => {
+                    return Err(ReplayError::Diverged {
+                        tick: record.tick,
+                        expected,
+                        actual,
+                    })
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the arena just before `tick` executed, as reconstructed by
+    /// [`Simulation::fork_from`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Simulation::fork_from`].
+    pub fn state_at(&self, tick: u64) -> Result<Arena, ReplayError> {
+        Ok(Simulation::fork_from(self, tick)?.arena().clone())
+    }
+
+    /// Returns a copy of the recording cut just before `tick`, to continue
+    /// recording a fork started there.
+    ///
+    /// Steps from `tick` on and keyframes after it are dropped; the next
+    /// recorded step stores a keyframe.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReplayError::TickOutOfRange`] for a tick outside the
+    /// recording.
+    pub fn branch(&self, tick: u64) -> Result<Self, ReplayError> {
+        if tick < self.header.start_tick || tick > self.end_tick() {
+            return Err(ReplayError::TickOutOfRange {
+                tick,
+                start: self.header.start_tick,
+                end: self.end_tick(),
+            });
+        }
+        let ticks: Vec<TickRecord> = self.ticks.iter().filter(|r| r.tick < tick).cloned().collect();
+        let mut header = self.header.clone();
+        header.tick_count = ticks.len() as u64;
+        Ok(Self {
+            header,
+            ticks,
+            keyframes: self.keyframes.iter().filter(|k| k.tick < tick).cloned().collect(),
+            last: None,
+        })
+    }
+
+    /// Writes the replay in the `.tbr` format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encoding or writing fails.
+    pub fn export(&self, mut writer: impl Write) -> Result<(), ReplayError> {
+        let header = serde_json::to_vec(&self.header)?;
+        let header_len = u32::try_from(header.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "replay header too large"))?;
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&header_len.to_le_bytes())?;
+        writer.write_all(&header)?;
+
+        let mut body = ZlibEncoder::new(writer, Compression::default());
+        serde_json::to_writer(
+            &mut body,
+            &ReplayBodyRef {
+                ticks: &self.ticks,
+                keyframes: &self.keyframes,
+            },
+        )?;
+        body.finish()?.flush()?;
+        Ok(())
+    }
+
+    /// Reads a replay written by [`export`](Self::export).
+    ///
+    /// Recording can continue on the result; its next step is stored with
+    /// a keyframe.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a supported `.tbr` replay.
+    pub fn import(mut reader: impl Read) -> Result<Self, ReplayError> {
+        let header = Self::read_header(&mut reader)?;
+        let body: ReplayBody = serde_json::from_reader(ZlibDecoder::new(reader))?;
+        Ok(Self {
+            header,
+            ticks: body.ticks,
+            keyframes: body.keyframes,
+            last: None,
+        })
+    }
+
+    /// Reads only the header of a `.tbr` replay, leaving the body unread.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a supported `.tbr` replay.
+    pub fn read_header(mut reader: impl Read) -> Result<ReplayHeader, ReplayError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(ReplayError::BadMagic);
+        }
+        let mut version = [0; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version > FORMAT_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+        let mut header_len = [0; 4];
+        reader.read_exact(&mut header_len)?;
+        let mut header = vec![0; u32::from_le_bytes(header_len) as usize];
+        reader.read_exact(&mut header)?;
+        Ok(serde_json::from_slice(&header)?)
+    }
+}
+
+/// Borrowed form of [`ReplayBody`] for writing.
+#[derive(Serialize)]
+struct ReplayBodyRef<'a> {
+    ticks: &'a [TickRecord],
+    keyframes: &'a [Keyframe],
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityId, EntityInner, EntityTag, ShipComponents};
+    use glam::Vec2;
+
+    fn sim_with_ship() -> (Simulation, EntityId) {
+        let mut sim = Simulation::new(11);
+        let ship = sim.arena_mut().spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::default().with_physics(10.0, 1.0)),
+        );
+        (sim, ship)
+    }
+
+    fn set_velocity(sim: &mut Simulation, id: EntityId, velocity: Vec2) {
+        let ship = sim.arena_mut().get_mut(id).unwrap().as_ship_mut().unwrap();
+        ship.physics.velocity = velocity;
+    }
+
+    #[test]
+    fn seek_reproduces_every_tick() {
+        let (mut sim, ship) = sim_with_ship();
+        let mut replay = Replay::new(4);
+        let mut hashes = vec![sim.arena().state_hash()];
+        for step in 0..10u8 {
+            let commands = [Command::SetVelocity {
+                target: ship,
+                velocity: Vec2::new(f32::from(step), 0.0),
+            }];
+            replay.record_step(&mut sim, &commands).unwrap();
+            hashes.push(sim.arena().state_hash());
+        }
+        assert_eq!(replay.keyframes().len(), 3);
+
+        let mut file = Vec::new();
+        replay.export(&mut file).unwrap();
+        let loaded = Replay::import(file.as_slice()).unwrap();
+        assert_eq!(loaded.header(), replay.header());
+
+        let mut viewer = Simulation::new(loaded.header().seed);
+        for tick in [7, 0, 10, 4, 5] {
+            loaded.seek(&mut viewer, tick as u64).unwrap();
+            assert_eq!(viewer.arena().state_hash(), hashes[tick], "tick {tick}");
+        }
+        assert!(matches!(
+            loaded.seek(&mut viewer, 11),
+            Err(ReplayError::TickOutOfRange { tick: 11, .. })
+        ));
+    }
+
+    #[test]
+    fn outside_edits_are_recorded() {
+        let (mut sim, ship) = sim_with_ship();
+        let mut replay = Replay::new(100);
+        replay.record_step(&mut sim, &[]).unwrap();
+        set_velocity(&mut sim, ship, Vec2::new(3.0, 4.0));
+        replay.record_step(&mut sim, &[]).unwrap();
+        replay.record_step(&mut sim, &[]).unwrap();
+
+        assert!(replay.tick(1).unwrap().edits.is_some());
+        assert!(replay.tick(2).unwrap().edits.is_none());
+
+        let mut viewer = Simulation::new(replay.header().seed);
+        replay.seek(&mut viewer, 3).unwrap();
+        assert_eq!(viewer.arena().state_hash(), sim.arena().state_hash());
+    }
+
+    #[test]
+    fn fork_continues_independently_and_branch_records_it() {
+        let (mut sim, ship) = sim_with_ship();
+        sim.set_action_interval(3);
+        let mut replay = Replay::new(5);
+        for step in 0..12 {
+            if step == 4 {
+                set_velocity(&mut sim, ship, Vec2::new(2.0, 0.0));
+            }
+            replay.record_step(&mut sim, &[]).unwrap();
+        }
+        assert_eq!(replay.header().action_interval, 3);
+
+        let before = replay.state_at(8).unwrap().state_hash();
+        let mut fork = Simulation::fork_from(&replay, 8).unwrap();
+        assert_eq!(fork.arena().state_hash(), before);
+        assert_eq!(fork.action_interval(), 3);
+
+        let mut branch = replay.branch(8).unwrap();
+        assert_eq!(branch.end_tick(), 8);
+        set_velocity(&mut fork, ship, Vec2::new(0.0, -2.0));
+        for _ in 0..4 {
+            branch.record_step(&mut fork, &[]).unwrap();
+        }
+        assert!(branch.keyframes().iter().any(|k| k.tick == 8));
+
+        // The original is untouched; the branch replays the fork
+        let mut viewer = Simulation::fork_from(&replay, 12).unwrap();
+        assert_eq!(viewer.arena().state_hash(), sim.arena().state_hash());
+        branch.seek(&mut viewer, 12).unwrap();
+        assert_eq!(viewer.arena().state_hash(), fork.arena().state_hash());
+        assert_ne!(fork.arena().state_hash(), sim.arena().state_hash());
+    }
+
+    #[test]
+    fn seek_and_fork_restore_kill_tallies() {
+        use crate::entity::{DamageType, TeamId};
+        use crate::output::{Modifier, Output, OutputKind, PluginId};
+        use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+        use crate::world_view::WorldView;
+        use std::sync::Arc;
+
+        /// Deals 10 damage per tick from `shooter` to each target.
+        struct Gunner {
+            declaration: PluginDeclaration,
+            shooter: EntityId,
+            targets: Vec<EntityId>,
+        }
+
+        impl Plugin for Gunner {
+            fn declaration(&self) -> &PluginDeclaration {
+                &self.declaration
+            }
+
+            fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+                if ctx.entity_id != self.shooter {
+                    return Vec::new();
+                }
+                self.targets
+                    .iter()
+                    .map(|&target| {
+                        Output::Modifier(Modifier::ApplyDamage {
+                            target,
+                            amount: 10.0,
+                            damage_type: DamageType::Kinetic,
+                        })
+                    })
+                    .collect()
+            }
+        }
+
+        let (mut sim, shooter) = sim_with_ship();
+        sim.arena_mut().set_team(shooter, Some(TeamId::new(0)));
+        sim.arena_mut().set_controller(shooter, Some(ControllerId::Agent(0)));
+        let targets: Vec<EntityId> = [20.0, 40.0, 60.0]
+            .into_iter()
+            .map(|hp| {
+                let id = sim.arena_mut().spawn(
+                    EntityTag::Ship,
+                    EntityInner::Ship(ShipComponents::default()),
+                );
+                sim.arena_mut().set_team(id, Some(TeamId::new(1)));
+                sim.arena_mut().get_mut(id).unwrap().as_ship_mut().unwrap().combat.hp = hp;
+                id
+            })
+            .collect();
+        let gunner = Arc::new(Gunner {
+            declaration: PluginDeclaration {
+                id: PluginId::new("gunner"),
+                required_tags: vec![EntityTag::Ship],
+                reads: vec![ComponentKind::Combat],
+                emits: vec![OutputKind::Modifier],
+                runs_after: vec![],
+                every_tick: true,
+            },
+            shooter,
+            targets,
+        });
+        sim.plugins_mut().register(EntityTag::Ship, gunner.clone());
+
+        let mut replay = Replay::new(4);
+        let mut kills = vec![0];
+        for _ in 0..8 {
+            replay.record_step(&mut sim, &[]).unwrap();
+            kills.push(sim.kill_ledger().tally(ControllerId::Agent(0)).kills);
+        }
+        assert_eq!(kills[8], 3);
+
+        // Seeking back drops the abandoned timeline's kills
+        for tick in [3, 8, 6, 1] {
+            replay.seek(&mut sim, tick as u64).unwrap();
+            let tally = sim.kill_ledger().tally(ControllerId::Agent(0));
+            assert_eq!(tally.kills, kills[tick], "tick {tick}");
+        }
+
+        // A fork keeps the kills made before its keyframe
+        let mut fork = Simulation::new(replay.header().seed);
+        fork.plugins_mut().register(EntityTag::Ship, gunner);
+        replay.seek(&mut fork, 7).unwrap();
+        assert_eq!(fork.kill_ledger().tally(ControllerId::Agent(0)).kills, kills[7]);
+    }
+
+    #[test]
+    fn seek_detects_divergence() {
+        let (mut sim, ship) = sim_with_ship();
+        let mut replay = Replay::new(10);
+        for _ in 0..3 {
+            replay.record_step(&mut sim, &[]).unwrap();
+        }
+        replay.ticks[1].commands.push(Command::SetVelocity {
+            target: ship,
+            velocity: Vec2::new(1.0, 0.0),
+        });
+        assert!(matches!(
+            Simulation::fork_from(&replay, 3),
+            Err(ReplayError::Diverged { tick: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn refuses_an_active_watchdog() {
+        use crate::watchdog::PluginBudget;
+        use std::time::Duration;
+
+        let (mut sim, _) = sim_with_ship();
+        let mut replay = Replay::new(10);
+        replay.record_step(&mut sim, &[]).unwrap();
+
+        let budget = PluginBudget::new(Duration::from_millis(1), 1);
+        sim.plugin_watchdog_mut().set_budget(Some(budget));
+        assert!(matches!(
+            replay.record_step(&mut sim, &[]),
+            Err(ReplayError::WatchdogActive)
+        ));
+        assert_eq!(sim.tick(), 1);
+
+        let mut viewer = Simulation::new(replay.header().seed);
+        viewer.plugin_watchdog_mut().set_budget(Some(budget));
+        assert!(matches!(
+            replay.seek(&mut viewer, 1),
+            Err(ReplayError::WatchdogActive)
+        ));
+    }
+
+    #[test]
+    fn rejects_foreign_data_and_gaps() {
+        assert!(matches!(Replay::import(&b"PK\x03\x04"[..]), Err(ReplayError::BadMagic)));
+        let mut newer = MAGIC.to_vec();
+        newer.extend_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            Replay::read_header(newer.as_slice()),
+            Err(ReplayError::UnsupportedVersion(_))
+        ));
+
+        let (mut sim, _) = sim_with_ship();
+        let mut replay = Replay::new(10);
+        replay.record_step(&mut sim, &[]).unwrap();
+        sim.step();
+        assert!(matches!(
+            replay.record_step(&mut sim, &[]),
+            Err(ReplayError::Discontinuous { expected: 1, actual: 2 })
+        ));
+    }
+}
@@ -0,0 +1,338 @@
+//! Aggregate (Lanchester) combat resolver for the strategic layer.
+//!
+//! Campaign-scale simulations do not need projectile-level fidelity. The
+//! `AggregateCombatResolver` resolves engagements between squadron/fleet-level
+//! entities statistically: every tick, each unit deals expected losses to the
+//! opposing units in range in proportion to its own strength (Lanchester's
+//! square law, `dA/dt = -lethality * B`).
+//!
+//! # Model
+//!
+//! - A unit's strength is its current HP, so a squadron built with
+//!   `with_craft_count` fights with the strength of its surviving craft.
+//! - Units on different teams engage when within `engagement_range`.
+//!   Unteamed units never engage.
+//! - A unit splits its fire evenly across all enemies in range.
+//! - Losses are computed from the current state for all units at once, then
+//!   applied to the next state, so the exchange is simultaneous.
+//!
+//! Weapon and projectile plugins are not needed for aggregate units. Plugin
+//! modifiers (healing, status flags) are still applied by the
+//! `CombatResolver`.
+
+use glam::Vec2;
+
+use crate::arena::Arena;
+use crate::entity::components::{CombatState, StatusFlags};
+use crate::entity::{Entity, EntityId, EntityInner, EntityTag, TeamId};
+use crate::output::{OutputEnvelope, OutputKind};
+
+use super::Resolver;
+
+/// Configuration for aggregate combat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateCombatConfig {
+    /// Damage dealt per tick per point of firing strength.
+    pub lethality: f32,
+    /// Maximum distance at which opposing units engage.
+    pub engagement_range: f32,
+    /// Entity types resolved in aggregate.
+    pub tags: Vec<EntityTag>,
+}
+
+impl Default for AggregateCombatConfig {
+    fn default() -> Self {
+        Self {
+            lethality: 0.001,
+            engagement_range: 5000.0,
+            tags: vec![EntityTag::Squadron],
+        }
+    }
+}
+
+/// A unit taking part in aggregate combat.
+struct Unit {
+    id: EntityId,
+    team: TeamId,
+    position: Vec2,
+    strength: f32,
+}
+
+/// Resolver applying Lanchester attrition between opposing units.
+///
+/// Opt-in: add it with `Simulation::add_resolver`, or select
+/// `CombatModel::Aggregate` in the `SimulationConfig`.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, SquadronComponents, TeamId};
+/// use tidebreak_core::resolver::{AggregateCombatResolver, Resolver};
+/// use glam::Vec2;
+///
+/// let mut arena = Arena::new();
+/// for (x, team, craft) in [(0.0, 0, 20), (100.0, 1, 10)] {
+///     let squadron = SquadronComponents::at_position(Vec2::new(x, 0.0), 0.0)
+///         .with_craft_count(craft, 10.0);
+///     let id = arena.spawn(EntityTag::Squadron, EntityInner::Squadron(squadron));
+///     arena.set_team(id, Some(TeamId::new(team)));
+/// }
+///
+/// let resolver = AggregateCombatResolver::new();
+/// let losses = resolver.expected_losses(&arena);
+/// // The larger squadron inflicts twice the losses it takes
+/// assert!((losses[1].1 - 2.0 * losses[0].1).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AggregateCombatResolver {
+    config: AggregateCombatConfig,
+}
+
+impl AggregateCombatResolver {
+    /// Creates a new resolver with the default configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_config(AggregateCombatConfig::default())
+    }
+
+    /// Creates a new resolver with a custom configuration.
+    #[must_use]
+    pub const fn with_config(config: AggregateCombatConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the resolver configuration.
+    #[must_use]
+    pub const fn config(&self) -> &AggregateCombatConfig {
+        &self.config
+    }
+
+    /// Returns the expected HP loss of every engaged unit for one tick,
+    /// sorted by entity ID.
+    ///
+    /// Units that are not engaged are omitted.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn expected_losses(&self, arena: &Arena) -> Vec<(EntityId, f32)> {
+        let units: Vec<Unit> = arena
+            .entities_sorted()
+            .filter(|e| self.config.tags.contains(&e.tag()))
+            .filter_map(Self::unit)
+            .collect();
+        let range_sq = self.config.engagement_range * self.config.engagement_range;
+        let engaged = |a: &Unit, b: &Unit| {
+            a.team != b.team && a.position.distance_squared(b.position) <= range_sq
+        };
+
+        let mut losses = vec![0.0_f32; units.len()];
+        for shooter in &units {
+            let targets: Vec<usize> = (0..units.len())
+                .filter(|&i| engaged(shooter, &units[i]))
+                .collect();
+            if targets.is_empty() {
+                continue;
+            }
+            let per_target = self.config.lethality * shooter.strength / targets.len() as f32;
+            for i in targets {
+                losses[i] += per_target;
+            }
+        }
+
+        units
+            .iter()
+            .zip(losses)
+            .filter(|(_, loss)| *loss > 0.0)
+            .map(|(unit, loss)| (unit.id, loss))
+            .collect()
+    }
+
+    /// Returns the unit view of an entity, if it can fight.
+    fn unit(entity: &Entity) -> Option<Unit> {
+        let (position, combat) = match entity.inner() {
+            EntityInner::Ship(c) => (c.transform.position, &c.combat),
+            EntityInner::Squadron(c) => (c.transform.position, &c.combat),
+            EntityInner::Platform(_) | EntityInner::Projectile(_) => return None,
+        };
+        if combat.is_destroyed() || combat.hp <= 0.0 {
+            return None;
+        }
+        Some(Unit {
+            id: entity.id(),
+            team: entity.team()?,
+            position,
+            strength: combat.hp,
+        })
+    }
+
+    /// Returns the combat state of an entity in the next arena.
+    fn combat_mut(next: &mut Arena, id: EntityId) -> Option<&mut CombatState> {
+        match next.get_mut(id)?.inner_mut() {
+            EntityInner::Ship(c) => Some(&mut c.combat),
+            EntityInner::Squadron(c) => Some(&mut c.combat),
+            EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+        }
+    }
+}
+
+impl Resolver for AggregateCombatResolver {
+    fn handles(&self) -> &[OutputKind] {
+        // Attrition is driven by world state, not plugin outputs
+        &[]
+    }
+
+    fn resolve(&self, _outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        for (id, loss) in self.expected_losses(current) {
+            if let Some(combat) = Self::combat_mut(next, id) {
+                combat.hp -= loss;
+                if combat.hp <= 0.0 {
+                    combat.hp = 0.0;
+                    combat.status_flags.insert(StatusFlags::DESTROYED);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{ShipComponents, SquadronComponents};
+
+    fn spawn_squadron(arena: &mut Arena, x: f32, team: Option<u32>, craft: u32) -> EntityId {
+        let squadron =
+            SquadronComponents::at_position(Vec2::new(x, 0.0), 0.0).with_craft_count(craft, 10.0);
+        let id = arena.spawn(EntityTag::Squadron, EntityInner::Squadron(squadron));
+        arena.set_team(id, team.map(TeamId::new));
+        id
+    }
+
+    fn hp(arena: &Arena, id: EntityId) -> f32 {
+        arena.get(id).unwrap().as_squadron().unwrap().combat.hp
+    }
+
+    fn resolve_tick(resolver: &AggregateCombatResolver, arena: &mut Arena) {
+        let current = arena.clone();
+        resolver.resolve(&[], &current, arena);
+    }
+
+    mod resolver_trait_tests {
+        use super::*;
+
+        #[test]
+        fn handles_no_outputs() {
+            assert!(AggregateCombatResolver::new().handles().is_empty());
+        }
+
+        #[test]
+        fn default_config_resolves_squadrons() {
+            let resolver = AggregateCombatResolver::new();
+            assert_eq!(resolver.config().tags, vec![EntityTag::Squadron]);
+        }
+    }
+
+    mod attrition_tests {
+        use super::*;
+
+        #[test]
+        fn losses_proportional_to_enemy_strength() {
+            let mut arena = Arena::new();
+            let strong = spawn_squadron(&mut arena, 0.0, Some(0), 20);
+            let weak = spawn_squadron(&mut arena, 100.0, Some(1), 10);
+            let resolver = AggregateCombatResolver::new();
+
+            resolve_tick(&resolver, &mut arena);
+
+            // 200 HP vs 100 HP at lethality 0.001
+            assert!((hp(&arena, strong) - (200.0 - 0.1)).abs() < 1e-4);
+            assert!((hp(&arena, weak) - (100.0 - 0.2)).abs() < 1e-4);
+        }
+
+        #[test]
+        fn fire_is_split_across_targets() {
+            let mut arena = Arena::new();
+            spawn_squadron(&mut arena, 0.0, Some(0), 10);
+            let a = spawn_squadron(&mut arena, 100.0, Some(1), 10);
+            let b = spawn_squadron(&mut arena, -100.0, Some(1), 10);
+            let resolver = AggregateCombatResolver::new();
+
+            let losses = resolver.expected_losses(&arena);
+
+            let loss = |id| losses.iter().find(|(e, _)| *e == id).unwrap().1;
+            assert!((loss(a) - 0.05).abs() < 1e-6);
+            assert!((loss(b) - 0.05).abs() < 1e-6);
+        }
+
+        #[test]
+        fn out_of_range_and_friendly_units_do_not_engage() {
+            let mut arena = Arena::new();
+            spawn_squadron(&mut arena, 0.0, Some(0), 10);
+            spawn_squadron(&mut arena, 10.0, Some(0), 10);
+            spawn_squadron(&mut arena, 100_000.0, Some(1), 10);
+            spawn_squadron(&mut arena, 20.0, None, 10);
+            let resolver = AggregateCombatResolver::new();
+
+            assert!(resolver.expected_losses(&arena).is_empty());
+        }
+
+        #[test]
+        fn weaker_side_is_destroyed_first() {
+            let mut arena = Arena::new();
+            let strong = spawn_squadron(&mut arena, 0.0, Some(0), 20);
+            let weak = spawn_squadron(&mut arena, 100.0, Some(1), 10);
+            let resolver = AggregateCombatResolver::with_config(AggregateCombatConfig {
+                lethality: 0.05,
+                ..AggregateCombatConfig::default()
+            });
+
+            for _ in 0..100 {
+                resolve_tick(&resolver, &mut arena);
+            }
+
+            let weak_combat = &arena.get(weak).unwrap().as_squadron().unwrap().combat;
+            assert!(weak_combat.is_destroyed());
+            assert!(weak_combat.hp.abs() < f32::EPSILON);
+            // Square law: survivors ~ sqrt(200^2 - 100^2) ~ 173
+            assert!(hp(&arena, strong) > 150.0);
+        }
+
+        #[test]
+        fn destroyed_units_stop_fighting() {
+            let mut arena = Arena::new();
+            let a = spawn_squadron(&mut arena, 0.0, Some(0), 10);
+            let b = spawn_squadron(&mut arena, 100.0, Some(1), 10);
+            arena
+                .get_mut(b)
+                .and_then(Entity::as_squadron_mut)
+                .unwrap()
+                .combat
+                .status_flags
+                .insert(StatusFlags::DESTROYED);
+            let resolver = AggregateCombatResolver::new();
+
+            resolve_tick(&resolver, &mut arena);
+
+            assert!((hp(&arena, a) - 100.0).abs() < f32::EPSILON);
+        }
+
+        #[test]
+        fn ships_take_part_only_when_configured() {
+            let mut arena = Arena::new();
+            let ship = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+            );
+            arena.set_team(ship, Some(TeamId::new(0)));
+            spawn_squadron(&mut arena, 100.0, Some(1), 10);
+
+            let default = AggregateCombatResolver::new();
+            assert!(default.expected_losses(&arena).is_empty());
+
+            let with_ships = AggregateCombatResolver::with_config(AggregateCombatConfig {
+                tags: vec![EntityTag::Ship, EntityTag::Squadron],
+                ..AggregateCombatConfig::default()
+            });
+            assert_eq!(with_ships.expected_losses(&arena).len(), 2);
+        }
+    }
+}
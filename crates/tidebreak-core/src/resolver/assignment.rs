@@ -0,0 +1,424 @@
+//! Weapon assignment resolver for deconflicting engagements.
+//!
+//! When several shooters on the same team fire at the same target in one
+//! tick, most of those shots are wasted (overkill). The
+//! `WeaponAssignmentResolver` looks at all `FireWeapon` commands for a tick,
+//! groups them by (team, target), and keeps only as many shooters as are
+//! needed to destroy the target, preferring the closest shooters.
+//!
+//! Accepted engagements are recorded as `WeaponAssigned` events, which can be
+//! drained with `take_assignments()`.
+//!
+//! Resolvers cannot hide outputs from each other, so registered as a plain
+//! resolver this one only logs. To drop the unassigned shots, enable it on
+//! the [`Simulation`] with `enable_weapon_assignment` (or the config's
+//! `weapon_assignment`): the simulation then runs
+//! [`WeaponAssignmentResolver::deconflict`] on each tick's outputs before any
+//! resolver sees them. Either way it does not mutate game state.
+//!
+//! [`Simulation`]: crate::simulation::Simulation
+//!
+//! # Grouping
+//!
+//! Shooters without a team are never grouped with anyone else, so they are
+//! always assigned.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::entity::{Entity, EntityId, EntityInner, TeamId};
+use crate::output::{Command, Event, Output, OutputEnvelope, OutputKind};
+
+use super::Resolver;
+
+/// Configuration for weapon assignment.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentConfig {
+    /// Expected damage of one shot, used to estimate shooters needed.
+    pub damage_per_shot: f32,
+    /// Upper bound on shooters assigned to one target per tick.
+    pub max_shooters_per_target: usize,
+}
+
+impl Default for AssignmentConfig {
+    fn default() -> Self {
+        Self {
+            damage_per_shot: 25.0,
+            max_shooters_per_target: 2,
+        }
+    }
+}
+
+/// Grouping key: a team, or a lone unteamed shooter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Side {
+    Team(TeamId),
+    Solo(EntityId),
+}
+
+/// Index of a `FireWeapon` command in the tick's outputs, with its shooter
+/// and weapon slot.
+type Engagement = (usize, EntityId, usize);
+
+/// Resolver that deconflicts `FireWeapon` commands within a team.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::resolver::{Resolver, WeaponAssignmentResolver};
+/// use tidebreak_core::output::OutputKind;
+///
+/// let resolver = WeaponAssignmentResolver::new();
+/// assert!(resolver.handles().contains(&OutputKind::Command));
+/// assert!(resolver.take_assignments().is_empty());
+/// ```
+#[derive(Debug, Default)]
+pub struct WeaponAssignmentResolver {
+    config: AssignmentConfig,
+    /// Assignment log, protected by a mutex for thread safety.
+    assignments: Mutex<Vec<OutputEnvelope>>,
+}
+
+impl WeaponAssignmentResolver {
+    /// Creates a new resolver with the default configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_config(AssignmentConfig::default())
+    }
+
+    /// Creates a new resolver with a custom configuration.
+    #[must_use]
+    pub fn with_config(config: AssignmentConfig) -> Self {
+        Self {
+            config,
+            assignments: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the resolver configuration.
+    #[must_use]
+    pub const fn config(&self) -> &AssignmentConfig {
+        &self.config
+    }
+
+    /// Drains and returns all recorded `WeaponAssigned` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn take_assignments(&self) -> Vec<OutputEnvelope> {
+        let mut log = self.assignments.lock().unwrap();
+        std::mem::take(&mut *log)
+    }
+
+    /// Returns the number of shooters needed to destroy `target`.
+    ///
+    /// Targets without combat state need a single shooter.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn shooters_needed(&self, current: &Arena, target: EntityId) -> usize {
+        let hp = current.get(target).and_then(|e| match e.inner() {
+            EntityInner::Ship(c) => Some(c.combat.hp),
+            EntityInner::Squadron(c) => Some(c.combat.hp),
+            _ => None,
+        });
+        let needed = match hp {
+            Some(hp) if self.config.damage_per_shot > 0.0 => {
+                (hp / self.config.damage_per_shot).ceil().max(1.0) as usize
+            }
+            _ => 1,
+        };
+        needed.min(self.config.max_shooters_per_target.max(1))
+    }
+
+    /// Returns `outputs` without the `FireWeapon` commands left unassigned,
+    /// recording the assigned ones as `WeaponAssigned` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn deconflict(&self, outputs: &[OutputEnvelope], current: &Arena) -> Vec<OutputEnvelope> {
+        let refs: Vec<_> = outputs.iter().collect();
+        let unassigned = self.assign(&refs, current);
+        outputs
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !unassigned.contains(index))
+            .map(|(_, envelope)| envelope.clone())
+            .collect()
+    }
+
+    /// Logs the assigned `FireWeapon` commands among `outputs` and returns
+    /// the indices of the unassigned ones.
+    fn assign(&self, outputs: &[&OutputEnvelope], current: &Arena) -> BTreeSet<usize> {
+        // Group FireWeapon commands by (side, target). BTreeMap keeps the
+        // grouping order deterministic.
+        let mut groups: BTreeMap<(Side, EntityId), Vec<Engagement>> = BTreeMap::new();
+        for (index, envelope) in outputs.iter().enumerate() {
+            if let Some(Command::FireWeapon {
+                source,
+                target,
+                slot,
+            }) = envelope.output().as_command()
+            {
+                let side = current
+                    .get(*source)
+                    .and_then(Entity::team)
+                    .map_or(Side::Solo(*source), Side::Team);
+                groups
+                    .entry((side, *target))
+                    .or_default()
+                    .push((index, *source, *slot));
+            }
+        }
+
+        let mut unassigned = BTreeSet::new();
+        let mut log = self.assignments.lock().unwrap();
+        for ((_, target), mut shooters) in groups {
+            let target_pos = current.spatial().get(target);
+            let distance = |shooter: EntityId| match (target_pos, current.spatial().get(shooter)) {
+                (Some(t), Some(s)) => t.distance_squared(s),
+                _ => f32::INFINITY,
+            };
+            shooters.sort_by(|a, b| {
+                distance(a.1)
+                    .total_cmp(&distance(b.1))
+                    .then(a.1.cmp(&b.1))
+                    .then(a.2.cmp(&b.2))
+            });
+
+            // One weapon per shooter per target.
+            let mut assigned: Vec<Engagement> = Vec::with_capacity(shooters.len());
+            let needed = self.shooters_needed(current, target);
+            for engagement in shooters {
+                let repeat = assigned.last().is_some_and(|last| last.1 == engagement.1);
+                if repeat || assigned.len() == needed {
+                    unassigned.insert(engagement.0);
+                } else {
+                    assigned.push(engagement);
+                }
+            }
+
+            for (index, shooter, slot) in assigned {
+                let envelope = outputs[index];
+                log.push(OutputEnvelope::new(
+                    Output::Event(Event::WeaponAssigned {
+                        shooter,
+                        target,
+                        weapon_slot: slot,
+                    }),
+                    envelope.source().clone(),
+                    envelope.trace_id(),
+                    envelope.tick(),
+                    envelope.sequence(),
+                ));
+            }
+        }
+        unassigned
+    }
+}
+
+impl Resolver for WeaponAssignmentResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[OutputKind::Command]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, _next: &mut Arena) {
+        self.assign(outputs, current);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{PluginId, PluginInstanceId, TraceId};
+    use crate::tests::spawn_team_ship;
+    use glam::Vec2;
+
+    fn make_envelope(output: Output, entity: EntityId) -> OutputEnvelope {
+        OutputEnvelope::new(
+            output,
+            PluginInstanceId::new(entity, PluginId::new("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn fire(source: EntityId, target: EntityId) -> OutputEnvelope {
+        make_envelope(
+            Output::Command(Command::FireWeapon {
+                source,
+                target,
+                slot: 0,
+            }),
+            source,
+        )
+    }
+
+    fn assigned_shooters(resolver: &WeaponAssignmentResolver) -> Vec<EntityId> {
+        resolver
+            .take_assignments()
+            .iter()
+            .map(|e| match e.output() {
+                Output::Event(Event::WeaponAssigned { shooter, .. }) => *shooter,
+                _ => panic!("Expected WeaponAssigned event"),
+            })
+            .collect()
+    }
+
+    mod resolver_trait_tests {
+        use super::*;
+
+        #[test]
+        fn handles_command_kind() {
+            let resolver = WeaponAssignmentResolver::new();
+            assert!(resolver.handles().contains(&OutputKind::Command));
+            assert!(!resolver.handles().contains(&OutputKind::Event));
+        }
+    }
+
+    mod deconfliction_tests {
+        use super::*;
+
+        #[test]
+        fn limits_shooters_to_those_needed() {
+            let mut arena = Arena::new();
+            let target = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+            let near = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+            let far = spawn_team_ship(&mut arena, Vec2::new(300.0, 0.0), Some(0));
+            let mid = spawn_team_ship(&mut arena, Vec2::new(200.0, 0.0), Some(0));
+
+            // 100 HP at 50 per shot needs two shooters.
+            let resolver = WeaponAssignmentResolver::with_config(AssignmentConfig {
+                damage_per_shot: 50.0,
+                max_shooters_per_target: 4,
+            });
+            let envs = [fire(far, target), fire(mid, target), fire(near, target)];
+            let refs: Vec<_> = envs.iter().collect();
+            let current = arena.clone();
+            resolver.resolve(&refs, &current, &mut arena);
+
+            assert_eq!(assigned_shooters(&resolver), vec![near, mid]);
+        }
+
+        #[test]
+        fn respects_max_shooters() {
+            let mut arena = Arena::new();
+            let target = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+            let a = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+            let b = spawn_team_ship(&mut arena, Vec2::new(200.0, 0.0), Some(0));
+
+            let resolver = WeaponAssignmentResolver::with_config(AssignmentConfig {
+                damage_per_shot: 1.0,
+                max_shooters_per_target: 1,
+            });
+            let envs = [fire(a, target), fire(b, target)];
+            let refs: Vec<_> = envs.iter().collect();
+            let current = arena.clone();
+            resolver.resolve(&refs, &current, &mut arena);
+
+            assert_eq!(assigned_shooters(&resolver), vec![a]);
+        }
+
+        #[test]
+        fn teams_are_deconflicted_independently() {
+            let mut arena = Arena::new();
+            let target = spawn_team_ship(&mut arena, Vec2::ZERO, Some(2));
+            let blue = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+            let red = spawn_team_ship(&mut arena, Vec2::new(200.0, 0.0), Some(1));
+
+            let resolver = WeaponAssignmentResolver::with_config(AssignmentConfig {
+                damage_per_shot: 1000.0,
+                max_shooters_per_target: 1,
+            });
+            let envs = [fire(blue, target), fire(red, target)];
+            let refs: Vec<_> = envs.iter().collect();
+            let current = arena.clone();
+            resolver.resolve(&refs, &current, &mut arena);
+
+            assert_eq!(assigned_shooters(&resolver).len(), 2);
+        }
+
+        #[test]
+        fn unteamed_shooters_are_always_assigned() {
+            let mut arena = Arena::new();
+            let target = spawn_team_ship(&mut arena, Vec2::ZERO, None);
+            let a = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), None);
+            let b = spawn_team_ship(&mut arena, Vec2::new(200.0, 0.0), None);
+
+            let resolver = WeaponAssignmentResolver::with_config(AssignmentConfig {
+                damage_per_shot: 1000.0,
+                max_shooters_per_target: 1,
+            });
+            let envs = [fire(a, target), fire(b, target)];
+            let refs: Vec<_> = envs.iter().collect();
+            let current = arena.clone();
+            resolver.resolve(&refs, &current, &mut arena);
+
+            assert_eq!(assigned_shooters(&resolver), vec![a, b]);
+        }
+
+        #[test]
+        fn deconflict_drops_unassigned_fire() {
+            let mut arena = Arena::new();
+            let target = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+            let far = spawn_team_ship(&mut arena, Vec2::new(200.0, 0.0), Some(0));
+            let near = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+            let turn = make_envelope(
+                Output::Command(Command::SetHeading {
+                    target: far,
+                    heading: 1.0,
+                }),
+                far,
+            );
+
+            let resolver = WeaponAssignmentResolver::with_config(AssignmentConfig {
+                damage_per_shot: 1000.0,
+                max_shooters_per_target: 2,
+            });
+            let outputs = [fire(far, target), turn.clone(), fire(near, target)];
+            let kept = resolver.deconflict(&outputs, &arena);
+
+            assert_eq!(kept, vec![turn, fire(near, target)]);
+            assert_eq!(assigned_shooters(&resolver), vec![near]);
+        }
+
+        #[test]
+        fn ignores_non_fire_commands() {
+            let mut arena = Arena::new();
+            let ship = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+
+            let envelope = make_envelope(
+                Output::Command(Command::SetHeading {
+                    target: ship,
+                    heading: 1.0,
+                }),
+                ship,
+            );
+            let resolver = WeaponAssignmentResolver::new();
+            let current = arena.clone();
+            resolver.resolve(&[&envelope], &current, &mut arena);
+
+            assert!(resolver.take_assignments().is_empty());
+        }
+
+        #[test]
+        fn does_not_mutate_state() {
+            let mut arena = Arena::new();
+            let target = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+            let shooter = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+
+            let resolver = WeaponAssignmentResolver::new();
+            let envelope = fire(shooter, target);
+            let current = arena.clone();
+            resolver.resolve(&[&envelope], &current, &mut arena);
+
+            assert_eq!(arena.get(target), current.get(target));
+            assert_eq!(arena.get(shooter), current.get(shooter));
+        }
+    }
+}
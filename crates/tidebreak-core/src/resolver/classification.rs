@@ -0,0 +1,474 @@
+//! Classification resolver for track identification.
+//!
+//! The `ClassificationResolver` turns repeated `ContactDetected` events into
+//! classification confidence on the observer's existing track for the target.
+//! Confidence grows with every detection, faster at short range and when the
+//! target is radiating (active emissions are easy to intercept). Once the
+//! confidence crosses the model's threshold, the track is classified:
+//! usually as the target's true [`EntityTag`], but occasionally as a wrong
+//! one, decided by a deterministic RNG seeded from
+//! (seed, tick, observer, target).
+//!
+//! Visual sightings (detections at [`TrackQuality::FireControl`] or better)
+//! identify the target outright: confidence jumps to the model's visual
+//! confidence and the track is classified as the true tag.
+//!
+//! Tracks below the threshold stay unclassified (`classified_as == None`),
+//! which observers should present as "unknown".
+
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::{Arena, Fnv1a};
+use crate::entity::components::{EmissionsMode, TrackQuality};
+use crate::entity::{EntityId, EntityInner, EntityTag};
+use crate::output::{Event, OutputEnvelope, OutputKind};
+use crate::rng_audit::RngAuditLog;
+
+use super::Resolver;
+
+/// Parameters of the classification model.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClassificationModel {
+    /// Confidence gained per detection at zero range.
+    pub gain_per_detection: f32,
+    /// Range at which the gain is halved.
+    pub half_gain_range: f32,
+    /// Gain multiplier when the target's emissions are active.
+    pub emissions_multiplier: f32,
+    /// Confidence at which a track becomes classified.
+    pub threshold: f32,
+    /// Probability that a classification picks a wrong tag.
+    pub misclassification_rate: f32,
+    /// Confidence reached at once by a visual sighting.
+    pub visual_confidence: f32,
+}
+
+impl Default for ClassificationModel {
+    fn default() -> Self {
+        Self {
+            gain_per_detection: 0.1,
+            half_gain_range: 5000.0,
+            emissions_multiplier: 2.0,
+            threshold: 0.7,
+            misclassification_rate: 0.05,
+            visual_confidence: 0.95,
+        }
+    }
+}
+
+impl ClassificationModel {
+    /// Confidence gained from one detection at `distance`.
+    #[must_use]
+    pub fn gain(&self, distance: f32, target_emitting: bool) -> f32 {
+        let range_factor = if self.half_gain_range > 0.0 {
+            self.half_gain_range / (self.half_gain_range + distance.max(0.0))
+        } else {
+            1.0
+        };
+        let emissions = if target_emitting {
+            self.emissions_multiplier
+        } else {
+            1.0
+        };
+        self.gain_per_detection * range_factor * emissions
+    }
+}
+
+/// All tags a classification can resolve to.
+const ALL_TAGS: [EntityTag; 4] = [
+    EntityTag::Ship,
+    EntityTag::Platform,
+    EntityTag::Projectile,
+    EntityTag::Squadron,
+];
+
+/// Resolver that grows track classification confidence from detections.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::resolver::{ClassificationResolver, Resolver};
+/// use tidebreak_core::output::OutputKind;
+///
+/// let resolver = ClassificationResolver::new(42);
+/// assert!(resolver.handles().contains(&OutputKind::Event));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClassificationResolver {
+    model: ClassificationModel,
+    seed: u64,
+    /// Log receiving classification rolls
+    rng_audit: Option<Arc<RngAuditLog>>,
+}
+
+impl ClassificationResolver {
+    /// Creates a resolver with the default model and the given RNG seed.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self::with_model(ClassificationModel::default(), seed)
+    }
+
+    /// Creates a resolver with a custom model.
+    #[must_use]
+    pub const fn with_model(model: ClassificationModel, seed: u64) -> Self {
+        Self {
+            model,
+            seed,
+            rng_audit: None,
+        }
+    }
+
+    /// Records classification rolls into `audit`.
+    #[must_use]
+    pub fn with_rng_audit(mut self, audit: Arc<RngAuditLog>) -> Self {
+        self.rng_audit = Some(audit);
+        self
+    }
+
+    /// Returns the classification model.
+    #[must_use]
+    pub const fn model(&self) -> &ClassificationModel {
+        &self.model
+    }
+
+    /// Key of the deterministic RNG stream for one (tick, observer, target)
+    /// classification.
+    fn stream_key(&self, tick: u64, observer: EntityId, target: EntityId) -> u64 {
+        let mut hasher = Fnv1a::default();
+        self.seed.hash(&mut hasher);
+        tick.hash(&mut hasher);
+        observer.hash(&mut hasher);
+        target.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Picks the tag a newly classified track resolves to.
+    fn roll_tag(&self, tick: u64, stream: u64, truth: EntityTag) -> EntityTag {
+        let mut rng = ChaCha8Rng::seed_from_u64(stream);
+        let roll: f32 = rng.gen();
+        self.audit(tick, stream, "classification.misclassify", f64::from(roll));
+        if roll >= self.model.misclassification_rate {
+            return truth;
+        }
+        let wrong: Vec<_> = ALL_TAGS.iter().copied().filter(|t| *t != truth).collect();
+        let index = rng.gen_range(0..wrong.len());
+        #[allow(clippy::cast_precision_loss)]
+        let value = index as f64;
+        self.audit(tick, stream, "classification.wrong_tag", value);
+        wrong[index]
+    }
+
+    /// Records a draw, if an RNG audit log is attached.
+    fn audit(&self, tick: u64, stream: u64, purpose: &'static str, value: f64) {
+        if let Some(audit) = &self.rng_audit {
+            audit.record(tick, stream, purpose, value);
+        }
+    }
+
+    fn classify(
+        &self,
+        current: &Arena,
+        next: &mut Arena,
+        observer: EntityId,
+        target: EntityId,
+        quality: TrackQuality,
+    ) {
+        let Some(target_entity) = current.get(target) else {
+            return;
+        };
+        let truth = target_entity.tag();
+        let emitting = match target_entity.inner() {
+            EntityInner::Ship(c) => c.sensor.emissions_mode == EmissionsMode::Active,
+            EntityInner::Platform(c) => c.sensor.emissions_mode == EmissionsMode::Active,
+            _ => false,
+        };
+        let distance = match (
+            current.spatial().get(observer),
+            current.spatial().get(target),
+        ) {
+            (Some(a), Some(b)) => a.distance(b),
+            _ => return,
+        };
+        let gain = self.model.gain(distance, emitting);
+
+        let tick = current.current_tick();
+        let Some(sensor) = next.get_mut(observer).and_then(|e| match e.inner_mut() {
+            EntityInner::Ship(c) => Some(&mut c.sensor),
+            EntityInner::Platform(c) => Some(&mut c.sensor),
+            _ => None,
+        }) else {
+            return;
+        };
+        let Some(index) = sensor.track_table.iter().position(|t| t.target_id == target) else {
+            return;
+        };
+        sensor.touch_tracks();
+        let track = &mut sensor.track_table[index];
+
+        track.classification_confidence = (track.classification_confidence + gain).min(1.0);
+        if quality >= TrackQuality::FireControl {
+            track.classification_confidence = track
+                .classification_confidence
+                .max(self.model.visual_confidence);
+            track.classified_as = Some(truth);
+            return;
+        }
+        if track.classified_as.is_none() && track.classification_confidence >= self.model.threshold
+        {
+            let stream = self.stream_key(tick, observer, target);
+            track.classified_as = Some(self.roll_tag(tick, stream, truth));
+        }
+    }
+}
+
+impl Resolver for ClassificationResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[OutputKind::Event]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        for envelope in outputs {
+            if let Some(Event::ContactDetected {
+                observer,
+                target,
+                quality,
+            }) = envelope.output().as_event()
+            {
+                self.classify(current, next, *observer, *target, *quality);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::Track;
+    use crate::entity::{PlatformComponents, ShipComponents};
+    use crate::output::{Output, PluginId, PluginInstanceId, TraceId};
+    use glam::Vec2;
+
+    fn make_envelope(output: Output, entity: EntityId) -> OutputEnvelope {
+        OutputEnvelope::new(
+            output,
+            PluginInstanceId::new(entity, PluginId::new("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn detection(observer: EntityId, target: EntityId) -> OutputEnvelope {
+        make_envelope(
+            Output::Event(Event::ContactDetected {
+                observer,
+                target,
+                quality: TrackQuality::Coarse,
+            }),
+            observer,
+        )
+    }
+
+    fn sighting(observer: EntityId, target: EntityId) -> OutputEnvelope {
+        make_envelope(
+            Output::Event(Event::ContactDetected {
+                observer,
+                target,
+                quality: TrackQuality::FireControl,
+            }),
+            observer,
+        )
+    }
+
+    /// Observer at origin tracking a platform at `x`.
+    fn setup(x: f32) -> (Arena, EntityId, EntityId) {
+        let mut arena = Arena::new();
+        let target = arena.spawn(
+            EntityTag::Platform,
+            EntityInner::Platform(PlatformComponents::at_position(Vec2::new(x, 0.0))),
+        );
+        let mut ship = ShipComponents::default();
+        ship.sensor
+            .track_table
+            .push(Track::new(target, Vec2::new(x, 0.0), TrackQuality::Coarse));
+        let observer = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+        (arena, observer, target)
+    }
+
+    fn track_of(arena: &Arena, observer: EntityId) -> &Track {
+        &arena
+            .get(observer)
+            .unwrap()
+            .as_ship()
+            .unwrap()
+            .sensor
+            .track_table[0]
+    }
+
+    fn step(
+        resolver: &ClassificationResolver,
+        arena: &mut Arena,
+        observer: EntityId,
+        target: EntityId,
+    ) {
+        let envelope = detection(observer, target);
+        let current = arena.clone();
+        resolver.resolve(&[&envelope], &current, arena);
+        arena.advance_tick();
+    }
+
+    mod model_tests {
+        use super::*;
+
+        #[test]
+        fn gain_decreases_with_range() {
+            let model = ClassificationModel::default();
+            assert!(model.gain(100.0, false) > model.gain(10_000.0, false));
+        }
+
+        #[test]
+        fn emissions_increase_gain() {
+            let model = ClassificationModel::default();
+            assert!(model.gain(1000.0, true) > model.gain(1000.0, false));
+        }
+    }
+
+    mod resolver_tests {
+        use super::*;
+
+        #[test]
+        fn confidence_grows_with_detections() {
+            let (mut arena, observer, target) = setup(1000.0);
+            let resolver = ClassificationResolver::new(1);
+
+            step(&resolver, &mut arena, observer, target);
+            let first = track_of(&arena, observer).classification_confidence;
+            step(&resolver, &mut arena, observer, target);
+            let second = track_of(&arena, observer).classification_confidence;
+
+            assert!(first > 0.0);
+            assert!(second > first);
+        }
+
+        #[test]
+        fn unknown_below_threshold() {
+            let (mut arena, observer, target) = setup(1000.0);
+            let resolver = ClassificationResolver::new(1);
+
+            step(&resolver, &mut arena, observer, target);
+
+            assert!(!track_of(&arena, observer).is_identified());
+        }
+
+        #[test]
+        fn classified_as_truth_without_misclassification() {
+            let (mut arena, observer, target) = setup(0.0);
+            let resolver = ClassificationResolver::with_model(
+                ClassificationModel {
+                    misclassification_rate: 0.0,
+                    ..ClassificationModel::default()
+                },
+                1,
+            );
+
+            for _ in 0..10 {
+                step(&resolver, &mut arena, observer, target);
+            }
+
+            let track = track_of(&arena, observer);
+            assert!(track.classification_confidence >= 0.7);
+            assert_eq!(track.classified_as, Some(EntityTag::Platform));
+        }
+
+        #[test]
+        fn certain_misclassification_picks_wrong_tag() {
+            let (mut arena, observer, target) = setup(0.0);
+            let resolver = ClassificationResolver::with_model(
+                ClassificationModel {
+                    misclassification_rate: 1.0,
+                    gain_per_detection: 1.0,
+                    ..ClassificationModel::default()
+                },
+                7,
+            );
+
+            step(&resolver, &mut arena, observer, target);
+
+            let tag = track_of(&arena, observer).classified_as.unwrap();
+            assert_ne!(tag, EntityTag::Platform);
+        }
+
+        #[test]
+        fn classification_is_deterministic() {
+            let model = ClassificationModel {
+                misclassification_rate: 0.5,
+                gain_per_detection: 1.0,
+                ..ClassificationModel::default()
+            };
+            let run = |seed| {
+                let (mut arena, observer, target) = setup(0.0);
+                step(
+                    &ClassificationResolver::with_model(model, seed),
+                    &mut arena,
+                    observer,
+                    target,
+                );
+                track_of(&arena, observer).classified_as
+            };
+
+            for seed in 0..16 {
+                assert_eq!(run(seed), run(seed));
+            }
+        }
+
+        #[test]
+        fn visual_sighting_identifies_target() {
+            let (mut arena, observer, target) = setup(4000.0);
+            let resolver = ClassificationResolver::with_model(
+                ClassificationModel {
+                    misclassification_rate: 1.0,
+                    ..ClassificationModel::default()
+                },
+                1,
+            );
+
+            let envelope = sighting(observer, target);
+            let current = arena.clone();
+            resolver.resolve(&[&envelope], &current, &mut arena);
+
+            let track = track_of(&arena, observer);
+            assert!(track.classification_confidence >= 0.95);
+            assert_eq!(track.classified_as, Some(EntityTag::Platform));
+        }
+
+        #[test]
+        fn detections_without_track_are_ignored() {
+            let mut arena = Arena::new();
+            let observer = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let target = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let resolver = ClassificationResolver::new(1);
+
+            step(&resolver, &mut arena, observer, target);
+
+            assert!(arena
+                .get(observer)
+                .unwrap()
+                .as_ship()
+                .unwrap()
+                .sensor
+                .track_table
+                .is_empty());
+        }
+    }
+}
@@ -1,21 +1,104 @@
-//! Combat resolver for damage, healing, and status effects.
+//! Combat resolver for damage, healing, status effects and gunfire.
 //!
 //! The `CombatResolver` handles:
 //! - `ApplyDamage` modifiers: Reduce entity HP
+//! - `ApplyAreaDamage` modifiers: Reduce the HP of every ship and squadron
+//!   in the blast that no solid obstacle shelters
 //! - `ApplyHealing` modifiers: Increase entity HP (capped at max)
 //! - `SetStatusFlag` modifiers: Enable or disable status flags
+//! - `SetAttribute` modifiers: Set or remove entity attributes
+//! - `FireWeapon` commands for guns: Resolve each round as hitscan
+//! - `InterceptSquadron` commands: Set squadrons' air-to-air targets
 //!
 //! # Destruction Handling
 //!
 //! When an entity's HP reaches 0 or below, the `DESTROYED` flag is set.
 //! The entity is not immediately removed - that's handled by a cleanup phase.
-
-use crate::arena::Arena;
-use crate::entity::components::StatusFlags;
-use crate::entity::EntityId;
-use crate::output::{Modifier, OutputEnvelope, OutputKind};
-
-use super::Resolver;
+//!
+//! # Mitigation
+//!
+//! Ships and squadrons with a [`MitigationState`] (a shield) take damage on
+//! it before HP: each hit is reduced by the resistance to its
+//! [`DamageType`] and absorbed by the pool, and only the rest reaches the
+//! hull. Gunfire is kinetic, blasts explosive, and `ApplyDamage` carries
+//! its own type. Pools regenerate at the start of every tick.
+//!
+//! # Gunfire
+//!
+//! Weapons with [`GunBallistics`] fire no projectile entity. When such a
+//! weapon fires (see [`WeaponResolver`](super::WeaponResolver) for when a
+//! shot is allowed), every round of the burst is resolved at once: it lands
+//! at the aim point displaced by an error drawn from a deterministic RNG
+//! seeded from (seed, tick, shooter, slot), and hits if that is within
+//! reach of the target's true position after the time of flight. A shooter
+//! whose track of the target has a course and speed estimate (see
+//! [`Track::estimate`](crate::entity::Track::estimate)) aims where the
+//! estimate puts the target; otherwise it leads the target perfectly.
+//! Hits are reported as `DamageDealt` events and misses as `ShellSplash`
+//! events (as is the damage each blast deals) in the event log given to
+//! [`with_event_log`](CombatResolver::with_event_log); the
+//! [`WreckageSystem`](crate::wreckage::WreckageSystem) stamps the splashes
+//! into the universe. Draws can be audited with
+//! [`with_rng_audit`](CombatResolver::with_rng_audit).
+//!
+//! # Air-to-air
+//!
+//! Squadrons with an [`AirWing`] fight each other with the aggregate model
+//! described there: every tick, each squadron within engagement range of
+//! the enemy squadron it intercepts exchanges fire with it, both sides
+//! firing from their state at the start of the tick. Hits are reported as
+//! kinetic `DamageDealt` events.
+//!
+//! # Subsystem damage
+//!
+//! Weapon mounts and sensor suites given hit points of their own (see
+//! [`WeaponState::with_hp`] and [`SensorState::with_hp`]) can be knocked
+//! out before the hull is lost. Each hit that puts damage into a live
+//! hull strikes one of its mounts that still has hit points with the
+//! chance given by [`CombatState::subsystem_exposure`], chosen uniformly
+//! from a deterministic RNG seeded from (seed, tick, target, HP left).
+//! The mount takes the same damage as the hull. A damaged sensor suite
+//! loses range in proportion (see [`SensorState::integrity`]); a mount
+//! reduced to 0 HP is knocked out, leaving the weapon inoperable or
+//! setting `SENSORS_DISABLED`, and a `SubsystemDestroyed` event names it.
+//!
+//! # Kill attribution
+//!
+//! Hull damage from a hostile entity (one not on the target's team) is
+//! credited to it on the target's [`CombatState`] (see
+//! [`CombatState::damage_by_source`]). Every entity destroyed during the
+//! tick is reported as an `EntityDestroyed` event naming the entity that
+//! last damaged it and, as assists, the others that did. The damage and
+//! kills are also tallied per controller in the ledger given to
+//! [`with_kill_ledger`](CombatResolver::with_kill_ledger) (see
+//! [`kill_ledger`](crate::kill_ledger)).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::f32::consts::TAU;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use glam::Vec2;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::arena::{Arena, Fnv1a};
+use crate::entity::components::{
+    AirWing, CombatState, DamageType, GunBallistics, SensorState, SquadronComponents,
+    StatusFlags, Subsystem,
+};
+#[cfg(doc)]
+use crate::entity::components::WeaponState;
+use crate::entity::{Entity, EntityId, EntityInner};
+use crate::kill_ledger::KillLedger;
+use crate::output::{
+    Command, Event, Modifier, Output, OutputEnvelope, OutputKind, PluginId, PluginInstanceId,
+};
+use crate::rng_audit::RngAuditLog;
+
+use super::physics::FIXED_DT;
+use super::weapon::weapon_actions;
+use super::{EventResolver, Resolver};
 
 /// Resolver for combat-related modifiers.
 ///
@@ -27,6 +110,12 @@ use super::Resolver;
 /// 2. Apply all healing modifiers (summed for each target, capped at max HP)
 /// 3. Apply all status flag changes
 /// 4. Set `DESTROYED` flag for any entities with HP <= 0
+/// 5. Resolve the rounds of guns that fired
+/// 6. Resolve air-to-air exchanges between squadrons
+/// 7. Report the entities destroyed during the tick
+///
+/// Every hit may also strike a weapon or sensor mount (see the module
+/// documentation).
 ///
 /// Note: The current implementation processes in output order, not the
 /// batched order described above. This matches the "last-write-wins" for
@@ -43,33 +132,227 @@ use super::Resolver;
 /// assert!(resolver.handles().contains(&OutputKind::Modifier));
 /// ```
 #[derive(Debug, Clone, Default)]
-pub struct CombatResolver;
+pub struct CombatResolver {
+    /// Seed of the gunnery RNG
+    seed: u64,
+    /// Log receiving hit and splash events
+    events: Option<Arc<EventResolver>>,
+    /// Log receiving gunnery draws
+    rng_audit: Option<Arc<RngAuditLog>>,
+    /// Ledger receiving damage, kills and assists per controller
+    kill_ledger: Option<Arc<KillLedger>>,
+}
 
 impl CombatResolver {
-    /// Creates a new combat resolver.
+    /// Creates a new combat resolver with gunnery seed 0 and no event log.
     #[must_use]
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
 
-    /// Applies damage to an entity, setting DESTROYED flag if HP <= 0.
-    fn apply_damage(next: &mut Arena, target: EntityId, amount: f32) {
-        if let Some(entity) = next.get_mut(target) {
-            // Try each entity type that has combat
-            if let Some(ship) = entity.as_ship_mut() {
-                ship.combat.hp -= amount;
-                if ship.combat.hp <= 0.0 {
-                    ship.combat.hp = 0.0;
-                    ship.combat.status_flags.insert(StatusFlags::DESTROYED);
-                }
-            } else if let Some(squadron) = entity.as_squadron_mut() {
-                squadron.combat.hp -= amount;
-                if squadron.combat.hp <= 0.0 {
-                    squadron.combat.hp = 0.0;
-                    squadron.combat.status_flags.insert(StatusFlags::DESTROYED);
+    /// Seeds the gunnery RNG.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Records gun hits and splashes into `events`.
+    #[must_use]
+    pub fn with_event_log(mut self, events: Arc<EventResolver>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Records gunnery draws into `audit`.
+    #[must_use]
+    pub fn with_rng_audit(mut self, audit: Arc<RngAuditLog>) -> Self {
+        self.rng_audit = Some(audit);
+        self
+    }
+
+    /// Tallies damage, kills and assists per controller into `ledger`.
+    #[must_use]
+    pub fn with_kill_ledger(mut self, ledger: Arc<KillLedger>) -> Self {
+        self.kill_ledger = Some(ledger);
+        self
+    }
+
+    /// Applies damage to an entity, through its mitigation, setting the
+    /// DESTROYED flag if HP <= 0. Negative damage (from a negative amount or
+    /// a resistance above 1) is dropped rather than healing past max HP.
+    ///
+    /// Returns the damage that reached the hull.
+    fn apply_damage(
+        next: &mut Arena,
+        target: EntityId,
+        amount: f32,
+        damage_type: DamageType,
+    ) -> f32 {
+        // Platforms and projectiles don't have combat state
+        let Some(combat) = next.get_mut(target).and_then(combat_mut) else {
+            return 0.0;
+        };
+        let amount = match &mut combat.mitigation {
+            Some(mitigation) => mitigation.absorb(amount, damage_type),
+            None => amount,
+        }
+        .max(0.0);
+        combat.hp -= amount;
+        if combat.hp <= 0.0 {
+            combat.hp = 0.0;
+            combat.status_flags.insert(StatusFlags::DESTROYED);
+        }
+        amount
+    }
+
+    /// Applies a hit from `source` as [`Self::apply_damage`] does, credits
+    /// the damage that reached the hull to `source` (see
+    /// [`Self::attribute`]), then lets it strike one of the target's mounts
+    /// (see [`Self::strike_subsystem`]).
+    ///
+    /// Returns the damage that reached the hull.
+    fn hit(
+        &self,
+        next: &mut Arena,
+        hit: (EntityId, EntityId),
+        amount: f32,
+        damage_type: DamageType,
+    ) -> f32 {
+        let (source, target) = hit;
+        let amount = Self::apply_damage(next, target, amount, damage_type);
+        self.attribute(next, source, target, amount);
+        self.strike_subsystem(next, source, target, amount);
+        amount
+    }
+
+    /// Credits `amount` of hull damage on `target` to `source`, on the
+    /// target and in the kill ledger, unless it is self-inflicted or
+    /// friendly fire.
+    fn attribute(&self, next: &mut Arena, source: EntityId, target: EntityId, amount: f32) {
+        if amount <= 0.0 || source == target {
+            return;
+        }
+        let (source_team, controller) = next
+            .get(source)
+            .map_or((None, None), |entity| (entity.team(), entity.controller()));
+        let Some(entity) = next.get_mut(target) else {
+            return;
+        };
+        if source_team.is_some() && source_team == entity.team() {
+            return;
+        }
+        let Some(combat) = combat_mut(entity) else {
+            return;
+        };
+        combat.record_damage(source, amount);
+        if let (Some(ledger), Some(controller)) = (&self.kill_ledger, controller) {
+            ledger.record_damage(controller, amount);
+        }
+    }
+
+    /// Records an `EntityDestroyed` event for every ship and squadron
+    /// destroyed during the tick, and tallies the kill and assists.
+    fn report_destroyed(&self, current: &Arena, next: &mut Arena) {
+        let destroyed: Vec<(EntityId, Option<EntityId>, Vec<EntityId>)> = next
+            .entities_sorted()
+            .filter(|entity| {
+                entity.is_destroyed() && !current.get(entity.id()).is_some_and(Entity::is_destroyed)
+            })
+            .filter_map(|entity| {
+                let combat = match entity.inner() {
+                    EntityInner::Ship(c) => &c.combat,
+                    EntityInner::Squadron(c) => &c.combat,
+                    EntityInner::Platform(_) | EntityInner::Projectile(_) => return None,
+                };
+                Some((entity.id(), combat.last_damager, combat.assists()))
+            })
+            .collect();
+        for (entity, destroyer, assists) in destroyed {
+            if let Some(ledger) = &self.kill_ledger {
+                let controller = |id: EntityId| next.get(id).and_then(Entity::controller);
+                ledger.record_kill(
+                    destroyer.and_then(controller),
+                    assists.iter().filter_map(|&id| controller(id)),
+                );
+            }
+            self.record(next, Event::EntityDestroyed {
+                entity,
+                destroyer,
+                assists,
+            });
+        }
+    }
+
+    /// Rolls whether `amount` of hull damage on `target` also struck one of
+    /// its mounts with hit points left, and damages that mount by the same
+    /// amount. A mount reduced to 0 HP is knocked out and recorded as a
+    /// `SubsystemDestroyed` event.
+    fn strike_subsystem(&self, next: &mut Arena, source: EntityId, target: EntityId, amount: f32) {
+        let tick = next.current_tick();
+        let Some((combat, sensor)) = next.get_mut(target).and_then(mounts_mut) else {
+            return;
+        };
+        if amount <= 0.0 || combat.is_destroyed() {
+            return;
+        }
+        let mut mounts: Vec<Subsystem> = combat
+            .weapons
+            .iter()
+            .filter(|w| w.max_hp > 0.0 && w.hp > 0.0)
+            .map(|w| Subsystem::Weapon(w.slot))
+            .collect();
+        if sensor.as_ref().is_some_and(|s| s.max_hp > 0.0 && s.hp > 0.0) {
+            mounts.push(Subsystem::Sensors);
+        }
+        if mounts.is_empty() {
+            return;
+        }
+
+        let stream = self.subsystem_stream(tick, target, combat.hp);
+        let mut rng = ChaCha8Rng::seed_from_u64(stream);
+        let roll = rng.gen::<f32>();
+        if let Some(audit) = &self.rng_audit {
+            audit.record(tick, stream, "combat.subsystem", f64::from(roll));
+        }
+        if roll >= combat.subsystem_exposure {
+            return;
+        }
+        let subsystem = mounts[rng.gen_range(0..mounts.len())];
+        let knocked_out = match (subsystem, sensor) {
+            (Subsystem::Weapon(slot), _) => combat.get_weapon_mut(slot).is_some_and(|weapon| {
+                weapon.hp = (weapon.hp - amount).max(0.0);
+                weapon.operational &= weapon.hp > 0.0;
+                weapon.hp <= 0.0
+            }),
+            (Subsystem::Sensors, Some(sensor)) => {
+                sensor.hp = (sensor.hp - amount).max(0.0);
+                let lost = sensor.hp <= 0.0;
+                if lost {
+                    combat.status_flags.insert(StatusFlags::SENSORS_DISABLED);
                 }
+                lost
+            }
+            (Subsystem::Sensors, None) => false,
+        };
+        if knocked_out {
+            self.record(next, Event::SubsystemDestroyed {
+                entity: target,
+                source,
+                subsystem,
+            });
+        }
+    }
+
+    /// Regenerates the mitigation pools of live entities by one tick.
+    fn regenerate(next: &mut Arena) {
+        for entity in next.entities_sorted_mut() {
+            let Some(combat) = combat_mut(entity).filter(|c| !c.is_destroyed()) else {
+                continue;
+            };
+            if let Some(mitigation) = &mut combat.mitigation {
+                mitigation.regenerate(FIXED_DT);
             }
-            // Platforms and projectiles don't have combat state
         }
     }
 
@@ -84,6 +367,157 @@ impl CombatResolver {
         }
     }
 
+    /// Resolves the `rounds` fired by `gun` on `source` at `target`.
+    fn fire_gun(
+        &self,
+        current: &Arena,
+        next: &mut Arena,
+        salvo: (EntityId, usize, EntityId),
+        gun: &GunBallistics,
+        rounds: u32,
+    ) {
+        let (source, slot, target) = salvo;
+        let (Some((origin, _, _)), Some((position, velocity, radius))) =
+            (kinematics(current, source), kinematics(current, target))
+        else {
+            return;
+        };
+        let tick = current.current_tick();
+        let range = origin.distance(position);
+        if range > gun.max_range {
+            // Short: every round falls at maximum range on the line of fire
+            let splash = origin + (position - origin).normalize_or_zero() * gun.max_range;
+            for _ in 0..rounds {
+                self.record(next, Event::ShellSplash {
+                    source,
+                    position: splash,
+                });
+            }
+            return;
+        }
+
+        let time_of_flight = gun.time_of_flight(range);
+        let arrival = position + velocity * time_of_flight;
+        let aim = fire_control_solution(current, source, target, time_of_flight).unwrap_or(arrival);
+        let sigma = gun.miss_sigma(range, velocity.length());
+        let stream = self.stream_key(tick, source, slot);
+        let mut rng = ChaCha8Rng::seed_from_u64(stream);
+        for _ in 0..rounds {
+            let miss = normal_pair(&mut rng) * sigma;
+            if let Some(audit) = &self.rng_audit {
+                audit.record(tick, stream, "combat.gun", f64::from(miss.length()));
+            }
+            if (aim + miss).distance(arrival) <= radius + gun.hit_radius {
+                let amount = self.hit(next, (source, target), gun.damage, DamageType::Kinetic);
+                self.record(next, Event::DamageDealt {
+                    source,
+                    target,
+                    amount,
+                });
+            } else {
+                self.record(next, Event::ShellSplash {
+                    source,
+                    position: aim + miss,
+                });
+            }
+        }
+    }
+
+    /// Applies this tick's `InterceptSquadron` orders and resolves the
+    /// air-to-air exchanges they lead to.
+    fn resolve_air_combat(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        let intercepts = intercept_orders(outputs, current);
+
+        // Pairs in range, each listed once, and how many each side is in
+        let mut pairs = BTreeSet::new();
+        for (&source, &target) in &intercepts {
+            let (Some(a), Some(b)) =
+                (live_squadron(current, source), live_squadron(current, target))
+            else {
+                continue;
+            };
+            let range = a.air_wing.map_or(0.0, |w| w.engagement_range);
+            if a.transform.position.distance(b.transform.position) <= range {
+                pairs.insert((source.min(target), source.max(target)));
+            }
+        }
+        let mut engagements: BTreeMap<EntityId, u8> = BTreeMap::new();
+        for &(a, b) in &pairs {
+            *engagements.entry(a).or_default() += 1;
+            *engagements.entry(b).or_default() += 1;
+        }
+
+        let mut volleys = Vec::new();
+        for &(a, b) in &pairs {
+            let (Some(first), Some(second)) = (live_squadron(current, a), live_squadron(current, b))
+            else {
+                continue;
+            };
+            volleys.push((a, b, air_to_air_damage(first, second, engagements[&a])));
+            volleys.push((b, a, air_to_air_damage(second, first, engagements[&b])));
+        }
+        for (source, target, damage) in volleys {
+            if damage > 0.0 {
+                let amount = self.hit(next, (source, target), damage, DamageType::Kinetic);
+                self.record(next, Event::DamageDealt {
+                    source,
+                    target,
+                    amount,
+                });
+            }
+        }
+
+        for entity in next.entities_sorted_mut() {
+            let id = entity.id();
+            let EntityInner::Squadron(squadron) = entity.inner_mut() else {
+                continue;
+            };
+            let Some(wing) = &mut squadron.air_wing else {
+                continue;
+            };
+            if engagements.contains_key(&id) {
+                wing.ordnance = (wing.ordnance - FIXED_DT).max(0.0);
+            }
+            wing.intercept = intercepts.get(&id).copied().filter(|_| wing.is_armed());
+        }
+    }
+
+    /// Key of the deterministic RNG stream for one (tick, shooter, slot)
+    /// salvo.
+    fn stream_key(&self, tick: u64, source: EntityId, slot: usize) -> u64 {
+        let mut hasher = Fnv1a::default();
+        self.seed.hash(&mut hasher);
+        tick.hash(&mut hasher);
+        source.hash(&mut hasher);
+        slot.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Key of the deterministic RNG stream for a hit that left `target` at
+    /// `hp`.
+    fn subsystem_stream(&self, tick: u64, target: EntityId, hp: f32) -> u64 {
+        let mut hasher = Fnv1a::default();
+        self.seed.hash(&mut hasher);
+        tick.hash(&mut hasher);
+        target.hash(&mut hasher);
+        hp.to_bits().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Records an event, if an event log is attached.
+    fn record(&self, next: &mut Arena, event: Event) {
+        if let Some(events) = &self.events {
+            let entity = event.primary_entity();
+            events.record(OutputEnvelope::new(
+                Output::Event(event),
+                PluginInstanceId::new(entity, PluginId::from_static("combat")),
+                next.new_trace_id(),
+                next.current_tick(),
+                0,
+            ));
+        }
+    }
+
     /// Sets or clears a status flag on an entity.
     fn set_status_flag(next: &mut Arena, target: EntityId, flag: StatusFlags, value: bool) {
         if let Some(entity) = next.get_mut(target) {
@@ -104,17 +538,197 @@ impl CombatResolver {
     }
 }
 
+/// Returns the damage a blast deals to each live ship and squadron it
+/// reaches, in ID order.
+///
+/// Damage is attenuated with distance as described on
+/// `Modifier::ApplyAreaDamage`; entities the arena's environment shelters
+/// from `center` (see [`Environment::is_occluded`]) take none.
+///
+/// [`Environment::is_occluded`]: crate::environment::Environment::is_occluded
+pub(crate) fn area_damage(
+    arena: &Arena,
+    center: Vec2,
+    radius: f32,
+    amount: f32,
+    falloff: f32,
+) -> Vec<(EntityId, f32)> {
+    if radius.is_nan() || radius <= 0.0 || amount.is_nan() || amount <= 0.0 {
+        return Vec::new();
+    }
+    arena
+        .spatial()
+        .query_radius(center, radius)
+        .into_iter()
+        .filter_map(|id| {
+            let (position, _, _) = kinematics(arena, id)?;
+            if arena.environment().is_occluded(center, position) {
+                return None;
+            }
+            let reach = (1.0 - center.distance(position) / radius).clamp(0.0, 1.0);
+            let damage = amount * reach.powf(falloff.max(0.0));
+            (damage > 0.0).then_some((id, damage))
+        })
+        .collect()
+}
+
+/// Returns the standing intercept orders at the start of the tick, updated
+/// with the valid `InterceptSquadron` commands in output order.
+///
+/// Orders against squadrons that are gone or destroyed, and orders of
+/// squadrons out of ordnance, are dropped.
+fn intercept_orders(outputs: &[&OutputEnvelope], current: &Arena) -> BTreeMap<EntityId, EntityId> {
+    let mut intercepts: BTreeMap<EntityId, EntityId> = current
+        .entities_sorted()
+        .filter_map(|e| match e.inner() {
+            EntityInner::Squadron(c) => Some((e.id(), c.air_wing?.intercept?)),
+            _ => None,
+        })
+        .collect();
+    for envelope in outputs {
+        if let Some(Command::InterceptSquadron { source, target }) = envelope.output().as_command()
+        {
+            let hostile = match (current.get(*source), current.get(*target)) {
+                (Some(a), Some(b)) => a.team().is_none() || a.team() != b.team(),
+                _ => false,
+            };
+            let armed = live_squadron(current, *source).is_some_and(|c| c.air_wing.is_some());
+            if hostile && armed && source != target && live_squadron(current, *target).is_some() {
+                intercepts.insert(*source, *target);
+            }
+        }
+    }
+    intercepts.retain(|source, target| {
+        let armed = live_squadron(current, *source)
+            .and_then(|c| c.air_wing)
+            .is_some_and(|w| w.is_armed());
+        armed && live_squadron(current, *target).is_some()
+    });
+    intercepts
+}
+
+/// Returns the damage `shooter` deals `target` in one tick of air-to-air
+/// combat, with its fire split over `engagements` enemies.
+fn air_to_air_damage(
+    shooter: &SquadronComponents,
+    target: &SquadronComponents,
+    engagements: u8,
+) -> f32 {
+    let Some(wing) = shooter.air_wing.filter(AirWing::is_armed) else {
+        return 0.0;
+    };
+    let target_craft = target.air_wing.map(|w| w.craft).unwrap_or_default();
+    shooter.combat.hp
+        * wing.lethality
+        * wing.craft.exchange_factor(target_craft)
+        * wing.ordnance_fraction()
+        * FIXED_DT
+        / f32::from(engagements.max(1))
+}
+
+/// Returns a squadron that is not destroyed.
+fn live_squadron(arena: &Arena, id: EntityId) -> Option<&SquadronComponents> {
+    match arena.get(id)?.inner() {
+        EntityInner::Squadron(c) if !c.combat.is_destroyed() => Some(c),
+        _ => None,
+    }
+}
+
+/// Returns the combat state of a ship or squadron.
+fn combat_mut(entity: &mut Entity) -> Option<&mut CombatState> {
+    match entity.inner_mut() {
+        EntityInner::Ship(c) => Some(&mut c.combat),
+        EntityInner::Squadron(c) => Some(&mut c.combat),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+    }
+}
+
+/// Returns the point `source` leads `target` to for rounds in flight for
+/// `time_of_flight` seconds, from the course and speed estimated on its
+/// track of the target (see [`Track::estimate`]).
+///
+/// Returns `None` if the shooter holds no track of the target with enough
+/// history for an estimate.
+///
+/// [`Track::estimate`]: crate::entity::Track::estimate
+fn fire_control_solution(
+    arena: &Arena,
+    source: EntityId,
+    target: EntityId,
+    time_of_flight: f32,
+) -> Option<Vec2> {
+    let track = match arena.get(source)?.inner() {
+        EntityInner::Ship(c) => c.sensor.find_track(target)?,
+        EntityInner::Platform(c) => c.sensor.find_track(target)?,
+        EntityInner::Projectile(_) | EntityInner::Squadron(_) => return None,
+    };
+    let estimate = track.estimate()?;
+    Some(track.position + estimate.velocity * (track.age + time_of_flight))
+}
+
+/// Returns the combat state of a ship or squadron, with a ship's sensors.
+fn mounts_mut(entity: &mut Entity) -> Option<(&mut CombatState, Option<&mut SensorState>)> {
+    match entity.inner_mut() {
+        EntityInner::Ship(c) => Some((&mut c.combat, Some(&mut c.sensor))),
+        EntityInner::Squadron(c) => Some((&mut c.combat, None)),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+    }
+}
+
+/// Returns the position, velocity and hull radius of a live ship or
+/// squadron.
+fn kinematics(arena: &Arena, id: EntityId) -> Option<(Vec2, Vec2, f32)> {
+    let (transform, physics, combat) = match arena.get(id)?.inner() {
+        EntityInner::Ship(c) => (&c.transform, &c.physics, &c.combat),
+        EntityInner::Squadron(c) => (&c.transform, &c.physics, &c.combat),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => return None,
+    };
+    (!combat.is_destroyed()).then_some((transform.position, physics.velocity, transform.radius))
+}
+
+/// Draws a pair of independent standard normal values (Box-Muller).
+fn normal_pair(rng: &mut ChaCha8Rng) -> Vec2 {
+    // 1 - [0, 1) keeps the logarithm finite
+    let radius = (-2.0 * (1.0 - rng.gen::<f32>()).ln()).sqrt();
+    let angle = TAU * rng.gen::<f32>();
+    Vec2::new(radius * angle.cos(), radius * angle.sin())
+}
+
 impl Resolver for CombatResolver {
     fn handles(&self) -> &[OutputKind] {
-        &[OutputKind::Modifier]
+        &[OutputKind::Modifier, OutputKind::Command]
     }
 
-    fn resolve(&self, outputs: &[&OutputEnvelope], _current: &Arena, next: &mut Arena) {
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        Self::regenerate(next);
         for envelope in outputs {
             if let Some(modifier) = envelope.output().as_modifier() {
                 match modifier {
-                    Modifier::ApplyDamage { target, amount } => {
-                        Self::apply_damage(next, *target, *amount);
+                    Modifier::ApplyDamage {
+                        target,
+                        amount,
+                        damage_type,
+                    } => {
+                        let source = envelope.source().entity_id();
+                        self.hit(next, (source, *target), *amount, *damage_type);
+                    }
+                    Modifier::ApplyAreaDamage {
+                        center,
+                        radius,
+                        amount,
+                        falloff,
+                    } => {
+                        let source = envelope.source().entity_id();
+                        let blast = area_damage(current, *center, *radius, *amount, *falloff);
+                        for (target, damage) in blast {
+                            let amount =
+                                self.hit(next, (source, target), damage, DamageType::Explosive);
+                            self.record(next, Event::DamageDealt {
+                                source,
+                                target,
+                                amount,
+                            });
+                        }
                     }
                     Modifier::ApplyHealing { target, amount } => {
                         Self::apply_healing(next, *target, *amount);
@@ -122,14 +736,25 @@ impl Resolver for CombatResolver {
                     Modifier::SetStatusFlag { target, flag, value } => {
                         Self::set_status_flag(next, *target, *flag, *value);
                     }
+                    Modifier::SetAttribute { target, key, value } => {
+                        next.set_attribute(*target, key.as_str(), value.clone());
+                    }
                     // ModifyStat is more complex and not MVP
                     Modifier::ModifyStat { .. } => {}
                 }
             }
         }
-        // Commands like FireWeapon are not yet implemented.
-        // When command support is added, they should be processed separately
-        // and registered in the `handles()` method.
+
+        for action in weapon_actions(outputs, current) {
+            let (Some(target), Some(gun)) = (action.fire_at, &action.weapon.gun) else {
+                continue;
+            };
+            let salvo = (action.source, action.weapon.slot, target);
+            self.fire_gun(current, next, salvo, gun, action.weapon.rounds_per_shot());
+        }
+
+        self.resolve_air_combat(outputs, current, next);
+        self.report_destroyed(current, next);
     }
 }
 
@@ -154,10 +779,10 @@ mod tests {
         use super::*;
 
         #[test]
-        fn handles_modifier_kind() {
+        fn handles_modifier_and_command_kinds() {
             let resolver = CombatResolver::new();
             assert!(resolver.handles().contains(&OutputKind::Modifier));
-            assert!(!resolver.handles().contains(&OutputKind::Command));
+            assert!(resolver.handles().contains(&OutputKind::Command));
             assert!(!resolver.handles().contains(&OutputKind::Event));
         }
     }
@@ -177,6 +802,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: ship_id,
                     amount: 30.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 ship_id,
             );
@@ -201,6 +827,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: ship_id,
                     amount: 100.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 ship_id,
             );
@@ -226,6 +853,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: ship_id,
                     amount: 150.0, // More than max HP
+                    damage_type: DamageType::Kinetic,
                 }),
                 ship_id,
             );
@@ -248,6 +876,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: fake_id,
                     amount: 50.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 fake_id,
             );
@@ -270,6 +899,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: ship_id,
                     amount: 20.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 ship_id,
             );
@@ -277,6 +907,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: ship_id,
                     amount: 30.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 ship_id,
             );
@@ -302,6 +933,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: squadron_id,
                     amount: 30.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 squadron_id,
             );
@@ -534,6 +1166,78 @@ mod tests {
         }
     }
 
+    mod set_attribute_tests {
+        use super::*;
+        use crate::entity::AttributeValue;
+
+        fn set_attribute(
+            target: EntityId,
+            key: &str,
+            value: Option<AttributeValue>,
+        ) -> OutputEnvelope {
+            make_envelope(
+                Output::Modifier(Modifier::SetAttribute {
+                    target,
+                    key: key.to_string(),
+                    value,
+                }),
+                target,
+            )
+        }
+
+        #[test]
+        fn set_attribute_inserts_value() {
+            let mut arena = Arena::new();
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let envelope = set_attribute(ship_id, "patrol.leg", Some(AttributeValue::Int(3)));
+
+            let resolver = CombatResolver::new();
+            let current = arena.clone();
+            resolver.resolve(&[&envelope], &current, &mut arena);
+
+            let ship = arena.get(ship_id).unwrap();
+            assert_eq!(ship.attribute("patrol.leg"), Some(&AttributeValue::Int(3)));
+        }
+
+        #[test]
+        fn set_attribute_none_removes_value() {
+            let mut arena = Arena::new();
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            arena.set_attribute(ship_id, "patrol.leg", Some(AttributeValue::Int(3)));
+            let envelope = set_attribute(ship_id, "patrol.leg", None);
+
+            let resolver = CombatResolver::new();
+            let current = arena.clone();
+            resolver.resolve(&[&envelope], &current, &mut arena);
+
+            assert!(arena.get(ship_id).unwrap().attributes().is_empty());
+        }
+
+        #[test]
+        fn last_write_wins() {
+            let mut arena = Arena::new();
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let first = set_attribute(ship_id, "mode", Some("search".into()));
+            let second = set_attribute(ship_id, "mode", Some("attack".into()));
+
+            let resolver = CombatResolver::new();
+            let current = arena.clone();
+            resolver.resolve(&[&first, &second], &current, &mut arena);
+
+            let mode = arena.get(ship_id).unwrap().attribute("mode").cloned();
+            assert_eq!(mode, Some(AttributeValue::Text("attack".to_string())));
+        }
+    }
+
     mod output_filtering_tests {
         use super::*;
 
@@ -574,6 +1278,7 @@ mod tests {
                 Output::Event(Event::EntityDestroyed {
                     entity: ship_id,
                     destroyer: None,
+                    assists: vec![],
                 }),
                 ship_id,
             );
@@ -589,4 +1294,568 @@ mod tests {
             assert!(!ship.combat.status_flags.contains(StatusFlags::DESTROYED));
         }
     }
+
+    mod mitigation_tests {
+        use super::*;
+        use crate::entity::MitigationState;
+
+        fn shielded(arena: &mut Arena, pool: f32) -> EntityId {
+            let mut ship = ShipComponents::default();
+            ship.combat = ship.combat.with_mitigation(MitigationState::new(pool, 60.0));
+            arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+        }
+
+        fn hit(arena: &mut Arena, target: EntityId, amount: f32) {
+            let envelope = make_envelope(
+                Output::Modifier(Modifier::ApplyDamage {
+                    target,
+                    amount,
+                    damage_type: DamageType::Energy,
+                }),
+                target,
+            );
+            let current = arena.clone();
+            CombatResolver::new().resolve(&[&envelope], &current, arena);
+        }
+
+        fn state(arena: &Arena, id: EntityId) -> (f32, f32) {
+            let combat = &arena.get(id).unwrap().as_ship().unwrap().combat;
+            (combat.hp, combat.mitigation.unwrap().pool)
+        }
+
+        #[test]
+        fn pool_absorbs_before_hp() {
+            let mut arena = Arena::new();
+            let ship = shielded(&mut arena, 50.0);
+
+            hit(&mut arena, ship, 30.0);
+            assert_eq!(state(&arena, ship), (100.0, 20.0));
+            // Regenerates one tick (1 per tick at 60/s), then absorbs 21 of 41
+            hit(&mut arena, ship, 41.0);
+            let (hp, pool) = state(&arena, ship);
+            assert!((hp - 80.0).abs() < 1e-3);
+            assert!(pool.abs() < 1e-3);
+        }
+
+        #[test]
+        fn unshielded_ships_take_full_damage() {
+            let mut arena = Arena::new();
+            let ship = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+            hit(&mut arena, ship, 30.0);
+            assert_eq!(arena.get(ship).unwrap().as_ship().unwrap().combat.hp, 70.0);
+        }
+    }
+
+    mod area_damage_tests {
+        use super::*;
+        use crate::environment::OccupancyField;
+
+        fn blast(arena: &mut Arena, falloff: f32) {
+            let envelope = make_envelope(
+                Output::Modifier(Modifier::ApplyAreaDamage {
+                    center: Vec2::ZERO,
+                    radius: 100.0,
+                    amount: 40.0,
+                    falloff,
+                }),
+                EntityId::new(99),
+            );
+            let current = arena.clone();
+            CombatResolver::new().resolve(&[&envelope], &current, arena);
+        }
+
+        fn ship_at(arena: &mut Arena, x: f32) -> EntityId {
+            arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(x, 0.0), 0.0)),
+            )
+        }
+
+        fn hp(arena: &Arena, id: EntityId) -> f32 {
+            arena.get(id).unwrap().as_ship().unwrap().combat.hp
+        }
+
+        #[test]
+        fn damage_falls_off_with_distance() {
+            let mut arena = Arena::new();
+            let center = ship_at(&mut arena, 0.0);
+            let halfway = ship_at(&mut arena, 50.0);
+            let outside = ship_at(&mut arena, 150.0);
+
+            blast(&mut arena, 2.0);
+
+            assert!((hp(&arena, center) - 60.0).abs() < 1e-4);
+            assert!((hp(&arena, halfway) - 90.0).abs() < 1e-4);
+            assert_eq!(hp(&arena, outside), 100.0);
+        }
+
+        #[test]
+        fn solid_obstacles_shelter_targets() {
+            let mut arena = Arena::new();
+            let exposed = ship_at(&mut arena, 50.0);
+            arena.environment_mut().occupancy = Some(OccupancyField::uniform(1.0));
+
+            blast(&mut arena, 0.0);
+            assert_eq!(hp(&arena, exposed), 100.0);
+
+            arena.environment_mut().occupancy = Some(OccupancyField::uniform(0.5));
+            blast(&mut arena, 0.0);
+            assert!((hp(&arena, exposed) - 60.0).abs() < 1e-4);
+        }
+    }
+
+    mod gunfire_tests {
+        use super::*;
+        use crate::entity::{AmmoType, Track, TrackQuality, WeaponState};
+
+        fn gun(dispersion: f32) -> GunBallistics {
+            GunBallistics {
+                dispersion,
+                ..GunBallistics::default()
+            }
+        }
+
+        /// Spawns a shooter at the origin with `gun` firing bursts of 4,
+        /// and a target at `range` on the x axis.
+        fn duel(gun: GunBallistics, range: f32) -> (Arena, EntityId, EntityId) {
+            let mut arena = Arena::new();
+            let mut shooter = ShipComponents::at_position(Vec2::ZERO, 0.0);
+            let weapon = WeaponState::new(0, 5.0, AmmoType::Shell)
+                .with_burst(4)
+                .with_gun(gun);
+            shooter.combat.weapons.push(weapon);
+            let shooter = arena.spawn(EntityTag::Ship, EntityInner::Ship(shooter));
+            let target = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(range, 0.0), 0.0)),
+            );
+            (arena, shooter, target)
+        }
+
+        fn fire(resolver: &CombatResolver, arena: &mut Arena, source: EntityId, target: EntityId) {
+            let envelope = make_envelope(
+                Output::Command(Command::FireWeapon {
+                    source,
+                    target,
+                    slot: 0,
+                }),
+                source,
+            );
+            let current = arena.clone();
+            resolver.resolve(&[&envelope], &current, arena);
+        }
+
+        fn logged(events: &EventResolver) -> Vec<Event> {
+            events
+                .take_events()
+                .iter()
+                .filter_map(|e| e.output().as_event().cloned())
+                .collect()
+        }
+
+        #[test]
+        fn accurate_gun_hits_every_round() {
+            let (mut arena, shooter, target) = duel(gun(0.0), 5000.0);
+            let events = Arc::new(EventResolver::new());
+            let resolver = CombatResolver::new().with_event_log(Arc::clone(&events));
+
+            fire(&resolver, &mut arena, shooter, target);
+
+            let ship = arena.get(target).unwrap().as_ship().unwrap();
+            assert!((ship.combat.hp - 60.0).abs() < 1e-4);
+            let hits = logged(&events);
+            assert_eq!(hits.len(), 4);
+            assert!(hits
+                .iter()
+                .all(|e| matches!(e, Event::DamageDealt { source, .. } if *source == shooter)));
+        }
+
+        #[test]
+        fn rounds_beyond_max_range_fall_short() {
+            let (mut arena, shooter, target) = duel(gun(0.0), 20_000.0);
+            let events = Arc::new(EventResolver::new());
+            let resolver = CombatResolver::new().with_event_log(Arc::clone(&events));
+
+            fire(&resolver, &mut arena, shooter, target);
+
+            assert_eq!(arena.get(target).unwrap().as_ship().unwrap().combat.hp, 100.0);
+            let splash = Event::ShellSplash {
+                source: shooter,
+                position: Vec2::new(15_000.0, 0.0),
+            };
+            assert_eq!(logged(&events), vec![splash; 4]);
+        }
+
+        #[test]
+        fn dispersion_is_deterministic_from_seed() {
+            let salvo = |seed| {
+                let (mut arena, shooter, target) = duel(gun(0.01), 10_000.0);
+                let events = Arc::new(EventResolver::new());
+                let resolver = CombatResolver::new()
+                    .with_seed(seed)
+                    .with_event_log(Arc::clone(&events));
+                fire(&resolver, &mut arena, shooter, target);
+                logged(&events)
+            };
+
+            let first = salvo(7);
+            // 100 m of spread against a point target: most rounds splash
+            assert!(first
+                .iter()
+                .any(|e| matches!(e, Event::ShellSplash { .. })));
+            assert_eq!(first, salvo(7));
+            assert_ne!(first, salvo(8));
+        }
+
+        #[test]
+        fn gun_on_cooldown_holds_fire() {
+            let (mut arena, shooter, target) = duel(gun(0.0), 5000.0);
+            arena.get_mut(shooter).unwrap().as_ship_mut().unwrap().combat.weapons[0].cooldown =
+                1.0;
+
+            fire(&CombatResolver::new(), &mut arena, shooter, target);
+
+            assert_eq!(arena.get(target).unwrap().as_ship().unwrap().combat.hp, 100.0);
+        }
+
+        /// Fires a perfect gun at a target crossing at 20 m/s, 5 km out, as
+        /// seen by a track whose fixes had it moving at `tracked_speed`.
+        fn fire_on_track(tracked_speed: f32) -> f32 {
+            let perfect = GunBallistics {
+                lead_error: 0.0,
+                ..gun(0.0)
+            };
+            let (mut arena, shooter, target) = duel(perfect, 5000.0);
+            let ship = arena.get_mut(target).unwrap().as_ship_mut().unwrap();
+            ship.physics.velocity = Vec2::new(0.0, 20.0);
+            let mut track = Track::new(target, Vec2::ZERO, TrackQuality::FireControl);
+            for t in [-2.0, -1.0, 0.0] {
+                track.record_fix(t, Vec2::new(5000.0, tracked_speed * t));
+            }
+            let shooter_ship = arena.get_mut(shooter).unwrap().as_ship_mut().unwrap();
+            shooter_ship.sensor.track_table.push(track);
+
+            fire(&CombatResolver::new(), &mut arena, shooter, target);
+            arena.get(target).unwrap().as_ship().unwrap().combat.hp
+        }
+
+        #[test]
+        fn fire_control_leads_by_the_track_estimate() {
+            // Tracked as stationary, the target sails out of the fall of shot
+            assert_eq!(fire_on_track(0.0), 100.0);
+            // Tracked on its true course and speed, every round hits
+            assert!((fire_on_track(20.0) - 60.0).abs() < 1e-4);
+        }
+    }
+
+    mod air_to_air_tests {
+        use super::*;
+        use crate::entity::{AirWing, CraftType, TeamId};
+
+        /// Spawns a 120 HP squadron of `craft` on `team` at `x`.
+        fn squadron(arena: &mut Arena, craft: CraftType, team: u32, x: f32) -> EntityId {
+            let components = SquadronComponents::at_position(Vec2::new(x, 0.0), 0.0)
+                .with_craft_count(12, 10.0)
+                .with_air_wing(AirWing::new(craft));
+            let id = arena.spawn(EntityTag::Squadron, EntityInner::Squadron(components));
+            arena.set_team(id, Some(TeamId::new(team)));
+            id
+        }
+
+        fn intercept(source: EntityId, target: EntityId) -> OutputEnvelope {
+            make_envelope(
+                Output::Command(Command::InterceptSquadron { source, target }),
+                source,
+            )
+        }
+
+        fn step(arena: &mut Arena, outputs: &[&OutputEnvelope]) {
+            let current = arena.clone();
+            CombatResolver::new().resolve(outputs, &current, arena);
+        }
+
+        fn state(arena: &Arena, id: EntityId) -> (f32, AirWing) {
+            let squadron = arena.get(id).unwrap().as_squadron().unwrap();
+            (squadron.combat.hp, squadron.air_wing.unwrap())
+        }
+
+        #[test]
+        fn fighters_outtrade_bombers_and_spend_ordnance() {
+            let mut arena = Arena::new();
+            let fighters = squadron(&mut arena, CraftType::Fighter, 1, 0.0);
+            let bombers = squadron(&mut arena, CraftType::Bomber, 2, 1000.0);
+
+            step(&mut arena, &[&intercept(fighters, bombers)]);
+
+            let (fighter_hp, wing) = state(&arena, fighters);
+            let (bomber_hp, bomber_wing) = state(&arena, bombers);
+            assert!((120.0 - bomber_hp - 120.0 * 0.02 * 1.5 * FIXED_DT).abs() < 1e-5);
+            assert!((120.0 - fighter_hp - 120.0 * 0.02 * 0.25 * FIXED_DT).abs() < 1e-5);
+            assert_eq!(wing.intercept, Some(bombers));
+            assert!((wing.ordnance - (60.0 - FIXED_DT)).abs() < 1e-5);
+            // The bombers returned fire without an order of their own
+            assert_eq!(bomber_wing.intercept, None);
+            assert!(bomber_wing.ordnance < 60.0);
+
+            // The order stands on later ticks
+            step(&mut arena, &[]);
+            assert!(state(&arena, bombers).0 < bomber_hp);
+        }
+
+        #[test]
+        fn remaining_ordnance_scales_fire_and_empty_wings_break_off() {
+            let mut arena = Arena::new();
+            let fighters = squadron(&mut arena, CraftType::Fighter, 1, 0.0);
+            let enemy = squadron(&mut arena, CraftType::Fighter, 2, 500.0);
+            if let Some(EntityInner::Squadron(c)) = arena.get_mut(fighters).map(Entity::inner_mut) {
+                c.air_wing.as_mut().unwrap().ordnance = 30.0;
+            }
+            if let Some(EntityInner::Squadron(c)) = arena.get_mut(enemy).map(Entity::inner_mut) {
+                c.air_wing.as_mut().unwrap().ordnance = 0.0;
+            }
+
+            step(&mut arena, &[&intercept(fighters, enemy)]);
+            let hit = 120.0 - state(&arena, enemy).0;
+            assert!((hit - 120.0 * 0.02 * 0.5 * FIXED_DT).abs() < 1e-5);
+            assert_eq!(state(&arena, fighters).0, 120.0);
+
+            // An unarmed squadron cannot take an intercept order
+            step(&mut arena, &[&intercept(enemy, fighters)]);
+            assert_eq!(state(&arena, enemy).1.intercept, None);
+        }
+
+        #[test]
+        fn friendly_and_out_of_range_intercepts_deal_no_damage() {
+            let mut arena = Arena::new();
+            let a = squadron(&mut arena, CraftType::Fighter, 1, 0.0);
+            let friend = squadron(&mut arena, CraftType::Fighter, 1, 100.0);
+            let far = squadron(&mut arena, CraftType::Patrol, 2, 5000.0);
+
+            step(&mut arena, &[&intercept(a, friend)]);
+            assert_eq!(state(&arena, a).1.intercept, None);
+
+            step(&mut arena, &[&intercept(a, far)]);
+            let (hp, wing) = state(&arena, a);
+            assert_eq!(wing.intercept, Some(far));
+            assert_eq!(wing.ordnance, 60.0);
+            assert_eq!((hp, state(&arena, far).0), (120.0, 120.0));
+        }
+
+        #[test]
+        fn exchange_factors_favor_fighters() {
+            assert!(CraftType::Fighter.exchange_factor(CraftType::Bomber) > 1.0);
+            assert!(CraftType::Patrol.exchange_factor(CraftType::Fighter) < 1.0);
+            assert!(CraftType::Bomber.exchange_factor(CraftType::Fighter) < 0.5);
+        }
+    }
+
+    mod subsystem_tests {
+        use super::*;
+        use crate::entity::{AmmoType, SensorState, WeaponState};
+
+        /// Spawns a ship with 1000 HP whose only mount is `weapon` or its
+        /// sensor suite with 100 HP, and whose every hull hit strikes it.
+        fn exposed(arena: &mut Arena, weapon: bool) -> EntityId {
+            let mut ship = ShipComponents {
+                combat: CombatState::new(1000.0).with_subsystem_exposure(1.0),
+                ..ShipComponents::default()
+            };
+            if weapon {
+                let mount = WeaponState::new(3, 1.0, AmmoType::Shell).with_hp(50.0);
+                ship.combat.weapons.push(mount);
+            } else {
+                ship.sensor = SensorState::new(10000.0, 5000.0).with_hp(100.0);
+            }
+            arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+        }
+
+        fn hit(resolver: &CombatResolver, arena: &mut Arena, target: EntityId, amount: f32) {
+            let envelope = make_envelope(
+                Output::Modifier(Modifier::ApplyDamage {
+                    target,
+                    amount,
+                    damage_type: DamageType::Kinetic,
+                }),
+                EntityId::new(99),
+            );
+            let current = arena.clone();
+            resolver.resolve(&[&envelope], &current, arena);
+        }
+
+        fn knocked_out(events: &EventResolver) -> Vec<Subsystem> {
+            events
+                .take_events()
+                .iter()
+                .filter_map(|e| match e.output().as_event() {
+                    Some(Event::SubsystemDestroyed {
+                        source, subsystem, ..
+                    }) if *source == EntityId::new(99) => Some(*subsystem),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        #[test]
+        fn weapon_mount_is_knocked_out() {
+            let mut arena = Arena::new();
+            let ship = exposed(&mut arena, true);
+            let events = Arc::new(EventResolver::new());
+            let resolver = CombatResolver::new().with_event_log(Arc::clone(&events));
+
+            hit(&resolver, &mut arena, ship, 30.0);
+            let weapon = |arena: &Arena| {
+                let ship = arena.get(ship).unwrap().as_ship().unwrap();
+                ship.combat.get_weapon(3).cloned().unwrap()
+            };
+            assert!((weapon(&arena).hp - 20.0).abs() < 1e-4);
+            assert!(weapon(&arena).operational);
+            assert!(knocked_out(&events).is_empty());
+
+            hit(&resolver, &mut arena, ship, 30.0);
+            assert!(!weapon(&arena).operational);
+            assert_eq!(knocked_out(&events), vec![Subsystem::Weapon(3)]);
+            let ship = arena.get(ship).unwrap().as_ship().unwrap();
+            assert!((ship.combat.hp - 940.0).abs() < 1e-3);
+        }
+
+        #[test]
+        fn sensor_range_degrades_with_damage() {
+            let mut arena = Arena::new();
+            let ship = exposed(&mut arena, false);
+            let events = Arc::new(EventResolver::new());
+            let resolver = CombatResolver::new().with_event_log(Arc::clone(&events));
+
+            hit(&resolver, &mut arena, ship, 40.0);
+            let sensor = &arena.get(ship).unwrap().as_ship().unwrap().sensor;
+            assert!((sensor.integrity() - 0.6).abs() < 1e-5);
+            let intact = SensorState::new(10000.0, 5000.0).effective_sonar_range();
+            assert!((sensor.effective_sonar_range() - intact * 0.6).abs() < 1e-2);
+
+            hit(&resolver, &mut arena, ship, 80.0);
+            let combat = &arena.get(ship).unwrap().as_ship().unwrap().combat;
+            assert!(combat.are_sensors_disabled());
+            assert_eq!(knocked_out(&events), vec![Subsystem::Sensors]);
+        }
+
+        #[test]
+        fn unexposed_and_unarmored_mounts_are_spared() {
+            let mut arena = Arena::new();
+            let ship = exposed(&mut arena, true);
+            if let Some(ship) = arena.get_mut(ship).and_then(Entity::as_ship_mut) {
+                ship.combat.subsystem_exposure = 0.0;
+            }
+            let plain = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+            let resolver = CombatResolver::new();
+
+            hit(&resolver, &mut arena, ship, 60.0);
+            hit(&resolver, &mut arena, plain, 60.0);
+
+            let ship = arena.get(ship).unwrap().as_ship().unwrap();
+            assert!(ship.combat.get_weapon(3).unwrap().operational);
+            let plain = arena.get(plain).unwrap().as_ship().unwrap();
+            assert!((plain.sensor.integrity() - 1.0).abs() < f32::EPSILON);
+        }
+    }
+
+    mod attribution_tests {
+        use super::*;
+        use crate::entity::ControllerId;
+        use crate::tests::spawn_team_ship;
+
+        /// Spawns a ship on `team` under `controller`.
+        fn ship(arena: &mut Arena, team: u32, controller: Option<ControllerId>) -> EntityId {
+            let id = spawn_team_ship(arena, Vec2::ZERO, Some(team));
+            arena.set_controller(id, controller);
+            id
+        }
+
+        /// Resolves one tick of `amount` damage from each source in turn.
+        fn hits(
+            resolver: &CombatResolver,
+            arena: &mut Arena,
+            target: EntityId,
+            from: &[(EntityId, f32)],
+        ) {
+            let envelopes: Vec<_> = from
+                .iter()
+                .map(|&(source, amount)| {
+                    make_envelope(
+                        Output::Modifier(Modifier::ApplyDamage {
+                            target,
+                            amount,
+                            damage_type: DamageType::Kinetic,
+                        }),
+                        source,
+                    )
+                })
+                .collect();
+            let refs: Vec<_> = envelopes.iter().collect();
+            let current = arena.clone();
+            resolver.resolve(&refs, &current, arena);
+        }
+
+        fn destroyed(events: &EventResolver) -> Vec<Event> {
+            events
+                .take_events()
+                .iter()
+                .filter_map(|e| e.output().as_event().cloned())
+                .filter(|e| matches!(e, Event::EntityDestroyed { .. }))
+                .collect()
+        }
+
+        #[test]
+        fn last_damager_destroys_and_others_assist() {
+            let mut arena = Arena::new();
+            let (a, b) = (ControllerId::Agent(0), ControllerId::Agent(1));
+            let target = ship(&mut arena, 1, None);
+            let first = ship(&mut arena, 0, Some(a));
+            let second = ship(&mut arena, 0, Some(a));
+            let third = ship(&mut arena, 0, Some(b));
+            let events = Arc::new(EventResolver::new());
+            let ledger = Arc::new(KillLedger::new());
+            let resolver = CombatResolver::new()
+                .with_event_log(Arc::clone(&events))
+                .with_kill_ledger(Arc::clone(&ledger));
+
+            hits(&resolver, &mut arena, target, &[(first, 20.0), (third, 30.0)]);
+            assert!(destroyed(&events).is_empty());
+            hits(&resolver, &mut arena, target, &[(second, 60.0)]);
+
+            assert_eq!(destroyed(&events), vec![Event::EntityDestroyed {
+                entity: target,
+                destroyer: Some(second),
+                assists: vec![third, first],
+            }]);
+            // The destroyer's controller gets no assist for its other ship
+            let tally = ledger.tally(a);
+            assert_eq!((tally.kills, tally.assists), (1, 0));
+            assert!((tally.damage - 80.0).abs() < 1e-4);
+            assert_eq!((ledger.tally(b).kills, ledger.tally(b).assists), (0, 1));
+
+            // Already destroyed: not reported again
+            hits(&resolver, &mut arena, target, &[(third, 10.0)]);
+            assert!(destroyed(&events).is_empty());
+        }
+
+        #[test]
+        fn friendly_and_self_inflicted_damage_is_not_credited() {
+            let mut arena = Arena::new();
+            let target = ship(&mut arena, 0, Some(ControllerId::Agent(0)));
+            let friend = ship(&mut arena, 0, Some(ControllerId::Agent(1)));
+            let events = Arc::new(EventResolver::new());
+            let ledger = Arc::new(KillLedger::new());
+            let resolver = CombatResolver::new()
+                .with_event_log(Arc::clone(&events))
+                .with_kill_ledger(Arc::clone(&ledger));
+
+            hits(&resolver, &mut arena, target, &[(friend, 50.0), (target, 60.0)]);
+
+            assert_eq!(destroyed(&events), vec![Event::EntityDestroyed {
+                entity: target,
+                destroyer: None,
+                assists: vec![],
+            }]);
+            assert!(ledger.tallies().is_empty());
+        }
+    }
 }
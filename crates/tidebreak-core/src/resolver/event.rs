@@ -78,6 +78,16 @@ impl EventResolver {
         std::mem::take(&mut *log)
     }
 
+    /// Returns a copy of all recorded events without draining them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn events(&self) -> Vec<OutputEnvelope> {
+        self.event_log.lock().unwrap().clone()
+    }
+
     /// Returns the number of events currently in the log.
     ///
     /// # Panics
@@ -98,6 +108,16 @@ impl EventResolver {
         self.event_log.lock().unwrap().is_empty()
     }
 
+    /// Appends an event raised outside the plugin phase (e.g. by another
+    /// resolver).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn record(&self, envelope: OutputEnvelope) {
+        self.event_log.lock().unwrap().push(envelope);
+    }
+
     /// Clears all events from the log without returning them.
     ///
     /// # Panics
@@ -126,7 +146,7 @@ impl Resolver for EventResolver {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::entity::{EntityId, EntityInner, EntityTag, ShipComponents};
+    use crate::entity::{DamageType, EntityId, EntityInner, EntityTag, ShipComponents};
     use crate::output::{Command, Event, Modifier, Output, PluginId, PluginInstanceId, TraceId};
     use glam::Vec2;
 
@@ -227,6 +247,7 @@ mod tests {
                 Output::Event(Event::EntityDestroyed {
                     entity: ship_id,
                     destroyer: None,
+                    assists: vec![],
                 }),
                 ship_id,
             );
@@ -429,6 +450,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: ship_id,
                     amount: 50.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 ship_id,
             );
@@ -508,6 +530,7 @@ mod tests {
                 Output::Event(Event::EntityDestroyed {
                     entity: ship_id,
                     destroyer: None,
+                    assists: vec![],
                 }),
                 ship_id,
             );
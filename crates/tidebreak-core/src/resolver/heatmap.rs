@@ -0,0 +1,308 @@
+//! Visitation and damage-exposure heatmaps.
+//!
+//! Analyzing where learned policies position their ships otherwise means
+//! logging every position to the host. The `HeatmapRecorder` bins the
+//! world into square cells and accumulates per cell, every tick:
+//!
+//! - **Visits**: one per live ship or squadron in the cell
+//! - **Exposure**: hit points those entities lost that tick
+//!
+//! Entities outside the grid are not recorded, and recording can be
+//! limited to one team. Like the [`ScoreKeeper`](super::ScoreKeeper), the
+//! recorder reads the resolved state from `next`;
+//! [`Simulation::enable_heatmap`](crate::simulation::Simulation::enable_heatmap)
+//! runs it after every other resolver.
+
+use std::sync::Mutex;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::entity::components::CombatState;
+use crate::entity::TeamId;
+use crate::output::{OutputEnvelope, OutputKind};
+
+use super::score::{combat, position};
+use super::Resolver;
+
+/// Largest number of cells along each axis of a [`HeatmapConfig`].
+pub const MAX_CELLS_PER_AXIS: usize = 1024;
+
+/// Grid layout of a heatmap.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapConfig {
+    /// Lower corner of the first cell
+    pub min: Vec2,
+    /// Side length of each square cell
+    pub cell_size: f32,
+    /// Number of cells along x and y
+    pub dims: [usize; 2],
+    /// Only record this team's entities, or every entity if `None`
+    pub team: Option<TeamId>,
+}
+
+impl HeatmapConfig {
+    /// Covers the rectangle `[min, max]` with cells of `cell_size`.
+    ///
+    /// Cells grow as needed to keep at most [`MAX_CELLS_PER_AXIS`] per
+    /// axis; the last cell on each axis may extend past `max`.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn new(min: Vec2, max: Vec2, cell_size: f32) -> Self {
+        let extent = (max - min).max(Vec2::ZERO);
+        let size = cell_size.max(extent.max_element() / MAX_CELLS_PER_AXIS as f32);
+        let cell_size = if size.is_finite() && size > 0.0 { size } else { 1.0 };
+        let count =
+            |length: f32| ((length / cell_size).ceil() as usize).clamp(1, MAX_CELLS_PER_AXIS);
+        Self {
+            min,
+            cell_size,
+            dims: [count(extent.x), count(extent.y)],
+            team: None,
+        }
+    }
+
+    /// Records only `team`'s entities.
+    #[must_use]
+    pub const fn with_team(mut self, team: TeamId) -> Self {
+        self.team = Some(team);
+        self
+    }
+
+    /// Returns the index of the cell containing `position` (row-major, x
+    /// fastest), or `None` outside the grid.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn cell(&self, position: Vec2) -> Option<usize> {
+        let grid = (position - self.min) / self.cell_size;
+        // Also rejects NaN
+        if !(grid.x >= 0.0 && grid.y >= 0.0) {
+            return None;
+        }
+        let (x, y) = (grid.x as usize, grid.y as usize);
+        (x < self.dims[0] && y < self.dims[1]).then(|| y * self.dims[0] + x)
+    }
+}
+
+/// Accumulated heatmap values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heatmap {
+    /// Grid layout
+    pub config: HeatmapConfig,
+    /// Entity-ticks spent in each cell, row-major (x fastest)
+    pub visits: Vec<u32>,
+    /// Hit points lost in each cell, row-major (x fastest)
+    pub exposure: Vec<f32>,
+    /// Ticks recorded
+    pub ticks: u64,
+}
+
+impl Heatmap {
+    /// Creates an empty heatmap.
+    #[must_use]
+    pub fn new(config: HeatmapConfig) -> Self {
+        let cells = config.dims[0] * config.dims[1];
+        Self {
+            config,
+            visits: vec![0; cells],
+            exposure: vec![0.0; cells],
+            ticks: 0,
+        }
+    }
+
+    /// Returns the visits of the cell containing `position`, or 0 outside
+    /// the grid.
+    #[must_use]
+    pub fn visits_at(&self, position: Vec2) -> u32 {
+        self.config.cell(position).map_or(0, |cell| self.visits[cell])
+    }
+
+    /// Returns the exposure of the cell containing `position`, or 0.0
+    /// outside the grid.
+    #[must_use]
+    pub fn exposure_at(&self, position: Vec2) -> f32 {
+        self.config.cell(position).map_or(0.0, |cell| self.exposure[cell])
+    }
+}
+
+/// Resolver accumulating a visitation and damage-exposure [`Heatmap`].
+///
+/// Not part of the default resolver set. Share it in an `Arc` to read the
+/// heatmap while it runs.
+///
+/// # Example
+///
+/// ```
+/// use glam::Vec2;
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+/// use tidebreak_core::resolver::{HeatmapConfig, HeatmapRecorder, Resolver};
+///
+/// let mut arena = Arena::new();
+/// let ship = ShipComponents::at_position(Vec2::new(150.0, 50.0), 0.0);
+/// arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+///
+/// let config = HeatmapConfig::new(Vec2::ZERO, Vec2::new(400.0, 400.0), 100.0);
+/// let recorder = HeatmapRecorder::new(config);
+/// let current = arena.clone();
+/// recorder.resolve(&[], &current, &mut arena);
+/// assert_eq!(recorder.heatmap().visits_at(Vec2::new(120.0, 10.0)), 1);
+/// ```
+#[derive(Debug)]
+pub struct HeatmapRecorder {
+    config: HeatmapConfig,
+    heatmap: Mutex<Heatmap>,
+}
+
+impl HeatmapRecorder {
+    /// Creates a recorder with an empty heatmap laid out by `config`.
+    #[must_use]
+    pub fn new(config: HeatmapConfig) -> Self {
+        Self {
+            config,
+            heatmap: Mutex::new(Heatmap::new(config)),
+        }
+    }
+
+    /// Returns the grid layout.
+    #[must_use]
+    pub const fn config(&self) -> &HeatmapConfig {
+        &self.config
+    }
+
+    /// Returns a copy of the heatmap so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn heatmap(&self) -> Heatmap {
+        self.heatmap.lock().unwrap().clone()
+    }
+
+    /// Replaces the heatmap so far, e.g. to resume from a checkpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn restore(&self, heatmap: Heatmap) {
+        *self.heatmap.lock().unwrap() = heatmap;
+    }
+
+    /// Zeroes the heatmap, e.g. between episodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn clear(&self) {
+        *self.heatmap.lock().unwrap() = Heatmap::new(self.config);
+    }
+}
+
+impl Resolver for HeatmapRecorder {
+    fn handles(&self) -> &[OutputKind] {
+        &[]
+    }
+
+    fn resolve(&self, _outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        let mut heatmap = self.heatmap.lock().unwrap();
+        heatmap.ticks += 1;
+        for entity in next.entities_sorted() {
+            if self.config.team.is_some_and(|team| entity.team() != Some(team)) {
+                continue;
+            }
+            let cell = position(entity).and_then(|p| self.config.cell(p));
+            let (Some(state), Some(cell)) = (combat(entity), cell) else {
+                continue;
+            };
+            let previous = current.get(entity.id()).and_then(combat);
+            if previous.is_some_and(CombatState::is_destroyed) {
+                continue;
+            }
+            if !state.is_destroyed() {
+                heatmap.visits[cell] += 1;
+            }
+            let lost = previous.map_or(0.0, |p| p.hp - state.hp);
+            if lost > 0.0 {
+                heatmap.exposure[cell] += lost;
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::StatusFlags;
+    use crate::tests::spawn_team_ship;
+
+    #[test]
+    fn layout_caps_cells_and_rejects_outside_positions() {
+        let config = HeatmapConfig::new(Vec2::new(-100.0, 0.0), Vec2::new(100.0, 50.0), 20.0);
+        assert_eq!(config.dims, [10, 3]);
+        assert_eq!(config.cell(Vec2::new(-100.0, 0.0)), Some(0));
+        assert_eq!(config.cell(Vec2::new(99.0, 45.0)), Some(29));
+        assert_eq!(config.cell(Vec2::new(-101.0, 0.0)), None);
+        assert_eq!(config.cell(Vec2::new(0.0, 60.0)), None);
+        assert_eq!(config.cell(Vec2::NAN), None);
+
+        let coarse = HeatmapConfig::new(Vec2::ZERO, Vec2::splat(1e6), 1.0);
+        assert_eq!(coarse.dims, [MAX_CELLS_PER_AXIS; 2]);
+        assert_eq!(HeatmapConfig::new(Vec2::ZERO, Vec2::ZERO, 0.0).dims, [1, 1]);
+    }
+
+    #[test]
+    fn records_visits_and_damage_where_it_was_taken() {
+        let mut arena = Arena::new();
+        let blue = spawn_team_ship(&mut arena, Vec2::new(50.0, 50.0), Some(0));
+        spawn_team_ship(&mut arena, Vec2::new(150.0, 50.0), Some(1));
+        let config = HeatmapConfig::new(Vec2::ZERO, Vec2::new(200.0, 100.0), 100.0);
+        let recorder = HeatmapRecorder::new(config);
+
+        let current = arena.clone();
+        arena.get_mut(blue).unwrap().as_ship_mut().unwrap().combat.hp -= 30.0;
+        recorder.resolve(&[], &current, &mut arena);
+        let current = arena.clone();
+        recorder.resolve(&[], &current, &mut arena);
+
+        let heatmap = recorder.heatmap();
+        assert_eq!(heatmap.ticks, 2);
+        assert_eq!(heatmap.visits, vec![2, 2]);
+        assert_eq!(heatmap.exposure, vec![30.0, 0.0]);
+
+        recorder.clear();
+        assert_eq!(recorder.heatmap(), Heatmap::new(config));
+    }
+
+    #[test]
+    fn team_filter_and_wrecks() {
+        let mut arena = Arena::new();
+        let blue = spawn_team_ship(&mut arena, Vec2::new(50.0, 50.0), Some(0));
+        spawn_team_ship(&mut arena, Vec2::new(50.0, 50.0), Some(1));
+        let config = HeatmapConfig::new(Vec2::ZERO, Vec2::splat(100.0), 100.0);
+        let recorder = HeatmapRecorder::new(config.with_team(TeamId::new(0)));
+
+        // The killing blow counts as exposure, but the wreck is not a visit
+        let current = arena.clone();
+        let combat = &mut arena.get_mut(blue).unwrap().as_ship_mut().unwrap().combat;
+        let hp = combat.hp;
+        combat.hp = 0.0;
+        combat.status_flags.insert(StatusFlags::DESTROYED);
+        recorder.resolve(&[], &current, &mut arena);
+        let current = arena.clone();
+        recorder.resolve(&[], &current, &mut arena);
+
+        let heatmap = recorder.heatmap();
+        assert_eq!(heatmap.visits, vec![0]);
+        assert_eq!(heatmap.exposure, vec![hp]);
+    }
+}
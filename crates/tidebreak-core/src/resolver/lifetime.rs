@@ -0,0 +1,189 @@
+//! Lifetime resolver for entities spawned with a time-to-live.
+//!
+//! Flares, smoke markers and debris live for a fixed number of ticks. They
+//! are spawned with [`Arena::spawn_with_ttl`] (or scheduled later with
+//! [`Arena::set_ttl`]), and each tick the `LifetimeResolver` despawns those
+//! whose expiry the tick reaches, in ID order, emitting an `EntityExpired`
+//! event for each in the event log given to
+//! [`with_event_log`](LifetimeResolver::with_event_log).
+//!
+//! Because the schedule lives in the arena, expiries survive snapshots and
+//! deltas and are replayed exactly, unlike despawns timed by the host.
+
+use std::sync::Arc;
+
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::output::{Event, Output, OutputEnvelope, OutputKind, PluginId, PluginInstanceId};
+
+use super::{EventResolver, Resolver};
+
+/// Resolver despawning entities whose time-to-live has run out.
+///
+/// Part of the default resolver set; it does nothing until an entity is
+/// spawned with a time-to-live.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ProjectileComponents};
+/// use tidebreak_core::resolver::{LifetimeResolver, Resolver};
+///
+/// let mut arena = Arena::new();
+/// let flare = arena.spawn_with_ttl(
+///     EntityTag::Projectile,
+///     EntityInner::Projectile(ProjectileComponents::default()),
+///     1,
+/// );
+///
+/// let current = arena.clone();
+/// LifetimeResolver::new().resolve(&[], &current, &mut arena);
+/// assert!(arena.get(flare).is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LifetimeResolver {
+    events: Option<Arc<EventResolver>>,
+}
+
+impl LifetimeResolver {
+    /// Creates a new lifetime resolver.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { events: None }
+    }
+
+    /// Records an `EntityExpired` event in `events` for each despawn.
+    #[must_use]
+    pub fn with_event_log(mut self, events: Arc<EventResolver>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Records an `EntityExpired` event, if an event log is attached.
+    fn record(&self, next: &mut Arena, entity: EntityId) {
+        if let Some(events) = &self.events {
+            events.record(OutputEnvelope::new(
+                Output::Event(Event::EntityExpired { entity }),
+                PluginInstanceId::new(entity, PluginId::from_static("lifetime")),
+                next.new_trace_id(),
+                next.current_tick(),
+                0,
+            ));
+        }
+    }
+}
+
+impl Resolver for LifetimeResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[]
+    }
+
+    fn resolve(&self, _outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        // The state being resolved is the one at the following tick
+        let tick = current.current_tick() + 1;
+        let expired: Vec<EntityId> = current
+            .lifetimes()
+            .filter(|(_, expiry)| *expiry <= tick)
+            .map(|(id, _)| id)
+            .collect();
+        for id in expired {
+            // An earlier expiry may have cascaded to this entity already
+            if next.despawn(id).is_some() {
+                self.record(next, id);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attachment::Attachment;
+    use crate::entity::{EntityInner, EntityTag, ProjectileComponents, ShipComponents};
+
+    fn flare(arena: &mut Arena, ticks: u64) -> EntityId {
+        arena.spawn_with_ttl(
+            EntityTag::Projectile,
+            EntityInner::Projectile(ProjectileComponents::default()),
+            ticks,
+        )
+    }
+
+    /// Runs the resolver and advances the tick, like a simulation step.
+    fn step(resolver: &LifetimeResolver, arena: &mut Arena) {
+        let current = arena.clone();
+        resolver.resolve(&[], &current, arena);
+        arena.advance_tick();
+    }
+
+    #[test]
+    fn despawns_at_expiry_with_an_event() {
+        let mut arena = Arena::new();
+        let short = flare(&mut arena, 2);
+        let long = flare(&mut arena, 3);
+        let events = Arc::new(EventResolver::new());
+        let resolver = LifetimeResolver::new().with_event_log(Arc::clone(&events));
+
+        step(&resolver, &mut arena);
+        assert!(arena.get(short).is_some());
+        step(&resolver, &mut arena);
+        assert_eq!(arena.current_tick(), 2);
+        assert!(arena.get(short).is_none());
+        assert!(arena.get(long).is_some());
+        assert_eq!(arena.expiry(short), None);
+        step(&resolver, &mut arena);
+        assert!(arena.get(long).is_none());
+
+        let expired: Vec<_> = events
+            .events()
+            .iter()
+            .map(|e| (e.tick(), e.output().clone()))
+            .collect();
+        assert_eq!(
+            expired,
+            vec![
+                (1, Output::Event(Event::EntityExpired { entity: short })),
+                (2, Output::Event(Event::EntityExpired { entity: long })),
+            ]
+        );
+    }
+
+    #[test]
+    fn schedule_can_be_replaced_and_cleared() {
+        let mut arena = Arena::new();
+        let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+        assert!(!arena.set_ttl(EntityId::new(99), 1));
+        assert!(arena.set_ttl(id, 1));
+        assert!(arena.set_ttl(id, 5));
+        assert_eq!(arena.expiry(id), Some(5));
+
+        let resolver = LifetimeResolver::new();
+        step(&resolver, &mut arena);
+        assert!(arena.get(id).is_some());
+        assert_eq!(arena.clear_ttl(id), Some(5));
+        for _ in 0..5 {
+            step(&resolver, &mut arena);
+        }
+        assert!(arena.get(id).is_some());
+    }
+
+    #[test]
+    fn expired_parent_takes_cascaded_children_once() {
+        let mut arena = Arena::new();
+        let parent = flare(&mut arena, 1);
+        let child = flare(&mut arena, 1);
+        let attachment = Attachment::new(parent).with_cascade(true);
+        assert!(arena.attach(child, attachment));
+        let events = Arc::new(EventResolver::new());
+
+        step(&LifetimeResolver::new().with_event_log(Arc::clone(&events)), &mut arena);
+        assert_eq!(arena.entity_count(), 0);
+        assert_eq!(events.events().len(), 1);
+        assert_eq!(arena.lifetimes().count(), 0);
+    }
+}
@@ -0,0 +1,346 @@
+//! Logistics resolver for cargo transfers between ships and depots.
+//!
+//! Each tick the `LogisticsResolver`:
+//!
+//! 1. **Progresses** every pending transfer in the arena's
+//!    [`SupplyLedger`](crate::logistics::SupplyLedger): a pair that is
+//!    alongside gains a tick, a pair that has drifted apart starts over, and
+//!    a transfer whose entities are gone is dropped.
+//! 2. **Completes** transfers that have been alongside for
+//!    `required_ticks`: the cargo moves from the giver's supplies to the
+//!    receiver's, limited by what the giver holds and the receiver has room
+//!    for, and the transfer is removed.
+//! 3. **Queues** every `TransferCargo` command as a new pending transfer.
+//!
+//! Completed transfers are reported as `CargoTransferred` events in the
+//! event log given to [`with_event_log`](LogisticsResolver::with_event_log).
+//! Transfers that end up moving nothing are removed without an event.
+
+use std::sync::Arc;
+
+use crate::arena::Arena;
+use crate::entity::{Cargo, Entity, EntityId};
+use crate::logistics::PendingTransfer;
+use crate::output::{
+    Command, Event, Output, OutputEnvelope, OutputKind, PluginId, PluginInstanceId,
+};
+
+use super::{EventResolver, Resolver};
+
+/// Resolver moving fuel and ammunition between entities.
+///
+/// Part of the default resolver set; it does nothing until a transfer is
+/// requested.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{Cargo, EntityInner, EntityTag, ShipComponents};
+/// use tidebreak_core::resolver::{LogisticsResolver, Resolver};
+/// use glam::Vec2;
+///
+/// let mut arena = Arena::new();
+/// let tanker = arena.spawn(
+///     EntityTag::Ship,
+///     EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+/// );
+/// let escort = arena.spawn(
+///     EntityTag::Ship,
+///     EntityInner::Ship(ShipComponents::at_position(Vec2::new(50.0, 0.0), 0.0)),
+/// );
+/// arena.get_mut(escort).unwrap().inventory_mut().unwrap().fuel = 0.0;
+/// arena.logistics_mut().request(tanker, escort, Cargo::Fuel(100.0));
+///
+/// let resolver = LogisticsResolver::new();
+/// for _ in 0..arena.logistics().config().required_ticks {
+///     let current = arena.clone();
+///     resolver.resolve(&[], &current, &mut arena);
+/// }
+/// assert!(arena.logistics().pending().is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LogisticsResolver {
+    /// Log receiving completed transfers
+    events: Option<Arc<EventResolver>>,
+}
+
+impl LogisticsResolver {
+    /// Creates a resolver without an event log.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { events: None }
+    }
+
+    /// Records completed transfers into `events`.
+    #[must_use]
+    pub fn with_event_log(mut self, events: Arc<EventResolver>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Moves as much of `cargo` as possible from `from` to `to` in `next`.
+    ///
+    /// Returns the cargo actually moved, or `None` if either entity has no
+    /// supplies.
+    fn complete(next: &mut Arena, from: EntityId, to: EntityId, cargo: Cargo) -> Option<Cargo> {
+        let available = next.get(from).and_then(Entity::inventory)?.supply(cargo);
+        let moved = next.get(to).and_then(Entity::inventory)?.room(available);
+        next.get_mut(from)
+            .and_then(Entity::inventory_mut)?
+            .withdraw(moved);
+        next.get_mut(to)
+            .and_then(Entity::inventory_mut)?
+            .deposit(moved);
+        Some(moved)
+    }
+
+    /// Records an event, if an event log is attached.
+    fn record(&self, next: &mut Arena, entity: EntityId, event: Event) {
+        if let Some(events) = &self.events {
+            events.record(OutputEnvelope::new(
+                Output::Event(event),
+                PluginInstanceId::new(entity, PluginId::from_static("logistics")),
+                next.new_trace_id(),
+                next.current_tick(),
+                0,
+            ));
+        }
+    }
+}
+
+impl Resolver for LogisticsResolver {
+    fn handles(&self) -> &[OutputKind] {
+        // Progress is driven by world state; only requests are commands
+        &[OutputKind::Command]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        let ledger = current.logistics();
+        let required = ledger.config().required_ticks;
+        let mut pending: Vec<PendingTransfer> = Vec::new();
+
+        for transfer in ledger.pending() {
+            // Entities despawned earlier this tick take their transfers with them
+            if next.get(transfer.from).is_none() || next.get(transfer.to).is_none() {
+                continue;
+            }
+            let mut transfer = *transfer;
+            if ledger.is_alongside(current, transfer.from, transfer.to) {
+                transfer.alongside_ticks += 1;
+            } else {
+                transfer.alongside_ticks = 0;
+            }
+
+            if transfer.alongside_ticks < required {
+                pending.push(transfer);
+                continue;
+            }
+            if let Some(moved) = Self::complete(next, transfer.from, transfer.to, transfer.cargo) {
+                if !moved.is_empty() {
+                    self.record(
+                        next,
+                        transfer.to,
+                        Event::CargoTransferred {
+                            from: transfer.from,
+                            to: transfer.to,
+                            cargo: moved,
+                        },
+                    );
+                }
+            }
+        }
+
+        next.logistics_mut().set_pending(pending);
+
+        for envelope in outputs {
+            if let Some(Command::TransferCargo { from, to, cargo }) = envelope.output().as_command()
+            {
+                next.logistics_mut().request(*from, *to, *cargo);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::{AmmoType, InventoryState};
+    use crate::entity::{EntityInner, EntityTag, PlatformComponents, ShipComponents};
+    use crate::logistics::TransferConfig;
+    use crate::output::TraceId;
+    use glam::Vec2;
+
+    fn ship(arena: &mut Arena, x: f32, fuel: f32) -> EntityId {
+        let id = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(Vec2::new(x, 0.0), 0.0)),
+        );
+        arena.get_mut(id).unwrap().inventory_mut().unwrap().fuel = fuel;
+        id
+    }
+
+    fn depot(arena: &mut Arena, shells: u32) -> EntityId {
+        let mut stock = InventoryState::new(5000.0);
+        stock.ammo.insert(AmmoType::Shell, shells);
+        arena.spawn(
+            EntityTag::Platform,
+            EntityInner::Platform(
+                PlatformComponents::at_position(Vec2::ZERO).with_stockpile(stock),
+            ),
+        )
+    }
+
+    fn fuel(arena: &Arena, id: EntityId) -> f32 {
+        arena.get(id).unwrap().inventory().unwrap().fuel
+    }
+
+    /// An arena whose transfers complete after three alongside ticks.
+    fn quick_arena() -> Arena {
+        let mut arena = Arena::new();
+        arena.logistics_mut().set_config(TransferConfig {
+            required_ticks: 3,
+            ..TransferConfig::default()
+        });
+        arena
+    }
+
+    fn step(resolver: &LogisticsResolver, arena: &mut Arena, outputs: &[OutputEnvelope]) {
+        let current = arena.clone();
+        let refs: Vec<&OutputEnvelope> = outputs.iter().collect();
+        resolver.resolve(&refs, &current, arena);
+    }
+
+    mod transfer_tests {
+        use super::*;
+
+        #[test]
+        fn command_queues_transfer() {
+            let mut arena = quick_arena();
+            let a = ship(&mut arena, 0.0, 500.0);
+            let b = ship(&mut arena, 50.0, 100.0);
+            let envelope = OutputEnvelope::new(
+                Output::Command(Command::TransferCargo {
+                    from: a,
+                    to: b,
+                    cargo: Cargo::Fuel(200.0),
+                }),
+                PluginInstanceId::new(a, PluginId::new("test")),
+                TraceId::new(1),
+                0,
+                0,
+            );
+
+            step(&LogisticsResolver::new(), &mut arena, &[envelope]);
+
+            assert_eq!(arena.logistics().pending().len(), 1);
+            assert_eq!(arena.logistics().pending()[0].alongside_ticks, 0);
+        }
+
+        #[test]
+        fn transfer_completes_after_required_ticks() {
+            let mut arena = quick_arena();
+            let a = ship(&mut arena, 0.0, 500.0);
+            let b = ship(&mut arena, 50.0, 100.0);
+            arena.logistics_mut().request(a, b, Cargo::Fuel(200.0));
+            let events = Arc::new(EventResolver::new());
+            let resolver = LogisticsResolver::new().with_event_log(Arc::clone(&events));
+
+            step(&resolver, &mut arena, &[]);
+            step(&resolver, &mut arena, &[]);
+            assert!((fuel(&arena, b) - 100.0).abs() < 1e-6);
+
+            step(&resolver, &mut arena, &[]);
+            assert!((fuel(&arena, a) - 300.0).abs() < 1e-6);
+            assert!((fuel(&arena, b) - 300.0).abs() < 1e-6);
+            assert!(arena.logistics().pending().is_empty());
+
+            let logged = events.take_events();
+            assert_eq!(logged.len(), 1);
+            assert_eq!(
+                logged[0].output().as_event(),
+                Some(&Event::CargoTransferred {
+                    from: a,
+                    to: b,
+                    cargo: Cargo::Fuel(200.0),
+                })
+            );
+        }
+
+        #[test]
+        fn drifting_apart_resets_progress() {
+            let mut arena = quick_arena();
+            let a = ship(&mut arena, 0.0, 500.0);
+            let b = ship(&mut arena, 50.0, 100.0);
+            arena.logistics_mut().request(a, b, Cargo::Fuel(200.0));
+            let resolver = LogisticsResolver::new();
+
+            step(&resolver, &mut arena, &[]);
+            step(&resolver, &mut arena, &[]);
+            arena
+                .get_mut(b)
+                .unwrap()
+                .as_ship_mut()
+                .unwrap()
+                .transform
+                .position = Vec2::new(1000.0, 0.0);
+            step(&resolver, &mut arena, &[]);
+
+            assert_eq!(arena.logistics().pending()[0].alongside_ticks, 0);
+            assert!((fuel(&arena, b) - 100.0).abs() < 1e-6);
+        }
+
+        #[test]
+        fn amount_is_limited_by_stock_and_room() {
+            let mut arena = quick_arena();
+            let a = ship(&mut arena, 0.0, 50.0);
+            let b = ship(&mut arena, 50.0, 980.0);
+            let c = ship(&mut arena, 100.0, 0.0);
+            arena.logistics_mut().request(a, b, Cargo::Fuel(40.0));
+            arena.logistics_mut().request(a, c, Cargo::Fuel(500.0));
+
+            for _ in 0..3 {
+                step(&LogisticsResolver::new(), &mut arena, &[]);
+            }
+
+            // b only had room for 20; c got the remaining 30
+            assert!((fuel(&arena, b) - 1000.0).abs() < 1e-6);
+            assert!((fuel(&arena, c) - 30.0).abs() < 1e-6);
+            assert!(fuel(&arena, a) <= 0.0);
+        }
+
+        #[test]
+        fn depot_resupplies_ammo() {
+            let mut arena = quick_arena();
+            let store = depot(&mut arena, 40);
+            let escort = ship(&mut arena, 30.0, 1000.0);
+            arena
+                .logistics_mut()
+                .request(store, escort, Cargo::Ammo(AmmoType::Shell, 25));
+
+            for _ in 0..3 {
+                step(&LogisticsResolver::new(), &mut arena, &[]);
+            }
+
+            let stock = arena.get(store).unwrap().inventory().unwrap();
+            let held = arena.get(escort).unwrap().inventory().unwrap();
+            assert_eq!(stock.get_ammo(AmmoType::Shell), 15);
+            assert_eq!(held.get_ammo(AmmoType::Shell), 25);
+        }
+
+        #[test]
+        fn despawned_entity_drops_transfer() {
+            let mut arena = quick_arena();
+            let a = ship(&mut arena, 0.0, 500.0);
+            let b = ship(&mut arena, 50.0, 100.0);
+            arena.logistics_mut().request(a, b, Cargo::Fuel(200.0));
+
+            let current = arena.clone();
+            arena.despawn(b);
+            LogisticsResolver::new().resolve(&[], &current, &mut arena);
+
+            assert!(arena.logistics().pending().is_empty());
+            assert!((fuel(&arena, a) - 500.0).abs() < 1e-6);
+        }
+    }
+}
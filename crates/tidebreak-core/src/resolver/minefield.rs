@@ -0,0 +1,649 @@
+//! Minefield resolver for laying, fuzing and sweeping mines.
+//!
+//! Mines are platforms carrying a [`MineState`]. Each tick the
+//! `MinefieldResolver`:
+//!
+//! 1. **Fuzes**: a mine whose trigger radius holds an enemy ship that sets
+//!    off its [`MineFuze`] detonates, damaging every ship within its blast
+//!    radius (friend or foe), and is removed. The nearest triggering ship
+//!    is reported as the trigger.
+//! 2. **Sweeps**: every ship has a chance to detect each undetected enemy
+//!    mine within its effective sonar range. The chance grows as the ship
+//!    closes in and is multiplied while its emissions are active (active
+//!    sonar). Rolls use a deterministic RNG seeded from
+//!    (seed, tick, mine, sweeper).
+//! 3. **Lays**: every `LayMine` command drops a mine at the source's
+//!    position, on the source's team.
+//!
+//! Mines never trigger on, and are not swept by, ships of their own team.
+//! Unteamed mines are enemies of every ship. Detection is global: once
+//! swept, a mine is visible to every sensor.
+//!
+//! Detonations are reported as `MineDetonated` and `DamageDealt` events,
+//! and detections as `ContactDetected` events, in the event log given to
+//! [`with_event_log`](MinefieldResolver::with_event_log). Detection rolls
+//! can be audited with [`with_rng_audit`](MinefieldResolver::with_rng_audit).
+
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use glam::Vec2;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::arena::{Arena, Fnv1a};
+use crate::entity::components::{EmissionsMode, MineState, StatusFlags, TrackQuality};
+use crate::entity::{
+    Entity, EntityId, EntityInner, EntityTag, PlatformComponents, ShipComponents, TeamId,
+};
+use crate::output::{
+    Command, Event, Output, OutputEnvelope, OutputKind, PluginId, PluginInstanceId,
+};
+use crate::rng_audit::RngAuditLog;
+
+use super::{EventResolver, Resolver};
+
+/// Parameters of the minesweeping model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinefieldConfig {
+    /// Per-tick chance that a sweeper directly over a mine detects it.
+    pub sweep_probability: f32,
+    /// Multiplier on the chance while the sweeper's emissions are active.
+    pub active_sonar_multiplier: f32,
+}
+
+impl Default for MinefieldConfig {
+    fn default() -> Self {
+        Self {
+            sweep_probability: 0.005,
+            active_sonar_multiplier: 4.0,
+        }
+    }
+}
+
+impl MinefieldConfig {
+    /// Per-tick detection chance for a sweeper at `distance` from a mine.
+    #[must_use]
+    pub fn detection_chance(&self, distance: f32, sonar_range: f32, active: bool) -> f32 {
+        if sonar_range <= 0.0 || distance > sonar_range {
+            return 0.0;
+        }
+        let emissions = if active {
+            self.active_sonar_multiplier
+        } else {
+            1.0
+        };
+        (self.sweep_probability * (1.0 - distance / sonar_range) * emissions).clamp(0.0, 1.0)
+    }
+}
+
+/// A laid mine as seen by the resolver.
+struct Mine {
+    id: EntityId,
+    team: Option<TeamId>,
+    position: Vec2,
+    state: MineState,
+}
+
+/// Resolver laying, detonating and sweeping mines.
+///
+/// Part of the default resolver set; it does nothing until a mine is laid.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::components::{MineFuze, MineState};
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+/// use tidebreak_core::resolver::{MinefieldResolver, Resolver};
+/// use glam::Vec2;
+///
+/// let mut arena = Arena::new();
+/// let layer = arena.spawn(
+///     EntityTag::Ship,
+///     EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+/// );
+/// let mine = MinefieldResolver::lay_mine(&mut arena, layer, MineState::new(MineFuze::Proximity));
+/// assert!(arena.get(mine.unwrap()).and_then(|e| e.mine()).is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MinefieldResolver {
+    config: MinefieldConfig,
+    seed: u64,
+    /// Log receiving detonation and detection events
+    events: Option<Arc<EventResolver>>,
+    /// Log receiving detection rolls
+    rng_audit: Option<Arc<RngAuditLog>>,
+}
+
+impl MinefieldResolver {
+    /// Creates a resolver with the default model and the given RNG seed.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self::with_config(MinefieldConfig::default(), seed)
+    }
+
+    /// Creates a resolver with a custom minesweeping model.
+    #[must_use]
+    pub const fn with_config(config: MinefieldConfig, seed: u64) -> Self {
+        Self {
+            config,
+            seed,
+            events: None,
+            rng_audit: None,
+        }
+    }
+
+    /// Records detonations and detections into `events`.
+    #[must_use]
+    pub fn with_event_log(mut self, events: Arc<EventResolver>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Records detection rolls into `audit`.
+    #[must_use]
+    pub fn with_rng_audit(mut self, audit: Arc<RngAuditLog>) -> Self {
+        self.rng_audit = Some(audit);
+        self
+    }
+
+    /// Returns the minesweeping model.
+    #[must_use]
+    pub const fn config(&self) -> &MinefieldConfig {
+        &self.config
+    }
+
+    /// Lays an undetected mine at `source`'s position, on its team.
+    ///
+    /// Returns the new mine, or `None` if the source does not exist or is
+    /// destroyed.
+    pub fn lay_mine(arena: &mut Arena, source: EntityId, mine: MineState) -> Option<EntityId> {
+        let (position, team) = Self::layer(arena, source)?;
+        Some(Self::spawn_mine(arena, position, team, mine))
+    }
+
+    /// Returns the position and team of a live entity able to lay mines.
+    fn layer(arena: &Arena, source: EntityId) -> Option<(Vec2, Option<TeamId>)> {
+        let entity = arena.get(source)?;
+        let position = match entity.inner() {
+            EntityInner::Ship(c) if !c.combat.is_destroyed() => c.transform.position,
+            EntityInner::Squadron(c) if !c.combat.is_destroyed() => c.transform.position,
+            EntityInner::Platform(c) if c.mine.is_none() => c.transform.position,
+            _ => return None,
+        };
+        Some((position, entity.team()))
+    }
+
+    /// Spawns an undetected mine on `team`.
+    fn spawn_mine(
+        arena: &mut Arena,
+        position: Vec2,
+        team: Option<TeamId>,
+        mine: MineState,
+    ) -> EntityId {
+        let mine = MineState {
+            detected: false,
+            ..mine
+        };
+        let id = arena.spawn(
+            EntityTag::Platform,
+            EntityInner::Platform(PlatformComponents::mine(position, mine)),
+        );
+        arena.set_team(id, team);
+        id
+    }
+
+    /// Returns every mine in the arena, sorted by ID.
+    fn mines(arena: &Arena) -> Vec<Mine> {
+        arena
+            .entities_sorted()
+            .filter_map(|entity| {
+                let platform = entity.as_platform()?;
+                Some(Mine {
+                    id: entity.id(),
+                    team: entity.team(),
+                    position: platform.transform.position,
+                    state: platform.mine?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the ship components of a live ship hostile to `team`.
+    fn enemy_ship(entity: &Entity, team: Option<TeamId>) -> Option<&ShipComponents> {
+        let ship = entity.as_ship()?;
+        let friendly = team.is_some() && entity.team() == team;
+        (!friendly && !ship.combat.is_destroyed()).then_some(ship)
+    }
+
+    /// Returns the nearest ship that sets off `mine`, ties broken by ID.
+    fn trigger(current: &Arena, mine: &Mine) -> Option<EntityId> {
+        current
+            .spatial()
+            .query_radius(mine.position, mine.state.trigger_radius)
+            .into_iter()
+            .filter_map(|id| {
+                let ship = Self::enemy_ship(current.get(id)?, mine.team)?;
+                mine.state
+                    .fuze
+                    .triggered_by(&ship.transform, &ship.physics)
+                    .then(|| (ship.transform.position.distance_squared(mine.position), id))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+            .map(|(_, id)| id)
+    }
+
+    /// Detonates `mine`: damages ships in the blast and removes the mine.
+    fn detonate(&self, current: &Arena, next: &mut Arena, mine: &Mine, trigger: EntityId) {
+        self.record(
+            next,
+            mine.id,
+            Event::MineDetonated {
+                mine: mine.id,
+                trigger,
+            },
+        );
+        for id in current
+            .spatial()
+            .query_radius(mine.position, mine.state.blast_radius)
+        {
+            let Some(ship) = next.get_mut(id).and_then(Entity::as_ship_mut) else {
+                continue;
+            };
+            if ship.combat.is_destroyed() {
+                continue;
+            }
+            ship.combat.hp -= mine.state.damage;
+            if ship.combat.hp <= 0.0 {
+                ship.combat.hp = 0.0;
+                ship.combat.status_flags.insert(StatusFlags::DESTROYED);
+            }
+            self.record(
+                next,
+                mine.id,
+                Event::DamageDealt {
+                    source: mine.id,
+                    target: id,
+                    amount: mine.state.damage,
+                },
+            );
+        }
+        next.despawn(mine.id);
+    }
+
+    /// Key of the deterministic RNG stream for one (tick, mine, sweeper)
+    /// detection roll.
+    fn stream_key(&self, tick: u64, mine: EntityId, sweeper: EntityId) -> u64 {
+        let mut hasher = Fnv1a::default();
+        self.seed.hash(&mut hasher);
+        tick.hash(&mut hasher);
+        mine.hash(&mut hasher);
+        sweeper.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the first ship, by ID, that detects `mine` this tick.
+    fn sweep(&self, current: &Arena, mine: &Mine) -> Option<EntityId> {
+        let tick = current.current_tick();
+        current.entities_sorted().find_map(|entity| {
+            let ship = Self::enemy_ship(entity, mine.team)?;
+            let chance = self.config.detection_chance(
+                ship.transform.position.distance(mine.position),
+                ship.sensor.effective_sonar_range(),
+                ship.sensor.emissions_mode == EmissionsMode::Active,
+            );
+            if chance <= 0.0 {
+                return None;
+            }
+            let stream = self.stream_key(tick, mine.id, entity.id());
+            let roll: f32 = ChaCha8Rng::seed_from_u64(stream).gen();
+            if let Some(audit) = &self.rng_audit {
+                audit.record(tick, stream, "minefield.sweep", f64::from(roll));
+            }
+            (roll < chance).then(|| entity.id())
+        })
+    }
+
+    /// Records an event, if an event log is attached.
+    fn record(&self, next: &mut Arena, entity: EntityId, event: Event) {
+        if let Some(events) = &self.events {
+            events.record(OutputEnvelope::new(
+                Output::Event(event),
+                PluginInstanceId::new(entity, PluginId::from_static("minefield")),
+                next.new_trace_id(),
+                next.current_tick(),
+                0,
+            ));
+        }
+    }
+}
+
+impl Resolver for MinefieldResolver {
+    fn handles(&self) -> &[OutputKind] {
+        // Fuzes and sweeps are driven by world state; only laying is a command
+        &[OutputKind::Command]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        for mine in Self::mines(current) {
+            if let Some(trigger) = Self::trigger(current, &mine) {
+                self.detonate(current, next, &mine, trigger);
+            } else if !mine.state.detected {
+                if let Some(sweeper) = self.sweep(current, &mine) {
+                    if let Some(state) = next
+                        .get_mut(mine.id)
+                        .and_then(Entity::as_platform_mut)
+                        .and_then(|p| p.mine.as_mut())
+                    {
+                        state.detected = true;
+                    }
+                    self.record(
+                        next,
+                        sweeper,
+                        Event::ContactDetected {
+                            observer: sweeper,
+                            target: mine.id,
+                            quality: TrackQuality::Coarse,
+                        },
+                    );
+                }
+            }
+        }
+
+        for envelope in outputs {
+            if let Some(Command::LayMine { source, mine }) = envelope.output().as_command() {
+                if let Some((position, team)) = Self::layer(current, *source) {
+                    Self::spawn_mine(next, position, team, *mine);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::MineFuze;
+    use crate::output::TraceId;
+    use crate::tests::spawn_team_ship;
+
+    fn mine(arena: &mut Arena, position: Vec2, team: Option<u32>, state: MineState) -> EntityId {
+        MinefieldResolver::spawn_mine(arena, position, team.map(TeamId::new), state)
+    }
+
+    fn hp(arena: &Arena, id: EntityId) -> f32 {
+        arena.get(id).unwrap().as_ship().unwrap().combat.hp
+    }
+
+    fn resolve(resolver: &MinefieldResolver, arena: &mut Arena, outputs: &[OutputEnvelope]) {
+        let current = arena.clone();
+        let refs: Vec<&OutputEnvelope> = outputs.iter().collect();
+        resolver.resolve(&refs, &current, arena);
+    }
+
+    /// A resolver that never detects mines, to isolate fuze behavior.
+    fn no_sweep() -> MinefieldResolver {
+        MinefieldResolver::with_config(
+            MinefieldConfig {
+                sweep_probability: 0.0,
+                ..MinefieldConfig::default()
+            },
+            0,
+        )
+    }
+
+    mod laying_tests {
+        use super::*;
+
+        #[test]
+        fn lay_mine_at_source_on_its_team() {
+            let mut arena = Arena::new();
+            let layer = spawn_team_ship(&mut arena, Vec2::new(10.0, 20.0), Some(1));
+            let id = MinefieldResolver::lay_mine(&mut arena, layer, MineState::default()).unwrap();
+
+            let entity = arena.get(id).unwrap();
+            assert_eq!(entity.tag(), EntityTag::Platform);
+            assert_eq!(entity.team(), Some(TeamId::new(1)));
+            assert_eq!(
+                entity.as_platform().unwrap().transform.position,
+                Vec2::new(10.0, 20.0)
+            );
+            assert!(!entity.mine().unwrap().detected);
+        }
+
+        #[test]
+        fn destroyed_or_missing_source_lays_nothing() {
+            let mut arena = Arena::new();
+            let layer = spawn_team_ship(&mut arena, Vec2::ZERO, None);
+            arena
+                .get_mut(layer)
+                .unwrap()
+                .as_ship_mut()
+                .unwrap()
+                .combat
+                .hp = 0.0;
+            arena
+                .get_mut(layer)
+                .unwrap()
+                .as_ship_mut()
+                .unwrap()
+                .combat
+                .status_flags
+                .insert(StatusFlags::DESTROYED);
+
+            assert!(MinefieldResolver::lay_mine(&mut arena, layer, MineState::default()).is_none());
+            assert!(MinefieldResolver::lay_mine(
+                &mut arena,
+                EntityId::new(99),
+                MineState::default()
+            )
+            .is_none());
+            assert_eq!(arena.entity_count(), 1);
+        }
+
+        #[test]
+        fn lay_mine_command_spawns_mine() {
+            let mut arena = Arena::new();
+            let layer = spawn_team_ship(&mut arena, Vec2::new(5.0, 0.0), Some(2));
+            let envelope = OutputEnvelope::new(
+                Output::Command(Command::LayMine {
+                    source: layer,
+                    mine: MineState::new(MineFuze::Acoustic { threshold: 3.0 }),
+                }),
+                PluginInstanceId::new(layer, PluginId::new("test")),
+                TraceId::new(1),
+                0,
+                0,
+            );
+
+            resolve(&no_sweep(), &mut arena, &[envelope]);
+
+            let mines: Vec<_> = arena.entities_sorted().filter_map(Entity::mine).collect();
+            assert_eq!(mines.len(), 1);
+            assert_eq!(mines[0].fuze, MineFuze::Acoustic { threshold: 3.0 });
+        }
+    }
+
+    mod fuze_tests {
+        use super::*;
+
+        #[test]
+        fn enemy_ship_in_range_detonates_mine() {
+            let mut arena = Arena::new();
+            let mine_id = mine(&mut arena, Vec2::ZERO, Some(1), MineState::default());
+            let target = spawn_team_ship(&mut arena, Vec2::new(30.0, 0.0), Some(2));
+            let bystander = spawn_team_ship(&mut arena, Vec2::new(70.0, 0.0), Some(2));
+            let far = spawn_team_ship(&mut arena, Vec2::new(500.0, 0.0), Some(2));
+
+            resolve(&no_sweep(), &mut arena, &[]);
+
+            assert!(arena.get(mine_id).is_none());
+            assert!((hp(&arena, target) - 20.0).abs() < 1e-4);
+            assert!((hp(&arena, bystander) - 20.0).abs() < 1e-4);
+            assert!((hp(&arena, far) - 100.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn friendly_ship_does_not_trigger() {
+            let mut arena = Arena::new();
+            let mine_id = mine(&mut arena, Vec2::ZERO, Some(1), MineState::default());
+            let friend = spawn_team_ship(&mut arena, Vec2::new(10.0, 0.0), Some(1));
+
+            resolve(&no_sweep(), &mut arena, &[]);
+
+            assert!(arena.get(mine_id).is_some());
+            assert!((hp(&arena, friend) - 100.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn unteamed_mine_triggers_on_anyone() {
+            let mut arena = Arena::new();
+            let mine_id = mine(&mut arena, Vec2::ZERO, None, MineState::default());
+            spawn_team_ship(&mut arena, Vec2::new(10.0, 0.0), None);
+
+            resolve(&no_sweep(), &mut arena, &[]);
+
+            assert!(arena.get(mine_id).is_none());
+        }
+
+        #[test]
+        fn slow_ship_passes_acoustic_mine() {
+            let mut arena = Arena::new();
+            let state = MineState::new(MineFuze::Acoustic { threshold: 5.0 });
+            let mine_id = mine(&mut arena, Vec2::ZERO, Some(1), state);
+            let target = spawn_team_ship(&mut arena, Vec2::new(10.0, 0.0), Some(2));
+            let set_speed = |arena: &mut Arena, speed: f32| {
+                arena
+                    .get_mut(target)
+                    .unwrap()
+                    .as_ship_mut()
+                    .unwrap()
+                    .physics
+                    .velocity = Vec2::new(speed, 0.0);
+            };
+
+            set_speed(&mut arena, 2.0);
+            resolve(&no_sweep(), &mut arena, &[]);
+            assert!(arena.get(mine_id).is_some());
+
+            set_speed(&mut arena, 8.0);
+            resolve(&no_sweep(), &mut arena, &[]);
+            assert!(arena.get(mine_id).is_none());
+        }
+
+        #[test]
+        fn detonation_is_logged_with_nearest_trigger() {
+            let events = Arc::new(EventResolver::new());
+            let resolver = no_sweep().with_event_log(Arc::clone(&events));
+            let mut arena = Arena::new();
+            let mine_id = mine(&mut arena, Vec2::ZERO, None, MineState::default());
+            spawn_team_ship(&mut arena, Vec2::new(40.0, 0.0), None);
+            let nearest = spawn_team_ship(&mut arena, Vec2::new(0.0, 20.0), None);
+
+            resolve(&resolver, &mut arena, &[]);
+
+            let events = events.take_events();
+            assert_eq!(
+                events[0].output().as_event(),
+                Some(&Event::MineDetonated {
+                    mine: mine_id,
+                    trigger: nearest,
+                })
+            );
+            let damaged = events
+                .iter()
+                .filter(|e| matches!(e.output().as_event(), Some(Event::DamageDealt { .. })))
+                .count();
+            assert_eq!(damaged, 2);
+        }
+    }
+
+    mod sweep_tests {
+        use super::*;
+
+        fn ticks_to_detect(active: bool) -> u64 {
+            let resolver = MinefieldResolver::new(7);
+            let mut arena = Arena::new();
+            let mine_id = mine(&mut arena, Vec2::ZERO, Some(1), MineState::default());
+            let sweeper = spawn_team_ship(&mut arena, Vec2::new(500.0, 0.0), Some(2));
+            if active {
+                arena
+                    .get_mut(sweeper)
+                    .unwrap()
+                    .as_ship_mut()
+                    .unwrap()
+                    .sensor
+                    .emissions_mode = EmissionsMode::Active;
+            }
+            for tick in 0..100_000 {
+                if arena.get(mine_id).and_then(Entity::mine).unwrap().detected {
+                    return tick;
+                }
+                resolve(&resolver, &mut arena, &[]);
+                arena.advance_tick();
+            }
+            panic!("mine never detected");
+        }
+
+        #[test]
+        fn detection_chance_falls_with_range() {
+            let config = MinefieldConfig::default();
+            let near = config.detection_chance(100.0, 1000.0, false);
+            let far = config.detection_chance(900.0, 1000.0, false);
+            assert!(near > far);
+            assert!(config.detection_chance(1100.0, 1000.0, false) <= 0.0);
+            assert!((config.detection_chance(100.0, 1000.0, true) - 4.0 * near).abs() < 1e-6);
+        }
+
+        #[test]
+        fn sweeping_eventually_detects_mine() {
+            assert!(ticks_to_detect(false) > 0);
+        }
+
+        #[test]
+        fn sweeping_is_deterministic() {
+            assert_eq!(ticks_to_detect(false), ticks_to_detect(false));
+        }
+
+        #[test]
+        fn active_sonar_sweeps_faster() {
+            assert!(ticks_to_detect(true) < ticks_to_detect(false));
+        }
+
+        #[test]
+        fn sweep_rolls_are_audited() {
+            let audit = Arc::new(RngAuditLog::new());
+            audit.enable(16);
+            let resolver = MinefieldResolver::new(7).with_rng_audit(Arc::clone(&audit));
+            let mut arena = Arena::new();
+            mine(&mut arena, Vec2::ZERO, Some(1), MineState::default());
+            spawn_team_ship(&mut arena, Vec2::new(500.0, 0.0), Some(2));
+
+            resolve(&resolver, &mut arena, &[]);
+
+            let draws = audit.draws();
+            assert_eq!(draws.len(), 1);
+            assert_eq!(draws[0].purpose, "minefield.sweep");
+            assert!((0.0..1.0).contains(&draws[0].value));
+        }
+
+        #[test]
+        fn own_team_does_not_sweep() {
+            let resolver = MinefieldResolver::with_config(
+                MinefieldConfig {
+                    sweep_probability: 1.0,
+                    ..MinefieldConfig::default()
+                },
+                0,
+            );
+            let mut arena = Arena::new();
+            let mine_id = mine(&mut arena, Vec2::ZERO, Some(1), MineState::default());
+            spawn_team_ship(&mut arena, Vec2::new(500.0, 0.0), Some(1));
+
+            resolve(&resolver, &mut arena, &[]);
+
+            assert!(!arena.get(mine_id).and_then(Entity::mine).unwrap().detected);
+        }
+    }
+}
@@ -17,23 +17,126 @@
 //! - Resolvers MUST be deterministic given the same inputs and output order
 //! - Resolvers should process outputs in a consistent order for determinism
 //!
+//! # Ordering
+//!
+//! A `Simulation` runs its resolvers in ascending [`priority`], and resolvers
+//! of equal priority in the order they were registered. Each resolver sees
+//! the mutations of those before it in `next`, but reads `current`, so the
+//! order matters only where resolvers write the same state. Downstream
+//! crates add resolvers (economy, morale, ...) with
+//! `Simulation::register_resolver` at a priority relative to the defaults.
+//!
 //! # Available Resolvers
 //!
 //! - [`PhysicsResolver`]: Handles movement commands and physics integration
-//! - [`CombatResolver`]: Handles damage, healing, and status effects
+//! - [`CombatResolver`]: Handles damage, healing, status effects and gunfire
 //! - [`EventResolver`]: Records events for telemetry (no state mutation)
+//! - [`WeaponAssignmentResolver`]: Deconflicts same-team engagements (opt-in)
+//! - [`ClassificationResolver`]: Grows track classification from detections (opt-in)
+//! - [`AggregateCombatResolver`]: Lanchester attrition between squadrons (opt-in)
+//! - [`MinefieldResolver`]: Lays, detonates and sweeps mines
+//! - [`WeaponResolver`]: Weapon cooldowns, magazines and reloads
+//! - [`SubmarineResolver`]: Submarine depth, battery and crush damage
+//! - [`SmokeResolver`]: Smoke screens laid by ships
+//! - [`TrackResolver`]: Refreshes and ages sensor tracks from detections
+//! - [`ProximityResolver`]: Records which targets are within proximity radii
+//! - [`OrderResolver`]: Standing orders from commanders to subordinates
+//! - [`SafetyResolver`]: Keep-out geofences
+//! - [`LifetimeResolver`]: Despawns entities whose time-to-live ran out
+//! - [`TriggerResolver`]: Scenario triggers
+//! - [`ScoreKeeper`]: Per-team mission scores (opt-in)
+//! - [`HeatmapRecorder`]: Per-cell visitation and damage exposure (opt-in)
 
+mod aggregate;
+mod assignment;
+mod classification;
 mod combat;
 mod event;
+mod heatmap;
+mod lifetime;
+mod logistics;
+mod minefield;
+mod orders;
 mod physics;
+mod proximity;
+mod safety;
+mod score;
+mod smoke;
+mod submarine;
+mod tracks;
+mod trigger;
+mod weapon;
 
+pub use aggregate::{AggregateCombatConfig, AggregateCombatResolver};
+pub use assignment::{AssignmentConfig, WeaponAssignmentResolver};
+pub use classification::{ClassificationModel, ClassificationResolver};
+pub(crate) use combat::area_damage;
 pub use combat::CombatResolver;
 pub use event::EventResolver;
+pub use heatmap::{Heatmap, HeatmapConfig, HeatmapRecorder, MAX_CELLS_PER_AXIS};
+pub use lifetime::LifetimeResolver;
+pub use logistics::LogisticsResolver;
+pub use minefield::{MinefieldConfig, MinefieldResolver};
+pub use orders::OrderResolver;
 pub use physics::PhysicsResolver;
+pub use proximity::ProximityResolver;
+pub(crate) use physics::FIXED_DT;
+pub use safety::SafetyResolver;
+pub use score::{ScoreKeeper, ScoreState, ScoredZone, ScoringRules, TeamScore};
+pub use smoke::SmokeResolver;
+pub use submarine::SubmarineResolver;
+pub use tracks::TrackResolver;
+pub use trigger::TriggerResolver;
+pub use weapon::WeaponResolver;
+
+use std::sync::Arc;
 
 use crate::arena::Arena;
 use crate::output::{OutputEnvelope, OutputKind};
 
+/// Handle returned when a resolver is registered with a `Simulation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResolverId(pub(crate) u32);
+
+/// Priorities of the default resolvers of a `Simulation`; lower runs first.
+///
+/// They are spaced so custom resolvers can run between two defaults: a
+/// resolver at `priority::COMBAT + 50` runs after combat and before
+/// weapons.
+pub mod priority {
+    /// [`PhysicsResolver`](super::PhysicsResolver)
+    pub const PHYSICS: i32 = 0;
+    /// [`SafetyResolver`](super::SafetyResolver)
+    pub const SAFETY: i32 = 100;
+    /// [`CombatResolver`](super::CombatResolver)
+    pub const COMBAT: i32 = 200;
+    /// [`WeaponResolver`](super::WeaponResolver)
+    pub const WEAPON: i32 = 300;
+    /// [`MinefieldResolver`](super::MinefieldResolver)
+    pub const MINEFIELD: i32 = 400;
+    /// [`LogisticsResolver`](super::LogisticsResolver)
+    pub const LOGISTICS: i32 = 500;
+    /// [`SubmarineResolver`](super::SubmarineResolver)
+    pub const SUBMARINE: i32 = 600;
+    /// [`SmokeResolver`](super::SmokeResolver)
+    pub const SMOKE: i32 = 700;
+    /// [`TrackResolver`](super::TrackResolver)
+    pub const TRACKS: i32 = 750;
+    /// [`ProximityResolver`](super::ProximityResolver)
+    pub const PROXIMITY: i32 = 780;
+    /// [`OrderResolver`](super::OrderResolver)
+    pub const ORDERS: i32 = 800;
+    /// [`TriggerResolver`](super::TriggerResolver)
+    pub const TRIGGERS: i32 = 900;
+    /// [`LifetimeResolver`](super::LifetimeResolver)
+    pub const LIFETIME: i32 = 1000;
+    /// [`EventResolver`](super::EventResolver), which records the events of
+    /// the tick
+    pub const EVENTS: i32 = 1100;
+    /// Resolvers added with `Simulation::add_resolver`: after every default
+    pub const CUSTOM: i32 = 2000;
+}
+
 /// Resolver processes outputs and mutates `NextState`.
 ///
 /// Resolvers are the write phase of the Entity-Plugin-Resolver architecture.
@@ -98,6 +201,25 @@ pub trait Resolver: Send + Sync {
     /// - Only mutate `next`, never read from it (use `current` for lookups)
     /// - Must be deterministic given the same inputs + output order
     fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena);
+
+    /// Changes the seconds simulated per tick, for resolvers that integrate
+    /// over time. [`Simulation::set_physics_dt`] calls this on every
+    /// resolver; the default ignores it.
+    ///
+    /// [`Simulation::set_physics_dt`]: crate::simulation::Simulation::set_physics_dt
+    fn set_dt(&mut self, _dt: f32) {}
+}
+
+/// Shared resolvers, so a caller can keep a handle to a resolver (e.g. to
+/// drain its log) after adding it to a `Simulation`.
+impl<R: Resolver + ?Sized> Resolver for Arc<R> {
+    fn handles(&self) -> &[OutputKind] {
+        (**self).handles()
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        (**self).resolve(outputs, current, next);
+    }
 }
 
 #[cfg(test)]
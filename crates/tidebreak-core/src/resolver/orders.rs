@@ -0,0 +1,140 @@
+//! Order resolver filing commander orders.
+//!
+//! Each tick the `OrderResolver` files every `Order` output in the arena's
+//! [`OrderBook`](crate::orders::OrderBook), in resolution order, so the last
+//! order a subordinate receives in a tick wins. Orders are checked with
+//! [`can_issue`] against the current state, and must come from a plugin
+//! running on the commander itself; anything else is dropped.
+
+use crate::arena::Arena;
+use crate::orders::can_issue;
+use crate::output::{Output, OutputEnvelope, OutputKind};
+
+use super::Resolver;
+
+/// Resolver turning order outputs into standing orders.
+///
+/// Part of the default resolver set; it does nothing until a commander
+/// issues an order.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+/// use tidebreak_core::output::{Order, Output, OutputEnvelope, PluginId, PluginInstanceId, TraceId};
+/// use tidebreak_core::resolver::{OrderResolver, Resolver};
+///
+/// let mut arena = Arena::new();
+/// let flagship = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+/// let escort = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+/// arena.set_team(flagship, Some(TeamId::new(0)));
+/// arena.set_team(escort, Some(TeamId::new(0)));
+///
+/// let order = OutputEnvelope::new(
+///     Output::Order(Order::Engage { commander: flagship, subordinate: escort, target: flagship }),
+///     PluginInstanceId::new(flagship, PluginId::from_static("commander")),
+///     TraceId::new(0),
+///     0,
+///     0,
+/// );
+/// let current = arena.clone();
+/// OrderResolver::new().resolve(&[&order], &current, &mut arena);
+/// assert_eq!(arena.orders().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OrderResolver;
+
+impl OrderResolver {
+    /// Creates a new order resolver.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Resolver for OrderResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[OutputKind::Order]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        let tick = current.current_tick();
+        for envelope in outputs {
+            let Output::Order(order) = envelope.output() else {
+                continue;
+            };
+            if envelope.source().entity_id() != order.commander() || !can_issue(current, order) {
+                continue;
+            }
+            // Subordinates despawned earlier this tick take no orders
+            if next.get(order.subordinate()).is_none() {
+                continue;
+            }
+            next.orders_mut().issue(order.clone(), tick);
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::EntityId;
+    use crate::output::{Order, PluginId, PluginInstanceId, TraceId};
+    use crate::tests::spawn_team_ship;
+    use glam::Vec2;
+
+    fn envelope(source: EntityId, order: Order) -> OutputEnvelope {
+        OutputEnvelope::new(
+            Output::Order(order),
+            PluginInstanceId::new(source, PluginId::from_static("commander")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn move_to(commander: EntityId, subordinate: EntityId, x: f32) -> Order {
+        Order::MoveTo {
+            commander,
+            subordinate,
+            destination: Vec2::new(x, 0.0),
+            speed: 0.0,
+        }
+    }
+
+    #[test]
+    fn last_order_of_the_tick_wins() {
+        let mut arena = Arena::new();
+        let commander = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let subordinate = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let first = envelope(commander, move_to(commander, subordinate, 10.0));
+        let second = envelope(commander, move_to(commander, subordinate, 20.0));
+
+        let current = arena.clone();
+        OrderResolver::new().resolve(&[&first, &second], &current, &mut arena);
+
+        let standing = arena.orders().get(subordinate).unwrap();
+        assert_eq!(standing.order, move_to(commander, subordinate, 20.0));
+    }
+
+    #[test]
+    fn drops_spoofed_and_hostile_orders() {
+        let mut arena = Arena::new();
+        let commander = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let subordinate = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let enemy = spawn_team_ship(&mut arena, Vec2::ZERO, Some(1));
+        // Issued by an entity other than the named commander
+        let spoofed = envelope(enemy, move_to(commander, subordinate, 10.0));
+        let hostile = envelope(enemy, move_to(enemy, subordinate, 10.0));
+
+        let current = arena.clone();
+        OrderResolver::new().resolve(&[&spoofed, &hostile], &current, &mut arena);
+
+        assert!(arena.orders().is_empty());
+    }
+}
@@ -2,21 +2,37 @@
 //!
 //! The `PhysicsResolver` handles:
 //! - `SetVelocity` commands: Update entity velocity
-//! - `SetHeading` commands: Update entity heading
+//! - `SetHeading` commands: Order an entity heading
+//! - `SetThrottle` commands: Order an engine speed along the heading
+//! - Engine model: Accelerate or decelerate throttled entities toward their
+//!   ordered speed, and turn them toward their ordered heading, limited by
+//!   their `EngineProfile`
 //! - Physics integration: Apply `position += velocity * dt` each tick
+//! - Steering assist: Blend the commanded motion of entities whose
+//!   controller opted in with collision avoidance (see [`crate::steering`])
+//! - Sea currents: Drift entities with the arena's `CurrentField`, if set
+//! - Wind: Drift entities with windage downwind, if the arena has a `Wind`
+//! - World bounds: Apply the arena's `BoundaryPolicy` to entities that moved
 //!
 //! # Fixed Timestep
 //!
 //! The physics resolver uses a fixed timestep of 1/60 seconds (60 FPS).
 //! This ensures deterministic physics regardless of actual frame time.
 
+use std::sync::Arc;
+
+use std::f32::consts::{PI, TAU};
+
 use glam::Vec2;
 
-use crate::arena::Arena;
-use crate::entity::EntityId;
-use crate::output::{Command, OutputEnvelope, OutputKind};
+use crate::arena::{Arena, BoundaryPolicy, WorldBounds};
+use crate::entity::components::{PhysicsState, TransformState};
+use crate::entity::{Entity, EntityId, EntityInner};
+use crate::output::{
+    Command, Event, Output, OutputEnvelope, OutputKind, PluginId, PluginInstanceId,
+};
 
-use super::Resolver;
+use super::{EventResolver, Resolver};
 
 /// Fixed timestep for physics integration (1/60 second = ~16.67ms).
 pub const FIXED_DT: f32 = 1.0 / 60.0;
@@ -28,9 +44,25 @@ pub const FIXED_DT: f32 = 1.0 / 60.0;
 ///
 /// # Processing Order
 ///
-/// 1. Apply all velocity changes from `SetVelocity` commands
-/// 2. Apply all heading changes from `SetHeading` commands
-/// 3. Integrate physics: `position += velocity * dt` for all entities
+/// 1. Apply `SetVelocity`, `SetHeading` and `SetThrottle` commands in order,
+///    then the arena's steering assists
+/// 2. Drive engines: entities under throttle turn toward their ordered
+///    heading at the turn rate their speed allows, and gain or shed speed
+///    along their heading toward `throttle * max_speed`; other entities
+///    take their ordered heading at once
+/// 3. Integrate physics: `position += (velocity + drift) * dt` for all
+///    entities, where drift comes from the arena's sea currents and, for
+///    entities with windage, its wind, if set
+/// 4. Enforce the arena's world bounds, if set, on entities that moved
+///
+/// `SetVelocity` sets velocity instantly and releases the throttle, so
+/// scripted movement keeps working; agents should steer with throttle and
+/// heading, which respect the hull's acceleration and turning limits (see
+/// [`EngineProfile`](crate::entity::EngineProfile)).
+///
+/// Entities despawned by [`BoundaryPolicy::Despawn`] are reported as
+/// `EntityOutOfBounds` events in the event log given to
+/// [`with_event_log`](Self::with_event_log).
 ///
 /// # Example
 ///
@@ -46,13 +78,15 @@ pub const FIXED_DT: f32 = 1.0 / 60.0;
 pub struct PhysicsResolver {
     /// Fixed timestep for physics integration
     dt: f32,
+    /// Log receiving out-of-bounds events
+    events: Option<Arc<EventResolver>>,
 }
 
 impl PhysicsResolver {
     /// Creates a new physics resolver with the default fixed timestep.
     #[must_use]
     pub fn new() -> Self {
-        Self { dt: FIXED_DT }
+        Self::with_dt(FIXED_DT)
     }
 
     /// Creates a physics resolver with a custom timestep.
@@ -60,7 +94,14 @@ impl PhysicsResolver {
     /// Useful for testing or non-standard tick rates.
     #[must_use]
     pub fn with_dt(dt: f32) -> Self {
-        Self { dt }
+        Self { dt, events: None }
+    }
+
+    /// Records out-of-bounds despawns into `events`.
+    #[must_use]
+    pub fn with_event_log(mut self, events: Arc<EventResolver>) -> Self {
+        self.events = Some(events);
+        self
     }
 
     /// Returns the timestep used for physics integration.
@@ -69,83 +110,296 @@ impl PhysicsResolver {
         self.dt
     }
 
-    /// Applies a velocity change to an entity.
+    /// Applies a velocity change to an entity, releasing its throttle.
     fn apply_set_velocity(next: &mut Arena, target: EntityId, velocity: Vec2) {
-        if let Some(entity) = next.get_mut(target) {
-            // Try each entity type that has physics
-            if let Some(ship) = entity.as_ship_mut() {
-                ship.physics.velocity = velocity;
-            } else if let Some(projectile) = entity.as_projectile_mut() {
-                projectile.physics.velocity = velocity;
-            } else if let Some(squadron) = entity.as_squadron_mut() {
-                squadron.physics.velocity = velocity;
+        // Platforms don't have physics - ignore
+        if let Some((_, physics)) = next.get_mut(target).and_then(kinematics_mut) {
+            physics.velocity = velocity;
+            physics.throttle = None;
+        }
+    }
+
+    /// Applies a throttle order to an entity.
+    ///
+    /// Non-finite fractions are ignored; others are limited to the entity's
+    /// engine range.
+    fn apply_set_throttle(next: &mut Arena, target: EntityId, fraction: f32) {
+        if !fraction.is_finite() {
+            return;
+        }
+        if let Some((_, physics)) = next.get_mut(target).and_then(kinematics_mut) {
+            physics.throttle = Some(physics.engine.clamp_throttle(fraction));
+        }
+    }
+
+    /// Moves every throttled entity's heading and speed toward its ordered
+    /// heading and speed, and gives unthrottled entities their ordered
+    /// heading.
+    ///
+    /// Velocity is kept along the heading, so a heading change turns the
+    /// ship's motion with it.
+    fn drive_engines(&self, next: &mut Arena) {
+        for entity in next.entities_sorted_mut() {
+            let Some((transform, physics)) = kinematics_mut(entity) else {
+                continue;
+            };
+            let Some(throttle) = physics.throttle else {
+                if let Some(heading) = physics.ordered_heading.take() {
+                    transform.heading = heading;
+                }
+                continue;
+            };
+            physics.angular_velocity = 0.0;
+            if let Some(ordered) = physics.ordered_heading {
+                let speed = physics.velocity.length();
+                let rate = physics
+                    .engine
+                    .turn_rate(speed, physics.max_speed, physics.max_turn_rate);
+                let error = (ordered - transform.heading + PI).rem_euclid(TAU) - PI;
+                let max_turn = rate * self.dt;
+                let turn = error.clamp(-max_turn, max_turn);
+                physics.angular_velocity = turn / self.dt;
+                if error.abs() <= max_turn {
+                    transform.heading = ordered;
+                    physics.ordered_heading = None;
+                } else {
+                    transform.heading += turn;
+                }
             }
-            // Platforms don't have physics - ignore
+            let forward = transform.forward();
+            let target = physics.engine.clamp_throttle(throttle) * physics.max_speed;
+            let speed = physics
+                .engine
+                .approach(physics.velocity.dot(forward), target, self.dt);
+            physics.velocity = forward * speed;
         }
     }
 
-    /// Applies a heading change to an entity.
-    fn apply_set_heading(next: &mut Arena, target: EntityId, heading: f32) {
-        if let Some(entity) = next.get_mut(target) {
-            // Try each entity type that has transform
-            if let Some(ship) = entity.as_ship_mut() {
-                ship.transform.heading = heading;
-            } else if let Some(platform) = entity.as_platform_mut() {
-                platform.transform.heading = heading;
-            } else if let Some(projectile) = entity.as_projectile_mut() {
-                projectile.transform.heading = heading;
-            } else if let Some(squadron) = entity.as_squadron_mut() {
-                squadron.transform.heading = heading;
+    /// Blends the commanded motion of assisted ships and squadrons with
+    /// collision avoidance (see [`crate::steering`]).
+    ///
+    /// Throttled entities going ahead are given an ordered heading along
+    /// the assisted velocity; entities under direct velocity control take
+    /// the assisted velocity, limited to their max speed.
+    fn assist_steering(next: &mut Arena) {
+        let book = next.steering_assist();
+        if book.is_empty() {
+            return;
+        }
+        let mut steered = Vec::new();
+        for entity in next.entities_sorted() {
+            let (Some(assist), Some((layer, position, _, radius))) =
+                (book.get(entity.controller()), obstacle(entity))
+            else {
+                continue;
+            };
+            let (transform, physics) = match entity.inner() {
+                EntityInner::Ship(c) => (&c.transform, &c.physics),
+                EntityInner::Squadron(c) => (&c.transform, &c.physics),
+                EntityInner::Platform(_) | EntityInner::Projectile(_) => continue,
+            };
+            let velocity = match physics.throttle {
+                Some(throttle) => {
+                    let speed = physics.engine.clamp_throttle(throttle) * physics.max_speed;
+                    if speed <= 0.0 {
+                        continue;
+                    }
+                    let heading = physics.ordered_heading.unwrap_or(transform.heading);
+                    Vec2::from_angle(heading) * speed
+                }
+                None => physics.velocity,
+            };
+            let neighbors = next
+                .spatial()
+                .query_radius(position, assist.range)
+                .into_iter()
+                .filter(|id| *id != entity.id())
+                .filter_map(|id| next.get(id).and_then(obstacle))
+                .filter(|other| other.0 == layer)
+                .map(|(_, position, velocity, radius)| (position, velocity, radius));
+            let change = assist.avoidance(position, velocity, radius, neighbors);
+            if change != Vec2::ZERO {
+                steered.push((entity.id(), velocity + change));
             }
         }
+
+        for (id, velocity) in steered {
+            if let Some((_, physics)) = next.get_mut(id).and_then(kinematics_mut) {
+                if physics.throttle.is_some() {
+                    physics.ordered_heading = Some(velocity.y.atan2(velocity.x));
+                } else {
+                    physics.velocity = velocity.clamp_length_max(physics.max_speed);
+                }
+            }
+        }
+    }
+
+    /// Applies a heading order to an entity.
+    ///
+    /// Entities that move take the heading when engines are driven (see
+    /// [`Self::drive_engines`]); platforms take it at once.
+    fn apply_set_heading(next: &mut Arena, target: EntityId, heading: f32) {
+        let Some(entity) = next.get_mut(target) else {
+            return;
+        };
+        if let Some(platform) = entity.as_platform_mut() {
+            platform.transform.heading = heading;
+        } else if let Some((_, physics)) = kinematics_mut(entity) {
+            physics.ordered_heading = heading.is_finite().then_some(heading);
+        }
     }
 
     /// Integrates physics for all entities: position += velocity * dt.
     ///
+    /// If the arena has sea currents, each entity also drifts with the
+    /// current at its position, scaled by the currents' coupling for its
+    /// type; if it has wind, entities with windage drift downwind too.
     /// After updating positions, syncs the spatial index for all entities
-    /// that moved (those with non-zero velocity).
+    /// that moved (those with non-zero ground velocity).
     fn integrate_physics(&self, next: &mut Arena) {
         let dt = self.dt;
-
-        // First pass: collect IDs of entities that will move (non-zero velocity)
-        let moved_entities: Vec<EntityId> = next
+        let currents = next.currents();
+        let wind = next.wind();
+
+        // First pass: collect entities that will move, with their velocity
+        // over the ground (through-water velocity plus drift). Attached
+        // entities ride on their parents instead.
+        let attachments = next.attachments();
+        let moved: Vec<(EntityId, Vec2)> = next
             .entities_sorted()
+            .filter(|entity| attachments.get(entity.id()).is_none())
             .filter_map(|entity| {
-                let has_velocity = if let Some(ship) = entity.as_ship() {
-                    ship.physics.velocity != Vec2::ZERO
-                } else if let Some(projectile) = entity.as_projectile() {
-                    projectile.physics.velocity != Vec2::ZERO
-                } else if let Some(squadron) = entity.as_squadron() {
-                    squadron.physics.velocity != Vec2::ZERO
-                } else {
-                    false // Platforms don't have physics
+                let (position, velocity) = match entity.inner() {
+                    EntityInner::Ship(c) => (c.transform.position, c.physics.velocity),
+                    EntityInner::Projectile(c) => (c.transform.position, c.physics.velocity),
+                    EntityInner::Squadron(c) => (c.transform.position, c.physics.velocity),
+                    EntityInner::Platform(_) => return None, // Platforms don't have physics
                 };
-                if has_velocity {
-                    Some(entity.id())
-                } else {
-                    None
-                }
+                let drift = currents.map_or(Vec2::ZERO, |c| c.drift(entity.tag(), position));
+                let leeway = wind.map_or(Vec2::ZERO, |w| w.drift(next.windage(entity.id())));
+                let ground = velocity + drift + leeway;
+                (ground != Vec2::ZERO).then_some((entity.id(), ground))
             })
             .collect();
 
         // Second pass: apply physics integration
-        for entity in next.entities_sorted_mut() {
-            // Try each entity type that has physics
-            if let Some(ship) = entity.as_ship_mut() {
-                ship.transform.position += ship.physics.velocity * dt;
-            } else if let Some(projectile) = entity.as_projectile_mut() {
-                projectile.transform.position += projectile.physics.velocity * dt;
-            } else if let Some(squadron) = entity.as_squadron_mut() {
-                squadron.transform.position += squadron.physics.velocity * dt;
+        for &(entity_id, ground) in &moved {
+            if let Some((transform, _)) = next.get_mut(entity_id).and_then(kinematics_mut) {
+                transform.position += ground * dt;
+            }
+        }
+        let moved_entities: Vec<EntityId> = moved.into_iter().map(|(id, _)| id).collect();
+
+        // Third pass: enforce world bounds on entities that moved
+        if let Some(bounds) = next.bounds().copied() {
+            let mut escaped = Vec::new();
+            for &entity_id in &moved_entities {
+                let Some((transform, physics)) = next.get_mut(entity_id).and_then(kinematics_mut)
+                else {
+                    continue;
+                };
+                if !apply_bounds(&bounds, &mut transform.position, &mut physics.velocity) {
+                    escaped.push((entity_id, transform.position));
+                }
+            }
+            for (entity_id, position) in escaped {
+                next.despawn(entity_id);
+                self.record_out_of_bounds(next, entity_id, position);
             }
-            // Platforms don't have physics - no integration
         }
 
-        // Third pass: update spatial index for entities that moved
+        // Fourth pass: update spatial index for entities that moved
         for entity_id in moved_entities {
             next.update_spatial(entity_id);
         }
+
+        // Finally carry attached entities along with their parents
+        next.sync_attachments();
+    }
+
+    /// Records an `EntityOutOfBounds` event, if an event log is attached.
+    fn record_out_of_bounds(&self, next: &mut Arena, entity: EntityId, position: Vec2) {
+        if let Some(events) = &self.events {
+            events.record(OutputEnvelope::new(
+                Output::Event(Event::EntityOutOfBounds { entity, position }),
+                PluginInstanceId::new(entity, PluginId::new("physics")),
+                next.new_trace_id(),
+                next.current_tick(),
+                0,
+            ));
+        }
+    }
+}
+
+/// Returns the transform and physics of an entity that can move.
+pub(super) fn kinematics_mut(
+    entity: &mut Entity,
+) -> Option<(&mut TransformState, &mut PhysicsState)> {
+    match entity.inner_mut() {
+        EntityInner::Ship(c) => Some((&mut c.transform, &mut c.physics)),
+        EntityInner::Projectile(c) => Some((&mut c.transform, &mut c.physics)),
+        EntityInner::Squadron(c) => Some((&mut c.transform, &mut c.physics)),
+        EntityInner::Platform(_) => None,
+    }
+}
+
+/// Returns whether a live entity flies, and its position, velocity and
+/// radius, if it is something the steering assist avoids.
+///
+/// Ships avoid ships and platforms (other than mines); squadrons avoid
+/// squadrons.
+fn obstacle(entity: &Entity) -> Option<(bool, Vec2, Vec2, f32)> {
+    let (airborne, transform, velocity) = match entity.inner() {
+        EntityInner::Ship(c) if !c.combat.is_destroyed() => {
+            (false, &c.transform, c.physics.velocity)
+        }
+        EntityInner::Squadron(c) if !c.combat.is_destroyed() => {
+            (true, &c.transform, c.physics.velocity)
+        }
+        EntityInner::Platform(c) if c.mine.is_none() => (false, &c.transform, Vec2::ZERO),
+        _ => return None,
+    };
+    Some((airborne, transform.position, velocity, transform.radius))
+}
+
+/// Applies the boundary policy to one entity.
+///
+/// Returns false if the entity left the bounds and must be despawned.
+fn apply_bounds(bounds: &WorldBounds, position: &mut Vec2, velocity: &mut Vec2) -> bool {
+    if bounds.contains(*position) {
+        return true;
+    }
+    match bounds.policy {
+        BoundaryPolicy::Clamp => {
+            for axis in 0..2 {
+                if position[axis] < bounds.min[axis] || position[axis] > bounds.max[axis] {
+                    velocity[axis] = 0.0;
+                }
+            }
+            *position = position.clamp(bounds.min, bounds.max);
+        }
+        BoundaryPolicy::Bounce => {
+            for axis in 0..2 {
+                if position[axis] < bounds.min[axis] {
+                    position[axis] = 2.0 * bounds.min[axis] - position[axis];
+                    velocity[axis] = velocity[axis].abs();
+                } else if position[axis] > bounds.max[axis] {
+                    position[axis] = 2.0 * bounds.max[axis] - position[axis];
+                    velocity[axis] = -velocity[axis].abs();
+                }
+            }
+            // An overshoot wider than the play area still ends up inside
+            *position = position.clamp(bounds.min, bounds.max);
+        }
+        BoundaryPolicy::Wrap => {
+            let size = bounds.size();
+            let wrapped = bounds.min + (*position - bounds.min).rem_euclid(size);
+            // rem_euclid can round up to `size` for tiny negative offsets
+            *position = wrapped.clamp(bounds.min, bounds.max);
+        }
+        BoundaryPolicy::Despawn => return false,
     }
+    true
 }
 
 impl Resolver for PhysicsResolver {
@@ -164,21 +418,37 @@ impl Resolver for PhysicsResolver {
                     Command::SetHeading { target, heading } => {
                         Self::apply_set_heading(next, *target, *heading);
                     }
+                    Command::SetThrottle { target, fraction } => {
+                        Self::apply_set_throttle(next, *target, *fraction);
+                    }
                     // Other commands are not handled by physics resolver
-                    Command::FireWeapon { .. } | Command::SpawnProjectile { .. } => {}
+                    Command::SetDepth { .. }
+                    | Command::FireWeapon { .. }
+                    | Command::SpawnProjectile { .. }
+                    | Command::LayMine { .. }
+                    | Command::TransferCargo { .. }
+                    | Command::ReloadWeapon { .. }
+                    | Command::DeploySmoke { .. }
+                    | Command::InterceptSquadron { .. } => {}
                 }
             }
         }
+        Self::assist_steering(next);
 
-        // Integrate physics after all commands are processed
+        // Drive engines and integrate physics after all commands are processed
+        self.drive_engines(next);
         self.integrate_physics(next);
     }
+
+    fn set_dt(&mut self, dt: f32) {
+        self.dt = dt;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::entity::{EntityInner, EntityTag, ShipComponents};
+    use crate::entity::{DamageType, EntityInner, EntityTag, ShipComponents};
     use crate::output::{Output, PluginId, PluginInstanceId, TraceId};
 
     fn make_envelope(output: Output, target: EntityId) -> OutputEnvelope {
@@ -382,6 +652,427 @@ mod tests {
         }
     }
 
+    mod throttle_tests {
+        use super::*;
+        use crate::entity::EngineProfile;
+
+        fn throttle(target: EntityId, fraction: f32) -> OutputEnvelope {
+            make_envelope(
+                Output::Command(Command::SetThrottle { target, fraction }),
+                target,
+            )
+        }
+
+        fn spawn_ship(arena: &mut Arena, engine: EngineProfile) -> EntityId {
+            let ship = ShipComponents::default()
+                .with_physics(10.0, 1.0)
+                .with_engine(engine);
+            arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+        }
+
+        fn physics(arena: &Arena, id: EntityId) -> PhysicsState {
+            arena.get(id).unwrap().as_ship().unwrap().physics
+        }
+
+        #[test]
+        fn throttle_accelerates_along_heading() {
+            let mut arena = Arena::new();
+            let ship_id = spawn_ship(&mut arena, EngineProfile::new(2.0, 4.0, 0.5));
+            let resolver = PhysicsResolver::with_dt(1.0);
+
+            let current = arena.clone();
+            resolver.resolve(&[&throttle(ship_id, 1.0)], &current, &mut arena);
+            assert_eq!(physics(&arena, ship_id).velocity, Vec2::new(2.0, 0.0));
+
+            // The order persists on later ticks without repeating the command
+            for _ in 0..10 {
+                let current = arena.clone();
+                resolver.resolve(&[], &current, &mut arena);
+            }
+            assert_eq!(physics(&arena, ship_id).velocity, Vec2::new(10.0, 0.0));
+        }
+
+        #[test]
+        fn throttle_down_uses_deceleration() {
+            let mut arena = Arena::new();
+            let ship_id = spawn_ship(&mut arena, EngineProfile::new(2.0, 4.0, 0.5));
+            if let Some(ship) = arena.get_mut(ship_id).unwrap().as_ship_mut() {
+                ship.physics.velocity = Vec2::new(10.0, 0.0);
+            }
+
+            let current = arena.clone();
+            let resolver = PhysicsResolver::with_dt(1.0);
+            resolver.resolve(&[&throttle(ship_id, 0.0)], &current, &mut arena);
+
+            assert_eq!(physics(&arena, ship_id).velocity, Vec2::new(6.0, 0.0));
+        }
+
+        #[test]
+        fn reverse_is_limited_by_engine() {
+            let mut arena = Arena::new();
+            let ship_id = spawn_ship(&mut arena, EngineProfile::new(2.0, 4.0, 0.3));
+            let resolver = PhysicsResolver::with_dt(1.0);
+
+            let current = arena.clone();
+            resolver.resolve(&[&throttle(ship_id, -1.0)], &current, &mut arena);
+            assert_eq!(physics(&arena, ship_id).throttle, Some(-0.3));
+            for _ in 0..5 {
+                let current = arena.clone();
+                resolver.resolve(&[], &current, &mut arena);
+            }
+
+            assert_eq!(physics(&arena, ship_id).velocity, Vec2::new(-3.0, 0.0));
+        }
+
+        #[test]
+        fn heading_change_turns_motion() {
+            let mut arena = Arena::new();
+            let ship_id = spawn_ship(&mut arena, EngineProfile::new(20.0, 20.0, 0.5));
+            let resolver = PhysicsResolver::with_dt(1.0);
+            let heading = make_envelope(
+                Output::Command(Command::SetHeading {
+                    target: ship_id,
+                    heading: std::f32::consts::FRAC_PI_2,
+                }),
+                ship_id,
+            );
+
+            let current = arena.clone();
+            resolver.resolve(&[&throttle(ship_id, 1.0), &heading], &current, &mut arena);
+            // One second at the hull's 1 rad/s turn rate falls short
+            let state = physics(&arena, ship_id);
+            assert!((state.angular_velocity - 1.0).abs() < 1e-4);
+            assert!(state.ordered_heading.is_some());
+
+            let current = arena.clone();
+            resolver.resolve(&[], &current, &mut arena);
+
+            let state = physics(&arena, ship_id);
+            assert!(state.velocity.x.abs() < 1e-4);
+            assert!((state.velocity.y - 10.0).abs() < 1e-4);
+            assert_eq!(state.ordered_heading, None);
+        }
+
+        #[test]
+        fn turn_rate_falls_with_speed_and_turning_circle() {
+            let engine = EngineProfile::new(2.0, 4.0, 0.5).with_turning(0.5, 20.0);
+            // At rest the circle allows no turn; at full speed half the rate
+            assert_eq!(engine.turn_rate(0.0, 10.0, 1.0), 0.0);
+            assert!((engine.turn_rate(10.0, 10.0, 1.0) - 0.5).abs() < 1e-6);
+            // At 4 m/s the 20 m circle (0.2 rad/s) binds before the rudder
+            assert!((engine.turn_rate(4.0, 10.0, 1.0) - 0.2).abs() < 1e-6);
+            assert_eq!(EngineProfile::new(1.0, 1.0, 0.0).turn_rate(10.0, 10.0, 1.0), 1.0);
+        }
+
+        #[test]
+        fn throttled_ship_cannot_pivot_at_speed() {
+            let mut arena = Arena::new();
+            let engine = EngineProfile::new(2.0, 4.0, 0.5).with_turning(0.5, 100.0);
+            let ship_id = spawn_ship(&mut arena, engine);
+            if let Some(ship) = arena.get_mut(ship_id).unwrap().as_ship_mut() {
+                ship.physics.velocity = Vec2::new(10.0, 0.0);
+            }
+            let resolver = PhysicsResolver::with_dt(0.1);
+            let heading = make_envelope(
+                Output::Command(Command::SetHeading {
+                    target: ship_id,
+                    heading: 3.0,
+                }),
+                ship_id,
+            );
+
+            let current = arena.clone();
+            resolver.resolve(&[&throttle(ship_id, 1.0), &heading], &current, &mut arena);
+
+            // 10 m/s on a 100 m circle turns 0.1 rad/s
+            let ship = arena.get(ship_id).unwrap().as_ship().unwrap();
+            assert!((ship.transform.heading - 0.01).abs() < 1e-5);
+            assert!(ship.physics.velocity.x > 9.9);
+        }
+
+        #[test]
+        fn unthrottled_heading_is_immediate() {
+            let mut arena = Arena::new();
+            let ship_id = spawn_ship(&mut arena, EngineProfile::CAPITAL);
+            let heading = make_envelope(
+                Output::Command(Command::SetHeading {
+                    target: ship_id,
+                    heading: 2.0,
+                }),
+                ship_id,
+            );
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(1.0).resolve(&[&heading], &current, &mut arena);
+
+            let ship = arena.get(ship_id).unwrap().as_ship().unwrap();
+            assert_eq!(ship.transform.heading, 2.0);
+            assert_eq!(ship.physics.ordered_heading, None);
+        }
+
+        #[test]
+        fn set_velocity_releases_throttle() {
+            let mut arena = Arena::new();
+            let ship_id = spawn_ship(&mut arena, EngineProfile::default());
+            let resolver = PhysicsResolver::with_dt(1.0);
+            let velocity = make_envelope(
+                Output::Command(Command::SetVelocity {
+                    target: ship_id,
+                    velocity: Vec2::new(0.0, 5.0),
+                }),
+                ship_id,
+            );
+
+            let current = arena.clone();
+            resolver.resolve(&[&throttle(ship_id, 1.0), &velocity], &current, &mut arena);
+
+            let physics = physics(&arena, ship_id);
+            assert_eq!(physics.throttle, None);
+            assert_eq!(physics.velocity, Vec2::new(0.0, 5.0));
+        }
+
+        #[test]
+        fn non_finite_throttle_ignored() {
+            let mut arena = Arena::new();
+            let ship_id = spawn_ship(&mut arena, EngineProfile::default());
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(1.0).resolve(
+                &[&throttle(ship_id, f32::NAN)],
+                &current,
+                &mut arena,
+            );
+
+            assert_eq!(physics(&arena, ship_id).throttle, None);
+        }
+    }
+
+    mod steering_assist_tests {
+        use super::*;
+        use crate::entity::ControllerId;
+        use crate::steering::SteeringAssist;
+
+        /// Spawns two ships closing head-on, 200 m apart and 5 m abreast.
+        fn head_on(arena: &mut Arena) -> (EntityId, EntityId) {
+            let mut spawn = |x: f32, y: f32, vx: f32| {
+                let mut ship = ShipComponents::at_position(Vec2::new(x, y), 0.0);
+                ship.physics.velocity = Vec2::new(vx, 0.0);
+                arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+            };
+            (spawn(0.0, 0.0, 10.0), spawn(200.0, 5.0, -10.0))
+        }
+
+        fn velocity(arena: &Arena, id: EntityId) -> Vec2 {
+            arena.get(id).unwrap().as_ship().unwrap().physics.velocity
+        }
+
+        #[test]
+        fn assisted_ships_steer_apart() {
+            let mut arena = Arena::new();
+            let (a, b) = head_on(&mut arena);
+            arena
+                .steering_assist_mut()
+                .set_uncontrolled(Some(SteeringAssist::default()));
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(0.1).resolve(&[], &current, &mut arena);
+
+            assert!(velocity(&arena, a).y < 0.0);
+            assert!(velocity(&arena, b).y > 0.0);
+            assert!(velocity(&arena, a).length() <= 10.0 + 1e-4);
+        }
+
+        #[test]
+        fn assist_applies_only_to_opted_in_controllers() {
+            let mut arena = Arena::new();
+            let (a, b) = head_on(&mut arena);
+            arena.set_controller(a, Some(ControllerId::Agent(0)));
+            arena.set_controller(b, Some(ControllerId::Agent(1)));
+            arena
+                .steering_assist_mut()
+                .set(ControllerId::Agent(1), Some(SteeringAssist::default()));
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(0.1).resolve(&[], &current, &mut arena);
+
+            assert_eq!(velocity(&arena, a), Vec2::new(10.0, 0.0));
+            assert!(velocity(&arena, b).y > 0.0);
+        }
+
+        #[test]
+        fn throttled_ship_is_given_a_heading() {
+            let mut arena = Arena::new();
+            let (a, _) = head_on(&mut arena);
+            arena
+                .steering_assist_mut()
+                .set_uncontrolled(Some(SteeringAssist::default()));
+            let throttle = make_envelope(
+                Output::Command(Command::SetThrottle {
+                    target: a,
+                    fraction: 1.0,
+                }),
+                a,
+            );
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(0.1).resolve(&[&throttle], &current, &mut arena);
+
+            let ship = arena.get(a).unwrap().as_ship().unwrap();
+            assert!(ship.transform.heading < 0.0);
+            assert!(ship.physics.ordered_heading.is_some_and(|h| h < 0.0));
+        }
+    }
+
+    mod attachment_tests {
+        use super::*;
+        use crate::attachment::Attachment;
+        use crate::entity::PlatformComponents;
+
+        #[test]
+        fn attached_entities_ride_on_their_parent() {
+            let mut arena = Arena::new();
+            let mut ship = ShipComponents::at_position(Vec2::ZERO, std::f32::consts::FRAC_PI_2);
+            ship.physics.velocity = Vec2::new(0.0, 10.0);
+            let ship = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+            let mut boat = ShipComponents::at_position(Vec2::new(500.0, 500.0), 0.0);
+            boat.physics.velocity = Vec2::new(-5.0, 0.0);
+            let boat = arena.spawn(EntityTag::Ship, EntityInner::Ship(boat));
+            let array = arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(PlatformComponents::default()),
+            );
+            assert!(arena.attach(boat, Attachment::new(ship).with_offset(Vec2::new(0.0, 20.0))));
+            assert!(arena.attach(array, Attachment::new(boat).with_offset(Vec2::new(-50.0, 0.0))));
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(1.0).resolve(&[], &current, &mut arena);
+
+            // Heading north, the boat's port-side offset points west
+            let boat = arena.get(boat).unwrap().as_ship().unwrap();
+            assert!(boat.transform.position.distance(Vec2::new(-20.0, 10.0)) < 1e-4);
+            assert_eq!(boat.physics.velocity, Vec2::new(0.0, 10.0));
+            let array = arena.spatial().get(array).unwrap();
+            assert!(array.distance(Vec2::new(-20.0, -40.0)) < 1e-4);
+        }
+
+        #[test]
+        fn despawn_cascades_or_releases() {
+            let mut arena = Arena::new();
+            let spawn = |arena: &mut Arena| {
+                let ship = ShipComponents::at_position(Vec2::ZERO, 0.0);
+                arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+            };
+            let (parent, turret, boat) = (spawn(&mut arena), spawn(&mut arena), spawn(&mut arena));
+            assert!(arena.attach(turret, Attachment::new(parent)));
+            assert!(arena.attach(boat, Attachment::new(parent).with_cascade(false)));
+            assert!(!arena.attach(parent, Attachment::new(boat)));
+
+            arena.despawn(parent);
+            assert!(arena.get(turret).is_none());
+            assert!(arena.get(boat).is_some());
+            assert!(arena.attachments().is_empty());
+        }
+    }
+
+    mod current_drift_tests {
+        use super::*;
+        use crate::currents::{CurrentField, DriftCoupling};
+        use crate::entity::SquadronComponents;
+
+        #[test]
+        fn current_drifts_stationary_ship() {
+            let mut arena = Arena::new();
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            arena.set_currents(Some(CurrentField::uniform(Vec2::new(0.0, 2.0))));
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(1.0).resolve(&[], &current, &mut arena);
+
+            let ship = arena.get(ship_id).unwrap().as_ship().unwrap();
+            assert_eq!(ship.transform.position, Vec2::new(0.0, 2.0));
+            // Drift is over the ground; velocity through the water is unchanged
+            assert_eq!(ship.physics.velocity, Vec2::ZERO);
+            assert_eq!(arena.spatial().get(ship_id), Some(Vec2::new(0.0, 2.0)));
+        }
+
+        #[test]
+        fn drift_adds_to_velocity_and_scales_by_type() {
+            let mut arena = Arena::new();
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let squadron_id = arena.spawn(
+                EntityTag::Squadron,
+                EntityInner::Squadron(SquadronComponents::default()),
+            );
+            if let Some(ship) = arena.get_mut(ship_id).unwrap().as_ship_mut() {
+                ship.physics.velocity = Vec2::new(5.0, 0.0);
+            }
+            arena.set_currents(Some(
+                CurrentField::uniform(Vec2::new(2.0, 0.0)).with_coupling(DriftCoupling {
+                    ship: 0.5,
+                    projectile: 0.0,
+                    squadron: 0.0,
+                }),
+            ));
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(1.0).resolve(&[], &current, &mut arena);
+
+            let ship = arena.get(ship_id).unwrap().as_ship().unwrap();
+            assert_eq!(ship.transform.position, Vec2::new(6.0, 0.0));
+            let squadron = arena.get(squadron_id).unwrap().as_squadron().unwrap();
+            assert_eq!(squadron.transform.position, Vec2::ZERO);
+        }
+
+        #[test]
+        fn no_currents_no_drift() {
+            let mut arena = Arena::new();
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(1.0).resolve(&[], &current, &mut arena);
+
+            let ship = arena.get(ship_id).unwrap().as_ship().unwrap();
+            assert_eq!(ship.transform.position, Vec2::ZERO);
+        }
+
+        #[test]
+        fn wind_drifts_only_entities_with_windage() {
+            use crate::wind::Wind;
+
+            let mut arena = Arena::new();
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let raft_id = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            assert!(arena.set_windage(raft_id, 0.5));
+            assert!(!arena.set_windage(raft_id, -1.0));
+            arena.set_wind(Some(Wind::from_velocity(Vec2::new(4.0, 0.0))));
+            arena.set_currents(Some(CurrentField::uniform(Vec2::new(0.0, 1.0))));
+
+            let current = arena.clone();
+            PhysicsResolver::with_dt(1.0).resolve(&[], &current, &mut arena);
+
+            let position = |id| arena.get(id).unwrap().as_ship().unwrap().transform.position;
+            assert_eq!(position(ship_id), Vec2::new(0.0, 1.0));
+            assert_eq!(position(raft_id), Vec2::new(2.0, 1.0));
+            arena.despawn(raft_id);
+            assert_eq!(arena.windage(raft_id), 0.0);
+        }
+    }
+
     mod physics_integration_tests {
         use super::*;
 
@@ -561,6 +1252,155 @@ mod tests {
         }
     }
 
+    mod world_bounds_tests {
+        use super::*;
+        use crate::arena::{BoundaryPolicy, WorldBounds};
+        use crate::entity::PlatformComponents;
+
+        fn moving_ship(start: Vec2, velocity: Vec2) -> EntityInner {
+            let mut ship = ShipComponents::at_position(start, 0.0);
+            ship.physics.velocity = velocity;
+            EntityInner::Ship(ship)
+        }
+
+        /// Moves one ship from `start` at `velocity` for one second inside
+        /// 100x100 bounds centered on the origin.
+        fn run(policy: BoundaryPolicy, start: Vec2, velocity: Vec2) -> (Arena, EntityId) {
+            let mut arena = Arena::new();
+            arena.set_bounds(Some(WorldBounds::centered(100.0, 100.0, policy)));
+            let ship_id = arena.spawn(EntityTag::Ship, moving_ship(start, velocity));
+
+            let resolver = PhysicsResolver::with_dt(1.0);
+            let current = arena.clone();
+            resolver.resolve(&[], &current, &mut arena);
+            (arena, ship_id)
+        }
+
+        fn kinematics(arena: &Arena, id: EntityId) -> (Vec2, Vec2) {
+            let ship = arena.get(id).unwrap().as_ship().unwrap();
+            (ship.transform.position, ship.physics.velocity)
+        }
+
+        #[test]
+        fn no_bounds_lets_entities_leave() {
+            let mut arena = Arena::new();
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                moving_ship(Vec2::new(1e6, 0.0), Vec2::new(10.0, 0.0)),
+            );
+
+            let resolver = PhysicsResolver::with_dt(1.0);
+            let current = arena.clone();
+            resolver.resolve(&[], &current, &mut arena);
+
+            assert!(kinematics(&arena, ship_id).0.x > 1e6);
+        }
+
+        #[test]
+        fn clamp_holds_at_edge_and_zeroes_crossing_velocity() {
+            let (arena, id) = run(
+                BoundaryPolicy::Clamp,
+                Vec2::new(45.0, 0.0),
+                Vec2::new(10.0, 3.0),
+            );
+
+            let (position, velocity) = kinematics(&arena, id);
+            assert!((position.x - 50.0).abs() < 1e-4);
+            assert!((position.y - 3.0).abs() < 1e-4);
+            assert!(velocity.x.abs() < 1e-6);
+            assert!((velocity.y - 3.0).abs() < 1e-6);
+        }
+
+        #[test]
+        fn bounce_reflects_position_and_velocity() {
+            let (arena, id) = run(
+                BoundaryPolicy::Bounce,
+                Vec2::new(0.0, -45.0),
+                Vec2::new(0.0, -10.0),
+            );
+
+            let (position, velocity) = kinematics(&arena, id);
+            assert!((position.y + 45.0).abs() < 1e-4);
+            assert!((velocity.y - 10.0).abs() < 1e-6);
+        }
+
+        #[test]
+        fn wrap_reenters_from_opposite_edge() {
+            let (arena, id) = run(
+                BoundaryPolicy::Wrap,
+                Vec2::new(45.0, 0.0),
+                Vec2::new(10.0, 0.0),
+            );
+
+            let (position, velocity) = kinematics(&arena, id);
+            assert!((position.x + 45.0).abs() < 1e-4);
+            assert!((velocity.x - 10.0).abs() < 1e-6);
+        }
+
+        #[test]
+        fn wrap_updates_spatial_index() {
+            let (arena, id) = run(
+                BoundaryPolicy::Wrap,
+                Vec2::new(45.0, 0.0),
+                Vec2::new(10.0, 0.0),
+            );
+
+            let indexed = arena.spatial().get(id).unwrap();
+            assert!((indexed.x + 45.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn despawn_removes_entity_and_records_event() {
+            let mut arena = Arena::new();
+            arena.set_bounds(Some(WorldBounds::centered(
+                100.0,
+                100.0,
+                BoundaryPolicy::Despawn,
+            )));
+            let ship_id = arena.spawn(
+                EntityTag::Ship,
+                moving_ship(Vec2::new(45.0, 0.0), Vec2::new(10.0, 0.0)),
+            );
+            let events = Arc::new(EventResolver::new());
+
+            let resolver = PhysicsResolver::with_dt(1.0).with_event_log(Arc::clone(&events));
+            let current = arena.clone();
+            resolver.resolve(&[], &current, &mut arena);
+
+            assert!(arena.get(ship_id).is_none());
+            assert!(arena.spatial().get(ship_id).is_none());
+            let logged = events.take_events();
+            assert_eq!(logged.len(), 1);
+            match logged[0].output().as_event() {
+                Some(Event::EntityOutOfBounds { entity, position }) => {
+                    assert_eq!(*entity, ship_id);
+                    assert!((position.x - 55.0).abs() < 1e-4);
+                }
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+
+        #[test]
+        fn platforms_are_not_bounded() {
+            let mut arena = Arena::new();
+            arena.set_bounds(Some(WorldBounds::centered(
+                100.0,
+                100.0,
+                BoundaryPolicy::Despawn,
+            )));
+            let platform = arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(PlatformComponents::at_position(Vec2::new(500.0, 0.0))),
+            );
+
+            let resolver = PhysicsResolver::with_dt(1.0);
+            let current = arena.clone();
+            resolver.resolve(&[], &current, &mut arena);
+
+            assert!(arena.get(platform).is_some());
+        }
+    }
+
     mod output_filtering_tests {
         use super::*;
         use crate::entity::components::StatusFlags;
@@ -578,6 +1418,7 @@ mod tests {
                 Output::Modifier(Modifier::ApplyDamage {
                     target: ship_id,
                     amount: 50.0,
+                    damage_type: DamageType::Kinetic,
                 }),
                 ship_id,
             );
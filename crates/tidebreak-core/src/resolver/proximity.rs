@@ -0,0 +1,88 @@
+//! Proximity resolver keeping the arena's proximity book.
+//!
+//! Each tick the `ProximityResolver` applies the `EnteredRange` and
+//! `LeftRange` events of [`ProximityPlugin`](crate::plugins::ProximityPlugin)s
+//! to the arena's [`ProximityBook`](crate::proximity::ProximityBook), under
+//! the ID of the plugin that emitted them, so the next run of each plugin
+//! compares against what it saw on this one. Observers that no longer
+//! exist are dropped from the book.
+
+use crate::arena::Arena;
+use crate::output::{Event, OutputEnvelope, OutputKind};
+
+use super::Resolver;
+
+/// Resolver recording which targets are within range of each observer.
+///
+/// Part of the default resolver set; it does nothing until a
+/// `ProximityPlugin` is registered.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+/// use tidebreak_core::output::{
+///     Event, Output, OutputEnvelope, PluginId, PluginInstanceId, TraceId,
+/// };
+/// use tidebreak_core::resolver::{ProximityResolver, Resolver};
+///
+/// let mut arena = Arena::new();
+/// let observer = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+/// let target = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+/// let watch = PluginId::from_static("proximity");
+/// let entered = OutputEnvelope::new(
+///     Output::Event(Event::EnteredRange {
+///         observer,
+///         target,
+///         radius: 500.0,
+///     }),
+///     PluginInstanceId::new(observer, watch.clone()),
+///     TraceId::new(0),
+///     0,
+///     0,
+/// );
+///
+/// let current = arena.clone();
+/// ProximityResolver::new().resolve(&[&entered], &current, &mut arena);
+/// assert_eq!(arena.proximity().inside(&watch, observer).count(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProximityResolver;
+
+impl ProximityResolver {
+    /// Creates a new proximity resolver.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Resolver for ProximityResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[OutputKind::Event]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        let book = next.proximity_mut();
+        for envelope in outputs {
+            let watch = envelope.source().plugin_id();
+            match envelope.output().as_event() {
+                Some(Event::EnteredRange {
+                    observer,
+                    target,
+                    radius,
+                }) => book.enter(watch, *observer, *target, *radius),
+                Some(Event::LeftRange {
+                    observer,
+                    target,
+                    radius,
+                }) => book.leave(watch, *observer, *target, *radius),
+                _ => {}
+            }
+        }
+        if !book.is_empty() {
+            book.retain_observers(|observer| current.get(observer).is_some());
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Safety resolver enforcing keep-out geofences.
+//!
+//! The `SafetyResolver` runs right after the [`PhysicsResolver`] and checks
+//! each entity with fences in the arena's
+//! [`GeofenceBook`](crate::geofence::GeofenceBook) against its integrated
+//! position. For every fence the entity has entered it:
+//!
+//! 1. **Reports**: records a `GeofenceViolated` event with the penetration
+//!    depth in the event log given to
+//!    [`with_event_log`](SafetyResolver::with_event_log).
+//! 2. **Enforces** the fence's [`FencePolicy`]:
+//!    - `Clamp` moves the entity to the nearest point on the fence and
+//!      cancels the part of its velocity pointing into the fence, so it can
+//!      still slide along the edge.
+//!    - `Reject` moves the entity back to its position before the tick and
+//!      stops it. An entity that was already inside (e.g. the fence was
+//!      added on top of it) is clamped instead.
+//!
+//! Unlike most resolvers it handles no outputs: it corrects whatever
+//! movement the tick's commands produced, so it reads the integrated
+//! positions from `next`.
+//!
+//! [`PhysicsResolver`]: super::PhysicsResolver
+
+use std::sync::Arc;
+
+use glam::Vec2;
+
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::geofence::{FencePolicy, Geofence};
+use crate::output::{Event, Output, OutputEnvelope, OutputKind, PluginId, PluginInstanceId};
+
+use super::physics::kinematics_mut;
+use super::{EventResolver, Resolver};
+
+/// Resolver keeping entities out of their geofences.
+///
+/// Part of the default resolver set; it does nothing until a fence is
+/// added.
+///
+/// # Example
+///
+/// ```
+/// use glam::Vec2;
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+/// use tidebreak_core::geofence::Geofence;
+/// use tidebreak_core::resolver::{Resolver, SafetyResolver};
+///
+/// let mut arena = Arena::new();
+/// let ship = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+/// arena.geofences_mut().add(ship, Geofence::circle(Vec2::new(50.0, 0.0), 100.0));
+///
+/// let current = arena.clone();
+/// SafetyResolver::new().resolve(&[], &current, &mut arena);
+/// let position = arena.get(ship).unwrap().as_ship().unwrap().transform.position;
+/// assert!(position.distance(Vec2::new(-50.0, 0.0)) < 1e-3);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SafetyResolver {
+    /// Log receiving violation events
+    events: Option<Arc<EventResolver>>,
+}
+
+impl SafetyResolver {
+    /// Creates a new safety resolver.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { events: None }
+    }
+
+    /// Records violations into `events`.
+    #[must_use]
+    pub fn with_event_log(mut self, events: Arc<EventResolver>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Enforces `fences` on `entity`, returning `true` if it was moved.
+    fn enforce(
+        &self,
+        entity: EntityId,
+        fences: &[Geofence],
+        before: Option<Vec2>,
+        next: &mut Arena,
+    ) -> bool {
+        let mut moved = false;
+        for (index, fence) in fences.iter().enumerate() {
+            let Some((transform, physics)) = next.get_mut(entity).and_then(kinematics_mut) else {
+                return moved;
+            };
+            let Some((depth, edge)) = fence.penetration(transform.position) else {
+                continue;
+            };
+            let outward = (edge - transform.position).normalize_or_zero();
+            match (fence.policy, before) {
+                (FencePolicy::Reject, Some(before)) if !fence.contains(before) => {
+                    transform.position = before;
+                    physics.velocity = Vec2::ZERO;
+                }
+                (FencePolicy::Clamp | FencePolicy::Reject, _) => {
+                    transform.position = edge;
+                    let inward = -physics.velocity.dot(outward);
+                    if inward > 0.0 {
+                        physics.velocity += outward * inward;
+                    }
+                }
+            }
+            moved = true;
+            self.record(next, entity, Event::GeofenceViolated {
+                entity,
+                fence: index,
+                depth,
+            });
+        }
+        moved
+    }
+
+    fn record(&self, next: &mut Arena, entity: EntityId, event: Event) {
+        if let Some(events) = &self.events {
+            events.record(OutputEnvelope::new(
+                Output::Event(event),
+                PluginInstanceId::new(entity, PluginId::from_static("safety")),
+                next.new_trace_id(),
+                next.current_tick(),
+                0,
+            ));
+        }
+    }
+}
+
+impl Resolver for SafetyResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[]
+    }
+
+    fn resolve(&self, _outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        if next.geofences().is_empty() {
+            return;
+        }
+        let fenced: Vec<(EntityId, Vec<Geofence>)> = next
+            .geofences()
+            .iter()
+            .map(|(id, fences)| (id, fences.to_vec()))
+            .collect();
+        for (entity, fences) in fenced {
+            let before = current.spatial().get(entity);
+            if self.enforce(entity, &fences, before, next) {
+                next.update_spatial(entity);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityInner, EntityTag, ShipComponents};
+
+    fn ship(arena: &mut Arena, position: Vec2, velocity: Vec2) -> EntityId {
+        let mut components = ShipComponents::at_position(position, 0.0);
+        components.physics.velocity = velocity;
+        arena.spawn(EntityTag::Ship, EntityInner::Ship(components))
+    }
+
+    fn kinematics(arena: &Arena, id: EntityId) -> (Vec2, Vec2) {
+        let ship = arena.get(id).unwrap().as_ship().unwrap();
+        (ship.transform.position, ship.physics.velocity)
+    }
+
+    fn violations(events: &EventResolver) -> Vec<(usize, f32)> {
+        events
+            .events()
+            .iter()
+            .filter_map(|e| match e.output().as_event() {
+                Some(Event::GeofenceViolated { fence, depth, .. }) => Some((*fence, *depth)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Steps `id` to `to`, as physics would, then resolves the fences.
+    fn step_to(resolver: &SafetyResolver, arena: &mut Arena, id: EntityId, to: Vec2) {
+        let current = arena.clone();
+        arena.get_mut(id).unwrap().as_ship_mut().unwrap().transform.position = to;
+        resolver.resolve(&[], &current, arena);
+    }
+
+    #[test]
+    fn clamp_pushes_to_edge_and_keeps_tangential_velocity() {
+        let events = Arc::new(EventResolver::new());
+        let resolver = SafetyResolver::new().with_event_log(Arc::clone(&events));
+        let mut arena = Arena::new();
+        let id = ship(&mut arena, Vec2::new(-120.0, 0.0), Vec2::new(10.0, 5.0));
+        arena.geofences_mut().add(id, Geofence::circle(Vec2::ZERO, 100.0));
+
+        step_to(&resolver, &mut arena, id, Vec2::new(-90.0, 0.0));
+        let (position, velocity) = kinematics(&arena, id);
+        assert!(position.distance(Vec2::new(-100.0, 0.0)) < 1e-3);
+        assert!(velocity.distance(Vec2::new(0.0, 5.0)) < 1e-3);
+        let found = violations(&events);
+        assert_eq!(found.len(), 1);
+        assert!((found[0].1 - 10.0).abs() < 1e-3);
+        assert_eq!(arena.spatial().get(id), Some(position));
+    }
+
+    #[test]
+    fn reject_undoes_the_move_and_stops() {
+        let resolver = SafetyResolver::new();
+        let mut arena = Arena::new();
+        let id = ship(&mut arena, Vec2::new(-120.0, 0.0), Vec2::new(10.0, 0.0));
+        let square = Geofence::polygon(vec![
+            Vec2::new(-100.0, -100.0),
+            Vec2::new(100.0, -100.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(-100.0, 100.0),
+        ])
+        .with_policy(FencePolicy::Reject);
+        arena.geofences_mut().add(id, square);
+
+        step_to(&resolver, &mut arena, id, Vec2::new(-95.0, 0.0));
+        assert_eq!(kinematics(&arena, id), (Vec2::new(-120.0, 0.0), Vec2::ZERO));
+
+        // Already inside: clamped instead
+        arena.get_mut(id).unwrap().as_ship_mut().unwrap().transform.position =
+            Vec2::new(-95.0, 0.0);
+        arena.update_spatial(id);
+        let current = arena.clone();
+        resolver.resolve(&[], &current, &mut arena);
+        assert!(kinematics(&arena, id).0.distance(Vec2::new(-100.0, 0.0)) < 1e-3);
+    }
+
+    #[test]
+    fn fences_only_bind_their_entity() {
+        let events = Arc::new(EventResolver::new());
+        let resolver = SafetyResolver::new().with_event_log(Arc::clone(&events));
+        let mut arena = Arena::new();
+        let fenced = ship(&mut arena, Vec2::new(500.0, 0.0), Vec2::ZERO);
+        let free = ship(&mut arena, Vec2::ZERO, Vec2::ZERO);
+        arena.geofences_mut().add(fenced, Geofence::circle(Vec2::ZERO, 100.0));
+
+        let current = arena.clone();
+        resolver.resolve(&[], &current, &mut arena);
+        assert_eq!(kinematics(&arena, free).0, Vec2::ZERO);
+        assert!(violations(&events).is_empty());
+
+        arena.despawn(fenced);
+        assert!(arena.geofences().is_empty());
+    }
+}
@@ -0,0 +1,480 @@
+//! Mission scoring from configurable rules.
+//!
+//! Winning on remaining HP says little about escort or strike missions. The
+//! `ScoreKeeper` accumulates a score per team from [`ScoringRules`]:
+//!
+//! - **Kills**: points for the team that last damaged an entity when it is
+//!   destroyed, by the entity's class.
+//! - **Losses**: points deducted from a team for each of its own entities
+//!   destroyed, by class.
+//! - **Zone control**: points per second a team is the only one with live
+//!   ships or squadrons inside a zone.
+//! - **Deliveries**: points for each of a team's ships reported by a
+//!   `ShipDelivered` event, once per ship.
+//!
+//! An entity's class is the first of its labels (in sorted order) that has
+//! a rule, falling back to its tag name (`Ship`, `Squadron`, ...).
+//!
+//! The keeper reads the resolved state from `next`, so it must run after
+//! the resolvers that apply damage; adding it with
+//! [`Simulation::enable_scoring`](crate::simulation::Simulation::enable_scoring)
+//! places it last. Scores can be read at any point during an episode.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::entity::components::CombatState;
+use crate::entity::{Entity, EntityId, EntityInner, TeamId};
+use crate::output::{Event, Modifier, Output, OutputEnvelope, OutputKind};
+use crate::team_observation::Zone;
+
+use super::combat::area_damage;
+use super::Resolver;
+
+/// A zone whose sole control scores points over time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoredZone {
+    /// Area to hold
+    pub zone: Zone,
+    /// Points per second of sole control
+    pub points_per_second: f32,
+}
+
+/// Points awarded for mission events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoringRules {
+    /// Points for destroying an enemy, by class
+    pub kills: BTreeMap<String, f32>,
+    /// Points deducted for each own entity destroyed, by class
+    pub losses: BTreeMap<String, f32>,
+    /// Zones scored per second of control
+    pub zones: Vec<ScoredZone>,
+    /// Points per ship delivered
+    pub delivery: f32,
+}
+
+impl ScoringRules {
+    /// Creates rules that award nothing.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Awards `points` for destroying an enemy of `class`.
+    #[must_use]
+    pub fn with_kill(mut self, class: impl Into<String>, points: f32) -> Self {
+        self.kills.insert(class.into(), points);
+        self
+    }
+
+    /// Deducts `points` for each own entity of `class` destroyed.
+    #[must_use]
+    pub fn with_loss(mut self, class: impl Into<String>, points: f32) -> Self {
+        self.losses.insert(class.into(), points);
+        self
+    }
+
+    /// Awards `points_per_second` for sole control of `zone`.
+    #[must_use]
+    pub fn with_zone(mut self, zone: Zone, points_per_second: f32) -> Self {
+        self.zones.push(ScoredZone {
+            zone,
+            points_per_second,
+        });
+        self
+    }
+
+    /// Awards `points` per ship delivered.
+    #[must_use]
+    pub const fn with_delivery(mut self, points: f32) -> Self {
+        self.delivery = points;
+        self
+    }
+}
+
+/// One team's score and its breakdown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamScore {
+    /// Team scored
+    pub team: TeamId,
+    /// Total points
+    pub score: f32,
+    /// Enemies destroyed, by class
+    pub kills: BTreeMap<String, u32>,
+    /// Own entities destroyed, by class
+    pub losses: BTreeMap<String, u32>,
+    /// Seconds of sole control, summed over zones
+    pub zone_seconds: f32,
+    /// Ships delivered
+    pub delivered: u32,
+}
+
+impl TeamScore {
+    fn new(team: TeamId) -> Self {
+        Self {
+            team,
+            score: 0.0,
+            kills: BTreeMap::new(),
+            losses: BTreeMap::new(),
+            zone_seconds: 0.0,
+            delivered: 0,
+        }
+    }
+}
+
+/// Scores accumulated so far, plus what is needed to score each entity once.
+///
+/// Opaque; taken with [`ScoreKeeper::state`] to checkpoint an episode.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreState {
+    teams: BTreeMap<TeamId, TeamScore>,
+    /// Team that last damaged each entity
+    attackers: BTreeMap<EntityId, TeamId>,
+    /// Entities whose destruction has been scored
+    destroyed: BTreeSet<EntityId>,
+    /// Ships whose delivery has been scored
+    delivered: BTreeSet<EntityId>,
+}
+
+/// Resolver accumulating per-team mission scores.
+///
+/// Not part of the default resolver set. Share it in an `Arc` to read the
+/// scores while it runs.
+///
+/// # Example
+///
+/// ```
+/// use glam::Vec2;
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+/// use tidebreak_core::resolver::{Resolver, ScoreKeeper, ScoringRules};
+/// use tidebreak_core::team_observation::Zone;
+///
+/// let mut arena = Arena::new();
+/// let ship = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+/// arena.set_team(ship, Some(TeamId::new(0)));
+///
+/// let rules = ScoringRules::new().with_zone(Zone::new(Vec2::ZERO, 100.0), 2.0);
+/// let keeper = ScoreKeeper::new(rules).with_dt(0.5);
+/// let current = arena.clone();
+/// keeper.resolve(&[], &current, &mut arena);
+/// assert_eq!(keeper.score(TeamId::new(0)).unwrap().score, 1.0);
+/// ```
+#[derive(Debug)]
+pub struct ScoreKeeper {
+    rules: ScoringRules,
+    /// Seconds per tick, for zone control
+    dt: f32,
+    state: Mutex<ScoreState>,
+}
+
+impl ScoreKeeper {
+    /// Creates a keeper for `rules` at the default tick of 1/60 s.
+    #[must_use]
+    pub fn new(rules: ScoringRules) -> Self {
+        Self {
+            rules,
+            dt: super::FIXED_DT,
+            state: Mutex::new(ScoreState::default()),
+        }
+    }
+
+    /// Sets the seconds per tick used for zone control.
+    #[must_use]
+    pub const fn with_dt(mut self, dt: f32) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    /// Returns the scoring rules.
+    #[must_use]
+    pub const fn rules(&self) -> &ScoringRules {
+        &self.rules
+    }
+
+    /// Returns every team's score, sorted by team.
+    ///
+    /// Teams appear once they have had an entity in the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn scores(&self) -> Vec<TeamScore> {
+        self.state.lock().unwrap().teams.values().cloned().collect()
+    }
+
+    /// Returns the score of `team`, if it has been seen.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn score(&self, team: TeamId) -> Option<TeamScore> {
+        self.state.lock().unwrap().teams.get(&team).cloned()
+    }
+
+    /// Returns the scores so far and the bookkeeping behind them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn state(&self) -> ScoreState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Replaces the scores with `state`, e.g. to resume from a checkpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn restore(&self, state: ScoreState) {
+        *self.state.lock().unwrap() = state;
+    }
+
+    /// Forgets all scores, e.g. between episodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn clear(&self) {
+        *self.state.lock().unwrap() = ScoreState::default();
+    }
+
+    /// Returns the class of `entity` under `table`.
+    fn class(entity: &Entity, table: &BTreeMap<String, f32>) -> (String, f32) {
+        entity
+            .labels()
+            .iter()
+            .find_map(|label| table.get(label).map(|&points| (label.clone(), points)))
+            .unwrap_or_else(|| {
+                let tag = entity.tag().to_string();
+                let points = table.get(&tag).copied().unwrap_or(0.0);
+                (tag, points)
+            })
+    }
+
+    /// Credits the team of `source` with the next kill of `target`.
+    fn record_attack(state: &mut ScoreState, current: &Arena, source: EntityId, target: EntityId) {
+        let Some(team) = current.get(source).and_then(Entity::team) else {
+            return;
+        };
+        if current.get(target).and_then(Entity::team) != Some(team) {
+            state.attackers.insert(target, team);
+        }
+    }
+}
+
+impl Resolver for ScoreKeeper {
+    fn handles(&self) -> &[OutputKind] {
+        &[OutputKind::Modifier, OutputKind::Event]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        for entity in next.entities_sorted() {
+            if let Some(team) = entity.team() {
+                state.teams.entry(team).or_insert_with(|| TeamScore::new(team));
+            }
+        }
+
+        for envelope in outputs {
+            let source = envelope.source().entity_id();
+            match envelope.output() {
+                Output::Modifier(Modifier::ApplyDamage { target, .. }) => {
+                    Self::record_attack(state, current, source, *target);
+                }
+                Output::Modifier(Modifier::ApplyAreaDamage {
+                    center,
+                    radius,
+                    amount,
+                    falloff,
+                }) => {
+                    for (target, _) in area_damage(current, *center, *radius, *amount, *falloff) {
+                        Self::record_attack(state, current, source, target);
+                    }
+                }
+                Output::Event(Event::EntityDestroyed {
+                    entity,
+                    destroyer: Some(destroyer),
+                    ..
+                }) => Self::record_attack(state, current, *destroyer, *entity),
+                Output::Event(Event::ShipDelivered { ship }) => {
+                    let Some(team) = next.get(*ship).and_then(Entity::team) else {
+                        continue;
+                    };
+                    if state.delivered.insert(*ship) {
+                        let score = state.teams.entry(team).or_insert_with(|| TeamScore::new(team));
+                        score.delivered += 1;
+                        score.score += self.rules.delivery;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Destruction applied this tick by the resolvers before this one
+        for entity in next.entities_sorted() {
+            if !combat(entity).is_some_and(CombatState::is_destroyed)
+                || !state.destroyed.insert(entity.id())
+            {
+                continue;
+            }
+            if let Some(team) = entity.team() {
+                let (class, points) = Self::class(entity, &self.rules.losses);
+                let score = state.teams.entry(team).or_insert_with(|| TeamScore::new(team));
+                *score.losses.entry(class).or_default() += 1;
+                score.score -= points;
+            }
+            if let Some(&team) = state.attackers.get(&entity.id()) {
+                let (class, points) = Self::class(entity, &self.rules.kills);
+                let score = state.teams.entry(team).or_insert_with(|| TeamScore::new(team));
+                *score.kills.entry(class).or_default() += 1;
+                score.score += points;
+            }
+        }
+
+        for scored in &self.rules.zones {
+            let holders: BTreeSet<TeamId> = next
+                .entities_sorted()
+                .filter(|e| combat(e).is_some_and(|c| !c.is_destroyed()))
+                .filter(|e| position(e).is_some_and(|p| scored.zone.contains(p)))
+                .filter_map(Entity::team)
+                .collect();
+            if let [team] = holders.into_iter().collect::<Vec<_>>()[..] {
+                let score = state.teams.entry(team).or_insert_with(|| TeamScore::new(team));
+                score.zone_seconds += self.dt;
+                score.score += scored.points_per_second * self.dt;
+            }
+        }
+
+        // Forget entities that have been removed
+        state.attackers.retain(|id, _| next.get(*id).is_some());
+        state.destroyed.retain(|id| next.get(*id).is_some());
+    }
+}
+
+/// Returns the combat state of a ship or squadron.
+pub(super) const fn combat(entity: &Entity) -> Option<&CombatState> {
+    match entity.inner() {
+        EntityInner::Ship(c) => Some(&c.combat),
+        EntityInner::Squadron(c) => Some(&c.combat),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+    }
+}
+
+/// Returns the position of a ship or squadron.
+pub(super) const fn position(entity: &Entity) -> Option<Vec2> {
+    match entity.inner() {
+        EntityInner::Ship(c) => Some(c.transform.position),
+        EntityInner::Squadron(c) => Some(c.transform.position),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::StatusFlags;
+    use crate::entity::DamageType;
+    use crate::output::{PluginId, PluginInstanceId, TraceId};
+    use crate::tests::spawn_team_ship;
+
+    fn envelope(output: Output, source: EntityId) -> OutputEnvelope {
+        OutputEnvelope::new(
+            output,
+            PluginInstanceId::new(source, PluginId::new("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn destroy(arena: &mut Arena, id: EntityId) {
+        let ship = arena.get_mut(id).unwrap().as_ship_mut().unwrap();
+        ship.combat.hp = 0.0;
+        ship.combat.status_flags.insert(StatusFlags::DESTROYED);
+    }
+
+    #[test]
+    fn kills_and_losses_by_class() {
+        let mut arena = Arena::new();
+        let blue = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let merchant = spawn_team_ship(&mut arena, Vec2::new(500.0, 0.0), Some(1));
+        arena.add_label(merchant, "merchant");
+        let rules = ScoringRules::new()
+            .with_kill("merchant", 10.0)
+            .with_kill("Ship", 3.0)
+            .with_loss("Ship", 4.0);
+        let keeper = ScoreKeeper::new(rules);
+
+        let hit = envelope(
+            Output::Modifier(Modifier::ApplyDamage {
+                target: merchant,
+                amount: 1000.0,
+                damage_type: DamageType::Kinetic,
+            }),
+            blue,
+        );
+        let current = arena.clone();
+        destroy(&mut arena, merchant);
+        keeper.resolve(&[&hit], &current, &mut arena);
+        // Scored once, even though the wreck stays in the arena
+        let current = arena.clone();
+        keeper.resolve(&[], &current, &mut arena);
+
+        let blue_score = keeper.score(TeamId::new(0)).unwrap();
+        assert_eq!(blue_score.score, 10.0);
+        assert_eq!(blue_score.kills.get("merchant"), Some(&1));
+        let red_score = keeper.score(TeamId::new(1)).unwrap();
+        assert_eq!(red_score.score, -4.0);
+        assert_eq!(red_score.losses.get("Ship"), Some(&1));
+    }
+
+    #[test]
+    fn contested_zones_score_nothing() {
+        let mut arena = Arena::new();
+        spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let red = spawn_team_ship(&mut arena, Vec2::new(50.0, 0.0), Some(1));
+        let rules = ScoringRules::new().with_zone(Zone::new(Vec2::ZERO, 100.0), 1.0);
+        let keeper = ScoreKeeper::new(rules).with_dt(1.0);
+
+        let current = arena.clone();
+        keeper.resolve(&[], &current, &mut arena);
+        assert_eq!(keeper.score(TeamId::new(0)).unwrap().zone_seconds, 0.0);
+
+        destroy(&mut arena, red);
+        let current = arena.clone();
+        keeper.resolve(&[], &current, &mut arena);
+        assert_eq!(keeper.score(TeamId::new(0)).unwrap().zone_seconds, 1.0);
+    }
+
+    #[test]
+    fn deliveries_count_once_per_ship() {
+        let mut arena = Arena::new();
+        let merchant = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let keeper = ScoreKeeper::new(ScoringRules::new().with_delivery(5.0));
+        let delivered = envelope(Output::Event(Event::ShipDelivered { ship: merchant }), merchant);
+
+        for _ in 0..2 {
+            let current = arena.clone();
+            keeper.resolve(&[&delivered], &current, &mut arena);
+        }
+        let score = keeper.score(TeamId::new(0)).unwrap();
+        assert_eq!((score.delivered, score.score), (1, 5.0));
+
+        keeper.clear();
+        assert!(keeper.scores().is_empty());
+    }
+}
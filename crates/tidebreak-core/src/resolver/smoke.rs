@@ -0,0 +1,284 @@
+//! Smoke resolver for ships laying smoke screens.
+//!
+//! Each tick the `SmokeResolver`:
+//!
+//! 1. **Deploys**: every `DeploySmoke` command uses one
+//!    [`AmmoType::Smoke`] charge from the ship's inventory and registers a
+//!    [`SmokeEmitter`] for the ordered number of ticks.
+//! 2. **Lays**: each emitter drops a [`SmokePuff`] astern of its ship
+//!    every [`SmokeEmitter::PUFF_INTERVAL`] ticks, so a moving ship leaves
+//!    a wall of smoke along its wake. Emitters stop early if their ship is
+//!    destroyed, removed or submerges.
+//! 3. **Drifts**: if the arena has a [`Wind`], clouds already laid move
+//!    downwind at the wind speed, so a screen laid upwind of a target
+//!    blows across it.
+//! 4. **Disperses**: clouds older than [`SmokePuff::LIFETIME`] are removed.
+//!
+//! Emitters and clouds live in the arena's
+//! [`Environment`](crate::environment::Environment), where they screen the
+//! visual detection channel and weapon targeting.
+//!
+//! [`AmmoType::Smoke`]: crate::entity::AmmoType::Smoke
+//! [`Wind`]: crate::wind::Wind
+
+use glam::Vec2;
+
+use crate::arena::Arena;
+use crate::entity::{AmmoType, Entity, EntityId};
+use crate::environment::{SmokeEmitter, SmokePuff};
+use crate::output::{Command, OutputEnvelope, OutputKind};
+
+use super::{Resolver, FIXED_DT};
+
+/// Resolver laying and dispersing smoke screens.
+///
+/// Part of the default resolver set; it does nothing until a ship deploys
+/// smoke.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{AmmoType, EntityInner, EntityTag, ShipComponents};
+/// use tidebreak_core::output::{Command, Output, OutputEnvelope, PluginId, PluginInstanceId, TraceId};
+/// use tidebreak_core::resolver::{Resolver, SmokeResolver};
+///
+/// let mut arena = Arena::new();
+/// let mut ship = ShipComponents::default();
+/// ship.inventory.ammo.insert(AmmoType::Smoke, 1);
+/// let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+/// let order = OutputEnvelope::new(
+///     Output::Command(Command::DeploySmoke { source: id, duration: 600 }),
+///     PluginInstanceId::new(id, PluginId::from_static("agent")),
+///     TraceId::new(0),
+///     0,
+///     0,
+/// );
+///
+/// let current = arena.clone();
+/// SmokeResolver::new().resolve(&[&order], &current, &mut arena);
+/// assert_eq!(arena.environment().screens.len(), 1);
+/// assert_eq!(arena.environment().emitters.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SmokeResolver;
+
+impl SmokeResolver {
+    /// Creates a new smoke resolver.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Starts `source` laying smoke for `duration` ticks.
+    ///
+    /// Returns `false`, changing nothing, if the source is not a live
+    /// surfaced ship, is already laying smoke, has no smoke charges left,
+    /// or `duration` is zero.
+    pub fn deploy(arena: &mut Arena, source: EntityId, duration: u32) -> bool {
+        if duration == 0
+            || arena
+                .environment()
+                .emitters
+                .iter()
+                .any(|emitter| emitter.source == source)
+            || Self::stern(arena, source).is_none()
+        {
+            return false;
+        }
+        let Some(ship) = arena.get_mut(source).and_then(Entity::as_ship_mut) else {
+            return false;
+        };
+        if !ship.inventory.consume_ammo(AmmoType::Smoke, 1) {
+            return false;
+        }
+        arena
+            .environment_mut()
+            .emitters
+            .push(SmokeEmitter::new(source, duration));
+        true
+    }
+
+    /// Returns the point astern of a live, surfaced ship where its smoke
+    /// is laid.
+    fn stern(arena: &Arena, id: EntityId) -> Option<Vec2> {
+        let ship = arena.get(id).and_then(Entity::as_ship)?;
+        if ship.combat.is_destroyed() || ship.submarine.is_some_and(|sub| sub.is_submerged()) {
+            return None;
+        }
+        Some(ship.transform.position - ship.transform.forward() * ship.transform.radius)
+    }
+
+    /// Lays this tick's clouds, drifts and disperses old ones.
+    fn advance(next: &mut Arena) {
+        let tick = next.current_tick();
+        let emitters = std::mem::take(&mut next.environment_mut().emitters);
+        let mut active = Vec::with_capacity(emitters.len());
+        let mut puffs = Vec::new();
+        for mut emitter in emitters {
+            let Some(stern) = Self::stern(next, emitter.source) else {
+                continue;
+            };
+            if emitter.elapsed % SmokeEmitter::PUFF_INTERVAL == 0 {
+                puffs.push(SmokePuff::new(stern, tick));
+            }
+            emitter.elapsed = emitter.elapsed.saturating_add(1);
+            emitter.remaining = emitter.remaining.saturating_sub(1);
+            if emitter.remaining > 0 {
+                active.push(emitter);
+            }
+        }
+
+        let drift = next.wind().map_or(Vec2::ZERO, |wind| wind.velocity * FIXED_DT);
+        let environment = next.environment_mut();
+        environment.emitters = active;
+        environment.screens.retain(|puff| puff.expires > tick);
+        for puff in &mut environment.screens {
+            puff.position += drift;
+        }
+        environment.screens.extend(puffs);
+    }
+}
+
+impl Resolver for SmokeResolver {
+    fn handles(&self) -> &[OutputKind] {
+        // Laying and dispersing are driven by world state; only deployment
+        // orders are commands
+        &[OutputKind::Command]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], _current: &Arena, next: &mut Arena) {
+        for envelope in outputs {
+            if let Some(Command::DeploySmoke { source, duration }) = envelope.output().as_command()
+            {
+                Self::deploy(next, *source, *duration);
+            }
+        }
+        if !next.environment().emitters.is_empty() || !next.environment().screens.is_empty() {
+            Self::advance(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityInner, EntityTag, ShipComponents, SubmarineState};
+    use crate::output::{Output, PluginId, PluginInstanceId, TraceId};
+
+    fn smoke_ship(arena: &mut Arena, charges: u32) -> EntityId {
+        let mut ship = ShipComponents::at_position(Vec2::ZERO, 0.0);
+        ship.transform.radius = 10.0;
+        ship.inventory.ammo.insert(AmmoType::Smoke, charges);
+        arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+    }
+
+    fn deploy(source: EntityId, duration: u32) -> OutputEnvelope {
+        OutputEnvelope::new(
+            Output::Command(Command::DeploySmoke { source, duration }),
+            PluginInstanceId::new(source, PluginId::from_static("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn resolve(arena: &mut Arena, outputs: &[OutputEnvelope]) {
+        let current = arena.clone();
+        let refs: Vec<&OutputEnvelope> = outputs.iter().collect();
+        SmokeResolver::new().resolve(&refs, &current, arena);
+    }
+
+    fn charges(arena: &Arena, id: EntityId) -> u32 {
+        arena
+            .get(id)
+            .and_then(Entity::as_ship)
+            .unwrap()
+            .inventory
+            .get_ammo(AmmoType::Smoke)
+    }
+
+    #[test]
+    fn deploying_uses_a_charge_and_lays_astern() {
+        let mut arena = Arena::new();
+        let id = smoke_ship(&mut arena, 2);
+
+        resolve(&mut arena, &[deploy(id, 60)]);
+        assert_eq!(charges(&arena, id), 1);
+        let screens = &arena.environment().screens;
+        assert_eq!(screens.len(), 1);
+        assert_eq!(screens[0].position, Vec2::new(-10.0, 0.0));
+    }
+
+    #[test]
+    fn emitter_lays_at_intervals_then_stops() {
+        let mut arena = Arena::new();
+        let id = smoke_ship(&mut arena, 1);
+
+        resolve(&mut arena, &[deploy(id, 60)]);
+        for _ in 1..60 {
+            resolve(&mut arena, &[]);
+        }
+        assert!(arena.environment().emitters.is_empty());
+        assert_eq!(arena.environment().screens.len(), 2);
+    }
+
+    #[test]
+    fn already_laying_or_empty_ignores_order() {
+        let mut arena = Arena::new();
+        let id = smoke_ship(&mut arena, 2);
+        let empty = smoke_ship(&mut arena, 0);
+
+        resolve(&mut arena, &[deploy(id, 600), deploy(id, 600), deploy(empty, 600)]);
+        assert_eq!(charges(&arena, id), 1);
+        assert_eq!(arena.environment().emitters.len(), 1);
+        assert!(!SmokeResolver::deploy(&mut arena, id, 0));
+    }
+
+    #[test]
+    fn submerged_boats_cannot_lay_smoke() {
+        let mut arena = Arena::new();
+        let id = smoke_ship(&mut arena, 1);
+        arena.get_mut(id).unwrap().as_ship_mut().unwrap().submarine = Some(SubmarineState {
+            depth: 50.0,
+            ..SubmarineState::default()
+        });
+
+        resolve(&mut arena, &[deploy(id, 600)]);
+        assert_eq!(charges(&arena, id), 1);
+        assert!(arena.environment().screens.is_empty());
+    }
+
+    #[test]
+    fn clouds_disperse() {
+        let mut arena = Arena::new();
+        let fresh = SmokePuff::new(Vec2::ZERO, 0);
+        let spent = SmokePuff {
+            expires: arena.current_tick(),
+            ..fresh
+        };
+        arena.environment_mut().screens.extend([spent, fresh]);
+
+        resolve(&mut arena, &[]);
+        assert_eq!(arena.environment().screens, vec![fresh]);
+    }
+
+    #[test]
+    fn wind_carries_laid_clouds_downwind() {
+        use crate::wind::Wind;
+
+        let mut arena = Arena::new();
+        let id = smoke_ship(&mut arena, 1);
+        arena.set_wind(Some(Wind::from_velocity(Vec2::new(0.0, 6.0))));
+
+        resolve(&mut arena, &[deploy(id, 60)]);
+        assert_eq!(arena.environment().screens[0].position, Vec2::new(-10.0, 0.0));
+        for _ in 0..60 {
+            resolve(&mut arena, &[]);
+        }
+        // One second of wind carries the first cloud 6 m downwind
+        let screens = &arena.environment().screens;
+        assert!((screens[0].position - Vec2::new(-10.0, 6.0)).length() < 1e-3);
+        assert!(screens[1].position.y < 6.0);
+    }
+}
@@ -0,0 +1,392 @@
+//! Submarine resolver for depth, battery and crush damage.
+//!
+//! Submarines are ships carrying a [`SubmarineState`]. Each tick the
+//! `SubmarineResolver`:
+//!
+//! 1. **Orders depth**: every `SetDepth` command sets its submarine's
+//!    ordered depth. Negative depths mean the surface; non-finite depths
+//!    are ignored.
+//! 2. **Dives**: each submarine moves toward its ordered depth at its dive
+//!    rate.
+//! 3. **Charges**: a submerged boat drains its battery with speed; a
+//!    snorkeling or surfaced boat recharges it. A boat whose battery runs
+//!    flat while submerged loses propulsion and is forced up to snorkel
+//!    depth.
+//! 4. **Crushes**: a boat below crush depth takes damage every tick until
+//!    it rises or is destroyed.
+//!
+//! Battery and damage use the depth at the start of the tick. Crush damage
+//! is reported as `DamageDealt` events (the submarine is both source and
+//! target) in the event log given to
+//! [`with_event_log`](SubmarineResolver::with_event_log).
+//!
+//! [`SubmarineState`]: crate::entity::SubmarineState
+
+use std::sync::Arc;
+
+use glam::Vec2;
+
+use crate::arena::Arena;
+use crate::entity::{Entity, EntityId, StatusFlags};
+use crate::output::{
+    Command, Event, Output, OutputEnvelope, OutputKind, PluginId, PluginInstanceId,
+};
+
+use super::physics::FIXED_DT;
+use super::{EventResolver, Resolver};
+
+/// Resolver diving, charging and crushing submarines.
+///
+/// Part of the default resolver set; it does nothing without submarines.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, SubmarineState};
+/// use tidebreak_core::output::{Command, Output, OutputEnvelope, PluginId, PluginInstanceId, TraceId};
+/// use tidebreak_core::resolver::{Resolver, SubmarineResolver};
+///
+/// let mut arena = Arena::new();
+/// let id = arena.spawn(
+///     EntityTag::Ship,
+///     EntityInner::Ship(ShipComponents::default().with_submarine(SubmarineState::new())),
+/// );
+/// let order = OutputEnvelope::new(
+///     Output::Command(Command::SetDepth { target: id, depth: 50.0 }),
+///     PluginInstanceId::new(id, PluginId::from_static("agent")),
+///     TraceId::new(0),
+///     0,
+///     0,
+/// );
+///
+/// let resolver = SubmarineResolver::new();
+/// let current = arena.clone();
+/// resolver.resolve(&[&order], &current, &mut arena);
+/// let sub = arena.get(id).unwrap().submarine().unwrap();
+/// assert_eq!(sub.ordered_depth, 50.0);
+/// assert!(sub.depth > 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubmarineResolver {
+    /// Seconds per tick
+    dt: f32,
+    /// Log receiving crush damage events
+    events: Option<Arc<EventResolver>>,
+}
+
+impl SubmarineResolver {
+    /// Creates a resolver with the standard timestep and no event log.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_dt(FIXED_DT)
+    }
+
+    /// Creates a resolver with a custom timestep.
+    #[must_use]
+    pub const fn with_dt(dt: f32) -> Self {
+        Self { dt, events: None }
+    }
+
+    /// Records crush damage into `events`.
+    #[must_use]
+    pub fn with_event_log(mut self, events: Arc<EventResolver>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Returns the timestep.
+    #[must_use]
+    pub const fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    /// Sets the ordered depth of a live submarine.
+    fn apply_set_depth(next: &mut Arena, target: EntityId, depth: f32) {
+        if !depth.is_finite() {
+            return;
+        }
+        let Some(ship) = next.get_mut(target).and_then(Entity::as_ship_mut) else {
+            return;
+        };
+        if ship.combat.is_destroyed() {
+            return;
+        }
+        if let Some(sub) = ship.submarine.as_mut() {
+            sub.ordered_depth = depth.max(0.0);
+        }
+    }
+
+    /// Advances one submarine by a tick.
+    ///
+    /// Returns the crush damage dealt, if any.
+    fn advance(&self, next: &mut Arena, id: EntityId) -> Option<f32> {
+        let ship = next.get_mut(id).and_then(Entity::as_ship_mut)?;
+        if ship.combat.is_destroyed() {
+            return None;
+        }
+        let speed_fraction = ship.speed_fraction();
+        let sub = ship.submarine.as_mut()?;
+        let start = *sub;
+
+        // Battery
+        if start.is_submerged() {
+            sub.battery = (sub.battery - sub.drain(speed_fraction) * self.dt).max(0.0);
+        } else {
+            sub.battery = (sub.battery + sub.recharge_rate * self.dt).min(sub.max_battery);
+        }
+        let flat = start.is_submerged() && sub.battery <= 0.0;
+        if flat {
+            sub.ordered_depth = sub.ordered_depth.min(sub.snorkel_depth);
+        }
+
+        // Depth
+        let step = sub.dive_rate.max(0.0) * self.dt;
+        let offset = sub.ordered_depth - sub.depth;
+        sub.depth += offset.clamp(-step, step);
+
+        // Propulsion is lost on a flat battery
+        if flat {
+            ship.physics.velocity = Vec2::ZERO;
+            ship.physics.throttle = None;
+        }
+
+        // Crush depth
+        if start.depth <= start.crush_depth {
+            return None;
+        }
+        let damage = start.crush_damage_rate * self.dt;
+        ship.combat.hp -= damage;
+        if ship.combat.hp <= 0.0 {
+            ship.combat.hp = 0.0;
+            ship.combat.status_flags.insert(StatusFlags::DESTROYED);
+        }
+        Some(damage)
+    }
+
+    /// Records an event, if an event log is attached.
+    fn record(&self, next: &mut Arena, entity: EntityId, event: Event) {
+        if let Some(events) = &self.events {
+            events.record(OutputEnvelope::new(
+                Output::Event(event),
+                PluginInstanceId::new(entity, PluginId::from_static("submarine")),
+                next.new_trace_id(),
+                next.current_tick(),
+                0,
+            ));
+        }
+    }
+}
+
+impl Default for SubmarineResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver for SubmarineResolver {
+    fn handles(&self) -> &[OutputKind] {
+        // Diving, charging and crushing are driven by world state; only depth
+        // orders are commands
+        &[OutputKind::Command]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        for envelope in outputs {
+            if let Some(Command::SetDepth { target, depth }) = envelope.output().as_command() {
+                Self::apply_set_depth(next, *target, *depth);
+            }
+        }
+
+        let submarines: Vec<EntityId> = current
+            .entities_sorted()
+            .filter(|entity| entity.submarine().is_some())
+            .map(Entity::id)
+            .collect();
+        for id in submarines {
+            if let Some(amount) = self.advance(next, id) {
+                self.record(
+                    next,
+                    id,
+                    Event::DamageDealt {
+                        source: id,
+                        target: id,
+                        amount,
+                    },
+                );
+            }
+        }
+    }
+
+    fn set_dt(&mut self, dt: f32) {
+        self.dt = dt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityInner, EntityTag, ShipComponents, SubmarineState};
+    use crate::output::TraceId;
+
+    fn submarine(arena: &mut Arena, state: SubmarineState) -> EntityId {
+        arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::default().with_submarine(state)),
+        )
+    }
+
+    fn at_depth(depth: f32) -> SubmarineState {
+        SubmarineState {
+            depth,
+            ordered_depth: depth,
+            ..SubmarineState::default()
+        }
+    }
+
+    fn state(arena: &Arena, id: EntityId) -> SubmarineState {
+        *arena.get(id).unwrap().submarine().unwrap()
+    }
+
+    fn set_depth(target: EntityId, depth: f32) -> OutputEnvelope {
+        OutputEnvelope::new(
+            Output::Command(Command::SetDepth { target, depth }),
+            PluginInstanceId::new(target, PluginId::from_static("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn resolve(resolver: &SubmarineResolver, arena: &mut Arena, outputs: &[OutputEnvelope]) {
+        let current = arena.clone();
+        let refs: Vec<&OutputEnvelope> = outputs.iter().collect();
+        resolver.resolve(&refs, &current, arena);
+    }
+
+    mod depth_tests {
+        use super::*;
+
+        #[test]
+        fn dives_at_dive_rate() {
+            let mut arena = Arena::new();
+            let id = submarine(&mut arena, SubmarineState::default());
+            let resolver = SubmarineResolver::with_dt(1.0);
+
+            resolve(&resolver, &mut arena, &[set_depth(id, 5.0)]);
+            assert_eq!(state(&arena, id).depth, 2.0);
+            resolve(&resolver, &mut arena, &[]);
+            resolve(&resolver, &mut arena, &[]);
+            assert_eq!(state(&arena, id).depth, 5.0);
+        }
+
+        #[test]
+        fn rises_to_surface_and_ignores_bad_orders() {
+            let mut arena = Arena::new();
+            let id = submarine(&mut arena, at_depth(1.0));
+            let resolver = SubmarineResolver::with_dt(1.0);
+
+            resolve(&resolver, &mut arena, &[set_depth(id, -20.0)]);
+            assert_eq!(state(&arena, id).ordered_depth, 0.0);
+            assert!(state(&arena, id).is_surfaced());
+
+            resolve(&resolver, &mut arena, &[set_depth(id, f32::NAN)]);
+            assert_eq!(state(&arena, id).ordered_depth, 0.0);
+        }
+
+        #[test]
+        fn surface_ships_ignore_depth_orders() {
+            let mut arena = Arena::new();
+            let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+            let before = arena.clone();
+
+            resolve(&SubmarineResolver::new(), &mut arena, &[set_depth(id, 50.0)]);
+            assert_eq!(arena.get(id), before.get(id));
+        }
+    }
+
+    mod battery_tests {
+        use super::*;
+
+        #[test]
+        fn submerged_drains_and_snorkeling_recharges() {
+            let mut arena = Arena::new();
+            let deep = submarine(&mut arena, at_depth(100.0).with_battery(100.0));
+            let snorkel = submarine(&mut arena, at_depth(10.0).with_battery(100.0));
+            arena
+                .get_mut(snorkel)
+                .unwrap()
+                .as_ship_mut()
+                .unwrap()
+                .submarine
+                .as_mut()
+                .unwrap()
+                .battery = 50.0;
+            let resolver = SubmarineResolver::with_dt(1.0);
+
+            resolve(&resolver, &mut arena, &[]);
+            let hotel = SubmarineState::HOTEL_LOAD;
+            assert!((state(&arena, deep).battery - (100.0 - hotel)).abs() < 1e-4);
+            assert_eq!(state(&arena, snorkel).battery, 52.0);
+        }
+
+        #[test]
+        fn flat_battery_forces_ascent_and_stops_motors() {
+            let mut arena = Arena::new();
+            let mut sub = at_depth(100.0);
+            sub.battery = 0.05;
+            let id = submarine(&mut arena, sub);
+            let ship = arena.get_mut(id).unwrap().as_ship_mut().unwrap();
+            ship.physics.velocity = Vec2::new(5.0, 0.0);
+            ship.physics.throttle = Some(0.5);
+
+            resolve(&SubmarineResolver::with_dt(1.0), &mut arena, &[]);
+            let ship = arena.get(id).unwrap().as_ship().unwrap();
+            let sub = ship.submarine.unwrap();
+            assert_eq!(sub.battery, 0.0);
+            assert_eq!(sub.ordered_depth, sub.snorkel_depth);
+            assert_eq!(sub.depth, 98.0);
+            assert_eq!(ship.physics.velocity, Vec2::ZERO);
+            assert_eq!(ship.physics.throttle, None);
+        }
+    }
+
+    mod crush_tests {
+        use super::*;
+
+        #[test]
+        fn below_crush_depth_takes_damage_until_destroyed() {
+            let events = Arc::new(EventResolver::new());
+            let resolver = SubmarineResolver::with_dt(1.0).with_event_log(Arc::clone(&events));
+            let mut arena = Arena::new();
+            let id = submarine(
+                &mut arena,
+                SubmarineState {
+                    crush_damage_rate: 60.0,
+                    ..at_depth(400.0)
+                },
+            );
+
+            resolve(&resolver, &mut arena, &[]);
+            assert_eq!(arena.get(id).unwrap().as_ship().unwrap().combat.hp, 40.0);
+            resolve(&resolver, &mut arena, &[]);
+            let combat = &arena.get(id).unwrap().as_ship().unwrap().combat;
+            assert_eq!(combat.hp, 0.0);
+            assert!(combat.is_destroyed());
+            assert_eq!(events.event_count(), 2);
+
+            // Wrecks are left alone
+            resolve(&resolver, &mut arena, &[]);
+            assert_eq!(events.event_count(), 2);
+        }
+
+        #[test]
+        fn above_crush_depth_is_safe() {
+            let mut arena = Arena::new();
+            let id = submarine(&mut arena, at_depth(299.0));
+
+            resolve(&SubmarineResolver::with_dt(1.0), &mut arena, &[]);
+            assert_eq!(arena.get(id).unwrap().as_ship().unwrap().combat.hp, 100.0);
+        }
+    }
+}
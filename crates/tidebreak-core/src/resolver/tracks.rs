@@ -0,0 +1,337 @@
+//! Track resolver maintaining sensor track tables.
+//!
+//! The `TrackResolver` turns the tick's `ContactDetected` events into
+//! updates of each observer's track table:
+//!
+//! 1. **Refreshes** the track of every detected target with a fix at the
+//!    target's position (see [`Track::record_fix`]), at the best quality it
+//!    was detected at this tick. A target without a track gets a new one.
+//! 2. **Ages** every other track by one tick, dropping it once it is older
+//!    than the resolver's maximum age, if it has one.
+//!
+//! Each table updated gets a new generation (see
+//! [`SensorState::touch_tracks`]).
+//!
+//! Fixes are timed in seconds of simulation time, so after a few detections
+//! the history gives the course and speed estimates used for gun laying and
+//! observations.
+//!
+//! Guided projectiles track a single target: the resolver also records the
+//! `LockAcquired` and `LockLost` events of their seekers in
+//! [`SeekerState::lock`](crate::entity::SeekerState::lock).
+
+use std::collections::BTreeMap;
+
+use crate::arena::Arena;
+use crate::entity::components::{SensorState, Track, TrackQuality};
+use crate::entity::{Entity, EntityId, EntityInner};
+use crate::output::{Event, OutputEnvelope, OutputKind};
+
+use super::physics::FIXED_DT;
+use super::Resolver;
+
+/// Resolver keeping track tables up to date with sensor detections.
+///
+/// Part of the default resolver set.
+///
+/// # Example
+///
+/// ```
+/// use glam::Vec2;
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TrackQuality};
+/// use tidebreak_core::output::{
+///     Event, Output, OutputEnvelope, PluginId, PluginInstanceId, TraceId,
+/// };
+/// use tidebreak_core::resolver::{Resolver, TrackResolver};
+///
+/// let mut arena = Arena::new();
+/// let observer = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+/// let target = ShipComponents::at_position(Vec2::new(500.0, 0.0), 0.0);
+/// let target = arena.spawn(EntityTag::Ship, EntityInner::Ship(target));
+///
+/// let detection = OutputEnvelope::new(
+///     Output::Event(Event::ContactDetected {
+///         observer,
+///         target,
+///         quality: TrackQuality::Coarse,
+///     }),
+///     PluginInstanceId::new(observer, PluginId::new("sensor")),
+///     TraceId::new(0),
+///     0,
+///     0,
+/// );
+/// let current = arena.clone();
+/// TrackResolver::new().resolve(&[&detection], &current, &mut arena);
+///
+/// let sensor = &arena.get(observer).unwrap().as_ship().unwrap().sensor;
+/// assert_eq!(sensor.find_track(target).unwrap().position, Vec2::new(500.0, 0.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrackResolver {
+    /// Seconds per tick, for fix times and track ages
+    dt: f32,
+    /// Age in seconds past which undetected tracks are dropped
+    max_age: Option<f32>,
+}
+
+impl TrackResolver {
+    /// Creates a new track resolver for the default timestep, keeping
+    /// tracks however old they get.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_dt(FIXED_DT)
+    }
+
+    /// Creates a new track resolver for a custom timestep.
+    #[must_use]
+    pub const fn with_dt(dt: f32) -> Self {
+        Self { dt, max_age: None }
+    }
+
+    /// Builder method to drop tracks not refreshed for more than `max_age`
+    /// seconds.
+    #[must_use]
+    pub const fn with_max_age(mut self, max_age: f32) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Returns the seconds simulated per tick.
+    #[must_use]
+    pub const fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    /// Returns the age past which undetected tracks are dropped, if any.
+    #[must_use]
+    pub const fn max_age(&self) -> Option<f32> {
+        self.max_age
+    }
+
+    /// Ages `sensor`'s tracks and refreshes those in `detected` with the
+    /// positions of their targets in `current`.
+    fn update(
+        &self,
+        sensor: &mut SensorState,
+        detected: &BTreeMap<EntityId, TrackQuality>,
+        current: &Arena,
+        time: f32,
+    ) {
+        for (&target, &quality) in detected {
+            let Some(position) = current.spatial().get(target) else {
+                continue;
+            };
+            let index = sensor
+                .track_table
+                .iter()
+                .position(|t| t.target_id == target)
+                .unwrap_or_else(|| {
+                    sensor.track_table.push(Track::new(target, position, quality));
+                    sensor.track_table.len() - 1
+                });
+            let track = &mut sensor.track_table[index];
+            track.record_fix(time, position);
+            track.quality = quality;
+        }
+        for track in &mut sensor.track_table {
+            if !detected.contains_key(&track.target_id) {
+                track.age += self.dt;
+            }
+        }
+        if let Some(max_age) = self.max_age {
+            sensor.track_table.retain(|t| t.age <= max_age);
+        }
+        sensor.touch_tracks();
+    }
+}
+
+impl Default for TrackResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the sensor suite of a ship or platform.
+fn sensor_mut(entity: &mut Entity) -> Option<&mut SensorState> {
+    match entity.inner_mut() {
+        EntityInner::Ship(c) => Some(&mut c.sensor),
+        EntityInner::Platform(c) => Some(&mut c.sensor),
+        EntityInner::Projectile(_) | EntityInner::Squadron(_) => None,
+    }
+}
+
+/// Sets the lock of `seeker`'s seeker head, if it is a guided projectile.
+fn set_lock(next: &mut Arena, seeker: EntityId, lock: Option<EntityId>) {
+    let head = next.get_mut(seeker).and_then(|e| e.as_projectile_mut()?.seeker.as_mut());
+    if let Some(head) = head {
+        head.lock = lock;
+    }
+}
+
+impl Resolver for TrackResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[OutputKind::Event]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        // Best quality per (observer, target) this tick
+        let mut detections: BTreeMap<EntityId, BTreeMap<EntityId, TrackQuality>> =
+            BTreeMap::new();
+        for envelope in outputs {
+            match envelope.output().as_event() {
+                Some(Event::ContactDetected {
+                    observer,
+                    target,
+                    quality,
+                }) => {
+                    let best = detections
+                        .entry(*observer)
+                        .or_default()
+                        .entry(*target)
+                        .or_insert(*quality);
+                    *best = (*best).max(*quality);
+                }
+                // In emission order, so a lost lock re-acquired in the same
+                // tick ends up held
+                Some(Event::LockAcquired { seeker, target }) => {
+                    set_lock(next, *seeker, Some(*target));
+                }
+                Some(Event::LockLost { seeker, .. }) => set_lock(next, *seeker, None),
+                _ => {}
+            }
+        }
+
+        // Computed in f64 so fix times stay precise over long runs
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+        let time = (current.current_tick() as f64 * f64::from(self.dt)) as f32;
+        let none = BTreeMap::new();
+        let observers: Vec<EntityId> = current.entities_sorted().map(Entity::id).collect();
+        for id in observers {
+            let Some(sensor) = next.get_mut(id).and_then(sensor_mut) else {
+                continue;
+            };
+            if sensor.track_table.is_empty() && !detections.contains_key(&id) {
+                continue;
+            }
+            let detected = detections.get(&id).unwrap_or(&none);
+            self.update(sensor, detected, current, time);
+        }
+    }
+
+    fn set_dt(&mut self, dt: f32) {
+        self.dt = dt;
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityTag, ProjectileComponents, SeekerState, ShipComponents};
+    use crate::output::{Output, PluginId, PluginInstanceId, TraceId};
+    use glam::Vec2;
+
+    fn detection(observer: EntityId, target: EntityId, quality: TrackQuality) -> OutputEnvelope {
+        OutputEnvelope::new(
+            Output::Event(Event::ContactDetected {
+                observer,
+                target,
+                quality,
+            }),
+            PluginInstanceId::new(observer, PluginId::new("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn tracks(arena: &Arena, observer: EntityId) -> &[Track] {
+        &arena.get(observer).unwrap().as_ship().unwrap().sensor.track_table
+    }
+
+    #[test]
+    fn detections_record_fixes_at_best_quality() {
+        let mut arena = Arena::new();
+        let observer = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+        let target = ShipComponents::at_position(Vec2::new(800.0, 0.0), 0.0);
+        let target = arena.spawn(EntityTag::Ship, EntityInner::Ship(target));
+        let resolver = TrackResolver::with_dt(1.0);
+
+        for x in [800.0, 810.0, 820.0] {
+            arena.get_mut(target).unwrap().as_ship_mut().unwrap().transform.position =
+                Vec2::new(x, 0.0);
+            arena.update_spatial(target);
+            let radar = detection(observer, target, TrackQuality::Coarse);
+            let visual = detection(observer, target, TrackQuality::FireControl);
+            let current = arena.clone();
+            resolver.resolve(&[&radar, &visual], &current, &mut arena);
+            arena.advance_tick();
+        }
+
+        let track = &tracks(&arena, observer)[0];
+        assert_eq!(track.quality, TrackQuality::FireControl);
+        assert_eq!(track.history.len(), 3);
+        assert!((track.velocity.unwrap().x - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn undetected_tracks_age_and_expire() {
+        let mut arena = Arena::new();
+        let mut ship = ShipComponents::default();
+        let lost = EntityId::new(99);
+        ship.sensor.track_table.push(Track::new(lost, Vec2::ZERO, TrackQuality::Coarse));
+        let observer = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+        let resolver = TrackResolver::with_dt(1.0).with_max_age(1.5);
+
+        let current = arena.clone();
+        resolver.resolve(&[], &current, &mut arena);
+        assert!((tracks(&arena, observer)[0].age - 1.0).abs() < 1e-6);
+
+        let current = arena.clone();
+        resolver.resolve(&[], &current, &mut arena);
+        assert!(tracks(&arena, observer).is_empty());
+    }
+
+    #[test]
+    fn lock_events_set_seeker_lock() {
+        let mut arena = Arena::new();
+        let missile = ProjectileComponents::default().with_seeker(SeekerState::default());
+        let seeker = arena.spawn(EntityTag::Projectile, EntityInner::Projectile(missile));
+        let (first, second) = (EntityId::new(7), EntityId::new(8));
+        let event = |event| {
+            OutputEnvelope::new(
+                Output::Event(event),
+                PluginInstanceId::new(seeker, PluginId::new("projectile")),
+                TraceId::new(0),
+                0,
+                0,
+            )
+        };
+        let lock = |arena: &Arena| arena.get(seeker).unwrap().as_projectile().unwrap().seeker;
+
+        let acquired = event(Event::LockAcquired {
+            seeker,
+            target: first,
+        });
+        let current = arena.clone();
+        TrackResolver::new().resolve(&[&acquired], &current, &mut arena);
+        assert_eq!(lock(&arena).unwrap().lock, Some(first));
+
+        // Lost and re-acquired within one tick
+        let lost = event(Event::LockLost {
+            seeker,
+            target: first,
+        });
+        let reacquired = event(Event::LockAcquired {
+            seeker,
+            target: second,
+        });
+        let current = arena.clone();
+        TrackResolver::new().resolve(&[&lost, &reacquired], &current, &mut arena);
+        assert_eq!(lock(&arena).unwrap().lock, Some(second));
+    }
+}
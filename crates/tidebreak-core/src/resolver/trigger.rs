@@ -0,0 +1,173 @@
+//! Trigger resolver for scripted scenarios.
+//!
+//! Each tick the `TriggerResolver` checks the pending triggers of the
+//! arena's [`TriggerBook`](crate::trigger::TriggerBook) against `current`,
+//! in the order they were added, and runs the actions of those whose
+//! condition is met on `next`. Conditions are all read before any action
+//! runs, so a trigger never sees the effects of another in the same tick.
+//! See [`crate::trigger`] for the conditions and actions.
+
+use crate::arena::Arena;
+use crate::entity::{EntityInner, EntityTag};
+use crate::output::{OutputEnvelope, OutputKind};
+use crate::trigger::{Trigger, TriggerAction};
+
+use super::Resolver;
+
+/// Resolver firing scenario triggers.
+///
+/// Part of the default resolver set; it does nothing until a trigger is
+/// added.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::TeamId;
+/// use tidebreak_core::orders::RulesOfEngagement;
+/// use tidebreak_core::resolver::{Resolver, TriggerResolver};
+/// use tidebreak_core::trigger::{Trigger, TriggerAction, TriggerCondition};
+///
+/// let mut arena = Arena::new();
+/// let red = TeamId::new(1);
+/// let ceasefire = Trigger::new("ceasefire", TriggerCondition::TickReached { tick: 0 })
+///     .with_action(TriggerAction::SetRulesOfEngagement {
+///         team: red,
+///         roe: RulesOfEngagement::HoldFire,
+///     });
+/// arena.triggers_mut().add(ceasefire);
+///
+/// let current = arena.clone();
+/// TriggerResolver::new().resolve(&[], &current, &mut arena);
+/// assert_eq!(arena.rules_of_engagement(red), RulesOfEngagement::HoldFire);
+/// assert_eq!(arena.triggers().fired(0), Some(1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TriggerResolver;
+
+impl TriggerResolver {
+    /// Creates a new trigger resolver.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Runs one action on `next`.
+    fn run(next: &mut Arena, action: &TriggerAction, tick: u64) {
+        match action {
+            TriggerAction::SpawnWave { ships } => {
+                for ship in ships {
+                    let inner = EntityInner::Ship(ship.components.clone());
+                    let id = next.spawn(EntityTag::Ship, inner);
+                    next.set_team(id, ship.team);
+                    next.set_metadata(id, ship.metadata.clone());
+                }
+            }
+            // Applied by the host from the firing record
+            TriggerAction::ApplyStamp { .. } => {}
+            TriggerAction::SetRulesOfEngagement { team, roe } => {
+                next.set_rules_of_engagement(*team, *roe);
+            }
+            TriggerAction::EndEpisode => next.triggers_mut().end_episode(tick),
+        }
+    }
+}
+
+impl Resolver for TriggerResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[]
+    }
+
+    fn resolve(&self, _outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        let book = current.triggers();
+        let met: Vec<(usize, &Trigger)> = book
+            .pending()
+            .filter_map(|index| book.get(index).map(|trigger| (index, trigger)))
+            .filter(|(_, trigger)| trigger.condition.is_met(current))
+            .collect();
+        // The state being resolved is the one at the following tick
+        let tick = current.current_tick() + 1;
+        for (index, trigger) in met {
+            next.triggers_mut().mark_fired(index, tick);
+            for action in &trigger.actions {
+                Self::run(next, action, tick);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use super::*;
+    use crate::entity::{EntityId, EntityMetadata, ShipComponents, TeamId};
+    use crate::trigger::{TriggerCondition, WaveShip};
+
+    fn step(arena: &mut Arena) {
+        let current = arena.clone();
+        TriggerResolver::new().resolve(&[], &current, arena);
+        arena.advance_tick();
+    }
+
+    #[test]
+    fn fires_once_when_the_condition_is_met() {
+        let mut arena = Arena::new();
+        let escort = arena.spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+        let wave = WaveShip {
+            team: Some(TeamId::new(1)),
+            components: ShipComponents::at_position(Vec2::new(2000.0, 0.0), 0.0),
+            metadata: EntityMetadata::default(),
+        };
+        arena.triggers_mut().add(
+            Trigger::new("reinforce", TriggerCondition::EntityDestroyed { entity: escort })
+                .with_action(TriggerAction::SpawnWave { ships: vec![wave] })
+                .with_action(TriggerAction::EndEpisode),
+        );
+
+        step(&mut arena);
+        assert_eq!(arena.entity_count(), 1);
+        assert_eq!(arena.triggers().fired(0), None);
+
+        arena.despawn(escort);
+        step(&mut arena);
+        step(&mut arena);
+        assert_eq!(arena.entity_count(), 1);
+        let reinforcement = arena.entities_sorted().next().unwrap();
+        assert_eq!(reinforcement.team(), Some(TeamId::new(1)));
+        assert_eq!(arena.triggers().fired(0), Some(2));
+        assert_eq!(arena.triggers().episode_end(), Some(2));
+    }
+
+    #[test]
+    fn triggers_do_not_see_each_others_effects_in_the_same_tick() {
+        let mut arena = Arena::new();
+        arena.triggers_mut().add(
+            Trigger::new("spawn", TriggerCondition::TickReached { tick: 0 }).with_action(
+                TriggerAction::SpawnWave {
+                    ships: vec![WaveShip {
+                        team: None,
+                        components: ShipComponents::default(),
+                        metadata: EntityMetadata::default(),
+                    }],
+                },
+            ),
+        );
+        let zone = TriggerCondition::ZoneEntered {
+            center: Vec2::ZERO,
+            radius: 10.0,
+            team: None,
+        };
+        arena.triggers_mut().add(Trigger::new("arrived", zone));
+
+        step(&mut arena);
+        assert_eq!(arena.triggers().fired(1), None);
+        step(&mut arena);
+        assert_eq!(arena.triggers().fired(1), Some(2));
+        assert!(arena.get(EntityId::new(0)).is_some());
+    }
+}
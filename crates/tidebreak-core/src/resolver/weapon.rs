@@ -0,0 +1,482 @@
+//! Weapon resolver for cooldowns, magazines and reloads.
+//!
+//! Each tick the `WeaponResolver`:
+//!
+//! 1. **Advances timers** on every weapon: cooldowns count down by `dt`, and
+//!    a reload in progress loses a tick. A reload reaching zero refills the
+//!    magazine.
+//! 2. **Fires** each `FireWeapon` command whose weapon was ready at the start
+//!    of the tick: the weapon goes on cooldown and, if magazine-fed, expends
+//!    its burst. A magazine that runs dry starts reloading.
+//! 3. **Reloads** on `ReloadWeapon` commands, so a partly spent magazine can
+//!    be topped up between engagements.
+//!
+//! Weapons without a magazine (`magazine_size == 0`) are limited by their
+//! cooldown only. Reloads are reported as `ReloadStarted` and
+//! `ReloadCompleted` events in the event log given to
+//! [`with_event_log`](WeaponResolver::with_event_log).
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::arena::Arena;
+use crate::entity::{CombatState, Entity, EntityId, EntityInner, WeaponState};
+use crate::output::{
+    Command, Event, Output, OutputEnvelope, OutputKind, PluginId, PluginInstanceId,
+};
+
+use super::physics::FIXED_DT;
+use super::{EventResolver, Resolver};
+
+/// Resolver applying weapon fire and reload timing.
+///
+/// Part of the default resolver set.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::arena::Arena;
+/// use tidebreak_core::entity::{AmmoType, EntityInner, EntityTag, ShipComponents, WeaponState};
+/// use tidebreak_core::resolver::{Resolver, WeaponResolver};
+///
+/// let mut ship = ShipComponents::default();
+/// ship.combat.weapons.push(WeaponState::new(0, 1.0, AmmoType::Shell).with_magazine(6, 30));
+/// let mut arena = Arena::new();
+/// let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+/// arena.get_mut(id).unwrap().as_ship_mut().unwrap().combat.weapons[0].rounds = 0;
+///
+/// // An empty magazine reloads once the resolver has seen it
+/// let resolver = WeaponResolver::new();
+/// let current = arena.clone();
+/// resolver.resolve(&[], &current, &mut arena);
+/// assert!(arena.get(id).unwrap().as_ship().unwrap().combat.weapons[0].is_reloading());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeaponResolver {
+    /// Seconds per tick, for cooldowns
+    dt: f32,
+    /// Log receiving reload events
+    events: Option<Arc<EventResolver>>,
+}
+
+impl WeaponResolver {
+    /// Creates a resolver with the standard timestep and no event log.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_dt(FIXED_DT)
+    }
+
+    /// Creates a resolver with a custom timestep.
+    #[must_use]
+    pub const fn with_dt(dt: f32) -> Self {
+        Self { dt, events: None }
+    }
+
+    /// Records reload events into `events`.
+    #[must_use]
+    pub fn with_event_log(mut self, events: Arc<EventResolver>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Returns the timestep.
+    #[must_use]
+    pub const fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    /// Starts a reload, refilling immediately if the weapon reloads in zero
+    /// ticks.
+    fn start_reload(&self, next: &mut Arena, source: EntityId, weapon: &mut WeaponState) {
+        self.record(next, Event::ReloadStarted {
+            source,
+            weapon_slot: weapon.slot,
+        });
+        if weapon.reload_ticks == 0 {
+            weapon.rounds = weapon.magazine_size;
+            self.record(next, Event::ReloadCompleted {
+                source,
+                weapon_slot: weapon.slot,
+            });
+        } else {
+            weapon.reload_remaining = weapon.reload_ticks;
+        }
+    }
+
+    /// Applies `change` to the weapon in `slot` of `source`, if it exists.
+    ///
+    /// The weapon is taken out of the arena while `change` runs so reload
+    /// events can be recorded through `next`.
+    fn with_weapon(
+        &self,
+        next: &mut Arena,
+        source: EntityId,
+        slot: usize,
+        change: impl FnOnce(&Self, &mut Arena, &mut WeaponState),
+    ) {
+        let Some((index, mut weapon)) = next.get(source).and_then(combat).and_then(|c| {
+            c.weapons
+                .iter()
+                .enumerate()
+                .find(|(_, w)| w.slot == slot)
+                .map(|(index, w)| (index, w.clone()))
+        }) else {
+            return;
+        };
+        change(self, next, &mut weapon);
+        if let Some(c) = next.get_mut(source).and_then(combat_mut) {
+            c.weapons[index] = weapon;
+        }
+    }
+
+    /// Records an event, if an event log is attached.
+    fn record(&self, next: &mut Arena, event: Event) {
+        if let Some(events) = &self.events {
+            let entity = event.primary_entity();
+            events.record(OutputEnvelope::new(
+                Output::Event(event),
+                PluginInstanceId::new(entity, PluginId::from_static("weapon")),
+                next.new_trace_id(),
+                next.current_tick(),
+                0,
+            ));
+        }
+    }
+}
+
+impl Default for WeaponResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the combat state of a ship or squadron.
+fn combat(entity: &Entity) -> Option<&CombatState> {
+    match entity.inner() {
+        EntityInner::Ship(c) => Some(&c.combat),
+        EntityInner::Squadron(c) => Some(&c.combat),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+    }
+}
+
+/// Returns the mutable combat state of a ship or squadron.
+fn combat_mut(entity: &mut Entity) -> Option<&mut CombatState> {
+    match entity.inner_mut() {
+        EntityInner::Ship(c) => Some(&mut c.combat),
+        EntityInner::Squadron(c) => Some(&mut c.combat),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+    }
+}
+
+/// A weapon command honored this tick.
+pub(super) struct WeaponAction<'a> {
+    /// Entity owning the weapon
+    pub source: EntityId,
+    /// Weapon state at the start of the tick
+    pub weapon: &'a WeaponState,
+    /// Target fired at, or `None` for a reload
+    pub fire_at: Option<EntityId>,
+}
+
+/// Returns the `FireWeapon` and `ReloadWeapon` commands that take effect,
+/// in output order.
+///
+/// A weapon fires only if it was ready at the start of the tick and its
+/// team is not holding fire, reloads only if a reload would add rounds, and
+/// acts at most once per tick.
+pub(super) fn weapon_actions<'a>(
+    outputs: &[&OutputEnvelope],
+    current: &'a Arena,
+) -> Vec<WeaponAction<'a>> {
+    let mut acted: BTreeSet<(EntityId, usize)> = BTreeSet::new();
+    let mut actions = Vec::new();
+    for envelope in outputs {
+        let (source, slot, fire_at) = match envelope.output().as_command() {
+            Some(Command::FireWeapon {
+                source,
+                target,
+                slot,
+            }) => (*source, *slot, Some(*target)),
+            Some(Command::ReloadWeapon { source, slot }) => (*source, *slot, None),
+            _ => continue,
+        };
+        let Some(weapon) = current
+            .get(source)
+            .and_then(combat)
+            .and_then(|c| c.weapons.iter().find(|w| w.slot == slot))
+        else {
+            continue;
+        };
+        let allowed = if fire_at.is_some() {
+            weapon.is_ready() && current.may_fire(source)
+        } else {
+            weapon.can_reload()
+        };
+        if allowed && acted.insert((source, slot)) {
+            actions.push(WeaponAction {
+                source,
+                weapon,
+                fire_at,
+            });
+        }
+    }
+    actions
+}
+
+impl Resolver for WeaponResolver {
+    fn handles(&self) -> &[OutputKind] {
+        &[OutputKind::Command]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, next: &mut Arena) {
+        // PHASE 1: timers, for every weapon alive in both arenas
+        let armed: Vec<(EntityId, Vec<WeaponState>)> = current
+            .entities_sorted()
+            .filter_map(|e| combat(e).map(|c| (e.id(), c.weapons.clone())))
+            .filter(|(_, weapons)| !weapons.is_empty())
+            .collect();
+        for (id, weapons) in armed {
+            for weapon in weapons {
+                self.with_weapon(next, id, weapon.slot, |resolver, next, w| {
+                    w.cooldown = (weapon.cooldown - resolver.dt).max(0.0);
+                    if weapon.is_reloading() {
+                        w.reload_remaining = weapon.reload_remaining - 1;
+                        if w.reload_remaining == 0 {
+                            w.rounds = w.magazine_size;
+                            resolver.record(next, Event::ReloadCompleted {
+                                source: id,
+                                weapon_slot: w.slot,
+                            });
+                        }
+                    } else if weapon.has_magazine() && weapon.rounds == 0 {
+                        // Ran dry outside this resolver (e.g. edited state)
+                        resolver.start_reload(next, id, w);
+                    }
+                });
+            }
+        }
+
+        // PHASE 2: commands, each weapon acting at most once per tick
+        for action in weapon_actions(outputs, current) {
+            let (source, fire) = (action.source, action.fire_at.is_some());
+            self.with_weapon(next, source, action.weapon.slot, |resolver, next, w| {
+                if !fire {
+                    resolver.start_reload(next, source, w);
+                    return;
+                }
+                w.cooldown = w.max_cooldown;
+                if w.has_magazine() {
+                    w.rounds = w.rounds.saturating_sub(w.rounds_per_shot());
+                    if w.rounds == 0 {
+                        resolver.start_reload(next, source, w);
+                    }
+                }
+            });
+        }
+    }
+
+    fn set_dt(&mut self, dt: f32) {
+        self.dt = dt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{AmmoType, EntityTag, ShipComponents, TeamId};
+    use crate::orders::RulesOfEngagement;
+    use crate::output::TraceId;
+
+    fn envelope(command: Command) -> OutputEnvelope {
+        OutputEnvelope::new(
+            Output::Command(command),
+            PluginInstanceId::new(EntityId::new(0), PluginId::new("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn fire(source: EntityId) -> OutputEnvelope {
+        envelope(Command::FireWeapon {
+            source,
+            target: source,
+            slot: 0,
+        })
+    }
+
+    fn armed_ship(arena: &mut Arena, weapon: WeaponState) -> EntityId {
+        let mut ship = ShipComponents::default();
+        ship.combat.weapons.push(weapon);
+        arena.spawn(EntityTag::Ship, EntityInner::Ship(ship))
+    }
+
+    fn weapon(arena: &Arena, id: EntityId) -> WeaponState {
+        arena.get(id).unwrap().as_ship().unwrap().combat.weapons[0].clone()
+    }
+
+    fn step(resolver: &WeaponResolver, arena: &mut Arena, outputs: &[OutputEnvelope]) {
+        let refs: Vec<_> = outputs.iter().collect();
+        let current = arena.clone();
+        resolver.resolve(&refs, &current, arena);
+        arena.advance_tick();
+    }
+
+    fn reload_events(events: &EventResolver) -> Vec<Event> {
+        events
+            .take_events()
+            .iter()
+            .filter_map(|e| e.output().as_event().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn firing_starts_cooldown() {
+        let mut arena = Arena::new();
+        let id = armed_ship(&mut arena, WeaponState::new(0, 0.5, AmmoType::Shell));
+        let resolver = WeaponResolver::with_dt(0.1);
+
+        step(&resolver, &mut arena, &[fire(id)]);
+        assert!((weapon(&arena, id).cooldown - 0.5).abs() < 1e-6);
+
+        step(&resolver, &mut arena, &[]);
+        assert!((weapon(&arena, id).cooldown - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn holding_fire_drops_fire_commands() {
+        let mut arena = Arena::new();
+        let id = armed_ship(&mut arena, WeaponState::new(0, 0.5, AmmoType::Shell));
+        let team = TeamId::new(1);
+        arena.set_team(id, Some(team));
+        arena.set_rules_of_engagement(team, RulesOfEngagement::HoldFire);
+        let resolver = WeaponResolver::with_dt(0.1);
+
+        step(&resolver, &mut arena, &[fire(id)]);
+        assert!(weapon(&arena, id).is_ready());
+
+        arena.set_rules_of_engagement(team, RulesOfEngagement::WeaponsFree);
+        step(&resolver, &mut arena, &[fire(id)]);
+        assert!(!weapon(&arena, id).is_ready());
+    }
+
+    #[test]
+    fn weapon_on_cooldown_does_not_fire() {
+        let mut arena = Arena::new();
+        let mut on_cooldown = WeaponState::new(0, 1.0, AmmoType::Shell).with_magazine(5, 10);
+        on_cooldown.cooldown = 0.5;
+        let id = armed_ship(&mut arena, on_cooldown);
+
+        step(&WeaponResolver::new(), &mut arena, &[fire(id)]);
+
+        assert_eq!(weapon(&arena, id).rounds, 5);
+    }
+
+    #[test]
+    fn bursts_expend_rounds() {
+        let mut arena = Arena::new();
+        let gun = WeaponState::new(0, 0.0, AmmoType::Bullet)
+            .with_magazine(10, 5)
+            .with_burst(3);
+        let id = armed_ship(&mut arena, gun);
+
+        step(&WeaponResolver::new(), &mut arena, &[fire(id)]);
+
+        assert_eq!(weapon(&arena, id).rounds, 7);
+    }
+
+    #[test]
+    fn empty_magazine_reloads() {
+        let events = Arc::new(EventResolver::new());
+        let resolver = WeaponResolver::new().with_event_log(Arc::clone(&events));
+        let mut arena = Arena::new();
+        let gun = WeaponState::new(0, 0.0, AmmoType::Bullet)
+            .with_magazine(2, 3)
+            .with_burst(2);
+        let id = armed_ship(&mut arena, gun);
+
+        step(&resolver, &mut arena, &[fire(id)]);
+        assert_eq!(
+            reload_events(&events),
+            vec![Event::ReloadStarted {
+                source: id,
+                weapon_slot: 0
+            }]
+        );
+        assert!(!weapon(&arena, id).is_ready());
+
+        step(&resolver, &mut arena, &[fire(id)]);
+        step(&resolver, &mut arena, &[]);
+        assert!(weapon(&arena, id).is_reloading());
+        assert!(reload_events(&events).is_empty());
+
+        step(&resolver, &mut arena, &[]);
+        assert_eq!(
+            reload_events(&events),
+            vec![Event::ReloadCompleted {
+                source: id,
+                weapon_slot: 0
+            }]
+        );
+        assert_eq!(weapon(&arena, id).rounds, 2);
+        assert!(weapon(&arena, id).is_ready());
+    }
+
+    #[test]
+    fn reload_command_tops_up_magazine() {
+        let mut arena = Arena::new();
+        let mut gun = WeaponState::new(0, 0.0, AmmoType::Bullet).with_magazine(10, 2);
+        gun.rounds = 4;
+        let id = armed_ship(&mut arena, gun);
+        let reload = envelope(Command::ReloadWeapon {
+            source: id,
+            slot: 0,
+        });
+        let resolver = WeaponResolver::new();
+
+        step(&resolver, &mut arena, &[reload.clone()]);
+        assert_eq!(weapon(&arena, id).reload_remaining, 2);
+        // A second request during the reload does not restart it
+        step(&resolver, &mut arena, &[reload]);
+        step(&resolver, &mut arena, &[]);
+
+        assert_eq!(weapon(&arena, id).rounds, 10);
+        assert!(!weapon(&arena, id).is_reloading());
+    }
+
+    #[test]
+    fn full_magazine_ignores_reload() {
+        let mut arena = Arena::new();
+        let id = armed_ship(
+            &mut arena,
+            WeaponState::new(0, 0.0, AmmoType::Bullet).with_magazine(10, 2),
+        );
+
+        step(
+            &WeaponResolver::new(),
+            &mut arena,
+            &[envelope(Command::ReloadWeapon {
+                source: id,
+                slot: 0,
+            })],
+        );
+
+        assert!(!weapon(&arena, id).is_reloading());
+    }
+
+    #[test]
+    fn cooldown_only_weapon_never_reloads() {
+        let mut arena = Arena::new();
+        let id = armed_ship(&mut arena, WeaponState::new(0, 0.0, AmmoType::Shell));
+        let resolver = WeaponResolver::new();
+
+        for _ in 0..5 {
+            step(&resolver, &mut arena, &[fire(id)]);
+        }
+
+        let gun = weapon(&arena, id);
+        assert!(gun.is_ready());
+        assert_eq!(gun.rounds, 0);
+        assert!(!gun.is_reloading());
+    }
+}
@@ -0,0 +1,240 @@
+//! Optional audit log of random number draws.
+//!
+//! Every random roll in the simulation comes from a deterministic stream
+//! keyed by the roll's inputs (seed, tick, entities). With auditing enabled,
+//! each draw is recorded as an [`RngDraw`] (stream key, purpose tag, value)
+//! into a bounded ring buffer. When two runs that should be identical
+//! diverge, [`first_divergence`] finds the first draw that differs.
+//!
+//! Auditing is disabled by default and costs one uncontended lock per draw
+//! while disabled.
+//!
+//! ```
+//! use tidebreak_core::rng_audit::{first_divergence, RngAuditLog};
+//!
+//! let log = RngAuditLog::new();
+//! log.enable(16);
+//! log.record(3, 0xfeed, "minefield.sweep", 0.25);
+//! let a = log.take();
+//!
+//! log.record(3, 0xfeed, "minefield.sweep", 0.75);
+//! let b = log.take();
+//! assert_eq!(first_divergence(&a, &b), Some(0));
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Suggested ring size for [`RngAuditLog::enable`].
+pub const DEFAULT_AUDIT_CAPACITY: usize = 4096;
+
+/// One recorded random draw.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RngDraw {
+    /// Position of the draw among all draws recorded since enabling.
+    pub sequence: u64,
+    /// Tick the draw was made on.
+    pub tick: u64,
+    /// Key of the stream the draw came from.
+    pub stream: u64,
+    /// What the draw was used for (e.g. `"minefield.sweep"`).
+    pub purpose: &'static str,
+    /// Drawn value.
+    pub value: f64,
+}
+
+impl RngDraw {
+    /// Returns true if both draws came from the same stream for the same
+    /// purpose and produced the same value, ignoring the sequence number.
+    #[must_use]
+    pub fn matches(&self, other: &Self) -> bool {
+        self.tick == other.tick
+            && self.stream == other.stream
+            && self.purpose == other.purpose
+            && self.value.to_bits() == other.value.to_bits()
+    }
+}
+
+/// Ring buffer state behind the lock.
+#[derive(Debug, Default)]
+struct Ring {
+    /// Maximum draws kept; zero while disabled.
+    capacity: usize,
+    /// Draws recorded since enabling.
+    total: u64,
+    /// Most recent draws, oldest first.
+    draws: VecDeque<RngDraw>,
+}
+
+/// Bounded log of random draws, shared between the resolvers that roll.
+///
+/// Once full, the oldest draws are dropped; the `sequence` of the
+/// remaining draws still counts from when auditing was enabled.
+#[derive(Debug, Default)]
+pub struct RngAuditLog {
+    ring: Mutex<Ring>,
+}
+
+impl RngAuditLog {
+    /// Creates a disabled audit log.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables auditing, keeping at most the last `capacity` draws.
+    ///
+    /// Clears any draws already recorded. A capacity of zero disables
+    /// auditing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn enable(&self, capacity: usize) {
+        let mut ring = self.ring.lock().unwrap();
+        *ring = Ring {
+            capacity,
+            total: 0,
+            draws: VecDeque::with_capacity(capacity.min(DEFAULT_AUDIT_CAPACITY)),
+        };
+    }
+
+    /// Disables auditing and drops all recorded draws.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn disable(&self) {
+        *self.ring.lock().unwrap() = Ring::default();
+    }
+
+    /// Returns true if draws are being recorded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.capacity() > 0
+    }
+
+    /// Returns the number of draws kept, or zero while disabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.ring.lock().unwrap().capacity
+    }
+
+    /// Records a draw; does nothing while disabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn record(&self, tick: u64, stream: u64, purpose: &'static str, value: f64) {
+        let mut ring = self.ring.lock().unwrap();
+        if ring.capacity == 0 {
+            return;
+        }
+        if ring.draws.len() == ring.capacity {
+            ring.draws.pop_front();
+        }
+        let sequence = ring.total;
+        ring.total += 1;
+        ring.draws.push_back(RngDraw {
+            sequence,
+            tick,
+            stream,
+            purpose,
+            value,
+        });
+    }
+
+    /// Returns the recorded draws, oldest first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    #[must_use]
+    pub fn draws(&self) -> Vec<RngDraw> {
+        self.ring.lock().unwrap().draws.iter().copied().collect()
+    }
+
+    /// Removes and returns the recorded draws, oldest first.
+    ///
+    /// Auditing stays enabled and sequence numbers keep counting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn take(&self) -> Vec<RngDraw> {
+        self.ring.lock().unwrap().draws.drain(..).collect()
+    }
+}
+
+/// Returns the index of the first draw that differs between two runs, or
+/// `None` if one run's draws are a prefix of the other's.
+///
+/// Draws are compared position by position with [`RngDraw::matches`].
+#[must_use]
+pub fn first_divergence(a: &[RngDraw], b: &[RngDraw]) -> Option<usize> {
+    a.iter().zip(b).position(|(x, y)| !x.matches(y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_log_records_nothing() {
+        let log = RngAuditLog::new();
+        assert!(!log.is_enabled());
+        log.record(1, 2, "test", 0.5);
+        assert!(log.draws().is_empty());
+    }
+
+    #[test]
+    fn ring_keeps_most_recent_draws() {
+        let log = RngAuditLog::new();
+        log.enable(2);
+        for tick in 0..5 {
+            log.record(tick, 7, "test", 0.5);
+        }
+        let draws = log.draws();
+        assert_eq!(draws.len(), 2);
+        assert_eq!(draws[0].sequence, 3);
+        assert_eq!(draws[1].tick, 4);
+    }
+
+    #[test]
+    fn take_drains_but_keeps_counting() {
+        let log = RngAuditLog::new();
+        log.enable(8);
+        log.record(0, 1, "test", 0.1);
+        assert_eq!(log.take().len(), 1);
+        assert!(log.draws().is_empty());
+        log.record(1, 1, "test", 0.2);
+        assert_eq!(log.draws()[0].sequence, 1);
+
+        log.disable();
+        assert!(!log.is_enabled());
+        assert!(log.draws().is_empty());
+    }
+
+    #[test]
+    fn first_divergence_ignores_sequence() {
+        let draw = |sequence, value| RngDraw {
+            sequence,
+            tick: 0,
+            stream: 9,
+            purpose: "test",
+            value,
+        };
+        let a = [draw(0, 0.1), draw(1, 0.2), draw(2, 0.3)];
+        let b = [draw(10, 0.1), draw(11, 0.2), draw(12, 0.4)];
+        assert_eq!(first_divergence(&a, &b), Some(2));
+        assert_eq!(first_divergence(&a, &b[..2]), None);
+    }
+}
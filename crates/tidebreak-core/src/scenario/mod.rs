@@ -0,0 +1,1125 @@
+//! Battle packages: the serialized scenario format.
+//!
+//! A [`BattlePackage`] is the input contract of the combat arena (schema
+//! `arena.v1`, see `docs/technical/contracts.md`): teams, map and ship
+//! snapshots. This module implements the subset the engine can
+//! instantiate today (bounds, weather, hull, sensors, weapons and initial
+//! state); fields of the full contract that have no engine counterpart
+//! yet are not modelled.
+//!
+//! [`BattlePackage::build`] validates a package and turns it into a
+//! ready-to-step [`Simulation`]. Ships given a [`SpawnRegion`] are placed
+//! at a clear, seeded position around their initial position rather than
+//! on it. [`TriggerSpec`]s script events during
+//! the battle (see [`crate::trigger`]). [`ScenarioRandomizer`] derives
+//! jittered variants of a package for domain randomization.
+//!
+//! ```
+//! use tidebreak_core::scenario::{BattlePackage, ShipSnapshot, ShipState, Team};
+//!
+//! let mut package = BattlePackage::new("duel", 7);
+//! package.teams = vec![Team::new("blue"), Team::new("red")];
+//! package.ships = vec![
+//!     ShipSnapshot::new("b1", "blue", ShipState::at(0.0, 0.0, 0.0)),
+//!     ShipSnapshot::new("r1", "red", ShipState::at(4000.0, 0.0, 3.0)),
+//! ];
+//!
+//! let (sim, ids) = package.build().unwrap();
+//! assert_eq!(sim.arena().entity_count(), 2);
+//! assert!(ids.contains_key("r1"));
+//! ```
+
+mod randomizer;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::Hasher;
+
+use glam::Vec2;
+use murk::{BlendOp, Field};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::arena::{Arena, BoundaryPolicy, Fnv1a, WorldBounds};
+use crate::entity::components::{AmmoType, MitigationState, WeaponState};
+use crate::entity::{EntityId, EntityInner, EntityMetadata, EntityTag, ShipComponents, TeamId};
+use crate::environment::{WorldClock, MAX_SEA_STATE};
+use crate::orders::RulesOfEngagement;
+use crate::simulation::Simulation;
+use crate::trigger::{Trigger, TriggerAction, TriggerCondition, TriggerStamp, WaveShip};
+use crate::wind::Wind;
+
+pub use randomizer::{Distribution, ScenarioRandomizer};
+
+/// Schema version written by [`BattlePackage::new`] and accepted by
+/// [`BattlePackage::validate`].
+pub const SCHEMA_VERSION: &str = "arena.v1";
+
+/// Errors found when validating or building a battle package.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ScenarioError {
+    /// The package declares a schema this engine does not read.
+    #[error("unsupported schema version '{0}', expected '{SCHEMA_VERSION}'")]
+    UnsupportedSchema(String),
+    /// Two teams share an ID.
+    #[error("duplicate team ID '{0}'")]
+    DuplicateTeam(String),
+    /// Two ships share an ID.
+    #[error("duplicate ship ID '{0}'")]
+    DuplicateShip(String),
+    /// A ship references a team that is not defined.
+    #[error("ship '{ship}' references unknown team '{team}'")]
+    UnknownTeam {
+        /// Ship with the bad reference
+        ship: String,
+        /// Missing team ID
+        team: String,
+    },
+    /// A number is out of range or not finite.
+    #[error("invalid {field} for '{id}'")]
+    InvalidValue {
+        /// Ship, trigger or battle ID the value belongs to
+        id: String,
+        /// Name of the offending field
+        field: &'static str,
+    },
+    /// No clear position was found in a ship's spawn region.
+    #[error("no clear spawn position for ship '{0}'")]
+    NoSpawnPosition(String),
+    /// A trigger references a ship or team that is not defined.
+    #[error("trigger '{trigger}' references unknown ship or team '{id}'")]
+    UnknownReference {
+        /// Trigger with the bad reference
+        trigger: String,
+        /// Missing ship or team ID
+        id: String,
+    },
+}
+
+/// A side in the battle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Team {
+    /// Stable ID referenced by ships
+    pub team_id: String,
+    /// Display name
+    #[serde(default)]
+    pub name: String,
+    /// Display color (RGB) for ships without one of their own
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+}
+
+impl Team {
+    /// Creates a team named after its ID.
+    #[must_use]
+    pub fn new(team_id: impl Into<String>) -> Self {
+        let team_id = team_id.into();
+        Self {
+            name: team_id.clone(),
+            team_id,
+            color: None,
+        }
+    }
+}
+
+/// Rectangle confining the battle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    /// Western edge
+    pub min_x: f32,
+    /// Southern edge
+    pub min_y: f32,
+    /// Eastern edge
+    pub max_x: f32,
+    /// Northern edge
+    pub max_y: f32,
+}
+
+/// Conditions at the start of the battle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeatherCondition {
+    /// Sea state on the Douglas scale (0-9)
+    pub sea_state: u8,
+    /// Hour of day (0-24) at the first tick
+    pub hour: f32,
+    /// Phase of the moon (0 new, 0.5 full) at the first tick
+    #[serde(default)]
+    pub moon_phase: f32,
+    /// Wind speed in m/s
+    #[serde(default)]
+    pub wind_speed: f32,
+    /// Heading the wind blows toward, in radians
+    #[serde(default)]
+    pub wind_direction: f32,
+}
+
+impl Default for WeatherCondition {
+    fn default() -> Self {
+        Self {
+            sea_state: 0,
+            hour: 12.0,
+            moon_phase: 0.0,
+            wind_speed: 0.0,
+            wind_direction: 0.0,
+        }
+    }
+}
+
+/// Battle map.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MapDefinition {
+    /// Edges of the battle area; unbounded if absent
+    #[serde(default)]
+    pub bounds: Option<Bounds>,
+    /// Starting weather
+    #[serde(default)]
+    pub weather: WeatherCondition,
+}
+
+/// Hull performance limits.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HullParams {
+    /// Hit points when undamaged
+    pub max_hp: f32,
+    /// Collision radius in meters
+    pub radius: f32,
+    /// Top speed in m/s
+    pub max_speed: f32,
+    /// Turn rate in rad/s
+    pub turn_rate: f32,
+    /// Shield or other soft mitigation; none if absent
+    #[serde(default)]
+    pub mitigation: Option<MitigationState>,
+}
+
+impl Default for HullParams {
+    fn default() -> Self {
+        let ship = ShipComponents::default();
+        Self {
+            max_hp: ship.combat.max_hp,
+            radius: ship.transform.radius,
+            max_speed: ship.physics.max_speed,
+            turn_rate: ship.physics.max_turn_rate,
+            mitigation: ship.combat.mitigation,
+        }
+    }
+}
+
+/// Kind of sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SensorType {
+    /// Active and passive radar
+    Radar,
+    /// Hull and towed sonar
+    Sonar,
+    /// Lookouts and periscopes (range in clear daylight)
+    Visual,
+}
+
+/// One sensor fit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SensorConfig {
+    /// Kind of sensor
+    pub sensor_type: SensorType,
+    /// Detection range in meters
+    pub range: f32,
+}
+
+/// One weapon mount and its ammunition.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeaponConfig {
+    /// Weapon slot index
+    pub slot: usize,
+    /// Ammunition fired
+    pub weapon_type: AmmoType,
+    /// Seconds between shots
+    pub cooldown: f32,
+    /// Rounds carried
+    pub ammunition: u32,
+}
+
+/// Position and condition at the first tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShipState {
+    /// East position in meters
+    pub x: f32,
+    /// North position in meters
+    pub y: f32,
+    /// Heading in radians
+    pub heading: f32,
+    /// Speed along the heading in m/s
+    #[serde(default)]
+    pub speed: f32,
+    /// Hit points; undamaged if absent
+    #[serde(default)]
+    pub hp: Option<f32>,
+}
+
+impl ShipState {
+    /// Creates a state at rest at (`x`, `y`) facing `heading`.
+    #[must_use]
+    pub const fn at(x: f32, y: f32, heading: f32) -> Self {
+        Self {
+            x,
+            y,
+            heading,
+            speed: 0.0,
+            hp: None,
+        }
+    }
+}
+
+/// Area a ship is spawned in, around its initial position.
+///
+/// The position is drawn with [`Arena::find_spawn_position`] from an RNG
+/// keyed by the package seed and the ship ID, so a package always spawns
+/// the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpawnRegion {
+    /// Largest distance from the initial position, in meters
+    pub radius: f32,
+    /// Clearance kept from other ships and platforms, in meters
+    #[serde(default = "SpawnRegion::default_min_separation")]
+    pub min_separation: f32,
+}
+
+impl SpawnRegion {
+    /// Clearance kept when none is given.
+    pub const DEFAULT_MIN_SEPARATION: f32 = 200.0;
+
+    /// Creates a region of `radius` meters with the default clearance.
+    #[must_use]
+    pub const fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            min_separation: Self::DEFAULT_MIN_SEPARATION,
+        }
+    }
+
+    const fn default_min_separation() -> f32 {
+        Self::DEFAULT_MIN_SEPARATION
+    }
+}
+
+/// A ship as handed to the arena.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShipSnapshot {
+    /// Stable ID from the main simulation
+    pub ship_id: String,
+    /// Team the ship fights for
+    pub team_id: String,
+    /// Hull limits
+    #[serde(default)]
+    pub hull: HullParams,
+    /// Sensor fit; the engine defaults apply to kinds not listed
+    #[serde(default)]
+    pub sensors: Vec<SensorConfig>,
+    /// Weapon fit
+    #[serde(default)]
+    pub weapons: Vec<WeaponConfig>,
+    /// Starting state
+    pub initial_state: ShipState,
+    /// Area to spawn in around the initial position, instead of on it
+    /// (ships of the initial fleet only)
+    #[serde(default)]
+    pub spawn_region: Option<SpawnRegion>,
+    /// Cosmetic name, class, hull number and color
+    #[serde(default)]
+    pub metadata: EntityMetadata,
+}
+
+impl ShipSnapshot {
+    /// Creates a ship with the default hull and no sensors or weapons.
+    #[must_use]
+    pub fn new(ship_id: impl Into<String>, team_id: impl Into<String>, state: ShipState) -> Self {
+        Self {
+            ship_id: ship_id.into(),
+            team_id: team_id.into(),
+            hull: HullParams::default(),
+            sensors: Vec::new(),
+            weapons: Vec::new(),
+            initial_state: state,
+            spawn_region: None,
+            metadata: EntityMetadata::default(),
+        }
+    }
+
+    /// Returns the ship's metadata, in its team's color unless it has its
+    /// own.
+    fn metadata_in(&self, teams: &[Team]) -> EntityMetadata {
+        let mut metadata = self.metadata.clone();
+        if metadata.color.is_none() {
+            let team = teams.iter().find(|team| team.team_id == self.team_id);
+            metadata.color = team.and_then(|team| team.color);
+        }
+        metadata
+    }
+
+    /// Checks the team reference and the numbers of the ship.
+    fn validate(&self, teams: &BTreeSet<&str>) -> Result<(), ScenarioError> {
+        if !teams.contains(self.team_id.as_str()) {
+            return Err(ScenarioError::UnknownTeam {
+                ship: self.ship_id.clone(),
+                team: self.team_id.clone(),
+            });
+        }
+        let invalid = |field| ScenarioError::InvalidValue {
+            id: self.ship_id.clone(),
+            field,
+        };
+        let state = &self.initial_state;
+        if ![state.x, state.y, state.heading, state.speed]
+            .iter()
+            .all(|v| v.is_finite())
+        {
+            return Err(invalid("initial_state"));
+        }
+        // Written as `>=` so that NaN fails too
+        let non_negative = |v: f32| v >= 0.0;
+        let hull = &self.hull;
+        let limits = [hull.radius, hull.max_speed, hull.turn_rate];
+        if hull.max_hp.is_nan() || hull.max_hp <= 0.0 || !limits.into_iter().all(non_negative) {
+            return Err(invalid("hull"));
+        }
+        let pool = |m: MitigationState| [m.capacity, m.pool, m.regen_rate, m.regen_delay];
+        if !hull.mitigation.is_none_or(|m| pool(m).into_iter().all(non_negative)) {
+            return Err(invalid("mitigation"));
+        }
+        if !self.sensors.iter().all(|s| non_negative(s.range)) {
+            return Err(invalid("sensor range"));
+        }
+        if !self.weapons.iter().all(|w| non_negative(w.cooldown)) {
+            return Err(invalid("weapon cooldown"));
+        }
+        let region = |r: SpawnRegion| {
+            r.radius.is_finite() && [r.radius, r.min_separation].into_iter().all(non_negative)
+        };
+        if !self.spawn_region.is_none_or(region) {
+            return Err(invalid("spawn_region"));
+        }
+        Ok(())
+    }
+
+    /// Returns where the ship spawns in `arena`: its initial position, or
+    /// a clear one drawn from its spawn region.
+    fn spawn_position(&self, arena: &Arena, seed: u64) -> Result<Vec2, ScenarioError> {
+        let state = &self.initial_state;
+        let center = Vec2::new(state.x, state.y);
+        let Some(region) = self.spawn_region else {
+            return Ok(center);
+        };
+        let mut hasher = Fnv1a::default();
+        hasher.write_u64(seed);
+        hasher.write_text(&self.ship_id);
+        hasher.write_text("spawn_region");
+        let mut rng = ChaCha8Rng::seed_from_u64(hasher.finish());
+        arena
+            .find_spawn_position(center, region.radius, region.min_separation, &mut rng)
+            .ok_or_else(|| ScenarioError::NoSpawnPosition(self.ship_id.clone()))
+    }
+
+    /// Builds the ship's components.
+    fn components(&self) -> ShipComponents {
+        let state = &self.initial_state;
+        let mut ship = ShipComponents::at_position(Vec2::new(state.x, state.y), state.heading)
+            .with_max_hp(self.hull.max_hp)
+            .with_physics(self.hull.max_speed, self.hull.turn_rate);
+        ship.transform.radius = self.hull.radius;
+        ship.combat.mitigation = self.hull.mitigation;
+        ship.physics.velocity = Vec2::from_angle(state.heading) * state.speed;
+        if let Some(hp) = state.hp {
+            ship.combat.hp = hp.min(self.hull.max_hp);
+        }
+        for sensor in &self.sensors {
+            let range = match sensor.sensor_type {
+                SensorType::Radar => &mut ship.sensor.radar_range,
+                SensorType::Sonar => &mut ship.sensor.sonar_range,
+                SensorType::Visual => &mut ship.sensor.visual_range,
+            };
+            *range = sensor.range;
+        }
+        for weapon in &self.weapons {
+            ship.combat.weapons.push(WeaponState::new(
+                weapon.slot,
+                weapon.cooldown,
+                weapon.weapon_type,
+            ));
+            *ship.inventory.ammo.entry(weapon.weapon_type).or_default() += weapon.ammunition;
+        }
+        ship
+    }
+}
+
+/// When a scenario trigger fires (see [`crate::trigger`]).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConditionSpec {
+    /// A ship present at the start is destroyed or removed
+    ShipDestroyed {
+        /// Ship watched
+        ship_id: String,
+    },
+    /// A live ship is within `radius` meters of (`x`, `y`)
+    ZoneEntered {
+        /// East position of the center in meters
+        x: f32,
+        /// North position of the center in meters
+        y: f32,
+        /// Radius in meters
+        radius: f32,
+        /// Only count this team's ships; any ship if absent
+        #[serde(default)]
+        team_id: Option<String>,
+    },
+    /// The tick is reached
+    TickReached {
+        /// First tick the condition holds on
+        tick: u64,
+    },
+    /// A ship present at the start is alive below a fraction of its hit
+    /// points
+    HpBelow {
+        /// Ship watched
+        ship_id: String,
+        /// Fraction of max hit points (0-1)
+        fraction: f32,
+    },
+}
+
+/// What a scenario trigger does when it fires.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionSpec {
+    /// Spawn ships; their IDs must not clash with any other ship's
+    SpawnWave {
+        /// Ships to spawn
+        ships: Vec<ShipSnapshot>,
+    },
+    /// Have the host stamp a sphere on the surface into the universe
+    ApplyStamp {
+        /// East position of the center in meters
+        x: f32,
+        /// North position of the center in meters
+        y: f32,
+        /// Radius in meters
+        radius: f32,
+        /// Field modified
+        field: Field,
+        /// How the value is blended into the field
+        op: BlendOp,
+        /// Value blended in
+        value: f32,
+    },
+    /// Change a team's rules of engagement
+    SetRulesOfEngagement {
+        /// Team affected
+        team_id: String,
+        /// New rules
+        roe: RulesOfEngagement,
+    },
+    /// End the episode
+    EndEpisode,
+}
+
+/// A scenario trigger: a condition and the actions run when it is first
+/// met.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerSpec {
+    /// Name reported to the host
+    pub name: String,
+    /// Condition firing the trigger
+    pub condition: ConditionSpec,
+    /// Actions run in order
+    #[serde(default)]
+    pub actions: Vec<ActionSpec>,
+}
+
+impl TriggerSpec {
+    /// Creates a trigger with no actions.
+    #[must_use]
+    pub fn new(name: impl Into<String>, condition: ConditionSpec) -> Self {
+        Self {
+            name: name.into(),
+            condition,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Adds an action run when the trigger fires.
+    #[must_use]
+    pub fn with_action(mut self, action: ActionSpec) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Returns the ships spawned by the trigger's waves.
+    fn wave_ships(&self) -> impl Iterator<Item = &ShipSnapshot> {
+        self.actions.iter().flat_map(|action| match action {
+            ActionSpec::SpawnWave { ships } => ships.as_slice(),
+            _ => &[],
+        })
+    }
+
+    /// Checks the trigger's references and numbers. Wave ships are checked
+    /// with the package's ships.
+    fn validate(
+        &self,
+        teams: &BTreeSet<&str>,
+        ships: &BTreeSet<&str>,
+    ) -> Result<(), ScenarioError> {
+        let unknown = |id: &str| ScenarioError::UnknownReference {
+            trigger: self.name.clone(),
+            id: id.to_string(),
+        };
+        let invalid = |field| ScenarioError::InvalidValue {
+            id: self.name.clone(),
+            field,
+        };
+        match &self.condition {
+            ConditionSpec::ShipDestroyed { ship_id } | ConditionSpec::HpBelow { ship_id, .. }
+                if !ships.contains(ship_id.as_str()) =>
+            {
+                return Err(unknown(ship_id));
+            }
+            ConditionSpec::HpBelow { fraction, .. } if !(0.0..=1.0).contains(fraction) => {
+                return Err(invalid("fraction"));
+            }
+            ConditionSpec::ZoneEntered {
+                x,
+                y,
+                radius,
+                team_id,
+            } => {
+                if !(x.is_finite() && y.is_finite() && radius.is_finite() && *radius >= 0.0) {
+                    return Err(invalid("zone"));
+                }
+                if let Some(team) = team_id.as_deref().filter(|t| !teams.contains(t)) {
+                    return Err(unknown(team));
+                }
+            }
+            _ => {}
+        }
+        for action in &self.actions {
+            match action {
+                ActionSpec::ApplyStamp {
+                    x,
+                    y,
+                    radius,
+                    value,
+                    ..
+                } if ![x, y, radius, value].iter().all(|v| v.is_finite()) || *radius < 0.0 => {
+                    return Err(invalid("stamp"));
+                }
+                ActionSpec::SetRulesOfEngagement { team_id, .. }
+                    if !teams.contains(team_id.as_str()) =>
+                {
+                    return Err(unknown(team_id));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the trigger, resolving ship and team IDs.
+    fn build(&self, package: &BattlePackage, ids: &BTreeMap<String, EntityId>) -> Trigger {
+        let teams = package.team_ids();
+        let condition = match &self.condition {
+            ConditionSpec::ShipDestroyed { ship_id } => TriggerCondition::EntityDestroyed {
+                entity: ids[ship_id],
+            },
+            ConditionSpec::ZoneEntered {
+                x,
+                y,
+                radius,
+                team_id,
+            } => TriggerCondition::ZoneEntered {
+                center: Vec2::new(*x, *y),
+                radius: *radius,
+                team: team_id.as_deref().map(|team| teams[team]),
+            },
+            ConditionSpec::TickReached { tick } => TriggerCondition::TickReached { tick: *tick },
+            ConditionSpec::HpBelow { ship_id, fraction } => TriggerCondition::HpBelow {
+                entity: ids[ship_id],
+                fraction: *fraction,
+            },
+        };
+        let mut trigger = Trigger::new(self.name.clone(), condition);
+        for action in &self.actions {
+            trigger = trigger.with_action(match action {
+                ActionSpec::SpawnWave { ships } => TriggerAction::SpawnWave {
+                    ships: ships
+                        .iter()
+                        .map(|ship| WaveShip {
+                            team: teams.get(ship.team_id.as_str()).copied(),
+                            components: ship.components(),
+                            metadata: ship.metadata_in(&package.teams),
+                        })
+                        .collect(),
+                },
+                ActionSpec::ApplyStamp {
+                    x,
+                    y,
+                    radius,
+                    field,
+                    op,
+                    value,
+                } => TriggerAction::ApplyStamp {
+                    stamp: TriggerStamp {
+                        center: Vec2::new(*x, *y),
+                        radius: *radius,
+                        field: *field,
+                        op: *op,
+                        value: *value,
+                    },
+                },
+                ActionSpec::SetRulesOfEngagement { team_id, roe } => {
+                    TriggerAction::SetRulesOfEngagement {
+                        team: teams[team_id.as_str()],
+                        roe: *roe,
+                    }
+                }
+                ActionSpec::EndEpisode => TriggerAction::EndEpisode,
+            });
+        }
+        trigger
+    }
+}
+
+/// Input contract of a battle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BattlePackage {
+    /// Contract version, e.g. `"arena.v1"`
+    pub schema_version: String,
+    /// Stable battle ID
+    pub battle_id: String,
+    /// Master seed of the simulation
+    pub seed: u64,
+    /// Sides in the battle
+    #[serde(default)]
+    pub teams: Vec<Team>,
+    /// Map and weather
+    #[serde(default)]
+    pub map: MapDefinition,
+    /// Ships present at the start
+    #[serde(default)]
+    pub ships: Vec<ShipSnapshot>,
+    /// Scripted events, checked every tick in order
+    #[serde(default)]
+    pub triggers: Vec<TriggerSpec>,
+}
+
+impl BattlePackage {
+    /// Creates an empty package at the current schema version.
+    #[must_use]
+    pub fn new(battle_id: impl Into<String>, seed: u64) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION.to_string(),
+            battle_id: battle_id.into(),
+            seed,
+            teams: Vec::new(),
+            map: MapDefinition::default(),
+            ships: Vec::new(),
+            triggers: Vec::new(),
+        }
+    }
+
+    /// Checks the package invariants: a supported schema, unique team and
+    /// ship IDs, team references that resolve, and finite, in-range numbers.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first violation found.
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        if self.schema_version != SCHEMA_VERSION {
+            return Err(ScenarioError::UnsupportedSchema(self.schema_version.clone()));
+        }
+        let invalid = |id: &str, field| ScenarioError::InvalidValue {
+            id: id.to_string(),
+            field,
+        };
+        let weather = &self.map.weather;
+        if weather.sea_state > MAX_SEA_STATE {
+            return Err(invalid(&self.battle_id, "sea_state"));
+        }
+        if !(0.0..24.0).contains(&weather.hour) {
+            return Err(invalid(&self.battle_id, "hour"));
+        }
+        if !(0.0..1.0).contains(&weather.moon_phase) {
+            return Err(invalid(&self.battle_id, "moon_phase"));
+        }
+        if !(weather.wind_speed.is_finite() && weather.wind_speed >= 0.0) {
+            return Err(invalid(&self.battle_id, "wind_speed"));
+        }
+        if !weather.wind_direction.is_finite() {
+            return Err(invalid(&self.battle_id, "wind_direction"));
+        }
+        if let Some(b) = &self.map.bounds {
+            let corners = [b.min_x, b.min_y, b.max_x, b.max_y];
+            if !corners.iter().all(|c| c.is_finite()) || b.min_x >= b.max_x || b.min_y >= b.max_y
+            {
+                return Err(invalid(&self.battle_id, "bounds"));
+            }
+        }
+
+        let mut teams = BTreeSet::new();
+        for team in &self.teams {
+            if !teams.insert(team.team_id.as_str()) {
+                return Err(ScenarioError::DuplicateTeam(team.team_id.clone()));
+            }
+        }
+        let mut ships = BTreeSet::new();
+        let waves = self.triggers.iter().flat_map(TriggerSpec::wave_ships);
+        for ship in self.ships.iter().chain(waves) {
+            if !ships.insert(ship.ship_id.as_str()) {
+                return Err(ScenarioError::DuplicateShip(ship.ship_id.clone()));
+            }
+            ship.validate(&teams)?;
+        }
+        let mut waves = self.triggers.iter().flat_map(TriggerSpec::wave_ships);
+        if let Some(ship) = waves.find(|ship| ship.spawn_region.is_some()) {
+            return Err(invalid(&ship.ship_id, "spawn_region"));
+        }
+        let present = self.ships.iter().map(|ship| ship.ship_id.as_str()).collect();
+        for trigger in &self.triggers {
+            trigger.validate(&teams, &present)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the team ID each team's ships are spawned with: the
+    /// position of the team in [`teams`](Self::teams).
+    #[must_use]
+    pub fn team_ids(&self) -> BTreeMap<&str, TeamId> {
+        (0_u32..)
+            .zip(&self.teams)
+            .map(|(index, team)| (team.team_id.as_str(), TeamId::new(index)))
+            .collect()
+    }
+
+    /// Spawns the package's ships into `arena` and applies its map,
+    /// returning the entity spawned for each ship ID.
+    ///
+    /// Ships are spawned in order, so a ship with a spawn region keeps
+    /// clear of the ships listed before it and of those already in `arena`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package is invalid or a ship finds no clear
+    /// position in its spawn region; `arena` is left untouched.
+    pub fn spawn(&self, arena: &mut Arena) -> Result<BTreeMap<String, EntityId>, ScenarioError> {
+        self.validate()?;
+        let mut staged = arena.clone();
+        let ids = self.spawn_into(&mut staged)?;
+        *arena = staged;
+        Ok(ids)
+    }
+
+    /// Applies the map and spawns the ships of a valid package.
+    fn spawn_into(&self, arena: &mut Arena) -> Result<BTreeMap<String, EntityId>, ScenarioError> {
+        if let Some(b) = &self.map.bounds {
+            arena.set_bounds(Some(WorldBounds::new(
+                Vec2::new(b.min_x, b.min_y),
+                Vec2::new(b.max_x, b.max_y),
+                BoundaryPolicy::Clamp,
+            )));
+        }
+        let weather = &self.map.weather;
+        if weather.wind_speed > 0.0 {
+            arena.set_wind(Some(Wind::new(weather.wind_speed, weather.wind_direction)));
+        }
+        let environment = arena.environment_mut();
+        environment.sea_state = weather.sea_state;
+        environment.clock = WorldClock {
+            moon_phase: weather.moon_phase,
+            ..WorldClock::starting_at(weather.hour)
+        };
+
+        let teams = self.team_ids();
+        let mut ids = BTreeMap::new();
+        for ship in &self.ships {
+            let mut components = ship.components();
+            components.transform.position = ship.spawn_position(arena, self.seed)?;
+            let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(components));
+            arena.set_team(id, teams.get(ship.team_id.as_str()).copied());
+            arena.set_metadata(id, ship.metadata_in(&self.teams));
+            ids.insert(ship.ship_id.clone(), id);
+        }
+        for trigger in &self.triggers {
+            arena.triggers_mut().add(trigger.build(self, &ids));
+        }
+        Ok(ids)
+    }
+
+    /// Creates a simulation seeded with the package seed and spawns the
+    /// package into it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package is invalid.
+    pub fn build(&self) -> Result<(Simulation, BTreeMap<String, EntityId>), ScenarioError> {
+        let mut sim = Simulation::new(self.seed);
+        let ids = self.spawn(sim.arena_mut())?;
+        Ok((sim, ids))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duel() -> BattlePackage {
+        let mut package = BattlePackage::new("duel", 7);
+        package.teams = vec![Team::new("blue"), Team::new("red")];
+        let mut blue = ShipSnapshot::new("b1", "blue", ShipState::at(0.0, 0.0, 0.0));
+        blue.sensors.push(SensorConfig {
+            sensor_type: SensorType::Radar,
+            range: 8000.0,
+        });
+        blue.weapons.push(WeaponConfig {
+            slot: 0,
+            weapon_type: AmmoType::Shell,
+            cooldown: 2.0,
+            ammunition: 40,
+        });
+        package.ships = vec![
+            blue,
+            ShipSnapshot::new("r1", "red", ShipState::at(4000.0, 0.0, 3.0)),
+        ];
+        package
+    }
+
+    #[test]
+    fn build_spawns_ships_on_their_teams() {
+        let (sim, ids) = duel().build().unwrap();
+        let blue = sim.arena().get(ids["b1"]).unwrap();
+        let red = sim.arena().get(ids["r1"]).unwrap();
+        assert_eq!(blue.team(), Some(TeamId::new(0)));
+        assert_eq!(red.team(), Some(TeamId::new(1)));
+
+        let ship = blue.as_ship().unwrap();
+        assert!((ship.sensor.radar_range - 8000.0).abs() < f32::EPSILON);
+        assert_eq!(ship.combat.weapons.len(), 1);
+        assert_eq!(ship.inventory.get_ammo(AmmoType::Shell), 40);
+    }
+
+    #[test]
+    fn build_applies_weather_and_bounds() {
+        let mut package = duel();
+        package.map.weather = WeatherCondition {
+            sea_state: 5,
+            hour: 0.0,
+            moon_phase: 0.5,
+            wind_speed: 12.0,
+            wind_direction: 0.0,
+        };
+        package.map.bounds = Some(Bounds {
+            min_x: -5000.0,
+            min_y: -5000.0,
+            max_x: 5000.0,
+            max_y: 5000.0,
+        });
+        let (sim, _) = package.build().unwrap();
+        assert_eq!(sim.arena().environment().sea_state, 5);
+        assert!(sim.arena().environment().clock.daylight(0) < 0.5);
+        assert!(sim.arena().environment().clock.moon_illumination(0) > 0.99);
+        assert_eq!(sim.arena().wind(), Some(&Wind::new(12.0, 0.0)));
+        assert!(sim.arena().bounds().is_some());
+    }
+
+    #[test]
+    fn validate_rejects_broken_references() {
+        let mut package = duel();
+        package.ships[1].team_id = "green".to_string();
+        assert!(matches!(
+            package.validate(),
+            Err(ScenarioError::UnknownTeam { .. })
+        ));
+
+        let mut package = duel();
+        package.ships[1].ship_id = "b1".to_string();
+        assert_eq!(
+            package.validate(),
+            Err(ScenarioError::DuplicateShip("b1".to_string()))
+        );
+
+        let mut package = duel();
+        package.schema_version = "arena.v9".to_string();
+        assert!(package.build().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_bad_numbers() {
+        let mut package = duel();
+        package.ships[0].initial_state.x = f32::NAN;
+        assert!(matches!(
+            package.validate(),
+            Err(ScenarioError::InvalidValue {
+                field: "initial_state",
+                ..
+            })
+        ));
+
+        let mut package = duel();
+        package.map.weather.sea_state = MAX_SEA_STATE + 1;
+        assert!(package.validate().is_err());
+    }
+
+    #[test]
+    fn triggers_resolve_ids_and_fire_in_the_simulation() {
+        let mut package = duel();
+        let wave = ShipSnapshot::new("r2", "red", ShipState::at(5000.0, 0.0, 3.0));
+        package.triggers = vec![
+            TriggerSpec::new(
+                "reinforce",
+                ConditionSpec::ShipDestroyed {
+                    ship_id: "r1".to_string(),
+                },
+            )
+            .with_action(ActionSpec::SpawnWave { ships: vec![wave] }),
+            TriggerSpec::new("ceasefire", ConditionSpec::TickReached { tick: 2 })
+                .with_action(ActionSpec::SetRulesOfEngagement {
+                    team_id: "blue".to_string(),
+                    roe: RulesOfEngagement::HoldFire,
+                })
+                .with_action(ActionSpec::EndEpisode),
+        ];
+        let json = serde_json::to_string(&package).unwrap();
+        assert!(json.contains(r#""type":"ship_destroyed""#));
+        let package: BattlePackage = serde_json::from_str(&json).unwrap();
+
+        let (mut sim, ids) = package.build().unwrap();
+        sim.arena_mut().despawn(ids["r1"]);
+        sim.step();
+        let red = TeamId::new(1);
+        assert_eq!(sim.arena().entities_sorted().filter(|e| e.team() == Some(red)).count(), 1);
+        sim.step();
+        sim.step();
+        let blue = TeamId::new(0);
+        assert_eq!(sim.arena().rules_of_engagement(blue), RulesOfEngagement::HoldFire);
+        assert_eq!(sim.arena().triggers().episode_end(), Some(3));
+    }
+
+    #[test]
+    fn metadata_reaches_entities_in_team_colors() {
+        let mut package = duel();
+        package.teams[1].color = Some([200, 30, 30]);
+        package.ships[0].metadata = EntityMetadata {
+            name: Some("Resolute".to_string()),
+            hull_number: Some("D32".to_string()),
+            color: Some([20, 60, 200]),
+            ..EntityMetadata::default()
+        };
+        let mut wave = ShipSnapshot::new("r2", "red", ShipState::at(5000.0, 0.0, 3.0));
+        wave.metadata.class_name = Some("Corvette".to_string());
+        package.triggers = vec![TriggerSpec::new("wave", ConditionSpec::TickReached { tick: 0 })
+            .with_action(ActionSpec::SpawnWave { ships: vec![wave] })];
+        let json = serde_json::to_string(&package).unwrap();
+        let package: BattlePackage = serde_json::from_str(&json).unwrap();
+
+        let (mut sim, ids) = package.build().unwrap();
+        sim.step();
+        let arena = sim.arena();
+        let blue = arena.get(ids["b1"]).unwrap();
+        assert_eq!(blue.display_name(), "Resolute (D32)");
+        assert_eq!(blue.metadata().color, Some([20, 60, 200]));
+        assert_eq!(arena.get(ids["r1"]).unwrap().metadata().color, Some([200, 30, 30]));
+        let wave = arena.entities_sorted().last().unwrap().metadata();
+        assert_eq!(wave.class_name.as_deref(), Some("Corvette"));
+        assert_eq!(wave.color, Some([200, 30, 30]));
+    }
+
+    #[test]
+    fn spawn_regions_place_ships_clear_of_each_other() {
+        let mut package = duel();
+        package.ships = (0..6)
+            .map(|i| {
+                let state = ShipState::at(0.0, 0.0, 0.0);
+                let mut ship = ShipSnapshot::new(format!("b{i}"), "blue", state);
+                ship.spawn_region = Some(SpawnRegion::new(1500.0));
+                ship
+            })
+            .collect();
+        let positions = |sim: &Simulation, ids: &BTreeMap<String, EntityId>| -> Vec<Vec2> {
+            let ship = |id: &EntityId| sim.arena().get(*id).unwrap().as_ship().unwrap().clone();
+            ids.values().map(|id| ship(id).transform.position).collect()
+        };
+        let (sim, ids) = package.build().unwrap();
+        let spawned = positions(&sim, &ids);
+        for (i, a) in spawned.iter().enumerate() {
+            assert!(a.length() <= 1500.0);
+            for b in &spawned[i + 1..] {
+                assert!(a.distance(*b) > SpawnRegion::DEFAULT_MIN_SEPARATION);
+            }
+        }
+
+        // The same package spawns the same way
+        let (again, ids) = package.build().unwrap();
+        assert_eq!(positions(&again, &ids), spawned);
+    }
+
+    #[test]
+    fn crowded_spawn_region_fails_without_spawning() {
+        let mut package = duel();
+        let mut crowded = ShipSnapshot::new("b2", "blue", ShipState::at(0.0, 0.0, 0.0));
+        crowded.spawn_region = Some(SpawnRegion::new(50.0));
+        package.ships.push(crowded);
+        let mut arena = Arena::new();
+        assert_eq!(
+            package.spawn(&mut arena),
+            Err(ScenarioError::NoSpawnPosition("b2".to_string()))
+        );
+        assert_eq!(arena.entity_count(), 0);
+
+        package.ships[2].spawn_region = Some(SpawnRegion::new(f32::INFINITY));
+        assert!(matches!(
+            package.validate(),
+            Err(ScenarioError::InvalidValue {
+                field: "spawn_region",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_bad_triggers() {
+        let mut package = duel();
+        package.triggers = vec![TriggerSpec::new(
+            "lost",
+            ConditionSpec::HpBelow {
+                ship_id: "b9".to_string(),
+                fraction: 0.5,
+            },
+        )];
+        assert_eq!(
+            package.validate(),
+            Err(ScenarioError::UnknownReference {
+                trigger: "lost".to_string(),
+                id: "b9".to_string(),
+            })
+        );
+
+        let mut package = duel();
+        let wave = ShipSnapshot::new("b1", "blue", ShipState::at(0.0, 0.0, 0.0));
+        package.triggers = vec![TriggerSpec::new("wave", ConditionSpec::TickReached { tick: 9 })
+            .with_action(ActionSpec::SpawnWave { ships: vec![wave] })];
+        assert_eq!(
+            package.validate(),
+            Err(ScenarioError::DuplicateShip("b1".to_string()))
+        );
+
+        let mut package = duel();
+        let zone = ConditionSpec::ZoneEntered {
+            x: 0.0,
+            y: 0.0,
+            radius: -1.0,
+            team_id: None,
+        };
+        package.triggers = vec![TriggerSpec::new("zone", zone)];
+        assert!(matches!(
+            package.validate(),
+            Err(ScenarioError::InvalidValue { field: "zone", .. })
+        ));
+    }
+}
@@ -0,0 +1,273 @@
+//! Domain randomization of battle packages.
+//!
+//! A [`ScenarioRandomizer`] declares how far each property of a package may
+//! stray from its authored value and draws a concrete variant per seed.
+//! Every value is drawn from its own RNG stream keyed by (seed, ship ID,
+//! property), so the same seed always yields the same variant, and adding
+//! a ship or changing one distribution leaves every other draw unchanged.
+
+use std::f32::consts::TAU;
+use std::hash::Hasher;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use super::BattlePackage;
+use crate::arena::Fnv1a;
+use crate::environment::MAX_SEA_STATE;
+
+/// Distribution a randomized value is drawn from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Distribution {
+    /// Always the same value.
+    Constant(f32),
+    /// Uniform over `[min, max)`.
+    Uniform {
+        /// Lower bound
+        min: f32,
+        /// Upper bound
+        max: f32,
+    },
+    /// Gaussian.
+    Normal {
+        /// Mean
+        mean: f32,
+        /// Standard deviation
+        std_dev: f32,
+    },
+}
+
+impl Distribution {
+    /// Draws a value.
+    pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+        match *self {
+            Self::Constant(value) => value,
+            Self::Uniform { min, max } => min + (max - min) * rng.gen::<f32>(),
+            Self::Normal { mean, std_dev } => {
+                // Box-Muller; 1 - u keeps the logarithm finite
+                let radius = (-2.0 * (1.0 - rng.gen::<f32>()).ln()).sqrt();
+                mean + std_dev * radius * (TAU * rng.gen::<f32>()).cos()
+            }
+        }
+    }
+}
+
+/// Declared jitter applied to a battle package.
+///
+/// Offsets are added to the authored value; scales multiply it. The
+/// defaults leave the package unchanged.
+///
+/// # Example
+///
+/// ```
+/// use tidebreak_core::scenario::{
+///     BattlePackage, Distribution, ScenarioRandomizer, ShipSnapshot, ShipState, Team,
+/// };
+///
+/// let mut package = BattlePackage::new("duel", 7);
+/// package.teams = vec![Team::new("blue")];
+/// package.ships = vec![ShipSnapshot::new("b1", "blue", ShipState::at(0.0, 0.0, 0.0))];
+///
+/// let randomizer = ScenarioRandomizer {
+///     position: Distribution::Uniform { min: -500.0, max: 500.0 },
+///     ..ScenarioRandomizer::default()
+/// };
+/// let a = randomizer.randomize(&package, 1);
+/// assert_eq!(a, randomizer.randomize(&package, 1));
+/// assert_ne!(a, randomizer.randomize(&package, 2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioRandomizer {
+    /// Offset of each spawn coordinate, in meters
+    pub position: Distribution,
+    /// Offset of each spawn heading, in radians
+    pub heading: Distribution,
+    /// Scale of each weapon's ammunition (rounded, at least 0)
+    pub ammunition: Distribution,
+    /// Scale of each sensor's range (at least 0)
+    pub sensor_range: Distribution,
+    /// Offset of the sea state (rounded, clamped to 0-9)
+    pub sea_state: Distribution,
+    /// Offset of the starting hour (wrapped to 0-24)
+    pub hour: Distribution,
+}
+
+impl Default for ScenarioRandomizer {
+    fn default() -> Self {
+        Self {
+            position: Distribution::Constant(0.0),
+            heading: Distribution::Constant(0.0),
+            ammunition: Distribution::Constant(1.0),
+            sensor_range: Distribution::Constant(1.0),
+            sea_state: Distribution::Constant(0.0),
+            hour: Distribution::Constant(0.0),
+        }
+    }
+}
+
+impl ScenarioRandomizer {
+    /// Returns the variant of `package` drawn for `seed`.
+    ///
+    /// Spawn positions stay inside the map bounds, if any. The package seed
+    /// is kept, so variants differ only in the jittered properties.
+    #[must_use]
+    pub fn randomize(&self, package: &BattlePackage, seed: u64) -> BattlePackage {
+        let mut out = package.clone();
+        let stream = |owner: &str, property: &str| {
+            let mut hasher = Fnv1a::default();
+            hasher.write_u64(seed);
+            hasher.write_text(owner);
+            hasher.write_text(property);
+            ChaCha8Rng::seed_from_u64(hasher.finish())
+        };
+
+        let weather = &mut out.map.weather;
+        let sea = f32::from(weather.sea_state)
+            + self.sea_state.sample(&mut stream(&package.battle_id, "sea_state"));
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        {
+            weather.sea_state = sea.round().clamp(0.0, f32::from(MAX_SEA_STATE)) as u8;
+        }
+        let hour = weather.hour + self.hour.sample(&mut stream(&package.battle_id, "hour"));
+        // rem_euclid can round up to exactly 24
+        weather.hour = hour.rem_euclid(24.0) % 24.0;
+
+        for ship in &mut out.ships {
+            let id = ship.ship_id.as_str();
+            let state = &mut ship.initial_state;
+            let mut rng = stream(id, "position");
+            state.x += self.position.sample(&mut rng);
+            state.y += self.position.sample(&mut rng);
+            if let Some(b) = &package.map.bounds {
+                state.x = state.x.clamp(b.min_x, b.max_x);
+                state.y = state.y.clamp(b.min_y, b.max_y);
+            }
+            state.heading += self.heading.sample(&mut stream(id, "heading"));
+
+            let mut rng = stream(id, "sensor_range");
+            for sensor in &mut ship.sensors {
+                sensor.range *= self.sensor_range.sample(&mut rng).max(0.0);
+            }
+            let mut rng = stream(id, "ammunition");
+            for weapon in &mut ship.weapons {
+                let scale = self.ammunition.sample(&mut rng).max(0.0);
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss,
+                    clippy::cast_precision_loss
+                )]
+                {
+                    weapon.ammunition = (weapon.ammunition as f32 * scale).round() as u32;
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::AmmoType;
+    use crate::scenario::{
+        Bounds, SensorConfig, SensorType, ShipSnapshot, ShipState, Team, WeaponConfig,
+    };
+
+    fn package() -> BattlePackage {
+        let mut package = BattlePackage::new("test", 1);
+        package.teams = vec![Team::new("blue")];
+        let mut ship = ShipSnapshot::new("b1", "blue", ShipState::at(0.0, 0.0, 0.0));
+        ship.sensors.push(SensorConfig {
+            sensor_type: SensorType::Radar,
+            range: 1000.0,
+        });
+        ship.weapons.push(WeaponConfig {
+            slot: 0,
+            weapon_type: AmmoType::Shell,
+            cooldown: 1.0,
+            ammunition: 100,
+        });
+        package.ships = vec![
+            ship,
+            ShipSnapshot::new("b2", "blue", ShipState::at(100.0, 0.0, 0.0)),
+        ];
+        package
+    }
+
+    #[test]
+    fn default_leaves_package_unchanged() {
+        let package = package();
+        assert_eq!(ScenarioRandomizer::default().randomize(&package, 9), package);
+    }
+
+    #[test]
+    fn uniform_stays_within_bounds() {
+        let randomizer = ScenarioRandomizer {
+            position: Distribution::Uniform {
+                min: -50.0,
+                max: 50.0,
+            },
+            sensor_range: Distribution::Uniform { min: 0.5, max: 1.5 },
+            ammunition: Distribution::Uniform { min: 0.5, max: 1.0 },
+            ..ScenarioRandomizer::default()
+        };
+        for seed in 0..32 {
+            let out = randomizer.randomize(&package(), seed);
+            let ship = &out.ships[0];
+            assert!(ship.initial_state.x.abs() <= 50.0);
+            assert!((500.0..=1500.0).contains(&ship.sensors[0].range));
+            assert!((50..=100).contains(&ship.weapons[0].ammunition));
+            out.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn positions_are_clamped_to_map_bounds() {
+        let mut package = package();
+        package.map.bounds = Some(Bounds {
+            min_x: -10.0,
+            min_y: -10.0,
+            max_x: 10.0,
+            max_y: 10.0,
+        });
+        let randomizer = ScenarioRandomizer {
+            position: Distribution::Constant(1000.0),
+            ..ScenarioRandomizer::default()
+        };
+        let out = randomizer.randomize(&package, 0);
+        assert_eq!(out.ships[0].initial_state.x, 10.0);
+        assert_eq!(out.ships[1].initial_state.y, 10.0);
+    }
+
+    #[test]
+    fn weather_is_clamped_and_wrapped() {
+        let mut package = package();
+        package.map.weather.hour = 22.0;
+        let randomizer = ScenarioRandomizer {
+            sea_state: Distribution::Constant(20.0),
+            hour: Distribution::Constant(4.0),
+            ..ScenarioRandomizer::default()
+        };
+        let out = randomizer.randomize(&package, 0);
+        assert_eq!(out.map.weather.sea_state, MAX_SEA_STATE);
+        assert!((out.map.weather.hour - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn draws_are_independent_per_ship() {
+        let randomizer = ScenarioRandomizer {
+            heading: Distribution::Normal {
+                mean: 0.0,
+                std_dev: 0.5,
+            },
+            ..ScenarioRandomizer::default()
+        };
+        let full = randomizer.randomize(&package(), 3);
+        let mut single = package();
+        single.ships.remove(0);
+        let single = randomizer.randomize(&single, 3);
+        assert_eq!(full.ships[1], single.ships[0]);
+        assert_ne!(full.ships[0].initial_state.heading, 0.0);
+    }
+}
@@ -0,0 +1,862 @@
+//! Component schema introspection.
+//!
+//! Describes the components of each [`EntityTag`] and their fields (name,
+//! dtype, shape, optionality and documented range) for tooling such as UIs,
+//! config validators and observation builders, so they need not hardcode
+//! component layouts.
+//!
+//! The description is generated from the components' `Serialize`
+//! implementations: a probe entity with every optional component and
+//! field present, and one element in every list and map, is walked field
+//! by field. Fields added to a component therefore appear here without
+//! further changes. Only value ranges are maintained by hand.
+//!
+//! # Naming
+//!
+//! Field names are dotted paths within their component, e.g.
+//! `engine.reverse_limit` in `physics`. Elements of lists and values of
+//! maps are marked `[]` and `{}`: `weapons[].cooldown` in `combat`,
+//! `ammo{}` in `inventory`. Such fields are `repeated`.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::entity::EntityTag;
+//! use tidebreak_core::schema::{tag_schema, Dtype};
+//!
+//! let ship = tag_schema(EntityTag::Ship);
+//! let physics = ship.component("physics").unwrap();
+//! let throttle = physics.field("throttle").unwrap();
+//! assert_eq!(throttle.dtype, Dtype::Float32);
+//! assert!(throttle.optional);
+//! assert_eq!((throttle.min, throttle.max), (Some(-1.0), Some(1.0)));
+//!
+//! let position = ship.component("transform").unwrap().field("position").unwrap();
+//! assert_eq!(position.shape, vec![2]);
+//! assert!(ship.component("submarine").unwrap().optional);
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use glam::Vec2;
+use serde::ser::{self, Serialize};
+
+use crate::entity::{
+    AirWing, AmmoType, CombatState, EntityId, EntityTag, GunBallistics, InventoryState, MineState,
+    MitigationState, PhysicsState, PlatformComponents, ProjectileComponents, SeekerState,
+    SensorState, ShipComponents, SquadronComponents, SubmarineState, Track, TrackFix,
+    WeaponState,
+};
+
+/// Documented value ranges by component and field path.
+const RANGES: &[(&str, Option<f64>, Option<f64>)] = &[
+    ("transform.radius", Some(0.0), None),
+    ("physics.max_speed", Some(0.0), None),
+    ("physics.max_turn_rate", Some(0.0), None),
+    ("physics.throttle", Some(-1.0), Some(1.0)),
+    ("physics.engine.acceleration", Some(0.0), None),
+    ("physics.engine.deceleration", Some(0.0), None),
+    ("physics.engine.reverse_limit", Some(0.0), Some(1.0)),
+    ("physics.engine.full_speed_turn", Some(0.0), Some(1.0)),
+    ("physics.engine.min_turn_radius", Some(0.0), None),
+    ("combat.hp", Some(0.0), None),
+    ("combat.max_hp", Some(0.0), None),
+    ("combat.mitigation.capacity", Some(0.0), None),
+    ("combat.mitigation.pool", Some(0.0), None),
+    ("combat.mitigation.regen_rate", Some(0.0), None),
+    ("combat.mitigation.regen_delay", Some(0.0), None),
+    ("combat.mitigation.recharge_in", Some(0.0), None),
+    ("combat.mitigation.resistances.kinetic", Some(0.0), Some(1.0)),
+    ("combat.mitigation.resistances.explosive", Some(0.0), Some(1.0)),
+    ("combat.mitigation.resistances.energy", Some(0.0), Some(1.0)),
+    ("combat.weapons[].cooldown", Some(0.0), None),
+    ("combat.weapons[].max_cooldown", Some(0.0), None),
+    ("combat.weapons[].gun.max_range", Some(0.0), None),
+    ("combat.weapons[].gun.dispersion", Some(0.0), None),
+    ("combat.weapons[].gun.hit_radius", Some(0.0), None),
+    ("combat.weapons[].max_hp", Some(0.0), None),
+    ("combat.weapons[].hp", Some(0.0), None),
+    ("combat.subsystem_exposure", Some(0.0), Some(1.0)),
+    ("sensor.radar_range", Some(0.0), None),
+    ("sensor.sonar_range", Some(0.0), None),
+    ("sensor.visual_range", Some(0.0), None),
+    ("sensor.max_hp", Some(0.0), None),
+    ("sensor.hp", Some(0.0), None),
+    ("sensor.track_table[].age", Some(0.0), None),
+    ("sensor.track_table[].classification_confidence", Some(0.0), Some(1.0)),
+    ("sensor.track_table[].history[].time", Some(0.0), None),
+    ("inventory.fuel", Some(0.0), None),
+    ("inventory.max_fuel", Some(0.0), None),
+    ("stockpile.fuel", Some(0.0), None),
+    ("stockpile.max_fuel", Some(0.0), None),
+    ("submarine.depth", Some(0.0), None),
+    ("submarine.ordered_depth", Some(0.0), None),
+    ("submarine.battery", Some(0.0), None),
+    ("submarine.max_battery", Some(0.0), None),
+    ("air_wing.ordnance", Some(0.0), None),
+    ("air_wing.max_ordnance", Some(0.0), None),
+    ("air_wing.engagement_range", Some(0.0), None),
+    ("air_wing.lethality", Some(0.0), None),
+    ("mine.trigger_radius", Some(0.0), None),
+    ("mine.blast_radius", Some(0.0), None),
+    ("seeker.fov", Some(0.0), None),
+    ("seeker.range", Some(0.0), None),
+];
+
+// =============================================================================
+// Schema Types
+// =============================================================================
+
+/// Element type of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dtype {
+    /// Boolean
+    Bool,
+    /// Signed 8-bit integer
+    Int8,
+    /// Signed 16-bit integer
+    Int16,
+    /// Signed 32-bit integer
+    Int32,
+    /// Signed 64-bit integer
+    Int64,
+    /// Unsigned 8-bit integer
+    UInt8,
+    /// Unsigned 16-bit integer
+    UInt16,
+    /// Unsigned 32-bit integer
+    UInt32,
+    /// Unsigned 64-bit integer (including entity IDs and indices)
+    UInt64,
+    /// 32-bit float
+    Float32,
+    /// 64-bit float
+    Float64,
+    /// String
+    Str,
+    /// Enum variant name
+    Enum,
+    /// Not determinable from the probe entity (an unset option or empty
+    /// collection); never produced for the built-in components
+    Unknown,
+}
+
+impl Dtype {
+    /// Returns the numpy-style name of the dtype (`float32`, `bool`, ...).
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::Int8 => "int8",
+            Self::Int16 => "int16",
+            Self::Int32 => "int32",
+            Self::Int64 => "int64",
+            Self::UInt8 => "uint8",
+            Self::UInt16 => "uint16",
+            Self::UInt32 => "uint32",
+            Self::UInt64 => "uint64",
+            Self::Float32 => "float32",
+            Self::Float64 => "float64",
+            Self::Str => "str",
+            Self::Enum => "enum",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Dtype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Description of one component field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSchema {
+    /// Path within the component (see the module docs).
+    pub name: String,
+    /// Element type.
+    pub dtype: Dtype,
+    /// Fixed array shape; empty for scalars, `[2]` for vectors.
+    pub shape: Vec<usize>,
+    /// Whether the field may be absent (`None`) within its component.
+    pub optional: bool,
+    /// Whether the field repeats per list element or map value.
+    pub repeated: bool,
+    /// Documented minimum value, if any.
+    pub min: Option<f64>,
+    /// Documented maximum value, if any.
+    pub max: Option<f64>,
+}
+
+/// Description of one component of an entity tag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentSchema {
+    /// Component name, e.g. `physics`.
+    pub name: String,
+    /// Whether entities of the tag may lack the component.
+    pub optional: bool,
+    /// Fields in declaration order.
+    pub fields: Vec<FieldSchema>,
+}
+
+impl ComponentSchema {
+    /// Returns the field named `name`, if any.
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+}
+
+/// Description of the components of one entity tag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagSchema {
+    /// The entity tag.
+    pub tag: EntityTag,
+    /// Components in declaration order.
+    pub components: Vec<ComponentSchema>,
+}
+
+impl TagSchema {
+    /// Returns the component named `name`, if any.
+    #[must_use]
+    pub fn component(&self, name: &str) -> Option<&ComponentSchema> {
+        self.components.iter().find(|c| c.name == name)
+    }
+}
+
+/// Returns the schema of every entity tag.
+#[must_use]
+pub fn schema() -> Vec<TagSchema> {
+    [
+        EntityTag::Ship,
+        EntityTag::Platform,
+        EntityTag::Projectile,
+        EntityTag::Squadron,
+    ]
+    .into_iter()
+    .map(tag_schema)
+    .collect()
+}
+
+/// Returns the schema of the components of `tag`.
+#[must_use]
+pub fn tag_schema(tag: EntityTag) -> TagSchema {
+    let walked = match tag {
+        EntityTag::Ship => walk(&probe_ship()),
+        EntityTag::Platform => walk(&probe_platform()),
+        EntityTag::Projectile => walk(&probe_projectile()),
+        EntityTag::Squadron => walk(&probe_squadron()),
+    };
+
+    let mut components: Vec<ComponentSchema> = Vec::new();
+    for mut field in walked.fields {
+        let (component, name) = field.name.split_once('.').unwrap_or((&field.name, ""));
+        let (component, name) = (component.to_string(), name.to_string());
+        if let Some(&(_, min, max)) = RANGES.iter().find(|(path, ..)| *path == field.name) {
+            field.min = min;
+            field.max = max;
+        }
+        field.name = name;
+        match components.last_mut() {
+            Some(last) if last.name == component => last.fields.push(field),
+            _ => components.push(ComponentSchema {
+                optional: walked.optional_components.contains(&component),
+                name: component,
+                fields: vec![field],
+            }),
+        }
+    }
+    TagSchema { tag, components }
+}
+
+// =============================================================================
+// Probe Entities
+// =============================================================================
+
+fn probe_physics() -> PhysicsState {
+    PhysicsState {
+        throttle: Some(0.0),
+        ordered_heading: Some(0.0),
+        ..PhysicsState::default()
+    }
+}
+
+fn probe_combat() -> CombatState {
+    CombatState {
+        weapons: vec![WeaponState::default().with_gun(GunBallistics::default())],
+        last_damager: Some(EntityId::new(0)),
+        damage_by_source: [(EntityId::new(0), 0.0)].into(),
+        ..CombatState::default()
+    }
+    .with_mitigation(MitigationState::default())
+}
+
+fn probe_sensor() -> SensorState {
+    SensorState {
+        track_table: vec![Track {
+            velocity: Some(Vec2::ZERO),
+            classified_as: Some(EntityTag::Ship),
+            history: [TrackFix {
+                time: 0.0,
+                position: Vec2::ZERO,
+            }]
+            .into(),
+            ..Track::default()
+        }],
+        ..SensorState::default()
+    }
+}
+
+fn probe_inventory() -> InventoryState {
+    let mut inventory = InventoryState::default();
+    inventory.ammo.insert(AmmoType::Shell, 0);
+    inventory
+}
+
+fn probe_ship() -> ShipComponents {
+    ShipComponents {
+        physics: probe_physics(),
+        combat: probe_combat(),
+        sensor: probe_sensor(),
+        inventory: probe_inventory(),
+        submarine: Some(SubmarineState::default()),
+        ..ShipComponents::default()
+    }
+}
+
+fn probe_platform() -> PlatformComponents {
+    PlatformComponents {
+        sensor: probe_sensor(),
+        mine: Some(MineState::default()),
+        stockpile: Some(probe_inventory()),
+        ..PlatformComponents::default()
+    }
+}
+
+fn probe_projectile() -> ProjectileComponents {
+    ProjectileComponents {
+        physics: probe_physics(),
+        seeker: Some(SeekerState {
+            cue: Some(EntityId::new(0)),
+            lock: Some(EntityId::new(0)),
+            ..SeekerState::default()
+        }),
+        ..ProjectileComponents::default()
+    }
+}
+
+fn probe_squadron() -> SquadronComponents {
+    SquadronComponents {
+        physics: probe_physics(),
+        combat: probe_combat(),
+        air_wing: Some(AirWing {
+            intercept: Some(EntityId::new(0)),
+            ..AirWing::default()
+        }),
+        ..SquadronComponents::default()
+    }
+}
+
+// =============================================================================
+// Walker
+// =============================================================================
+
+/// Fields collected from one walk.
+#[derive(Default)]
+struct Walked {
+    fields: Vec<FieldSchema>,
+    /// Top-level fields that are `Option`s
+    optional_components: BTreeSet<String>,
+}
+
+fn walk<T: Serialize>(value: &T) -> Walked {
+    let mut walked = Walked::default();
+    // The walker itself never fails, and component types serialize cleanly
+    let _ = value.serialize(Walker {
+        out: &mut walked,
+        path: String::new(),
+        optional: false,
+        repeated: false,
+    });
+    walked
+}
+
+#[derive(Debug)]
+struct WalkError(String);
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for WalkError {}
+
+impl ser::Error for WalkError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Serializer recording the dtype of every leaf under `path`.
+struct Walker<'a> {
+    out: &'a mut Walked,
+    path: String,
+    optional: bool,
+    repeated: bool,
+}
+
+impl Walker<'_> {
+    fn child(&mut self, path: String) -> Walker<'_> {
+        Walker {
+            out: &mut *self.out,
+            path,
+            optional: self.optional,
+            repeated: self.repeated,
+        }
+    }
+
+    // Returns a `Result` to serve as the tail of the serializer methods
+    #[allow(clippy::unnecessary_wraps)]
+    fn leaf(self, dtype: Dtype) -> Result<(), WalkError> {
+        self.out.fields.push(FieldSchema {
+            name: self.path,
+            dtype,
+            shape: Vec::new(),
+            optional: self.optional,
+            repeated: self.repeated,
+            min: None,
+            max: None,
+        });
+        Ok(())
+    }
+
+    fn join(&self, key: &str) -> String {
+        if self.path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{key}", self.path)
+        }
+    }
+}
+
+impl<'a> ser::Serializer for Walker<'a> {
+    type Ok = ();
+    type Error = WalkError;
+    type SerializeSeq = Collection<'a>;
+    type SerializeTuple = Tuple<'a>;
+    type SerializeTupleStruct = Tuple<'a>;
+    type SerializeTupleVariant = Ignore;
+    type SerializeMap = Collection<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Ignore;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<(), WalkError> {
+        self.leaf(Dtype::Bool)
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<(), WalkError> {
+        self.leaf(Dtype::Int8)
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<(), WalkError> {
+        self.leaf(Dtype::Int16)
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<(), WalkError> {
+        self.leaf(Dtype::Int32)
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<(), WalkError> {
+        self.leaf(Dtype::Int64)
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<(), WalkError> {
+        self.leaf(Dtype::UInt8)
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<(), WalkError> {
+        self.leaf(Dtype::UInt16)
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<(), WalkError> {
+        self.leaf(Dtype::UInt32)
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<(), WalkError> {
+        self.leaf(Dtype::UInt64)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<(), WalkError> {
+        self.leaf(Dtype::Float32)
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<(), WalkError> {
+        self.leaf(Dtype::Float64)
+    }
+
+    fn serialize_char(self, _: char) -> Result<(), WalkError> {
+        self.leaf(Dtype::Str)
+    }
+
+    fn serialize_str(self, _: &str) -> Result<(), WalkError> {
+        self.leaf(Dtype::Str)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), WalkError> {
+        self.leaf(Dtype::Unknown)
+    }
+
+    fn serialize_none(mut self) -> Result<(), WalkError> {
+        self.optional = true;
+        self.leaf(Dtype::Unknown)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(mut self, value: &T) -> Result<(), WalkError> {
+        if self.path.contains('.') {
+            self.optional = true;
+        } else {
+            // An optional component: its fields are present with it
+            self.out.optional_components.insert(self.path.clone());
+        }
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), WalkError> {
+        self.leaf(Dtype::Unknown)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), WalkError> {
+        self.leaf(Dtype::Unknown)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), WalkError> {
+        self.leaf(Dtype::Enum)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), WalkError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), WalkError> {
+        self.leaf(Dtype::Enum)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Collection<'a>, WalkError> {
+        Ok(Collection::new(self, "[]"))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Tuple<'a>, WalkError> {
+        Ok(Tuple {
+            walker: self,
+            elements: Walked::default(),
+        })
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Tuple<'a>, WalkError> {
+        Ok(Tuple {
+            walker: self,
+            elements: Walked::default(),
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Ignore, WalkError> {
+        self.leaf(Dtype::Enum)?;
+        Ok(Ignore)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Collection<'a>, WalkError> {
+        Ok(Collection::new(self, "{}"))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, WalkError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Ignore, WalkError> {
+        self.leaf(Dtype::Enum)?;
+        Ok(Ignore)
+    }
+}
+
+impl ser::SerializeStruct for Walker<'_> {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), WalkError> {
+        let path = self.join(key);
+        value.serialize(self.child(path))
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+/// A list or map, described by its first element or value.
+struct Collection<'a> {
+    walker: Walker<'a>,
+    suffix: &'static str,
+    described: bool,
+}
+
+impl<'a> Collection<'a> {
+    const fn new(walker: Walker<'a>, suffix: &'static str) -> Self {
+        Self {
+            walker,
+            suffix,
+            described: false,
+        }
+    }
+
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), WalkError> {
+        if self.described {
+            return Ok(());
+        }
+        self.described = true;
+        let path = format!("{}{}", self.walker.path, self.suffix);
+        let mut child = self.walker.child(path);
+        child.repeated = true;
+        value.serialize(child)
+    }
+
+    fn finish(mut self) -> Result<(), WalkError> {
+        if self.described {
+            return Ok(());
+        }
+        self.walker.path.push_str(self.suffix);
+        self.walker.repeated = true;
+        self.walker.leaf(Dtype::Unknown)
+    }
+}
+
+impl ser::SerializeSeq for Collection<'_> {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), WalkError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for Collection<'_> {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, _: &T) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), WalkError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        self.finish()
+    }
+}
+
+/// A tuple; homogeneous scalar tuples such as `Vec2` collapse into one
+/// field with a shape.
+struct Tuple<'a> {
+    walker: Walker<'a>,
+    elements: Walked,
+}
+
+impl Tuple<'_> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), WalkError> {
+        let index = self.elements.fields.len();
+        value.serialize(Walker {
+            out: &mut self.elements,
+            path: format!("{}.{index}", self.walker.path),
+            optional: self.walker.optional,
+            repeated: self.walker.repeated,
+        })
+    }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn finish(self) -> Result<(), WalkError> {
+        let fields = self.elements.fields;
+        let dtype = fields.first().map(|f| f.dtype);
+        let homogeneous = fields
+            .iter()
+            .all(|f| f.shape.is_empty() && Some(f.dtype) == dtype);
+        match dtype {
+            Some(dtype) if homogeneous => {
+                let len = fields.len();
+                self.walker.out.fields.push(FieldSchema {
+                    name: self.walker.path,
+                    dtype,
+                    shape: vec![len],
+                    optional: self.walker.optional,
+                    repeated: self.walker.repeated,
+                    min: None,
+                    max: None,
+                });
+            }
+            _ => self.walker.out.fields.extend(fields),
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Tuple<'_> {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), WalkError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for Tuple<'_> {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), WalkError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        self.finish()
+    }
+}
+
+/// Skips the payload of enum variants, recorded as [`Dtype::Enum`].
+struct Ignore;
+
+impl ser::SerializeTupleVariant for Ignore {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _: &T) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Ignore {
+    type Ok = ();
+    type Error = WalkError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), WalkError> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), WalkError> {
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_field_has_a_known_dtype() {
+        for tag in schema() {
+            for component in &tag.components {
+                for field in &component.fields {
+                    assert_ne!(
+                        field.dtype,
+                        Dtype::Unknown,
+                        "{}.{}.{}: extend the probe entity",
+                        tag.tag,
+                        component.name,
+                        field.name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn components_match_serialized_layout() {
+        let ship = tag_schema(EntityTag::Ship);
+        let serialized = serde_json::to_value(ShipComponents::default()).unwrap();
+        let expected: Vec<&String> = serialized.as_object().unwrap().keys().collect();
+        let mut names: Vec<&String> = ship.components.iter().map(|c| &c.name).collect();
+        names.sort();
+        assert_eq!(names, expected);
+
+        let combat = ship.component("combat").unwrap();
+        let cooldown = combat.field("weapons[].cooldown").unwrap();
+        assert!(cooldown.repeated && !cooldown.optional);
+        assert_eq!(combat.field("status_flags").unwrap().dtype, Dtype::UInt32);
+        let ammo = ship.component("inventory").unwrap().field("ammo{}").unwrap();
+        assert_eq!(ammo.dtype, Dtype::UInt32);
+
+        // Fields of an optional component are present whenever it is
+        let depth = ship.component("submarine").unwrap().field("depth").unwrap();
+        assert!(!depth.optional);
+    }
+
+    #[test]
+    fn every_range_names_a_field() {
+        let all = schema();
+        for (path, ..) in RANGES {
+            let (component, name) = path.split_once('.').unwrap();
+            let found = all.iter().any(|tag| {
+                let field = tag.component(component).and_then(|c| c.field(name));
+                field.is_some_and(|f| f.min.is_some() || f.max.is_some())
+            });
+            assert!(found, "range for unknown field {path}");
+        }
+    }
+}
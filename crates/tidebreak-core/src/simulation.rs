@@ -39,21 +39,113 @@
 //! ```
 
 use rayon::prelude::*;
-use std::collections::hash_map::DefaultHasher;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-
-use crate::arena::Arena;
-use crate::output::{OutputEnvelope, PluginInstanceId, TraceId};
+use std::time::{Duration, Instant};
+
+use crate::arena::{Arena, Fnv1a, WorldBounds};
+use crate::balance::{BalanceConfig, BalanceEvaluator, BalanceReport};
+use crate::battle_log::query::EventHistory;
+use crate::battle_log::{BattleLog, BattleLogConfig};
+use crate::debugger::{Breakpoint, BreakpointHit, BreakpointId, StopReason};
+use crate::entity::ControllerId;
+use crate::kill_ledger::KillLedger;
+use crate::output::{Command, Event, Output, OutputEnvelope, PluginId, PluginInstanceId, TraceId};
 use crate::plugin::{PluginContext, PluginRegistry};
-use crate::resolver::{CombatResolver, EventResolver, PhysicsResolver, Resolver};
+#[cfg(feature = "replay")]
+use crate::replay::{Replay, ReplayError};
+use crate::resolver::{
+    priority, AggregateCombatConfig, AggregateCombatResolver, AssignmentConfig,
+    ClassificationModel, ClassificationResolver, CombatResolver, EventResolver, Heatmap,
+    HeatmapConfig, HeatmapRecorder, LifetimeResolver, LogisticsResolver, MinefieldResolver,
+    OrderResolver, PhysicsResolver, ProximityResolver, Resolver, ResolverId, SafetyResolver,
+    ScoreKeeper, ScoringRules, SmokeResolver, SubmarineResolver, TeamScore, TrackResolver,
+    TriggerResolver, WeaponAssignmentResolver, WeaponResolver, FIXED_DT,
+};
+use crate::rng_audit::RngAuditLog;
+use crate::watchdog::{PluginBudget, PluginWatchdog};
 use crate::world_view::WorldView;
 
+/// Plugin ID recorded as the source of commands passed to
+/// [`Simulation::step_with_commands`].
+pub const EXTERNAL_PLUGIN: &str = "external";
+
+// =============================================================================
+// SimulationConfig
+// =============================================================================
+
+/// How engagements between combat entities are resolved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CombatModel {
+    /// Per-weapon, per-projectile combat driven by plugins.
+    #[default]
+    Projectile,
+    /// Statistical Lanchester attrition between squadron/fleet-level
+    /// entities, for the strategic layer.
+    Aggregate(AggregateCombatConfig),
+}
+
+/// Optional simulation features.
+///
+/// The default config enables nothing beyond the core execution loop.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulationConfig {
+    /// Stream events, damage and entity summaries to Arrow IPC files.
+    pub battle_log: Option<BattleLogConfig>,
+    /// Play area enforced on moving entities (see [`Arena::set_bounds`]).
+    pub bounds: Option<WorldBounds>,
+    /// Combat resolution model.
+    pub combat: CombatModel,
+    /// Per-run time budget enforced on every plugin instance.
+    pub plugin_budget: Option<PluginBudget>,
+    /// Weights used by [`Simulation::evaluate_balance`].
+    pub balance: BalanceConfig,
+    /// Physics ticks per agent decision (0 and 1 both mean every tick).
+    ///
+    /// See [`Simulation::set_action_interval`].
+    pub action_interval: u32,
+    /// Seconds per physics tick; `None` keeps the default of 1/60 s.
+    ///
+    /// See [`Simulation::set_physics_dt`].
+    pub physics_dt: Option<f32>,
+    /// Mission scoring rules, if scores should be kept.
+    ///
+    /// See [`Simulation::enable_scoring`].
+    pub scoring: Option<ScoringRules>,
+    /// Same-team fire deconfliction, if unneeded shots should be dropped.
+    ///
+    /// See [`Simulation::enable_weapon_assignment`].
+    pub weapon_assignment: Option<AssignmentConfig>,
+    /// Track classification model, if detections should identify contacts.
+    ///
+    /// See [`Simulation::enable_classification`].
+    pub classification: Option<ClassificationModel>,
+}
+
 // =============================================================================
 // Simulation
 // =============================================================================
 
+/// A tick whose plugin outputs have not been resolved yet.
+#[derive(Debug)]
+struct PausedTick {
+    outputs: Vec<OutputEnvelope>,
+    hits: Vec<BreakpointHit>,
+}
+
+/// One plugin run of a tick, not yet recorded with the watchdog.
+struct PluginRun {
+    instance: PluginInstanceId,
+    plugin_idx: usize,
+    every_tick: bool,
+    trace_id: TraceId,
+    elapsed: Duration,
+    envelopes: Vec<OutputEnvelope>,
+}
+
 /// The main simulation orchestrator implementing the 4-phase execution loop.
 ///
 /// `Simulation` manages:
@@ -84,8 +176,51 @@ pub struct Simulation {
     next: Arena,
     /// Registry of plugins organized by entity tag.
     plugins: PluginRegistry,
-    /// Resolvers that process plugin outputs.
-    resolvers: Vec<Box<dyn Resolver>>,
+    /// Resolvers that process plugin outputs, with their priorities, in run
+    /// order.
+    resolvers: Vec<(ResolverId, i32, Box<dyn Resolver>)>,
+    /// ID assigned to the next registered resolver.
+    next_resolver_id: u32,
+    /// Event log of the most recent step (shared with `resolvers`).
+    events: Arc<EventResolver>,
+    /// Audit log of random draws (shared with `resolvers`).
+    rng_audit: Arc<RngAuditLog>,
+    /// Kill, assist and damage tallies per controller (shared with
+    /// `resolvers`).
+    kill_ledger: Arc<KillLedger>,
+    /// Events of past steps, if enabled.
+    event_history: EventHistory,
+    /// Battle log sink, if enabled.
+    battle_log: Option<BattleLog>,
+    /// First battle log write error; the log is closed when one occurs.
+    battle_log_error: Option<io::Error>,
+    /// Breakpoints checked by `run_until`, in insertion order.
+    breakpoints: Vec<(BreakpointId, Breakpoint)>,
+    /// ID assigned to the next breakpoint.
+    next_breakpoint_id: u32,
+    /// Tick paused before resolution by a breakpoint.
+    paused: Option<PausedTick>,
+    /// Plugin timings and budget enforcement.
+    watchdog: PluginWatchdog,
+    /// Team strength heuristic for curriculum scheduling.
+    balance: BalanceEvaluator,
+    /// Mission scores, run after every other resolver, if enabled.
+    scoring: Option<Arc<ScoreKeeper>>,
+    /// Visitation and exposure heatmap, run after scoring, if enabled.
+    heatmap: Option<Arc<HeatmapRecorder>>,
+    /// Fire deconfliction, run on the outputs before the resolvers, if
+    /// enabled.
+    assignment: Option<Arc<WeaponAssignmentResolver>>,
+    /// Track classification, run after the resolvers so tracks started this
+    /// tick are classified, if enabled.
+    classification: Option<Arc<ClassificationResolver>>,
+    /// Seconds simulated per physics tick.
+    physics_dt: f32,
+    /// Physics ticks per plugin (decision) run.
+    action_interval: u32,
+    /// Sustained commands from the last decision tick, replayed until the
+    /// next one.
+    held_commands: Vec<OutputEnvelope>,
     /// Master seed for deterministic trace ID generation.
     master_seed: u64,
 }
@@ -97,6 +232,25 @@ impl fmt::Debug for Simulation {
             .field("next", &self.next)
             .field("plugins", &self.plugins)
             .field("resolvers", &format!("[{} resolvers]", self.resolvers.len()))
+            .field("next_resolver_id", &self.next_resolver_id)
+            .field("events", &self.events.event_count())
+            .field("rng_audit", &self.rng_audit.capacity())
+            .field("kill_ledger", &self.kill_ledger)
+            .field("event_history", &self.event_history.len())
+            .field("battle_log", &self.battle_log)
+            .field("battle_log_error", &self.battle_log_error)
+            .field("breakpoints", &self.breakpoints)
+            .field("next_breakpoint_id", &self.next_breakpoint_id)
+            .field("paused", &self.paused)
+            .field("watchdog", &self.watchdog)
+            .field("balance", &self.balance)
+            .field("scoring", &self.scoring)
+            .field("heatmap", &self.heatmap)
+            .field("assignment", &self.assignment)
+            .field("classification", &self.classification)
+            .field("physics_dt", &self.physics_dt)
+            .field("action_interval", &self.action_interval)
+            .field("held_commands", &self.held_commands.len())
             .field("master_seed", &self.master_seed)
             .finish()
     }
@@ -106,7 +260,9 @@ impl Simulation {
     /// Creates a new simulation with the given master seed.
     ///
     /// The simulation starts at tick 0 with empty arenas and the default
-    /// set of resolvers (Physics, Combat, Event).
+    /// set of resolvers (Physics, Safety, Combat, Weapon, Minefield,
+    /// Logistics, Submarine, Smoke, Track, Proximity, Order, Trigger,
+    /// Lifetime, Event), run in the order of their [`priority`].
     ///
     /// # Arguments
     ///
@@ -123,19 +279,123 @@ impl Simulation {
     /// ```
     #[must_use]
     pub fn new(seed: u64) -> Self {
+        let events = Arc::new(EventResolver::new());
+        let rng_audit = Arc::new(RngAuditLog::new());
+        let kill_ledger = Arc::new(KillLedger::new());
+        let defaults: Vec<(i32, Box<dyn Resolver>)> = vec![
+            (
+                priority::PHYSICS,
+                Box::new(PhysicsResolver::new().with_event_log(Arc::clone(&events))),
+            ),
+            (
+                priority::SAFETY,
+                Box::new(SafetyResolver::new().with_event_log(Arc::clone(&events))),
+            ),
+            (
+                priority::COMBAT,
+                Box::new(
+                    CombatResolver::new()
+                        .with_seed(seed)
+                        .with_event_log(Arc::clone(&events))
+                        .with_rng_audit(Arc::clone(&rng_audit))
+                        .with_kill_ledger(Arc::clone(&kill_ledger)),
+                ),
+            ),
+            (
+                priority::WEAPON,
+                Box::new(WeaponResolver::new().with_event_log(Arc::clone(&events))),
+            ),
+            (
+                priority::MINEFIELD,
+                Box::new(
+                    MinefieldResolver::new(seed)
+                        .with_event_log(Arc::clone(&events))
+                        .with_rng_audit(Arc::clone(&rng_audit)),
+                ),
+            ),
+            (
+                priority::LOGISTICS,
+                Box::new(LogisticsResolver::new().with_event_log(Arc::clone(&events))),
+            ),
+            (
+                priority::SUBMARINE,
+                Box::new(SubmarineResolver::new().with_event_log(Arc::clone(&events))),
+            ),
+            (priority::SMOKE, Box::new(SmokeResolver::new())),
+            (priority::TRACKS, Box::new(TrackResolver::new())),
+            (priority::PROXIMITY, Box::new(ProximityResolver::new())),
+            (priority::ORDERS, Box::new(OrderResolver::new())),
+            (priority::TRIGGERS, Box::new(TriggerResolver::new())),
+            (
+                priority::LIFETIME,
+                Box::new(LifetimeResolver::new().with_event_log(Arc::clone(&events))),
+            ),
+            (priority::EVENTS, Box::new(Arc::clone(&events))),
+        ];
+        let resolvers: Vec<_> = (0..)
+            .zip(defaults)
+            .map(|(id, (priority, resolver))| (ResolverId(id), priority, resolver))
+            .collect();
         Self {
             current: Arena::default(),
             next: Arena::default(),
             plugins: PluginRegistry::new(),
-            resolvers: vec![
-                Box::new(PhysicsResolver::new()),
-                Box::new(CombatResolver::new()),
-                Box::new(EventResolver::new()),
-            ],
+            next_resolver_id: u32::try_from(resolvers.len()).unwrap_or(u32::MAX),
+            resolvers,
+            events,
+            rng_audit,
+            kill_ledger,
+            event_history: EventHistory::new(),
+            battle_log: None,
+            battle_log_error: None,
+            breakpoints: Vec::new(),
+            next_breakpoint_id: 0,
+            paused: None,
+            watchdog: PluginWatchdog::default(),
+            balance: BalanceEvaluator::new(),
+            scoring: None,
+            heatmap: None,
+            assignment: None,
+            classification: None,
+            physics_dt: FIXED_DT,
+            action_interval: 1,
+            held_commands: Vec::new(),
             master_seed: seed,
         }
     }
 
+    /// Creates a new simulation with optional features enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the battle log files cannot be created.
+    pub fn with_config(seed: u64, config: SimulationConfig) -> io::Result<Self> {
+        let mut sim = Self::new(seed);
+        sim.current.set_bounds(config.bounds);
+        sim.watchdog.set_budget(config.plugin_budget);
+        sim.balance = BalanceEvaluator::with_config(config.balance);
+        sim.set_action_interval(config.action_interval);
+        if let Some(dt) = config.physics_dt {
+            sim.set_physics_dt(dt);
+        }
+        if let Some(rules) = config.scoring {
+            sim.enable_scoring(rules);
+        }
+        if let Some(assignment) = config.weapon_assignment {
+            sim.enable_weapon_assignment(assignment);
+        }
+        if let Some(model) = config.classification {
+            sim.enable_classification(model);
+        }
+        if let CombatModel::Aggregate(aggregate) = config.combat {
+            sim.add_resolver(Box::new(AggregateCombatResolver::with_config(aggregate)));
+        }
+        if let Some(battle_log) = config.battle_log {
+            sim.open_battle_log(battle_log)?;
+        }
+        Ok(sim)
+    }
+
     /// Executes one simulation tick using the 4-phase execution loop.
     ///
     /// # Execution Phases
@@ -155,112 +415,443 @@ impl Simulation {
     ///
     /// # Determinism
     ///
-    /// Plugin outputs are sorted by (`entity_id`, `plugin_id`, sequence) before
-    /// resolution to ensure deterministic processing regardless of parallel
-    /// execution order.
+    /// Plugin outputs are sorted by (`entity_id`, plugin order, sequence)
+    /// before resolution to ensure deterministic processing regardless of
+    /// parallel execution order. Plugin order is the topological order of
+    /// the entity's [`PluginRegistry`] bundle.
+    ///
+    /// # Breakpoints
+    ///
+    /// `step()` does not check breakpoints. If the simulation is paused by
+    /// [`run_until`](Self::run_until), it resolves the pending outputs
+    /// instead of running the plugins again.
     pub fn step(&mut self) {
+        let outputs = match self.paused.take() {
+            Some(paused) => paused.outputs,
+            None => self.run_plugins(),
+        };
+        self.resolve_tick(&outputs);
+    }
+
+    /// Executes one tick unless `cancel` is set.
+    ///
+    /// `cancel` is checked before the plugin phase and again before
+    /// resolution. If it is set, the tick is abandoned and the simulation is
+    /// left exactly as it was: the arena, the last step's events, the
+    /// commands held between decisions and the watchdog's timings are only
+    /// updated once the plugin outputs are kept. A long-running step can so
+    /// be aborted from another thread without corrupting the simulation. A
+    /// paused tick is resolved unless cancelled beforehand.
+    ///
+    /// Returns true if the tick was completed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::AtomicBool;
+    /// use tidebreak_core::simulation::Simulation;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// assert!(sim.step_cancellable(&AtomicBool::new(false)));
+    /// assert!(!sim.step_cancellable(&AtomicBool::new(true)));
+    /// assert_eq!(sim.tick(), 1);
+    /// ```
+    pub fn step_cancellable(&mut self, cancel: &AtomicBool) -> bool {
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        let outputs = if let Some(paused) = self.paused.take() {
+            paused.outputs
+        } else {
+            // Nothing is recorded until the runs are kept
+            let runs = self.execute_plugins_parallel();
+            if cancel.load(Ordering::Relaxed) {
+                return false;
+            }
+            self.record_plugin_runs(runs)
+        };
+        self.resolve_tick(&outputs);
+        true
+    }
+
+    /// Executes one tick with commands issued from outside the plugins.
+    ///
+    /// The commands are resolved after every plugin output of the tick, in
+    /// the given order, as if emitted by an [`EXTERNAL_PLUGIN`] plugin on
+    /// each command's source entity. Their trace IDs are derived from the
+    /// seed like plugin trace IDs, so peers stepping with the same commands
+    /// stay in lockstep.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use glam::Vec2;
+    /// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+    /// use tidebreak_core::output::Command;
+    /// use tidebreak_core::simulation::Simulation;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// let ship = sim.arena_mut().spawn(
+    ///     EntityTag::Ship,
+    ///     EntityInner::Ship(ShipComponents::default().with_physics(10.0, 1.0)),
+    /// );
+    ///
+    /// sim.step_with_commands(&[Command::SetVelocity {
+    ///     target: ship,
+    ///     velocity: Vec2::new(5.0, 0.0),
+    /// }]);
+    ///
+    /// let velocity = sim.arena().get(ship).unwrap().as_ship().unwrap().physics.velocity;
+    /// assert_eq!(velocity, Vec2::new(5.0, 0.0));
+    /// ```
+    pub fn step_with_commands(&mut self, commands: &[Command]) {
+        let mut outputs = match self.paused.take() {
+            Some(paused) => paused.outputs,
+            None => self.run_plugins(),
+        };
+        let tick = self.current.current_tick();
+        for (sequence, command) in commands.iter().enumerate() {
+            let Some(entity) = command.source() else {
+                continue;
+            };
+            let trace_id = self.generate_trace_id(tick, entity.as_u64(), u64::MAX);
+            // At most a handful of commands are issued per tick
+            #[allow(clippy::cast_possible_truncation)]
+            outputs.push(OutputEnvelope::new(
+                Output::Command(command.clone()),
+                PluginInstanceId::new(entity, PluginId::new(EXTERNAL_PLUGIN)),
+                trace_id,
+                tick,
+                sequence as u32,
+            ));
+        }
+        self.resolve_tick(&outputs);
+    }
+
+    /// Executes one tick with commands issued by `controller`.
+    ///
+    /// Like [`step_with_commands`](Self::step_with_commands), but commands
+    /// whose source entity `controller` does not control (see
+    /// [`Arena::can_command`]) are dropped. Returns the indices of the
+    /// dropped commands.
+    pub fn step_with_commands_as(
+        &mut self,
+        controller: ControllerId,
+        commands: &[Command],
+    ) -> Vec<usize> {
+        let mut rejected = Vec::new();
+        let mut allowed = Vec::with_capacity(commands.len());
+        for (index, command) in commands.iter().enumerate() {
+            match command.source() {
+                Some(source) if self.current.can_command(Some(controller), source) => {
+                    allowed.push(command.clone());
+                }
+                _ => rejected.push(index),
+            }
+        }
+        self.step_with_commands(&allowed);
+        rejected
+    }
+
+    /// Runs phases 1-2 of a tick, returning the sorted plugin outputs.
+    fn run_plugins(&mut self) -> Vec<OutputEnvelope> {
+        let runs = self.execute_plugins_parallel();
+        self.record_plugin_runs(runs)
+    }
+
+    /// Keeps the plugin runs of the current tick: records their timings with
+    /// the watchdog, clears the last step's events and updates the commands
+    /// held between decisions. Returns the sorted plugin outputs.
+    fn record_plugin_runs(&mut self, runs: Vec<PluginRun>) -> Vec<OutputEnvelope> {
         let tick = self.current.current_tick();
+        let decision = self.is_decision_tick();
+
+        // Only the most recent step's events are retained
+        self.events.clear();
+
+        // Record timings in instance order so budget events are deterministic
+        // given the same overruns
+        let mut outputs = Vec::new();
+        for mut run in runs {
+            if let Some(overruns) = self.watchdog.record(&run.instance, run.elapsed) {
+                #[allow(clippy::cast_possible_truncation)]
+                let sequence = run.envelopes.len() as u32;
+                run.envelopes.push(OutputEnvelope::new(
+                    Output::Event(Event::PluginBudgetExceeded {
+                        entity: run.instance.entity_id(),
+                        plugin: run.instance.plugin_id().clone(),
+                        overruns,
+                    }),
+                    run.instance,
+                    run.trace_id,
+                    tick,
+                    sequence,
+                ));
+            }
+            let (plugin_idx, every_tick) = (run.plugin_idx, run.every_tick);
+            outputs.extend(
+                run.envelopes.into_iter().map(|envelope| (plugin_idx, every_tick, envelope)),
+            );
+        }
+        self.watchdog.retain_live(&self.current);
+
+        // CRITICAL: Sort for determinism
+        outputs.sort_by_key(|(plugin_idx, _, envelope)| {
+            (envelope.source().entity_id(), *plugin_idx, envelope.sequence())
+        });
+
+        // Between decisions, repeat the last decision's sustained commands,
+        // dropping those whose source has since been destroyed or despawned.
+        // Per entity they precede the outputs of the plugins that ran.
+        if !decision {
+            let current = &self.current;
+            let mut outputs: Vec<_> = self
+                .held_commands
+                .iter()
+                .filter(|held| {
+                    current.get(held.source().entity_id()).is_some_and(|e| !e.is_destroyed())
+                })
+                .map(|held| {
+                    OutputEnvelope::new(
+                        held.output().clone(),
+                        held.source().clone(),
+                        held.trace_id(),
+                        tick,
+                        held.sequence(),
+                    )
+                })
+                .chain(outputs.into_iter().map(|(_, _, envelope)| envelope))
+                .collect();
+            outputs.sort_by_key(|envelope| envelope.source().entity_id());
+            return outputs;
+        }
+
+        if self.action_interval > 1 {
+            // Plugins running every tick issue their commands afresh
+            self.held_commands = outputs
+                .iter()
+                .filter(|(_, every_tick, o)| {
+                    !every_tick && matches!(o.output(), Output::Command(c) if c.is_sustained())
+                })
+                .map(|(_, _, o)| o.clone())
+                .collect();
+        }
+        outputs.into_iter().map(|(_, _, envelope)| envelope).collect()
+    }
+
+    /// Sets the number of physics ticks per agent decision.
+    ///
+    /// Plugins run only on decision ticks (multiples of `interval`). On the
+    /// ticks in between, their sustained commands (see
+    /// [`Command::is_sustained`](crate::output::Command::is_sustained)) are
+    /// repeated and nothing else is emitted, so e.g. 60 Hz physics can be
+    /// driven by 5 Hz decisions with an interval of 12. Plugins declared
+    /// [`every_tick`](crate::plugin::PluginDeclaration::every_tick), such as
+    /// missile guidance, still run on every tick. Values of 0 and 1 both
+    /// mean every tick.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::simulation::Simulation;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// sim.set_action_interval(4);
+    /// assert!(sim.is_decision_tick());
+    /// assert_eq!(sim.step_decision(), 4);
+    /// assert_eq!(sim.tick(), 4);
+    /// ```
+    pub fn set_action_interval(&mut self, interval: u32) {
+        self.action_interval = interval.max(1);
+        self.held_commands.clear();
+    }
+
+    /// Sets the seconds simulated per physics tick (default 1/60 s), passing
+    /// it to every resolver through [`Resolver::set_dt`].
+    ///
+    /// Call before [`enable_scoring`](Self::enable_scoring) so zone control
+    /// is timed with the same step.
+    pub fn set_physics_dt(&mut self, dt: f32) {
+        self.physics_dt = dt;
+        for (_, _, resolver) in &mut self.resolvers {
+            resolver.set_dt(dt);
+        }
+    }
+
+    /// Returns the seconds simulated per physics tick.
+    #[must_use]
+    pub const fn physics_dt(&self) -> f32 {
+        self.physics_dt
+    }
+
+    /// Returns the number of physics ticks per agent decision.
+    #[must_use]
+    pub fn action_interval(&self) -> u32 {
+        self.action_interval
+    }
+
+    /// Returns `true` if plugins run on the next tick, i.e. the current
+    /// state is a decision point where observations should be taken.
+    #[must_use]
+    pub fn is_decision_tick(&self) -> bool {
+        self.current
+            .current_tick()
+            .is_multiple_of(u64::from(self.action_interval))
+    }
+
+    /// Steps until the next decision point (at least one tick).
+    ///
+    /// Returns the number of ticks run.
+    pub fn step_decision(&mut self) -> u64 {
+        let mut ticks = 0;
+        loop {
+            self.step();
+            ticks += 1;
+            if self.is_decision_tick() {
+                return ticks;
+            }
+        }
+    }
 
-        // PHASE 1: SNAPSHOT (implicit - current is immutable during plugin phase)
+    /// Runs phases 3-4 of a tick on the plugin outputs.
+    fn resolve_tick(&mut self, outputs: &[OutputEnvelope]) {
+        let tick = self.current.current_tick();
 
-        // PHASE 2: PLUGIN - execute all plugins in parallel
-        let outputs = self.execute_plugins_parallel(tick);
+        // Unassigned shots never reach the resolvers
+        let deconflicted;
+        let outputs = match &self.assignment {
+            Some(assignment) => {
+                deconflicted = assignment.deconflict(outputs, &self.current);
+                deconflicted.as_slice()
+            }
+            None => outputs,
+        };
 
         // PHASE 3: RESOLUTION - clone current to next, run resolvers
         self.next.clone_from(&self.current);
-        for resolver in &self.resolvers {
+        for (_, _, resolver) in &self.resolvers {
             let relevant: Vec<_> = outputs
                 .iter()
                 .filter(|o| resolver.handles().contains(&o.output().kind()))
                 .collect();
             resolver.resolve(&relevant, &self.current, &mut self.next);
         }
+        if let Some(classification) = &self.classification {
+            let relevant: Vec<_> = outputs
+                .iter()
+                .filter(|o| classification.handles().contains(&o.output().kind()))
+                .collect();
+            classification.resolve(&relevant, &self.current, &mut self.next);
+        }
+        if let Some(keeper) = &self.scoring {
+            let relevant: Vec<_> = outputs
+                .iter()
+                .filter(|o| keeper.handles().contains(&o.output().kind()))
+                .collect();
+            keeper.resolve(&relevant, &self.current, &mut self.next);
+        }
+        if let Some(recorder) = &self.heatmap {
+            recorder.resolve(&[], &self.current, &mut self.next);
+        }
 
         // PHASE 4: APPLY - swap buffers, advance tick
         std::mem::swap(&mut self.current, &mut self.next);
         self.current.advance_tick();
+
+        if self.event_history.is_enabled() {
+            self.event_history.record(&self.events.events());
+        }
+
+        if let Some(log) = &mut self.battle_log {
+            if let Err(err) = log.record_tick(tick, outputs, &self.current) {
+                self.battle_log = None;
+                self.battle_log_error = Some(err);
+            }
+        }
     }
 
-    /// Executes all plugins in parallel and collects their outputs.
+    /// Executes all plugins in parallel and collects their runs.
     ///
     /// This method:
-    /// 1. Collects all (`entity_id`, `plugin_index`, plugin) tuples
-    /// 2. Executes plugins in parallel using rayon
+    /// 1. Collects all (`entity_id`, `plugin_index`, plugin) tuples, skipping
+    ///    destroyed entities, instances disabled by the watchdog and, off
+    ///    decision ticks, plugins not declared `every_tick`
+    /// 2. Executes plugins in parallel using rayon, timing each run
     /// 3. Wraps outputs in envelopes with causal chain metadata
-    /// 4. Sorts outputs for deterministic resolution order
-    ///
-    /// # Arguments
     ///
-    /// * `tick` - The current simulation tick
+    /// It does not modify the simulation; see
+    /// [`record_plugin_runs`](Self::record_plugin_runs).
     ///
     /// # Returns
     ///
-    /// A vector of `OutputEnvelope`s sorted by (`entity_id`, `plugin_id`, sequence).
-    fn execute_plugins_parallel(&self, tick: u64) -> Vec<OutputEnvelope> {
-        // Collect (entity_id, plugin_idx, plugin) tuples
+    /// The runs in instance order, i.e. by entity and then plugin order.
+    fn execute_plugins_parallel(&self) -> Vec<PluginRun> {
+        let tick = self.current.current_tick();
+        let decision = self.is_decision_tick();
+
+        // Collect (instance, plugin_idx, plugin) tuples
         let plugin_instances: Vec<_> = self
             .current
             .entities_sorted()
+            .filter(|entity| !entity.is_destroyed())
             .flat_map(|entity| {
                 self.plugins
                     .plugins_for(entity.tag())
                     .iter()
                     .enumerate()
-                    .map(move |(idx, plugin)| (entity.id(), idx, Arc::clone(plugin)))
+                    .map(move |(idx, plugin)| {
+                        let instance =
+                            PluginInstanceId::new(entity.id(), plugin.declaration().id.clone());
+                        (instance, idx, Arc::clone(plugin))
+                    })
+            })
+            .filter(|(instance, _, plugin)| {
+                (decision || plugin.declaration().every_tick)
+                    && !self.watchdog.is_disabled(instance)
             })
             .collect();
 
         // Execute in parallel with rayon
-        let mut all_outputs: Vec<OutputEnvelope> = plugin_instances
-            .par_iter()
-            .flat_map(|(entity_id, plugin_idx, plugin)| {
+        plugin_instances
+            .into_par_iter()
+            .map(|(instance, plugin_idx, plugin)| {
+                let entity_id = instance.entity_id();
                 let decl = plugin.declaration();
                 let view = WorldView::for_plugin(&self.current, decl, tick);
                 let trace_id =
-                    self.generate_trace_id(tick, entity_id.as_u64(), *plugin_idx as u64);
+                    self.generate_trace_id(tick, entity_id.as_u64(), plugin_idx as u64);
 
                 let ctx = PluginContext {
-                    entity_id: *entity_id,
+                    entity_id,
                     tick,
                     trace_id,
                 };
 
+                let started = Instant::now();
                 let outputs = plugin.run(&ctx, &view);
+                let elapsed = started.elapsed();
 
                 // Wrap in envelopes
                 // The sequence number is u32, which can hold up to ~4B outputs per plugin per tick.
                 // In practice, plugins emit at most a handful of outputs per tick.
                 #[allow(clippy::cast_possible_truncation)]
-                outputs
+                let envelopes: Vec<_> = outputs
                     .into_iter()
                     .enumerate()
                     .map(|(seq, output)| {
-                        OutputEnvelope::new(
-                            output,
-                            PluginInstanceId::new(*entity_id, decl.id.clone()),
-                            trace_id,
-                            tick,
-                            seq as u32,
-                        )
+                        OutputEnvelope::new(output, instance.clone(), trace_id, tick, seq as u32)
                     })
-                    .collect::<Vec<_>>()
+                    .collect();
+                PluginRun {
+                    instance,
+                    plugin_idx,
+                    every_tick: decl.every_tick,
+                    trace_id,
+                    elapsed,
+                    envelopes,
+                }
             })
-            .collect();
-
-        // CRITICAL: Sort for determinism
-        all_outputs.sort_by(|a, b| {
-            let entity_cmp = a.source().entity_id().cmp(&b.source().entity_id());
-            if entity_cmp != std::cmp::Ordering::Equal {
-                return entity_cmp;
-            }
-            let plugin_cmp = a.source().plugin_id().as_str().cmp(b.source().plugin_id().as_str());
-            if plugin_cmp != std::cmp::Ordering::Equal {
-                return plugin_cmp;
-            }
-            a.sequence().cmp(&b.sequence())
-        });
-
-        all_outputs
+            .collect()
     }
 
     /// Generates a deterministic trace ID from the simulation state.
@@ -273,7 +864,7 @@ impl Simulation {
     ///
     /// This ensures reproducible trace IDs across runs with the same seed.
     fn generate_trace_id(&self, tick: u64, entity: u64, plugin: u64) -> TraceId {
-        let mut hasher = DefaultHasher::new();
+        let mut hasher = Fnv1a::default();
         self.master_seed.hash(&mut hasher);
         tick.hash(&mut hasher);
         entity.hash(&mut hasher);
@@ -298,558 +889,2335 @@ impl Simulation {
         &mut self.current
     }
 
-    /// Returns the current simulation tick.
+    /// Starts a new simulation in the state a replay's run had just before
+    /// `tick`, to continue it with different actions.
     ///
-    /// The tick counter starts at 0 and increments by 1 after each `step()`.
-    #[must_use]
-    pub fn tick(&self) -> u64 {
-        self.current.current_tick()
-    }
-
-    /// Returns a mutable reference to the plugin registry.
+    /// The fork uses the recorded seed, action interval and physics step
+    /// with the default resolvers and no plugins, which reproduces runs
+    /// driven by commands or outside edits (e.g. from Python). A run with
+    /// plugins is reproduced by [`Replay::seek`] on a simulation set up
+    /// like the original instead.
     ///
-    /// Use this to register plugins before running simulation steps.
+    /// # Errors
+    ///
+    /// Returns [`ReplayError::TickOutOfRange`] for a tick outside the
+    /// recording, or [`ReplayError::Diverged`] if re-stepping does not
+    /// reproduce the recorded state.
     ///
     /// # Example
     ///
     /// ```
+    /// use glam::Vec2;
+    /// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+    /// use tidebreak_core::output::Command;
+    /// use tidebreak_core::replay::Replay;
     /// use tidebreak_core::simulation::Simulation;
-    /// use tidebreak_core::entity::EntityTag;
-    /// use tidebreak_core::plugin::{Plugin, PluginContext, PluginDeclaration, PluginId, ComponentKind};
-    /// use tidebreak_core::output::{Output, OutputKind};
-    /// use tidebreak_core::world_view::WorldView;
-    /// use std::sync::Arc;
-    ///
-    /// struct DummyPlugin {
-    ///     declaration: PluginDeclaration,
-    /// }
     ///
-    /// impl Plugin for DummyPlugin {
-    ///     fn declaration(&self) -> &PluginDeclaration {
-    ///         &self.declaration
-    ///     }
-    ///     fn run(&self, _ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
-    ///         vec![]
-    ///     }
+    /// let mut sim = Simulation::new(3);
+    /// let ship = sim.arena_mut().spawn(
+    ///     EntityTag::Ship,
+    ///     EntityInner::Ship(ShipComponents::default().with_physics(10.0, 1.0)),
+    /// );
+    /// let mut replay = Replay::new(50);
+    /// for _ in 0..100 {
+    ///     replay.record_step(&mut sim, &[]).unwrap();
     /// }
     ///
-    /// let mut sim = Simulation::new(42);
-    /// let plugin = Arc::new(DummyPlugin {
-    ///     declaration: PluginDeclaration {
-    ///         id: PluginId::new("dummy"),
-    ///         required_tags: vec![EntityTag::Ship],
-    ///         reads: vec![ComponentKind::Transform],
-    ///         emits: vec![OutputKind::Command],
-    ///     },
-    /// });
-    /// sim.plugins_mut().register(EntityTag::Ship, plugin);
+    /// // What if the ship had turned east at tick 60?
+    /// let mut fork = Simulation::fork_from(&replay, 60).unwrap();
+    /// fork.step_with_commands(&[Command::SetVelocity {
+    ///     target: ship,
+    ///     velocity: Vec2::new(5.0, 0.0),
+    /// }]);
+    /// assert_eq!(fork.tick(), 61);
+    /// assert_eq!(sim.tick(), 100);
     /// ```
-    #[must_use]
-    pub fn plugins_mut(&mut self) -> &mut PluginRegistry {
-        &mut self.plugins
+    #[cfg(feature = "replay")]
+    pub fn fork_from(replay: &Replay, tick: u64) -> Result<Self, ReplayError> {
+        let header = replay.header();
+        let mut sim = Self::new(header.seed);
+        sim.set_action_interval(header.action_interval);
+        sim.set_physics_dt(header.physics_dt);
+        replay.seek(&mut sim, tick)?;
+        Ok(sim)
     }
 
-    /// Returns the master seed used for deterministic trace ID generation.
+    /// Returns the sustained commands repeated until the next decision tick.
     #[must_use]
-    pub fn seed(&self) -> u64 {
-        self.master_seed
+    pub fn held_commands(&self) -> &[OutputEnvelope] {
+        &self.held_commands
     }
 
-    /// Adds a custom resolver to the simulation.
-    ///
-    /// Resolvers are executed in the order they are added. The default resolvers
-    /// (Physics, Combat, Event) are added in `new()`.
-    ///
-    /// # Arguments
-    ///
-    /// * `resolver` - The resolver to add
-    pub fn add_resolver(&mut self, resolver: Box<dyn Resolver>) {
-        self.resolvers.push(resolver);
+    /// Replaces the sustained commands, e.g. after restoring a checkpoint
+    /// taken between decision ticks.
+    pub fn set_held_commands(&mut self, held: Vec<OutputEnvelope>) {
+        self.held_commands = held;
     }
 
-    /// Returns the number of resolvers in the simulation.
-    #[must_use]
-    pub fn resolver_count(&self) -> usize {
-        self.resolvers.len()
+    /// Replaces the current arena, e.g. to resume from a checkpoint.
+    ///
+    /// Stepping a restored simulation with the same seed, plugins and
+    /// resolvers reproduces the run the checkpoint was taken from. The
+    /// [`kill_ledger`](Self::kill_ledger) is cleared; restore the
+    /// checkpoint's tallies into it afterwards.
+    pub fn restore(&mut self, arena: Arena) {
+        self.current = arena;
+        self.kill_ledger.clear();
+        self.events.clear();
+        self.paused = None;
+        self.held_commands.clear();
+        self.watchdog.reset();
     }
-}
 
-// =============================================================================
-// Tests
-// =============================================================================
+    // =========================================================================
+    // Debugging
+    // =========================================================================
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::entity::{EntityInner, EntityTag, ShipComponents};
-    use crate::output::{Command, Output, OutputKind, PluginId};
-    use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
-    use glam::Vec2;
+    /// Adds a breakpoint checked by [`run_until`](Self::run_until).
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> BreakpointId {
+        let id = BreakpointId(self.next_breakpoint_id);
+        self.next_breakpoint_id += 1;
+        self.breakpoints.push((id, breakpoint));
+        id
+    }
 
-    // Test plugin that emits a velocity command
-    struct VelocityPlugin {
-        declaration: PluginDeclaration,
-        velocity: Vec2,
+    /// Removes a breakpoint. Returns false if it does not exist.
+    pub fn remove_breakpoint(&mut self, id: BreakpointId) -> bool {
+        let before = self.breakpoints.len();
+        self.breakpoints.retain(|(bp_id, _)| *bp_id != id);
+        self.breakpoints.len() != before
     }
 
-    impl VelocityPlugin {
-        fn new(velocity: Vec2) -> Self {
-            Self {
-                declaration: PluginDeclaration {
-                    id: PluginId::new("velocity_test"),
-                    required_tags: vec![EntityTag::Ship],
-                    reads: vec![ComponentKind::Transform, ComponentKind::Physics],
-                    emits: vec![OutputKind::Command],
-                },
-                velocity,
-            }
-        }
+    /// Removes all breakpoints.
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
     }
 
-    impl Plugin for VelocityPlugin {
-        fn declaration(&self) -> &PluginDeclaration {
-            &self.declaration
+    /// Steps until `predicate` holds, a breakpoint fires, or `max_ticks`
+    /// ticks have been completed.
+    ///
+    /// `predicate` is checked before each tick, so it returns immediately if
+    /// it already holds. Breakpoints are checked after the plugin phase; when
+    /// any fire, the tick is left paused with its outputs available from
+    /// [`pending_outputs`](Self::pending_outputs). A tick that is already
+    /// paused is resolved first without re-checking breakpoints.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::debugger::StopReason;
+    /// use tidebreak_core::simulation::Simulation;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// let reason = sim.run_until(|sim| sim.tick() == 10, 100);
+    ///
+    /// assert_eq!(reason, StopReason::Predicate);
+    /// assert_eq!(sim.tick(), 10);
+    /// ```
+    pub fn run_until(
+        &mut self,
+        mut predicate: impl FnMut(&Self) -> bool,
+        max_ticks: u64,
+    ) -> StopReason {
+        let mut completed = 0;
+        if let Some(paused) = self.paused.take() {
+            self.resolve_tick(&paused.outputs);
+            completed += 1;
         }
-
-        fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
-            vec![Output::Command(Command::SetVelocity {
-                target: ctx.entity_id,
-                velocity: self.velocity,
-            })]
+        loop {
+            if predicate(self) {
+                return StopReason::Predicate;
+            }
+            if completed >= max_ticks {
+                return StopReason::MaxTicks;
+            }
+            let outputs = self.run_plugins();
+            let hits = self.check_breakpoints(&outputs);
+            if !hits.is_empty() {
+                self.paused = Some(PausedTick {
+                    outputs,
+                    hits: hits.clone(),
+                });
+                return StopReason::Breakpoint(hits);
+            }
+            self.resolve_tick(&outputs);
+            completed += 1;
         }
     }
 
-    // No-op plugin for testing - used in tests where plugin output is not needed
-    #[allow(dead_code)]
-    struct NoOpPlugin {
+    /// Evaluates every breakpoint against a tick's pending outputs.
+    fn check_breakpoints(&self, outputs: &[OutputEnvelope]) -> Vec<BreakpointHit> {
+        let tick = self.current.current_tick();
+        self.breakpoints
+            .iter()
+            .filter_map(|(id, bp)| {
+                bp.check(tick, &self.current, outputs)
+                    .map(|entity| BreakpointHit {
+                        id: *id,
+                        name: bp.name().to_string(),
+                        tick,
+                        entity,
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns true if a tick is paused before resolution.
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    /// Returns the paused tick's unresolved plugin outputs.
+    #[must_use]
+    pub fn pending_outputs(&self) -> Option<&[OutputEnvelope]> {
+        self.paused.as_ref().map(|p| p.outputs.as_slice())
+    }
+
+    /// Returns the breakpoints that paused the current tick.
+    #[must_use]
+    pub fn breakpoint_hits(&self) -> &[BreakpointHit] {
+        self.paused.as_ref().map_or(&[], |p| p.hits.as_slice())
+    }
+
+    /// Drains the events recorded during the most recent `step()`.
+    ///
+    /// Events not drained are discarded at the start of the next step, so the
+    /// log never grows beyond one tick's worth of events.
+    #[must_use]
+    pub fn take_events(&self) -> Vec<OutputEnvelope> {
+        self.events.take_events()
+    }
+
+    /// Returns the events recorded during the most recent `step()` without
+    /// draining them.
+    #[must_use]
+    pub fn events(&self) -> Vec<OutputEnvelope> {
+        self.events.events()
+    }
+
+    /// Keeps the events of past steps, up to the last `capacity`, for
+    /// [`event_history`](Self::event_history) queries.
+    ///
+    /// Drops any events already kept. A capacity of zero disables the
+    /// history (the default).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::battle_log::query::EventQuery;
+    /// use tidebreak_core::simulation::Simulation;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// sim.enable_event_history(10_000);
+    /// sim.step();
+    /// let hits = sim.event_history().rows(&EventQuery::new().kind("DamageDealt"));
+    /// assert!(hits.is_empty());
+    /// ```
+    pub fn enable_event_history(&mut self, capacity: usize) {
+        self.event_history.enable(capacity);
+    }
+
+    /// Returns the events kept across steps.
+    #[must_use]
+    pub const fn event_history(&self) -> &EventHistory {
+        &self.event_history
+    }
+
+    /// Starts keeping per-team mission scores under `rules`, replacing any
+    /// scores kept so far.
+    ///
+    /// The [`ScoreKeeper`] runs after every other resolver, including ones
+    /// added later, so it sees each tick's damage as applied. Zone control
+    /// is timed with the current physics step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+    /// use tidebreak_core::resolver::ScoringRules;
+    /// use tidebreak_core::simulation::Simulation;
+    /// use tidebreak_core::team_observation::Zone;
+    /// use glam::Vec2;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// let ship = ShipComponents::default();
+    /// let ship = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(ship));
+    /// sim.arena_mut().set_team(ship, Some(TeamId::new(0)));
+    /// sim.enable_scoring(ScoringRules::new().with_zone(Zone::new(Vec2::ZERO, 500.0), 1.0));
+    /// for _ in 0..60 {
+    ///     sim.step();
+    /// }
+    /// assert!((sim.scores()[0].score - 1.0).abs() < 1e-4);
+    /// ```
+    pub fn enable_scoring(&mut self, rules: ScoringRules) -> Arc<ScoreKeeper> {
+        let keeper = Arc::new(ScoreKeeper::new(rules).with_dt(self.physics_dt));
+        self.scoring = Some(Arc::clone(&keeper));
+        keeper
+    }
+
+    /// Returns the score keeper, if scoring is enabled.
+    #[must_use]
+    pub const fn score_keeper(&self) -> Option<&Arc<ScoreKeeper>> {
+        self.scoring.as_ref()
+    }
+
+    /// Returns every team's mission score so far, sorted by team, or an
+    /// empty list if scoring is disabled.
+    #[must_use]
+    pub fn scores(&self) -> Vec<TeamScore> {
+        self.scoring
+            .as_ref()
+            .map_or_else(Vec::new, |keeper| keeper.scores())
+    }
+
+    /// Starts accumulating a visitation and damage-exposure heatmap laid
+    /// out by `config`, replacing any heatmap kept so far.
+    ///
+    /// The [`HeatmapRecorder`] runs after every other resolver, so it sees
+    /// each tick's damage as applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use glam::Vec2;
+    /// use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+    /// use tidebreak_core::resolver::HeatmapConfig;
+    /// use tidebreak_core::simulation::Simulation;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// let ship = ShipComponents::at_position(Vec2::new(10.0, 10.0), 0.0);
+    /// sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(ship));
+    /// sim.enable_heatmap(HeatmapConfig::new(Vec2::ZERO, Vec2::splat(100.0), 50.0));
+    /// for _ in 0..30 {
+    ///     sim.step();
+    /// }
+    /// let heatmap = sim.heatmap().unwrap();
+    /// assert_eq!(heatmap.visits_at(Vec2::new(10.0, 10.0)), 30);
+    /// ```
+    pub fn enable_heatmap(&mut self, config: HeatmapConfig) -> Arc<HeatmapRecorder> {
+        let recorder = Arc::new(HeatmapRecorder::new(config));
+        self.heatmap = Some(Arc::clone(&recorder));
+        recorder
+    }
+
+    /// Returns the heatmap recorder, if a heatmap is enabled.
+    #[must_use]
+    pub const fn heatmap_recorder(&self) -> Option<&Arc<HeatmapRecorder>> {
+        self.heatmap.as_ref()
+    }
+
+    /// Returns a copy of the heatmap so far, or `None` if disabled.
+    #[must_use]
+    pub fn heatmap(&self) -> Option<Heatmap> {
+        self.heatmap.as_ref().map(|recorder| recorder.heatmap())
+    }
+
+    /// Stops accumulating the heatmap.
+    pub fn disable_heatmap(&mut self) {
+        self.heatmap = None;
+    }
+
+    /// Starts deconflicting fire with `config`, replacing any assignment
+    /// enabled so far.
+    ///
+    /// Every tick, `FireWeapon` commands are grouped by the shooter's team
+    /// and target before resolution, and only the shooters the
+    /// [`WeaponAssignmentResolver`] assigns fire; the others' commands are
+    /// dropped, so their weapons stay ready.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use glam::Vec2;
+    /// use tidebreak_core::entity::{
+    ///     AmmoType, CombatState, EntityInner, EntityTag, ShipComponents, TeamId, WeaponState,
+    /// };
+    /// use tidebreak_core::output::Command;
+    /// use tidebreak_core::resolver::AssignmentConfig;
+    /// use tidebreak_core::simulation::Simulation;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// let mut shooters = Vec::new();
+    /// for x in [100.0, 200.0] {
+    ///     let mut ship = ShipComponents::at_position(Vec2::new(x, 0.0), 0.0);
+    ///     let gun = WeaponState::new(0, 5.0, AmmoType::Shell);
+    ///     ship.combat = CombatState::with_weapons(100.0, vec![gun]);
+    ///     let id = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(ship));
+    ///     sim.arena_mut().set_team(id, Some(TeamId::new(0)));
+    ///     shooters.push(id);
+    /// }
+    /// let target = ShipComponents::default();
+    /// let target = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(target));
+    ///
+    /// let assignment = sim.enable_weapon_assignment(AssignmentConfig {
+    ///     damage_per_shot: 100.0,
+    ///     max_shooters_per_target: 2,
+    /// });
+    /// let fire: Vec<_> = shooters
+    ///     .iter()
+    ///     .map(|&source| Command::FireWeapon {
+    ///         source,
+    ///         target,
+    ///         slot: 0,
+    ///     })
+    ///     .collect();
+    /// sim.step_with_commands(&fire);
+    /// assert_eq!(assignment.take_assignments().len(), 1);
+    /// ```
+    pub fn enable_weapon_assignment(
+        &mut self,
+        config: AssignmentConfig,
+    ) -> Arc<WeaponAssignmentResolver> {
+        let assignment = Arc::new(WeaponAssignmentResolver::with_config(config));
+        self.assignment = Some(Arc::clone(&assignment));
+        assignment
+    }
+
+    /// Returns the fire deconfliction, if enabled.
+    #[must_use]
+    pub const fn weapon_assignment(&self) -> Option<&Arc<WeaponAssignmentResolver>> {
+        self.assignment.as_ref()
+    }
+
+    /// Stops deconflicting fire.
+    pub fn disable_weapon_assignment(&mut self) {
+        self.assignment = None;
+    }
+
+    /// Starts classifying tracks with `model`, replacing any classification
+    /// enabled so far.
+    ///
+    /// Every tick, after the other resolvers have refreshed the tracks, the
+    /// [`ClassificationResolver`] grows the classification confidence of
+    /// each detected contact. Its misclassification rolls are drawn from
+    /// streams keyed by the simulation seed and recorded in
+    /// [`rng_audit`](Self::rng_audit), like the combat rolls.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use glam::Vec2;
+    /// use tidebreak_core::entity::{EntityInner, EntityTag, SensorState, ShipComponents};
+    /// use tidebreak_core::plugins::SensorPlugin;
+    /// use tidebreak_core::resolver::ClassificationModel;
+    /// use tidebreak_core::simulation::Simulation;
+    /// use std::sync::Arc;
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// sim.plugins_mut().register(EntityTag::Ship, Arc::new(SensorPlugin::new()));
+    /// let mut observer = ShipComponents::default();
+    /// observer.sensor = SensorState::new(5000.0, 0.0);
+    /// let observer = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(observer));
+    /// let target = ShipComponents::at_position(Vec2::new(2000.0, 0.0), 0.0);
+    /// sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(target));
+    ///
+    /// sim.enable_classification(ClassificationModel::default());
+    /// sim.step();
+    /// let ship = sim.arena().get(observer).unwrap().as_ship().unwrap();
+    /// assert!(ship.sensor.track_table[0].classification_confidence > 0.0);
+    /// ```
+    pub fn enable_classification(
+        &mut self,
+        model: ClassificationModel,
+    ) -> Arc<ClassificationResolver> {
+        let classification = Arc::new(
+            ClassificationResolver::with_model(model, self.master_seed)
+                .with_rng_audit(Arc::clone(&self.rng_audit)),
+        );
+        self.classification = Some(Arc::clone(&classification));
+        classification
+    }
+
+    /// Returns the track classification, if enabled.
+    #[must_use]
+    pub const fn classification(&self) -> Option<&Arc<ClassificationResolver>> {
+        self.classification.as_ref()
+    }
+
+    /// Stops classifying tracks.
+    pub fn disable_classification(&mut self) {
+        self.classification = None;
+    }
+
+    /// Starts streaming the battle log, closing any log already open.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if closing the previous log or creating the new
+    /// files fails.
+    pub fn open_battle_log(&mut self, config: BattleLogConfig) -> io::Result<()> {
+        self.close_battle_log()?;
+        self.battle_log = Some(BattleLog::create(config)?);
+        Ok(())
+    }
+
+    /// Returns true if a battle log is being written.
+    #[must_use]
+    pub fn has_battle_log(&self) -> bool {
+        self.battle_log.is_some()
+    }
+
+    /// Flushes and terminates the battle log, if open.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered while writing the log, including
+    /// errors from earlier steps that caused the log to be closed.
+    pub fn close_battle_log(&mut self) -> io::Result<()> {
+        if let Some(err) = self.battle_log_error.take() {
+            return Err(err);
+        }
+        self.battle_log.take().map_or(Ok(()), BattleLog::finish)
+    }
+
+    /// Returns the current simulation tick.
+    ///
+    /// The tick counter starts at 0 and increments by 1 after each `step()`.
+    #[must_use]
+    pub fn tick(&self) -> u64 {
+        self.current.current_tick()
+    }
+
+    /// Returns a mutable reference to the plugin registry.
+    ///
+    /// Use this to register plugins before running simulation steps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::simulation::Simulation;
+    /// use tidebreak_core::entity::EntityTag;
+    /// use tidebreak_core::plugin::{Plugin, PluginContext, PluginDeclaration, PluginId, ComponentKind};
+    /// use tidebreak_core::output::{Output, OutputKind};
+    /// use tidebreak_core::world_view::WorldView;
+    /// use std::sync::Arc;
+    ///
+    /// struct DummyPlugin {
+    ///     declaration: PluginDeclaration,
+    /// }
+    ///
+    /// impl Plugin for DummyPlugin {
+    ///     fn declaration(&self) -> &PluginDeclaration {
+    ///         &self.declaration
+    ///     }
+    ///     fn run(&self, _ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+    ///         vec![]
+    ///     }
+    /// }
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// let plugin = Arc::new(DummyPlugin {
+    ///     declaration: PluginDeclaration {
+    ///         id: PluginId::new("dummy"),
+    ///         required_tags: vec![EntityTag::Ship],
+    ///         reads: vec![ComponentKind::Transform],
+    ///         emits: vec![OutputKind::Command],
+    ///         runs_after: vec![],
+    ///         every_tick: false,
+    ///     },
+    /// });
+    /// sim.plugins_mut().register(EntityTag::Ship, plugin);
+    /// ```
+    #[must_use]
+    pub fn plugins_mut(&mut self) -> &mut PluginRegistry {
+        &mut self.plugins
+    }
+
+    /// Returns the master seed used for deterministic trace ID generation.
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.master_seed
+    }
+
+    /// Returns the audit log of random draws made by the default resolvers.
+    ///
+    /// Auditing is disabled until [`RngAuditLog::enable`] is called. Custom
+    /// resolvers can record into the same log by sharing this handle.
+    #[must_use]
+    pub fn rng_audit(&self) -> &Arc<RngAuditLog> {
+        &self.rng_audit
+    }
+
+    /// Returns the kills, assists and damage tallied per controller by the
+    /// default combat resolver (see [`kill_ledger`](crate::kill_ledger)).
+    #[must_use]
+    pub fn kill_ledger(&self) -> &Arc<KillLedger> {
+        &self.kill_ledger
+    }
+
+    /// Adds a custom resolver to the simulation.
+    ///
+    /// Equivalent to [`register_resolver`](Self::register_resolver) at
+    /// [`priority::CUSTOM`], so resolvers added this way run after the
+    /// default ones, in the order they are added.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - The resolver to add
+    pub fn add_resolver(&mut self, resolver: Box<dyn Resolver>) {
+        self.register_resolver(resolver, priority::CUSTOM);
+    }
+
+    /// Registers a resolver to run at `priority`, returning a handle for
+    /// [`remove_resolver`](Self::remove_resolver).
+    ///
+    /// Resolvers run in ascending priority each tick, those of equal
+    /// priority in registration order; the [`priority`] constants place the
+    /// default resolvers. The [`ScoreKeeper`] and [`HeatmapRecorder`] always
+    /// run last.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tidebreak_core::arena::Arena;
+    /// use tidebreak_core::output::{OutputEnvelope, OutputKind};
+    /// use tidebreak_core::resolver::{priority, Resolver};
+    /// use tidebreak_core::simulation::Simulation;
+    ///
+    /// /// Downstream resolver adjusting crew morale after combat.
+    /// struct MoraleResolver;
+    ///
+    /// impl Resolver for MoraleResolver {
+    ///     fn handles(&self) -> &[OutputKind] {
+    ///         &[OutputKind::Event]
+    ///     }
+    ///
+    ///     fn resolve(&self, _outputs: &[&OutputEnvelope], _current: &Arena, _next: &mut Arena) {}
+    /// }
+    ///
+    /// let mut sim = Simulation::new(42);
+    /// let defaults = sim.resolver_count();
+    /// let morale = sim.register_resolver(Box::new(MoraleResolver), priority::COMBAT + 50);
+    /// assert_eq!(sim.resolver_count(), defaults + 1);
+    ///
+    /// assert!(sim.remove_resolver(morale));
+    /// assert!(!sim.remove_resolver(morale));
+    /// ```
+    pub fn register_resolver(&mut self, resolver: Box<dyn Resolver>, priority: i32) -> ResolverId {
+        let id = ResolverId(self.next_resolver_id);
+        self.next_resolver_id += 1;
+        let index = self.resolvers.partition_point(|(_, p, _)| *p <= priority);
+        self.resolvers.insert(index, (id, priority, resolver));
+        id
+    }
+
+    /// Removes a resolver registered with
+    /// [`register_resolver`](Self::register_resolver). Returns false if it
+    /// does not exist.
+    pub fn remove_resolver(&mut self, id: ResolverId) -> bool {
+        let before = self.resolvers.len();
+        self.resolvers.retain(|(resolver_id, ..)| *resolver_id != id);
+        self.resolvers.len() != before
+    }
+
+    /// Returns the plugin timings and budget state.
+    #[must_use]
+    pub const fn plugin_watchdog(&self) -> &PluginWatchdog {
+        &self.watchdog
+    }
+
+    /// Returns the plugin watchdog for changing the budget or re-enabling
+    /// disabled plugin instances.
+    #[must_use]
+    pub fn plugin_watchdog_mut(&mut self) -> &mut PluginWatchdog {
+        &mut self.watchdog
+    }
+
+    /// Returns the number of resolvers in the simulation.
+    #[must_use]
+    pub fn resolver_count(&self) -> usize {
+        self.resolvers.len()
+    }
+
+    /// Estimates every team's strength and win probability from the
+    /// current state.
+    ///
+    /// Cheap enough to call every tick; see [`crate::balance`] for the
+    /// heuristic.
+    #[must_use]
+    pub fn evaluate_balance(&self) -> BalanceReport {
+        self.balance.evaluate(&self.current)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{DamageType, EntityInner, EntityTag, ShipComponents};
+    use crate::output::{Command, Modifier, Output, OutputKind, PluginId};
+    use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+    use glam::Vec2;
+
+    // Test plugin that emits a velocity command
+    struct VelocityPlugin {
         declaration: PluginDeclaration,
+        velocity: Vec2,
     }
 
-    #[allow(dead_code)]
-    impl NoOpPlugin {
-        fn new(id: &str) -> Self {
-            Self {
-                declaration: PluginDeclaration {
-                    id: PluginId::new(id),
-                    required_tags: vec![EntityTag::Ship],
-                    reads: vec![ComponentKind::Transform],
-                    emits: vec![OutputKind::Command],
-                },
+    impl VelocityPlugin {
+        fn new(velocity: Vec2) -> Self {
+            Self {
+                declaration: PluginDeclaration {
+                    id: PluginId::new("velocity_test"),
+                    required_tags: vec![EntityTag::Ship],
+                    reads: vec![ComponentKind::Transform, ComponentKind::Physics],
+                    emits: vec![OutputKind::Command],
+                    runs_after: vec![],
+                    every_tick: false,
+                },
+                velocity,
+            }
+        }
+    }
+
+    impl Plugin for VelocityPlugin {
+        fn declaration(&self) -> &PluginDeclaration {
+            &self.declaration
+        }
+
+        fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+            vec![Output::Command(Command::SetVelocity {
+                target: ctx.entity_id,
+                velocity: self.velocity,
+            })]
+        }
+    }
+
+    // No-op plugin for testing - used in tests where plugin output is not needed
+    #[allow(dead_code)]
+    struct NoOpPlugin {
+        declaration: PluginDeclaration,
+    }
+
+    #[allow(dead_code)]
+    impl NoOpPlugin {
+        fn new(id: &str) -> Self {
+            Self {
+                declaration: PluginDeclaration {
+                    id: PluginId::new(id),
+                    required_tags: vec![EntityTag::Ship],
+                    reads: vec![ComponentKind::Transform],
+                    emits: vec![OutputKind::Command],
+                    runs_after: vec![],
+                    every_tick: false,
+                },
+            }
+        }
+    }
+
+    impl Plugin for NoOpPlugin {
+        fn declaration(&self) -> &PluginDeclaration {
+            &self.declaration
+        }
+
+        fn run(&self, _ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+            vec![]
+        }
+    }
+
+    struct DamagePlugin {
+        declaration: PluginDeclaration,
+        amount: f32,
+    }
+
+    impl DamagePlugin {
+        fn new(amount: f32) -> Self {
+            Self {
+                declaration: PluginDeclaration {
+                    id: PluginId::new("damage_test"),
+                    required_tags: vec![EntityTag::Ship],
+                    reads: vec![ComponentKind::Combat],
+                    emits: vec![OutputKind::Modifier],
+                    runs_after: vec![],
+                    every_tick: false,
+                },
+                amount,
+            }
+        }
+    }
+
+    impl Plugin for DamagePlugin {
+        fn declaration(&self) -> &PluginDeclaration {
+            &self.declaration
+        }
+
+        fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+            vec![Output::Modifier(Modifier::ApplyDamage {
+                target: ctx.entity_id,
+                amount: self.amount,
+                damage_type: DamageType::Kinetic,
+            })]
+        }
+    }
+
+    mod creation_tests {
+        use super::*;
+
+        #[test]
+        fn new_creates_simulation() {
+            let sim = Simulation::new(42);
+            assert_eq!(sim.tick(), 0);
+            assert_eq!(sim.seed(), 42);
+            assert!(sim.arena().is_empty());
+        }
+
+        #[test]
+        fn arena_mut_allows_setup() {
+            let mut sim = Simulation::new(42);
+            let ship_id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            assert!(sim.arena().get(ship_id).is_some());
+        }
+
+        #[test]
+        fn restore_replays_identically() {
+            let mut sim = Simulation::new(42);
+            sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
+            sim.plugins_mut().register(EntityTag::Ship, plugin.clone());
+
+            sim.step();
+            let checkpoint = sim.arena().clone();
+            for _ in 0..5 {
+                sim.step();
+            }
+
+            let mut replay = Simulation::new(42);
+            replay.plugins_mut().register(EntityTag::Ship, plugin);
+            replay.restore(checkpoint);
+            for _ in 0..5 {
+                replay.step();
+            }
+
+            assert_eq!(replay.tick(), sim.tick());
+            assert_eq!(replay.arena().state_hash(), sim.arena().state_hash());
+        }
+
+        #[test]
+        fn with_default_config_has_no_battle_log() {
+            let sim = Simulation::with_config(42, SimulationConfig::default()).unwrap();
+            assert!(!sim.has_battle_log());
+        }
+
+        #[test]
+        fn battle_log_records_steps() {
+            use crate::battle_log::{DAMAGE_FILE, ENTITIES_FILE, EVENTS_FILE};
+
+            let dir = std::env::temp_dir()
+                .join(format!("tidebreak-sim-battle-log-{}", std::process::id()));
+            let config = SimulationConfig {
+                battle_log: Some(BattleLogConfig::new(&dir)),
+                ..SimulationConfig::default()
+            };
+            let mut sim = Simulation::with_config(42, config).unwrap();
+            sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            for _ in 0..3 {
+                sim.step();
+            }
+            sim.close_battle_log().unwrap();
+
+            assert!(!sim.has_battle_log());
+            let entities = std::fs::metadata(dir.join(ENTITIES_FILE)).unwrap();
+            let events = std::fs::metadata(dir.join(EVENTS_FILE)).unwrap();
+            assert!(entities.len() > events.len());
+            assert!(dir.join(DAMAGE_FILE).exists());
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn config_bounds_despawn_emits_event() {
+            use crate::arena::{BoundaryPolicy, WorldBounds};
+            use crate::output::Event;
+
+            let config = SimulationConfig {
+                bounds: Some(WorldBounds::centered(100.0, 100.0, BoundaryPolicy::Despawn)),
+                ..SimulationConfig::default()
+            };
+            let mut sim = Simulation::with_config(42, config).unwrap();
+            let ship_id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(49.9, 0.0), 0.0)),
+            );
+            sim.plugins_mut().register(
+                EntityTag::Ship,
+                Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0))),
+            );
+
+            sim.step();
+
+            assert!(sim.arena().get(ship_id).is_none());
+            let events = sim.take_events();
+            assert!(events.iter().any(|e| matches!(
+                e.output().as_event(),
+                Some(Event::EntityOutOfBounds { entity, .. }) if *entity == ship_id
+            )));
+        }
+
+        #[test]
+        fn aggregate_combat_model_attrits_squadrons() {
+            use crate::entity::{SquadronComponents, TeamId};
+
+            let config = SimulationConfig {
+                combat: CombatModel::Aggregate(AggregateCombatConfig::default()),
+                ..SimulationConfig::default()
+            };
+            let mut sim = Simulation::with_config(42, config).unwrap();
+            assert_eq!(sim.resolver_count(), Simulation::new(42).resolver_count() + 1);
+            let mut ids = Vec::new();
+            for (x, team) in [(0.0, 0), (100.0, 1)] {
+                let squadron = SquadronComponents::at_position(Vec2::new(x, 0.0), 0.0)
+                    .with_craft_count(10, 10.0);
+                let id = sim
+                    .arena_mut()
+                    .spawn(EntityTag::Squadron, EntityInner::Squadron(squadron));
+                sim.arena_mut().set_team(id, Some(TeamId::new(team)));
+                ids.push(id);
+            }
+
+            sim.step();
+
+            for id in ids {
+                let hp = sim.arena().get(id).unwrap().as_squadron().unwrap().combat.hp;
+                assert!(hp < 100.0);
+            }
+        }
+
+        #[test]
+        fn different_seeds_produce_different_trace_ids() {
+            let sim1 = Simulation::new(1);
+            let sim2 = Simulation::new(2);
+
+            let trace1 = sim1.generate_trace_id(0, 0, 0);
+            let trace2 = sim2.generate_trace_id(0, 0, 0);
+
+            assert_ne!(trace1, trace2);
+        }
+    }
+
+    mod step_tests {
+        use super::*;
+
+        #[test]
+        fn step_advances_tick() {
+            let mut sim = Simulation::new(42);
+            assert_eq!(sim.tick(), 0);
+
+            sim.step();
+            assert_eq!(sim.tick(), 1);
+
+            sim.step();
+            sim.step();
+            assert_eq!(sim.tick(), 3);
+        }
+
+        #[test]
+        fn step_as_controller_drops_foreign_commands() {
+            let mut sim = Simulation::new(42);
+            let own = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default().with_physics(10.0, 1.0)),
+            );
+            let foreign = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default().with_physics(10.0, 1.0)),
+            );
+            sim.arena_mut().set_controller(own, Some(ControllerId::Agent(0)));
+            sim.arena_mut().set_controller(foreign, Some(ControllerId::Human(0)));
+
+            let velocity = Vec2::new(5.0, 0.0);
+            let commands = [
+                Command::SetVelocity { target: own, velocity },
+                Command::SetVelocity { target: foreign, velocity },
+            ];
+            let rejected = sim.step_with_commands_as(ControllerId::Agent(0), &commands);
+            assert_eq!(rejected, vec![1]);
+
+            let speed = |id| sim.arena().get(id).unwrap().as_ship().unwrap().physics.velocity;
+            assert_eq!(speed(own), velocity);
+            assert_eq!(speed(foreign), Vec2::ZERO);
+        }
+
+        #[test]
+        fn step_with_no_plugins_no_entities() {
+            let mut sim = Simulation::new(42);
+            // Should not panic
+            sim.step();
+            assert_eq!(sim.tick(), 1);
+        }
+
+        #[test]
+        fn step_with_entities_no_plugins() {
+            let mut sim = Simulation::new(42);
+            sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+
+            // Should not panic
+            sim.step();
+            assert_eq!(sim.tick(), 1);
+        }
+
+        #[test]
+        fn step_with_plugins() {
+            let mut sim = Simulation::new(42);
+            let ship_id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+
+            let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
+            sim.plugins_mut().register(EntityTag::Ship, plugin);
+
+            // Check plugins registered
+            let plugins = sim.plugins_mut().plugins_for(EntityTag::Ship);
+            assert_eq!(plugins.len(), 1, "Plugin not registered");
+
+            sim.step();
+
+            // The physics resolver uses FIXED_DT = 1/60
+            // velocity = (60, 0), so position += (60, 0) * (1/60) = (1, 0)
+            let ship = sim.arena().get(ship_id).unwrap().as_ship().unwrap();
+
+            // First check velocity was set
+            assert_eq!(
+                ship.physics.velocity,
+                Vec2::new(60.0, 0.0),
+                "Velocity was not set"
+            );
+
+            // After step: plugin emitted SetVelocity, physics resolver set velocity to (60,0)
+            // and then integrated: position += velocity * dt = (0,0) + (60,0) * (1/60) = (1, 0)
+            let expected_x = 60.0 / 60.0; // 1.0
+            assert!(
+                (ship.transform.position.x - expected_x).abs() < 0.0001,
+                "Expected x={}, got x={}, velocity={:?}",
+                expected_x,
+                ship.transform.position.x,
+                ship.physics.velocity
+            );
+        }
+
+        #[test]
+        fn multiple_steps_accumulate() {
+            let mut sim = Simulation::new(42);
+            let ship_id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+
+            let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
+            sim.plugins_mut().register(EntityTag::Ship, plugin);
+
+            for _ in 0..10 {
+                sim.step();
+            }
+
+            // After 10 steps at velocity 60, with dt=1/60, position = 10
+            let ship = sim.arena().get(ship_id).unwrap().as_ship().unwrap();
+            assert!((ship.transform.position.x - 10.0).abs() < 0.0001);
+        }
+    }
+
+    mod resolver_filtering_tests {
+        use super::*;
+
+        #[test]
+        fn resolver_receives_only_relevant_outputs() {
+            let mut sim = Simulation::new(42);
+            let ship_id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+
+            // Register both a velocity plugin (Command) and damage plugin (Modifier)
+            let velocity_plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
+            let damage_plugin = Arc::new(DamagePlugin::new(25.0));
+
+            sim.plugins_mut().register(EntityTag::Ship, velocity_plugin);
+            sim.plugins_mut().register(EntityTag::Ship, damage_plugin);
+
+            sim.step();
+
+            let ship = sim.arena().get(ship_id).unwrap().as_ship().unwrap();
+
+            // Physics resolver handled velocity
+            assert_eq!(ship.physics.velocity, Vec2::new(60.0, 0.0));
+
+            // Combat resolver handled damage
+            assert!((ship.combat.hp - 75.0).abs() < 0.0001);
+        }
+    }
+
+    mod resolver_registration_tests {
+        use super::*;
+        use crate::resolver::priority;
+        use std::sync::Mutex;
+
+        /// Appends its name to a shared log whenever it runs.
+        struct Recorder(&'static str, Arc<Mutex<Vec<&'static str>>>);
+
+        impl Resolver for Recorder {
+            fn handles(&self) -> &[OutputKind] {
+                &[]
+            }
+
+            fn resolve(&self, _: &[&OutputEnvelope], _: &Arena, _: &mut Arena) {
+                self.1.lock().unwrap().push(self.0);
+            }
+        }
+
+        #[test]
+        fn resolvers_run_by_priority_then_registration() {
+            let log = Arc::new(Mutex::new(Vec::new()));
+            let recorder = |name| Box::new(Recorder(name, Arc::clone(&log)));
+            let mut sim = Simulation::new(42);
+            sim.add_resolver(recorder("appended"));
+            sim.register_resolver(recorder("late"), priority::COMBAT + 50);
+            let early = sim.register_resolver(recorder("early"), priority::PHYSICS - 1);
+            sim.register_resolver(recorder("late_too"), priority::COMBAT + 50);
+
+            sim.step();
+            assert_eq!(*log.lock().unwrap(), ["early", "late", "late_too", "appended"]);
+
+            log.lock().unwrap().clear();
+            assert!(sim.remove_resolver(early));
+            assert!(!sim.remove_resolver(early));
+            sim.step();
+            assert_eq!(*log.lock().unwrap(), ["late", "late_too", "appended"]);
+        }
+
+        #[test]
+        fn physics_dt_reaches_the_physics_resolver() {
+            let mut sim = Simulation::new(42);
+            let log = Arc::new(Mutex::new(Vec::new()));
+            sim.register_resolver(Box::new(Recorder("first", log)), priority::PHYSICS - 1);
+            let count = sim.resolver_count();
+            sim.set_physics_dt(0.5);
+            assert_eq!(sim.resolver_count(), count);
+
+            let ship_id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            sim.plugins_mut().register(
+                EntityTag::Ship,
+                Arc::new(VelocityPlugin::new(Vec2::new(2.0, 0.0))),
+            );
+            sim.step();
+
+            let ship = sim.arena().get(ship_id).unwrap().as_ship().unwrap();
+            assert!((ship.transform.position.x - 1.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn physics_dt_reaches_the_submarine_resolver() {
+            use crate::entity::SubmarineState;
+
+            let mut sim = Simulation::new(42);
+            sim.set_physics_dt(0.5);
+            let sub = SubmarineState {
+                ordered_depth: 100.0,
+                ..SubmarineState::new()
+            };
+            let id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default().with_submarine(sub)),
+            );
+            sim.step();
+
+            let state = sim.arena().get(id).unwrap().submarine().unwrap();
+            assert!((state.depth - sub.dive_rate * 0.5).abs() < 1e-4);
+        }
+    }
+
+    mod custom_output_tests {
+        use super::*;
+        use crate::entity::EntityId;
+        use crate::output::{CustomOutput, CustomPayload};
+        use crate::resolver::priority;
+        use serde::{Deserialize, Serialize};
+        use std::sync::Mutex;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Hail {
+            from: EntityId,
+            channel: u8,
+        }
+
+        impl CustomPayload for Hail {
+            const KIND: &'static str = "test.hail";
+        }
+
+        /// Plugin that hails on a fixed channel every tick.
+        struct HailPlugin(PluginDeclaration);
+
+        impl Plugin for HailPlugin {
+            fn declaration(&self) -> &PluginDeclaration {
+                &self.0
+            }
+
+            fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+                let hail = Hail {
+                    from: ctx.entity_id,
+                    channel: 16,
+                };
+                vec![CustomOutput::new(&hail).unwrap().into()]
+            }
+        }
+
+        /// Resolver collecting every hail it is routed.
+        struct HailLog(Arc<Mutex<Vec<Hail>>>);
+
+        impl Resolver for HailLog {
+            fn handles(&self) -> &[OutputKind] {
+                &[OutputKind::Custom]
+            }
+
+            fn resolve(&self, outputs: &[&OutputEnvelope], _: &Arena, _: &mut Arena) {
+                let hails = outputs
+                    .iter()
+                    .filter_map(|envelope| envelope.output().as_custom()?.decode::<Hail>());
+                self.0.lock().unwrap().extend(hails);
+            }
+        }
+
+        #[test]
+        fn custom_outputs_reach_their_resolver() {
+            let mut sim = Simulation::new(42);
+            let ship_id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            sim.plugins_mut().register(
+                EntityTag::Ship,
+                Arc::new(HailPlugin(PluginDeclaration {
+                    id: PluginId::new("hail"),
+                    required_tags: vec![EntityTag::Ship],
+                    reads: vec![],
+                    emits: vec![OutputKind::Custom],
+                    runs_after: vec![],
+                    every_tick: false,
+                })),
+            );
+            let log = Arc::new(Mutex::new(Vec::new()));
+            sim.register_resolver(Box::new(HailLog(Arc::clone(&log))), priority::CUSTOM);
+
+            sim.step();
+
+            let expected = Hail {
+                from: ship_id,
+                channel: 16,
+            };
+            assert_eq!(*log.lock().unwrap(), [expected]);
+        }
+    }
+
+    mod cancellation_tests {
+        use super::*;
+
+        /// Plugin that raises a cancel flag while running.
+        struct CancellingPlugin {
+            declaration: PluginDeclaration,
+            cancel: Arc<AtomicBool>,
+        }
+
+        impl CancellingPlugin {
+            fn new(id: &str, every_tick: bool, cancel: &Arc<AtomicBool>) -> Arc<Self> {
+                Arc::new(Self {
+                    declaration: PluginDeclaration {
+                        id: PluginId::new(id),
+                        required_tags: vec![EntityTag::Ship],
+                        reads: vec![],
+                        emits: vec![
+                            OutputKind::Modifier,
+                            OutputKind::Event,
+                            OutputKind::Command,
+                        ],
+                        runs_after: vec![],
+                        every_tick,
+                    },
+                    cancel: Arc::clone(cancel),
+                })
+            }
+        }
+
+        impl Plugin for CancellingPlugin {
+            fn declaration(&self) -> &PluginDeclaration {
+                &self.declaration
+            }
+
+            fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+                self.cancel.store(true, Ordering::Relaxed);
+                vec![
+                    Output::Modifier(Modifier::ApplyDamage {
+                        target: ctx.entity_id,
+                        amount: 10.0,
+                        damage_type: DamageType::Kinetic,
+                    }),
+                    Output::Event(Event::DamageDealt {
+                        source: ctx.entity_id,
+                        target: ctx.entity_id,
+                        amount: 10.0,
+                    }),
+                    Output::Command(Command::SetVelocity {
+                        target: ctx.entity_id,
+                        velocity: Vec2::new(5.0, 0.0),
+                    }),
+                ]
+            }
+        }
+
+        #[test]
+        fn cancelled_before_start_does_nothing() {
+            let mut sim = Simulation::new(42);
+
+            assert!(!sim.step_cancellable(&AtomicBool::new(true)));
+            assert_eq!(sim.tick(), 0);
+        }
+
+        #[test]
+        fn cancelled_during_plugins_leaves_state_unchanged() {
+            let mut sim = Simulation::new(42);
+            sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let cancel = Arc::new(AtomicBool::new(false));
+            sim.plugins_mut()
+                .register(EntityTag::Ship, CancellingPlugin::new("cancel_test", false, &cancel));
+            let before = sim.arena().state_hash();
+
+            assert!(!sim.step_cancellable(&cancel));
+
+            assert_eq!(sim.tick(), 0);
+            assert_eq!(sim.arena().state_hash(), before);
+        }
+
+        #[test]
+        fn cancelled_during_plugins_keeps_events_held_commands_and_timings() {
+            let mut sim = Simulation::new(42);
+            sim.set_action_interval(2);
+            sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let cancel = Arc::new(AtomicBool::new(false));
+            for (id, every_tick) in [("decision", false), ("guidance", true)] {
+                sim.plugins_mut()
+                    .register(EntityTag::Ship, CancellingPlugin::new(id, every_tick, &cancel));
+            }
+            sim.step();
+            sim.step();
+            cancel.store(false, Ordering::Relaxed);
+            let events = sim.events();
+            let held = sim.held_commands.clone();
+            let timings: Vec<_> = sim
+                .plugin_watchdog()
+                .timings()
+                .map(|(instance, timing)| (instance.clone(), *timing))
+                .collect();
+            assert!(!events.is_empty() && !held.is_empty());
+
+            assert!(!sim.step_cancellable(&cancel));
+
+            assert_eq!(sim.tick(), 2);
+            assert_eq!(sim.events(), events);
+            assert_eq!(sim.held_commands, held);
+            let after: Vec<_> = sim
+                .plugin_watchdog()
+                .timings()
+                .map(|(instance, timing)| (instance.clone(), *timing))
+                .collect();
+            assert_eq!(after, timings);
+        }
+
+        #[test]
+        fn uncancelled_matches_step() {
+            let mut a = Simulation::new(42);
+            let mut b = Simulation::new(42);
+            for sim in [&mut a, &mut b] {
+                sim.arena_mut().spawn(
+                    EntityTag::Ship,
+                    EntityInner::Ship(ShipComponents::default()),
+                );
+                sim.plugins_mut()
+                    .register(EntityTag::Ship, Arc::new(DamagePlugin::new(5.0)));
+            }
+
+            a.step();
+            assert!(b.step_cancellable(&AtomicBool::new(false)));
+
+            assert_eq!(a.arena().state_hash(), b.arena().state_hash());
+        }
+    }
+
+    mod debugger_tests {
+        use super::*;
+        use crate::debugger::Breakpoint;
+        use crate::entity::EntityId;
+
+        fn damaged_sim(amount: f32) -> (Simulation, EntityId) {
+            let mut sim = Simulation::new(42);
+            let ship_id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            sim.plugins_mut()
+                .register(EntityTag::Ship, Arc::new(DamagePlugin::new(amount)));
+            (sim, ship_id)
+        }
+
+        fn hp(sim: &Simulation, id: EntityId) -> f32 {
+            sim.arena().get(id).unwrap().as_ship().unwrap().combat.hp
+        }
+
+        #[test]
+        fn run_until_stops_on_predicate() {
+            let (mut sim, ship_id) = damaged_sim(10.0);
+
+            let reason = sim.run_until(|sim| hp(sim, ship_id) <= 50.0, 100);
+
+            assert_eq!(reason, StopReason::Predicate);
+            assert_eq!(sim.tick(), 5);
+            assert!(!sim.is_paused());
+        }
+
+        #[test]
+        fn run_until_respects_max_ticks() {
+            let mut sim = Simulation::new(42);
+
+            assert_eq!(sim.run_until(|_| false, 7), StopReason::MaxTicks);
+            assert_eq!(sim.tick(), 7);
+        }
+
+        #[test]
+        fn breakpoint_pauses_before_resolution() {
+            let (mut sim, ship_id) = damaged_sim(10.0);
+            let bp = sim.add_breakpoint(Breakpoint::hp_below(75.0));
+
+            let reason = sim.run_until(|_| false, 100);
+
+            // Tick 2 would take HP from 80 to 70
+            let StopReason::Breakpoint(hits) = reason else {
+                panic!("expected breakpoint, got {reason:?}");
+            };
+            assert_eq!(hits.len(), 1);
+            assert_eq!(hits[0].id, bp);
+            assert_eq!(hits[0].tick, 2);
+            assert_eq!(hits[0].entity, Some(ship_id));
+            assert_eq!(sim.breakpoint_hits(), hits.as_slice());
+            assert_eq!(sim.tick(), 2);
+            assert!((hp(&sim, ship_id) - 80.0).abs() < 1e-4);
+            assert_eq!(sim.pending_outputs().unwrap().len(), 1);
+
+            sim.step();
+
+            assert!(!sim.is_paused());
+            assert_eq!(sim.pending_outputs(), None);
+            assert_eq!(sim.tick(), 3);
+            assert!((hp(&sim, ship_id) - 70.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn run_until_resumes_paused_tick() {
+            let (mut sim, ship_id) = damaged_sim(10.0);
+            sim.add_breakpoint(Breakpoint::hp_below(75.0));
+            sim.run_until(|_| false, 100);
+
+            // The crossing already happened, so the breakpoint does not refire
+            let reason = sim.run_until(|sim| sim.tick() == 6, 100);
+
+            assert_eq!(reason, StopReason::Predicate);
+            assert!((hp(&sim, ship_id) - 40.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn paused_run_matches_uninterrupted_run() {
+            let (mut paused, ship_id) = damaged_sim(7.0);
+            paused.add_breakpoint(Breakpoint::entity_destroyed());
+            let (mut plain, _) = damaged_sim(7.0);
+
+            assert!(matches!(
+                paused.run_until(|_| false, 100),
+                StopReason::Breakpoint(_)
+            ));
+            paused.run_until(|sim| sim.tick() == 20, 100);
+            for _ in 0..20 {
+                plain.step();
+            }
+
+            assert_eq!(paused.arena().state_hash(), plain.arena().state_hash());
+            assert!(hp(&paused, ship_id) <= 0.0);
+        }
+
+        #[test]
+        fn removed_breakpoints_do_not_fire() {
+            let mut sim = Simulation::new(42);
+            let bp = sim.add_breakpoint(Breakpoint::at_tick(2));
+
+            assert!(sim.remove_breakpoint(bp));
+            assert!(!sim.remove_breakpoint(bp));
+            assert_eq!(sim.run_until(|_| false, 5), StopReason::MaxTicks);
+        }
+
+        #[test]
+        fn restore_discards_paused_tick() {
+            let mut sim = Simulation::new(42);
+            sim.add_breakpoint(Breakpoint::at_tick(1));
+            let checkpoint = sim.arena().clone();
+            sim.run_until(|_| false, 5);
+            assert!(sim.is_paused());
+
+            sim.restore(checkpoint);
+
+            assert!(!sim.is_paused());
+            assert!(sim.breakpoint_hits().is_empty());
+        }
+    }
+
+    mod determinism_tests {
+        use super::*;
+
+        #[test]
+        fn same_seed_same_results() {
+            fn run_simulation(seed: u64) -> Vec2 {
+                let mut sim = Simulation::new(seed);
+                let ship_id = sim.arena_mut().spawn(
+                    EntityTag::Ship,
+                    EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+                );
+
+                let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 30.0)));
+                sim.plugins_mut().register(EntityTag::Ship, plugin);
+
+                for _ in 0..10 {
+                    sim.step();
+                }
+
+                sim.arena()
+                    .get(ship_id)
+                    .unwrap()
+                    .as_ship()
+                    .unwrap()
+                    .transform
+                    .position
+            }
+
+            let pos1 = run_simulation(42);
+            let pos2 = run_simulation(42);
+
+            assert_eq!(pos1, pos2);
+        }
+
+        #[test]
+        fn trace_ids_are_deterministic() {
+            let sim = Simulation::new(12345);
+
+            // Same inputs should produce same trace ID
+            let trace1 = sim.generate_trace_id(10, 5, 2);
+            let trace2 = sim.generate_trace_id(10, 5, 2);
+            assert_eq!(trace1, trace2);
+
+            // Different inputs should produce different trace IDs
+            let trace3 = sim.generate_trace_id(10, 5, 3);
+            assert_ne!(trace1, trace3);
+        }
+
+        #[test]
+        fn output_order_is_deterministic() {
+            // Create multiple entities and plugins to test parallel execution order
+            let mut sim = Simulation::new(42);
+
+            for i in 0..5 {
+                sim.arena_mut().spawn(
+                    EntityTag::Ship,
+                    EntityInner::Ship(ShipComponents::at_position(
+                        Vec2::new(i as f32 * 100.0, 0.0),
+                        0.0,
+                    )),
+                );
+            }
+
+            let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
+            sim.plugins_mut().register(EntityTag::Ship, plugin);
+
+            // Run multiple times to verify determinism despite parallel execution
+            let mut results = Vec::new();
+            for _ in 0..5 {
+                let mut sim_copy = Simulation::new(42);
+                for i in 0..5 {
+                    sim_copy.arena_mut().spawn(
+                        EntityTag::Ship,
+                        EntityInner::Ship(ShipComponents::at_position(
+                            Vec2::new(i as f32 * 100.0, 0.0),
+                            0.0,
+                        )),
+                    );
+                }
+                let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
+                sim_copy.plugins_mut().register(EntityTag::Ship, plugin);
+
+                sim_copy.step();
+
+                let positions: Vec<_> = sim_copy
+                    .arena()
+                    .entities_sorted()
+                    .map(|e| e.as_ship().unwrap().transform.position)
+                    .collect();
+                results.push(positions);
+            }
+
+            // All runs should produce identical results
+            for i in 1..results.len() {
+                assert_eq!(results[0], results[i]);
             }
         }
     }
 
-    impl Plugin for NoOpPlugin {
-        fn declaration(&self) -> &PluginDeclaration {
-            &self.declaration
+    mod plugin_execution_tests {
+        use super::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct CountingPlugin {
+            declaration: PluginDeclaration,
+            counter: Arc<AtomicUsize>,
         }
 
-        fn run(&self, _ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
-            vec![]
+        impl CountingPlugin {
+            fn new(counter: Arc<AtomicUsize>) -> Self {
+                Self {
+                    declaration: PluginDeclaration {
+                        id: PluginId::new("counting"),
+                        required_tags: vec![EntityTag::Ship],
+                        reads: vec![ComponentKind::Transform],
+                        emits: vec![OutputKind::Command],
+                        runs_after: vec![],
+                        every_tick: false,
+                    },
+                    counter,
+                }
+            }
         }
-    }
 
-    mod creation_tests {
-        use super::*;
+        impl Plugin for CountingPlugin {
+            fn declaration(&self) -> &PluginDeclaration {
+                &self.declaration
+            }
+
+            fn run(&self, _ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+                self.counter.fetch_add(1, Ordering::SeqCst);
+                vec![]
+            }
+        }
 
         #[test]
-        fn new_creates_simulation() {
-            let sim = Simulation::new(42);
-            assert_eq!(sim.tick(), 0);
-            assert_eq!(sim.seed(), 42);
-            assert!(sim.arena().is_empty());
+        fn plugins_execute_for_all_matching_entities() {
+            let counter = Arc::new(AtomicUsize::new(0));
+
+            let mut sim = Simulation::new(42);
+
+            // Spawn 5 ships
+            for _ in 0..5 {
+                sim.arena_mut().spawn(
+                    EntityTag::Ship,
+                    EntityInner::Ship(ShipComponents::default()),
+                );
+            }
+
+            let plugin = Arc::new(CountingPlugin::new(Arc::clone(&counter)));
+            sim.plugins_mut().register(EntityTag::Ship, plugin);
+
+            sim.step();
+
+            // Plugin should have executed once per ship
+            assert_eq!(counter.load(Ordering::SeqCst), 5);
         }
 
         #[test]
-        fn arena_mut_allows_setup() {
+        fn plugins_only_execute_for_matching_tags() {
+            let counter = Arc::new(AtomicUsize::new(0));
+
             let mut sim = Simulation::new(42);
-            let ship_id = sim.arena_mut().spawn(
+
+            // Spawn ships and platforms
+            sim.arena_mut().spawn(
                 EntityTag::Ship,
                 EntityInner::Ship(ShipComponents::default()),
             );
-            assert!(sim.arena().get(ship_id).is_some());
-        }
+            sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            sim.arena_mut().spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(crate::entity::PlatformComponents::default()),
+            );
 
-        #[test]
-        fn different_seeds_produce_different_trace_ids() {
-            let sim1 = Simulation::new(1);
-            let sim2 = Simulation::new(2);
+            let plugin = Arc::new(CountingPlugin::new(Arc::clone(&counter)));
+            sim.plugins_mut().register(EntityTag::Ship, plugin);
 
-            let trace1 = sim1.generate_trace_id(0, 0, 0);
-            let trace2 = sim2.generate_trace_id(0, 0, 0);
+            sim.step();
 
-            assert_ne!(trace1, trace2);
+            // Plugin should only execute for ships (2), not platforms
+            assert_eq!(counter.load(Ordering::SeqCst), 2);
         }
-    }
-
-    mod step_tests {
-        use super::*;
 
         #[test]
-        fn step_advances_tick() {
+        fn multiple_plugins_per_entity() {
+            let counter1 = Arc::new(AtomicUsize::new(0));
+            let counter2 = Arc::new(AtomicUsize::new(0));
+
             let mut sim = Simulation::new(42);
-            assert_eq!(sim.tick(), 0);
+            sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
 
-            sim.step();
-            assert_eq!(sim.tick(), 1);
+            let plugin1 = Arc::new(CountingPlugin::new(Arc::clone(&counter1)));
+            let plugin2 = Arc::new(CountingPlugin::new(Arc::clone(&counter2)));
+
+            sim.plugins_mut().register(EntityTag::Ship, plugin1);
+            sim.plugins_mut().register(EntityTag::Ship, plugin2);
 
             sim.step();
-            sim.step();
-            assert_eq!(sim.tick(), 3);
+
+            // Both plugins should execute for the ship
+            assert_eq!(counter1.load(Ordering::SeqCst), 1);
+            assert_eq!(counter2.load(Ordering::SeqCst), 1);
         }
+    }
 
-        #[test]
-        fn step_with_no_plugins_no_entities() {
-            let mut sim = Simulation::new(42);
-            // Should not panic
-            sim.step();
-            assert_eq!(sim.tick(), 1);
+    mod attribute_tests {
+        use super::*;
+        use crate::entity::AttributeValue;
+
+        /// Plugin that counts its runs in an attribute.
+        struct CounterPlugin {
+            declaration: PluginDeclaration,
+        }
+
+        impl Plugin for CounterPlugin {
+            fn declaration(&self) -> &PluginDeclaration {
+                &self.declaration
+            }
+
+            fn run(&self, ctx: &PluginContext, view: &WorldView) -> Vec<Output> {
+                let count = view
+                    .get_attribute(ctx.entity_id, "counter.runs")
+                    .and_then(AttributeValue::as_i64)
+                    .unwrap_or(0);
+                vec![Output::Modifier(Modifier::SetAttribute {
+                    target: ctx.entity_id,
+                    key: "counter.runs".to_string(),
+                    value: Some(AttributeValue::Int(count + 1)),
+                })]
+            }
         }
 
         #[test]
-        fn step_with_entities_no_plugins() {
+        fn plugin_state_persists_across_ticks() {
             let mut sim = Simulation::new(42);
-            sim.arena_mut().spawn(
+            let ship = sim.arena_mut().spawn(
                 EntityTag::Ship,
                 EntityInner::Ship(ShipComponents::default()),
             );
+            sim.plugins_mut().register(
+                EntityTag::Ship,
+                Arc::new(CounterPlugin {
+                    declaration: PluginDeclaration {
+                        id: PluginId::new("counter"),
+                        required_tags: vec![EntityTag::Ship],
+                        reads: vec![ComponentKind::Attributes],
+                        emits: vec![OutputKind::Modifier],
+                        runs_after: vec![],
+                        every_tick: false,
+                    },
+                }),
+            );
 
-            // Should not panic
-            sim.step();
-            assert_eq!(sim.tick(), 1);
+            for _ in 0..3 {
+                sim.step();
+            }
+
+            let runs = sim.arena().get(ship).unwrap().attribute("counter.runs");
+            assert_eq!(runs, Some(&AttributeValue::Int(3)));
         }
 
         #[test]
-        fn step_with_plugins() {
+        fn attributes_affect_state_hash() {
             let mut sim = Simulation::new(42);
-            let ship_id = sim.arena_mut().spawn(
+            let ship = sim.arena_mut().spawn(
                 EntityTag::Ship,
                 EntityInner::Ship(ShipComponents::default()),
             );
+            let before = sim.arena().state_hash();
 
-            let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
-            sim.plugins_mut().register(EntityTag::Ship, plugin);
-
-            // Check plugins registered
-            let plugins = sim.plugins_mut().plugins_for(EntityTag::Ship);
-            assert_eq!(plugins.len(), 1, "Plugin not registered");
-
-            sim.step();
+            sim.arena_mut().set_attribute(ship, "tag", Some(true.into()));
 
-            // The physics resolver uses FIXED_DT = 1/60
-            // velocity = (60, 0), so position += (60, 0) * (1/60) = (1, 0)
-            let ship = sim.arena().get(ship_id).unwrap().as_ship().unwrap();
+            assert_ne!(sim.arena().state_hash(), before);
+        }
+    }
 
-            // First check velocity was set
-            assert_eq!(
-                ship.physics.velocity,
-                Vec2::new(60.0, 0.0),
-                "Velocity was not set"
-            );
+    mod minefield_tests {
+        use super::*;
+        use crate::entity::components::MineState;
+        use crate::entity::TeamId;
+        use crate::output::Event;
 
-            // After step: plugin emitted SetVelocity, physics resolver set velocity to (60,0)
-            // and then integrated: position += velocity * dt = (0,0) + (60,0) * (1/60) = (1, 0)
-            let expected_x = 60.0 / 60.0; // 1.0
-            assert!(
-                (ship.transform.position.x - expected_x).abs() < 0.0001,
-                "Expected x={}, got x={}, velocity={:?}",
-                expected_x,
-                ship.transform.position.x,
-                ship.physics.velocity
+        #[test]
+        fn ship_entering_minefield_detonates_mine() {
+            let mut sim = Simulation::new(42);
+            let layer = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(100.0, 0.0), 0.0)),
             );
+            sim.arena_mut().set_team(layer, Some(TeamId::new(1)));
+            let mine = MinefieldResolver::lay_mine(sim.arena_mut(), layer, MineState::default())
+                .unwrap();
+            sim.arena_mut().despawn(layer);
+            let mut ship = ShipComponents::at_position(Vec2::new(0.0, 0.0), 0.0);
+            ship.physics.velocity = Vec2::new(10.0, 0.0);
+            let target = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(ship));
+
+            sim.run_until(|s| s.arena().get(mine).is_none(), 1000);
+
+            let hp = sim.arena().get(target).unwrap().as_ship().unwrap().combat.hp;
+            assert!((hp - 20.0).abs() < 1e-4);
+            assert!(sim.take_events().iter().any(|e| matches!(
+                e.output().as_event(),
+                Some(Event::MineDetonated { trigger, .. }) if *trigger == target
+            )));
         }
 
         #[test]
-        fn multiple_steps_accumulate() {
+        fn event_history_keeps_events_across_steps() {
+            use crate::battle_log::query::EventQuery;
+
             let mut sim = Simulation::new(42);
-            let ship_id = sim.arena_mut().spawn(
+            sim.enable_event_history(64);
+            let layer = sim.arena_mut().spawn(
                 EntityTag::Ship,
-                EntityInner::Ship(ShipComponents::default()),
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(100.0, 0.0), 0.0)),
             );
+            let mine = MinefieldResolver::lay_mine(sim.arena_mut(), layer, MineState::default())
+                .unwrap();
+            sim.arena_mut().despawn(layer);
+            let mut ship = ShipComponents::at_position(Vec2::new(0.0, 0.0), 0.0);
+            ship.physics.velocity = Vec2::new(10.0, 0.0);
+            let target = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(ship));
+
+            sim.run_until(|s| s.arena().get(mine).is_none(), 1000);
+            sim.step();
+            sim.step();
 
-            let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
-            sim.plugins_mut().register(EntityTag::Ship, plugin);
+            let query = EventQuery::new().kind("MineDetonated").target(target);
+            let rows = sim.event_history().rows(&query);
+            assert_eq!(rows.len(), 1);
+            assert_eq!(rows[0].entity, mine.as_u64());
+            let later = query.ticks(rows[0].tick + 1, u64::MAX);
+            assert!(sim.event_history().query(&later).is_empty());
+        }
+    }
 
-            for _ in 0..10 {
+    mod rng_audit_tests {
+        use super::*;
+        use crate::entity::components::MineState;
+        use crate::entity::TeamId;
+        use crate::rng_audit::first_divergence;
+
+        fn sweep_draws(seed: u64) -> Vec<crate::rng_audit::RngDraw> {
+            let mut sim = Simulation::new(seed);
+            sim.rng_audit().enable(64);
+            let layer = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+            );
+            sim.arena_mut().set_team(layer, Some(TeamId::new(1)));
+            MinefieldResolver::lay_mine(sim.arena_mut(), layer, MineState::default()).unwrap();
+            sim.arena_mut().despawn(layer);
+            let sweeper = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(500.0, 0.0), 0.0)),
+            );
+            sim.arena_mut().set_team(sweeper, Some(TeamId::new(2)));
+            for _ in 0..5 {
                 sim.step();
             }
+            sim.rng_audit().take()
+        }
 
-            // After 10 steps at velocity 60, with dt=1/60, position = 10
-            let ship = sim.arena().get(ship_id).unwrap().as_ship().unwrap();
-            assert!((ship.transform.position.x - 10.0).abs() < 0.0001);
+        #[test]
+        fn audit_is_disabled_by_default() {
+            assert!(!Simulation::new(1).rng_audit().is_enabled());
+        }
+
+        #[test]
+        fn same_seed_draws_match() {
+            let a = sweep_draws(3);
+            assert!(!a.is_empty());
+            assert_eq!(first_divergence(&a, &sweep_draws(3)), None);
+        }
+
+        #[test]
+        fn different_seed_diverges_at_first_draw() {
+            assert_eq!(first_divergence(&sweep_draws(3), &sweep_draws(4)), Some(0));
         }
     }
 
-    mod resolver_filtering_tests {
+    mod balance_tests {
         use super::*;
-        use crate::output::Modifier;
+        use crate::entity::TeamId;
 
-        struct DamagePlugin {
-            declaration: PluginDeclaration,
-            amount: f32,
+        #[test]
+        fn evaluate_balance_reports_current_tick() {
+            let config = SimulationConfig {
+                balance: BalanceConfig {
+                    base_firepower: 2.0,
+                    ..BalanceConfig::default()
+                },
+                ..SimulationConfig::default()
+            };
+            let mut sim = Simulation::with_config(42, config).unwrap();
+            let id = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+            );
+            sim.arena_mut().set_team(id, Some(TeamId::new(0)));
+            sim.step();
+            sim.step();
+
+            let report = sim.evaluate_balance();
+
+            assert_eq!(report.tick, 2);
+            assert_eq!(report.teams.len(), 1);
+            assert!((report.teams[0].firepower - 200.0).abs() < 1e-3);
         }
+    }
+
+    mod action_interval_tests {
+        use super::*;
+        use crate::entity::{Entity, EntityId};
+        use std::sync::atomic::AtomicUsize;
 
-        impl DamagePlugin {
-            fn new(amount: f32) -> Self {
-                Self {
-                    declaration: PluginDeclaration {
-                        id: PluginId::new("damage_test"),
-                        required_tags: vec![EntityTag::Ship],
-                        reads: vec![ComponentKind::Combat],
-                        emits: vec![OutputKind::Modifier],
-                    },
-                    amount,
-                }
-            }
+        /// Counts runs and emits a sustained and a one-shot command.
+        struct DecisionPlugin {
+            declaration: PluginDeclaration,
+            runs: Arc<AtomicUsize>,
         }
 
-        impl Plugin for DamagePlugin {
+        impl Plugin for DecisionPlugin {
             fn declaration(&self) -> &PluginDeclaration {
                 &self.declaration
             }
 
             fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
-                vec![Output::Modifier(Modifier::ApplyDamage {
-                    target: ctx.entity_id,
-                    amount: self.amount,
-                })]
+                self.runs.fetch_add(1, Ordering::SeqCst);
+                vec![
+                    Output::Command(Command::SetVelocity {
+                        target: ctx.entity_id,
+                        velocity: Vec2::new(5.0, 0.0),
+                    }),
+                    Output::Command(Command::FireWeapon {
+                        source: ctx.entity_id,
+                        target: ctx.entity_id,
+                        slot: 0,
+                    }),
+                ]
             }
         }
 
-        #[test]
-        fn resolver_receives_only_relevant_outputs() {
-            let mut sim = Simulation::new(42);
-            let ship_id = sim.arena_mut().spawn(
+        fn sim_with_interval(interval: u32) -> (Simulation, EntityId, Arc<AtomicUsize>) {
+            let config = SimulationConfig {
+                action_interval: interval,
+                ..SimulationConfig::default()
+            };
+            let mut sim = Simulation::with_config(42, config).unwrap();
+            let id = sim.arena_mut().spawn(
                 EntityTag::Ship,
-                EntityInner::Ship(ShipComponents::default()),
+                EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
             );
+            let runs = Arc::new(AtomicUsize::new(0));
+            sim.plugins_mut().register(
+                EntityTag::Ship,
+                Arc::new(DecisionPlugin {
+                    declaration: PluginDeclaration {
+                        id: PluginId::new("decision"),
+                        required_tags: vec![EntityTag::Ship],
+                        reads: vec![ComponentKind::Transform],
+                        emits: vec![OutputKind::Command],
+                        runs_after: vec![],
+                        every_tick: false,
+                    },
+                    runs: Arc::clone(&runs),
+                }),
+            );
+            (sim, id, runs)
+        }
 
-            // Register both a velocity plugin (Command) and damage plugin (Modifier)
-            let velocity_plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
-            let damage_plugin = Arc::new(DamagePlugin::new(25.0));
-
-            sim.plugins_mut().register(EntityTag::Ship, velocity_plugin);
-            sim.plugins_mut().register(EntityTag::Ship, damage_plugin);
-
-            sim.step();
+        fn velocity(sim: &Simulation, id: EntityId) -> Vec2 {
+            match sim.arena().get(id).map(Entity::inner) {
+                Some(EntityInner::Ship(c)) => c.physics.velocity,
+                _ => panic!("ship missing"),
+            }
+        }
 
-            let ship = sim.arena().get(ship_id).unwrap().as_ship().unwrap();
+        #[test]
+        fn plugins_run_only_on_decision_ticks() {
+            let (mut sim, _, runs) = sim_with_interval(3);
 
-            // Physics resolver handled velocity
-            assert_eq!(ship.physics.velocity, Vec2::new(60.0, 0.0));
+            for _ in 0..7 {
+                sim.step();
+            }
 
-            // Combat resolver handled damage
-            assert!((ship.combat.hp - 75.0).abs() < 0.0001);
+            // Ticks 0, 3 and 6
+            assert_eq!(runs.load(Ordering::SeqCst), 3);
         }
-    }
-
-    mod determinism_tests {
-        use super::*;
 
         #[test]
-        fn same_seed_same_results() {
-            fn run_simulation(seed: u64) -> Vec2 {
-                let mut sim = Simulation::new(seed);
-                let ship_id = sim.arena_mut().spawn(
-                    EntityTag::Ship,
-                    EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
-                );
+        fn default_interval_runs_every_tick() {
+            let (mut sim, _, runs) = sim_with_interval(0);
+            assert_eq!(sim.action_interval(), 1);
 
-                let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 30.0)));
-                sim.plugins_mut().register(EntityTag::Ship, plugin);
+            sim.step();
+            sim.step();
 
-                for _ in 0..10 {
-                    sim.step();
-                }
+            assert_eq!(runs.load(Ordering::SeqCst), 2);
+            assert!(sim.held_commands.is_empty());
+        }
 
-                sim.arena()
-                    .get(ship_id)
-                    .unwrap()
-                    .as_ship()
-                    .unwrap()
-                    .transform
-                    .position
-            }
+        #[test]
+        fn sustained_commands_repeat_between_decisions() {
+            let (mut sim, id, _) = sim_with_interval(4);
+            sim.step();
 
-            let pos1 = run_simulation(42);
-            let pos2 = run_simulation(42);
+            // Knock the ship off its setpoint between decisions
+            if let Some(EntityInner::Ship(c)) = sim.arena_mut().get_mut(id).map(Entity::inner_mut) {
+                c.physics.velocity = Vec2::ZERO;
+            }
+            sim.step();
 
-            assert_eq!(pos1, pos2);
+            assert_eq!(velocity(&sim, id), Vec2::new(5.0, 0.0));
+            // Firing is not replayed
+            assert_eq!(sim.held_commands.len(), 1);
         }
 
         #[test]
-        fn trace_ids_are_deterministic() {
-            let sim = Simulation::new(12345);
-
-            // Same inputs should produce same trace ID
-            let trace1 = sim.generate_trace_id(10, 5, 2);
-            let trace2 = sim.generate_trace_id(10, 5, 2);
-            assert_eq!(trace1, trace2);
+        fn step_decision_stops_at_decision_points() {
+            let (mut sim, _, runs) = sim_with_interval(5);
+            sim.step();
 
-            // Different inputs should produce different trace IDs
-            let trace3 = sim.generate_trace_id(10, 5, 3);
-            assert_ne!(trace1, trace3);
+            assert!(!sim.is_decision_tick());
+            assert_eq!(sim.step_decision(), 4);
+            assert!(sim.is_decision_tick());
+            assert_eq!(sim.step_decision(), 5);
+            assert_eq!(sim.tick(), 10);
+            assert_eq!(runs.load(Ordering::SeqCst), 2);
         }
 
         #[test]
-        fn output_order_is_deterministic() {
-            // Create multiple entities and plugins to test parallel execution order
-            let mut sim = Simulation::new(42);
+        fn guided_projectile_steers_between_decisions() {
+            use crate::entity::{ProjectileComponents, SeekerState, TeamId};
+            use crate::plugins::ProjectilePlugin;
 
-            for i in 0..5 {
-                sim.arena_mut().spawn(
-                    EntityTag::Ship,
-                    EntityInner::Ship(ShipComponents::at_position(
-                        Vec2::new(i as f32 * 100.0, 0.0),
-                        0.0,
-                    )),
-                );
-            }
+            let (mut sim, _, _) = sim_with_interval(4);
+            sim.plugins_mut().register(EntityTag::Projectile, Arc::new(ProjectilePlugin::new()));
+            let target = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::new(3000.0, 1000.0), 0.0)),
+            );
+            sim.arena_mut().set_team(target, Some(TeamId::new(1)));
+            let missile = ProjectileComponents::at_position_with_velocity(
+                Vec2::ZERO,
+                0.0,
+                Vec2::new(300.0, 0.0),
+            )
+            .with_seeker(SeekerState {
+                cue: Some(target),
+                ..SeekerState::default()
+            });
+            let missile =
+                sim.arena_mut().spawn(EntityTag::Projectile, EntityInner::Projectile(missile));
+            let heading = |sim: &Simulation| {
+                sim.arena().get(missile).unwrap().as_projectile().unwrap().transform.heading
+            };
 
-            let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
-            sim.plugins_mut().register(EntityTag::Ship, plugin);
+            sim.step();
+            let after_decision = heading(&sim);
+            assert!(!sim.is_decision_tick());
+            sim.step();
 
-            // Run multiple times to verify determinism despite parallel execution
-            let mut results = Vec::new();
-            for _ in 0..5 {
-                let mut sim_copy = Simulation::new(42);
-                for i in 0..5 {
-                    sim_copy.arena_mut().spawn(
-                        EntityTag::Ship,
-                        EntityInner::Ship(ShipComponents::at_position(
-                            Vec2::new(i as f32 * 100.0, 0.0),
-                            0.0,
-                        )),
-                    );
-                }
-                let plugin = Arc::new(VelocityPlugin::new(Vec2::new(60.0, 0.0)));
-                sim_copy.plugins_mut().register(EntityTag::Ship, plugin);
+            assert!(after_decision > 0.0);
+            assert!(heading(&sim) > after_decision);
+        }
 
-                sim_copy.step();
+        #[test]
+        fn restore_drops_held_commands() {
+            let (mut sim, _, _) = sim_with_interval(4);
+            sim.step();
+            let arena = sim.arena().clone();
 
-                let positions: Vec<_> = sim_copy
-                    .arena()
-                    .entities_sorted()
-                    .map(|e| e.as_ship().unwrap().transform.position)
-                    .collect();
-                results.push(positions);
-            }
+            sim.restore(arena);
 
-            // All runs should produce identical results
-            for i in 1..results.len() {
-                assert_eq!(results[0], results[i]);
-            }
+            assert!(sim.held_commands.is_empty());
         }
     }
 
-    mod plugin_execution_tests {
+    mod logistics_tests {
         use super::*;
-        use std::sync::atomic::{AtomicUsize, Ordering};
+        use crate::entity::Cargo;
+        use crate::output::Event;
 
-        struct CountingPlugin {
-            declaration: PluginDeclaration,
-            counter: Arc<AtomicUsize>,
+        #[test]
+        fn alongside_ships_transfer_fuel() {
+            let mut sim = Simulation::new(42);
+            let tanker = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::at_position(Vec2::ZERO, 0.0)),
+            );
+            let mut escort = ShipComponents::at_position(Vec2::new(80.0, 0.0), 0.0);
+            escort.inventory.fuel = 100.0;
+            let escort = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(escort));
+            sim.arena_mut()
+                .logistics_mut()
+                .request(tanker, escort, Cargo::Fuel(250.0));
+
+            sim.run_until(|s| s.arena().logistics().pending().is_empty(), 1000);
+
+            let fuel = |id| sim.arena().get(id).unwrap().inventory().unwrap().fuel;
+            assert!((fuel(escort) - 350.0).abs() < 1e-4);
+            assert!((fuel(tanker) - 750.0).abs() < 1e-4);
+            assert!(sim.take_events().iter().any(|e| matches!(
+                e.output().as_event(),
+                Some(Event::CargoTransferred { to, .. }) if *to == escort
+            )));
         }
+    }
 
-        impl CountingPlugin {
-            fn new(counter: Arc<AtomicUsize>) -> Self {
-                Self {
-                    declaration: PluginDeclaration {
-                        id: PluginId::new("counting"),
-                        required_tags: vec![EntityTag::Ship],
-                        reads: vec![ComponentKind::Transform],
-                        emits: vec![OutputKind::Command],
-                    },
-                    counter,
-                }
-            }
+    mod weapon_assignment_tests {
+        use super::*;
+        use crate::entity::{AmmoType, CombatState, EntityId, TeamId, WeaponState};
+
+        fn armed(sim: &mut Simulation, x: f32, team: u32) -> EntityId {
+            let mut ship = ShipComponents::at_position(Vec2::new(x, 0.0), 0.0);
+            let weapons = vec![WeaponState::new(0, 5.0, AmmoType::Shell)];
+            ship.combat = CombatState::with_weapons(100.0, weapons);
+            let id = sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(ship));
+            sim.arena_mut().set_team(id, Some(TeamId::new(team)));
+            id
         }
 
-        impl Plugin for CountingPlugin {
-            fn declaration(&self) -> &PluginDeclaration {
-                &self.declaration
+        fn fire(source: EntityId, target: EntityId) -> Command {
+            Command::FireWeapon {
+                source,
+                target,
+                slot: 0,
             }
+        }
 
-            fn run(&self, _ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
-                self.counter.fetch_add(1, Ordering::SeqCst);
-                vec![]
-            }
+        fn fired(sim: &Simulation, id: EntityId) -> bool {
+            let combat = &sim.arena().get(id).unwrap().as_ship().unwrap().combat;
+            !combat.weapons[0].is_ready()
         }
 
         #[test]
-        fn plugins_execute_for_all_matching_entities() {
-            let counter = Arc::new(AtomicUsize::new(0));
+        fn only_assigned_shooter_fires_at_shared_target() {
+            let config = SimulationConfig {
+                weapon_assignment: Some(AssignmentConfig {
+                    damage_per_shot: 100.0,
+                    max_shooters_per_target: 2,
+                }),
+                ..SimulationConfig::default()
+            };
+            let mut sim = Simulation::with_config(42, config).unwrap();
+            let target = armed(&mut sim, 0.0, 1);
+            let far = armed(&mut sim, 300.0, 0);
+            let near = armed(&mut sim, 100.0, 0);
+
+            sim.step_with_commands(&[fire(far, target), fire(near, target)]);
+
+            assert!(fired(&sim, near));
+            assert!(!fired(&sim, far));
+            let assigned = sim.weapon_assignment().unwrap().take_assignments();
+            assert_eq!(assigned.len(), 1);
+        }
 
+        #[test]
+        fn without_assignment_every_shooter_fires() {
             let mut sim = Simulation::new(42);
+            let target = armed(&mut sim, 0.0, 1);
+            let far = armed(&mut sim, 300.0, 0);
+            let near = armed(&mut sim, 100.0, 0);
 
-            // Spawn 5 ships
-            for _ in 0..5 {
-                sim.arena_mut().spawn(
-                    EntityTag::Ship,
-                    EntityInner::Ship(ShipComponents::default()),
-                );
-            }
+            sim.step_with_commands(&[fire(far, target), fire(near, target)]);
 
-            let plugin = Arc::new(CountingPlugin::new(Arc::clone(&counter)));
-            sim.plugins_mut().register(EntityTag::Ship, plugin);
+            assert!(fired(&sim, near) && fired(&sim, far));
+        }
+    }
 
-            sim.step();
+    mod classification_tests {
+        use super::*;
+        use crate::entity::SensorState;
+        use crate::plugins::SensorPlugin;
+        use crate::rng_audit::{first_divergence, RngDraw};
+
+        /// Radar-only observer classifying a contact beyond visual range.
+        fn classification_draws(seed: u64) -> Vec<RngDraw> {
+            let config = SimulationConfig {
+                classification: Some(ClassificationModel::default()),
+                ..SimulationConfig::default()
+            };
+            let mut sim = Simulation::with_config(seed, config).unwrap();
+            sim.rng_audit().enable(64);
+            sim.plugins_mut().register(EntityTag::Ship, Arc::new(SensorPlugin::new()));
+            let observer = ShipComponents {
+                sensor: SensorState::new(8000.0, 0.0).with_visual_range(0.0),
+                ..ShipComponents::default()
+            };
+            sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(observer));
+            let target = ShipComponents::at_position(Vec2::new(4000.0, 0.0), 0.0);
+            sim.arena_mut().spawn(EntityTag::Ship, EntityInner::Ship(target));
+            for _ in 0..100 {
+                sim.step();
+            }
+            sim.rng_audit().take()
+        }
 
-            // Plugin should have executed once per ship
-            assert_eq!(counter.load(Ordering::SeqCst), 5);
+        #[test]
+        fn classification_rolls_are_audited() {
+            let draws = classification_draws(7);
+            assert!(draws.iter().any(|d| d.purpose == "classification.misclassify"));
+            assert_eq!(first_divergence(&draws, &classification_draws(7)), None);
         }
 
         #[test]
-        fn plugins_only_execute_for_matching_tags() {
-            let counter = Arc::new(AtomicUsize::new(0));
+        fn classification_is_disabled_by_default() {
+            let sim = Simulation::new(7);
+            assert!(sim.classification().is_none());
+        }
+    }
 
-            let mut sim = Simulation::new(42);
+    mod watchdog_tests {
+        use super::*;
+        use crate::entity::EntityId;
+        use std::time::Duration;
 
-            // Spawn ships and platforms
-            sim.arena_mut().spawn(
+        /// Plugin that sleeps before emitting damage.
+        struct SlowPlugin {
+            declaration: PluginDeclaration,
+        }
+
+        impl Plugin for SlowPlugin {
+            fn declaration(&self) -> &PluginDeclaration {
+                &self.declaration
+            }
+
+            fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+                std::thread::sleep(Duration::from_millis(2));
+                vec![Output::Modifier(Modifier::ApplyDamage {
+                    target: ctx.entity_id,
+                    amount: 1.0,
+                    damage_type: DamageType::Kinetic,
+                })]
+            }
+        }
+
+        fn slow_sim() -> (Simulation, EntityId) {
+            let config = SimulationConfig {
+                plugin_budget: Some(PluginBudget::new(Duration::from_micros(1), 2)),
+                ..SimulationConfig::default()
+            };
+            let mut sim = Simulation::with_config(42, config).unwrap();
+            let ship = sim.arena_mut().spawn(
                 EntityTag::Ship,
                 EntityInner::Ship(ShipComponents::default()),
             );
-            sim.arena_mut().spawn(
+            sim.plugins_mut().register(
                 EntityTag::Ship,
-                EntityInner::Ship(ShipComponents::default()),
-            );
-            sim.arena_mut().spawn(
-                EntityTag::Platform,
-                EntityInner::Platform(crate::entity::PlatformComponents::default()),
+                Arc::new(SlowPlugin {
+                    declaration: PluginDeclaration {
+                        id: PluginId::new("slow"),
+                        required_tags: vec![EntityTag::Ship],
+                        reads: vec![],
+                        emits: vec![OutputKind::Modifier],
+                        runs_after: vec![],
+                        every_tick: false,
+                    },
+                }),
             );
+            (sim, ship)
+        }
 
-            let plugin = Arc::new(CountingPlugin::new(Arc::clone(&counter)));
-            sim.plugins_mut().register(EntityTag::Ship, plugin);
+        fn hp(sim: &Simulation, ship: EntityId) -> f32 {
+            sim.arena().get(ship).unwrap().as_ship().unwrap().combat.hp
+        }
 
-            sim.step();
+        #[test]
+        fn disables_plugin_after_max_overruns() {
+            let (mut sim, ship) = slow_sim();
+            let instance = PluginInstanceId::new(ship, PluginId::new("slow"));
 
-            // Plugin should only execute for ships (2), not platforms
-            assert_eq!(counter.load(Ordering::SeqCst), 2);
+            sim.step();
+            assert!(!sim.plugin_watchdog().is_disabled(&instance));
+            sim.step();
+            assert!(sim.plugin_watchdog().is_disabled(&instance));
+
+            let events = sim.take_events();
+            assert_eq!(events.len(), 1);
+            assert!(matches!(
+                events[0].output(),
+                Output::Event(Event::PluginBudgetExceeded { entity, overruns: 2, .. })
+                    if *entity == ship
+            ));
+
+            // Disabled plugin no longer runs
+            let hp_after_disable = hp(&sim, ship);
+            sim.step();
+            assert!((hp(&sim, ship) - hp_after_disable).abs() < f32::EPSILON);
+            assert_eq!(sim.plugin_watchdog().timing(&instance).unwrap().runs, 2);
         }
 
         #[test]
-        fn multiple_plugins_per_entity() {
-            let counter1 = Arc::new(AtomicUsize::new(0));
-            let counter2 = Arc::new(AtomicUsize::new(0));
+        fn re_enabled_plugin_runs_again() {
+            let (mut sim, ship) = slow_sim();
+            let instance = PluginInstanceId::new(ship, PluginId::new("slow"));
+            sim.step();
+            sim.step();
 
-            let mut sim = Simulation::new(42);
-            sim.arena_mut().spawn(
-                EntityTag::Ship,
-                EntityInner::Ship(ShipComponents::default()),
-            );
+            assert!(sim.plugin_watchdog_mut().enable(&instance));
+            sim.step();
 
-            let plugin1 = Arc::new(CountingPlugin::new(Arc::clone(&counter1)));
-            let plugin2 = Arc::new(CountingPlugin::new(Arc::clone(&counter2)));
+            assert_eq!(sim.plugin_watchdog().timing(&instance).unwrap().runs, 3);
+        }
 
-            sim.plugins_mut().register(EntityTag::Ship, plugin1);
-            sim.plugins_mut().register(EntityTag::Ship, plugin2);
+        #[test]
+        fn no_budget_only_times_plugins() {
+            let (mut sim, ship) = slow_sim();
+            sim.plugin_watchdog_mut().set_budget(None);
+            let instance = PluginInstanceId::new(ship, PluginId::new("slow"));
 
-            sim.step();
+            for _ in 0..5 {
+                sim.step();
+            }
 
-            // Both plugins should execute for the ship
-            assert_eq!(counter1.load(Ordering::SeqCst), 1);
-            assert_eq!(counter2.load(Ordering::SeqCst), 1);
+            assert!(!sim.plugin_watchdog().is_disabled(&instance));
+            let timing = sim.plugin_watchdog().timing(&instance).unwrap();
+            assert_eq!(timing.runs, 5);
+            assert!(timing.max >= Duration::from_millis(2));
+            assert!(sim.take_events().is_empty());
         }
     }
 
@@ -0,0 +1,234 @@
+//! Collision-avoidance steering assist.
+//!
+//! Scripted traffic steers straight at its waypoints and agents steer
+//! wherever their policy says, so hulls sharing a lane run into each
+//! other. The arena's [`SteeringAssistBook`] lets each controller opt into
+//! a [`SteeringAssist`]: after movement commands are applied, the
+//! [`PhysicsResolver`](crate::resolver::PhysicsResolver) blends the
+//! commanded velocity of every assisted ship and squadron with an
+//! avoidance term computed from its neighbors' velocity obstacles.
+//!
+//! For each neighbor within `range`, the relative motion is extrapolated
+//! up to `horizon` seconds ahead. If the two would pass closer than their
+//! radii plus `margin`, the assist adds the velocity change that pushes the
+//! point of closest approach back out to that distance, spread over the
+//! time left until it. The sum over all neighbors, scaled by `blend`, is
+//! added to the commanded velocity and limited to the hull's max speed.
+//!
+//! Entities under throttle keep their ordered speed and are turned toward
+//! the assisted direction within their turning limits; others take the
+//! assisted velocity directly. Ships avoid ships and platforms, squadrons
+//! avoid squadrons; projectiles and mines are never avoided or assisted.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::ControllerId;
+//! use tidebreak_core::steering::SteeringAssist;
+//!
+//! let mut arena = Arena::new();
+//! let assist = arena.steering_assist_mut();
+//! assist.set_uncontrolled(Some(SteeringAssist::default()));
+//! assist.set(ControllerId::Agent(0), Some(SteeringAssist::default().with_blend(0.5)));
+//!
+//! assert!(assist.get(None).is_some());
+//! assert!(assist.get(Some(ControllerId::Agent(1))).is_none());
+//! ```
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::ControllerId;
+
+/// Avoidance settings for the entities of one controller.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SteeringAssist {
+    /// Seconds ahead a conflict is looked for
+    pub horizon: f32,
+    /// Distance within which neighbors are considered, in meters
+    pub range: f32,
+    /// Clearance kept beyond the two hulls' radii, in meters
+    pub margin: f32,
+    /// Weight of the avoidance term, 0 (off) to 1 (full correction)
+    pub blend: f32,
+}
+
+impl SteeringAssist {
+    /// Builder method to set the look-ahead horizon in seconds.
+    #[must_use]
+    pub const fn with_horizon(mut self, horizon: f32) -> Self {
+        self.horizon = horizon;
+        self
+    }
+
+    /// Builder method to set the clearance kept beyond the hulls' radii.
+    #[must_use]
+    pub const fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Builder method to set the weight of the avoidance term.
+    #[must_use]
+    pub const fn with_blend(mut self, blend: f32) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Returns the velocity change that keeps a hull at `position` moving
+    /// at `velocity` clear of `neighbors`, given as (position, velocity,
+    /// radius) with the hull's own `radius`.
+    ///
+    /// The change is already scaled by `blend`.
+    #[must_use]
+    pub fn avoidance(
+        &self,
+        position: Vec2,
+        velocity: Vec2,
+        radius: f32,
+        neighbors: impl IntoIterator<Item = (Vec2, Vec2, f32)>,
+    ) -> Vec2 {
+        let horizon = self.horizon.max(0.0);
+        let mut correction = Vec2::ZERO;
+        for (other, other_velocity, other_radius) in neighbors {
+            let offset = other - position;
+            let closing = velocity - other_velocity;
+            let speed_squared = closing.length_squared();
+            let t = if speed_squared > 0.0 {
+                (offset.dot(closing) / speed_squared).clamp(0.0, horizon)
+            } else {
+                0.0
+            };
+            // Neighbor relative to the hull at closest approach
+            let closest = offset - closing * t;
+            let clearance = radius + other_radius + self.margin.max(0.0);
+            let miss = closest.length();
+            if miss >= clearance {
+                continue;
+            }
+            let away = (-closest)
+                .try_normalize()
+                .or_else(|| (-offset).try_normalize())
+                .unwrap_or_else(|| closing.perp().try_normalize().unwrap_or(Vec2::Y));
+            correction += away * (clearance - miss) / t.max(1.0);
+        }
+        correction * self.blend.clamp(0.0, 1.0)
+    }
+}
+
+impl Default for SteeringAssist {
+    fn default() -> Self {
+        Self {
+            horizon: 30.0,
+            range: 1000.0,
+            margin: 20.0,
+            blend: 1.0,
+        }
+    }
+}
+
+/// Per-arena record of which controllers use a steering assist.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SteeringAssistBook {
+    /// Assist for entities without a controller
+    #[serde(default)]
+    uncontrolled: Option<SteeringAssist>,
+    /// Assists by controller, sorted by controller
+    #[serde(default)]
+    controllers: Vec<(ControllerId, SteeringAssist)>,
+}
+
+impl SteeringAssistBook {
+    /// Creates a book with no assists.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets (or with `None` removes) the assist of a controller's entities.
+    pub fn set(&mut self, controller: ControllerId, assist: Option<SteeringAssist>) {
+        let index = self.controllers.binary_search_by_key(&controller, |(c, _)| *c);
+        match (index, assist) {
+            (Ok(i), Some(assist)) => self.controllers[i].1 = assist,
+            (Ok(i), None) => {
+                self.controllers.remove(i);
+            }
+            (Err(i), Some(assist)) => self.controllers.insert(i, (controller, assist)),
+            (Err(_), None) => {}
+        }
+    }
+
+    /// Sets (or with `None` removes) the assist of entities without a
+    /// controller, such as scripted traffic.
+    pub fn set_uncontrolled(&mut self, assist: Option<SteeringAssist>) {
+        self.uncontrolled = assist;
+    }
+
+    /// Returns the assist for an entity with the given controller, if any.
+    #[must_use]
+    pub fn get(&self, controller: Option<ControllerId>) -> Option<&SteeringAssist> {
+        match controller {
+            None => self.uncontrolled.as_ref(),
+            Some(controller) => self
+                .controllers
+                .binary_search_by_key(&controller, |(c, _)| *c)
+                .ok()
+                .map(|i| &self.controllers[i].1),
+        }
+    }
+
+    /// Returns true if no entity is assisted.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.uncontrolled.is_none() && self.controllers.is_empty()
+    }
+
+    /// Removes all assists.
+    pub fn clear(&mut self) {
+        self.uncontrolled = None;
+        self.controllers.clear();
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_on_conflict_is_pushed_aside() {
+        let assist = SteeringAssist::default();
+        let neighbor = (Vec2::new(200.0, 5.0), Vec2::new(-10.0, 0.0), 10.0);
+        let change = assist.avoidance(Vec2::ZERO, Vec2::new(10.0, 0.0), 10.0, [neighbor]);
+        // Closest approach in 10 s at 5 m against 40 m of clearance
+        assert!((change.y + 3.5).abs() < 1e-4);
+        assert!(change.x.abs() < 1e-4);
+
+        let diverging = (Vec2::new(200.0, 5.0), Vec2::new(20.0, 0.0), 10.0);
+        let change = assist.avoidance(Vec2::ZERO, Vec2::new(10.0, 0.0), 10.0, [diverging]);
+        assert_eq!(change, Vec2::ZERO);
+        let off = assist.with_blend(0.0);
+        assert_eq!(off.avoidance(Vec2::ZERO, Vec2::X, 10.0, [neighbor]), Vec2::ZERO);
+    }
+
+    #[test]
+    fn book_keys_assists_by_controller() {
+        let mut book = SteeringAssistBook::new();
+        assert!(book.is_empty());
+        book.set(ControllerId::Scripted(2), Some(SteeringAssist::default()));
+        book.set(ControllerId::Agent(0), Some(SteeringAssist::default().with_margin(5.0)));
+        assert_eq!(book.get(Some(ControllerId::Agent(0))).unwrap().margin, 5.0);
+        assert!(book.get(Some(ControllerId::Scripted(2))).is_some());
+        assert!(book.get(None).is_none());
+
+        book.set(ControllerId::Scripted(2), None);
+        assert!(book.get(Some(ControllerId::Scripted(2))).is_none());
+        book.clear();
+        assert!(book.is_empty());
+    }
+}
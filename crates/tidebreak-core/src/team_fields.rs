@@ -0,0 +1,249 @@
+//! Fog-of-war views of the murk universe for one team.
+//!
+//! Agents should not read temperatures, noise or smoke in water their side
+//! has no eyes on. A [`TeamFieldView`] wraps the ground-truth universe and
+//! answers queries only where the team's [`SensorCoverage`] reaches; the
+//! universe itself is only read.
+//!
+//! Coverage is the union of one disc per active member ship whose sensors
+//! are not disabled, as wide as the furthest of its sensors reaches:
+//!
+//! - radar and sonar out to their effective ranges (emissions mode and
+//!   damage applied),
+//! - lookouts out to their visual range shortened by damage, darkness and
+//!   sea state as in the `SensorPlugin`.
+//!
+//! Submerged boats keep only their sonar. Squadrons carry no sensor suite
+//! and cover nothing.
+//!
+//! ```
+//! use glam::{Vec2, Vec3};
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, SensorState, ShipComponents, TeamId};
+//! use tidebreak_core::murk::{FieldValues, Stamp, Universe, UniverseConfig};
+//! use tidebreak_core::team_fields::TeamFieldView;
+//!
+//! let mut universe = Universe::new(UniverseConfig::with_bounds(20_000.0, 20_000.0, 64.0));
+//! universe.set_point(Vec3::ZERO, FieldValues::new());
+//! universe.stamp(&Stamp::fire(Vec3::new(6000.0, 0.0, 0.0), 50.0, 1.0));
+//!
+//! let mut arena = Arena::new();
+//! let mut ship = ShipComponents::at_position(Vec2::ZERO, 0.0);
+//! ship.sensor = SensorState::new(4000.0, 2000.0);
+//! let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+//! arena.set_team(id, Some(TeamId::new(0)));
+//!
+//! // Passive sensors: the lookouts see 3 km, and the fire burns beyond
+//! let view = TeamFieldView::for_team(&universe, &arena, TeamId::new(0));
+//! assert!(view.query_point(Vec3::new(6000.0, 0.0, 0.0)).is_none());
+//! assert!(view.query_point(Vec3::new(1000.0, 0.0, 0.0)).is_some());
+//! ```
+
+use glam::{Vec2, Vec3};
+use murk::query::{PointResult, QueryResolution, QueryResult};
+use murk::Universe;
+
+use crate::arena::Arena;
+use crate::entity::components::{StatusFlags, SubmarineState};
+use crate::entity::{EntityInner, TeamId};
+
+/// Area one member's sensors cover.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoverageDisc {
+    /// Center in world coordinates
+    pub center: Vec2,
+    /// Radius in meters
+    pub radius: f32,
+}
+
+impl CoverageDisc {
+    /// Returns true if `position` lies inside the disc (edge included).
+    #[must_use]
+    pub fn contains(&self, position: Vec2) -> bool {
+        position.distance_squared(self.center) <= self.radius * self.radius
+    }
+
+    /// Returns true if the disc holds the whole circle of `radius` around
+    /// `center`.
+    #[must_use]
+    pub fn contains_circle(&self, center: Vec2, radius: f32) -> bool {
+        center.distance(self.center) + radius <= self.radius
+    }
+}
+
+/// Areas a team's sensors cover.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SensorCoverage {
+    discs: Vec<CoverageDisc>,
+}
+
+impl SensorCoverage {
+    /// Creates coverage from explicit discs.
+    #[must_use]
+    pub fn from_discs(discs: impl IntoIterator<Item = CoverageDisc>) -> Self {
+        Self {
+            discs: discs.into_iter().filter(|d| d.radius > 0.0).collect(),
+        }
+    }
+
+    /// Computes the coverage of `team`'s members in `arena`.
+    #[must_use]
+    pub fn of_team(arena: &Arena, team: TeamId) -> Self {
+        let light = arena.environment().visual_range_factor(arena.current_tick());
+        let discs = arena
+            .entities_sorted()
+            .filter(|e| e.team() == Some(team))
+            .filter_map(|e| match e.inner() {
+                EntityInner::Ship(c) => Some(c),
+                _ => None,
+            })
+            .filter(|c| {
+                c.combat.hp > 0.0
+                    && !c.combat.is_destroyed()
+                    && !c.combat.status_flags.intersects(
+                        StatusFlags::SURRENDERED | StatusFlags::SENSORS_DISABLED,
+                    )
+            })
+            .map(|c| {
+                let sensor = &c.sensor;
+                let submerged = c.submarine.as_ref().is_some_and(SubmarineState::is_submerged);
+                let above = if submerged {
+                    0.0
+                } else {
+                    let visual = sensor.visual_range * sensor.integrity() * light;
+                    sensor.effective_radar_range().max(visual)
+                };
+                CoverageDisc {
+                    center: c.transform.position,
+                    radius: above.max(sensor.effective_sonar_range()),
+                }
+            });
+        Self::from_discs(discs)
+    }
+
+    /// Returns the covering discs, in member order.
+    #[must_use]
+    pub fn discs(&self) -> &[CoverageDisc] {
+        &self.discs
+    }
+
+    /// Returns true if any member covers `position`.
+    #[must_use]
+    pub fn covers(&self, position: Vec2) -> bool {
+        self.discs.iter().any(|d| d.contains(position))
+    }
+}
+
+/// A team's view of the universe: ground truth where it has coverage,
+/// nothing elsewhere.
+#[derive(Debug, Clone)]
+pub struct TeamFieldView<'a> {
+    universe: &'a Universe,
+    coverage: SensorCoverage,
+}
+
+impl<'a> TeamFieldView<'a> {
+    /// Creates a view of `universe` through `coverage`.
+    #[must_use]
+    pub const fn new(universe: &'a Universe, coverage: SensorCoverage) -> Self {
+        Self { universe, coverage }
+    }
+
+    /// Creates the view `team` has of `universe` with its members in
+    /// `arena`.
+    #[must_use]
+    pub fn for_team(universe: &'a Universe, arena: &Arena, team: TeamId) -> Self {
+        Self::new(universe, SensorCoverage::of_team(arena, team))
+    }
+
+    /// Returns the coverage the view is filtered by.
+    #[must_use]
+    pub fn coverage(&self) -> &SensorCoverage {
+        &self.coverage
+    }
+
+    /// Queries a point, or returns `None` if the team does not cover it.
+    #[must_use]
+    pub fn query_point(&self, position: Vec3) -> Option<PointResult> {
+        self.coverage
+            .covers(position.truncate())
+            .then(|| self.universe.query_point(position))
+    }
+
+    /// Queries a volume, or returns `None` unless a single member covers
+    /// the whole horizontal extent of the sphere.
+    #[must_use]
+    pub fn query_volume(
+        &self,
+        center: Vec3,
+        radius: f32,
+        resolution: QueryResolution,
+    ) -> Option<QueryResult> {
+        let footprint = center.truncate();
+        let covered = self.coverage.discs.iter().any(|d| d.contains_circle(footprint, radius));
+        covered.then(|| self.universe.query_volume(center, radius, resolution))
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::{EmissionsMode, SensorState};
+    use crate::entity::EntityId;
+    use crate::tests::spawn_team_ship;
+    use murk::{FieldValues, Stamp, UniverseConfig};
+
+    fn ship(arena: &mut Arena, x: f32, team: u32, mode: EmissionsMode) -> EntityId {
+        let id = spawn_team_ship(arena, Vec2::new(x, 0.0), Some(team));
+        let sensor = &mut arena.get_mut(id).unwrap().as_ship_mut().unwrap().sensor;
+        *sensor = SensorState::new(5000.0, 2000.0).with_visual_range(1000.0);
+        sensor.emissions_mode = mode;
+        id
+    }
+
+    #[test]
+    fn coverage_follows_member_sensors() {
+        let mut arena = Arena::new();
+        ship(&mut arena, 0.0, 0, EmissionsMode::Active);
+        ship(&mut arena, 20_000.0, 0, EmissionsMode::Passive);
+        let blind = ship(&mut arena, -20_000.0, 0, EmissionsMode::Active);
+        let combat = &mut arena.get_mut(blind).unwrap().as_ship_mut().unwrap().combat;
+        combat.status_flags |= StatusFlags::SENSORS_DISABLED;
+        ship(&mut arena, 40_000.0, 1, EmissionsMode::Active);
+
+        let coverage = SensorCoverage::of_team(&arena, TeamId::new(0));
+        let radii: Vec<f32> = coverage.discs().iter().map(|d| d.radius).collect();
+        // Radar when active; passive sonar outreaches the lookouts
+        assert_eq!(radii, [5000.0, 1500.0]);
+        assert!(coverage.covers(Vec2::new(21_000.0, 0.0)));
+        assert!(!coverage.covers(Vec2::new(-20_000.0, 0.0)));
+        assert!(!coverage.covers(Vec2::new(40_000.0, 0.0)));
+    }
+
+    #[test]
+    fn view_hides_uncovered_fields_without_touching_the_universe() {
+        let mut universe = Universe::new(UniverseConfig::with_bounds(4000.0, 4000.0, 64.0));
+        universe.set_point(Vec3::ZERO, FieldValues::new());
+        universe.stamp(&Stamp::fire(Vec3::new(1500.0, 0.0, 0.0), 50.0, 1.0));
+        let hash = universe.state_hash();
+        let coverage = SensorCoverage::from_discs([CoverageDisc {
+            center: Vec2::new(-1000.0, 0.0),
+            radius: 1000.0,
+        }]);
+        let view = TeamFieldView::new(&universe, coverage);
+
+        assert!(view.query_point(Vec3::new(1500.0, 0.0, 0.0)).is_none());
+        let near = Vec3::new(-500.0, 0.0, 0.0);
+        let seen = view.query_point(near).unwrap().values;
+        assert_eq!(seen.as_slice(), universe.query_point(near).values.as_slice());
+        let coarse = QueryResolution::Coarse;
+        assert!(view.query_volume(Vec3::new(-1000.0, 0.0, 0.0), 400.0, coarse).is_some());
+        // Partly outside the disc
+        assert!(view.query_volume(Vec3::new(-200.0, 0.0, 0.0), 400.0, coarse).is_none());
+        assert_eq!(universe.state_hash(), hash);
+    }
+}
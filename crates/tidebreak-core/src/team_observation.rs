@@ -0,0 +1,477 @@
+//! Commander-level observations aggregated over a whole team.
+//!
+//! Per-ship observations only show what one hull sees. A commander policy
+//! needs the team's combined picture instead, which [`TeamObservation`]
+//! assembles from three parts:
+//!
+//! - **Fused tracks**: every member's track table merged per target. The
+//!   best report wins (highest quality, then freshest), and a
+//!   classification from any report fills in an unclassified best one.
+//!   Tracks of the team's own members are dropped. Members with disabled
+//!   sensors contribute nothing, as in [`crate::balance`].
+//! - **Own-force summary**: one row per teamed ship or squadron.
+//! - **Zone statuses**: for each caller-declared circular [`Zone`], the
+//!   members inside (ground truth) and the fused tracks inside (perceived).
+//!
+//! The observation also carries the [`ClockReading`] of the world clock,
+//! so a commander can plan around darkness and moonlight.
+//!
+//! # Example
+//!
+//! ```
+//! use tidebreak_core::arena::Arena;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents, TeamId};
+//! use tidebreak_core::team_observation::{TeamObservationBuilder, Zone, ZoneControl};
+//! use glam::Vec2;
+//!
+//! let mut arena = Arena::new();
+//! let ship = ShipComponents::at_position(Vec2::new(100.0, 0.0), 0.0);
+//! let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+//! arena.set_team(id, Some(TeamId::new(0)));
+//!
+//! let obs = TeamObservationBuilder::new(TeamId::new(0))
+//!     .with_zones([Zone::new(Vec2::ZERO, 500.0)])
+//!     .build(&arena);
+//! assert_eq!(obs.members.len(), 1);
+//! assert_eq!(obs.zones[0].control, ZoneControl::Friendly);
+//! ```
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::environment::ClockReading;
+use crate::entity::components::{CombatState, StatusFlags};
+use crate::entity::{Entity, EntityId, EntityInner, EntityTag, TeamId, Track, TrackQuality};
+
+// =============================================================================
+// Zones
+// =============================================================================
+
+/// Circular area whose status is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Zone {
+    /// Center in world coordinates
+    pub center: Vec2,
+    /// Radius in meters
+    pub radius: f32,
+}
+
+impl Zone {
+    /// Creates a zone.
+    #[must_use]
+    pub const fn new(center: Vec2, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Returns true if `position` lies inside the zone (edge included).
+    #[must_use]
+    pub fn contains(&self, position: Vec2) -> bool {
+        position.distance_squared(self.center) <= self.radius * self.radius
+    }
+}
+
+/// Who holds a zone, as the team sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZoneControl {
+    /// Neither members nor tracks inside.
+    Empty,
+    /// Only members inside.
+    Friendly,
+    /// Only tracks inside.
+    Hostile,
+    /// Both inside.
+    Contested,
+}
+
+impl ZoneControl {
+    /// Classifies a zone from its occupant counts.
+    #[must_use]
+    pub const fn from_counts(friendly: usize, hostile: usize) -> Self {
+        match (friendly > 0, hostile > 0) {
+            (false, false) => Self::Empty,
+            (true, false) => Self::Friendly,
+            (false, true) => Self::Hostile,
+            (true, true) => Self::Contested,
+        }
+    }
+}
+
+/// Status of one zone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoneStatus {
+    /// Zone reported on
+    pub zone: Zone,
+    /// Active members inside
+    pub friendly: usize,
+    /// Fused tracks inside
+    pub hostile: usize,
+    /// Resulting control
+    pub control: ZoneControl,
+}
+
+// =============================================================================
+// Rows
+// =============================================================================
+
+/// A target as seen by the whole team.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FusedTrack {
+    /// Tracked entity
+    pub target_id: EntityId,
+    /// Position from the best report
+    pub position: Vec2,
+    /// Velocity from the best report, zero if unknown
+    pub velocity: Vec2,
+    /// Best quality among the reports
+    pub quality: TrackQuality,
+    /// Age of the best report in seconds
+    pub age: f32,
+    /// Classified tag from any report, or `None` while unidentified
+    pub classified_as: Option<EntityTag>,
+    /// Number of members holding a track on the target
+    pub reporters: usize,
+}
+
+impl FusedTrack {
+    fn from_track(track: &Track) -> Self {
+        Self {
+            target_id: track.target_id,
+            position: track.position,
+            velocity: track.velocity.unwrap_or(Vec2::ZERO),
+            quality: track.quality,
+            age: track.age,
+            classified_as: track.classified_as,
+            reporters: 1,
+        }
+    }
+
+    /// Folds another member's report on the same target into this one.
+    fn merge(&mut self, track: &Track) {
+        let classified_as = self.classified_as.or(track.classified_as);
+        let reporters = self.reporters + 1;
+        if (track.quality, -track.age) > (self.quality, -self.age) {
+            *self = Self::from_track(track);
+        }
+        self.classified_as = self.classified_as.or(classified_as);
+        self.reporters = reporters;
+    }
+}
+
+/// Own-force summary of one member.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemberSummary {
+    /// Member entity
+    pub id: EntityId,
+    /// Entity kind (ship or squadron)
+    pub tag: EntityTag,
+    /// Position in world coordinates
+    pub position: Vec2,
+    /// Heading in radians
+    pub heading: f32,
+    /// Velocity in m/s
+    pub velocity: Vec2,
+    /// HP as a fraction of max HP [0, 1]
+    pub hp_fraction: f32,
+    /// Fuel as a fraction of capacity [0, 1]; 1 for squadrons
+    pub fuel_fraction: f32,
+    /// Operational weapons
+    pub weapons_ready: usize,
+    /// Rounds carried, summed over ammo types; 0 for squadrons
+    pub ammo: u32,
+    /// Neither destroyed nor surrendered
+    pub active: bool,
+}
+
+impl MemberSummary {
+    fn of(entity: &Entity) -> Option<Self> {
+        let (transform, physics, combat) = match entity.inner() {
+            EntityInner::Ship(c) => (&c.transform, &c.physics, &c.combat),
+            EntityInner::Squadron(c) => (&c.transform, &c.physics, &c.combat),
+            EntityInner::Platform(_) | EntityInner::Projectile(_) => return None,
+        };
+        let (fuel_fraction, ammo) = match entity.inner() {
+            EntityInner::Ship(c) if c.inventory.max_fuel > 0.0 => (
+                (c.inventory.fuel / c.inventory.max_fuel).clamp(0.0, 1.0),
+                c.inventory.ammo.values().sum(),
+            ),
+            EntityInner::Ship(c) => (0.0, c.inventory.ammo.values().sum()),
+            _ => (1.0, 0),
+        };
+        Some(Self {
+            id: entity.id(),
+            tag: entity.tag(),
+            position: transform.position,
+            heading: transform.heading,
+            velocity: physics.velocity,
+            hp_fraction: hp_fraction(combat),
+            fuel_fraction,
+            weapons_ready: combat.weapons.iter().filter(|w| w.operational).count(),
+            ammo,
+            active: !combat.is_destroyed()
+                && combat.hp > 0.0
+                && !combat.status_flags.contains(StatusFlags::SURRENDERED),
+        })
+    }
+}
+
+fn hp_fraction(combat: &CombatState) -> f32 {
+    if combat.max_hp > 0.0 {
+        (combat.hp / combat.max_hp).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+// =============================================================================
+// TeamObservation
+// =============================================================================
+
+/// Aggregated picture of the battle for one team.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamObservation {
+    /// Team observed for
+    pub team: TeamId,
+    /// Tick the observation was built at
+    pub tick: u64,
+    /// Date, time and light at `tick`
+    pub clock: ClockReading,
+    /// Fused tracks, sorted by target
+    pub tracks: Vec<FusedTrack>,
+    /// Members, sorted by entity ID
+    pub members: Vec<MemberSummary>,
+    /// Zone statuses, in declaration order
+    pub zones: Vec<ZoneStatus>,
+}
+
+/// Builder for [`TeamObservation`]s.
+#[derive(Debug, Clone)]
+pub struct TeamObservationBuilder {
+    team: TeamId,
+    zones: Vec<Zone>,
+}
+
+impl TeamObservationBuilder {
+    /// Creates a builder for `team` with no zones.
+    #[must_use]
+    pub const fn new(team: TeamId) -> Self {
+        Self {
+            team,
+            zones: Vec::new(),
+        }
+    }
+
+    /// Adds zones whose status is reported.
+    #[must_use]
+    pub fn with_zones(mut self, zones: impl IntoIterator<Item = Zone>) -> Self {
+        self.zones.extend(zones);
+        self
+    }
+
+    /// Returns the declared zones.
+    #[must_use]
+    pub fn zones(&self) -> &[Zone] {
+        &self.zones
+    }
+
+    /// Builds the team's observation of `arena`.
+    #[must_use]
+    pub fn build(&self, arena: &Arena) -> TeamObservation {
+        let mut members = Vec::new();
+        let mut fused: BTreeMap<EntityId, FusedTrack> = BTreeMap::new();
+        for entity in arena.entities_sorted() {
+            if entity.team() != Some(self.team) {
+                continue;
+            }
+            let Some(summary) = MemberSummary::of(entity) else {
+                continue;
+            };
+            members.push(summary);
+            let EntityInner::Ship(c) = entity.inner() else {
+                continue;
+            };
+            if !summary.active || c.combat.status_flags.contains(StatusFlags::SENSORS_DISABLED) {
+                continue;
+            }
+            for track in &c.sensor.track_table {
+                match fused.entry(track.target_id) {
+                    Entry::Vacant(slot) => {
+                        slot.insert(FusedTrack::from_track(track));
+                    }
+                    Entry::Occupied(mut slot) => slot.get_mut().merge(track),
+                }
+            }
+        }
+
+        let own: BTreeSet<EntityId> = members.iter().map(|m| m.id).collect();
+        let tracks: Vec<FusedTrack> = fused
+            .into_values()
+            .filter(|t| !own.contains(&t.target_id))
+            .collect();
+
+        let zones = self
+            .zones
+            .iter()
+            .map(|&zone| {
+                let friendly = members
+                    .iter()
+                    .filter(|m| m.active && zone.contains(m.position))
+                    .count();
+                let hostile = tracks.iter().filter(|t| zone.contains(t.position)).count();
+                ZoneStatus {
+                    zone,
+                    friendly,
+                    hostile,
+                    control: ZoneControl::from_counts(friendly, hostile),
+                }
+            })
+            .collect();
+
+        let tick = arena.current_tick();
+        TeamObservation {
+            team: self.team,
+            tick,
+            clock: arena.environment().clock.reading(tick),
+            tracks,
+            members,
+            zones,
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::components::AmmoType;
+    use crate::entity::{ShipComponents, SquadronComponents};
+    use crate::tests::spawn_team_ship;
+
+    fn ship_mut(arena: &mut Arena, id: EntityId) -> &mut ShipComponents {
+        arena.get_mut(id).unwrap().as_ship_mut().unwrap()
+    }
+
+    fn track(target: EntityId, x: f32, quality: TrackQuality, age: f32) -> Track {
+        let mut track = Track::new(target, Vec2::new(x, 0.0), quality);
+        track.age = age;
+        track
+    }
+
+    #[test]
+    fn fuses_reports_per_target() {
+        let mut arena = Arena::new();
+        let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let b = spawn_team_ship(&mut arena, Vec2::new(100.0, 0.0), Some(0));
+        let enemy = spawn_team_ship(&mut arena, Vec2::new(1000.0, 0.0), Some(1));
+
+        ship_mut(&mut arena, a).sensor.track_table = vec![
+            track(enemy, 990.0, TrackQuality::Coarse, 0.0),
+            // Own members are dropped
+            track(b, 100.0, TrackQuality::FireControl, 0.0),
+        ];
+        let mut classified = track(enemy, 1010.0, TrackQuality::Cue, 0.0);
+        classified.classified_as = Some(EntityTag::Ship);
+        ship_mut(&mut arena, b).sensor.track_table = vec![
+            track(enemy, 1005.0, TrackQuality::FireControl, 2.0),
+            classified,
+        ];
+
+        let obs = TeamObservationBuilder::new(TeamId::new(0)).build(&arena);
+        assert_eq!(obs.tracks.len(), 1);
+        let fused = obs.tracks[0];
+        assert_eq!(fused.target_id, enemy);
+        assert_eq!(fused.quality, TrackQuality::FireControl);
+        assert_eq!(fused.position.x, 1005.0);
+        assert_eq!(fused.classified_as, Some(EntityTag::Ship));
+        assert_eq!(fused.reporters, 3);
+    }
+
+    #[test]
+    fn fresher_report_wins_at_equal_quality() {
+        let mut arena = Arena::new();
+        let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let target = EntityId::new(99);
+        ship_mut(&mut arena, a).sensor.track_table = vec![
+            track(target, 10.0, TrackQuality::Coarse, 5.0),
+            track(target, 20.0, TrackQuality::Coarse, 1.0),
+        ];
+
+        let obs = TeamObservationBuilder::new(TeamId::new(0)).build(&arena);
+        assert_eq!(obs.tracks[0].position.x, 20.0);
+    }
+
+    #[test]
+    fn disabled_sensors_do_not_report() {
+        let mut arena = Arena::new();
+        let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let ship = ship_mut(&mut arena, a);
+        ship.sensor.track_table = vec![track(EntityId::new(99), 10.0, TrackQuality::Cue, 0.0)];
+        ship.combat.status_flags.insert(StatusFlags::SENSORS_DISABLED);
+
+        let obs = TeamObservationBuilder::new(TeamId::new(0)).build(&arena);
+        assert!(obs.tracks.is_empty());
+        assert_eq!(obs.members.len(), 1);
+    }
+
+    #[test]
+    fn summarizes_members() {
+        let mut arena = Arena::new();
+        let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        spawn_team_ship(&mut arena, Vec2::new(500.0, 0.0), Some(1));
+        let squadron = arena.spawn(
+            EntityTag::Squadron,
+            EntityInner::Squadron(SquadronComponents::at_position(Vec2::ZERO, 0.0)),
+        );
+        arena.set_team(squadron, Some(TeamId::new(0)));
+        let ship = ship_mut(&mut arena, a);
+        ship.combat.hp = ship.combat.max_hp / 2.0;
+        ship.inventory.ammo.insert(AmmoType::Shell, 30);
+        ship.inventory.ammo.insert(AmmoType::Torpedo, 4);
+
+        let obs = TeamObservationBuilder::new(TeamId::new(0)).build(&arena);
+        assert_eq!(obs.members.len(), 2);
+        assert_eq!(obs.members[0].id, a);
+        assert!((obs.members[0].hp_fraction - 0.5).abs() < 1e-6);
+        assert_eq!(obs.members[0].ammo, 34);
+        assert!(obs.members[0].active);
+        assert_eq!(obs.members[1].tag, EntityTag::Squadron);
+        assert_eq!(obs.members[1].fuel_fraction, 1.0);
+        // The default clock reads noon
+        assert_eq!(obs.clock.phase, crate::environment::DayPhase::Day);
+    }
+
+    #[test]
+    fn reports_zone_control() {
+        let mut arena = Arena::new();
+        let a = spawn_team_ship(&mut arena, Vec2::ZERO, Some(0));
+        let enemy = spawn_team_ship(&mut arena, Vec2::new(1000.0, 0.0), Some(1));
+        ship_mut(&mut arena, a).sensor.track_table =
+            vec![track(enemy, 1000.0, TrackQuality::Coarse, 0.0)];
+
+        let zones = [
+            Zone::new(Vec2::ZERO, 100.0),
+            Zone::new(Vec2::new(1000.0, 0.0), 100.0),
+            Zone::new(Vec2::new(500.0, 0.0), 600.0),
+            Zone::new(Vec2::new(0.0, 5000.0), 100.0),
+        ];
+        let obs = TeamObservationBuilder::new(TeamId::new(0))
+            .with_zones(zones)
+            .build(&arena);
+        let control: Vec<_> = obs.zones.iter().map(|z| z.control).collect();
+        assert_eq!(
+            control,
+            vec![
+                ZoneControl::Friendly,
+                ZoneControl::Hostile,
+                ZoneControl::Contested,
+                ZoneControl::Empty,
+            ]
+        );
+    }
+}
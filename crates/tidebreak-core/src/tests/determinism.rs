@@ -42,6 +42,8 @@ impl DeterministicVelocityPlugin {
                 required_tags: vec![EntityTag::Ship],
                 reads: vec![ComponentKind::Transform, ComponentKind::Physics],
                 emits: vec![OutputKind::Command],
+                runs_after: vec![],
+                every_tick: false,
             },
             base_velocity,
         }
@@ -80,6 +82,8 @@ impl MultiOutputPlugin {
                 required_tags: vec![EntityTag::Ship],
                 reads: vec![ComponentKind::Transform],
                 emits: vec![OutputKind::Command],
+                runs_after: vec![],
+                every_tick: false,
             },
         }
     }
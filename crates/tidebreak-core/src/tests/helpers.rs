@@ -7,8 +7,8 @@ use glam::Vec2;
 
 use crate::arena::Arena;
 use crate::entity::{
-    AmmoType, CombatState, EntityId, EntityInner, EntityTag, ShipComponents, Track, TrackQuality,
-    WeaponState,
+    AmmoType, CombatState, EntityId, EntityInner, EntityTag, ShipComponents, TeamId, Track,
+    TrackQuality, WeaponState,
 };
 use crate::simulation::Simulation;
 
@@ -101,6 +101,23 @@ pub fn spawn_test_ship(arena: &mut Arena, position: Vec2) -> EntityId {
     arena.spawn(EntityTag::Ship, inner)
 }
 
+/// Spawns a test ship at the given position on a team.
+///
+/// # Arguments
+///
+/// * `arena` - The arena to spawn in
+/// * `position` - World position for the ship
+/// * `team` - Team of the ship, or `None` for no team
+///
+/// # Returns
+///
+/// The entity ID of the spawned ship.
+pub fn spawn_team_ship(arena: &mut Arena, position: Vec2, team: Option<u32>) -> EntityId {
+    let id = spawn_test_ship(arena, position);
+    arena.set_team(id, team.map(TeamId::new));
+    id
+}
+
 /// Spawns a ship with a weapon at the given position.
 ///
 /// Creates a ship with one weapon in slot 0, ready to fire.
@@ -121,6 +138,8 @@ pub fn spawn_armed_ship(arena: &mut Arena, position: Vec2) -> EntityId {
         combat: CombatState::with_weapons(100.0, weapons),
         sensor: crate::entity::SensorState::default(),
         inventory: crate::entity::InventoryState::default(),
+        signature: crate::entity::SignatureState::default(),
+        submarine: None,
     });
     arena.spawn(EntityTag::Ship, inner)
 }
@@ -144,11 +163,12 @@ pub fn spawn_ship_with_hp(arena: &mut Arena, position: Vec2, hp: f32, max_hp: f3
         combat: CombatState {
             hp,
             max_hp,
-            weapons: Vec::new(),
-            status_flags: crate::entity::StatusFlags::empty(),
+            ..CombatState::default()
         },
         sensor: crate::entity::SensorState::default(),
         inventory: crate::entity::InventoryState::default(),
+        signature: crate::entity::SignatureState::default(),
+        submarine: None,
     });
     arena.spawn(EntityTag::Ship, inner)
 }
@@ -13,13 +13,14 @@ use std::sync::Arc;
 use glam::Vec2;
 
 use crate::entity::{
-    EntityId, EntityInner, EntityTag, PlatformComponents, ProjectileComponents, ShipComponents,
-    SquadronComponents, StatusFlags,
+    DamageType, Entity, EntityId, EntityInner, EntityTag, PlatformComponents,
+    ProjectileComponents, ShipComponents, SquadronComponents, StatusFlags,
 };
 use crate::output::{Command, Event, Modifier, Output, OutputKind, PluginId};
 use crate::plugin::{
     ComponentKind, Plugin, PluginContext, PluginDeclaration, PluginRegistry,
 };
+use crate::plugins::SensorPlugin;
 use crate::simulation::Simulation;
 use crate::world_view::WorldView;
 
@@ -46,6 +47,8 @@ impl ConstantVelocityPlugin {
                 required_tags: vec![EntityTag::Ship],
                 reads: vec![ComponentKind::Transform, ComponentKind::Physics],
                 emits: vec![OutputKind::Command],
+                runs_after: vec![],
+                every_tick: false,
             },
             velocity,
         }
@@ -80,6 +83,8 @@ impl DamagePlugin {
                 required_tags: vec![EntityTag::Ship],
                 reads: vec![ComponentKind::Combat],
                 emits: vec![OutputKind::Modifier],
+                runs_after: vec![],
+                every_tick: false,
             },
             target,
             damage,
@@ -98,6 +103,7 @@ impl Plugin for DamagePlugin {
             vec![Output::Modifier(Modifier::ApplyDamage {
                 target: self.target,
                 amount: self.damage,
+                damage_type: DamageType::Kinetic,
             })]
         } else {
             vec![]
@@ -118,6 +124,8 @@ impl EventEmitterPlugin {
                 required_tags: vec![EntityTag::Ship],
                 reads: vec![ComponentKind::Transform],
                 emits: vec![OutputKind::Event],
+                runs_after: vec![],
+                every_tick: false,
             },
         }
     }
@@ -448,9 +456,31 @@ fn events_are_captured() {
     sim.step();
 
     // Events should have been captured by the EventResolver
-    // Note: The EventResolver is internal to Simulation, so we can't directly
-    // access its events. This test verifies the flow doesn't crash.
     assert_eq!(sim.tick(), 1);
+    let events = sim.take_events();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(
+        events[0].output().as_event(),
+        Some(Event::WeaponFired { .. })
+    ));
+    assert!(sim.take_events().is_empty());
+}
+
+/// Test that the event log only holds the most recent step.
+#[test]
+fn event_log_is_bounded_to_last_step() {
+    let mut sim = Simulation::new(42);
+
+    let _ship_id = spawn_test_ship(sim.arena_mut(), Vec2::new(0.0, 0.0));
+
+    let plugin = Arc::new(EventEmitterPlugin::new());
+    sim.plugins_mut().register(EntityTag::Ship, plugin);
+
+    for _ in 0..5 {
+        sim.step();
+    }
+
+    assert_eq!(sim.take_events().len(), 1);
 }
 
 // =============================================================================
@@ -477,6 +507,8 @@ fn worldview_denies_undeclared_access() {
         required_tags: vec![EntityTag::Ship],
         reads: vec![ComponentKind::Transform], // Only Transform
         emits: vec![OutputKind::Command],
+        runs_after: vec![],
+        every_tick: false,
     };
 
     let view = WorldView::for_plugin(&arena, &decl, 0);
@@ -569,6 +601,38 @@ fn spatial_index_updates_after_movement() {
     assert!(nearby.contains(&ship_id), "Ship should be near (600, 0)");
 }
 
+// =============================================================================
+// Sensor Tracking Tests
+// =============================================================================
+
+/// Test that sensor detections of a moving target build a track whose
+/// history estimates its course and speed.
+#[test]
+fn detections_estimate_moving_target_velocity() {
+    let mut sim = Simulation::new(42);
+    let station = sim.arena_mut().spawn(
+        EntityTag::Platform,
+        EntityInner::Platform(PlatformComponents::at_position(Vec2::ZERO)),
+    );
+    let target = spawn_test_ship(sim.arena_mut(), Vec2::new(2000.0, 0.0));
+    sim.plugins_mut().register(EntityTag::Platform, Arc::new(SensorPlugin::new()));
+    let plugin = Arc::new(ConstantVelocityPlugin::new(Vec2::new(0.0, 12.0)));
+    sim.plugins_mut().register(EntityTag::Ship, plugin);
+
+    for _ in 0..30 {
+        sim.step();
+    }
+
+    let sensor = match sim.arena().get(station).map(Entity::inner) {
+        Some(EntityInner::Platform(c)) => &c.sensor,
+        _ => panic!("station missing"),
+    };
+    let track = sensor.find_track(target).expect("target should be tracked");
+    let estimate = track.estimate().expect("history should hold enough fixes");
+    assert!((estimate.velocity - Vec2::new(0.0, 12.0)).length() < 0.1);
+    assert_eq!(track.velocity, Some(estimate.velocity));
+}
+
 // =============================================================================
 // Multi-Entity Interaction Tests
 // =============================================================================
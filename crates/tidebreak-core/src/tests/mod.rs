@@ -3,17 +3,20 @@
 //! This module provides comprehensive tests for the Entity-Plugin-Resolver system:
 //! - **Determinism tests**: Verify same seed produces identical results
 //! - **Integration tests**: Test the full simulation pipeline
+//! - **Property tests**: Check invariants over generated arenas and outputs
 //! - **Helper functions**: Utilities for test setup
 //!
 //! # Test Structure
 //!
 //! - `determinism.rs`: Tests that verify deterministic execution
 //! - `integration.rs`: End-to-end tests of the simulation
+//! - `properties.rs`: Proptest generators and resolver invariants
 //! - `helpers.rs`: Test setup utilities and factory functions
 
 mod determinism;
 mod helpers;
 mod integration;
+mod properties;
 
 // Re-export for convenience
 pub use helpers::*;
@@ -0,0 +1,298 @@
+//! Property-based tests for arena bookkeeping and the resolver pipeline.
+//!
+//! Generators build arbitrary arenas and per-tick scripts of plugin outputs
+//! (including outputs aimed at missing entities), then step the simulation
+//! and check invariants that must hold whatever plugins emit:
+//! - hp is finite and never exceeds max hp
+//! - destroyed entities emit no further commands, held or fresh
+//! - the spatial index agrees with every entity's transform
+
+use std::f32::consts::PI;
+use std::sync::{Arc, Mutex};
+
+use glam::Vec2;
+use proptest::prelude::*;
+
+use crate::arena::Arena;
+use crate::entity::{
+    AmmoType, CombatState, DamageType, Entity, EntityId, EntityInner, EntityTag, ShipComponents,
+    WeaponState,
+};
+use crate::output::{Command, Modifier, Output, OutputEnvelope, OutputKind, PluginId};
+use crate::plugin::{ComponentKind, Plugin, PluginContext, PluginDeclaration};
+use crate::resolver::Resolver;
+use crate::simulation::Simulation;
+use crate::world_view::WorldView;
+
+use super::helpers::{get_position, spawn_test_ship};
+
+// =============================================================================
+// Generators
+// =============================================================================
+
+/// Outputs each entity emits on each tick, keyed by emitting entity.
+type Script = Vec<Vec<(EntityId, Output)>>;
+
+/// Starting state of one generated ship.
+#[derive(Debug, Clone)]
+struct ShipSpec {
+    position: Vec2,
+    heading: f32,
+    max_hp: f32,
+    hp_fraction: f32,
+}
+
+fn arb_vec2(extent: f32) -> impl Strategy<Value = Vec2> {
+    (-extent..extent, -extent..extent).prop_map(|(x, y)| Vec2::new(x, y))
+}
+
+fn arb_ship() -> impl Strategy<Value = ShipSpec> {
+    (arb_vec2(2000.0), -PI..PI, 10.0f32..500.0, 0.0f32..=1.0).prop_map(
+        |(position, heading, max_hp, hp_fraction)| ShipSpec {
+            position,
+            heading,
+            max_hp,
+            hp_fraction,
+        },
+    )
+}
+
+fn arb_damage_type() -> impl Strategy<Value = DamageType> {
+    prop_oneof![
+        Just(DamageType::Kinetic),
+        Just(DamageType::Explosive),
+        Just(DamageType::Energy),
+    ]
+}
+
+/// Generates an output whose entity references fall in `0..=count`, so one
+/// in `count + 1` references names an entity that does not exist.
+fn arb_output(count: u64) -> impl Strategy<Value = Output> {
+    let id = (0..=count).prop_map(EntityId::new);
+    let amount = -50.0f32..1000.0;
+    prop_oneof![
+        (id.clone(), amount.clone(), arb_damage_type()).prop_map(
+            |(target, amount, damage_type)| {
+                Output::Modifier(Modifier::ApplyDamage { target, amount, damage_type })
+            }
+        ),
+        (id.clone(), amount.clone()).prop_map(|(target, amount)| {
+            Output::Modifier(Modifier::ApplyHealing { target, amount })
+        }),
+        (arb_vec2(2000.0), 0.0f32..500.0, amount, 0.0f32..=1.0).prop_map(
+            |(center, radius, amount, falloff)| {
+                Output::Modifier(Modifier::ApplyAreaDamage { center, radius, amount, falloff })
+            }
+        ),
+        (id.clone(), arb_vec2(50.0)).prop_map(|(target, velocity)| {
+            Output::Command(Command::SetVelocity { target, velocity })
+        }),
+        (id.clone(), -PI..PI).prop_map(|(target, heading)| {
+            Output::Command(Command::SetHeading { target, heading })
+        }),
+        (id.clone(), -1.0f32..2.0).prop_map(|(target, fraction)| {
+            Output::Command(Command::SetThrottle { target, fraction })
+        }),
+        (id.clone(), id.clone(), 0usize..2).prop_map(|(source, target, slot)| {
+            Output::Command(Command::FireWeapon { source, target, slot })
+        }),
+        (id, 0usize..2).prop_map(|(source, slot)| {
+            Output::Command(Command::ReloadWeapon { source, slot })
+        }),
+    ]
+}
+
+/// Generates ships, an action interval and a script of up to 24 ticks.
+fn arb_battle() -> impl Strategy<Value = (Vec<ShipSpec>, u32, Script)> {
+    (prop::collection::vec(arb_ship(), 1..8), 1u32..4).prop_flat_map(|(ships, interval)| {
+        let count = ships.len() as u64;
+        let emission = ((0..count).prop_map(EntityId::new), arb_output(count));
+        let tick = prop::collection::vec(emission, 0..4);
+        (Just(ships), Just(interval), prop::collection::vec(tick, 1..24))
+    })
+}
+
+/// An arena mutation: spawn at a position, or despawn or move the live
+/// entity at an index (taken modulo the live count).
+#[derive(Debug, Clone)]
+enum ArenaOp {
+    Spawn(Vec2),
+    Despawn(usize),
+    Move(usize, Vec2),
+}
+
+fn arb_arena_op() -> impl Strategy<Value = ArenaOp> {
+    prop_oneof![
+        arb_vec2(2000.0).prop_map(ArenaOp::Spawn),
+        any::<usize>().prop_map(ArenaOp::Despawn),
+        (any::<usize>(), arb_vec2(2000.0)).prop_map(|(index, to)| ArenaOp::Move(index, to)),
+    ]
+}
+
+// =============================================================================
+// Harness
+// =============================================================================
+
+fn spawn_ship(arena: &mut Arena, spec: &ShipSpec) -> EntityId {
+    let weapons = vec![
+        WeaponState::new(0, 1.0, AmmoType::Bullet),
+        WeaponState::new(1, 1.0, AmmoType::Shell),
+    ];
+    let mut combat = CombatState::with_weapons(spec.max_hp, weapons);
+    combat.hp = spec.max_hp * spec.hp_fraction;
+    let inner = EntityInner::Ship(ShipComponents {
+        transform: crate::entity::TransformState::new(spec.position, spec.heading),
+        physics: crate::entity::PhysicsState::default(),
+        combat,
+        sensor: crate::entity::SensorState::default(),
+        inventory: crate::entity::InventoryState::default(),
+        signature: crate::entity::SignatureState::default(),
+        submarine: None,
+    });
+    arena.spawn(EntityTag::Ship, inner)
+}
+
+/// Replays a script: each ship emits its scripted outputs for the tick.
+struct ScriptPlugin {
+    declaration: PluginDeclaration,
+    script: Script,
+}
+
+impl ScriptPlugin {
+    fn new(script: Script) -> Self {
+        Self {
+            declaration: PluginDeclaration {
+                id: PluginId::new("script"),
+                required_tags: vec![EntityTag::Ship],
+                reads: vec![ComponentKind::Combat],
+                emits: vec![OutputKind::Command, OutputKind::Modifier],
+                runs_after: vec![],
+                every_tick: false,
+            },
+            script,
+        }
+    }
+}
+
+impl Plugin for ScriptPlugin {
+    fn declaration(&self) -> &PluginDeclaration {
+        &self.declaration
+    }
+
+    fn run(&self, ctx: &PluginContext, _view: &WorldView) -> Vec<Output> {
+        let Some(tick) = usize::try_from(ctx.tick).ok().and_then(|t| self.script.get(t)) else {
+            return Vec::new();
+        };
+        tick.iter()
+            .filter(|(source, _)| *source == ctx.entity_id)
+            .map(|(_, output)| output.clone())
+            .collect()
+    }
+}
+
+/// Records every command whose emitter was already destroyed.
+#[derive(Default)]
+struct CommandAudit {
+    violations: Mutex<Vec<(u64, EntityId)>>,
+}
+
+impl Resolver for CommandAudit {
+    fn handles(&self) -> &[OutputKind] {
+        &[OutputKind::Command]
+    }
+
+    fn resolve(&self, outputs: &[&OutputEnvelope], current: &Arena, _next: &mut Arena) {
+        let mut violations = self.violations.lock().expect("audit lock poisoned");
+        for envelope in outputs {
+            let source = envelope.source().entity_id();
+            if current.get(source).is_some_and(Entity::is_destroyed) {
+                violations.push((envelope.tick(), source));
+            }
+        }
+    }
+}
+
+fn check_hp(arena: &Arena) -> Result<(), TestCaseError> {
+    for entity in arena.entities_sorted() {
+        let combat = match entity.inner() {
+            EntityInner::Ship(c) => &c.combat,
+            EntityInner::Squadron(c) => &c.combat,
+            EntityInner::Platform(_) | EntityInner::Projectile(_) => continue,
+        };
+        prop_assert!(combat.hp.is_finite(), "{:?} hp is {}", entity.id(), combat.hp);
+        prop_assert!(
+            combat.hp <= combat.max_hp,
+            "{:?} hp {} exceeds max {}",
+            entity.id(),
+            combat.hp,
+            combat.max_hp
+        );
+    }
+    Ok(())
+}
+
+fn check_spatial(arena: &Arena) -> Result<(), TestCaseError> {
+    prop_assert_eq!(arena.spatial().len(), arena.entity_count());
+    for entity in arena.entities_sorted() {
+        let id = entity.id();
+        prop_assert_eq!(arena.spatial().get(id), get_position(arena, id), "{:?}", id);
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Properties
+// =============================================================================
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn resolver_invariants_hold_for_arbitrary_outputs((ships, interval, script) in arb_battle()) {
+        let ticks = script.len();
+        let mut sim = Simulation::new(7);
+        for spec in &ships {
+            spawn_ship(sim.arena_mut(), spec);
+        }
+        sim.set_action_interval(interval);
+        sim.plugins_mut().register(EntityTag::Ship, Arc::new(ScriptPlugin::new(script)));
+        let audit = Arc::new(CommandAudit::default());
+        sim.add_resolver(Box::new(Arc::clone(&audit)));
+
+        check_spatial(sim.arena())?;
+        for _ in 0..ticks {
+            sim.step();
+            check_hp(sim.arena())?;
+            check_spatial(sim.arena())?;
+        }
+        let violations = audit.violations.lock().expect("audit lock poisoned");
+        prop_assert!(violations.is_empty(), "destroyed entities commanded: {:?}", *violations);
+    }
+
+    #[test]
+    fn spatial_index_tracks_spawn_despawn_and_moves(
+        ops in prop::collection::vec(arb_arena_op(), 1..64),
+    ) {
+        let mut arena = Arena::new();
+        for op in ops {
+            let live: Vec<EntityId> = arena.entity_ids_sorted().collect();
+            match op {
+                ArenaOp::Spawn(position) => {
+                    spawn_test_ship(&mut arena, position);
+                }
+                ArenaOp::Despawn(index) if !live.is_empty() => {
+                    arena.despawn(live[index % live.len()]);
+                }
+                ArenaOp::Move(index, to) if !live.is_empty() => {
+                    let id = live[index % live.len()];
+                    if let Some(ship) = arena.get_mut(id).and_then(Entity::as_ship_mut) {
+                        ship.transform.position = to;
+                    }
+                    arena.update_spatial(id);
+                }
+                ArenaOp::Despawn(_) | ArenaOp::Move(..) => {}
+            }
+            check_spatial(&arena)?;
+        }
+    }
+}
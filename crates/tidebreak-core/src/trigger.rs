@@ -0,0 +1,408 @@
+//! Scenario triggers: conditions paired with actions.
+//!
+//! Dynamic scenarios (a reinforcement wave once the escort is sunk, an
+//! order to hold fire when a convoy enters a strait) are scripted as
+//! [`Trigger`]s held in the arena's [`TriggerBook`]. Each tick the
+//! [`TriggerResolver`](crate::resolver::TriggerResolver) checks, in the
+//! order they were added, the condition of every trigger that has not
+//! fired yet against the state at the start of the tick, and runs the
+//! actions of those that are met; their effects show in the state after
+//! the tick:
+//!
+//! - `SpawnWave` spawns ships
+//! - `ApplyStamp` queues a stamp for the host to write into the murk
+//!   universe (see [`TriggerBook::stamps_fired_at`])
+//! - `SetRulesOfEngagement` changes a team's [`RulesOfEngagement`]
+//! - `EndEpisode` marks the episode over
+//!
+//! Each trigger fires at most once. The triggers and their firing record
+//! live in the arena, so they are snapshotted, diffed and hashed with the
+//! rest of the state and a replay reproduces them exactly. Battle packages
+//! declare triggers by ship and team ID (see
+//! [`TriggerSpec`](crate::scenario::TriggerSpec)).
+//!
+//! # Example
+//!
+//! ```
+//! use glam::Vec2;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ShipComponents};
+//! use tidebreak_core::simulation::Simulation;
+//! use tidebreak_core::trigger::{Trigger, TriggerAction, TriggerCondition};
+//!
+//! let mut sim = Simulation::new(7);
+//! sim.arena_mut()
+//!     .spawn(EntityTag::Ship, EntityInner::Ship(ShipComponents::default()));
+//! let arrived = Trigger::new(
+//!     "convoy_arrived",
+//!     TriggerCondition::ZoneEntered {
+//!         center: Vec2::ZERO,
+//!         radius: 100.0,
+//!         team: None,
+//!     },
+//! )
+//! .with_action(TriggerAction::EndEpisode);
+//! sim.arena_mut().triggers_mut().add(arrived);
+//!
+//! sim.step();
+//! assert_eq!(sim.arena().triggers().episode_end(), Some(1));
+//! ```
+
+use std::collections::BTreeMap;
+use std::ops::RangeBounds;
+
+use glam::Vec2;
+use murk::{BlendOp, Field, FieldMod, Stamp, StampShape};
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::entity::components::CombatState;
+use crate::entity::{Entity, EntityId, EntityInner, EntityMetadata, ShipComponents, TeamId};
+use crate::orders::RulesOfEngagement;
+
+/// When a trigger fires.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TriggerCondition {
+    /// The entity is destroyed or no longer exists.
+    EntityDestroyed {
+        /// Entity watched
+        entity: EntityId,
+    },
+    /// A live ship or squadron is within `radius` of `center`.
+    ZoneEntered {
+        /// Center of the zone
+        center: Vec2,
+        /// Radius of the zone in meters
+        radius: f32,
+        /// Only count this team's entities, or any entity if `None`
+        team: Option<TeamId>,
+    },
+    /// The tick has been reached.
+    TickReached {
+        /// First tick the condition holds on
+        tick: u64,
+    },
+    /// The entity is alive with hit points below a fraction of its maximum.
+    HpBelow {
+        /// Entity watched
+        entity: EntityId,
+        /// Fraction of max hit points (0-1)
+        fraction: f32,
+    },
+}
+
+impl TriggerCondition {
+    /// Returns `true` if the condition holds in `arena`.
+    #[must_use]
+    pub fn is_met(&self, arena: &Arena) -> bool {
+        match *self {
+            Self::EntityDestroyed { entity } => arena.get(entity).is_none_or(Entity::is_destroyed),
+            Self::ZoneEntered {
+                center,
+                radius,
+                team,
+            } => arena.entities_sorted().any(|entity| {
+                team.is_none_or(|team| entity.team() == Some(team))
+                    && live_position(entity).is_some_and(|p| p.distance(center) <= radius)
+            }),
+            Self::TickReached { tick } => arena.current_tick() >= tick,
+            Self::HpBelow { entity, fraction } => arena
+                .get(entity)
+                .and_then(combat)
+                .is_some_and(|c| !c.is_destroyed() && c.hp < c.max_hp * fraction),
+        }
+    }
+}
+
+/// A ship spawned by a `SpawnWave` action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WaveShip {
+    /// Team the ship fights for
+    pub team: Option<TeamId>,
+    /// Components the ship spawns with
+    pub components: ShipComponents,
+    /// Cosmetic metadata the ship spawns with
+    #[serde(default)]
+    pub metadata: EntityMetadata,
+}
+
+/// A spherical stamp written into the murk universe by the host.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TriggerStamp {
+    /// Center of the stamp on the surface
+    pub center: Vec2,
+    /// Radius in meters
+    pub radius: f32,
+    /// Field modified
+    pub field: Field,
+    /// How the value is blended into the field
+    pub op: BlendOp,
+    /// Value blended in
+    pub value: f32,
+}
+
+impl TriggerStamp {
+    /// Returns the murk stamp, centered at altitude `z`.
+    #[must_use]
+    pub fn stamp(&self, z: f32) -> Stamp {
+        Stamp::new(
+            StampShape::sphere(self.center.extend(z), self.radius),
+            vec![FieldMod::new(self.field, self.op, self.value)],
+        )
+    }
+}
+
+/// What a trigger does when it fires.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TriggerAction {
+    /// Spawn ships.
+    SpawnWave {
+        /// Ships to spawn, in order
+        ships: Vec<WaveShip>,
+    },
+    /// Queue a stamp for the host to apply to the universe.
+    ApplyStamp {
+        /// Stamp to apply
+        stamp: TriggerStamp,
+    },
+    /// Change a team's rules of engagement.
+    SetRulesOfEngagement {
+        /// Team affected
+        team: TeamId,
+        /// New rules
+        roe: RulesOfEngagement,
+    },
+    /// Mark the episode over.
+    EndEpisode,
+}
+
+/// A condition and the actions run when it is first met.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trigger {
+    /// Name for logs and the host
+    pub name: String,
+    /// Condition firing the trigger
+    pub condition: TriggerCondition,
+    /// Actions run, in order, when the trigger fires
+    pub actions: Vec<TriggerAction>,
+}
+
+impl Trigger {
+    /// Creates a trigger with no actions.
+    #[must_use]
+    pub fn new(name: impl Into<String>, condition: TriggerCondition) -> Self {
+        Self {
+            name: name.into(),
+            condition,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Adds an action run when the trigger fires.
+    #[must_use]
+    pub fn with_action(mut self, action: TriggerAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+}
+
+/// Per-arena record of triggers and when they fired.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TriggerBook {
+    triggers: Vec<Trigger>,
+    /// Tick each fired trigger's effects first showed in, by index
+    fired: BTreeMap<usize, u64>,
+    episode_end: Option<u64>,
+}
+
+impl TriggerBook {
+    /// Creates an empty trigger book.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a trigger, returning its index.
+    pub fn add(&mut self, trigger: Trigger) -> usize {
+        self.triggers.push(trigger);
+        self.triggers.len() - 1
+    }
+
+    /// Returns the trigger at `index`.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&Trigger> {
+        self.triggers.get(index)
+    }
+
+    /// Iterates over the triggers in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &Trigger> {
+        self.triggers.iter()
+    }
+
+    /// Returns the number of triggers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.triggers.len()
+    }
+
+    /// Returns true if there are no triggers.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.triggers.is_empty()
+    }
+
+    /// Returns the tick the trigger at `index` fired in, if it has.
+    #[must_use]
+    pub fn fired(&self, index: usize) -> Option<u64> {
+        self.fired.get(&index).copied()
+    }
+
+    /// Indices of the triggers that have not fired yet.
+    pub fn pending(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.triggers.len()).filter(|index| !self.fired.contains_key(index))
+    }
+
+    /// Records that the trigger at `index` fired in `tick`.
+    pub fn mark_fired(&mut self, index: usize, tick: u64) {
+        self.fired.insert(index, tick);
+    }
+
+    /// Returns the tick an `EndEpisode` action first took effect in.
+    #[must_use]
+    pub const fn episode_end(&self) -> Option<u64> {
+        self.episode_end
+    }
+
+    /// Marks the episode over from `tick`, unless it already is.
+    pub fn end_episode(&mut self, tick: u64) {
+        self.episode_end.get_or_insert(tick);
+    }
+
+    /// Returns the stamps of the `ApplyStamp` actions that fired in `tick`,
+    /// centered at altitude `z`.
+    ///
+    /// Call after each step with the new tick to keep the universe in sync.
+    #[must_use]
+    pub fn stamps_fired_at(&self, tick: u64, z: f32) -> Vec<Stamp> {
+        self.stamps_fired_in(tick..=tick, z)
+    }
+
+    /// Returns the stamps of the `ApplyStamp` actions that fired in `tick`
+    /// or later, centered at altitude `z`, in trigger order.
+    ///
+    /// Hosts stepping several ticks between syncs (e.g. one decision of
+    /// [`step_decision`](crate::simulation::Simulation::step_decision)) call
+    /// this with the tick after the last one they synced.
+    #[must_use]
+    pub fn stamps_fired_since(&self, tick: u64, z: f32) -> Vec<Stamp> {
+        self.stamps_fired_in(tick.., z)
+    }
+
+    /// Returns the stamps of the `ApplyStamp` actions that fired in `ticks`.
+    fn stamps_fired_in(&self, ticks: impl RangeBounds<u64>, z: f32) -> Vec<Stamp> {
+        self.fired
+            .iter()
+            .filter(|(_, fired)| ticks.contains(*fired))
+            .flat_map(|(index, _)| &self.triggers[*index].actions)
+            .filter_map(|action| match action {
+                TriggerAction::ApplyStamp { stamp } => Some(stamp.stamp(z)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Returns the combat state of a ship or squadron.
+const fn combat(entity: &Entity) -> Option<&CombatState> {
+    match entity.inner() {
+        EntityInner::Ship(c) => Some(&c.combat),
+        EntityInner::Squadron(c) => Some(&c.combat),
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => None,
+    }
+}
+
+/// Returns the position of a ship or squadron that is not destroyed.
+fn live_position(entity: &Entity) -> Option<Vec2> {
+    let position = match entity.inner() {
+        EntityInner::Ship(c) => c.transform.position,
+        EntityInner::Squadron(c) => c.transform.position,
+        EntityInner::Platform(_) | EntityInner::Projectile(_) => return None,
+    };
+    (!entity.is_destroyed()).then_some(position)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::EntityTag;
+
+    #[test]
+    fn conditions_read_the_arena() {
+        let mut arena = Arena::new();
+        let ship = ShipComponents::at_position(Vec2::new(500.0, 0.0), 0.0);
+        let id = arena.spawn(EntityTag::Ship, EntityInner::Ship(ship));
+        arena.set_team(id, Some(TeamId::new(1)));
+
+        let zone = |team| TriggerCondition::ZoneEntered {
+            center: Vec2::new(450.0, 0.0),
+            radius: 100.0,
+            team,
+        };
+        assert!(zone(None).is_met(&arena));
+        assert!(zone(Some(TeamId::new(1))).is_met(&arena));
+        assert!(!zone(Some(TeamId::new(0))).is_met(&arena));
+
+        let hp = TriggerCondition::HpBelow {
+            entity: id,
+            fraction: 0.5,
+        };
+        assert!(!hp.is_met(&arena));
+        let combat = &mut arena.get_mut(id).unwrap().as_ship_mut().unwrap().combat;
+        combat.hp = combat.max_hp * 0.25;
+        assert!(hp.is_met(&arena));
+
+        let destroyed = TriggerCondition::EntityDestroyed { entity: id };
+        assert!(!destroyed.is_met(&arena));
+        arena.despawn(id);
+        assert!(destroyed.is_met(&arena));
+        assert!(!hp.is_met(&arena));
+
+        assert!(!TriggerCondition::TickReached { tick: 1 }.is_met(&arena));
+        arena.advance_tick();
+        assert!(TriggerCondition::TickReached { tick: 1 }.is_met(&arena));
+    }
+
+    #[test]
+    fn book_tracks_firing_and_stamps() {
+        let stamp = TriggerStamp {
+            center: Vec2::new(10.0, 20.0),
+            radius: 50.0,
+            field: Field::Smoke,
+            op: BlendOp::Max,
+            value: 0.8,
+        };
+        let mut book = TriggerBook::new();
+        let first = book.add(
+            Trigger::new("smoke", TriggerCondition::TickReached { tick: 0 })
+                .with_action(TriggerAction::ApplyStamp { stamp }),
+        );
+        let second = book.add(Trigger::new("end", TriggerCondition::TickReached { tick: 5 }));
+        assert_eq!(book.pending().collect::<Vec<_>>(), vec![first, second]);
+
+        book.mark_fired(first, 1);
+        assert_eq!(book.fired(first), Some(1));
+        assert_eq!(book.pending().collect::<Vec<_>>(), vec![second]);
+        assert_eq!(book.stamps_fired_at(1, 0.0).len(), 1);
+        assert!(book.stamps_fired_at(2, 0.0).is_empty());
+        assert_eq!(book.stamps_fired_since(0, 0.0).len(), 1);
+        assert_eq!(book.stamps_fired_since(1, 0.0).len(), 1);
+        assert!(book.stamps_fired_since(2, 0.0).is_empty());
+
+        book.end_episode(6);
+        book.end_episode(7);
+        assert_eq!(book.episode_end(), Some(6));
+    }
+}
@@ -0,0 +1,339 @@
+//! Live visualization feed (feature `viz`).
+//!
+//! Publishes simulation state to external viewers so battles can be rendered
+//! in real time by a separate process that does not link against this crate.
+//! Each published [`Frame`] is one JSON text message on a websocket served by
+//! [`VizServer`].
+//!
+//! ```no_run
+//! use tidebreak_core::simulation::Simulation;
+//! use tidebreak_core::viz::{Frame, VizServer};
+//!
+//! let mut sim = Simulation::new(42);
+//! let mut server = VizServer::bind("127.0.0.1:9001")?;
+//! for _ in 0..1000 {
+//!     sim.step();
+//!     server.publish(&Frame::capture(&sim));
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! # Schema
+//!
+//! The schema is versioned by [`SCHEMA_VERSION`], carried in every frame and
+//! advertised as the websocket subprotocol [`SUBPROTOCOL`]. Any change to the
+//! shape below bumps the version. Viewers should reject frames with a
+//! version they do not understand.
+//!
+//! ```text
+//! Frame {
+//!   "schema_version": u32,
+//!   "tick":           u64,            // tick the frame describes
+//!   "entities":       [EntityState],  // sorted by id
+//!   "events":         [EventRecord],  // events of the last step, in order
+//!   "fields":         [FieldSlice]    // optional murk field slices
+//! }
+//! EntityState {
+//!   "id": u64, "tag": "Ship" | "Platform" | "Projectile" | "Squadron",
+//!   "team": u32 | null, "x": f32, "y": f32, "heading": f32 (radians),
+//!   "vx": f32 | null, "vy": f32 | null,          // null for platforms
+//!   "hp": f32 | null, "max_hp": f32 | null,      // ships and squadrons
+//!   "name": str,                                 // `Entity::display_name`
+//!   "color": [u8, u8, u8] | null                 // RGB, from the metadata
+//! }
+//! EventRecord {
+//!   "trace_id": u64,
+//!   "event": { "<Variant>": { ...fields } }      // serde form of `Event`
+//! }
+//! FieldSlice {
+//!   "field": "Temperature" | ...,  "z": f32,
+//!   "origin_x": f32, "origin_y": f32, "cell_size": f32,
+//!   "width": u32, "height": u32,
+//!   "values": [f32]                // row-major, width * height
+//! }
+//! ```
+//!
+//! Only websocket transport is provided; a gRPC service can be layered on
+//! the same [`Frame`] type where protobuf tooling is available.
+
+mod ws;
+
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::entity::{Entity, EntityInner, EntityTag, TeamId};
+use crate::output::{Event, OutputEnvelope};
+use crate::simulation::Simulation;
+
+pub use ws::VizServer;
+
+/// Version of the frame schema.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Websocket subprotocol naming the schema version.
+pub const SUBPROTOCOL: &str = "tidebreak.v2";
+
+// =============================================================================
+// Frame
+// =============================================================================
+
+/// One snapshot of simulation state for viewers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// Schema version ([`SCHEMA_VERSION`]).
+    pub schema_version: u32,
+    /// Tick the frame describes.
+    pub tick: u64,
+    /// Entity summaries, sorted by ID.
+    pub entities: Vec<EntityState>,
+    /// Events of the most recent step.
+    pub events: Vec<EventRecord>,
+    /// Sampled murk field slices.
+    pub fields: Vec<FieldSlice>,
+}
+
+impl Frame {
+    /// Captures entities and the last step's events from `sim`.
+    #[must_use]
+    pub fn capture(sim: &Simulation) -> Self {
+        let arena = sim.arena();
+        Self {
+            schema_version: SCHEMA_VERSION,
+            tick: arena.current_tick(),
+            entities: arena.entities_sorted().map(EntityState::from).collect(),
+            events: sim
+                .take_events()
+                .iter()
+                .filter_map(EventRecord::from_envelope)
+                .collect(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds a field slice to the frame.
+    #[must_use]
+    pub fn with_field(mut self, slice: FieldSlice) -> Self {
+        self.fields.push(slice);
+        self
+    }
+
+    /// Serializes the frame to its JSON wire form.
+    ///
+    /// # Panics
+    ///
+    /// Never in practice: every frame type serializes to JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("frames serialize to JSON")
+    }
+}
+
+/// Compact entity summary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityState {
+    /// Entity ID.
+    pub id: u64,
+    /// Entity type.
+    pub tag: EntityTag,
+    /// Team, if any.
+    pub team: Option<u32>,
+    /// X position.
+    pub x: f32,
+    /// Y position.
+    pub y: f32,
+    /// Heading in radians.
+    pub heading: f32,
+    /// X velocity (entities with physics).
+    pub vx: Option<f32>,
+    /// Y velocity (entities with physics).
+    pub vy: Option<f32>,
+    /// Current HP (entities with combat state).
+    pub hp: Option<f32>,
+    /// Maximum HP (entities with combat state).
+    pub max_hp: Option<f32>,
+    /// Display name.
+    pub name: String,
+    /// Display color as RGB, if any.
+    pub color: Option<[u8; 3]>,
+}
+
+impl From<&Entity> for EntityState {
+    fn from(entity: &Entity) -> Self {
+        let (transform, physics, combat) = match entity.inner() {
+            EntityInner::Ship(c) => (&c.transform, Some(&c.physics), Some(&c.combat)),
+            EntityInner::Platform(c) => (&c.transform, None, None),
+            EntityInner::Projectile(c) => (&c.transform, Some(&c.physics), None),
+            EntityInner::Squadron(c) => (&c.transform, Some(&c.physics), Some(&c.combat)),
+        };
+        Self {
+            id: entity.id().as_u64(),
+            tag: entity.tag(),
+            team: entity.team().map(TeamId::as_u32),
+            x: transform.position.x,
+            y: transform.position.y,
+            heading: transform.heading,
+            vx: physics.map(|p| p.velocity.x),
+            vy: physics.map(|p| p.velocity.y),
+            hp: combat.map(|c| c.hp),
+            max_hp: combat.map(|c| c.max_hp),
+            name: entity.display_name(),
+            color: entity.metadata().color,
+        }
+    }
+}
+
+/// An event with its causal trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventRecord {
+    /// Causal trace ID.
+    pub trace_id: u64,
+    /// The event.
+    pub event: Event,
+}
+
+impl EventRecord {
+    /// Extracts the event from an output envelope, if it carries one.
+    #[must_use]
+    pub fn from_envelope(envelope: &OutputEnvelope) -> Option<Self> {
+        envelope.output().as_event().map(|event| Self {
+            trace_id: envelope.trace_id().as_u64(),
+            event: event.clone(),
+        })
+    }
+}
+
+/// Horizontal grid of samples of one murk field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldSlice {
+    /// Sampled field.
+    pub field: murk::Field,
+    /// Altitude of the slice.
+    pub z: f32,
+    /// X of the first cell's center.
+    pub origin_x: f32,
+    /// Y of the first cell's center.
+    pub origin_y: f32,
+    /// Distance between cell centers.
+    pub cell_size: f32,
+    /// Cells per row.
+    pub width: u32,
+    /// Number of rows.
+    pub height: u32,
+    /// Row-major samples (`width * height`).
+    pub values: Vec<f32>,
+}
+
+impl FieldSlice {
+    /// Samples `field` on a `width` x `height` grid at altitude `z`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn sample(
+        universe: &murk::Universe,
+        field: murk::Field,
+        z: f32,
+        origin: Vec2,
+        cell_size: f32,
+        (width, height): (u32, u32),
+    ) -> Self {
+        let mut values = Vec::with_capacity(width as usize * height as usize);
+        for row in 0..height {
+            for col in 0..width {
+                let x = origin.x + col as f32 * cell_size;
+                let y = origin.y + row as f32 * cell_size;
+                values.push(universe.query_point(Vec3::new(x, y, z)).get(field));
+            }
+        }
+        Self {
+            field,
+            z,
+            origin_x: origin.x,
+            origin_y: origin.y,
+            cell_size,
+            width,
+            height,
+            values,
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{PlatformComponents, ShipComponents};
+
+    mod frame_tests {
+        use super::*;
+
+        #[test]
+        fn capture_lists_entities_in_id_order() {
+            let mut sim = Simulation::new(1);
+            let ship = sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let platform = sim.arena_mut().spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(PlatformComponents::at_position(Vec2::new(5.0, 6.0))),
+            );
+            sim.step();
+
+            let frame = Frame::capture(&sim);
+
+            assert_eq!(frame.schema_version, SCHEMA_VERSION);
+            assert_eq!(frame.tick, 1);
+            let ids: Vec<_> = frame.entities.iter().map(|e| e.id).collect();
+            assert_eq!(ids, vec![ship.as_u64(), platform.as_u64()]);
+            assert!(frame.entities[0].hp.is_some());
+            assert_eq!(frame.entities[1].vx, None);
+            assert_eq!(frame.entities[1].x.to_bits(), 5.0_f32.to_bits());
+            assert_eq!(frame.entities[1].name, format!("Platform {platform}"));
+        }
+
+        #[test]
+        fn json_carries_schema_version() {
+            let frame = Frame::capture(&Simulation::new(1));
+            let json: serde_json::Value = serde_json::from_str(&frame.to_json()).unwrap();
+
+            assert_eq!(json["schema_version"], SCHEMA_VERSION);
+            assert!(json["entities"].as_array().unwrap().is_empty());
+        }
+
+        #[test]
+        fn json_round_trips() {
+            let mut sim = Simulation::new(1);
+            sim.arena_mut().spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let frame = Frame::capture(&sim);
+
+            let parsed: Frame = serde_json::from_str(&frame.to_json()).unwrap();
+
+            assert_eq!(parsed, frame);
+        }
+    }
+
+    mod field_slice_tests {
+        use super::*;
+
+        #[test]
+        fn sample_is_row_major() {
+            let universe = murk::Universe::new(murk::UniverseConfig::default());
+            let slice = FieldSlice::sample(
+                &universe,
+                murk::Field::Temperature,
+                10.0,
+                Vec2::new(1.0, 2.0),
+                4.0,
+                (3, 2),
+            );
+
+            assert_eq!(slice.values.len(), 6);
+            assert_eq!((slice.width, slice.height), (3, 2));
+        }
+    }
+}
@@ -0,0 +1,445 @@
+//! Minimal websocket (RFC 6455) broadcast server.
+//!
+//! The server never blocks the simulation on the network for long: the
+//! listener is non-blocking and pending connections are accepted (and
+//! handshaken) at the start of each [`VizServer::publish`]. Frames are sent
+//! as unmasked text messages; a client whose write fails or times out is
+//! dropped. Messages from clients are ignored.
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use super::{Frame, SUBPROTOCOL};
+
+/// GUID appended to the client key when computing the accept key.
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// Opcode of a text message.
+const OPCODE_TEXT: u8 = 0x1;
+/// Largest accepted handshake request, in bytes.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+/// Default timeout for the handshake and for each message write.
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Websocket server broadcasting [`Frame`]s to every connected viewer.
+#[derive(Debug)]
+pub struct VizServer {
+    listener: TcpListener,
+    clients: Vec<TcpStream>,
+    timeout: Duration,
+}
+
+impl VizServer {
+    /// Listens on `addr` (use port 0 for an ephemeral port).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be bound.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            clients: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// Sets the handshake and write timeout.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the bound address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket address cannot be queried.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Returns the number of connected viewers.
+    #[must_use]
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Accepts pending viewers and sends `frame` to all of them.
+    ///
+    /// Returns the number of viewers the frame was delivered to.
+    pub fn publish(&mut self, frame: &Frame) -> usize {
+        self.accept_pending();
+        if self.clients.is_empty() {
+            return 0;
+        }
+        let message = encode_text(frame.to_json().as_bytes());
+        self.clients
+            .retain_mut(|client| client.write_all(&message).is_ok());
+        self.clients.len()
+    }
+
+    /// Accepts and handshakes every queued connection.
+    fn accept_pending(&mut self) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if let Ok(stream) = handshake(stream, self.timeout) {
+                self.clients.push(stream);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Handshake
+// =============================================================================
+
+/// Performs the server side of the opening handshake.
+fn handshake(stream: TcpStream, timeout: Duration) -> io::Result<TcpStream> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    stream.set_nodelay(true)?;
+
+    let mut reader = BufReader::new(stream);
+    let mut key = None;
+    let mut upgrade = false;
+    let mut protocol = false;
+    let mut read = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        read += reader.read_line(&mut line)?;
+        if read > MAX_REQUEST_BYTES || line.is_empty() {
+            return Err(io::ErrorKind::InvalidData.into());
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "upgrade" => upgrade = value.eq_ignore_ascii_case("websocket"),
+            "sec-websocket-key" => key = Some(value.to_string()),
+            "sec-websocket-protocol" => {
+                protocol = value.split(',').any(|p| p.trim() == SUBPROTOCOL);
+            }
+            _ => {}
+        }
+    }
+
+    let mut stream = reader.into_inner();
+    let Some(key) = key.filter(|_| upgrade) else {
+        stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n")?;
+        return Err(io::ErrorKind::InvalidData.into());
+    };
+    let mut response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n",
+        accept_key(&key)
+    );
+    if protocol {
+        let _ = write!(response, "Sec-WebSocket-Protocol: {SUBPROTOCOL}\r\n");
+    }
+    response.push_str("\r\n");
+    stream.write_all(response.as_bytes())?;
+    Ok(stream)
+}
+
+/// Computes `Sec-WebSocket-Accept` for a client key.
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{key}{WEBSOCKET_GUID}").as_bytes()))
+}
+
+/// Encodes an unmasked, unfragmented text message.
+#[allow(clippy::cast_possible_truncation)]
+fn encode_text(payload: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(payload.len() + 10);
+    message.push(0x80 | OPCODE_TEXT);
+    match payload.len() {
+        len @ 0..=125 => message.push(len as u8),
+        len @ 126..=0xFFFF => {
+            message.push(126);
+            message.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            message.push(127);
+            message.extend((len as u64).to_be_bytes());
+        }
+    }
+    message.extend_from_slice(payload);
+    message
+}
+
+// =============================================================================
+// SHA-1 / Base64
+// =============================================================================
+
+/// SHA-1 digest (only used for the handshake, not for security).
+#[allow(clippy::many_single_char_names)]
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [
+        0x6745_2301,
+        0xEFCD_AB89,
+        0x98BA_DCFE,
+        0x1032_5476,
+        0xC3D2_E1F0,
+    ];
+
+    let bit_len = (data.len() as u64).wrapping_mul(8);
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend(bit_len.to_be_bytes());
+
+    for block in padded.chunks_exact(64) {
+        let mut w = [0_u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+                _ => (b ^ c ^ d, 0xCA62_C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0; 20];
+    for (chunk, word) in digest.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// Standard (padded) base64 encoding.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |n, (i, byte)| n | u32::from(*byte) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3F]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::Simulation;
+    use std::io::Read;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().fold(String::new(), |mut s, b| {
+            let _ = write!(s, "{b:02x}");
+            s
+        })
+    }
+
+    mod encoding_tests {
+        use super::*;
+
+        #[test]
+        fn sha1_known_vectors() {
+            assert_eq!(hex(&sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+            assert_eq!(
+                hex(&sha1(b"abc")),
+                "a9993e364706816aba3e25717850c26c9cd0d89d"
+            );
+            let long = [b'a'; 1000];
+            assert_eq!(
+                hex(&sha1(&long)),
+                "291e9a6c66994949b57ba5e650361e98fc36b1ba"
+            );
+        }
+
+        #[test]
+        fn base64_pads() {
+            assert_eq!(base64(b""), "");
+            assert_eq!(base64(b"f"), "Zg==");
+            assert_eq!(base64(b"fo"), "Zm8=");
+            assert_eq!(base64(b"foo"), "Zm9v");
+            assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        }
+
+        #[test]
+        fn accept_key_matches_rfc_example() {
+            assert_eq!(
+                accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+                "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+            );
+        }
+
+        #[test]
+        fn text_frame_lengths() {
+            assert_eq!(encode_text(b"hi"), vec![0x81, 2, b'h', b'i']);
+            let medium = encode_text(&[b'x'; 300]);
+            assert_eq!(medium[..4], [0x81, 126, 0x01, 0x2C]);
+            let large = encode_text(&vec![b'x'; 70_000]);
+            assert_eq!(large[1], 127);
+            assert_eq!(large[2..10], 70_000_u64.to_be_bytes());
+        }
+    }
+
+    mod server_tests {
+        use super::*;
+
+        /// Connects and handshakes, returning the stream and response head.
+        fn connect(server: &mut VizServer, protocol: Option<&str>) -> (TcpStream, String) {
+            let mut client = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+            client
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            let mut request = String::from(
+                "GET / HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\n\
+                 Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+                 Sec-WebSocket-Version: 13\r\n",
+            );
+            if let Some(protocol) = protocol {
+                let _ = write!(request, "Sec-WebSocket-Protocol: {protocol}\r\n");
+            }
+            request.push_str("\r\n");
+            client.write_all(request.as_bytes()).unwrap();
+
+            // The connection is accepted on the next publish.
+            server.publish(&Frame::capture(&Simulation::new(1)));
+
+            // Byte-wise, so no message bytes are buffered past the head.
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                let mut byte = [0];
+                client.read_exact(&mut byte).unwrap();
+                head.push(byte[0]);
+            }
+            (client, String::from_utf8(head).unwrap())
+        }
+
+        /// Reads one unmasked text message.
+        fn read_text(stream: &mut TcpStream) -> String {
+            let mut header = [0; 2];
+            stream.read_exact(&mut header).unwrap();
+            assert_eq!(header[0], 0x81);
+            let len = match header[1] {
+                126 => {
+                    let mut ext = [0; 2];
+                    stream.read_exact(&mut ext).unwrap();
+                    usize::from(u16::from_be_bytes(ext))
+                }
+                127 => {
+                    let mut ext = [0; 8];
+                    stream.read_exact(&mut ext).unwrap();
+                    usize::try_from(u64::from_be_bytes(ext)).unwrap()
+                }
+                len => usize::from(len),
+            };
+            let mut payload = vec![0; len];
+            stream.read_exact(&mut payload).unwrap();
+            String::from_utf8(payload).unwrap()
+        }
+
+        #[test]
+        fn handshake_and_receive_frame() {
+            let mut server = VizServer::bind("127.0.0.1:0").unwrap();
+            let (mut client, head) = connect(&mut server, Some(SUBPROTOCOL));
+
+            assert!(head.starts_with("HTTP/1.1 101"));
+            assert!(head.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+            assert!(head.contains(&format!("Sec-WebSocket-Protocol: {SUBPROTOCOL}")));
+            assert_eq!(server.client_count(), 1);
+
+            // Skip the frame sent during the connecting publish.
+            read_text(&mut client);
+            let mut sim = Simulation::new(3);
+            sim.step();
+            assert_eq!(server.publish(&Frame::capture(&sim)), 1);
+
+            let frame: Frame = serde_json::from_str(&read_text(&mut client)).unwrap();
+            assert_eq!(frame.tick, 1);
+        }
+
+        #[test]
+        fn unknown_subprotocol_is_not_echoed() {
+            let mut server = VizServer::bind("127.0.0.1:0").unwrap();
+            let (_client, head) = connect(&mut server, Some("other"));
+
+            assert!(head.starts_with("HTTP/1.1 101"));
+            assert!(!head.contains("Sec-WebSocket-Protocol"));
+        }
+
+        #[test]
+        fn non_websocket_request_is_rejected() {
+            let mut server = VizServer::bind("127.0.0.1:0").unwrap();
+            let mut client = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+            client
+                .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap();
+
+            server.publish(&Frame::capture(&Simulation::new(1)));
+
+            let mut response = String::new();
+            client.read_to_string(&mut response).unwrap();
+            assert!(response.starts_with("HTTP/1.1 400"));
+            assert_eq!(server.client_count(), 0);
+        }
+
+        #[test]
+        fn closed_clients_are_dropped() {
+            let mut server = VizServer::bind("127.0.0.1:0").unwrap();
+            let (client, _) = connect(&mut server, None);
+            drop(client);
+
+            let frame = Frame::capture(&Simulation::new(1));
+            // The first write after a peer close may still succeed.
+            for _ in 0..10 {
+                if server.publish(&frame) == 0 {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            assert_eq!(server.client_count(), 0);
+        }
+    }
+}
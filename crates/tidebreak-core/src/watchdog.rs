@@ -0,0 +1,270 @@
+//! Per-plugin execution timing and budget enforcement.
+//!
+//! Every plugin run is timed per plugin instance (entity + plugin). With a
+//! [`PluginBudget`] configured, a run that takes longer than the budget counts
+//! as an overrun; after `max_overruns` overruns the instance is disabled and
+//! a `PluginBudgetExceeded` event is emitted. Disabled instances are skipped
+//! in later ticks until re-enabled, so one misbehaving (e.g. third-party)
+//! plugin cannot keep stalling the tick.
+//!
+//! # Limitations
+//!
+//! Plugins run to completion; a plugin that never returns is not preempted.
+//! Budgets measure wall-clock time, so enabling one gives up determinism: a
+//! run that disables a plugin is only reproducible if the plugin overruns the
+//! same way again. Replay recording and lockstep networking therefore refuse
+//! a simulation whose watchdog [`is_active`](PluginWatchdog::is_active).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::arena::Arena;
+use crate::output::PluginInstanceId;
+
+/// Execution budget for a single plugin run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginBudget {
+    /// Maximum wall-clock time of one run.
+    pub limit: Duration,
+    /// Overruns after which the plugin instance is disabled.
+    pub max_overruns: u32,
+}
+
+impl PluginBudget {
+    /// Creates a budget of `limit` per run, disabling after `max_overruns`.
+    #[must_use]
+    pub const fn new(limit: Duration, max_overruns: u32) -> Self {
+        Self {
+            limit,
+            max_overruns,
+        }
+    }
+}
+
+/// Timing statistics of one plugin instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PluginTiming {
+    /// Duration of the most recent run.
+    pub last: Duration,
+    /// Longest run.
+    pub max: Duration,
+    /// Total time over all runs.
+    pub total: Duration,
+    /// Number of runs.
+    pub runs: u64,
+    /// Runs that exceeded the budget.
+    pub overruns: u32,
+}
+
+/// Timing and budget state of all plugin instances.
+#[derive(Debug, Clone, Default)]
+pub struct PluginWatchdog {
+    budget: Option<PluginBudget>,
+    timings: BTreeMap<PluginInstanceId, PluginTiming>,
+    disabled: BTreeSet<PluginInstanceId>,
+}
+
+impl PluginWatchdog {
+    /// Creates a watchdog enforcing `budget`, or only timing if `None`.
+    #[must_use]
+    pub fn new(budget: Option<PluginBudget>) -> Self {
+        Self {
+            budget,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the budget, if any.
+    #[must_use]
+    pub const fn budget(&self) -> Option<&PluginBudget> {
+        self.budget.as_ref()
+    }
+
+    /// Returns `true` if wall-clock timing can affect the run: a budget is
+    /// set or an instance is disabled.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.budget.is_some() || !self.disabled.is_empty()
+    }
+
+    /// Sets (or removes) the budget. Overrun counts are kept.
+    pub fn set_budget(&mut self, budget: Option<PluginBudget>) {
+        self.budget = budget;
+    }
+
+    /// Returns the timing of a plugin instance.
+    #[must_use]
+    pub fn timing(&self, instance: &PluginInstanceId) -> Option<&PluginTiming> {
+        self.timings.get(instance)
+    }
+
+    /// Returns all timings, ordered by (entity, plugin).
+    pub fn timings(&self) -> impl Iterator<Item = (&PluginInstanceId, &PluginTiming)> + '_ {
+        self.timings.iter()
+    }
+
+    /// Returns true if the instance has been disabled.
+    #[must_use]
+    pub fn is_disabled(&self, instance: &PluginInstanceId) -> bool {
+        self.disabled.contains(instance)
+    }
+
+    /// Returns the disabled instances, ordered by (entity, plugin).
+    pub fn disabled(&self) -> impl Iterator<Item = &PluginInstanceId> + '_ {
+        self.disabled.iter()
+    }
+
+    /// Re-enables a disabled instance and resets its overrun count.
+    ///
+    /// Returns true if the instance was disabled.
+    pub fn enable(&mut self, instance: &PluginInstanceId) -> bool {
+        if let Some(timing) = self.timings.get_mut(instance) {
+            timing.overruns = 0;
+        }
+        self.disabled.remove(instance)
+    }
+
+    /// Records one run of `instance`.
+    ///
+    /// Returns the overrun count if this run disabled the instance.
+    pub fn record(&mut self, instance: &PluginInstanceId, elapsed: Duration) -> Option<u32> {
+        let timing = self.timings.entry(instance.clone()).or_default();
+        timing.last = elapsed;
+        timing.max = timing.max.max(elapsed);
+        timing.total += elapsed;
+        timing.runs += 1;
+
+        let budget = self.budget?;
+        if elapsed <= budget.limit {
+            return None;
+        }
+        timing.overruns += 1;
+        if timing.overruns < budget.max_overruns || self.disabled.contains(instance) {
+            return None;
+        }
+        self.disabled.insert(instance.clone());
+        Some(timing.overruns)
+    }
+
+    /// Drops the state of instances whose entity no longer exists.
+    pub fn retain_live(&mut self, arena: &Arena) {
+        self.timings
+            .retain(|instance, _| arena.get(instance.entity_id()).is_some());
+        self.disabled
+            .retain(|instance| arena.get(instance.entity_id()).is_some());
+    }
+
+    /// Clears all timings and re-enables every instance.
+    pub fn reset(&mut self) {
+        self.timings.clear();
+        self.disabled.clear();
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::{EntityId, EntityInner, EntityTag, ShipComponents};
+    use crate::output::PluginId;
+
+    fn instance(entity: u64) -> PluginInstanceId {
+        PluginInstanceId::new(EntityId::new(entity), PluginId::new("slow"))
+    }
+
+    fn budget() -> PluginBudget {
+        PluginBudget::new(Duration::from_millis(5), 2)
+    }
+
+    mod timing_tests {
+        use super::*;
+
+        #[test]
+        fn records_timing_without_budget() {
+            let mut watchdog = PluginWatchdog::new(None);
+
+            assert_eq!(
+                watchdog.record(&instance(0), Duration::from_millis(3)),
+                None
+            );
+            assert_eq!(
+                watchdog.record(&instance(0), Duration::from_millis(1)),
+                None
+            );
+
+            let timing = watchdog.timing(&instance(0)).unwrap();
+            assert_eq!(timing.runs, 2);
+            assert_eq!(timing.last, Duration::from_millis(1));
+            assert_eq!(timing.max, Duration::from_millis(3));
+            assert_eq!(timing.total, Duration::from_millis(4));
+            assert_eq!(timing.overruns, 0);
+        }
+
+        #[test]
+        fn retain_live_drops_despawned_entities() {
+            let mut arena = Arena::new();
+            let live = arena.spawn(
+                EntityTag::Ship,
+                EntityInner::Ship(ShipComponents::default()),
+            );
+            let mut watchdog = PluginWatchdog::new(Some(budget()));
+            watchdog.record(&instance(live.as_u64()), Duration::ZERO);
+            watchdog.record(&instance(99), Duration::from_secs(1));
+            watchdog.record(&instance(99), Duration::from_secs(1));
+
+            watchdog.retain_live(&arena);
+
+            assert_eq!(watchdog.timings().count(), 1);
+            assert_eq!(watchdog.disabled().count(), 0);
+        }
+    }
+
+    mod budget_tests {
+        use super::*;
+
+        #[test]
+        fn disables_after_max_overruns() {
+            let mut watchdog = PluginWatchdog::new(Some(budget()));
+            let slow = Duration::from_millis(10);
+
+            assert_eq!(watchdog.record(&instance(0), slow), None);
+            assert!(!watchdog.is_disabled(&instance(0)));
+            assert_eq!(watchdog.record(&instance(0), slow), Some(2));
+            assert!(watchdog.is_disabled(&instance(0)));
+            assert!(!watchdog.is_disabled(&instance(1)));
+        }
+
+        #[test]
+        fn runs_within_budget_are_not_overruns() {
+            let mut watchdog = PluginWatchdog::new(Some(budget()));
+
+            for _ in 0..10 {
+                assert_eq!(
+                    watchdog.record(&instance(0), Duration::from_millis(5)),
+                    None
+                );
+            }
+
+            assert_eq!(watchdog.timing(&instance(0)).unwrap().overruns, 0);
+        }
+
+        #[test]
+        fn enable_resets_overruns() {
+            let mut watchdog = PluginWatchdog::new(Some(budget()));
+            let slow = Duration::from_millis(10);
+            watchdog.record(&instance(0), slow);
+            watchdog.record(&instance(0), slow);
+
+            assert!(watchdog.enable(&instance(0)));
+
+            assert!(!watchdog.is_disabled(&instance(0)));
+            assert_eq!(watchdog.timing(&instance(0)).unwrap().overruns, 0);
+            assert!(!watchdog.enable(&instance(0)));
+        }
+    }
+}
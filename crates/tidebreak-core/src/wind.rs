@@ -0,0 +1,93 @@
+//! Surface wind drifting smoke and lightweight entities.
+//!
+//! Once a [`Wind`] is installed with [`Arena::set_wind`]:
+//!
+//! - The `SmokeResolver` carries every smoke cloud laid by ships downwind
+//!   at the full wind speed.
+//! - The `PhysicsResolver` carries entities given a windage coefficient
+//!   with [`Arena::set_windage`] downwind at that fraction of the wind
+//!   speed, on top of any sea-current drift. Liferafts, decoys and other
+//!   light floating objects are blown about; entities without a
+//!   coefficient, like hulls under way, are not.
+//!
+//! Like currents, wind moves entities over the ground without changing
+//! their velocity through the water. The wind is uniform over the arena;
+//! scenarios set it from their starting weather.
+//!
+//! ```
+//! use glam::Vec2;
+//! use tidebreak_core::entity::{EntityInner, EntityTag, ProjectileComponents};
+//! use tidebreak_core::simulation::Simulation;
+//! use tidebreak_core::wind::Wind;
+//!
+//! let mut sim = Simulation::new(42);
+//! let arena = sim.arena_mut();
+//! // 30 knots blowing toward +X
+//! arena.set_wind(Some(Wind::new(15.4, 0.0)));
+//! let decoy = arena.spawn(
+//!     EntityTag::Projectile,
+//!     EntityInner::Projectile(ProjectileComponents::default()),
+//! );
+//! arena.set_windage(decoy, Wind::DECOY_WINDAGE);
+//! sim.step();
+//! ```
+//!
+//! [`Arena::set_wind`]: crate::arena::Arena::set_wind
+//! [`Arena::set_windage`]: crate::arena::Arena::set_windage
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Uniform surface wind.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Wind {
+    /// Air velocity in m/s, pointing where the wind blows toward
+    pub velocity: Vec2,
+}
+
+impl Wind {
+    /// Windage of a liferaft: the fraction of the wind speed it drifts at.
+    pub const LIFERAFT_WINDAGE: f32 = 0.04;
+    /// Windage of a floating decoy.
+    pub const DECOY_WINDAGE: f32 = 0.08;
+
+    /// Creates a wind of `speed` m/s blowing toward `direction` (radians,
+    /// like a heading).
+    #[must_use]
+    pub fn new(speed: f32, direction: f32) -> Self {
+        Self {
+            velocity: Vec2::from_angle(direction) * speed,
+        }
+    }
+
+    /// Creates a wind with the given air velocity.
+    #[must_use]
+    pub const fn from_velocity(velocity: Vec2) -> Self {
+        Self { velocity }
+    }
+
+    /// Returns the drift velocity of an entity with the given windage.
+    #[must_use]
+    pub fn drift(&self, windage: f32) -> Vec2 {
+        self.velocity * windage
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn direction_follows_the_heading_convention() {
+        let wind = Wind::new(10.0, FRAC_PI_2);
+        assert!(wind.velocity.x.abs() < 1e-5);
+        assert!((wind.velocity.y - 10.0).abs() < 1e-5);
+        assert_eq!(Wind::from_velocity(Vec2::X).drift(0.5), Vec2::new(0.5, 0.0));
+    }
+}
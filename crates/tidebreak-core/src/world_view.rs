@@ -44,6 +44,8 @@
 //!     required_tags: vec![EntityTag::Ship],
 //!     reads: vec![ComponentKind::Transform],
 //!     emits: vec![OutputKind::Command],
+//!     runs_after: vec![],
+//!     every_tick: false,
 //! };
 //!
 //! // Create a scoped WorldView
@@ -62,9 +64,15 @@ use glam::Vec2;
 
 use crate::arena::Arena;
 use crate::entity::components::{
-    CombatState, InventoryState, PhysicsState, SensorState, TransformState,
+    CombatState, InventoryState, PhysicsState, SensorState, SignatureState, SubmarineState,
+    TransformState,
 };
-use crate::entity::{Entity, EntityId, EntityInner, EntityTag};
+use crate::entity::{
+    AttributeValue, Attributes, Entity, EntityId, EntityInner, EntityTag, ShipComponents,
+};
+use crate::environment::Environment;
+use crate::orders::StandingOrder;
+use crate::output::PluginId;
 use crate::plugin::{ComponentKind, PluginDeclaration};
 
 // =============================================================================
@@ -145,6 +153,8 @@ impl<'a> WorldView<'a> {
             ComponentKind::Combat,
             ComponentKind::Sensor,
             ComponentKind::Inventory,
+            ComponentKind::Signature,
+            ComponentKind::Submarine,
         ];
 
         Self {
@@ -160,6 +170,14 @@ impl<'a> WorldView<'a> {
         self.tick
     }
 
+    /// Returns the time of day, sea state and smoke.
+    ///
+    /// Always allowed: conditions are shared by every entity.
+    #[must_use]
+    pub const fn environment(&self) -> &'a Environment {
+        self.arena.environment()
+    }
+
     /// Returns a reference to an entity by ID.
     ///
     /// Entity access is always allowed - plugins may need to inspect entity
@@ -282,6 +300,109 @@ impl<'a> WorldView<'a> {
         Self::extract_inventory(entity)
     }
 
+    /// Gets the attribute store for an entity.
+    ///
+    /// # Access Control
+    ///
+    /// Requires `ComponentKind::Attributes` in the plugin declaration.
+    /// Panics in debug builds if access is denied.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The entity ID to look up
+    ///
+    /// # Returns
+    ///
+    /// The attribute store if the entity exists (possibly empty).
+    #[must_use]
+    pub fn get_attributes(&self, id: EntityId) -> Option<&'a Attributes> {
+        self.check_access(ComponentKind::Attributes)?;
+        self.arena.get(id).map(Entity::attributes)
+    }
+
+    /// Gets a single attribute of an entity.
+    ///
+    /// # Access Control
+    ///
+    /// Requires `ComponentKind::Attributes` in the plugin declaration.
+    /// Panics in debug builds if access is denied.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The entity ID to look up
+    /// * `key` - The attribute key
+    ///
+    /// # Returns
+    ///
+    /// The attribute value if the entity exists and has the key.
+    #[must_use]
+    pub fn get_attribute(&self, id: EntityId, key: &str) -> Option<&'a AttributeValue> {
+        self.get_attributes(id)?.get(key)
+    }
+
+    /// Returns an entity's signature for its current speed, damage and
+    /// emissions mode.
+    ///
+    /// # Access Control
+    ///
+    /// Requires `ComponentKind::Signature` in the plugin declaration.
+    /// Panics in debug builds if access is denied.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The entity ID to look up
+    ///
+    /// # Returns
+    ///
+    /// The current signature if the entity exists and is a ship; other
+    /// entity types have no signature model.
+    #[must_use]
+    pub fn current_signature(&self, id: EntityId) -> Option<SignatureState> {
+        self.check_access(ComponentKind::Signature)?;
+        let entity = self.arena.get(id)?;
+        entity.as_ship().map(ShipComponents::current_signature)
+    }
+
+    /// Gets the diving state of a submarine.
+    ///
+    /// # Access Control
+    ///
+    /// Requires `ComponentKind::Submarine` in the plugin declaration.
+    /// Panics in debug builds if access is denied.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The entity ID to look up
+    ///
+    /// # Returns
+    ///
+    /// The diving state if the entity exists and is a submarine.
+    #[must_use]
+    pub fn get_submarine(&self, id: EntityId) -> Option<&'a SubmarineState> {
+        self.check_access(ComponentKind::Submarine)?;
+        self.arena.get(id)?.submarine()
+    }
+
+    /// Returns the standing order of an entity, if it has one.
+    ///
+    /// Always allowed: an entity's orders are addressed to it.
+    #[must_use]
+    pub fn standing_order(&self, id: EntityId) -> Option<&'a StandingOrder> {
+        self.arena.orders().get(id)
+    }
+
+    /// Returns the (target, radius) pairs the proximity plugin `watch` had
+    /// within range of `observer` on its last run.
+    ///
+    /// Always allowed: the record is the plugin's own.
+    pub fn in_range(
+        &self,
+        watch: &PluginId,
+        observer: EntityId,
+    ) -> impl Iterator<Item = (EntityId, f32)> + 'a {
+        self.arena.proximity().inside(watch, observer)
+    }
+
     /// Queries for entities within a radius of a center point.
     ///
     /// This is always allowed since it only returns entity IDs, not component data.
@@ -300,6 +421,47 @@ impl<'a> WorldView<'a> {
         self.arena.spatial().query_radius(center, radius)
     }
 
+    /// Queries for the `k` entities closest to a point.
+    ///
+    /// This is always allowed since it only returns entity IDs, not component data.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - The query point
+    /// * `k` - Maximum number of entities to return
+    /// * `filter_tag` - Only return entities with this tag, if given
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` entity IDs sorted by distance (ties by ID), nearest first.
+    #[must_use]
+    pub fn query_knearest(
+        &self,
+        center: Vec2,
+        k: usize,
+        filter_tag: Option<EntityTag>,
+    ) -> Vec<EntityId> {
+        self.arena.spatial().query_knearest(center, k, filter_tag)
+    }
+
+    /// Queries for entities whose hull intersects a swept segment.
+    ///
+    /// This is always allowed since it only returns entity IDs, not component data.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Segment start point
+    /// * `end` - Segment end point
+    /// * `width` - Full width of the swept segment (0 for a ray)
+    ///
+    /// # Returns
+    ///
+    /// Entity IDs ordered by distance along the segment from `start`.
+    #[must_use]
+    pub fn query_segment(&self, start: Vec2, end: Vec2, width: f32) -> Vec<EntityId> {
+        self.arena.spatial().query_segment(start, end, width)
+    }
+
     /// Queries for entities with a specific tag.
     ///
     /// This iterates through all entities and filters by tag. The results
@@ -319,6 +481,22 @@ impl<'a> WorldView<'a> {
             .map(Entity::id)
     }
 
+    /// Queries for entities carrying a label.
+    ///
+    /// This is always allowed since it only returns entity IDs, not component data.
+    /// The results are sorted by entity ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label to filter by
+    ///
+    /// # Returns
+    ///
+    /// An iterator over entity IDs carrying the label.
+    pub fn query_by_label(&self, label: &'a str) -> impl Iterator<Item = EntityId> + 'a {
+        self.arena.query_by_label(label)
+    }
+
     /// Checks if access to a component kind is allowed.
     ///
     /// In debug builds, panics if access is denied.
@@ -394,14 +572,9 @@ impl<'a> WorldView<'a> {
         }
     }
 
-    /// Extracts inventory from entity types that have it.
+    /// Extracts inventory from ships and depot platforms.
     fn extract_inventory(entity: &Entity) -> Option<&InventoryState> {
-        match entity.inner() {
-            EntityInner::Ship(c) => Some(&c.inventory),
-            EntityInner::Platform(_) | EntityInner::Projectile(_) | EntityInner::Squadron(_) => {
-                None
-            }
-        }
+        entity.inventory()
     }
 }
 
@@ -412,9 +585,7 @@ impl<'a> WorldView<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::entity::{
-        PlatformComponents, ProjectileComponents, ShipComponents, SquadronComponents,
-    };
+    use crate::entity::{PlatformComponents, ProjectileComponents, SquadronComponents};
     use crate::output::{OutputKind, PluginId};
 
     // Helper to create a test arena with various entities
@@ -459,6 +630,8 @@ mod tests {
             required_tags: vec![EntityTag::Ship],
             reads,
             emits: vec![OutputKind::Command],
+            runs_after: vec![],
+            every_tick: false,
         }
     }
 
@@ -722,13 +895,29 @@ mod tests {
             assert!(view.get_inventory(EntityId::new(0)).is_some());
         }
 
+        #[test]
+        fn get_inventory_returns_depot_stockpile() {
+            let mut arena = Arena::new();
+            let depot = arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(
+                    PlatformComponents::at_position(Vec2::ZERO)
+                        .with_stockpile(InventoryState::new(2000.0)),
+                ),
+            );
+            let decl = make_declaration(vec![ComponentKind::Inventory]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            assert!(view.get_inventory(depot).is_some_and(|i| i.max_fuel > 1000.0));
+        }
+
         #[test]
         fn get_inventory_other_types_return_none() {
             let arena = create_test_arena();
             let decl = make_declaration(vec![ComponentKind::Inventory]);
             let view = WorldView::for_plugin(&arena, &decl, 0);
 
-            // Platform doesn't have inventory
+            // Platform without a stockpile doesn't have inventory
             assert!(view.get_inventory(EntityId::new(1)).is_none());
 
             // Projectile doesn't have inventory
@@ -750,9 +939,138 @@ mod tests {
         }
     }
 
+    mod attribute_access_tests {
+        use super::*;
+
+        #[test]
+        fn get_attribute_with_permission() {
+            let mut arena = create_test_arena();
+            arena.set_attribute(EntityId::new(0), "patrol.leg", Some(AttributeValue::Int(2)));
+            let decl = make_declaration(vec![ComponentKind::Attributes]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            assert_eq!(
+                view.get_attribute(EntityId::new(0), "patrol.leg"),
+                Some(&AttributeValue::Int(2))
+            );
+            assert_eq!(view.get_attribute(EntityId::new(0), "missing"), None);
+        }
+
+        #[test]
+        fn every_entity_type_has_attributes() {
+            let arena = create_test_arena();
+            let decl = make_declaration(vec![ComponentKind::Attributes]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            for id in 0..4 {
+                assert!(view.get_attributes(EntityId::new(id)).unwrap().is_empty());
+            }
+            assert!(view.get_attributes(EntityId::new(999)).is_none());
+        }
+
+        #[test]
+        #[should_panic(expected = "access denied")]
+        #[cfg(debug_assertions)]
+        fn get_attribute_without_permission_panics_debug() {
+            let arena = create_test_arena();
+            let decl = make_declaration(vec![]); // No attribute access
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            let _ = view.get_attribute(EntityId::new(0), "patrol.leg");
+        }
+    }
+
+    mod signature_access_tests {
+        use super::*;
+        use crate::entity::EmissionsMode;
+
+        #[test]
+        fn current_signature_with_permission() {
+            let mut arena = create_test_arena();
+            let ship = arena.get_mut(EntityId::new(0)).unwrap().as_ship_mut().unwrap();
+            ship.sensor.emissions_mode = EmissionsMode::Active;
+            let expected = ship.current_signature();
+            let decl = make_declaration(vec![ComponentKind::Signature]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            assert_eq!(view.current_signature(EntityId::new(0)), Some(expected));
+            // Only ships have a signature model
+            for id in 1..4 {
+                assert_eq!(view.current_signature(EntityId::new(id)), None);
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "access denied")]
+        #[cfg(debug_assertions)]
+        fn current_signature_without_permission_panics_debug() {
+            let arena = create_test_arena();
+            let decl = make_declaration(vec![ComponentKind::Sensor]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            let _ = view.current_signature(EntityId::new(0));
+        }
+
+        #[test]
+        fn get_submarine_only_for_submarines() {
+            let mut arena = create_test_arena();
+            let ship = arena.get_mut(EntityId::new(0)).unwrap().as_ship_mut().unwrap();
+            ship.submarine = Some(SubmarineState::default());
+            let decl = make_declaration(vec![ComponentKind::Submarine]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            assert_eq!(
+                view.get_submarine(EntityId::new(0)),
+                Some(&SubmarineState::default())
+            );
+            for id in 1..4 {
+                assert_eq!(view.get_submarine(EntityId::new(id)), None);
+            }
+        }
+    }
+
     mod spatial_query_tests {
         use super::*;
 
+        #[test]
+        fn query_by_label_finds_labelled_entities() {
+            let mut arena = create_test_arena();
+            arena.add_label(EntityId::new(3), "strike");
+            arena.add_label(EntityId::new(0), "strike");
+            let decl = make_declaration(vec![]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            let strike: Vec<_> = view.query_by_label("strike").collect();
+            assert_eq!(strike, vec![EntityId::new(0), EntityId::new(3)]);
+            assert_eq!(view.query_by_label("missing").count(), 0);
+        }
+
+        #[test]
+        fn query_segment_orders_hits_along_segment() {
+            let arena = create_test_arena();
+            let decl = make_declaration(vec![]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            // Entities sit at x = 0, 100, 200, 300; fire from the right
+            let hits = view.query_segment(Vec2::new(250.0, 0.0), Vec2::new(50.0, 0.0), 0.0);
+            assert_eq!(hits, vec![EntityId::new(2), EntityId::new(1)]);
+        }
+
+        #[test]
+        fn query_knearest_uses_spawn_tags() {
+            let arena = create_test_arena();
+            let decl = make_declaration(vec![]);
+            let view = WorldView::for_plugin(&arena, &decl, 0);
+
+            // Entities sit at x = 0, 100, 200, 300
+            let nearest = view.query_knearest(Vec2::new(290.0, 0.0), 2, None);
+            assert_eq!(nearest, vec![EntityId::new(3), EntityId::new(2)]);
+
+            let platforms =
+                view.query_knearest(Vec2::new(290.0, 0.0), 2, Some(EntityTag::Platform));
+            assert_eq!(platforms, vec![EntityId::new(1)]);
+        }
+
         #[test]
         fn query_in_radius_finds_nearby() {
             let arena = create_test_arena();
@@ -0,0 +1,432 @@
+//! Persistent battle damage in the murk substrate.
+//!
+//! The [`WreckageSystem`] turns combat events into murk stamps so that
+//! damage outlives the entities involved:
+//!
+//! - `DamageDealt` scorches the target's surroundings (heat, smoke, reduced
+//!   integrity), scaled by the damage amount.
+//! - `EntityDestroyed` leaves an explosion, a debris field (occupancy, low
+//!   integrity) that obstructs navigation, and a burning wreck that keeps
+//!   showing up on thermal sensing.
+//! - `ShellSplash` raises a column of spray (noise, sonar return) where a
+//!   gun round fell into the sea.
+//!
+//! Optionally a wreck platform entity is spawned at each destroyed entity,
+//! labelled `"wreck"` with a `"wreck.of"` attribute naming the original.
+//!
+//! The system is driven by the host after each step, since the simulation
+//! does not own a universe:
+//!
+//! ```
+//! use tidebreak_core::murk::Universe;
+//! use tidebreak_core::simulation::Simulation;
+//! use tidebreak_core::wreckage::WreckageSystem;
+//!
+//! let mut sim = Simulation::new(42);
+//! let mut universe = Universe::default();
+//! let wreckage = WreckageSystem::new();
+//!
+//! sim.step();
+//! let events = sim.take_events();
+//! let wrecks = wreckage.apply(&events, sim.arena_mut(), &mut universe);
+//! assert!(wrecks.is_empty());
+//! ```
+
+use std::collections::BTreeSet;
+
+use glam::{Vec2, Vec3};
+use murk::{BlendOp, Field, FieldMod, Stamp, StampShape, Universe};
+
+use crate::arena::Arena;
+use crate::entity::{AttributeValue, Entity, EntityId, EntityInner, EntityTag, PlatformComponents};
+use crate::output::{Event, OutputEnvelope};
+
+/// Label given to spawned wreck platforms.
+pub const WRECK_LABEL: &str = "wreck";
+
+/// Attribute on a wreck platform holding the ID of the destroyed entity.
+pub const WRECK_OF_ATTRIBUTE: &str = "wreck.of";
+
+/// Configuration for the wreckage system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WreckageConfig {
+    /// Radius of the explosion at a destroyed entity.
+    pub blast_radius: f32,
+    /// Radius of the debris field and wreck fire.
+    pub debris_radius: f32,
+    /// Occupancy of the debris field [0, 1].
+    pub debris_occupancy: f32,
+    /// Radius of the scorch stamp for a damage hit.
+    pub damage_radius: f32,
+    /// Damage at which a hit scorches at full intensity.
+    pub damage_scale: f32,
+    /// Radius of the spray stamp for a gun round that missed.
+    pub splash_radius: f32,
+    /// Altitude at which stamps are placed (the sea surface).
+    pub surface_z: f32,
+    /// Spawn a wreck platform at each destroyed entity.
+    pub spawn_wrecks: bool,
+    /// Entity types that leave wreckage.
+    pub tags: Vec<EntityTag>,
+}
+
+impl Default for WreckageConfig {
+    fn default() -> Self {
+        Self {
+            blast_radius: 100.0,
+            debris_radius: 60.0,
+            debris_occupancy: 0.5,
+            damage_radius: 20.0,
+            damage_scale: 100.0,
+            splash_radius: 10.0,
+            surface_z: 0.0,
+            spawn_wrecks: false,
+            tags: vec![EntityTag::Ship],
+        }
+    }
+}
+
+/// Bridges combat events to murk stamps and wreck entities.
+#[derive(Debug, Clone, Default)]
+pub struct WreckageSystem {
+    config: WreckageConfig,
+}
+
+impl WreckageSystem {
+    /// Creates a wreckage system with the default configuration.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_config(WreckageConfig::default())
+    }
+
+    /// Creates a wreckage system with a custom configuration.
+    #[must_use]
+    pub const fn with_config(config: WreckageConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the configuration.
+    #[must_use]
+    pub const fn config(&self) -> &WreckageConfig {
+        &self.config
+    }
+
+    /// Returns the stamps produced by `events`, in event order.
+    ///
+    /// Positions are read from `arena`; events about entities that are gone
+    /// or of a type not in `tags` are ignored, as are repeated destruction
+    /// events for the same entity.
+    #[must_use]
+    pub fn stamps(&self, events: &[OutputEnvelope], arena: &Arena) -> Vec<Stamp> {
+        let mut stamps = Vec::new();
+        let mut destroyed = BTreeSet::new();
+        for event in events.iter().filter_map(|e| e.output().as_event()) {
+            match event {
+                Event::DamageDealt { target, amount, .. } => {
+                    if let Some(center) = self.position(arena, *target) {
+                        stamps.push(self.scorch(center, *amount));
+                    }
+                }
+                Event::EntityDestroyed { entity, .. } => {
+                    if !destroyed.insert(*entity) {
+                        continue;
+                    }
+                    if let Some(center) = self.position(arena, *entity) {
+                        stamps.extend(self.wreck_stamps(center));
+                    }
+                }
+                Event::ShellSplash { position, .. } => {
+                    let center = Vec3::new(position.x, position.y, self.config.surface_z);
+                    stamps.push(self.splash(center));
+                }
+                _ => {}
+            }
+        }
+        stamps
+    }
+
+    /// Stamps `events` into `universe` and, if configured, spawns wreck
+    /// platforms into `arena`.
+    ///
+    /// Returns the IDs of the spawned wrecks.
+    pub fn apply(
+        &self,
+        events: &[OutputEnvelope],
+        arena: &mut Arena,
+        universe: &mut Universe,
+    ) -> Vec<EntityId> {
+        universe.stamp_many(&self.stamps(events, arena));
+        if !self.config.spawn_wrecks {
+            return Vec::new();
+        }
+
+        let destroyed: BTreeSet<EntityId> = events
+            .iter()
+            .filter_map(|e| match e.output().as_event() {
+                Some(Event::EntityDestroyed { entity, .. }) => Some(*entity),
+                _ => None,
+            })
+            .collect();
+        destroyed
+            .into_iter()
+            .filter_map(|id| {
+                let entity = arena
+                    .get(id)
+                    .filter(|e| self.config.tags.contains(&e.tag()))?;
+                let mut platform =
+                    PlatformComponents::at_position(entity_position(entity)).with_sensors(0.0, 0.0);
+                platform.transform.radius = entity_radius(entity);
+                let team = entity.team();
+
+                let wreck = arena.spawn(EntityTag::Platform, EntityInner::Platform(platform));
+                arena.set_team(wreck, team);
+                arena.add_label(wreck, WRECK_LABEL);
+                #[allow(clippy::cast_possible_wrap)]
+                let original = AttributeValue::Int(id.as_u64() as i64);
+                arena.set_attribute(wreck, WRECK_OF_ATTRIBUTE, Some(original));
+                Some(wreck)
+            })
+            .collect()
+    }
+
+    /// Returns the stamp position of an entity that leaves wreckage.
+    fn position(&self, arena: &Arena, id: EntityId) -> Option<Vec3> {
+        let entity = arena
+            .get(id)
+            .filter(|e| self.config.tags.contains(&e.tag()))?;
+        let pos = entity_position(entity);
+        Some(Vec3::new(pos.x, pos.y, self.config.surface_z))
+    }
+
+    /// Heat, smoke and weakened structure around a damage hit.
+    fn scorch(&self, center: Vec3, amount: f32) -> Stamp {
+        let intensity = (amount / self.config.damage_scale).clamp(0.0, 1.0);
+        Stamp::new(
+            StampShape::sphere(center, self.config.damage_radius),
+            vec![
+                FieldMod::new(Field::Temperature, BlendOp::Add, 200.0 * intensity),
+                FieldMod::new(Field::Smoke, BlendOp::Add, 0.2 * intensity),
+                FieldMod::new(Field::Integrity, BlendOp::Multiply, 1.0 - 0.3 * intensity),
+            ],
+        )
+        .with_falloff()
+    }
+
+    /// Spray and noise where a gun round fell into the sea.
+    fn splash(&self, center: Vec3) -> Stamp {
+        Stamp::new(
+            StampShape::sphere(center, self.config.splash_radius),
+            vec![
+                FieldMod::new(Field::Noise, BlendOp::Max, 120.0),
+                FieldMod::new(Field::SonarReturn, BlendOp::Max, 0.5),
+            ],
+        )
+        .with_falloff()
+    }
+
+    /// Explosion, debris field and burning wreck at a destroyed entity.
+    fn wreck_stamps(&self, center: Vec3) -> [Stamp; 3] {
+        let debris = Stamp::new(
+            StampShape::sphere(center, self.config.debris_radius),
+            vec![
+                FieldMod::new(Field::Occupancy, BlendOp::Max, self.config.debris_occupancy),
+                FieldMod::new(Field::Integrity, BlendOp::Min, 0.2),
+            ],
+        );
+        [
+            Stamp::explosion(center, self.config.blast_radius, 1.0),
+            debris,
+            Stamp::fire(center, self.config.debris_radius, 1.0),
+        ]
+    }
+}
+
+/// Returns the position of an entity.
+fn entity_position(entity: &Entity) -> Vec2 {
+    match entity.inner() {
+        EntityInner::Ship(c) => c.transform.position,
+        EntityInner::Platform(c) => c.transform.position,
+        EntityInner::Projectile(c) => c.transform.position,
+        EntityInner::Squadron(c) => c.transform.position,
+    }
+}
+
+/// Returns the hull radius of an entity.
+fn entity_radius(entity: &Entity) -> f32 {
+    match entity.inner() {
+        EntityInner::Ship(c) => c.transform.radius,
+        EntityInner::Platform(c) => c.transform.radius,
+        EntityInner::Projectile(c) => c.transform.radius,
+        EntityInner::Squadron(c) => c.transform.radius,
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::ShipComponents;
+    use crate::output::{Output, PluginId, PluginInstanceId, TraceId};
+
+    fn event(event: Event) -> OutputEnvelope {
+        OutputEnvelope::new(
+            Output::Event(event),
+            PluginInstanceId::new(EntityId::new(0), PluginId::new("test")),
+            TraceId::new(0),
+            0,
+            0,
+        )
+    }
+
+    fn destroyed(entity: EntityId) -> OutputEnvelope {
+        event(Event::EntityDestroyed {
+            entity,
+            destroyer: None,
+            assists: vec![],
+        })
+    }
+
+    fn arena_with_ship(position: Vec2) -> (Arena, EntityId) {
+        let mut arena = Arena::new();
+        let ship = arena.spawn(
+            EntityTag::Ship,
+            EntityInner::Ship(ShipComponents::at_position(position, 0.0)),
+        );
+        (arena, ship)
+    }
+
+    mod stamp_tests {
+        use super::*;
+
+        #[test]
+        fn destruction_stamps_explosion_debris_and_fire() {
+            let (arena, ship) = arena_with_ship(Vec2::new(10.0, 20.0));
+            let system = WreckageSystem::new();
+
+            let stamps = system.stamps(&[destroyed(ship)], &arena);
+
+            assert_eq!(stamps.len(), 3);
+            assert!(stamps[1]
+                .modifications
+                .iter()
+                .any(|m| m.field == Field::Occupancy));
+            assert!(stamps
+                .iter()
+                .all(|s| s.shape.contains(Vec3::new(10.0, 20.0, 0.0))));
+        }
+
+        #[test]
+        fn repeated_destruction_stamps_once() {
+            let (arena, ship) = arena_with_ship(Vec2::ZERO);
+            let system = WreckageSystem::new();
+
+            let stamps = system.stamps(&[destroyed(ship), destroyed(ship)], &arena);
+
+            assert_eq!(stamps.len(), 3);
+        }
+
+        #[test]
+        fn damage_scorch_scales_with_amount() {
+            let (arena, ship) = arena_with_ship(Vec2::ZERO);
+            let system = WreckageSystem::new();
+            let hit = |amount| {
+                event(Event::DamageDealt {
+                    source: EntityId::new(9),
+                    target: ship,
+                    amount,
+                })
+            };
+
+            let stamps = system.stamps(&[hit(10.0), hit(1000.0)], &arena);
+
+            let heat = |stamp: &Stamp| stamp.modifications[0].value;
+            assert_eq!(stamps.len(), 2);
+            assert!((heat(&stamps[0]) - 20.0).abs() < 1e-4);
+            assert!((heat(&stamps[1]) - 200.0).abs() < 1e-4);
+        }
+
+        #[test]
+        fn shell_splash_stamps_spray_at_impact() {
+            let system = WreckageSystem::new();
+            let splash = event(Event::ShellSplash {
+                source: EntityId::new(9),
+                position: Vec2::new(500.0, -20.0),
+            });
+
+            let stamps = system.stamps(&[splash], &Arena::new());
+
+            assert_eq!(stamps.len(), 1);
+            assert!(stamps[0].shape.contains(Vec3::new(500.0, -20.0, 0.0)));
+            assert_eq!(stamps[0].modifications[0].field, Field::Noise);
+        }
+
+        #[test]
+        fn unknown_and_untracked_entities_are_ignored() {
+            let mut arena = Arena::new();
+            let platform = arena.spawn(
+                EntityTag::Platform,
+                EntityInner::Platform(PlatformComponents::default()),
+            );
+            let system = WreckageSystem::new();
+
+            let events = [destroyed(platform), destroyed(EntityId::new(99))];
+
+            assert!(system.stamps(&events, &arena).is_empty());
+        }
+    }
+
+    mod apply_tests {
+        use super::*;
+
+        #[test]
+        fn debris_persists_in_universe() {
+            let (mut arena, ship) = arena_with_ship(Vec2::new(50.0, 50.0));
+            let mut universe = Universe::default();
+            let center = Vec3::new(50.0, 50.0, 0.0);
+            let before = universe.query_point(center).get(Field::Occupancy);
+
+            WreckageSystem::new().apply(&[destroyed(ship)], &mut arena, &mut universe);
+            universe.step(1.0);
+
+            let after = universe.query_point(center).get(Field::Occupancy);
+            assert!(after > before);
+            assert!(universe.query_point(center).get(Field::Integrity) <= 0.2 + 1e-4);
+        }
+
+        #[test]
+        fn no_wrecks_by_default() {
+            let (mut arena, ship) = arena_with_ship(Vec2::ZERO);
+            let mut universe = Universe::default();
+
+            let wrecks = WreckageSystem::new().apply(&[destroyed(ship)], &mut arena, &mut universe);
+
+            assert!(wrecks.is_empty());
+            assert_eq!(arena.entity_count(), 1);
+        }
+
+        #[test]
+        fn spawns_labelled_wreck_platform() {
+            let (mut arena, ship) = arena_with_ship(Vec2::new(30.0, -40.0));
+            let mut universe = Universe::default();
+            let system = WreckageSystem::with_config(WreckageConfig {
+                spawn_wrecks: true,
+                ..WreckageConfig::default()
+            });
+
+            let wrecks = system.apply(&[destroyed(ship)], &mut arena, &mut universe);
+
+            assert_eq!(wrecks.len(), 1);
+            let wreck = arena.get(wrecks[0]).unwrap();
+            assert_eq!(wreck.tag(), EntityTag::Platform);
+            assert!(wreck.has_label(WRECK_LABEL));
+            #[allow(clippy::cast_possible_wrap)]
+            let original = AttributeValue::Int(ship.as_u64() as i64);
+            assert_eq!(wreck.attribute(WRECK_OF_ATTRIBUTE), Some(&original));
+            assert_eq!(entity_position(wreck), Vec2::new(30.0, -40.0));
+            assert_eq!(arena.query_by_label(WRECK_LABEL).count(), 1);
+        }
+    }
+}
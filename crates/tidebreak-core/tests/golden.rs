@@ -0,0 +1,334 @@
+//! Golden battle regression suite.
+//!
+//! Runs a fixed set of canonical scenarios for a fixed number of ticks and
+//! compares the final state hash and a handful of telemetry values against
+//! the goldens committed in `tests/golden/battles.json`. Unit tests pin
+//! individual resolvers; this suite catches behavior changes that only show
+//! once the whole pipeline runs together (sensors feeding weapons feeding
+//! damage, convoy logic reacting to hits, murk propagation).
+//!
+//! The suite is behind the `golden-tests` feature:
+//!
+//! ```text
+//! cargo test -p tidebreak-core --features golden-tests --test golden
+//! ```
+//!
+//! When a change is meant to alter behavior, regenerate the goldens and
+//! commit the diff together with the change:
+//!
+//! ```text
+//! TIDEBREAK_BLESS=1 cargo test -p tidebreak-core --features golden-tests --test golden
+//! ```
+//!
+//! Arena state hashes are FNV-1a and stable across toolchains. The murk
+//! universe hash uses the standard library's `DefaultHasher`, so a toolchain
+//! upgrade may require regenerating the murk goldens; the telemetry values
+//! should then stay unchanged.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::f32::consts::PI;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+use tidebreak_core::entity::components::{AmmoType, GunBallistics};
+use tidebreak_core::entity::{EntityTag, TeamId, Track, TrackQuality};
+use tidebreak_core::murk::{self, QueryResolution, Stamp, Universe, UniverseConfig};
+use tidebreak_core::output::{Event, Output};
+use tidebreak_core::plugins::{spawn_merchant, ConvoyPlugin};
+use tidebreak_core::resolver::ClassificationModel;
+use tidebreak_core::scenario::{
+    BattlePackage, SensorConfig, SensorType, ShipSnapshot, ShipState, Team, WeaponConfig,
+};
+use tidebreak_core::{PluginRegistry, Simulation};
+
+/// Environment variable that rewrites the goldens instead of checking them.
+const BLESS_VAR: &str = "TIDEBREAK_BLESS";
+
+/// Recorded outcome of one scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Golden {
+    /// Ticks (or murk steps) the scenario ran for
+    ticks: u64,
+    /// Final state hash, as hex
+    state_hash: String,
+    /// Named summary values, rounded to 1e-3
+    telemetry: BTreeMap<String, f64>,
+}
+
+impl Golden {
+    fn new(ticks: u64, hash: u64, telemetry: Telemetry) -> Self {
+        Self {
+            ticks,
+            state_hash: format!("{hash:016x}"),
+            telemetry: telemetry.0,
+        }
+    }
+
+    /// Describes every difference from `expected`, one per line.
+    fn diff(&self, expected: &Self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.ticks != expected.ticks {
+            lines.push(format!("ticks: {} != {}", self.ticks, expected.ticks));
+        }
+        if self.state_hash != expected.state_hash {
+            lines.push(format!("state_hash: {} != {}", self.state_hash, expected.state_hash));
+        }
+        let keys: BTreeSet<_> =
+            self.telemetry.keys().chain(expected.telemetry.keys()).collect();
+        for key in keys {
+            let (got, want) = (self.telemetry.get(key), expected.telemetry.get(key));
+            if got != want {
+                lines.push(format!("{key}: {got:?} != {want:?}"));
+            }
+        }
+        lines
+    }
+}
+
+/// Telemetry values collected while a scenario runs.
+#[derive(Debug, Default)]
+struct Telemetry(BTreeMap<String, f64>);
+
+impl Telemetry {
+    fn set(&mut self, key: &str, value: f64) {
+        self.0.insert(key.to_string(), (value * 1000.0).round() / 1000.0);
+    }
+
+    fn add(&mut self, key: &str, value: f64) {
+        let total = self.0.get(key).copied().unwrap_or(0.0) + value;
+        self.set(key, total);
+    }
+
+    /// Counts the combat events of the last step.
+    fn record_events(&mut self, sim: &Simulation) {
+        for envelope in sim.take_events() {
+            match envelope.output() {
+                Output::Event(Event::ShellSplash { .. }) => self.add("splashes", 1.0),
+                Output::Event(Event::DamageDealt { amount, .. }) => {
+                    self.add("hits", 1.0);
+                    self.add("damage_dealt", f64::from(*amount));
+                }
+                Output::Event(Event::EntityDestroyed { .. }) => self.add("destroyed", 1.0),
+                _ => {}
+            }
+        }
+    }
+
+    /// Records the surviving ships and their hit points per team.
+    fn record_fleet(&mut self, sim: &Simulation) {
+        for entity in sim.arena().entities_sorted() {
+            let (Some(ship), Some(team)) = (entity.as_ship(), entity.team()) else {
+                continue;
+            };
+            let team = team.as_u32();
+            self.add(&format!("team{team}.ships"), 1.0);
+            self.add(&format!("team{team}.hp"), f64::from(ship.combat.hp));
+        }
+        #[allow(clippy::cast_precision_loss)]
+        self.set("entities", sim.arena().entity_count() as f64);
+    }
+}
+
+/// Ship with a radar and one gun mount.
+fn warship(id: &str, team: &str, state: ShipState) -> ShipSnapshot {
+    let mut ship = ShipSnapshot::new(id, team, state);
+    ship.sensors.push(SensorConfig {
+        sensor_type: SensorType::Radar,
+        range: 8000.0,
+    });
+    ship.weapons.push(WeaponConfig {
+        slot: 0,
+        weapon_type: AmmoType::Shell,
+        cooldown: 2.0,
+        ammunition: 40,
+    });
+    ship
+}
+
+/// Fits every gun with default ballistics and gives every ship a
+/// fire-control track on each enemy ship.
+///
+/// Contacts do not open tracks on their own, so the scenarios start with
+/// the enemy already plotted.
+fn clear_for_action(sim: &mut Simulation) {
+    let ships: Vec<_> = sim
+        .arena()
+        .entities_sorted()
+        .filter_map(|e| Some((e.id(), e.team(), e.as_ship()?.transform.position)))
+        .collect();
+    for entity in sim.arena_mut().entities_sorted_mut() {
+        let team = entity.team();
+        let Some(ship) = entity.as_ship_mut() else {
+            continue;
+        };
+        for weapon in &mut ship.combat.weapons {
+            weapon.gun = Some(GunBallistics::default());
+        }
+        for &(target, _, position) in ships.iter().filter(|(_, other, _)| *other != team) {
+            let track = Track::new(target, position, TrackQuality::FireControl);
+            ship.sensor.track_table.push(track);
+        }
+    }
+}
+
+/// Runs `sim` with `plugins` and track classification for `ticks`, then
+/// records the final fleet state.
+fn run_battle(mut sim: Simulation, plugins: PluginRegistry, ticks: u64) -> Golden {
+    clear_for_action(&mut sim);
+    *sim.plugins_mut() = plugins;
+    sim.enable_classification(ClassificationModel::default());
+    let mut telemetry = Telemetry::default();
+    for _ in 0..ticks {
+        sim.step();
+        telemetry.record_events(&sim);
+    }
+    telemetry.record_fleet(&sim);
+    Golden::new(ticks, sim.arena().state_hash(), telemetry)
+}
+
+/// Two armed ships trading fire at 4 km.
+fn duel() -> Golden {
+    let mut package = BattlePackage::new("golden-duel", 7);
+    package.teams = vec![Team::new("blue"), Team::new("red")];
+    package.ships = vec![
+        warship("b1", "blue", ShipState::at(0.0, 0.0, 0.0)),
+        warship("r1", "red", ShipState::at(4000.0, 0.0, 3.0)),
+    ];
+    let (sim, _) = package.build().expect("duel package is valid");
+    run_battle(sim, PluginRegistry::default_bundles(), 1200)
+}
+
+/// Two fleets of three closing from 6 km.
+fn fleet_action() -> Golden {
+    let mut package = BattlePackage::new("golden-fleet-action", 11);
+    package.teams = vec![Team::new("blue"), Team::new("red")];
+    for i in 0..3u8 {
+        let y = f32::from(i) * 400.0 - 400.0;
+        let (blue, red) = (ShipState::at(0.0, y, 0.0), ShipState::at(6000.0, y, PI));
+        package.ships.push(warship(&format!("b{i}"), "blue", blue));
+        package.ships.push(warship(&format!("r{i}"), "red", red));
+    }
+    let (sim, _) = package.build().expect("fleet package is valid");
+    run_battle(sim, PluginRegistry::default_bundles(), 1500)
+}
+
+/// A two-ship convoy sailing past a raider, scattering once hit.
+fn convoy_raid() -> Golden {
+    let mut package = BattlePackage::new("golden-convoy-raid", 23);
+    package.teams = vec![Team::new("blue"), Team::new("red")];
+    package.ships = vec![warship("raider", "red", ShipState::at(3000.0, 2500.0, 4.7))];
+    let (mut sim, _) = package.build().expect("convoy package is valid");
+    for x in [0.0, -300.0] {
+        spawn_merchant(sim.arena_mut(), Vec2::new(x, 0.0), 0.0, Some(TeamId::new(0)));
+    }
+    let mut plugins = PluginRegistry::default_bundles();
+    let route = vec![Vec2::new(3000.0, 0.0), Vec2::new(8000.0, 0.0)];
+    plugins.register(EntityTag::Ship, Arc::new(ConvoyPlugin::new(route)));
+    run_battle(sim, plugins, 1500)
+}
+
+/// Murk fields under repeated fires and explosions, propagated each step.
+fn murk_storm() -> Golden {
+    const STEPS: u64 = 60;
+    let config = UniverseConfig {
+        base_resolution: 4.0,
+        planar: true,
+        ..UniverseConfig::with_bounds(256.0, 256.0, 16.0)
+    };
+    let mut universe = Universe::new_with_seed(config, 5);
+    let mut telemetry = Telemetry::default();
+    for step in 0..STEPS {
+        #[allow(clippy::cast_precision_loss)]
+        let angle = step as f32 * 0.7;
+        let center = Vec3::new(angle.cos() * 80.0, angle.sin() * 80.0, 0.0);
+        if step % 10 == 0 {
+            universe.stamp(&Stamp::explosion(center, 12.0, 1.0));
+        }
+        universe.stamp(&Stamp::fire(center * 0.5, 6.0, 0.8));
+        universe.step(0.5);
+    }
+    let stats = universe.stats();
+    #[allow(clippy::cast_precision_loss)]
+    {
+        telemetry.set("nodes", stats.node_count as f64);
+        telemetry.set("leaves", stats.leaf_count as f64);
+    }
+    let region = universe.query_volume(Vec3::ZERO, 100.0, QueryResolution::Coarse);
+    for field in [murk::Field::Temperature, murk::Field::Smoke, murk::Field::Noise] {
+        telemetry.set(&format!("mean.{field:?}"), f64::from(region.mean(field)));
+    }
+    Golden::new(STEPS, universe.state_hash(), telemetry)
+}
+
+/// A named scenario and the function running it.
+type Scenario = (&'static str, fn() -> Golden);
+
+/// The canonical scenarios, by name.
+fn scenarios() -> Vec<Scenario> {
+    vec![
+        ("duel", duel),
+        ("fleet_action", fleet_action),
+        ("convoy_raid", convoy_raid),
+        ("murk_storm", murk_storm),
+    ]
+}
+
+fn golden_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/battles.json")
+}
+
+#[test]
+fn golden_battles() {
+    let actual: BTreeMap<String, Golden> = scenarios()
+        .into_iter()
+        .map(|(name, run)| (name.to_string(), run()))
+        .collect();
+
+    let path = golden_path();
+    if std::env::var_os(BLESS_VAR).is_some() {
+        let json = serde_json::to_string_pretty(&actual).expect("goldens serialize");
+        std::fs::create_dir_all(path.parent().expect("golden path has a parent"))
+            .expect("golden directory is writable");
+        std::fs::write(&path, json + "\n").expect("golden file is writable");
+        return;
+    }
+
+    let expected: BTreeMap<String, Golden> = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(|| {
+            panic!("no readable goldens at {}; run with {BLESS_VAR}=1", path.display())
+        });
+
+    let mut report = Vec::new();
+    for (name, golden) in &actual {
+        match expected.get(name) {
+            Some(want) => {
+                report.extend(golden.diff(want).into_iter().map(|line| format!("{name}.{line}")));
+            }
+            None => report.push(format!("{name}: no golden recorded")),
+        }
+    }
+    report.extend(
+        expected
+            .keys()
+            .filter(|name| !actual.contains_key(*name))
+            .map(|name| format!("{name}: golden recorded but scenario missing")),
+    );
+    assert!(
+        report.is_empty(),
+        "golden battles diverged (got != golden):\n  {}\n\
+         If the change is intended, rerun with {BLESS_VAR}=1 and commit the new goldens.",
+        report.join("\n  ")
+    );
+}
+
+#[test]
+fn golden_scenarios_are_deterministic() {
+    for (name, run) in scenarios() {
+        assert_eq!(run(), run(), "scenario {name} differs between two runs");
+    }
+}
@@ -0,0 +1,32 @@
+//! Generates the type stub of the extension module from the PyO3
+//! declarations in `src/lib.rs` into `OUT_DIR`.
+//!
+//! The build never writes to the source tree. The committed stub,
+//! `python/tidebreak/_tidebreak.pyi`, is checked against the generated one
+//! by `tests/stub.rs`; after changing the bindings, regenerate it with
+//!
+//! ```text
+//! TIDEBREAK_BLESS=1 cargo test -p tidebreak-py --test stub
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[path = "build/stubgen.rs"]
+mod stubgen;
+
+const SOURCE: &str = "src/lib.rs";
+
+fn main() {
+    println!("cargo:rerun-if-changed={SOURCE}");
+    println!("cargo:rerun-if-changed=build/stubgen.rs");
+    let source = fs::read_to_string(SOURCE).expect("bindings source is readable");
+    let stub = stubgen::generate(&source).unwrap_or_else(|err| {
+        // rustc reports the parse error itself when compiling the crate
+        println!("cargo:warning=skipping stub generation: {err}");
+        String::new()
+    });
+    let out = env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    fs::write(Path::new(&out).join("_tidebreak.pyi"), stub).expect("OUT_DIR is writable");
+}
@@ -0,0 +1,666 @@
+//! Python type stub generation for the `_tidebreak` extension module.
+//!
+//! Parses the bindings source with `syn` and renders a `.pyi` covering
+//! everything the `#[pymodule]` function registers: classes (fields,
+//! methods, properties, enum variants), module functions and exceptions.
+//! Doc comments become docstrings, so numpy shapes documented on a method
+//! show up in IDE hovers. Rust types map to annotations in [`py_type`];
+//! anything unrecognised is annotated `Any`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Expr, ExprLit, Fields, FnArg, GenericArgument, ImplItem, ImplItemFn, Item, Lit,
+    Meta, Pat, PathArguments, ReturnType, Token, Type,
+};
+
+/// Header written at the top of the generated stub.
+const HEADER: &str = "\
+# Generated by crates/tidebreak-py/build.rs from src/lib.rs. Do not edit.
+
+from typing import Any, ClassVar, final
+
+import numpy as np
+import numpy.typing as npt
+";
+
+/// Renders the stub for the bindings in `source`.
+///
+/// # Errors
+///
+/// Returns an error if `source` does not parse as Rust.
+pub fn generate(source: &str) -> syn::Result<String> {
+    let file = syn::parse_file(source)?;
+    let mut module = Module::default();
+    for item in &file.items {
+        module.collect(item);
+    }
+    Ok(module.render())
+}
+
+/// Everything the stub is rendered from.
+#[derive(Default)]
+struct Module {
+    classes: BTreeMap<String, Class>,
+    /// `FromPyObject`/`IntoPyObject` enums, as the Python union they accept
+    unions: BTreeMap<String, Vec<Type>>,
+    exceptions: BTreeMap<String, Exception>,
+    functions: BTreeMap<String, Member>,
+    exports: Vec<Export>,
+}
+
+/// A name registered by the `#[pymodule]` function, in registration order.
+enum Export {
+    Class(String),
+    Function(String),
+    Exception(String),
+}
+
+#[derive(Default)]
+struct Class {
+    py_name: String,
+    doc: String,
+    final_: bool,
+    eq: bool,
+    hash: bool,
+    variants: Vec<(String, String)>,
+    members: Vec<Member>,
+}
+
+struct Exception {
+    base: String,
+    doc: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MemberKind {
+    Method,
+    Static,
+    ClassMethod,
+    New,
+    Getter,
+    Setter,
+}
+
+struct Member {
+    py_name: String,
+    kind: MemberKind,
+    params: Vec<Param>,
+    ret: Option<Type>,
+    doc: String,
+}
+
+struct Param {
+    name: String,
+    ty: Option<Type>,
+    default: Option<String>,
+}
+
+impl Module {
+    fn collect(&mut self, item: &Item) {
+        match item {
+            Item::Struct(s) if has_attr(&s.attrs, "pyclass") => {
+                let class = self.class_entry(&s.ident.to_string(), &s.attrs);
+                if let Fields::Named(fields) = &s.fields {
+                    class.members.extend(fields.named.iter().flat_map(field_members));
+                }
+            }
+            Item::Enum(e) if has_attr(&e.attrs, "pyclass") => {
+                let class = self.class_entry(&e.ident.to_string(), &e.attrs);
+                class.variants =
+                    e.variants.iter().map(|v| (v.ident.to_string(), doc(&v.attrs))).collect();
+            }
+            Item::Enum(e) if derives(&e.attrs, &["FromPyObject", "IntoPyObject"]) => {
+                let types = e.variants.iter().filter_map(|v| v.fields.iter().next());
+                self.unions.insert(e.ident.to_string(), types.map(|f| f.ty.clone()).collect());
+            }
+            Item::Impl(block) if has_attr(&block.attrs, "pymethods") => {
+                let Type::Path(path) = &*block.self_ty else { return };
+                let name = last_ident(&path.path);
+                let members = block.items.iter().filter_map(|item| match item {
+                    ImplItem::Fn(f) => method_member(f),
+                    _ => None,
+                });
+                let members: Vec<_> = members.collect();
+                self.classes.entry(name).or_default().members.extend(members);
+            }
+            Item::Fn(f) if has_attr(&f.attrs, "pyfunction") => {
+                let member = function_member(&f.attrs, &f.sig, MemberKind::Static);
+                self.functions.insert(f.sig.ident.to_string(), member);
+            }
+            Item::Fn(f) if has_attr(&f.attrs, "pymodule") => {
+                self.exports = f.block.stmts.iter().filter_map(export).collect();
+            }
+            Item::Macro(m) if last_ident(&m.mac.path) == "create_exception" => {
+                if let Some((name, exception)) = exception(&m.mac) {
+                    self.exceptions.insert(name, exception);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Records the `#[pyclass]` options of `name`, returning its entry.
+    fn class_entry(&mut self, name: &str, attrs: &[Attribute]) -> &mut Class {
+        let class = self.classes.entry(name.to_string()).or_default();
+        class.py_name = name.to_string();
+        class.doc = doc(attrs);
+        class.final_ = true;
+        for option in attr_options(attrs, "pyclass") {
+            match option.path().get_ident().map(ToString::to_string).as_deref() {
+                Some("name") => class.py_name = option_str(&option).unwrap_or_default(),
+                Some("subclass") => class.final_ = false,
+                Some("eq") => class.eq = true,
+                Some("hash") => class.hash = true,
+                _ => {}
+            }
+        }
+        class
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::from(HEADER);
+        for export in &self.exports {
+            out.push_str("\n\n");
+            match export {
+                Export::Class(name) => {
+                    if let Some(class) = self.classes.get(name) {
+                        self.render_class(&mut out, class);
+                    }
+                }
+                Export::Function(name) => {
+                    if let Some(function) = self.functions.get(name) {
+                        self.render_member(&mut out, function, "", "");
+                    }
+                }
+                Export::Exception(name) => {
+                    if let Some(exception) = self.exceptions.get(name) {
+                        let _ = writeln!(out, "class {name}({}):", exception.base);
+                        render_body(&mut out, &exception.doc, "    ");
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn render_class(&self, out: &mut String, class: &Class) {
+        if class.final_ {
+            out.push_str("@final\n");
+        }
+        let _ = writeln!(out, "class {}:", class.py_name);
+        let mut body = String::new();
+        if !class.doc.is_empty() {
+            render_doc(&mut body, &class.doc, "    ");
+        }
+        for (variant, doc) in &class.variants {
+            let _ = writeln!(body, "    {variant}: ClassVar[{}]", class.py_name);
+            if !doc.is_empty() {
+                render_doc(&mut body, doc, "    ");
+            }
+        }
+        for member in &class.members {
+            self.render_member(&mut body, member, "    ", &class.py_name);
+        }
+        if !class.variants.is_empty() {
+            let _ = writeln!(body, "    def __int__(self) -> int: ...");
+        }
+        if class.eq {
+            let _ = writeln!(body, "    def __eq__(self, other: object) -> bool: ...");
+        }
+        if class.hash {
+            let _ = writeln!(body, "    def __hash__(self) -> int: ...");
+        }
+        if body.is_empty() {
+            body.push_str("    ...\n");
+        }
+        out.push_str(&body);
+    }
+
+    fn render_member(&self, out: &mut String, member: &Member, indent: &str, class: &str) {
+        let name = &member.py_name;
+        let (receiver, name) = match member.kind {
+            MemberKind::Method => (Some("self"), name.as_str()),
+            MemberKind::New => (Some("self"), "__init__"),
+            MemberKind::Getter => {
+                let _ = writeln!(out, "{indent}@property");
+                (Some("self"), name.as_str())
+            }
+            MemberKind::Setter => {
+                let _ = writeln!(out, "{indent}@{name}.setter");
+                (Some("self"), name.as_str())
+            }
+            MemberKind::Static if indent.is_empty() => (None, name.as_str()),
+            MemberKind::Static => {
+                let _ = writeln!(out, "{indent}@staticmethod");
+                (None, name.as_str())
+            }
+            MemberKind::ClassMethod => {
+                let _ = writeln!(out, "{indent}@classmethod");
+                (Some("cls"), name.as_str())
+            }
+        };
+        let mut params: Vec<String> = receiver.map(String::from).into_iter().collect();
+        params.extend(member.params.iter().map(|p| self.render_param(p, class)));
+        let ret = match (member.kind, &member.ret) {
+            (MemberKind::New | MemberKind::Setter, _) | (_, None) => "None".to_string(),
+            (_, Some(ty)) => self.py_type(ty, class),
+        };
+        let _ = write!(out, "{indent}def {name}({}) -> {ret}:", params.join(", "));
+        if member.doc.is_empty() {
+            out.push_str(" ...\n");
+        } else {
+            out.push('\n');
+            render_doc(out, &member.doc, &format!("{indent}    "));
+        }
+    }
+
+    fn render_param(&self, param: &Param, class: &str) -> String {
+        let mut rendered = param.name.clone();
+        if let Some(ty) = &param.ty {
+            let _ = write!(rendered, ": {}", self.py_type(ty, class));
+        }
+        if let Some(default) = &param.default {
+            let _ = write!(rendered, " = {default}");
+        }
+        rendered
+    }
+
+    /// Maps a Rust type to a Python annotation, as PyO3 converts it.
+    fn py_type(&self, ty: &Type, class: &str) -> String {
+        match ty {
+            Type::Reference(r) => self.py_type(&r.elem, class),
+            Type::Slice(s) if is_u8(&s.elem) => "bytes".to_string(),
+            Type::Slice(s) => format!("list[{}]", self.py_type(&s.elem, class)),
+            Type::Tuple(t) if t.elems.is_empty() => "None".to_string(),
+            Type::Tuple(t) => {
+                let elems: Vec<_> = t.elems.iter().map(|e| self.tuple_elem(e, class)).collect();
+                format!("tuple[{}]", elems.join(", "))
+            }
+            Type::Path(p) => {
+                let segment = p.path.segments.last().expect("type path has a segment");
+                let args: Vec<&Type> = match &segment.arguments {
+                    PathArguments::AngleBracketed(a) => a
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            GenericArgument::Type(t) => Some(t),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                self.path_type(&segment.ident.to_string(), &args, class)
+            }
+            _ => "Any".to_string(),
+        }
+    }
+
+    /// Renders a tuple element, spelling the empty tuple `tuple[()]`.
+    fn tuple_elem(&self, ty: &Type, class: &str) -> String {
+        match ty {
+            Type::Tuple(t) if t.elems.is_empty() => "tuple[()]".to_string(),
+            _ => self.py_type(ty, class),
+        }
+    }
+
+    fn path_type(&self, ident: &str, args: &[&Type], class: &str) -> String {
+        let arg = |i: usize| args.get(i).map_or("Any".to_string(), |t| self.py_type(t, class));
+        match ident {
+            "bool" => "bool".to_string(),
+            "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => {
+                "int".to_string()
+            }
+            "f32" | "f64" => "float".to_string(),
+            "str" | "String" | "PathBuf" | "char" => "str".to_string(),
+            "Vec" | "VecDeque" => format!("list[{}]", arg(0)),
+            "Option" => format!("{} | None", arg(0)),
+            "HashMap" | "BTreeMap" => format!("dict[{}, {}]", arg(0), arg(1)),
+            "PyResult" | "Bound" | "Py" | "PyRef" | "PyRefMut" => arg(0),
+            "Self" => class.to_string(),
+            "PyList" => "list[Any]".to_string(),
+            "PyDict" => "dict[str, Any]".to_string(),
+            "PyBytes" => "bytes".to_string(),
+            "PyType" => "type".to_string(),
+            "PyBaseException" => "BaseException".to_string(),
+            "PyUntypedArray" => "npt.NDArray[Any]".to_string(),
+            _ if ident.contains("Array") && ident.starts_with("Py") => {
+                format!("npt.NDArray[{}]", args.first().map_or("Any", |t| numpy_dtype(t)))
+            }
+            _ => self.named_type(ident, class),
+        }
+    }
+
+    /// Resolves a bindings type: a pyclass, or a union of convertible types.
+    fn named_type(&self, ident: &str, class: &str) -> String {
+        if let Some(class) = self.classes.get(ident) {
+            return class.py_name.clone();
+        }
+        match self.unions.get(ident) {
+            Some(types) => {
+                let types: Vec<_> = types.iter().map(|t| self.py_type(t, class)).collect();
+                types.join(" | ")
+            }
+            None => "Any".to_string(),
+        }
+    }
+}
+
+/// Builds the property accessors of a `#[pyo3(get, set)]` field.
+fn field_members(field: &syn::Field) -> Vec<Member> {
+    let options = attr_options(&field.attrs, "pyo3");
+    let name = options.iter().find(|o| o.path().is_ident("name")).and_then(option_str);
+    let Some(py_name) = name.or_else(|| field.ident.as_ref().map(ToString::to_string)) else {
+        return Vec::new();
+    };
+    let accessor = |kind, params| Member {
+        py_name: py_name.clone(),
+        kind,
+        params,
+        ret: Some(field.ty.clone()),
+        doc: String::new(),
+    };
+    let mut members = Vec::new();
+    if options.iter().any(|o| o.path().is_ident("get")) {
+        members.push(Member { doc: doc(&field.attrs), ..accessor(MemberKind::Getter, Vec::new()) });
+    }
+    if options.iter().any(|o| o.path().is_ident("set")) {
+        let value = Param { name: "value".to_string(), ty: Some(field.ty.clone()), default: None };
+        members.push(accessor(MemberKind::Setter, vec![value]));
+    }
+    members
+}
+
+/// Builds the stub member for a `#[pymethods]` function.
+fn method_member(f: &ImplItemFn) -> Option<Member> {
+    let kind = if has_attr(&f.attrs, "new") {
+        MemberKind::New
+    } else if has_attr(&f.attrs, "getter") {
+        MemberKind::Getter
+    } else if has_attr(&f.attrs, "setter") {
+        MemberKind::Setter
+    } else if has_attr(&f.attrs, "staticmethod") {
+        MemberKind::Static
+    } else if has_attr(&f.attrs, "classmethod") {
+        MemberKind::ClassMethod
+    } else {
+        MemberKind::Method
+    };
+    Some(function_member(&f.attrs, &f.sig, kind))
+}
+
+fn function_member(attrs: &[Attribute], sig: &syn::Signature, kind: MemberKind) -> Member {
+    let name = sig.ident.to_string();
+    let options = attr_options(attrs, "pyo3");
+    let renamed = options.iter().find(|o| o.path().is_ident("name")).and_then(option_str);
+    // `#[getter(name)]` / `#[setter(name)]` name the property explicitly
+    let accessor_name = attrs
+        .iter()
+        .find(|a| a.path().is_ident("getter") || a.path().is_ident("setter"))
+        .and_then(|a| a.parse_args::<syn::Ident>().ok())
+        .map(|ident| ident.to_string());
+    let py_name = match kind {
+        MemberKind::Getter => accessor_name
+            .unwrap_or_else(|| name.strip_prefix("get_").unwrap_or(&name).to_string()),
+        MemberKind::Setter => accessor_name
+            .unwrap_or_else(|| name.strip_prefix("set_").unwrap_or(&name).to_string()),
+        _ => renamed.unwrap_or(name),
+    };
+    let mut params = python_params(sig, kind);
+    if kind == MemberKind::Setter {
+        if let Some(param) = params.first_mut() {
+            param.name = "value".to_string();
+        }
+    }
+    if let Some(signature) = attr_signature(attrs) {
+        params = apply_signature(params, &signature);
+    } else {
+        default_trailing_options(&mut params);
+    }
+    let ret = match &sig.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => Some((**ty).clone()),
+    };
+    Member { py_name, kind, params, ret, doc: doc(attrs) }
+}
+
+/// The parameters Python passes: drops receivers, the GIL token and the
+/// `cls` argument of class methods.
+fn python_params(sig: &syn::Signature, kind: MemberKind) -> Vec<Param> {
+    let mut params = Vec::new();
+    for input in &sig.inputs {
+        let FnArg::Typed(typed) = input else { continue };
+        let Pat::Ident(ident) = &*typed.pat else { continue };
+        let ty = (*typed.ty).clone();
+        let rendered = ty.to_token_stream().to_string();
+        let is_token = rendered.starts_with("Python");
+        let is_slf = ident.ident == "slf" || rendered.contains("Self >");
+        let is_cls = kind == MemberKind::ClassMethod && params.is_empty() && ident.ident == "cls";
+        if is_token || is_slf || is_cls {
+            continue;
+        }
+        params.push(Param { name: ident.ident.to_string(), ty: Some(ty), default: None });
+    }
+    params
+}
+
+/// PyO3 gives trailing `Option` arguments a `None` default when no explicit
+/// signature is declared.
+fn default_trailing_options(params: &mut [Param]) {
+    for param in params.iter_mut().rev() {
+        let is_option = param.ty.as_ref().is_some_and(|ty| match ty {
+            Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "Option"),
+            _ => false,
+        });
+        if !is_option {
+            break;
+        }
+        param.default = Some("None".to_string());
+    }
+}
+
+/// Orders and defaults `params` by a `#[pyo3(signature = (...))]` list.
+fn apply_signature(params: Vec<Param>, signature: &[String]) -> Vec<Param> {
+    let mut by_name: BTreeMap<String, Param> =
+        params.into_iter().map(|p| (p.name.clone(), p)).collect();
+    let mut ordered = Vec::new();
+    for entry in signature {
+        // Tokens render `**kwargs` as `* * kwargs`
+        let entry = entry.replace("* ", "*");
+        let (name, default) = match entry.split_once('=') {
+            Some((name, default)) => (name.trim(), Some(py_default(default.trim()))),
+            None => (entry.trim(), None),
+        };
+        if name == "*" || name == "/" {
+            ordered.push(Param { name: name.to_string(), ty: None, default: None });
+            continue;
+        }
+        let bare = name.trim_start_matches('*');
+        let mut param = by_name.remove(bare).unwrap_or(Param {
+            name: bare.to_string(),
+            ty: None,
+            default: None,
+        });
+        if name.starts_with("**") {
+            param.ty = None;
+            param.name = format!("**{bare}: Any");
+        } else if name.starts_with('*') {
+            param.ty = None;
+            param.name = format!("*{bare}: Any");
+        }
+        param.default = default;
+        ordered.push(param);
+    }
+    ordered
+}
+
+/// Renders a signature default as Python, or `...` if it is not a literal.
+fn py_default(default: &str) -> String {
+    let compact = default.replace(' ', "");
+    match compact.as_str() {
+        "None" => "None".to_string(),
+        "true" => "True".to_string(),
+        "false" => "False".to_string(),
+        _ if compact.starts_with('"') => default.to_string(),
+        _ if compact.trim_start_matches('-').parse::<f64>().is_ok() => compact,
+        _ => "...".to_string(),
+    }
+}
+
+/// Reads the entries of `#[pyo3(signature = (...))]`, split at top-level commas.
+fn attr_signature(attrs: &[Attribute]) -> Option<Vec<String>> {
+    let option = attr_options(attrs, "pyo3").into_iter().find(|o| o.path().is_ident("signature"))?;
+    let Meta::NameValue(value) = option else { return None };
+    let Expr::Tuple(tuple) = &value.value else {
+        let Expr::Paren(paren) = &value.value else { return Some(Vec::new()) };
+        return Some(vec![paren.expr.to_token_stream().to_string()]);
+    };
+    Some(tuple.elems.iter().map(|e| e.to_token_stream().to_string()).collect())
+}
+
+fn export(stmt: &syn::Stmt) -> Option<Export> {
+    let syn::Stmt::Expr(expr, _) = stmt else { return None };
+    let Expr::Try(tried) = expr else { return None };
+    let Expr::MethodCall(call) = &*tried.expr else { return None };
+    match call.method.to_string().as_str() {
+        "add_class" => {
+            let turbofish = call.turbofish.as_ref()?;
+            let GenericArgument::Type(Type::Path(ty)) = turbofish.args.first()? else {
+                return None;
+            };
+            Some(Export::Class(last_ident(&ty.path)))
+        }
+        "add_function" => {
+            let Expr::Try(arg) = call.args.first()? else { return None };
+            let Expr::Macro(mac) = &*arg.expr else { return None };
+            let name = mac.mac.tokens.clone().into_iter().next()?.to_string();
+            Some(Export::Function(name))
+        }
+        "add" => {
+            let Expr::Lit(ExprLit { lit: Lit::Str(name), .. }) = call.args.first()? else {
+                return None;
+            };
+            Some(Export::Exception(name.value()))
+        }
+        _ => None,
+    }
+}
+
+/// Reads `create_exception!(module, Name, Base, "doc")`.
+fn exception(mac: &syn::Macro) -> Option<(String, Exception)> {
+    let args = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated).ok()?;
+    let mut args = args.into_iter().skip(1);
+    let Some(Expr::Path(name)) = args.next() else { return None };
+    let Some(Expr::Path(base)) = args.next() else { return None };
+    let doc = match args.next() {
+        Some(Expr::Lit(ExprLit { lit: Lit::Str(doc), .. })) => doc.value(),
+        _ => String::new(),
+    };
+    let base = match last_ident(&base.path).as_str() {
+        "PyException" => "Exception".to_string(),
+        "PyValueError" => "ValueError".to_string(),
+        "PyRuntimeError" => "RuntimeError".to_string(),
+        other => other.to_string(),
+    };
+    Some((last_ident(&name.path), Exception { base, doc }))
+}
+
+fn numpy_dtype(ty: &Type) -> &'static str {
+    match ty.to_token_stream().to_string().as_str() {
+        "f32" => "np.float32",
+        "f64" => "np.float64",
+        "i8" => "np.int8",
+        "i16" => "np.int16",
+        "i32" => "np.int32",
+        "i64" => "np.int64",
+        "u8" => "np.uint8",
+        "u16" => "np.uint16",
+        "u32" => "np.uint32",
+        "u64" => "np.uint64",
+        "bool" => "np.bool_",
+        _ => "Any",
+    }
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(ty, Type::Path(p) if p.path.is_ident("u8"))
+}
+
+fn last_ident(path: &syn::Path) -> String {
+    path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default()
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|a| a.path().is_ident(name))
+}
+
+fn derives(attrs: &[Attribute], traits: &[&str]) -> bool {
+    attrs.iter().filter(|a| a.path().is_ident("derive")).any(|a| {
+        let derived = a.to_token_stream().to_string();
+        traits.iter().any(|t| derived.contains(t))
+    })
+}
+
+/// The comma-separated options of every `#[name(...)]` attribute.
+fn attr_options(attrs: &[Attribute], name: &str) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|a| a.path().is_ident(name))
+        .filter_map(|a| a.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated).ok())
+        .flatten()
+        .collect()
+}
+
+fn option_str(option: &Meta) -> Option<String> {
+    let Meta::NameValue(value) = option else { return None };
+    let Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) = &value.value else { return None };
+    Some(s.value())
+}
+
+/// Joins `///` lines, dropping the single space after each `///`.
+fn doc(attrs: &[Attribute]) -> String {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(v) => match &v.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).trim_end().to_string())
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+fn render_body(out: &mut String, doc: &str, indent: &str) {
+    if doc.is_empty() {
+        let _ = writeln!(out, "{indent}...");
+    } else {
+        render_doc(out, doc, indent);
+    }
+}
+
+fn render_doc(out: &mut String, doc: &str, indent: &str) {
+    let escaped = doc.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    let mut lines = escaped.lines();
+    let first = lines.next().unwrap_or_default();
+    let rest: Vec<&str> = lines.collect();
+    if rest.is_empty() {
+        let _ = writeln!(out, "{indent}\"\"\"{first}\"\"\"");
+        return;
+    }
+    let _ = writeln!(out, "{indent}\"\"\"{first}");
+    for line in rest {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            let _ = writeln!(out, "{indent}{line}");
+        }
+    }
+    let _ = writeln!(out, "{indent}\"\"\"");
+}
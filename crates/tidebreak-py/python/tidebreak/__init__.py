@@ -8,28 +8,45 @@ from __future__ import annotations
 
 # Import from the compiled Rust extension
 from tidebreak._tidebreak import (
+    # Command errors
+    CommandError,
+    EntityDestroyed,
     # Murk bindings (existing)
     Field,
+    InvalidValue,
+    NotController,
+    NotSupportedForTag,
+    PyCancellationToken,
     PyCombatState,
     PyEntity,
     # Tidebreak-core bindings (new)
     PyEntityId,
     PyEntityTag,
     PyObservation,
+    PyObservationCodec,
+    PyObservationSpec,
     PyPhysicsState,
     PyPointResult,
     PyQueryResult,
+    PyScenarioRandomizer,
     PySimulation,
     PyTransformState,
     PyUniverse,
+    Replay,
+    UnknownEntity,
+    schema,
 )
 
 # Aliases for convenience
 Universe = PyUniverse
 Simulation = PySimulation
+CancellationToken = PyCancellationToken
 EntityId = PyEntityId
 EntityTag = PyEntityTag
 Entity = PyEntity
+ObservationCodec = PyObservationCodec
+ObservationSpec = PyObservationSpec
+ScenarioRandomizer = PyScenarioRandomizer
 
 __all__ = [
     # Murk types
@@ -53,8 +70,26 @@ __all__ = [
     # Simulation
     "PySimulation",
     "Simulation",
+    "PyCancellationToken",
+    "CancellationToken",
+    "PyScenarioRandomizer",
+    "ScenarioRandomizer",
+    "Replay",
     # DRL
     "PyObservation",
+    "PyObservationCodec",
+    "ObservationCodec",
+    "PyObservationSpec",
+    "ObservationSpec",
+    # Command errors
+    "CommandError",
+    "UnknownEntity",
+    "EntityDestroyed",
+    "InvalidValue",
+    "NotSupportedForTag",
+    "NotController",
+    # Introspection
+    "schema",
     # Envs submodule
     "envs",
 ]
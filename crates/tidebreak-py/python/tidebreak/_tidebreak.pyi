@@ -0,0 +1,1881 @@
+# Generated by crates/tidebreak-py/build.rs from src/lib.rs. Do not edit.
+
+from typing import Any, ClassVar, final
+
+import numpy as np
+import numpy.typing as npt
+
+
+@final
+class PyUniverse:
+    """Universe wrapper for Python."""
+    def __init__(self, width: float = 1024.0, height: float = 1024.0, depth: float = 256.0, base_resolution: float = 1.0, memory_budget: int | None = None, planar: bool = False) -> None:
+        """Create a new Universe.
+
+        With `memory_budget` (bytes), the octree regions least recently
+        stamped or queried are coarsened whenever the tree outgrows it; see
+        `evictions` in `debug_tree()`. With `planar`, cells split in X and Y
+        only and each spans the full depth, for surface-only scenarios.
+        """
+    @property
+    def tick(self) -> int:
+        """Get current tick."""
+    @property
+    def time(self) -> float:
+        """Get current simulation time."""
+    def __repr__(self) -> str: ...
+    def stamp_explosion(self, center: tuple[float, float, float], radius: float, intensity: float = 1.0) -> None:
+        """Apply an explosion stamp."""
+    def stamp_fire(self, center: tuple[float, float, float], radius: float, intensity: float = 1.0) -> None:
+        """Apply a fire stamp."""
+    def stamp_sonar_ping(self, center: tuple[float, float, float], radius: float, strength: float = 1.0) -> None:
+        """Apply a sonar ping stamp."""
+    def stamp_template(self, name: str, center: tuple[float, float, float], **params: Any) -> None:
+        """Apply a stamp from a named template, overriding its parameters.
+
+        Built-in templates are "explosion", "fire", "sonar_ping",
+        "depth_charge" and "volcanic_vent"; more can be added with
+        `load_stamp_templates`. Raises `ValueError` for an unknown template or
+        parameter.
+
+        # Example
+
+        ```python
+        universe.stamp_template("depth_charge", (0.0, 0.0, -40.0), radius=50.0)
+        ```
+        """
+    def load_stamp_templates(self, path: str) -> list[str]:
+        """Register stamp templates from a JSON file (or TOML, by extension),
+        replacing same-named ones. Returns the names loaded.
+
+        The file maps template names to templates; see `murk::template` for
+        the format.
+        """
+    @property
+    def stamp_templates(self) -> list[str]:
+        """Names of the registered stamp templates, sorted."""
+    def schedule_stamp(self, kind: str, center: tuple[float, float, float], radius: float, at_time: float, intensity: float = 1.0) -> int:
+        """Schedule a stamp to be applied once at simulation time `at_time`.
+
+        `kind` is "explosion", "fire" or "sonar_ping"; `intensity` is the
+        ping strength for sonar. Returns a schedule ID for `cancel_scheduled`.
+        """
+    def schedule_periodic(self, kind: str, center: tuple[float, float, float], radius: float, interval: float, count: int | None = None, intensity: float = 1.0) -> int:
+        """Schedule a stamp every `interval` seconds, first one interval from now.
+
+        `count` limits the number of applications (None repeats forever).
+        Returns a schedule ID for `cancel_scheduled`.
+
+        # Example
+
+        ```python
+        # Vent pulse every 30 s, forever
+        universe.schedule_periodic("fire", (100.0, 40.0, -50.0), 5.0, 30.0)
+        ```
+        """
+    def cancel_scheduled(self, id: int) -> bool:
+        """Cancel a scheduled stamp. Returns whether it was pending."""
+    @property
+    def pending_stamps(self) -> int:
+        """Number of pending stamp schedules."""
+    def freeze_region(self, min: tuple[float, float, float], max: tuple[float, float, float], fields: list[str]) -> None:
+        """Make fields inside a box immutable to stamps and propagation.
+
+        # Example
+
+        ```python
+        # Keep the coastline intact under shellfire
+        universe.freeze_region((-512.0, -512.0, -128.0), (0.0, 512.0, 128.0), ["occupancy", "depth"])
+        ```
+        """
+    def clear_frozen(self) -> None:
+        """Remove all frozen regions."""
+    def stats(self) -> dict[str, Any]:
+        """Octree size and usage counters, for capacity planning and
+        performance tracking.
+
+        Returns a dict with `node_count`, `leaf_count`, `max_depth`,
+        `nodes_by_depth` (list, root first), `memory_bytes`, `memory_budget`
+        (or `None`), `evictions`, `stamps_applied`, `queries_served`,
+        `nodes_visited`, `avg_nodes_visited` and `last_step_seconds` (or
+        `None` before the first step). Usage counters are not cleared by
+        `reset()` without a seed.
+        """
+    def debug_tree(self, max_depth: int = 3) -> dict[str, Any]:
+        """Describe the octree for debugging, e.g. why a region won't coarsen.
+
+        Returns a dict with `node_count`, `leaf_count`, `memory_bytes` (an
+        estimate), `memory_budget` (or `None`), `evictions` (subtrees
+        coarsened to fit the budget) and `root`, a nested node dict with keys `min`, `max`,
+        `depth`, `state` (`"empty"`, `"leaf"` or `"internal"`), `uniform`
+        (variance within the merge threshold), `subtree_nodes`, `stats`
+        (field name to `mean`/`variance`/`min`/`max`, or `None` if empty)
+        and `children`. Children are listed down to `max_depth`.
+        """
+    def query_point(self, position: tuple[float, float, float]) -> PyPointResult:
+        """Query a point."""
+    def query_volume(self, center: tuple[float, float, float], radius: float, resolution: str = "medium") -> PyQueryResult:
+        """Query a volume."""
+    def query_batch(self, queries: list[tuple[tuple[float, float, float], float]], resolution: str = "medium") -> list[PyQueryResult]:
+        """Query many volumes in one traversal of the octree.
+
+        `queries` is a list of `(center, radius)` pairs, all answered at the
+        same `resolution`. Returns one result per query, in order, equal to
+        calling `query_volume` for each.
+
+        ```python
+        near, far = universe.query_batch([((0.0, 0.0, 0.0), 10.0), ((50.0, 0.0, 0.0), 40.0)])
+        ```
+        """
+    def extract_isosurface(self, field: str, threshold: float, region_min: tuple[float, float, float] | None = None, region_max: tuple[float, float, float] | None = None) -> tuple[npt.NDArray[np.float32], npt.NDArray[np.uint32]]:
+        """Extract the surface where a field crosses a threshold.
+
+        The region defaults to the whole world. Returns `(vertices, indices)`
+        as numpy arrays of shape (N, 3) float32 and (M, 3) uint32; triangle
+        normals point toward values below the threshold.
+
+        # Example
+
+        ```python
+        universe.stamp_fire((0.0, 0.0, 0.0), 10.0)
+        vertices, indices = universe.extract_isosurface("smoke", 0.5)
+        ```
+        """
+    def extract_isolines(self, field: str, threshold: float, z: float, region_min: tuple[float, float, float] | None = None, region_max: tuple[float, float, float] | None = None) -> list[npt.NDArray[np.float32]]:
+        """Extract contour lines where a field crosses a threshold on the
+        horizontal slice at height `z`.
+
+        The region defaults to the whole world; only its x/y extent is used.
+        Returns a list of (N, 2) float32 arrays; closed loops end with their
+        first point.
+        """
+    def find_path(self, start: tuple[float, float, float], goal: tuple[float, float, float], draft: float = 0.0, resolution: float = 10.0, field_costs: list[tuple[str, float, float, float]] | None = None, threat: list[list[float]] | None = None, threat_min: tuple[float, float] | None = None, threat_cell_size: float | None = None, threat_weight: float = 1.0) -> list[tuple[float, float, float]] | None:
+        """Find a surface route for a vessel of `draft` meters from `start` to
+        `goal`, on a grid of cells about `resolution` meters wide.
+
+        Cells are blocked by occupancy of 0.5 or more, or by less than
+        `draft` meters of depth; unset depth counts as open water. Returns
+        the `(x, y, z)` waypoints from `start` to `goal`, or `None` if no
+        route exists. Drop the heights to sail it with `enable_convoy()`.
+
+        Without costs the route is the shortest. Each move's length is
+        otherwise multiplied by one plus the penalties of the cell entered:
+
+        - `field_costs`: `(field, zero, full, weight)` ramps, penalizing a
+          cell `weight` when the field reads `full` and nothing at `zero`.
+          `full` may be below `zero`, e.g. `("depth", 200.0, 20.0, 1.0)`
+          prefers deep water.
+        - `threat`: 2D array of penalties indexed `[y, x]`, such as a threat
+          heatmap, scaled by `threat_weight`. Its cells are `threat_cell_size`
+          square from the `threat_min` corner; by default the array spans
+          the world's width.
+
+        ```python
+        route = universe.find_path((0.0, 0.0, 0.0), (400.0, 250.0, 0.0), draft=6.0)
+        quiet = universe.find_path(
+            (0.0, 0.0, 0.0), (400.0, 250.0, 0.0), field_costs=[("noise", 60.0, 120.0, 5.0)]
+        )
+        ```
+        """
+    def step(self, dt: float) -> None:
+        """Advance simulation by dt seconds.
+
+        Releases the GIL during computation for better Python threading.
+        """
+    def reset(self, seed: int | None = None) -> None:
+        """Reset the universe, optionally with a seed for determinism.
+
+        If a seed is provided, the universe is recreated with that seed,
+        ensuring deterministic replay of all subsequent operations.
+
+        # Arguments
+
+        * `seed` - Optional seed for deterministic RNG initialization
+
+        # Example
+
+        ```python
+        universe = PyUniverse(width=100.0, height=100.0, depth=50.0)
+
+        # Reset with seed for deterministic replay
+        universe.reset(seed=42)
+
+        # Reset without seed (uses previous seed if one existed)
+        universe.reset()
+        ```
+        """
+    def observe_foveated(self, position: tuple[float, float, float], heading: tuple[float, float, float], shells: list[Any] | None = None, normalize: bool = False) -> npt.NDArray[np.float32]:
+        """Get foveated observation as numpy array.
+
+        Returns a flat array of field means for each sector in each shell.
+        Shape: (total_sectors * num_fields,)
+
+        # Arguments
+
+        * `position` - Agent position as (x, y, z) tuple
+        * `heading` - Agent heading direction as (x, y, z) tuple
+        * `shells` - Optional list of shell configurations as dicts with keys:
+          - `radius_inner`: Inner radius of shell
+          - `radius_outer`: Outer radius of shell
+          - `sectors`: Number of angular divisions
+        * `normalize` - Emit each field on its canonical scale (see
+          `Field.normalize`) instead of raw units
+
+        # Returns
+
+        A flat numpy array of f32 values with shape (total_sectors * num_fields,).
+        Default fields are: temperature, noise, occupancy, sonar_return.
+        Default shells are: (0-10, 16 sectors), (10-50, 8 sectors), (50-200, 4 sectors).
+
+        # Example
+
+        ```python
+        obs = universe.observe_foveated(
+            position=(0.0, 0.0, 0.0),
+            heading=(1.0, 0.0, 0.0),
+            shells=[
+                {"radius_inner": 0.0, "radius_outer": 10.0, "sectors": 8},
+                {"radius_inner": 10.0, "radius_outer": 50.0, "sectors": 4},
+            ],
+        )
+        ```
+        """
+    def __reduce__(self) -> tuple[type, tuple[()], bytes]:
+        """Pickle support: rebuilt with default arguments, then `__setstate__`."""
+    def __setstate__(self, state: bytes) -> None: ...
+
+
+@final
+class PyPointResult:
+    """Point query result wrapper."""
+    def get(self, field: Field | str) -> float:
+        """Get value for a field.
+
+        Accepts either a Field enum or a string for backwards compatibility.
+
+        # Examples
+
+        ```python
+        from tidebreak import Field
+
+        # Using enum (preferred)
+        temp = result.get(Field.TEMPERATURE)
+
+        # Using string (backwards compatible)
+        temp = result.get("temperature")
+        ```
+        """
+    @property
+    def depth(self) -> int:
+        """Get depth at which value was found."""
+    @property
+    def interpolated(self) -> bool:
+        """Whether value is interpolated."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class PyQueryResult:
+    """Volume query result wrapper."""
+    def mean(self, field: Field | str) -> float:
+        """Get mean value for a field.
+
+        Accepts either a Field enum or a string for backwards compatibility.
+
+        # Examples
+
+        ```python
+        from tidebreak import Field
+
+        # Using enum (preferred)
+        temp = result.mean(Field.TEMPERATURE)
+
+        # Using string (backwards compatible)
+        temp = result.mean("temperature")
+        ```
+        """
+    def normalized(self, field: Field | str) -> float:
+        """Get the mean of a field on its canonical normalized scale.
+
+        See `Field.normalize`. Accepts either a Field enum or a string.
+        """
+    def variance(self, field: Field | str) -> float:
+        """Get variance for a field.
+
+        Accepts either a Field enum or a string for backwards compatibility.
+        """
+    def min(self, field: Field | str) -> float:
+        """Get min value for a field.
+
+        Accepts either a Field enum or a string for backwards compatibility.
+        """
+    def max(self, field: Field | str) -> float:
+        """Get max value for a field.
+
+        Accepts either a Field enum or a string for backwards compatibility.
+        """
+    def percentile(self, field: Field | str, p: float) -> float:
+        """Estimate the value below which fraction `p` (0-1) of a field's
+        samples fall.
+        """
+    def p50(self, field: Field | str) -> float:
+        """Median of a field."""
+    def p90(self, field: Field | str) -> float:
+        """90th percentile of a field."""
+    def histogram(self, field: Field | str, buckets: int = 8) -> tuple[float, float, list[float]]:
+        """Histogram of a field as (min, max, counts) with `buckets`
+        equal-width buckets spanning the field's min and max.
+        """
+    @property
+    def coverage(self) -> float:
+        """Fraction (0-1) of the queried volume holding non-default data."""
+    @property
+    def nodes_visited(self) -> int:
+        """Get nodes visited."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class Field:
+    """Field enum for Python.
+
+    Represents the different scalar fields that can be queried or modified
+    in the spatial substrate. Using this enum provides IDE autocomplete
+    and type checking benefits over string-based field names.
+
+    # Python Usage
+
+    ```python
+    from tidebreak import Field
+
+    # Use enum values for type-safe field access
+    temp = result.mean(Field.TEMPERATURE)
+    noise = result.max(Field.NOISE)
+
+    # Enums can be used as dict keys
+    field_names = {Field.TEMPERATURE: "temp", Field.NOISE: "noise"}
+    ```
+    """
+    OCCUPANCY: ClassVar[Field]
+    """Solid vs empty space [0, 1]"""
+    MATERIAL: ClassVar[Field]
+    """Material type (encoded as float for storage uniformity)"""
+    INTEGRITY: ClassVar[Field]
+    """Structural integrity [0, 1]"""
+    TEMPERATURE: ClassVar[Field]
+    """Temperature in Kelvin [0, infinity)"""
+    SMOKE: ClassVar[Field]
+    """Smoke density [0, 1]"""
+    NOISE: ClassVar[Field]
+    """Acoustic noise level in dB [0, 200]"""
+    SIGNAL: ClassVar[Field]
+    """Generic signal field (configurable)"""
+    CURRENT_X: ClassVar[Field]
+    """Water current X component [-10, 10] m/s"""
+    CURRENT_Y: ClassVar[Field]
+    """Water current Y component [-10, 10] m/s"""
+    DEPTH: ClassVar[Field]
+    """Water depth in meters [0, 10000]"""
+    SALINITY: ClassVar[Field]
+    """Salinity in ppt [0, 50]"""
+    SONAR_RETURN: ClassVar[Field]
+    """Sonar return strength [0, 1]"""
+    @property
+    def unit(self) -> str:
+        """Unit symbol of the field's raw values (empty if dimensionless)."""
+    def normalize(self, value: float) -> float:
+        """Map a raw value onto the field's canonical scale: [0, 1], or [-1, 1]
+        for the signed current components.
+        """
+    def __int__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+
+@final
+class PyEntityId:
+    """Unique entity identifier exposed to Python."""
+    def __init__(self, value: int) -> None:
+        """Create an ID from its raw u64 value."""
+    @property
+    def value(self) -> int:
+        """Get the raw u64 value."""
+    @staticmethod
+    def namespaced(namespace: int, local: int) -> PyEntityId:
+        """Create an ID in a producer namespace (high 16 bits)."""
+    @property
+    def namespace(self) -> int:
+        """Producer namespace (high 16 bits)."""
+    @property
+    def local(self) -> int:
+        """ID within the namespace (low 48 bits)."""
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[type, tuple[int]]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+
+@final
+class PyEntityTag:
+    """Entity type classification for Python."""
+    Ship: ClassVar[PyEntityTag]
+    Platform: ClassVar[PyEntityTag]
+    Projectile: ClassVar[PyEntityTag]
+    Squadron: ClassVar[PyEntityTag]
+    def __int__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+
+@final
+class PyTransformState:
+    """Transform state (position and heading)."""
+    @property
+    def x(self) -> float:
+        """X position."""
+    @property
+    def y(self) -> float:
+        """Y position."""
+    @property
+    def heading(self) -> float:
+        """Heading in radians (CCW from +X)."""
+    @property
+    def position(self) -> tuple[float, float]:
+        """Get position as (x, y) tuple."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class PyPhysicsState:
+    """Physics state (velocity and limits)."""
+    @property
+    def vx(self) -> float: ...
+    @property
+    def vy(self) -> float: ...
+    @property
+    def angular_velocity(self) -> float: ...
+    @property
+    def max_speed(self) -> float: ...
+    @property
+    def max_turn_rate(self) -> float: ...
+    @property
+    def throttle(self) -> float | None:
+        """Ordered throttle fraction, or None under direct velocity control"""
+    @property
+    def ordered_heading(self) -> float | None:
+        """Heading the ship is still turning toward, if any"""
+    @property
+    def velocity(self) -> tuple[float, float]:
+        """Get velocity as (vx, vy) tuple."""
+    @property
+    def speed(self) -> float:
+        """Get current speed."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class PyCombatState:
+    """Combat state (HP and status)."""
+    @property
+    def hp(self) -> float: ...
+    @property
+    def max_hp(self) -> float: ...
+    @property
+    def weapon_count(self) -> int: ...
+    @property
+    def is_destroyed(self) -> bool: ...
+    @property
+    def is_mobility_disabled(self) -> bool: ...
+    @property
+    def shield(self) -> float | None:
+        """Shield pool, or None without mitigation"""
+    @property
+    def max_shield(self) -> float | None: ...
+    @property
+    def health_pct(self) -> float:
+        """Get health as percentage [0, 1]."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class PyEntity:
+    """Read-only view of an entity."""
+    @property
+    def id(self) -> PyEntityId:
+        """Entity ID."""
+    @property
+    def tag(self) -> PyEntityTag:
+        """Entity tag (type)."""
+    @property
+    def transform(self) -> PyTransformState:
+        """Transform state (always present)."""
+    @property
+    def physics(self) -> PyPhysicsState | None:
+        """Physics state (if entity has physics)."""
+    @property
+    def combat(self) -> PyCombatState | None:
+        """Combat state (if entity has combat)."""
+    @property
+    def team(self) -> int | None:
+        """Team the entity belongs to (None if neutral)."""
+    @property
+    def controller(self) -> str | None:
+        """Controller of the entity, such as "agent:0" (None if uncontrolled)."""
+    @property
+    def labels(self) -> list[str]:
+        """Labels carried by the entity, sorted."""
+    @property
+    def attributes(self) -> dict[str, bool | int | float | str]:
+        """Attribute store as a dict of bool, int, float or str values."""
+    @property
+    def name(self) -> str | None:
+        """Display name, e.g. "Resolute" (None if unnamed)."""
+    @property
+    def class_name(self) -> str | None:
+        """Class label, e.g. "Daring-class destroyer" (None if unset)."""
+    @property
+    def hull_number(self) -> str | None:
+        """Hull number or pennant, e.g. "D32" (None if unset)."""
+    @property
+    def color(self) -> tuple[int, int, int] | None:
+        """Display color as (r, g, b) in 0-255 (None if unset)."""
+    @property
+    def display_name(self) -> str:
+        """Name for viewers and logs: the name and hull number if set,
+        otherwise the tag and ID, such as "Ship 3".
+        """
+    @property
+    def mine_detected(self) -> bool | None:
+        """Whether a mine has been found by minesweeping; None if not a mine."""
+    @property
+    def signature(self) -> tuple[float, float, float] | None:
+        """Current signature as (radar_cross_section m², acoustic_level dB,
+        thermal), adjusted for speed, damage and emissions; None if not a
+        ship.
+        """
+    @property
+    def depth(self) -> float | None:
+        """Depth in meters below the surface; None if not a submarine."""
+    @property
+    def battery(self) -> float | None:
+        """Battery charge as a fraction of capacity; None if not a submarine."""
+    def is_ship(self) -> bool:
+        """Check if entity is a ship."""
+    def is_mine(self) -> bool:
+        """Check if entity is a mine."""
+    def is_destroyed(self) -> bool:
+        """Check if entity is destroyed."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class PySimulation:
+    """Main simulation orchestrator."""
+    def __init__(self, seed: int | None = None, config: Any | None = None, overrides: dict[str, Any] | None = None) -> None:
+        """Create a new simulation.
+
+        `config` tunes the run (see `tidebreak_core::config`): a path to a
+        TOML file, or a dict of overrides. `overrides` is applied on top of
+        it, so a scenario file can be swept from Python:
+
+            Simulation(config="frigates.toml", overrides={"physics.max_speed": 14.0})
+
+        Dict keys are section names with nested dicts ({"physics":
+        {"max_speed": 14.0}}) or dotted paths ("physics.max_speed"). The
+        config sets the physics timestep, decision interval, and the
+        physics and sensors of ships from `spawn_ship()`. `seed`, if given,
+        overrides the config's seed (default 42).
+
+        Raises InvalidValue for unknown keys or invalid values, and OSError
+        if the file cannot be read.
+        """
+    @property
+    def config_toml(self) -> str:
+        """The full tuning config of this simulation, as TOML."""
+    @staticmethod
+    def from_package(package: str) -> tuple[PySimulation, dict[str, PyEntityId]]:
+        """Build a simulation from a battle package (JSON, schema "arena.v1").
+
+        Returns the simulation, seeded with the package seed, and a dict
+        mapping each `ship_id` to its entity ID. Raises InvalidValue if the
+        package is malformed or fails validation.
+        """
+    @staticmethod
+    def fork_from(replay: Replay, tick: int) -> PySimulation:
+        """Start a simulation in the state a replay's run had just before
+        `tick`, to continue it with different actions.
+
+        The fork has the recorded seed, decision interval and timestep, no
+        plugins and the default config otherwise, which reproduces runs
+        driven from Python. Raises InvalidValue for a tick outside the
+        recording or if the run cannot be reproduced; for runs with plugins,
+        set up a matching simulation and use `Replay.seek` instead.
+
+        ```python
+        fork = tidebreak.Simulation.fork_from(replay, 640)
+        branch = replay.branch(640)
+        fork.apply_action(ship, {"heading": 1.57})
+        branch.record_step(fork)
+        ```
+        """
+    @property
+    def tick(self) -> int:
+        """Current tick number."""
+    @property
+    def seed(self) -> int:
+        """Master seed."""
+    @property
+    def entity_count(self) -> int:
+        """Number of entities in the arena."""
+    @property
+    def state_hash(self) -> int:
+        """Hash of the arena state; two simulations with equal hashes are in
+        the same state.
+        """
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> tuple[type, tuple[int], bytes]:
+        """Pickle support: rebuilt from the seed, then `__setstate__`.
+
+        The state covers the arena, the commands held between decisions,
+        scores, heatmap and kill tallies, and everything `reset()` keeps, so
+        an unpickled simulation steps exactly like the original. An open
+        battle log, the RNG audit and event history entries, and stacked
+        observation frames are not carried over.
+        """
+    def __setstate__(self, state: bytes) -> None: ...
+    def step(self) -> None:
+        """Execute one simulation step.
+
+        Releases the GIL during execution for better Python threading.
+        """
+    @property
+    def action_interval(self) -> int:
+        """Physics ticks per agent decision.
+
+        Plugins run only on decision ticks; in between, their velocity and
+        heading commands are repeated. Setting 0 or 1 decides every tick.
+        """
+    @action_interval.setter
+    def action_interval(self, value: int) -> None: ...
+    @property
+    def is_decision_tick(self) -> bool:
+        """Whether the current state is a decision point."""
+    def step_decision(self) -> int:
+        """Step to the next decision point, returning the number of ticks run.
+
+        Releases the GIL during execution.
+
+        ```python
+        sim.action_interval = 12  # 60 Hz physics, 5 Hz decisions
+        while True:
+            obs = sim.get_observation(ship_id)
+            sim.apply_action(ship_id, policy(obs))
+            sim.step_decision()
+        ```
+        """
+    def step_async(self, ticks: int = 1, cancel: PyCancellationToken | None = None) -> Any:
+        """Run `ticks` steps in the loop's default executor, returning an awaitable.
+
+        Must be called from a running asyncio event loop, which stays free
+        while the ticks execute. The awaitable resolves to the number of
+        ticks completed. Setting `cancel` (or cancelling the awaiting task)
+        stops the run at the next tick boundary or before the current tick's
+        resolution; an abandoned tick leaves the state untouched.
+
+        While the run is in progress the simulation is borrowed; other calls
+        on it raise RuntimeError.
+
+        ```python
+        token = tidebreak.CancellationToken()
+        done = await sim.step_async(ticks=100, cancel=token)
+        ```
+        """
+    def run_batch(self, seeds: list[int], policy: Any, max_ticks: int, parallel: bool = False) -> list[Any]:
+        """Run one episode per seed from the current state, calling `policy` at
+        decision points, and return a result dict per episode in seed order.
+
+        Each episode starts from a copy of this simulation's arena under its
+        seed, set up as `reset(seed)` would (bounds, scoring, plugins and
+        league opponent); proximity triggers stay with this simulation. It
+        runs for `max_ticks` ticks. At each decision point `policy(episode)`
+        is called with the episode's Simulation to read observations and
+        apply actions; a truthy return value ends the episode early. Ticks
+        between decisions run with the GIL released, and an exception from
+        the policy aborts the batch.
+
+        With `parallel`, episodes advance in lockstep: the policy is called
+        for every running episode in seed order, then all of them step to
+        their next decision point on a thread pool. Results are the same
+        either way unless the policy carries state between episodes.
+
+        Each result has "seed", "ticks", "decisions", "terminated" (ended by
+        the policy), "scores" (as from `scores()`) and "survivors" (IDs of
+        the ships not destroyed).
+
+        ```python
+        def policy(episode):
+            obs = episode.get_observation(ship_id)
+            episode.apply_action(ship_id, act(obs))
+
+        results = sim.run_batch(range(1000), policy, max_ticks=3000)
+        ```
+        """
+    def add_proximity_trigger(self, radii: list[float], observers: list[PyEntityTag] | None = None, targets: list[PyEntityTag] | None = None, team: str = "any") -> None:
+        """Report entities crossing the distances in `radii`.
+
+        Every entity with one of the `observers` tags (default: ships)
+        watches the other entities, optionally limited to the `targets`
+        tags and to `team` ("any", "friendly" or "hostile"; unteamed
+        entities are hostile). Crossings are read with `proximity_events()`.
+        Triggers survive `reset()`.
+        """
+    def proximity_events(self) -> list[Any]:
+        """Proximity crossings of the last step as a list of dicts with keys
+        "kind" ("entered" or "left"), "observer", "target" and "radius".
+
+        Does not drain the step's events.
+        """
+    def enable_order_following(self, arrival_radius: float = ..., engage_range: float = ...) -> None:
+        """Make ships and squadrons carry out their standing orders.
+
+        Each step, an entity with a standing order steers toward it:
+        "move_to" stops within `arrival_radius` of the destination,
+        "engage" closes to `engage_range` of its own track of the target,
+        and "screen" keeps station on the protected entity. Survives
+        `reset()`. Raises RuntimeError if already enabled.
+        """
+    def enable_convoy(self, route: list[tuple[float, float]], label: str = ..., arrival_radius: float = ..., cruise_throttle: float = ..., scatter_ticks: int = ...) -> None:
+        """Sail the ships labelled `label` (default: merchants from
+        `spawn_merchant()`) along `route`, a list of `(x, y)` waypoints.
+
+        Each merchant takes the waypoints in order at `cruise_throttle`,
+        counting one as reached within `arrival_radius`. When any merchant
+        of a team is hit, that team's convoy scatters away from its center
+        at full speed for `scatter_ticks`, then resumes the route. A
+        merchant reaching the last waypoint stops and counts as delivered
+        (see `enable_scoring(delivery=...)`). Call once per convoy label;
+        survives `reset()`.
+        """
+    def set_objectives(self, zones: list[tuple[float, float, float]] | None = None, convoy_label: str | None = None, mission_ticks: int | None = None) -> None:
+        """Set the scenario objectives reported in the objectives block of
+        observations (see `PyObservation.objectives`).
+
+        `zones` lists `(x, y, radius)` zones to hold, `convoy_label` the
+        label of the convoy ships to protect or hunt (e.g. "merchant"), and
+        `mission_ticks` the tick the mission timer runs out at. Replaces
+        any objectives set before; survives `reset()`. Raises InvalidValue
+        for a non-positive radius or a zero mission length.
+        """
+    def enable_league(self, league: Any, team: int) -> None:
+        """Play `team` with frozen opponents drawn from a league.
+
+        `league` is a path to a league TOML file or an equivalent dict (see
+        `tidebreak_core::league`): a selection rule ("round_robin",
+        "uniform" or "pfsp") and a list of named opponents with scripted
+        policy parameters. An opponent is drawn now and on every `reset()`,
+        from the seed and league state only, so seeded runs replay the same
+        opponents. Report each episode's outcome with
+        `record_league_result()`. Raises InvalidValue for a malformed
+        league, OSError if the file cannot be read, and RuntimeError if a
+        league is already enabled.
+        """
+    @property
+    def league_opponent(self) -> str | None:
+        """Name of this episode's league opponent, or None without a league."""
+    def record_league_result(self, result: float) -> None:
+        """Record the learner's result against this episode's opponent: 1 for
+        a win, 0.5 for a draw, 0 for a loss.
+
+        Later PFSP draws weight opponents by these results. Raises
+        RuntimeError without a league, and InvalidValue for a result
+        outside 0-1.
+        """
+    @property
+    def league_toml(self) -> str | None:
+        """The league with its episode count and results, as TOML, or None
+        without a league. Pass a saved copy to `enable_league()` to resume.
+        """
+    def spawn_merchant(self, x: float, y: float, heading: float = 0.0, team: int | None = None, label: str = ..., id: PyEntityId | None = None) -> PyEntityId:
+        """Spawn an unarmed, slow, tough merchant ship labelled `label`,
+        optionally assigned to a team.
+        """
+    def order_move_to(self, commander: PyEntityId, subordinate: PyEntityId, x: float, y: float, speed: float = 0.0) -> bool:
+        """Order `subordinate` to `(x, y)` at `speed` m/s (0 = max speed).
+
+        Replaces the subordinate's standing order. Returns False, issuing
+        nothing, unless both entities exist, differ and share a team.
+        """
+    def order_engage(self, commander: PyEntityId, subordinate: PyEntityId, target: PyEntityId) -> bool:
+        """Order `subordinate` to engage `target`; see `order_move_to`."""
+    def order_screen(self, commander: PyEntityId, subordinate: PyEntityId, protect: PyEntityId, distance: float, bearing: float = 0.0) -> bool:
+        """Order `subordinate` to screen `protect` from `distance` meters at
+        `bearing` radians off its heading; see `order_move_to`.
+        """
+    def cancel_order(self, commander: PyEntityId, subordinate: PyEntityId) -> bool:
+        """Withdraw the standing order of `subordinate`; see `order_move_to`."""
+    def standing_order(self, entity_id: PyEntityId) -> dict[str, Any] | None:
+        """Standing order of an entity as a dict, or None.
+
+        Keys are "kind" ("move_to", "engage" or "screen"), "commander" and
+        "issued_tick", plus "x", "y" and "speed" for move_to, "target" for
+        engage, and "protect", "distance" and "bearing" for screen.
+        """
+    def apply_wreckage(self, universe: PyUniverse, spawn_wrecks: bool = False) -> list[PyEntityId]:
+        """Stamp this tick's damage, destruction and shell splash events into
+        `universe`.
+
+        Damage scorches the target's surroundings; destroyed ships leave an
+        explosion, a debris field and a burning wreck that persist in the
+        universe; gun rounds that miss raise spray where they land. With
+        `spawn_wrecks`, a platform labelled "wreck" is spawned at each
+        destroyed ship. Drains the events of the last step.
+
+        Returns the IDs of spawned wrecks.
+        """
+    def apply_smoke(self, universe: PyUniverse, z: float = 0.0) -> None:
+        """Write the smoke screens laid by ships into `universe`.
+
+        Raises the Smoke field inside each cloud to the cloud's density at
+        altitude `z`; call after each step to keep the universe in sync.
+        """
+    def apply_trigger_stamps(self, universe: PyUniverse, z: float = 0.0) -> None:
+        """Write the stamps of scenario triggers that fired since the last call
+        into `universe`, at altitude `z`; call after each step or decision.
+        """
+    def fired_triggers(self) -> dict[str, int]:
+        """Names of the scenario triggers that have fired, mapped to the tick
+        their effects first showed in.
+        """
+    @property
+    def episode_ended(self) -> int | None:
+        """Tick a scenario trigger ended the episode in, or None."""
+    def rules_of_engagement(self, team: int) -> str:
+        """A team's rules of engagement: "weapons_free" or "hold_fire"."""
+    def set_rules_of_engagement(self, team: int, roe: str) -> None:
+        """Set a team's rules of engagement ("weapons_free" or "hold_fire").
+
+        Ships of a team holding fire cannot fire. Raises InvalidValue for
+        other names.
+        """
+    def open_battle_log(self, directory: str, batch_ticks: int = 64, entity_summary_every: int = 1, fog_of_war: bool = False) -> None:
+        """Start streaming the battle log (Arrow IPC files) into `directory`.
+
+        Any log already open is closed first. Raises OSError if the files
+        cannot be created.
+
+        With `fog_of_war=True` (evaluation mode) the log also writes each
+        team's believed tracks to "tracks.arrows", per-team sensor metrics
+        (position error, track continuity, time to detect) to "fog.arrows"
+        and track association metrics (OSPA, track purity, fragmentation) to
+        "association.arrows", to compare against the ground truth in
+        "entities.arrows".
+        """
+    def close_battle_log(self) -> None:
+        """Flush and close the battle log, if open.
+
+        Raises OSError if writing the log failed at any point.
+        """
+    @property
+    def has_battle_log(self) -> bool:
+        """Whether a battle log is being written."""
+    def spawn_ship(self, x: float, y: float, heading: float = 0.0, team: int | None = None, id: PyEntityId | None = None, submarine: bool = False) -> PyEntityId:
+        """Spawn a ship at the given position, optionally assigned to a team.
+
+        `submarine` spawns a surfaced submarine with a full battery, which
+        dives with the "depth" action.
+
+        `id` spawns with an explicit ID (e.g. when merging content produced
+        in another ID namespace); raises InvalidValue if it is already in use.
+        """
+    @property
+    def id_namespace(self) -> int:
+        """Namespace stamped on automatically assigned entity IDs.
+
+        Give each process generating scenario content its own namespace so
+        their entities can be merged without ID collisions. 0 (the default)
+        yields plain sequential IDs.
+        """
+    @id_namespace.setter
+    def id_namespace(self, value: int) -> None: ...
+    def spawn_depot(self, x: float, y: float, fuel: float = 5000.0, ammo: dict[str, int] | None = None, team: int | None = None, id: PyEntityId | None = None) -> PyEntityId:
+        """Spawn a stationary supply depot holding `fuel` and `ammo`.
+
+        `ammo` maps ammunition type names ("bullet", "missile", "torpedo",
+        "shell", "depth_charge", "countermeasure", "smoke") to round counts. Raises
+        InvalidValue for an unknown type or negative fuel.
+        """
+    def get_inventory(self, entity_id: PyEntityId) -> tuple[float, dict[str, int]] | None:
+        """Fuel and ammunition held by a ship or depot.
+
+        Returns (fuel, {ammo_type: rounds}), or None if the entity does not
+        exist or holds no supplies.
+        """
+    def transfer_cargo(self, from_id: PyEntityId, to_id: PyEntityId, cargo: str, amount: float) -> bool:
+        """Request a transfer of `amount` of `cargo` from one entity to another.
+
+        `cargo` is "fuel" or an ammunition type name (see `spawn_depot`). The
+        transfer completes once both entities have stayed within range and
+        speed-matched for the required number of ticks, moving as much as
+        the giver holds and the receiver has room for.
+
+        Returns False if the same kind of cargo is already pending between
+        the two entities. Raises UnknownEntity, NotSupportedForTag (entity
+        holds no supplies) or InvalidValue (unknown cargo, non-positive
+        amount, or a transfer to itself).
+        """
+    def set_team(self, entity_id: PyEntityId, team: int | None = None) -> bool:
+        """Assign an entity to a team (None makes it neutral).
+
+        Returns False if the entity does not exist.
+        """
+    def set_metadata(self, entity_id: PyEntityId, name: str | None = None, class_name: str | None = None, hull_number: str | None = None, color: tuple[int, int, int] | None = None) -> bool:
+        """Set an entity's cosmetic name, class label, hull number and
+        (r, g, b) color, replacing any it had; omitted fields are cleared.
+
+        Returns False if the entity does not exist.
+        """
+    def set_mitigation(self, entity_id: PyEntityId, capacity: float, regen_rate: float, regen_delay: float = 3.0, kinetic: float = 0.0, explosive: float = 0.0, energy: float = 0.0) -> None:
+        """Give a ship or squadron a regenerating shield, absorbing damage
+        before its hull.
+
+        Resistances are fractions in [0, 1] removed from each damage type
+        before the shield. The shield starts full and regenerates at
+        `regen_rate` per second once `regen_delay` seconds pass without a hit.
+        """
+    def clear_mitigation(self, entity_id: PyEntityId) -> None:
+        """Remove an entity's shield, so damage goes straight to its hull."""
+    def set_controller(self, entity_id: PyEntityId, controller: str | None = None) -> bool:
+        """Hand control of an entity to `controller` (None releases it).
+
+        Controllers are written "human:N", "scripted:N" or "agent:N". Once
+        set, only that controller's `apply_action(..., controller=...)`
+        calls may command the entity. Returns False if the entity does not
+        exist; raises InvalidValue for a malformed controller.
+        """
+    def set_steering_assist(self, controller: str | None = None, horizon: float = 30.0, range: float = 1000.0, margin: float = 20.0, blend: float = 1.0) -> None:
+        """Opt the entities of `controller` into collision-avoidance steering.
+
+        `controller` is written like in `set_controller`; None covers
+        entities without a controller, such as scripted traffic. Each tick,
+        their commanded motion is blended with the velocity change that
+        keeps them `margin` meters clear of hulls within `range` meters over
+        the next `horizon` seconds, weighted by `blend` (0 to 1). Throttled
+        ships are turned within their turning limits; others have their
+        velocity adjusted. Raises InvalidValue for a malformed controller or
+        a negative or non-finite setting.
+        """
+    def clear_steering_assist(self, controller: str | None = None) -> None:
+        """Turn collision-avoidance steering off for `controller` (None for
+        entities without a controller).
+        """
+    def controlled_entities(self, controller: str) -> list[PyEntityId]:
+        """IDs of all entities under `controller`, sorted by ID."""
+    def add_label(self, entity_id: PyEntityId, label: str) -> bool:
+        """Add a label to an entity. Returns False if the entity does not exist."""
+    def remove_label(self, entity_id: PyEntityId, label: str) -> bool:
+        """Remove a label from an entity. Returns False if it was not labelled."""
+    def query_by_label(self, label: str) -> list[PyEntityId]:
+        """IDs of all entities carrying `label`, sorted by ID."""
+    def set_attribute(self, entity_id: PyEntityId, key: str, value: bool | int | float | str | None = None) -> bool:
+        """Set an entity attribute (bool, int, float or str); None removes it.
+
+        Returns False if the entity does not exist.
+        """
+    def get_attribute(self, entity_id: PyEntityId, key: str) -> bool | int | float | str | None:
+        """Get an entity attribute, or None if the entity or key is missing."""
+    def configure_comms(self, range: float | None = None, latency_ticks: int | None = None, max_intent_len: int | None = None) -> None:
+        """Configure the intent channel.
+
+        Omitted arguments keep their current value.
+        """
+    def add_jammer(self, x: float, y: float, radius: float) -> None:
+        """Add a circular jamming zone that blocks intent delivery."""
+    def clear_jammers(self) -> None:
+        """Remove all jamming zones."""
+    def broadcast_intent(self, entity_id: PyEntityId, intent: list[float]) -> None:
+        """Broadcast an intent vector from an entity for the current tick.
+
+        Raises ValueError if the intent is longer than `max_intent_len`.
+        """
+    def get_entity(self, id: PyEntityId) -> PyEntity | None:
+        """Get entity by ID."""
+    def entity_ids(self) -> list[PyEntityId]:
+        """Get all entity IDs."""
+    def query_radius(self, x: float, y: float, radius: float) -> list[PyEntityId]:
+        """Query entities within radius."""
+    def query_knearest(self, x: float, y: float, k: int, tag: PyEntityTag | None = None) -> list[PyEntityId]:
+        """Query the `k` entities nearest to (x, y), nearest first.
+
+        With `tag`, only entities of that type are returned. Equal distances
+        are ordered by entity ID.
+        """
+    def query_segment(self, start: tuple[float, float], end: tuple[float, float], width: float = 0.0) -> list[PyEntityId]:
+        """Query entities whose hull intersects the segment `start`..`end`
+        swept with `width`, ordered by distance from `start`.
+        """
+    def despawn(self, id: PyEntityId) -> bool:
+        """Despawn an entity."""
+    def attach(self, child_id: PyEntityId, parent_id: PyEntityId, offset: tuple[float, float] = ..., heading: float = 0.0, cascade: bool = True) -> bool:
+        """Attach `child_id` to `parent_id` so it rides on the parent.
+
+        `offset` is the child's position in the parent's frame (+x ahead,
+        +y to port) and `heading` its heading relative to the parent's. The
+        child is moved onto the parent at once and follows it every tick.
+        With `cascade` it is despawned with the parent; otherwise it is
+        released. Returns False if either entity does not exist or the link
+        would form a cycle.
+        """
+    def detach(self, child_id: PyEntityId) -> bool:
+        """Release an attached entity where it is. Returns False if it was not
+        attached.
+        """
+    def parent_of(self, child_id: PyEntityId) -> PyEntityId | None:
+        """ID of the entity `child_id` is attached to, or None."""
+    def children_of(self, parent_id: PyEntityId) -> list[PyEntityId]:
+        """IDs of the entities attached directly to `parent_id`, sorted by ID."""
+    def set_ttl(self, entity_id: PyEntityId, ticks: int) -> bool:
+        """Despawn `entity_id` `ticks` ticks from now, emitting an
+        "entity_expired" event, replacing any earlier schedule.
+
+        The schedule is part of the simulation state, so unlike despawns
+        timed from Python it is snapshotted and replayed exactly. Returns
+        False if the entity does not exist.
+        """
+    def clear_ttl(self, entity_id: PyEntityId) -> bool:
+        """Cancel a scheduled despawn. Returns False if none was scheduled."""
+    def expiry(self, entity_id: PyEntityId) -> int | None:
+        """Tick at which `entity_id` is scheduled to be despawned, or None."""
+    def reset(self, seed: int | None = None) -> None:
+        """Reset simulation with optional new seed."""
+    def set_contact_sort_key(self, sort_key: str) -> None:
+        """Set the contact ordering used by observations.
+
+        `sort_key` is "distance" (nearest first, the default), "threat"
+        (most threatening first), "quality" (best track first) or "track"
+        (the first tracks in track table order, as observations were laid
+        out before contacts were ranked).
+        """
+    def set_bounds(self, min: tuple[float, float], max: tuple[float, float], policy: str = "clamp") -> None:
+        """Confine moving entities to the rectangle `min`..`max`.
+
+        `policy` decides what happens at the edges: "clamp" (stop at the
+        edge), "bounce" (reflect), "wrap" (re-enter opposite) or "despawn"
+        (remove, with an `EntityOutOfBounds` event). Bounds survive
+        `reset()`.
+        """
+    def clear_bounds(self) -> None:
+        """Remove the world bounds."""
+    def add_geofence(self, entity_id: PyEntityId, circle: tuple[float, float, float] | None = None, polygon: list[tuple[float, float]] | None = None, policy: str = "clamp") -> int:
+        """Keep an entity out of an area, returning the fence's index among
+        the entity's fences.
+
+        Give either `circle` as `(x, y, radius)` or `polygon` as a list of
+        at least three `(x, y)` corners. After each step, an entity inside
+        one of its fences is handled by `policy`: "clamp" (push back to the
+        edge, keeping velocity along it) or "reject" (undo the step's move
+        and stop). Each entry is reported by `geofence_violations()`.
+        Fences are dropped with their entity and on `reset()`.
+        """
+    def clear_geofences(self, entity_id: PyEntityId) -> None:
+        """Remove every geofence of an entity."""
+    def geofence_violations(self) -> list[Any]:
+        """Geofence entries of the last step as a list of dicts with keys
+        "entity", "fence" (index from `add_geofence`) and "depth" (meters
+        the entity would have been inside).
+
+        Does not drain the step's events.
+        """
+    def sample_currents(self, universe: PyUniverse, spacing: float = 50.0, z: float = 0.0, ship: float = 1.0, projectile: float = 0.2, squadron: float = 0.0) -> None:
+        """Drift entities with the sea currents of `universe`.
+
+        Samples CurrentX/CurrentY across the universe at altitude `z`, at
+        most `spacing` apart. Each tick, ships, projectiles and squadrons
+        are carried by the current at their position times `ship`,
+        `projectile` or `squadron`. The snapshot is not updated as the
+        universe evolves; call again to refresh it. Currents survive
+        `reset()`.
+        """
+    def clear_currents(self) -> None:
+        """Stop drifting entities with sea currents."""
+    def set_wind(self, speed: float, direction: float) -> None:
+        """Blow a uniform wind of `speed` m/s toward `direction` (radians,
+        like a heading).
+
+        Each tick the wind carries smoke clouds laid by ships downwind at
+        its full speed, and entities given a windage with `set_windage`
+        at that fraction of it. The wind survives `reset()`.
+        """
+    def clear_wind(self) -> None:
+        """Stop the wind."""
+    @property
+    def wind(self) -> tuple[float, float] | None:
+        """Wind velocity as (vx, vy) in m/s, or None if there is no wind."""
+    def set_windage(self, entity_id: PyEntityId, windage: float) -> bool:
+        """Let the wind drift an entity at `windage` times the wind speed
+        (about 0.04 for a liferaft, 0.08 for a floating decoy; 0 to stop).
+
+        Returns False if the entity does not exist or `windage` is negative.
+        """
+    def windage(self, entity_id: PyEntityId) -> float:
+        """Fraction of the wind speed an entity drifts at (0 if none)."""
+    def sample_smoke(self, universe: PyUniverse, spacing: float = 50.0, z: float = 0.0) -> None:
+        """Obscure visual sighting lines with the smoke of `universe`.
+
+        Samples Smoke across the universe at altitude `z`, at most
+        `spacing` apart. Like currents, the snapshot is not updated as the
+        universe evolves and survives `reset()`.
+        """
+    def clear_smoke(self) -> None:
+        """Remove the smoke snapshot."""
+    def sample_occupancy(self, universe: PyUniverse, spacing: float = 50.0, z: float = 0.0) -> None:
+        """Shelter entities from blasts behind the solid cells of `universe`.
+
+        Samples Occupancy across the universe at altitude `z`, at most
+        `spacing` apart; cells at or above 0.8 block area damage. Like
+        smoke, the snapshot is not updated as the universe evolves and
+        survives `reset()`.
+        """
+    def clear_occupancy(self) -> None:
+        """Remove the occupancy snapshot."""
+    def set_clock(self, hour: float, day_length: float = 86400.0, day: int = 0, moon_phase: float = 0.0) -> None:
+        """Start the world clock at `hour` (0-24) of `day` at tick 0.
+
+        `day_length` is the length of a day in seconds; shorten it to cycle
+        through day and night faster. `moon_phase` is the phase of the moon
+        at tick 0 (0 new, 0.5 full). Darkness shortens visual range, less so
+        under a bright moon.
+        """
+    @property
+    def time_of_day(self) -> float:
+        """Current hour of the world clock (0-24)."""
+    @property
+    def clock(self) -> dict[str, Any]:
+        """Current reading of the world clock as a dict with `day`, `hour`,
+        `phase` ("night", "dawn", "day" or "dusk"), `daylight`,
+        `moon_phase`, `moon_illumination` and `light`.
+        """
+    @property
+    def sea_state(self) -> int:
+        """Sea state (0 calm to 9 phenomenal); rough seas shorten visual range."""
+    @sea_state.setter
+    def sea_state(self, value: int) -> None: ...
+    @property
+    def bounds(self) -> tuple[tuple[float, float], tuple[float, float], str] | None:
+        """World bounds as ((min_x, min_y), (max_x, max_y), policy), or None."""
+    def set_plugin_budget(self, limit_ms: float, max_overruns: int = 3) -> None:
+        """Limit each plugin run to `limit_ms` milliseconds.
+
+        A plugin instance (entity + plugin) that exceeds the limit
+        `max_overruns` times is disabled and a `PluginBudgetExceeded` event
+        is emitted. The budget survives `reset()`.
+
+        Budgets measure wall-clock time, so the run is no longer
+        deterministic; `Replay` refuses to record or seek a simulation with a
+        budget or disabled plugins.
+        """
+    def clear_plugin_budget(self) -> None:
+        """Remove the plugin budget. Disabled plugins stay disabled."""
+    def plugin_timings(self) -> list[Any]:
+        """Plugin timings as a list of dicts with keys "entity_id", "plugin",
+        "last_ms", "max_ms", "mean_ms", "runs", "overruns" and "disabled".
+        """
+    def enable_plugin(self, entity_id: PyEntityId, plugin: str) -> bool:
+        """Re-enable a plugin disabled by the budget. Returns True if it was
+        disabled.
+        """
+    def enable_rng_audit(self, capacity: int = ...) -> None:
+        """Record every random draw, keeping the last `capacity` of them.
+
+        Clears draws already recorded. Auditing survives `reset()`, which
+        starts a fresh log.
+        """
+    def disable_rng_audit(self) -> None:
+        """Stop recording random draws and drop the recorded ones."""
+    def rng_draws(self, clear: bool = False) -> list[Any]:
+        """Recorded random draws, oldest first, as a list of dicts with keys
+        "sequence", "tick", "stream", "purpose" and "value".
+
+        Compare the lists of two runs to find the first draw where they
+        diverge. With `clear=True` the draws are removed from the log.
+        """
+    def enable_event_history(self, capacity: int) -> None:
+        """Keep the last `capacity` events across steps for `query_events()`.
+
+        Clears events already kept; a capacity of 0 stops keeping events.
+        The history survives `reset()`, which starts it afresh.
+        """
+    def query_events(self, kind: str | None = None, entity: PyEntityId | None = None, source: PyEntityId | None = None, target: PyEntityId | None = None, tick_range: tuple[int, int] | None = None, trace_id: int | None = None, format: str = "dicts") -> Any:
+        """Search the event history (see `enable_event_history()`).
+
+        Every given filter must match:
+        - `kind`: event name, e.g. "DamageDealt" or "damage_dealt"
+        - `entity`: entity named by the event in any role
+        - `source`: entity whose plugin emitted the event
+        - `target`: entity the event acts upon
+        - `tick_range`: (start, end) ticks, end exclusive
+        - `trace_id`: causal trace the event belongs to
+
+        With `format="dicts"` (default) returns a list of dicts with keys
+        "tick", "trace_id", "kind", "entity", "other", "value",
+        "weapon_slot" and "quality", the columns of the battle log's
+        `events.arrows`. With `format="arrow"` returns a `pyarrow.Table`
+        with the same columns.
+        """
+    def apply_action(self, entity_id: PyEntityId, action: dict[str, Any], controller: str | None = None) -> None:
+        """Apply an action dict to an entity.
+
+        Action dict can contain:
+        - "velocity": (vx, vy) tuple, clamped to the ship's max speed
+        - "heading": float in radians; under throttle, reached over the
+          following ticks at the turn rate the ship's speed allows
+        - "throttle": float fraction of max speed, -1 (astern) to 1 (ahead),
+          reached over the following ticks at the ship's engine rates
+
+        - "depth": float ordered depth in meters (submarines only), reached
+          over the following ticks at the boat's dive rate
+
+        "velocity" takes direct control and releases any throttle; it is
+        ignored when "throttle" is given in the same action.
+
+        Pass `controller` (e.g. "agent:0") when acting for one player or
+        agent: the action is then only accepted for entities it controls
+        (see `set_controller`).
+
+        Raises a `CommandError` subclass if the action is rejected:
+        `UnknownEntity`, `EntityDestroyed`, `NotSupportedForTag` (only ships
+        accept actions), `NotController` (the entity belongs to another
+        controller) or `InvalidValue` (malformed or non-finite values, or
+        "depth" for a surface ship).
+        Nothing is applied when an error is raised.
+        """
+    def lay_mine(self, entity_id: PyEntityId, fuze: str = "proximity", threshold: float = 0.0, trigger_radius: float = 50.0, damage: float = 80.0, blast_radius: float = 75.0) -> PyEntityId:
+        """Lay a mine at a ship's position, on the ship's team.
+
+        `fuze` is "proximity" (any enemy ship within `trigger_radius`),
+        "acoustic" (enemy ships moving at `threshold` m/s or faster) or
+        "magnetic" (enemy hulls of radius `threshold` m or more). The mine
+        deals `damage` to every ship within `blast_radius` when it goes off,
+        and stays hidden from sensors until swept by sonar.
+
+        Returns the mine's ID. Raises the same CommandError subclasses as
+        `apply_action`, and InvalidValue for an unknown fuze or a negative
+        parameter.
+        """
+    def deploy_smoke(self, entity_id: PyEntityId, duration: int = 600) -> bool:
+        """Lay a smoke screen astern of a ship for `duration` ticks.
+
+        Uses one "smoke" charge from the ship's inventory. Clouds block
+        visual sighting and weapons hold fire through them until they
+        disperse, drifting with the wind (see `set_wind`). Returns False if the ship has no smoke left, is already
+        laying smoke or is submerged. Raises the same CommandError
+        subclasses as `apply_action`.
+        """
+    def add_weapon(self, entity_id: PyEntityId, ammo: str = "shell", cooldown: float = 1.0, magazine_size: int = 0, reload_ticks: int = 0, burst_size: int = 1, gun: dict[str, Any] | None = None) -> int:
+        """Mount a weapon on a ship and return its slot.
+
+        `magazine_size` of 0 gives a cooldown-only weapon. Otherwise each
+        shot expends `burst_size` rounds, and an empty magazine takes
+        `reload_ticks` ticks to refill.
+
+        `gun`, a dict of ballistics overriding a 5-inch gun's ("damage",
+        "max_range", "muzzle_velocity", "dispersion", "lead_error",
+        "hit_radius"; `{}` for the defaults), makes the weapon a gun whose
+        rounds hit or splash the moment it fires, with no projectile
+        entity. Raises the same CommandError subclasses as `apply_action`,
+        and InvalidValue for an unknown ammunition type, a negative
+        cooldown or an unknown ballistics key.
+        """
+    def reload_weapon(self, entity_id: PyEntityId, slot: int) -> bool:
+        """Start reloading a ship's weapon.
+
+        Returns False if the weapon has no magazine, is already reloading or
+        is full. Raises the same CommandError subclasses as `apply_action`,
+        and InvalidValue for an unknown slot.
+        """
+    def validate_action(self, entity_id: PyEntityId, action: dict[str, Any], controller: str | None = None) -> BaseException | None:
+        """Check an action dict without applying it.
+
+        Returns None if `apply_action` would accept it, otherwise the
+        `CommandError` it would raise (not raised). Useful for building
+        action masks.
+        """
+    def evaluate_balance(self) -> list[Any]:
+        """Per-team strength estimate of the current state.
+
+        Returns a list of dicts sorted by team, with keys "team", "units",
+        "hp", "firepower", "positioning", "score" and "win_probability".
+        Win probabilities sum to 1 across teams, so a curriculum scheduler
+        can truncate episodes once one team passes a threshold.
+        """
+    def enable_scoring(self, kills: dict[str, float] | None = None, losses: dict[str, float] | None = None, zones: list[tuple[float, float, float, float]] | None = None, delivery: float = 0.0) -> None:
+        """Keep per-team mission scores, replacing any kept so far.
+
+        `kills` and `losses` map a class (an entity label, or a tag name
+        such as "Ship" for entities with no labelled rule) to the points won
+        for destroying an enemy of that class or lost when one of the team's
+        own is destroyed. Each `(x, y, radius, points_per_second)` in
+        `zones` scores while a team alone has live ships or squadrons
+        inside it, and `delivery` is scored per convoy ship delivered.
+        Scoring survives `reset()`.
+
+        ```python
+        sim.enable_scoring(kills={"merchant": 10.0, "Ship": 3.0},
+                           losses={"Ship": 5.0},
+                           zones=[(0.0, 0.0, 2000.0, 0.1)])
+        ```
+        """
+    def scores(self) -> list[Any] | None:
+        """Mission scores so far, or None if scoring is not enabled.
+
+        Returns a list of dicts sorted by team, with keys "team", "score",
+        "kills" and "losses" (dicts of counts by class), "zone_seconds" and
+        "delivered".
+        """
+    def kill_ledger(self) -> dict[str, Any]:
+        """Kills, assists and damage dealt so far by each controller.
+
+        Returns a dict keyed by controller (written like in
+        `set_controller`) of dicts with keys "kills", "assists" and
+        "damage". A kill goes to the controller of the entity that last
+        damaged a destroyed hostile, and an assist to every other
+        controller whose entities damaged it. Friendly fire is not counted.
+        The ledger starts empty on `reset()`.
+        """
+    def enable_weapon_assignment(self, damage_per_shot: float = 25.0, max_shooters_per_target: int = 2) -> None:
+        """Deconflict fire within each team, replacing any assignment enabled
+        so far.
+
+        Every tick, of a team's ships firing at one target, only the nearest
+        needed to destroy it at `damage_per_shot` each fire, and at most
+        `max_shooters_per_target`; the others' shots are dropped and their
+        weapons stay ready. Assignment survives `reset()`.
+
+        Raises InvalidValue for a non-positive `damage_per_shot`.
+        """
+    def disable_weapon_assignment(self) -> None:
+        """Stop deconflicting fire."""
+    def enable_classification(self, gain_per_detection: float = 0.1, half_gain_range: float = 5000.0, emissions_multiplier: float = 2.0, threshold: float = 0.7, misclassification_rate: float = 0.05, visual_confidence: float = 0.95) -> None:
+        """Classify contacts from detections, replacing any classification
+        enabled so far.
+
+        Every tick, each detected contact's classification confidence grows
+        by `gain_per_detection`, halved at `half_gain_range` meters and
+        multiplied by `emissions_multiplier` if the contact is emitting. A
+        contact within visual range jumps to `visual_confidence`. Past
+        `threshold` it is classified, as a wrong type with probability
+        `misclassification_rate`; the rolls are seeded by the simulation
+        seed. Classified contacts fill the `contact_tags` observation.
+
+        Ships' sensors start detecting contacts, filling their track tables,
+        and keep doing so after `disable_classification()`. Classification
+        and sensors survive `reset()`.
+
+        Raises InvalidValue for a negative or non-finite parameter, or a
+        `threshold`, `misclassification_rate` or `visual_confidence` outside
+        [0, 1].
+        """
+    def disable_classification(self) -> None:
+        """Stop classifying contacts. Classifications made so far are kept."""
+    def take_weapon_assignments(self) -> list[tuple[PyEntityId, PyEntityId, int]]:
+        """Drain the shots assigned since the last call, as `(shooter, target,
+        slot)` tuples in resolution order. Empty if assignment is disabled.
+        """
+    def enable_heatmap(self, min: tuple[float, float], max: tuple[float, float], cell_size: float, team: int | None = None) -> None:
+        """Accumulate a visitation and damage-exposure heatmap over the
+        rectangle `[min, max]` in square cells of `cell_size` meters.
+
+        Every tick, each live ship or squadron in the grid adds a visit to
+        its cell, and the hit points it lost that tick to the cell's
+        exposure. `team` limits recording to that team's entities. Cells
+        grow as needed to keep at most 1024 per axis. Replaces any heatmap
+        kept so far; the heatmap survives `reset()`, which zeroes it.
+
+        Raises InvalidValue for a non-positive `cell_size`.
+        """
+    def disable_heatmap(self) -> None:
+        """Stop accumulating the heatmap."""
+    def heatmap(self) -> dict[str, Any] | None:
+        """The heatmap so far, or None if not enabled.
+
+        Returns a dict with:
+        - "visits": uint32 (ny, nx) entity-ticks spent in each cell
+        - "exposure": float32 (ny, nx) hit points lost in each cell
+        - "min": (x, y) lower corner of the first cell
+        - "cell_size": side length of each cell in meters
+        - "ticks": number of ticks recorded
+
+        Row `j`, column `i` covers x from `min[0] + i * cell_size` and y
+        from `min[1] + j * cell_size`.
+        """
+    def team_observation(self, team: int, zones: list[tuple[float, float, float]] | None = None) -> dict[str, Any]:
+        """Commander-level picture of a team, as numpy arrays.
+
+        Returns a dict with:
+        - "tracks": float32 (N, 8) rows of `[x, y, vx, vy, quality, age,
+          tag, reporters]`, the members' track tables fused per target
+          (best quality, then freshest). `tag` is encoded as in
+          `contact_tags`; `reporters` counts members holding the track.
+        - "track_ids": uint64 (N,) target IDs.
+        - "members": float32 (M, 10) own-force rows of `[x, y, heading, vx,
+          vy, hp_fraction, fuel_fraction, weapons_ready, ammo, active]`.
+        - "member_ids": uint64 (M,) member IDs.
+        - "zones": float32 (Z, 3) rows of `[friendly, hostile, control]`
+          for each `(x, y, radius)` in `zones`, with control 0 (empty),
+          1 (friendly), 2 (hostile) or 3 (contested).
+        - "clock": the world clock reading, as returned by `clock`.
+        """
+    def team_coverage(self, team: int) -> list[tuple[float, float, float]]:
+        """Areas a team's sensors cover, as `(x, y, radius)` discs.
+
+        One disc per active member ship with working sensors, as wide as
+        the furthest of its radar, sonar and lookouts reach.
+        """
+    def team_query_point(self, universe: PyUniverse, team: int, position: tuple[float, float, float]) -> PyPointResult | None:
+        """Query a point of `universe` as `team` sees it.
+
+        Returns None where the team has no sensor coverage (see
+        `team_coverage`), so observations of the environment reveal nothing
+        the team could not sense. The universe is only read.
+        """
+    def team_query_volume(self, universe: PyUniverse, team: int, center: tuple[float, float, float], radius: float, resolution: str = "medium") -> PyQueryResult | None:
+        """Query a volume of `universe` as `team` sees it.
+
+        Returns None unless a single member covers the whole horizontal
+        extent of the sphere.
+        """
+    def get_threat_scores(self, entity_id: PyEntityId) -> list[tuple[PyEntityId, float]]:
+        """Threat scores for every track held by an entity.
+
+        Returns a list of (target_id, score) tuples in track-table order,
+        scored with the default `ThreatEvaluationPlugin` weights.
+        """
+    def get_observation(self, entity_id: PyEntityId, max_contacts: int = 16, max_intents: int = 0, frames: int = 1, sort: str | None = None, min_quality: str | None = None, tags: list[PyEntityTag] | None = None, teams: list[int] | None = None, relative_velocity: bool = False, objectives: bool = False) -> PyObservation | None:
+        """Get observation for an entity.
+
+        `max_intents` controls how many received intents (from friendly
+        entities in comms range) are included; 0 disables the intent block.
+
+        With `frames` > 1 the observation also carries the entity's last
+        `frames` observations (one per tick, kept in a per-entity ring
+        buffer), available through the `stacked_*` accessors. Missing history
+        is padded by repeating the oldest frame; changing `max_contacts`,
+        `max_intents` or the contact row width restarts the history.
+
+        The contact block can be shaped per call:
+        - `sort` overrides the contact ordering set by
+          `set_contact_sort_key` ("distance", "threat", "quality" or
+          "track").
+        - `min_quality` drops tracks below "cue", "coarse", "fire_control"
+          or "shared".
+        - `tags` keeps only contacts classified as one of the given tags.
+        - `teams` keeps only contacts on one of the given teams.
+        - `relative_velocity` appends `[rel_vx, rel_vy]` to each contact row.
+
+        `objectives` fills the `objectives` block from the objectives set
+        with `set_objectives`.
+
+        ```python
+        obs = sim.get_observation(ship_id, frames=4)
+        obs.stacked_own_state().shape  # (4, 7)
+        obs = sim.get_observation(ship_id, sort="threat", teams=[1], relative_velocity=True)
+        obs.contacts().shape  # (16, 7)
+        ```
+        """
+    def observation_size(self, max_contacts: int = 16, max_intents: int = 0, spec: PyObservationSpec | None = None) -> int:
+        """Length of the flat observation written by `obs_into`.
+
+        7 own-state values, then `max_contacts` rows of 5 contact values,
+        `max_contacts` contact tags, 4 bound distances and `max_intents`
+        rows of (3 + max_intent_len) intent values, followed by the 7
+        objective values when `spec` asks for them. A `spec` overrides
+        `max_contacts` and `max_intents`.
+        """
+    def obs_into(self, entity_id: PyEntityId, out: Any, max_contacts: int = 16, max_intents: int = 0, spec: PyObservationSpec | None = None) -> bool:
+        """Write an entity's observation into the preallocated array `out`,
+        without allocating numpy arrays.
+
+        `out` must be contiguous with exactly `observation_size(...)`
+        values, laid out as own_state, contacts, contact_tags,
+        bound_distances, intents and (if `spec` asks for them) objectives,
+        each flattened row-major. Weapons and
+        stacked frames are not included. `out` is float32 unless `spec`
+        selects float16 or int8, in which case values are converted in Rust.
+        Returns False, zero-filling `out`, if the entity does not exist.
+
+        ```python
+        spec = ObservationSpec(dtype="float16")
+        out = np.empty(sim.observation_size(spec=spec), dtype=np.float16)
+        sim.obs_into(ship_id, out, spec=spec)
+        ```
+        """
+    def obs_into_batch(self, entity_ids: list[PyEntityId], out: Any, max_contacts: int = 16, max_intents: int = 0, spec: PyObservationSpec | None = None) -> list[bool]:
+        """Write the observations of `entity_ids` into the rows of the
+        preallocated array `out`, shape (len(entity_ids),
+        observation_size(...)).
+
+        Each row is laid out as in `obs_into`, with the same `spec`
+        handling. Returns, per entity, whether it exists; rows of missing
+        entities are zero-filled.
+        """
+    def clear_frame_history(self) -> None:
+        """Drop all recorded observation frames."""
+
+
+@final
+class PyCancellationToken:
+    """Cooperative cancellation flag for `PySimulation.step_async`.
+
+    Clones share the flag, so a token can be cancelled from any thread.
+    """
+    def __init__(self) -> None: ...
+    def cancel(self) -> None:
+        """Request cancellation."""
+    @property
+    def cancelled(self) -> bool:
+        """Whether cancellation has been requested."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class Replay:
+    """Recording of a simulation run, saved as a portable `.tbr` file.
+
+    Step the simulation through `record_step` to record it. Keyframes are
+    kept every `keyframe_interval` steps so `seek` can jump to any tick by
+    re-running at most one interval. Actions applied between steps are
+    recorded too.
+
+    ```python
+    replay = tidebreak.Replay(keyframe_interval=100, scenario="duel.toml")
+    for _ in range(1000):
+        sim.apply_action(ship, policy(sim.get_observation(ship)))
+        replay.record_step(sim)
+    replay.save("duel.tbr")
+
+    loaded = tidebreak.Replay.load("duel.tbr")
+    loaded.seek(viewer_sim, 640)
+    ```
+    """
+    def __init__(self, keyframe_interval: int = 100, scenario: str | None = None) -> None: ...
+    @staticmethod
+    def load(path: str) -> Replay:
+        """Read a replay saved with `save`."""
+    def save(self, path: str) -> None:
+        """Write the replay to a `.tbr` file."""
+    def record_step(self, sim: PySimulation) -> None:
+        """Step `sim` once and record the step.
+
+        Raises InvalidValue if `sim` is not at the tick after the last
+        recorded step. Releases the GIL during execution.
+        """
+    def seek(self, sim: PySimulation, tick: int) -> None:
+        """Put `sim` in its state just before `tick` executed.
+
+        `sim` must use the recorded seed and the same plugins for the
+        result to match the recording. Raises InvalidValue for a tick
+        outside `start_tick..=end_tick`, or if the re-stepped state differs
+        from the recorded one.
+        """
+    def branch(self, tick: int) -> Replay:
+        """Copy of the recording cut just before `tick`, to record a fork
+        started there (see `Simulation.fork_from`).
+        """
+    @property
+    def seed(self) -> int:
+        """Seed of the recorded simulation."""
+    @property
+    def scenario(self) -> str | None:
+        """Scenario the run was set up from, if given."""
+    @property
+    def start_tick(self) -> int:
+        """Tick of the first recorded step."""
+    @property
+    def end_tick(self) -> int:
+        """Tick after the last recorded step."""
+    @property
+    def keyframe_ticks(self) -> list[int]:
+        """Ticks holding a keyframe."""
+    def events(self, tick: int) -> list[Any]:
+        """Events recorded for `tick`, as dicts with the keys of
+        `Simulation.query_events`. Empty for a tick outside the recording.
+        """
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+
+@final
+class PyObservation:
+    """Observation for a single agent (ship).
+
+    Pre-vectorized observation suitable for DRL training. Contains:
+    - `own_state`: Position, heading, velocity, and health as a 1D array
+    - `contacts`: Detected contacts from the sensor track table as a 2D array
+    - `intents`: Intents received from friendly entities as a 2D array
+    - `bound_distances`: Distances to the world edges as a 1D array
+    - `weapons`: Readiness, magazine and reload state per weapon as a 2D array
+    - `own_systems`: Fuel, ammunition stores, status flags and weapon
+      cooldowns as a 1D array
+    - `objectives`: Nearest zone, convoy center and mission timer as a 1D
+      array, if requested
+
+    The block methods return read-only numpy views of buffers owned by the
+    observation rather than copies; a view keeps its observation alive.
+    Copy an array (`arr.copy()`) before modifying it. The `stacked_*`
+    methods build new arrays.
+    """
+    def __init__(self, own_state: list[float], contacts: list[list[float]], intents: list[list[float]] = ..., contact_tags: list[int] = ..., bounds: list[float] = ..., weapons: list[list[float]] = ..., contact_kinematics: list[list[float]] = ..., own_systems: list[float] = ..., objectives: list[float] = ...) -> None:
+        """Create an observation from its raw blocks."""
+    def __reduce__(self) -> tuple[type, tuple[list[float], list[list[float]], list[list[float]], list[int], list[float], list[list[float]], list[list[float]], list[float], list[float]]]:
+        """Pickle support: rebuilt from its raw blocks (the current frame only;
+        stacked history is not carried over).
+        """
+    def own_state(self) -> npt.NDArray[np.float32]:
+        """Own state as numpy array.
+
+        Returns a 1D array with shape (7,) containing:
+        [x, y, heading, vx, vy, hp, max_hp]
+        """
+    def contacts(self) -> npt.NDArray[np.float32]:
+        """Contacts as 2D numpy array, shape (max_contacts, 5), or
+        (max_contacts, 7) with relative velocity.
+
+        Each row contains: [x, y, rel_heading, distance, quality], followed
+        by [rel_vx, rel_vy] if requested. Unused slots are zero-padded.
+        """
+    @property
+    def own_state_dim(self) -> int:
+        """Feature dimension for own_state."""
+    @property
+    def max_contacts(self) -> int:
+        """Number of contact slots."""
+    def contact_tags(self) -> npt.NDArray[np.int32]:
+        """Observed classification per contact slot as 1D int32 array, shape
+        (max_contacts,).
+
+        0 = unknown (below the classification threshold) or empty slot,
+        1 = ship, 2 = platform, 3 = projectile, 4 = squadron.
+        Classifications may be wrong.
+        """
+    def bound_distances(self) -> npt.NDArray[np.float32]:
+        """Distances to the world edges as 1D array with shape (4,):
+        [min_x, max_x, min_y, max_y]
+
+        Negative beyond an edge; all zero when the simulation has no bounds.
+        """
+    def weapons(self) -> npt.NDArray[np.float32]:
+        """Weapon state as 2D numpy array, shape (num_weapons, 5).
+
+        Each row contains: [ready, cooldown, rounds, magazine_size,
+        reload_progress], in slot order. `ready` is 1.0 when the weapon can
+        fire; `reload_progress` runs from 0.0 to 1.0 while reloading and is
+        0.0 otherwise. Cooldown-only weapons have a magazine_size of 0.
+        """
+    def contact_kinematics(self) -> npt.NDArray[np.float32]:
+        """Contact course and speed estimates as 2D numpy array, shape
+        (max_contacts, 5).
+
+        Each row contains: [estimated, course, speed, course_sigma,
+        speed_sigma] for the contact in the same slot of `contacts`, fitted
+        to its track's recent position fixes. `estimated` is 1.0 when the
+        track has enough history for an estimate; rows without one, and
+        unused slots, are zero. Course is in radians in the world frame.
+        """
+    def own_systems(self) -> npt.NDArray[np.float32]:
+        """Own fuel, stores, status and weapon cooldowns as 1D numpy array,
+        shape (15 + num_weapons,).
+
+        Contains, in order:
+        - fuel fraction (0.0 to 1.0)
+        - rounds held per ammunition type: bullet, missile, torpedo, shell,
+          depth_charge, countermeasure, smoke
+        - status flags as 0.0/1.0: mobility_disabled, weapons_disabled,
+          sensors_disabled, destroyed, on_fire, flooding, surrendered
+        - cooldown fraction per weapon, in slot order (1.0 just after
+          firing, 0.0 when the cooldown has run out)
+
+        The first 15 values are always present; the length is 15 plus the
+        number of weapons.
+        """
+    def objectives(self) -> npt.NDArray[np.float32]:
+        """Scenario objectives set with `PySimulation.set_objectives` as 1D
+        numpy array, shape (7,), or (0,) unless requested with
+        `get_observation(..., objectives=True)`.
+
+        Contains `[zone_bearing, zone_distance, zone_control, convoy_rel_x,
+        convoy_rel_y, convoy_present, mission_fraction]`:
+        - world bearing (radians) and distance to the center of the nearest
+          zone, and its control as seen by the entity's team from the live
+          ships and squadrons inside: 0 (empty), 1 (friendly), 2 (hostile)
+          or 3 (contested)
+        - the convoy's mean position relative to the entity, and 1.0 while
+          any of its ships is afloat
+        - the fraction of the mission time elapsed, capped at 1.0
+
+        Values of objectives that are not set are zero.
+        """
+    def intents(self) -> npt.NDArray[np.float32]:
+        """Received intents as 2D numpy array, shape
+        (max_intents, 3 + max_intent_len).
+
+        Each row contains: [rel_x, rel_y, age, payload...]
+        Unused slots are zero-padded.
+        """
+    @property
+    def max_intents(self) -> int:
+        """Number of intent slots."""
+    @property
+    def frames(self) -> int:
+        """Number of stacked frames (1 when not stacking)."""
+    def __repr__(self) -> str: ...
+    def stacked_own_state(self) -> npt.NDArray[np.float32]:
+        """Own state over the stacked frames, shape (frames, 7), oldest first."""
+    def stacked_contacts(self) -> npt.NDArray[np.float32]:
+        """Contacts over the stacked frames, shape (frames, max_contacts, 5),
+        or 7 columns with relative velocity, oldest first.
+        """
+    def stacked_contact_tags(self) -> npt.NDArray[np.int32]:
+        """Contact tags over the stacked frames, shape (frames, max_contacts),
+        oldest first.
+        """
+    def stacked_bound_distances(self) -> npt.NDArray[np.float32]:
+        """Distances to the world edges over the stacked frames, shape
+        (frames, 4), oldest first.
+        """
+    def stacked_weapons(self) -> npt.NDArray[np.float32]:
+        """Weapon state over the stacked frames, shape (frames, num_weapons, 5),
+        oldest first.
+        """
+    def stacked_intents(self) -> npt.NDArray[np.float32]:
+        """Received intents over the stacked frames, shape
+        (frames, max_intents, 3 + max_intent_len), oldest first.
+        """
+    def encode(self, bit_budget: int) -> bytes:
+        """Quantize and bit-pack the whole observation within `bit_budget` bits.
+
+        Each feature is quantized over its own range: the packed columns are
+        each own_state value, then each contact column, then each intent
+        column. Decode with `PyObservationCodec.decode_columns`.
+        """
+
+
+@final
+class PyObservationCodec:
+    """Quantizing bit-packer for bandwidth-limited observations.
+
+    Each column of values is quantized uniformly between its own minimum
+    and maximum, at the largest bits-per-value (up to 16) that keeps the
+    message within the bit budget.
+    """
+    def __init__(self, bit_budget: int) -> None: ...
+    @property
+    def bit_budget(self) -> int:
+        """Configured bit budget."""
+    def bits_per_value(self, count: int, columns: int = 1) -> int:
+        """Bits per value used for a message with `count` values in `columns`
+        columns.
+        """
+    def encode(self, values: list[float]) -> bytes:
+        """Encode a 1D float array into packed bytes, as a single column."""
+    def encode_columns(self, columns: list[list[float]]) -> bytes:
+        """Encode a list of float columns into packed bytes, each quantized over
+        its own range. Put the values of one feature in one column.
+        """
+    @staticmethod
+    def decode(packed: bytes) -> npt.NDArray[np.float32]:
+        """Decode packed bytes into a 1D float32 array, shape (n,) for the `n`
+        values encoded, with the columns one after another.
+        """
+    @staticmethod
+    def decode_columns(packed: bytes) -> list[npt.NDArray[np.float32]]:
+        """Decode packed bytes into a list of 1D float32 arrays, one per column."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class PyObservationSpec:
+    """Layout and element type of the flat observations written by
+    `PySimulation.obs_into`.
+
+    `dtype` is "float32", "float16" or "int8". int8 values are stored as
+    `round(value / scale)`, saturating at ±127; pick `scale` so the
+    largest expected magnitude fits. `objectives` appends the objectives
+    block (see `PyObservation.objectives`).
+    """
+    def __init__(self, max_contacts: int = 16, max_intents: int = 0, dtype: str = "float32", scale: float = 1.0, objectives: bool = False) -> None: ...
+    @property
+    def max_contacts(self) -> int:
+        """Number of contact slots."""
+    @property
+    def max_intents(self) -> int:
+        """Number of intent slots."""
+    @property
+    def dtype(self) -> str:
+        """Element type name, usable as a numpy dtype."""
+    @property
+    def objectives(self) -> bool:
+        """Whether the objectives block is appended."""
+    @property
+    def scale(self) -> float | None:
+        """Quantization step for int8, None otherwise."""
+    def __repr__(self) -> str: ...
+
+
+@final
+class PyScenarioRandomizer:
+    """Declared jitter for domain randomization of battle packages.
+
+    Each argument is a distribution: a number (constant), `("uniform", min,
+    max)` or `("normal", mean, std_dev)`. `position`, `heading`,
+    `sea_state` and `hour` are offsets added to the authored values;
+    `ammunition` and `sensor_range` scale them. Omitted arguments leave the
+    property unchanged.
+
+    ```python
+    randomizer = tidebreak.ScenarioRandomizer(position=("uniform", -500, 500))
+    variant = randomizer.randomize(package_json, seed=3)
+    sim, ids = tidebreak.Simulation.from_package(variant)
+    ```
+    """
+    def __init__(self, position: Any | None = None, heading: Any | None = None, ammunition: Any | None = None, sensor_range: Any | None = None, sea_state: Any | None = None, hour: Any | None = None) -> None: ...
+    def randomize(self, package: str, seed: int) -> str:
+        """Draw the variant of a battle package (JSON) for `seed`, returned as
+        JSON. The same seed always yields the same variant.
+        """
+    def __repr__(self) -> str: ...
+
+
+def schema() -> dict[str, Any]:
+    """Describe the components and fields of every entity tag.
+
+    Returns `{tag: {component: {"optional": bool, "fields": {name: field}}}}`
+    keyed by tag name ("Ship", ...) and component name ("physics", ...),
+    where each field is a dict with keys "dtype" (numpy-style name, or
+    "enum"), "shape" (tuple, empty for scalars), "optional", "repeated"
+    (one value per list element or map entry) and "min"/"max" (documented
+    range, or None). Generated from the Rust component types, so it always
+    matches the installed build.
+    """
+
+
+class CommandError(Exception):
+    """Base class for commands rejected by the simulation."""
+
+
+class UnknownEntity(CommandError):
+    """The command targets an entity that does not exist."""
+
+
+class EntityDestroyed(CommandError):
+    """The command targets a destroyed entity."""
+
+
+class InvalidValue(CommandError):
+    """The command contains a malformed or out-of-range value."""
+
+
+class NotSupportedForTag(CommandError):
+    """The command is not supported for the target's entity type."""
+
+
+class NotController(CommandError):
+    """The command targets an entity the caller does not control."""
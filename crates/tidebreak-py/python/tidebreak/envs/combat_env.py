@@ -19,6 +19,8 @@ class CombatEnv(gym.Env):
         Dict with:
         - "own_state": Box(7,) - [x, y, heading, vx, vy, hp, max_hp]
         - "contacts": Box(max_contacts, 5) - contact info per track
+        - "bounds": Box(4,) - distances to the [min_x, max_x, min_y, max_y]
+          edges of the play area (zeros when unbounded)
 
     Action space:
         Dict with:
@@ -33,6 +35,9 @@ class CombatEnv(gym.Env):
         max_contacts: int = 16,
         max_speed: float = 20.0,
         max_steps: int = 1000,
+        arena_size: float | None = None,
+        bounds_policy: str = "clamp",
+        scoring: dict[str, Any] | None = None,
         render_mode: str | None = None,
     ) -> None:
         super().__init__()
@@ -40,6 +45,9 @@ class CombatEnv(gym.Env):
         self.max_contacts = max_contacts
         self.max_speed = max_speed
         self.max_steps = max_steps
+        self.arena_size = arena_size
+        self.bounds_policy = bounds_policy
+        self.scoring = scoring
         self.render_mode = render_mode
 
         # Observation space
@@ -47,6 +55,7 @@ class CombatEnv(gym.Env):
             {
                 "own_state": spaces.Box(low=-np.inf, high=np.inf, shape=(7,), dtype=np.float32),
                 "contacts": spaces.Box(low=-np.inf, high=np.inf, shape=(max_contacts, 5), dtype=np.float32),
+                "bounds": spaces.Box(low=-np.inf, high=np.inf, shape=(4,), dtype=np.float32),
             }
         )
 
@@ -73,6 +82,11 @@ class CombatEnv(gym.Env):
         # Create new simulation
         sim_seed = seed if seed is not None else self.np_random.integers(0, 2**32)
         self._sim = PySimulation(seed=sim_seed)
+        if self.arena_size is not None:
+            half = self.arena_size / 2.0
+            self._sim.set_bounds((-half, -half), (half, half), self.bounds_policy)
+        if self.scoring is not None:
+            self._sim.enable_scoring(**self.scoring)
 
         # Spawn agent ship at origin
         self._agent_id = self._sim.spawn_ship(0.0, 0.0, 0.0)
@@ -120,6 +134,9 @@ class CombatEnv(gym.Env):
             "tick": self._sim.tick,
             "entity_count": self._sim.entity_count,
         }
+        scores = self._sim.scores()
+        if scores is not None:
+            info["scores"] = scores
 
         return obs, reward, terminated, truncated, info
 
@@ -132,11 +149,13 @@ class CombatEnv(gym.Env):
             return {
                 "own_state": np.zeros(7, dtype=np.float32),
                 "contacts": np.zeros((self.max_contacts, 5), dtype=np.float32),
+                "bounds": np.zeros(4, dtype=np.float32),
             }
 
         return {
             "own_state": np.asarray(py_obs.own_state(), dtype=np.float32),
             "contacts": np.asarray(py_obs.contacts(), dtype=np.float32),
+            "bounds": np.asarray(py_obs.bound_distances(), dtype=np.float32),
         }
 
     def _compute_reward(self) -> float:
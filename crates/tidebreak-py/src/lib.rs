@@ -35,13 +35,72 @@
 //! print(f"Avg temperature: {stats.mean('temperature')}")
 //! ```
 
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use glam::Vec2;
-use numpy::{PyArray1, ToPyArray};
+use numpy::{
+    PyArray1, PyArrayDescrMethods, PyArrayMethods, PyUntypedArray, PyUntypedArrayMethods, ToPyArray,
+};
+use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
-use pyo3::types::PyList;
-use tidebreak_core::entity::components::{CombatState, PhysicsState, StatusFlags, TransformState};
-use tidebreak_core::entity::{Entity, EntityId, EntityInner, EntityTag, ShipComponents};
+use pyo3::types::{PyBytes, PyList, PyType};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tidebreak_core::arena::{Arena, BoundaryPolicy, WorldBounds};
+use tidebreak_core::attachment::Attachment;
+use tidebreak_core::battle_log::query::{to_arrow, EventQuery};
+use tidebreak_core::battle_log::{BattleLogConfig, EventRow};
+use tidebreak_core::codec::{encode_f16, encode_i8, ObservationCodec, ObservationDtype};
+use tidebreak_core::comms::{CommsConfig, JammingZone};
+use tidebreak_core::config::{ConfigError, TidebreakConfig};
+use tidebreak_core::currents::{CurrentField, DriftCoupling};
+use tidebreak_core::environment::{
+    ClockReading, DayPhase, OccupancyField, SmokeField, WorldClock, MAX_SEA_STATE,
+};
+use tidebreak_core::entity::components::{
+    AmmoType, CombatState, GunBallistics, InventoryState, MineFuze, MineState, MitigationState,
+    PhysicsState, Resistances, StatusFlags, TransformState, WeaponState,
+};
+use tidebreak_core::entity::{
+    AttributeValue, Attributes, Cargo, ControllerId, Entity, EntityId, EntityInner,
+    EntityMetadata, EntityTag, PlatformComponents, ShipComponents, SubmarineState, TeamId,
+    TrackQuality,
+};
+use tidebreak_core::geofence::{FencePolicy, Geofence};
+use tidebreak_core::interest::{CachedContact, ContactFilter, ContactSortKey, InterestManager};
+use tidebreak_core::kill_ledger::ControllerTally;
+use tidebreak_core::league::{League, LeagueError};
+use tidebreak_core::orders::{can_issue, RulesOfEngagement};
+use tidebreak_core::output::{
+    Event, Order, OutputEnvelope, PluginId, PluginInstanceId, TraceId,
+};
+use tidebreak_core::plugin::Plugin;
+use tidebreak_core::plugins::{
+    ConvoyPlugin, OrderFollowerPlugin, ProximityPlugin, ScriptedPolicy, SensorPlugin, TeamFilter,
+    ThreatWeights, MERCHANT_LABEL,
+};
+use tidebreak_core::replay::{Replay, ReplayError};
+use tidebreak_core::resolver::{
+    AssignmentConfig, ClassificationModel, Heatmap, HeatmapConfig, MinefieldResolver, ScoreState,
+    ScoringRules, SmokeResolver,
+};
+use tidebreak_core::rng_audit::DEFAULT_AUDIT_CAPACITY;
+use tidebreak_core::scenario::{BattlePackage, Distribution, ScenarioRandomizer};
+use tidebreak_core::schema::schema as component_schema;
 use tidebreak_core::simulation::Simulation;
+use tidebreak_core::steering::SteeringAssist;
+use tidebreak_core::team_fields::{SensorCoverage, TeamFieldView};
+use tidebreak_core::team_observation::{TeamObservationBuilder, Zone, ZoneControl};
+use tidebreak_core::watchdog::PluginBudget;
+use tidebreak_core::wind::Wind;
+use tidebreak_core::wreckage::{WreckageConfig, WreckageSystem};
 
 /// Field enum for Python.
 ///
@@ -91,6 +150,21 @@ pub enum Field {
     SONAR_RETURN,
 }
 
+#[pymethods]
+impl Field {
+    /// Unit symbol of the field's raw values (empty if dimensionless).
+    #[getter]
+    fn unit(&self) -> &'static str {
+        murk::Field::from(*self).unit().symbol()
+    }
+
+    /// Map a raw value onto the field's canonical scale: [0, 1], or [-1, 1]
+    /// for the signed current components.
+    fn normalize(&self, value: f32) -> f32 {
+        murk::Field::from(*self).normalize(value)
+    }
+}
+
 impl From<Field> for murk::Field {
     fn from(f: Field) -> Self {
         match f {
@@ -145,25 +219,144 @@ impl From<FieldOrStr> for murk::Field {
     }
 }
 
+/// Entity attribute value as seen from Python (bool, int, float or str).
+///
+/// `Bool` is tried first since Python bools are also ints.
+#[derive(FromPyObject, IntoPyObject)]
+enum PyAttributeValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Text(String),
+}
+
+impl From<PyAttributeValue> for AttributeValue {
+    fn from(v: PyAttributeValue) -> Self {
+        match v {
+            PyAttributeValue::Bool(b) => Self::Bool(b),
+            PyAttributeValue::Int(i) => Self::Int(i),
+            PyAttributeValue::Float(f) => Self::Float(f),
+            PyAttributeValue::Text(s) => Self::Text(s),
+        }
+    }
+}
+
+impl From<&AttributeValue> for PyAttributeValue {
+    fn from(v: &AttributeValue) -> Self {
+        match v {
+            AttributeValue::Bool(b) => Self::Bool(*b),
+            AttributeValue::Int(i) => Self::Int(*i),
+            AttributeValue::Float(f) => Self::Float(*f),
+            AttributeValue::Text(s) => Self::Text(s.clone()),
+        }
+    }
+}
+
+pyo3::create_exception!(
+    tidebreak,
+    CommandError,
+    pyo3::exceptions::PyException,
+    "Base class for commands rejected by the simulation."
+);
+pyo3::create_exception!(
+    tidebreak,
+    UnknownEntity,
+    CommandError,
+    "The command targets an entity that does not exist."
+);
+pyo3::create_exception!(
+    tidebreak,
+    EntityDestroyed,
+    CommandError,
+    "The command targets a destroyed entity."
+);
+pyo3::create_exception!(
+    tidebreak,
+    InvalidValue,
+    CommandError,
+    "The command contains a malformed or out-of-range value."
+);
+pyo3::create_exception!(
+    tidebreak,
+    NotSupportedForTag,
+    CommandError,
+    "The command is not supported for the target's entity type."
+);
+pyo3::create_exception!(
+    tidebreak,
+    NotController,
+    CommandError,
+    "The command targets an entity the caller does not control."
+);
+
 /// Universe wrapper for Python.
-#[pyclass]
+#[pyclass(module = "tidebreak._tidebreak")]
 pub struct PyUniverse {
     inner: murk::Universe,
+    templates: murk::StampLibrary,
+}
+
+/// Pickled form of a `PyUniverse`; reads the bare universe of older pickles.
+#[derive(Serialize)]
+struct UniverseStateRef<'a> {
+    #[serde(flatten)]
+    universe: &'a murk::Universe,
+    stamp_templates: &'a murk::StampLibrary,
+}
+
+/// Owned counterpart of [`UniverseStateRef`].
+#[derive(Deserialize)]
+struct UniverseState {
+    #[serde(flatten)]
+    universe: murk::Universe,
+    #[serde(default = "murk::StampLibrary::with_builtins")]
+    stamp_templates: murk::StampLibrary,
+}
+
+impl PyUniverse {
+    /// Region from optional corners, defaulting to the world bounds.
+    fn region(&self, min: Option<(f32, f32, f32)>, max: Option<(f32, f32, f32)>) -> murk::Bounds {
+        let world = self.inner.bounds();
+        let corner = |c: Option<(f32, f32, f32)>, default| c.map_or(default, |(x, y, z)| glam::Vec3::new(x, y, z));
+        murk::Bounds::from_min_max(corner(min, world.min), corner(max, world.max))
+    }
 }
 
 #[pymethods]
 impl PyUniverse {
     /// Create a new Universe.
+    ///
+    /// With `memory_budget` (bytes), the octree regions least recently
+    /// stamped or queried are coarsened whenever the tree outgrows it; see
+    /// `evictions` in `debug_tree()`. With `planar`, cells split in X and Y
+    /// only and each spans the full depth, for surface-only scenarios.
     #[new]
-    #[pyo3(signature = (width=1024.0, height=1024.0, depth=256.0, base_resolution=1.0))]
-    fn new(width: f32, height: f32, depth: f32, base_resolution: f32) -> Self {
+    #[pyo3(signature = (
+        width=1024.0,
+        height=1024.0,
+        depth=256.0,
+        base_resolution=1.0,
+        memory_budget=None,
+        planar=false,
+    ))]
+    fn new(
+        width: f32,
+        height: f32,
+        depth: f32,
+        base_resolution: f32,
+        memory_budget: Option<usize>,
+        planar: bool,
+    ) -> Self {
         let config = murk::UniverseConfig {
             bounds: murk::Bounds::new(width, height, depth),
             base_resolution,
+            memory_budget,
+            planar,
             ..Default::default()
         };
         Self {
             inner: murk::Universe::new(config),
+            templates: murk::StampLibrary::with_builtins(),
         }
     }
 
@@ -179,6 +372,18 @@ impl PyUniverse {
         self.inner.time()
     }
 
+    fn __repr__(&self) -> String {
+        let size = self.inner.bounds().size();
+        format!(
+            "Universe(size=({:.1}, {:.1}, {:.1}), tick={}, nodes={})",
+            size.x,
+            size.y,
+            size.z,
+            self.inner.tick(),
+            self.inner.stats().node_count
+        )
+    }
+
     /// Apply an explosion stamp.
     #[pyo3(signature = (center, radius, intensity=1.0))]
     fn stamp_explosion(&mut self, center: (f32, f32, f32), radius: f32, intensity: f32) {
@@ -203,6 +408,193 @@ impl PyUniverse {
             .stamp(&murk::Stamp::sonar_ping(center, radius, strength));
     }
 
+    /// Apply a stamp from a named template, overriding its parameters.
+    ///
+    /// Built-in templates are "explosion", "fire", "sonar_ping",
+    /// "depth_charge" and "volcanic_vent"; more can be added with
+    /// `load_stamp_templates`. Raises `ValueError` for an unknown template or
+    /// parameter.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// universe.stamp_template("depth_charge", (0.0, 0.0, -40.0), radius=50.0)
+    /// ```
+    #[pyo3(signature = (name, center, **params))]
+    fn stamp_template(
+        &mut self,
+        name: &str,
+        center: (f32, f32, f32),
+        params: Option<BTreeMap<String, f32>>,
+    ) -> PyResult<()> {
+        let center = glam::Vec3::new(center.0, center.1, center.2);
+        let stamp = self
+            .templates
+            .instantiate(name, center, &params.unwrap_or_default())
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        self.inner.stamp(&stamp);
+        Ok(())
+    }
+
+    /// Register stamp templates from a JSON file (or TOML, by extension),
+    /// replacing same-named ones. Returns the names loaded.
+    ///
+    /// The file maps template names to templates; see `murk::template` for
+    /// the format.
+    fn load_stamp_templates(&mut self, path: PathBuf) -> PyResult<Vec<String>> {
+        let text = std::fs::read_to_string(&path)?;
+        let parsed: Result<murk::StampLibrary, String> =
+            if path.extension().is_some_and(|e| e == "toml") {
+                toml::from_str(&text).map_err(|e| e.message().to_string())
+            } else {
+                serde_json::from_str(&text).map_err(|e| e.to_string())
+            };
+        let library = parsed.map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let names = library.names().map(str::to_string).collect();
+        self.templates.extend(library);
+        Ok(names)
+    }
+
+    /// Names of the registered stamp templates, sorted.
+    #[getter]
+    fn stamp_templates(&self) -> Vec<String> {
+        self.templates.names().map(str::to_string).collect()
+    }
+
+    /// Schedule a stamp to be applied once at simulation time `at_time`.
+    ///
+    /// `kind` is "explosion", "fire" or "sonar_ping"; `intensity` is the
+    /// ping strength for sonar. Returns a schedule ID for `cancel_scheduled`.
+    #[pyo3(signature = (kind, center, radius, at_time, intensity=1.0))]
+    fn schedule_stamp(
+        &mut self,
+        kind: &str,
+        center: (f32, f32, f32),
+        radius: f32,
+        at_time: f64,
+        intensity: f32,
+    ) -> PyResult<u64> {
+        let stamp = stamp_from_kind(kind, center, radius, intensity)?;
+        Ok(self.inner.schedule_stamp(stamp, at_time))
+    }
+
+    /// Schedule a stamp every `interval` seconds, first one interval from now.
+    ///
+    /// `count` limits the number of applications (None repeats forever).
+    /// Returns a schedule ID for `cancel_scheduled`.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// # Vent pulse every 30 s, forever
+    /// universe.schedule_periodic("fire", (100.0, 40.0, -50.0), 5.0, 30.0)
+    /// ```
+    #[pyo3(signature = (kind, center, radius, interval, count=None, intensity=1.0))]
+    fn schedule_periodic(
+        &mut self,
+        kind: &str,
+        center: (f32, f32, f32),
+        radius: f32,
+        interval: f64,
+        count: Option<u32>,
+        intensity: f32,
+    ) -> PyResult<u64> {
+        let stamp = stamp_from_kind(kind, center, radius, intensity)?;
+        self.inner
+            .schedule_periodic(stamp, interval, count)
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("interval and count must be positive"))
+    }
+
+    /// Cancel a scheduled stamp. Returns whether it was pending.
+    fn cancel_scheduled(&mut self, id: u64) -> bool {
+        self.inner.cancel_scheduled(id)
+    }
+
+    /// Number of pending stamp schedules.
+    #[getter]
+    fn pending_stamps(&self) -> usize {
+        self.inner.scheduler().len()
+    }
+
+    /// Make fields inside a box immutable to stamps and propagation.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// # Keep the coastline intact under shellfire
+    /// universe.freeze_region((-512.0, -512.0, -128.0), (0.0, 512.0, 128.0), ["occupancy", "depth"])
+    /// ```
+    fn freeze_region(&mut self, min: (f32, f32, f32), max: (f32, f32, f32), fields: Vec<String>) {
+        let bounds = murk::Bounds::from_min_max(
+            glam::Vec3::new(min.0, min.1, min.2),
+            glam::Vec3::new(max.0, max.1, max.2),
+        );
+        let fields: Vec<murk::Field> = fields.iter().map(|f| str_to_field(f)).collect();
+        self.inner.freeze_region(bounds, &fields);
+    }
+
+    /// Remove all frozen regions.
+    fn clear_frozen(&mut self) {
+        self.inner.clear_frozen();
+    }
+
+    /// Octree size and usage counters, for capacity planning and
+    /// performance tracking.
+    ///
+    /// Returns a dict with `node_count`, `leaf_count`, `max_depth`,
+    /// `nodes_by_depth` (list, root first), `memory_bytes`, `memory_budget`
+    /// (or `None`), `evictions`, `stamps_applied`, `queries_served`,
+    /// `nodes_visited`, `avg_nodes_visited` and `last_step_seconds` (or
+    /// `None` before the first step). Usage counters are not cleared by
+    /// `reset()` without a seed.
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = self.inner.stats();
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("node_count", stats.node_count)?;
+        dict.set_item("leaf_count", stats.leaf_count)?;
+        dict.set_item("max_depth", stats.max_depth)?;
+        dict.set_item("nodes_by_depth", &stats.nodes_by_depth)?;
+        dict.set_item("memory_bytes", stats.memory_bytes)?;
+        dict.set_item("memory_budget", stats.memory_budget)?;
+        dict.set_item("evictions", stats.evictions)?;
+        dict.set_item("stamps_applied", stats.stamps_applied)?;
+        dict.set_item("queries_served", stats.queries_served)?;
+        dict.set_item("nodes_visited", stats.nodes_visited)?;
+        dict.set_item("avg_nodes_visited", stats.avg_nodes_visited())?;
+        dict.set_item(
+            "last_step_seconds",
+            stats.last_step_duration.map(|d| d.as_secs_f64()),
+        )?;
+        Ok(dict)
+    }
+
+    /// Describe the octree for debugging, e.g. why a region won't coarsen.
+    ///
+    /// Returns a dict with `node_count`, `leaf_count`, `memory_bytes` (an
+    /// estimate), `memory_budget` (or `None`), `evictions` (subtrees
+    /// coarsened to fit the budget) and `root`, a nested node dict with keys `min`, `max`,
+    /// `depth`, `state` (`"empty"`, `"leaf"` or `"internal"`), `uniform`
+    /// (variance within the merge threshold), `subtree_nodes`, `stats`
+    /// (field name to `mean`/`variance`/`min`/`max`, or `None` if empty)
+    /// and `children`. Children are listed down to `max_depth`.
+    #[pyo3(signature = (max_depth=3))]
+    fn debug_tree<'py>(
+        &self,
+        py: Python<'py>,
+        max_depth: u8,
+    ) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let stats = self.inner.stats();
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("node_count", stats.node_count)?;
+        dict.set_item("leaf_count", stats.leaf_count)?;
+        dict.set_item("memory_bytes", stats.memory_bytes)?;
+        dict.set_item("memory_budget", stats.memory_budget)?;
+        dict.set_item("evictions", stats.evictions)?;
+        let root = self.inner.octree().debug_tree(max_depth);
+        dict.set_item("root", node_summary_dict(py, &root)?)?;
+        Ok(dict)
+    }
+
     /// Query a point.
     fn query_point(&self, position: (f32, f32, f32)) -> PyPointResult {
         let position = glam::Vec3::new(position.0, position.1, position.2);
@@ -229,6 +621,178 @@ impl PyUniverse {
         PyQueryResult { inner: result }
     }
 
+    /// Query many volumes in one traversal of the octree.
+    ///
+    /// `queries` is a list of `(center, radius)` pairs, all answered at the
+    /// same `resolution`. Returns one result per query, in order, equal to
+    /// calling `query_volume` for each.
+    ///
+    /// ```python
+    /// near, far = universe.query_batch([((0.0, 0.0, 0.0), 10.0), ((50.0, 0.0, 0.0), 40.0)])
+    /// ```
+    #[pyo3(signature = (queries, resolution="medium"))]
+    fn query_batch(
+        &self,
+        queries: Vec<((f32, f32, f32), f32)>,
+        resolution: &str,
+    ) -> Vec<PyQueryResult> {
+        let res = match resolution {
+            "coarse" => murk::QueryResolution::Coarse,
+            "fine" => murk::QueryResolution::Fine,
+            "full" => murk::QueryResolution::Full,
+            _ => murk::QueryResolution::Medium,
+        };
+        let queries: Vec<murk::VolumeQuery> = queries
+            .into_iter()
+            .map(|((x, y, z), radius)| {
+                murk::VolumeQuery::new(glam::Vec3::new(x, y, z), radius).with_resolution(res)
+            })
+            .collect();
+        self.inner
+            .query_batch(&queries)
+            .into_iter()
+            .map(|inner| PyQueryResult { inner })
+            .collect()
+    }
+
+    /// Extract the surface where a field crosses a threshold.
+    ///
+    /// The region defaults to the whole world. Returns `(vertices, indices)`
+    /// as numpy arrays of shape (N, 3) float32 and (M, 3) uint32; triangle
+    /// normals point toward values below the threshold.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// universe.stamp_fire((0.0, 0.0, 0.0), 10.0)
+    /// vertices, indices = universe.extract_isosurface("smoke", 0.5)
+    /// ```
+    #[pyo3(signature = (field, threshold, region_min=None, region_max=None))]
+    #[allow(clippy::type_complexity)]
+    fn extract_isosurface<'py>(
+        &self,
+        py: Python<'py>,
+        field: &str,
+        threshold: f32,
+        region_min: Option<(f32, f32, f32)>,
+        region_max: Option<(f32, f32, f32)>,
+    ) -> PyResult<(Bound<'py, numpy::PyArray2<f32>>, Bound<'py, numpy::PyArray2<u32>>)> {
+        let region = self.region(region_min, region_max);
+        let mesh = self.inner.extract_isosurface(str_to_field(field), threshold, region);
+        let vertices: Vec<f32> = mesh.vertices.iter().flat_map(|v| v.to_array()).collect();
+        let indices: Vec<u32> = mesh.triangles.iter().flatten().copied().collect();
+        Ok((
+            vertices.to_pyarray(py).reshape([mesh.vertices.len(), 3])?,
+            indices.to_pyarray(py).reshape([mesh.triangles.len(), 3])?,
+        ))
+    }
+
+    /// Extract contour lines where a field crosses a threshold on the
+    /// horizontal slice at height `z`.
+    ///
+    /// The region defaults to the whole world; only its x/y extent is used.
+    /// Returns a list of (N, 2) float32 arrays; closed loops end with their
+    /// first point.
+    #[pyo3(signature = (field, threshold, z, region_min=None, region_max=None))]
+    fn extract_isolines<'py>(
+        &self,
+        py: Python<'py>,
+        field: &str,
+        threshold: f32,
+        z: f32,
+        region_min: Option<(f32, f32, f32)>,
+        region_max: Option<(f32, f32, f32)>,
+    ) -> PyResult<Vec<Bound<'py, numpy::PyArray2<f32>>>> {
+        let region = self.region(region_min, region_max);
+        self.inner
+            .extract_isolines(str_to_field(field), threshold, region, z)
+            .iter()
+            .map(|line| {
+                let points: Vec<f32> = line.iter().flat_map(|p| p.to_array()).collect();
+                points.to_pyarray(py).reshape([line.len(), 2])
+            })
+            .collect()
+    }
+
+    /// Find a surface route for a vessel of `draft` meters from `start` to
+    /// `goal`, on a grid of cells about `resolution` meters wide.
+    ///
+    /// Cells are blocked by occupancy of 0.5 or more, or by less than
+    /// `draft` meters of depth; unset depth counts as open water. Returns
+    /// the `(x, y, z)` waypoints from `start` to `goal`, or `None` if no
+    /// route exists. Drop the heights to sail it with `enable_convoy()`.
+    ///
+    /// Without costs the route is the shortest. Each move's length is
+    /// otherwise multiplied by one plus the penalties of the cell entered:
+    ///
+    /// - `field_costs`: `(field, zero, full, weight)` ramps, penalizing a
+    ///   cell `weight` when the field reads `full` and nothing at `zero`.
+    ///   `full` may be below `zero`, e.g. `("depth", 200.0, 20.0, 1.0)`
+    ///   prefers deep water.
+    /// - `threat`: 2D array of penalties indexed `[y, x]`, such as a threat
+    ///   heatmap, scaled by `threat_weight`. Its cells are `threat_cell_size`
+    ///   square from the `threat_min` corner; by default the array spans
+    ///   the world's width.
+    ///
+    /// ```python
+    /// route = universe.find_path((0.0, 0.0, 0.0), (400.0, 250.0, 0.0), draft=6.0)
+    /// quiet = universe.find_path(
+    ///     (0.0, 0.0, 0.0), (400.0, 250.0, 0.0), field_costs=[("noise", 60.0, 120.0, 5.0)]
+    /// )
+    /// ```
+    #[pyo3(signature = (
+        start,
+        goal,
+        draft=0.0,
+        resolution=10.0,
+        field_costs=None,
+        threat=None,
+        threat_min=None,
+        threat_cell_size=None,
+        threat_weight=1.0,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn find_path(
+        &self,
+        start: (f32, f32, f32),
+        goal: (f32, f32, f32),
+        draft: f32,
+        resolution: f32,
+        field_costs: Option<Vec<(String, f32, f32, f32)>>,
+        threat: Option<Vec<Vec<f32>>>,
+        threat_min: Option<(f32, f32)>,
+        threat_cell_size: Option<f32>,
+        threat_weight: f32,
+    ) -> PyResult<Option<Vec<(f32, f32, f32)>>> {
+        let mut costs = murk::PathCosts::new();
+        for (field, zero, full, weight) in field_costs.unwrap_or_default() {
+            let cost = murk::FieldCost::new(str_to_field(&field), zero, full, weight);
+            costs = costs.with_field(cost);
+        }
+        if let Some(rows) = threat {
+            let width = rows.first().map_or(0, Vec::len);
+            if rows.iter().any(|row| row.len() != width) {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "threat rows must all have the same length",
+                ));
+            }
+            let world = self.inner.bounds();
+            let min = threat_min.map_or(world.min.truncate(), |(x, y)| Vec2::new(x, y));
+            let cell_size =
+                threat_cell_size.unwrap_or((world.max.x - world.min.x) / width.max(1) as f32);
+            let dims = [width, rows.len()];
+            let grid = murk::CostGrid::new(min, cell_size, dims, rows.concat(), threat_weight)
+                .ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err("threat_cell_size must be positive")
+                })?;
+            costs = costs.with_grid(grid);
+        }
+        let start = glam::Vec3::new(start.0, start.1, start.2);
+        let goal = glam::Vec3::new(goal.0, goal.1, goal.2);
+        let route = self.inner.find_path_with_costs(start, goal, draft, resolution, &costs);
+        Ok(route.map(|route| route.iter().map(|p| (p.x, p.y, p.z)).collect()))
+    }
+
     /// Advance simulation by dt seconds.
     ///
     /// Releases the GIL during computation for better Python threading.
@@ -264,6 +828,8 @@ impl PyUniverse {
             // Re-create with seed
             let config = murk::UniverseConfig {
                 bounds: self.inner.bounds(),
+                memory_budget: self.inner.octree().config().memory_budget,
+                planar: self.inner.octree().config().planar,
                 ..Default::default()
             };
             self.inner = murk::Universe::new_with_seed(config, s);
@@ -285,6 +851,8 @@ impl PyUniverse {
     ///   - `radius_inner`: Inner radius of shell
     ///   - `radius_outer`: Outer radius of shell
     ///   - `sectors`: Number of angular divisions
+    /// * `normalize` - Emit each field on its canonical scale (see
+    ///   `Field.normalize`) instead of raw units
     ///
     /// # Returns
     ///
@@ -304,13 +872,14 @@ impl PyUniverse {
     ///     ],
     /// )
     /// ```
-    #[pyo3(signature = (position, heading, shells=None))]
+    #[pyo3(signature = (position, heading, shells=None, normalize=false))]
     fn observe_foveated<'py>(
         &self,
         py: Python<'py>,
         position: (f32, f32, f32),
         heading: (f32, f32, f32),
         shells: Option<&Bound<'py, PyList>>,
+        normalize: bool,
     ) -> PyResult<Bound<'py, PyArray1<f32>>> {
         let position = glam::Vec3::new(position.0, position.1, position.2);
         let heading = glam::Vec3::new(heading.0, heading.1, heading.2);
@@ -357,10 +926,33 @@ impl PyUniverse {
         let query = murk::query::FoveatedQuery::new(position, heading).with_shells(shell_configs);
 
         let result = self.inner.observe_foveated(&query);
-        let flat = result.to_flat_vec(&query.fields);
+        let flat = if normalize {
+            result.to_normalized_flat_vec(&query.fields)
+        } else {
+            result.to_flat_vec(&query.fields)
+        };
 
         Ok(flat.to_pyarray(py))
     }
+
+    /// Pickle support: rebuilt with default arguments, then `__setstate__`.
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyType>, (), Bound<'py, PyBytes>)> {
+        let universe = slf.borrow();
+        let state = to_state_bytes(&UniverseStateRef {
+            universe: &universe.inner,
+            stamp_templates: &universe.templates,
+        })?;
+        Ok((slf.get_type(), (), PyBytes::new(slf.py(), &state)))
+    }
+
+    fn __setstate__(&mut self, state: &[u8]) -> PyResult<()> {
+        let state: UniverseState = from_state_bytes(state)?;
+        self.inner = state.universe;
+        self.templates = state.stamp_templates;
+        Ok(())
+    }
 }
 
 /// Point query result wrapper.
@@ -402,6 +994,14 @@ impl PyPointResult {
     fn interpolated(&self) -> bool {
         self.inner.interpolated
     }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PointResult(depth={}, interpolated={})",
+            self.inner.depth,
+            if self.inner.interpolated { "True" } else { "False" }
+        )
+    }
 }
 
 /// Volume query result wrapper.
@@ -432,6 +1032,14 @@ impl PyQueryResult {
         self.inner.mean(field)
     }
 
+    /// Get the mean of a field on its canonical normalized scale.
+    ///
+    /// See `Field.normalize`. Accepts either a Field enum or a string.
+    fn normalized(&self, field: FieldOrStr) -> f32 {
+        let field: murk::Field = field.into();
+        self.inner.normalized(field)
+    }
+
     /// Get variance for a field.
     ///
     /// Accepts either a Field enum or a string for backwards compatibility.
@@ -456,29 +1064,100 @@ impl PyQueryResult {
         self.inner.max(field)
     }
 
+    /// Estimate the value below which fraction `p` (0-1) of a field's
+    /// samples fall.
+    fn percentile(&self, field: FieldOrStr, p: f32) -> f32 {
+        let field: murk::Field = field.into();
+        self.inner.percentile(field, p)
+    }
+
+    /// Median of a field.
+    fn p50(&self, field: FieldOrStr) -> f32 {
+        let field: murk::Field = field.into();
+        self.inner.p50(field)
+    }
+
+    /// 90th percentile of a field.
+    fn p90(&self, field: FieldOrStr) -> f32 {
+        let field: murk::Field = field.into();
+        self.inner.p90(field)
+    }
+
+    /// Histogram of a field as (min, max, counts) with `buckets`
+    /// equal-width buckets spanning the field's min and max.
+    #[pyo3(signature = (field, buckets=8))]
+    fn histogram(&self, field: FieldOrStr, buckets: usize) -> (f32, f32, Vec<f32>) {
+        let field: murk::Field = field.into();
+        let histogram = self.inner.histogram(field, buckets);
+        (histogram.min, histogram.max, histogram.counts)
+    }
+
+    /// Fraction (0-1) of the queried volume holding non-default data.
+    #[getter]
+    fn coverage(&self) -> f32 {
+        self.inner.coverage()
+    }
+
     /// Get nodes visited.
     #[getter]
     fn nodes_visited(&self) -> u32 {
         self.inner.nodes_visited
     }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "QueryResult(coverage={:.2}, nodes_visited={}, max_depth_reached={})",
+            self.inner.coverage(),
+            self.inner.nodes_visited,
+            self.inner.max_depth_reached
+        )
+    }
 }
 
 /// Unique entity identifier exposed to Python.
-#[pyclass(frozen, eq, hash)]
+#[pyclass(module = "tidebreak._tidebreak", frozen, eq, hash)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PyEntityId(EntityId);
 
 #[pymethods]
 impl PyEntityId {
+    /// Create an ID from its raw u64 value.
+    #[new]
+    fn new(value: u64) -> Self {
+        Self(EntityId::new(value))
+    }
+
     /// Get the raw u64 value.
     #[getter]
     fn value(&self) -> u64 {
         self.0.as_u64()
     }
 
+    /// Create an ID in a producer namespace (high 16 bits).
+    #[staticmethod]
+    fn namespaced(namespace: u16, local: u64) -> Self {
+        Self(EntityId::namespaced(namespace, local))
+    }
+
+    /// Producer namespace (high 16 bits).
+    #[getter]
+    fn namespace(&self) -> u16 {
+        self.0.namespace()
+    }
+
+    /// ID within the namespace (low 48 bits).
+    #[getter]
+    fn local(&self) -> u64 {
+        self.0.local()
+    }
+
     fn __repr__(&self) -> String {
         format!("EntityId({})", self.0.as_u64())
     }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> (Bound<'py, PyType>, (u64,)) {
+        (slf.get_type(), (slf.get().0.as_u64(),))
+    }
 }
 
 impl From<EntityId> for PyEntityId {
@@ -580,6 +1259,12 @@ pub struct PyPhysicsState {
     pub max_speed: f32,
     #[pyo3(get)]
     pub max_turn_rate: f32,
+    /// Ordered throttle fraction, or None under direct velocity control
+    #[pyo3(get)]
+    pub throttle: Option<f32>,
+    /// Heading the ship is still turning toward, if any
+    #[pyo3(get)]
+    pub ordered_heading: Option<f32>,
 }
 
 impl From<&PhysicsState> for PyPhysicsState {
@@ -590,6 +1275,8 @@ impl From<&PhysicsState> for PyPhysicsState {
             angular_velocity: p.angular_velocity,
             max_speed: p.max_speed,
             max_turn_rate: p.max_turn_rate,
+            throttle: p.throttle,
+            ordered_heading: p.ordered_heading,
         }
     }
 }
@@ -632,6 +1319,11 @@ pub struct PyCombatState {
     pub is_destroyed: bool,
     #[pyo3(get)]
     pub is_mobility_disabled: bool,
+    /// Shield pool, or None without mitigation
+    #[pyo3(get)]
+    pub shield: Option<f32>,
+    #[pyo3(get)]
+    pub max_shield: Option<f32>,
 }
 
 impl From<&CombatState> for PyCombatState {
@@ -642,6 +1334,8 @@ impl From<&CombatState> for PyCombatState {
             weapon_count: c.weapons.len(),
             is_destroyed: c.status_flags.contains(StatusFlags::DESTROYED),
             is_mobility_disabled: c.status_flags.contains(StatusFlags::MOBILITY_DISABLED),
+            shield: c.mitigation.as_ref().map(|m| m.pool),
+            max_shield: c.mitigation.as_ref().map(|m| m.capacity),
         }
     }
 }
@@ -675,6 +1369,15 @@ pub struct PyEntity {
     transform: PyTransformState,
     physics: Option<PyPhysicsState>,
     combat: Option<PyCombatState>,
+    team: Option<u32>,
+    controller: Option<String>,
+    attributes: Attributes,
+    labels: Vec<String>,
+    metadata: EntityMetadata,
+    display_name: String,
+    mine_detected: Option<bool>,
+    signature: Option<(f32, f32, f32)>,
+    submarine: Option<SubmarineState>,
 }
 
 impl PyEntity {
@@ -704,6 +1407,18 @@ impl PyEntity {
             transform,
             physics,
             combat,
+            team: entity.team().map(TeamId::as_u32),
+            controller: entity.controller().map(|c| c.to_string()),
+            attributes: entity.attributes().clone(),
+            labels: entity.labels().iter().cloned().collect(),
+            metadata: entity.metadata().clone(),
+            display_name: entity.display_name(),
+            mine_detected: entity.mine().map(|m| m.detected),
+            signature: entity.as_ship().map(|c| {
+                let s = c.current_signature();
+                (s.radar_cross_section, s.acoustic_level, s.thermal)
+            }),
+            submarine: entity.submarine().copied(),
         }
     }
 }
@@ -740,298 +1455,5089 @@ impl PyEntity {
         self.combat.clone()
     }
 
+    /// Team the entity belongs to (None if neutral).
+    #[getter]
+    fn team(&self) -> Option<u32> {
+        self.team
+    }
+
+    /// Controller of the entity, such as "agent:0" (None if uncontrolled).
+    #[getter]
+    fn controller(&self) -> Option<String> {
+        self.controller.clone()
+    }
+
+    /// Labels carried by the entity, sorted.
+    #[getter]
+    fn labels(&self) -> Vec<String> {
+        self.labels.clone()
+    }
+
+    /// Attribute store as a dict of bool, int, float or str values.
+    #[getter]
+    fn attributes(&self) -> BTreeMap<String, PyAttributeValue> {
+        self.attributes
+            .iter()
+            .map(|(k, v)| (k.clone(), PyAttributeValue::from(v)))
+            .collect()
+    }
+
+    /// Display name, e.g. "Resolute" (None if unnamed).
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.metadata.name.clone()
+    }
+
+    /// Class label, e.g. "Daring-class destroyer" (None if unset).
+    #[getter]
+    fn class_name(&self) -> Option<String> {
+        self.metadata.class_name.clone()
+    }
+
+    /// Hull number or pennant, e.g. "D32" (None if unset).
+    #[getter]
+    fn hull_number(&self) -> Option<String> {
+        self.metadata.hull_number.clone()
+    }
+
+    /// Display color as (r, g, b) in 0-255 (None if unset).
+    #[getter]
+    fn color(&self) -> Option<(u8, u8, u8)> {
+        self.metadata.color.map(|[r, g, b]| (r, g, b))
+    }
+
+    /// Name for viewers and logs: the name and hull number if set,
+    /// otherwise the tag and ID, such as "Ship 3".
+    #[getter]
+    fn display_name(&self) -> String {
+        self.display_name.clone()
+    }
+
+    /// Whether a mine has been found by minesweeping; None if not a mine.
+    #[getter]
+    fn mine_detected(&self) -> Option<bool> {
+        self.mine_detected
+    }
+
+    /// Current signature as (radar_cross_section m², acoustic_level dB,
+    /// thermal), adjusted for speed, damage and emissions; None if not a
+    /// ship.
+    #[getter]
+    fn signature(&self) -> Option<(f32, f32, f32)> {
+        self.signature
+    }
+
+    /// Depth in meters below the surface; None if not a submarine.
+    #[getter]
+    fn depth(&self) -> Option<f32> {
+        self.submarine.map(|s| s.depth)
+    }
+
+    /// Battery charge as a fraction of capacity; None if not a submarine.
+    #[getter]
+    fn battery(&self) -> Option<f32> {
+        self.submarine.as_ref().map(SubmarineState::battery_fraction)
+    }
+
     /// Check if entity is a ship.
     fn is_ship(&self) -> bool {
         matches!(self.tag, PyEntityTag::Ship)
     }
 
+    /// Check if entity is a mine.
+    fn is_mine(&self) -> bool {
+        self.mine_detected.is_some()
+    }
+
     /// Check if entity is destroyed.
     fn is_destroyed(&self) -> bool {
         self.combat.as_ref().is_some_and(|c| c.is_destroyed)
     }
 
     fn __repr__(&self) -> String {
-        format!("Entity(id={}, tag={:?})", self.id.value(), self.tag)
+        let name = self.metadata.name.as_ref().map_or(String::new(), |n| format!(", name={n:?}"));
+        format!("Entity(id={}, tag={:?}{name})", self.id.value(), self.tag)
     }
 }
 
 /// Main simulation orchestrator.
-#[pyclass]
+#[pyclass(module = "tidebreak._tidebreak")]
 pub struct PySimulation {
     inner: Simulation,
+    interest: InterestManager,
+    frames: FrameHistory,
+    /// Proximity triggers and their observer tags, re-registered by `reset()`
+    proximity: Vec<(Vec<EntityTag>, Arc<ProximityPlugin>)>,
+    /// Order follower, re-registered by `reset()`
+    order_follower: Option<Arc<OrderFollowerPlugin>>,
+    /// Ship sensors feeding classification, re-registered by `reset()`
+    sensors: Option<Arc<SensorPlugin>>,
+    /// Convoy routes, re-registered by `reset()`
+    convoys: Vec<Arc<ConvoyPlugin>>,
+    /// Scenario objectives summarized in observations, kept by `reset()`
+    objectives: Objectives,
+    /// Opponent league and the team it plays, re-drawn by `reset()`
+    league: Option<(League, TeamId)>,
+    /// This episode's league opponent
+    opponent: Option<Arc<ScriptedPolicy>>,
+    /// Tuning config: physics timestep and spawned ship defaults
+    config: TidebreakConfig,
+    /// First tick whose trigger stamps `apply_trigger_stamps()` has not
+    /// written yet
+    stamps_from: u64,
 }
 
 #[pymethods]
 impl PySimulation {
-    /// Create a new simulation with the given seed.
+    /// Create a new simulation.
+    ///
+    /// `config` tunes the run (see `tidebreak_core::config`): a path to a
+    /// TOML file, or a dict of overrides. `overrides` is applied on top of
+    /// it, so a scenario file can be swept from Python:
+    ///
+    ///     Simulation(config="frigates.toml", overrides={"physics.max_speed": 14.0})
+    ///
+    /// Dict keys are section names with nested dicts ({"physics":
+    /// {"max_speed": 14.0}}) or dotted paths ("physics.max_speed"). The
+    /// config sets the physics timestep, decision interval, and the
+    /// physics and sensors of ships from `spawn_ship()`. `seed`, if given,
+    /// overrides the config's seed (default 42).
+    ///
+    /// Raises InvalidValue for unknown keys or invalid values, and OSError
+    /// if the file cannot be read.
     #[new]
-    #[pyo3(signature = (seed=42))]
-    fn new(seed: u64) -> Self {
-        Self {
-            inner: Simulation::new(seed),
+    #[pyo3(signature = (seed=None, config=None, overrides=None))]
+    fn new(
+        seed: Option<u64>,
+        config: Option<&Bound<'_, PyAny>>,
+        overrides: Option<&Bound<'_, pyo3::types::PyDict>>,
+    ) -> PyResult<Self> {
+        let mut tidebreak = TidebreakConfig::default();
+        if let Some(config) = config {
+            if let Ok(layer) = config.downcast::<pyo3::types::PyDict>() {
+                merge_config_dict(&mut tidebreak, layer)?;
+            } else {
+                let path: PathBuf = config.extract()?;
+                tidebreak.merge_file(path).map_err(config_error)?;
+            }
+        }
+        if let Some(overrides) = overrides {
+            merge_config_dict(&mut tidebreak, overrides)?;
         }
+        if let Some(seed) = seed {
+            tidebreak.simulation.seed = seed;
+        }
+        let inner = tidebreak.build();
+        Ok(Self {
+            stamps_from: inner.tick() + 1,
+            inner,
+            interest: InterestManager::default(),
+            frames: FrameHistory::default(),
+            proximity: Vec::new(),
+            order_follower: None,
+            sensors: None,
+            convoys: Vec::new(),
+            objectives: Objectives::default(),
+            league: None,
+            opponent: None,
+            config: tidebreak,
+        })
     }
 
-    /// Current tick number.
+    /// The full tuning config of this simulation, as TOML.
     #[getter]
-    fn tick(&self) -> u64 {
-        self.inner.tick()
+    fn config_toml(&self) -> String {
+        self.config.to_toml()
     }
 
-    /// Master seed.
-    #[getter]
-    fn seed(&self) -> u64 {
-        self.inner.seed()
-    }
+    /// Build a simulation from a battle package (JSON, schema "arena.v1").
+    ///
+    /// Returns the simulation, seeded with the package seed, and a dict
+    /// mapping each `ship_id` to its entity ID. Raises InvalidValue if the
+    /// package is malformed or fails validation.
+    #[staticmethod]
+    fn from_package(package: &str) -> PyResult<(Self, BTreeMap<String, PyEntityId>)> {
+        let package: BattlePackage = serde_json::from_str(package)
+            .map_err(|e| InvalidValue::new_err(e.to_string()))?;
+        let (inner, ids) = package
+            .build()
+            .map_err(|e| InvalidValue::new_err(e.to_string()))?;
+        let sim = Self {
+            stamps_from: inner.tick() + 1,
+            inner,
+            interest: InterestManager::default(),
+            frames: FrameHistory::default(),
+            proximity: Vec::new(),
+            order_follower: None,
+            sensors: None,
+            convoys: Vec::new(),
+            objectives: Objectives::default(),
+            league: None,
+            opponent: None,
+            config: TidebreakConfig::default(),
+        };
+        Ok((sim, ids.into_iter().map(|(k, v)| (k, v.into())).collect()))
+    }
+
+    /// Start a simulation in the state a replay's run had just before
+    /// `tick`, to continue it with different actions.
+    ///
+    /// The fork has the recorded seed, decision interval and timestep, no
+    /// plugins and the default config otherwise, which reproduces runs
+    /// driven from Python. Raises InvalidValue for a tick outside the
+    /// recording or if the run cannot be reproduced; for runs with plugins,
+    /// set up a matching simulation and use `Replay.seek` instead.
+    ///
+    /// ```python
+    /// fork = tidebreak.Simulation.fork_from(replay, 640)
+    /// branch = replay.branch(640)
+    /// fork.apply_action(ship, {"heading": 1.57})
+    /// branch.record_step(fork)
+    /// ```
+    #[staticmethod]
+    fn fork_from(py: Python, replay: &PyReplay, tick: u64) -> PyResult<Self> {
+        let inner = py
+            .allow_threads(|| Simulation::fork_from(&replay.inner, tick))
+            .map_err(replay_error)?;
+        let mut config = TidebreakConfig::default();
+        config.simulation.seed = inner.seed();
+        config.simulation.action_interval = inner.action_interval();
+        config.physics.dt = inner.physics_dt();
+        Ok(Self {
+            stamps_from: inner.tick() + 1,
+            inner,
+            interest: InterestManager::default(),
+            frames: FrameHistory::default(),
+            proximity: Vec::new(),
+            order_follower: None,
+            sensors: None,
+            convoys: Vec::new(),
+            objectives: Objectives::default(),
+            league: None,
+            opponent: None,
+            config,
+        })
+    }
+
+    /// Current tick number.
+    #[getter]
+    fn tick(&self) -> u64 {
+        self.inner.tick()
+    }
+
+    /// Master seed.
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.inner.seed()
+    }
+
+    /// Number of entities in the arena.
+    #[getter]
+    fn entity_count(&self) -> usize {
+        self.inner.arena().entity_count()
+    }
+
+    /// Hash of the arena state; two simulations with equal hashes are in
+    /// the same state.
+    #[getter]
+    fn state_hash(&self) -> u64 {
+        self.inner.arena().state_hash()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Simulation(seed={}, tick={}, entities={})",
+            self.inner.seed(),
+            self.inner.tick(),
+            self.inner.arena().entity_count()
+        )
+    }
+
+    /// Pickle support: rebuilt from the seed, then `__setstate__`.
+    ///
+    /// The state covers the arena, the commands held between decisions,
+    /// scores, heatmap and kill tallies, and everything `reset()` keeps, so
+    /// an unpickled simulation steps exactly like the original. An open
+    /// battle log, the RNG audit and event history entries, and stacked
+    /// observation frames are not carried over.
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<(Bound<'py, PyType>, (u64,), Bound<'py, PyBytes>)> {
+        let sim = slf.borrow();
+        let inner = &sim.inner;
+        let state = to_state_bytes(&SimulationState {
+            arena: inner.arena(),
+            sort_key: sim.interest.sort_key(),
+            action_interval: inner.action_interval(),
+            config: sim.config.clone(),
+            held_commands: inner.held_commands().to_vec(),
+            scoring: inner.score_keeper().map(|keeper| (keeper.rules().clone(), keeper.state())),
+            heatmap: inner.heatmap(),
+            weapon_assignment: inner.weapon_assignment().map(|a| *a.config()),
+            classification: inner.classification().map(|c| *c.model()),
+            kill_ledger: inner.kill_ledger().tallies().into_iter().collect(),
+            plugin_budget: inner.plugin_watchdog().budget().copied(),
+            rng_audit_capacity: inner.rng_audit().capacity(),
+            event_history_capacity: inner.event_history().capacity(),
+            proximity: sim
+                .proximity
+                .iter()
+                .map(|(observers, plugin)| ProximityTrigger::of(observers, plugin))
+                .collect(),
+            order_follower: sim
+                .order_follower
+                .as_ref()
+                .map(|plugin| (plugin.arrival_radius(), plugin.engage_range())),
+            sensors: sim.sensors.is_some(),
+            convoys: sim.convoys.iter().map(|plugin| ConvoyRoute::of(plugin)).collect(),
+            objectives: sim.objectives.clone(),
+            league: sim.league.clone(),
+            opponent: sim.opponent.as_ref().map(|policy| policy.name().to_string()),
+        })?;
+        Ok((
+            slf.get_type(),
+            (sim.inner.seed(),),
+            PyBytes::new(slf.py(), &state),
+        ))
+    }
+
+    fn __setstate__(&mut self, state: &[u8]) -> PyResult<()> {
+        let state: SimulationState<Arena> = from_state_bytes(state)?;
+        // Enable what `configured()` carries over, then rebuild through it
+        let inner = &mut self.inner;
+        inner.set_action_interval(state.action_interval);
+        if let Some((rules, _)) = &state.scoring {
+            inner.enable_scoring(rules.clone());
+        }
+        if let Some(heatmap) = &state.heatmap {
+            inner.enable_heatmap(heatmap.config);
+        }
+        if let Some(config) = state.weapon_assignment {
+            inner.enable_weapon_assignment(config);
+        }
+        if let Some(model) = state.classification {
+            inner.enable_classification(model);
+        }
+        inner.plugin_watchdog_mut().set_budget(state.plugin_budget);
+        inner.rng_audit().enable(state.rng_audit_capacity);
+        inner.enable_event_history(state.event_history_capacity);
+        self.config = state.config;
+        self.order_follower = state.order_follower.map(|(arrival_radius, engage_range)| {
+            Arc::new(
+                OrderFollowerPlugin::new()
+                    .with_arrival_radius(arrival_radius)
+                    .with_engage_range(engage_range),
+            )
+        });
+        self.sensors = state.sensors.then(|| Arc::new(SensorPlugin::new()));
+        self.convoys = state.convoys.iter().map(|route| Arc::new(route.plugin())).collect();
+        self.proximity = state
+            .proximity
+            .iter()
+            .map(|trigger| (trigger.observers.clone(), Arc::new(trigger.plugin())))
+            .collect();
+        self.objectives = state.objectives;
+        self.league = state.league;
+        self.inner = self.configured(self.inner.seed());
+        self.register_proximity();
+        // The league has moved on since the opponent was drawn; look it up
+        self.opponent = None;
+        let opponent = self.league.as_ref().zip(state.opponent).and_then(|((league, team), name)| {
+            league.opponent(&name).map(|opponent| opponent.policy(*team))
+        });
+        if let Some(policy) = opponent {
+            self.set_opponent(policy);
+        }
+
+        self.inner.restore(state.arena);
+        self.stamps_from = self.inner.tick() + 1;
+        self.inner.set_held_commands(state.held_commands);
+        if let (Some(keeper), Some((_, scores))) = (self.inner.score_keeper(), state.scoring) {
+            keeper.restore(scores);
+        }
+        if let (Some(recorder), Some(heatmap)) = (self.inner.heatmap_recorder(), state.heatmap) {
+            recorder.restore(heatmap);
+        }
+        self.inner.kill_ledger().restore(state.kill_ledger.into_iter().collect());
+        self.interest = InterestManager::new(state.sort_key);
+        self.frames.clear();
+        Ok(())
+    }
+
+    /// Execute one simulation step.
+    ///
+    /// Releases the GIL during execution for better Python threading.
+    fn step(&mut self, py: Python) {
+        py.allow_threads(|| {
+            self.inner.step();
+        });
+    }
+
+    /// Physics ticks per agent decision.
+    ///
+    /// Plugins run only on decision ticks; in between, their velocity and
+    /// heading commands are repeated. Setting 0 or 1 decides every tick.
+    #[getter]
+    fn action_interval(&self) -> u32 {
+        self.inner.action_interval()
+    }
+
+    #[setter]
+    fn set_action_interval(&mut self, interval: u32) {
+        self.inner.set_action_interval(interval);
+    }
+
+    /// Whether the current state is a decision point.
+    #[getter]
+    fn is_decision_tick(&self) -> bool {
+        self.inner.is_decision_tick()
+    }
+
+    /// Step to the next decision point, returning the number of ticks run.
+    ///
+    /// Releases the GIL during execution.
+    ///
+    /// ```python
+    /// sim.action_interval = 12  # 60 Hz physics, 5 Hz decisions
+    /// while True:
+    ///     obs = sim.get_observation(ship_id)
+    ///     sim.apply_action(ship_id, policy(obs))
+    ///     sim.step_decision()
+    /// ```
+    fn step_decision(&mut self, py: Python) -> u64 {
+        py.allow_threads(|| self.inner.step_decision())
+    }
+
+    /// Run `ticks` steps in the loop's default executor, returning an awaitable.
+    ///
+    /// Must be called from a running asyncio event loop, which stays free
+    /// while the ticks execute. The awaitable resolves to the number of
+    /// ticks completed. Setting `cancel` (or cancelling the awaiting task)
+    /// stops the run at the next tick boundary or before the current tick's
+    /// resolution; an abandoned tick leaves the state untouched.
+    ///
+    /// While the run is in progress the simulation is borrowed; other calls
+    /// on it raise RuntimeError.
+    ///
+    /// ```python
+    /// token = tidebreak.CancellationToken()
+    /// done = await sim.step_async(ticks=100, cancel=token)
+    /// ```
+    #[pyo3(signature = (ticks=1, cancel=None))]
+    fn step_async(
+        slf: Py<Self>,
+        py: Python,
+        ticks: u64,
+        cancel: Option<PyCancellationToken>,
+    ) -> PyResult<PyObject> {
+        let flag = cancel.map_or_else(|| Arc::new(AtomicBool::new(false)), |c| c.flag);
+        let worker = StepWorker {
+            sim: slf,
+            ticks,
+            flag: Arc::clone(&flag),
+        };
+        let future = py
+            .import("asyncio")?
+            .call_method0("get_running_loop")?
+            .call_method1("run_in_executor", (py.None(), worker))?;
+        future.call_method1("add_done_callback", (CancelOnDone { flag },))?;
+        Ok(future.unbind())
+    }
+
+    /// Run one episode per seed from the current state, calling `policy` at
+    /// decision points, and return a result dict per episode in seed order.
+    ///
+    /// Each episode starts from a copy of this simulation's arena under its
+    /// seed, set up as `reset(seed)` would (bounds, scoring, plugins and
+    /// league opponent); proximity triggers stay with this simulation. It
+    /// runs for `max_ticks` ticks. At each decision point `policy(episode)`
+    /// is called with the episode's Simulation to read observations and
+    /// apply actions; a truthy return value ends the episode early. Ticks
+    /// between decisions run with the GIL released, and an exception from
+    /// the policy aborts the batch.
+    ///
+    /// With `parallel`, episodes advance in lockstep: the policy is called
+    /// for every running episode in seed order, then all of them step to
+    /// their next decision point on a thread pool. Results are the same
+    /// either way unless the policy carries state between episodes.
+    ///
+    /// Each result has "seed", "ticks", "decisions", "terminated" (ended by
+    /// the policy), "scores" (as from `scores()`) and "survivors" (IDs of
+    /// the ships not destroyed).
+    ///
+    /// ```python
+    /// def policy(episode):
+    ///     obs = episode.get_observation(ship_id)
+    ///     episode.apply_action(ship_id, act(obs))
+    ///
+    /// results = sim.run_batch(range(1000), policy, max_ticks=3000)
+    /// ```
+    #[pyo3(signature = (seeds, policy, max_ticks, parallel=false))]
+    fn run_batch<'py>(
+        &self,
+        py: Python<'py>,
+        seeds: Vec<u64>,
+        policy: &Bound<'py, PyAny>,
+        max_ticks: u64,
+        parallel: bool,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let results = PyList::empty(py);
+        if parallel {
+            let mut episodes = seeds
+                .iter()
+                .map(|&seed| BatchEpisode::new(py, self.episode(seed), max_ticks))
+                .collect::<PyResult<Vec<_>>>()?;
+            BatchEpisode::run_lockstep(py, &mut episodes, policy)?;
+            for episode in &episodes {
+                results.append(episode.result(py)?)?;
+            }
+        } else {
+            for seed in seeds {
+                let mut episode = BatchEpisode::new(py, self.episode(seed), max_ticks)?;
+                while episode.decide(py, policy)? {
+                    let mut sim = episode.sim.try_borrow_mut(py)?;
+                    let (inner, end) = (&mut sim.inner, episode.end);
+                    py.allow_threads(|| BatchEpisode::advance(inner, end));
+                }
+                results.append(episode.result(py)?)?;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Report entities crossing the distances in `radii`.
+    ///
+    /// Every entity with one of the `observers` tags (default: ships)
+    /// watches the other entities, optionally limited to the `targets`
+    /// tags and to `team` ("any", "friendly" or "hostile"; unteamed
+    /// entities are hostile). Crossings are read with `proximity_events()`.
+    /// Triggers survive `reset()`.
+    #[pyo3(signature = (radii, observers=None, targets=None, team="any"))]
+    fn add_proximity_trigger(
+        &mut self,
+        radii: Vec<f32>,
+        observers: Option<Vec<PyEntityTag>>,
+        targets: Option<Vec<PyEntityTag>>,
+        team: &str,
+    ) -> PyResult<()> {
+        let team = match team.to_lowercase().as_str() {
+            "any" => TeamFilter::Any,
+            "friendly" => TeamFilter::Friendly,
+            "hostile" => TeamFilter::Hostile,
+            other => {
+                return Err(InvalidValue::new_err(format!(
+                    "unknown team filter '{other}', expected 'any', 'friendly' or 'hostile'"
+                )))
+            }
+        };
+        let trigger = ProximityTrigger {
+            // Each trigger keeps its own record of the targets in range
+            id: format!("proximity_{}", self.proximity.len()),
+            observers: observers.map_or_else(
+                || vec![EntityTag::Ship],
+                |tags| tags.into_iter().map(EntityTag::from).collect(),
+            ),
+            radii,
+            targets: targets.map_or_else(Vec::new, |tags| {
+                tags.into_iter().map(EntityTag::from).collect()
+            }),
+            team,
+        };
+        let plugin = trigger.plugin();
+        if plugin.radii().is_empty() {
+            return Err(InvalidValue::new_err("radii must contain a positive distance"));
+        }
+        let plugin = Arc::new(plugin);
+        for tag in &trigger.observers {
+            self.inner.plugins_mut().register(*tag, plugin.clone());
+        }
+        self.proximity.push((trigger.observers, plugin));
+        Ok(())
+    }
+
+    /// Proximity crossings of the last step as a list of dicts with keys
+    /// "kind" ("entered" or "left"), "observer", "target" and "radius".
+    ///
+    /// Does not drain the step's events.
+    fn proximity_events<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let list = PyList::empty(py);
+        for envelope in self.inner.events() {
+            let (kind, observer, target, radius) = match envelope.output().as_event() {
+                Some(Event::EnteredRange {
+                    observer,
+                    target,
+                    radius,
+                }) => ("entered", observer, target, radius),
+                Some(Event::LeftRange {
+                    observer,
+                    target,
+                    radius,
+                }) => ("left", observer, target, radius),
+                _ => continue,
+            };
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("kind", kind)?;
+            dict.set_item("observer", PyEntityId::from(*observer))?;
+            dict.set_item("target", PyEntityId::from(*target))?;
+            dict.set_item("radius", *radius)?;
+            list.append(dict)?;
+        }
+        Ok(list)
+    }
+
+    /// Make ships and squadrons carry out their standing orders.
+    ///
+    /// Each step, an entity with a standing order steers toward it:
+    /// "move_to" stops within `arrival_radius` of the destination,
+    /// "engage" closes to `engage_range` of its own track of the target,
+    /// and "screen" keeps station on the protected entity. Survives
+    /// `reset()`. Raises RuntimeError if already enabled.
+    #[pyo3(signature = (
+        arrival_radius=OrderFollowerPlugin::DEFAULT_ARRIVAL_RADIUS,
+        engage_range=OrderFollowerPlugin::DEFAULT_ENGAGE_RANGE,
+    ))]
+    fn enable_order_following(&mut self, arrival_radius: f32, engage_range: f32) -> PyResult<()> {
+        if self.order_follower.is_some() {
+            return Err(PyRuntimeError::new_err("order following is already enabled"));
+        }
+        let plugin = Arc::new(
+            OrderFollowerPlugin::new()
+                .with_arrival_radius(arrival_radius)
+                .with_engage_range(engage_range),
+        );
+        for tag in [EntityTag::Ship, EntityTag::Squadron] {
+            self.inner.plugins_mut().register(tag, plugin.clone());
+        }
+        self.order_follower = Some(plugin);
+        Ok(())
+    }
+
+    /// Sail the ships labelled `label` (default: merchants from
+    /// `spawn_merchant()`) along `route`, a list of `(x, y)` waypoints.
+    ///
+    /// Each merchant takes the waypoints in order at `cruise_throttle`,
+    /// counting one as reached within `arrival_radius`. When any merchant
+    /// of a team is hit, that team's convoy scatters away from its center
+    /// at full speed for `scatter_ticks`, then resumes the route. A
+    /// merchant reaching the last waypoint stops and counts as delivered
+    /// (see `enable_scoring(delivery=...)`). Call once per convoy label;
+    /// survives `reset()`.
+    #[pyo3(signature = (
+        route,
+        label=MERCHANT_LABEL,
+        arrival_radius=ConvoyPlugin::DEFAULT_ARRIVAL_RADIUS,
+        cruise_throttle=ConvoyPlugin::DEFAULT_CRUISE_THROTTLE,
+        scatter_ticks=ConvoyPlugin::DEFAULT_SCATTER_TICKS,
+    ))]
+    fn enable_convoy(
+        &mut self,
+        route: Vec<(f32, f32)>,
+        label: &str,
+        arrival_radius: f32,
+        cruise_throttle: f32,
+        scatter_ticks: u64,
+    ) -> PyResult<()> {
+        if route.is_empty() {
+            return Err(InvalidValue::new_err("route must contain a waypoint"));
+        }
+        if self.convoys.iter().any(|c| c.label() == label) {
+            return Err(PyRuntimeError::new_err(format!(
+                "a convoy is already enabled for '{label}'"
+            )));
+        }
+        let route = ConvoyRoute {
+            route: route.into_iter().map(|(x, y)| Vec2::new(x, y)).collect(),
+            label: label.to_string(),
+            arrival_radius,
+            cruise_throttle,
+            scatter_ticks,
+        };
+        let plugin = Arc::new(route.plugin());
+        self.inner.plugins_mut().register(EntityTag::Ship, plugin.clone());
+        self.convoys.push(plugin);
+        Ok(())
+    }
+
+    /// Set the scenario objectives reported in the objectives block of
+    /// observations (see `PyObservation.objectives`).
+    ///
+    /// `zones` lists `(x, y, radius)` zones to hold, `convoy_label` the
+    /// label of the convoy ships to protect or hunt (e.g. "merchant"), and
+    /// `mission_ticks` the tick the mission timer runs out at. Replaces
+    /// any objectives set before; survives `reset()`. Raises InvalidValue
+    /// for a non-positive radius or a zero mission length.
+    #[pyo3(signature = (zones=None, convoy_label=None, mission_ticks=None))]
+    fn set_objectives(
+        &mut self,
+        zones: Option<Vec<(f32, f32, f32)>>,
+        convoy_label: Option<String>,
+        mission_ticks: Option<u64>,
+    ) -> PyResult<()> {
+        let zones = zones.unwrap_or_default();
+        if zones.iter().any(|&(_, _, radius)| !(radius.is_finite() && radius > 0.0)) {
+            return Err(InvalidValue::new_err("zone radius must be positive"));
+        }
+        if mission_ticks == Some(0) {
+            return Err(InvalidValue::new_err("mission_ticks must be at least 1"));
+        }
+        self.objectives = Objectives {
+            zones: zones
+                .into_iter()
+                .map(|(x, y, radius)| Zone::new(Vec2::new(x, y), radius))
+                .collect(),
+            convoy_label,
+            mission_ticks,
+        };
+        Ok(())
+    }
+
+    /// Play `team` with frozen opponents drawn from a league.
+    ///
+    /// `league` is a path to a league TOML file or an equivalent dict (see
+    /// `tidebreak_core::league`): a selection rule ("round_robin",
+    /// "uniform" or "pfsp") and a list of named opponents with scripted
+    /// policy parameters. An opponent is drawn now and on every `reset()`,
+    /// from the seed and league state only, so seeded runs replay the same
+    /// opponents. Report each episode's outcome with
+    /// `record_league_result()`. Raises InvalidValue for a malformed
+    /// league, OSError if the file cannot be read, and RuntimeError if a
+    /// league is already enabled.
+    fn enable_league(&mut self, league: &Bound<'_, PyAny>, team: u32) -> PyResult<()> {
+        if self.league.is_some() {
+            return Err(PyRuntimeError::new_err("a league is already enabled"));
+        }
+        let league = if let Ok(dict) = league.downcast::<pyo3::types::PyDict>() {
+            let toml::Value::Table(table) = config_value(dict.as_any())? else {
+                unreachable!("dicts convert to tables");
+            };
+            let text = toml::to_string(&table).map_err(|e| InvalidValue::new_err(e.to_string()));
+            League::from_toml(&text?)
+        } else {
+            League::load(league.extract::<PathBuf>()?)
+        }
+        .map_err(league_error)?;
+        self.league = Some((league, TeamId::new(team)));
+        self.draw_opponent();
+        Ok(())
+    }
+
+    /// Name of this episode's league opponent, or None without a league.
+    #[getter]
+    fn league_opponent(&self) -> Option<String> {
+        self.opponent.as_ref().map(|policy| policy.name().to_string())
+    }
+
+    /// Record the learner's result against this episode's opponent: 1 for
+    /// a win, 0.5 for a draw, 0 for a loss.
+    ///
+    /// Later PFSP draws weight opponents by these results. Raises
+    /// RuntimeError without a league, and InvalidValue for a result
+    /// outside 0-1.
+    fn record_league_result(&mut self, result: f32) -> PyResult<()> {
+        let (Some((league, _)), Some(opponent)) = (&mut self.league, &self.opponent) else {
+            return Err(PyRuntimeError::new_err("no league is enabled"));
+        };
+        league.record(opponent.name(), result).map_err(league_error)
+    }
+
+    /// The league with its episode count and results, as TOML, or None
+    /// without a league. Pass a saved copy to `enable_league()` to resume.
+    #[getter]
+    fn league_toml(&self) -> Option<String> {
+        self.league.as_ref().map(|(league, _)| league.to_toml())
+    }
+
+    /// Spawn an unarmed, slow, tough merchant ship labelled `label`,
+    /// optionally assigned to a team.
+    #[pyo3(signature = (x, y, heading=0.0, team=None, label=MERCHANT_LABEL, id=None))]
+    fn spawn_merchant(
+        &mut self,
+        x: f32,
+        y: f32,
+        heading: f32,
+        team: Option<u32>,
+        label: &str,
+        id: Option<PyEntityId>,
+    ) -> PyResult<PyEntityId> {
+        let ship = ShipComponents::merchant(Vec2::new(x, y), heading);
+        let id = self.spawn_entity(EntityTag::Ship, EntityInner::Ship(ship), id)?;
+        self.inner.arena_mut().add_label(id, label);
+        self.inner.arena_mut().set_team(id, team.map(TeamId::new));
+        Ok(id.into())
+    }
+
+    /// Order `subordinate` to `(x, y)` at `speed` m/s (0 = max speed).
+    ///
+    /// Replaces the subordinate's standing order. Returns False, issuing
+    /// nothing, unless both entities exist, differ and share a team.
+    #[pyo3(signature = (commander, subordinate, x, y, speed=0.0))]
+    fn order_move_to(
+        &mut self,
+        commander: PyEntityId,
+        subordinate: PyEntityId,
+        x: f32,
+        y: f32,
+        speed: f32,
+    ) -> bool {
+        self.issue_order(Order::MoveTo {
+            commander: commander.into(),
+            subordinate: subordinate.into(),
+            destination: Vec2::new(x, y),
+            speed,
+        })
+    }
+
+    /// Order `subordinate` to engage `target`; see `order_move_to`.
+    fn order_engage(
+        &mut self,
+        commander: PyEntityId,
+        subordinate: PyEntityId,
+        target: PyEntityId,
+    ) -> bool {
+        self.issue_order(Order::Engage {
+            commander: commander.into(),
+            subordinate: subordinate.into(),
+            target: target.into(),
+        })
+    }
+
+    /// Order `subordinate` to screen `protect` from `distance` meters at
+    /// `bearing` radians off its heading; see `order_move_to`.
+    #[pyo3(signature = (commander, subordinate, protect, distance, bearing=0.0))]
+    fn order_screen(
+        &mut self,
+        commander: PyEntityId,
+        subordinate: PyEntityId,
+        protect: PyEntityId,
+        distance: f32,
+        bearing: f32,
+    ) -> bool {
+        self.issue_order(Order::Screen {
+            commander: commander.into(),
+            subordinate: subordinate.into(),
+            protect: protect.into(),
+            distance,
+            bearing,
+        })
+    }
+
+    /// Withdraw the standing order of `subordinate`; see `order_move_to`.
+    fn cancel_order(&mut self, commander: PyEntityId, subordinate: PyEntityId) -> bool {
+        self.issue_order(Order::Cancel {
+            commander: commander.into(),
+            subordinate: subordinate.into(),
+        })
+    }
+
+    /// Standing order of an entity as a dict, or None.
+    ///
+    /// Keys are "kind" ("move_to", "engage" or "screen"), "commander" and
+    /// "issued_tick", plus "x", "y" and "speed" for move_to, "target" for
+    /// engage, and "protect", "distance" and "bearing" for screen.
+    fn standing_order<'py>(
+        &self,
+        py: Python<'py>,
+        entity_id: PyEntityId,
+    ) -> PyResult<Option<Bound<'py, pyo3::types::PyDict>>> {
+        let Some(standing) = self.inner.arena().orders().get(entity_id.into()) else {
+            return Ok(None);
+        };
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("commander", PyEntityId::from(standing.order.commander()))?;
+        dict.set_item("issued_tick", standing.issued_tick)?;
+        match standing.order {
+            Order::MoveTo {
+                destination, speed, ..
+            } => {
+                dict.set_item("kind", "move_to")?;
+                dict.set_item("x", destination.x)?;
+                dict.set_item("y", destination.y)?;
+                dict.set_item("speed", speed)?;
+            }
+            Order::Engage { target, .. } => {
+                dict.set_item("kind", "engage")?;
+                dict.set_item("target", PyEntityId::from(target))?;
+            }
+            Order::Screen {
+                protect,
+                distance,
+                bearing,
+                ..
+            } => {
+                dict.set_item("kind", "screen")?;
+                dict.set_item("protect", PyEntityId::from(protect))?;
+                dict.set_item("distance", distance)?;
+                dict.set_item("bearing", bearing)?;
+            }
+            // Never filed
+            Order::Cancel { .. } => return Ok(None),
+        }
+        Ok(Some(dict))
+    }
+
+    /// Stamp this tick's damage, destruction and shell splash events into
+    /// `universe`.
+    ///
+    /// Damage scorches the target's surroundings; destroyed ships leave an
+    /// explosion, a debris field and a burning wreck that persist in the
+    /// universe; gun rounds that miss raise spray where they land. With
+    /// `spawn_wrecks`, a platform labelled "wreck" is spawned at each
+    /// destroyed ship. Drains the events of the last step.
+    ///
+    /// Returns the IDs of spawned wrecks.
+    #[pyo3(signature = (universe, spawn_wrecks=false))]
+    fn apply_wreckage(&mut self, universe: &mut PyUniverse, spawn_wrecks: bool) -> Vec<PyEntityId> {
+        let system = WreckageSystem::with_config(WreckageConfig {
+            spawn_wrecks,
+            ..WreckageConfig::default()
+        });
+        let events = self.inner.take_events();
+        system
+            .apply(&events, self.inner.arena_mut(), &mut universe.inner)
+            .into_iter()
+            .map(PyEntityId::from)
+            .collect()
+    }
+
+    /// Write the smoke screens laid by ships into `universe`.
+    ///
+    /// Raises the Smoke field inside each cloud to the cloud's density at
+    /// altitude `z`; call after each step to keep the universe in sync.
+    #[pyo3(signature = (universe, z=0.0))]
+    fn apply_smoke(&self, universe: &mut PyUniverse, z: f32) {
+        let stamps = self.inner.arena().environment().smoke_stamps(z);
+        universe.inner.stamp_many(&stamps);
+    }
+
+    /// Write the stamps of scenario triggers that fired since the last call
+    /// into `universe`, at altitude `z`; call after each step or decision.
+    #[pyo3(signature = (universe, z=0.0))]
+    fn apply_trigger_stamps(&mut self, universe: &mut PyUniverse, z: f32) {
+        let arena = self.inner.arena();
+        let stamps = arena.triggers().stamps_fired_since(self.stamps_from, z);
+        universe.inner.stamp_many(&stamps);
+        self.stamps_from = arena.current_tick() + 1;
+    }
+
+    /// Names of the scenario triggers that have fired, mapped to the tick
+    /// their effects first showed in.
+    fn fired_triggers(&self) -> BTreeMap<String, u64> {
+        let triggers = self.inner.arena().triggers();
+        triggers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, trigger)| Some((trigger.name.clone(), triggers.fired(index)?)))
+            .collect()
+    }
+
+    /// Tick a scenario trigger ended the episode in, or None.
+    #[getter]
+    fn episode_ended(&self) -> Option<u64> {
+        self.inner.arena().triggers().episode_end()
+    }
+
+    /// A team's rules of engagement: "weapons_free" or "hold_fire".
+    fn rules_of_engagement(&self, team: u32) -> &'static str {
+        roe_to_str(self.inner.arena().rules_of_engagement(TeamId::new(team)))
+    }
+
+    /// Set a team's rules of engagement ("weapons_free" or "hold_fire").
+    ///
+    /// Ships of a team holding fire cannot fire. Raises InvalidValue for
+    /// other names.
+    fn set_rules_of_engagement(&mut self, team: u32, roe: &str) -> PyResult<()> {
+        let roe = str_to_roe(roe)?;
+        self.inner.arena_mut().set_rules_of_engagement(TeamId::new(team), roe);
+        Ok(())
+    }
+
+    /// Start streaming the battle log (Arrow IPC files) into `directory`.
+    ///
+    /// Any log already open is closed first. Raises OSError if the files
+    /// cannot be created.
+    ///
+    /// With `fog_of_war=True` (evaluation mode) the log also writes each
+    /// team's believed tracks to "tracks.arrows", per-team sensor metrics
+    /// (position error, track continuity, time to detect) to "fog.arrows"
+    /// and track association metrics (OSPA, track purity, fragmentation) to
+    /// "association.arrows", to compare against the ground truth in
+    /// "entities.arrows".
+    #[pyo3(signature = (directory, batch_ticks=64, entity_summary_every=1, fog_of_war=false))]
+    fn open_battle_log(
+        &mut self,
+        directory: PathBuf,
+        batch_ticks: u64,
+        entity_summary_every: u64,
+        fog_of_war: bool,
+    ) -> PyResult<()> {
+        let config = BattleLogConfig {
+            batch_ticks,
+            entity_summary_every,
+            fog_of_war,
+            ..BattleLogConfig::new(directory)
+        };
+        Ok(self.inner.open_battle_log(config)?)
+    }
+
+    /// Flush and close the battle log, if open.
+    ///
+    /// Raises OSError if writing the log failed at any point.
+    fn close_battle_log(&mut self) -> PyResult<()> {
+        Ok(self.inner.close_battle_log()?)
+    }
+
+    /// Whether a battle log is being written.
+    #[getter]
+    fn has_battle_log(&self) -> bool {
+        self.inner.has_battle_log()
+    }
+
+    /// Spawn a ship at the given position, optionally assigned to a team.
+    ///
+    /// `submarine` spawns a surfaced submarine with a full battery, which
+    /// dives with the "depth" action.
+    ///
+    /// `id` spawns with an explicit ID (e.g. when merging content produced
+    /// in another ID namespace); raises InvalidValue if it is already in use.
+    #[pyo3(signature = (x, y, heading=0.0, team=None, id=None, submarine=false))]
+    fn spawn_ship(
+        &mut self,
+        x: f32,
+        y: f32,
+        heading: f32,
+        team: Option<u32>,
+        id: Option<PyEntityId>,
+        submarine: bool,
+    ) -> PyResult<PyEntityId> {
+        let mut components = self.config.ship(Vec2::new(x, y), heading);
+        if submarine {
+            components = components.with_submarine(SubmarineState::new());
+        }
+        let id = self.spawn_entity(EntityTag::Ship, EntityInner::Ship(components), id)?;
+        self.inner.arena_mut().set_team(id, team.map(TeamId::new));
+        Ok(id.into())
+    }
+
+    /// Namespace stamped on automatically assigned entity IDs.
+    ///
+    /// Give each process generating scenario content its own namespace so
+    /// their entities can be merged without ID collisions. 0 (the default)
+    /// yields plain sequential IDs.
+    #[getter]
+    fn id_namespace(&self) -> u16 {
+        self.inner.arena().id_namespace()
+    }
+
+    #[setter]
+    fn set_id_namespace(&mut self, namespace: u16) {
+        self.inner.arena_mut().set_id_namespace(namespace);
+    }
+
+    /// Spawn a stationary supply depot holding `fuel` and `ammo`.
+    ///
+    /// `ammo` maps ammunition type names ("bullet", "missile", "torpedo",
+    /// "shell", "depth_charge", "countermeasure", "smoke") to round counts. Raises
+    /// InvalidValue for an unknown type or negative fuel.
+    #[pyo3(signature = (x, y, fuel=5000.0, ammo=None, team=None, id=None))]
+    fn spawn_depot(
+        &mut self,
+        x: f32,
+        y: f32,
+        fuel: f32,
+        ammo: Option<BTreeMap<String, u32>>,
+        team: Option<u32>,
+        id: Option<PyEntityId>,
+    ) -> PyResult<PyEntityId> {
+        if !(fuel.is_finite() && fuel >= 0.0) {
+            return Err(InvalidValue::new_err(
+                "fuel must be finite and non-negative",
+            ));
+        }
+        let mut stockpile = InventoryState::new(fuel);
+        for (name, count) in ammo.unwrap_or_default() {
+            stockpile.ammo.insert(str_to_ammo(&name)?, count);
+        }
+        let components = PlatformComponents::at_position(Vec2::new(x, y)).with_stockpile(stockpile);
+        let id = self.spawn_entity(EntityTag::Platform, EntityInner::Platform(components), id)?;
+        self.inner.arena_mut().set_team(id, team.map(TeamId::new));
+        Ok(id.into())
+    }
+
+    /// Fuel and ammunition held by a ship or depot.
+    ///
+    /// Returns (fuel, {ammo_type: rounds}), or None if the entity does not
+    /// exist or holds no supplies.
+    fn get_inventory(&self, entity_id: PyEntityId) -> Option<(f32, BTreeMap<String, u32>)> {
+        let inventory = self.inner.arena().get(entity_id.into())?.inventory()?;
+        let ammo = inventory
+            .ammo
+            .iter()
+            .map(|(ammo_type, count)| (ammo_to_str(*ammo_type).to_string(), *count))
+            .collect();
+        Some((inventory.fuel, ammo))
+    }
+
+    /// Request a transfer of `amount` of `cargo` from one entity to another.
+    ///
+    /// `cargo` is "fuel" or an ammunition type name (see `spawn_depot`). The
+    /// transfer completes once both entities have stayed within range and
+    /// speed-matched for the required number of ticks, moving as much as
+    /// the giver holds and the receiver has room for.
+    ///
+    /// Returns False if the same kind of cargo is already pending between
+    /// the two entities. Raises UnknownEntity, NotSupportedForTag (entity
+    /// holds no supplies) or InvalidValue (unknown cargo, non-positive
+    /// amount, or a transfer to itself).
+    fn transfer_cargo(
+        &mut self,
+        from_id: PyEntityId,
+        to_id: PyEntityId,
+        cargo: &str,
+        amount: f32,
+    ) -> PyResult<bool> {
+        let (from, to): (EntityId, EntityId) = (from_id.into(), to_id.into());
+        for id in [from, to] {
+            let Some(entity) = self.inner.arena().get(id) else {
+                return Err(UnknownEntity::new_err(format!(
+                    "entity {} does not exist",
+                    id.as_u64()
+                )));
+            };
+            if entity.inventory().is_none() {
+                return Err(NotSupportedForTag::new_err(format!(
+                    "entity {} is a {:?} without supplies",
+                    id.as_u64(),
+                    entity.tag()
+                )));
+            }
+        }
+        if from == to {
+            return Err(InvalidValue::new_err(
+                "cannot transfer cargo to the same entity",
+            ));
+        }
+        if !(amount.is_finite() && amount > 0.0) {
+            return Err(InvalidValue::new_err("amount must be finite and positive"));
+        }
+        let cargo = if cargo.eq_ignore_ascii_case("fuel") {
+            Cargo::Fuel(amount)
+        } else {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let rounds = amount as u32;
+            Cargo::Ammo(str_to_ammo(cargo)?, rounds)
+        };
+        Ok(self
+            .inner
+            .arena_mut()
+            .logistics_mut()
+            .request(from, to, cargo))
+    }
+
+    /// Assign an entity to a team (None makes it neutral).
+    ///
+    /// Returns False if the entity does not exist.
+    #[pyo3(signature = (entity_id, team=None))]
+    fn set_team(&mut self, entity_id: PyEntityId, team: Option<u32>) -> bool {
+        self.inner
+            .arena_mut()
+            .set_team(entity_id.into(), team.map(TeamId::new))
+    }
+
+    /// Set an entity's cosmetic name, class label, hull number and
+    /// (r, g, b) color, replacing any it had; omitted fields are cleared.
+    ///
+    /// Returns False if the entity does not exist.
+    #[pyo3(signature = (entity_id, name=None, class_name=None, hull_number=None, color=None))]
+    fn set_metadata(
+        &mut self,
+        entity_id: PyEntityId,
+        name: Option<String>,
+        class_name: Option<String>,
+        hull_number: Option<String>,
+        color: Option<(u8, u8, u8)>,
+    ) -> bool {
+        let metadata = EntityMetadata {
+            name,
+            class_name,
+            hull_number,
+            color: color.map(|(r, g, b)| [r, g, b]),
+        };
+        self.inner.arena_mut().set_metadata(entity_id.into(), metadata)
+    }
+
+    /// Give a ship or squadron a regenerating shield, absorbing damage
+    /// before its hull.
+    ///
+    /// Resistances are fractions in [0, 1] removed from each damage type
+    /// before the shield. The shield starts full and regenerates at
+    /// `regen_rate` per second once `regen_delay` seconds pass without a hit.
+    #[pyo3(signature = (
+        entity_id, capacity, regen_rate, regen_delay=3.0, kinetic=0.0, explosive=0.0, energy=0.0
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn set_mitigation(
+        &mut self,
+        entity_id: PyEntityId,
+        capacity: f32,
+        regen_rate: f32,
+        regen_delay: f32,
+        kinetic: f32,
+        explosive: f32,
+        energy: f32,
+    ) -> PyResult<()> {
+        if capacity < 0.0 || regen_rate < 0.0 || regen_delay < 0.0 {
+            return Err(InvalidValue::new_err(
+                "capacity, regen_rate and regen_delay must be non-negative",
+            ));
+        }
+        let mitigation = MitigationState::new(capacity, regen_rate)
+            .with_regen_delay(regen_delay)
+            .with_resistances(Resistances {
+                kinetic,
+                explosive,
+                energy,
+            });
+        *self.mitigation_mut(entity_id)? = Some(mitigation);
+        Ok(())
+    }
+
+    /// Remove an entity's shield, so damage goes straight to its hull.
+    fn clear_mitigation(&mut self, entity_id: PyEntityId) -> PyResult<()> {
+        *self.mitigation_mut(entity_id)? = None;
+        Ok(())
+    }
+
+    /// Hand control of an entity to `controller` (None releases it).
+    ///
+    /// Controllers are written "human:N", "scripted:N" or "agent:N". Once
+    /// set, only that controller's `apply_action(..., controller=...)`
+    /// calls may command the entity. Returns False if the entity does not
+    /// exist; raises InvalidValue for a malformed controller.
+    #[pyo3(signature = (entity_id, controller=None))]
+    fn set_controller(
+        &mut self,
+        entity_id: PyEntityId,
+        controller: Option<&str>,
+    ) -> PyResult<bool> {
+        let controller = controller.map(str_to_controller).transpose()?;
+        Ok(self
+            .inner
+            .arena_mut()
+            .set_controller(entity_id.into(), controller))
+    }
+
+    /// Opt the entities of `controller` into collision-avoidance steering.
+    ///
+    /// `controller` is written like in `set_controller`; None covers
+    /// entities without a controller, such as scripted traffic. Each tick,
+    /// their commanded motion is blended with the velocity change that
+    /// keeps them `margin` meters clear of hulls within `range` meters over
+    /// the next `horizon` seconds, weighted by `blend` (0 to 1). Throttled
+    /// ships are turned within their turning limits; others have their
+    /// velocity adjusted. Raises InvalidValue for a malformed controller or
+    /// a negative or non-finite setting.
+    #[pyo3(signature = (controller=None, horizon=30.0, range=1000.0, margin=20.0, blend=1.0))]
+    fn set_steering_assist(
+        &mut self,
+        controller: Option<&str>,
+        horizon: f32,
+        range: f32,
+        margin: f32,
+        blend: f32,
+    ) -> PyResult<()> {
+        if [horizon, range, margin, blend]
+            .iter()
+            .any(|v| !(v.is_finite() && *v >= 0.0))
+        {
+            return Err(InvalidValue::new_err(
+                "steering assist settings must be finite and non-negative",
+            ));
+        }
+        let assist = SteeringAssist {
+            horizon,
+            range,
+            margin,
+            blend: blend.min(1.0),
+        };
+        self.set_assist(controller, Some(assist))
+    }
+
+    /// Turn collision-avoidance steering off for `controller` (None for
+    /// entities without a controller).
+    #[pyo3(signature = (controller=None))]
+    fn clear_steering_assist(&mut self, controller: Option<&str>) -> PyResult<()> {
+        self.set_assist(controller, None)
+    }
+
+    /// IDs of all entities under `controller`, sorted by ID.
+    fn controlled_entities(&self, controller: &str) -> PyResult<Vec<PyEntityId>> {
+        let controller = str_to_controller(controller)?;
+        Ok(self
+            .inner
+            .arena()
+            .controlled_by(controller)
+            .map(PyEntityId::from)
+            .collect())
+    }
+
+    /// Add a label to an entity. Returns False if the entity does not exist.
+    fn add_label(&mut self, entity_id: PyEntityId, label: &str) -> bool {
+        self.inner.arena_mut().add_label(entity_id.into(), label)
+    }
+
+    /// Remove a label from an entity. Returns False if it was not labelled.
+    fn remove_label(&mut self, entity_id: PyEntityId, label: &str) -> bool {
+        self.inner.arena_mut().remove_label(entity_id.into(), label)
+    }
+
+    /// IDs of all entities carrying `label`, sorted by ID.
+    fn query_by_label(&self, label: &str) -> Vec<PyEntityId> {
+        self.inner
+            .arena()
+            .query_by_label(label)
+            .map(PyEntityId::from)
+            .collect()
+    }
+
+    /// Set an entity attribute (bool, int, float or str); None removes it.
+    ///
+    /// Returns False if the entity does not exist.
+    #[pyo3(signature = (entity_id, key, value=None))]
+    fn set_attribute(
+        &mut self,
+        entity_id: PyEntityId,
+        key: &str,
+        value: Option<PyAttributeValue>,
+    ) -> bool {
+        self.inner
+            .arena_mut()
+            .set_attribute(entity_id.into(), key, value.map(AttributeValue::from))
+    }
+
+    /// Get an entity attribute, or None if the entity or key is missing.
+    fn get_attribute(&self, entity_id: PyEntityId, key: &str) -> Option<PyAttributeValue> {
+        self.inner
+            .arena()
+            .get(entity_id.into())?
+            .attribute(key)
+            .map(PyAttributeValue::from)
+    }
+
+    /// Configure the intent channel.
+    ///
+    /// Omitted arguments keep their current value.
+    #[pyo3(signature = (range=None, latency_ticks=None, max_intent_len=None))]
+    fn configure_comms(
+        &mut self,
+        range: Option<f32>,
+        latency_ticks: Option<u64>,
+        max_intent_len: Option<usize>,
+    ) {
+        let comms = self.inner.arena_mut().comms_mut();
+        let current = *comms.config();
+        comms.set_config(CommsConfig {
+            range: range.unwrap_or(current.range),
+            latency_ticks: latency_ticks.unwrap_or(current.latency_ticks),
+            max_intent_len: max_intent_len.unwrap_or(current.max_intent_len),
+        });
+    }
+
+    /// Add a circular jamming zone that blocks intent delivery.
+    fn add_jammer(&mut self, x: f32, y: f32, radius: f32) {
+        self.inner
+            .arena_mut()
+            .comms_mut()
+            .add_jammer(JammingZone::new(Vec2::new(x, y), radius));
+    }
+
+    /// Remove all jamming zones.
+    fn clear_jammers(&mut self) {
+        self.inner.arena_mut().comms_mut().clear_jammers();
+    }
+
+    /// Broadcast an intent vector from an entity for the current tick.
+    ///
+    /// Raises ValueError if the intent is longer than `max_intent_len`.
+    fn broadcast_intent(&mut self, entity_id: PyEntityId, intent: Vec<f32>) -> PyResult<()> {
+        let tick = self.inner.tick();
+        self.inner
+            .arena_mut()
+            .comms_mut()
+            .broadcast(entity_id.into(), intent, tick)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Get entity by ID.
+    fn get_entity(&self, id: PyEntityId) -> Option<PyEntity> {
+        self.inner.arena().get(id.into()).map(PyEntity::from_entity)
+    }
+
+    /// Get all entity IDs.
+    fn entity_ids(&self) -> Vec<PyEntityId> {
+        self.inner
+            .arena()
+            .entity_ids_sorted()
+            .map(|id| id.into())
+            .collect()
+    }
+
+    /// Query entities within radius.
+    fn query_radius(&self, x: f32, y: f32, radius: f32) -> Vec<PyEntityId> {
+        self.inner
+            .arena()
+            .spatial()
+            .query_radius(Vec2::new(x, y), radius)
+            .into_iter()
+            .map(|id| id.into())
+            .collect()
+    }
+
+    /// Query the `k` entities nearest to (x, y), nearest first.
+    ///
+    /// With `tag`, only entities of that type are returned. Equal distances
+    /// are ordered by entity ID.
+    #[pyo3(signature = (x, y, k, tag=None))]
+    fn query_knearest(
+        &self,
+        x: f32,
+        y: f32,
+        k: usize,
+        tag: Option<PyEntityTag>,
+    ) -> Vec<PyEntityId> {
+        self.inner
+            .arena()
+            .spatial()
+            .query_knearest(Vec2::new(x, y), k, tag.map(EntityTag::from))
+            .into_iter()
+            .map(|id| id.into())
+            .collect()
+    }
+
+    /// Query entities whose hull intersects the segment `start`..`end`
+    /// swept with `width`, ordered by distance from `start`.
+    #[pyo3(signature = (start, end, width=0.0))]
+    fn query_segment(&self, start: (f32, f32), end: (f32, f32), width: f32) -> Vec<PyEntityId> {
+        self.inner
+            .arena()
+            .spatial()
+            .query_segment(Vec2::new(start.0, start.1), Vec2::new(end.0, end.1), width)
+            .into_iter()
+            .map(|id| id.into())
+            .collect()
+    }
+
+    /// Despawn an entity.
+    fn despawn(&mut self, id: PyEntityId) -> bool {
+        self.frames.remove(id.into());
+        self.inner.arena_mut().despawn(id.into()).is_some()
+    }
+
+    /// Attach `child_id` to `parent_id` so it rides on the parent.
+    ///
+    /// `offset` is the child's position in the parent's frame (+x ahead,
+    /// +y to port) and `heading` its heading relative to the parent's. The
+    /// child is moved onto the parent at once and follows it every tick.
+    /// With `cascade` it is despawned with the parent; otherwise it is
+    /// released. Returns False if either entity does not exist or the link
+    /// would form a cycle.
+    #[pyo3(signature = (child_id, parent_id, offset=(0.0, 0.0), heading=0.0, cascade=true))]
+    fn attach(
+        &mut self,
+        child_id: PyEntityId,
+        parent_id: PyEntityId,
+        offset: (f32, f32),
+        heading: f32,
+        cascade: bool,
+    ) -> bool {
+        let attachment = Attachment::new(parent_id.into())
+            .with_offset(Vec2::new(offset.0, offset.1))
+            .with_heading(heading)
+            .with_cascade(cascade);
+        self.inner.arena_mut().attach(child_id.into(), attachment)
+    }
+
+    /// Release an attached entity where it is. Returns False if it was not
+    /// attached.
+    fn detach(&mut self, child_id: PyEntityId) -> bool {
+        self.inner.arena_mut().detach(child_id.into()).is_some()
+    }
+
+    /// ID of the entity `child_id` is attached to, or None.
+    fn parent_of(&self, child_id: PyEntityId) -> Option<PyEntityId> {
+        self.inner
+            .arena()
+            .attachments()
+            .parent_of(child_id.into())
+            .map(PyEntityId::from)
+    }
+
+    /// IDs of the entities attached directly to `parent_id`, sorted by ID.
+    fn children_of(&self, parent_id: PyEntityId) -> Vec<PyEntityId> {
+        self.inner
+            .arena()
+            .attachments()
+            .children(parent_id.into())
+            .map(PyEntityId::from)
+            .collect()
+    }
+
+    /// Despawn `entity_id` `ticks` ticks from now, emitting an
+    /// "entity_expired" event, replacing any earlier schedule.
+    ///
+    /// The schedule is part of the simulation state, so unlike despawns
+    /// timed from Python it is snapshotted and replayed exactly. Returns
+    /// False if the entity does not exist.
+    fn set_ttl(&mut self, entity_id: PyEntityId, ticks: u64) -> bool {
+        self.inner.arena_mut().set_ttl(entity_id.into(), ticks)
+    }
+
+    /// Cancel a scheduled despawn. Returns False if none was scheduled.
+    fn clear_ttl(&mut self, entity_id: PyEntityId) -> bool {
+        self.inner.arena_mut().clear_ttl(entity_id.into()).is_some()
+    }
+
+    /// Tick at which `entity_id` is scheduled to be despawned, or None.
+    fn expiry(&self, entity_id: PyEntityId) -> Option<u64> {
+        self.inner.arena().expiry(entity_id.into())
+    }
+
+    /// Reset simulation with optional new seed.
+    #[pyo3(signature = (seed=None))]
+    fn reset(&mut self, seed: Option<u64>) {
+        self.inner = self.configured(seed.unwrap_or(self.inner.seed()));
+        self.stamps_from = self.inner.tick() + 1;
+        self.register_proximity();
+        self.draw_opponent();
+        self.interest.clear();
+        self.frames.clear();
+    }
+
+    /// Set the contact ordering used by observations.
+    ///
+    /// `sort_key` is "distance" (nearest first, the default), "threat"
+    /// (most threatening first), "quality" (best track first) or "track"
+    /// (the first tracks in track table order, as observations were laid
+    /// out before contacts were ranked).
+    fn set_contact_sort_key(&mut self, sort_key: &str) -> PyResult<()> {
+        self.interest.set_sort_key(str_to_sort_key(sort_key)?);
+        Ok(())
+    }
+
+    /// Confine moving entities to the rectangle `min`..`max`.
+    ///
+    /// `policy` decides what happens at the edges: "clamp" (stop at the
+    /// edge), "bounce" (reflect), "wrap" (re-enter opposite) or "despawn"
+    /// (remove, with an `EntityOutOfBounds` event). Bounds survive
+    /// `reset()`.
+    #[pyo3(signature = (min, max, policy="clamp"))]
+    fn set_bounds(&mut self, min: (f32, f32), max: (f32, f32), policy: &str) -> PyResult<()> {
+        let policy = match policy.to_lowercase().as_str() {
+            "clamp" => BoundaryPolicy::Clamp,
+            "bounce" => BoundaryPolicy::Bounce,
+            "wrap" => BoundaryPolicy::Wrap,
+            "despawn" => BoundaryPolicy::Despawn,
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown boundary policy '{other}', expected 'clamp', 'bounce', 'wrap' or 'despawn'"
+                )))
+            }
+        };
+        if min.0 >= max.0 || min.1 >= max.1 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "bounds min must be below max on both axes",
+            ));
+        }
+        self.inner.arena_mut().set_bounds(Some(WorldBounds::new(
+            Vec2::new(min.0, min.1),
+            Vec2::new(max.0, max.1),
+            policy,
+        )));
+        Ok(())
+    }
+
+    /// Remove the world bounds.
+    fn clear_bounds(&mut self) {
+        self.inner.arena_mut().set_bounds(None);
+    }
+
+    /// Keep an entity out of an area, returning the fence's index among
+    /// the entity's fences.
+    ///
+    /// Give either `circle` as `(x, y, radius)` or `polygon` as a list of
+    /// at least three `(x, y)` corners. After each step, an entity inside
+    /// one of its fences is handled by `policy`: "clamp" (push back to the
+    /// edge, keeping velocity along it) or "reject" (undo the step's move
+    /// and stop). Each entry is reported by `geofence_violations()`.
+    /// Fences are dropped with their entity and on `reset()`.
+    #[pyo3(signature = (entity_id, circle=None, polygon=None, policy="clamp"))]
+    fn add_geofence(
+        &mut self,
+        entity_id: PyEntityId,
+        circle: Option<(f32, f32, f32)>,
+        polygon: Option<Vec<(f32, f32)>>,
+        policy: &str,
+    ) -> PyResult<usize> {
+        let id = EntityId::from(entity_id);
+        if self.inner.arena().get(id).is_none() {
+            return Err(UnknownEntity::new_err(format!(
+                "entity {} does not exist",
+                id.as_u64()
+            )));
+        }
+        let policy = match policy.to_lowercase().as_str() {
+            "clamp" => FencePolicy::Clamp,
+            "reject" => FencePolicy::Reject,
+            other => {
+                return Err(InvalidValue::new_err(format!(
+                    "unknown fence policy '{other}', expected 'clamp' or 'reject'"
+                )))
+            }
+        };
+        let fence = match (circle, polygon) {
+            (Some((x, y, radius)), None) if radius > 0.0 => {
+                Geofence::circle(Vec2::new(x, y), radius)
+            }
+            (None, Some(corners)) if corners.len() >= 3 => {
+                Geofence::polygon(corners.into_iter().map(|(x, y)| Vec2::new(x, y)).collect())
+            }
+            _ => {
+                return Err(InvalidValue::new_err(
+                    "give either circle=(x, y, radius) with a positive radius or polygon \
+                     with at least three corners",
+                ))
+            }
+        };
+        Ok(self
+            .inner
+            .arena_mut()
+            .geofences_mut()
+            .add(id, fence.with_policy(policy)))
+    }
+
+    /// Remove every geofence of an entity.
+    fn clear_geofences(&mut self, entity_id: PyEntityId) {
+        self.inner
+            .arena_mut()
+            .geofences_mut()
+            .remove_entity(entity_id.into());
+    }
+
+    /// Geofence entries of the last step as a list of dicts with keys
+    /// "entity", "fence" (index from `add_geofence`) and "depth" (meters
+    /// the entity would have been inside).
+    ///
+    /// Does not drain the step's events.
+    fn geofence_violations<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let list = PyList::empty(py);
+        for envelope in self.inner.events() {
+            let Some(Event::GeofenceViolated {
+                entity,
+                fence,
+                depth,
+            }) = envelope.output().as_event()
+            else {
+                continue;
+            };
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("entity", PyEntityId::from(*entity))?;
+            dict.set_item("fence", *fence)?;
+            dict.set_item("depth", *depth)?;
+            list.append(dict)?;
+        }
+        Ok(list)
+    }
+
+    /// Drift entities with the sea currents of `universe`.
+    ///
+    /// Samples CurrentX/CurrentY across the universe at altitude `z`, at
+    /// most `spacing` apart. Each tick, ships, projectiles and squadrons
+    /// are carried by the current at their position times `ship`,
+    /// `projectile` or `squadron`. The snapshot is not updated as the
+    /// universe evolves; call again to refresh it. Currents survive
+    /// `reset()`.
+    #[pyo3(signature = (universe, spacing=50.0, z=0.0, ship=1.0, projectile=0.2, squadron=0.0))]
+    fn sample_currents(
+        &mut self,
+        universe: &PyUniverse,
+        spacing: f32,
+        z: f32,
+        ship: f32,
+        projectile: f32,
+        squadron: f32,
+    ) -> PyResult<()> {
+        if !(spacing.is_finite() && spacing > 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "spacing must be positive",
+            ));
+        }
+        let bounds = universe.inner.bounds();
+        let currents = CurrentField::sample(
+            &universe.inner,
+            bounds.min.truncate(),
+            bounds.max.truncate(),
+            spacing,
+            z,
+        )
+        .with_coupling(DriftCoupling {
+            ship,
+            projectile,
+            squadron,
+        });
+        self.inner.arena_mut().set_currents(Some(currents));
+        Ok(())
+    }
+
+    /// Stop drifting entities with sea currents.
+    fn clear_currents(&mut self) {
+        self.inner.arena_mut().set_currents(None);
+    }
+
+    /// Blow a uniform wind of `speed` m/s toward `direction` (radians,
+    /// like a heading).
+    ///
+    /// Each tick the wind carries smoke clouds laid by ships downwind at
+    /// its full speed, and entities given a windage with `set_windage`
+    /// at that fraction of it. The wind survives `reset()`.
+    fn set_wind(&mut self, speed: f32, direction: f32) -> PyResult<()> {
+        if !(speed.is_finite() && speed >= 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "speed must be non-negative",
+            ));
+        }
+        if !direction.is_finite() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "direction must be finite",
+            ));
+        }
+        self.inner.arena_mut().set_wind(Some(Wind::new(speed, direction)));
+        Ok(())
+    }
+
+    /// Stop the wind.
+    fn clear_wind(&mut self) {
+        self.inner.arena_mut().set_wind(None);
+    }
+
+    /// Wind velocity as (vx, vy) in m/s, or None if there is no wind.
+    #[getter]
+    fn wind(&self) -> Option<(f32, f32)> {
+        self.inner.arena().wind().map(|wind| (wind.velocity.x, wind.velocity.y))
+    }
+
+    /// Let the wind drift an entity at `windage` times the wind speed
+    /// (about 0.04 for a liferaft, 0.08 for a floating decoy; 0 to stop).
+    ///
+    /// Returns False if the entity does not exist or `windage` is negative.
+    fn set_windage(&mut self, entity_id: PyEntityId, windage: f32) -> bool {
+        self.inner.arena_mut().set_windage(entity_id.into(), windage)
+    }
+
+    /// Fraction of the wind speed an entity drifts at (0 if none).
+    fn windage(&self, entity_id: PyEntityId) -> f32 {
+        self.inner.arena().windage(entity_id.into())
+    }
+
+    /// Obscure visual sighting lines with the smoke of `universe`.
+    ///
+    /// Samples Smoke across the universe at altitude `z`, at most
+    /// `spacing` apart. Like currents, the snapshot is not updated as the
+    /// universe evolves and survives `reset()`.
+    #[pyo3(signature = (universe, spacing=50.0, z=0.0))]
+    fn sample_smoke(&mut self, universe: &PyUniverse, spacing: f32, z: f32) -> PyResult<()> {
+        if !(spacing.is_finite() && spacing > 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "spacing must be positive",
+            ));
+        }
+        let bounds = universe.inner.bounds();
+        let smoke = SmokeField::sample(
+            &universe.inner,
+            bounds.min.truncate(),
+            bounds.max.truncate(),
+            spacing,
+            z,
+        );
+        self.inner.arena_mut().environment_mut().smoke = Some(smoke);
+        Ok(())
+    }
+
+    /// Remove the smoke snapshot.
+    fn clear_smoke(&mut self) {
+        self.inner.arena_mut().environment_mut().smoke = None;
+    }
+
+    /// Shelter entities from blasts behind the solid cells of `universe`.
+    ///
+    /// Samples Occupancy across the universe at altitude `z`, at most
+    /// `spacing` apart; cells at or above 0.8 block area damage. Like
+    /// smoke, the snapshot is not updated as the universe evolves and
+    /// survives `reset()`.
+    #[pyo3(signature = (universe, spacing=50.0, z=0.0))]
+    fn sample_occupancy(&mut self, universe: &PyUniverse, spacing: f32, z: f32) -> PyResult<()> {
+        if !(spacing.is_finite() && spacing > 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "spacing must be positive",
+            ));
+        }
+        let bounds = universe.inner.bounds();
+        let occupancy = OccupancyField::sample(
+            &universe.inner,
+            bounds.min.truncate(),
+            bounds.max.truncate(),
+            spacing,
+            z,
+        );
+        self.inner.arena_mut().environment_mut().occupancy = Some(occupancy);
+        Ok(())
+    }
+
+    /// Remove the occupancy snapshot.
+    fn clear_occupancy(&mut self) {
+        self.inner.arena_mut().environment_mut().occupancy = None;
+    }
+
+    /// Start the world clock at `hour` (0-24) of `day` at tick 0.
+    ///
+    /// `day_length` is the length of a day in seconds; shorten it to cycle
+    /// through day and night faster. `moon_phase` is the phase of the moon
+    /// at tick 0 (0 new, 0.5 full). Darkness shortens visual range, less so
+    /// under a bright moon.
+    #[pyo3(signature = (hour, day_length=86400.0, day=0, moon_phase=0.0))]
+    fn set_clock(&mut self, hour: f32, day_length: f32, day: u64, moon_phase: f32) -> PyResult<()> {
+        if !(0.0..=24.0).contains(&hour) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "hour must be between 0 and 24",
+            ));
+        }
+        if !(day_length.is_finite() && day_length > 0.0) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "day_length must be positive",
+            ));
+        }
+        if !(0.0..1.0).contains(&moon_phase) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "moon_phase must be at least 0 and below 1",
+            ));
+        }
+        self.inner.arena_mut().environment_mut().clock = WorldClock {
+            start: hour / 24.0 * day_length,
+            day_length,
+            start_day: day,
+            moon_phase,
+        };
+        Ok(())
+    }
+
+    /// Current hour of the world clock (0-24).
+    #[getter]
+    fn time_of_day(&self) -> f32 {
+        self.inner.arena().environment().clock.hour(self.inner.tick())
+    }
+
+    /// Current reading of the world clock as a dict with `day`, `hour`,
+    /// `phase` ("night", "dawn", "day" or "dusk"), `daylight`,
+    /// `moon_phase`, `moon_illumination` and `light`.
+    #[getter]
+    fn clock<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let reading = self.inner.arena().environment().clock.reading(self.inner.tick());
+        clock_dict(py, &reading)
+    }
+
+    /// Sea state (0 calm to 9 phenomenal); rough seas shorten visual range.
+    #[getter]
+    fn sea_state(&self) -> u8 {
+        self.inner.arena().environment().sea_state
+    }
+
+    #[setter]
+    fn set_sea_state(&mut self, sea_state: u8) -> PyResult<()> {
+        if sea_state > MAX_SEA_STATE {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "sea state must be at most {MAX_SEA_STATE}"
+            )));
+        }
+        self.inner.arena_mut().environment_mut().sea_state = sea_state;
+        Ok(())
+    }
+
+    /// World bounds as ((min_x, min_y), (max_x, max_y), policy), or None.
+    #[getter]
+    #[allow(clippy::type_complexity)]
+    fn bounds(&self) -> Option<((f32, f32), (f32, f32), &'static str)> {
+        self.inner.arena().bounds().map(|b| {
+            let policy = match b.policy {
+                BoundaryPolicy::Clamp => "clamp",
+                BoundaryPolicy::Bounce => "bounce",
+                BoundaryPolicy::Wrap => "wrap",
+                BoundaryPolicy::Despawn => "despawn",
+            };
+            ((b.min.x, b.min.y), (b.max.x, b.max.y), policy)
+        })
+    }
+
+    /// Limit each plugin run to `limit_ms` milliseconds.
+    ///
+    /// A plugin instance (entity + plugin) that exceeds the limit
+    /// `max_overruns` times is disabled and a `PluginBudgetExceeded` event
+    /// is emitted. The budget survives `reset()`.
+    ///
+    /// Budgets measure wall-clock time, so the run is no longer
+    /// deterministic; `Replay` refuses to record or seek a simulation with a
+    /// budget or disabled plugins.
+    #[pyo3(signature = (limit_ms, max_overruns=3))]
+    fn set_plugin_budget(&mut self, limit_ms: f64, max_overruns: u32) -> PyResult<()> {
+        if !(limit_ms.is_finite() && limit_ms > 0.0) || max_overruns == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "limit_ms must be positive and max_overruns at least 1",
+            ));
+        }
+        let limit = Duration::from_secs_f64(limit_ms / 1000.0);
+        self.inner
+            .plugin_watchdog_mut()
+            .set_budget(Some(PluginBudget::new(limit, max_overruns)));
+        Ok(())
+    }
+
+    /// Remove the plugin budget. Disabled plugins stay disabled.
+    fn clear_plugin_budget(&mut self) {
+        self.inner.plugin_watchdog_mut().set_budget(None);
+    }
+
+    /// Plugin timings as a list of dicts with keys "entity_id", "plugin",
+    /// "last_ms", "max_ms", "mean_ms", "runs", "overruns" and "disabled".
+    fn plugin_timings<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let watchdog = self.inner.plugin_watchdog();
+        let list = PyList::empty(py);
+        for (instance, timing) in watchdog.timings() {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("entity_id", PyEntityId::from(instance.entity_id()))?;
+            dict.set_item("plugin", instance.plugin_id().as_str())?;
+            dict.set_item("last_ms", timing.last.as_secs_f64() * 1000.0)?;
+            dict.set_item("max_ms", timing.max.as_secs_f64() * 1000.0)?;
+            #[allow(clippy::cast_precision_loss)]
+            let mean = timing.total.as_secs_f64() * 1000.0 / timing.runs.max(1) as f64;
+            dict.set_item("mean_ms", mean)?;
+            dict.set_item("runs", timing.runs)?;
+            dict.set_item("overruns", timing.overruns)?;
+            dict.set_item("disabled", watchdog.is_disabled(instance))?;
+            list.append(dict)?;
+        }
+        Ok(list)
+    }
+
+    /// Re-enable a plugin disabled by the budget. Returns True if it was
+    /// disabled.
+    fn enable_plugin(&mut self, entity_id: PyEntityId, plugin: &str) -> bool {
+        let instance = PluginInstanceId::new(entity_id.into(), PluginId::new(plugin));
+        self.inner.plugin_watchdog_mut().enable(&instance)
+    }
+
+    /// Record every random draw, keeping the last `capacity` of them.
+    ///
+    /// Clears draws already recorded. Auditing survives `reset()`, which
+    /// starts a fresh log.
+    #[pyo3(signature = (capacity=DEFAULT_AUDIT_CAPACITY))]
+    fn enable_rng_audit(&self, capacity: usize) -> PyResult<()> {
+        if capacity == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "capacity must be at least 1",
+            ));
+        }
+        self.inner.rng_audit().enable(capacity);
+        Ok(())
+    }
+
+    /// Stop recording random draws and drop the recorded ones.
+    fn disable_rng_audit(&self) {
+        self.inner.rng_audit().disable();
+    }
+
+    /// Recorded random draws, oldest first, as a list of dicts with keys
+    /// "sequence", "tick", "stream", "purpose" and "value".
+    ///
+    /// Compare the lists of two runs to find the first draw where they
+    /// diverge. With `clear=True` the draws are removed from the log.
+    #[pyo3(signature = (clear=false))]
+    fn rng_draws<'py>(&self, py: Python<'py>, clear: bool) -> PyResult<Bound<'py, PyList>> {
+        let audit = self.inner.rng_audit();
+        let draws = if clear { audit.take() } else { audit.draws() };
+        let list = PyList::empty(py);
+        for draw in draws {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("sequence", draw.sequence)?;
+            dict.set_item("tick", draw.tick)?;
+            dict.set_item("stream", draw.stream)?;
+            dict.set_item("purpose", draw.purpose)?;
+            dict.set_item("value", draw.value)?;
+            list.append(dict)?;
+        }
+        Ok(list)
+    }
+
+    /// Keep the last `capacity` events across steps for `query_events()`.
+    ///
+    /// Clears events already kept; a capacity of 0 stops keeping events.
+    /// The history survives `reset()`, which starts it afresh.
+    fn enable_event_history(&mut self, capacity: usize) {
+        self.inner.enable_event_history(capacity);
+    }
+
+    /// Search the event history (see `enable_event_history()`).
+    ///
+    /// Every given filter must match:
+    /// - `kind`: event name, e.g. "DamageDealt" or "damage_dealt"
+    /// - `entity`: entity named by the event in any role
+    /// - `source`: entity whose plugin emitted the event
+    /// - `target`: entity the event acts upon
+    /// - `tick_range`: (start, end) ticks, end exclusive
+    /// - `trace_id`: causal trace the event belongs to
+    ///
+    /// With `format="dicts"` (default) returns a list of dicts with keys
+    /// "tick", "trace_id", "kind", "entity", "other", "value",
+    /// "weapon_slot" and "quality", the columns of the battle log's
+    /// `events.arrows`. With `format="arrow"` returns a `pyarrow.Table`
+    /// with the same columns.
+    #[pyo3(signature = (
+        kind=None,
+        entity=None,
+        source=None,
+        target=None,
+        tick_range=None,
+        trace_id=None,
+        format="dicts",
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn query_events<'py>(
+        &self,
+        py: Python<'py>,
+        kind: Option<&str>,
+        entity: Option<PyEntityId>,
+        source: Option<PyEntityId>,
+        target: Option<PyEntityId>,
+        tick_range: Option<(u64, u64)>,
+        trace_id: Option<u64>,
+        format: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut query = EventQuery::new();
+        if let Some(kind) = kind {
+            query = query.kind(kind);
+        }
+        if let Some(entity) = entity {
+            query = query.entity(entity.into());
+        }
+        if let Some(source) = source {
+            query = query.source(source.into());
+        }
+        if let Some(target) = target {
+            query = query.target(target.into());
+        }
+        if let Some((start, end)) = tick_range {
+            query = query.ticks(start, end);
+        }
+        if let Some(trace_id) = trace_id {
+            query = query.trace_id(TraceId::new(trace_id));
+        }
+        let rows = self.inner.event_history().rows(&query);
+
+        match format {
+            "dicts" => {
+                let list = PyList::empty(py);
+                for row in rows {
+                    list.append(event_row_dict(py, &row)?)?;
+                }
+                Ok(list.into_any())
+            }
+            "arrow" => {
+                let bytes = to_arrow(&rows).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+                py.import("pyarrow.ipc")?
+                    .call_method1("open_stream", (PyBytes::new(py, &bytes),))?
+                    .call_method0("read_all")
+            }
+            _ => Err(InvalidValue::new_err(format!(
+                "format must be \"dicts\" or \"arrow\", got {format:?}"
+            ))),
+        }
+    }
+
+    /// Apply an action dict to an entity.
+    ///
+    /// Action dict can contain:
+    /// - "velocity": (vx, vy) tuple, clamped to the ship's max speed
+    /// - "heading": float in radians; under throttle, reached over the
+    ///   following ticks at the turn rate the ship's speed allows
+    /// - "throttle": float fraction of max speed, -1 (astern) to 1 (ahead),
+    ///   reached over the following ticks at the ship's engine rates
+    ///
+    /// - "depth": float ordered depth in meters (submarines only), reached
+    ///   over the following ticks at the boat's dive rate
+    ///
+    /// "velocity" takes direct control and releases any throttle; it is
+    /// ignored when "throttle" is given in the same action.
+    ///
+    /// Pass `controller` (e.g. "agent:0") when acting for one player or
+    /// agent: the action is then only accepted for entities it controls
+    /// (see `set_controller`).
+    ///
+    /// Raises a `CommandError` subclass if the action is rejected:
+    /// `UnknownEntity`, `EntityDestroyed`, `NotSupportedForTag` (only ships
+    /// accept actions), `NotController` (the entity belongs to another
+    /// controller) or `InvalidValue` (malformed or non-finite values, or
+    /// "depth" for a surface ship).
+    /// Nothing is applied when an error is raised.
+    #[pyo3(signature = (entity_id, action, controller=None))]
+    fn apply_action(
+        &mut self,
+        entity_id: PyEntityId,
+        action: &Bound<'_, pyo3::types::PyDict>,
+        controller: Option<&str>,
+    ) -> PyResult<()> {
+        let id: EntityId = entity_id.into();
+        self.check_commandable(id)?;
+        self.check_controller(id, controller)?;
+        let action = ShipAction::parse(action)?;
+        self.check_depth_order(id, &action)?;
+
+        if let Some(EntityInner::Ship(c)) =
+            self.inner.arena_mut().get_mut(id).map(Entity::inner_mut)
+        {
+            if let Some(throttle) = action.throttle {
+                c.physics.throttle = Some(c.physics.engine.clamp_throttle(throttle));
+            } else if let Some(vel) = action.velocity {
+                c.physics.throttle = None;
+                // Clamp to max speed
+                c.physics.velocity = if vel.length() > c.physics.max_speed {
+                    vel.normalize() * c.physics.max_speed
+                } else {
+                    vel
+                };
+            }
+
+            if let Some(h) = action.heading {
+                if c.physics.throttle.is_some() {
+                    c.physics.ordered_heading = Some(h);
+                } else {
+                    c.transform.heading = h;
+                    c.physics.ordered_heading = None;
+                }
+            }
+
+            if let (Some(depth), Some(sub)) = (action.depth, c.submarine.as_mut()) {
+                sub.ordered_depth = depth.max(0.0);
+            }
+        }
+
+        // Update spatial index after position changes
+        self.inner.arena_mut().update_spatial(id);
+
+        Ok(())
+    }
+
+    /// Lay a mine at a ship's position, on the ship's team.
+    ///
+    /// `fuze` is "proximity" (any enemy ship within `trigger_radius`),
+    /// "acoustic" (enemy ships moving at `threshold` m/s or faster) or
+    /// "magnetic" (enemy hulls of radius `threshold` m or more). The mine
+    /// deals `damage` to every ship within `blast_radius` when it goes off,
+    /// and stays hidden from sensors until swept by sonar.
+    ///
+    /// Returns the mine's ID. Raises the same CommandError subclasses as
+    /// `apply_action`, and InvalidValue for an unknown fuze or a negative
+    /// parameter.
+    #[pyo3(signature = (
+        entity_id,
+        fuze="proximity",
+        threshold=0.0,
+        trigger_radius=50.0,
+        damage=80.0,
+        blast_radius=75.0
+    ))]
+    fn lay_mine(
+        &mut self,
+        entity_id: PyEntityId,
+        fuze: &str,
+        threshold: f32,
+        trigger_radius: f32,
+        damage: f32,
+        blast_radius: f32,
+    ) -> PyResult<PyEntityId> {
+        let id: EntityId = entity_id.into();
+        self.check_commandable(id)?;
+        let fuze = match fuze.to_lowercase().as_str() {
+            "proximity" => MineFuze::Proximity,
+            "acoustic" => MineFuze::Acoustic { threshold },
+            "magnetic" => MineFuze::Magnetic { threshold },
+            other => {
+                return Err(InvalidValue::new_err(format!(
+                    "unknown fuze '{other}', expected 'proximity', 'acoustic' or 'magnetic'"
+                )))
+            }
+        };
+        if [threshold, trigger_radius, damage, blast_radius]
+            .iter()
+            .any(|v| !(v.is_finite() && *v >= 0.0))
+        {
+            return Err(InvalidValue::new_err(
+                "mine parameters must be finite and non-negative",
+            ));
+        }
+        let mine = MineState::new(fuze)
+            .with_trigger_radius(trigger_radius)
+            .with_warhead(damage, blast_radius);
+        MinefieldResolver::lay_mine(self.inner.arena_mut(), id, mine)
+            .map(PyEntityId::from)
+            .ok_or_else(|| {
+                EntityDestroyed::new_err(format!("entity {} cannot lay mines", id.as_u64()))
+            })
+    }
+
+    /// Lay a smoke screen astern of a ship for `duration` ticks.
+    ///
+    /// Uses one "smoke" charge from the ship's inventory. Clouds block
+    /// visual sighting and weapons hold fire through them until they
+    /// disperse, drifting with the wind (see `set_wind`). Returns False if the ship has no smoke left, is already
+    /// laying smoke or is submerged. Raises the same CommandError
+    /// subclasses as `apply_action`.
+    #[pyo3(signature = (entity_id, duration=600))]
+    fn deploy_smoke(&mut self, entity_id: PyEntityId, duration: u32) -> PyResult<bool> {
+        let id: EntityId = entity_id.into();
+        self.check_commandable(id)?;
+        Ok(SmokeResolver::deploy(self.inner.arena_mut(), id, duration))
+    }
+
+    /// Mount a weapon on a ship and return its slot.
+    ///
+    /// `magazine_size` of 0 gives a cooldown-only weapon. Otherwise each
+    /// shot expends `burst_size` rounds, and an empty magazine takes
+    /// `reload_ticks` ticks to refill.
+    ///
+    /// `gun`, a dict of ballistics overriding a 5-inch gun's ("damage",
+    /// "max_range", "muzzle_velocity", "dispersion", "lead_error",
+    /// "hit_radius"; `{}` for the defaults), makes the weapon a gun whose
+    /// rounds hit or splash the moment it fires, with no projectile
+    /// entity. Raises the same CommandError subclasses as `apply_action`,
+    /// and InvalidValue for an unknown ammunition type, a negative
+    /// cooldown or an unknown ballistics key.
+    #[pyo3(signature = (
+        entity_id,
+        ammo="shell",
+        cooldown=1.0,
+        magazine_size=0,
+        reload_ticks=0,
+        burst_size=1,
+        gun=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_weapon(
+        &mut self,
+        entity_id: PyEntityId,
+        ammo: &str,
+        cooldown: f32,
+        magazine_size: u32,
+        reload_ticks: u32,
+        burst_size: u32,
+        gun: Option<&Bound<'_, pyo3::types::PyDict>>,
+    ) -> PyResult<usize> {
+        let id: EntityId = entity_id.into();
+        self.check_commandable(id)?;
+        let ammo = str_to_ammo(ammo)?;
+        if !(cooldown.is_finite() && cooldown >= 0.0) {
+            return Err(InvalidValue::new_err(
+                "cooldown must be finite and non-negative",
+            ));
+        }
+        let gun = gun
+            .map(|gun| {
+                config_value(gun.as_any())?
+                    .try_into::<GunBallistics>()
+                    .map_err(|e| InvalidValue::new_err(e.message().to_string()))
+            })
+            .transpose()?;
+        let Some(c) = self.inner.arena_mut().get_mut(id).and_then(Entity::as_ship_mut) else {
+            return Err(UnknownEntity::new_err(format!("entity {} does not exist", id.as_u64())));
+        };
+        let slot = c.combat.weapons.iter().map(|w| w.slot + 1).max().unwrap_or(0);
+        let mut weapon = WeaponState::new(slot, cooldown, ammo)
+            .with_magazine(magazine_size, reload_ticks)
+            .with_burst(burst_size);
+        weapon.gun = gun;
+        c.combat.weapons.push(weapon);
+        Ok(slot)
+    }
+
+    /// Start reloading a ship's weapon.
+    ///
+    /// Returns False if the weapon has no magazine, is already reloading or
+    /// is full. Raises the same CommandError subclasses as `apply_action`,
+    /// and InvalidValue for an unknown slot.
+    fn reload_weapon(&mut self, entity_id: PyEntityId, slot: usize) -> PyResult<bool> {
+        let id: EntityId = entity_id.into();
+        self.check_commandable(id)?;
+        let weapon = self
+            .inner
+            .arena_mut()
+            .get_mut(id)
+            .and_then(Entity::as_ship_mut)
+            .and_then(|c| c.combat.weapons.iter_mut().find(|w| w.slot == slot))
+            .ok_or_else(|| InvalidValue::new_err(format!("no weapon in slot {slot}")))?;
+        if !weapon.can_reload() {
+            return Ok(false);
+        }
+        if weapon.reload_ticks == 0 {
+            weapon.rounds = weapon.magazine_size;
+        } else {
+            weapon.reload_remaining = weapon.reload_ticks;
+        }
+        Ok(true)
+    }
+
+    /// Check an action dict without applying it.
+    ///
+    /// Returns None if `apply_action` would accept it, otherwise the
+    /// `CommandError` it would raise (not raised). Useful for building
+    /// action masks.
+    #[pyo3(signature = (entity_id, action, controller=None))]
+    fn validate_action(
+        &self,
+        py: Python,
+        entity_id: PyEntityId,
+        action: &Bound<'_, pyo3::types::PyDict>,
+        controller: Option<&str>,
+    ) -> Option<Py<pyo3::exceptions::PyBaseException>> {
+        let id: EntityId = entity_id.into();
+        self.check_commandable(id)
+            .and_then(|()| self.check_controller(id, controller))
+            .and_then(|()| ShipAction::parse(action))
+            .and_then(|action| self.check_depth_order(id, &action))
+            .err()
+            .map(|e| e.into_value(py))
+    }
+
+    /// Per-team strength estimate of the current state.
+    ///
+    /// Returns a list of dicts sorted by team, with keys "team", "units",
+    /// "hp", "firepower", "positioning", "score" and "win_probability".
+    /// Win probabilities sum to 1 across teams, so a curriculum scheduler
+    /// can truncate episodes once one team passes a threshold.
+    fn evaluate_balance<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let list = PyList::empty(py);
+        for team in self.inner.evaluate_balance().teams {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("team", team.team.as_u32())?;
+            dict.set_item("units", team.units)?;
+            dict.set_item("hp", team.hp)?;
+            dict.set_item("firepower", team.firepower)?;
+            dict.set_item("positioning", team.positioning)?;
+            dict.set_item("score", team.score)?;
+            dict.set_item("win_probability", team.win_probability)?;
+            list.append(dict)?;
+        }
+        Ok(list)
+    }
+
+    /// Keep per-team mission scores, replacing any kept so far.
+    ///
+    /// `kills` and `losses` map a class (an entity label, or a tag name
+    /// such as "Ship" for entities with no labelled rule) to the points won
+    /// for destroying an enemy of that class or lost when one of the team's
+    /// own is destroyed. Each `(x, y, radius, points_per_second)` in
+    /// `zones` scores while a team alone has live ships or squadrons
+    /// inside it, and `delivery` is scored per convoy ship delivered.
+    /// Scoring survives `reset()`.
+    ///
+    /// ```python
+    /// sim.enable_scoring(kills={"merchant": 10.0, "Ship": 3.0},
+    ///                    losses={"Ship": 5.0},
+    ///                    zones=[(0.0, 0.0, 2000.0, 0.1)])
+    /// ```
+    #[pyo3(signature = (kills=None, losses=None, zones=None, delivery=0.0))]
+    fn enable_scoring(
+        &mut self,
+        kills: Option<BTreeMap<String, f32>>,
+        losses: Option<BTreeMap<String, f32>>,
+        zones: Option<Vec<(f32, f32, f32, f32)>>,
+        delivery: f32,
+    ) {
+        let mut rules = ScoringRules {
+            kills: kills.unwrap_or_default(),
+            losses: losses.unwrap_or_default(),
+            delivery,
+            ..ScoringRules::default()
+        };
+        for (x, y, radius, points) in zones.unwrap_or_default() {
+            rules = rules.with_zone(Zone::new(Vec2::new(x, y), radius), points);
+        }
+        self.inner.enable_scoring(rules);
+    }
+
+    /// Mission scores so far, or None if scoring is not enabled.
+    ///
+    /// Returns a list of dicts sorted by team, with keys "team", "score",
+    /// "kills" and "losses" (dicts of counts by class), "zone_seconds" and
+    /// "delivered".
+    fn scores<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyList>>> {
+        if self.inner.score_keeper().is_none() {
+            return Ok(None);
+        }
+        let list = PyList::empty(py);
+        for team in self.inner.scores() {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("team", team.team.as_u32())?;
+            dict.set_item("score", team.score)?;
+            dict.set_item("kills", team.kills)?;
+            dict.set_item("losses", team.losses)?;
+            dict.set_item("zone_seconds", team.zone_seconds)?;
+            dict.set_item("delivered", team.delivered)?;
+            list.append(dict)?;
+        }
+        Ok(Some(list))
+    }
+
+    /// Kills, assists and damage dealt so far by each controller.
+    ///
+    /// Returns a dict keyed by controller (written like in
+    /// `set_controller`) of dicts with keys "kills", "assists" and
+    /// "damage". A kill goes to the controller of the entity that last
+    /// damaged a destroyed hostile, and an assist to every other
+    /// controller whose entities damaged it. Friendly fire is not counted.
+    /// The ledger starts empty on `reset()`.
+    fn kill_ledger<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let dict = pyo3::types::PyDict::new(py);
+        for (controller, tally) in self.inner.kill_ledger().tallies() {
+            let entry = pyo3::types::PyDict::new(py);
+            entry.set_item("kills", tally.kills)?;
+            entry.set_item("assists", tally.assists)?;
+            entry.set_item("damage", tally.damage)?;
+            dict.set_item(controller.to_string(), entry)?;
+        }
+        Ok(dict)
+    }
+
+    /// Deconflict fire within each team, replacing any assignment enabled
+    /// so far.
+    ///
+    /// Every tick, of a team's ships firing at one target, only the nearest
+    /// needed to destroy it at `damage_per_shot` each fire, and at most
+    /// `max_shooters_per_target`; the others' shots are dropped and their
+    /// weapons stay ready. Assignment survives `reset()`.
+    ///
+    /// Raises InvalidValue for a non-positive `damage_per_shot`.
+    #[pyo3(signature = (damage_per_shot=25.0, max_shooters_per_target=2))]
+    fn enable_weapon_assignment(
+        &mut self,
+        damage_per_shot: f32,
+        max_shooters_per_target: usize,
+    ) -> PyResult<()> {
+        if !(damage_per_shot.is_finite() && damage_per_shot > 0.0) {
+            return Err(InvalidValue::new_err("damage_per_shot must be positive"));
+        }
+        self.inner.enable_weapon_assignment(AssignmentConfig {
+            damage_per_shot,
+            max_shooters_per_target,
+        });
+        Ok(())
+    }
+
+    /// Stop deconflicting fire.
+    fn disable_weapon_assignment(&mut self) {
+        self.inner.disable_weapon_assignment();
+    }
+
+    /// Classify contacts from detections, replacing any classification
+    /// enabled so far.
+    ///
+    /// Every tick, each detected contact's classification confidence grows
+    /// by `gain_per_detection`, halved at `half_gain_range` meters and
+    /// multiplied by `emissions_multiplier` if the contact is emitting. A
+    /// contact within visual range jumps to `visual_confidence`. Past
+    /// `threshold` it is classified, as a wrong type with probability
+    /// `misclassification_rate`; the rolls are seeded by the simulation
+    /// seed. Classified contacts fill the `contact_tags` observation.
+    ///
+    /// Ships' sensors start detecting contacts, filling their track tables,
+    /// and keep doing so after `disable_classification()`. Classification
+    /// and sensors survive `reset()`.
+    ///
+    /// Raises InvalidValue for a negative or non-finite parameter, or a
+    /// `threshold`, `misclassification_rate` or `visual_confidence` outside
+    /// [0, 1].
+    #[pyo3(signature = (
+        gain_per_detection=0.1,
+        half_gain_range=5000.0,
+        emissions_multiplier=2.0,
+        threshold=0.7,
+        misclassification_rate=0.05,
+        visual_confidence=0.95,
+    ))]
+    fn enable_classification(
+        &mut self,
+        gain_per_detection: f32,
+        half_gain_range: f32,
+        emissions_multiplier: f32,
+        threshold: f32,
+        misclassification_rate: f32,
+        visual_confidence: f32,
+    ) -> PyResult<()> {
+        for (name, value) in [
+            ("gain_per_detection", gain_per_detection),
+            ("half_gain_range", half_gain_range),
+            ("emissions_multiplier", emissions_multiplier),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(InvalidValue::new_err(format!("{name} must be non-negative")));
+            }
+        }
+        for (name, value) in [
+            ("threshold", threshold),
+            ("misclassification_rate", misclassification_rate),
+            ("visual_confidence", visual_confidence),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(InvalidValue::new_err(format!("{name} must be in [0, 1]")));
+            }
+        }
+        self.inner.enable_classification(ClassificationModel {
+            gain_per_detection,
+            half_gain_range,
+            emissions_multiplier,
+            threshold,
+            misclassification_rate,
+            visual_confidence,
+        });
+        if self.sensors.is_none() {
+            let plugin = Arc::new(SensorPlugin::new());
+            self.inner.plugins_mut().register(EntityTag::Ship, plugin.clone());
+            self.sensors = Some(plugin);
+        }
+        Ok(())
+    }
+
+    /// Stop classifying contacts. Classifications made so far are kept.
+    fn disable_classification(&mut self) {
+        self.inner.disable_classification();
+    }
+
+    /// Drain the shots assigned since the last call, as `(shooter, target,
+    /// slot)` tuples in resolution order. Empty if assignment is disabled.
+    fn take_weapon_assignments(&self) -> Vec<(PyEntityId, PyEntityId, usize)> {
+        let Some(assignment) = self.inner.weapon_assignment() else {
+            return Vec::new();
+        };
+        assignment
+            .take_assignments()
+            .iter()
+            .filter_map(|envelope| match envelope.output().as_event() {
+                Some(Event::WeaponAssigned {
+                    shooter,
+                    target,
+                    weapon_slot,
+                }) => Some(((*shooter).into(), (*target).into(), *weapon_slot)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Accumulate a visitation and damage-exposure heatmap over the
+    /// rectangle `[min, max]` in square cells of `cell_size` meters.
+    ///
+    /// Every tick, each live ship or squadron in the grid adds a visit to
+    /// its cell, and the hit points it lost that tick to the cell's
+    /// exposure. `team` limits recording to that team's entities. Cells
+    /// grow as needed to keep at most 1024 per axis. Replaces any heatmap
+    /// kept so far; the heatmap survives `reset()`, which zeroes it.
+    ///
+    /// Raises InvalidValue for a non-positive `cell_size`.
+    #[pyo3(signature = (min, max, cell_size, team=None))]
+    fn enable_heatmap(
+        &mut self,
+        min: (f32, f32),
+        max: (f32, f32),
+        cell_size: f32,
+        team: Option<u32>,
+    ) -> PyResult<()> {
+        if !(cell_size.is_finite() && cell_size > 0.0) {
+            return Err(InvalidValue::new_err("cell_size must be positive"));
+        }
+        let mut config =
+            HeatmapConfig::new(Vec2::new(min.0, min.1), Vec2::new(max.0, max.1), cell_size);
+        if let Some(team) = team {
+            config = config.with_team(TeamId::new(team));
+        }
+        self.inner.enable_heatmap(config);
+        Ok(())
+    }
+
+    /// Stop accumulating the heatmap.
+    fn disable_heatmap(&mut self) {
+        self.inner.disable_heatmap();
+    }
+
+    /// The heatmap so far, or None if not enabled.
+    ///
+    /// Returns a dict with:
+    /// - "visits": uint32 (ny, nx) entity-ticks spent in each cell
+    /// - "exposure": float32 (ny, nx) hit points lost in each cell
+    /// - "min": (x, y) lower corner of the first cell
+    /// - "cell_size": side length of each cell in meters
+    /// - "ticks": number of ticks recorded
+    ///
+    /// Row `j`, column `i` covers x from `min[0] + i * cell_size` and y
+    /// from `min[1] + j * cell_size`.
+    fn heatmap<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Option<Bound<'py, pyo3::types::PyDict>>> {
+        let Some(heatmap) = self.inner.heatmap() else {
+            return Ok(None);
+        };
+        let [nx, ny] = heatmap.config.dims;
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("visits", heatmap.visits.to_pyarray(py).reshape([ny, nx])?)?;
+        dict.set_item("exposure", heatmap.exposure.to_pyarray(py).reshape([ny, nx])?)?;
+        dict.set_item("min", (heatmap.config.min.x, heatmap.config.min.y))?;
+        dict.set_item("cell_size", heatmap.config.cell_size)?;
+        dict.set_item("ticks", heatmap.ticks)?;
+        Ok(Some(dict))
+    }
+
+    /// Commander-level picture of a team, as numpy arrays.
+    ///
+    /// Returns a dict with:
+    /// - "tracks": float32 (N, 8) rows of `[x, y, vx, vy, quality, age,
+    ///   tag, reporters]`, the members' track tables fused per target
+    ///   (best quality, then freshest). `tag` is encoded as in
+    ///   `contact_tags`; `reporters` counts members holding the track.
+    /// - "track_ids": uint64 (N,) target IDs.
+    /// - "members": float32 (M, 10) own-force rows of `[x, y, heading, vx,
+    ///   vy, hp_fraction, fuel_fraction, weapons_ready, ammo, active]`.
+    /// - "member_ids": uint64 (M,) member IDs.
+    /// - "zones": float32 (Z, 3) rows of `[friendly, hostile, control]`
+    ///   for each `(x, y, radius)` in `zones`, with control 0 (empty),
+    ///   1 (friendly), 2 (hostile) or 3 (contested).
+    /// - "clock": the world clock reading, as returned by `clock`.
+    #[pyo3(signature = (team, zones=None))]
+    #[allow(clippy::cast_precision_loss)]
+    fn team_observation<'py>(
+        &self,
+        py: Python<'py>,
+        team: u32,
+        zones: Option<Vec<(f32, f32, f32)>>,
+    ) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let zones = zones
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(x, y, radius)| Zone::new(Vec2::new(x, y), radius));
+        let obs = TeamObservationBuilder::new(TeamId::new(team))
+            .with_zones(zones)
+            .build(self.inner.arena());
+
+        let tracks: Vec<f32> = obs
+            .tracks
+            .iter()
+            .flat_map(|t| {
+                [
+                    t.position.x,
+                    t.position.y,
+                    t.velocity.x,
+                    t.velocity.y,
+                    t.quality as i32 as f32,
+                    t.age,
+                    tag_code(t.classified_as) as f32,
+                    t.reporters as f32,
+                ]
+            })
+            .collect();
+        let members: Vec<f32> = obs
+            .members
+            .iter()
+            .flat_map(|m| {
+                [
+                    m.position.x,
+                    m.position.y,
+                    m.heading,
+                    m.velocity.x,
+                    m.velocity.y,
+                    m.hp_fraction,
+                    m.fuel_fraction,
+                    m.weapons_ready as f32,
+                    m.ammo as f32,
+                    if m.active { 1.0 } else { 0.0 },
+                ]
+            })
+            .collect();
+        let zones: Vec<f32> = obs
+            .zones
+            .iter()
+            .flat_map(|z| [z.friendly as f32, z.hostile as f32, z.control as i32 as f32])
+            .collect();
+        let track_ids: Vec<u64> = obs.tracks.iter().map(|t| t.target_id.as_u64()).collect();
+        let member_ids: Vec<u64> = obs.members.iter().map(|m| m.id.as_u64()).collect();
+
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("tracks", tracks.to_pyarray(py).reshape([obs.tracks.len(), 8])?)?;
+        dict.set_item("track_ids", track_ids.to_pyarray(py))?;
+        dict.set_item("members", members.to_pyarray(py).reshape([obs.members.len(), 10])?)?;
+        dict.set_item("member_ids", member_ids.to_pyarray(py))?;
+        dict.set_item("zones", zones.to_pyarray(py).reshape([obs.zones.len(), 3])?)?;
+        dict.set_item("clock", clock_dict(py, &obs.clock)?)?;
+        Ok(dict)
+    }
+
+    /// Areas a team's sensors cover, as `(x, y, radius)` discs.
+    ///
+    /// One disc per active member ship with working sensors, as wide as
+    /// the furthest of its radar, sonar and lookouts reach.
+    fn team_coverage(&self, team: u32) -> Vec<(f32, f32, f32)> {
+        SensorCoverage::of_team(self.inner.arena(), TeamId::new(team))
+            .discs()
+            .iter()
+            .map(|d| (d.center.x, d.center.y, d.radius))
+            .collect()
+    }
+
+    /// Query a point of `universe` as `team` sees it.
+    ///
+    /// Returns None where the team has no sensor coverage (see
+    /// `team_coverage`), so observations of the environment reveal nothing
+    /// the team could not sense. The universe is only read.
+    fn team_query_point(
+        &self,
+        universe: &PyUniverse,
+        team: u32,
+        position: (f32, f32, f32),
+    ) -> Option<PyPointResult> {
+        let view = TeamFieldView::for_team(&universe.inner, self.inner.arena(), TeamId::new(team));
+        let position = glam::Vec3::new(position.0, position.1, position.2);
+        view.query_point(position).map(|inner| PyPointResult { inner })
+    }
+
+    /// Query a volume of `universe` as `team` sees it.
+    ///
+    /// Returns None unless a single member covers the whole horizontal
+    /// extent of the sphere.
+    #[pyo3(signature = (universe, team, center, radius, resolution="medium"))]
+    fn team_query_volume(
+        &self,
+        universe: &PyUniverse,
+        team: u32,
+        center: (f32, f32, f32),
+        radius: f32,
+        resolution: &str,
+    ) -> Option<PyQueryResult> {
+        let view = TeamFieldView::for_team(&universe.inner, self.inner.arena(), TeamId::new(team));
+        let center = glam::Vec3::new(center.0, center.1, center.2);
+        let res = match resolution {
+            "coarse" => murk::QueryResolution::Coarse,
+            "fine" => murk::QueryResolution::Fine,
+            "full" => murk::QueryResolution::Full,
+            _ => murk::QueryResolution::Medium,
+        };
+        view.query_volume(center, radius, res).map(|inner| PyQueryResult { inner })
+    }
+
+    /// Threat scores for every track held by an entity.
+    ///
+    /// Returns a list of (target_id, score) tuples in track-table order,
+    /// scored with the default `ThreatEvaluationPlugin` weights.
+    fn get_threat_scores(&self, entity_id: PyEntityId) -> Vec<(PyEntityId, f32)> {
+        let Some(EntityInner::Ship(c)) =
+            self.inner.arena().get(entity_id.into()).map(Entity::inner)
+        else {
+            return Vec::new();
+        };
+        let weights = ThreatWeights::default();
+        c.sensor
+            .track_table
+            .iter()
+            .map(|t| {
+                (
+                    t.target_id.into(),
+                    weights.score(t, c.transform.position, c.physics.velocity),
+                )
+            })
+            .collect()
+    }
+
+    /// Get observation for an entity.
+    ///
+    /// `max_intents` controls how many received intents (from friendly
+    /// entities in comms range) are included; 0 disables the intent block.
+    ///
+    /// With `frames` > 1 the observation also carries the entity's last
+    /// `frames` observations (one per tick, kept in a per-entity ring
+    /// buffer), available through the `stacked_*` accessors. Missing history
+    /// is padded by repeating the oldest frame; changing `max_contacts`,
+    /// `max_intents` or the contact row width restarts the history.
+    ///
+    /// The contact block can be shaped per call:
+    /// - `sort` overrides the contact ordering set by
+    ///   `set_contact_sort_key` ("distance", "threat", "quality" or
+    ///   "track").
+    /// - `min_quality` drops tracks below "cue", "coarse", "fire_control"
+    ///   or "shared".
+    /// - `tags` keeps only contacts classified as one of the given tags.
+    /// - `teams` keeps only contacts on one of the given teams.
+    /// - `relative_velocity` appends `[rel_vx, rel_vy]` to each contact row.
+    ///
+    /// `objectives` fills the `objectives` block from the objectives set
+    /// with `set_objectives`.
+    ///
+    /// ```python
+    /// obs = sim.get_observation(ship_id, frames=4)
+    /// obs.stacked_own_state().shape  # (4, 7)
+    /// obs = sim.get_observation(ship_id, sort="threat", teams=[1], relative_velocity=True)
+    /// obs.contacts().shape  # (16, 7)
+    /// ```
+    #[pyo3(signature = (
+        entity_id,
+        max_contacts=16,
+        max_intents=0,
+        frames=1,
+        sort=None,
+        min_quality=None,
+        tags=None,
+        teams=None,
+        relative_velocity=false,
+        objectives=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn get_observation(
+        &mut self,
+        entity_id: PyEntityId,
+        max_contacts: usize,
+        max_intents: usize,
+        frames: usize,
+        sort: Option<&str>,
+        min_quality: Option<&str>,
+        tags: Option<Vec<PyEntityTag>>,
+        teams: Option<Vec<u32>>,
+        relative_velocity: bool,
+        objectives: bool,
+    ) -> PyResult<Option<PyObservation>> {
+        let mut filter = ContactFilter::default();
+        if let Some(quality) = min_quality {
+            filter = filter.with_min_quality(str_to_quality(quality)?);
+        }
+        if let Some(tags) = tags {
+            filter = filter.with_tags(tags.into_iter().map(EntityTag::from));
+        }
+        if let Some(teams) = teams {
+            filter = filter.with_teams(teams.into_iter().map(TeamId::new));
+        }
+        let query = ContactQuery {
+            sort_key: sort.map(str_to_sort_key).transpose()?,
+            filter,
+            relative_velocity,
+        };
+        let Some(mut obs) = PyObservation::for_entity(
+            self.inner.arena(),
+            &mut self.interest,
+            entity_id.into(),
+            max_contacts,
+            &query,
+        ) else {
+            return Ok(None);
+        };
+        obs.intents =
+            PyObservation::build_intents(self.inner.arena(), entity_id.into(), max_intents);
+        if objectives {
+            obs.objectives = self.objective_features(entity_id.into());
+        }
+        if frames > 1 {
+            let frame = ObservationFrame::of(&obs, self.inner.tick());
+            obs.history = self.frames.record(entity_id.into(), frame, frames);
+        }
+        Ok(Some(obs))
+    }
+
+    /// Length of the flat observation written by `obs_into`.
+    ///
+    /// 7 own-state values, then `max_contacts` rows of 5 contact values,
+    /// `max_contacts` contact tags, 4 bound distances and `max_intents`
+    /// rows of (3 + max_intent_len) intent values, followed by the 7
+    /// objective values when `spec` asks for them. A `spec` overrides
+    /// `max_contacts` and `max_intents`.
+    #[pyo3(signature = (max_contacts=16, max_intents=0, spec=None))]
+    fn observation_size(
+        &self,
+        max_contacts: usize,
+        max_intents: usize,
+        spec: Option<&PyObservationSpec>,
+    ) -> usize {
+        let (max_contacts, max_intents, _) =
+            PyObservationSpec::resolve(spec, max_contacts, max_intents);
+        let intent_width = 3 + self.inner.arena().comms().config().max_intent_len;
+        let objectives = spec.is_some_and(|spec| spec.objectives);
+        PyObservation::flat_len(max_contacts, max_intents, intent_width, objectives)
+    }
+
+    /// Write an entity's observation into the preallocated array `out`,
+    /// without allocating numpy arrays.
+    ///
+    /// `out` must be contiguous with exactly `observation_size(...)`
+    /// values, laid out as own_state, contacts, contact_tags,
+    /// bound_distances, intents and (if `spec` asks for them) objectives,
+    /// each flattened row-major. Weapons and
+    /// stacked frames are not included. `out` is float32 unless `spec`
+    /// selects float16 or int8, in which case values are converted in Rust.
+    /// Returns False, zero-filling `out`, if the entity does not exist.
+    ///
+    /// ```python
+    /// spec = ObservationSpec(dtype="float16")
+    /// out = np.empty(sim.observation_size(spec=spec), dtype=np.float16)
+    /// sim.obs_into(ship_id, out, spec=spec)
+    /// ```
+    #[pyo3(signature = (entity_id, out, max_contacts=16, max_intents=0, spec=None))]
+    fn obs_into(
+        &mut self,
+        entity_id: PyEntityId,
+        out: &Bound<'_, PyAny>,
+        max_contacts: usize,
+        max_intents: usize,
+        spec: Option<&PyObservationSpec>,
+    ) -> PyResult<bool> {
+        let ids = [entity_id.into()];
+        let found = self.write_observations(&ids, out, max_contacts, max_intents, spec)?;
+        Ok(found[0])
+    }
+
+    /// Write the observations of `entity_ids` into the rows of the
+    /// preallocated array `out`, shape (len(entity_ids),
+    /// observation_size(...)).
+    ///
+    /// Each row is laid out as in `obs_into`, with the same `spec`
+    /// handling. Returns, per entity, whether it exists; rows of missing
+    /// entities are zero-filled.
+    #[pyo3(signature = (entity_ids, out, max_contacts=16, max_intents=0, spec=None))]
+    fn obs_into_batch(
+        &mut self,
+        entity_ids: Vec<PyEntityId>,
+        out: &Bound<'_, PyAny>,
+        max_contacts: usize,
+        max_intents: usize,
+        spec: Option<&PyObservationSpec>,
+    ) -> PyResult<Vec<bool>> {
+        let ids: Vec<EntityId> = entity_ids.into_iter().map(EntityId::from).collect();
+        self.write_observations(&ids, out, max_contacts, max_intents, spec)
+    }
+
+    /// Drop all recorded observation frames.
+    fn clear_frame_history(&mut self) {
+        self.frames.clear();
+    }
+}
+
+impl PySimulation {
+    /// Returns the mitigation slot of a ship or squadron.
+    fn mitigation_mut(&mut self, entity_id: PyEntityId) -> PyResult<&mut Option<MitigationState>> {
+        let id = EntityId::from(entity_id);
+        match self.inner.arena_mut().get_mut(id).map(Entity::inner_mut) {
+            Some(EntityInner::Ship(c)) => Ok(&mut c.combat.mitigation),
+            Some(EntityInner::Squadron(c)) => Ok(&mut c.combat.mitigation),
+            Some(_) => Err(InvalidValue::new_err(format!(
+                "entity {} has no combat state",
+                id.as_u64()
+            ))),
+            None => Err(UnknownEntity::new_err(format!(
+                "entity {} does not exist",
+                id.as_u64()
+            ))),
+        }
+    }
+
+    /// Files `order` directly in the arena, bypassing the order resolver.
+    fn issue_order(&mut self, order: Order) -> bool {
+        let arena = self.inner.arena_mut();
+        if !can_issue(arena, &order) {
+            return false;
+        }
+        let tick = arena.current_tick();
+        arena.orders_mut().issue(order, tick);
+        true
+    }
+
+    /// Writes one flat observation row per entity into `out`, converting
+    /// to the dtype selected by `spec`.
+    fn write_observations(
+        &mut self,
+        ids: &[EntityId],
+        out: &Bound<'_, PyAny>,
+        max_contacts: usize,
+        max_intents: usize,
+        spec: Option<&PyObservationSpec>,
+    ) -> PyResult<Vec<bool>> {
+        let width = self.observation_size(max_contacts, max_intents, spec);
+        let (max_contacts, max_intents, dtype) =
+            PyObservationSpec::resolve(spec, max_contacts, max_intents);
+        let objectives = spec.is_some_and(|spec| spec.objectives);
+        let slots = (max_contacts, max_intents, objectives);
+        let expected = ids.len() * width;
+
+        if dtype == ObservationDtype::F32 {
+            let mut buffer = writable_buffer::<f32>(out, "float32")?;
+            let rows = buffer_slice(&mut buffer, expected)?.chunks_exact_mut(width);
+            return Ok(ids
+                .iter()
+                .zip(rows)
+                .map(|(id, row)| self.write_observation(*id, row, slots))
+                .collect());
+        }
+
+        let mut scratch = vec![0.0; width];
+        let mut found = Vec::with_capacity(ids.len());
+        if let ObservationDtype::Int8 { scale } = dtype {
+            let mut buffer = writable_buffer::<i8>(out, "int8")?;
+            let rows = buffer_slice(&mut buffer, expected)?.chunks_exact_mut(width);
+            for (id, row) in ids.iter().zip(rows) {
+                found.push(self.write_observation(*id, &mut scratch, slots));
+                encode_i8(&scratch, scale, row);
+            }
+        } else {
+            // numpy has no Rust float16 type here; write the raw bits through a uint16 view
+            let is_f16 = out
+                .downcast::<PyUntypedArray>()
+                .is_ok_and(|array| array.dtype().char() == b'e');
+            if !is_f16 {
+                return Err(pyo3::exceptions::PyValueError::new_err("out must be a float16 array"));
+            }
+            let view = out.call_method1("view", ("uint16",))?;
+            let mut buffer = writable_buffer::<u16>(&view, "float16")?;
+            let rows = buffer_slice(&mut buffer, expected)?.chunks_exact_mut(width);
+            for (id, row) in ids.iter().zip(rows) {
+                found.push(self.write_observation(*id, &mut scratch, slots));
+                encode_f16(&scratch, row);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Writes the flat observation of `id` into `out`, zero-filling it if
+    /// the entity does not exist. `out` must be `observation_size` long
+    /// for `slots`: (max_contacts, max_intents, objectives).
+    fn write_observation(
+        &mut self,
+        id: EntityId,
+        out: &mut [f32],
+        slots: (usize, usize, bool),
+    ) -> bool {
+        let (max_contacts, max_intents, objectives) = slots;
+        let arena = self.inner.arena();
+        let query = ContactQuery::default();
+        let Some(mut obs) =
+            PyObservation::for_entity(arena, &mut self.interest, id, max_contacts, &query)
+        else {
+            out.fill(0.0);
+            return false;
+        };
+        obs.intents = PyObservation::build_intents(self.inner.arena(), id, max_intents);
+        if objectives {
+            obs.objectives = self.objective_features(id);
+        }
+        obs.write_flat(out);
+        true
+    }
+
+    /// The objectives block of `id`'s observation (zeros if it does not
+    /// exist).
+    fn objective_features(&self, id: EntityId) -> Vec<f32> {
+        let arena = self.inner.arena();
+        arena.get(id).map_or_else(
+            || vec![0.0; Objectives::WIDTH],
+            |entity| self.objectives.features(arena, entity),
+        )
+    }
+
+    /// Spawns with the next free ID, or with `id` if given.
+    fn spawn_entity(&mut self, tag: EntityTag, inner: EntityInner, id: Option<PyEntityId>) -> PyResult<EntityId> {
+        let arena = self.inner.arena_mut();
+        match id {
+            Some(id) => arena
+                .spawn_with_id(id.into(), tag, inner)
+                .map_err(|e| InvalidValue::new_err(e.to_string())),
+            None => Ok(arena.spawn(tag, inner)),
+        }
+    }
+
+    /// An empty simulation under `seed` with this one's settings, scoring,
+    /// heatmap, weapon assignment, classification, order follower, convoys
+    /// and sensors; what `reset()` keeps, less proximity triggers and the
+    /// league opponent.
+    fn configured(&self, seed: u64) -> Simulation {
+        let arena = self.inner.arena();
+        let mut inner = Simulation::new(seed);
+        inner.set_physics_dt(self.config.physics.dt);
+        if let Some(keeper) = self.inner.score_keeper() {
+            inner.enable_scoring(keeper.rules().clone());
+        }
+        if let Some(recorder) = self.inner.heatmap_recorder() {
+            inner.enable_heatmap(*recorder.config());
+        }
+        if let Some(assignment) = self.inner.weapon_assignment() {
+            inner.enable_weapon_assignment(*assignment.config());
+        }
+        if let Some(classification) = self.inner.classification() {
+            inner.enable_classification(*classification.model());
+        }
+        inner.arena_mut().set_bounds(arena.bounds().copied());
+        inner.arena_mut().set_currents(arena.currents().cloned());
+        inner.arena_mut().set_wind(arena.wind().copied());
+        *inner.arena_mut().environment_mut() = arena.environment().clone();
+        inner.arena_mut().set_id_namespace(arena.id_namespace());
+        inner.plugin_watchdog_mut().set_budget(self.inner.plugin_watchdog().budget().copied());
+        inner.set_action_interval(self.inner.action_interval());
+        inner.rng_audit().enable(self.inner.rng_audit().capacity());
+        inner.enable_event_history(self.inner.event_history().capacity());
+        if let Some(plugin) = &self.order_follower {
+            for tag in [EntityTag::Ship, EntityTag::Squadron] {
+                inner.plugins_mut().register(tag, plugin.clone());
+            }
+        }
+        for plugin in &self.convoys {
+            inner.plugins_mut().register(EntityTag::Ship, plugin.clone());
+        }
+        if let Some(plugin) = &self.sensors {
+            inner.plugins_mut().register(EntityTag::Ship, plugin.clone());
+        }
+        inner
+    }
+
+    /// A `run_batch` episode: a copy of the current arena under `seed`,
+    /// set up as `reset()` would but without proximity triggers.
+    fn episode(&self, seed: u64) -> Self {
+        let mut inner = self.configured(seed);
+        inner.restore(self.inner.arena().clone());
+        let mut episode = Self {
+            stamps_from: inner.tick() + 1,
+            inner,
+            interest: InterestManager::new(self.interest.sort_key()),
+            frames: FrameHistory::default(),
+            proximity: Vec::new(),
+            order_follower: self.order_follower.clone(),
+            sensors: self.sensors.clone(),
+            convoys: self.convoys.clone(),
+            objectives: self.objectives.clone(),
+            league: self.league.clone(),
+            opponent: None,
+            config: self.config.clone(),
+        };
+        episode.draw_opponent();
+        episode
+    }
+
+    /// Draws the episode's league opponent from the seed and registers it.
+    fn draw_opponent(&mut self) {
+        let Some((league, team)) = &mut self.league else {
+            return;
+        };
+        let policy = league.select(self.inner.seed()).policy(*team);
+        self.set_opponent(policy);
+    }
+
+    /// Registers `policy` as the episode's league opponent.
+    fn set_opponent(&mut self, policy: ScriptedPolicy) {
+        let policy = Arc::new(policy);
+        self.inner.plugins_mut().register(EntityTag::Ship, policy.clone());
+        self.opponent = Some(policy);
+    }
+
+    /// Registers the proximity triggers with their observer tags.
+    fn register_proximity(&mut self) {
+        for (tags, plugin) in &self.proximity {
+            for tag in tags {
+                self.inner.plugins_mut().register(*tag, plugin.clone());
+            }
+        }
+    }
+
+    /// Checks that an entity exists, is alive and accepts actions.
+    fn check_commandable(&self, id: EntityId) -> PyResult<()> {
+        let Some(entity) = self.inner.arena().get(id) else {
+            return Err(UnknownEntity::new_err(format!(
+                "entity {} does not exist",
+                id.as_u64()
+            )));
+        };
+        let EntityInner::Ship(c) = entity.inner() else {
+            return Err(NotSupportedForTag::new_err(format!(
+                "entity {} is a {:?}; only ships accept actions",
+                id.as_u64(),
+                entity.tag()
+            )));
+        };
+        if c.combat.is_destroyed() {
+            return Err(EntityDestroyed::new_err(format!(
+                "entity {} is destroyed",
+                id.as_u64()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sets or clears the steering assist of a controller's entities.
+    fn set_assist(
+        &mut self,
+        controller: Option<&str>,
+        assist: Option<SteeringAssist>,
+    ) -> PyResult<()> {
+        let controller = controller.map(str_to_controller).transpose()?;
+        let book = self.inner.arena_mut().steering_assist_mut();
+        match controller {
+            Some(controller) => book.set(controller, assist),
+            None => book.set_uncontrolled(assist),
+        }
+        Ok(())
+    }
+
+    /// Checks that `controller`, if given, controls the entity.
+    fn check_controller(&self, id: EntityId, controller: Option<&str>) -> PyResult<()> {
+        let Some(controller) = controller.map(str_to_controller).transpose()? else {
+            return Ok(());
+        };
+        if self.inner.arena().can_command(Some(controller), id) {
+            return Ok(());
+        }
+        Err(NotController::new_err(format!(
+            "entity {} is not controlled by {controller}",
+            id.as_u64()
+        )))
+    }
+
+    /// Checks that an action ordering a depth targets a submarine.
+    fn check_depth_order(&self, id: EntityId, action: &ShipAction) -> PyResult<()> {
+        let submarine = self.inner.arena().get(id).and_then(Entity::submarine);
+        if action.depth.is_some() && submarine.is_none() {
+            return Err(InvalidValue::new_err(format!(
+                "entity {} is not a submarine",
+                id.as_u64()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// An `apply_action` dict, parsed and validated.
+struct ShipAction {
+    velocity: Option<Vec2>,
+    heading: Option<f32>,
+    throttle: Option<f32>,
+    depth: Option<f32>,
+}
+
+impl ShipAction {
+    fn parse(action: &Bound<'_, pyo3::types::PyDict>) -> PyResult<Self> {
+        let velocity = action
+            .get_item("velocity")?
+            .map(|v| {
+                v.extract::<(f32, f32)>().map_err(|_| {
+                    InvalidValue::new_err("velocity must be a (vx, vy) tuple of floats")
+                })
+            })
+            .transpose()?
+            .map(|(vx, vy)| Vec2::new(vx, vy));
+        if velocity.is_some_and(|v| !v.is_finite()) {
+            return Err(InvalidValue::new_err("velocity must be finite"));
+        }
+
+        let heading = action
+            .get_item("heading")?
+            .map(|h| {
+                h.extract::<f32>()
+                    .map_err(|_| InvalidValue::new_err("heading must be a float"))
+            })
+            .transpose()?;
+        if heading.is_some_and(|h| !h.is_finite()) {
+            return Err(InvalidValue::new_err("heading must be finite"));
+        }
+
+        let throttle = action
+            .get_item("throttle")?
+            .map(|t| {
+                t.extract::<f32>()
+                    .map_err(|_| InvalidValue::new_err("throttle must be a float"))
+            })
+            .transpose()?;
+        if throttle.is_some_and(|t| !t.is_finite()) {
+            return Err(InvalidValue::new_err("throttle must be finite"));
+        }
+
+        let depth = action
+            .get_item("depth")?
+            .map(|d| {
+                d.extract::<f32>()
+                    .map_err(|_| InvalidValue::new_err("depth must be a float"))
+            })
+            .transpose()?;
+        if depth.is_some_and(|d| !d.is_finite()) {
+            return Err(InvalidValue::new_err("depth must be finite"));
+        }
+
+        Ok(Self {
+            velocity,
+            heading,
+            throttle,
+            depth,
+        })
+    }
+}
+
+/// Cooperative cancellation flag for `PySimulation.step_async`.
+///
+/// Clones share the flag, so a token can be cancelled from any thread.
+#[pyclass(module = "tidebreak._tidebreak")]
+#[derive(Clone, Default)]
+pub struct PyCancellationToken {
+    flag: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl PyCancellationToken {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation.
+    fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation has been requested.
+    #[getter]
+    fn cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CancellationToken(cancelled={})", self.cancelled())
+    }
+}
+
+/// Recording of a simulation run, saved as a portable `.tbr` file.
+///
+/// Step the simulation through `record_step` to record it. Keyframes are
+/// kept every `keyframe_interval` steps so `seek` can jump to any tick by
+/// re-running at most one interval. Actions applied between steps are
+/// recorded too.
+///
+/// ```python
+/// replay = tidebreak.Replay(keyframe_interval=100, scenario="duel.toml")
+/// for _ in range(1000):
+///     sim.apply_action(ship, policy(sim.get_observation(ship)))
+///     replay.record_step(sim)
+/// replay.save("duel.tbr")
+///
+/// loaded = tidebreak.Replay.load("duel.tbr")
+/// loaded.seek(viewer_sim, 640)
+/// ```
+#[pyclass(name = "Replay", module = "tidebreak._tidebreak")]
+pub struct PyReplay {
+    inner: Replay,
+}
+
+#[pymethods]
+impl PyReplay {
+    #[new]
+    #[pyo3(signature = (keyframe_interval=100, scenario=None))]
+    fn new(keyframe_interval: u64, scenario: Option<String>) -> Self {
+        let replay = Replay::new(keyframe_interval);
+        Self {
+            inner: match scenario {
+                Some(scenario) => replay.with_scenario(scenario),
+                None => replay,
+            },
+        }
+    }
+
+    /// Read a replay saved with `save`.
+    #[staticmethod]
+    fn load(path: PathBuf) -> PyResult<Self> {
+        let file = BufReader::new(File::open(path)?);
+        Ok(Self {
+            inner: Replay::import(file).map_err(replay_error)?,
+        })
+    }
+
+    /// Write the replay to a `.tbr` file.
+    fn save(&self, path: PathBuf) -> PyResult<()> {
+        let file = BufWriter::new(File::create(path)?);
+        self.inner.export(file).map_err(replay_error)
+    }
+
+    /// Step `sim` once and record the step.
+    ///
+    /// Raises InvalidValue if `sim` is not at the tick after the last
+    /// recorded step. Releases the GIL during execution.
+    fn record_step(&mut self, py: Python, sim: &mut PySimulation) -> PyResult<()> {
+        py.allow_threads(|| self.inner.record_step(&mut sim.inner, &[]))
+            .map_err(replay_error)
+    }
+
+    /// Put `sim` in its state just before `tick` executed.
+    ///
+    /// `sim` must use the recorded seed and the same plugins for the
+    /// result to match the recording. Raises InvalidValue for a tick
+    /// outside `start_tick..=end_tick`, or if the re-stepped state differs
+    /// from the recorded one.
+    fn seek(&self, py: Python, sim: &mut PySimulation, tick: u64) -> PyResult<()> {
+        py.allow_threads(|| self.inner.seek(&mut sim.inner, tick))
+            .map_err(replay_error)?;
+        sim.stamps_from = sim.inner.tick() + 1;
+        sim.frames.clear();
+        sim.interest.clear();
+        Ok(())
+    }
+
+    /// Copy of the recording cut just before `tick`, to record a fork
+    /// started there (see `Simulation.fork_from`).
+    fn branch(&self, tick: u64) -> PyResult<Self> {
+        Ok(Self {
+            inner: self.inner.branch(tick).map_err(replay_error)?,
+        })
+    }
+
+    /// Seed of the recorded simulation.
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.inner.header().seed
+    }
+
+    /// Scenario the run was set up from, if given.
+    #[getter]
+    fn scenario(&self) -> Option<String> {
+        self.inner.header().scenario.clone()
+    }
+
+    /// Tick of the first recorded step.
+    #[getter]
+    fn start_tick(&self) -> u64 {
+        self.inner.header().start_tick
+    }
+
+    /// Tick after the last recorded step.
+    #[getter]
+    fn end_tick(&self) -> u64 {
+        self.inner.end_tick()
+    }
+
+    /// Ticks holding a keyframe.
+    #[getter]
+    fn keyframe_ticks(&self) -> Vec<u64> {
+        self.inner.keyframes().iter().map(|k| k.tick).collect()
+    }
+
+    /// Events recorded for `tick`, as dicts with the keys of
+    /// `Simulation.query_events`. Empty for a tick outside the recording.
+    fn events<'py>(&self, py: Python<'py>, tick: u64) -> PyResult<Bound<'py, PyList>> {
+        let list = PyList::empty(py);
+        let rows = self
+            .inner
+            .tick(tick)
+            .into_iter()
+            .flat_map(|record| &record.events)
+            .filter_map(EventRow::from_envelope);
+        for row in rows {
+            list.append(event_row_dict(py, &row)?)?;
+        }
+        Ok(list)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.ticks().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Replay(ticks={}..{}, keyframes={})",
+            self.start_tick(),
+            self.end_tick(),
+            self.inner.keyframes().len()
+        )
+    }
+}
+
+/// Future done-callback that forwards asyncio cancellation to the worker.
+#[pyclass]
+struct CancelOnDone {
+    flag: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl CancelOnDone {
+    fn __call__(&self, future: &Bound<'_, PyAny>) -> PyResult<()> {
+        if future.call_method0("cancelled")?.is_truthy()? {
+            self.flag.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+/// Executor job behind `PySimulation.step_async`.
+#[pyclass]
+struct StepWorker {
+    sim: Py<PySimulation>,
+    ticks: u64,
+    flag: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl StepWorker {
+    fn __call__(&self, py: Python) -> PyResult<u64> {
+        let mut sim = self.sim.try_borrow_mut(py)?;
+        let sim = &mut sim.inner;
+        py.allow_threads(|| {
+            panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut completed = 0;
+                while completed < self.ticks && sim.step_cancellable(&self.flag) {
+                    completed += 1;
+                }
+                completed
+            }))
+        })
+        .map_err(|_| PyRuntimeError::new_err("simulation step panicked"))
+    }
+}
+
+/// One `PySimulation.run_batch` episode and its progress.
+struct BatchEpisode {
+    seed: u64,
+    sim: Py<PySimulation>,
+    /// Tick at which the episode stops
+    end: u64,
+    start: u64,
+    decisions: u64,
+    terminated: bool,
+}
+
+impl BatchEpisode {
+    fn new(py: Python, sim: PySimulation, max_ticks: u64) -> PyResult<Self> {
+        let seed = sim.inner.seed();
+        let start = sim.inner.tick();
+        Ok(Self {
+            seed,
+            sim: Py::new(py, sim)?,
+            end: start.saturating_add(max_ticks),
+            start,
+            decisions: 0,
+            terminated: false,
+        })
+    }
+
+    /// Calls the policy if the episode is at a decision point, returning
+    /// whether the episode goes on.
+    fn decide(&mut self, py: Python, policy: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let (tick, decision) = {
+            let sim = self.sim.try_borrow(py)?;
+            (sim.inner.tick(), sim.inner.is_decision_tick())
+        };
+        if self.terminated || tick >= self.end {
+            return Ok(false);
+        }
+        if decision {
+            self.decisions += 1;
+            self.terminated = policy.call1((self.sim.clone_ref(py),))?.is_truthy()?;
+        }
+        Ok(!self.terminated)
+    }
+
+    /// Steps to the next decision point, or to `end` if that comes first.
+    fn advance(sim: &mut Simulation, end: u64) {
+        loop {
+            sim.step();
+            if sim.is_decision_tick() || sim.tick() >= end {
+                return;
+            }
+        }
+    }
+
+    /// Runs `episodes` to the end together, calling the policy for each in
+    /// turn and then stepping them all in parallel.
+    fn run_lockstep(py: Python, episodes: &mut [Self], policy: &Bound<'_, PyAny>) -> PyResult<()> {
+        loop {
+            let mut running = Vec::new();
+            for episode in episodes.iter_mut() {
+                if episode.decide(py, policy)? {
+                    running.push((episode.sim.try_borrow_mut(py)?, episode.end));
+                }
+            }
+            if running.is_empty() {
+                return Ok(());
+            }
+            let mut sims: Vec<(&mut Simulation, u64)> =
+                running.iter_mut().map(|(sim, end)| (&mut sim.inner, *end)).collect();
+            py.allow_threads(|| {
+                sims.par_iter_mut().for_each(|(sim, end)| Self::advance(sim, *end));
+            });
+        }
+    }
+
+    fn result<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+        let sim = self.sim.try_borrow(py)?;
+        let survivors: Vec<PyEntityId> = sim
+            .inner
+            .arena()
+            .entities_sorted()
+            .filter(|e| e.is_ship() && !e.is_destroyed())
+            .map(|e| e.id().into())
+            .collect();
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("seed", self.seed)?;
+        dict.set_item("ticks", sim.inner.tick() - self.start)?;
+        dict.set_item("decisions", self.decisions)?;
+        dict.set_item("terminated", self.terminated)?;
+        dict.set_item("scores", sim.scores(py)?)?;
+        dict.set_item("survivors", survivors)?;
+        Ok(dict)
+    }
+}
+
+/// Observation for a single agent (ship).
+///
+/// Pre-vectorized observation suitable for DRL training. Contains:
+/// - `own_state`: Position, heading, velocity, and health as a 1D array
+/// - `contacts`: Detected contacts from the sensor track table as a 2D array
+/// - `intents`: Intents received from friendly entities as a 2D array
+/// - `bound_distances`: Distances to the world edges as a 1D array
+/// - `weapons`: Readiness, magazine and reload state per weapon as a 2D array
+/// - `own_systems`: Fuel, ammunition stores, status flags and weapon
+///   cooldowns as a 1D array
+/// - `objectives`: Nearest zone, convoy center and mission timer as a 1D
+///   array, if requested
+///
+/// The block methods return read-only numpy views of buffers owned by the
+/// observation rather than copies; a view keeps its observation alive.
+/// Copy an array (`arr.copy()`) before modifying it. The `stacked_*`
+/// methods build new arrays.
+#[pyclass(module = "tidebreak._tidebreak", frozen)]
+pub struct PyObservation {
+    /// Own state: [x, y, heading, vx, vy, hp, max_hp]
+    own_state: Vec<f32>,
+    /// Contacts: [[x, y, rel_heading, distance, quality], ...]
+    contacts: Rows,
+    /// Intents: [[rel_x, rel_y, age, payload...], ...]
+    intents: Rows,
+    /// Observed tag per contact slot (0 = unknown or empty)
+    contact_tags: Vec<i32>,
+    /// Distances to the world edges: [min_x, max_x, min_y, max_y]
+    bounds: Vec<f32>,
+    /// Weapons: [[ready, cooldown, rounds, magazine_size, reload_progress], ...]
+    weapons: Rows,
+    /// Contact kinematics: [[estimated, course, speed, course_sigma, speed_sigma], ...]
+    contact_kinematics: Rows,
+    /// Own systems: [fuel, ammo by type..., status flags..., cooldown per weapon...]
+    own_systems: Vec<f32>,
+    /// Objectives: [zone_bearing, zone_distance, zone_control, convoy_rel_x,
+    /// convoy_rel_y, convoy_present, mission_fraction] (empty unless requested)
+    objectives: Vec<f32>,
+    /// Stacked frames, oldest first (empty when not stacking)
+    history: Vec<Arc<ObservationFrame>>,
+}
+
+/// One recorded observation, shared between the frame history and the
+/// observations that stack it.
+struct ObservationFrame {
+    tick: u64,
+    own_state: Vec<f32>,
+    contacts: Rows,
+    intents: Rows,
+    contact_tags: Vec<i32>,
+    bounds: Vec<f32>,
+    weapons: Rows,
+}
+
+impl ObservationFrame {
+    fn of(obs: &PyObservation, tick: u64) -> Self {
+        Self {
+            tick,
+            own_state: obs.own_state.clone(),
+            contacts: obs.contacts.clone(),
+            intents: obs.intents.clone(),
+            contact_tags: obs.contact_tags.clone(),
+            bounds: obs.bounds.clone(),
+            weapons: obs.weapons.clone(),
+        }
+    }
+
+    /// Whether both frames have the same slot counts.
+    fn same_shape(&self, other: &Self) -> bool {
+        self.contacts.shape() == other.contacts.shape()
+            && self.weapons.shape() == other.weapons.shape()
+            && self.intents.shape() == other.intents.shape()
+    }
+}
+
+/// Equal-width rows of an observation block, stored row-major in one
+/// buffer so numpy can view them without copying.
+#[derive(Clone, Default)]
+struct Rows {
+    values: Vec<f32>,
+    len: usize,
+    width: usize,
+}
+
+impl Rows {
+    /// No rows of `width` values.
+    const fn new(width: usize) -> Self {
+        Self {
+            values: Vec::new(),
+            len: 0,
+            width,
+        }
+    }
+
+    /// Packs `rows`, which must all have the same length; `width` is used
+    /// when there are none.
+    fn from_vecs(rows: &[Vec<f32>], width: usize) -> PyResult<Self> {
+        let mut packed = Self::new(rows.first().map_or(width, Vec::len));
+        for row in rows {
+            if row.len() != packed.width {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "rows must all have the same length",
+                ));
+            }
+            packed.push(row);
+        }
+        Ok(packed)
+    }
+
+    fn push(&mut self, row: &[f32]) {
+        debug_assert_eq!(row.len(), self.width);
+        self.values.extend_from_slice(row);
+        self.len += 1;
+    }
+
+    /// Appends zero rows up to `len` rows.
+    fn pad(&mut self, len: usize) {
+        if self.len < len {
+            self.values.resize(len * self.width, 0.0);
+            self.len = len;
+        }
+    }
+
+    const fn len(&self) -> usize {
+        self.len
+    }
+
+    const fn shape(&self) -> [usize; 2] {
+        [self.len, self.width]
+    }
+
+    fn as_slice(&self) -> &[f32] {
+        &self.values
+    }
+
+    fn to_vecs(&self) -> Vec<Vec<f32>> {
+        if self.width == 0 {
+            return vec![Vec::new(); self.len];
+        }
+        self.values.chunks(self.width).map(<[f32]>::to_vec).collect()
+    }
+}
+
+/// How the contact block of an observation is selected and laid out.
+#[derive(Default)]
+struct ContactQuery {
+    /// Ordering, or `None` for the interest manager's
+    sort_key: Option<ContactSortKey>,
+    filter: ContactFilter,
+    /// Append `[rel_vx, rel_vy]` to each contact row
+    relative_velocity: bool,
+}
+
+/// Scenario objectives summarized in the objectives block of an
+/// observation.
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct Objectives {
+    zones: Vec<Zone>,
+    /// Label of the convoy ships whose center is reported
+    convoy_label: Option<String>,
+    /// Episode length the mission timer counts toward
+    mission_ticks: Option<u64>,
+}
+
+impl Objectives {
+    /// Values in the objectives block.
+    const WIDTH: usize = 7;
+
+    /// `[zone_bearing, zone_distance, zone_control, convoy_rel_x,
+    /// convoy_rel_y, convoy_present, mission_fraction]` for `entity`.
+    ///
+    /// The zone values describe the zone whose center is nearest, with
+    /// control as seen by the entity's team from the live ships and
+    /// squadrons inside (coded as in `team_observation`); the convoy
+    /// values the mean position of its live ships relative to the entity.
+    /// Missing objectives are zero.
+    #[allow(clippy::cast_precision_loss)]
+    fn features(&self, arena: &tidebreak_core::arena::Arena, entity: &Entity) -> Vec<f32> {
+        let position = arena.spatial().get(entity.id()).unwrap_or(Vec2::ZERO);
+        let mut values = vec![0.0; Self::WIDTH];
+        let nearest = self.zones.iter().min_by(|a, b| {
+            let (a, b) = (a.center.distance(position), b.center.distance(position));
+            a.total_cmp(&b)
+        });
+        if let Some(zone) = nearest {
+            let rel = zone.center - position;
+            let (mut friendly, mut hostile) = (0, 0);
+            for other in arena.entities_sorted() {
+                let live = (other.is_ship() || other.is_squadron()) && !other.is_destroyed();
+                let inside = arena.spatial().get(other.id()).is_some_and(|p| zone.contains(p));
+                if live && inside {
+                    if other.team().is_some() && other.team() == entity.team() {
+                        friendly += 1;
+                    } else {
+                        hostile += 1;
+                    }
+                }
+            }
+            values[0] = rel.y.atan2(rel.x);
+            values[1] = rel.length();
+            values[2] = ZoneControl::from_counts(friendly, hostile) as i32 as f32;
+        }
+        if let Some(label) = &self.convoy_label {
+            let ships: Vec<Vec2> = arena
+                .entities_sorted()
+                .filter(|e| e.has_label(label) && !e.is_destroyed())
+                .filter_map(|e| arena.spatial().get(e.id()))
+                .collect();
+            if !ships.is_empty() {
+                let center = ships.iter().sum::<Vec2>() / ships.len() as f32;
+                values[3] = center.x - position.x;
+                values[4] = center.y - position.y;
+                values[5] = 1.0;
+            }
+        }
+        if let Some(ticks) = self.mission_ticks {
+            values[6] = (arena.current_tick() as f32 / ticks as f32).min(1.0);
+        }
+        values
+    }
+}
+
+/// Per-entity ring buffers of recent observation frames.
+#[derive(Default)]
+struct FrameHistory {
+    buffers: BTreeMap<EntityId, VecDeque<Arc<ObservationFrame>>>,
+}
+
+impl FrameHistory {
+    /// Record `frame` (replacing one from the same tick) and return the last
+    /// `capacity` frames, oldest first, padded with the oldest frame.
+    fn record(
+        &mut self,
+        id: EntityId,
+        frame: ObservationFrame,
+        capacity: usize,
+    ) -> Vec<Arc<ObservationFrame>> {
+        let buffer = self.buffers.entry(id).or_default();
+        if buffer.back().is_some_and(|last| !last.same_shape(&frame)) {
+            buffer.clear();
+        }
+        if buffer.back().is_some_and(|last| last.tick == frame.tick) {
+            buffer.pop_back();
+        }
+        buffer.push_back(Arc::new(frame));
+        while buffer.len() > capacity {
+            buffer.pop_front();
+        }
+
+        let oldest = Arc::clone(&buffer[0]);
+        std::iter::repeat_n(oldest, capacity - buffer.len())
+            .chain(buffer.iter().cloned())
+            .collect()
+    }
+
+    fn remove(&mut self, id: EntityId) {
+        self.buffers.remove(&id);
+    }
+
+    fn clear(&mut self) {
+        self.buffers.clear();
+    }
+}
+
+/// Converts a world clock reading to a dict.
+fn clock_dict<'py>(
+    py: Python<'py>,
+    reading: &ClockReading,
+) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+    let phase = match reading.phase {
+        DayPhase::Night => "night",
+        DayPhase::Dawn => "dawn",
+        DayPhase::Day => "day",
+        DayPhase::Dusk => "dusk",
+    };
+    let dict = pyo3::types::PyDict::new(py);
+    dict.set_item("day", reading.day)?;
+    dict.set_item("hour", reading.hour)?;
+    dict.set_item("phase", phase)?;
+    dict.set_item("daylight", reading.daylight)?;
+    dict.set_item("moon_phase", reading.moon_phase)?;
+    dict.set_item("moon_illumination", reading.moon_illumination)?;
+    dict.set_item("light", reading.light)?;
+    Ok(dict)
+}
+
+/// Encode an observed tag as 0 (unknown), 1 (ship), 2 (platform),
+/// 3 (projectile) or 4 (squadron).
+const fn tag_code(tag: Option<EntityTag>) -> i32 {
+    match tag {
+        None => 0,
+        Some(EntityTag::Ship) => 1,
+        Some(EntityTag::Platform) => 2,
+        Some(EntityTag::Projectile) => 3,
+        Some(EntityTag::Squadron) => 4,
+    }
+}
+
+impl PyObservation {
+    /// Length of the flat layout written by `write_flat`.
+    const fn flat_len(
+        max_contacts: usize,
+        max_intents: usize,
+        intent_width: usize,
+        objectives: bool,
+    ) -> usize {
+        let objectives = if objectives { Objectives::WIDTH } else { 0 };
+        7 + max_contacts * 6 + 4 + max_intents * intent_width + objectives
+    }
+
+    /// Copies own_state, contacts, contact_tags, bounds, intents and
+    /// objectives into `out`, which must be exactly `flat_len` long.
+    #[allow(clippy::cast_precision_loss)]
+    fn write_flat(&self, out: &mut [f32]) {
+        let values = self
+            .own_state
+            .iter()
+            .copied()
+            .chain(self.contacts.as_slice().iter().copied())
+            .chain(self.contact_tags.iter().map(|&tag| tag as f32))
+            .chain(self.bounds.iter().copied())
+            .chain(self.intents.as_slice().iter().copied())
+            .chain(self.objectives.iter().copied());
+        for (slot, value) in out.iter_mut().zip(values) {
+            *slot = value;
+        }
+    }
+
+    /// Build observation for a specific entity.
+    fn for_entity(
+        arena: &tidebreak_core::arena::Arena,
+        interest: &mut InterestManager,
+        entity_id: EntityId,
+        max_contacts: usize,
+        query: &ContactQuery,
+    ) -> Option<Self> {
+        let entity = arena.get(entity_id)?;
+
+        // Build own state vector
+        let own_state = Self::build_own_state(entity);
+
+        // Build contacts from the cached nearest-K selection
+        let sort_key = query.sort_key.unwrap_or_else(|| interest.sort_key());
+        let selected =
+            interest.contacts_matching(arena, entity_id, max_contacts, sort_key, &query.filter);
+        let contacts = Self::build_contacts(selected, max_contacts, query.relative_velocity);
+        let contact_tags = Self::build_contact_tags(selected, max_contacts);
+        let contact_kinematics = Self::build_contact_kinematics(selected, max_contacts);
+        let bounds = Self::build_bounds(arena, entity);
+        let weapons = Self::build_weapons(entity);
+        let own_systems = Self::build_own_systems(entity);
+
+        Some(Self {
+            own_state,
+            contacts,
+            intents: Rows::default(),
+            contact_tags,
+            bounds,
+            weapons,
+            contact_kinematics,
+            own_systems,
+            objectives: Vec::new(),
+            history: Vec::new(),
+        })
+    }
+
+    /// One row per weapon: `[ready, cooldown, rounds, magazine_size,
+    /// reload_progress]`, with `ready` as 0.0 or 1.0.
+    #[allow(clippy::cast_precision_loss)]
+    fn build_weapons(entity: &Entity) -> Rows {
+        let mut rows = Rows::new(5);
+        let weapons = match entity.inner() {
+            EntityInner::Ship(c) => &c.combat.weapons,
+            EntityInner::Squadron(c) => &c.combat.weapons,
+            _ => return rows,
+        };
+        for w in weapons {
+            rows.push(&[
+                if w.is_ready() { 1.0 } else { 0.0 },
+                w.cooldown,
+                w.rounds as f32,
+                w.magazine_size as f32,
+                w.reload_progress(),
+            ]);
+        }
+        rows
+    }
+
+    /// Fuel fraction, rounds held per ammunition type (in `AmmoType::ALL`
+    /// order), one 0.0/1.0 value per status flag (in bit order) and the
+    /// cooldown fraction of each weapon. Entities without an inventory
+    /// report no fuel and no stores.
+    #[allow(clippy::cast_precision_loss)]
+    fn build_own_systems(entity: &Entity) -> Vec<f32> {
+        let (inventory, combat) = match entity.inner() {
+            EntityInner::Ship(c) => (Some(&c.inventory), Some(&c.combat)),
+            EntityInner::Squadron(c) => (None, Some(&c.combat)),
+            _ => (None, None),
+        };
+        let flags = combat.map_or(StatusFlags::empty(), |c| c.status_flags);
+        let mut values = vec![inventory.map_or(0.0, InventoryState::fuel_percent)];
+        values.extend(
+            AmmoType::ALL
+                .iter()
+                .map(|&ammo| inventory.map_or(0, |i| i.get_ammo(ammo)) as f32),
+        );
+        values.extend(StatusFlags::all().iter().map(|f| f32::from(u8::from(flags.contains(f)))));
+        if let Some(combat) = combat {
+            values.extend(combat.weapons.iter().map(WeaponState::cooldown_fraction));
+        }
+        values
+    }
+
+    /// One row per contact slot: `[estimated, course, speed, course_sigma,
+    /// speed_sigma]` from the track history, with `estimated` as 0.0 or 1.0
+    /// and all zero without an estimate, zero-padded to `max_contacts`.
+    fn build_contact_kinematics(selected: &[CachedContact], max_contacts: usize) -> Rows {
+        let mut rows = Rows::new(5);
+        for c in selected {
+            rows.push(&c.estimate.map_or([0.0; 5], |e| {
+                [1.0, e.course, e.speed, e.course_sigma, e.speed_sigma]
+            }));
+        }
+        rows.pad(max_contacts);
+        rows
+    }
+
+    /// Encode observed tags as 0 (unknown), 1 (ship), 2 (platform),
+    /// 3 (projectile) or 4 (squadron), zero-padded to `max_contacts`.
+    fn build_contact_tags(selected: &[CachedContact], max_contacts: usize) -> Vec<i32> {
+        let mut tags: Vec<i32> = selected
+            .iter()
+            .map(|c| tag_code(c.observed_tag))
+            .collect();
+        tags.resize(max_contacts, 0);
+        tags
+    }
+
+    /// Distances from the entity to the `[min_x, max_x, min_y, max_y]` world
+    /// edges, all zero when the arena is unbounded.
+    fn build_bounds(arena: &tidebreak_core::arena::Arena, entity: &Entity) -> Vec<f32> {
+        let position = arena.spatial().get(entity.id()).unwrap_or(Vec2::ZERO);
+        arena
+            .bounds()
+            .map_or([0.0; 4], |b| b.edge_distances(position))
+            .to_vec()
+    }
+
+    /// Build the received-intent block.
+    ///
+    /// Each row is `[rel_x, rel_y, age, payload...]`, with the payload
+    /// zero-padded to the channel's `max_intent_len`. Rows beyond the
+    /// received intents are zero-filled.
+    fn build_intents(
+        arena: &tidebreak_core::arena::Arena,
+        entity_id: EntityId,
+        max_intents: usize,
+    ) -> Rows {
+        let comms = arena.comms();
+        let width = 3 + comms.config().max_intent_len;
+        let own_pos = arena.spatial().get(entity_id).unwrap_or(Vec2::ZERO);
+        let tick = arena.current_tick();
+
+        let mut rows = Rows::new(width);
+        let mut row = Vec::with_capacity(width);
+        for intent in comms.received_by(arena, entity_id).into_iter().take(max_intents) {
+            let rel = arena.spatial().get(intent.sender).unwrap_or(own_pos) - own_pos;
+            row.clear();
+            row.extend([rel.x, rel.y, (tick - intent.sent_tick) as f32]);
+            row.extend_from_slice(&intent.payload);
+            row.resize(width, 0.0);
+            rows.push(&row);
+        }
+        rows.pad(max_intents);
+        rows
+    }
+
+    fn build_own_state(entity: &Entity) -> Vec<f32> {
+        match entity.inner() {
+            EntityInner::Ship(c) => vec![
+                c.transform.position.x,
+                c.transform.position.y,
+                c.transform.heading,
+                c.physics.velocity.x,
+                c.physics.velocity.y,
+                c.combat.hp,
+                c.combat.max_hp,
+            ],
+            EntityInner::Squadron(c) => vec![
+                c.transform.position.x,
+                c.transform.position.y,
+                c.transform.heading,
+                c.physics.velocity.x,
+                c.physics.velocity.y,
+                c.combat.hp,
+                c.combat.max_hp,
+            ],
+            _ => vec![0.0; 7], // Platforms/projectiles shouldn't be agents
+        }
+    }
+
+    fn build_contacts(
+        selected: &[CachedContact],
+        max_contacts: usize,
+        relative_velocity: bool,
+    ) -> Rows {
+        let width = if relative_velocity { 7 } else { 5 };
+        let mut rows = Rows::new(width);
+        for c in selected {
+            let row = [
+                c.position.x,
+                c.position.y,
+                c.rel_heading,
+                c.distance,
+                c.quality as i32 as f32,
+                c.rel_velocity.x,
+                c.rel_velocity.y,
+            ];
+            rows.push(&row[..width]);
+        }
+        rows.pad(max_contacts);
+        rows
+    }
+
+    /// One block concatenated over the stacked frames, oldest first, or
+    /// just the current frame when not stacking.
+    fn stack<T: Copy>(&self, current: &[T], block: impl Fn(&ObservationFrame) -> &[T]) -> Vec<T> {
+        if self.history.is_empty() {
+            return current.to_vec();
+        }
+        self.history.iter().flat_map(|f| block(f).iter().copied()).collect()
+    }
+
+    /// A read-only numpy view of `values`, one of the blocks of `slf`.
+    ///
+    /// The view holds a reference to the observation as its base, so the
+    /// buffer outlives every view of it.
+    fn view<'py, T, D>(
+        slf: &Bound<'py, Self>,
+        values: &[T],
+        shape: impl Into<numpy::ndarray::StrideShape<D>>,
+    ) -> PyResult<Bound<'py, numpy::PyArray<T, D>>>
+    where
+        T: numpy::Element,
+        D: numpy::ndarray::Dimension,
+    {
+        let values = numpy::ndarray::ArrayView::from_shape(shape, values)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        // SAFETY: `values` lives in a buffer owned by `slf`, which becomes the
+        // array's base and so outlives it. The class is frozen, so the buffer
+        // is never written or reallocated once the observation is shared.
+        let array = unsafe { numpy::PyArray::borrow_from_array(&values, slf.clone().into_any()) };
+        // SAFETY: `array` was created above and is not yet shared.
+        unsafe {
+            (*array.as_array_ptr()).flags &= !numpy::npyffi::flags::NPY_ARRAY_WRITEABLE;
+        }
+        Ok(array)
+    }
+}
+
+#[pymethods]
+impl PyObservation {
+    /// Create an observation from its raw blocks.
+    #[new]
+    #[pyo3(signature = (
+        own_state,
+        contacts,
+        intents=Vec::new(),
+        contact_tags=Vec::new(),
+        bounds=Vec::new(),
+        weapons=Vec::new(),
+        contact_kinematics=Vec::new(),
+        own_systems=Vec::new(),
+        objectives=Vec::new(),
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        own_state: Vec<f32>,
+        contacts: Vec<Vec<f32>>,
+        intents: Vec<Vec<f32>>,
+        contact_tags: Vec<i32>,
+        bounds: Vec<f32>,
+        weapons: Vec<Vec<f32>>,
+        contact_kinematics: Vec<Vec<f32>>,
+        own_systems: Vec<f32>,
+        objectives: Vec<f32>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            own_state,
+            contacts: Rows::from_vecs(&contacts, 5)?,
+            intents: Rows::from_vecs(&intents, 3)?,
+            contact_tags,
+            bounds,
+            weapons: Rows::from_vecs(&weapons, 5)?,
+            contact_kinematics: Rows::from_vecs(&contact_kinematics, 5)?,
+            own_systems,
+            objectives,
+            history: Vec::new(),
+        })
+    }
+
+    /// Pickle support: rebuilt from its raw blocks (the current frame only;
+    /// stacked history is not carried over).
+    #[allow(clippy::type_complexity)]
+    fn __reduce__<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> (
+        Bound<'py, PyType>,
+        (
+            Vec<f32>,
+            Vec<Vec<f32>>,
+            Vec<Vec<f32>>,
+            Vec<i32>,
+            Vec<f32>,
+            Vec<Vec<f32>>,
+            Vec<Vec<f32>>,
+            Vec<f32>,
+            Vec<f32>,
+        ),
+    ) {
+        let obs = slf.get();
+        (
+            slf.get_type(),
+            (
+                obs.own_state.clone(),
+                obs.contacts.to_vecs(),
+                obs.intents.to_vecs(),
+                obs.contact_tags.clone(),
+                obs.bounds.clone(),
+                obs.weapons.to_vecs(),
+                obs.contact_kinematics.to_vecs(),
+                obs.own_systems.clone(),
+                obs.objectives.clone(),
+            ),
+        )
+    }
+
+    /// Own state as numpy array.
+    ///
+    /// Returns a 1D array with shape (7,) containing:
+    /// [x, y, heading, vx, vy, hp, max_hp]
+    fn own_state<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let values = &slf.get().own_state;
+        Self::view(slf, values, values.len())
+    }
+
+    /// Contacts as 2D numpy array, shape (max_contacts, 5), or
+    /// (max_contacts, 7) with relative velocity.
+    ///
+    /// Each row contains: [x, y, rel_heading, distance, quality], followed
+    /// by [rel_vx, rel_vy] if requested. Unused slots are zero-padded.
+    fn contacts<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, numpy::PyArray2<f32>>> {
+        let rows = &slf.get().contacts;
+        Self::view(slf, rows.as_slice(), rows.shape())
+    }
+
+    /// Feature dimension for own_state.
+    #[getter]
+    fn own_state_dim(&self) -> usize {
+        self.own_state.len()
+    }
+
+    /// Number of contact slots.
+    #[getter]
+    fn max_contacts(&self) -> usize {
+        self.contacts.len()
+    }
+
+    /// Observed classification per contact slot as 1D int32 array, shape
+    /// (max_contacts,).
+    ///
+    /// 0 = unknown (below the classification threshold) or empty slot,
+    /// 1 = ship, 2 = platform, 3 = projectile, 4 = squadron.
+    /// Classifications may be wrong.
+    fn contact_tags<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyArray1<i32>>> {
+        let values = &slf.get().contact_tags;
+        Self::view(slf, values, values.len())
+    }
+
+    /// Distances to the world edges as 1D array with shape (4,):
+    /// [min_x, max_x, min_y, max_y]
+    ///
+    /// Negative beyond an edge; all zero when the simulation has no bounds.
+    fn bound_distances<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let values = &slf.get().bounds;
+        Self::view(slf, values, values.len())
+    }
+
+    /// Weapon state as 2D numpy array, shape (num_weapons, 5).
+    ///
+    /// Each row contains: [ready, cooldown, rounds, magazine_size,
+    /// reload_progress], in slot order. `ready` is 1.0 when the weapon can
+    /// fire; `reload_progress` runs from 0.0 to 1.0 while reloading and is
+    /// 0.0 otherwise. Cooldown-only weapons have a magazine_size of 0.
+    fn weapons<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, numpy::PyArray2<f32>>> {
+        let rows = &slf.get().weapons;
+        Self::view(slf, rows.as_slice(), rows.shape())
+    }
+
+    /// Contact course and speed estimates as 2D numpy array, shape
+    /// (max_contacts, 5).
+    ///
+    /// Each row contains: [estimated, course, speed, course_sigma,
+    /// speed_sigma] for the contact in the same slot of `contacts`, fitted
+    /// to its track's recent position fixes. `estimated` is 1.0 when the
+    /// track has enough history for an estimate; rows without one, and
+    /// unused slots, are zero. Course is in radians in the world frame.
+    fn contact_kinematics<'py>(
+        slf: &Bound<'py, Self>,
+    ) -> PyResult<Bound<'py, numpy::PyArray2<f32>>> {
+        let rows = &slf.get().contact_kinematics;
+        Self::view(slf, rows.as_slice(), rows.shape())
+    }
+
+    /// Own fuel, stores, status and weapon cooldowns as 1D numpy array,
+    /// shape (15 + num_weapons,).
+    ///
+    /// Contains, in order:
+    /// - fuel fraction (0.0 to 1.0)
+    /// - rounds held per ammunition type: bullet, missile, torpedo, shell,
+    ///   depth_charge, countermeasure, smoke
+    /// - status flags as 0.0/1.0: mobility_disabled, weapons_disabled,
+    ///   sensors_disabled, destroyed, on_fire, flooding, surrendered
+    /// - cooldown fraction per weapon, in slot order (1.0 just after
+    ///   firing, 0.0 when the cooldown has run out)
+    ///
+    /// The first 15 values are always present; the length is 15 plus the
+    /// number of weapons.
+    fn own_systems<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let values = &slf.get().own_systems;
+        Self::view(slf, values, values.len())
+    }
+
+    /// Scenario objectives set with `PySimulation.set_objectives` as 1D
+    /// numpy array, shape (7,), or (0,) unless requested with
+    /// `get_observation(..., objectives=True)`.
+    ///
+    /// Contains `[zone_bearing, zone_distance, zone_control, convoy_rel_x,
+    /// convoy_rel_y, convoy_present, mission_fraction]`:
+    /// - world bearing (radians) and distance to the center of the nearest
+    ///   zone, and its control as seen by the entity's team from the live
+    ///   ships and squadrons inside: 0 (empty), 1 (friendly), 2 (hostile)
+    ///   or 3 (contested)
+    /// - the convoy's mean position relative to the entity, and 1.0 while
+    ///   any of its ships is afloat
+    /// - the fraction of the mission time elapsed, capped at 1.0
+    ///
+    /// Values of objectives that are not set are zero.
+    fn objectives<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let values = &slf.get().objectives;
+        Self::view(slf, values, values.len())
+    }
+
+    /// Received intents as 2D numpy array, shape
+    /// (max_intents, 3 + max_intent_len).
+    ///
+    /// Each row contains: [rel_x, rel_y, age, payload...]
+    /// Unused slots are zero-padded.
+    fn intents<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, numpy::PyArray2<f32>>> {
+        let rows = &slf.get().intents;
+        Self::view(slf, rows.as_slice(), rows.shape())
+    }
+
+    /// Number of intent slots.
+    #[getter]
+    fn max_intents(&self) -> usize {
+        self.intents.len()
+    }
+
+    /// Number of stacked frames (1 when not stacking).
+    #[getter]
+    fn frames(&self) -> usize {
+        self.history.len().max(1)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Observation(own_state_dim={}, max_contacts={}, max_intents={}, weapons={}, frames={})",
+            self.own_state.len(),
+            self.contacts.len(),
+            self.intents.len(),
+            self.weapons.len(),
+            self.frames()
+        )
+    }
+
+    /// Own state over the stacked frames, shape (frames, 7), oldest first.
+    fn stacked_own_state<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, numpy::PyArray2<f32>>> {
+        let values = self.stack(&self.own_state, |f| &f.own_state);
+        values.to_pyarray(py).reshape([self.frames(), self.own_state.len()])
+    }
+
+    /// Contacts over the stacked frames, shape (frames, max_contacts, 5),
+    /// or 7 columns with relative velocity, oldest first.
+    fn stacked_contacts<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, numpy::PyArray3<f32>>> {
+        let values = self.stack(self.contacts.as_slice(), |f| f.contacts.as_slice());
+        let [len, width] = self.contacts.shape();
+        values.to_pyarray(py).reshape([self.frames(), len, width])
+    }
+
+    /// Contact tags over the stacked frames, shape (frames, max_contacts),
+    /// oldest first.
+    fn stacked_contact_tags<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, numpy::PyArray2<i32>>> {
+        let values = self.stack(&self.contact_tags, |f| &f.contact_tags);
+        values.to_pyarray(py).reshape([self.frames(), self.contact_tags.len()])
+    }
+
+    /// Distances to the world edges over the stacked frames, shape
+    /// (frames, 4), oldest first.
+    fn stacked_bound_distances<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, numpy::PyArray2<f32>>> {
+        let values = self.stack(&self.bounds, |f| &f.bounds);
+        values.to_pyarray(py).reshape([self.frames(), self.bounds.len()])
+    }
+
+    /// Weapon state over the stacked frames, shape (frames, num_weapons, 5),
+    /// oldest first.
+    fn stacked_weapons<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, numpy::PyArray3<f32>>> {
+        let values = self.stack(self.weapons.as_slice(), |f| f.weapons.as_slice());
+        values.to_pyarray(py).reshape([self.frames(), self.weapons.len(), 5])
+    }
+
+    /// Received intents over the stacked frames, shape
+    /// (frames, max_intents, 3 + max_intent_len), oldest first.
+    fn stacked_intents<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, numpy::PyArray3<f32>>> {
+        let values = self.stack(self.intents.as_slice(), |f| f.intents.as_slice());
+        let [len, width] = self.intents.shape();
+        values.to_pyarray(py).reshape([self.frames(), len, width])
+    }
+
+    /// Quantize and bit-pack the whole observation within `bit_budget` bits.
+    ///
+    /// Each feature is quantized over its own range: the packed columns are
+    /// each own_state value, then each contact column, then each intent
+    /// column. Decode with `PyObservationCodec.decode_columns`.
+    fn encode<'py>(&self, py: Python<'py>, bit_budget: usize) -> PyResult<Bound<'py, PyBytes>> {
+        let mut columns: Vec<Vec<f32>> = self.own_state.iter().map(|v| vec![*v]).collect();
+        for rows in [&self.contacts, &self.intents] {
+            let [len, width] = rows.shape();
+            let values = rows.as_slice();
+            columns.extend((0..width).map(|c| (0..len).map(|r| values[r * width + c]).collect()));
+        }
+        let columns: Vec<&[f32]> = columns.iter().map(Vec::as_slice).collect();
+        let packed = ObservationCodec::new(bit_budget)
+            .encode_columns(&columns)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &packed))
+    }
+}
+
+/// Layout and element type of the flat observations written by
+/// `PySimulation.obs_into`.
+///
+/// `dtype` is "float32", "float16" or "int8". int8 values are stored as
+/// `round(value / scale)`, saturating at ±127; pick `scale` so the
+/// largest expected magnitude fits. `objectives` appends the objectives
+/// block (see `PyObservation.objectives`).
+#[pyclass(frozen)]
+pub struct PyObservationSpec {
+    max_contacts: usize,
+    max_intents: usize,
+    dtype: ObservationDtype,
+    objectives: bool,
+}
+
+impl PyObservationSpec {
+    /// Returns `(max_contacts, max_intents, dtype)` from `spec`, or the
+    /// given counts at float32 without one.
+    fn resolve(
+        spec: Option<&Self>,
+        max_contacts: usize,
+        max_intents: usize,
+    ) -> (usize, usize, ObservationDtype) {
+        spec.map_or((max_contacts, max_intents, ObservationDtype::F32), |spec| {
+            (spec.max_contacts, spec.max_intents, spec.dtype)
+        })
+    }
+}
 
-    /// Number of entities in the arena.
-    #[getter]
-    fn entity_count(&self) -> usize {
-        self.inner.arena().entity_count()
+#[pymethods]
+impl PyObservationSpec {
+    #[new]
+    #[pyo3(signature = (
+        max_contacts=16, max_intents=0, dtype="float32", scale=1.0, objectives=false
+    ))]
+    fn new(
+        max_contacts: usize,
+        max_intents: usize,
+        dtype: &str,
+        scale: f32,
+        objectives: bool,
+    ) -> PyResult<Self> {
+        let dtype = match dtype.to_lowercase().as_str() {
+            "float32" | "f32" => ObservationDtype::F32,
+            "float16" | "f16" => ObservationDtype::F16,
+            "int8" | "i8" => {
+                if !(scale.is_finite() && scale > 0.0) {
+                    return Err(pyo3::exceptions::PyValueError::new_err(
+                        "scale must be positive",
+                    ));
+                }
+                ObservationDtype::Int8 { scale }
+            }
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown dtype '{other}', expected 'float32', 'float16' or 'int8'"
+                )))
+            }
+        };
+        Ok(Self {
+            max_contacts,
+            max_intents,
+            dtype,
+            objectives,
+        })
     }
 
-    /// Execute one simulation step.
-    ///
-    /// Releases the GIL during execution for better Python threading.
-    fn step(&mut self, py: Python) {
-        py.allow_threads(|| {
-            self.inner.step();
-        });
+    /// Number of contact slots.
+    #[getter]
+    fn max_contacts(&self) -> usize {
+        self.max_contacts
     }
 
-    /// Spawn a ship at the given position.
-    #[pyo3(signature = (x, y, heading=0.0))]
-    fn spawn_ship(&mut self, x: f32, y: f32, heading: f32) -> PyEntityId {
-        let components = ShipComponents::at_position(Vec2::new(x, y), heading);
-        let id = self
-            .inner
-            .arena_mut()
-            .spawn(EntityTag::Ship, EntityInner::Ship(components));
-        id.into()
+    /// Number of intent slots.
+    #[getter]
+    fn max_intents(&self) -> usize {
+        self.max_intents
     }
 
-    /// Get entity by ID.
-    fn get_entity(&self, id: PyEntityId) -> Option<PyEntity> {
-        self.inner.arena().get(id.into()).map(PyEntity::from_entity)
+    /// Element type name, usable as a numpy dtype.
+    #[getter]
+    fn dtype(&self) -> &'static str {
+        match self.dtype {
+            ObservationDtype::F32 => "float32",
+            ObservationDtype::F16 => "float16",
+            ObservationDtype::Int8 { .. } => "int8",
+        }
     }
 
-    /// Get all entity IDs.
-    fn entity_ids(&self) -> Vec<PyEntityId> {
-        self.inner
-            .arena()
-            .entity_ids_sorted()
-            .map(|id| id.into())
-            .collect()
+    /// Whether the objectives block is appended.
+    #[getter]
+    fn objectives(&self) -> bool {
+        self.objectives
     }
 
-    /// Query entities within radius.
-    fn query_radius(&self, x: f32, y: f32, radius: f32) -> Vec<PyEntityId> {
-        self.inner
-            .arena()
-            .spatial()
-            .query_radius(Vec2::new(x, y), radius)
-            .into_iter()
-            .map(|id| id.into())
-            .collect()
+    /// Quantization step for int8, None otherwise.
+    #[getter]
+    fn scale(&self) -> Option<f32> {
+        match self.dtype {
+            ObservationDtype::Int8 { scale } => Some(scale),
+            _ => None,
+        }
     }
 
-    /// Despawn an entity.
-    fn despawn(&mut self, id: PyEntityId) -> bool {
-        self.inner.arena_mut().despawn(id.into()).is_some()
+    fn __repr__(&self) -> String {
+        let scale = self.scale().map_or(String::new(), |scale| format!(", scale={scale}"));
+        let objectives = if self.objectives { ", objectives=True" } else { "" };
+        format!(
+            "ObservationSpec(max_contacts={}, max_intents={}, dtype=\"{}\"{scale}{objectives})",
+            self.max_contacts,
+            self.max_intents,
+            self.dtype()
+        )
     }
+}
 
-    /// Reset simulation with optional new seed.
-    #[pyo3(signature = (seed=None))]
-    fn reset(&mut self, seed: Option<u64>) {
-        let s = seed.unwrap_or(self.inner.seed());
-        self.inner = Simulation::new(s);
+/// Borrows `out` as a writable numpy array of `T`.
+fn writable_buffer<'py, T: numpy::Element>(
+    out: &Bound<'py, PyAny>,
+    dtype: &str,
+) -> PyResult<numpy::PyReadwriteArrayDyn<'py, T>> {
+    out.downcast::<numpy::PyArrayDyn<T>>()
+        .map_err(|_| {
+            pyo3::exceptions::PyValueError::new_err(format!("out must be a {dtype} array"))
+        })?
+        .try_readwrite()
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("out is not writable: {e}")))
+}
+
+/// Returns the contiguous values of `buffer`, which must number `expected`.
+fn buffer_slice<'a, T: numpy::Element>(
+    buffer: &'a mut numpy::PyReadwriteArrayDyn<'_, T>,
+    expected: usize,
+) -> PyResult<&'a mut [T]> {
+    let slice = buffer
+        .as_slice_mut()
+        .map_err(|_| pyo3::exceptions::PyValueError::new_err("out must be contiguous"))?;
+    if slice.len() != expected {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "out has {} values, expected {expected}",
+            slice.len()
+        )));
     }
+    Ok(slice)
+}
 
-    /// Apply an action dict to an entity.
-    ///
-    /// Action dict can contain:
-    /// - "velocity": (vx, vy) tuple
-    /// - "heading": float in radians
-    fn apply_action(
-        &mut self,
-        entity_id: PyEntityId,
-        action: &Bound<'_, pyo3::types::PyDict>,
-    ) -> PyResult<()> {
-        let id: EntityId = entity_id.into();
+/// Quantizing bit-packer for bandwidth-limited observations.
+///
+/// Each column of values is quantized uniformly between its own minimum
+/// and maximum, at the largest bits-per-value (up to 16) that keeps the
+/// message within the bit budget.
+#[pyclass(frozen)]
+pub struct PyObservationCodec {
+    inner: ObservationCodec,
+}
 
-        // Parse velocity
-        let velocity: Option<(f32, f32)> = action
-            .get_item("velocity")?
-            .map(|v| v.extract())
-            .transpose()?;
+#[pymethods]
+impl PyObservationCodec {
+    #[new]
+    fn new(bit_budget: usize) -> Self {
+        Self {
+            inner: ObservationCodec::new(bit_budget),
+        }
+    }
 
-        // Parse heading
-        let heading: Option<f32> = action
-            .get_item("heading")?
-            .map(|h| h.extract())
-            .transpose()?;
+    /// Configured bit budget.
+    #[getter]
+    fn bit_budget(&self) -> usize {
+        self.inner.bit_budget()
+    }
 
-        if let Some(entity) = self.inner.arena_mut().get_mut(id) {
-            if let EntityInner::Ship(c) = entity.inner_mut() {
-                if let Some((vx, vy)) = velocity {
-                    let vel = Vec2::new(vx, vy);
-                    // Clamp to max speed
-                    let clamped = if vel.length() > c.physics.max_speed {
-                        vel.normalize() * c.physics.max_speed
-                    } else {
-                        vel
-                    };
-                    c.physics.velocity = clamped;
-                }
+    /// Bits per value used for a message with `count` values in `columns`
+    /// columns.
+    #[pyo3(signature = (count, columns=1))]
+    fn bits_per_value(&self, count: usize, columns: usize) -> PyResult<u8> {
+        self.inner
+            .bits_per_value(columns, count)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
 
-                if let Some(h) = heading {
-                    c.transform.heading = h;
-                }
-            }
-        }
+    /// Encode a 1D float array into packed bytes, as a single column.
+    fn encode<'py>(&self, py: Python<'py>, values: Vec<f32>) -> PyResult<Bound<'py, PyBytes>> {
+        let packed = self
+            .inner
+            .encode(&values)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &packed))
+    }
 
-        // Update spatial index after position changes
-        self.inner.arena_mut().update_spatial(id);
+    /// Encode a list of float columns into packed bytes, each quantized over
+    /// its own range. Put the values of one feature in one column.
+    fn encode_columns<'py>(
+        &self,
+        py: Python<'py>,
+        columns: Vec<Vec<f32>>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let columns: Vec<&[f32]> = columns.iter().map(Vec::as_slice).collect();
+        let packed = self
+            .inner
+            .encode_columns(&columns)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &packed))
+    }
 
-        Ok(())
+    /// Decode packed bytes into a 1D float32 array, shape (n,) for the `n`
+    /// values encoded, with the columns one after another.
+    #[staticmethod]
+    fn decode<'py>(py: Python<'py>, packed: &[u8]) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let values = ObservationCodec::decode(packed)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(values.to_pyarray(py))
     }
 
-    /// Get observation for an entity.
-    #[pyo3(signature = (entity_id, max_contacts=16))]
-    fn get_observation(&self, entity_id: PyEntityId, max_contacts: usize) -> Option<PyObservation> {
-        PyObservation::for_entity(self.inner.arena(), entity_id.into(), max_contacts)
+    /// Decode packed bytes into a list of 1D float32 arrays, one per column.
+    #[staticmethod]
+    fn decode_columns<'py>(
+        py: Python<'py>,
+        packed: &[u8],
+    ) -> PyResult<Vec<Bound<'py, PyArray1<f32>>>> {
+        let columns = ObservationCodec::decode_columns(packed)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Ok(columns.iter().map(|column| column.to_pyarray(py)).collect())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ObservationCodec(bit_budget={})", self.inner.bit_budget())
     }
 }
 
-/// Observation for a single agent (ship).
+/// Declared jitter for domain randomization of battle packages.
 ///
-/// Pre-vectorized observation suitable for DRL training. Contains:
-/// - `own_state`: Position, heading, velocity, and health as a 1D array
-/// - `contacts`: Detected contacts from the sensor track table as a 2D array
-#[pyclass]
-pub struct PyObservation {
-    /// Own state: [x, y, heading, vx, vy, hp, max_hp]
-    own_state: Vec<f32>,
-    /// Contacts: [[x, y, rel_heading, distance, quality], ...]
-    contacts: Vec<Vec<f32>>,
+/// Each argument is a distribution: a number (constant), `("uniform", min,
+/// max)` or `("normal", mean, std_dev)`. `position`, `heading`,
+/// `sea_state` and `hour` are offsets added to the authored values;
+/// `ammunition` and `sensor_range` scale them. Omitted arguments leave the
+/// property unchanged.
+///
+/// ```python
+/// randomizer = tidebreak.ScenarioRandomizer(position=("uniform", -500, 500))
+/// variant = randomizer.randomize(package_json, seed=3)
+/// sim, ids = tidebreak.Simulation.from_package(variant)
+/// ```
+#[pyclass(frozen)]
+pub struct PyScenarioRandomizer {
+    inner: ScenarioRandomizer,
 }
 
-impl PyObservation {
-    /// Build observation for a specific entity.
-    pub fn for_entity(
-        arena: &tidebreak_core::arena::Arena,
-        entity_id: EntityId,
-        max_contacts: usize,
-    ) -> Option<Self> {
-        let entity = arena.get(entity_id)?;
+#[pymethods]
+impl PyScenarioRandomizer {
+    #[new]
+    #[pyo3(signature = (
+        position=None,
+        heading=None,
+        ammunition=None,
+        sensor_range=None,
+        sea_state=None,
+        hour=None
+    ))]
+    fn new(
+        position: Option<&Bound<'_, PyAny>>,
+        heading: Option<&Bound<'_, PyAny>>,
+        ammunition: Option<&Bound<'_, PyAny>>,
+        sensor_range: Option<&Bound<'_, PyAny>>,
+        sea_state: Option<&Bound<'_, PyAny>>,
+        hour: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let defaults = ScenarioRandomizer::default();
+        let pick = |value: Option<&Bound<'_, PyAny>>, default| {
+            value.map_or(Ok(default), distribution_from_py)
+        };
+        Ok(Self {
+            inner: ScenarioRandomizer {
+                position: pick(position, defaults.position)?,
+                heading: pick(heading, defaults.heading)?,
+                ammunition: pick(ammunition, defaults.ammunition)?,
+                sensor_range: pick(sensor_range, defaults.sensor_range)?,
+                sea_state: pick(sea_state, defaults.sea_state)?,
+                hour: pick(hour, defaults.hour)?,
+            },
+        })
+    }
 
-        // Build own state vector
-        let own_state = Self::build_own_state(entity);
+    /// Draw the variant of a battle package (JSON) for `seed`, returned as
+    /// JSON. The same seed always yields the same variant.
+    fn randomize(&self, package: &str, seed: u64) -> PyResult<String> {
+        let package: BattlePackage = serde_json::from_str(package)
+            .map_err(|e| InvalidValue::new_err(e.to_string()))?;
+        let variant = self.inner.randomize(&package, seed);
+        serde_json::to_string(&variant)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
 
-        // Build contacts from sensor track table
-        let contacts = Self::build_contacts(entity, max_contacts);
+    fn __repr__(&self) -> String {
+        format!("ScenarioRandomizer({:?})", self.inner)
+    }
+}
 
-        Some(Self {
-            own_state,
-            contacts,
-        })
+/// Parse a distribution: a number, `("uniform", min, max)` or
+/// `("normal", mean, std_dev)`.
+fn distribution_from_py(value: &Bound<'_, PyAny>) -> PyResult<Distribution> {
+    if let Ok(constant) = value.extract::<f32>() {
+        return Ok(Distribution::Constant(constant));
     }
+    let (kind, a, b) = value.extract::<(String, f32, f32)>().map_err(|_| {
+        InvalidValue::new_err("distribution must be a number or a (kind, a, b) tuple")
+    })?;
+    match kind.to_lowercase().as_str() {
+        "uniform" => Ok(Distribution::Uniform { min: a, max: b }),
+        "normal" => Ok(Distribution::Normal {
+            mean: a,
+            std_dev: b,
+        }),
+        other => Err(InvalidValue::new_err(format!(
+            "unknown distribution '{other}', expected 'uniform' or 'normal'"
+        ))),
+    }
+}
 
-    fn build_own_state(entity: &Entity) -> Vec<f32> {
-        match entity.inner() {
-            EntityInner::Ship(c) => vec![
-                c.transform.position.x,
-                c.transform.position.y,
-                c.transform.heading,
-                c.physics.velocity.x,
-                c.physics.velocity.y,
-                c.combat.hp,
-                c.combat.max_hp,
-            ],
-            EntityInner::Squadron(c) => vec![
-                c.transform.position.x,
-                c.transform.position.y,
-                c.transform.heading,
-                c.physics.velocity.x,
-                c.physics.velocity.y,
-                c.combat.hp,
-                c.combat.max_hp,
-            ],
-            _ => vec![0.0; 7], // Platforms/projectiles shouldn't be agents
+/// Pickled `PySimulation` state (`A` is `&Arena` when saving).
+#[derive(Serialize, Deserialize)]
+struct SimulationState<A> {
+    arena: A,
+    sort_key: ContactSortKey,
+    #[serde(default)]
+    action_interval: u32,
+    #[serde(default)]
+    config: TidebreakConfig,
+    #[serde(default)]
+    held_commands: Vec<OutputEnvelope>,
+    /// Rules and scores so far, if scoring is enabled
+    #[serde(default)]
+    scoring: Option<(ScoringRules, ScoreState)>,
+    #[serde(default)]
+    heatmap: Option<Heatmap>,
+    #[serde(default)]
+    weapon_assignment: Option<AssignmentConfig>,
+    #[serde(default)]
+    classification: Option<ClassificationModel>,
+    /// Kill ledger tallies (a list, as JSON keys must be strings)
+    #[serde(default)]
+    kill_ledger: Vec<(ControllerId, ControllerTally)>,
+    #[serde(default)]
+    plugin_budget: Option<PluginBudget>,
+    #[serde(default)]
+    rng_audit_capacity: usize,
+    #[serde(default)]
+    event_history_capacity: usize,
+    #[serde(default)]
+    proximity: Vec<ProximityTrigger>,
+    /// Arrival radius and engage range, if order following is enabled
+    #[serde(default)]
+    order_follower: Option<(f32, f32)>,
+    #[serde(default)]
+    sensors: bool,
+    #[serde(default)]
+    convoys: Vec<ConvoyRoute>,
+    #[serde(default)]
+    objectives: Objectives,
+    #[serde(default)]
+    league: Option<(League, TeamId)>,
+    /// Name of the episode's league opponent
+    #[serde(default)]
+    opponent: Option<String>,
+}
+
+/// A proximity trigger as added by `add_proximity_trigger()`.
+#[derive(Serialize, Deserialize)]
+struct ProximityTrigger {
+    /// Plugin ID, under which the targets in range are kept
+    id: String,
+    observers: Vec<EntityTag>,
+    radii: Vec<f32>,
+    /// Target tags; empty means all
+    targets: Vec<EntityTag>,
+    team: TeamFilter,
+}
+
+impl ProximityTrigger {
+    /// The trigger `plugin` watching from `observers`.
+    fn of(observers: &[EntityTag], plugin: &ProximityPlugin) -> Self {
+        Self {
+            id: plugin.declaration().id.as_str().to_string(),
+            observers: observers.to_vec(),
+            radii: plugin.radii().to_vec(),
+            targets: plugin.target_tags().to_vec(),
+            team: plugin.team_filter(),
         }
     }
 
-    fn build_contacts(entity: &Entity, max_contacts: usize) -> Vec<Vec<f32>> {
-        let mut contacts = Vec::with_capacity(max_contacts);
+    fn plugin(&self) -> ProximityPlugin {
+        ProximityPlugin::new(0.0)
+            .with_id(PluginId::new(&self.id))
+            .with_radii(self.radii.iter().copied())
+            .with_target_tags(self.targets.iter().copied())
+            .with_team_filter(self.team)
+    }
+}
 
-        // Get own position for relative calculations
-        let own_pos = match entity.inner() {
-            EntityInner::Ship(c) => c.transform.position,
-            EntityInner::Squadron(c) => c.transform.position,
-            _ => return Self::pad_contacts(contacts, max_contacts),
-        };
+/// A convoy as enabled by `enable_convoy()`.
+#[derive(Serialize, Deserialize)]
+struct ConvoyRoute {
+    route: Vec<Vec2>,
+    label: String,
+    arrival_radius: f32,
+    cruise_throttle: f32,
+    scatter_ticks: u64,
+}
 
-        // Get track table if entity has sensors
-        let tracks = match entity.inner() {
-            EntityInner::Ship(c) => &c.sensor.track_table,
-            _ => return Self::pad_contacts(contacts, max_contacts),
-        };
+impl ConvoyRoute {
+    fn of(plugin: &ConvoyPlugin) -> Self {
+        Self {
+            route: plugin.route().to_vec(),
+            label: plugin.label().to_string(),
+            arrival_radius: plugin.arrival_radius(),
+            cruise_throttle: plugin.cruise_throttle(),
+            scatter_ticks: plugin.scatter_ticks(),
+        }
+    }
 
-        for track in tracks.iter().take(max_contacts) {
-            let rel = track.position - own_pos;
-            let distance = rel.length();
-            let rel_heading = rel.y.atan2(rel.x);
-            let quality = track.quality as i32 as f32;
+    fn plugin(&self) -> ConvoyPlugin {
+        ConvoyPlugin::new(self.route.clone())
+            .with_label(&self.label)
+            .with_arrival_radius(self.arrival_radius)
+            .with_cruise_throttle(self.cruise_throttle)
+            .with_scatter_ticks(self.scatter_ticks)
+    }
+}
 
-            contacts.push(vec![
-                track.position.x,
-                track.position.y,
-                rel_heading,
-                distance,
-                quality,
-            ]);
-        }
+/// Serialize pickle state.
+fn to_state_bytes<T: Serialize>(value: &T) -> PyResult<Vec<u8>> {
+    serde_json::to_vec(value).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+/// Deserialize pickle state.
+fn from_state_bytes<T: for<'de> Deserialize<'de>>(state: &[u8]) -> PyResult<T> {
+    serde_json::from_slice(state)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
 
-        Self::pad_contacts(contacts, max_contacts)
+/// Parse a controller written as "human:N", "scripted:N" or "agent:N".
+fn str_to_controller(s: &str) -> PyResult<ControllerId> {
+    let malformed = || {
+        InvalidValue::new_err(format!(
+            "malformed controller '{s}', expected 'human:N', 'scripted:N' or 'agent:N'"
+        ))
+    };
+    let (kind, id) = s.split_once(':').ok_or_else(malformed)?;
+    let id = id.trim().parse().map_err(|_| malformed())?;
+    match kind.trim().to_lowercase().as_str() {
+        "human" => Ok(ControllerId::Human(id)),
+        "scripted" => Ok(ControllerId::Scripted(id)),
+        "agent" => Ok(ControllerId::Agent(id)),
+        _ => Err(malformed()),
     }
+}
 
-    fn pad_contacts(mut contacts: Vec<Vec<f32>>, max_contacts: usize) -> Vec<Vec<f32>> {
-        while contacts.len() < max_contacts {
-            contacts.push(vec![0.0; 5]);
-        }
-        contacts
+/// Parse a contact sort key: "distance", "threat" or "quality".
+fn str_to_sort_key(s: &str) -> PyResult<ContactSortKey> {
+    match s.to_lowercase().as_str() {
+        "distance" => Ok(ContactSortKey::Distance),
+        "threat" => Ok(ContactSortKey::Threat),
+        "quality" => Ok(ContactSortKey::Quality),
+        "track" => Ok(ContactSortKey::TrackTable),
+        other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "unknown contact sort key '{other}', expected 'distance', 'threat', 'quality' or \
+             'track'"
+        ))),
     }
 }
 
-#[pymethods]
-impl PyObservation {
-    /// Own state as numpy array.
-    ///
-    /// Returns a 1D array with shape (7,) containing:
-    /// [x, y, heading, vx, vy, hp, max_hp]
-    fn own_state<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f32>> {
-        self.own_state.to_pyarray(py)
+/// Parse a track quality: "cue", "coarse", "fire_control" or "shared".
+fn str_to_quality(s: &str) -> PyResult<TrackQuality> {
+    match s.to_lowercase().as_str() {
+        "cue" => Ok(TrackQuality::Cue),
+        "coarse" => Ok(TrackQuality::Coarse),
+        "fire_control" | "firecontrol" => Ok(TrackQuality::FireControl),
+        "shared" => Ok(TrackQuality::Shared),
+        other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "unknown track quality '{other}', expected 'cue', 'coarse', 'fire_control' or 'shared'"
+        ))),
     }
+}
 
-    /// Contacts as 2D numpy array (max_contacts x 5).
-    ///
-    /// Each row contains: [x, y, rel_heading, distance, quality]
-    /// Unused slots are zero-padded.
-    fn contacts<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, numpy::PyArray2<f32>>> {
-        numpy::PyArray2::from_vec2(py, &self.contacts)
-            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("{e}")))
+/// Convert an ammunition type name to `AmmoType`.
+fn str_to_ammo(s: &str) -> PyResult<AmmoType> {
+    match s.to_lowercase().as_str() {
+        "bullet" => Ok(AmmoType::Bullet),
+        "missile" => Ok(AmmoType::Missile),
+        "torpedo" => Ok(AmmoType::Torpedo),
+        "shell" => Ok(AmmoType::Shell),
+        "depth_charge" | "depthcharge" => Ok(AmmoType::DepthCharge),
+        "countermeasure" => Ok(AmmoType::Countermeasure),
+        "smoke" => Ok(AmmoType::Smoke),
+        other => Err(InvalidValue::new_err(format!(
+            "unknown ammo type '{other}'"
+        ))),
     }
+}
 
-    /// Feature dimension for own_state.
-    #[getter]
-    fn own_state_dim(&self) -> usize {
-        self.own_state.len()
+/// Convert a rules of engagement name to `RulesOfEngagement`.
+fn str_to_roe(s: &str) -> PyResult<RulesOfEngagement> {
+    match s.to_lowercase().as_str() {
+        "weapons_free" => Ok(RulesOfEngagement::WeaponsFree),
+        "hold_fire" => Ok(RulesOfEngagement::HoldFire),
+        other => Err(InvalidValue::new_err(format!(
+            "unknown rules of engagement '{other}'"
+        ))),
     }
+}
 
-    /// Number of contact slots.
-    #[getter]
-    fn max_contacts(&self) -> usize {
-        self.contacts.len()
+/// Python name of a `RulesOfEngagement`.
+const fn roe_to_str(roe: RulesOfEngagement) -> &'static str {
+    match roe {
+        RulesOfEngagement::WeaponsFree => "weapons_free",
+        RulesOfEngagement::HoldFire => "hold_fire",
+    }
+}
+
+/// Python name of an `AmmoType`.
+const fn ammo_to_str(ammo_type: AmmoType) -> &'static str {
+    match ammo_type {
+        AmmoType::Bullet => "bullet",
+        AmmoType::Missile => "missile",
+        AmmoType::Torpedo => "torpedo",
+        AmmoType::Shell => "shell",
+        AmmoType::DepthCharge => "depth_charge",
+        AmmoType::Countermeasure => "countermeasure",
+        AmmoType::Smoke => "smoke",
+    }
+}
+
+/// Build one of the stock stamps by name.
+fn stamp_from_kind(kind: &str, center: (f32, f32, f32), radius: f32, intensity: f32) -> PyResult<murk::Stamp> {
+    let center = glam::Vec3::new(center.0, center.1, center.2);
+    match kind {
+        "explosion" => Ok(murk::Stamp::explosion(center, radius, intensity)),
+        "fire" => Ok(murk::Stamp::fire(center, radius, intensity)),
+        "sonar_ping" => Ok(murk::Stamp::sonar_ping(center, radius, intensity)),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!("unknown stamp kind: {kind}"))),
+    }
+}
+
+/// Builds the nested dict returned by `Universe.debug_tree`.
+fn node_summary_dict<'py>(
+    py: Python<'py>,
+    node: &murk::NodeSummary,
+) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+    let dict = pyo3::types::PyDict::new(py);
+    dict.set_item("min", node.bounds.min.to_array())?;
+    dict.set_item("max", node.bounds.max.to_array())?;
+    dict.set_item("depth", node.depth)?;
+    let state = match node.kind {
+        murk::NodeKind::Empty => "empty",
+        murk::NodeKind::Leaf => "leaf",
+        murk::NodeKind::Internal => "internal",
+    };
+    dict.set_item("state", state)?;
+    dict.set_item("uniform", node.uniform)?;
+    dict.set_item("subtree_nodes", node.subtree_nodes)?;
+    let stats = match &node.stats {
+        Some(stats) => {
+            let fields = pyo3::types::PyDict::new(py);
+            for &field in murk::Field::all() {
+                let s = stats.get(field);
+                let summary = pyo3::types::PyDict::new(py);
+                summary.set_item("mean", s.mean)?;
+                summary.set_item("variance", s.variance)?;
+                summary.set_item("min", s.min)?;
+                summary.set_item("max", s.max)?;
+                fields.set_item(field_to_str(field), summary)?;
+            }
+            Some(fields)
+        }
+        None => None,
+    };
+    dict.set_item("stats", stats)?;
+    let children = node
+        .children
+        .iter()
+        .map(|child| node_summary_dict(py, child))
+        .collect::<PyResult<Vec<_>>>()?;
+    dict.set_item("children", children)?;
+    Ok(dict)
+}
+
+/// Convert Field enum to its Python name.
+const fn field_to_str(field: murk::Field) -> &'static str {
+    match field {
+        murk::Field::Occupancy => "occupancy",
+        murk::Field::Material => "material",
+        murk::Field::Integrity => "integrity",
+        murk::Field::Temperature => "temperature",
+        murk::Field::Smoke => "smoke",
+        murk::Field::Noise => "noise",
+        murk::Field::Signal => "signal",
+        murk::Field::CurrentX => "current_x",
+        murk::Field::CurrentY => "current_y",
+        murk::Field::Depth => "depth",
+        murk::Field::Salinity => "salinity",
+        murk::Field::SonarReturn => "sonar_return",
     }
 }
 
@@ -1054,6 +6560,126 @@ fn str_to_field(s: &str) -> murk::Field {
     }
 }
 
+/// Maps a league error to `OSError` (unreadable file) or `InvalidValue`.
+/// Build the dict form of an event row.
+fn event_row_dict<'py>(
+    py: Python<'py>,
+    row: &EventRow,
+) -> PyResult<Bound<'py, pyo3::types::PyDict>> {
+    let dict = pyo3::types::PyDict::new(py);
+    dict.set_item("tick", row.tick)?;
+    dict.set_item("trace_id", row.trace_id)?;
+    dict.set_item("kind", row.kind)?;
+    dict.set_item("entity", row.entity)?;
+    dict.set_item("other", row.other)?;
+    dict.set_item("value", row.value)?;
+    dict.set_item("weapon_slot", row.weapon_slot)?;
+    dict.set_item("quality", row.quality)?;
+    Ok(dict)
+}
+
+fn replay_error(err: ReplayError) -> PyErr {
+    match err {
+        ReplayError::Io(err) => err.into(),
+        err => InvalidValue::new_err(err.to_string()),
+    }
+}
+
+fn league_error(err: LeagueError) -> PyErr {
+    match err {
+        LeagueError::Io(err) => err.into(),
+        err => InvalidValue::new_err(err.to_string()),
+    }
+}
+
+/// Maps a config error to `OSError` (unreadable file) or `InvalidValue`.
+fn config_error(err: ConfigError) -> PyErr {
+    match err {
+        ConfigError::Io(err) => err.into(),
+        err => InvalidValue::new_err(err.to_string()),
+    }
+}
+
+/// Applies a dict of config overrides, keyed by section or dotted path.
+fn merge_config_dict(
+    config: &mut TidebreakConfig,
+    layer: &Bound<'_, pyo3::types::PyDict>,
+) -> PyResult<()> {
+    for (key, value) in layer.iter() {
+        let key: String = key.extract()?;
+        config.set(&key, config_value(&value)?).map_err(config_error)?;
+    }
+    Ok(())
+}
+
+/// Converts a Python config value to TOML.
+fn config_value(value: &Bound<'_, PyAny>) -> PyResult<toml::Value> {
+    // bool is a subclass of int, so it must be tested first
+    if let Ok(value) = value.downcast::<pyo3::types::PyBool>() {
+        return Ok(toml::Value::Boolean(value.is_true()));
+    }
+    if let Ok(value) = value.downcast::<pyo3::types::PyInt>() {
+        return Ok(toml::Value::Integer(value.extract()?));
+    }
+    if let Ok(value) = value.extract::<f64>() {
+        return Ok(toml::Value::Float(value));
+    }
+    if let Ok(value) = value.extract::<String>() {
+        return Ok(toml::Value::String(value));
+    }
+    if let Ok(list) = value.downcast::<PyList>() {
+        let items = list.iter().map(|item| config_value(&item));
+        return Ok(toml::Value::Array(items.collect::<PyResult<_>>()?));
+    }
+    if let Ok(dict) = value.downcast::<pyo3::types::PyDict>() {
+        let mut table = toml::Table::new();
+        for (key, value) in dict.iter() {
+            table.insert(key.extract()?, config_value(&value)?);
+        }
+        return Ok(toml::Value::Table(table));
+    }
+    Err(InvalidValue::new_err(format!(
+        "unsupported config value {}",
+        value.repr()?
+    )))
+}
+
+/// Describe the components and fields of every entity tag.
+///
+/// Returns `{tag: {component: {"optional": bool, "fields": {name: field}}}}`
+/// keyed by tag name ("Ship", ...) and component name ("physics", ...),
+/// where each field is a dict with keys "dtype" (numpy-style name, or
+/// "enum"), "shape" (tuple, empty for scalars), "optional", "repeated"
+/// (one value per list element or map entry) and "min"/"max" (documented
+/// range, or None). Generated from the Rust component types, so it always
+/// matches the installed build.
+#[pyfunction]
+fn schema(py: Python<'_>) -> PyResult<Bound<'_, pyo3::types::PyDict>> {
+    let tags = pyo3::types::PyDict::new(py);
+    for tag in component_schema() {
+        let components = pyo3::types::PyDict::new(py);
+        for component in tag.components {
+            let fields = pyo3::types::PyDict::new(py);
+            for field in component.fields {
+                let entry = pyo3::types::PyDict::new(py);
+                entry.set_item("dtype", field.dtype.name())?;
+                entry.set_item("shape", pyo3::types::PyTuple::new(py, field.shape)?)?;
+                entry.set_item("optional", field.optional)?;
+                entry.set_item("repeated", field.repeated)?;
+                entry.set_item("min", field.min)?;
+                entry.set_item("max", field.max)?;
+                fields.set_item(field.name, entry)?;
+            }
+            let entry = pyo3::types::PyDict::new(py);
+            entry.set_item("optional", component.optional)?;
+            entry.set_item("fields", fields)?;
+            components.set_item(component.name, entry)?;
+        }
+        tags.set_item(tag.tag.to_string(), components)?;
+    }
+    Ok(tags)
+}
+
 /// Python module definition.
 #[pymodule]
 fn _tidebreak(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -1068,6 +6694,21 @@ fn _tidebreak(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyCombatState>()?;
     m.add_class::<PyEntity>()?;
     m.add_class::<PySimulation>()?;
+    m.add_class::<PyCancellationToken>()?;
+    m.add_class::<PyReplay>()?;
     m.add_class::<PyObservation>()?;
+    m.add_class::<PyObservationCodec>()?;
+    m.add_class::<PyObservationSpec>()?;
+    m.add_class::<PyScenarioRandomizer>()?;
+    m.add_function(wrap_pyfunction!(schema, m)?)?;
+    m.add("CommandError", m.py().get_type::<CommandError>())?;
+    m.add("UnknownEntity", m.py().get_type::<UnknownEntity>())?;
+    m.add("EntityDestroyed", m.py().get_type::<EntityDestroyed>())?;
+    m.add("InvalidValue", m.py().get_type::<InvalidValue>())?;
+    m.add(
+        "NotSupportedForTag",
+        m.py().get_type::<NotSupportedForTag>(),
+    )?;
+    m.add("NotController", m.py().get_type::<NotController>())?;
     Ok(())
 }
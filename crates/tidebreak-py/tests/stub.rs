@@ -0,0 +1,30 @@
+//! Checks that the committed type stub matches the one generated from the
+//! bindings by `build.rs`.
+//!
+//! When the bindings change, regenerate the stub and commit it together
+//! with the change:
+//!
+//! ```text
+//! TIDEBREAK_BLESS=1 cargo test -p tidebreak-py --test stub
+//! ```
+
+use std::path::Path;
+
+const GENERATED: &str = include_str!(concat!(env!("OUT_DIR"), "/_tidebreak.pyi"));
+
+#[test]
+fn committed_stub_is_current() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("python/tidebreak/_tidebreak.pyi");
+    if std::env::var_os("TIDEBREAK_BLESS").is_some() {
+        std::fs::write(&path, GENERATED).unwrap();
+        return;
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+
+    assert!(
+        committed == GENERATED,
+        "{} is stale; regenerate it with \
+         `TIDEBREAK_BLESS=1 cargo test -p tidebreak-py --test stub`",
+        path.display()
+    );
+}
@@ -0,0 +1,38 @@
+"""Tests for contact classification in tidebreak Python bindings."""
+
+import pytest
+
+
+def test_classification_fills_contact_tags():
+    """Contacts are unclassified until classification is enabled."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    observer = sim.spawn_ship(0.0, 0.0, 0.0, team=0)
+    sim.spawn_ship(1000.0, 0.0, 0.0, team=1)
+    sim.step()
+    obs = sim.get_observation(observer, max_contacts=4)
+    assert obs is None or not obs.contact_tags().any()
+
+    sim.enable_classification(misclassification_rate=0.0)
+    sim.step()
+    assert sim.get_observation(observer, max_contacts=4).contact_tags()[0] != 0
+
+    # Classification and sensors survive a reset
+    sim.reset()
+    observer = sim.spawn_ship(0.0, 0.0, 0.0, team=0)
+    sim.spawn_ship(1000.0, 0.0, 0.0, team=1)
+    sim.step()
+    assert sim.get_observation(observer, max_contacts=4).contact_tags()[0] != 0
+    sim.disable_classification()
+
+
+def test_classification_rejects_out_of_range_parameters():
+    """Probabilities and confidences must lie in [0, 1]."""
+    from tidebreak import InvalidValue, PySimulation
+
+    sim = PySimulation(seed=1)
+    with pytest.raises(InvalidValue):
+        sim.enable_classification(threshold=1.5)
+    with pytest.raises(InvalidValue):
+        sim.enable_classification(gain_per_detection=-0.1)
@@ -102,3 +102,49 @@ def test_seeded_reset_preserves_dimensions():
     # We can verify this indirectly by checking that queries still work
     result = universe.query_volume(center=(50.0, 50.0, 25.0), radius=10.0)
     assert result.nodes_visited >= 0  # Query succeeded
+
+
+def test_run_batch_is_deterministic_and_matches_parallel():
+    """Batch episodes depend only on their seed, sequential or in lockstep."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    sim.action_interval = 5
+    ship = sim.spawn_ship(0.0, 0.0, 0.0)
+    sim.spawn_ship(300.0, 0.0, 3.14)
+
+    def policy(episode):
+        episode.apply_action(ship, {"velocity": (5.0, 0.0)})
+        return episode.seed == 3 and episode.tick >= 40
+
+    sequential = sim.run_batch([1, 2, 3], policy, max_ticks=100)
+    parallel = sim.run_batch([1, 2, 3], policy, max_ticks=100, parallel=True)
+
+    assert sequential == parallel
+    assert [r["seed"] for r in sequential] == [1, 2, 3]
+    assert [r["ticks"] for r in sequential] == [100, 100, 40]
+    assert [r["terminated"] for r in sequential] == [False, False, True]
+    assert sequential[0]["decisions"] == 20
+    assert sim.tick == 0
+
+
+def test_ttl_despawns_inside_the_simulation():
+    """Scheduled despawns happen on the expiry tick and are logged as events."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    sim.enable_event_history(64)
+    marker = sim.spawn_ship(0.0, 0.0, 0.0)
+    assert sim.set_ttl(marker, 3)
+    assert sim.expiry(marker) == 3
+
+    sim.step()
+    sim.step()
+    assert sim.entity_count == 1
+    sim.step()
+    assert sim.entity_count == 0
+    assert sim.expiry(marker) is None
+    assert not sim.clear_ttl(marker)
+
+    expired = sim.query_events(kind="entity_expired")
+    assert [(e["tick"], e["entity"]) for e in expired] == [(2, marker.value)]
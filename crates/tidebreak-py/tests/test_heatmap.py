@@ -0,0 +1,37 @@
+"""Tests for the visitation/exposure heatmap in tidebreak Python bindings."""
+
+import numpy as np
+import pytest
+
+
+def test_heatmap_accumulates_visits_per_cell():
+    """Each tick a ship spends in a cell adds one visit there."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    sim.spawn_ship(10.0, 10.0, 0.0)
+    sim.spawn_ship(150.0, 50.0, 0.0)
+    assert sim.heatmap() is None
+
+    sim.enable_heatmap((0.0, 0.0), (200.0, 100.0), 100.0)
+    for _ in range(5):
+        sim.step()
+
+    heatmap = sim.heatmap()
+    assert heatmap["visits"].shape == (1, 2)
+    assert heatmap["visits"].dtype == np.uint32
+    np.testing.assert_array_equal(heatmap["visits"], [[5, 5]])
+    np.testing.assert_array_equal(heatmap["exposure"], [[0.0, 0.0]])
+    assert heatmap["ticks"] == 5
+
+    sim.reset()
+    assert sim.heatmap()["ticks"] == 0
+
+
+def test_heatmap_rejects_bad_cell_size():
+    """A non-positive cell size is an InvalidValue."""
+    from tidebreak import InvalidValue, PySimulation
+
+    sim = PySimulation(seed=1)
+    with pytest.raises(InvalidValue):
+        sim.enable_heatmap((0.0, 0.0), (100.0, 100.0), 0.0)
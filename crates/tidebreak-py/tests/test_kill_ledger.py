@@ -0,0 +1,16 @@
+"""Tests for the per-controller kill ledger in tidebreak Python bindings."""
+
+
+def test_kill_ledger_starts_empty():
+    """No controller is credited before any damage is dealt."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    ship = sim.spawn_ship(0.0, 0.0, 0.0)
+    sim.set_controller(ship, "agent:0")
+    for _ in range(3):
+        sim.step()
+
+    assert sim.kill_ledger() == {}
+    sim.reset()
+    assert sim.kill_ledger() == {}
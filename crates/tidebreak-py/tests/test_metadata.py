@@ -0,0 +1,59 @@
+"""Tests for cosmetic entity metadata in tidebreak Python bindings."""
+
+import json
+
+
+def test_metadata_is_read_and_written_on_entities():
+    """Metadata set on an entity shows up on its view and name."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    ship = sim.spawn_ship(0.0, 0.0, 0.0)
+    entity = sim.get_entity(ship)
+    assert entity.name is None
+    assert entity.color is None
+    assert entity.display_name == f"Ship {ship.value}"
+
+    assert sim.set_metadata(ship, name="Resolute", hull_number="D32", color=(20, 60, 200))
+    entity = sim.get_entity(ship)
+    assert entity.display_name == "Resolute (D32)"
+    assert entity.class_name is None
+    assert entity.color == (20, 60, 200)
+    assert "Resolute" in repr(entity)
+
+    # Replacing clears omitted fields
+    assert sim.set_metadata(ship, class_name="Daring-class destroyer")
+    entity = sim.get_entity(ship)
+    assert entity.name is None
+    assert entity.class_name == "Daring-class destroyer"
+
+    sim.despawn(ship)
+    assert not sim.set_metadata(ship, name="Ghost")
+
+
+def test_package_metadata_defaults_to_the_team_color():
+    """Ships in a package carry their metadata, in team colors by default."""
+    from tidebreak import PySimulation
+
+    package = {
+        "schema_version": "arena.v1",
+        "battle_id": "duel",
+        "seed": 3,
+        "teams": [{"team_id": "blue", "color": [20, 60, 200]}, {"team_id": "red"}],
+        "ships": [
+            {
+                "ship_id": "b1",
+                "team_id": "blue",
+                "initial_state": {"x": 0, "y": 0, "heading": 0},
+                "metadata": {"name": "Resolute", "class_name": "Daring-class destroyer"},
+            },
+            {"ship_id": "r1", "team_id": "red", "initial_state": {"x": 900, "y": 0, "heading": 3}},
+        ],
+    }
+    sim, ids = PySimulation.from_package(json.dumps(package))
+
+    blue = sim.get_entity(ids["b1"])
+    assert blue.name == "Resolute"
+    assert blue.class_name == "Daring-class destroyer"
+    assert blue.color == (20, 60, 200)
+    assert sim.get_entity(ids["r1"]).color is None
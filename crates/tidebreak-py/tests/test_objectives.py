@@ -0,0 +1,60 @@
+"""Tests for the objectives block of observations in tidebreak Python bindings."""
+
+import numpy as np
+import pytest
+
+
+def _scenario():
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    escort = sim.spawn_ship(0.0, 0.0, 0.0, team=0)
+    sim.spawn_ship(1000.0, 0.0, 3.14, team=1)
+    sim.spawn_merchant(0.0, 100.0, team=0)
+    sim.spawn_merchant(0.0, 300.0, team=0)
+    return sim, escort
+
+
+def test_objectives_block_reports_zone_convoy_and_timer():
+    """The nearest zone, the convoy center and the mission clock are observed."""
+    sim, escort = _scenario()
+    sim.set_objectives(zones=[(5000.0, 5000.0, 100.0), (1000.0, 0.0, 200.0)], convoy_label="merchant", mission_ticks=10)
+    sim.step()
+    sim.step()
+
+    assert sim.get_observation(escort).objectives().shape == (0,)
+    objectives = sim.get_observation(escort, objectives=True).objectives()
+    np.testing.assert_allclose(objectives, [0.0, 1000.0, 2.0, 0.0, 200.0, 1.0, 0.2], atol=1e-3)
+
+    # Objectives survive a reset; the clock starts over
+    sim.reset()
+    escort = sim.spawn_ship(0.0, 0.0, 0.0, team=0)
+    objectives = sim.get_observation(escort, objectives=True).objectives()
+    np.testing.assert_allclose(objectives, [0.0, 1000.0, 0.0, 0.0, 0.0, 0.0, 0.0], atol=1e-3)
+
+
+def test_flat_observation_appends_objectives():
+    """An ObservationSpec with objectives appends the block to obs_into rows."""
+    from tidebreak import ObservationSpec
+
+    sim, escort = _scenario()
+    sim.set_objectives(zones=[(1000.0, 0.0, 200.0)], mission_ticks=4)
+    plain = ObservationSpec(max_contacts=4)
+    spec = ObservationSpec(max_contacts=4, objectives=True)
+    assert spec.objectives
+    assert sim.observation_size(spec=spec) == sim.observation_size(spec=plain) + 7
+
+    out = np.zeros(sim.observation_size(spec=spec), dtype=np.float32)
+    assert sim.obs_into(escort, out, spec=spec)
+    expected = sim.get_observation(escort, objectives=True).objectives()
+    np.testing.assert_array_equal(out[-7:], expected)
+
+
+def test_objectives_reject_bad_values():
+    from tidebreak import InvalidValue
+
+    sim, _ = _scenario()
+    with pytest.raises(InvalidValue):
+        sim.set_objectives(zones=[(0.0, 0.0, 0.0)])
+    with pytest.raises(InvalidValue):
+        sim.set_objectives(mission_ticks=0)
@@ -156,3 +156,98 @@ def test_foveated_observation_different_headings():
     # Both observations should have the same shape and type
     assert obs_forward.shape == obs_right.shape
     assert obs_forward.dtype == obs_right.dtype == np.float32
+
+
+def test_simulation_observation_blocks_are_readonly_views():
+    """Observation blocks are read-only views that keep the observation alive."""
+    from tidebreak import PyObservation
+
+    obs = PyObservation(
+        [1.0, 2.0, 0.5, 0.0, 0.0, 80.0, 100.0],
+        [[3.0, 4.0, 0.1, 5.0, 2.0]] * 4,
+        weapons=[[1.0, 0.0, 10.0, 10.0, 0.0]],
+    )
+
+    contacts = obs.contacts()
+    assert contacts.shape == (4, 5)
+    assert contacts.dtype == np.float32
+    assert not contacts.flags["OWNDATA"]
+    assert not contacts.flags["WRITEABLE"]
+    with pytest.raises(ValueError):
+        contacts[0, 0] = 0.0
+    assert obs.weapons().shape == (1, 5)
+
+    own_state = obs.own_state()
+    del obs
+    np.testing.assert_array_equal(own_state, [1.0, 2.0, 0.5, 0.0, 0.0, 80.0, 100.0])
+
+
+def test_simulation_observation_rejects_ragged_rows():
+    """Rows of a block must all have the same length."""
+    from tidebreak import PyObservation
+
+    with pytest.raises(ValueError):
+        PyObservation([0.0] * 7, [[1.0, 2.0], [1.0]])
+
+
+def _engaged_simulation():
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    a = sim.spawn_ship(0.0, 0.0, 0.0, team=0)
+    b = sim.spawn_ship(1000.0, 0.0, 3.14, team=1)
+    sim.step()
+    return sim, a, b
+
+
+def _flat_observation(sim, entity_id, max_contacts, max_intents):
+    obs = sim.get_observation(entity_id, max_contacts=max_contacts, max_intents=max_intents)
+    return np.concatenate(
+        [
+            obs.own_state().ravel(),
+            obs.contacts().ravel(),
+            obs.contact_tags().astype(np.float32),
+            obs.bound_distances().ravel(),
+            obs.intents().ravel(),
+        ]
+    )
+
+
+def test_obs_into_matches_get_observation():
+    """obs_into writes the concatenated blocks of get_observation."""
+    sim, a, _ = _engaged_simulation()
+    out = np.zeros(sim.observation_size(max_contacts=4, max_intents=2), dtype=np.float32)
+
+    assert sim.obs_into(a, out, max_contacts=4, max_intents=2)
+    np.testing.assert_array_equal(out, _flat_observation(sim, a, 4, 2))
+
+
+def test_obs_into_rejects_bad_buffers():
+    """The output buffer must be contiguous and exactly observation_size long."""
+    sim, a, _ = _engaged_simulation()
+    size = sim.observation_size()
+
+    with pytest.raises(ValueError):
+        sim.obs_into(a, np.zeros(size + 1, dtype=np.float32))
+    with pytest.raises(ValueError):
+        sim.obs_into(a, np.zeros(2 * size, dtype=np.float32)[::2])
+
+
+def test_obs_into_zero_fills_missing_entity():
+    """A missing entity returns False and leaves a zeroed buffer."""
+    sim, a, _ = _engaged_simulation()
+    sim.despawn(a)
+    out = np.ones(sim.observation_size(), dtype=np.float32)
+
+    assert not sim.obs_into(a, out)
+    assert not out.any()
+
+
+def test_obs_into_batch_follows_entity_order():
+    """Row i of obs_into_batch is the observation of entity_ids[i]."""
+    sim, a, b = _engaged_simulation()
+    out = np.zeros((2, sim.observation_size()), dtype=np.float32)
+
+    assert sim.obs_into_batch([b, a], out) == [True, True]
+    np.testing.assert_array_equal(out[0], _flat_observation(sim, b, 16, 0))
+    np.testing.assert_array_equal(out[1], _flat_observation(sim, a, 16, 0))
@@ -0,0 +1,79 @@
+"""Tests for coarse surface pathfinding over the universe."""
+
+import json
+import os
+import tempfile
+
+import pytest
+from tidebreak import PyUniverse
+
+ISLAND = {
+    "island": {
+        "shape": {"Box": {"half_extents": [16.0, 80.0, 8.0]}},
+        "modifications": [{"field": "Occupancy", "op": "Set", "value": 1.0}],
+    }
+}
+
+
+def _universe_with_island() -> PyUniverse:
+    universe = PyUniverse(width=256.0, height=256.0, depth=32.0, base_resolution=4.0)
+    with tempfile.TemporaryDirectory() as directory:
+        path = os.path.join(directory, "island.json")
+        with open(path, "w") as f:
+            json.dump(ISLAND, f)
+        universe.load_stamp_templates(path)
+    # The first stamp into an empty world fills it as a single cell; later
+    # stamps refine it, so lay a faint ping far away first
+    universe.stamp_sonar_ping((100.0, 100.0, 0.0), 4.0, 0.1)
+    universe.stamp_template("island", (0.0, -40.0, 0.0))
+    return universe
+
+
+def test_open_water_is_a_straight_leg():
+    universe = PyUniverse(width=256.0, height=256.0, depth=32.0, base_resolution=4.0)
+
+    route = universe.find_path((-100.0, -100.0, 0.0), (100.0, 90.0, 0.0), draft=5.0)
+
+    assert route == [(-100.0, -100.0, 0.0), (100.0, 90.0, 0.0)]
+
+
+def test_route_detours_around_an_island():
+    universe = _universe_with_island()
+
+    route = universe.find_path((-80.0, 0.0, 0.0), (80.0, 0.0, 0.0), resolution=8.0)
+
+    assert route is not None
+    assert route[0] == (-80.0, 0.0, 0.0)
+    assert route[-1] == (80.0, 0.0, 0.0)
+    # The island spans y in [-120, 40]; the route passes north of it
+    assert any(y > 40.0 for _, y, _ in route)
+
+
+def test_unreachable_goal_returns_none():
+    universe = _universe_with_island()
+
+    assert universe.find_path((-80.0, 0.0, 0.0), (0.0, 0.0, 0.0)) is None
+    assert universe.find_path((-80.0, 0.0, 0.0), (500.0, 0.0, 0.0)) is None
+
+
+def test_threat_heatmap_steers_the_route():
+    universe = PyUniverse(width=256.0, height=256.0, depth=32.0, base_resolution=4.0)
+    # 32 m cells over the world, hot around x = 0 south of y = 64
+    threat = [[1.0 if x in (3, 4) and y < 6 else 0.0 for x in range(8)] for y in range(8)]
+
+    route = universe.find_path((-80.0, 0.0, 0.0), (80.0, 0.0, 0.0), resolution=8.0, threat=threat, threat_weight=20.0)
+
+    assert route is not None
+    assert any(y > 64.0 for _, y, _ in route)
+
+
+def test_field_costs_and_malformed_threats():
+    universe = PyUniverse(width=256.0, height=256.0, depth=32.0, base_resolution=4.0)
+
+    # Quiet water everywhere: the penalty never applies
+    route = universe.find_path((-80.0, 0.0, 0.0), (80.0, 0.0, 0.0), field_costs=[("noise", 60.0, 120.0, 5.0)])
+    assert route == [(-80.0, 0.0, 0.0), (80.0, 0.0, 0.0)]
+    with pytest.raises(ValueError):
+        universe.find_path((-80.0, 0.0, 0.0), (80.0, 0.0, 0.0), threat=[[0.0, 1.0], [0.0]])
+    with pytest.raises(ValueError):
+        universe.find_path((-80.0, 0.0, 0.0), (80.0, 0.0, 0.0), threat=[[0.0]], threat_cell_size=0.0)
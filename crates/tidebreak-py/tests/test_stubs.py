@@ -0,0 +1,56 @@
+"""Tests that the generated type stub matches the compiled extension."""
+
+import ast
+import inspect
+from pathlib import Path
+
+import tidebreak._tidebreak as ext
+
+STUB = Path(__file__).parent.parent / "python" / "tidebreak" / "_tidebreak.pyi"
+
+
+def _stub_tree() -> ast.Module:
+    return ast.parse(STUB.read_text())
+
+
+def _stub_members(node: ast.ClassDef) -> set[str]:
+    names = {n.name for n in node.body if isinstance(n, ast.FunctionDef)}
+    names |= {n.target.id for n in node.body if isinstance(n, ast.AnnAssign)}
+    return names
+
+
+def test_stub_covers_module():
+    """Every public name of the extension should be stubbed, and nothing else."""
+    stubbed = {n.name for n in _stub_tree().body if isinstance(n, ast.ClassDef | ast.FunctionDef)}
+    public = {name for name in dir(ext) if not name.startswith("_")}
+
+    assert stubbed == public
+
+
+def test_stub_covers_class_members():
+    """Stubbed classes should list exactly the public members of the runtime class."""
+    for node in _stub_tree().body:
+        if not isinstance(node, ast.ClassDef) or issubclass(getattr(ext, node.name), BaseException):
+            continue
+        runtime = {name for name in vars(getattr(ext, node.name)) if not name.startswith("_")}
+        stubbed = {name for name in _stub_members(node) if not name.startswith("_")}
+
+        assert stubbed == runtime, node.name
+
+
+def test_stub_signatures_match():
+    """Stubbed method parameters should match the runtime text signatures."""
+    for node in _stub_tree().body:
+        if not isinstance(node, ast.ClassDef):
+            continue
+        cls = getattr(ext, node.name)
+        for method in node.body:
+            if not isinstance(method, ast.FunctionDef) or method.decorator_list or method.name.startswith("_"):
+                continue
+            params = inspect.signature(getattr(cls, method.name)).parameters
+            runtime = [name for name in params if name != "self"]
+            args = method.args
+            stubbed = [a.arg for a in [*args.args[1:], *args.kwonlyargs]]
+            stubbed += [a.arg for a in (args.vararg, args.kwarg) if a is not None]
+
+            assert sorted(stubbed) == sorted(runtime), f"{node.name}.{method.name}"
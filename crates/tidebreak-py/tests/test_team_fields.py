@@ -0,0 +1,26 @@
+"""Tests for team-filtered views of the universe."""
+
+
+def test_team_sees_fields_only_under_its_sensors():
+    """Queries outside a team's coverage return None; ground truth is untouched."""
+    from tidebreak import PySimulation, PyUniverse
+
+    sim = PySimulation(seed=1)
+    ship = sim.spawn_ship(0.0, 0.0, 0.0)
+    sim.set_team(ship, 0)
+    universe = PyUniverse(width=40000.0, height=40000.0, depth=64.0, base_resolution=64.0)
+    universe.stamp_explosion((0.0, 0.0, 0.0), 50.0, 1.0)
+
+    [(x, y, radius)] = sim.team_coverage(0)
+    assert (x, y) == (0.0, 0.0)
+    assert radius > 0.0
+    assert sim.team_coverage(1) == []
+
+    seen = sim.team_query_point(universe, 0, (0.0, 0.0, 0.0))
+    assert seen is not None
+    assert seen.get("temperature") == universe.query_point((0.0, 0.0, 0.0)).get("temperature")
+    assert sim.team_query_point(universe, 0, (radius + 100.0, 0.0, 0.0)) is None
+    assert sim.team_query_point(universe, 1, (0.0, 0.0, 0.0)) is None
+
+    assert sim.team_query_volume(universe, 0, (0.0, 0.0, 0.0), radius / 2) is not None
+    assert sim.team_query_volume(universe, 0, (radius, 0.0, 0.0), radius / 2, "coarse") is None
@@ -0,0 +1,91 @@
+"""Tests for scenario triggers in tidebreak Python bindings."""
+
+import json
+
+import pytest
+
+
+def package(triggers):
+    return json.dumps(
+        {
+            "schema_version": "arena.v1",
+            "battle_id": "convoy",
+            "seed": 3,
+            "teams": [{"team_id": "blue"}, {"team_id": "red"}],
+            "ships": [
+                {"ship_id": "b1", "team_id": "blue", "initial_state": {"x": 0, "y": 0, "heading": 0}},
+                {"ship_id": "r1", "team_id": "red", "initial_state": {"x": 900, "y": 0, "heading": 3}},
+            ],
+            "triggers": triggers,
+        }
+    )
+
+
+def test_triggers_fire_inside_the_simulation():
+    """Trigger actions run on the tick after their condition is met."""
+    from tidebreak import PySimulation
+
+    triggers = [
+        {
+            "name": "reinforce",
+            "condition": {"type": "ship_destroyed", "ship_id": "r1"},
+            "actions": [
+                {
+                    "type": "spawn_wave",
+                    "ships": [
+                        {"ship_id": "r2", "team_id": "red", "initial_state": {"x": 1200, "y": 0, "heading": 3}}
+                    ],
+                },
+                {"type": "set_rules_of_engagement", "team_id": "blue", "roe": "hold_fire"},
+            ],
+        },
+        {"name": "timeout", "condition": {"type": "tick_reached", "tick": 4}, "actions": [{"type": "end_episode"}]},
+    ]
+    sim, ids = PySimulation.from_package(package(triggers))
+
+    sim.despawn(ids["r1"])
+    sim.step()
+    assert sim.entity_count == 2
+    assert sim.rules_of_engagement(0) == "hold_fire"
+    assert sim.fired_triggers() == {"reinforce": 1}
+
+    while sim.episode_ended is None:
+        sim.step()
+    assert sim.episode_ended == 5
+    assert sim.fired_triggers() == {"reinforce": 1, "timeout": 5}
+
+
+def test_rules_of_engagement_and_bad_references():
+    from tidebreak import InvalidValue, PySimulation
+
+    sim = PySimulation(seed=1)
+    assert sim.rules_of_engagement(2) == "weapons_free"
+    sim.set_rules_of_engagement(2, "hold_fire")
+    assert sim.rules_of_engagement(2) == "hold_fire"
+    with pytest.raises(InvalidValue):
+        sim.set_rules_of_engagement(2, "weapons_tight")
+
+    bad = [{"name": "lost", "condition": {"type": "hp_below", "ship_id": "x9", "fraction": 0.5}}]
+    with pytest.raises(InvalidValue):
+        PySimulation.from_package(package(bad))
+
+
+def test_trigger_stamps_fired_between_decisions_are_applied():
+    """Stamps from every tick of a decision reach the universe, once."""
+    from tidebreak import PySimulation, PyUniverse
+
+    def smoke_at(name, tick, x):
+        stamp = {"type": "apply_stamp", "x": x, "y": 10.0, "radius": 8.0, "field": "Smoke", "op": "Max", "value": 0.8}
+        return {"name": name, "condition": {"type": "tick_reached", "tick": tick}, "actions": [stamp]}
+
+    sim, _ = PySimulation.from_package(package([smoke_at("early", 1, 10.0), smoke_at("late", 2, 40.0)]))
+    sim.action_interval = 4
+    universe = PyUniverse(width=100.0, height=100.0, depth=50.0)
+
+    sim.step_decision()
+    assert sim.tick == 4
+    assert sim.fired_triggers() == {"early": 2, "late": 3}
+    sim.apply_trigger_stamps(universe, z=5.0)
+    assert universe.query_point((10.0, 10.0, 5.0)).get("smoke") > 0.0
+    assert universe.query_point((40.0, 10.0, 5.0)).get("smoke") > 0.0
+    assert universe.query_point((70.0, 10.0, 5.0)).get("smoke") == 0.0
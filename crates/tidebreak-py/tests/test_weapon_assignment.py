@@ -0,0 +1,30 @@
+"""Tests for same-team fire deconfliction in tidebreak Python bindings."""
+
+import pytest
+
+
+def test_weapon_assignment_is_opt_in_and_survives_reset():
+    """Nothing is assigned until enabled, and reset keeps the assignment."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    sim.spawn_ship(0.0, 0.0, 0.0)
+    assert sim.take_weapon_assignments() == []
+
+    sim.enable_weapon_assignment(damage_per_shot=50.0, max_shooters_per_target=1)
+    sim.step()
+    assert sim.take_weapon_assignments() == []
+
+    sim.reset()
+    sim.step()
+    assert sim.take_weapon_assignments() == []
+    sim.disable_weapon_assignment()
+
+
+def test_weapon_assignment_rejects_non_positive_damage():
+    """A shot must be expected to do some damage."""
+    from tidebreak import InvalidValue, PySimulation
+
+    sim = PySimulation(seed=1)
+    with pytest.raises(InvalidValue):
+        sim.enable_weapon_assignment(damage_per_shot=0.0)
@@ -0,0 +1,43 @@
+"""Tests for wind drift in tidebreak Python bindings."""
+
+import math
+
+import pytest
+
+
+def test_wind_drifts_only_entities_with_windage():
+    """A liferaft blows downwind; a ship without windage stays put."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    ship = sim.spawn_ship(0.0, 0.0, 0.0)
+    raft = sim.spawn_ship(0.0, 100.0, 0.0)
+    assert sim.wind is None
+    assert sim.windage(raft) == 0.0
+
+    sim.set_wind(20.0, math.pi / 2)
+    assert sim.set_windage(raft, 0.05)
+    assert not sim.set_windage(raft, -1.0)
+    vx, vy = sim.wind
+    assert abs(vx) < 1e-4 and abs(vy - 20.0) < 1e-4
+
+    for _ in range(60):
+        sim.step()
+    # One second at 5% of 20 m/s
+    assert sim.get_entity(raft).transform.y == pytest.approx(101.0, abs=1e-3)
+    assert sim.get_entity(ship).transform.position == (0.0, 0.0)
+
+    # The wind survives reset
+    sim.reset()
+    assert sim.wind is not None
+    sim.clear_wind()
+    assert sim.wind is None
+
+
+def test_wind_rejects_bad_speed():
+    """A negative wind speed is a ValueError."""
+    from tidebreak import PySimulation
+
+    sim = PySimulation(seed=1)
+    with pytest.raises(ValueError):
+        sim.set_wind(-1.0, 0.0)
@@ -67,14 +67,34 @@ if _rust is not None:
     PyCombatState = _rust.PyCombatState
     PyEntity = _rust.PyEntity
     PySimulation = _rust.PySimulation
+    PyCancellationToken = _rust.PyCancellationToken
+    PyScenarioRandomizer = _rust.PyScenarioRandomizer
+    Replay = _rust.Replay
     PyObservation = _rust.PyObservation
+    PyObservationCodec = _rust.PyObservationCodec
+    PyObservationSpec = _rust.PyObservationSpec
+
+    # Command errors
+    CommandError = _rust.CommandError
+    UnknownEntity = _rust.UnknownEntity
+    EntityDestroyed = _rust.EntityDestroyed
+    InvalidValue = _rust.InvalidValue
+    NotSupportedForTag = _rust.NotSupportedForTag
+    NotController = _rust.NotController
+
+    # Introspection
+    schema = _rust.schema
 
     # Aliases for convenience
     Universe = PyUniverse
     Simulation = PySimulation
+    CancellationToken = PyCancellationToken
     EntityId = PyEntityId
     EntityTag = PyEntityTag
     Entity = PyEntity
+    ObservationCodec = PyObservationCodec
+    ObservationSpec = PyObservationSpec
+    ScenarioRandomizer = PyScenarioRandomizer
 
     __all__ = [
         # Murk types
@@ -98,8 +118,26 @@ if _rust is not None:
         # Simulation
         "PySimulation",
         "Simulation",
+        "PyCancellationToken",
+        "CancellationToken",
+        "PyScenarioRandomizer",
+        "ScenarioRandomizer",
+        "Replay",
         # DRL
         "PyObservation",
+        "PyObservationCodec",
+        "ObservationCodec",
+        "PyObservationSpec",
+        "ObservationSpec",
+        # Command errors
+        "CommandError",
+        "UnknownEntity",
+        "EntityDestroyed",
+        "InvalidValue",
+        "NotSupportedForTag",
+        "NotController",
+        # Introspection
+        "schema",
         # Envs submodule
         "envs",
     ]
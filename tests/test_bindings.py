@@ -2,6 +2,9 @@
 
 from __future__ import annotations
 
+import asyncio
+import pickle
+
 import numpy as np
 import pytest
 
@@ -105,6 +108,135 @@ class TestObservation:
         assert len(contacts.shape) == 2
         assert contacts.shape[1] == 5
 
+    def test_contact_tags_default_unknown(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(50.0, 50.0, 0.0)
+
+        tags = sim.get_observation(ship_id, max_contacts=4).contact_tags()
+
+        assert tags.shape == (4,)
+        assert (tags == 0).all()
+
+
+class TestFrameStacking:
+    def test_unstacked_observation_has_one_frame(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(50.0, 50.0, 0.0)
+
+        obs = sim.get_observation(ship_id, max_contacts=4)
+
+        assert obs.frames == 1
+        assert obs.stacked_own_state().shape == (1, 7)
+        np.testing.assert_array_equal(obs.stacked_own_state()[0], obs.own_state())
+
+    def test_history_padded_then_filled(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0, 0.0)
+        sim.apply_action(ship_id, {"velocity": (5.0, 0.0)})
+
+        first = sim.get_observation(ship_id, max_contacts=4, frames=3)
+        assert first.stacked_own_state().shape == (3, 7)
+        assert first.stacked_contacts().shape == (3, 4, 5)
+        assert first.stacked_contact_tags().shape == (3, 4)
+        assert first.stacked_bound_distances().shape == (3, 4)
+        # Padded by repeating the only frame
+        assert (first.stacked_own_state() == first.own_state()).all()
+
+        xs = []
+        for _ in range(3):
+            sim.step()
+            obs = sim.get_observation(ship_id, max_contacts=4, frames=3)
+            xs = obs.stacked_own_state()[:, 0]
+        # Oldest first, newest equals the current frame
+        assert xs[0] < xs[1] < xs[2]
+        assert xs[2] == obs.own_state()[0]
+
+    def test_same_tick_replaces_frame(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0, 0.0)
+
+        sim.get_observation(ship_id, frames=2)
+        sim.step()
+        sim.get_observation(ship_id, frames=2)
+        obs = sim.get_observation(ship_id, frames=2)
+
+        stacked = obs.stacked_own_state()
+        assert stacked.shape == (2, 7)
+
+    def test_shape_change_restarts_history(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0, 0.0)
+
+        sim.get_observation(ship_id, max_contacts=4, frames=2)
+        sim.step()
+        obs = sim.get_observation(ship_id, max_contacts=8, frames=2)
+
+        assert obs.stacked_contacts().shape == (2, 8, 5)
+        sim.clear_frame_history()
+
+
+class TestActionInterval:
+    def test_step_decision_runs_interval_ticks(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.action_interval = 4
+
+        assert sim.is_decision_tick
+        assert sim.step_decision() == 4
+        assert sim.tick == 4
+        sim.step()
+        assert not sim.is_decision_tick
+        assert sim.step_decision() == 3
+
+    def test_interval_survives_reset_and_pickle(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.action_interval = 6
+        sim.reset()
+        assert sim.action_interval == 6
+
+        clone = pickle.loads(pickle.dumps(sim))
+        assert clone.action_interval == 6
+
+    def test_zero_means_every_tick(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.action_interval = 0
+
+        assert sim.action_interval == 1
+        assert sim.step_decision() == 1
+
+
+class TestIdNamespaces:
+    def test_namespaced_ids(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.id_namespace = 3
+
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        assert ship_id.namespace == 3
+        assert ship_id.local == 0
+        assert ship_id == tidebreak.EntityId.namespaced(3, 0)
+
+    def test_merge_with_explicit_ids(self) -> None:
+        producer_a = tidebreak.PySimulation()
+        producer_b = tidebreak.PySimulation()
+        producer_a.id_namespace = 1
+        producer_b.id_namespace = 2
+        ids = [producer_a.spawn_ship(0.0, 0.0), producer_b.spawn_ship(10.0, 0.0)]
+
+        merged = tidebreak.PySimulation()
+        for ship_id in ids:
+            merged.spawn_ship(0.0, 0.0, id=ship_id)
+
+        assert merged.entity_count == 2
+        with pytest.raises(tidebreak.InvalidValue):
+            merged.spawn_ship(0.0, 0.0, id=ids[0])
+
+    def test_namespace_survives_reset(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.id_namespace = 7
+        sim.reset()
+
+        assert sim.id_namespace == 7
+
 
 class TestApplyAction:
     def test_velocity(self) -> None:
@@ -197,5 +329,699 @@ class TestCombatEnv:
         assert isinstance(truncated, bool)
 
 
+
+class TestIntentBroadcast:
+    def test_friendly_receives_intent(self) -> None:
+        sim = tidebreak.PySimulation()
+        sender = sim.spawn_ship(0.0, 0.0, team=0)
+        receiver = sim.spawn_ship(100.0, 0.0, team=0)
+
+        sim.broadcast_intent(sender, [1.0, 0.5])
+        obs = sim.get_observation(receiver, max_intents=2)
+
+        intents = obs.intents()
+        assert intents.shape == (2, 3 + 8)
+        assert abs(intents[0, 0] - (-100.0)) < 0.001
+        assert abs(intents[0, 3] - 1.0) < 0.001
+        assert abs(intents[0, 4] - 0.5) < 0.001
+        assert np.all(intents[1] == 0.0)
+
+    def test_enemy_does_not_receive_intent(self) -> None:
+        sim = tidebreak.PySimulation()
+        sender = sim.spawn_ship(0.0, 0.0, team=0)
+        enemy = sim.spawn_ship(100.0, 0.0, team=1)
+
+        sim.broadcast_intent(sender, [1.0])
+        obs = sim.get_observation(enemy, max_intents=1)
+
+        assert np.all(obs.intents() == 0.0)
+
+    def test_jammer_blocks_intent(self) -> None:
+        sim = tidebreak.PySimulation()
+        sender = sim.spawn_ship(0.0, 0.0, team=0)
+        receiver = sim.spawn_ship(100.0, 0.0, team=0)
+        sim.add_jammer(100.0, 0.0, 20.0)
+
+        sim.broadcast_intent(sender, [1.0])
+        obs = sim.get_observation(receiver, max_intents=1)
+
+        assert np.all(obs.intents() == 0.0)
+
+    def test_oversized_intent_raises(self) -> None:
+        sim = tidebreak.PySimulation()
+        sender = sim.spawn_ship(0.0, 0.0, team=0)
+        sim.configure_comms(max_intent_len=2)
+
+        with pytest.raises(ValueError):
+            sim.broadcast_intent(sender, [1.0, 2.0, 3.0])
+
+    def test_team_is_exposed_on_entity(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship = sim.spawn_ship(0.0, 0.0, team=3)
+
+        assert sim.get_entity(ship).team == 3
+        assert sim.set_team(ship, None)
+        assert sim.get_entity(ship).team is None
+
+
+class TestContactSortKey:
+    def test_accepts_known_keys(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.set_contact_sort_key("threat")
+        sim.set_contact_sort_key("distance")
+
+    def test_rejects_unknown_key(self) -> None:
+        sim = tidebreak.PySimulation()
+        with pytest.raises(ValueError):
+            sim.set_contact_sort_key("alphabetical")
+
+    def test_repeated_observations_are_identical(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        first = sim.get_observation(ship_id, max_contacts=4).contacts()
+        second = sim.get_observation(ship_id, max_contacts=4).contacts()
+
+        np.testing.assert_array_equal(first, second)
+
+
+class TestObservationCodec:
+    def test_roundtrip_within_budget(self) -> None:
+        codec = tidebreak.PyObservationCodec(bit_budget=512)
+        values = np.linspace(-10.0, 10.0, 20, dtype=np.float32)
+
+        packed = codec.encode(values.tolist())
+        decoded = tidebreak.PyObservationCodec.decode(packed)
+
+        assert len(packed) * 8 <= 512
+        assert decoded.dtype == np.float32
+        np.testing.assert_allclose(decoded, values, atol=0.1)
+
+    def test_budget_too_small_raises(self) -> None:
+        codec = tidebreak.PyObservationCodec(bit_budget=90)
+        with pytest.raises(ValueError):
+            codec.encode([1.0, 2.0, 3.0])
+
+    def test_observation_encode(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(50.0, 50.0, 0.0)
+        obs = sim.get_observation(ship_id, max_contacts=4)
+
+        packed = obs.encode(bit_budget=1024)
+        decoded = tidebreak.PyObservationCodec.decode(packed)
+
+        assert decoded.shape == (7 + 4 * 5,)
+        assert abs(decoded[0] - 50.0) < 0.5
+
+
+class TestThreatScores:
+    def test_no_tracks_no_scores(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        assert sim.get_threat_scores(ship_id) == []
+
+
+class TestBattleLog:
+    def test_open_and_close_writes_streams(self, tmp_path) -> None:
+        sim = tidebreak.PySimulation()
+        sim.spawn_ship(0.0, 0.0)
+        sim.open_battle_log(str(tmp_path))
+        assert sim.has_battle_log
+
+        for _ in range(3):
+            sim.step()
+        sim.close_battle_log()
+
+        assert not sim.has_battle_log
+        for name in ("events.arrows", "damage.arrows", "entities.arrows"):
+            assert (tmp_path / name).stat().st_size > 0
+
+
+class TestPickling:
+    def test_entity_id_round_trip(self) -> None:
+        entity_id = tidebreak.PyEntityId(7)
+
+        assert pickle.loads(pickle.dumps(entity_id)) == entity_id
+
+    def test_simulation_round_trip_continues_identically(self) -> None:
+        sim = tidebreak.PySimulation(seed=7)
+        ship_id = sim.spawn_ship(1.0, 2.0, heading=0.5, team=1)
+        sim.apply_action(ship_id, {"velocity": (3.0, 1.0)})
+        for _ in range(5):
+            sim.step()
+
+        clone = pickle.loads(pickle.dumps(sim))
+        assert clone.seed == sim.seed
+        assert clone.tick == sim.tick
+        assert clone.entity_count == sim.entity_count
+
+        for _ in range(5):
+            sim.step()
+            clone.step()
+        assert clone.get_entity(ship_id).transform.position == sim.get_entity(ship_id).transform.position
+
+    @pytest.mark.parametrize("protocol", range(pickle.HIGHEST_PROTOCOL + 1))
+    def test_simulation_all_protocols(self, protocol: int) -> None:
+        sim = tidebreak.PySimulation(seed=3)
+        sim.spawn_ship(0.0, 0.0)
+        sim.step()
+
+        clone = pickle.loads(pickle.dumps(sim, protocol=protocol))
+
+        assert clone.tick == 1
+
+    def test_universe_round_trip(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0)
+        universe.step(0.1)
+
+        clone = pickle.loads(pickle.dumps(universe))
+
+        assert clone.tick == universe.tick
+        assert clone.time == universe.time
+
+    def test_observation_round_trip(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+        obs = sim.get_observation(ship_id)
+
+        clone = pickle.loads(pickle.dumps(obs))
+
+        np.testing.assert_array_equal(clone.own_state(), obs.own_state())
+        np.testing.assert_array_equal(clone.contacts(), obs.contacts())
+        np.testing.assert_array_equal(clone.contact_tags(), obs.contact_tags())
+
+
+class TestStepAsync:
+    def test_runs_requested_ticks(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.spawn_ship(0.0, 0.0)
+
+        completed = asyncio.run(sim.step_async(ticks=5))
+
+        assert completed == 5
+        assert sim.tick == 5
+
+    def test_cancelled_token_runs_nothing(self) -> None:
+        sim = tidebreak.PySimulation()
+        token = tidebreak.CancellationToken()
+        token.cancel()
+
+        completed = asyncio.run(sim.step_async(ticks=5, cancel=token))
+
+        assert completed == 0
+        assert sim.tick == 0
+
+    def test_task_cancellation_stops_at_tick_boundary(self) -> None:
+        sim = tidebreak.PySimulation()
+
+        async def run() -> None:
+            task = asyncio.ensure_future(sim.step_async(ticks=10**9))
+            await asyncio.sleep(0.01)
+            task.cancel()
+            with pytest.raises(asyncio.CancelledError):
+                await task
+
+        asyncio.run(run())
+        tick = sim.tick
+        sim.step()
+        assert sim.tick == tick + 1
+
+    def test_requires_running_loop(self) -> None:
+        with pytest.raises(RuntimeError):
+            tidebreak.PySimulation().step_async()
+
+
+class TestWorldBounds:
+    def test_unbounded_by_default(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        assert sim.bounds is None
+        np.testing.assert_array_equal(sim.get_observation(ship_id).bound_distances(), np.zeros(4))
+
+    def test_clamp_holds_ship_at_edge(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.set_bounds((-10.0, -10.0), (10.0, 10.0), "clamp")
+        ship_id = sim.spawn_ship(9.0, 0.0)
+        sim.apply_action(ship_id, {"velocity": (10.0, 0.0)})
+
+        for _ in range(30):
+            sim.step()
+
+        x, _y = sim.get_entity(ship_id).transform.position
+        assert x == pytest.approx(10.0)
+
+    def test_despawn_removes_ship(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.set_bounds((-10.0, -10.0), (10.0, 10.0), "despawn")
+        ship_id = sim.spawn_ship(9.0, 0.0)
+        sim.apply_action(ship_id, {"velocity": (10.0, 0.0)})
+
+        for _ in range(30):
+            sim.step()
+
+        assert sim.get_entity(ship_id) is None
+
+    def test_observation_reports_edge_distances(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.set_bounds((-10.0, -20.0), (10.0, 20.0), "wrap")
+        ship_id = sim.spawn_ship(4.0, 5.0)
+
+        distances = sim.get_observation(ship_id).bound_distances()
+
+        np.testing.assert_allclose(distances, [14.0, 6.0, 25.0, 15.0])
+
+    def test_bounds_survive_reset_and_clear(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.set_bounds((0.0, 0.0), (5.0, 5.0), "bounce")
+
+        sim.reset()
+        assert sim.bounds == ((0.0, 0.0), (5.0, 5.0), "bounce")
+
+        sim.clear_bounds()
+        assert sim.bounds is None
+
+    def test_invalid_policy_raises(self) -> None:
+        with pytest.raises(ValueError):
+            tidebreak.PySimulation().set_bounds((0.0, 0.0), (1.0, 1.0), "teleport")
+
+    def test_env_observes_bounds(self) -> None:
+        from tidebreak.envs import CombatEnv
+
+        env = CombatEnv(arena_size=400.0)
+        obs, _info = env.reset(seed=42)
+
+        np.testing.assert_allclose(obs["bounds"], [200.0, 200.0, 200.0, 200.0])
+
+
+
+class TestPluginBudget:
+    def test_no_timings_without_plugins(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.spawn_ship(0.0, 0.0)
+        sim.set_plugin_budget(5.0, max_overruns=2)
+        sim.step()
+
+        assert sim.plugin_timings() == []
+
+    def test_invalid_budget_raises(self) -> None:
+        sim = tidebreak.PySimulation()
+        with pytest.raises(ValueError):
+            sim.set_plugin_budget(0.0)
+        with pytest.raises(ValueError):
+            sim.set_plugin_budget(1.0, max_overruns=0)
+
+    def test_enable_unknown_plugin_returns_false(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+        sim.clear_plugin_budget()
+
+        assert not sim.enable_plugin(ship_id, "movement")
+
+
+
+class TestAttributes:
+    def test_set_and_get_typed_values(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        assert sim.set_attribute(ship_id, "flag", True)
+        sim.set_attribute(ship_id, "count", 3)
+        sim.set_attribute(ship_id, "ratio", 0.5)
+        sim.set_attribute(ship_id, "mode", "patrol")
+
+        assert sim.get_attribute(ship_id, "flag") is True
+        assert sim.get_attribute(ship_id, "count") == 3
+        assert sim.get_entity(ship_id).attributes == {"count": 3, "flag": True, "mode": "patrol", "ratio": 0.5}
+
+    def test_none_removes_attribute(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+        sim.set_attribute(ship_id, "mode", "patrol")
+
+        sim.set_attribute(ship_id, "mode", None)
+
+        assert sim.get_attribute(ship_id, "mode") is None
+        assert sim.get_entity(ship_id).attributes == {}
+
+    def test_unsupported_value_raises(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        with pytest.raises(TypeError):
+            sim.set_attribute(ship_id, "waypoints", [1, 2])
+
+
+
+class TestCommandErrors:
+    def test_unknown_entity_raises(self) -> None:
+        sim = tidebreak.PySimulation()
+
+        with pytest.raises(tidebreak.UnknownEntity):
+            sim.apply_action(tidebreak.EntityId(99), {"heading": 0.0})
+
+    def test_invalid_values_raise(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.apply_action(ship_id, {"velocity": (float("nan"), 0.0)})
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.apply_action(ship_id, {"heading": "north"})
+
+    def test_rejected_action_is_not_applied(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        with pytest.raises(tidebreak.CommandError):
+            sim.apply_action(ship_id, {"velocity": (1.0, 0.0), "heading": float("inf")})
+
+        assert sim.get_entity(ship_id).physics.velocity == (0.0, 0.0)
+
+    def test_validate_action_returns_error_without_raising(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+
+        assert sim.validate_action(ship_id, {"velocity": (1.0, 0.0)}) is None
+        error = sim.validate_action(tidebreak.EntityId(99), {})
+        assert isinstance(error, tidebreak.UnknownEntity)
+        assert isinstance(error, tidebreak.CommandError)
+
+
+
+class TestKNearest:
+    def test_returns_nearest_first(self) -> None:
+        sim = tidebreak.PySimulation()
+        far = sim.spawn_ship(300.0, 0.0)
+        near = sim.spawn_ship(100.0, 0.0)
+        sim.spawn_ship(1000.0, 0.0)
+
+        assert sim.query_knearest(0.0, 0.0, 2) == [near, far]
+
+    def test_tag_filter(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.spawn_ship(10.0, 0.0)
+
+        assert sim.query_knearest(0.0, 0.0, 5, tidebreak.EntityTag.Platform) == []
+        assert len(sim.query_knearest(0.0, 0.0, 5, tidebreak.EntityTag.Ship)) == 1
+
+
+
+class TestSegmentQuery:
+    def test_hits_ordered_from_start(self) -> None:
+        sim = tidebreak.PySimulation()
+        far = sim.spawn_ship(80.0, 0.0)
+        near = sim.spawn_ship(20.0, 1.0)
+        sim.spawn_ship(50.0, 40.0)
+
+        assert sim.query_segment((0.0, 0.0), (100.0, 0.0), width=4.0) == [near, far]
+
+
+
+class TestLabels:
+    def test_query_by_label(self) -> None:
+        sim = tidebreak.PySimulation()
+        a = sim.spawn_ship(0.0, 0.0)
+        sim.spawn_ship(10.0, 0.0)
+        c = sim.spawn_ship(20.0, 0.0)
+
+        assert sim.add_label(c, "convoy_1")
+        assert sim.add_label(a, "convoy_1")
+
+        assert sim.query_by_label("convoy_1") == [a, c]
+        assert sim.get_entity(a).labels == ["convoy_1"]
+
+    def test_remove_label(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship_id = sim.spawn_ship(0.0, 0.0)
+        sim.add_label(ship_id, "escort")
+
+        assert sim.remove_label(ship_id, "escort")
+        assert not sim.remove_label(ship_id, "escort")
+        assert sim.query_by_label("escort") == []
+
+
+
+class TestWreckage:
+    def test_no_events_leaves_universe_untouched(self) -> None:
+        sim = tidebreak.PySimulation()
+        universe = tidebreak.PyUniverse()
+        sim.spawn_ship(0.0, 0.0)
+        sim.step()
+
+        assert sim.apply_wreckage(universe, spawn_wrecks=True) == []
+        assert sim.query_by_label("wreck") == []
+
+
+class TestMinefield:
+    def test_lay_mine_creates_hidden_mine(self) -> None:
+        sim = tidebreak.PySimulation()
+        layer = sim.spawn_ship(10.0, 20.0, team=1)
+
+        mine_id = sim.lay_mine(layer, fuze="acoustic", threshold=3.0)
+
+        mine = sim.get_entity(mine_id)
+        assert mine.is_mine()
+        assert mine.mine_detected is False
+        assert mine.team == 1
+        assert sim.get_entity(layer).mine_detected is None
+
+    def test_enemy_ship_detonates_mine(self) -> None:
+        sim = tidebreak.PySimulation()
+        layer = sim.spawn_ship(0.0, 0.0, team=1)
+        mine_id = sim.lay_mine(layer)
+        sim.despawn(layer)
+        target = sim.spawn_ship(20.0, 0.0, team=2)
+
+        sim.step()
+
+        assert sim.get_entity(mine_id) is None
+        assert sim.get_entity(target).combat.hp == pytest.approx(20.0)
+
+    def test_invalid_mines_raise(self) -> None:
+        sim = tidebreak.PySimulation()
+        layer = sim.spawn_ship(0.0, 0.0)
+
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.lay_mine(layer, fuze="pressure")
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.lay_mine(layer, damage=-1.0)
+        with pytest.raises(tidebreak.UnknownEntity):
+            sim.lay_mine(tidebreak.EntityId(99))
+
+
+class TestLogistics:
+    def test_depot_resupplies_alongside_ship(self) -> None:
+        sim = tidebreak.PySimulation()
+        depot = sim.spawn_depot(0.0, 0.0, ammo={"shell": 40})
+        ship = sim.spawn_ship(50.0, 0.0)
+
+        assert sim.transfer_cargo(depot, ship, "shell", 25)
+        assert not sim.transfer_cargo(depot, ship, "shell", 5)
+        for _ in range(60):
+            sim.step()
+
+        assert sim.get_inventory(depot)[1]["shell"] == 15
+        assert sim.get_inventory(ship)[1]["shell"] == 25
+
+    def test_distant_ship_is_not_resupplied(self) -> None:
+        sim = tidebreak.PySimulation()
+        depot = sim.spawn_depot(0.0, 0.0, fuel=500.0)
+        ship = sim.spawn_ship(5000.0, 0.0)
+        fuel_before = sim.get_inventory(ship)[0]
+
+        sim.transfer_cargo(depot, ship, "fuel", 100.0)
+        for _ in range(60):
+            sim.step()
+
+        assert sim.get_inventory(depot)[0] == pytest.approx(500.0)
+        assert sim.get_inventory(ship)[0] == pytest.approx(fuel_before)
+
+    def test_invalid_transfers_raise(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship = sim.spawn_ship(0.0, 0.0)
+        other = sim.spawn_ship(10.0, 0.0)
+
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.transfer_cargo(ship, other, "plasma", 1.0)
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.transfer_cargo(ship, other, "fuel", -1.0)
+        with pytest.raises(tidebreak.UnknownEntity):
+            sim.transfer_cargo(ship, tidebreak.EntityId(99), "fuel", 1.0)
+        assert sim.get_inventory(tidebreak.EntityId(99)) is None
+
+
+class TestWeaponReload:
+    def test_add_weapon_appears_in_observation(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship = sim.spawn_ship(0.0, 0.0)
+
+        slot = sim.add_weapon(ship, magazine_size=4, reload_ticks=3, burst_size=2)
+        second = sim.add_weapon(ship, ammo="torpedo", cooldown=5.0)
+
+        weapons = sim.get_observation(ship).weapons()
+        assert (slot, second) == (0, 1)
+        assert weapons.shape == (2, 5)
+        assert list(weapons[0]) == pytest.approx([1.0, 0.0, 4.0, 4.0, 0.0])
+        assert weapons[1][3] == 0.0
+
+    def test_full_or_cooldown_only_weapons_do_not_reload(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship = sim.spawn_ship(0.0, 0.0)
+        magazine = sim.add_weapon(ship, magazine_size=4, reload_ticks=3)
+        cooldown_only = sim.add_weapon(ship)
+
+        assert sim.reload_weapon(ship, magazine) is False
+        assert sim.reload_weapon(ship, cooldown_only) is False
+
+    def test_invalid_weapons_raise(self) -> None:
+        sim = tidebreak.PySimulation()
+        ship = sim.spawn_ship(0.0, 0.0)
+
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.add_weapon(ship, ammo="laser")
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.add_weapon(ship, cooldown=-1.0)
+        with pytest.raises(tidebreak.InvalidValue):
+            sim.reload_weapon(ship, 3)
+        with pytest.raises(tidebreak.UnknownEntity):
+            sim.add_weapon(tidebreak.EntityId(99))
+
+
+class TestBalance:
+    def test_empty_simulation_has_no_teams(self) -> None:
+        sim = tidebreak.PySimulation()
+        assert sim.evaluate_balance() == []
+
+    def test_outnumbered_team_is_behind(self) -> None:
+        sim = tidebreak.PySimulation()
+        sim.spawn_ship(0.0, 0.0, team=0)
+        sim.spawn_ship(500.0, 0.0, team=0)
+        sim.spawn_ship(1000.0, 0.0, team=1)
+
+        balance = sim.evaluate_balance()
+
+        assert [t["team"] for t in balance] == [0, 1]
+        assert balance[0]["units"] == 2
+        assert balance[0]["win_probability"] == pytest.approx(0.8)
+        assert sum(t["win_probability"] for t in balance) == pytest.approx(1.0)
+
+
+class TestQueryMoments:
+    def test_smoke_distribution(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0)
+        universe.stamp_fire((0.0, 0.0, 0.0), 4.0, 1.0)
+
+        result = universe.query_volume((0.0, 0.0, 0.0), 30.0, resolution="full")
+
+        assert 0.0 < result.coverage < 1.0
+        assert result.p50("smoke") <= result.p90("smoke") <= result.max("smoke")
+        low, high, counts = result.histogram("smoke", buckets=4)
+        assert len(counts) == 4
+        assert low == pytest.approx(result.min("smoke"))
+        assert high == pytest.approx(result.max("smoke"))
+
+    def test_empty_universe_has_no_coverage(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0)
+
+        result = universe.query_volume((0.0, 0.0, 0.0), 10.0)
+
+        assert result.coverage == 0.0
+        assert result.percentile("noise", 0.5) == 0.0
+
+
+
+class TestIsosurface:
+    def test_fire_produces_closed_surface(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0, base_resolution=2.0)
+        universe.stamp_fire((0.0, 0.0, 0.0), 8.0, 1.0)
+
+        vertices, indices = universe.extract_isosurface("smoke", 0.1)
+
+        assert vertices.dtype == np.float32
+        assert indices.dtype == np.uint32
+        assert vertices.shape[1] == 3
+        assert indices.shape[1] == 3
+        assert len(indices) > 0
+        assert indices.max() < len(vertices)
+
+    def test_empty_universe_has_no_surface(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0)
+
+        vertices, indices = universe.extract_isosurface("smoke", 0.5, (-8.0, -8.0, -8.0), (8.0, 8.0, 8.0))
+
+        assert vertices.shape == (0, 3)
+        assert indices.shape == (0, 3)
+
+    def test_isolines_on_slice(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0, base_resolution=2.0)
+        universe.stamp_fire((0.0, 0.0, 0.0), 8.0, 1.0)
+
+        lines = universe.extract_isolines("smoke", 0.1, 0.0)
+
+        assert len(lines) >= 1
+        assert all(line.shape[1] == 2 for line in lines)
+        assert universe.extract_isolines("smoke", 0.1, 100.0) == []
+
+
+
+class TestStampScheduling:
+    def test_scheduled_stamp_applied_on_time(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0)
+        universe.schedule_stamp("sonar_ping", (0.0, 0.0, 0.0), 4.0, at_time=1.0)
+
+        universe.step(0.5)
+        assert universe.query_point((0.0, 0.0, 0.0)).get("sonar_return") == 0.0
+
+        universe.step(0.5)
+        assert universe.query_point((0.0, 0.0, 0.0)).get("sonar_return") > 0.0
+        assert universe.pending_stamps == 0
+
+    def test_periodic_and_cancel(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0)
+        limited = universe.schedule_periodic("fire", (0.0, 0.0, 0.0), 4.0, interval=1.0, count=2)
+        forever = universe.schedule_periodic("fire", (10.0, 0.0, 0.0), 4.0, interval=1.0)
+
+        universe.step(1.0)
+        universe.step(1.0)
+        assert universe.pending_stamps == 1
+        assert not universe.cancel_scheduled(limited)
+        assert universe.cancel_scheduled(forever)
+        assert universe.pending_stamps == 0
+
+    def test_invalid_schedules_rejected(self) -> None:
+        universe = tidebreak.PyUniverse(width=64.0, height=64.0, depth=32.0)
+
+        with pytest.raises(ValueError):
+            universe.schedule_stamp("meteor", (0.0, 0.0, 0.0), 4.0, at_time=1.0)
+        with pytest.raises(ValueError):
+            universe.schedule_periodic("fire", (0.0, 0.0, 0.0), 4.0, interval=0.0)
+
+
+
+class TestFrozenRegions:
+    def test_explosion_does_not_carve_land(self) -> None:
+        universe = tidebreak.PyUniverse(width=32.0, height=32.0, depth=32.0)
+        universe.freeze_region((-16.0, -16.0, -16.0), (0.0, 16.0, 16.0), ["occupancy"])
+        land_before = universe.query_point((-0.5, 0.5, 0.5)).get("occupancy")
+        sea_before = universe.query_point((1.5, 0.5, 0.5)).get("occupancy")
+
+        universe.stamp_explosion((0.5, 0.5, 0.5), 6.0)
+
+        assert universe.query_point((-0.5, 0.5, 0.5)).get("occupancy") == land_before
+        assert universe.query_point((1.5, 0.5, 0.5)).get("occupancy") < sea_before
+
+    def test_clear_frozen(self) -> None:
+        universe = tidebreak.PyUniverse(width=32.0, height=32.0, depth=32.0)
+        universe.freeze_region((-16.0, -16.0, -16.0), (16.0, 16.0, 16.0), ["occupancy"])
+        universe.clear_frozen()
+        before = universe.query_point((0.5, 0.5, 0.5)).get("occupancy")
+
+        universe.stamp_explosion((0.5, 0.5, 0.5), 6.0)
+
+        assert universe.query_point((0.5, 0.5, 0.5)).get("occupancy") < before
+
+
 if __name__ == "__main__":
     pytest.main([__file__, "-v"])